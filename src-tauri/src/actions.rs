@@ -0,0 +1,269 @@
+//! Command registry for the keyboard palette
+//!
+//! Enumerates every action the palette can run, with a stable id, title,
+//! and a JSON Schema describing its arguments, and dispatches them through
+//! [`invoke_action`]. The palette renders whatever [`list_actions`]
+//! returns instead of keeping its own hard-coded list.
+//!
+//! Actions that only change the view (opening a tool) return a
+//! [`ActionOutcome::Navigate`] for the frontend to follow; actions with a
+//! backend effect run here and report their result.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+
+use crate::catalog::{self, TOOLS};
+use crate::clipboard_history::{self, ClipboardState};
+use crate::history::HistoryState;
+use crate::network::monitor::{self, PortMonitorRecord};
+use crate::network::NetworkScannerState;
+use crate::update_check::{self, UpdateChannel};
+
+/// Prefix of the per-tool open actions (`tool.open.json-formatter`)
+const TOOL_OPEN_PREFIX: &str = "tool.open.";
+
+/// Prefix of the per-monitor start actions (`monitor.start.<id>`)
+const MONITOR_START_PREFIX: &str = "monitor.start.";
+
+/// One invocable action.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDescriptor {
+    /// Stable identifier passed to [`invoke_action`].
+    pub id: String,
+    /// Display title.
+    pub title: String,
+    /// Grouping shown in the palette (`Tools`, `Scan profiles`, ...).
+    pub category: &'static str,
+    /// JSON Schema of the `args` object.
+    pub params: Value,
+}
+
+/// Result of running an action.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionOutcome {
+    /// The frontend should navigate to `url`.
+    Navigate {
+        /// Route path.
+        url: String,
+        /// History entry to restore on the target page, if any.
+        #[serde(rename = "historyId", skip_serializing_if = "Option::is_none")]
+        history_id: Option<i64>,
+    },
+    /// The action produced a value for the palette to display.
+    Data {
+        /// Action-specific result.
+        value: Value,
+    },
+}
+
+fn no_params() -> Value {
+    json!({ "type": "object", "properties": {}, "additionalProperties": false })
+}
+
+fn descriptor(
+    id: impl Into<String>,
+    title: impl Into<String>,
+    category: &'static str,
+    params: Value,
+) -> ActionDescriptor {
+    ActionDescriptor {
+        id: id.into(),
+        title: title.into(),
+        category,
+        params,
+    }
+}
+
+/// Actions that exist regardless of saved data.
+fn static_actions() -> Vec<ActionDescriptor> {
+    let tool_ids: Vec<&str> = TOOLS.iter().map(|t| t.id).collect();
+    let mut actions: Vec<ActionDescriptor> = TOOLS
+        .iter()
+        .map(|t| {
+            descriptor(
+                format!("{TOOL_OPEN_PREFIX}{}", t.id),
+                format!("Open {}", t.title),
+                "Tools",
+                no_params(),
+            )
+        })
+        .collect();
+    actions.extend([
+        descriptor(
+            "tool.open",
+            "Open tool",
+            "Tools",
+            json!({
+                "type": "object",
+                "properties": { "tool": { "type": "string", "enum": tool_ids } },
+                "required": ["tool"],
+                "additionalProperties": false,
+            }),
+        ),
+        descriptor(
+            "history.rerun-last",
+            "Re-run last conversion",
+            "History",
+            json!({
+                "type": "object",
+                "properties": {
+                    "tool": {
+                        "type": "string",
+                        "enum": tool_ids,
+                        "description": "Limit to the last run of this tool",
+                    },
+                },
+                "additionalProperties": false,
+            }),
+        ),
+        descriptor(
+            "clipboard.analyze",
+            "Analyze clipboard",
+            "Clipboard",
+            no_params(),
+        ),
+        descriptor(
+            "app.check-updates",
+            "Check for updates",
+            "Application",
+            json!({
+                "type": "object",
+                "properties": { "channel": { "type": "string", "enum": ["stable", "beta"] } },
+                "additionalProperties": false,
+            }),
+        ),
+    ]);
+    actions
+}
+
+/// One start action per saved port monitor.
+fn monitor_actions(records: &[PortMonitorRecord]) -> Vec<ActionDescriptor> {
+    records
+        .iter()
+        .map(|r| {
+            descriptor(
+                format!("{MONITOR_START_PREFIX}{}", r.id),
+                format!("Run scan profile: {}", r.config.name),
+                "Scan profiles",
+                no_params(),
+            )
+        })
+        .collect()
+}
+
+fn arg_str<'a>(args: &'a Value, name: &str) -> Option<&'a str> {
+    args.get(name).and_then(Value::as_str)
+}
+
+fn navigate_to_tool(tool_id: &str, history_id: Option<i64>) -> Result<ActionOutcome, String> {
+    let tool = catalog::find_tool(tool_id).ok_or_else(|| format!("Unknown tool: {tool_id}"))?;
+    Ok(ActionOutcome::Navigate {
+        url: tool.url.to_string(),
+        history_id,
+    })
+}
+
+fn to_value(value: impl Serialize) -> Result<ActionOutcome, String> {
+    serde_json::to_value(value)
+        .map(|value| ActionOutcome::Data { value })
+        .map_err(|e| format!("Failed to serialize action result: {e}"))
+}
+
+/// List every action the palette can invoke, including one per saved
+/// scan profile.
+#[tauri::command(async)]
+pub fn list_actions(app: AppHandle) -> Vec<ActionDescriptor> {
+    let mut actions = static_actions();
+    actions.extend(monitor_actions(
+        &monitor::export_records(&app).unwrap_or_default(),
+    ));
+    actions
+}
+
+/// Run the action `id` with `args` (validated against its schema by the
+/// caller; missing or mistyped arguments are reported as errors here).
+///
+/// # Errors
+///
+/// Returns an error string for unknown actions, invalid arguments, or
+/// when the underlying operation fails.
+#[tauri::command]
+pub async fn invoke_action(
+    id: String,
+    args: Option<Value>,
+    app: AppHandle,
+    history: State<'_, HistoryState>,
+    scanner: State<'_, NetworkScannerState>,
+    clipboard: State<'_, ClipboardState>,
+) -> Result<ActionOutcome, String> {
+    let args = args.unwrap_or(Value::Null);
+
+    if let Some(tool_id) = id.strip_prefix(TOOL_OPEN_PREFIX) {
+        return navigate_to_tool(tool_id, None);
+    }
+    if let Some(monitor_id) = id.strip_prefix(MONITOR_START_PREFIX) {
+        let record = monitor::export_records(&app)?
+            .into_iter()
+            .find(|r| r.id == monitor_id)
+            .ok_or_else(|| format!("Unknown scan profile: {monitor_id}"))?;
+        let started = monitor::port_monitor_start(app, record.config, scanner).await?;
+        return to_value(started);
+    }
+
+    match id.as_str() {
+        "tool.open" => {
+            let tool_id = arg_str(&args, "tool").ok_or("Missing argument: tool")?;
+            navigate_to_tool(tool_id, None)
+        }
+        "history.rerun-last" => {
+            // Most recent first, regardless of favorites.
+            let tool = arg_str(&args, "tool");
+            let last = history
+                .export_all()?
+                .into_iter()
+                .find(|e| tool.is_none_or(|t| e.tool == t))
+                .ok_or("No history to re-run")?;
+            navigate_to_tool(&last.tool, Some(last.id))
+        }
+        "clipboard.analyze" => to_value(clipboard_history::clipboard_analyze(app, clipboard)),
+        "app.check-updates" => {
+            let channel = match arg_str(&args, "channel") {
+                None | Some("stable") => UpdateChannel::Stable,
+                Some("beta") => UpdateChannel::Beta,
+                Some(other) => return Err(format!("Unknown update channel: {other}")),
+            };
+            to_value(update_check::check_for_updates(Some(channel), None).await?)
+        }
+        _ => Err(format!("Unknown action: {id}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_ids_are_unique_and_cover_every_tool() {
+        let actions = static_actions();
+        let mut ids: Vec<&str> = actions.iter().map(|a| a.id.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), actions.len());
+        for tool in TOOLS {
+            assert!(ids.contains(&format!("{TOOL_OPEN_PREFIX}{}", tool.id).as_str()));
+        }
+        assert!(actions.iter().all(|a| a.params["type"] == "object"));
+    }
+
+    #[test]
+    fn navigates_to_known_tools_only() {
+        assert!(matches!(
+            navigate_to_tool("dns-lookup", Some(3)),
+            Ok(ActionOutcome::Navigate { ref url, history_id: Some(3) }) if url == "/dns-lookup"
+        ));
+        assert!(navigate_to_tool("nope", None).is_err());
+    }
+}
@@ -0,0 +1,440 @@
+//! Encrypted export and import of application data
+//!
+//! Bundles settings, tool history (favorites are pinned history entries),
+//! saved port monitors (definitions, last snapshot, and change history),
+//! scheduled tasks, and port scan history into one passphrase-protected
+//! file for moving between machines.
+//!
+//! The payload records which sections it holds, so an exported but empty
+//! section still clears existing data on a replacing import.
+//!
+//! File layout:
+//!
+//! ```text
+//! "KOGUBAK" | version (1 byte) | salt (16) | nonce (12) | AES-256-GCM ciphertext
+//! ```
+//!
+//! The key is derived from the passphrase with Argon2id (default
+//! parameters) and the header is authenticated as associated data. The
+//! plaintext is gzip-compressed JSON.
+
+use std::io::{Read, Write};
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::Argon2;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::history::{HistoryEntry, HistoryState};
+use crate::network::monitor::{self, PortMonitorRecord};
+use crate::network::scan_history::{self, StoredScan};
+use crate::network::NetworkScannerState;
+use crate::redaction;
+use crate::scheduler::{ScheduledTask, SchedulerState};
+use crate::settings::{AppSettings, SettingsState};
+
+const MAGIC: &[u8; 7] = b"KOGUBAK";
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+/// Minimum passphrase length in characters
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// Largest backup file accepted on import
+const MAX_BACKUP_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Decrypted backup contents.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupPayload {
+    app_version: String,
+    created_at: i64,
+    /// Sections the backup holds; absent in backups that predate it.
+    #[serde(default)]
+    sections: Option<BackupSections>,
+    #[serde(default)]
+    settings: Option<AppSettings>,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    #[serde(default)]
+    monitors: Vec<PortMonitorRecord>,
+    #[serde(default)]
+    tasks: Vec<ScheduledTask>,
+    #[serde(default)]
+    scans: Vec<StoredScan>,
+}
+
+impl BackupPayload {
+    /// Sections to restore. Older backups did not record them and wrote
+    /// left-out sections as empty, so only their non-empty sections count.
+    fn present_sections(&self) -> BackupSections {
+        self.sections.unwrap_or_else(|| BackupSections {
+            settings: self.settings.is_some(),
+            history: !self.history.is_empty(),
+            monitors: !self.monitors.is_empty(),
+            tasks: !self.tasks.is_empty(),
+            scans: !self.scans.is_empty(),
+        })
+    }
+}
+
+/// Sections to include in an export.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSections {
+    /// Application settings.
+    #[serde(default = "default_true")]
+    pub settings: bool,
+    /// Tool history and favorites.
+    #[serde(default = "default_true")]
+    pub history: bool,
+    /// Saved port monitors.
+    #[serde(default = "default_true")]
+    pub monitors: bool,
+    /// Scheduled tasks.
+    #[serde(default = "default_true")]
+    pub tasks: bool,
+    /// Stored port scans.
+    #[serde(default = "default_true")]
+    pub scans: bool,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+impl Default for BackupSections {
+    fn default() -> Self {
+        Self {
+            settings: true,
+            history: true,
+            monitors: true,
+            tasks: true,
+            scans: true,
+        }
+    }
+}
+
+/// How imported data combines with existing data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Keep existing data and add or update from the backup.
+    #[default]
+    Merge,
+    /// Discard existing data in each section present in the backup.
+    Replace,
+}
+
+/// What an export or import touched.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    /// Application version that wrote the backup.
+    pub app_version: String,
+    /// Backup creation time in milliseconds since the Unix epoch.
+    pub created_at: i64,
+    /// Whether settings were included or restored.
+    pub settings: bool,
+    /// History entries written.
+    pub history_entries: usize,
+    /// Favorites among the history entries.
+    pub favorites: usize,
+    /// Port monitors written.
+    pub monitors: usize,
+    /// Scheduled tasks written.
+    pub tasks: usize,
+    /// Stored scans written.
+    pub scans: usize,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let mut out = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Invalid encryption key: {e}"))?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &out,
+            },
+        )
+        .map_err(|_| "Encryption failed".to_string())?;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+        return Err("Not a Kogu backup file".to_string());
+    }
+    let version = data[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(format!("Unsupported backup format version {version}"));
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    let salt = &header[MAGIC.len() + 1..MAGIC.len() + 1 + SALT_LEN];
+    let nonce = &header[MAGIC.len() + 1 + SALT_LEN..];
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Invalid encryption key: {e}"))?;
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| "Wrong passphrase or corrupted backup".to_string())
+}
+
+fn seal(payload: &BackupPayload, passphrase: &str) -> Result<Vec<u8>, String> {
+    let json =
+        serde_json::to_vec(payload).map_err(|e| format!("Failed to serialize backup: {e}"))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| format!("Failed to compress backup: {e}"))
+        .and_then(|compressed| encrypt(&compressed, passphrase))
+}
+
+fn open(data: &[u8], passphrase: &str) -> Result<BackupPayload, String> {
+    let compressed = decrypt(data, passphrase)?;
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .take(MAX_BACKUP_BYTES * 4)
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Failed to decompress backup: {e}"))?;
+    serde_json::from_slice(&json).map_err(|e| format!("Invalid backup contents: {e}"))
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!(
+            "Passphrase must be at least {MIN_PASSPHRASE_CHARS} characters"
+        ));
+    }
+    Ok(())
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+}
+
+/// Export the selected sections to an encrypted backup at `path`.
+///
+/// # Errors
+///
+/// Returns an error string when the passphrase is too short, a section
+/// cannot be read, or the file cannot be written.
+#[tauri::command(async)]
+pub fn export_app_data(
+    path: String,
+    passphrase: String,
+    sections: Option<BackupSections>,
+    app: AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+    history: tauri::State<'_, HistoryState>,
+    scheduler: tauri::State<'_, SchedulerState>,
+) -> Result<BackupSummary, String> {
+    check_passphrase(&passphrase)?;
+    let sections = sections.unwrap_or_default();
+
+    let payload = BackupPayload {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: now_ms(),
+        sections: Some(sections),
+        settings: sections.settings.then(|| settings.current()).transpose()?,
+        history: if sections.history {
+            // Labels written before redaction existed may still hold secrets.
+            let mut entries = history.export_all()?;
+            for entry in &mut entries {
+                if let Some(label) = &entry.label {
+                    entry.label = Some(redaction::redact(label).into_owned());
+                }
+            }
+            entries
+        } else {
+            Vec::new()
+        },
+        monitors: if sections.monitors {
+            monitor::export_records(&app)?
+        } else {
+            Vec::new()
+        },
+        tasks: if sections.tasks {
+            scheduler.export_all()
+        } else {
+            Vec::new()
+        },
+        scans: if sections.scans {
+            scan_history::export_scans(&app)?
+        } else {
+            Vec::new()
+        },
+    };
+
+    let data = seal(&payload, &passphrase)?;
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write {path}: {e}"))?;
+
+    Ok(BackupSummary {
+        app_version: payload.app_version,
+        created_at: payload.created_at,
+        settings: payload.settings.is_some(),
+        history_entries: payload.history.len(),
+        favorites: payload.history.iter().filter(|e| e.pinned).count(),
+        monitors: payload.monitors.len(),
+        tasks: payload.tasks.len(),
+        scans: payload.scans.len(),
+    })
+}
+
+/// Import an encrypted backup from `path`.
+///
+/// Settings in the backup always replace the current settings. The other
+/// sections merge or replace according to `mode`; a section the backup
+/// holds replaces existing data even when it is empty, while sections
+/// missing from the backup are left untouched.
+///
+/// # Errors
+///
+/// Returns an error string when the file cannot be read, the passphrase
+/// is wrong, or a section cannot be written.
+#[tauri::command(async)]
+pub fn import_app_data(
+    path: String,
+    passphrase: String,
+    mode: Option<ImportMode>,
+    app: AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+    history: tauri::State<'_, HistoryState>,
+    scanner: tauri::State<'_, NetworkScannerState>,
+    scheduler: tauri::State<'_, SchedulerState>,
+) -> Result<BackupSummary, String> {
+    let size = std::fs::metadata(&path)
+        .map_err(|e| format!("Failed to read {path}: {e}"))?
+        .len();
+    if size > MAX_BACKUP_BYTES {
+        return Err(format!("Backup file is too large ({size} bytes)"));
+    }
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let payload = open(&data, &passphrase)?;
+    let replace = mode.unwrap_or_default() == ImportMode::Replace;
+    let present = payload.present_sections();
+
+    let mut summary = BackupSummary {
+        app_version: payload.app_version,
+        created_at: payload.created_at,
+        ..BackupSummary::default()
+    };
+    if let Some(imported) = payload.settings {
+        settings.replace(imported)?;
+        summary.settings = true;
+    }
+    if present.history {
+        summary.favorites = payload.history.iter().filter(|e| e.pinned).count();
+        summary.history_entries = history.import(&payload.history, replace)?;
+    }
+    if present.monitors {
+        summary.monitors = monitor::import_records(&app, payload.monitors, replace, &scanner)?;
+    }
+    if present.tasks {
+        summary.tasks = scheduler.import(payload.tasks, replace)?;
+    }
+    if present.scans {
+        summary.scans = scan_history::import_scans(&app, payload.scans, replace)?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> BackupPayload {
+        BackupPayload {
+            app_version: "0.0.6".to_string(),
+            created_at: 1,
+            sections: Some(BackupSections::default()),
+            settings: Some(AppSettings::default()),
+            history: vec![HistoryEntry {
+                id: 7,
+                tool: "json-formatter".to_string(),
+                input_hash: "0123456789abcdef".to_string(),
+                label: None,
+                created_at: 1,
+                last_used: 2,
+                use_count: 3,
+                pinned: true,
+            }],
+            monitors: Vec::new(),
+            tasks: Vec::new(),
+            scans: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn round_trips_with_the_right_passphrase() {
+        let sealed = seal(&payload(), "correct horse").unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        let opened = open(&sealed, "correct horse").unwrap();
+        assert_eq!(opened.history.len(), 1);
+        assert!(opened.history[0].pinned);
+        assert!(opened.settings.is_some());
+        // Empty sections are still recorded as present.
+        assert!(opened.present_sections().tasks);
+    }
+
+    #[test]
+    fn older_backups_hold_only_their_non_empty_sections() {
+        let mut json = serde_json::to_value(payload()).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        for key in ["sections", "tasks", "scans"] {
+            fields.remove(key);
+        }
+        let older: BackupPayload = serde_json::from_value(json).unwrap();
+        let present = older.present_sections();
+        assert!(present.settings && present.history);
+        assert!(!present.monitors && !present.tasks && !present.scans);
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase_and_tampering() {
+        let mut sealed = seal(&payload(), "correct horse").unwrap();
+        assert!(open(&sealed, "wrong horse").is_err());
+
+        // Flipping a header byte breaks authentication too.
+        sealed[MAGIC.len() + 1] ^= 1;
+        assert!(open(&sealed, "correct horse").is_err());
+        assert!(open(b"garbage", "correct horse").is_err());
+        assert!(check_passphrase("short").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Recently parsed documents, shared between AST commands
+//!
+//! `parse_to_ast` stores every tree it builds, so commands that receive
+//! the same text afterwards (`path_at_offset` on every cursor move) reuse
+//! the tree instead of parsing again. Entries match on the full text,
+//! the language, and the SQL dialect; the least recently used entry is
+//! evicted first. A parse cancelled while in flight is never stored.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    parse_cancellable, parse_sql_cancellable, AstError, AstLanguage, AstParseResult, SqlDialect,
+};
+
+/// Documents kept at once. Trees of large documents take several times
+/// the memory of their text, so only the editor's current document and
+/// the one before it are kept.
+const CACHE_CAPACITY: usize = 2;
+
+struct Entry {
+    text: String,
+    language: AstLanguage,
+    /// Dialect for SQL; always `None` for other languages
+    dialect: Option<SqlDialect>,
+    result: Arc<AstParseResult>,
+}
+
+impl Entry {
+    fn matches(&self, text: &str, language: AstLanguage, dialect: Option<SqlDialect>) -> bool {
+        self.language == language && self.dialect == dialect && self.text == text
+    }
+}
+
+/// Parsed-document cache, held in managed state. Clones share entries.
+#[derive(Clone, Default)]
+pub struct AstCache {
+    entries: Arc<Mutex<VecDeque<Entry>>>,
+}
+
+impl AstCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tree of `text`, parsed with `dialect` for SQL (generic when
+    /// omitted). Parses only when the document is not cached, and stops
+    /// parsing once `cancel` is cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AstError::Cancelled`] when `cancel` is cancelled before
+    /// the tree is complete.
+    pub fn parse(
+        &self,
+        text: &str,
+        language: AstLanguage,
+        dialect: Option<SqlDialect>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Arc<AstParseResult>, AstError> {
+        let dialect = (language == AstLanguage::Sql).then(|| dialect.unwrap_or_default());
+        if let Some(result) = self.get(text, language, dialect) {
+            return Ok(result);
+        }
+        let result = dialect.map_or_else(
+            || parse_cancellable(text, language, cancel),
+            |dialect| parse_sql_cancellable(text, dialect, cancel),
+        );
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(AstError::Cancelled);
+        }
+        let result = Arc::new(result);
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|entry| !entry.matches(text, language, dialect));
+        entries.push_front(Entry {
+            text: text.to_string(),
+            language,
+            dialect,
+            result: Arc::clone(&result),
+        });
+        entries.truncate(CACHE_CAPACITY);
+        drop(entries);
+        Ok(result)
+    }
+
+    fn get(
+        &self,
+        text: &str,
+        language: AstLanguage,
+        dialect: Option<SqlDialect>,
+    ) -> Option<Arc<AstParseResult>> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let index = entries
+            .iter()
+            .position(|entry| entry.matches(text, language, dialect))?;
+        let entry = entries.remove(index)?;
+        let result = Arc::clone(&entry.result);
+        entries.push_front(entry);
+        drop(entries);
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuses_parsed_documents() {
+        let cache = AstCache::new();
+        let first = cache
+            .parse(r#"{"a": 1}"#, AstLanguage::Json, None, None)
+            .unwrap();
+        let again = cache
+            .parse(r#"{"a": 1}"#, AstLanguage::Json, None, None)
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+
+        let as_yaml = cache
+            .parse(r#"{"a": 1}"#, AstLanguage::Yaml, None, None)
+            .unwrap();
+        assert!(!Arc::ptr_eq(&first, &as_yaml));
+    }
+
+    #[test]
+    fn test_sql_dialect_is_part_of_the_key() {
+        let cache = AstCache::new();
+        let generic = cache
+            .parse("SELECT 1", AstLanguage::Sql, None, None)
+            .unwrap();
+        let explicit = cache
+            .parse(
+                "SELECT 1",
+                AstLanguage::Sql,
+                Some(SqlDialect::Generic),
+                None,
+            )
+            .unwrap();
+        let postgres = cache
+            .parse(
+                "SELECT 1",
+                AstLanguage::Sql,
+                Some(SqlDialect::Postgres),
+                None,
+            )
+            .unwrap();
+        assert!(Arc::ptr_eq(&generic, &explicit));
+        assert!(!Arc::ptr_eq(&generic, &postgres));
+        assert_eq!(postgres.dialect, Some(SqlDialect::Postgres));
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = AstCache::new();
+        let a = cache.parse("[1]", AstLanguage::Json, None, None).unwrap();
+        let b = cache.parse("[2]", AstLanguage::Json, None, None).unwrap();
+        // Touch `a` so `b` is evicted next.
+        assert!(Arc::ptr_eq(
+            &a,
+            &cache.parse("[1]", AstLanguage::Json, None, None).unwrap()
+        ));
+        cache.parse("[3]", AstLanguage::Json, None, None).unwrap();
+        assert!(Arc::ptr_eq(
+            &a,
+            &cache.parse("[1]", AstLanguage::Json, None, None).unwrap()
+        ));
+        assert!(!Arc::ptr_eq(
+            &b,
+            &cache.parse("[2]", AstLanguage::Json, None, None).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_cancelled_parse_is_not_cached() {
+        let cache = AstCache::new();
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            cache.parse("[1, [2, 3]]", AstLanguage::Json, None, Some(&token)),
+            Err(AstError::Cancelled)
+        ));
+        assert!(cache.get("[1, [2, 3]]", AstLanguage::Json, None).is_none());
+    }
+}
@@ -0,0 +1,265 @@
+//! Flat AST wire format
+//!
+//! Nested [`AstNode`] JSON repeats every field name and node type per node
+//! and makes serde recurse through the whole tree. [`CompactAst`] stores
+//! the same tree as parallel arrays in pre-order, with node types, paths,
+//! and labels interned into one string table. The frontend requests it
+//! with `format: "compact"` and rebuilds the tree on its side.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::{AstNamespace, AstNode, AstParseError, AstParseResult, SqlDialect};
+
+/// Values per node in [`CompactAst::nodes`]
+const NODE_STRIDE: usize = 4;
+
+/// Wire format requested for `parse_to_ast`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AstWireFormat {
+    /// Nested [`AstNode`] objects.
+    #[default]
+    Tree,
+    /// Flat [`CompactAst`] arrays.
+    Compact,
+}
+
+/// Flattened AST. Node `i` is described by `nodes[i * 4..i * 4 + 4]` and
+/// `ranges[i * 8..i * 8 + 8]`; node 0 is the root and every parent precedes
+/// its children, which appear in source order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactAst {
+    /// Interned node types, paths, and labels.
+    pub strings: Vec<String>,
+    /// Per node: type, path, and label string indices, then the parent
+    /// node index plus one (0 for the root).
+    pub nodes: Vec<u32>,
+    /// Per node: start line, column, UTF-16 column, offset, then the same
+    /// for the end.
+    pub ranges: Vec<usize>,
+    /// `[node index, value]` pairs for nodes that carry a value.
+    pub values: Vec<(u32, serde_json::Value)>,
+    /// `[node index, namespace]` pairs for namespaced XML nodes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<(u32, AstNamespace)>,
+}
+
+/// [`AstParseResult`] with a compact tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactAstParseResult {
+    /// Always [`AstWireFormat::Compact`], so the frontend can tell the two
+    /// shapes apart.
+    pub format: AstWireFormat,
+    /// Flattened tree (null if parsing failed).
+    pub ast: Option<CompactAst>,
+    /// Parse errors.
+    pub errors: Vec<AstParseError>,
+    /// SQL dialect the text was parsed with (SQL only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<SqlDialect>,
+}
+
+/// `parse_to_ast` response in the requested wire format.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AstParseResponse {
+    /// Nested tree, shared with the parse cache.
+    Tree(Arc<AstParseResult>),
+    /// Flattened tree.
+    Compact(CompactAstParseResult),
+}
+
+impl AstParseResponse {
+    /// Encode `result` in `format`. The nested tree is serialized straight
+    /// from the shared result, without copying it.
+    pub fn encode(result: Arc<AstParseResult>, format: AstWireFormat) -> Self {
+        match format {
+            AstWireFormat::Tree => Self::Tree(result),
+            AstWireFormat::Compact => Self::Compact(CompactAstParseResult {
+                format,
+                ast: result.ast.as_ref().map(CompactAst::from_tree),
+                errors: result.errors.clone(),
+                dialect: result.dialect,
+            }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Interner<'a> {
+    strings: Vec<String>,
+    index: HashMap<&'a str, u32>,
+}
+
+impl<'a> Interner<'a> {
+    fn intern(&mut self, s: &'a str) -> u32 {
+        *self.index.entry(s).or_insert_with(|| {
+            self.strings.push(s.to_string());
+            u32::try_from(self.strings.len() - 1).unwrap_or(u32::MAX)
+        })
+    }
+}
+
+impl CompactAst {
+    /// Flatten `root` in pre-order.
+    pub fn from_tree(root: &AstNode) -> Self {
+        let mut interner = Interner::default();
+        let mut nodes = Vec::new();
+        let mut ranges = Vec::new();
+        let mut values = Vec::new();
+        let mut namespaces = Vec::new();
+
+        // (node, parent index + 1); children are pushed in reverse so they
+        // pop in source order.
+        let mut stack = vec![(root, 0u32)];
+        while let Some((node, parent)) = stack.pop() {
+            let index = u32::try_from(nodes.len() / NODE_STRIDE).unwrap_or(u32::MAX);
+            nodes.extend([
+                interner.intern(node.node_type.as_str()),
+                interner.intern(&node.path),
+                interner.intern(&node.label),
+                parent,
+            ]);
+            let (start, end) = (node.range.start, node.range.end);
+            ranges.extend([
+                start.line,
+                start.column,
+                start.utf16_column,
+                start.offset,
+                end.line,
+                end.column,
+                end.utf16_column,
+                end.offset,
+            ]);
+            if let Some(value) = &node.value {
+                values.push((index, value.clone()));
+            }
+            if let Some(namespace) = &node.namespace {
+                namespaces.push((index, namespace.clone()));
+            }
+            for child in node.children.iter().flatten().rev() {
+                stack.push((child, index + 1));
+            }
+        }
+
+        Self {
+            strings: interner.strings,
+            nodes,
+            ranges,
+            values,
+            namespaces,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_to_ast, AstLanguage, AstNodeType, AstPosition, AstRange};
+    use super::*;
+
+    const RANGE_STRIDE: usize = 8;
+
+    /// Rebuild the nested tree the way the frontend does.
+    fn expand(ast: &CompactAst) -> AstNode {
+        let values: HashMap<u32, &serde_json::Value> =
+            ast.values.iter().map(|(i, v)| (*i, v)).collect();
+        let namespaces: HashMap<u32, &AstNamespace> =
+            ast.namespaces.iter().map(|(i, ns)| (*i, ns)).collect();
+        let mut built: Vec<AstNode> = Vec::with_capacity(ast.nodes.len() / NODE_STRIDE);
+        let mut parents = Vec::with_capacity(ast.nodes.len() / NODE_STRIDE);
+        for (i, node) in ast.nodes.chunks(NODE_STRIDE).enumerate() {
+            let r = &ast.ranges[i * RANGE_STRIDE..(i + 1) * RANGE_STRIDE];
+            let node_type: AstNodeType =
+                serde_json::from_value(serde_json::json!(ast.strings[node[0] as usize])).unwrap();
+            let mut built_node = AstNode::new(
+                node_type,
+                ast.strings[node[1] as usize].clone(),
+                ast.strings[node[2] as usize].clone(),
+                AstRange::new(
+                    AstPosition::new(r[0], r[1], r[3]).with_utf16_column(r[2]),
+                    AstPosition::new(r[4], r[5], r[7]).with_utf16_column(r[6]),
+                ),
+            );
+            built_node.value = values.get(&u32::try_from(i).unwrap()).map(|v| (*v).clone());
+            built_node.namespace = namespaces
+                .get(&u32::try_from(i).unwrap())
+                .map(|ns| (*ns).clone());
+            built.push(built_node);
+            parents.push(node[3]);
+        }
+        // Children follow their parent, so attach from the end.
+        while built.len() > 1 {
+            let node = built.pop().unwrap();
+            let parent = parents.pop().unwrap() as usize - 1;
+            built[parent]
+                .children
+                .get_or_insert_with(Vec::new)
+                .insert(0, node);
+        }
+        built.pop().unwrap()
+    }
+
+    #[test]
+    fn round_trips_parsed_documents() {
+        let sources = [
+            (
+                AstLanguage::Json,
+                r#"{"name":"kogu","tags":["a","b",{"deep":[1,2,null]}],"ok":true}"#,
+            ),
+            (
+                AstLanguage::Yaml,
+                "user:\n  name: John\n  roles: [admin, dev]\n",
+            ),
+            (AstLanguage::Xml, "<a x=\"1\"><b>text</b><!-- c --><b/></a>"),
+            (
+                AstLanguage::Xml,
+                r#"<s:a xmlns:s="urn:s" xmlns="urn:d"><b s:x="1"/></s:a>"#,
+            ),
+            (AstLanguage::Sql, "SELECT id, name FROM users WHERE id = 1"),
+        ];
+        for (language, text) in sources {
+            let tree = parse_to_ast(text, language).ast.unwrap();
+            let compact = CompactAst::from_tree(&tree);
+            assert_eq!(expand(&compact), tree, "{language:?}");
+        }
+    }
+
+    #[test]
+    fn tree_response_serializes_the_shared_result() {
+        let result = Arc::new(parse_to_ast(r#"{"a":[1,2]}"#, AstLanguage::Json));
+        let response = AstParseResponse::encode(Arc::clone(&result), AstWireFormat::Tree);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::to_value(&*result).unwrap()
+        );
+        let AstParseResponse::Tree(tree) = response else {
+            unreachable!("expected tree response");
+        };
+        assert!(Arc::ptr_eq(&tree, &result));
+    }
+
+    #[test]
+    fn interns_repeated_strings_and_shrinks_payload() {
+        let items: Vec<String> = (0..200)
+            .map(|i| format!(r#"{{"id":{i},"name":"item","active":true}}"#))
+            .collect();
+        let text = format!("[{}]", items.join(","));
+        let result = parse_to_ast(&text, AstLanguage::Json);
+        let tree_json = serde_json::to_string(&result).unwrap();
+
+        let response = AstParseResponse::encode(Arc::new(result), AstWireFormat::Compact);
+        let compact_json = serde_json::to_string(&response).unwrap();
+        let AstParseResponse::Compact(compact) = response else {
+            unreachable!("expected compact response");
+        };
+        let ast = compact.ast.unwrap();
+        assert_eq!(ast.nodes.len() / NODE_STRIDE, 1 + 200 * 4);
+        assert!(ast.strings.iter().filter(|s| *s == "number").count() == 1);
+        assert!(compact_json.len() * 2 < tree_json.len());
+        assert!(compact_json.contains(r#""format":"compact""#));
+    }
+}
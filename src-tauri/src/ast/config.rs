@@ -0,0 +1,456 @@
+//! Line-oriented config file AST parser with position tracking
+//!
+//! One parser serves the `key = value` family, with per-dialect rules:
+//!
+//! - INI: `[section]` headers, `=` or `:` separators, `;` and `#` comments
+//! - Java properties: `=`, `:`, or whitespace separators, `#` and `!`
+//!   comments, backslash line continuations, and `\uXXXX` escapes
+//! - .env: optional `export`, single/double/backtick quoted values that
+//!   may span lines, and trailing ` #` comments on unquoted values
+//!
+//! Keys become leaf properties (`$.database.host`); comments are kept as
+//! [`AstNodeType::Comment`] nodes so the tree mirrors the file.
+
+use super::{
+    child_path, truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange,
+};
+
+/// Config file dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Ini,
+    Properties,
+    DotEnv,
+}
+
+impl Dialect {
+    fn is_comment(self, line: &str) -> bool {
+        let markers: &[char] = match self {
+            Self::Ini => &[';', '#'],
+            Self::Properties => &['#', '!'],
+            Self::DotEnv => &['#'],
+        };
+        line.starts_with(markers)
+    }
+}
+
+/// Parse config text in `dialect` to AST with position information
+pub fn parse(text: &str, dialect: Dialect) -> AstParseResult {
+    match ConfigParser::new(text, dialect).parse() {
+        Ok(ast) => AstParseResult::success(ast),
+        Err(e) => AstParseResult::failure(vec![e]),
+    }
+}
+
+/// `key = value` entry before it becomes a node
+struct Entry {
+    key: String,
+    value: Option<String>,
+    start: usize,
+    end: usize,
+}
+
+/// INI section being filled
+struct Section {
+    name: String,
+    path: String,
+    start: usize,
+    header_end: usize,
+    children: Vec<AstNode>,
+}
+
+/// Count of properties (not comments) among `children`.
+fn property_count(children: &[AstNode]) -> usize {
+    children
+        .iter()
+        .filter(|node| node.node_type != AstNodeType::Comment)
+        .count()
+}
+
+struct ConfigParser<'a> {
+    text: &'a str,
+    dialect: Dialect,
+    pos: usize,
+}
+
+impl<'a> ConfigParser<'a> {
+    const fn new(text: &'a str, dialect: Dialect) -> Self {
+        Self {
+            text,
+            dialect,
+            pos: 0,
+        }
+    }
+
+    fn range(&self, start: usize, end: usize) -> AstRange {
+        AstRange::from_offset(self.text, start, end)
+    }
+
+    fn error(&self, start: usize, end: usize, message: &str) -> AstParseError {
+        AstParseError::new(message).with_range(self.range(start, end))
+    }
+
+    /// End of the line starting at `from` (before any `\r\n`), and the
+    /// start of the next line.
+    fn line_bounds(&self, from: usize) -> (usize, usize) {
+        let newline = self.text[from..]
+            .find('\n')
+            .map_or(self.text.len(), |i| from + i);
+        let next = (newline + 1).min(self.text.len());
+        let end = if self.text[from..newline].ends_with('\r') {
+            newline - 1
+        } else {
+            newline
+        };
+        (end, next)
+    }
+
+    fn parse(mut self) -> Result<AstNode, AstParseError> {
+        let mut root: Vec<AstNode> = Vec::new();
+        let mut section: Option<Section> = None;
+
+        while self.pos < self.text.len() {
+            let (line_end, next) = self.line_bounds(self.pos);
+            let line = &self.text[self.pos..line_end];
+            let content = line.trim();
+            let start = self.pos + (line.len() - line.trim_start().len());
+            let end = start + content.len();
+
+            let node = if content.is_empty() {
+                self.pos = next;
+                continue;
+            } else if self.dialect.is_comment(content) {
+                self.pos = next;
+                let path = section.as_ref().map_or("$", |s| s.path.as_str());
+                AstNode::new(
+                    AstNodeType::Comment,
+                    format!("{path}/#comment"),
+                    truncate_label(content),
+                    self.range(start, end),
+                )
+            } else if self.dialect == Dialect::Ini && content.starts_with('[') {
+                let Some(close) = content.find(']') else {
+                    return Err(self.error(start, end, "Unterminated section header"));
+                };
+                self.pos = next;
+                if let Some(done) = section.take() {
+                    root.push(self.section_node(done));
+                }
+                let name = content[1..close].trim().to_string();
+                section = Some(Section {
+                    path: child_path("$", &name),
+                    name,
+                    start,
+                    header_end: start + close + 1,
+                    children: Vec::new(),
+                });
+                continue;
+            } else {
+                let entry = match self.dialect {
+                    Dialect::Ini => self.ini_entry(start, end, next)?,
+                    Dialect::Properties => self.properties_entry(start),
+                    Dialect::DotEnv => self.dotenv_entry(start, end)?,
+                };
+                let path = section.as_ref().map_or("$", |s| s.path.as_str());
+                self.entry_node(entry, path)
+            };
+
+            match section.as_mut() {
+                Some(section) => section.children.push(node),
+                None => root.push(node),
+            }
+        }
+
+        if let Some(done) = section.take() {
+            root.push(self.section_node(done));
+        }
+        let label = format!("{{}} ({} properties)", property_count(&root));
+        Ok(AstNode::new(
+            AstNodeType::Object,
+            "$".to_string(),
+            label,
+            self.range(0, self.text.len()),
+        )
+        .with_children(root))
+    }
+
+    fn section_node(&self, section: Section) -> AstNode {
+        let end = section
+            .children
+            .last()
+            .map_or(section.header_end, |child| child.range.end.offset);
+        let label = format!(
+            "[{}] ({} properties)",
+            section.name,
+            property_count(&section.children)
+        );
+        AstNode::new(
+            AstNodeType::Object,
+            section.path,
+            label,
+            self.range(section.start, end),
+        )
+        .with_children(section.children)
+    }
+
+    fn entry_node(&self, entry: Entry, path: &str) -> AstNode {
+        let range = self.range(entry.start, entry.end);
+        let path = child_path(path, &entry.key);
+        match entry.value {
+            Some(value) => AstNode::new(AstNodeType::String, path, entry.key, range)
+                .with_value(serde_json::Value::String(value)),
+            None => AstNode::new(AstNodeType::Null, path, entry.key, range)
+                .with_value(serde_json::Value::Null),
+        }
+    }
+
+    /// `key = value` or `key: value`; a bare `key` has no value.
+    fn ini_entry(&mut self, start: usize, end: usize, next: usize) -> Result<Entry, AstParseError> {
+        self.pos = next;
+        let content = &self.text[start..end];
+        let (key, value) = content.find(['=', ':']).map_or((content, None), |i| {
+            (&content[..i], Some(content[i + 1..].trim().to_string()))
+        });
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(self.error(start, end, "Missing key before separator"));
+        }
+        Ok(Entry {
+            key: key.to_string(),
+            value,
+            start,
+            end,
+        })
+    }
+
+    /// Properties entry starting at `start`, joining lines that end in an
+    /// unescaped backslash.
+    fn properties_entry(&mut self, start: usize) -> Entry {
+        let mut logical = String::new();
+        let mut from = start;
+        let end = loop {
+            let (line_end, next) = self.line_bounds(from);
+            let line = self.text[from..line_end].trim_end();
+            let trailing = line.len() - line.trim_end_matches('\\').len();
+            self.pos = next;
+            if trailing % 2 == 1 && next < self.text.len() && next > line_end {
+                logical.push_str(&line[..line.len() - 1]);
+                // Leading whitespace of a continuation line is dropped.
+                let (continued_end, _) = self.line_bounds(next);
+                let continued = &self.text[next..continued_end];
+                from = next + (continued.len() - continued.trim_start().len());
+            } else {
+                logical.push_str(line);
+                break from + line.len();
+            }
+        };
+
+        // The key ends at the first unescaped separator or whitespace.
+        let mut escaped = false;
+        let split = logical
+            .char_indices()
+            .find(|&(_, c)| {
+                let separator = !escaped && (c == '=' || c == ':' || c.is_whitespace());
+                escaped = !escaped && c == '\\';
+                separator
+            })
+            .map_or(logical.len(), |(i, _)| i);
+        let (key, rest) = logical.split_at(split);
+        let rest = rest.trim_start();
+        let rest = rest.strip_prefix(['=', ':']).map_or(rest, str::trim_start);
+
+        Entry {
+            key: unescape_properties(key),
+            value: Some(unescape_properties(rest)),
+            start,
+            end,
+        }
+    }
+
+    /// `[export] KEY=value`, where a quoted value may run across lines.
+    fn dotenv_entry(&mut self, start: usize, end: usize) -> Result<Entry, AstParseError> {
+        let content = &self.text[start..end];
+        let body = content
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with([' ', '\t']))
+            .map_or(content, str::trim_start);
+        let Some(eq) = body.find('=') else {
+            return Err(self.error(start, end, "Expected '=' after variable name"));
+        };
+        let key = body[..eq].trim();
+        if key.is_empty() {
+            return Err(self.error(start, end, "Missing variable name before '='"));
+        }
+
+        let after_eq = end - body.len() + eq + 1;
+        let raw = &self.text[after_eq..end];
+        let value_start = after_eq + (raw.len() - raw.trim_start().len());
+        let quote = self.text[value_start..]
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\'' | '`'));
+
+        let Some(quote) = quote else {
+            // Unquoted: a `#` after whitespace starts a comment.
+            let value = &self.text[value_start..end];
+            let value = value
+                .find(" #")
+                .or_else(|| value.find("\t#"))
+                .map_or(value, |i| &value[..i])
+                .trim_end();
+            self.pos = self.line_bounds(start).1;
+            return Ok(Entry {
+                key: key.to_string(),
+                value: Some(value.to_string()),
+                start,
+                end: value_start + value.len(),
+            });
+        };
+
+        let mut value = String::new();
+        let mut chars = self.text[value_start + 1..].char_indices();
+        let close = loop {
+            let Some((i, c)) = chars.next() else {
+                return Err(self.error(value_start, value_start + 1, "Unterminated quoted value"));
+            };
+            match c {
+                c if c == quote => break value_start + 1 + i,
+                '\\' if quote == '"' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => {}
+                },
+                c => value.push(c),
+            }
+        };
+        self.pos = self.line_bounds(close).1;
+        Ok(Entry {
+            key: key.to_string(),
+            value: Some(value),
+            start,
+            end: close + 1,
+        })
+    }
+}
+
+/// Resolve properties escapes: `\t`, `\n`, `\r`, `\f`, `\uXXXX`, and
+/// `\x` for any other `x`.
+fn unescape_properties(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.clone().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) if hex.len() == 4 => {
+                        out.push(decoded);
+                        chars.nth(3);
+                    }
+                    _ => out.push('u'),
+                }
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_parse_ini_sections_and_comments() {
+        let ini = "; global\nname = demo\n\n[database]\nhost = localhost\nport: 5432\n# note\n[my section]\nflag\n";
+        let ast = parse(ini, Dialect::Ini).ast.unwrap();
+        assert_eq!(ast.label, "{} (3 properties)");
+
+        let root = children(&ast);
+        assert_eq!(root[0].node_type, AstNodeType::Comment);
+        assert_eq!(root[0].label, "; global");
+        assert_eq!(root[1].path, "$.name");
+
+        let database = &root[2];
+        assert_eq!(database.label, "[database] (2 properties)");
+        assert_eq!(database.range.start.line, 4);
+        assert_eq!(database.range.end.line, 7);
+        let port = &children(database)[1];
+        assert_eq!(port.path, "$.database.port");
+        assert_eq!(port.value, Some(serde_json::json!("5432")));
+        assert_eq!((port.range.start.line, port.range.start.column), (6, 1));
+        assert_eq!(children(database)[2].path, "$.database/#comment");
+
+        let flag = &children(&root[3])[0];
+        assert_eq!(flag.path, "$['my section'].flag");
+        assert_eq!(flag.node_type, AstNodeType::Null);
+    }
+
+    #[test]
+    fn test_parse_properties_escapes_and_continuations() {
+        let props = "! comment\nserver.port=8080\ngreeting : hello \\\n    world\nkey\\ with\\ spaces value\nunicode=caf\\u00e9\n";
+        let ast = parse(props, Dialect::Properties).ast.unwrap();
+        let entries = children(&ast);
+        assert_eq!(entries[0].node_type, AstNodeType::Comment);
+
+        assert_eq!(entries[1].path, "$['server.port']");
+        assert_eq!(entries[1].value, Some(serde_json::json!("8080")));
+
+        let greeting = &entries[2];
+        assert_eq!(greeting.value, Some(serde_json::json!("hello world")));
+        assert_eq!(greeting.range.start.line, 3);
+        assert_eq!(greeting.range.end.line, 4);
+
+        assert_eq!(entries[3].label, "key with spaces");
+        assert_eq!(entries[3].value, Some(serde_json::json!("value")));
+        assert_eq!(entries[4].value, Some(serde_json::json!("café")));
+    }
+
+    #[test]
+    fn test_parse_dotenv_quotes_and_export() {
+        let env = "# app\nexport API_URL=https://example.com # prod\nSECRET='a#b'\nCERT=\"line1\\nline2\"\nMULTI=\"first\nsecond\"\nEMPTY=\n";
+        let ast = parse(env, Dialect::DotEnv).ast.unwrap();
+        let entries = children(&ast);
+
+        assert_eq!(entries[1].path, "$.API_URL");
+        assert_eq!(
+            entries[1].value,
+            Some(serde_json::json!("https://example.com"))
+        );
+        assert_eq!(entries[2].value, Some(serde_json::json!("a#b")));
+        assert_eq!(entries[3].value, Some(serde_json::json!("line1\nline2")));
+
+        let multi = &entries[4];
+        assert_eq!(multi.value, Some(serde_json::json!("first\nsecond")));
+        assert_eq!((multi.range.start.line, multi.range.end.line), (5, 6));
+        assert_eq!(entries[5].value, Some(serde_json::json!("")));
+        assert_eq!(entries[5].range.start.line, 7);
+    }
+
+    #[test]
+    fn test_errors_have_ranges() {
+        let ini = parse("[open\nkey=value\n", Dialect::Ini);
+        assert!(ini.errors[0].message.contains("section header"));
+
+        let env = parse("OK=1\nBROKEN\n", Dialect::DotEnv);
+        assert_eq!(env.errors[0].range.unwrap().start.line, 2);
+
+        let quote = parse("KEY=\"never closed\n", Dialect::DotEnv);
+        assert!(quote.errors[0].message.contains("Unterminated"));
+    }
+}
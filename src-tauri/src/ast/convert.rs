@@ -0,0 +1,1209 @@
+//! Conversion between JSON, YAML, TOML, XML, and CSV
+//!
+//! Every input is read into a format-neutral [`Value`] tree whose maps
+//! keep their entry order, then written out in the target format.
+//! JSON, YAML, and TOML are read through their AST parsers, CSV through
+//! its record reader, and XML directly from the `roxmltree` document.
+//! Anything the target cannot represent faithfully (XML attributes, TOML
+//! nulls, nested CSV cells) is reported as a warning with the path of the
+//! first value it affected.
+
+use roxmltree::{Document, Node, ParsingOptions};
+use serde::{Deserialize, Serialize};
+
+use super::child_path;
+use super::format::{block_scalar, property_value, yaml_string, QuoteStyle};
+use super::xml::{create_parse_error, node_path};
+use super::{csv, parse_to_ast, AstError, AstLanguage, AstNode, AstNodeType, AstParseError};
+
+/// Options for [`convert_document`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConvertOptions {
+    /// Spaces per indentation level for JSON, YAML, and XML; 0 indents
+    /// JSON and XML with tabs. YAML always indents with at least two
+    /// spaces.
+    pub indent: usize,
+    /// Key prefix that XML attributes are read into and written from
+    pub attribute_prefix: String,
+    /// Key holding the text of an XML element that also has attributes
+    /// or child elements
+    pub text_key: String,
+    /// Root element for XML output that is not a map with a single key
+    pub root_element: String,
+    /// Field separator for CSV output
+    pub delimiter: char,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            attribute_prefix: "@".to_string(),
+            text_key: "#text".to_string(),
+            root_element: "root".to_string(),
+            delimiter: ',',
+        }
+    }
+}
+
+/// Information lost or changed by a conversion
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertWarning {
+    pub message: String,
+    /// Path of the first value affected
+    pub path: String,
+}
+
+/// Result of [`convert_document`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertResult {
+    /// Converted document; absent when the input failed to parse or
+    /// cannot be written in the target format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub warnings: Vec<ConvertWarning>,
+    pub errors: Vec<AstParseError>,
+}
+
+/// Convert `text` from one document format to another
+///
+/// # Errors
+///
+/// Returns [`AstError::UnsupportedLanguage`] when either language is not
+/// JSON, YAML, TOML, XML, or CSV.
+pub fn convert_document(
+    text: &str,
+    from: AstLanguage,
+    to: AstLanguage,
+    options: &ConvertOptions,
+) -> Result<ConvertResult, AstError> {
+    for language in [from, to] {
+        if !matches!(
+            language,
+            AstLanguage::Json
+                | AstLanguage::Yaml
+                | AstLanguage::Toml
+                | AstLanguage::Xml
+                | AstLanguage::Csv
+        ) {
+            let name = format!("{language:?}").to_lowercase();
+            return Err(AstError::UnsupportedLanguage(name));
+        }
+    }
+    let mut converter = Converter {
+        options,
+        warnings: Vec::new(),
+    };
+    let value = match converter.read(text, from) {
+        Ok(value) => value,
+        Err(errors) => {
+            return Ok(ConvertResult {
+                errors,
+                ..ConvertResult::default()
+            })
+        }
+    };
+    let written = converter.write(&value, to);
+    let warnings = converter.warnings;
+    Ok(match written {
+        Ok(text) => ConvertResult {
+            text: Some(text),
+            warnings,
+            errors: Vec::new(),
+        },
+        Err(error) => ConvertResult {
+            text: None,
+            warnings,
+            errors: vec![error],
+        },
+    })
+}
+
+/// Format-neutral document tree; maps keep their entry order
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    List(Vec<Self>),
+    Map(Vec<(String, Self)>),
+}
+
+impl Value {
+    fn from_json(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Self::Null,
+            serde_json::Value::Bool(b) => Self::Bool(*b),
+            serde_json::Value::Number(n) => Self::Number(n.clone()),
+            serde_json::Value::String(s) => Self::String(s.clone()),
+            serde_json::Value::Array(items) => {
+                Self::List(items.iter().map(Self::from_json).collect())
+            }
+            serde_json::Value::Object(map) => Self::Map(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), Self::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    const fn is_list(&self) -> bool {
+        matches!(self, Self::List(_))
+    }
+
+    const fn is_container(&self) -> bool {
+        matches!(self, Self::List(_) | Self::Map(_))
+    }
+
+    /// Text of a scalar, as written to XML and CSV
+    fn scalar_text(&self) -> String {
+        match self {
+            Self::Null => String::new(),
+            Self::Bool(b) => b.to_string(),
+            Self::Number(n) => n.to_string(),
+            Self::String(s) => s.clone(),
+            Self::List(_) | Self::Map(_) => json_compact(self),
+        }
+    }
+}
+
+/// Read a JSON document, keeping its key order
+pub(super) fn read_json(text: &str) -> Result<Value, Vec<AstParseError>> {
+    let options = ConvertOptions::default();
+    let mut converter = Converter {
+        options: &options,
+        warnings: Vec::new(),
+    };
+    converter.read(text, AstLanguage::Json)
+}
+
+/// Value of a node from the JSON, YAML, or TOML parser
+pub(super) fn read_ast_value(node: &AstNode) -> Value {
+    let options = ConvertOptions::default();
+    let mut converter = Converter {
+        options: &options,
+        warnings: Vec::new(),
+    };
+    converter.read_node(node)
+}
+
+/// Value of the XML element at AST `path`, read as [`convert_document`]
+/// reads elements
+pub(super) fn read_xml_element(text: &str, path: &str) -> Option<Value> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let doc = Document::parse_with_options(text, options).ok()?;
+    let element = doc
+        .descendants()
+        .find(|node| node.is_element() && node_path(*node) == path)?;
+    let convert_options = ConvertOptions::default();
+    let mut converter = Converter {
+        options: &convert_options,
+        warnings: Vec::new(),
+    };
+    Some(converter.read_element(element, path))
+}
+
+/// `value` as pretty-printed JSON with a trailing newline
+pub(super) fn json_text(value: &Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_json(&mut out, value, 0, &indent_unit(indent));
+    out.push('\n');
+    out
+}
+
+/// One indentation level: `indent` spaces, or a tab for 0
+fn indent_unit(indent: usize) -> String {
+    if indent == 0 {
+        "\t".to_string()
+    } else {
+        " ".repeat(indent)
+    }
+}
+
+struct Converter<'o> {
+    options: &'o ConvertOptions,
+    warnings: Vec<ConvertWarning>,
+}
+
+impl Converter<'_> {
+    /// Record a warning; a message already reported is not repeated.
+    fn warn(&mut self, path: &str, message: impl Into<String>) {
+        let message = message.into();
+        if !self.warnings.iter().any(|w| w.message == message) {
+            self.warnings.push(ConvertWarning {
+                message,
+                path: path.to_string(),
+            });
+        }
+    }
+
+    // ========================================================================
+    // Reading
+    // ========================================================================
+
+    fn read(&mut self, text: &str, from: AstLanguage) -> Result<Value, Vec<AstParseError>> {
+        match from {
+            AstLanguage::Xml => self.read_xml(text).map_err(|e| vec![e]),
+            AstLanguage::Csv => {
+                let rows = csv::read_rows(text).map_err(|e| vec![e])?;
+                Ok(Value::List(
+                    rows.into_iter()
+                        .map(|cells| {
+                            Value::Map(
+                                cells
+                                    .into_iter()
+                                    .map(|(name, value)| (name, Value::from_json(&value)))
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                ))
+            }
+            _ if from == AstLanguage::Yaml && text.trim().is_empty() => Ok(Value::Null),
+            _ => {
+                let result = parse_to_ast(text, from);
+                match result.ast {
+                    Some(root) if !result.has_errors() => Ok(self.read_node(&root)),
+                    _ => Err(result.errors),
+                }
+            }
+        }
+    }
+
+    fn read_node(&mut self, node: &AstNode) -> Value {
+        let children = node.children.iter().flatten();
+        match node.node_type {
+            AstNodeType::Object => Value::Map(
+                children
+                    .map(|property| {
+                        (
+                            property.label.clone(),
+                            self.read_node(property_value(property)),
+                        )
+                    })
+                    .collect(),
+            ),
+            AstNodeType::Array => Value::List(children.map(|item| self.read_node(item)).collect()),
+            AstNodeType::Unknown => {
+                self.warn(&node.path, "Unsupported values are converted to null");
+                Value::Null
+            }
+            _ => node.value.as_ref().map_or(Value::Null, Value::from_json),
+        }
+    }
+
+    fn read_xml(&mut self, text: &str) -> Result<Value, AstParseError> {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let doc =
+            Document::parse_with_options(text, options).map_err(|e| create_parse_error(&e))?;
+        if doc
+            .descendants()
+            .any(|node| node.is_comment() || node.is_pi())
+        {
+            self.warn("$", "XML comments and processing instructions are dropped");
+        }
+        let root = doc.root_element();
+        let name = root.tag_name().name().to_string();
+        let value = self.read_element(root, &child_path("$", &name));
+        Ok(Value::Map(vec![(name, value)]))
+    }
+
+    /// Value of an element: a map of its attributes, child elements, and
+    /// text, or just its text when it has neither attributes nor children.
+    /// Repeated child elements become a list.
+    fn read_element(&mut self, element: Node<'_, '_>, path: &str) -> Value {
+        if element.tag_name().namespace().is_some() {
+            self.warn(
+                path,
+                "XML namespaces are dropped; elements keep their local names",
+            );
+        }
+        let prefix = self.options.attribute_prefix.clone();
+        let mut entries: Vec<(String, Value)> = element
+            .attributes()
+            .map(|a| {
+                (
+                    format!("{prefix}{}", a.name()),
+                    Value::String(a.value().to_string()),
+                )
+            })
+            .collect();
+        if !entries.is_empty() {
+            self.warn(
+                path,
+                format!("XML attributes are converted to keys prefixed with '{prefix}'"),
+            );
+        }
+        let mut text = String::new();
+        let mut has_children = false;
+        let mut previous: Option<&str> = None;
+        for child in element.children() {
+            if let Some(child_text) = child.text().filter(|_| child.is_text()) {
+                text.push_str(child_text);
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+            has_children = true;
+            let name = child.tag_name().name();
+            let key_path = child_path(path, name);
+            match entries.iter().position(|(key, _)| key == name) {
+                None => {
+                    let value = self.read_element(child, &key_path);
+                    entries.push((name.to_string(), value));
+                }
+                Some(index) => {
+                    if previous != Some(name) {
+                        self.warn(
+                            &key_path,
+                            "Repeated XML elements that are not adjacent are grouped together",
+                        );
+                    }
+                    let count = match &entries[index].1 {
+                        Value::List(items) => items.len(),
+                        _ => 1,
+                    };
+                    let value = self.read_element(child, &format!("{key_path}[{count}]"));
+                    let slot = &mut entries[index].1;
+                    match slot {
+                        Value::List(items) => items.push(value),
+                        _ => *slot = Value::List(vec![std::mem::replace(slot, Value::Null), value]),
+                    }
+                }
+            }
+            previous = Some(name);
+        }
+        let trimmed = text.trim();
+        if entries.is_empty() {
+            return if trimmed.is_empty() {
+                Value::Null
+            } else {
+                Value::String(text)
+            };
+        }
+        if !trimmed.is_empty() {
+            if has_children {
+                self.warn(
+                    path,
+                    "Text mixed with child elements is joined into one value",
+                );
+            }
+            entries.push((
+                self.options.text_key.clone(),
+                Value::String(trimmed.to_string()),
+            ));
+        }
+        Value::Map(entries)
+    }
+
+    // ========================================================================
+    // Writing
+    // ========================================================================
+
+    fn write(&mut self, value: &Value, to: AstLanguage) -> Result<String, AstParseError> {
+        match to {
+            AstLanguage::Yaml => Ok(self.write_yaml(value)),
+            AstLanguage::Toml => self.write_toml(value),
+            AstLanguage::Xml => Ok(self.write_xml(value)),
+            AstLanguage::Csv => self.write_csv(value),
+            _ => Ok(json_text(value, self.options.indent)),
+        }
+    }
+
+    fn write_yaml(&self, value: &Value) -> String {
+        // YAML has no tab indentation, and `- ` needs two columns.
+        let unit = " ".repeat(self.options.indent.max(2));
+        let mut out = String::new();
+        if is_empty_container(value) || !value.is_container() {
+            out.push_str(&yaml_scalar(value));
+            out.push('\n');
+        } else {
+            write_yaml_entries(&mut out, value, 0, false, &unit);
+        }
+        out
+    }
+
+    fn write_toml(&mut self, value: &Value) -> Result<String, AstParseError> {
+        let Value::Map(entries) = value else {
+            return Err(AstParseError::new(
+                "TOML documents must be a table at the top level",
+            ));
+        };
+        let mut out = String::new();
+        self.write_toml_table(&mut out, &[], entries, "$");
+        Ok(out)
+    }
+
+    /// Write the plain keys of a table, then its sub-tables and arrays of
+    /// tables under their own headers
+    fn write_toml_table(
+        &mut self,
+        out: &mut String,
+        header: &[&str],
+        entries: &[(String, Value)],
+        path: &str,
+    ) {
+        for (key, value) in entries {
+            if is_toml_table(value) || is_toml_table_array(value) {
+                continue;
+            }
+            let value_path = child_path(path, key);
+            if *value == Value::Null {
+                self.warn(&value_path, "TOML has no null; null values are left out");
+                continue;
+            }
+            let inline = self.toml_inline(value, &value_path);
+            out.push_str(&toml_key(key));
+            out.push_str(" = ");
+            out.push_str(&inline);
+            out.push('\n');
+        }
+        for (key, value) in entries {
+            let mut nested = header.to_vec();
+            nested.push(key);
+            let value_path = child_path(path, key);
+            let name = nested
+                .iter()
+                .map(|k| toml_key(k))
+                .collect::<Vec<_>>()
+                .join(".");
+            match value {
+                Value::Map(table) if is_toml_table(value) => {
+                    let has_values = table.is_empty()
+                        || table
+                            .iter()
+                            .any(|(_, v)| !is_toml_table(v) && !is_toml_table_array(v));
+                    if has_values {
+                        if !out.is_empty() {
+                            out.push('\n');
+                        }
+                        out.push('[');
+                        out.push_str(&name);
+                        out.push_str("]\n");
+                    }
+                    self.write_toml_table(out, &nested, table, &value_path);
+                }
+                Value::List(items) if is_toml_table_array(value) => {
+                    for (i, item) in items.iter().enumerate() {
+                        if let Value::Map(table) = item {
+                            if !out.is_empty() {
+                                out.push('\n');
+                            }
+                            out.push_str("[[");
+                            out.push_str(&name);
+                            out.push_str("]]\n");
+                            self.write_toml_table(
+                                out,
+                                &nested,
+                                table,
+                                &format!("{value_path}[{i}]"),
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn toml_inline(&mut self, value: &Value, path: &str) -> String {
+        match value {
+            Value::Null => String::new(),
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) if n.is_f64() => {
+                let text = n.to_string();
+                if text.contains(['.', 'e', 'E']) {
+                    text
+                } else {
+                    format!("{text}.0")
+                }
+            }
+            Value::Number(n) if n.as_i64().is_none() => {
+                self.warn(
+                    path,
+                    "Integers beyond the TOML range are written as strings",
+                );
+                toml_string(&n.to_string())
+            }
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => toml_string(s),
+            Value::List(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| self.toml_item(item, &format!("{path}[{i}]")))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            Value::Map(entries) if entries.is_empty() => "{}".to_string(),
+            Value::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .filter_map(|(key, item)| {
+                        let inline = self.toml_item(item, &child_path(path, key))?;
+                        Some(format!("{} = {inline}", toml_key(key)))
+                    })
+                    .collect();
+                format!("{{ {} }}", entries.join(", "))
+            }
+        }
+    }
+
+    /// Inline value of an array item or inline table entry; `None` for nulls
+    fn toml_item(&mut self, value: &Value, path: &str) -> Option<String> {
+        if *value == Value::Null {
+            self.warn(path, "TOML has no null; null values are left out");
+            return None;
+        }
+        Some(self.toml_inline(value, path))
+    }
+
+    fn write_xml(&mut self, value: &Value) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        match value {
+            Value::Map(entries)
+                if entries.len() == 1
+                    && !entries[0].1.is_list()
+                    && !self.is_attribute(&entries[0].0)
+                    && entries[0].0 != self.options.text_key =>
+            {
+                let (key, value) = &entries[0];
+                self.write_xml_element(&mut out, key, value, 0, &child_path("$", key));
+            }
+            _ => {
+                let root = self.options.root_element.clone();
+                self.write_xml_element(&mut out, &root, value, 0, "$");
+            }
+        }
+        out
+    }
+
+    fn is_attribute(&self, key: &str) -> bool {
+        !self.options.attribute_prefix.is_empty() && key.starts_with(&self.options.attribute_prefix)
+    }
+
+    /// Write `value` under `key`: one element, or one per item of a list
+    fn write_xml_entry(
+        &mut self,
+        out: &mut String,
+        key: &str,
+        value: &Value,
+        depth: usize,
+        path: &str,
+    ) {
+        if let Value::List(items) = value {
+            for (i, item) in items.iter().enumerate() {
+                self.write_xml_element(out, key, item, depth, &format!("{path}[{i}]"));
+            }
+        } else {
+            self.write_xml_element(out, key, value, depth, path);
+        }
+    }
+
+    fn write_xml_element(
+        &mut self,
+        out: &mut String,
+        key: &str,
+        value: &Value,
+        depth: usize,
+        path: &str,
+    ) {
+        let unit = indent_unit(self.options.indent);
+        let name = self.xml_name(key, path);
+        out.push_str(&unit.repeat(depth));
+        out.push('<');
+        out.push_str(&name);
+        let children: Vec<(&str, &Value, String)> = match value {
+            Value::Map(entries) => {
+                let mut children = Vec::new();
+                let mut text = None;
+                for (key, item) in entries {
+                    let item_path = child_path(path, key);
+                    if self.is_attribute(key) && !item.is_container() {
+                        let attribute = &key[self.options.attribute_prefix.len()..];
+                        let attribute = self.xml_name(attribute, &item_path);
+                        self.warn_typed(item, &item_path);
+                        out.push(' ');
+                        out.push_str(&attribute);
+                        out.push_str("=\"");
+                        out.push_str(&escape_xml(&item.scalar_text(), true));
+                        out.push('"');
+                    } else if *key == self.options.text_key && !item.is_container() {
+                        self.warn_typed(item, &item_path);
+                        text = Some(item.scalar_text());
+                    } else {
+                        children.push((key.as_str(), item, item_path));
+                    }
+                }
+                match (children.is_empty(), text) {
+                    (true, None) => {
+                        out.push_str("/>\n");
+                        return;
+                    }
+                    (true, Some(text)) => {
+                        out.push('>');
+                        out.push_str(&escape_xml(&text, false));
+                        close_tag(out, &name);
+                        return;
+                    }
+                    (false, text) => {
+                        out.push_str(">\n");
+                        if let Some(text) = text {
+                            out.push_str(&unit.repeat(depth + 1));
+                            out.push_str(&escape_xml(&text, false));
+                            out.push('\n');
+                        }
+                    }
+                }
+                children
+            }
+            Value::List(items) if !items.is_empty() => {
+                self.warn(path, "Nested lists are written as <item> elements");
+                out.push_str(">\n");
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| ("item", item, format!("{path}[{i}]")))
+                    .collect()
+            }
+            Value::List(_) | Value::Null => {
+                if *value == Value::Null {
+                    self.warn(
+                        path,
+                        "XML has no null; null values are written as empty elements",
+                    );
+                }
+                out.push_str("/>\n");
+                return;
+            }
+            scalar => {
+                self.warn_typed(scalar, path);
+                out.push('>');
+                out.push_str(&escape_xml(&scalar.scalar_text(), false));
+                close_tag(out, &name);
+                return;
+            }
+        };
+        for (key, item, item_path) in children {
+            self.write_xml_entry(out, key, item, depth + 1, &item_path);
+        }
+        out.push_str(&unit.repeat(depth));
+        close_tag(out, &name);
+    }
+
+    fn warn_typed(&mut self, value: &Value, path: &str) {
+        if matches!(value, Value::Bool(_) | Value::Number(_)) {
+            self.warn(
+                path,
+                "XML has no value types; numbers and booleans are written as text",
+            );
+        }
+    }
+
+    /// `key` as an XML name, replacing characters a name cannot contain
+    fn xml_name(&mut self, key: &str, path: &str) -> String {
+        let mut name: String = key
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            name.insert(0, '_');
+        }
+        if name != key {
+            self.warn(
+                path,
+                format!("'{key}' is not a valid XML name and is written as '{name}'"),
+            );
+        }
+        name
+    }
+
+    fn write_csv(&mut self, value: &Value) -> Result<String, AstParseError> {
+        let rows: Vec<&Value> = match value {
+            Value::List(items) => items.iter().collect(),
+            Value::Map(_) => vec![value],
+            _ => {
+                return Err(AstParseError::new(
+                    "CSV output needs a list of records or a single record",
+                ))
+            }
+        };
+        let mut columns: Vec<&str> = Vec::new();
+        for row in &rows {
+            let keys: Vec<&str> = match row {
+                Value::Map(entries) => entries.iter().map(|(key, _)| key.as_str()).collect(),
+                _ => vec!["value"],
+            };
+            for key in keys {
+                if !columns.contains(&key) {
+                    columns.push(key);
+                }
+            }
+        }
+        let delimiter = self.options.delimiter;
+        let separator = delimiter.to_string();
+        let mut lines = vec![columns
+            .iter()
+            .map(|column| csv_field(column, delimiter))
+            .collect::<Vec<_>>()
+            .join(&separator)];
+        for (i, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = columns
+                .iter()
+                .map(|column| {
+                    let cell = match row {
+                        Value::Map(entries) => entries
+                            .iter()
+                            .find(|(key, _)| key == column)
+                            .map(|(_, value)| value),
+                        other => (*column == "value").then_some(*other),
+                    };
+                    let Some(cell) = cell else {
+                        return String::new();
+                    };
+                    if cell.is_container() {
+                        self.warn(
+                            &child_path(&format!("$[{i}]"), column),
+                            format!("Nested values in column '{column}' are written as JSON text"),
+                        );
+                    }
+                    csv_field(&cell.scalar_text(), delimiter)
+                })
+                .collect();
+            lines.push(cells.join(&separator));
+        }
+        let mut out = lines.join("\n");
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+const fn is_empty_container(value: &Value) -> bool {
+    match value {
+        Value::List(items) => items.is_empty(),
+        Value::Map(entries) => entries.is_empty(),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+fn write_json(out: &mut String, value: &Value, depth: usize, unit: &str) {
+    let (open, close, items): (char, char, Vec<(Option<&str>, &Value)>) = match value {
+        Value::List(items) if !items.is_empty() => {
+            ('[', ']', items.iter().map(|v| (None, v)).collect())
+        }
+        Value::Map(entries) if !entries.is_empty() => (
+            '{',
+            '}',
+            entries.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+        ),
+        _ => {
+            out.push_str(&json_compact(value));
+            return;
+        }
+    };
+    out.push(open);
+    out.push('\n');
+    for (i, (key, item)) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.push_str(",\n");
+        }
+        out.push_str(&unit.repeat(depth + 1));
+        if let Some(key) = key {
+            out.push_str(&json_string(key));
+            out.push_str(": ");
+        }
+        write_json(out, item, depth + 1, unit);
+    }
+    out.push('\n');
+    out.push_str(&unit.repeat(depth));
+    out.push(close);
+}
+
+fn json_compact(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => json_string(s),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(json_compact).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}:{}", json_string(key), json_compact(value)))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string()
+}
+
+// ============================================================================
+// YAML
+// ============================================================================
+
+/// Write the entries of a non-empty mapping or sequence, one per line at
+/// `depth`. With `inline_first`, the first entry continues the current
+/// line (after a sequence dash).
+fn write_yaml_entries(
+    out: &mut String,
+    value: &Value,
+    depth: usize,
+    inline_first: bool,
+    unit: &str,
+) {
+    let entries: Vec<(Option<&str>, &Value)> = match value {
+        Value::Map(entries) => entries.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+        Value::List(items) => items.iter().map(|v| (None, v)).collect(),
+        _ => return,
+    };
+    for (i, (key, item)) in entries.into_iter().enumerate() {
+        if i > 0 || !inline_first {
+            out.push_str(&unit.repeat(depth));
+        }
+        if let Some(key) = key {
+            out.push_str(&yaml_string(key, QuoteStyle::Double));
+            out.push(':');
+        } else {
+            out.push('-');
+        }
+        if item.is_container() && !is_empty_container(item) {
+            if key.is_some() {
+                out.push('\n');
+                write_yaml_entries(out, item, depth + 1, false, unit);
+            } else {
+                out.push_str(&" ".repeat(unit.len() - 1));
+                write_yaml_entries(out, item, depth + 1, true, unit);
+            }
+            continue;
+        }
+        let block = match item {
+            Value::String(s) => block_scalar(s),
+            _ => None,
+        };
+        if let Some((chomp, lines)) = block {
+            out.push_str(" |");
+            out.push_str(chomp);
+            out.push('\n');
+            for line in lines {
+                if !line.is_empty() {
+                    out.push_str(&unit.repeat(depth + 1));
+                    out.push_str(line);
+                }
+                out.push('\n');
+            }
+        } else {
+            out.push(' ');
+            out.push_str(&yaml_scalar(item));
+            out.push('\n');
+        }
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => yaml_string(s, QuoteStyle::Double),
+        Value::List(_) => "[]".to_string(),
+        Value::Map(_) => "{}".to_string(),
+        scalar => json_compact(scalar),
+    }
+}
+
+// ============================================================================
+// TOML
+// ============================================================================
+
+/// Map, written as a `[table]`
+const fn is_toml_table(value: &Value) -> bool {
+    matches!(value, Value::Map(_))
+}
+
+/// Non-empty list of maps, written as `[[array of tables]]`
+fn is_toml_table_array(value: &Value) -> bool {
+    matches!(value, Value::List(items) if !items.is_empty() && items.iter().all(|item| matches!(item, Value::Map(_))))
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+fn toml_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => {
+                let escape = format!("\\u{:04X}", u32::from(c));
+                out.push_str(&escape);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// ============================================================================
+// XML and CSV
+// ============================================================================
+
+fn close_tag(out: &mut String, name: &str) {
+    out.push_str("</");
+    out.push_str(name);
+    out.push_str(">\n");
+}
+
+fn escape_xml(s: &str, attribute: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            '\n' if attribute => out.push_str("&#10;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `s` as a CSV field, quoted when it holds the delimiter, a quote, a
+/// line break, or surrounding whitespace
+fn csv_field(s: &str, delimiter: char) -> String {
+    let quote = s.contains([delimiter, '"', '\n', '\r']) || s.trim() != s;
+    if quote {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(text: &str, from: AstLanguage, to: AstLanguage) -> ConvertResult {
+        convert_document(text, from, to, &ConvertOptions::default()).unwrap()
+    }
+
+    fn messages(result: &ConvertResult) -> Vec<&str> {
+        result.warnings.iter().map(|w| w.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_json_to_yaml_keeps_key_order() {
+        let result = convert(
+            r#"{"b": 1, "a": {"y": [1, "two", null], "x": "multi\nline\n"}}"#,
+            AstLanguage::Json,
+            AstLanguage::Yaml,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some("b: 1\na:\n  y:\n    - 1\n    - two\n    - null\n  x: |\n    multi\n    line\n")
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_yaml_to_json() {
+        let result = convert(
+            "name: kogu\ntags:\n  - a\n  - b\n",
+            AstLanguage::Yaml,
+            AstLanguage::Json,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some("{\n  \"name\": \"kogu\",\n  \"tags\": [\n    \"a\",\n    \"b\"\n  ]\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_xml_to_json_reports_attributes() {
+        let result = convert(
+            "<book id=\"7\"><!-- note --><title>Rust</title><tag>a</tag><tag>b</tag><empty/></book>",
+            AstLanguage::Xml,
+            AstLanguage::Json,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some(concat!(
+                "{\n  \"book\": {\n    \"@id\": \"7\",\n    \"title\": \"Rust\",\n",
+                "    \"tag\": [\n      \"a\",\n      \"b\"\n    ],\n    \"empty\": null\n  }\n}\n"
+            ))
+        );
+        assert_eq!(
+            messages(&result),
+            [
+                "XML comments and processing instructions are dropped",
+                "XML attributes are converted to keys prefixed with '@'",
+            ]
+        );
+        assert_eq!(result.warnings[1].path, "$.book");
+    }
+
+    #[test]
+    fn test_json_to_xml() {
+        let result = convert(
+            r#"{"book": {"@id": 7, "title": "A & B", "tag": ["a", "b"], "2nd": null}}"#,
+            AstLanguage::Json,
+            AstLanguage::Xml,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some(concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<book id=\"7\">\n  <title>A &amp; B</title>\n  <tag>a</tag>\n  <tag>b</tag>\n",
+                "  <_2nd/>\n</book>\n"
+            ))
+        );
+        assert_eq!(
+            messages(&result),
+            [
+                "XML has no value types; numbers and booleans are written as text",
+                "'2nd' is not a valid XML name and is written as '_2nd'",
+                "XML has no null; null values are written as empty elements",
+            ]
+        );
+        assert_eq!(result.warnings[0].path, "$.book['@id']");
+    }
+
+    #[test]
+    fn test_json_to_xml_wraps_in_root_element() {
+        let result = convert("[1, 2]", AstLanguage::Json, AstLanguage::Xml);
+        assert_eq!(
+            result.text.as_deref(),
+            Some(concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<root>\n  <item>1</item>\n  <item>2</item>\n</root>\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_json_to_toml() {
+        let result = convert(
+            r#"{"name": "x", "deps": {"serde": {"version": "1"}}, "bin": [{"name": "a"}], "n": null, "v": 1.0, "list": [1, null]}"#,
+            AstLanguage::Json,
+            AstLanguage::Toml,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some(concat!(
+                "name = \"x\"\nv = 1.0\nlist = [1]\n\n[deps.serde]\nversion = \"1\"\n\n",
+                "[[bin]]\nname = \"a\"\n"
+            ))
+        );
+        assert_eq!(
+            messages(&result),
+            ["TOML has no null; null values are left out"]
+        );
+        assert_eq!(result.warnings[0].path, "$.n");
+    }
+
+    #[test]
+    fn test_toml_to_json_keeps_key_order() {
+        let result = convert(
+            "[package]\nname = \"kogu\"\nedition = \"2021\"\n",
+            AstLanguage::Toml,
+            AstLanguage::Json,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some(
+                "{\n  \"package\": {\n    \"name\": \"kogu\",\n    \"edition\": \"2021\"\n  }\n}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_toml_requires_table_root() {
+        let result = convert("[1, 2]", AstLanguage::Json, AstLanguage::Toml);
+        assert!(result.text.is_none());
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_json_to_csv() {
+        let result = convert(
+            r#"[{"a": 1, "b": "x, y"}, {"b": "z", "c": {"d": 1}}]"#,
+            AstLanguage::Json,
+            AstLanguage::Csv,
+        );
+        assert_eq!(
+            result.text.as_deref(),
+            Some("a,b,c\n1,\"x, y\",\n,z,\"{\"\"d\"\":1}\"\n")
+        );
+        assert_eq!(
+            messages(&result),
+            ["Nested values in column 'c' are written as JSON text"]
+        );
+        assert_eq!(result.warnings[0].path, "$[1].c");
+    }
+
+    #[test]
+    fn test_csv_to_json() {
+        let result = convert("name,age\nAlice,30\n", AstLanguage::Csv, AstLanguage::Json);
+        assert_eq!(
+            result.text.as_deref(),
+            Some("[\n  {\n    \"name\": \"Alice\",\n    \"age\": 30\n  }\n]\n")
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_reports_errors() {
+        let result = convert("{\"a\": ", AstLanguage::Json, AstLanguage::Yaml);
+        assert!(result.text.is_none());
+        assert!(!result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        let result = convert_document(
+            "SELECT 1",
+            AstLanguage::Sql,
+            AstLanguage::Json,
+            &ConvertOptions::default(),
+        );
+        assert!(matches!(result, Err(AstError::UnsupportedLanguage(name)) if name == "sql"));
+    }
+}
@@ -0,0 +1,591 @@
+//! Crontab AST parser with position tracking
+//!
+//! Each job line becomes a [`AstNodeType::Statement`] labelled with its
+//! schedule and a description of it ("At 02:30 on Monday through
+//! Friday"). Its children are the five schedule fields, or the `@daily`
+//! style macro, followed by the command and any `%`-separated input.
+//! `NAME=value` lines become leaf properties and comments are kept, so
+//! the tree mirrors the file. Field values are checked with the
+//! scheduler's cron rules; an invalid field is reported with its range
+//! and the rest of the file is still parsed.
+
+use serde_json::{json, Value};
+
+use super::{truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+use crate::scheduler::CronSchedule;
+
+/// Schedule field names, in order
+const FIELD_NAMES: [&str; 5] = ["minute", "hour", "day-of-month", "month", "day-of-week"];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Day-of-week names; 7 is Sunday as well as 0
+const WEEKDAYS: [&str; 8] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Parse crontab text to AST with position information
+pub fn parse(text: &str) -> AstParseResult {
+    let mut nodes = Vec::new();
+    let mut errors = Vec::new();
+    let mut entries = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end();
+        let indent = content.len() - content.trim_start().len();
+        let content = content.trim_start();
+        let start = start + indent;
+        let range = AstRange::from_offset(text, start, start + content.len());
+
+        if content.is_empty() {
+            continue;
+        }
+        if content.starts_with('#') {
+            nodes.push(AstNode::new(
+                AstNodeType::Comment,
+                "$/#comment".to_string(),
+                truncate_label(content),
+                range,
+            ));
+            continue;
+        }
+        if let Some(node) = variable(content, range) {
+            nodes.push(node);
+            continue;
+        }
+        match entry(text, content, start, entries) {
+            Ok((node, field_errors)) => {
+                nodes.push(node);
+                errors.extend(field_errors);
+                entries += 1;
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+
+    let ast = AstNode::new(
+        AstNodeType::Root,
+        "$".to_string(),
+        format!("Crontab ({entries} entries)"),
+        AstRange::from_offset(text, 0, text.len()),
+    )
+    .with_children(nodes);
+    if errors.is_empty() {
+        AstParseResult::success(ast)
+    } else {
+        AstParseResult::partial(ast, errors)
+    }
+}
+
+/// `NAME=value` environment setting, with optional quotes around the value
+fn variable(content: &str, range: AstRange) -> Option<AstNode> {
+    let (name, value) = content.split_once('=')?;
+    let name = name.trim_end();
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !identifier {
+        return None;
+    }
+    let value = value.trim();
+    let value = ['"', '\'']
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value);
+    Some(
+        AstNode::new(
+            AstNodeType::String,
+            format!("$.env.{name}"),
+            name.to_string(),
+            range,
+        )
+        .with_value(Value::String(value.to_string())),
+    )
+}
+
+/// Whitespace-separated words of `line` as byte spans
+fn words(line: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices() {
+        match (start, c.is_whitespace()) {
+            (Some(word_start), true) => {
+                words.push((word_start, i));
+                start = None;
+            }
+            (None, false) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push((word_start, line.len()));
+    }
+    words
+}
+
+/// Fields a schedule macro stands for; `@reboot` is not a schedule
+fn expand_macro(name: &str) -> Option<[&'static str; 5]> {
+    match name {
+        "@yearly" | "@annually" => Some(["0", "0", "1", "1", "*"]),
+        "@monthly" => Some(["0", "0", "1", "*", "*"]),
+        "@weekly" => Some(["0", "0", "*", "*", "0"]),
+        "@daily" | "@midnight" => Some(["0", "0", "*", "*", "*"]),
+        "@hourly" => Some(["0", "*", "*", "*", "*"]),
+        _ => None,
+    }
+}
+
+/// Job line starting at byte `start` of `text`. Invalid field values are
+/// returned alongside the node; a line that is not a job is an error.
+fn entry(
+    text: &str,
+    content: &str,
+    start: usize,
+    index: usize,
+) -> Result<(AstNode, Vec<AstParseError>), AstParseError> {
+    let path = format!("$.entries[{index}]");
+    let range = |from: usize, to: usize| AstRange::from_offset(text, start + from, start + to);
+    let words = words(content);
+    let mut children = Vec::new();
+    let mut errors = Vec::new();
+
+    let (schedule, description, command_start) = if content.starts_with('@') {
+        let (from, to) = words[0];
+        let name = &content[from..to];
+        let description = if name == "@reboot" {
+            "At startup".to_string()
+        } else {
+            expand_macro(name).map(describe).ok_or_else(|| {
+                AstParseError::new(format!("Unknown schedule macro: {name}"))
+                    .with_range(range(from, to))
+            })?
+        };
+        children.push(
+            AstNode::new(
+                AstNodeType::Keyword,
+                format!("{path}.schedule"),
+                name.to_string(),
+                range(from, to),
+            )
+            .with_value(Value::String(name.to_string())),
+        );
+        (name.to_string(), Some(description), words.get(1))
+    } else {
+        if words.len() < 6 {
+            return Err(
+                AstParseError::new("Expected five schedule fields followed by a command")
+                    .with_range(range(0, content.len())),
+            );
+        }
+        let mut fields = [""; 5];
+        for (i, &(from, to)) in words.iter().take(5).enumerate() {
+            let field = &content[from..to];
+            fields[i] = field;
+            if let Err(message) = CronSchedule::check_field(i, field) {
+                errors.push(
+                    AstParseError::new(format!("Invalid {} field: {message}", FIELD_NAMES[i]))
+                        .with_range(range(from, to)),
+                );
+            }
+            children.push(
+                AstNode::new(
+                    AstNodeType::Property,
+                    format!("{path}.{}", FIELD_NAMES[i].replace('-', "_")),
+                    format!("{}: {field}", FIELD_NAMES[i]),
+                    range(from, to),
+                )
+                .with_value(Value::String(field.to_string())),
+            );
+        }
+        let description = errors.is_empty().then(|| describe(fields));
+        (fields.join(" "), description, words.get(5))
+    };
+
+    let Some(&(command_start, _)) = command_start else {
+        return Err(AstParseError::new("Missing command after the schedule")
+            .with_range(range(0, content.len())));
+    };
+    let (command, input) = split_input(&content[command_start..]);
+    let command_end = command_start + command.raw_len;
+    children.push(
+        AstNode::new(
+            AstNodeType::Text,
+            format!("{path}.command"),
+            truncate_label(&command.text),
+            range(command_start, command_end),
+        )
+        .with_value(Value::String(command.text.clone())),
+    );
+    if let Some(input) = input {
+        children.push(
+            AstNode::new(
+                AstNodeType::Text,
+                format!("{path}.input"),
+                truncate_label(&input),
+                range(command_end + 1, content.len()),
+            )
+            .with_value(Value::String(input)),
+        );
+    }
+
+    let label = description.as_ref().map_or_else(
+        || schedule.clone(),
+        |description| format!("{schedule} ({description})"),
+    );
+    let node = AstNode::new(AstNodeType::Statement, path, label, range(0, content.len()))
+        .with_value(json!({
+            "schedule": schedule,
+            "description": description,
+            "command": command.text,
+        }))
+        .with_children(children);
+    Ok((node, errors))
+}
+
+/// Command text with `\%` unescaped, and how many source bytes it spans
+struct Command {
+    text: String,
+    raw_len: usize,
+}
+
+/// Split a job's command at the first unescaped `%`. Later `%`s in the
+/// input stand for newlines, as cron feeds it to the command's stdin.
+fn split_input(raw: &str) -> (Command, Option<String>) {
+    let mut text = String::new();
+    let mut chars = raw.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if chars.peek().is_some_and(|&(_, next)| next == '%') => {
+                text.push('%');
+                chars.next();
+            }
+            '%' => {
+                let input = raw[i + 1..].replace('%', "\n");
+                let raw_len = raw[..i].trim_end().len();
+                text.truncate(text.trim_end().len());
+                return (Command { text, raw_len }, Some(input));
+            }
+            c => text.push(c),
+        }
+    }
+    (
+        Command {
+            text,
+            raw_len: raw.len(),
+        },
+        None,
+    )
+}
+
+/// Phrase for a schedule ("At 02:30 on Monday through Friday")
+fn describe(fields: [&str; 5]) -> String {
+    let [minute, hour, day, month, weekday] = fields;
+    let number = |field: &str| field.parse::<u32>().ok();
+    let hours: Option<Vec<u32>> = hour.split(',').map(number).collect();
+
+    let mut parts = vec![match (number(minute), hours) {
+        _ if minute == "*" && hour == "*" => "Every minute".to_string(),
+        (_, _) if hour == "*" && minute.starts_with("*/") => {
+            capitalize(&phrase(minute, &Unit::MINUTE))
+        }
+        (Some(minute), Some(hours)) => format!(
+            "At {}",
+            join(
+                &hours
+                    .iter()
+                    .map(|hour| format!("{hour:02}:{minute:02}"))
+                    .collect::<Vec<_>>()
+            )
+        ),
+        _ => format!(
+            "At {} past {}",
+            phrase(minute, &Unit::MINUTE),
+            phrase(hour, &Unit::HOUR)
+        ),
+    }];
+
+    let on = |field: &str, unit: &Unit| {
+        let phrase = phrase(field, unit);
+        if phrase.starts_with("every") {
+            phrase
+        } else {
+            format!("on {phrase}")
+        }
+    };
+    match (day == "*", weekday == "*") {
+        (true, true) => {}
+        (false, true) => parts.push(on(day, &Unit::DAY)),
+        (true, false) => parts.push(on(weekday, &Unit::WEEKDAY)),
+        // Either day field matching is enough when both are restricted.
+        (false, false) => parts.push(format!(
+            "{} or {}",
+            on(day, &Unit::DAY),
+            on(weekday, &Unit::WEEKDAY)
+        )),
+    }
+    if month != "*" {
+        let phrase = phrase(month, &Unit::MONTH);
+        parts.push(if phrase.starts_with("every") {
+            phrase
+        } else {
+            format!("in {phrase}")
+        });
+    }
+    parts.join(" ")
+}
+
+/// How values of a schedule field read in a description
+struct Unit {
+    singular: &'static str,
+    plural: &'static str,
+    names: &'static [&'static str],
+    /// Value of the first name
+    first: u32,
+}
+
+impl Unit {
+    const MINUTE: Self = Self::numeric("minute", "minutes");
+    const HOUR: Self = Self::numeric("hour", "hours");
+    const DAY: Self = Self::numeric("day", "days");
+    const MONTH: Self = Self {
+        singular: "month",
+        plural: "months",
+        names: &MONTHS,
+        first: 1,
+    };
+    const WEEKDAY: Self = Self {
+        singular: "day of the week",
+        plural: "days of the week",
+        names: &WEEKDAYS,
+        first: 0,
+    };
+
+    const fn numeric(singular: &'static str, plural: &'static str) -> Self {
+        Self {
+            singular,
+            plural,
+            names: &[],
+            first: 0,
+        }
+    }
+
+    /// Name for `value`, a number or a three-letter abbreviation
+    fn name(&self, value: &str) -> Option<&'static str> {
+        if self.names.is_empty() {
+            return None;
+        }
+        let by_number = value
+            .parse::<u32>()
+            .ok()
+            .and_then(|n| n.checked_sub(self.first))
+            .and_then(|n| self.names.get(usize::try_from(n).ok()?));
+        let by_abbreviation = || {
+            self.names
+                .iter()
+                .find(|name| value.len() == 3 && name[..3].eq_ignore_ascii_case(value))
+        };
+        by_number.or_else(by_abbreviation).copied()
+    }
+}
+
+/// Phrase for one schedule field: "every 15 minutes", "Monday through
+/// Friday", "hours 9 and 17"
+fn phrase(field: &str, unit: &Unit) -> String {
+    let items: Vec<String> = field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            let bounds = range.split_once('-');
+            let value = |value: &str| {
+                unit.name(value)
+                    .map_or_else(|| value.to_string(), str::to_string)
+            };
+            let span = match bounds {
+                Some((from, to)) if unit.names.is_empty() => {
+                    format!("{} {from} through {to}", unit.plural)
+                }
+                Some((from, to)) => format!("{} through {}", value(from), value(to)),
+                None => unit
+                    .name(range)
+                    .map_or_else(|| format!("{} {range}", unit.singular), str::to_string),
+            };
+            match (range, step) {
+                ("*", None) => format!("every {}", unit.singular),
+                ("*", Some(step)) => format!("every {step} {}", unit.plural),
+                (_, Some(step)) if bounds.is_none() => {
+                    format!("every {step} {} from {}", unit.plural, value(range))
+                }
+                (_, Some(step)) => format!(
+                    "every {step} {} from {}",
+                    unit.plural,
+                    span.trim_start_matches(unit.plural).trim_start()
+                ),
+                (_, None) => span,
+            }
+        })
+        .collect();
+
+    // "hours 9 and 17" rather than "hour 9 and hour 17"
+    let singles = items.len() > 1
+        && unit.names.is_empty()
+        && field.split(',').all(|item| item.parse::<u32>().is_ok());
+    if singles {
+        return format!(
+            "{} {}",
+            unit.plural,
+            join(&field.split(',').collect::<Vec<_>>())
+        );
+    }
+    join(&items)
+}
+
+/// "a", "a and b", "a, b and c"
+fn join<S: AsRef<str>>(items: &[S]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.as_ref().to_string(),
+        [rest @ .., last] => format!(
+            "{} and {}",
+            rest.iter()
+                .map(AsRef::as_ref)
+                .collect::<Vec<_>>()
+                .join(", "),
+            last.as_ref()
+        ),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_parse_entries_variables_and_comments() {
+        let crontab = "# backups\nMAILTO=\"ops@example.com\"\n*/15 * * * * /usr/bin/sync --quiet\n30 2 * * 1-5 backup.sh > /var/log/backup.log 2>&1\n@reboot /opt/app/start\n";
+        let result = parse(crontab);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "Crontab (3 entries)");
+
+        let nodes = children(&ast);
+        assert_eq!(nodes[0].node_type, AstNodeType::Comment);
+        assert_eq!(nodes[1].path, "$.env.MAILTO");
+        assert_eq!(nodes[1].value, Some(json!("ops@example.com")));
+
+        let sync = &nodes[2];
+        assert_eq!(sync.node_type, AstNodeType::Statement);
+        assert_eq!(sync.path, "$.entries[0]");
+        assert_eq!(sync.label, "*/15 * * * * (Every 15 minutes)");
+        let fields = children(sync);
+        assert_eq!(fields[0].label, "minute: */15");
+        assert_eq!(fields[2].path, "$.entries[0].day_of_month");
+        assert_eq!(fields[5].path, "$.entries[0].command");
+        assert_eq!(fields[5].value, Some(json!("/usr/bin/sync --quiet")));
+        assert_eq!(
+            (fields[5].range.start.line, fields[5].range.start.column),
+            (3, 14)
+        );
+
+        assert_eq!(
+            nodes[3].value.as_ref().unwrap()["description"],
+            json!("At 02:30 on Monday through Friday")
+        );
+        assert_eq!(nodes[4].label, "@reboot (At startup)");
+        assert_eq!(children(&nodes[4])[0].node_type, AstNodeType::Keyword);
+    }
+
+    #[test]
+    fn test_describe_schedules() {
+        for (schedule, description) in [
+            ("* * * * *", "Every minute"),
+            ("0 */2 * * *", "At minute 0 past every 2 hours"),
+            ("0 9,17 * * *", "At 09:00 and 17:00"),
+            ("5 0 1 jan,jul *", "At 00:05 on day 1 in January and July"),
+            ("0 0 1,15 * sun", "At 00:00 on days 1 and 15 or on Sunday"),
+            (
+                "0-30/10 8-18 * * *",
+                "At every 10 minutes from 0 through 30 past hours 8 through 18",
+            ),
+        ] {
+            let fields: Vec<&str> = schedule.split(' ').collect();
+            assert_eq!(
+                describe(fields.try_into().unwrap()),
+                description,
+                "{schedule}"
+            );
+        }
+        assert_eq!(
+            describe(expand_macro("@weekly").unwrap()),
+            "At 00:00 on Sunday"
+        );
+    }
+
+    #[test]
+    fn test_command_input() {
+        let ast = parse("0 0 * * * mail -s \"50\\% off\" root%Hello%World\n")
+            .ast
+            .unwrap();
+        let fields = children(&children(&ast)[0]);
+        assert_eq!(fields[5].value, Some(json!("mail -s \"50% off\" root")));
+        assert_eq!(fields[6].path, "$.entries[0].input");
+        assert_eq!(fields[6].value, Some(json!("Hello\nWorld")));
+        assert_eq!(fields[6].range.start.column, 35);
+    }
+
+    #[test]
+    fn test_invalid_fields_have_ranges() {
+        let result = parse("61 * * * * job\n0 0 * 13 * other\n* * *\n@often job\n");
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "Crontab (2 entries)");
+        assert_eq!(children(&ast)[0].label, "61 * * * *");
+
+        let errors = &result.errors;
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].message.starts_with("Invalid minute field"));
+        let month = errors[1].range.unwrap();
+        assert_eq!(
+            (month.start.line, month.start.column, month.end.column),
+            (2, 7, 9)
+        );
+        assert!(errors[2].message.contains("five schedule fields"));
+        assert_eq!(errors[3].message, "Unknown schedule macro: @often");
+    }
+}
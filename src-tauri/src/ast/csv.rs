@@ -0,0 +1,463 @@
+//! CSV/TSV AST parser with position tracking
+//!
+//! The delimiter (comma, tab, semicolon, or pipe) is detected from the
+//! first lines, and the first record is taken as the header. Each data
+//! record becomes an object whose cells are keyed by column name, like
+//! the JSON array a CSV-to-JSON conversion would produce. Column types
+//! are inferred from the data and shown on the header (`age: number`).
+
+use super::{truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+
+/// Candidate delimiters; earlier entries win ties.
+const DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// Lines sampled for delimiter detection
+const SAMPLE_LINES: usize = 20;
+
+/// Parse CSV or TSV text to AST with position information
+pub fn parse(text: &str) -> AstParseResult {
+    let delimiter = detect_delimiter(text);
+    match parse_records(text, delimiter) {
+        Ok(records) => AstParseResult::success(build_tree(text, delimiter, &records)),
+        Err(e) => AstParseResult::failure(vec![e]),
+    }
+}
+
+/// Data records as `(column name, typed value)` pairs in column order,
+/// named and typed as in the AST, for document conversion
+pub(super) fn read_rows(
+    text: &str,
+) -> Result<Vec<Vec<(String, serde_json::Value)>>, AstParseError> {
+    let records = parse_records(text, detect_delimiter(text))?;
+    let Some((header, rows)) = records.split_first() else {
+        return Ok(Vec::new());
+    };
+    let names: Vec<&str> = header.iter().map(|cell| cell.value.trim()).collect();
+    let column_types: Vec<ColumnType> = (0..header.len())
+        .map(|index| infer_column_type(rows, index))
+        .collect();
+    Ok(rows
+        .iter()
+        .map(|record| {
+            record
+                .iter()
+                .enumerate()
+                .map(|(index, cell)| {
+                    let column_type = column_types
+                        .get(index)
+                        .copied()
+                        .unwrap_or(ColumnType::String);
+                    let name = names
+                        .get(index)
+                        .filter(|name| !name.is_empty())
+                        .map_or_else(|| format!("column {}", index + 1), ToString::to_string);
+                    (name, cell_value(&cell.value, column_type).1)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Inferred column type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ColumnType {
+    Number,
+    Boolean,
+    String,
+    /// No non-empty cells
+    Empty,
+}
+
+impl ColumnType {
+    pub(super) const fn as_str(self) -> &'static str {
+        match self {
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::String => "string",
+            Self::Empty => "empty",
+        }
+    }
+}
+
+/// One field with its unquoted value and byte range (quotes included).
+struct Cell {
+    value: String,
+    start: usize,
+    end: usize,
+}
+
+/// Delimiter that splits the sampled lines into the most consistent
+/// number of fields, falling back to a comma.
+pub(super) fn detect_delimiter(text: &str) -> u8 {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .collect();
+
+    let mut best = (b',', 0, 0);
+    for delimiter in DELIMITERS {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|line| count_unquoted(line, delimiter))
+            .collect();
+        let Some(&first) = counts.first() else {
+            break;
+        };
+        if first == 0 {
+            continue;
+        }
+        let consistent = counts.iter().filter(|&&count| count == first).count();
+        if (consistent, first) > (best.1, best.2) {
+            best = (delimiter, consistent, first);
+        }
+    }
+    best.0
+}
+
+/// Occurrences of `delimiter` outside double quotes.
+fn count_unquoted(line: &str, delimiter: u8) -> usize {
+    let mut quoted = false;
+    line.bytes()
+        .filter(|&b| {
+            if b == b'"' {
+                quoted = !quoted;
+            }
+            !quoted && b == delimiter
+        })
+        .count()
+}
+
+/// Split `text` into records per RFC 4180: quoted fields may contain
+/// delimiters, newlines, and doubled quotes. Blank lines are skipped.
+///
+/// Scans bytes: delimiters, quotes, and line breaks are ASCII, so every
+/// split lands on a character boundary.
+fn parse_records(text: &str, delimiter: u8) -> Result<Vec<Vec<Cell>>, AstParseError> {
+    let bytes = text.as_bytes();
+    let mut records = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let mut record = Vec::new();
+        loop {
+            let start = pos;
+            let mut value = String::new();
+            if bytes.get(pos) == Some(&b'"') {
+                pos += 1;
+                loop {
+                    let Some(quote) = text[pos..].find('"') else {
+                        return Err(AstParseError::new("Unterminated quoted field")
+                            .with_range(AstRange::from_offset(text, start, start + 1)));
+                    };
+                    value.push_str(&text[pos..pos + quote]);
+                    pos += quote + 1;
+                    if bytes.get(pos) == Some(&b'"') {
+                        value.push('"');
+                        pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            // Unquoted field, or stray text after a closing quote.
+            let rest = pos;
+            while pos < bytes.len()
+                && !matches!(bytes[pos], b'\n' | b'\r')
+                && bytes[pos] != delimiter
+            {
+                pos += 1;
+            }
+            value.push_str(&text[rest..pos]);
+            record.push(Cell {
+                value,
+                start,
+                end: pos,
+            });
+
+            if bytes.get(pos) == Some(&delimiter) {
+                pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        if bytes.get(pos) == Some(&b'\r') {
+            pos += 1;
+        }
+        if bytes.get(pos) == Some(&b'\n') {
+            pos += 1;
+        }
+        let blank = record.len() == 1 && record[0].start == record[0].end;
+        if !blank {
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Whether `s` is a plain decimal number (`-12`, `3.5`, `1e-3`).
+pub(super) fn is_number(s: &str) -> bool {
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    digits.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '-' | '+'))
+        && s.parse::<f64>().is_ok()
+}
+
+pub(super) const fn is_boolean(s: &str) -> bool {
+    s.eq_ignore_ascii_case("true") || s.eq_ignore_ascii_case("false")
+}
+
+/// Type shared by every non-empty cell in column `index`.
+fn infer_column_type(records: &[Vec<Cell>], index: usize) -> ColumnType {
+    let mut values = records
+        .iter()
+        .filter_map(|record| record.get(index))
+        .map(|cell| cell.value.trim())
+        .filter(|value| !value.is_empty())
+        .peekable();
+
+    if values.peek().is_none() {
+        return ColumnType::Empty;
+    }
+    let (mut number, mut boolean) = (true, true);
+    for value in values {
+        number &= is_number(value);
+        boolean &= is_boolean(value);
+    }
+    if number {
+        ColumnType::Number
+    } else if boolean {
+        ColumnType::Boolean
+    } else {
+        ColumnType::String
+    }
+}
+
+/// Cell node typed by its column; empty cells are null.
+pub(super) fn cell_value(value: &str, column_type: ColumnType) -> (AstNodeType, serde_json::Value) {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return (AstNodeType::Null, serde_json::Value::Null);
+    }
+    match column_type {
+        ColumnType::Number => trimmed
+            .parse::<i64>()
+            .map(serde_json::Value::from)
+            .ok()
+            .or_else(|| {
+                trimmed
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(serde_json::Value::Number)
+            })
+            .map_or_else(
+                || (AstNodeType::String, serde_json::json!(value)),
+                |number| (AstNodeType::Number, number),
+            ),
+        ColumnType::Boolean => (
+            AstNodeType::Boolean,
+            serde_json::Value::Bool(trimmed.eq_ignore_ascii_case("true")),
+        ),
+        ColumnType::String | ColumnType::Empty => (AstNodeType::String, serde_json::json!(value)),
+    }
+}
+
+/// Path segment for a column: its name when that is a unique, plain
+/// identifier, bracketed when unique but not plain, else its index.
+fn column_segment(names: &[&str], index: usize) -> String {
+    let Some(name) = names.get(index).filter(|name| !name.is_empty()) else {
+        return format!("[{index}]");
+    };
+    if names.iter().filter(|other| *other == name).count() > 1 {
+        return format!("[{index}]");
+    }
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!(".{name}")
+    } else {
+        format!("['{}']", name.replace('\'', "\\'"))
+    }
+}
+
+fn build_tree(text: &str, delimiter: u8, records: &[Vec<Cell>]) -> AstNode {
+    let format = if delimiter == b'\t' { "TSV" } else { "CSV" };
+    let root_range = AstRange::from_offset(text, 0, text.len());
+    let delimiter = serde_json::json!(char::from(delimiter).to_string());
+
+    let Some((header, rows)) = records.split_first() else {
+        let label = format!("{format} (0 rows)");
+        return AstNode::new(AstNodeType::Array, "$".to_string(), label, root_range)
+            .with_value(delimiter);
+    };
+
+    let names: Vec<&str> = header.iter().map(|cell| cell.value.trim()).collect();
+    let column_types: Vec<ColumnType> = (0..header.len())
+        .map(|index| infer_column_type(rows, index))
+        .collect();
+
+    let columns: Vec<AstNode> = header
+        .iter()
+        .enumerate()
+        .map(|(index, cell)| {
+            let name = names[index];
+            AstNode::new(
+                AstNodeType::Property,
+                format!("$.header[{index}]"),
+                format!("{name}: {}", column_types[index].as_str()),
+                AstRange::from_offset(text, cell.start, cell.end),
+            )
+            .with_value(serde_json::json!(name))
+        })
+        .collect();
+    let header_node = AstNode::new(
+        AstNodeType::Array,
+        "$.header".to_string(),
+        format!("header ({} columns)", columns.len()),
+        record_range(text, header),
+    )
+    .with_children(columns);
+
+    let mut children = vec![header_node];
+    children.extend(rows.iter().enumerate().map(|(row_index, record)| {
+        let row_path = format!("$.rows[{row_index}]");
+        let cells: Vec<AstNode> = record
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                let column_type = column_types
+                    .get(index)
+                    .copied()
+                    .unwrap_or(ColumnType::String);
+                let (node_type, value) = cell_value(&cell.value, column_type);
+                let name = names
+                    .get(index)
+                    .filter(|name| !name.is_empty())
+                    .map_or_else(|| format!("column {}", index + 1), ToString::to_string);
+                let value_label = match &value {
+                    serde_json::Value::String(s) => format!("\"{}\"", truncate_label(s)),
+                    other => other.to_string(),
+                };
+                AstNode::new(
+                    node_type,
+                    format!("{row_path}{}", column_segment(&names, index)),
+                    format!("{name}: {value_label}"),
+                    AstRange::from_offset(text, cell.start, cell.end),
+                )
+                .with_value(value)
+            })
+            .collect();
+        AstNode::new(
+            AstNodeType::Object,
+            row_path,
+            format!("{{}} ({} fields)", cells.len()),
+            record_range(text, record),
+        )
+        .with_children(cells)
+    }));
+
+    let label = format!("{format} ({} rows, {} columns)", rows.len(), header.len());
+    AstNode::new(AstNodeType::Array, "$".to_string(), label, root_range)
+        .with_value(delimiter)
+        .with_children(children)
+}
+
+fn record_range(text: &str, record: &[Cell]) -> AstRange {
+    let start = record.first().map_or(0, |cell| cell.start);
+    let end = record.last().map_or(start, |cell| cell.end);
+    AstRange::from_offset(text, start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_parse_with_header_and_types() {
+        let csv = "name,age,active\nAlice,30,true\nBob,,FALSE\n";
+        let ast = parse(csv).ast.unwrap();
+        assert_eq!(ast.label, "CSV (2 rows, 3 columns)");
+        assert_eq!(ast.value, Some(serde_json::json!(",")));
+
+        let header = &children(&ast)[0];
+        let labels: Vec<&str> = children(header).iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["name: string", "age: number", "active: boolean"]);
+
+        let bob = &children(&ast)[2];
+        assert_eq!(bob.path, "$.rows[1]");
+        let cells = children(bob);
+        assert_eq!(cells[0].path, "$.rows[1].name");
+        assert_eq!(cells[0].label, "name: \"Bob\"");
+        assert_eq!(cells[1].node_type, AstNodeType::Null);
+        assert_eq!(cells[2].value, Some(serde_json::json!(false)));
+        assert_eq!(cells[2].range.start.line, 3);
+        assert_eq!(cells[2].range.start.column, 6);
+    }
+
+    #[test]
+    fn test_detects_tab_and_semicolon_delimiters() {
+        let tsv = parse("id\tscore\n1\t2.5\n2\t3\n").ast.unwrap();
+        assert_eq!(tsv.label, "TSV (2 rows, 2 columns)");
+        let score = &children(&children(&tsv)[1])[1];
+        assert_eq!(score.value, Some(serde_json::json!(2.5)));
+
+        // Commas inside the values do not outvote the semicolons.
+        let semicolon = parse("city;note\nParis;\"a, b\"\nRome;c\n").ast.unwrap();
+        assert_eq!(semicolon.value, Some(serde_json::json!(";")));
+        assert_eq!(
+            children(&children(&semicolon)[1])[1].label,
+            "note: \"a, b\""
+        );
+    }
+
+    #[test]
+    fn test_quoted_fields_and_ranges() {
+        let csv = "first name,quote\r\n\"Ann\",\"said \"\"hi\"\"\nthen left\"\r\n\r\nZed,x\r\n";
+        let ast = parse(csv).ast.unwrap();
+        assert_eq!(ast.label, "CSV (2 rows, 2 columns)");
+
+        let first = children(&children(&ast)[1]);
+        assert_eq!(first[0].path, "$.rows[0]['first name']");
+        assert_eq!(first[0].range.start.offset, 18);
+        assert_eq!(first[0].range.end.offset, 23);
+        assert_eq!(
+            first[1].value,
+            Some(serde_json::json!("said \"hi\"\nthen left"))
+        );
+        assert_eq!(first[1].range.end.line, 3);
+
+        let zed = &children(&ast)[2];
+        assert_eq!(zed.range.start.line, 5);
+    }
+
+    #[test]
+    fn test_ragged_rows_and_duplicate_columns() {
+        let ast = parse("a,a\n1,2,3\n").ast.unwrap();
+        let cells = children(&children(&ast)[1]);
+        let paths: Vec<&str> = cells.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["$.rows[0][0]", "$.rows[0][1]", "$.rows[0][2]"]);
+        assert_eq!(cells[2].label, "column 3: \"3\"");
+    }
+
+    #[test]
+    fn test_unterminated_quote_is_an_error() {
+        let result = parse("a,b\n1,\"open\n");
+        assert!(result.ast.is_none());
+        let range = result.errors[0].range.unwrap();
+        assert_eq!((range.start.line, range.start.column), (2, 3));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let ast = parse("").ast.unwrap();
+        assert_eq!(ast.label, "CSV (0 rows)");
+        assert!(ast.children.is_none());
+    }
+}
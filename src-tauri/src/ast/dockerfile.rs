@@ -0,0 +1,783 @@
+//! Dockerfile AST parser with position tracking
+//!
+//! Each instruction becomes a [`AstNodeType::Statement`] whose children
+//! are its `--flags` ([`AstNodeType::Attribute`]), arguments
+//! ([`AstNodeType::Literal`], or properties for `ENV`/`LABEL`/`ARG`
+//! pairs), and heredoc bodies ([`AstNodeType::Text`]). Every `FROM`
+//! opens a build stage that groups the instructions up to the next
+//! `FROM`. Line continuations honour the `# escape=` parser directive.
+
+use super::{
+    child_path, truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange,
+};
+
+/// Known instruction keywords
+const INSTRUCTIONS: [&str; 18] = [
+    "ADD",
+    "ARG",
+    "CMD",
+    "COPY",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "FROM",
+    "HEALTHCHECK",
+    "LABEL",
+    "MAINTAINER",
+    "ONBUILD",
+    "RUN",
+    "SHELL",
+    "STOPSIGNAL",
+    "USER",
+    "VOLUME",
+    "WORKDIR",
+];
+
+/// Parser directives recognised at the top of the file
+const DIRECTIVES: [&str; 3] = ["syntax", "escape", "check"];
+
+/// Parse Dockerfile text to AST with position information
+pub fn parse(text: &str) -> AstParseResult {
+    match DockerfileParser::new(text).parse() {
+        Ok(ast) => AstParseResult::success(ast),
+        Err(e) => AstParseResult::failure(vec![e]),
+    }
+}
+
+/// Instruction text with continuations joined, plus the source offset of
+/// every byte so spans inside it map back to the file.
+struct Logical {
+    text: String,
+    offsets: Vec<usize>,
+    start: usize,
+    end: usize,
+}
+
+impl Logical {
+    fn push(&mut self, source: &str, start: usize) {
+        self.text.push_str(source);
+        self.offsets.extend(start..start + source.len());
+    }
+
+    /// Source range of `text[from..to]`.
+    fn range(&self, source: &str, from: usize, to: usize) -> AstRange {
+        let start = self.offsets.get(from).copied().unwrap_or(self.end);
+        let end = to
+            .checked_sub(1)
+            .and_then(|last| self.offsets.get(last))
+            .map_or(start, |last| last + 1)
+            .max(start);
+        AstRange::from_offset(source, start, end)
+    }
+}
+
+/// Build stage being filled
+struct Stage {
+    index: usize,
+    label: String,
+    name: Option<String>,
+    start: usize,
+    children: Vec<AstNode>,
+}
+
+impl Stage {
+    fn path(&self) -> String {
+        format!("$.stages[{}]", self.index)
+    }
+
+    fn into_node(self, source: &str) -> AstNode {
+        let path = self.path();
+        let end = self
+            .children
+            .last()
+            .map_or(self.start, |child| child.range.end.offset);
+        let node = AstNode::new(
+            AstNodeType::Object,
+            path,
+            self.label,
+            AstRange::from_offset(source, self.start, end),
+        );
+        let node = match self.name {
+            Some(name) => node.with_value(serde_json::Value::String(name)),
+            None => node,
+        };
+        node.with_children(self.children)
+    }
+}
+
+/// Whitespace-separated words of `text` from `from`, keeping quoted
+/// runs together.
+fn split_words(text: &str, from: usize) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    for (i, c) in text[from..].char_indices() {
+        let i = from + i;
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                start.get_or_insert(i);
+            }
+            (None, c) if c.is_whitespace() => {
+                if let Some(word_start) = start.take() {
+                    words.push((word_start, i));
+                }
+            }
+            (None, _) => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(word_start) = start {
+        words.push((word_start, text.len()));
+    }
+    words
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Elements of a JSON exec-form array (`["npm", "start"]`) with the span
+/// of each string literal in `args`.
+fn exec_form(args: &str) -> Option<Vec<(String, usize, usize)>> {
+    let values: Vec<String> = serde_json::from_str(args).ok()?;
+    let mut spans = Vec::new();
+    let mut chars = args.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c != '"' {
+            continue;
+        }
+        let mut escaped = false;
+        for (i, c) in chars.by_ref() {
+            if c == '"' && !escaped {
+                spans.push((start, i + 1));
+                break;
+            }
+            escaped = !escaped && c == '\\';
+        }
+    }
+    (spans.len() == values.len()).then(|| {
+        values
+            .into_iter()
+            .zip(spans)
+            .map(|(value, (start, end))| (value, start, end))
+            .collect()
+    })
+}
+
+/// `# key=value` parser directive.
+fn parse_directive(comment: &str) -> Option<(String, String)> {
+    let (key, value) = comment.strip_prefix('#')?.split_once('=')?;
+    let key = key.trim().to_ascii_lowercase();
+    let value = value.trim();
+    (DIRECTIVES.contains(&key.as_str()) && !value.is_empty()).then(|| (key, value.to_string()))
+}
+
+struct DockerfileParser<'a> {
+    text: &'a str,
+    pos: usize,
+    escape: char,
+}
+
+impl<'a> DockerfileParser<'a> {
+    const fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            escape: '\\',
+        }
+    }
+
+    /// End of the line starting at `from` (before any `\r\n`), and the
+    /// start of the next line.
+    fn line_bounds(&self, from: usize) -> (usize, usize) {
+        let newline = self.text[from..]
+            .find('\n')
+            .map_or(self.text.len(), |i| from + i);
+        let next = (newline + 1).min(self.text.len());
+        let end = if self.text[from..newline].ends_with('\r') {
+            newline - 1
+        } else {
+            newline
+        };
+        (end, next)
+    }
+
+    fn parse(mut self) -> Result<AstNode, AstParseError> {
+        let mut root: Vec<AstNode> = Vec::new();
+        let mut stage: Option<Stage> = None;
+        let mut stage_count = 0;
+        let mut directives_allowed = true;
+
+        while self.pos < self.text.len() {
+            let (line_end, next) = self.line_bounds(self.pos);
+            let line = &self.text[self.pos..line_end];
+            let content = line.trim();
+            let start = self.pos + (line.len() - line.trim_start().len());
+
+            if content.is_empty() {
+                directives_allowed = false;
+                self.pos = next;
+                continue;
+            }
+
+            if content.starts_with('#') {
+                self.pos = next;
+                let range = AstRange::from_offset(self.text, start, start + content.len());
+                if directives_allowed {
+                    if let Some(directive) = self.directive_node(content, range) {
+                        root.push(directive);
+                        continue;
+                    }
+                }
+                directives_allowed = false;
+                let container = stage.as_ref().map_or_else(|| "$".to_string(), Stage::path);
+                let comment = AstNode::new(
+                    AstNodeType::Comment,
+                    format!("{container}/#comment"),
+                    truncate_label(content),
+                    range,
+                );
+                match stage.as_mut() {
+                    Some(stage) => stage.children.push(comment),
+                    None => root.push(comment),
+                }
+                continue;
+            }
+
+            directives_allowed = false;
+            let logical = self.read_instruction(start);
+            let keyword_end = logical
+                .text
+                .find(char::is_whitespace)
+                .unwrap_or(logical.text.len());
+            let keyword = logical.text[..keyword_end].to_ascii_uppercase();
+            if !INSTRUCTIONS.contains(&keyword.as_str()) {
+                return Err(AstParseError::new(format!(
+                    "Unknown instruction '{}'",
+                    &logical.text[..keyword_end]
+                ))
+                .with_range(logical.range(self.text, 0, keyword_end)));
+            }
+
+            if keyword == "FROM" {
+                if let Some(done) = stage.take() {
+                    root.push(done.into_node(self.text));
+                }
+                stage = Some(self.start_stage(&logical, keyword_end, stage_count)?);
+                stage_count += 1;
+                continue;
+            }
+
+            let (container, siblings) = stage.as_ref().map_or_else(
+                || ("$".to_string(), root.as_slice()),
+                |stage| (stage.path(), stage.children.as_slice()),
+            );
+            let index = siblings
+                .iter()
+                .filter(|node| node.node_type == AstNodeType::Statement)
+                .count();
+            let node = self.instruction_node(
+                &logical,
+                &keyword,
+                keyword_end,
+                &format!("{container}.instructions[{index}]"),
+            )?;
+            match stage.as_mut() {
+                Some(stage) => stage.children.push(node),
+                None => root.push(node),
+            }
+        }
+
+        if let Some(done) = stage.take() {
+            root.push(done.into_node(self.text));
+        }
+        let label = format!("Dockerfile ({stage_count} stages)");
+        Ok(AstNode::new(
+            AstNodeType::Root,
+            "$".to_string(),
+            label,
+            AstRange::from_offset(self.text, 0, self.text.len()),
+        )
+        .with_children(root))
+    }
+
+    /// Node for a `# key=value` parser directive; `escape` also switches
+    /// the continuation character.
+    fn directive_node(&mut self, comment: &str, range: AstRange) -> Option<AstNode> {
+        let (key, value) = parse_directive(comment)?;
+        if key == "escape" && value == "`" {
+            self.escape = '`';
+        }
+        Some(
+            AstNode::new(
+                AstNodeType::Attribute,
+                format!("$.directives.{key}"),
+                format!("{key}={value}"),
+                range,
+            )
+            .with_value(serde_json::Value::String(value)),
+        )
+    }
+
+    /// Open stage `index` with its `FROM` instruction, named by a trailing
+    /// `AS name`.
+    fn start_stage(
+        &mut self,
+        logical: &Logical,
+        keyword_end: usize,
+        index: usize,
+    ) -> Result<Stage, AstParseError> {
+        let path = format!("$.stages[{index}]");
+        let from = self.instruction_node(
+            logical,
+            "FROM",
+            keyword_end,
+            &format!("{path}.instructions[0]"),
+        )?;
+        let args: Vec<&str> = from
+            .children
+            .iter()
+            .flatten()
+            .filter(|child| child.node_type == AstNodeType::Literal)
+            .filter_map(|child| child.value.as_ref()?.as_str())
+            .collect();
+        let image = args.first().copied().unwrap_or_default();
+        let name = match args.as_slice() {
+            [_, as_keyword, name, ..] if as_keyword.eq_ignore_ascii_case("as") => {
+                Some((*name).to_string())
+            }
+            _ => None,
+        };
+        let label = name.as_ref().map_or_else(
+            || format!("Stage {index} ({image})"),
+            |name| format!("Stage {index}: {name} ({image})"),
+        );
+        Ok(Stage {
+            index,
+            label,
+            name,
+            start: logical.start,
+            children: vec![from],
+        })
+    }
+
+    /// Join an instruction's physical lines. Lines ending in the escape
+    /// character continue; comment and blank lines inside a continuation
+    /// are skipped.
+    fn read_instruction(&mut self, start: usize) -> Logical {
+        let mut logical = Logical {
+            text: String::new(),
+            offsets: Vec::new(),
+            start,
+            end: start,
+        };
+        let mut from = start;
+        loop {
+            let (line_end, next) = self.line_bounds(from);
+            self.pos = next;
+            let content = self.text[from..line_end].trim_end();
+            if from != start && (content.trim().is_empty() || content.trim().starts_with('#')) {
+                if next >= self.text.len() {
+                    break;
+                }
+                from = next;
+                continue;
+            }
+            match content.strip_suffix(self.escape) {
+                Some(joined) if next < self.text.len() => {
+                    logical.push(joined, from);
+                    logical.end = from + joined.len();
+                    from = next;
+                }
+                _ => {
+                    logical.push(content, from);
+                    logical.end = from + content.len();
+                    break;
+                }
+            }
+        }
+        logical
+    }
+
+    /// Heredoc body opened by `<<DELIM` or `<<-DELIM`, read from the lines
+    /// after the instruction.
+    fn read_heredoc(
+        &mut self,
+        delimiter: &str,
+        strip_tabs: bool,
+    ) -> Option<(String, usize, usize)> {
+        let start = self.pos;
+        let mut lines = Vec::new();
+        while self.pos < self.text.len() {
+            let (line_end, next) = self.line_bounds(self.pos);
+            let line = &self.text[self.pos..line_end];
+            let line = if strip_tabs {
+                line.trim_start_matches('\t')
+            } else {
+                line
+            };
+            self.pos = next;
+            if line == delimiter {
+                return Some((lines.join("\n"), start, line_end));
+            }
+            lines.push(line);
+        }
+        None
+    }
+
+    fn instruction_node(
+        &mut self,
+        logical: &Logical,
+        keyword: &str,
+        keyword_end: usize,
+        path: &str,
+    ) -> Result<AstNode, AstParseError> {
+        let text = logical.text.as_str();
+        let words = split_words(text, keyword_end);
+
+        // ONBUILD wraps another instruction, so its arguments take no flags.
+        let flag_count = if keyword == "ONBUILD" {
+            0
+        } else {
+            words
+                .iter()
+                .take_while(|&&(start, end)| {
+                    text[start..end].len() > 2 && text[start..].starts_with("--")
+                })
+                .count()
+        };
+        let (flags, args) = words.split_at(flag_count);
+
+        let mut children = flag_nodes(self.text, logical, flags, path);
+        children.extend(argument_nodes(self.text, logical, keyword, args, path));
+        let mut end = logical.end;
+        if matches!(keyword, "RUN" | "COPY" | "ADD") {
+            let heredocs = self.heredoc_nodes(logical, args, path)?;
+            if let Some(last) = heredocs.last() {
+                end = last.range.end.offset;
+            }
+            children.extend(heredocs);
+        }
+
+        let label = std::iter::once(keyword)
+            .chain(text[keyword_end..].split_whitespace())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(AstNode::new(
+            AstNodeType::Statement,
+            path.to_string(),
+            truncate_label(&label),
+            AstRange::from_offset(self.text, logical.start, end),
+        )
+        .with_value(serde_json::json!(text[keyword_end..].trim()))
+        .with_children(children))
+    }
+
+    /// Bodies of the `<<DELIM` heredocs among `args`, in order.
+    fn heredoc_nodes(
+        &mut self,
+        logical: &Logical,
+        args: &[(usize, usize)],
+        path: &str,
+    ) -> Result<Vec<AstNode>, AstParseError> {
+        let text = logical.text.as_str();
+        let mut nodes = Vec::new();
+        for &(start, end) in args {
+            let Some(spec) = text[start..end]
+                .strip_prefix("<<")
+                .filter(|spec| !spec.starts_with('<'))
+            else {
+                continue;
+            };
+            let strip_tabs = spec.starts_with('-');
+            let delimiter = unquote(spec.trim_start_matches('-'));
+            if delimiter.is_empty()
+                || !delimiter
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+            let (body, body_start, body_end) =
+                self.read_heredoc(delimiter, strip_tabs).ok_or_else(|| {
+                    AstParseError::new(format!("Unterminated heredoc <<{delimiter}"))
+                        .with_range(logical.range(self.text, start, end))
+                })?;
+            nodes.push(
+                AstNode::new(
+                    AstNodeType::Text,
+                    format!("{path}.heredocs[{}]", nodes.len()),
+                    format!("<<{delimiter} ({} lines)", body.lines().count()),
+                    AstRange::from_offset(self.text, body_start, body_end),
+                )
+                .with_value(serde_json::Value::String(body)),
+            );
+        }
+        Ok(nodes)
+    }
+}
+
+/// `--name[=value]` flag nodes; a bare flag has the value `true`.
+fn flag_nodes(
+    source: &str,
+    logical: &Logical,
+    flags: &[(usize, usize)],
+    path: &str,
+) -> Vec<AstNode> {
+    let text = logical.text.as_str();
+    flags
+        .iter()
+        .map(|&(start, end)| {
+            let flag = &text[start + 2..end];
+            let (name, value) = flag.split_once('=').map_or_else(
+                || (flag, serde_json::Value::Bool(true)),
+                |(name, value)| (name, serde_json::json!(unquote(value))),
+            );
+            AstNode::new(
+                AstNodeType::Attribute,
+                child_path(&format!("{path}.flags"), name),
+                text[start..end].to_string(),
+                logical.range(source, start, end),
+            )
+            .with_value(value)
+        })
+        .collect()
+}
+
+/// Argument nodes: JSON exec-form elements, a shell-form command as one
+/// literal, `ENV`/`LABEL`/`ARG` pairs as properties, or plain words.
+fn argument_nodes(
+    source: &str,
+    logical: &Logical,
+    keyword: &str,
+    words: &[(usize, usize)],
+    path: &str,
+) -> Vec<AstNode> {
+    let text = logical.text.as_str();
+    let args_start = words.first().map_or(text.len(), |&(start, _)| start);
+    let args = &text[args_start..];
+    let args_path = format!("{path}.args");
+    let literal = |index: usize, value: &str, start: usize, end: usize| {
+        AstNode::new(
+            AstNodeType::Literal,
+            format!("{args_path}[{index}]"),
+            truncate_label(value),
+            logical.range(source, start, end),
+        )
+        .with_value(serde_json::json!(value))
+    };
+    let property = |key: &str, value: Option<&str>, start: usize, end: usize| {
+        let node_path = child_path(&args_path, key);
+        let range = logical.range(source, start, end);
+        match value {
+            Some(value) => AstNode::new(AstNodeType::String, node_path, key.to_string(), range)
+                .with_value(serde_json::json!(value)),
+            None => AstNode::new(AstNodeType::Null, node_path, key.to_string(), range)
+                .with_value(serde_json::Value::Null),
+        }
+    };
+
+    let exec = matches!(
+        keyword,
+        "RUN" | "CMD" | "ENTRYPOINT" | "SHELL" | "VOLUME" | "COPY" | "ADD"
+    )
+    .then(|| exec_form(args))
+    .flatten();
+
+    if let Some(elements) = exec {
+        return elements
+            .iter()
+            .enumerate()
+            .map(|(i, (value, start, end))| literal(i, value, args_start + start, args_start + end))
+            .collect();
+    }
+
+    if matches!(keyword, "RUN" | "CMD" | "ENTRYPOINT" | "ONBUILD") {
+        // Shell form: the command is one argument.
+        if args.is_empty() {
+            Vec::new()
+        } else {
+            vec![literal(0, args, args_start, text.len())]
+        }
+    } else if matches!(keyword, "ENV" | "LABEL" | "ARG") {
+        match words {
+            // `ENV KEY value with spaces`
+            [(key_start, key_end), (value_start, _), ..]
+                if keyword == "ENV" && !text[*key_start..*key_end].contains('=') =>
+            {
+                vec![property(
+                    &text[*key_start..*key_end],
+                    Some(&text[*value_start..]),
+                    *key_start,
+                    text.len(),
+                )]
+            }
+            _ => words
+                .iter()
+                .map(|&(start, end)| {
+                    let word = &text[start..end];
+                    let (key, value) = word
+                        .split_once('=')
+                        .map_or((word, None), |(k, v)| (k, Some(unquote(v))));
+                    property(unquote(key), value, start, end)
+                })
+                .collect(),
+        }
+    } else {
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| literal(i, unquote(&text[start..end]), start, end))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"# syntax=docker/dockerfile:1
+ARG VERSION=1.22
+
+FROM --platform=$BUILDPLATFORM golang:${VERSION} AS builder
+WORKDIR /src
+# Build the binary
+RUN go mod download && \
+    go build -o /out/app ./cmd/app
+
+FROM alpine:3.20
+COPY --from=builder --link /out/app /usr/local/bin/app
+ENV APP_ENV=production LOG_LEVEL="debug"
+RUN <<EOF
+apk add --no-cache ca-certificates
+adduser -D app
+EOF
+ENTRYPOINT ["app", "serve"]
+"#;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_groups_instructions_into_stages() {
+        let ast = parse(SAMPLE).ast.unwrap();
+        assert_eq!(ast.label, "Dockerfile (2 stages)");
+
+        let root = children(&ast);
+        assert_eq!(root[0].path, "$.directives.syntax");
+        assert_eq!(root[1].path, "$.instructions[0]");
+        assert_eq!(root[1].label, "ARG VERSION=1.22");
+
+        let builder = &root[2];
+        assert_eq!(builder.label, "Stage 0: builder (golang:${VERSION})");
+        assert_eq!(builder.value, Some(serde_json::json!("builder")));
+        assert_eq!((builder.range.start.line, builder.range.end.line), (4, 8));
+        assert_eq!(root[3].label, "Stage 1 (alpine:3.20)");
+    }
+
+    #[test]
+    fn test_flags_arguments_and_continuations() {
+        let ast = parse(SAMPLE).ast.unwrap();
+        let builder = children(&children(&ast)[2]);
+
+        let from = &builder[0];
+        let platform = &children(from)[0];
+        assert_eq!(platform.path, "$.stages[0].instructions[0].flags.platform");
+        assert_eq!(platform.value, Some(serde_json::json!("$BUILDPLATFORM")));
+
+        assert_eq!(builder[2].node_type, AstNodeType::Comment);
+        let run = &builder[3];
+        assert_eq!(run.path, "$.stages[0].instructions[2]");
+        assert_eq!((run.range.start.line, run.range.end.line), (7, 8));
+        let command = &children(run)[0];
+        assert_eq!(
+            command.value,
+            Some(serde_json::json!(
+                "go mod download &&     go build -o /out/app ./cmd/app"
+            ))
+        );
+        // The argument spans both physical lines.
+        assert_eq!(
+            (command.range.start.line, command.range.start.column),
+            (7, 5)
+        );
+        assert_eq!(command.range.end.line, 8);
+
+        let runtime = children(&children(&ast)[3]);
+        let copy_flags: Vec<&str> = children(&runtime[1])
+            .iter()
+            .filter(|c| c.node_type == AstNodeType::Attribute)
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(copy_flags, ["--from=builder", "--link"]);
+
+        let env = children(&runtime[2]);
+        assert_eq!(env[1].path, "$.stages[1].instructions[2].args.LOG_LEVEL");
+        assert_eq!(env[1].value, Some(serde_json::json!("debug")));
+    }
+
+    #[test]
+    fn test_heredocs_and_exec_form() {
+        let ast = parse(SAMPLE).ast.unwrap();
+        let runtime = children(&children(&ast)[3]);
+
+        let run = &runtime[3];
+        assert_eq!((run.range.start.line, run.range.end.line), (13, 16));
+        let heredoc = children(run)
+            .iter()
+            .find(|c| c.node_type == AstNodeType::Text)
+            .unwrap();
+        assert_eq!(heredoc.label, "<<EOF (2 lines)");
+        assert_eq!(
+            heredoc.value,
+            Some(serde_json::json!(
+                "apk add --no-cache ca-certificates\nadduser -D app"
+            ))
+        );
+
+        let entrypoint = children(&runtime[4]);
+        let args: Vec<_> = entrypoint
+            .iter()
+            .map(|c| c.value.clone().unwrap())
+            .collect();
+        assert_eq!(args, [serde_json::json!("app"), serde_json::json!("serve")]);
+        assert_eq!(entrypoint[1].range.start.column, 20);
+    }
+
+    #[test]
+    fn test_escape_directive_and_legacy_env() {
+        let text = "# escape=`\nFROM windows\nRUN dir `\n  c:\\\nENV PATH C:\\bin;C:\\tools\n";
+        let ast = parse(text).ast.unwrap();
+        let stage = children(&children(&ast)[1]);
+        assert_eq!(
+            children(&stage[1])[0].value,
+            Some(serde_json::json!("dir   c:\\"))
+        );
+        let path = &children(&stage[2])[0];
+        assert_eq!(path.label, "PATH");
+        assert_eq!(path.value, Some(serde_json::json!("C:\\bin;C:\\tools")));
+    }
+
+    #[test]
+    fn test_errors() {
+        let unknown = parse("FROM alpine\nRUNN echo\n");
+        assert!(unknown.errors[0].message.contains("RUNN"));
+        assert_eq!(unknown.errors[0].range.unwrap().start.line, 2);
+
+        let heredoc = parse("FROM alpine\nRUN <<EOF\necho hi\n");
+        assert!(heredoc.errors[0].message.contains("Unterminated heredoc"));
+    }
+}
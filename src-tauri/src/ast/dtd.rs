@@ -0,0 +1,815 @@
+//! DTD parsing and validation
+//!
+//! Element and attribute-list declarations are read from the document's
+//! internal subset and, when given, from a pasted external subset, with
+//! internal declarations taking precedence. Entity and notation
+//! declarations are skipped since roxmltree already expands internal
+//! entities; parameter-entity references and conditional sections are
+//! reported as unsupported.
+
+use std::collections::{HashMap, HashSet};
+
+use roxmltree::{Attribute, Document, Node};
+
+use super::xml::{raw_attribute_name, raw_name};
+use super::xml_validate::{
+    is_name, is_nmtoken, report_mismatch, significant_text, Particle, Report, Term,
+};
+use super::{AstParseError, AstRange};
+
+/// Allowed content of an element
+enum ContentSpec {
+    Empty,
+    Any,
+    /// `(#PCDATA | a | b)*`: text mixed with the listed elements
+    Mixed(HashSet<String>),
+    /// Element content; leaves index [`Dtd::names`]
+    Children(Particle),
+}
+
+enum AttributeType {
+    Cdata,
+    Id,
+    IdRef,
+    IdRefs,
+    Entity,
+    Entities,
+    NmToken,
+    NmTokens,
+    /// Enumerated values, including `NOTATION (...)`
+    Enumeration(Vec<String>),
+}
+
+enum AttributeDefault {
+    Required,
+    Implied,
+    Fixed(String),
+    Value,
+}
+
+struct AttributeDecl {
+    name: String,
+    kind: AttributeType,
+    default: AttributeDefault,
+}
+
+/// Declarations collected from one or more DTD subsets
+#[derive(Default)]
+struct Dtd {
+    elements: HashMap<String, ContentSpec>,
+    attributes: HashMap<String, Vec<AttributeDecl>>,
+    /// Element names referenced by content-model leaves
+    names: Vec<String>,
+}
+
+/// Validate `doc` against its internal DTD subset and the optional
+/// pasted `external` subset
+pub(super) fn validate<'a, 'input>(
+    doc: &'a Document<'input>,
+    external: Option<&str>,
+    report: &mut Report<'a, 'input>,
+    schema_errors: &mut Vec<AstParseError>,
+) {
+    let text = doc.input_text();
+    let root = doc.root_element();
+    let doctype = Doctype::find(text, root.range().start);
+    let mut dtd = Dtd::default();
+
+    if let Some(doctype) = &doctype {
+        if let Some(start) = doctype.subset {
+            let mut errors = Vec::new();
+            DtdParser::new(text, start, true, &mut dtd, &mut errors).parse();
+            for error in errors {
+                report.parse_error(error);
+            }
+        }
+        if doctype.name != raw_name(root) {
+            report.element(
+                root,
+                format!(
+                    "Root element <{}> does not match the DOCTYPE name '{}'",
+                    raw_name(root),
+                    doctype.name
+                ),
+            );
+        }
+    }
+    match (external, &doctype) {
+        (Some(external), _) => DtdParser::new(external, 0, false, &mut dtd, schema_errors).parse(),
+        (None, None) => schema_errors.push(AstParseError::new(
+            "The document has no DOCTYPE declaration and no DTD was given",
+        )),
+        (None, Some(doctype)) => {
+            if let Some(system) = &doctype.system {
+                schema_errors.push(AstParseError::new(format!(
+                    "External DTD '{system}' is not loaded; paste it as the schema to validate against it"
+                )));
+            }
+        }
+    }
+
+    for node in root.descendants().filter(Node::is_element) {
+        if report.is_full() {
+            break;
+        }
+        dtd.validate_element(node, report);
+    }
+}
+
+/// `<!DOCTYPE name SYSTEM "uri" [ ... ]>` in the document prolog
+struct Doctype {
+    name: String,
+    system: Option<String>,
+    /// Byte offset just past the `[` opening the internal subset
+    subset: Option<usize>,
+}
+
+impl Doctype {
+    fn find(text: &str, root_start: usize) -> Option<Self> {
+        let start = text[..root_start].find("<!DOCTYPE")? + "<!DOCTYPE".len();
+        let end = text[start..root_start]
+            .find(['[', '>'])
+            .map_or(root_start, |i| start + i);
+        let tokens = lex(text, start, end);
+        let mut names = tokens.iter().filter_map(|(token, _)| match token {
+            Token::Name(name) => Some(*name),
+            _ => None,
+        });
+        let name = names.next()?.to_string();
+        // SYSTEM "uri" or PUBLIC "id" "uri": the last literal is the system id
+        let system = tokens.iter().rev().find_map(|(token, _)| match token {
+            Token::Literal(uri) => Some((*uri).to_string()),
+            _ => None,
+        });
+        let subset = text[end..].starts_with('[').then_some(end + 1);
+        Some(Self {
+            name,
+            system,
+            subset,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'s> {
+    Name(&'s str),
+    Literal(&'s str),
+    Punct(char),
+}
+
+/// Split a declaration body into names, quoted literals, and punctuation
+fn lex(text: &str, start: usize, end: usize) -> Vec<(Token<'_>, std::ops::Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut chars = text[start..end].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at = start + i;
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            let close = text[at + 1..end].find(c).map_or(end, |j| at + 1 + j);
+            tokens.push((
+                Token::Literal(&text[at + 1..close]),
+                at..(close + 1).min(end),
+            ));
+            while chars.peek().is_some_and(|&(j, _)| start + j <= close) {
+                chars.next();
+            }
+        } else if c == '#' || c == '%' || c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.')
+        {
+            let mut stop = at + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if !(next.is_alphanumeric() || matches!(next, '_' | ':' | '-' | '.' | ';')) {
+                    break;
+                }
+                stop = start + j + next.len_utf8();
+                chars.next();
+            }
+            tokens.push((Token::Name(&text[at..stop]), at..stop));
+        } else {
+            tokens.push((Token::Punct(c), at..at + c.len_utf8()));
+        }
+    }
+    tokens
+}
+
+/// Reads markup declarations from `text`, starting at `pos`. An internal
+/// subset ends at the first top-level `]`.
+struct DtdParser<'s, 'd> {
+    text: &'s str,
+    pos: usize,
+    internal: bool,
+    dtd: &'d mut Dtd,
+    errors: &'d mut Vec<AstParseError>,
+}
+
+impl<'s, 'd> DtdParser<'s, 'd> {
+    const fn new(
+        text: &'s str,
+        pos: usize,
+        internal: bool,
+        dtd: &'d mut Dtd,
+        errors: &'d mut Vec<AstParseError>,
+    ) -> Self {
+        Self {
+            text,
+            pos,
+            internal,
+            dtd,
+            errors,
+        }
+    }
+
+    fn error(&mut self, range: std::ops::Range<usize>, message: impl Into<String>) {
+        let range = AstRange::from_offset(self.text, range.start, range.end);
+        self.errors
+            .push(AstParseError::new(message).with_range(range));
+    }
+
+    /// Move past `terminator`, or to the end of the text with an error
+    fn skip_past(&mut self, start: usize, terminator: &str, what: &str) {
+        if let Some(i) = self.text[self.pos..].find(terminator) {
+            self.pos += i + terminator.len();
+        } else {
+            self.pos = self.text.len();
+            self.error(start..self.pos, format!("Unterminated {what}"));
+        }
+    }
+
+    fn parse(mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            let start = self.pos;
+            if trimmed.is_empty() || (self.internal && trimmed.starts_with(']')) {
+                break;
+            }
+            if trimmed.starts_with("<!--") {
+                self.skip_past(start, "-->", "comment");
+            } else if trimmed.starts_with("<?") {
+                self.skip_past(start, "?>", "processing instruction");
+            } else if trimmed.starts_with("<![") {
+                self.skip_past(start, "]]>", "conditional section");
+                self.error(start..self.pos, "Conditional sections are not supported");
+            } else if trimmed.starts_with("<!") {
+                self.declaration(start);
+            } else if trimmed.starts_with('%') {
+                self.skip_past(start, ";", "parameter-entity reference");
+                self.error(
+                    start..self.pos,
+                    "Parameter-entity references are not supported",
+                );
+            } else {
+                self.pos = self.text[start + 1..]
+                    .find('<')
+                    .map_or(self.text.len(), |i| start + 1 + i);
+                self.error(start..self.pos, "Unexpected text in DTD");
+            }
+        }
+    }
+
+    fn declaration(&mut self, start: usize) {
+        // Find the closing '>' outside quoted literals
+        let mut quote = None;
+        let mut end = None;
+        for (i, c) in self.text[start..].char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '>') => {
+                    end = Some(start + i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let Some(end) = end else {
+            self.pos = self.text.len();
+            self.error(start..self.pos, "Unterminated markup declaration");
+            return;
+        };
+        self.pos = end + 1;
+
+        let tokens = lex(self.text, start + 2, end);
+        let references_entity = tokens
+            .iter()
+            .any(|(token, _)| matches!(token, Token::Name(name) if name.starts_with('%')));
+        let mut tokens = Tokens {
+            items: tokens,
+            index: 0,
+            end,
+        };
+        let result = match tokens.next() {
+            Some((Token::Name("ENTITY" | "NOTATION"), _)) => Ok(()),
+            _ if references_entity => Err((
+                start..end + 1,
+                "Parameter-entity references are not supported".to_string(),
+            )),
+            Some((Token::Name("ELEMENT"), _)) => self.element_decl(&mut tokens),
+            Some((Token::Name("ATTLIST"), _)) => self.attlist_decl(&mut tokens),
+            Some((_, range)) => Err((range, "Unknown markup declaration".to_string())),
+            None => Err((start..end + 1, "Empty markup declaration".to_string())),
+        };
+        if let Err((range, message)) = result {
+            self.error(range, message);
+        }
+    }
+
+    fn element_decl(&mut self, tokens: &mut Tokens<'s>) -> Result<(), DeclError> {
+        let (name, range) = tokens.name("element name")?;
+        let spec = match tokens.next() {
+            Some((Token::Name("EMPTY"), _)) => ContentSpec::Empty,
+            Some((Token::Name("ANY"), _)) => ContentSpec::Any,
+            Some((Token::Punct('('), _)) if tokens.peek() == Some(Token::Name("#PCDATA")) => {
+                tokens.next();
+                mixed(tokens)?
+            }
+            Some((Token::Punct('('), _)) => {
+                let group = self.group(tokens)?;
+                ContentSpec::Children(occurrence(tokens, group))
+            }
+            other => return Err(tokens.unexpected(other, "content specification")),
+        };
+        tokens.finish()?;
+        if self.dtd.elements.contains_key(name) {
+            return Err((
+                range,
+                format!("Element <{name}> is declared more than once"),
+            ));
+        }
+        self.dtd.elements.insert(name.to_string(), spec);
+        Ok(())
+    }
+
+    /// Content particles after an opening '(' up to and including ')'
+    fn group(&mut self, tokens: &mut Tokens<'s>) -> Result<Term, DeclError> {
+        let mut items = vec![self.content_particle(tokens)?];
+        let mut separator = None;
+        loop {
+            match tokens.next() {
+                Some((Token::Punct(')'), _)) => break,
+                Some((Token::Punct(c @ ('|' | ',')), range)) => {
+                    if separator.is_some_and(|s| s != c) {
+                        return Err((range, "Cannot mix ',' and '|' in one group".to_string()));
+                    }
+                    separator = Some(c);
+                    items.push(self.content_particle(tokens)?);
+                }
+                other => return Err(tokens.unexpected(other, "',', '|', or ')'")),
+            }
+        }
+        Ok(if separator == Some('|') {
+            Term::Choice(items)
+        } else {
+            Term::Sequence(items)
+        })
+    }
+
+    fn content_particle(&mut self, tokens: &mut Tokens<'s>) -> Result<Particle, DeclError> {
+        let term = match tokens.next() {
+            Some((Token::Name(name), _)) if !name.starts_with('#') => {
+                self.dtd.names.push(name.to_string());
+                Term::Leaf(self.dtd.names.len() - 1)
+            }
+            Some((Token::Punct('('), _)) => self.group(tokens)?,
+            other => return Err(tokens.unexpected(other, "element name or '('")),
+        };
+        Ok(occurrence(tokens, term))
+    }
+
+    fn attlist_decl(&mut self, tokens: &mut Tokens<'s>) -> Result<(), DeclError> {
+        let (element, _) = tokens.name("element name")?;
+        while tokens.peek().is_some() {
+            let (name, _) = tokens.name("attribute name")?;
+            let kind = match tokens.next() {
+                Some((Token::Name("CDATA"), _)) => AttributeType::Cdata,
+                Some((Token::Name("ID"), _)) => AttributeType::Id,
+                Some((Token::Name("IDREF"), _)) => AttributeType::IdRef,
+                Some((Token::Name("IDREFS"), _)) => AttributeType::IdRefs,
+                Some((Token::Name("ENTITY"), _)) => AttributeType::Entity,
+                Some((Token::Name("ENTITIES"), _)) => AttributeType::Entities,
+                Some((Token::Name("NMTOKEN"), _)) => AttributeType::NmToken,
+                Some((Token::Name("NMTOKENS"), _)) => AttributeType::NmTokens,
+                Some((Token::Name("NOTATION"), _)) => {
+                    tokens.punct('(')?;
+                    AttributeType::Enumeration(enumeration(tokens)?)
+                }
+                Some((Token::Punct('('), _)) => AttributeType::Enumeration(enumeration(tokens)?),
+                other => return Err(tokens.unexpected(other, "attribute type")),
+            };
+            let default = match tokens.next() {
+                Some((Token::Name("#REQUIRED"), _)) => AttributeDefault::Required,
+                Some((Token::Name("#IMPLIED"), _)) => AttributeDefault::Implied,
+                Some((Token::Name("#FIXED"), _)) => match tokens.next() {
+                    Some((Token::Literal(value), _)) => AttributeDefault::Fixed(value.to_string()),
+                    other => return Err(tokens.unexpected(other, "fixed value")),
+                },
+                Some((Token::Literal(_), _)) => AttributeDefault::Value,
+                other => return Err(tokens.unexpected(other, "attribute default")),
+            };
+            // The first declaration of an attribute is binding
+            let decls = self.dtd.attributes.entry(element.to_string()).or_default();
+            if !decls.iter().any(|decl| decl.name == name) {
+                decls.push(AttributeDecl {
+                    name: name.to_string(),
+                    kind,
+                    default,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+type DeclError = (std::ops::Range<usize>, String);
+
+/// Cursor over the tokens of one declaration
+struct Tokens<'s> {
+    items: Vec<(Token<'s>, std::ops::Range<usize>)>,
+    index: usize,
+    /// Offset of the closing '>', for errors at the end of the declaration
+    end: usize,
+}
+
+impl<'s> Tokens<'s> {
+    fn next(&mut self) -> Option<(Token<'s>, std::ops::Range<usize>)> {
+        let token = self.items.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<Token<'s>> {
+        self.items.get(self.index).map(|(token, _)| *token)
+    }
+
+    fn unexpected(
+        &self,
+        token: Option<(Token<'s>, std::ops::Range<usize>)>,
+        expected: &str,
+    ) -> DeclError {
+        token.map_or_else(
+            || (self.end..self.end + 1, format!("Expected {expected}")),
+            |(_, range)| (range, format!("Expected {expected}")),
+        )
+    }
+
+    fn name(&mut self, what: &str) -> Result<(&'s str, std::ops::Range<usize>), DeclError> {
+        match self.next() {
+            Some((Token::Name(name), range)) if is_name(name) => Ok((name, range)),
+            other => Err(self.unexpected(other, what)),
+        }
+    }
+
+    fn punct(&mut self, c: char) -> Result<(), DeclError> {
+        match self.next() {
+            Some((Token::Punct(p), _)) if p == c => Ok(()),
+            other => Err(self.unexpected(other, &format!("'{c}'"))),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), DeclError> {
+        match self.next() {
+            None => Ok(()),
+            other => Err(self.unexpected(other, "'>'")),
+        }
+    }
+}
+
+/// Rest of a mixed-content declaration after `(#PCDATA`
+fn mixed(tokens: &mut Tokens<'_>) -> Result<ContentSpec, DeclError> {
+    let mut names = HashSet::new();
+    loop {
+        match tokens.next() {
+            Some((Token::Punct(')'), _)) => break,
+            Some((Token::Punct('|'), _)) => {
+                names.insert(tokens.name("element name")?.0.to_string());
+            }
+            other => return Err(tokens.unexpected(other, "'|' or ')'")),
+        }
+    }
+    match tokens.peek() {
+        Some(Token::Punct('*')) => {
+            tokens.next();
+        }
+        _ if !names.is_empty() => {
+            let next = tokens.next();
+            return Err(tokens.unexpected(next, "'*' after mixed content with element names"));
+        }
+        _ => {}
+    }
+    Ok(ContentSpec::Mixed(names))
+}
+
+/// Values of an enumerated type after the opening '('
+fn enumeration(tokens: &mut Tokens<'_>) -> Result<Vec<String>, DeclError> {
+    let mut values = Vec::new();
+    loop {
+        match tokens.next() {
+            Some((Token::Name(value), _)) if is_nmtoken(value) => values.push(value.to_string()),
+            other => return Err(tokens.unexpected(other, "enumerated value")),
+        }
+        match tokens.next() {
+            Some((Token::Punct(')'), _)) => return Ok(values),
+            Some((Token::Punct('|'), _)) => {}
+            other => return Err(tokens.unexpected(other, "'|' or ')'")),
+        }
+    }
+}
+
+/// Wrap `term` in the occurrence indicator that follows it, if any
+fn occurrence(tokens: &mut Tokens<'_>, term: Term) -> Particle {
+    let (min, max) = match tokens.peek() {
+        Some(Token::Punct('?')) => (0, Some(1)),
+        Some(Token::Punct('*')) => (0, None),
+        Some(Token::Punct('+')) => (1, None),
+        _ => return Particle::once(term),
+    };
+    tokens.next();
+    Particle { term, min, max }
+}
+
+impl Dtd {
+    fn validate_element<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        report: &mut Report<'a, 'input>,
+    ) {
+        let name = raw_name(node);
+        let Some(spec) = self.elements.get(name) else {
+            report.element(node, format!("Element <{name}> is not declared"));
+            return;
+        };
+        match spec {
+            ContentSpec::Empty => {
+                if node
+                    .children()
+                    .any(|child| child.is_element() || child.is_text())
+                {
+                    report.element(
+                        node,
+                        format!("Element <{name}> is declared EMPTY but has content"),
+                    );
+                }
+            }
+            ContentSpec::Any => {}
+            ContentSpec::Mixed(allowed) => {
+                for child in node.children().filter(Node::is_element) {
+                    if !allowed.contains(raw_name(child)) {
+                        report.element(
+                            child,
+                            format!("Element <{}> is not allowed in <{name}>", raw_name(child)),
+                        );
+                    }
+                }
+            }
+            ContentSpec::Children(particle) => {
+                if let Some(text) = significant_text(node) {
+                    report.node(text, format!("Text is not allowed in element <{name}>"));
+                }
+                let children: Vec<_> = node.children().filter(Node::is_element).collect();
+                if let Err(mismatch) = particle.check(children.len(), |leaf, i| {
+                    self.names[leaf] == raw_name(children[i])
+                }) {
+                    let expected: Vec<_> = mismatch
+                        .expected
+                        .iter()
+                        .map(|&leaf| format!("<{}>", self.names[leaf]))
+                        .collect();
+                    report_mismatch(report, node, &children, &mismatch, &expected);
+                }
+            }
+        }
+        self.validate_attributes(node, report);
+    }
+
+    fn validate_attributes<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        report: &mut Report<'a, 'input>,
+    ) {
+        let name = raw_name(node);
+        let decls = self.attributes.get(name).map_or(&[][..], Vec::as_slice);
+        for attribute in node.attributes() {
+            let attribute_name = raw_attribute_name(node, &attribute);
+            match decls.iter().find(|decl| decl.name == attribute_name) {
+                Some(decl) => check_attribute(decl, node, attribute, report),
+                None => report.attribute(
+                    node,
+                    attribute,
+                    format!("Attribute '{attribute_name}' is not declared for element <{name}>"),
+                ),
+            }
+        }
+        for decl in decls {
+            if matches!(decl.default, AttributeDefault::Required)
+                && !node
+                    .attributes()
+                    .any(|attribute| raw_attribute_name(node, &attribute) == decl.name)
+            {
+                report.element(
+                    node,
+                    format!(
+                        "Element <{name}> is missing required attribute '{}'",
+                        decl.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn check_attribute<'a, 'input>(
+    decl: &AttributeDecl,
+    node: Node<'a, 'input>,
+    attribute: Attribute<'a, 'input>,
+    report: &mut Report<'a, 'input>,
+) {
+    let value = if matches!(decl.kind, AttributeType::Cdata) {
+        attribute.value().to_string()
+    } else {
+        attribute
+            .value()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let name = &decl.name;
+    let tokens: Vec<&str> = value.split(' ').filter(|t| !t.is_empty()).collect();
+    let problem = match &decl.kind {
+        AttributeType::Id | AttributeType::IdRef | AttributeType::Entity if !is_name(&value) => {
+            Some(format!(
+                "Value '{value}' of attribute '{name}' is not a valid name"
+            ))
+        }
+        AttributeType::Id => {
+            report.id(node, attribute, &value);
+            None
+        }
+        AttributeType::IdRef => {
+            report.idref(node, attribute, &value);
+            None
+        }
+        AttributeType::IdRefs | AttributeType::Entities
+            if tokens.is_empty() || !tokens.iter().all(|t| is_name(t)) =>
+        {
+            Some(format!(
+                "Value '{value}' of attribute '{name}' is not a list of names"
+            ))
+        }
+        AttributeType::IdRefs => {
+            for token in tokens {
+                report.idref(node, attribute, token);
+            }
+            None
+        }
+        AttributeType::NmToken if !is_nmtoken(&value) => Some(format!(
+            "Value '{value}' of attribute '{name}' is not a valid name token"
+        )),
+        AttributeType::NmTokens if tokens.is_empty() || !tokens.iter().all(|t| is_nmtoken(t)) => {
+            Some(format!(
+                "Value '{value}' of attribute '{name}' is not a list of name tokens"
+            ))
+        }
+        AttributeType::Enumeration(values) if !values.contains(&value) => Some(format!(
+            "Value '{value}' of attribute '{name}' is not one of: {}",
+            values.join(", ")
+        )),
+        AttributeType::Cdata
+        | AttributeType::Entity
+        | AttributeType::Entities
+        | AttributeType::NmToken
+        | AttributeType::NmTokens
+        | AttributeType::Enumeration(_) => None,
+    };
+    if let Some(message) = problem {
+        report.attribute(node, attribute, message);
+    }
+    if let AttributeDefault::Fixed(fixed) = &decl.default {
+        if *fixed != value {
+            report.attribute(
+                node,
+                attribute,
+                format!("Attribute '{name}' must have the fixed value '{fixed}'"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::xml_validate::{validate_xml, SchemaKind};
+
+    const NOTE: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE note [
+  <!ELEMENT note (to+, from, body?)>
+  <!ELEMENT to (#PCDATA)>
+  <!ELEMENT from (#PCDATA)>
+  <!ELEMENT body (#PCDATA | em)*>
+  <!ELEMENT em (#PCDATA)>
+  <!ATTLIST note id ID #REQUIRED
+                 kind (memo | letter) "memo">
+  <!ATTLIST to ref IDREF #IMPLIED>
+]>
+"#;
+
+    fn messages(xml: &str) -> Vec<String> {
+        validate_xml(xml, None, SchemaKind::Dtd)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_inline_dtd_valid() {
+        let xml = format!(
+            "{NOTE}<note id=\"n1\"><to ref=\"n1\">A</to><from>B</from><body>Hi <em>there</em></body></note>"
+        );
+        let result = validate_xml(&xml, None, SchemaKind::Dtd);
+        assert!(
+            result.valid,
+            "{:?} {:?}",
+            result.errors, result.schema_errors
+        );
+    }
+
+    #[test]
+    fn test_content_model_errors_point_at_children() {
+        let xml = format!("{NOTE}<note id=\"n1\"><from>B</from><to>A</to></note>");
+        let result = validate_xml(&xml, None, SchemaKind::Dtd);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(
+            error.message,
+            "Element <from> is not allowed here; expected <to>"
+        );
+        assert_eq!(error.path.as_deref(), Some("$.note.from"));
+        let range = error.range.as_ref().unwrap();
+        assert_eq!(&xml[range.start.offset..range.end.offset], "from");
+        assert_eq!(range.start.line, 12);
+
+        let xml = format!("{NOTE}<note id=\"n1\"><to>A</to></note>");
+        assert_eq!(
+            messages(&xml),
+            ["Element <note> is incomplete; expected <to>, or <from>"]
+        );
+    }
+
+    #[test]
+    fn test_attribute_errors() {
+        let xml = format!(
+            "{NOTE}<note kind=\"fax\" extra=\"1\"><to ref=\"nowhere\">A</to><from>B<em/></from></note>"
+        );
+        assert_eq!(
+            messages(&xml),
+            [
+                "Value 'fax' of attribute 'kind' is not one of: memo, letter",
+                "Attribute 'extra' is not declared for element <note>",
+                "Element <note> is missing required attribute 'id'",
+                "Element <em> is not allowed in <from>",
+                "IDREF 'nowhere' does not match any ID",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_external_dtd_and_root_name() {
+        let dtd = "<!ELEMENT list (item*)>\n<!ELEMENT item EMPTY>";
+        let result = validate_xml(
+            "<list><item/><item>x</item></list>",
+            Some(dtd),
+            SchemaKind::Dtd,
+        );
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path.as_deref(), Some("$.list.item[1]"));
+
+        let xml = "<!DOCTYPE other SYSTEM \"list.dtd\"><list/>";
+        let result = validate_xml(xml, Some(dtd), SchemaKind::Dtd);
+        assert_eq!(
+            result.errors[0].message,
+            "Root element <list> does not match the DOCTYPE name 'other'"
+        );
+
+        let result = validate_xml(xml, None, SchemaKind::Dtd);
+        assert!(result.schema_errors[0].message.contains("list.dtd"));
+    }
+
+    #[test]
+    fn test_dtd_syntax_errors() {
+        let result = validate_xml(
+            "<a/>",
+            Some("<!ELEMENT a (b | c, d)>\n<!ELEMENT b"),
+            SchemaKind::Dtd,
+        );
+        assert_eq!(result.schema_errors.len(), 2);
+        assert_eq!(
+            result.schema_errors[0].message,
+            "Cannot mix ',' and '|' in one group"
+        );
+        assert_eq!(
+            result.schema_errors[1].range.as_ref().unwrap().start.line,
+            2
+        );
+    }
+}
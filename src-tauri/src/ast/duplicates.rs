@@ -0,0 +1,321 @@
+//! Duplicate key diagnostics for JSON, YAML, and TOML
+//!
+//! JSON allows duplicate keys, with readers keeping the last value, so
+//! the JSON parser keeps them all; they are reported here as warnings.
+//! The YAML and TOML parsers reject them with an error that points at the
+//! second key at best. That error is rewritten to name the key and carry
+//! the range of the first one as well.
+
+use std::collections::HashMap;
+
+use super::{AstLanguage, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+
+/// Add or complete the duplicate key diagnostics of `result`, parsed
+/// from `text` in `language`
+pub(super) fn check(
+    text: &str,
+    language: AstLanguage,
+    mut result: AstParseResult,
+) -> AstParseResult {
+    match language {
+        AstLanguage::Json => {
+            if let Some(ast) = &result.ast {
+                let warnings = json_duplicates(text, ast);
+                result.errors.extend(warnings);
+            }
+        }
+        AstLanguage::Yaml | AstLanguage::Toml => {
+            for error in &mut result.errors {
+                let rewritten = if language == AstLanguage::Yaml {
+                    yaml_duplicate(text, error)
+                } else {
+                    toml_duplicate(text, error)
+                };
+                if let Some(rewritten) = rewritten {
+                    *error = rewritten;
+                }
+            }
+        }
+        _ => {}
+    }
+    result
+}
+
+/// Lines of `text` with their start offsets, without line breaks
+fn lines(text: &str) -> Vec<(usize, &str)> {
+    let mut start = 0;
+    text.split_inclusive('\n')
+        .map(|line| {
+            let line_start = start;
+            start += line.len();
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            (line_start, line.strip_suffix('\r').unwrap_or(line))
+        })
+        .collect()
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+fn json_duplicates(text: &str, root: &AstNode) -> Vec<AstParseError> {
+    let mut warnings = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let children = node.children.as_deref().unwrap_or_default();
+        if node.node_type == AstNodeType::Object {
+            let mut seen: HashMap<&str, &AstNode> = HashMap::new();
+            for member in children {
+                if let Some(first) = seen.get(member.label.as_str()) {
+                    warnings.push(
+                        AstParseError::warning(format!("Duplicate key '{}'", member.label))
+                            .with_range(json_key_range(text, member))
+                            .with_related_range(json_key_range(text, first)),
+                    );
+                } else {
+                    seen.insert(&member.label, member);
+                }
+            }
+        }
+        stack.extend(children);
+    }
+    warnings.sort_by_key(|warning| warning.range.map(|range| range.start.offset));
+    warnings
+}
+
+/// Range of the quoted key a JSON member starts with
+fn json_key_range(text: &str, member: &AstNode) -> AstRange {
+    let start = member.range.start.offset;
+    let mut escaped = false;
+    let end = text
+        .get(start + 1..)
+        .and_then(|rest| {
+            rest.char_indices().find_map(|(i, c)| {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    return Some(start + 1 + i + 1);
+                }
+                None
+            })
+        })
+        .unwrap_or(member.range.end.offset);
+    AstRange::from_offset(text, start, end)
+}
+
+// ============================================================================
+// YAML
+// ============================================================================
+
+/// `error` rewritten when it is the YAML parser's
+/// `String("a"): duplicated key in mapping at byte 8 line 2 column 4`
+fn yaml_duplicate(text: &str, error: &AstParseError) -> Option<AstParseError> {
+    let (debug_key, location) = error.message.split_once(": duplicated key in mapping")?;
+    let key = yaml_debug_key(debug_key)?;
+    let reported: usize = location
+        .split(" line ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+
+    let lines = lines(text);
+    let mut rewritten = AstParseError::new(format!("Duplicate key '{key}'"));
+    let Some((index, column, end)) = (0..reported.min(lines.len())).rev().find_map(|i| {
+        yaml_line_key(lines[i].1)
+            .filter(|&(_, _, line_key)| line_key == key)
+            .map(|(column, end, _)| (i, column, end))
+    }) else {
+        return Some(rewritten.with_range(error.range?));
+    };
+    let line_start = lines[index].0;
+    rewritten = rewritten.with_range(AstRange::from_offset(
+        text,
+        line_start + column,
+        line_start + end,
+    ));
+
+    for &(start, line) in lines[..index].iter().rev() {
+        let content = line.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if let Some((line_column, line_end, line_key)) = yaml_line_key(line) {
+            if line_column == column && line_key == key {
+                let first = AstRange::from_offset(text, start + column, start + line_end);
+                return Some(rewritten.with_related_range(first));
+            }
+        }
+        // A line starting left of the key opens the enclosing block.
+        if line.len() - content.len() < column {
+            break;
+        }
+    }
+    Some(rewritten)
+}
+
+/// Key from its debug form in a YAML error: `String("a")`, `Integer(1)`
+fn yaml_debug_key(debug: &str) -> Option<String> {
+    let (_, inner) = debug.split_once('(')?;
+    let inner = inner.strip_suffix(')')?;
+    Some(
+        inner
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.strip_suffix('"'))
+            .map_or_else(
+                || inner.to_string(),
+                |quoted| quoted.replace("\\\"", "\"").replace("\\\\", "\\"),
+            ),
+    )
+}
+
+/// Start and end columns (0-indexed) and text of the block mapping key
+/// `line` holds, past any sequence dashes; the text is without quotes
+fn yaml_line_key(line: &str) -> Option<(usize, usize, &str)> {
+    let mut column = line.len() - line.trim_start().len();
+    while let Some(rest) = line[column..].strip_prefix('-') {
+        if !rest.starts_with([' ', '\t']) {
+            break;
+        }
+        column = line.len() - rest.trim_start().len();
+    }
+    let content = &line[column..];
+    let (key, length) = if let quote @ ('"' | '\'') = content.chars().next()? {
+        let close = content[1..].find(quote)? + 1;
+        let key = content[close + 1..]
+            .starts_with(':')
+            .then(|| &content[1..close])?;
+        (key, close + 1)
+    } else {
+        let colon = content.match_indices(':').map(|(i, _)| i).find(|&i| {
+            content[i + 1..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+        })?;
+        let key = content[..colon].trim_end();
+        (key, key.len())
+    };
+    (!key.is_empty() && !key.starts_with('#')).then_some((column, column + length, key))
+}
+
+// ============================================================================
+// TOML
+// ============================================================================
+
+/// `error` rewritten when it is the TOML parser's `duplicate key`
+fn toml_duplicate(text: &str, error: &AstParseError) -> Option<AstParseError> {
+    if error.message != "duplicate key" {
+        return None;
+    }
+    let range = error.range?;
+    let lines = lines(text);
+    let index = lines
+        .iter()
+        .rposition(|&(start, _)| start <= range.start.offset)?;
+    let (_, line) = lines[index];
+
+    let header = toml_header(line);
+    let (kind, name) = match header {
+        Some(header) => ("table", header),
+        None => ("key", toml_line_key(line)?),
+    };
+    let mut rewritten = AstParseError::new(format!("Duplicate {kind} '{name}'")).with_range(range);
+    for &(start, earlier) in lines[..index].iter().rev() {
+        let same = if header.is_some() {
+            toml_header(earlier) == Some(name)
+        } else {
+            toml_line_key(earlier) == Some(name)
+        };
+        if same {
+            let column = earlier.find(name).unwrap_or(0);
+            let first = AstRange::from_offset(text, start + column, start + column + name.len());
+            rewritten = rewritten.with_related_range(first);
+            break;
+        }
+        // A key's table starts at the header above it.
+        if header.is_none() && toml_header(earlier).is_some() {
+            break;
+        }
+    }
+    Some(rewritten)
+}
+
+/// `[table]` or `[[array]]` header `line` holds
+fn toml_header(line: &str) -> Option<&str> {
+    let content = line.trim_start();
+    if !content.starts_with('[') {
+        return None;
+    }
+    let close = content.rfind(']')?;
+    Some(&content[..=close])
+}
+
+/// Key before the `=` of a key/value `line`, as written
+fn toml_line_key(line: &str) -> Option<&str> {
+    let content = line.trim();
+    if content.starts_with(['#', '[']) {
+        return None;
+    }
+    let (key, _) = content.split_once('=')?;
+    Some(key.trim_end()).filter(|key| !key.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_to_ast, AstSeverity};
+    use super::*;
+
+    fn offsets(range: &AstRange) -> (usize, usize) {
+        (range.start.offset, range.end.offset)
+    }
+
+    #[test]
+    fn test_json_duplicates_are_warnings() {
+        let json = r#"{"a": 1, "b": {"c": 1, "c": [2]}, "a": 3}"#;
+        let result = parse_to_ast(json, AstLanguage::Json);
+        assert!(result.ast.is_some());
+        assert!(!result.has_errors());
+        assert_eq!(result.errors.len(), 2);
+
+        let nested = &result.errors[0];
+        assert_eq!(nested.message, "Duplicate key 'c'");
+        assert_eq!(nested.severity, AstSeverity::Warning);
+        assert_eq!(offsets(&nested.range.unwrap()), (23, 26));
+        assert_eq!(offsets(&nested.related_ranges[0]), (15, 18));
+        assert_eq!(offsets(&result.errors[1].related_ranges[0]), (1, 4));
+    }
+
+    #[test]
+    fn test_yaml_duplicates() {
+        let yaml = "server:\n  port: 80\n  host: a\nitems:\n  - port: 1\n  - name: x\n    port: 2\nserver2:\n  \"port\": 1\n  port: 2\n";
+        let result = parse_to_ast(yaml, AstLanguage::Yaml);
+        assert!(result.has_errors());
+        let error = &result.errors[0];
+        assert_eq!(error.message, "Duplicate key 'port'");
+        let range = error.range.unwrap();
+        assert_eq!((range.start.line, range.start.column), (10, 3));
+        let first = error.related_ranges[0];
+        assert_eq!(
+            (first.start.line, first.start.column, first.end.column),
+            (9, 3, 9)
+        );
+    }
+
+    #[test]
+    fn test_toml_duplicates() {
+        let toml = "[a]\nx = 1\n\n[b]\nx = 1\ny = 2\nx = 3\n";
+        let result = parse_to_ast(toml, AstLanguage::Toml);
+        let error = &result.errors[0];
+        assert_eq!(error.message, "Duplicate key 'x'");
+        assert_eq!(error.related_ranges[0].start.line, 5);
+
+        let tables = parse_to_ast("[t]\nx = 1\n[t]\ny = 2\n", AstLanguage::Toml);
+        let error = &tables.errors[0];
+        assert_eq!(error.message, "Duplicate table '[t]'");
+        assert_eq!(offsets(&error.related_ranges[0]), (0, 3));
+    }
+}
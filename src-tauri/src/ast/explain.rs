@@ -0,0 +1,1010 @@
+//! Query plans from EXPLAIN output
+//!
+//! `PostgreSQL` `EXPLAIN (FORMAT JSON)`, `MySQL` `EXPLAIN FORMAT=JSON` (the
+//! classic layout and `explain_json_format_version = 2`), and `SQLite`
+//! `EXPLAIN QUERY PLAN` output become one tree of plan nodes. A plan node
+//! is an object labelled with its operation and cost estimate, as the
+//! text form of EXPLAIN prints them; its value holds the same figures as
+//! numbers. Its children are the node's details (filters, index
+//! conditions, sort keys) as properties, then its input plans.
+//!
+//! JSON plans keep the JSON path and range of the object they come from.
+//! Query output copied from psql, with its header and `+` continuation
+//! markers, is read as is.
+
+use std::fmt::Write as _;
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    parse_to_ast, AstLanguage, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange,
+    SqlDialect,
+};
+
+/// Postgres plan members shown in the label or value rather than as
+/// properties
+const POSTGRES_SUMMARY: [&str; 13] = [
+    "Node Type",
+    "Relation Name",
+    "Alias",
+    "Index Name",
+    "Startup Cost",
+    "Total Cost",
+    "Plan Rows",
+    "Plan Width",
+    "Actual Startup Time",
+    "Actual Total Time",
+    "Actual Rows",
+    "Actual Loops",
+    "Plans",
+];
+
+/// `MySQL` (format version 2) plan members shown in the label or value
+const MYSQL_SUMMARY: [&str; 11] = [
+    "operation",
+    "inputs",
+    "query",
+    "estimated_first_row_cost",
+    "estimated_total_cost",
+    "estimated_rows",
+    "actual_first_row_ms",
+    "actual_last_row_ms",
+    "actual_rows",
+    "actual_loops",
+    "json_schema_version",
+];
+
+/// `MySQL` (classic format) members holding nested operations
+const MYSQL_OPERATIONS: [&str; 12] = [
+    "table",
+    "nested_loop",
+    "ordering_operation",
+    "grouping_operation",
+    "duplicates_removal",
+    "windowing",
+    "buffer_result",
+    "union_result",
+    "materialized_from_subquery",
+    "attached_subqueries",
+    "optimized_away_subqueries",
+    "query_block",
+];
+
+/// Parse EXPLAIN output produced by `dialect`. Dialects without a
+/// supported format, and the generic dialect, are recognised from the
+/// output itself.
+pub fn parse_explain_plan(text: &str, dialect: SqlDialect) -> AstParseResult {
+    let format = match dialect {
+        SqlDialect::Postgres => Format::Postgres,
+        SqlDialect::MySql => Format::MySql,
+        SqlDialect::Sqlite => Format::Sqlite,
+        _ => match detect(text) {
+            Some(format) => format,
+            None => {
+                return AstParseResult::failure(vec![AstParseError::new(
+                    "Unrecognized EXPLAIN output: expected PostgreSQL or MySQL JSON, or SQLite EXPLAIN QUERY PLAN",
+                )])
+                .with_dialect(dialect)
+            }
+        },
+    };
+    let result = match format {
+        Format::Postgres | Format::MySql => parse_json_plan(text, format),
+        Format::Sqlite => parse_sqlite(text),
+    };
+    result.with_dialect(dialect)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl Format {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Postgres => "PostgreSQL",
+            Self::MySql => "MySQL",
+            Self::Sqlite => "SQLite",
+        }
+    }
+}
+
+fn detect(text: &str) -> Option<Format> {
+    let cleaned = strip_psql_decoration(text);
+    match cleaned.trim_start().chars().next() {
+        Some('[' | '{') if cleaned.contains("\"Node Type\"") => Some(Format::Postgres),
+        Some('[' | '{')
+            if cleaned.contains("\"query_block\"") || cleaned.contains("\"operation\"") =>
+        {
+            Some(Format::MySql)
+        }
+        Some('[' | '{') | None => None,
+        Some(_) => Some(Format::Sqlite),
+    }
+}
+
+/// `text` with the header, row count, and `+` line continuations psql
+/// prints around a single-column result blanked out. Offsets are
+/// unchanged, so ranges still point into `text`.
+fn strip_psql_decoration(text: &str) -> String {
+    if text.trim_start().starts_with(['[', '{']) {
+        return text.to_string();
+    }
+    text.split_inclusive('\n')
+        .map(|line| {
+            let content = line.trim_end();
+            let trimmed = content.trim_start();
+            let blank = trimmed == "QUERY PLAN"
+                || (!trimmed.is_empty() && trimmed.chars().all(|c| c == '-'))
+                || (trimmed.starts_with('(') && trimmed.ends_with("row)")
+                    || trimmed.ends_with("rows)"));
+            if blank {
+                return blank_out(line, line.len() - line.trim_end_matches(['\r', '\n']).len());
+            }
+            content.strip_suffix('+').map_or_else(
+                || line.to_string(),
+                |kept| {
+                    let mut line = line.to_string();
+                    line.replace_range(kept.len()..=kept.len(), " ");
+                    line
+                },
+            )
+        })
+        .collect()
+}
+
+/// Spaces in place of `line`, keeping its `newline_len` line ending bytes
+fn blank_out(line: &str, newline_len: usize) -> String {
+    let content = line.len() - newline_len;
+    let mut blank = " ".repeat(content);
+    blank.push_str(&line[content..]);
+    blank
+}
+
+/// Figures EXPLAIN reports for a plan node
+#[derive(Debug, Default, Clone, Copy)]
+struct Costs {
+    startup: Option<f64>,
+    total: Option<f64>,
+    rows: Option<f64>,
+    actual_startup: Option<f64>,
+    actual_total: Option<f64>,
+    actual_rows: Option<f64>,
+    loops: Option<f64>,
+}
+
+impl Costs {
+    /// `(cost=0.00..35.50 rows=2550) (actual time=0.010..0.020 rows=10
+    /// loops=1)`, leaving out what was not reported
+    fn annotation(&self) -> String {
+        let mut estimate = Vec::new();
+        match (self.startup, self.total) {
+            (Some(startup), Some(total)) => estimate.push(format!("cost={startup:.2}..{total:.2}")),
+            (None, Some(total)) => estimate.push(format!("cost={total:.2}")),
+            _ => {}
+        }
+        if let Some(rows) = self.rows {
+            estimate.push(format!("rows={rows:.0}"));
+        }
+        let mut actual = Vec::new();
+        match (self.actual_startup, self.actual_total) {
+            (Some(startup), Some(total)) => {
+                actual.push(format!("time={startup:.3}..{total:.3}"));
+            }
+            (None, Some(total)) => actual.push(format!("time={total:.3}")),
+            _ => {}
+        }
+        if let Some(rows) = self.actual_rows {
+            actual.push(format!("rows={rows:.0}"));
+        }
+        if let Some(loops) = self.loops {
+            actual.push(format!("loops={loops:.0}"));
+        }
+
+        let mut annotation = String::new();
+        if !estimate.is_empty() {
+            let _ = write!(annotation, " ({})", estimate.join(" "));
+        }
+        if !actual.is_empty() {
+            let _ = write!(annotation, " (actual {})", actual.join(" "));
+        }
+        annotation
+    }
+
+    fn to_value(self, operation: &str) -> Value {
+        let mut value = Map::new();
+        value.insert("operation".to_string(), json!(operation));
+        for (key, figure) in [
+            ("startupCost", self.startup),
+            ("totalCost", self.total),
+            ("rows", self.rows),
+            ("actualStartupTime", self.actual_startup),
+            ("actualTotalTime", self.actual_total),
+            ("actualRows", self.actual_rows),
+            ("loops", self.loops),
+        ] {
+            if let Some(figure) = figure {
+                value.insert(key.to_string(), json!(figure));
+            }
+        }
+        Value::Object(value)
+    }
+}
+
+/// Plan node labelled `title` plus its cost annotation
+fn plan_node(
+    path: &str,
+    range: AstRange,
+    title: &str,
+    operation: &str,
+    costs: Costs,
+    mut children: Vec<AstNode>,
+    inputs: Vec<AstNode>,
+) -> AstNode {
+    children.extend(inputs);
+    AstNode::new(
+        AstNodeType::Object,
+        path.to_string(),
+        format!("{title}{}", costs.annotation()),
+        range,
+    )
+    .with_value(costs.to_value(operation))
+    .with_children(children)
+}
+
+/// Detail of a plan node, labelled `key: value`
+fn detail(path: &str, key: &str, text: String, value: Value, range: AstRange) -> AstNode {
+    AstNode::new(
+        AstNodeType::Property,
+        format!("{path}.{key}"),
+        format!("{key}: {text}"),
+        range,
+    )
+    .with_value(value)
+}
+
+/// Plan count below `nodes`, counting only plan nodes
+fn count_plans(nodes: &[AstNode]) -> usize {
+    nodes
+        .iter()
+        .filter(|node| node.node_type == AstNodeType::Object)
+        .map(|node| 1 + count_plans(node.children.as_deref().unwrap_or_default()))
+        .sum()
+}
+
+fn root(text: &str, format: Format, children: Vec<AstNode>) -> AstNode {
+    let plans = count_plans(&children);
+    AstNode::new(
+        AstNodeType::Root,
+        "$".to_string(),
+        format!("{} plan ({plans} nodes)", format.name()),
+        AstRange::from_offset(text, 0, text.len()),
+    )
+    .with_children(children)
+}
+
+// ============================================================================
+// JSON plans
+// ============================================================================
+
+fn parse_json_plan(text: &str, format: Format) -> AstParseResult {
+    let cleaned = strip_psql_decoration(text);
+    let parsed = parse_to_ast(&cleaned, AstLanguage::Json);
+    let Some(document) = parsed.ast else {
+        return AstParseResult::failure(parsed.errors);
+    };
+    let children = match format {
+        Format::Postgres => postgres_document(&document),
+        _ => mysql_document(&document),
+    };
+    let mut errors = parsed.errors;
+    if children.is_empty() {
+        errors.push(
+            AstParseError::new(format!("No {} plan found in the JSON", format.name()))
+                .with_range(document.range),
+        );
+    }
+    let ast = root(text, format, children);
+    if errors.is_empty() {
+        AstParseResult::success(ast)
+    } else {
+        AstParseResult::partial(ast, errors)
+    }
+}
+
+/// Object or array `node` holds, looking through the property around it
+fn container(node: &AstNode) -> &AstNode {
+    match (&node.node_type, node.children.as_deref()) {
+        (AstNodeType::Property, Some([inner])) => inner,
+        _ => node,
+    }
+}
+
+fn children_of<'a>(node: &'a AstNode, node_type: &AstNodeType) -> &'a [AstNode] {
+    let node = container(node);
+    if &node.node_type == node_type {
+        node.children.as_deref().unwrap_or_default()
+    } else {
+        &[]
+    }
+}
+
+fn members(node: &AstNode) -> &[AstNode] {
+    children_of(node, &AstNodeType::Object)
+}
+
+fn items(node: &AstNode) -> &[AstNode] {
+    children_of(node, &AstNodeType::Array)
+}
+
+fn member<'a>(node: &'a AstNode, key: &str) -> Option<&'a AstNode> {
+    members(node).iter().find(|m| m.label == key)
+}
+
+fn member_str<'a>(node: &'a AstNode, key: &str) -> Option<&'a str> {
+    member(node, key)?.value.as_ref()?.as_str()
+}
+
+/// Number of a member; `MySQL` writes costs as strings
+fn member_number(node: &AstNode, key: &str) -> Option<f64> {
+    match member(node, key)?.value.as_ref()? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Display text and value of a scalar member, or of an array of scalars
+fn scalar(node: &AstNode) -> Option<(String, Value)> {
+    if let Some(value) = &node.value {
+        let text = match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        return Some((text, value.clone()));
+    }
+    if container(node).node_type != AstNodeType::Array {
+        return None;
+    }
+    let values: Vec<Value> = items(node)
+        .iter()
+        .map(|item| item.value.clone())
+        .collect::<Option<_>>()?;
+    let text = values
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some((text, Value::Array(values)))
+}
+
+/// Details of a JSON plan: its scalar members not in `summary`
+fn json_details(node: &AstNode, summary: &[&str]) -> Vec<AstNode> {
+    members(node)
+        .iter()
+        .filter(|m| !summary.contains(&m.label.as_str()))
+        .filter_map(|m| {
+            let (text, value) = scalar(m)?;
+            Some(detail(&node.path, &m.label, text, value, m.range))
+        })
+        .collect()
+}
+
+/// `[{"Plan": {...}, "Planning Time": ..}]`, one entry per statement
+fn postgres_document(document: &AstNode) -> Vec<AstNode> {
+    let statements: Vec<&AstNode> = if document.node_type == AstNodeType::Array {
+        items(document).iter().collect()
+    } else {
+        vec![document]
+    };
+    statements
+        .into_iter()
+        .filter_map(|statement| {
+            let plan = member(statement, "Plan")?;
+            let mut nodes = vec![postgres_plan(container(plan))];
+            nodes.extend(json_details(statement, &["Plan"]));
+            Some(nodes)
+        })
+        .flatten()
+        .collect()
+}
+
+fn postgres_plan(plan: &AstNode) -> AstNode {
+    let operation = member_str(plan, "Node Type").unwrap_or("Plan");
+    let mut title = operation.to_string();
+    if let Some(index) = member_str(plan, "Index Name") {
+        let _ = write!(title, " using {index}");
+    }
+    if let Some(relation) = member_str(plan, "Relation Name") {
+        let _ = write!(title, " on {relation}");
+        if let Some(alias) = member_str(plan, "Alias").filter(|alias| *alias != relation) {
+            let _ = write!(title, " {alias}");
+        }
+    }
+    let costs = Costs {
+        startup: member_number(plan, "Startup Cost"),
+        total: member_number(plan, "Total Cost"),
+        rows: member_number(plan, "Plan Rows"),
+        actual_startup: member_number(plan, "Actual Startup Time"),
+        actual_total: member_number(plan, "Actual Total Time"),
+        actual_rows: member_number(plan, "Actual Rows"),
+        loops: member_number(plan, "Actual Loops"),
+    };
+    let inputs = member(plan, "Plans")
+        .map(|plans| {
+            items(plans)
+                .iter()
+                .map(|input| postgres_plan(container(input)))
+                .collect()
+        })
+        .unwrap_or_default();
+    plan_node(
+        &plan.path,
+        plan.range,
+        &title,
+        operation,
+        costs,
+        json_details(plan, &POSTGRES_SUMMARY),
+        inputs,
+    )
+}
+
+fn mysql_document(document: &AstNode) -> Vec<AstNode> {
+    if member(document, "operation").is_some() {
+        return vec![mysql_v2_plan(document)];
+    }
+    mysql_operations(document)
+}
+
+/// `explain_json_format_version = 2`: nodes with `operation` and `inputs`
+fn mysql_v2_plan(plan: &AstNode) -> AstNode {
+    let operation = member_str(plan, "operation").unwrap_or("Plan");
+    let costs = Costs {
+        startup: member_number(plan, "estimated_first_row_cost"),
+        total: member_number(plan, "estimated_total_cost"),
+        rows: member_number(plan, "estimated_rows"),
+        actual_startup: member_number(plan, "actual_first_row_ms"),
+        actual_total: member_number(plan, "actual_last_row_ms"),
+        actual_rows: member_number(plan, "actual_rows"),
+        loops: member_number(plan, "actual_loops"),
+    };
+    let inputs = member(plan, "inputs")
+        .map(|inputs| {
+            items(inputs)
+                .iter()
+                .map(|input| mysql_v2_plan(container(input)))
+                .collect()
+        })
+        .unwrap_or_default();
+    plan_node(
+        &plan.path,
+        plan.range,
+        operation,
+        operation,
+        costs,
+        json_details(plan, &MYSQL_SUMMARY),
+        inputs,
+    )
+}
+
+/// Plans for the operation members of a classic-format object, in
+/// document order
+fn mysql_operations(node: &AstNode) -> Vec<AstNode> {
+    members(node)
+        .iter()
+        .filter(|m| MYSQL_OPERATIONS.contains(&m.label.as_str()))
+        .flat_map(|m| {
+            let inner = container(m);
+            match m.label.as_str() {
+                "query_block" => vec![mysql_query_block(inner)],
+                "table" => vec![mysql_table(inner)],
+                "nested_loop" => {
+                    let inputs = items(m).iter().flat_map(mysql_operations).collect();
+                    vec![mysql_wrapper(inner, "Nested loop", Vec::new(), inputs)]
+                }
+                "attached_subqueries" | "optimized_away_subqueries" => {
+                    items(m).iter().flat_map(mysql_operations).collect()
+                }
+                "union_result" => {
+                    let inputs = member(inner, "query_specifications")
+                        .map(|specs| items(specs).iter().flat_map(mysql_operations).collect())
+                        .unwrap_or_default();
+                    let details = json_details(inner, &["query_specifications"]);
+                    vec![mysql_wrapper(inner, "Union", details, inputs)]
+                }
+                label => {
+                    let title = match label {
+                        "ordering_operation"
+                            if member(inner, "using_filesort")
+                                .and_then(|f| f.value.as_ref())
+                                .and_then(Value::as_bool)
+                                == Some(true) =>
+                        {
+                            "Sort"
+                        }
+                        "ordering_operation" => "Ordering",
+                        "grouping_operation" => "Group",
+                        "duplicates_removal" => "Remove duplicates",
+                        "windowing" => "Window",
+                        "buffer_result" => "Buffer result",
+                        _ => "Materialize",
+                    };
+                    let details = json_details(inner, &[]);
+                    vec![mysql_wrapper(
+                        inner,
+                        title,
+                        details,
+                        mysql_operations(inner),
+                    )]
+                }
+            }
+        })
+        .collect()
+}
+
+fn mysql_wrapper(
+    node: &AstNode,
+    title: &str,
+    details: Vec<AstNode>,
+    inputs: Vec<AstNode>,
+) -> AstNode {
+    let costs = Costs {
+        total: member(node, "cost_info").and_then(|cost| {
+            ["sort_cost", "query_cost"]
+                .into_iter()
+                .find_map(|key| member_number(cost, key))
+        }),
+        ..Costs::default()
+    };
+    plan_node(&node.path, node.range, title, title, costs, details, inputs)
+}
+
+fn mysql_query_block(block: &AstNode) -> AstNode {
+    let title = member(block, "select_id").and_then(scalar).map_or_else(
+        || "Query block".to_string(),
+        |(id, _)| format!("Query block #{id}"),
+    );
+    let costs = Costs {
+        total: member(block, "cost_info").and_then(|cost| member_number(cost, "query_cost")),
+        ..Costs::default()
+    };
+    plan_node(
+        &block.path,
+        block.range,
+        &title,
+        "Query block",
+        costs,
+        json_details(block, &["select_id"]),
+        mysql_operations(block),
+    )
+}
+
+fn mysql_table(table: &AstNode) -> AstNode {
+    let access_type = member_str(table, "access_type").unwrap_or_default();
+    let operation = match access_type {
+        "ALL" => "Table scan",
+        "index" => "Index scan",
+        "range" => "Index range scan",
+        "ref" | "eq_ref" | "ref_or_null" => "Index lookup",
+        "const" | "system" => "Constant row",
+        "index_merge" => "Index merge",
+        "fulltext" => "Full-text index lookup",
+        _ => "Table access",
+    };
+    let mut title = operation.to_string();
+    if let Some(name) = member_str(table, "table_name") {
+        let _ = write!(title, " on {name}");
+    }
+    if let Some(key) = member_str(table, "key") {
+        let _ = write!(title, " using {key}");
+    }
+    let cost_info = member(table, "cost_info");
+    let costs = Costs {
+        total: cost_info.and_then(|cost| member_number(cost, "prefix_cost")),
+        rows: member_number(table, "rows_produced_per_join"),
+        ..Costs::default()
+    };
+    plan_node(
+        &table.path,
+        table.range,
+        &title,
+        operation,
+        costs,
+        json_details(table, &["table_name"]),
+        mysql_operations(table),
+    )
+}
+
+// ============================================================================
+// SQLite EXPLAIN QUERY PLAN
+// ============================================================================
+
+/// Plan row before it is placed in the tree
+struct Row<'a> {
+    detail: &'a str,
+    range: AstRange,
+}
+
+/// Rows of the tree the sqlite3 shell draws (`|--SCAN t`), or of the raw
+/// `id|parent|notused|detail` result, as (parent row index, row) pairs
+fn sqlite_rows(text: &str) -> Result<Vec<(Option<usize>, Row<'_>)>, AstParseError> {
+    let mut rows: Vec<(Option<usize>, Row<'_>)> = Vec::new();
+    // Depth and row index of the rows on the path to the current one
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    // Row index by the id column of raw rows
+    let mut ids: Vec<(i64, usize)> = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end();
+        if content.trim().is_empty() || content.trim() == "QUERY PLAN" {
+            continue;
+        }
+        let range = |at: usize, detail: &str| {
+            AstRange::from_offset(text, start + at, start + at + detail.len())
+        };
+
+        if let Some((id, parent, at, detail)) = raw_row(content) {
+            let parent = ids
+                .iter()
+                .rev()
+                .find(|(known, _)| *known == parent)
+                .map(|&(_, index)| index);
+            ids.push((id, rows.len()));
+            rows.push((
+                parent,
+                Row {
+                    detail,
+                    range: range(at, detail),
+                },
+            ));
+            continue;
+        }
+
+        let (depth, at, detail) = tree_row(content).ok_or_else(|| {
+            AstParseError::new("Expected an EXPLAIN QUERY PLAN row")
+                .with_range(AstRange::from_offset(text, start, start + content.len()))
+        })?;
+        while open
+            .last()
+            .is_some_and(|&(open_depth, _)| open_depth >= depth)
+        {
+            open.pop();
+        }
+        let parent = open.last().map(|&(_, index)| index);
+        open.push((depth, rows.len()));
+        rows.push((
+            parent,
+            Row {
+                detail,
+                range: range(at, detail),
+            },
+        ));
+    }
+    Ok(rows)
+}
+
+/// `2|0|0|SCAN t` (or with the columns separated by spaces), as id,
+/// parent id, and the detail with its byte offset in `line`
+fn raw_row(line: &str) -> Option<(i64, i64, usize, &str)> {
+    let mut rest = line.trim_start();
+    let mut numbers = [0_i64; 3];
+    for number in &mut numbers {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .filter(|&end| end > 0)?;
+        *number = rest[..end].parse().ok()?;
+        rest = rest[end..].trim_start_matches([' ', '\t']);
+        rest = rest.strip_prefix('|').unwrap_or(rest);
+        rest = rest.trim_start_matches([' ', '\t']);
+    }
+    (!rest.is_empty()).then(|| (numbers[0], numbers[1], line.len() - rest.len(), rest))
+}
+
+/// Depth, detail byte offset in `line`, and detail of a row drawn as
+/// `|  |--SEARCH t USING INDEX i`
+fn tree_row(line: &str) -> Option<(usize, usize, &str)> {
+    let detail_at = |detail: &str| line.len() - detail.len();
+    let mut rest = line;
+    let mut depth = 0;
+    loop {
+        if let Some(detail) = rest
+            .strip_prefix("|--")
+            .or_else(|| rest.strip_prefix("`--"))
+        {
+            let detail = detail.trim_start();
+            return Some((depth + 1, detail_at(detail), detail.trim_end()));
+        }
+        if let Some(next) = rest
+            .strip_prefix("|  ")
+            .or_else(|| rest.strip_prefix("   "))
+        {
+            rest = next;
+            depth += 1;
+            continue;
+        }
+        // A row without a connector is a root, as printed by older shells.
+        return (depth == 0 && !rest.starts_with(['|', '`', ' ']))
+            .then(|| (0, detail_at(rest), rest.trim_end()));
+    }
+}
+
+fn parse_sqlite(text: &str) -> AstParseResult {
+    let rows = match sqlite_rows(text) {
+        Ok(rows) => rows,
+        Err(error) => return AstParseResult::failure(vec![error]),
+    };
+    if rows.is_empty() {
+        return AstParseResult::failure(vec![AstParseError::new(
+            "No EXPLAIN QUERY PLAN rows found",
+        )]);
+    }
+
+    let mut children_of: Vec<Vec<usize>> = vec![Vec::new(); rows.len()];
+    let mut roots = Vec::new();
+    for (index, (parent, _)) in rows.iter().enumerate() {
+        match parent {
+            Some(parent) => children_of[*parent].push(index),
+            None => roots.push(index),
+        }
+    }
+    let children = roots
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| {
+            sqlite_plan(&rows, &children_of, index, &format!("$[{position}]"))
+        })
+        .collect();
+    AstParseResult::success(root(text, Format::Sqlite, children))
+}
+
+fn sqlite_plan(
+    rows: &[(Option<usize>, Row<'_>)],
+    children_of: &[Vec<usize>],
+    index: usize,
+    path: &str,
+) -> AstNode {
+    let row = &rows[index].1;
+    let operation = row
+        .detail
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    let mut details = Vec::new();
+    if operation == "SCAN" && !row.detail.contains(" USING ") {
+        details.push(detail(
+            path,
+            "Full scan",
+            "true".to_string(),
+            Value::Bool(true),
+            row.range,
+        ));
+    }
+    let inputs = children_of[index]
+        .iter()
+        .enumerate()
+        .map(|(position, &child)| {
+            sqlite_plan(rows, children_of, child, &format!("{path}[{position}]"))
+        })
+        .collect();
+    plan_node(
+        path,
+        row.range,
+        row.detail,
+        &operation,
+        Costs::default(),
+        details,
+        inputs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(node: &AstNode) -> Vec<&str> {
+        node.children
+            .iter()
+            .flatten()
+            .map(|child| child.label.as_str())
+            .collect()
+    }
+
+    const POSTGRES: &str = r#"[
+  {
+    "Plan": {
+      "Node Type": "Hash Join",
+      "Join Type": "Inner",
+      "Startup Cost": 1.09,
+      "Total Cost": 2.22,
+      "Plan Rows": 4,
+      "Hash Cond": "(o.user_id = u.id)",
+      "Plans": [
+        {"Node Type": "Seq Scan", "Relation Name": "orders", "Alias": "o",
+         "Startup Cost": 0.00, "Total Cost": 1.04, "Plan Rows": 4,
+         "Filter": "(total > 10)"},
+        {"Node Type": "Index Scan", "Index Name": "users_pkey",
+         "Relation Name": "users", "Alias": "users",
+         "Startup Cost": 0.15, "Total Cost": 1.05, "Plan Rows": 1,
+         "Actual Startup Time": 0.01, "Actual Total Time": 0.02,
+         "Actual Rows": 1, "Actual Loops": 4,
+         "Sort Key": ["u.id", "u.name"]}
+      ]
+    },
+    "Planning Time": 0.2
+  }
+]"#;
+
+    #[test]
+    fn test_postgres_plan() {
+        let result = parse_explain_plan(POSTGRES, SqlDialect::Postgres);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.dialect, Some(SqlDialect::Postgres));
+        let root = result.ast.unwrap();
+        assert_eq!(root.label, "PostgreSQL plan (3 nodes)");
+        assert_eq!(
+            labels(&root),
+            ["Hash Join (cost=1.09..2.22 rows=4)", "Planning Time: 0.2"]
+        );
+
+        let join = &root.children.as_ref().unwrap()[0];
+        assert_eq!(join.path, "$[0].Plan");
+        assert_eq!(join.value.as_ref().unwrap()["totalCost"], json!(2.22));
+        assert_eq!(
+            labels(join),
+            [
+                "Join Type: Inner",
+                "Hash Cond: (o.user_id = u.id)",
+                "Seq Scan on orders o (cost=0.00..1.04 rows=4)",
+                "Index Scan using users_pkey on users (cost=0.15..1.05 rows=1) (actual time=0.010..0.020 rows=1 loops=4)",
+            ]
+        );
+        let index_scan = &join.children.as_ref().unwrap()[3];
+        assert_eq!(index_scan.path, "$[0].Plan.Plans[1]");
+        assert_eq!(labels(index_scan), ["Sort Key: u.id, u.name"]);
+        assert_eq!(
+            index_scan.range.start.offset,
+            POSTGRES.find("{\"Node Type\": \"Index Scan\"").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_psql_output_keeps_offsets() {
+        let pasted = "              QUERY PLAN\n--------------------------------------\n [                                   +\n   {                                 +\n     \"Plan\": {                       +\n       \"Node Type\": \"Result\",       +\n       \"Total Cost\": 0.01           +\n     }                               +\n   }                                 +\n ]\n(1 row)\n";
+        let result = parse_explain_plan(pasted, SqlDialect::Generic);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let plan = &result.ast.unwrap().children.unwrap()[0];
+        assert_eq!(plan.label, "Result (cost=0.01)");
+        assert_eq!(plan.range.start.line, 5);
+        assert!(pasted[plan.range.start.offset..].starts_with('{'));
+    }
+
+    #[test]
+    fn test_mysql_classic_plan() {
+        let json = r#"{
+  "query_block": {
+    "select_id": 1,
+    "cost_info": {"query_cost": "3.50"},
+    "ordering_operation": {
+      "using_filesort": true,
+      "nested_loop": [
+        {"table": {"table_name": "o", "access_type": "ALL",
+                   "rows_produced_per_join": 10,
+                   "cost_info": {"prefix_cost": "1.25"},
+                   "attached_condition": "(o.total > 10)"}},
+        {"table": {"table_name": "u", "access_type": "eq_ref", "key": "PRIMARY",
+                   "ref": ["db.o.user_id"], "rows_produced_per_join": 10,
+                   "cost_info": {"prefix_cost": "3.50"}}}
+      ]
+    }
+  }
+}"#;
+        let result = parse_explain_plan(json, SqlDialect::MySql);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let root = result.ast.unwrap();
+        assert_eq!(root.label, "MySQL plan (5 nodes)");
+        let block = &root.children.as_ref().unwrap()[0];
+        assert_eq!(block.label, "Query block #1 (cost=3.50)");
+        let sort = &block.children.as_ref().unwrap()[0];
+        assert_eq!(sort.label, "Sort");
+        let nested = &sort.children.as_ref().unwrap()[1];
+        assert_eq!(nested.path, "$.query_block.ordering_operation.nested_loop");
+        assert_eq!(
+            labels(nested),
+            [
+                "Table scan on o (cost=1.25 rows=10)",
+                "Index lookup on u using PRIMARY (cost=3.50 rows=10)",
+            ]
+        );
+        let scan = &nested.children.as_ref().unwrap()[0];
+        assert!(labels(scan).contains(&"attached_condition: (o.total > 10)"));
+    }
+
+    #[test]
+    fn test_mysql_format_version_2() {
+        let json = r#"{"query": "select ...", "operation": "Nested loop inner join",
+            "estimated_rows": 4, "estimated_total_cost": 2.5, "json_schema_version": "2.0",
+            "inputs": [{"operation": "Table scan on t", "table_name": "t",
+                        "estimated_rows": 4, "estimated_total_cost": 0.65,
+                        "actual_first_row_ms": 0.02, "actual_last_row_ms": 0.05,
+                        "actual_rows": 4, "actual_loops": 1}]}"#;
+        let result = parse_explain_plan(json, SqlDialect::Generic);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let root = result.ast.unwrap();
+        let join = &root.children.as_ref().unwrap()[0];
+        assert_eq!(join.label, "Nested loop inner join (cost=2.50 rows=4)");
+        assert_eq!(
+            labels(join),
+            ["Table scan on t (cost=0.65 rows=4) (actual time=0.020..0.050 rows=4 loops=1)"]
+        );
+        assert_eq!(
+            join.children.as_ref().unwrap()[0]
+                .children
+                .as_ref()
+                .unwrap()[0]
+                .label,
+            "table_name: t"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_tree_and_raw_rows() {
+        let tree = "QUERY PLAN\n|--SCAN o\n|--SEARCH u USING INTEGER PRIMARY KEY (rowid=?)\n`--CORRELATED SCALAR SUBQUERY 1\n   `--SCAN t USING COVERING INDEX t_idx\n";
+        let result = parse_explain_plan(tree, SqlDialect::Sqlite);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let root = result.ast.unwrap();
+        assert_eq!(root.label, "SQLite plan (4 nodes)");
+        assert_eq!(
+            labels(&root),
+            [
+                "SCAN o",
+                "SEARCH u USING INTEGER PRIMARY KEY (rowid=?)",
+                "CORRELATED SCALAR SUBQUERY 1"
+            ]
+        );
+        let scan = &root.children.as_ref().unwrap()[0];
+        assert_eq!(labels(scan), ["Full scan: true"]);
+        assert_eq!(scan.range.start.line, 2);
+        assert_eq!(scan.range.start.column, 4);
+        let subquery = &root.children.as_ref().unwrap()[2];
+        assert_eq!(labels(subquery), ["SCAN t USING COVERING INDEX t_idx"]);
+        let nested = &subquery.children.as_ref().unwrap()[0];
+        assert_eq!(nested.path, "$[2][0]");
+        assert_eq!((nested.range.start.line, nested.range.start.column), (5, 7));
+
+        let raw = "2|0|0|SCAN o\n4|0|0|CORRELATED SCALAR SUBQUERY 1\n7|4|0|SCAN t\n";
+        let root = parse_explain_plan(raw, SqlDialect::Sqlite).ast.unwrap();
+        assert_eq!(labels(&root), ["SCAN o", "CORRELATED SCALAR SUBQUERY 1"]);
+        let subquery = &root.children.as_ref().unwrap()[1];
+        assert_eq!(labels(subquery), ["SCAN t"]);
+        let nested = &subquery.children.as_ref().unwrap()[0];
+        assert_eq!((nested.range.start.line, nested.range.start.column), (3, 7));
+        assert_eq!(nested.range.end.column, 13);
+    }
+
+    #[test]
+    fn test_unrecognized_output() {
+        let result = parse_explain_plan(r#"{"rows": []}"#, SqlDialect::Generic);
+        assert!(result.ast.is_none());
+        assert_eq!(result.dialect, Some(SqlDialect::Generic));
+
+        let result = parse_explain_plan(r#"{"rows": []}"#, SqlDialect::Postgres);
+        assert_eq!(
+            result.errors[0].message,
+            "No PostgreSQL plan found in the JSON"
+        );
+
+        let result = parse_explain_plan("QUERY PLAN\n  |- broken\n", SqlDialect::Sqlite);
+        assert!(result.ast.is_none());
+        assert_eq!(result.errors[0].range.unwrap().start.line, 2);
+    }
+}
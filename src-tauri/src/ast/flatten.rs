@@ -0,0 +1,403 @@
+//! Flattening of nested JSON into single-level key paths and back
+//!
+//! `{"a": {"b": [1, 2]}}` flattens to `{"a.b[0]": 1, "a.b[1]": 2}`, the
+//! shape spreadsheets and env-var style config expect. Key order is kept
+//! in both directions, so a flatten/unflatten round trip reproduces the
+//! original document. Empty objects and arrays are kept as values.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::convert::{json_text, read_json, Value};
+use super::AstParseError;
+
+/// Largest array index accepted when unflattening, so a stray key like
+/// `a[99999999]` cannot allocate a huge array of nulls.
+const MAX_INDEX: usize = 100_000;
+
+/// How arrays appear in flattened keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayHandling {
+    /// Indices in brackets: `items[0].name`
+    #[default]
+    Brackets,
+    /// Indices as key segments after the separator: `items.0.name`
+    Separator,
+    /// Arrays are not flattened and stay values: `items: [...]`
+    Preserve,
+}
+
+/// Options for [`flatten_json`] and [`unflatten_json`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlattenOptions {
+    /// Separator between object keys, such as `.` or `__`
+    pub separator: String,
+    pub array_handling: ArrayHandling,
+    /// Spaces per indentation level of the output; 0 indents with tabs
+    pub indent: usize,
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self {
+            separator: ".".to_string(),
+            array_handling: ArrayHandling::Brackets,
+            indent: 2,
+        }
+    }
+}
+
+/// Result of [`flatten_json`] and [`unflatten_json`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenResult {
+    /// Output document; absent when the input is invalid or its keys
+    /// collide
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Number of flattened keys written or read
+    pub keys: usize,
+    pub errors: Vec<AstParseError>,
+}
+
+impl FlattenResult {
+    fn failure(errors: Vec<AstParseError>) -> Self {
+        Self {
+            errors,
+            ..Self::default()
+        }
+    }
+}
+
+/// Flatten the JSON object or array `text` into one object whose keys
+/// are the paths of its leaf values
+pub fn flatten_json(text: &str, options: &FlattenOptions) -> FlattenResult {
+    if options.separator.is_empty() {
+        return FlattenResult::failure(vec![AstParseError::new("Separator must not be empty")]);
+    }
+    let root = match read_json(text) {
+        Ok(root) => root,
+        Err(errors) => return FlattenResult::failure(errors),
+    };
+    if !matches!(root, Value::Map(_) | Value::List(_)) {
+        return FlattenResult::failure(vec![AstParseError::new(
+            "Only objects and arrays can be flattened",
+        )]);
+    }
+    let mut entries = Vec::new();
+    flatten(&root, None, options, &mut entries);
+
+    let mut seen = HashSet::new();
+    let mut repeated = HashSet::new();
+    let mut errors = Vec::new();
+    for (key, _) in &entries {
+        if !seen.insert(key.as_str()) && repeated.insert(key.as_str()) {
+            errors.push(AstParseError::new(format!(
+                "Flattened key '{key}' occurs more than once; rename keys containing the separator"
+            )));
+        }
+    }
+    if !errors.is_empty() {
+        return FlattenResult::failure(errors);
+    }
+    FlattenResult {
+        text: Some(json_text(&Value::Map(entries.clone()), options.indent)),
+        keys: entries.len(),
+        errors,
+    }
+}
+
+/// Rebuild nested JSON from an object of flattened keys, the reverse of
+/// [`flatten_json`] with the same options
+pub fn unflatten_json(text: &str, options: &FlattenOptions) -> FlattenResult {
+    if options.separator.is_empty() {
+        return FlattenResult::failure(vec![AstParseError::new("Separator must not be empty")]);
+    }
+    let entries = match read_json(text) {
+        Ok(Value::Map(entries)) => entries,
+        Ok(_) => {
+            return FlattenResult::failure(vec![AstParseError::new(
+                "Only objects of flattened keys can be unflattened",
+            )])
+        }
+        Err(errors) => return FlattenResult::failure(errors),
+    };
+    let mut root = Node::Empty;
+    let mut errors = Vec::new();
+    for (key, value) in &entries {
+        let result =
+            segments(key, options).and_then(|segments| root.insert(&segments, value.clone(), key));
+        if let Err(message) = result {
+            errors.push(AstParseError::new(message));
+        }
+    }
+    if !errors.is_empty() {
+        return FlattenResult::failure(errors);
+    }
+    FlattenResult {
+        text: Some(json_text(&root.into_value(), options.indent)),
+        keys: entries.len(),
+        errors,
+    }
+}
+
+fn flatten(
+    value: &Value,
+    prefix: Option<&str>,
+    options: &FlattenOptions,
+    out: &mut Vec<(String, Value)>,
+) {
+    match value {
+        Value::Map(entries) if !entries.is_empty() => {
+            for (key, item) in entries {
+                let key = prefix.map_or_else(
+                    || key.clone(),
+                    |prefix| format!("{prefix}{}{key}", options.separator),
+                );
+                flatten(item, Some(&key), options, out);
+            }
+        }
+        Value::List(items)
+            if !items.is_empty() && options.array_handling != ArrayHandling::Preserve =>
+        {
+            for (i, item) in items.iter().enumerate() {
+                let key = match (options.array_handling, prefix) {
+                    (ArrayHandling::Separator, Some(prefix)) => {
+                        format!("{prefix}{}{i}", options.separator)
+                    }
+                    (ArrayHandling::Separator, None) => i.to_string(),
+                    (_, prefix) => format!("{}[{i}]", prefix.unwrap_or_default()),
+                };
+                flatten(item, Some(&key), options, out);
+            }
+        }
+        _ => out.push((prefix.unwrap_or_default().to_string(), value.clone())),
+    }
+}
+
+/// Step in a flattened key
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Split a flattened key into object keys and array indices
+fn segments(key: &str, options: &FlattenOptions) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    for part in key.split(options.separator.as_str()) {
+        match options.array_handling {
+            ArrayHandling::Brackets => match bracket_segments(part) {
+                Some(parsed) => segments.extend(parsed),
+                // Not indices after all, such as `a[b`; keep it as a key.
+                None => segments.push(Segment::Key(part.to_string())),
+            },
+            ArrayHandling::Separator if is_index(part) => {
+                segments.push(Segment::Index(part.parse().map_err(|_| {
+                    format!("Array index '{part}' in '{key}' is too large")
+                })?));
+            }
+            _ => segments.push(Segment::Key(part.to_string())),
+        }
+    }
+    if let Some(index) = segments.iter().find_map(|segment| match segment {
+        Segment::Index(index) if *index > MAX_INDEX => Some(index),
+        _ => None,
+    }) {
+        return Err(format!("Array index {index} in '{key}' is too large"));
+    }
+    Ok(segments)
+}
+
+/// `name[0][1]` as a key followed by indices; `[0]` alone has no key.
+/// `None` when the brackets do not hold indices.
+fn bracket_segments(part: &str) -> Option<Vec<Segment>> {
+    let (name, mut indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+    let mut segments = Vec::new();
+    if !name.is_empty() || indices.is_empty() {
+        segments.push(Segment::Key(name.to_string()));
+    }
+    while !indices.is_empty() {
+        let (index, rest) = indices.strip_prefix('[')?.split_once(']')?;
+        if !is_index(index) {
+            return None;
+        }
+        segments.push(Segment::Index(index.parse().ok()?));
+        indices = rest;
+    }
+    Some(segments)
+}
+
+/// Decimal digits without leading zeros
+fn is_index(part: &str) -> bool {
+    !part.is_empty()
+        && part.bytes().all(|b| b.is_ascii_digit())
+        && (part == "0" || !part.starts_with('0'))
+}
+
+/// Partially rebuilt document
+enum Node {
+    Empty,
+    Leaf(Value),
+    Map(Vec<(String, Self)>),
+    List(Vec<Self>),
+}
+
+impl Node {
+    fn insert(&mut self, segments: &[Segment], value: Value, key: &str) -> Result<(), String> {
+        let conflict = || format!("Key '{key}' conflicts with another key");
+        match segments.split_first() {
+            None if matches!(self, Self::Empty) => {
+                *self = Self::Leaf(value);
+                Ok(())
+            }
+            None => Err(conflict()),
+            Some((Segment::Key(name), rest)) => {
+                if matches!(self, Self::Empty) {
+                    *self = Self::Map(Vec::new());
+                }
+                let Self::Map(entries) = self else {
+                    return Err(conflict());
+                };
+                let index = entries
+                    .iter()
+                    .position(|(existing, _)| existing == name)
+                    .unwrap_or_else(|| {
+                        entries.push((name.clone(), Self::Empty));
+                        entries.len() - 1
+                    });
+                entries[index].1.insert(rest, value, key)
+            }
+            Some((Segment::Index(index), rest)) => {
+                if matches!(self, Self::Empty) {
+                    *self = Self::List(Vec::new());
+                }
+                let Self::List(items) = self else {
+                    return Err(conflict());
+                };
+                if items.len() <= *index {
+                    items.resize_with(index + 1, || Self::Empty);
+                }
+                items[*index].insert(rest, value, key)
+            }
+        }
+    }
+
+    /// The rebuilt value; array slots no key filled become null
+    fn into_value(self) -> Value {
+        match self {
+            Self::Empty => Value::Null,
+            Self::Leaf(value) => value,
+            Self::Map(entries) => Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, node)| (key, node.into_value()))
+                    .collect(),
+            ),
+            Self::List(items) => Value::List(items.into_iter().map(Self::into_value).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NESTED: &str = r#"{"name": "kogu", "tags": ["a", "b"], "db": {"host": "localhost", "ports": [{"n": 1}], "opts": {}}}"#;
+
+    fn options(separator: &str, array_handling: ArrayHandling) -> FlattenOptions {
+        FlattenOptions {
+            separator: separator.to_string(),
+            array_handling,
+            indent: 0,
+        }
+    }
+
+    #[test]
+    fn test_flatten_with_brackets() {
+        let result = flatten_json(NESTED, &options(".", ArrayHandling::Brackets));
+        assert_eq!(
+            result.text.as_deref(),
+            Some(concat!(
+                "{\n\t\"name\": \"kogu\",\n\t\"tags[0]\": \"a\",\n\t\"tags[1]\": \"b\",\n",
+                "\t\"db.host\": \"localhost\",\n\t\"db.ports[0].n\": 1,\n\t\"db.opts\": {}\n}\n"
+            ))
+        );
+        assert_eq!(result.keys, 6);
+    }
+
+    #[test]
+    fn test_flatten_with_separator_indices() {
+        let result = flatten_json(NESTED, &options("__", ArrayHandling::Separator));
+        let text = result.text.unwrap();
+        assert!(text.contains("\"tags__1\": \"b\""));
+        assert!(text.contains("\"db__ports__0__n\": 1"));
+    }
+
+    #[test]
+    fn test_flatten_preserving_arrays() {
+        let result = flatten_json(NESTED, &options(".", ArrayHandling::Preserve));
+        let text = result.text.unwrap();
+        assert!(text.contains("\"tags\": [\n\t\t\"a\",\n\t\t\"b\"\n\t]"));
+        assert!(text.contains("\"db.ports\": ["));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for handling in [
+            ArrayHandling::Brackets,
+            ArrayHandling::Separator,
+            ArrayHandling::Preserve,
+        ] {
+            let options = options("/", handling);
+            let flat = flatten_json(NESTED, &options).text.unwrap();
+            let nested = unflatten_json(&flat, &options).text.unwrap();
+            assert_eq!(
+                read_json(&nested).ok(),
+                read_json(NESTED).ok(),
+                "{handling:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unflatten_root_array_and_gaps() {
+        let result = unflatten_json(r#"{"[0].a": 1, "[2]": true}"#, &FlattenOptions::default());
+        assert_eq!(
+            result.text.as_deref(),
+            Some("[\n  {\n    \"a\": 1\n  },\n  null,\n  true\n]\n")
+        );
+    }
+
+    #[test]
+    fn test_unflatten_reports_conflicts() {
+        let result = unflatten_json(r#"{"a": 1, "a.b": 2}"#, &FlattenOptions::default());
+        assert!(result.text.is_none());
+        assert_eq!(
+            result.errors[0].message,
+            "Key 'a.b' conflicts with another key"
+        );
+    }
+
+    #[test]
+    fn test_flatten_reports_colliding_keys() {
+        let result = flatten_json(r#"{"a.b": 1, "a": {"b": 2}}"#, &FlattenOptions::default());
+        assert!(result.text.is_none());
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_scalars_and_huge_indices() {
+        assert!(!flatten_json("1", &FlattenOptions::default())
+            .errors
+            .is_empty());
+        assert!(!unflatten_json("[]", &FlattenOptions::default())
+            .errors
+            .is_empty());
+        let result = unflatten_json(r#"{"a[999999999]": 1}"#, &FlattenOptions::default());
+        assert!(result.errors[0].message.contains("too large"));
+    }
+}
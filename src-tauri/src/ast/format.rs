@@ -0,0 +1,805 @@
+//! Canonical formatter built on the AST parsers
+//!
+//! JSON and YAML are re-emitted from their AST trees, XML from the
+//! `roxmltree` document, and SQL from the `sqlparser` statements. Every
+//! emitted node is recorded in a source map from its original range to
+//! its range in the output. YAML and SQL comments are not kept, as their
+//! parsers drop them; XML comments, processing instructions, and
+//! text-bearing element content are copied verbatim.
+
+use serde::{Deserialize, Serialize};
+
+use roxmltree::{Document, Node, NodeType};
+
+use super::sql_format::{format_sql, SqlFormatOptions};
+use super::xml::{create_parse_error, node_path, node_range};
+use super::{
+    parse_to_ast, AstError, AstLanguage, AstNode, AstNodeType, AstParseError, AstPosition,
+    AstRange, SqlDialect,
+};
+
+/// Quote character for values that need quoting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteStyle {
+    #[default]
+    Double,
+    Single,
+}
+
+/// Options for [`format_document`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatOptions {
+    /// Spaces per indentation level; 0 indents with tabs. YAML always
+    /// indents with at least two spaces.
+    pub indent: usize,
+    /// Quote for YAML strings that cannot be plain and for XML attributes
+    pub quote_style: QuoteStyle,
+    /// Sort object keys (JSON, YAML) and attributes (XML)
+    pub sort_keys: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            quote_style: QuoteStyle::Double,
+            sort_keys: false,
+        }
+    }
+}
+
+/// Where an AST node was before and after formatting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceMapping {
+    /// AST path of the node, as assigned by the parser
+    pub path: String,
+    pub original: AstRange,
+    pub formatted: AstRange,
+}
+
+/// Result of [`format_document`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatResult {
+    /// Formatted document; absent when the input failed to parse
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// One entry per emitted node, ordered by formatted position
+    pub source_map: Vec<SourceMapping>,
+    pub errors: Vec<AstParseError>,
+}
+
+impl FormatResult {
+    pub(super) fn failure(errors: Vec<AstParseError>) -> Self {
+        Self {
+            errors,
+            ..Self::default()
+        }
+    }
+}
+
+/// Pretty-print `text` as `language`
+///
+/// # Errors
+///
+/// Returns [`AstError::UnsupportedLanguage`] for languages other than
+/// JSON, YAML, XML, and SQL.
+pub fn format_document(
+    text: &str,
+    language: AstLanguage,
+    options: &FormatOptions,
+) -> Result<FormatResult, AstError> {
+    Ok(match language {
+        AstLanguage::Json => format_tree(text, language, Writer::new(options), write_json),
+        AstLanguage::Yaml if text.trim().is_empty() => FormatResult {
+            text: Some(String::new()),
+            ..FormatResult::default()
+        },
+        AstLanguage::Yaml => {
+            // YAML has no tab indentation, and `- ` needs two columns.
+            let options = FormatOptions {
+                indent: options.indent.max(2),
+                ..*options
+            };
+            format_tree(text, language, Writer::new(&options), write_yaml)
+        }
+        AstLanguage::Xml => format_xml(text, options),
+        AstLanguage::Sql => format_sql(
+            text,
+            SqlDialect::default(),
+            &SqlFormatOptions {
+                indent: options.indent,
+                max_line_length: 0,
+                ..SqlFormatOptions::default()
+            },
+        ),
+        other => {
+            let name = format!("{other:?}").to_lowercase();
+            return Err(AstError::UnsupportedLanguage(name));
+        }
+    })
+}
+
+/// Line starts of a text, for converting many offsets without rescanning
+pub(super) struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub(super) fn new(text: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { starts }
+    }
+
+    pub(super) fn position(&self, offset: usize) -> AstPosition {
+        let line = self.starts.partition_point(|&start| start <= offset).max(1);
+        AstPosition::new(line, offset - self.starts[line - 1] + 1, offset)
+    }
+
+    /// `range`, known by line and column only, with its offsets filled in
+    pub(super) fn locate(&self, text: &str, range: AstRange) -> AstRange {
+        let position = |p: AstPosition| self.position(self.offset(text, p.line, p.column));
+        AstRange::new(position(range.start), position(range.end))
+    }
+
+    /// Byte offset of a 1-based line and character column
+    pub(super) fn offset(&self, text: &str, line: usize, column: usize) -> usize {
+        let Some(&start) = self.starts.get(line.saturating_sub(1)) else {
+            return text.len();
+        };
+        text[start..]
+            .char_indices()
+            .take_while(|&(_, c)| c != '\n')
+            .nth(column.saturating_sub(1))
+            .map_or_else(
+                || text[start..].find('\n').map_or(text.len(), |i| start + i),
+                |(i, _)| start + i,
+            )
+    }
+}
+
+/// Output buffer that records where each node ends up
+pub(super) struct Writer {
+    pub(super) out: String,
+    unit: String,
+    options: FormatOptions,
+    marks: Vec<(String, AstRange, usize, usize)>,
+}
+
+impl Writer {
+    pub(super) fn new(options: &FormatOptions) -> Self {
+        Self {
+            out: String::new(),
+            unit: if options.indent == 0 {
+                "\t".to_string()
+            } else {
+                " ".repeat(options.indent)
+            },
+            options: *options,
+            marks: Vec::new(),
+        }
+    }
+
+    pub(super) fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    fn indent(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.out.push_str(&self.unit);
+        }
+    }
+
+    /// Record that the node at `path` was written from `start` to here,
+    /// not counting a trailing newline.
+    pub(super) fn mark(&mut self, path: &str, original: AstRange, start: usize) {
+        let end = self.out.strip_suffix('\n').unwrap_or(&self.out).len();
+        self.marks
+            .push((path.to_string(), original, start, end.max(start)));
+    }
+
+    pub(super) fn finish(self) -> FormatResult {
+        let index = LineIndex::new(&self.out);
+        let mut marks = self.marks;
+        marks.sort_by_key(|&(_, _, start, end)| (start, std::cmp::Reverse(end)));
+        let source_map = marks
+            .into_iter()
+            .map(|(path, original, start, end)| SourceMapping {
+                path,
+                original,
+                formatted: AstRange::new(index.position(start), index.position(end)),
+            })
+            .collect();
+        FormatResult {
+            text: Some(self.out),
+            source_map,
+            errors: Vec::new(),
+        }
+    }
+}
+
+fn format_tree(
+    text: &str,
+    language: AstLanguage,
+    mut writer: Writer,
+    write: fn(&mut Writer, &AstNode),
+) -> FormatResult {
+    let parsed = parse_to_ast(text, language);
+    let valid = !parsed.has_errors();
+    let Some(root) = parsed.ast.filter(|_| valid) else {
+        return FormatResult::failure(parsed.errors);
+    };
+    write(&mut writer, &root);
+    writer.finish()
+}
+
+/// Children of an object node, sorted by key when requested
+fn properties<'n>(node: &'n AstNode, options: &FormatOptions) -> Vec<&'n AstNode> {
+    let mut children: Vec<_> = node.children.iter().flatten().collect();
+    if options.sort_keys {
+        children.sort_by(|a, b| a.label.cmp(&b.label));
+    }
+    children
+}
+
+/// Value of a property node: its child for containers, itself for
+/// scalars (the parsers fold scalar values into the property).
+pub(super) fn property_value(property: &AstNode) -> &AstNode {
+    match (&property.node_type, property.children.as_deref()) {
+        (AstNodeType::Property, Some([value])) => value,
+        _ => property,
+    }
+}
+
+fn is_empty_container(node: &AstNode) -> bool {
+    matches!(node.node_type, AstNodeType::Object | AstNodeType::Array)
+        && node.children.as_ref().is_none_or(Vec::is_empty)
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+fn write_json(writer: &mut Writer, root: &AstNode) {
+    write_json_node(writer, root, 0);
+    writer.push("\n");
+}
+
+fn write_json_node(writer: &mut Writer, node: &AstNode, depth: usize) {
+    let start = writer.out.len();
+    write_json_value(writer, node, depth);
+    writer.mark(&node.path, node.range, start);
+}
+
+fn write_json_value(writer: &mut Writer, node: &AstNode, depth: usize) {
+    let (open, close) = match node.node_type {
+        AstNodeType::Object => ("{", "}"),
+        AstNodeType::Array => ("[", "]"),
+        _ => {
+            let value = node.value.as_ref().unwrap_or(&serde_json::Value::Null);
+            writer.push(&value.to_string());
+            return;
+        }
+    };
+    if is_empty_container(node) {
+        writer.push(open);
+        writer.push(close);
+        return;
+    }
+    writer.push(open);
+    writer.push("\n");
+    let children = if node.node_type == AstNodeType::Object {
+        properties(node, &writer.options)
+    } else {
+        node.children.iter().flatten().collect()
+    };
+    for (i, child) in children.into_iter().enumerate() {
+        if i > 0 {
+            writer.push(",\n");
+        }
+        writer.indent(depth + 1);
+        if node.node_type == AstNodeType::Object {
+            let start = writer.out.len();
+            writer.push(&serde_json::Value::String(child.label.clone()).to_string());
+            writer.push(": ");
+            write_json_value(writer, property_value(child), depth + 1);
+            writer.mark(&child.path, child.range, start);
+        } else {
+            write_json_node(writer, child, depth + 1);
+        }
+    }
+    writer.push("\n");
+    writer.indent(depth);
+    writer.push(close);
+}
+
+// ============================================================================
+// YAML
+// ============================================================================
+
+fn write_yaml(writer: &mut Writer, root: &AstNode) {
+    let start = writer.out.len();
+    match root.node_type {
+        AstNodeType::Object | AstNodeType::Array if !is_empty_container(root) => {
+            write_yaml_entries(writer, root, 0, false);
+        }
+        _ => {
+            let scalar = yaml_scalar(root, writer.options.quote_style);
+            writer.push(&scalar);
+            writer.push("\n");
+        }
+    }
+    writer.mark(&root.path, root.range, start);
+}
+
+/// Write the entries of a non-empty mapping or sequence, one per line at
+/// `depth`. With `inline_first`, the first entry continues the current
+/// line (after a sequence dash).
+fn write_yaml_entries(writer: &mut Writer, node: &AstNode, depth: usize, inline_first: bool) {
+    let is_object = node.node_type == AstNodeType::Object;
+    let children = if is_object {
+        properties(node, &writer.options)
+    } else {
+        node.children.iter().flatten().collect()
+    };
+    for (i, child) in children.into_iter().enumerate() {
+        if i > 0 || !inline_first {
+            writer.indent(depth);
+        }
+        let start = writer.out.len();
+        let value = if is_object {
+            let key = yaml_string(&child.label, writer.options.quote_style);
+            writer.push(&key);
+            writer.push(":");
+            property_value(child)
+        } else {
+            writer.push("-");
+            child
+        };
+        match value.node_type {
+            AstNodeType::Object | AstNodeType::Array if !is_empty_container(value) => {
+                if is_object {
+                    writer.push("\n");
+                    write_yaml_entries(writer, value, depth + 1, false);
+                } else {
+                    let pad = " ".repeat(writer.options.indent - 1);
+                    writer.push(&pad);
+                    write_yaml_entries(writer, value, depth + 1, true);
+                }
+            }
+            _ => {
+                let block = value
+                    .value
+                    .as_ref()
+                    .and_then(|v| v.as_str())
+                    .and_then(block_scalar);
+                if let Some((chomp, lines)) = block {
+                    writer.push(" |");
+                    writer.push(chomp);
+                    writer.push("\n");
+                    for line in lines {
+                        if !line.is_empty() {
+                            writer.indent(depth + 1);
+                            writer.push(line);
+                        }
+                        writer.push("\n");
+                    }
+                } else {
+                    let scalar = yaml_scalar(value, writer.options.quote_style);
+                    writer.push(" ");
+                    writer.push(&scalar);
+                    writer.push("\n");
+                }
+            }
+        }
+        writer.mark(&child.path, child.range, start);
+    }
+}
+
+fn yaml_scalar(node: &AstNode, quote: QuoteStyle) -> String {
+    match (&node.node_type, &node.value) {
+        (AstNodeType::Object, _) => "{}".to_string(),
+        (AstNodeType::Array, _) => "[]".to_string(),
+        (_, Some(serde_json::Value::String(s))) => yaml_string(s, quote),
+        (_, Some(value)) => value.to_string(),
+        (_, None) => "null".to_string(),
+    }
+}
+
+/// Chomping indicator and lines of `s` as a literal block scalar, for
+/// multi-line strings that need no escapes
+pub(super) fn block_scalar(s: &str) -> Option<(&'static str, std::str::Split<'_, char>)> {
+    let printable = !s.chars().any(|c| c.is_control() && c != '\n' && c != '\t');
+    if !s.contains('\n') || !printable || s.starts_with([' ', '\n']) {
+        return None;
+    }
+    let (chomp, body) = match s.strip_suffix('\n') {
+        Some(body) if body.ends_with('\n') => ("+", body),
+        Some(body) => ("", body),
+        None => ("-", s),
+    };
+    Some((chomp, body.split('\n')))
+}
+
+/// `s` as a plain scalar when that reads back as the same string,
+/// otherwise quoted. Strings with control characters are always
+/// double-quoted, the only style with escapes.
+pub(super) fn yaml_string(s: &str, quote: QuoteStyle) -> String {
+    if is_plain_safe(s) {
+        return s.to_string();
+    }
+    if quote == QuoteStyle::Single && !s.chars().any(char::is_control) {
+        return format!("'{}'", s.replace('\'', "''"));
+    }
+    serde_json::Value::String(s.to_string()).to_string()
+}
+
+fn is_plain_safe(s: &str) -> bool {
+    // YAML 1.1 booleans are quoted too, for readers that still use them.
+    const RESERVED: [&str; 9] = ["true", "false", "yes", "no", "on", "off", "null", "~", ""];
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    s.trim() == s
+        && !"-?:,[]{}#&*!|>'\"%@`".contains(first)
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !s.chars().any(char::is_control)
+        && !RESERVED.contains(&s.to_ascii_lowercase().as_str())
+        && !looks_numeric(s)
+}
+
+fn looks_numeric(s: &str) -> bool {
+    let lower = s.to_ascii_lowercase();
+    let unsigned = lower.trim_start_matches(['+', '-']);
+    s.parse::<f64>().is_ok()
+        || unsigned.starts_with("0x")
+        || unsigned.starts_with("0o")
+        || matches!(unsigned, ".inf" | ".nan")
+}
+
+// ============================================================================
+// XML
+// ============================================================================
+
+fn format_xml(text: &str, options: &FormatOptions) -> FormatResult {
+    let doc = match Document::parse(text) {
+        Ok(doc) => doc,
+        Err(e) => return FormatResult::failure(vec![create_parse_error(&e)]),
+    };
+    let mut writer = Writer::new(options);
+    if let Some(declaration) = xml_declaration(text) {
+        writer.push(declaration);
+        writer.push("\n");
+    }
+    for child in doc.root().children() {
+        write_xml_node(&mut writer, text, child, 0);
+    }
+    writer.finish()
+}
+
+fn write_xml_node(writer: &mut Writer, text: &str, node: Node<'_, '_>, depth: usize) {
+    match node.node_type() {
+        NodeType::Element => write_xml_element(writer, text, node, depth),
+        NodeType::Comment | NodeType::PI => {
+            writer.indent(depth);
+            let start = writer.out.len();
+            writer.push(&text[node.range()]);
+            writer.mark(&node_path(node), node_range(&node), start);
+            writer.push("\n");
+        }
+        // Whitespace between elements; text-bearing content is copied
+        // with its element.
+        NodeType::Text | NodeType::Root => {}
+    }
+}
+
+fn write_xml_element(writer: &mut Writer, text: &str, node: Node<'_, '_>, depth: usize) {
+    let range = node.range();
+    writer.indent(depth);
+    let start = writer.out.len();
+    let Some(tag) = StartTag::scan(text, range.start) else {
+        writer.push(&text[range]);
+        writer.mark(&node_path(node), node_range(&node), start);
+        writer.push("\n");
+        return;
+    };
+
+    writer.push("<");
+    writer.push(tag.name);
+    let mut attributes = tag.attributes;
+    if writer.options.sort_keys {
+        attributes.sort_by_key(|&(name, _, _)| name);
+    }
+    for (name, value, quote) in attributes {
+        writer.push(" ");
+        writer.push(name);
+        writer.push("=");
+        let requoted = requote(value, quote, writer.options.quote_style);
+        writer.push(&requoted);
+    }
+
+    let has_text = node
+        .children()
+        .any(|c| c.is_text() && !c.text().unwrap_or("").trim().is_empty());
+    let has_nodes = node
+        .children()
+        .any(|c| c.is_element() || c.is_comment() || c.is_pi());
+    if has_text {
+        // Mixed and text content is whitespace-sensitive; keep it as is.
+        let close = text[..range.end].rfind("</").unwrap_or(range.end);
+        writer.push(">");
+        writer.push(&text[tag.end..close.max(tag.end)]);
+        writer.push("</");
+        writer.push(tag.name);
+        writer.push(">");
+    } else if has_nodes {
+        writer.push(">\n");
+        for child in node.children() {
+            write_xml_node(writer, text, child, depth + 1);
+        }
+        writer.indent(depth);
+        writer.push("</");
+        writer.push(tag.name);
+        writer.push(">");
+    } else {
+        writer.push("/>");
+    }
+    writer.mark(&node_path(node), node_range(&node), start);
+    writer.push("\n");
+}
+
+/// Attribute value re-quoted with `style`, escaping that quote inside
+fn requote(raw: &str, original: u8, style: QuoteStyle) -> String {
+    let (quote, escaped) = match style {
+        QuoteStyle::Double => ('"', "&quot;"),
+        QuoteStyle::Single => ('\'', "&apos;"),
+    };
+    if original == quote as u8 {
+        return format!("{quote}{raw}{quote}");
+    }
+    format!("{quote}{}{quote}", raw.replace(quote, escaped))
+}
+
+/// Start tag of an element, read from the source so prefixes and entity
+/// references in attribute values survive
+struct StartTag<'t> {
+    name: &'t str,
+    /// Raw name, raw value, and quote byte of each attribute
+    attributes: Vec<(&'t str, &'t str, u8)>,
+    /// Offset just past the closing `>`
+    end: usize,
+}
+
+impl<'t> StartTag<'t> {
+    fn scan(text: &'t str, start: usize) -> Option<Self> {
+        let bytes = text.as_bytes();
+        let is_space = |b: u8| b.is_ascii_whitespace();
+        let name_end = |from: usize| {
+            (from..bytes.len())
+                .find(|&i| is_space(bytes[i]) || matches!(bytes[i], b'/' | b'>' | b'='))
+                .unwrap_or(bytes.len())
+        };
+        let skip_space = |from: usize| {
+            (from..bytes.len())
+                .find(|&i| !is_space(bytes[i]))
+                .unwrap_or(bytes.len())
+        };
+
+        if bytes.get(start) != Some(&b'<') {
+            return None;
+        }
+        let end = name_end(start + 1);
+        let name = text.get(start + 1..end)?;
+        let mut attributes = Vec::new();
+        let mut pos = skip_space(end);
+        loop {
+            match *bytes.get(pos)? {
+                b'>' => break,
+                b'/' => {
+                    pos += 1;
+                    break;
+                }
+                _ => {
+                    let attr_end = name_end(pos);
+                    let attr_name = text.get(pos..attr_end)?;
+                    pos = skip_space(attr_end);
+                    if bytes.get(pos) != Some(&b'=') {
+                        return None;
+                    }
+                    pos = skip_space(pos + 1);
+                    let quote = *bytes.get(pos)?;
+                    let value_end = pos + 1 + text.get(pos + 1..)?.find(char::from(quote))?;
+                    attributes.push((attr_name, text.get(pos + 1..value_end)?, quote));
+                    pos = skip_space(value_end + 1);
+                }
+            }
+        }
+        (bytes.get(pos) == Some(&b'>')).then_some(Self {
+            name,
+            attributes,
+            end: pos + 1,
+        })
+    }
+}
+
+/// `<?xml ...?>`, which roxmltree does not keep as a node
+fn xml_declaration(text: &str) -> Option<&str> {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    let rest = text.strip_prefix("<?xml")?;
+    if !rest.starts_with(|c: char| c.is_ascii_whitespace()) {
+        return None;
+    }
+    Some(&text[..text.find("?>")? + 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(text: &str, language: AstLanguage, options: &FormatOptions) -> String {
+        let result = format_document(text, language, options).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        result.text.unwrap()
+    }
+
+    fn mapping<'r>(result: &'r FormatResult, path: &str) -> &'r SourceMapping {
+        result.source_map.iter().find(|m| m.path == path).unwrap()
+    }
+
+    #[test]
+    fn test_json_indent_and_sorting() {
+        let text = r#"{"b": [1, 2.5, {}], "a": {"x": "q\"", "y": null}, "c": []}"#;
+        let options = FormatOptions {
+            indent: 4,
+            sort_keys: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(text, AstLanguage::Json, &options),
+            "{\n    \"a\": {\n        \"x\": \"q\\\"\",\n        \"y\": null\n    },\n    \
+             \"b\": [\n        1,\n        2.5,\n        {}\n    ],\n    \"c\": []\n}\n"
+        );
+
+        let tabs = FormatOptions {
+            indent: 0,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format("[1]", AstLanguage::Json, &tabs), "[\n\t1\n]\n");
+    }
+
+    #[test]
+    fn test_json_source_map() {
+        let text = r#"{"name":"kogu","tags":["a"]}"#;
+        let result = format_document(text, AstLanguage::Json, &FormatOptions::default()).unwrap();
+        assert_eq!(
+            result.text.as_deref(),
+            Some("{\n  \"name\": \"kogu\",\n  \"tags\": [\n    \"a\"\n  ]\n}\n")
+        );
+
+        let name = mapping(&result, "$.name");
+        assert_eq!(name.original.start.offset, 1);
+        assert_eq!(
+            (name.formatted.start.line, name.formatted.start.column),
+            (2, 3)
+        );
+        assert_eq!(
+            (name.formatted.end.line, name.formatted.end.column),
+            (2, 17)
+        );
+
+        let item = mapping(&result, "$.tags[0]");
+        assert_eq!(
+            &text[item.original.start.offset..item.original.end.offset],
+            "\"a\""
+        );
+        assert_eq!(item.formatted.start.line, 4);
+
+        assert_eq!(result.source_map[0].path, "$");
+        assert_eq!(result.source_map[0].formatted.end.line, 6);
+    }
+
+    #[test]
+    fn test_json_parse_error_keeps_range() {
+        let result =
+            format_document("{\"a\": }", AstLanguage::Json, &FormatOptions::default()).unwrap();
+        assert!(result.text.is_none());
+        assert!(result.errors[0].range.is_some());
+    }
+
+    #[test]
+    fn test_yaml_block_style_and_quoting() {
+        let text =
+            "name: kogu\nlist:\n- 1\n- {a: true, b: 'x: y', c: yes}\n- []\nempty: {}\nnum: '42'\n";
+        assert_eq!(
+            format(text, AstLanguage::Yaml, &FormatOptions::default()),
+            "name: kogu\nlist:\n  - 1\n  - a: true\n    b: \"x: y\"\n    c: \"yes\"\n  - []\nempty: {}\nnum: \"42\"\n"
+        );
+
+        let options = FormatOptions {
+            indent: 4,
+            quote_style: QuoteStyle::Single,
+            sort_keys: true,
+        };
+        assert_eq!(
+            format(
+                "z: \"it's: x\"\na:\n  - [1, 2]\n",
+                AstLanguage::Yaml,
+                &options
+            ),
+            "a:\n    -   - 1\n        - 2\nz: 'it''s: x'\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_scalars_round_trip() {
+        let text = "a: \"true\"\nb: \"\"\nc: \"line\\nbreak\"\nd: 0x1F\ne: ~\nf: \"- x\"\ng: \"\\tx\\n\\n\"\n";
+        let formatted = format(text, AstLanguage::Yaml, &FormatOptions::default());
+        assert_eq!(
+            formatted,
+            "a: \"true\"\nb: \"\"\nc: |-\n  line\n  break\nd: 31\ne: null\nf: \"- x\"\ng: |+\n  \tx\n\n"
+        );
+        assert_eq!(format("", AstLanguage::Yaml, &FormatOptions::default()), "");
+    }
+
+    #[test]
+    fn test_xml_layout() {
+        let text = "<?xml version=\"1.0\"?>\n<!-- top --><root b='1' a=\"x&amp;y\">\
+                    <item  id='q\"'/><p>Hello <b>world</b></p><empty> </empty></root>";
+        let options = FormatOptions {
+            sort_keys: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(text, AstLanguage::Xml, &options),
+            "<?xml version=\"1.0\"?>\n<!-- top -->\n<root a=\"x&amp;y\" b=\"1\">\n  \
+             <item id=\"q&quot;\"/>\n  <p>Hello <b>world</b></p>\n  <empty/>\n</root>\n"
+        );
+    }
+
+    #[test]
+    fn test_xml_keeps_prefixes_and_maps_elements() {
+        let text = "<x:a xmlns:x=\"urn:x\"><x:b>1</x:b><x:b>2</x:b></x:a>";
+        let options = FormatOptions {
+            quote_style: QuoteStyle::Single,
+            ..FormatOptions::default()
+        };
+        let result = format_document(text, AstLanguage::Xml, &options).unwrap();
+        assert_eq!(
+            result.text.as_deref(),
+            Some("<x:a xmlns:x='urn:x'>\n  <x:b>1</x:b>\n  <x:b>2</x:b>\n</x:a>\n")
+        );
+        let second = mapping(&result, "$.x:a.x:b[1]");
+        assert_eq!(second.original.start.offset, 33);
+        assert_eq!(second.formatted.start.line, 3);
+    }
+
+    #[test]
+    fn test_sql_statements_are_mapped() {
+        let result = format_document(
+            "select a, b from t where a = 1; delete from t",
+            AstLanguage::Sql,
+            &FormatOptions::default(),
+        )
+        .unwrap();
+        let text = result.text.unwrap();
+        assert!(text.starts_with("SELECT"));
+        assert!(text.contains(";\n\nDELETE FROM\n  t;\n"));
+        assert_eq!(result.source_map[0].path, "$[0]");
+        assert!(result.source_map[1].original.start.offset >= 32);
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(matches!(
+            format_document("a = 1", AstLanguage::Toml, &FormatOptions::default()),
+            Err(AstError::UnsupportedLanguage(name)) if name == "toml"
+        ));
+    }
+}
@@ -0,0 +1,1059 @@
+//! Tolerant HTML AST parser with position tracking
+//!
+//! Follows the HTML5 tree construction rules that shape real-world
+//! markup: void elements, raw text in `<script>` and `<style>`, end tags
+//! implied by the next start tag (`<p>`, `<li>`, table rows and cells,
+//! `<option>`), implied `<tbody>` and `<tr>`, and case-insensitive names
+//! outside SVG and `MathML`. A document with a doctype or an `<html>`,
+//! `<head>`, or `<body>` tag gets the implied `html`/`head`/`body`
+//! structure; anything else is parsed as a fragment, as `innerHTML` is.
+//!
+//! Elements are labelled like CSS selectors (`<div#main.card>`), and
+//! their ranges run from the start tag to the end tag, or to their last
+//! content when the end tag is implied. Stray end tags and elements left
+//! open are reported alongside the complete tree.
+
+use std::collections::HashMap;
+
+use super::{AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+
+/// Elements nested deeper than this are added without children, keeping
+/// tree building within the call stack
+const MAX_NESTING: usize = 256;
+
+const VOID_ELEMENTS: [&str; 15] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param",
+    "source", "track", "wbr",
+];
+
+/// Elements whose content is text up to their end tag
+const RAW_TEXT_ELEMENTS: [&str; 8] = [
+    "script", "style", "textarea", "title", "xmp", "iframe", "noembed", "noframes",
+];
+
+/// Elements that may go in `<head>`
+const HEAD_ELEMENTS: [&str; 8] = [
+    "base", "link", "meta", "title", "style", "script", "noscript", "template",
+];
+
+/// Start tags that close an open `<p>`
+const CLOSES_P: [&str; 31] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Elements whose end tag may be left out
+const OPTIONAL_END: [&str; 18] = [
+    "html", "head", "body", "p", "li", "dt", "dd", "option", "optgroup", "tr", "td", "th", "thead",
+    "tbody", "tfoot", "colgroup", "rt", "rp",
+];
+
+const HEADINGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Elements an implied end tag does not look past
+const SCOPE_BOUNDARIES: [&str; 9] = [
+    "applet", "caption", "html", "table", "td", "th", "marquee", "object", "template",
+];
+
+/// Parse HTML text to AST with position information
+pub fn parse(text: &str) -> AstParseResult {
+    let mut parser = HtmlParser::new(text);
+    parser.run();
+    let ast = parser.root_node();
+    if parser.warnings.is_empty() {
+        AstParseResult::success(ast)
+    } else {
+        AstParseResult::partial(ast, parser.warnings)
+    }
+}
+
+struct Attribute {
+    name: String,
+    value: String,
+    start: usize,
+    end: usize,
+}
+
+enum Child {
+    Element(usize),
+    Text { start: usize, end: usize },
+    Comment { start: usize, end: usize },
+}
+
+struct Element {
+    name: String,
+    attributes: Vec<Attribute>,
+    /// Start of the start tag; for implied elements, of their first content
+    start: usize,
+    /// End of the start tag
+    tag_end: usize,
+    /// End of the end tag, when there is one
+    end: Option<usize>,
+    children: Vec<Child>,
+}
+
+/// Where content goes in a full document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    BeforeHead,
+    InHead,
+    AfterHead,
+    InBody,
+}
+
+struct HtmlParser<'a> {
+    text: &'a str,
+    lower: String,
+    pos: usize,
+    /// Element 0 is the document itself
+    elements: Vec<Element>,
+    open: Vec<usize>,
+    /// `None` for a fragment
+    phase: Option<Phase>,
+    html: Option<usize>,
+    head: Option<usize>,
+    body: Option<usize>,
+    /// Whether [`MAX_NESTING`] has been reported
+    flattened: bool,
+    warnings: Vec<AstParseError>,
+}
+
+impl<'a> HtmlParser<'a> {
+    fn new(text: &'a str) -> Self {
+        let lower = text.to_ascii_lowercase();
+        let document = ["html", "head", "body"]
+            .iter()
+            .any(|name| has_tag(&lower, name))
+            || lower.contains("<!doctype");
+        Self {
+            text,
+            lower,
+            pos: 0,
+            elements: vec![Element {
+                name: String::new(),
+                attributes: Vec::new(),
+                start: 0,
+                tag_end: 0,
+                end: Some(text.len()),
+                children: Vec::new(),
+            }],
+            open: vec![0],
+            phase: document.then_some(Phase::BeforeHead),
+            html: None,
+            head: None,
+            body: None,
+            flattened: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn range(&self, start: usize, end: usize) -> AstRange {
+        AstRange::from_offset(self.text, start, end)
+    }
+
+    fn warn(&mut self, start: usize, end: usize, message: String) {
+        let range = self.range(start, end);
+        self.warnings
+            .push(AstParseError::new(message).with_range(range));
+    }
+
+    fn current(&self) -> usize {
+        self.open.last().copied().unwrap_or(0)
+    }
+
+    fn current_name(&self) -> &str {
+        &self.elements[self.current()].name
+    }
+
+    /// Whether an open `<svg>` or `<math>` makes names case-sensitive
+    fn in_foreign(&self) -> bool {
+        self.open
+            .iter()
+            .any(|&index| matches!(self.elements[index].name.as_str(), "svg" | "math"))
+    }
+
+    fn run(&mut self) {
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len() {
+            let rest = &self.lower[self.pos..];
+            let next = bytes.get(self.pos + 1).copied().unwrap_or(0);
+            if rest.starts_with("<!--") {
+                self.comment();
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                // Doctype, CDATA, or processing instruction: skipped.
+                self.pos = self.lower[self.pos..]
+                    .find('>')
+                    .map_or(bytes.len(), |i| self.pos + i + 1);
+            } else if rest.starts_with("</")
+                && bytes.get(self.pos + 2).is_some_and(u8::is_ascii_alphabetic)
+            {
+                self.end_tag();
+            } else if bytes[self.pos] == b'<' && next.is_ascii_alphabetic() {
+                self.start_tag();
+            } else {
+                let from = self.pos
+                    + self.text[self.pos..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8);
+                let end = self.lower[from..]
+                    .find('<')
+                    .map_or(bytes.len(), |i| from + i);
+                self.text_content(self.pos, end);
+                self.pos = end;
+            }
+        }
+
+        for &index in self.open.iter().skip(1) {
+            let element = &self.elements[index];
+            if element.end.is_none() && !OPTIONAL_END.contains(&element.name.as_str()) {
+                let (start, end, name) = (element.start, element.tag_end, element.name.clone());
+                self.warnings.push(
+                    AstParseError::new(format!("Unclosed element <{name}>"))
+                        .with_range(AstRange::from_offset(self.text, start, end)),
+                );
+            }
+        }
+    }
+
+    fn comment(&mut self) {
+        let start = self.pos;
+        let end = self.lower[start + 4..]
+            .find("-->")
+            .map_or(self.text.len(), |i| start + 4 + i + 3);
+        self.pos = end;
+        let parent = self.current();
+        self.elements[parent]
+            .children
+            .push(Child::Comment { start, end });
+    }
+
+    fn text_content(&mut self, start: usize, end: usize) {
+        let blank = self.text[start..end].trim().is_empty();
+        match self.phase {
+            Some(Phase::InBody) | None => {}
+            // Whitespace between head elements is not content.
+            Some(_) if blank => return,
+            Some(_) => self.ensure_body(start),
+        }
+        let parent = self.current();
+        self.elements[parent]
+            .children
+            .push(Child::Text { start, end });
+    }
+
+    /// Name at `self.pos`, lowercased outside SVG and `MathML`
+    fn tag_name(&mut self) -> String {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        while self.pos < bytes.len()
+            && !bytes[self.pos].is_ascii_whitespace()
+            && !matches!(bytes[self.pos], b'/' | b'>')
+        {
+            self.pos += 1;
+        }
+        if self.in_foreign() {
+            self.text[start..self.pos].to_string()
+        } else {
+            self.lower[start..self.pos].to_string()
+        }
+    }
+
+    /// Attributes up to the end of a start tag, and whether it ends `/>`
+    fn attributes(&mut self) -> (Vec<Attribute>, bool) {
+        let bytes = self.text.as_bytes();
+        let mut attributes: Vec<Attribute> = Vec::new();
+        loop {
+            while self.pos < bytes.len()
+                && (bytes[self.pos].is_ascii_whitespace() || bytes[self.pos] == b'/')
+            {
+                if bytes[self.pos] == b'/' && bytes.get(self.pos + 1) == Some(&b'>') {
+                    self.pos += 2;
+                    return (attributes, true);
+                }
+                self.pos += 1;
+            }
+            if self.pos >= bytes.len() {
+                return (attributes, false);
+            }
+            if bytes[self.pos] == b'>' {
+                self.pos += 1;
+                return (attributes, false);
+            }
+
+            let start = self.pos;
+            self.pos += 1;
+            while self.pos < bytes.len()
+                && !bytes[self.pos].is_ascii_whitespace()
+                && !matches!(bytes[self.pos], b'/' | b'>' | b'=')
+            {
+                self.pos += 1;
+            }
+            let name = self.lower[start..self.pos].to_string();
+            let mut end = self.pos;
+            let mut value = String::new();
+
+            let mut after = self.pos;
+            while after < bytes.len() && bytes[after].is_ascii_whitespace() {
+                after += 1;
+            }
+            if bytes.get(after) == Some(&b'=') {
+                self.pos = after + 1;
+                while self.pos < bytes.len() && bytes[self.pos].is_ascii_whitespace() {
+                    self.pos += 1;
+                }
+                if let Some(&quote @ (b'"' | b'\'')) = bytes.get(self.pos) {
+                    let close = self.text[self.pos + 1..]
+                        .find(char::from(quote))
+                        .map_or(bytes.len(), |i| self.pos + 1 + i);
+                    value = decode(&self.text[self.pos + 1..close]);
+                    self.pos = (close + 1).min(bytes.len());
+                } else {
+                    let value_start = self.pos;
+                    while self.pos < bytes.len()
+                        && !bytes[self.pos].is_ascii_whitespace()
+                        && bytes[self.pos] != b'>'
+                    {
+                        self.pos += 1;
+                    }
+                    value = decode(&self.text[value_start..self.pos]);
+                }
+                end = self.pos;
+            }
+            // The first of duplicate attributes wins.
+            if !attributes.iter().any(|attribute| attribute.name == name) {
+                attributes.push(Attribute {
+                    name,
+                    value,
+                    start,
+                    end,
+                });
+            }
+        }
+    }
+
+    fn start_tag(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        let name = self.tag_name();
+        let (attributes, self_closing) = self.attributes();
+        let tag_end = self.pos;
+
+        if self.phase.is_some() && self.document_tag(&name, &attributes, start, tag_end) {
+            return;
+        }
+
+        let foreign = self.in_foreign();
+        if !foreign {
+            self.close_implied(&name, start);
+        }
+        let parent = self.current();
+        let index = self.elements.len();
+        self.elements.push(Element {
+            name: name.clone(),
+            attributes,
+            start,
+            tag_end,
+            end: None,
+            children: Vec::new(),
+        });
+        self.elements[parent].children.push(Child::Element(index));
+
+        let childless =
+            (!foreign && VOID_ELEMENTS.contains(&name.as_str())) || (foreign && self_closing);
+        if childless {
+            self.elements[index].end = Some(tag_end);
+            return;
+        }
+        if self.open.len() >= MAX_NESTING {
+            if !self.flattened {
+                self.flattened = true;
+                self.warn(
+                    start,
+                    tag_end,
+                    format!(
+                    "Elements nested deeper than {MAX_NESTING} levels are shown without children"
+                ),
+                );
+            }
+            self.elements[index].end = Some(tag_end);
+            return;
+        }
+        self.open.push(index);
+
+        if !foreign && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let close = format!("</{name}");
+            let end = self.lower[self.pos..]
+                .match_indices(&close)
+                .map(|(i, _)| self.pos + i)
+                .find(|&i| {
+                    self.text
+                        .as_bytes()
+                        .get(i + close.len())
+                        .is_none_or(|&b| b.is_ascii_whitespace() || matches!(b, b'/' | b'>'))
+                })
+                .unwrap_or(self.text.len());
+            if end > self.pos {
+                self.elements[index].children.push(Child::Text {
+                    start: self.pos,
+                    end,
+                });
+            }
+            self.pos = end;
+        }
+    }
+
+    /// Start tags that shape a full document: `html`, `head`, `body`, and
+    /// head content before the body. Returns whether the tag was handled.
+    fn document_tag(
+        &mut self,
+        name: &str,
+        attributes: &[Attribute],
+        start: usize,
+        tag_end: usize,
+    ) -> bool {
+        let phase = self.phase.unwrap_or(Phase::InBody);
+        match name {
+            "html" if self.html.is_none() => {
+                let html = self.implied("html", start);
+                self.set_tag(html, attributes, start, tag_end);
+                true
+            }
+            "head" if self.head.is_none() && phase != Phase::InBody => {
+                self.ensure_html(start);
+                let head = self.implied("head", start);
+                self.set_tag(head, attributes, start, tag_end);
+                self.head = Some(head);
+                self.phase = Some(Phase::InHead);
+                true
+            }
+            "body" if self.body.is_none() => {
+                self.ensure_body(start);
+                if let Some(body) = self.body {
+                    self.set_tag(body, attributes, start, tag_end);
+                }
+                true
+            }
+            "html" | "head" | "body" => {
+                self.warn(start, tag_end, format!("Unexpected <{name}>"));
+                true
+            }
+            _ if phase != Phase::InBody && HEAD_ELEMENTS.contains(&name) => {
+                if self.head.is_none() {
+                    self.ensure_html(start);
+                    self.head = Some(self.implied("head", start));
+                    self.phase = Some(Phase::InHead);
+                } else if phase == Phase::AfterHead {
+                    // Late head content goes back into the closed head.
+                    if let Some(head) = self.head {
+                        self.open.push(head);
+                    }
+                }
+                false
+            }
+            _ => {
+                if phase != Phase::InBody {
+                    self.ensure_body(start);
+                }
+                false
+            }
+        }
+    }
+
+    fn set_tag(&mut self, index: usize, attributes: &[Attribute], start: usize, tag_end: usize) {
+        let element = &mut self.elements[index];
+        element.start = start;
+        element.tag_end = tag_end;
+        element.attributes = attributes
+            .iter()
+            .map(|a| Attribute {
+                name: a.name.clone(),
+                value: a.value.clone(),
+                start: a.start,
+                end: a.end,
+            })
+            .collect();
+    }
+
+    /// Open a new `name` element under the current one, without a tag
+    fn implied(&mut self, name: &str, at: usize) -> usize {
+        let parent = self.current();
+        let index = self.elements.len();
+        self.elements.push(Element {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            start: at,
+            tag_end: at,
+            end: None,
+            children: Vec::new(),
+        });
+        self.elements[parent].children.push(Child::Element(index));
+        self.open.push(index);
+        if name == "html" {
+            self.html = Some(index);
+        }
+        index
+    }
+
+    fn ensure_html(&mut self, at: usize) {
+        if self.html.is_none() {
+            self.implied("html", at);
+        }
+    }
+
+    /// Close the head and open the body, implying whatever is missing
+    fn ensure_body(&mut self, at: usize) {
+        if self.body.is_some() {
+            return;
+        }
+        self.ensure_html(at);
+        if self.head.is_none() {
+            let head = self.implied("head", at);
+            self.head = Some(head);
+        }
+        if let Some(head) = self.head {
+            if let Some(position) = self.open.iter().position(|&index| index == head) {
+                self.open.truncate(position);
+            }
+        }
+        self.body = Some(self.implied("body", at));
+        self.phase = Some(Phase::InBody);
+    }
+
+    /// Close elements a `name` start tag ends, and open the table
+    /// sections and rows it implies
+    fn close_implied(&mut self, name: &str, at: usize) {
+        if CLOSES_P.contains(&name) {
+            self.close_in_scope(&["p"], &["button"]);
+        }
+        match name {
+            "li" => self.close_in_scope(&["li"], &["ul", "ol"]),
+            "dt" | "dd" => self.close_in_scope(&["dt", "dd"], &["dl"]),
+            "option" => self.close_current(&["option"]),
+            "optgroup" => {
+                self.close_current(&["option"]);
+                self.close_current(&["optgroup"]);
+            }
+            "rt" | "rp" => self.close_in_scope(&["rt", "rp"], &["ruby"]),
+            "a" => self.close_in_scope(&["a"], &[]),
+            "button" => self.close_in_scope(&["button"], &[]),
+            _ if HEADINGS.contains(&name) => self.close_current(&HEADINGS),
+            "thead" | "tbody" | "tfoot" => {
+                self.close_in_scope(&["td", "th"], &["tr"]);
+                self.close_in_scope(&["tr"], &["thead", "tbody", "tfoot"]);
+                self.close_in_scope(&["thead", "tbody", "tfoot"], &[]);
+            }
+            "tr" => {
+                self.close_in_scope(&["td", "th"], &["tr"]);
+                self.close_in_scope(&["tr"], &["thead", "tbody", "tfoot"]);
+                if self.current_name() == "table" {
+                    self.implied("tbody", at);
+                }
+            }
+            "td" | "th" => {
+                self.close_in_scope(&["td", "th"], &["tr"]);
+                if self.current_name() == "table" {
+                    self.implied("tbody", at);
+                }
+                if matches!(self.current_name(), "tbody" | "thead" | "tfoot") {
+                    self.implied("tr", at);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Close the current element if it is one of `names`
+    fn close_current(&mut self, names: &[&str]) {
+        if names.contains(&self.current_name()) && self.open.len() > 1 {
+            self.close_to(self.open.len() - 1, None);
+        }
+    }
+
+    /// Close the innermost open element named in `names`, and everything
+    /// inside it, unless one of `boundaries` or a scope boundary comes
+    /// first
+    fn close_in_scope(&mut self, names: &[&str], boundaries: &[&str]) {
+        for position in (1..self.open.len()).rev() {
+            let name = self.elements[self.open[position]].name.as_str();
+            if names.contains(&name) {
+                self.close_to(position, None);
+                return;
+            }
+            if boundaries.contains(&name) || SCOPE_BOUNDARIES.contains(&name) {
+                return;
+            }
+        }
+    }
+
+    /// Pop the open elements from `position` up. The one at `position`
+    /// ends at `end` when its end tag was found; those above it were left
+    /// open, which is reported unless their end tag is optional.
+    fn close_to(&mut self, position: usize, end: Option<usize>) {
+        let closed = self.open.split_off(position);
+        for (i, &index) in closed.iter().enumerate() {
+            if i == 0 {
+                self.elements[index].end = end;
+                continue;
+            }
+            let element = &self.elements[index];
+            if end.is_some() && !OPTIONAL_END.contains(&element.name.as_str()) {
+                let message = format!("Unclosed element <{}>", element.name);
+                let (start, tag_end) = (element.start, element.tag_end);
+                self.warn(start, tag_end, message);
+            }
+        }
+    }
+
+    fn end_tag(&mut self) {
+        let start = self.pos;
+        self.pos += 2;
+        let name = self.tag_name().to_ascii_lowercase();
+        let end = self.lower[self.pos..]
+            .find('>')
+            .map_or(self.text.len(), |i| self.pos + i + 1);
+        self.pos = end;
+
+        if self.phase.is_some() {
+            match name.as_str() {
+                "head" => {
+                    if let Some(head) = self.head {
+                        if let Some(position) = self.open.iter().position(|&index| index == head) {
+                            self.close_to(position, Some(end));
+                            self.phase = Some(Phase::AfterHead);
+                            return;
+                        }
+                    }
+                }
+                "body" | "html" => {
+                    // Content after these still belongs to the body.
+                    let index = if name == "body" { self.body } else { self.html };
+                    if let Some(index) = index {
+                        self.elements[index].end = Some(end);
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let found = (1..self.open.len()).rev().find(|&position| {
+            self.elements[self.open[position]]
+                .name
+                .eq_ignore_ascii_case(&name)
+        });
+        let protected = [self.html, self.head, self.body];
+        match found {
+            Some(position) if !protected.contains(&Some(self.open[position])) || name == "head" => {
+                self.close_to(position, Some(end));
+                // Head content reopened the head after it was closed.
+                if self.phase == Some(Phase::AfterHead) {
+                    if let Some(head) = self.head {
+                        if let Some(position) = self.open.iter().position(|&index| index == head) {
+                            self.open.truncate(position);
+                        }
+                    }
+                }
+            }
+            _ => self.warn(start, end, format!("Unexpected end tag </{name}>")),
+        }
+    }
+
+    fn root_node(&self) -> AstNode {
+        let kind = if self.phase.is_some() {
+            "document"
+        } else {
+            "fragment"
+        };
+        let count = self.elements.len() - 1;
+        let children = self.child_nodes(0, "$");
+        AstNode::new(
+            AstNodeType::Root,
+            "$".to_string(),
+            format!("HTML {kind} ({count} elements)"),
+            self.range(0, self.text.len()),
+        )
+        .with_children(children)
+    }
+
+    fn child_nodes(&self, index: usize, path: &str) -> Vec<AstNode> {
+        let element = &self.elements[index];
+        let mut totals: HashMap<&str, usize> = HashMap::new();
+        for child in &element.children {
+            if let Child::Element(child) = child {
+                *totals
+                    .entry(self.elements[*child].name.as_str())
+                    .or_insert(0) += 1;
+            }
+        }
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        element
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                Child::Element(child) => {
+                    let name = self.elements[*child].name.as_str();
+                    let count = seen.entry(name).or_insert(0);
+                    let child_path = if totals.get(name).is_some_and(|&total| total > 1) {
+                        format!("{path}.{name}[{count}]")
+                    } else {
+                        format!("{path}.{name}")
+                    };
+                    *count += 1;
+                    Some(self.element_node(*child, &child_path))
+                }
+                Child::Text { start, end } => self.text_node(*start, *end, path),
+                Child::Comment { start, end } => Some(self.comment_node(*start, *end, path)),
+            })
+            .collect()
+    }
+
+    fn element_node(&self, index: usize, path: &str) -> AstNode {
+        let element = &self.elements[index];
+        let mut children: Vec<AstNode> = element
+            .attributes
+            .iter()
+            .map(|attribute| {
+                AstNode::new(
+                    AstNodeType::Attribute,
+                    format!("{path}[@{}]", attribute.name),
+                    format!("@{}", attribute.name),
+                    self.range(attribute.start, attribute.end),
+                )
+                .with_value(serde_json::Value::String(attribute.value.clone()))
+            })
+            .collect();
+        let attr_count = children.len();
+        children.extend(self.child_nodes(index, path));
+        let element_count = children
+            .iter()
+            .filter(|child| child.node_type == AstNodeType::Element)
+            .count();
+
+        let end = element.end.unwrap_or_else(|| {
+            children
+                .iter()
+                .map(|child| child.range.end.offset)
+                .max()
+                .unwrap_or(element.tag_end)
+                .max(element.tag_end)
+        });
+        let selector = selector(element);
+        let label = if attr_count > 0 {
+            format!("<{selector}> ({attr_count} attrs, {element_count} children)")
+        } else if element_count > 0 {
+            format!("<{selector}> ({element_count} children)")
+        } else {
+            format!("<{selector}>")
+        };
+        AstNode::new(
+            AstNodeType::Element,
+            path.to_string(),
+            label,
+            self.range(element.start, end),
+        )
+        .with_children(children)
+    }
+
+    fn text_node(&self, start: usize, end: usize, path: &str) -> Option<AstNode> {
+        let raw = &self.text[start..end];
+        let content = raw.trim();
+        if content.is_empty() {
+            return None;
+        }
+        let start = start + (raw.len() - raw.trim_start().len());
+        let value = decode(content);
+        let label = if value.chars().count() > 50 {
+            format!("\"{}...\"", value.chars().take(47).collect::<String>())
+        } else {
+            format!("\"{value}\"")
+        };
+        Some(
+            AstNode::new(
+                AstNodeType::Text,
+                format!("{path}/#text"),
+                label,
+                self.range(start, start + content.len()),
+            )
+            .with_value(serde_json::Value::String(value)),
+        )
+    }
+
+    fn comment_node(&self, start: usize, end: usize, path: &str) -> AstNode {
+        let body = self.text[start + 4..end]
+            .strip_suffix("-->")
+            .unwrap_or_else(|| &self.text[start + 4..end])
+            .trim();
+        let label = if body.chars().count() > 50 {
+            format!("<!-- {}... -->", body.chars().take(47).collect::<String>())
+        } else {
+            format!("<!-- {body} -->")
+        };
+        AstNode::new(
+            AstNodeType::Comment,
+            format!("{path}/#comment"),
+            label,
+            self.range(start, end),
+        )
+    }
+}
+
+/// `div#main.card.wide` for `<div id="main" class="card wide">`
+fn selector(element: &Element) -> String {
+    let mut selector = element.name.clone();
+    let value = |name: &str| {
+        element
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    };
+    if let Some(id) = value("id").filter(|id| !id.trim().is_empty()) {
+        selector.push('#');
+        selector.push_str(id.trim());
+    }
+    for class in value("class").unwrap_or_default().split_whitespace() {
+        selector.push('.');
+        selector.push_str(class);
+    }
+    selector
+}
+
+/// Whether `lower` has a `<name>` start tag
+fn has_tag(lower: &str, name: &str) -> bool {
+    let open = format!("<{name}");
+    lower.match_indices(&open).any(|(i, _)| {
+        lower
+            .as_bytes()
+            .get(i + open.len())
+            .is_none_or(|&b| b.is_ascii_whitespace() || matches!(b, b'/' | b'>'))
+    })
+}
+
+/// Text with character references replaced; unknown ones are kept
+fn decode(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&semi| semi <= 32)
+            .and_then(|semi| Some((character_reference(&rest[1..=semi])?, semi + 2)));
+        if let Some((c, length)) = decoded {
+            out.push(c);
+            rest = &rest[length..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Character for `&name;` or `&#code;`, without the `&` and `;`
+fn character_reference(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = number.strip_prefix(['x', 'X']).map_or_else(
+            || number.parse().ok(),
+            |hex| u32::from_str_radix(hex, 16).ok(),
+        );
+        return code.and_then(char::from_u32);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "euro" => '€',
+        "times" => '×',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    fn elements(node: &AstNode) -> Vec<&str> {
+        children(node)
+            .iter()
+            .filter(|child| child.node_type == AstNodeType::Element)
+            .map(|child| child.label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_fragment_with_selectors_and_ranges() {
+        let html = "<div id=\"main\" class=\"card wide\">\n  <p>Hello &amp; welcome<br>\n  <img src=logo.png alt=''>\n</div>\n<!-- end -->";
+        let result = parse(html);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "HTML fragment (4 elements)");
+
+        let div = &children(&ast)[0];
+        assert_eq!(div.path, "$.div");
+        assert_eq!(div.label, "<div#main.card.wide> (2 attrs, 1 children)");
+        assert_eq!(
+            (
+                div.range.start.line,
+                div.range.end.line,
+                div.range.end.column
+            ),
+            (1, 4, 7)
+        );
+        assert_eq!(children(div)[1].value, Some(serde_json::json!("card wide")));
+
+        // The </div> end tag implies the end of the open <p>.
+        let p = &children(div)[2];
+        assert_eq!(p.label, "<p> (2 children)");
+        assert_eq!(p.range.end.line, 3);
+        assert_eq!(
+            children(p)[0].value,
+            Some(serde_json::json!("Hello & welcome"))
+        );
+        let img = &children(p)[2];
+        assert_eq!(img.path, "$.div.p.img");
+        assert_eq!(children(img)[0].value, Some(serde_json::json!("logo.png")));
+        assert_eq!(children(&ast)[1].label, "<!-- end -->");
+    }
+
+    #[test]
+    fn test_implied_end_tags() {
+        let html = "<ul><li>One<li>Two</ul><p>a<p>b<div>c</div><table><tr><td>1<td>2<tr><td>3</table><select><option>x<option>y</select>";
+        let result = parse(html);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let ast = result.ast.unwrap();
+        assert_eq!(
+            elements(&ast),
+            [
+                "<ul> (2 children)",
+                "<p>",
+                "<p>",
+                "<div>",
+                "<table> (1 children)",
+                "<select> (2 children)"
+            ]
+        );
+        let nodes = children(&ast);
+        assert_eq!(children(&nodes[0])[1].path, "$.ul.li[1]");
+        assert_eq!(nodes[2].path, "$.p[1]");
+
+        let tbody = &children(&nodes[4])[0];
+        assert_eq!(tbody.path, "$.table.tbody");
+        assert_eq!(elements(tbody), ["<tr> (2 children)", "<tr> (1 children)"]);
+        assert_eq!(
+            children(&children(tbody)[0])[1].path,
+            "$.table.tbody.tr[0].td[1]"
+        );
+    }
+
+    #[test]
+    fn test_document_structure_and_raw_text() {
+        let html = "<!DOCTYPE html>\n<title>Demo</title>\n<meta charset=utf-8>\n<script>if (a < b) { x = '</div>'; }</script>\n<h1>Title</h1>\n</body>\n";
+        let result = parse(html);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "HTML document (7 elements)");
+
+        let html_element = &children(&ast)[0];
+        assert_eq!(html_element.path, "$.html");
+        assert_eq!(
+            elements(html_element),
+            ["<head> (3 children)", "<body> (1 children)"]
+        );
+        let head = &children(html_element)[0];
+        let script = &children(head)[2];
+        assert_eq!(script.path, "$.html.head.script");
+        assert_eq!(
+            children(script)[0].value,
+            Some(serde_json::json!("if (a < b) { x = '</div>'; }"))
+        );
+        let body = &children(html_element)[1];
+        assert_eq!(children(body)[0].path, "$.html.body.h1");
+        assert_eq!(body.range.end.line, 6);
+    }
+
+    #[test]
+    fn test_svg_keeps_case_and_self_closing() {
+        let html = "<svg viewBox=\"0 0 10 10\"><linearGradient id=g /><circle r=1 /></svg><P>Text";
+        let ast = parse(html).ast.unwrap();
+        let svg = &children(&ast)[0];
+        assert_eq!(
+            elements(svg),
+            [
+                "<linearGradient#g> (1 attrs, 0 children)",
+                "<circle> (1 attrs, 0 children)"
+            ]
+        );
+        assert_eq!(children(&ast)[1].path, "$.p");
+    }
+
+    #[test]
+    fn test_stray_and_unclosed_tags_are_reported() {
+        let result = parse("<div><span>text</div></em><section>open");
+        let messages: Vec<&str> = result.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Unclosed element <span>",
+                "Unexpected end tag </em>",
+                "Unclosed element <section>",
+            ]
+        );
+        let span = result.errors[0].range.unwrap();
+        assert_eq!((span.start.column, span.end.column), (6, 12));
+        let ast = result.ast.unwrap();
+        assert_eq!(elements(&ast), ["<div> (1 children)", "<section>"]);
+    }
+}
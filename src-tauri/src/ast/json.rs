@@ -1,28 +1,152 @@
 //! JSON AST parser with position tracking
+//!
+//! The parser recovers from errors inside objects and arrays by skipping
+//! to the next `,` or closing bracket, so a document being edited still
+//! yields a partial tree alongside every error found.
+
+use tokio_util::sync::CancellationToken;
+
+use super::parallel::{self, ItemSpan, PARALLEL_THRESHOLD};
+use super::{
+    offset_to_position, AstNode, AstNodeType, AstParseError, AstParseResult, AstPosition, AstRange,
+};
+
+/// Parse JSON text to AST with position information. Once `cancel` is
+/// cancelled every open container ends at its next item, so the parse
+/// returns promptly with a partial tree the caller discards.
+pub fn parse(text: &str, cancel: Option<&CancellationToken>) -> AstParseResult {
+    if text.len() >= PARALLEL_THRESHOLD {
+        if let Some(ast) = parse_array_parallel(text, cancel) {
+            return AstParseResult::success(ast);
+        }
+    }
+    let mut parser = JsonParser::new(text, cancel);
+    match parser.parse_value("$") {
+        Ok(ast) => {
+            parser.skip_whitespace();
+            if parser.current().is_some() {
+                let error = parser.error("Unexpected content after the document");
+                parser.errors.push(error);
+            }
+            if parser.errors.is_empty() {
+                AstParseResult::success(ast)
+            } else {
+                AstParseResult::partial(ast, parser.errors)
+            }
+        }
+        Err(e) => {
+            parser.errors.push(e);
+            AstParseResult::failure(parser.errors)
+        }
+    }
+}
 
-use super::{offset_to_position, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+/// Top-level array parsed one item per task on the rayon pool. `None`
+/// when the document is not an array or an item has errors, leaving the
+/// sequential parser to recover from and report them.
+fn parse_array_parallel(text: &str, cancel: Option<&CancellationToken>) -> Option<AstNode> {
+    let (open, spans, close) = split_array(text)?;
+    let children = parallel::build_items(&spans, cancel, |index, span| {
+        let mut parser = JsonParser::new(&text[span.start.offset..span.end], None);
+        let mut item = parser.parse_value(&format!("$[{index}]")).ok()?;
+        parser.skip_whitespace();
+        if parser.current().is_some() || !parser.errors.is_empty() {
+            return None;
+        }
+        parallel::shift_node(&mut item, span.start);
+        Some(item)
+    })?;
 
-/// Parse JSON text to AST with position information
-pub fn parse(text: &str) -> AstParseResult {
-    let mut parser = JsonParser::new(text);
-    match parser.parse_value("$") {
-        Ok(ast) => AstParseResult::success(ast),
-        Err(e) => AstParseResult::failure(vec![e]),
+    let range = AstRange::from_offset(text, open, close);
+    let label = format!("[] ({} items)", children.len());
+    Some(AstNode::new(AstNodeType::Array, "$".to_string(), label, range).with_children(children))
+}
+
+/// Spans of the items of a top-level array, plus the offsets of its `[`
+/// and just past its `]`. Only strings and brackets are followed, so the
+/// items themselves may still be invalid. `None` unless the document is
+/// a non-empty array with balanced brackets and nothing after it.
+fn split_array(text: &str) -> Option<(usize, Vec<ItemSpan>, usize)> {
+    let bytes = text.as_bytes();
+    let is_space = |byte: &u8| matches!(byte, b' ' | b'\t' | b'\n' | b'\r');
+    let open = bytes.iter().position(|byte| !is_space(byte))?;
+    if bytes[open] != b'[' {
+        return None;
     }
+
+    let bracket = offset_to_position(text, open);
+    let mut line = bracket.line;
+    let mut line_start = open + 1 - bracket.column;
+    let mut spans = Vec::new();
+    let mut item_start: Option<AstPosition> = None;
+    let mut item_end = 0;
+    let mut nested: Vec<u8> = Vec::new();
+    let mut i = open + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => {
+                line += 1;
+                line_start = i + 1;
+            }
+            b' ' | b'\t' | b'\r' => {}
+            byte @ (b',' | b']') if nested.is_empty() => {
+                spans.push(ItemSpan {
+                    start: item_start.take()?,
+                    end: item_end,
+                });
+                if byte == b']' {
+                    return bytes[i + 1..]
+                        .iter()
+                        .all(is_space)
+                        .then_some((open, spans, i + 1));
+                }
+            }
+            byte => {
+                item_start.get_or_insert_with(|| AstPosition::new(line, i - line_start + 1, i));
+                match byte {
+                    b'"' => loop {
+                        i += 1;
+                        match bytes.get(i)? {
+                            b'"' => break,
+                            b'\\' => i += 1,
+                            b'\n' => {
+                                line += 1;
+                                line_start = i + 1;
+                            }
+                            _ => {}
+                        }
+                    },
+                    b'{' => nested.push(b'}'),
+                    b'[' => nested.push(b']'),
+                    b'}' | b']' if nested.pop()? != byte => return None,
+                    _ => {}
+                }
+                item_end = i + 1;
+            }
+        }
+        i += 1;
+    }
+    None
 }
 
 struct JsonParser<'a> {
     text: &'a str,
     chars: Vec<char>,
     pos: usize,
+    /// Errors recovered from so far
+    errors: Vec<AstParseError>,
+    /// Checked before each item of a container
+    cancel: Option<&'a CancellationToken>,
 }
 
 impl<'a> JsonParser<'a> {
-    fn new(text: &'a str) -> Self {
+    fn new(text: &'a str, cancel: Option<&'a CancellationToken>) -> Self {
         Self {
             text,
             chars: text.chars().collect(),
             pos: 0,
+            errors: Vec::new(),
+            cancel,
         }
     }
 
@@ -61,8 +185,8 @@ impl<'a> JsonParser<'a> {
         let start_offset = self.byte_offset();
 
         match self.current() {
-            Some('{') => self.parse_object(path, start_offset),
-            Some('[') => self.parse_array(path, start_offset),
+            Some('{') => Ok(self.parse_object(path, start_offset)),
+            Some('[') => Ok(self.parse_array(path, start_offset)),
             Some('"') => self.parse_string(path, start_offset),
             Some('t' | 'f') => self.parse_boolean(path, start_offset),
             Some('n') => self.parse_null(path, start_offset),
@@ -72,119 +196,176 @@ impl<'a> JsonParser<'a> {
         }
     }
 
-    fn parse_object(&mut self, path: &str, start_offset: usize) -> Result<AstNode, AstParseError> {
+    fn parse_object(&mut self, path: &str, start_offset: usize) -> AstNode {
         self.advance(); // consume '{'
         self.skip_whitespace();
 
         let mut children = Vec::new();
 
-        if self.current() != Some('}') {
+        if self.current() == Some('}') {
+            self.advance();
+        } else {
             loop {
-                self.skip_whitespace();
-
-                // Parse key
-                if self.current() != Some('"') {
-                    return Err(self.error("Expected string key"));
+                let failed = match self.parse_property(path) {
+                    Ok(property) => {
+                        children.push(property);
+                        false
+                    }
+                    Err(e) => {
+                        self.errors.push(e);
+                        self.skip_to_delimiter();
+                        true
+                    }
+                };
+                if !self.next_item('}', "Expected ',' or '}'", failed) {
+                    break;
                 }
+            }
+        }
 
-                let key_start = self.byte_offset();
-                let key = self.parse_string_value()?;
-                let _key_end = self.byte_offset();
+        let end_offset = self.byte_offset();
 
-                self.skip_whitespace();
+        let range = AstRange::from_offset(self.text, start_offset, end_offset);
+        let label = format!("{{}} ({} properties)", children.len());
 
-                // Expect colon
-                if self.current() != Some(':') {
-                    return Err(self.error("Expected ':'"));
-                }
-                self.advance();
+        AstNode::new(AstNodeType::Object, path.to_string(), label, range).with_children(children)
+    }
 
-                // Parse value
-                let child_path = format!("{path}.{key}");
-                let value_node = self.parse_value(&child_path)?;
-
-                // Create property node
-                let prop_range = AstRange::new(
-                    offset_to_position(self.text, key_start),
-                    value_node.range.end,
-                );
-
-                let mut prop_node = AstNode::new(
-                    AstNodeType::Property,
-                    child_path.clone(),
-                    key.clone(),
-                    prop_range,
-                );
-
-                // For leaf values, store directly; for objects/arrays, add as child
-                match value_node.node_type {
-                    AstNodeType::Object | AstNodeType::Array => {
-                        prop_node = prop_node.with_children(vec![value_node]);
-                    }
-                    _ => {
-                        prop_node.value.clone_from(&value_node.value);
-                        prop_node.node_type = value_node.node_type.clone();
-                    }
-                }
+    /// Parse a `"key": value` member of the object at `path`
+    fn parse_property(&mut self, path: &str) -> Result<AstNode, AstParseError> {
+        self.skip_whitespace();
 
-                children.push(prop_node);
+        // Parse key
+        if self.current() != Some('"') {
+            return Err(self.error("Expected string key"));
+        }
 
-                self.skip_whitespace();
+        let key_start = self.byte_offset();
+        let key = self.parse_string_value()?;
 
-                match self.current() {
-                    Some(',') => self.advance(),
-                    Some('}') => break,
-                    _ => return Err(self.error("Expected ',' or '}'")),
-                }
-            }
+        self.skip_whitespace();
+
+        // Expect colon
+        if self.current() != Some(':') {
+            return Err(self.error("Expected ':'"));
         }
+        self.advance();
 
-        self.advance(); // consume '}'
-        let end_offset = self.byte_offset();
+        // Parse value
+        let child_path = format!("{path}.{key}");
+        let value_node = self.parse_value(&child_path)?;
 
-        let range = AstRange::from_offset(self.text, start_offset, end_offset);
-        let label = format!("{{}} ({} properties)", children.len());
+        // Create property node
+        let prop_range = AstRange::new(
+            offset_to_position(self.text, key_start),
+            value_node.range.end,
+        );
 
-        Ok(
-            AstNode::new(AstNodeType::Object, path.to_string(), label, range)
-                .with_children(children),
-        )
+        let mut prop_node = AstNode::new(AstNodeType::Property, child_path, key, prop_range);
+
+        // For leaf values, store directly; for objects/arrays, add as child
+        match value_node.node_type {
+            AstNodeType::Object | AstNodeType::Array => {
+                prop_node = prop_node.with_children(vec![value_node]);
+            }
+            _ => {
+                prop_node.value.clone_from(&value_node.value);
+                prop_node.node_type = value_node.node_type.clone();
+            }
+        }
+
+        Ok(prop_node)
     }
 
-    fn parse_array(&mut self, path: &str, start_offset: usize) -> Result<AstNode, AstParseError> {
+    fn parse_array(&mut self, path: &str, start_offset: usize) -> AstNode {
         self.advance(); // consume '['
         self.skip_whitespace();
 
         let mut children = Vec::new();
         let mut index = 0;
 
-        if self.current() != Some(']') {
+        if self.current() == Some(']') {
+            self.advance();
+        } else {
             loop {
                 let child_path = format!("{path}[{index}]");
-                let item = self.parse_value(&child_path)?;
-                children.push(item);
-                index += 1;
-
-                self.skip_whitespace();
-
-                match self.current() {
-                    Some(',') => self.advance(),
-                    Some(']') => break,
-                    _ => return Err(self.error("Expected ',' or ']'")),
+                let failed = match self.parse_value(&child_path) {
+                    Ok(item) => {
+                        children.push(item);
+                        index += 1;
+                        false
+                    }
+                    Err(e) => {
+                        self.errors.push(e);
+                        self.skip_to_delimiter();
+                        true
+                    }
+                };
+                if !self.next_item(']', "Expected ',' or ']'", failed) {
+                    break;
                 }
             }
         }
 
-        self.advance(); // consume ']'
         let end_offset = self.byte_offset();
 
         let range = AstRange::from_offset(self.text, start_offset, end_offset);
         let label = format!("[] ({} items)", children.len());
 
-        Ok(
-            AstNode::new(AstNodeType::Array, path.to_string(), label, range)
-                .with_children(children),
-        )
+        AstNode::new(AstNodeType::Array, path.to_string(), label, range).with_children(children)
+    }
+
+    /// Consume the `,` before the next item of a container, or its
+    /// `close`. Returns false once the container has ended: at `close`,
+    /// or unterminated at the end of input or another container's
+    /// closing bracket. `failed` items already reported their error, so
+    /// a missing delimiter after them is not reported again.
+    fn next_item(&mut self, close: char, message: &str, failed: bool) -> bool {
+        if self.cancel.is_some_and(CancellationToken::is_cancelled) {
+            // Jump to the end so every enclosing container stops too.
+            self.pos = self.chars.len();
+            return false;
+        }
+        self.skip_whitespace();
+        if self.current() != Some(',') && self.current() != Some(close) {
+            if !failed {
+                self.errors.push(self.error(message));
+            }
+            self.skip_to_delimiter();
+        }
+        match self.current() {
+            Some(',') => {
+                self.advance();
+                true
+            }
+            Some(c) if c == close => {
+                self.advance();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Skip to the next `,`, `]`, or `}` outside nested containers and
+    /// strings, where parsing can resume after an error
+    fn skip_to_delimiter(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.current() {
+            match c {
+                '"' => {
+                    // An unterminated string runs to the end of input.
+                    if self.parse_string_value().is_err() {
+                        self.advance();
+                    }
+                    continue;
+                }
+                '{' | '[' => depth += 1,
+                ',' | '}' | ']' if depth == 0 => return,
+                '}' | ']' => depth -= 1,
+                _ => {}
+            }
+            self.advance();
+        }
     }
 
     fn parse_string(&mut self, path: &str, start_offset: usize) -> Result<AstNode, AstParseError> {
@@ -385,7 +566,7 @@ mod tests {
     #[test]
     fn test_parse_simple_object() {
         let json = r#"{"name": "John", "age": 30}"#;
-        let result = parse(json);
+        let result = parse(json, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -398,7 +579,7 @@ mod tests {
     #[test]
     fn test_parse_nested_object() {
         let json = r#"{"user": {"name": "John"}}"#;
-        let result = parse(json);
+        let result = parse(json, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -409,7 +590,7 @@ mod tests {
     #[test]
     fn test_parse_array() {
         let json = r"[1, 2, 3]";
-        let result = parse(json);
+        let result = parse(json, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -421,7 +602,7 @@ mod tests {
     #[test]
     fn test_position_tracking() {
         let json = "{\n  \"name\": \"John\"\n}";
-        let result = parse(json);
+        let result = parse(json, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -437,10 +618,89 @@ mod tests {
 
     #[test]
     fn test_parse_error() {
-        let json = r#"{"name": }"#;
-        let result = parse(json);
+        let result = parse("@", None);
 
         assert!(result.ast.is_none());
-        assert!(!result.errors.is_empty());
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_partial_ast_after_errors() {
+        let json = r#"{"name": , "tags": [1, @, 3], "ok": true"#;
+        let result = parse(json, None);
+
+        let ast = result.ast.unwrap();
+        let children = ast.children.unwrap();
+        let paths: Vec<&str> = children.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["$.tags", "$.ok"]);
+        let tags = children[0].children.as_ref().unwrap()[0]
+            .children
+            .as_ref()
+            .unwrap();
+        let items: Vec<&str> = tags.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(items, ["$.tags[0]", "$.tags[1]"]);
+
+        let messages: Vec<&str> = result.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Unexpected character: ','",
+                "Unexpected character: '@'",
+                "Expected ',' or '}'",
+            ]
+        );
+        assert_eq!(result.errors[0].range.unwrap().start.column, 10);
+    }
+
+    #[test]
+    fn test_recovers_from_missing_comma_and_unterminated_string() {
+        let result = parse("[{\"a\": 1 \"b\": 2}, \"open", None);
+
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.children.as_ref().unwrap().len(), 1);
+        let messages: Vec<&str> = result.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["Expected ',' or '}'", "Unterminated string"]);
+    }
+
+    #[test]
+    fn test_parallel_array_matches_sequential_parse() {
+        let json = "\n [\n  {\"name\": \"caf\u{e9}\", \"tags\": [\"a\", \"]\"]},\n  42, \"x\\\"y\",\n  [[], {}], null\n]\n";
+        let parallel = parse_array_parallel(json, None).unwrap();
+        let mut parser = JsonParser::new(json, None);
+        assert_eq!(parallel, parser.parse_value("$").unwrap());
+        assert_eq!(parallel.children.as_ref().unwrap()[1].range.start.line, 4);
+    }
+
+    #[test]
+    fn test_parallel_array_falls_back() {
+        assert!(parse_array_parallel(r#"{"a": [1, 2]}"#, None).is_none());
+        assert!(parse_array_parallel("[1, @, 3]", None).is_none());
+        assert!(parse_array_parallel("[1, , 3]", None).is_none());
+        assert!(parse_array_parallel("[1, [2}, 3]", None).is_none());
+        assert!(parse_array_parallel("[1, 2] 3", None).is_none());
+        assert!(parse_array_parallel("[]", None).is_none());
+    }
+
+    #[test]
+    fn test_cancelled_parse_stops_at_the_next_item() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = parse("[[1, 2], 3, 4]", Some(&token));
+
+        let ast = result.ast.unwrap();
+        let items = ast.children.as_ref().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].children.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_trailing_content_is_an_error() {
+        let result = parse("{} {}", None);
+
+        assert!(result.ast.is_some());
+        assert_eq!(
+            result.errors[0].message,
+            "Unexpected content after the document"
+        );
     }
 }
@@ -0,0 +1,141 @@
+//! Node lookup by source offset, for breadcrumbs and "copy path"
+//!
+//! Starting at the root, the lookup descends into the child whose range
+//! contains the offset. A cursor just past the end of a node (after a
+//! closing quote or bracket) still selects it when no sibling starts
+//! there. A child sharing its parent's path, like a JSON property and the
+//! object it holds, appears in the breadcrumb once, as the outer node.
+
+use serde::Serialize;
+
+use super::{AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+
+/// Node on the way from the root to an offset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AstNodeSummary {
+    pub path: String,
+    pub label: String,
+    pub node_type: AstNodeType,
+    pub range: AstRange,
+}
+
+/// Result of [`path_at_offset`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathAtOffset {
+    /// Path of the deepest node containing the offset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Nodes from the root down to the deepest one; empty when the offset
+    /// is outside the tree
+    pub breadcrumb: Vec<AstNodeSummary>,
+    /// Parse errors; a partial tree is still searched
+    pub errors: Vec<AstParseError>,
+}
+
+/// Deepest node of `parsed` containing the byte `offset`, with its
+/// ancestors
+pub fn path_at_offset(parsed: &AstParseResult, offset: usize) -> PathAtOffset {
+    let mut breadcrumb: Vec<AstNodeSummary> = Vec::new();
+    let mut current = parsed
+        .ast
+        .as_ref()
+        .filter(|root| root.range.start.offset <= offset && offset <= root.range.end.offset);
+    while let Some(node) = current {
+        if breadcrumb
+            .last()
+            .is_none_or(|parent| parent.path != node.path)
+        {
+            breadcrumb.push(AstNodeSummary {
+                path: node.path.clone(),
+                label: node.label.clone(),
+                node_type: node.node_type.clone(),
+                range: node.range,
+            });
+        }
+        current = child_at(node, offset);
+    }
+    PathAtOffset {
+        path: breadcrumb.last().map(|node| node.path.clone()),
+        breadcrumb,
+        errors: parsed.errors.clone(),
+    }
+}
+
+fn child_at(node: &AstNode, offset: usize) -> Option<&AstNode> {
+    let children = node.children.as_deref()?;
+    children
+        .iter()
+        .find(|child| child.range.start.offset <= offset && offset < child.range.end.offset)
+        .or_else(|| {
+            children
+                .iter()
+                .find(|child| child.range.start.offset < offset && offset == child.range.end.offset)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_to_ast, AstLanguage};
+    use super::*;
+
+    fn locate(text: &str, language: AstLanguage, needle: &str) -> PathAtOffset {
+        let offset = text.find(needle).unwrap();
+        path_at_offset(&parse_to_ast(text, language), offset)
+    }
+
+    fn paths(result: &PathAtOffset) -> Vec<&str> {
+        result.breadcrumb.iter().map(|n| n.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_json_breadcrumb() {
+        let json = r#"{"user": {"tags": ["a", "bee"], "id": 7}}"#;
+        let result = locate(json, AstLanguage::Json, "ee");
+        assert_eq!(result.path.as_deref(), Some("$.user.tags[1]"));
+        assert_eq!(
+            paths(&result),
+            ["$", "$.user", "$.user.tags", "$.user.tags[1]"]
+        );
+        assert_eq!(result.breadcrumb[1].label, "user");
+
+        let id = locate(json, AstLanguage::Json, "7");
+        assert_eq!(id.path.as_deref(), Some("$.user.id"));
+        assert_eq!(id.breadcrumb.last().unwrap().node_type, AstNodeType::Number);
+    }
+
+    #[test]
+    fn test_end_of_node_and_outside() {
+        let json = "[1, 22]\n";
+        let after = path_at_offset(&parse_to_ast(json, AstLanguage::Json), 6);
+        assert_eq!(after.path.as_deref(), Some("$[1]"));
+
+        let outside = path_at_offset(&parse_to_ast(json, AstLanguage::Json), 100);
+        assert!(outside.path.is_none());
+        assert!(outside.breadcrumb.is_empty());
+    }
+
+    #[test]
+    fn test_xml_elements() {
+        let xml = "<root><item/><item><name>kogu</name></item></root>";
+        let result = locate(xml, AstLanguage::Xml, "kogu");
+        assert_eq!(
+            paths(&result),
+            [
+                "$.root",
+                "$.root.item[1]",
+                "$.root.item[1].name",
+                "$.root.item[1].name/#text"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_tree() {
+        let json = r#"{"ok": 1, "broken": }"#;
+        let result = locate(json, AstLanguage::Json, "1");
+        assert_eq!(result.path.as_deref(), Some("$.ok"));
+        assert_eq!(result.errors.len(), 1);
+    }
+}
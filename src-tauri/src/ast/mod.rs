@@ -3,13 +3,78 @@
 //! Provides unified AST parsing for multiple languages with position information
 //! for tree view synchronization with Monaco Editor.
 
+mod cache;
+mod compact;
+mod config;
+mod convert;
+mod crontab;
+mod csv;
+mod dockerfile;
+mod dtd;
+mod duplicates;
+mod explain;
+mod flatten;
+mod format;
+mod html;
 mod json;
+mod locate;
+mod openapi;
+mod parallel;
+mod position;
+mod profile;
+mod proto;
+mod search;
+mod server_config;
 mod sql;
+mod sql_deps;
+mod sql_format;
+mod sql_lint;
+mod stream;
+mod subtree;
+mod systemd;
+mod table;
+mod toml;
+mod typegen;
 mod xml;
+mod xml_validate;
+mod xpath;
+mod xsd;
 mod yaml;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use crate::error_codes::{serialize_coded, ErrorCode};
+
+pub use cache::AstCache;
+pub use compact::{AstParseResponse, AstWireFormat};
+pub use convert::{convert_document, ConvertOptions, ConvertResult, ConvertWarning};
+pub use explain::parse_explain_plan;
+pub use flatten::{flatten_json, unflatten_json, ArrayHandling, FlattenOptions, FlattenResult};
+pub use format::{format_document, FormatOptions, FormatResult};
+pub use locate::{path_at_offset, AstNodeSummary, PathAtOffset};
+pub use openapi::{analyze_openapi, OpenApiAnalysis};
+pub use position::{byte_offset, PositionEncoding};
+pub use profile::{
+    profile_document, ContainerSize, DocumentProfile, LengthBucket, NumberStats, StringStats,
+};
+pub use search::{search_ast, AstSearchMatch, AstSearchQuery, AstSearchResult};
+pub use sql::SqlDialect;
+pub use sql_deps::{
+    extract_sql_dependencies, SqlColumnRef, SqlDependencies, SqlName, SqlTableRef, TableAccess,
+};
+pub use sql_format::{format_sql, CommaStyle, KeywordCase, SqlFormatOptions};
+pub use sql_lint::{analyze_sql, SqlAnalysisResult, SqlRule, SqlWarning};
+pub use stream::{NodeExpansion, StreamedDocuments, StreamedParseResult};
+pub use subtree::{get_subtree_text, SubtreeText};
+pub use table::{parse_table, parse_table_file, TableColumn, TableModel, TableOptions};
+pub use typegen::{
+    generate_types, FieldNaming, Optionality, TargetLanguage, TypeGenOptions, TypeGenResult,
+    TypeInput,
+};
+pub use xml_validate::{validate_xml, SchemaKind, XmlValidationResult};
+pub use xpath::{xpath_query, XPathResult};
 
 /// Supported languages for AST parsing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,6 +84,18 @@ pub enum AstLanguage {
     Yaml,
     Xml,
     Sql,
+    Toml,
+    Proto,
+    Csv,
+    Ini,
+    Properties,
+    DotEnv,
+    Dockerfile,
+    Nginx,
+    ApacheConf,
+    Crontab,
+    Systemd,
+    Html,
 }
 
 impl std::str::FromStr for AstLanguage {
@@ -30,6 +107,18 @@ impl std::str::FromStr for AstLanguage {
             "yaml" => Ok(Self::Yaml),
             "xml" => Ok(Self::Xml),
             "sql" => Ok(Self::Sql),
+            "toml" => Ok(Self::Toml),
+            "proto" => Ok(Self::Proto),
+            "csv" | "tsv" => Ok(Self::Csv),
+            "ini" => Ok(Self::Ini),
+            "properties" => Ok(Self::Properties),
+            "dotenv" | "env" => Ok(Self::DotEnv),
+            "dockerfile" => Ok(Self::Dockerfile),
+            "nginx" => Ok(Self::Nginx),
+            "apacheconf" | "apache" | "htaccess" => Ok(Self::ApacheConf),
+            "crontab" | "cron" => Ok(Self::Crontab),
+            "systemd" | "service" | "timer" | "socket" => Ok(Self::Systemd),
+            "html" | "htm" => Ok(Self::Html),
             _ => Err(AstError::UnsupportedLanguage(s.to_string())),
         }
     }
@@ -66,25 +155,66 @@ pub enum AstNodeType {
     Unknown,
 }
 
+impl AstNodeType {
+    /// Serialized name, as written by serde.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Root => "root",
+            Self::Object => "object",
+            Self::Array => "array",
+            Self::Property => "property",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Null => "null",
+            Self::Element => "element",
+            Self::Attribute => "attribute",
+            Self::Text => "text",
+            Self::Comment => "comment",
+            Self::Statement => "statement",
+            Self::Clause => "clause",
+            Self::Expression => "expression",
+            Self::Identifier => "identifier",
+            Self::Literal => "literal",
+            Self::Operator => "operator",
+            Self::Keyword => "keyword",
+            Self::Function => "function",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
 /// Position in the source text (1-indexed)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AstPosition {
     /// Line number (1-indexed)
     pub line: usize,
-    /// Column number (1-indexed)
+    /// Column number in UTF-8 bytes (1-indexed)
     pub column: usize,
-    /// Character offset from start of text (0-indexed)
+    /// Column number in UTF-16 code units (1-indexed), as Monaco counts
+    #[serde(default, rename = "utf16Column")]
+    pub utf16_column: usize,
+    /// Byte offset from start of text (0-indexed)
     pub offset: usize,
 }
 
 impl AstPosition {
+    /// Position whose UTF-16 column is `column` until
+    /// [`parse_to_ast`] counts it from the offset
     pub const fn new(line: usize, column: usize, offset: usize) -> Self {
         Self {
             line,
             column,
+            utf16_column: column,
             offset,
         }
     }
+
+    #[must_use]
+    pub const fn with_utf16_column(mut self, column: usize) -> Self {
+        self.utf16_column = column;
+        self
+    }
 }
 
 /// Range in the source text
@@ -107,6 +237,16 @@ impl AstRange {
     }
 }
 
+/// XML namespace an element or attribute name resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AstNamespace {
+    /// Prefix as written (absent for the default namespace)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Namespace URI
+    pub uri: String,
+}
+
 /// Unified AST node structure
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AstNode {
@@ -125,6 +265,9 @@ pub struct AstNode {
     /// Child nodes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<Self>>,
+    /// Namespace of an XML element or attribute (XML only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<AstNamespace>,
 }
 
 impl AstNode {
@@ -136,6 +279,7 @@ impl AstNode {
             value: None,
             range,
             children: None,
+            namespace: None,
         }
     }
 
@@ -155,10 +299,14 @@ impl AstNode {
 /// Result of AST parsing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AstParseResult {
-    /// Root AST node (null if parsing failed)
+    /// Root AST node (null if parsing failed). Parsers that recover from
+    /// errors return a partial tree alongside the errors.
     pub ast: Option<AstNode>,
     /// Parse errors
     pub errors: Vec<AstParseError>,
+    /// SQL dialect the text was parsed with (SQL only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<SqlDialect>,
 }
 
 impl AstParseResult {
@@ -166,20 +314,68 @@ impl AstParseResult {
         Self {
             ast: Some(ast),
             errors: vec![],
+            dialect: None,
         }
     }
 
     pub const fn failure(errors: Vec<AstParseError>) -> Self {
-        Self { ast: None, errors }
+        Self {
+            ast: None,
+            errors,
+            dialect: None,
+        }
+    }
+
+    /// Tree built by recovering from `errors`
+    pub const fn partial(ast: AstNode, errors: Vec<AstParseError>) -> Self {
+        Self {
+            ast: Some(ast),
+            errors,
+            dialect: None,
+        }
+    }
+
+    /// Whether any of `errors` is an error rather than a warning or note
+    pub fn has_errors(&self) -> bool {
+        self.errors
+            .iter()
+            .any(|error| error.severity == AstSeverity::Error)
+    }
+
+    #[must_use]
+    pub const fn with_dialect(mut self, dialect: SqlDialect) -> Self {
+        self.dialect = Some(dialect);
+        self
     }
 }
 
+/// How serious an [`AstParseError`] is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AstSeverity {
+    /// The document is invalid
+    #[default]
+    Error,
+    /// The document is valid but likely not what was meant
+    Warning,
+    /// The document is valid; something in it was ignored or assumed
+    Info,
+}
+
 /// AST parse error
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AstParseError {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub range: Option<AstRange>,
+    #[serde(default)]
+    pub severity: AstSeverity,
+    /// Ranges of other parts of the document involved, like the first of
+    /// two duplicate keys. A boxed slice keeps the error small enough to
+    /// return by value.
+    #[serde(default, skip_serializing_if = "<[_]>::is_empty")]
+    pub related_ranges: Box<[AstRange]>,
 }
 
 impl AstParseError {
@@ -187,6 +383,24 @@ impl AstParseError {
         Self {
             message: message.into(),
             range: None,
+            severity: AstSeverity::Error,
+            related_ranges: Box::default(),
+        }
+    }
+
+    /// Diagnostic that leaves the document valid
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: AstSeverity::Warning,
+            ..Self::new(message)
+        }
+    }
+
+    /// Diagnostic that only notes how the document was read
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            severity: AstSeverity::Info,
+            ..Self::new(message)
         }
     }
 
@@ -194,13 +408,79 @@ impl AstParseError {
         self.range = Some(range);
         self
     }
+
+    #[must_use]
+    pub fn with_related_range(mut self, range: AstRange) -> Self {
+        let mut ranges = std::mem::take(&mut self.related_ranges).into_vec();
+        ranges.push(range);
+        self.related_ranges = ranges.into_boxed_slice();
+        self
+    }
 }
 
+/// Largest document `parse_to_ast` accepts (32 MiB). Parsers hold the
+/// text, a character buffer, and the tree at once, so memory grows to
+/// several times the input.
+pub const MAX_INPUT_BYTES: usize = 32 * 1024 * 1024;
+
 /// AST parsing errors
 #[derive(Debug, Error)]
 pub enum AstError {
     #[error("Unsupported language: {0}")]
     UnsupportedLanguage(String),
+    #[error("Document is too large to parse ({size} bytes, limit {limit})")]
+    InputTooLarge { size: usize, limit: usize },
+    #[error("Parsing cancelled")]
+    Cancelled,
+    #[error("Parser failed: {0}")]
+    Internal(String),
+    #[error("Could not read the file: {0}")]
+    Io(String),
+    #[error("{0} changed since it was opened; open it again")]
+    FileChanged(String),
+    #[error("{0} is not a collapsed node of an open file")]
+    NotCollapsed(String),
+}
+
+impl ErrorCode for AstError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnsupportedLanguage(_) => "ast.unsupported_language",
+            Self::InputTooLarge { .. } => "ast.input_too_large",
+            Self::Cancelled => "ast.cancelled",
+            Self::Internal(_) => "ast.internal",
+            Self::Io(_) => "ast.io",
+            Self::FileChanged(_) => "ast.file_changed",
+            Self::NotCollapsed(_) => "ast.not_collapsed",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::UnsupportedLanguage(language) => vec![("language", language.clone())],
+            Self::InputTooLarge { size, limit } => {
+                vec![("size", size.to_string()), ("limit", limit.to_string())]
+            }
+            Self::Cancelled => Vec::new(),
+            Self::Internal(detail) | Self::Io(detail) => vec![("detail", detail.clone())],
+            Self::FileChanged(path) | Self::NotCollapsed(path) => vec![("path", path.clone())],
+        }
+    }
+}
+
+/// Reject documents larger than [`MAX_INPUT_BYTES`].
+///
+/// # Errors
+///
+/// Returns [`AstError::InputTooLarge`] for oversized input.
+pub const fn check_input_size(text: &str) -> Result<(), AstError> {
+    if text.len() > MAX_INPUT_BYTES {
+        return Err(AstError::InputTooLarge {
+            size: text.len(),
+            limit: MAX_INPUT_BYTES,
+        });
+    }
+    Ok(())
 }
 
 impl Serialize for AstError {
@@ -208,7 +488,34 @@ impl Serialize for AstError {
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        serialize_coded(self, serializer)
+    }
+}
+
+/// Append `key` to `path`, using bracket notation for keys that are not
+/// plain identifiers (quoted keys may contain dots, spaces, or quotes).
+fn child_path(path: &str, key: &str) -> String {
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        format!("{path}.{key}")
+    } else {
+        format!(
+            "{path}['{}']",
+            key.replace('\\', "\\\\").replace('\'', "\\'")
+        )
+    }
+}
+
+/// Shorten `text` to at most 50 characters for a node label, ending
+/// cut-off text with "...".
+fn truncate_label(text: &str) -> String {
+    if text.chars().count() > 50 {
+        format!("{}...", text.chars().take(47).collect::<String>())
+    } else {
+        text.to_string()
     }
 }
 
@@ -219,24 +526,94 @@ pub fn offset_to_position(text: &str, offset: usize) -> AstPosition {
     let line = prefix.matches('\n').count() + 1;
     let last_newline = prefix.rfind('\n').map_or(0, |i| i + 1);
     let column = offset - last_newline + 1;
+    let utf16_column = text
+        .get(last_newline..offset)
+        .map_or(column, |line| line.encode_utf16().count() + 1);
 
-    AstPosition::new(line, column, offset)
+    AstPosition::new(line, column, offset).with_utf16_column(utf16_column)
 }
 
-/// Parse text to AST based on language
+/// Parse text to AST based on language. Columns are counted in both
+/// UTF-8 bytes and UTF-16 code units, whatever the parser reports.
 pub fn parse_to_ast(text: &str, language: AstLanguage) -> AstParseResult {
-    match language {
-        AstLanguage::Json => json::parse(text),
-        AstLanguage::Yaml => yaml::parse(text),
+    parse_cancellable(text, language, None)
+}
+
+/// [`parse_to_ast`] that stops early once `cancel` is cancelled: inside
+/// the JSON and YAML parsers, and between the parse, the duplicate key
+/// check, and position normalization for every language. A cancelled
+/// parse returns an incomplete result the caller must discard.
+fn parse_cancellable(
+    text: &str,
+    language: AstLanguage,
+    cancel: Option<&CancellationToken>,
+) -> AstParseResult {
+    let result = match language {
+        AstLanguage::Json => json::parse(text, cancel),
+        AstLanguage::Yaml => yaml::parse(text, cancel),
         AstLanguage::Xml => xml::parse(text),
         AstLanguage::Sql => sql::parse(text),
+        AstLanguage::Toml => toml::parse(text),
+        AstLanguage::Proto => proto::parse(text),
+        AstLanguage::Csv => csv::parse(text),
+        AstLanguage::Ini => config::parse(text, config::Dialect::Ini),
+        AstLanguage::Properties => config::parse(text, config::Dialect::Properties),
+        AstLanguage::DotEnv => config::parse(text, config::Dialect::DotEnv),
+        AstLanguage::Dockerfile => dockerfile::parse(text),
+        AstLanguage::Nginx => server_config::parse(text, server_config::Dialect::Nginx),
+        AstLanguage::ApacheConf => server_config::parse(text, server_config::Dialect::Apache),
+        AstLanguage::Crontab => crontab::parse(text),
+        AstLanguage::Systemd => systemd::parse(text),
+        AstLanguage::Html => html::parse(text),
+    };
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return result;
+    }
+    let result = match language {
+        AstLanguage::Json | AstLanguage::Yaml | AstLanguage::Toml => {
+            duplicates::check(text, language, result)
+        }
+        _ => result,
+    };
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return result;
+    }
+    position::normalize(text, result)
+}
+
+/// Parse SQL text to AST using a specific dialect
+pub fn parse_sql_to_ast(text: &str, dialect: SqlDialect) -> AstParseResult {
+    parse_sql_cancellable(text, dialect, None)
+}
+
+/// [`parse_sql_to_ast`] that skips position normalization once `cancel`
+/// is cancelled
+fn parse_sql_cancellable(
+    text: &str,
+    dialect: SqlDialect,
+    cancel: Option<&CancellationToken>,
+) -> AstParseResult {
+    let result = sql::parse_dialect(text, dialect);
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return result;
     }
+    position::normalize(text, result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn child_path_brackets_and_escapes_non_identifier_keys() {
+        assert_eq!(child_path("$", "server-port"), "$.server-port");
+        assert_eq!(child_path("$", "a.b"), "$['a.b']");
+        assert_eq!(
+            child_path("$.env", r"it's C:\tmp"),
+            r"$.env['it\'s C:\\tmp']"
+        );
+    }
+
     // ========================================================================
     // Test Fixtures
     // ========================================================================
@@ -261,14 +638,6 @@ mod tests {
         AstRange::new(AstPosition::new(1, 1, 0), AstPosition::new(1, 1, 0))
     }
 
-    fn truncate_label(s: &str, max_len: usize) -> String {
-        if s.len() > max_len {
-            format!("{}...", &s[..max_len.saturating_sub(3)])
-        } else {
-            s.to_string()
-        }
-    }
-
     fn format_string_label(s: &str) -> String {
         if s.len() > 50 {
             format!("\"{}...\"", &s[..47])
@@ -508,6 +877,78 @@ mod tests {
             assert_eq!("sql".parse::<AstLanguage>().unwrap(), AstLanguage::Sql);
         }
 
+        #[test]
+        fn test_from_str_toml() {
+            assert_eq!("TOML".parse::<AstLanguage>().unwrap(), AstLanguage::Toml);
+        }
+
+        #[test]
+        fn test_from_str_proto() {
+            assert_eq!("proto".parse::<AstLanguage>().unwrap(), AstLanguage::Proto);
+        }
+
+        #[test]
+        fn test_from_str_tsv_is_csv() {
+            assert_eq!("tsv".parse::<AstLanguage>().unwrap(), AstLanguage::Csv);
+        }
+
+        #[test]
+        fn test_from_str_config_dialects() {
+            assert_eq!("ini".parse::<AstLanguage>().unwrap(), AstLanguage::Ini);
+            assert_eq!(
+                "Properties".parse::<AstLanguage>().unwrap(),
+                AstLanguage::Properties
+            );
+            assert_eq!("env".parse::<AstLanguage>().unwrap(), AstLanguage::DotEnv);
+        }
+
+        #[test]
+        fn test_from_str_dockerfile() {
+            assert_eq!(
+                "Dockerfile".parse::<AstLanguage>().unwrap(),
+                AstLanguage::Dockerfile
+            );
+        }
+
+        #[test]
+        fn test_from_str_server_configs() {
+            assert_eq!("nginx".parse::<AstLanguage>().unwrap(), AstLanguage::Nginx);
+            assert_eq!(
+                "apacheconf".parse::<AstLanguage>().unwrap(),
+                AstLanguage::ApacheConf
+            );
+            assert_eq!(
+                "htaccess".parse::<AstLanguage>().unwrap(),
+                AstLanguage::ApacheConf
+            );
+        }
+
+        #[test]
+        fn test_from_str_crontab() {
+            assert_eq!(
+                "crontab".parse::<AstLanguage>().unwrap(),
+                AstLanguage::Crontab
+            );
+        }
+
+        #[test]
+        fn test_from_str_systemd() {
+            assert_eq!(
+                "systemd".parse::<AstLanguage>().unwrap(),
+                AstLanguage::Systemd
+            );
+            assert_eq!(
+                "timer".parse::<AstLanguage>().unwrap(),
+                AstLanguage::Systemd
+            );
+        }
+
+        #[test]
+        fn test_from_str_html() {
+            assert_eq!("html".parse::<AstLanguage>().unwrap(), AstLanguage::Html);
+            assert_eq!("htm".parse::<AstLanguage>().unwrap(), AstLanguage::Html);
+        }
+
         #[test]
         fn test_from_str_unknown_returns_error() {
             let result = "unknown".parse::<AstLanguage>();
@@ -581,7 +1022,7 @@ mod tests {
         #[test]
         fn test_truncate_label_short_string_unchanged() {
             // Act
-            let result = truncate_label("short", 10);
+            let result = truncate_label("short");
 
             // Assert
             assert_eq!(result, "short");
@@ -590,10 +1031,10 @@ mod tests {
         #[test]
         fn test_truncate_label_long_string_truncated() {
             // Act
-            let result = truncate_label("this is a very long string", 10);
+            let result = truncate_label(&"é".repeat(60));
 
             // Assert
-            assert!(result.len() <= 10);
+            assert_eq!(result.chars().count(), 50);
             assert!(result.ends_with("..."));
         }
 
@@ -692,5 +1133,20 @@ mod tests {
             // Assert
             assert!(result.ast.is_some());
         }
+
+        #[test]
+        fn test_check_input_size_rejects_oversized_documents() {
+            // Arrange
+            let at_limit = " ".repeat(MAX_INPUT_BYTES);
+            let over_limit = " ".repeat(MAX_INPUT_BYTES + 1);
+
+            // Act & Assert
+            assert!(check_input_size(&at_limit).is_ok());
+            assert!(matches!(
+                check_input_size(&over_limit),
+                Err(AstError::InputTooLarge { size, limit })
+                    if size == MAX_INPUT_BYTES + 1 && limit == MAX_INPUT_BYTES
+            ));
+        }
     }
 }
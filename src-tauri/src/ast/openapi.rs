@@ -0,0 +1,897 @@
+//! `OpenAPI` 3.x document analysis
+//!
+//! The document is read through the JSON or YAML AST, so every node of the
+//! operation tree keeps the path and range of the part of the document it
+//! summarises: path items, their operations, parameters, request bodies,
+//! and responses, then the component schemas with their properties.
+//!
+//! Validation covers the structural rules of the specification: required
+//! fields, parameter locations, path templates matching their declared
+//! path parameters, response status codes, unique operation ids, and local
+//! `$ref`s that resolve within the document.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::xml_validate::ValidationError;
+use super::{parse_to_ast, AstLanguage, AstNode, AstNodeType};
+
+/// Operations a path item may declare, in the order the tree lists them
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Path item fields other than operations
+const PATH_ITEM_FIELDS: [&str; 5] = ["$ref", "summary", "description", "servers", "parameters"];
+
+/// `$ref` hops followed before a reference is treated as unresolvable
+const MAX_REF_HOPS: usize = 16;
+
+/// Result of [`analyze_openapi`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenApiAnalysis {
+    /// The document parsed and follows the specification
+    pub valid: bool,
+    /// `openapi` version the document declares
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub openapi: Option<String>,
+    /// `info.title`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `info.version`, the version of the API itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Paths with their operations, then component schemas; ranges point
+    /// into the document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<AstNode>,
+    pub operation_count: usize,
+    pub schema_count: usize,
+    /// Parse errors and specification violations
+    pub errors: Vec<ValidationError>,
+}
+
+/// Parse an `OpenAPI` 3.x document (JSON or YAML) into its operation and
+/// schema tree and check it against the specification
+pub fn analyze_openapi(text: &str) -> OpenApiAnalysis {
+    let language = if text.trim_start().starts_with('{') {
+        AstLanguage::Json
+    } else {
+        AstLanguage::Yaml
+    };
+    let parsed = parse_to_ast(text, language);
+    let mut errors: Vec<ValidationError> = parsed.errors.into_iter().map(Into::into).collect();
+    let Some(root) = parsed.ast else {
+        return OpenApiAnalysis {
+            errors,
+            ..OpenApiAnalysis::default()
+        };
+    };
+
+    let mut analyzer = Analyzer::new(&root);
+    let tree = analyzer.document();
+    errors.append(&mut analyzer.errors);
+    OpenApiAnalysis {
+        valid: errors.is_empty(),
+        openapi: analyzer.openapi,
+        title: analyzer.title,
+        version: analyzer.version,
+        tree,
+        operation_count: analyzer.operation_count,
+        schema_count: analyzer.schema_count,
+        errors,
+    }
+}
+
+/// Object or array `node` holds, looking through the property around it
+fn container(node: &AstNode) -> &AstNode {
+    match (&node.node_type, node.children.as_deref()) {
+        (AstNodeType::Property, Some([inner])) => inner,
+        _ => node,
+    }
+}
+
+fn is_object(node: &AstNode) -> bool {
+    container(node).node_type == AstNodeType::Object
+}
+
+/// Members of an object; empty for anything else
+fn members(node: &AstNode) -> &[AstNode] {
+    let node = container(node);
+    if node.node_type == AstNodeType::Object {
+        node.children.as_deref().unwrap_or_default()
+    } else {
+        &[]
+    }
+}
+
+/// Items of an array; empty for anything else
+fn items(node: &AstNode) -> &[AstNode] {
+    let node = container(node);
+    if node.node_type == AstNodeType::Array {
+        node.children.as_deref().unwrap_or_default()
+    } else {
+        &[]
+    }
+}
+
+fn member<'a>(node: &'a AstNode, key: &str) -> Option<&'a AstNode> {
+    members(node).iter().find(|m| m.label == key)
+}
+
+fn string(node: &AstNode) -> Option<&str> {
+    node.value.as_ref()?.as_str()
+}
+
+/// Text of a string or number value (`openapi: 3.1` reads as a number in
+/// YAML)
+fn scalar_text(node: &AstNode) -> Option<String> {
+    match node.value.as_ref()? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn member_string<'a>(node: &'a AstNode, key: &str) -> Option<&'a str> {
+    member(node, key).and_then(string)
+}
+
+/// Target of a local reference (`#/components/schemas/Pet`) in `root`
+fn pointer<'a>(root: &'a AstNode, reference: &str) -> Option<&'a AstNode> {
+    let pointer = reference.strip_prefix('#')?;
+    let mut node = root;
+    for token in pointer.split('/').skip(1) {
+        let key = token.replace("~1", "/").replace("~0", "~");
+        node = match container(node).node_type {
+            AstNodeType::Object => member(node, &key)?,
+            AstNodeType::Array => items(node).get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+/// Last segment of a reference, naming the component it points to
+fn ref_name(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+/// Names of the `{param}` placeholders in a path template
+fn template_params(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+/// `default`, a status code from 100 to 599, or a range like `4XX`
+fn is_status_code(code: &str) -> bool {
+    if code == "default" {
+        return true;
+    }
+    let bytes = code.as_bytes();
+    bytes.len() == 3
+        && (b'1'..=b'5').contains(&bytes[0])
+        && (bytes[1..].iter().all(u8::is_ascii_digit) || &bytes[1..] == b"XX")
+}
+
+/// Short description of a schema: its type, the component it references,
+/// or its composition keyword
+fn schema_type(schema: &AstNode) -> String {
+    if let Some(reference) = member_string(schema, "$ref") {
+        return ref_name(reference).to_string();
+    }
+    let Some(kind) = member(schema, "type") else {
+        return ["oneOf", "anyOf", "allOf"]
+            .into_iter()
+            .find(|keyword| member(schema, keyword).is_some())
+            .unwrap_or("any")
+            .to_string();
+    };
+    // OpenAPI 3.1 allows `type: [string, "null"]`.
+    let kind = scalar_text(kind).unwrap_or_else(|| {
+        items(kind)
+            .iter()
+            .filter_map(string)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    });
+    match (kind.as_str(), member(schema, "items")) {
+        ("array", Some(item)) => format!("{}[]", schema_type(item)),
+        _ => match member_string(schema, "format") {
+            Some(format) => format!("{kind} ({format})"),
+            None => kind,
+        },
+    }
+}
+
+struct Analyzer<'a> {
+    root: &'a AstNode,
+    errors: Vec<ValidationError>,
+    /// Reported `(path, message)` pairs; a component used by several
+    /// operations is reported once
+    reported: HashSet<(String, String)>,
+    operation_ids: HashSet<&'a str>,
+    openapi: Option<String>,
+    title: Option<String>,
+    version: Option<String>,
+    operation_count: usize,
+    schema_count: usize,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(root: &'a AstNode) -> Self {
+        Self {
+            root,
+            errors: Vec::new(),
+            reported: HashSet::new(),
+            operation_ids: HashSet::new(),
+            openapi: None,
+            title: None,
+            version: None,
+            operation_count: 0,
+            schema_count: 0,
+        }
+    }
+
+    fn issue(&mut self, node: &AstNode, message: String) {
+        if self.reported.insert((node.path.clone(), message.clone())) {
+            self.errors.push(ValidationError {
+                message,
+                path: Some(node.path.clone()),
+                range: Some(node.range),
+            });
+        }
+    }
+
+    fn missing(&mut self, node: &AstNode, field: &str) {
+        self.issue(node, format!("Missing required field '{field}'"));
+    }
+
+    /// Node `node` stands for, following `$ref`s
+    fn resolve(&mut self, node: &'a AstNode) -> &'a AstNode {
+        let mut target = node;
+        for _ in 0..MAX_REF_HOPS {
+            match member_string(target, "$ref").and_then(|r| pointer(self.root, r)) {
+                Some(next) => target = next,
+                None => return target,
+            }
+        }
+        self.issue(node, "Circular $ref".to_string());
+        target
+    }
+
+    fn document(&mut self) -> Option<AstNode> {
+        let root = self.root;
+        if !is_object(root) {
+            self.issue(root, "An OpenAPI document must be an object".to_string());
+            return None;
+        }
+
+        self.check_version();
+        match member(root, "info") {
+            Some(info) => {
+                self.title = member(info, "title").and_then(scalar_text);
+                self.version = member(info, "version").and_then(scalar_text);
+                if self.title.is_none() {
+                    self.missing(info, "title");
+                }
+                if self.version.is_none() {
+                    self.missing(info, "version");
+                }
+            }
+            None => self.missing(root, "info"),
+        }
+
+        let mut children = Vec::new();
+        match member(root, "paths") {
+            Some(paths) => children.push(self.paths(paths)),
+            None if self.is_31() => {
+                if member(root, "components").is_none() && member(root, "webhooks").is_none() {
+                    self.issue(
+                        root,
+                        "A document needs 'paths', 'components', or 'webhooks'".to_string(),
+                    );
+                }
+            }
+            None => self.missing(root, "paths"),
+        }
+        if let Some(schemas) = member(root, "components").and_then(|c| member(c, "schemas")) {
+            children.push(self.schemas(schemas));
+        }
+        self.check_refs();
+
+        let label = match (&self.title, &self.version) {
+            (Some(title), Some(version)) => format!("{title} {version}"),
+            (Some(title), None) => title.clone(),
+            _ => "OpenAPI document".to_string(),
+        };
+        Some(
+            AstNode::new(AstNodeType::Root, "$".to_string(), label, root.range)
+                .with_children(children),
+        )
+    }
+
+    fn is_31(&self) -> bool {
+        self.openapi
+            .as_deref()
+            .is_some_and(|v| v.starts_with("3.1"))
+    }
+
+    fn check_version(&mut self) {
+        let root = self.root;
+        let Some(openapi) = member(root, "openapi") else {
+            match member(root, "swagger") {
+                Some(swagger) => self.issue(
+                    swagger,
+                    "Swagger 2.0 documents are not supported; convert the document to OpenAPI 3"
+                        .to_string(),
+                ),
+                None => self.missing(root, "openapi"),
+            }
+            return;
+        };
+        self.openapi = scalar_text(openapi);
+        if !self.openapi.as_deref().is_some_and(|v| v.starts_with("3.")) {
+            let version = self.openapi.clone().unwrap_or_default();
+            self.issue(
+                openapi,
+                format!("Unsupported OpenAPI version '{version}'; expected 3.x"),
+            );
+        }
+    }
+
+    fn paths(&mut self, paths: &'a AstNode) -> AstNode {
+        let children: Vec<AstNode> = members(paths)
+            .iter()
+            .map(|item| self.path_item(item))
+            .collect();
+        AstNode::new(
+            AstNodeType::Object,
+            paths.path.clone(),
+            format!("Paths ({})", children.len()),
+            paths.range,
+        )
+        .with_children(children)
+    }
+
+    fn path_item(&mut self, item: &'a AstNode) -> AstNode {
+        let template = item.label.as_str();
+        if !template.starts_with('/') {
+            self.issue(item, format!("Path '{template}' must start with '/'"));
+        }
+        let node = AstNode::new(
+            AstNodeType::Object,
+            item.path.clone(),
+            template.to_string(),
+            item.range,
+        );
+        if !is_object(item) {
+            self.issue(item, "A path item must be an object".to_string());
+            return node;
+        }
+
+        let shared = member(item, "parameters");
+        let mut children = Vec::new();
+        if let Some(shared) = shared {
+            children.push(self.parameters(shared));
+        }
+        for field in members(item) {
+            let key = field.label.as_str();
+            if METHODS.contains(&key) {
+                children.push(self.operation(template, shared, key, field));
+            } else if !PATH_ITEM_FIELDS.contains(&key) && !key.starts_with("x-") {
+                self.issue(field, format!("Unknown field '{key}' in path item"));
+            }
+        }
+        node.with_children(children)
+    }
+
+    fn operation(
+        &mut self,
+        template: &str,
+        shared: Option<&'a AstNode>,
+        method: &str,
+        operation: &'a AstNode,
+    ) -> AstNode {
+        self.operation_count += 1;
+        let operation_id = member_string(operation, "operationId");
+        if let Some(id) = operation_id {
+            if !self.operation_ids.insert(id) {
+                self.issue(operation, format!("Duplicate operationId '{id}'"));
+            }
+        }
+        let method_name = method.to_ascii_uppercase();
+        let label = operation_id
+            .or_else(|| member_string(operation, "summary"))
+            .map_or_else(
+                || method_name.clone(),
+                |name| format!("{method_name} {name}"),
+            );
+
+        let own = member(operation, "parameters");
+        self.check_path_params(template, shared, own, operation);
+
+        let mut children = Vec::new();
+        if let Some(own) = own {
+            children.push(self.parameters(own));
+        }
+        if let Some(body) = member(operation, "requestBody") {
+            children.push(self.request_body(body));
+        }
+        match member(operation, "responses") {
+            Some(responses) => children.push(self.responses(responses)),
+            None if self.is_31() => {}
+            None => self.missing(operation, "responses"),
+        }
+
+        let node = AstNode::new(
+            AstNodeType::Statement,
+            operation.path.clone(),
+            label,
+            operation.range,
+        );
+        let node = match operation_id {
+            Some(id) => node.with_value(serde_json::Value::String(id.to_string())),
+            None => node,
+        };
+        node.with_children(children)
+    }
+
+    /// `in: path` parameters declared for an operation, path-level ones
+    /// included, against the placeholders of its path template
+    fn check_path_params(
+        &mut self,
+        template: &str,
+        shared: Option<&'a AstNode>,
+        own: Option<&'a AstNode>,
+        operation: &AstNode,
+    ) {
+        let placeholders = template_params(template);
+        let mut declared: Vec<&str> = Vec::new();
+        for list in [shared, own].into_iter().flatten() {
+            for parameter in items(list) {
+                let resolved = self.resolve(parameter);
+                if member_string(resolved, "in") != Some("path") {
+                    continue;
+                }
+                let Some(name) = member_string(resolved, "name") else {
+                    continue;
+                };
+                declared.push(name);
+                if !placeholders.contains(&name) {
+                    self.issue(
+                        parameter,
+                        format!("Path parameter '{name}' does not appear in '{template}'"),
+                    );
+                }
+            }
+        }
+        for placeholder in placeholders {
+            if !declared.contains(&placeholder) {
+                self.issue(
+                    operation,
+                    format!("Path parameter '{placeholder}' of '{template}' is not declared"),
+                );
+            }
+        }
+    }
+
+    fn parameters(&mut self, list: &'a AstNode) -> AstNode {
+        let mut seen: HashSet<(&str, &str)> = HashSet::new();
+        let mut children = Vec::new();
+        for parameter in items(list) {
+            let resolved = self.resolve(parameter);
+            let name = member_string(resolved, "name");
+            let location = member_string(resolved, "in");
+            if name.is_none() {
+                self.missing(resolved, "name");
+            }
+            match location {
+                Some("query" | "header" | "path" | "cookie") | None => {}
+                Some(other) => self.issue(
+                    resolved,
+                    format!(
+                        "Invalid parameter location '{other}'; expected query, header, path, or cookie"
+                    ),
+                ),
+            }
+            if location.is_none() {
+                self.missing(resolved, "in");
+            }
+            let required = member(resolved, "required")
+                .and_then(|r| r.value.as_ref())
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false);
+            if let (Some(name), Some(location)) = (name, location) {
+                if location == "path" && !required {
+                    self.issue(
+                        resolved,
+                        format!("Path parameter '{name}' must be required"),
+                    );
+                }
+                if !seen.insert((name, location)) {
+                    self.issue(
+                        parameter,
+                        format!("Duplicate parameter '{name}' in {location}"),
+                    );
+                }
+            }
+
+            let mut label = format!(
+                "{} ({}{})",
+                name.unwrap_or("?"),
+                location.unwrap_or("?"),
+                if required { ", required" } else { "" }
+            );
+            if let Some(schema) = member(resolved, "schema") {
+                label = format!("{label}: {}", schema_type(schema));
+            }
+            children.push(AstNode::new(
+                AstNodeType::Property,
+                parameter.path.clone(),
+                label,
+                parameter.range,
+            ));
+        }
+        AstNode::new(
+            AstNodeType::Array,
+            list.path.clone(),
+            format!("Parameters ({})", children.len()),
+            list.range,
+        )
+        .with_children(children)
+    }
+
+    fn request_body(&mut self, body: &'a AstNode) -> AstNode {
+        let resolved = self.resolve(body);
+        let content = member(resolved, "content");
+        if content.is_none() {
+            self.missing(resolved, "content");
+        }
+        let media: Vec<String> = content.map_or_else(Vec::new, |content| {
+            members(content)
+                .iter()
+                .map(|m| {
+                    member(m, "schema").map_or_else(
+                        || m.label.clone(),
+                        |schema| format!("{} → {}", m.label, schema_type(schema)),
+                    )
+                })
+                .collect()
+        });
+        let mut label = "Request body".to_string();
+        if !media.is_empty() {
+            label = format!("{label}: {}", media.join(", "));
+        }
+        AstNode::new(AstNodeType::Property, body.path.clone(), label, body.range)
+    }
+
+    fn responses(&mut self, responses: &'a AstNode) -> AstNode {
+        if members(responses).is_empty() {
+            self.issue(responses, "At least one response is required".to_string());
+        }
+        let mut children = Vec::new();
+        for response in members(responses) {
+            let code = response.label.as_str();
+            if !is_status_code(code) && !code.starts_with("x-") {
+                self.issue(response, format!("Invalid response status code '{code}'"));
+            }
+            let resolved = self.resolve(response);
+            let description = member_string(resolved, "description");
+            if description.is_none() {
+                self.missing(resolved, "description");
+            }
+            let schema = member(resolved, "content")
+                .and_then(|content| members(content).first())
+                .and_then(|media| member(media, "schema"));
+            let mut label = description.map_or_else(
+                || code.to_string(),
+                |description| format!("{code} {description}"),
+            );
+            if let Some(schema) = schema {
+                label = format!("{label} → {}", schema_type(schema));
+            }
+            children.push(AstNode::new(
+                AstNodeType::Property,
+                response.path.clone(),
+                label,
+                response.range,
+            ));
+        }
+        AstNode::new(
+            AstNodeType::Object,
+            responses.path.clone(),
+            format!("Responses ({})", children.len()),
+            responses.range,
+        )
+        .with_children(children)
+    }
+
+    fn schemas(&mut self, schemas: &'a AstNode) -> AstNode {
+        let children: Vec<AstNode> = members(schemas)
+            .iter()
+            .map(|schema| {
+                let required: Vec<&str> = member(schema, "required")
+                    .map(|r| items(r).iter().filter_map(string).collect())
+                    .unwrap_or_default();
+                let properties: Vec<AstNode> = member(schema, "properties")
+                    .map(members)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|property| {
+                        let mut label = format!("{}: {}", property.label, schema_type(property));
+                        if required.contains(&property.label.as_str()) {
+                            label.push_str(" (required)");
+                        }
+                        AstNode::new(
+                            AstNodeType::Property,
+                            property.path.clone(),
+                            label,
+                            property.range,
+                        )
+                    })
+                    .collect();
+                AstNode::new(
+                    AstNodeType::Object,
+                    schema.path.clone(),
+                    format!("{}: {}", schema.label, schema_type(schema)),
+                    schema.range,
+                )
+                .with_children(properties)
+            })
+            .collect();
+        self.schema_count = children.len();
+        AstNode::new(
+            AstNodeType::Object,
+            schemas.path.clone(),
+            format!("Schemas ({})", children.len()),
+            schemas.range,
+        )
+        .with_children(children)
+    }
+
+    /// Report local `$ref`s whose target is missing. References to other
+    /// files are left alone.
+    fn check_refs(&mut self) {
+        let mut stack = vec![self.root];
+        while let Some(node) = stack.pop() {
+            if node.label == "$ref" {
+                if let Some(reference) = string(node).filter(|r| r.starts_with('#')) {
+                    if pointer(self.root, reference).is_none() {
+                        self.issue(node, format!("Cannot resolve $ref '{reference}'"));
+                    }
+                }
+            }
+            if let Some(children) = &node.children {
+                stack.extend(children.iter().rev());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = r##"openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+            format: int32
+      responses:
+        "200":
+          description: A page of pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+    post:
+      summary: Create a pet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "201":
+          description: Created
+  /pets/{petId}:
+    parameters:
+      - $ref: "#/components/parameters/PetId"
+    get:
+      operationId: showPet
+      responses:
+        default:
+          $ref: "#/components/responses/Error"
+components:
+  parameters:
+    PetId:
+      name: petId
+      in: path
+      required: true
+      schema:
+        type: string
+  responses:
+    Error:
+      description: Unexpected error
+  schemas:
+    Pet:
+      type: object
+      required: [id]
+      properties:
+        id:
+          type: integer
+          format: int64
+        tag:
+          type: string
+"##;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    fn messages(analysis: &OpenApiAnalysis) -> Vec<&str> {
+        analysis.errors.iter().map(|e| e.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_operation_tree() {
+        let analysis = analyze_openapi(PETSTORE);
+        assert!(analysis.valid, "{:?}", messages(&analysis));
+        assert_eq!(analysis.openapi.as_deref(), Some("3.0.3"));
+        assert_eq!(analysis.operation_count, 3);
+        assert_eq!(analysis.schema_count, 1);
+
+        let tree = analysis.tree.unwrap();
+        assert_eq!(tree.label, "Petstore 1.0.0");
+        let paths = &children(&tree)[0];
+        assert_eq!(paths.label, "Paths (2)");
+
+        let pets = &children(paths)[0];
+        assert_eq!(pets.path, "$.paths./pets");
+        let list = &children(pets)[0];
+        assert_eq!(list.label, "GET listPets");
+        assert_eq!(list.value, Some(serde_json::json!("listPets")));
+        let list_children = children(list);
+        assert_eq!(
+            children(&list_children[0])[0].label,
+            "limit (query): integer (int32)"
+        );
+        assert_eq!(
+            children(&list_children[1])[0].label,
+            "200 A page of pets → Pet[]"
+        );
+
+        let create = &children(pets)[1];
+        assert_eq!(create.label, "POST Create a pet");
+        assert_eq!(
+            children(create)[0].label,
+            "Request body: application/json → Pet"
+        );
+
+        let pet = &children(paths)[1];
+        let shared = &children(pet)[0];
+        assert_eq!(children(shared)[0].label, "petId (path, required): string");
+        let error = &children(&children(&children(pet)[1])[0])[0];
+        assert_eq!(error.label, "default Unexpected error");
+    }
+
+    #[test]
+    fn test_schemas() {
+        let tree = analyze_openapi(PETSTORE).tree.unwrap();
+        let schemas = &children(&tree)[1];
+        assert_eq!(schemas.path, "$.components.schemas");
+        let pet = &children(schemas)[0];
+        assert_eq!(pet.label, "Pet: object");
+        let labels: Vec<&str> = children(pet).iter().map(|p| p.label.as_str()).collect();
+        assert_eq!(labels, ["id: integer (int64) (required)", "tag: string"]);
+        assert_eq!(
+            children(pet)[0].path,
+            "$.components.schemas.Pet.properties.id"
+        );
+    }
+
+    #[test]
+    fn test_json_document_ranges() {
+        let json = r#"{
+  "openapi": "3.1.0",
+  "info": {"title": "Tiny", "version": "2"},
+  "paths": {
+    "/health": {"get": {"responses": {"204": {"description": "Up"}}}}
+  }
+}"#;
+        let analysis = analyze_openapi(json);
+        assert!(analysis.valid, "{:?}", messages(&analysis));
+        let tree = analysis.tree.unwrap();
+        let get = &children(&children(&children(&tree)[0])[0])[0];
+        assert_eq!(get.path, "$.paths./health.get");
+        assert_eq!(get.range.start.line, 5);
+    }
+
+    #[test]
+    fn test_spec_violations() {
+        let yaml = r##"openapi: 3.0.0
+info:
+  title: Broken
+paths:
+  users/{id}:
+    get:
+      operationId: getUser
+      parameters:
+        - name: id
+          in: path
+        - name: q
+          in: body
+      responses:
+        "600":
+          content: {}
+  /teams/{teamId}:
+    get:
+      operationId: getUser
+      responses:
+        "200":
+          $ref: "#/components/responses/Missing"
+    fetch: {}
+"##;
+        let analysis = analyze_openapi(yaml);
+        assert!(!analysis.valid);
+        let messages = messages(&analysis);
+        for expected in [
+            "Missing required field 'version'",
+            "Path 'users/{id}' must start with '/'",
+            "Path parameter 'id' must be required",
+            "Invalid parameter location 'body'; expected query, header, path, or cookie",
+            "Invalid response status code '600'",
+            "Missing required field 'description'",
+            "Duplicate operationId 'getUser'",
+            "Path parameter 'teamId' of '/teams/{teamId}' is not declared",
+            "Cannot resolve $ref '#/components/responses/Missing'",
+            "Unknown field 'fetch' in path item",
+        ] {
+            assert!(
+                messages.contains(&expected),
+                "{expected} not in {messages:?}"
+            );
+        }
+        let duplicate = analysis
+            .errors
+            .iter()
+            .find(|e| e.message.starts_with("Duplicate operationId"))
+            .unwrap();
+        assert_eq!(
+            duplicate.path.as_deref(),
+            Some("$.paths./teams/{teamId}.get")
+        );
+    }
+
+    #[test]
+    fn test_not_openapi() {
+        let swagger = analyze_openapi(
+            r#"{"swagger": "2.0", "info": {"title": "t", "version": "1"}, "paths": {}}"#,
+        );
+        assert!(messages(&swagger)[0].starts_with("Swagger 2.0 documents are not supported"));
+
+        let broken = analyze_openapi(r#"{"openapi": "3.0.0", "info": }"#);
+        assert!(!broken.valid);
+        assert!(broken.errors[0].range.is_some());
+
+        let list = analyze_openapi("- a\n- b\n");
+        assert_eq!(messages(&list), ["An OpenAPI document must be an object"]);
+        assert!(list.tree.is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! Parallel tree building for documents that are one large array
+//!
+//! Exports and logs are often a single top-level array of similar items.
+//! Above [`PARALLEL_THRESHOLD`] the JSON and YAML parsers split such an
+//! array into its items, parse each item as a separate fragment on the
+//! rayon pool, and shift the fragment's positions back into the document.
+//! Items are independent, so the tree is the one a sequential parse would
+//! build; whenever that is not guaranteed (a parse error, a YAML alias)
+//! the parsers fall back to the sequential path.
+
+use rayon::prelude::*;
+use tokio_util::sync::CancellationToken;
+
+use super::{AstNode, AstPosition};
+
+/// Documents smaller than this are parsed sequentially; splitting costs
+/// more than it saves on small inputs.
+pub const PARALLEL_THRESHOLD: usize = 1024 * 1024;
+
+/// Item of a top-level array: its source slice and where it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemSpan {
+    pub start: AstPosition,
+    /// Byte offset just past the item
+    pub end: usize,
+}
+
+/// Build every item with `build`, in parallel. `build` receives the item
+/// index and its span; `None` from any item, or `cancel` being cancelled
+/// before an item starts, abandons the whole batch.
+pub fn build_items<F>(
+    spans: &[ItemSpan],
+    cancel: Option<&CancellationToken>,
+    build: F,
+) -> Option<Vec<AstNode>>
+where
+    F: Fn(usize, ItemSpan) -> Option<AstNode> + Sync,
+{
+    spans
+        .par_iter()
+        .enumerate()
+        .map(|(index, span)| {
+            if cancel.is_some_and(CancellationToken::is_cancelled) {
+                return None;
+            }
+            build(index, *span)
+        })
+        .collect()
+}
+
+/// Move `node`, parsed from a fragment starting at `base`, to its place
+/// in the document. Only the fragment's first line shares a line with
+/// earlier text, so only positions there gain a column offset.
+pub fn shift_node(node: &mut AstNode, base: AstPosition) {
+    shift_position(&mut node.range.start, base);
+    shift_position(&mut node.range.end, base);
+    for child in node.children.iter_mut().flatten() {
+        shift_node(child, base);
+    }
+}
+
+const fn shift_position(position: &mut AstPosition, base: AstPosition) {
+    if position.line == 1 {
+        position.column += base.column - 1;
+        position.utf16_column += base.utf16_column - 1;
+    }
+    position.line += base.line - 1;
+    position.offset += base.offset;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{AstNodeType, AstRange};
+    use super::*;
+
+    #[test]
+    fn test_shift_node() {
+        let mut node = AstNode::new(
+            AstNodeType::Array,
+            "$[1]".to_string(),
+            "[] (1 items)".to_string(),
+            AstRange::new(AstPosition::new(1, 1, 0), AstPosition::new(2, 2, 6)),
+        )
+        .with_children(vec![AstNode::new(
+            AstNodeType::Number,
+            "$[1][0]".to_string(),
+            "1".to_string(),
+            AstRange::new(AstPosition::new(1, 2, 1), AstPosition::new(1, 3, 2)),
+        )]);
+        shift_node(&mut node, AstPosition::new(3, 5, 20));
+
+        assert_eq!(node.range.start, AstPosition::new(3, 5, 20));
+        assert_eq!(node.range.end, AstPosition::new(4, 2, 26));
+        let child = &node.children.as_ref().unwrap()[0];
+        assert_eq!(child.range.start, AstPosition::new(3, 6, 21));
+    }
+}
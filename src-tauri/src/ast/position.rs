@@ -0,0 +1,205 @@
+//! Column units of AST positions
+//!
+//! Parsers report columns in whatever unit their library counts: bytes for
+//! most, characters for roxmltree and yaml-rust2. Monaco counts UTF-16 code
+//! units, so highlights drift on lines with non-ASCII text. [`normalize`]
+//! rewrites every position of a parse result from its offset, leaving
+//! `column` in UTF-8 bytes and `utf16_column` in UTF-16 code units.
+
+use serde::{Deserialize, Serialize};
+
+use super::{AstParseResult, AstPosition, AstRange};
+
+/// Bytes between the checkpoints of [`LineIndex`]
+const CHECKPOINT_STEP: usize = 256;
+
+/// Unit of the offsets and columns a caller exchanges with the backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionEncoding {
+    /// UTF-8 bytes, the unit of the backend
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    /// UTF-16 code units, the unit of JavaScript strings and Monaco
+    #[serde(rename = "utf-16")]
+    Utf16,
+}
+
+/// Byte offset of `offset`, counted in `encoding`, in `text`
+pub fn byte_offset(text: &str, offset: usize, encoding: PositionEncoding) -> usize {
+    match encoding {
+        PositionEncoding::Utf8 => offset.min(text.len()),
+        PositionEncoding::Utf16 => LineIndex::new(text).utf16_to_byte(offset),
+    }
+}
+
+/// Rewrite the positions of `result`, parsed from `text`, so both columns
+/// are counted from the offsets. A position without an offset, or with
+/// one off its line, is placed by its line and its column counted in
+/// characters.
+pub(super) fn normalize(text: &str, mut result: AstParseResult) -> AstParseResult {
+    let index = LineIndex::new(text);
+    let fix = |range: &mut AstRange| {
+        range.start = index.normalize(range.start);
+        range.end = index.normalize(range.end);
+    };
+    let mut stack: Vec<_> = result.ast.iter_mut().collect();
+    while let Some(node) = stack.pop() {
+        fix(&mut node.range);
+        stack.extend(node.children.iter_mut().flatten());
+    }
+    for error in &mut result.errors {
+        error.range.iter_mut().for_each(fix);
+        error.related_ranges.iter_mut().for_each(fix);
+    }
+    result
+}
+
+/// Line starts of a text, with UTF-16 counts at regular byte offsets so
+/// columns on long lines are found without counting from the line start
+pub(super) struct LineIndex<'a> {
+    text: &'a str,
+    /// Offset of the start of each line
+    lines: Vec<usize>,
+    /// First char boundary at or after every [`CHECKPOINT_STEP`]-th byte,
+    /// with the UTF-16 code units before it; empty for ASCII text, where
+    /// both units agree
+    checkpoints: Vec<(usize, usize)>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(super) fn new(text: &'a str) -> Self {
+        let lines = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut checkpoints = Vec::new();
+        if !text.is_ascii() {
+            checkpoints.reserve(text.len() / CHECKPOINT_STEP + 1);
+            let mut units = 0;
+            for (i, c) in text.char_indices() {
+                while checkpoints.len() * CHECKPOINT_STEP <= i {
+                    checkpoints.push((i, units));
+                }
+                units += c.len_utf16();
+            }
+            while checkpoints.len() * CHECKPOINT_STEP <= text.len() {
+                checkpoints.push((text.len(), units));
+            }
+        }
+        Self {
+            text,
+            lines,
+            checkpoints,
+        }
+    }
+
+    /// Position at byte `offset`, moved back to a char boundary
+    pub(super) fn position(&self, offset: usize) -> AstPosition {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.lines.partition_point(|&start| start <= offset);
+        let start = self.lines[line - 1];
+        AstPosition::new(line, offset - start + 1, offset)
+            .with_utf16_column(self.utf16_units(offset) - self.utf16_units(start) + 1)
+    }
+
+    fn normalize(&self, position: AstPosition) -> AstPosition {
+        let Some(&start) = position
+            .line
+            .checked_sub(1)
+            .and_then(|line| self.lines.get(line))
+        else {
+            return position;
+        };
+        let end = self
+            .lines
+            .get(position.line)
+            .map_or(self.text.len(), |&next| next - 1);
+        // Parsers without offsets leave them at 0.
+        let has_offset = position.offset > 0 || position.column == 1;
+        if has_offset && (start..=end).contains(&position.offset) {
+            return self.position(position.offset);
+        }
+        let line = &self.text[start..end];
+        let column = line
+            .char_indices()
+            .nth(position.column.saturating_sub(1))
+            .map_or(line.len(), |(i, _)| i);
+        self.position(start + column)
+    }
+
+    /// UTF-16 code units before byte `offset`, a char boundary
+    fn utf16_units(&self, offset: usize) -> usize {
+        let Some(&(at, units)) = self.checkpoints.get(offset / CHECKPOINT_STEP) else {
+            return offset;
+        };
+        units + self.text[at..offset].encode_utf16().count()
+    }
+
+    /// Byte offset `units` UTF-16 code units into the text; within a
+    /// surrogate pair, the offset of its char
+    fn utf16_to_byte(&self, units: usize) -> usize {
+        if self.checkpoints.is_empty() {
+            return units.min(self.text.len());
+        }
+        let block = self
+            .checkpoints
+            .partition_point(|&(_, before)| before <= units)
+            .saturating_sub(1);
+        let (mut offset, mut before) = self.checkpoints[block];
+        for c in self.text[offset..].chars() {
+            if before + c.len_utf16() > units {
+                break;
+            }
+            before += c.len_utf16();
+            offset += c.len_utf8();
+        }
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_to_ast, AstLanguage};
+    use super::*;
+
+    #[test]
+    fn test_columns_in_both_units() {
+        let text = "{\"名前\": \"😀\", \"a\": 1}";
+        let result = parse_to_ast(text, AstLanguage::Json);
+        let children = result.ast.unwrap().children.unwrap();
+        let a = children[1].range.start;
+        assert_eq!(
+            (a.line, a.column, a.utf16_column, a.offset),
+            (1, 20, 14, 19)
+        );
+        let emoji = children[0].range.end;
+        assert_eq!((emoji.column, emoji.utf16_column), (18, 12));
+    }
+
+    #[test]
+    fn test_positions_without_offsets_use_character_columns() {
+        // roxmltree reports error columns in characters, with no offset.
+        let xml = "<a>\n  <é></b>\n</a>";
+        let result = parse_to_ast(xml, AstLanguage::Xml);
+        let start = result.errors[0].range.unwrap().start;
+        assert_eq!(start.line, 2);
+        assert_eq!(&xml[start.offset..start.offset + 2], "</");
+        assert_eq!(start.column, start.utf16_column + 1);
+    }
+
+    #[test]
+    fn test_byte_offset() {
+        let text = "a\u{e9}\u{1F600}b\n".repeat(100);
+        let index = LineIndex::new(&text);
+        for (byte, _) in text.char_indices() {
+            let units = text[..byte].encode_utf16().count();
+            assert_eq!(byte_offset(&text, units, PositionEncoding::Utf16), byte);
+            assert_eq!(index.utf16_units(byte), units);
+        }
+        assert_eq!(byte_offset(&text, 3, PositionEncoding::Utf16), 3);
+        assert_eq!(byte_offset(&text, 9999, PositionEncoding::Utf8), text.len());
+    }
+}
@@ -0,0 +1,339 @@
+//! Document statistics computed over the parsed AST
+//!
+//! Every node of the tree is counted by type. Strings and numbers are the
+//! scalar values of leaf nodes whatever their node type, so XML text and
+//! attributes and INI values are profiled alongside JSON and YAML scalars.
+//! Depth counts nodes from the root (depth 1) as the tree view shows them.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use super::{parse_to_ast, AstLanguage, AstNode, AstNodeType, AstParseError, AstRange};
+
+/// Containers reported in each of the largest-array and largest-object lists
+const LARGEST_LIMIT: usize = 10;
+
+/// Distinct strings are tracked until this many strings have been seen
+const DISTINCT_LIMIT: usize = 100_000;
+
+/// Upper bounds (inclusive) of the string length buckets; the last bucket
+/// holds everything longer
+const LENGTH_BOUNDS: [usize; 5] = [0, 8, 32, 128, 1024];
+
+/// Array, object, or element with its number of children
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerSize {
+    pub path: String,
+    pub node_type: AstNodeType,
+    /// Items, members, or child nodes
+    pub size: usize,
+    pub range: AstRange,
+}
+
+/// Number of strings whose length falls in `min..=max`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthBucket {
+    pub min: usize,
+    /// Absent for the open-ended last bucket
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<usize>,
+    pub count: usize,
+}
+
+/// String values; lengths are in characters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StringStats {
+    pub count: usize,
+    /// Sum of all lengths, for the average
+    pub total_length: usize,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// Distinct values, up to the first 100,000 strings
+    pub distinct: usize,
+    pub length_buckets: Vec<LengthBucket>,
+}
+
+/// Numeric values
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberStats {
+    pub count: usize,
+    pub integers: usize,
+    pub floats: usize,
+    pub negative: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+}
+
+/// Result of [`profile_document`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentProfile {
+    /// Size of the text in bytes
+    pub total_bytes: usize,
+    pub total_lines: usize,
+    pub total_nodes: usize,
+    /// Node count per node type
+    pub node_counts: BTreeMap<&'static str, usize>,
+    pub max_depth: usize,
+    /// Path of the first node at `max_depth`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deepest_path: Option<String>,
+    /// Arrays with the most items, largest first (at most 10)
+    pub largest_arrays: Vec<ContainerSize>,
+    /// Objects and XML elements with the most children, largest first
+    /// (at most 10)
+    pub largest_objects: Vec<ContainerSize>,
+    pub strings: StringStats,
+    pub numbers: NumberStats,
+    /// Parse errors. Documents that parse with errors are profiled as far
+    /// as their partial tree goes.
+    pub errors: Vec<AstParseError>,
+}
+
+/// Profile the structure and values of `text`
+pub fn profile_document(text: &str, language: AstLanguage) -> DocumentProfile {
+    let parsed = parse_to_ast(text, language);
+    let mut profiler = Profiler::default();
+    if let Some(root) = &parsed.ast {
+        profiler.visit(root);
+    }
+    profiler.finish(text, parsed.errors)
+}
+
+#[derive(Default)]
+struct Profiler<'a> {
+    total_nodes: usize,
+    node_counts: BTreeMap<&'static str, usize>,
+    max_depth: usize,
+    deepest_path: Option<&'a str>,
+    largest_arrays: Vec<&'a AstNode>,
+    largest_objects: Vec<&'a AstNode>,
+    string_lengths: Vec<usize>,
+    distinct_strings: HashSet<&'a str>,
+    numbers: NumberStats,
+    number_sum: f64,
+}
+
+impl<'a> Profiler<'a> {
+    /// Walk the tree with an explicit stack so deeply nested payloads
+    /// cannot overflow the call stack
+    fn visit(&mut self, root: &'a AstNode) {
+        let mut stack = vec![(root, 1)];
+        while let Some((node, depth)) = stack.pop() {
+            self.total_nodes += 1;
+            *self.node_counts.entry(node.node_type.as_str()).or_insert(0) += 1;
+            if depth > self.max_depth {
+                self.max_depth = depth;
+                self.deepest_path = Some(node.path.as_str());
+            }
+            let Some(children) = &node.children else {
+                self.scalar(node);
+                continue;
+            };
+            match node.node_type {
+                AstNodeType::Array => keep_largest(&mut self.largest_arrays, node),
+                AstNodeType::Object | AstNodeType::Element => {
+                    keep_largest(&mut self.largest_objects, node);
+                }
+                _ => {}
+            }
+            stack.extend(children.iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+
+    fn scalar(&mut self, node: &'a AstNode) {
+        match &node.value {
+            Some(serde_json::Value::String(s)) => {
+                self.string_lengths.push(s.chars().count());
+                if self.string_lengths.len() <= DISTINCT_LIMIT {
+                    self.distinct_strings.insert(s.as_str());
+                }
+            }
+            Some(serde_json::Value::Number(n)) => {
+                let numbers = &mut self.numbers;
+                numbers.count += 1;
+                if n.is_f64() {
+                    numbers.floats += 1;
+                } else {
+                    numbers.integers += 1;
+                }
+                let value = n.as_f64().unwrap_or_default();
+                if value < 0.0 {
+                    numbers.negative += 1;
+                }
+                numbers.min = Some(numbers.min.map_or(value, |min| min.min(value)));
+                numbers.max = Some(numbers.max.map_or(value, |max| max.max(value)));
+                self.number_sum += value;
+            }
+            _ => {}
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(mut self, text: &str, errors: Vec<AstParseError>) -> DocumentProfile {
+        if self.numbers.count > 0 {
+            self.numbers.mean = Some(self.number_sum / self.numbers.count as f64);
+        }
+        DocumentProfile {
+            total_bytes: text.len(),
+            total_lines: text.lines().count(),
+            total_nodes: self.total_nodes,
+            node_counts: self.node_counts,
+            max_depth: self.max_depth,
+            deepest_path: self.deepest_path.map(str::to_string),
+            largest_arrays: containers(&self.largest_arrays),
+            largest_objects: containers(&self.largest_objects),
+            strings: string_stats(&self.string_lengths, self.distinct_strings.len()),
+            numbers: self.numbers,
+            errors,
+        }
+    }
+}
+
+fn child_count(node: &AstNode) -> usize {
+    node.children.as_ref().map_or(0, Vec::len)
+}
+
+/// Insert `node` into `largest`, kept sorted by size (largest first) and
+/// at most [`LARGEST_LIMIT`] long; ties keep document order
+fn keep_largest<'a>(largest: &mut Vec<&'a AstNode>, node: &'a AstNode) {
+    let size = child_count(node);
+    let index = largest.partition_point(|other| child_count(other) >= size);
+    if index < LARGEST_LIMIT {
+        largest.insert(index, node);
+        largest.truncate(LARGEST_LIMIT);
+    }
+}
+
+fn containers(nodes: &[&AstNode]) -> Vec<ContainerSize> {
+    nodes
+        .iter()
+        .map(|node| ContainerSize {
+            path: node.path.clone(),
+            node_type: node.node_type.clone(),
+            size: child_count(node),
+            range: node.range,
+        })
+        .collect()
+}
+
+fn string_stats(lengths: &[usize], distinct: usize) -> StringStats {
+    let mut length_buckets: Vec<LengthBucket> = LENGTH_BOUNDS
+        .iter()
+        .enumerate()
+        .map(|(i, &max)| LengthBucket {
+            min: if i == 0 { 0 } else { LENGTH_BOUNDS[i - 1] + 1 },
+            max: Some(max),
+            count: 0,
+        })
+        .collect();
+    length_buckets.push(LengthBucket {
+        min: LENGTH_BOUNDS[LENGTH_BOUNDS.len() - 1] + 1,
+        max: None,
+        count: 0,
+    });
+    for &length in lengths {
+        let bucket = LENGTH_BOUNDS.partition_point(|&max| max < length);
+        length_buckets[bucket].count += 1;
+    }
+    StringStats {
+        count: lengths.len(),
+        total_length: lengths.iter().sum(),
+        min_length: lengths.iter().min().copied(),
+        max_length: lengths.iter().max().copied(),
+        distinct,
+        length_buckets,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{
+  "name": "kogu",
+  "tags": ["a", "b", "a", ""],
+  "scores": [1, -2, 3.5],
+  "owner": {"id": 7, "nested": {"deep": [true, null]}}
+}"#;
+
+    #[test]
+    fn test_counts_and_size() {
+        let profile = profile_document(JSON, AstLanguage::Json);
+        assert!(profile.errors.is_empty());
+        assert_eq!(profile.total_bytes, JSON.len());
+        assert_eq!(profile.total_lines, 6);
+        assert_eq!(profile.node_counts.get("array"), Some(&3));
+        assert_eq!(profile.node_counts.get("boolean"), Some(&1));
+        assert_eq!(profile.node_counts.get("null"), Some(&1));
+        assert_eq!(
+            profile.total_nodes,
+            profile.node_counts.values().sum::<usize>()
+        );
+        assert_eq!(
+            profile.deepest_path.as_deref(),
+            Some("$.owner.nested.deep[0]")
+        );
+    }
+
+    #[test]
+    fn test_largest_containers() {
+        let profile = profile_document(JSON, AstLanguage::Json);
+        let arrays: Vec<(&str, usize)> = profile
+            .largest_arrays
+            .iter()
+            .map(|c| (c.path.as_str(), c.size))
+            .collect();
+        assert_eq!(
+            arrays,
+            [("$.tags", 4), ("$.scores", 3), ("$.owner.nested.deep", 2)]
+        );
+        assert_eq!(profile.largest_objects[0].path, "$");
+        assert_eq!(profile.largest_objects[0].size, 4);
+    }
+
+    #[test]
+    fn test_value_distribution() {
+        let profile = profile_document(JSON, AstLanguage::Json);
+        let strings = &profile.strings;
+        assert_eq!(strings.count, 5);
+        assert_eq!(strings.total_length, 7);
+        assert_eq!((strings.min_length, strings.max_length), (Some(0), Some(4)));
+        assert_eq!(strings.distinct, 4);
+        assert_eq!(strings.length_buckets[0].count, 1);
+        assert_eq!(strings.length_buckets[1].count, 4);
+        assert_eq!(strings.length_buckets.last().unwrap().max, None);
+
+        let numbers = &profile.numbers;
+        assert_eq!(
+            (numbers.integers, numbers.floats, numbers.negative),
+            (3, 1, 1)
+        );
+        assert_eq!((numbers.min, numbers.max), (Some(-2.0), Some(7.0)));
+        assert_eq!(numbers.mean, Some(2.375));
+    }
+
+    #[test]
+    fn test_xml_and_errors() {
+        let xml = r#"<items><item id="1">x</item><item id="2"/><item/></items>"#;
+        let profile = profile_document(xml, AstLanguage::Xml);
+        assert_eq!(profile.largest_objects[0].path, "$.items");
+        assert_eq!(profile.largest_objects[0].size, 3);
+        assert_eq!(profile.numbers.count, 0);
+        assert_eq!(profile.strings.count, 3);
+
+        let broken = profile_document(r#"{"a": [1, 2], "b": }"#, AstLanguage::Json);
+        assert_eq!(broken.errors.len(), 1);
+        assert_eq!(broken.numbers.count, 2);
+
+        let empty = profile_document("", AstLanguage::Yaml);
+        assert_eq!(empty.numbers.mean, None);
+        assert_eq!(empty.strings.min_length, None);
+    }
+}
@@ -0,0 +1,1025 @@
+//! Protocol Buffers (.proto) AST parser with position tracking
+//!
+//! Hand-written recursive descent over proto2/proto3/editions syntax.
+//! Definitions map onto the unified node types:
+//!
+//! - `message`, `enum`, `service`, `oneof`, `extend` → [`AstNodeType::Object`]
+//! - fields and enum values → [`AstNodeType::Property`] (value is the number)
+//! - `rpc` → [`AstNodeType::Function`]
+//! - `option` and `[...]` field options → [`AstNodeType::Attribute`]
+//! - `syntax`, `package`, `import`, `reserved`, … → [`AstNodeType::Statement`]
+//!
+//! Paths follow declaration names (`$.User.address.city`), so a field's
+//! path is its qualified name within the file.
+
+use super::{child_path, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+
+/// Parse .proto text to AST with position information
+pub fn parse(text: &str) -> AstParseResult {
+    let result = tokenize(text).and_then(|tokens| ProtoParser::new(text, tokens).parse_file());
+    match result {
+        Ok(ast) => AstParseResult::success(ast),
+        Err(e) => AstParseResult::failure(vec![e]),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident,
+    Int,
+    Float,
+    /// String literal with escapes resolved
+    Str(String),
+    Symbol(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn error_at(text: &str, start: usize, end: usize, message: impl Into<String>) -> AstParseError {
+    AstParseError::new(message).with_range(AstRange::from_offset(text, start, end))
+}
+
+/// Split `text` into tokens, dropping whitespace and comments.
+///
+/// Scans bytes: every byte the grammar cares about is ASCII, and UTF-8
+/// continuation bytes never match an ASCII pattern, so offsets stay on
+/// character boundaries.
+fn tokenize(text: &str) -> Result<Vec<Token>, AstParseError> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        let start = pos;
+        let next = bytes.get(pos + 1).copied();
+
+        if c.is_ascii_whitespace() {
+            pos += 1;
+        } else if c == b'/' && next == Some(b'/') {
+            while pos < bytes.len() && bytes[pos] != b'\n' {
+                pos += 1;
+            }
+        } else if c == b'/' && next == Some(b'*') {
+            let close = text[pos + 2..]
+                .find("*/")
+                .ok_or_else(|| error_at(text, start, start + 2, "Unterminated block comment"))?;
+            pos += 2 + close + 2;
+        } else if c.is_ascii_alphabetic()
+            || c == b'_'
+            || (c == b'.' && next.is_some_and(|n| n.is_ascii_alphabetic() || n == b'_'))
+        {
+            // Dotted names (`google.protobuf.Timestamp`, `.pkg.Type`) are one token.
+            pos += 1;
+            while pos < bytes.len()
+                && (bytes[pos].is_ascii_alphanumeric() || matches!(bytes[pos], b'_' | b'.'))
+            {
+                pos += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Ident,
+                start,
+                end: pos,
+            });
+        } else if c.is_ascii_digit() || (c == b'.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            let hex = c == b'0' && matches!(next, Some(b'x' | b'X'));
+            let mut float = c == b'.';
+            pos += 1;
+            while pos < bytes.len() {
+                let b = bytes[pos];
+                let exponent_sign =
+                    !hex && matches!(b, b'+' | b'-') && matches!(bytes[pos - 1], b'e' | b'E');
+                if b.is_ascii_alphanumeric() || b == b'.' || exponent_sign {
+                    float |= b == b'.' || (!hex && matches!(b, b'e' | b'E'));
+                    pos += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token {
+                kind: if float {
+                    TokenKind::Float
+                } else {
+                    TokenKind::Int
+                },
+                start,
+                end: pos,
+            });
+        } else if c == b'"' || c == b'\'' {
+            let (value, end) = lex_string(text, start)?;
+            pos = end;
+            tokens.push(Token {
+                kind: TokenKind::Str(value),
+                start,
+                end,
+            });
+        } else if b"=;{}[]()<>,:-+".contains(&c) {
+            pos += 1;
+            tokens.push(Token {
+                kind: TokenKind::Symbol(char::from(c)),
+                start,
+                end: pos,
+            });
+        } else {
+            let ch = text[start..].chars().next().unwrap_or_default();
+            return Err(error_at(
+                text,
+                start,
+                start + ch.len_utf8(),
+                format!("Unexpected character '{ch}'"),
+            ));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Read the string literal opening at `start`; returns its value and the
+/// offset just past the closing quote.
+fn lex_string(text: &str, start: usize) -> Result<(String, usize), AstParseError> {
+    let mut chars = text[start..].char_indices();
+    let (_, quote) = chars.next().unwrap_or((0, '"'));
+    let mut value = String::new();
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            c if c == quote => return Ok((value, start + i + 1)),
+            '\n' => break,
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 'r')) => value.push('\r'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, '0')) => value.push('\0'),
+                Some((_, escaped)) => value.push(escaped),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+
+    Err(error_at(
+        text,
+        start,
+        start + 1,
+        "Unterminated string literal",
+    ))
+}
+
+/// `{path}.{key}[i]`, where `i` counts the `key` statements already in
+/// `siblings` (imports, reserved ranges, ...).
+fn indexed_path(path: &str, key: &str, siblings: &[AstNode]) -> String {
+    let prefix = format!("{path}.{key}[");
+    let index = siblings
+        .iter()
+        .filter(|node| node.path.starts_with(&prefix))
+        .count();
+    format!("{prefix}{index}]")
+}
+
+struct ProtoParser<'a> {
+    text: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> ProtoParser<'a> {
+    const fn new(text: &'a str, tokens: Vec<Token>) -> Self {
+        Self {
+            text,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_is(&self, word: &str) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == TokenKind::Ident && self.source(t) == word)
+    }
+
+    fn peek_symbol(&self, symbol: char) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == TokenKind::Symbol(symbol))
+    }
+
+    fn source(&self, token: &Token) -> &'a str {
+        &self.text[token.start..token.end]
+    }
+
+    fn range(&self, start: usize, end: usize) -> AstRange {
+        AstRange::from_offset(self.text, start, end)
+    }
+
+    /// Offset just past the last consumed token.
+    fn last_end(&self) -> usize {
+        self.pos
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map_or(0, |t| t.end)
+    }
+
+    fn error(&self, expected: &str) -> AstParseError {
+        let end = self.text.len();
+        self.peek().map_or_else(
+            || {
+                error_at(
+                    self.text,
+                    end,
+                    end,
+                    format!("Expected {expected}, found end of input"),
+                )
+            },
+            |token| {
+                error_at(
+                    self.text,
+                    token.start,
+                    token.end,
+                    format!("Expected {expected}, found '{}'", self.source(token)),
+                )
+            },
+        )
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token, AstParseError> {
+        let token = self.peek().cloned().ok_or_else(|| self.error(expected))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat_symbol(&mut self, symbol: char) -> bool {
+        let matched = self.peek_symbol(symbol);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<Token, AstParseError> {
+        if self.peek_symbol(symbol) {
+            self.next("")
+        } else {
+            Err(self.error(&format!("'{symbol}'")))
+        }
+    }
+
+    fn expect_ident(&mut self, expected: &str) -> Result<&'a str, AstParseError> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Ident => {
+                let word = self.source(token);
+                self.pos += 1;
+                Ok(word)
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), AstParseError> {
+        if self.peek_is(word) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{word}'")))
+        }
+    }
+
+    fn expect_string(&mut self, expected: &str) -> Result<String, AstParseError> {
+        match self.peek().map(|t| t.kind.clone()) {
+            Some(TokenKind::Str(mut value)) => {
+                self.pos += 1;
+                // Adjacent literals concatenate.
+                while let Some(TokenKind::Str(more)) = self.peek().map(|t| t.kind.clone()) {
+                    value.push_str(&more);
+                    self.pos += 1;
+                }
+                Ok(value)
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    fn expect_int(&mut self) -> Result<serde_json::Value, AstParseError> {
+        let negative = self.eat_symbol('-');
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Int => {
+                let token = token.clone();
+                self.pos += 1;
+                Ok(int_value(self.source(&token), negative)
+                    .unwrap_or_else(|| serde_json::json!(self.source(&token))))
+            }
+            _ => Err(self.error("integer")),
+        }
+    }
+
+    fn parse_file(&mut self) -> Result<AstNode, AstParseError> {
+        let mut children = Vec::new();
+        while let Some(token) = self.peek() {
+            let start = token.start;
+            let node = match self.source(token) {
+                "syntax" | "edition" => self.parse_syntax(start)?,
+                "package" => self.parse_package(start)?,
+                "import" => self.parse_import(start, &children)?,
+                "option" => self.parse_option(start, "$")?,
+                "message" => self.parse_message(start, "$")?,
+                "enum" => self.parse_enum(start, "$")?,
+                "service" => self.parse_service(start, "$")?,
+                "extend" => self.parse_extend(start, "$", &children)?,
+                ";" => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => return Err(self.error("a top-level definition")),
+            };
+            children.push(node);
+        }
+
+        let label = format!("Proto ({} definitions)", children.len());
+        Ok(AstNode::new(
+            AstNodeType::Root,
+            "$".to_string(),
+            label,
+            self.range(0, self.text.len()),
+        )
+        .with_children(children))
+    }
+
+    fn parse_syntax(&mut self, start: usize) -> Result<AstNode, AstParseError> {
+        let keyword = self.expect_ident("'syntax' or 'edition'")?;
+        self.expect_symbol('=')?;
+        let version = self.expect_string("a version string")?;
+        let end = self.expect_symbol(';')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Statement,
+            format!("$.{keyword}"),
+            format!("{keyword} = \"{version}\""),
+            self.range(start, end),
+        )
+        .with_value(serde_json::Value::String(version)))
+    }
+
+    fn parse_package(&mut self, start: usize) -> Result<AstNode, AstParseError> {
+        self.expect_word("package")?;
+        let name = self.expect_ident("a package name")?;
+        let end = self.expect_symbol(';')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Statement,
+            "$.package".to_string(),
+            format!("package {name}"),
+            self.range(start, end),
+        )
+        .with_value(serde_json::json!(name)))
+    }
+
+    fn parse_import(
+        &mut self,
+        start: usize,
+        siblings: &[AstNode],
+    ) -> Result<AstNode, AstParseError> {
+        self.expect_word("import")?;
+        let modifier = if self.peek_is("public") || self.peek_is("weak") {
+            self.expect_ident("").map(|word| format!("{word} "))?
+        } else {
+            String::new()
+        };
+        let file = self.expect_string("an import path")?;
+        let end = self.expect_symbol(';')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Statement,
+            indexed_path("$", "import", siblings),
+            format!("import {modifier}\"{file}\""),
+            self.range(start, end),
+        )
+        .with_value(serde_json::Value::String(file)))
+    }
+
+    /// `option name = constant;`
+    fn parse_option(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        self.expect_word("option")?;
+        let option = self.parse_option_assignment(path)?;
+        let end = self.expect_symbol(';')?.end;
+        Ok(AstNode {
+            range: self.range(start, end),
+            ..option
+        })
+    }
+
+    /// `name = constant`, shared by `option` statements and `[...]` lists.
+    fn parse_option_assignment(&mut self, path: &str) -> Result<AstNode, AstParseError> {
+        let start = self.peek().map_or(self.text.len(), |t| t.start);
+        let name = self.parse_option_name()?;
+        self.expect_symbol('=')?;
+        let value_start = self.peek().map_or(self.text.len(), |t| t.start);
+        let value = self.parse_constant()?;
+        let end = self.last_end();
+        let label = format!("{name} = {}", &self.text[value_start..end]);
+        Ok(AstNode::new(
+            AstNodeType::Attribute,
+            child_path(&format!("{path}.option"), &name),
+            label,
+            self.range(start, end),
+        )
+        .with_value(value))
+    }
+
+    /// Plain (`java_package`) or custom (`(my.ext).field`) option name.
+    fn parse_option_name(&mut self) -> Result<String, AstParseError> {
+        let mut name = String::new();
+        if self.eat_symbol('(') {
+            name.push('(');
+            name.push_str(self.expect_ident("an extension name")?);
+            self.expect_symbol(')')?;
+            name.push(')');
+            // The suffix lexes as one dotted identifier (`.field.sub`).
+            if let Some(token) = self.peek() {
+                let suffix = self.source(token);
+                if token.kind == TokenKind::Ident && suffix.starts_with('.') {
+                    name.push_str(suffix);
+                    self.pos += 1;
+                }
+            }
+        } else {
+            name.push_str(self.expect_ident("an option name")?);
+        }
+        Ok(name)
+    }
+
+    /// Option value: identifier, signed number, string, or `{ ... }`
+    /// message literal (kept as source text).
+    fn parse_constant(&mut self) -> Result<serde_json::Value, AstParseError> {
+        if self.peek_symbol('{') {
+            let start = self.expect_symbol('{')?.start;
+            let mut depth = 1;
+            while depth > 0 {
+                let token = self.next("'}'")?;
+                match token.kind {
+                    TokenKind::Symbol('{') => depth += 1,
+                    TokenKind::Symbol('}') => depth -= 1,
+                    _ => {}
+                }
+            }
+            return Ok(serde_json::json!(&self.text[start..self.last_end()]));
+        }
+
+        let sign = if self.peek_symbol('-') || self.peek_symbol('+') {
+            self.next("")?.kind == TokenKind::Symbol('-')
+        } else {
+            false
+        };
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| self.error("a constant"))?;
+        let source = self.source(&token);
+        let value = match &token.kind {
+            TokenKind::Str(_) => {
+                return self
+                    .expect_string("a string")
+                    .map(serde_json::Value::String)
+            }
+            TokenKind::Int => int_value(source, sign),
+            TokenKind::Float => source
+                .parse::<f64>()
+                .ok()
+                .map(|v| if sign { -v } else { v })
+                .and_then(serde_json::Number::from_f64)
+                .map(serde_json::Value::Number),
+            TokenKind::Ident if !sign && (source == "true" || source == "false") => {
+                Some(serde_json::Value::Bool(source == "true"))
+            }
+            TokenKind::Ident => None,
+            TokenKind::Symbol(_) => return Err(self.error("a constant")),
+        };
+        self.pos += 1;
+        Ok(value.unwrap_or_else(|| {
+            // Enum names, inf, nan, and out-of-range numbers keep their text.
+            let raw = &self.text[token.start..token.end];
+            serde_json::json!(if sign {
+                format!("-{raw}")
+            } else {
+                raw.to_string()
+            })
+        }))
+    }
+
+    /// Optional `[name = value, ...]` after a field or enum value.
+    fn parse_field_options(&mut self, path: &str) -> Result<Vec<AstNode>, AstParseError> {
+        let mut options = Vec::new();
+        if self.eat_symbol('[') {
+            loop {
+                options.push(self.parse_option_assignment(path)?);
+                if !self.eat_symbol(',') {
+                    break;
+                }
+            }
+            self.expect_symbol(']')?;
+        }
+        Ok(options)
+    }
+
+    fn parse_message(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        self.expect_word("message")?;
+        let name = self.expect_ident("a message name")?;
+        let path = child_path(path, name);
+        let (children, end) = self.parse_message_body(&path)?;
+        Ok(AstNode::new(
+            AstNodeType::Object,
+            path,
+            format!("message {name}"),
+            self.range(start, end),
+        )
+        .with_children(children))
+    }
+
+    /// `{ ... }` of a message or group; returns the items and the offset
+    /// past the closing brace.
+    fn parse_message_body(&mut self, path: &str) -> Result<(Vec<AstNode>, usize), AstParseError> {
+        self.expect_symbol('{')?;
+        let mut children = Vec::new();
+        loop {
+            let Some(token) = self.peek() else {
+                return Err(self.error("'}'"));
+            };
+            let start = token.start;
+            let node = match self.source(token) {
+                "}" => break,
+                ";" => {
+                    self.pos += 1;
+                    continue;
+                }
+                "option" => self.parse_option(start, path)?,
+                "message" => self.parse_message(start, path)?,
+                "enum" => self.parse_enum(start, path)?,
+                "oneof" => self.parse_oneof(start, path)?,
+                "extend" => self.parse_extend(start, path, &children)?,
+                keyword @ ("reserved" | "extensions") => {
+                    self.parse_ranges(start, path, keyword, &children)?
+                }
+                _ => self.parse_field(start, path)?,
+            };
+            children.push(node);
+        }
+        let end = self.expect_symbol('}')?.end;
+        Ok((children, end))
+    }
+
+    /// `[label] type name = number [options];`, including `map<K, V>`
+    /// fields and proto2 groups.
+    fn parse_field(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        let label = if ["optional", "required", "repeated"]
+            .iter()
+            .any(|label| self.peek_is(label))
+        {
+            Some(self.expect_ident("")?)
+        } else {
+            None
+        };
+
+        let field_type = if self.peek_is("map") {
+            self.pos += 1;
+            self.expect_symbol('<')?;
+            let key = self.expect_ident("a map key type")?;
+            self.expect_symbol(',')?;
+            let value = self.expect_ident("a map value type")?;
+            self.expect_symbol('>')?;
+            format!("map<{key}, {value}>")
+        } else {
+            self.expect_ident("a field type")?.to_string()
+        };
+
+        let name = self.expect_ident("a field name")?;
+        self.expect_symbol('=')?;
+        let number = self.expect_int()?;
+        let field_path = child_path(path, name);
+        let mut children = self.parse_field_options(&field_path)?;
+
+        let end = if field_type == "group" {
+            let (body, end) = self.parse_message_body(&field_path)?;
+            children.extend(body);
+            end
+        } else {
+            self.expect_symbol(';')?.end
+        };
+
+        let label = label.map_or_else(String::new, |label| format!("{label} "));
+        Ok(AstNode::new(
+            AstNodeType::Property,
+            field_path,
+            format!("{label}{field_type} {name} = {number}"),
+            self.range(start, end),
+        )
+        .with_value(number)
+        .with_children(children))
+    }
+
+    fn parse_oneof(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        self.expect_word("oneof")?;
+        let name = self.expect_ident("a oneof name")?;
+        let path = child_path(path, name);
+        self.expect_symbol('{')?;
+        let mut children = Vec::new();
+        while !self.peek_symbol('}') {
+            let Some(token) = self.peek() else {
+                return Err(self.error("'}'"));
+            };
+            let item_start = token.start;
+            if self.eat_symbol(';') {
+                continue;
+            }
+            children.push(if self.peek_is("option") {
+                self.parse_option(item_start, &path)?
+            } else {
+                self.parse_field(item_start, &path)?
+            });
+        }
+        let end = self.expect_symbol('}')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Object,
+            path,
+            format!("oneof {name}"),
+            self.range(start, end),
+        )
+        .with_children(children))
+    }
+
+    fn parse_enum(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        self.expect_word("enum")?;
+        let name = self.expect_ident("an enum name")?;
+        let path = child_path(path, name);
+        self.expect_symbol('{')?;
+        let mut children = Vec::new();
+        while !self.peek_symbol('}') {
+            let Some(token) = self.peek() else {
+                return Err(self.error("'}'"));
+            };
+            let item_start = token.start;
+            if self.eat_symbol(';') {
+                continue;
+            }
+            let node = if self.peek_is("option") {
+                self.parse_option(item_start, &path)?
+            } else if self.peek_is("reserved") {
+                self.parse_ranges(item_start, &path, "reserved", &children)?
+            } else {
+                let value_name = self.expect_ident("an enum value name")?;
+                self.expect_symbol('=')?;
+                let number = self.expect_int()?;
+                let value_path = child_path(&path, value_name);
+                let options = self.parse_field_options(&value_path)?;
+                let end = self.expect_symbol(';')?.end;
+                AstNode::new(
+                    AstNodeType::Property,
+                    value_path,
+                    format!("{value_name} = {number}"),
+                    self.range(item_start, end),
+                )
+                .with_value(number)
+                .with_children(options)
+            };
+            children.push(node);
+        }
+        let end = self.expect_symbol('}')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Object,
+            path,
+            format!("enum {name}"),
+            self.range(start, end),
+        )
+        .with_children(children))
+    }
+
+    fn parse_service(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        self.expect_word("service")?;
+        let name = self.expect_ident("a service name")?;
+        let path = child_path(path, name);
+        self.expect_symbol('{')?;
+        let mut children = Vec::new();
+        while !self.peek_symbol('}') {
+            let Some(token) = self.peek() else {
+                return Err(self.error("'}'"));
+            };
+            let item_start = token.start;
+            if self.eat_symbol(';') {
+                continue;
+            }
+            children.push(if self.peek_is("option") {
+                self.parse_option(item_start, &path)?
+            } else {
+                self.parse_rpc(item_start, &path)?
+            });
+        }
+        let end = self.expect_symbol('}')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Object,
+            path,
+            format!("service {name}"),
+            self.range(start, end),
+        )
+        .with_children(children))
+    }
+
+    /// `rpc Name (Request) returns (Response)` followed by `;` or an
+    /// option block.
+    fn parse_rpc(&mut self, start: usize, path: &str) -> Result<AstNode, AstParseError> {
+        self.expect_word("rpc")?;
+        let name = self.expect_ident("an rpc name")?;
+        let request = self.parse_rpc_type()?;
+        self.expect_word("returns")?;
+        let response = self.parse_rpc_type()?;
+        let rpc_path = child_path(path, name);
+
+        let mut options = Vec::new();
+        let end = if self.eat_symbol('{') {
+            while !self.peek_symbol('}') {
+                let Some(token) = self.peek() else {
+                    return Err(self.error("'}'"));
+                };
+                let item_start = token.start;
+                if !self.eat_symbol(';') {
+                    options.push(self.parse_option(item_start, &rpc_path)?);
+                }
+            }
+            self.expect_symbol('}')?.end
+        } else {
+            self.expect_symbol(';')?.end
+        };
+
+        Ok(AstNode::new(
+            AstNodeType::Function,
+            rpc_path,
+            format!("rpc {name}({request}) returns ({response})"),
+            self.range(start, end),
+        )
+        .with_children(options))
+    }
+
+    /// `([stream] Type)`
+    fn parse_rpc_type(&mut self) -> Result<String, AstParseError> {
+        self.expect_symbol('(')?;
+        let mut message = self.expect_ident("a message type")?.to_string();
+        // `stream` is only a keyword when a type follows it.
+        if message == "stream" && !self.peek_symbol(')') {
+            message = format!("stream {}", self.expect_ident("a message type")?);
+        }
+        self.expect_symbol(')')?;
+        Ok(message)
+    }
+
+    fn parse_extend(
+        &mut self,
+        start: usize,
+        path: &str,
+        siblings: &[AstNode],
+    ) -> Result<AstNode, AstParseError> {
+        self.expect_word("extend")?;
+        let extendee = self.expect_ident("a message type")?;
+        let path = indexed_path(path, "extend", siblings);
+        let (children, end) = self.parse_message_body(&path)?;
+        Ok(AstNode::new(
+            AstNodeType::Object,
+            path,
+            format!("extend {extendee}"),
+            self.range(start, end),
+        )
+        .with_children(children))
+    }
+
+    /// `reserved` / `extensions` statements, labelled with their source
+    /// text (`reserved 2, 15, 9 to 11`).
+    fn parse_ranges(
+        &mut self,
+        start: usize,
+        path: &str,
+        keyword: &str,
+        siblings: &[AstNode],
+    ) -> Result<AstNode, AstParseError> {
+        self.expect_word(keyword)?;
+        let mut parts: Vec<String> = Vec::new();
+        let mut negative = false;
+        while !self.peek_symbol(';') {
+            let token = self.next("';'")?;
+            match token.kind {
+                TokenKind::Symbol(',') => {
+                    if let Some(last) = parts.last_mut() {
+                        last.push(',');
+                    }
+                }
+                TokenKind::Symbol('-') => negative = true,
+                TokenKind::Symbol('[') => {
+                    // Trailing `[declaration = ...]` options are not shown.
+                    let mut depth = 1;
+                    while depth > 0 {
+                        match self.next("']'")?.kind {
+                            TokenKind::Symbol('[') => depth += 1,
+                            TokenKind::Symbol(']') => depth -= 1,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {
+                    let sign = if std::mem::take(&mut negative) {
+                        "-"
+                    } else {
+                        ""
+                    };
+                    parts.push(format!("{sign}{}", self.source(&token)));
+                }
+            }
+        }
+        let end = self.expect_symbol(';')?.end;
+        Ok(AstNode::new(
+            AstNodeType::Statement,
+            indexed_path(path, keyword, siblings),
+            format!("{keyword} {}", parts.join(" ")),
+            self.range(start, end),
+        ))
+    }
+}
+
+/// Decimal, hex (`0x1F`), or octal (`017`) integer literal.
+fn int_value(source: &str, negative: bool) -> Option<serde_json::Value> {
+    let hex = source
+        .strip_prefix("0x")
+        .or_else(|| source.strip_prefix("0X"));
+    let (digits, radix) = match hex {
+        Some(hex) => (hex, 16),
+        None if source.len() > 1 && source.starts_with('0') => (&source[1..], 8),
+        None => (source, 10),
+    };
+    let magnitude = u64::from_str_radix(digits, radix).ok()?;
+    if negative {
+        0i64.checked_sub_unsigned(magnitude)
+            .map(serde_json::Value::from)
+    } else {
+        Some(serde_json::Value::from(magnitude))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"syntax = "proto3";
+
+package example.v1;
+
+import "google/protobuf/timestamp.proto";
+option java_package = "com.example.v1";
+
+// A user account.
+message User {
+  reserved 4, 8 to 10;
+  string name = 1;
+  repeated string tags = 2 [deprecated = true, (my.ext).label = "t"];
+  map<string, int32> scores = 3;
+  google.protobuf.Timestamp created_at = 5;
+
+  message Address {
+    string city = 1;
+  }
+
+  oneof contact {
+    string email = 6;
+    string phone = 7;
+  }
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  ACTIVE = 1;
+  LEGACY = -2 [deprecated = true];
+}
+
+service UserService {
+  rpc GetUser (GetUserRequest) returns (User);
+  rpc Watch (stream WatchRequest) returns (stream User) {
+    option idempotency_level = NO_SIDE_EFFECTS;
+  }
+}
+"#;
+
+    fn find<'a>(node: &'a AstNode, path: &str) -> Option<&'a AstNode> {
+        if node.path == path {
+            return Some(node);
+        }
+        node.children
+            .iter()
+            .flatten()
+            .find_map(|child| find(child, path))
+    }
+
+    #[test]
+    fn test_parse_top_level_definitions() {
+        let ast = parse(SAMPLE).ast.unwrap();
+        assert_eq!(ast.node_type, AstNodeType::Root);
+        let labels: Vec<&str> = ast
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "syntax = \"proto3\"",
+                "package example.v1",
+                "import \"google/protobuf/timestamp.proto\"",
+                "java_package = \"com.example.v1\"",
+                "message User",
+                "enum Status",
+                "service UserService",
+            ]
+        );
+        assert_eq!(
+            find(&ast, "$.import[0]").unwrap().value,
+            Some(serde_json::json!("google/protobuf/timestamp.proto"))
+        );
+    }
+
+    #[test]
+    fn test_parse_fields_with_numbers_and_options() {
+        let ast = parse(SAMPLE).ast.unwrap();
+
+        let tags = find(&ast, "$.User.tags").unwrap();
+        assert_eq!(tags.node_type, AstNodeType::Property);
+        assert_eq!(tags.label, "repeated string tags = 2");
+        assert_eq!(tags.value, Some(serde_json::json!(2)));
+        assert_eq!(tags.range.start.line, 12);
+        let options = tags.children.as_ref().unwrap();
+        assert_eq!(options[0].path, "$.User.tags.option.deprecated");
+        assert_eq!(options[0].value, Some(serde_json::json!(true)));
+        assert_eq!(options[1].path, "$.User.tags.option['(my.ext).label']");
+
+        let scores = find(&ast, "$.User.scores").unwrap();
+        assert_eq!(scores.label, "map<string, int32> scores = 3");
+        assert_eq!(
+            find(&ast, "$.User.created_at").unwrap().label,
+            "google.protobuf.Timestamp created_at = 5"
+        );
+        assert_eq!(
+            find(&ast, "$.User.Address.city").unwrap().label,
+            "string city = 1"
+        );
+        assert_eq!(
+            find(&ast, "$.User.contact.phone").unwrap().label,
+            "string phone = 7"
+        );
+        assert_eq!(
+            find(&ast, "$.User.reserved[0]").unwrap().label,
+            "reserved 4, 8 to 10"
+        );
+    }
+
+    #[test]
+    fn test_parse_enums_and_services() {
+        let ast = parse(SAMPLE).ast.unwrap();
+
+        let legacy = find(&ast, "$.Status.LEGACY").unwrap();
+        assert_eq!(legacy.value, Some(serde_json::json!(-2)));
+        assert_eq!(legacy.children.as_ref().unwrap().len(), 1);
+
+        let watch = find(&ast, "$.UserService.Watch").unwrap();
+        assert_eq!(watch.node_type, AstNodeType::Function);
+        assert_eq!(
+            watch.label,
+            "rpc Watch(stream WatchRequest) returns (stream User)"
+        );
+        assert_eq!(
+            find(&ast, "$.UserService.Watch.option.idempotency_level")
+                .unwrap()
+                .value,
+            Some(serde_json::json!("NO_SIDE_EFFECTS"))
+        );
+
+        let service = find(&ast, "$.UserService").unwrap();
+        assert_eq!(service.range.start.line, 32);
+        assert_eq!(service.range.end.line, 37);
+    }
+
+    #[test]
+    fn test_int_value_radix() {
+        assert_eq!(int_value("0x1F", false), Some(serde_json::json!(31)));
+        assert_eq!(int_value("017", false), Some(serde_json::json!(15)));
+        assert_eq!(int_value("0", false), Some(serde_json::json!(0)));
+        assert_eq!(int_value("5", true), Some(serde_json::json!(-5)));
+    }
+
+    #[test]
+    fn test_parse_error_has_range() {
+        let result = parse("message User {\n  string name = ;\n}\n");
+        assert!(result.ast.is_none());
+        let error = &result.errors[0];
+        assert!(error.message.contains("integer"));
+        assert_eq!(error.range.unwrap().start.line, 2);
+
+        let unterminated = parse("message User {\n  string name = 1;\n");
+        assert!(unterminated.errors[0].message.contains("end of input"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Find-in-structure search over parsed AST trees
+//!
+//! A query combines a key name, a value pattern, and node-type filters;
+//! a node matches when it meets every criterion given. Keys are property
+//! names (object members, CSV columns) and XML element and attribute
+//! names; values are the scalar values of leaf nodes.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::{parse_to_ast, AstLanguage, AstNode, AstNodeType, AstParseError, AstRange};
+
+/// Most matches returned; `total` still counts every matching node.
+const MAX_MATCHES: usize = 10_000;
+
+/// Criteria for [`search_ast`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AstSearchQuery {
+    /// Key name to match exactly, or a pattern with `regex`
+    pub key: Option<String>,
+    /// Text to find in scalar values, or a pattern with `regex`
+    pub value: Option<String>,
+    /// Read `key` and `value` as regular expressions
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Node types to match; any type when empty
+    pub node_types: Vec<AstNodeType>,
+}
+
+/// Node matching a search query
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AstSearchMatch {
+    pub path: String,
+    pub node_type: AstNodeType,
+    /// Property, element, or attribute name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    pub range: AstRange,
+}
+
+/// Result of [`search_ast`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AstSearchResult {
+    /// Matching nodes in document order
+    pub matches: Vec<AstSearchMatch>,
+    /// Number of matching nodes; `matches` is capped at 10,000
+    pub total: usize,
+    /// Parse errors and invalid patterns. Documents that parse with
+    /// errors are still searched as far as their partial tree goes.
+    pub errors: Vec<AstParseError>,
+}
+
+/// Search the AST of `text` for nodes matching `query`
+pub fn search_ast(text: &str, language: AstLanguage, query: &AstSearchQuery) -> AstSearchResult {
+    let matcher = match Matcher::new(query) {
+        Ok(matcher) => matcher,
+        Err(error) => {
+            return AstSearchResult {
+                errors: vec![error],
+                ..AstSearchResult::default()
+            }
+        }
+    };
+    let parsed = parse_to_ast(text, language);
+    let mut result = AstSearchResult {
+        errors: parsed.errors,
+        ..AstSearchResult::default()
+    };
+    if let Some(root) = &parsed.ast {
+        let mut search = Search {
+            matcher: &matcher,
+            language,
+            result: &mut result,
+        };
+        search.visit(root, None);
+    }
+    result
+}
+
+/// Compiled form of a query
+struct Matcher<'q> {
+    query: &'q AstSearchQuery,
+    key: Option<Regex>,
+    value: Option<Regex>,
+}
+
+impl<'q> Matcher<'q> {
+    fn new(query: &'q AstSearchQuery) -> Result<Self, AstParseError> {
+        if query.key.is_none() && query.value.is_none() && query.node_types.is_empty() {
+            return Err(AstParseError::new(
+                "Search needs a key, a value, or node types",
+            ));
+        }
+        let compile = |pattern: &Option<String>, what: &str, whole: bool| {
+            pattern
+                .as_deref()
+                .map(|pattern| {
+                    let pattern = if query.regex {
+                        pattern.to_string()
+                    } else if whole {
+                        format!("^{}$", regex::escape(pattern))
+                    } else {
+                        regex::escape(pattern)
+                    };
+                    RegexBuilder::new(&pattern)
+                        .case_insensitive(!query.case_sensitive)
+                        .build()
+                        .map_err(|e| AstParseError::new(format!("Invalid {what} pattern: {e}")))
+                })
+                .transpose()
+        };
+        Ok(Self {
+            query,
+            key: compile(&query.key, "key", true)?,
+            value: compile(&query.value, "value", false)?,
+        })
+    }
+
+    fn matches(&self, node: &AstNode, key: Option<&str>) -> bool {
+        if !self.query.node_types.is_empty() && !self.query.node_types.contains(&node.node_type) {
+            return false;
+        }
+        if let Some(pattern) = &self.key {
+            if !key.is_some_and(|key| pattern.is_match(key)) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.value {
+            let text = match &node.value {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(value) if !value.is_array() && !value.is_object() => value.to_string(),
+                _ => return false,
+            };
+            if !pattern.is_match(&text) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+struct Search<'a> {
+    matcher: &'a Matcher<'a>,
+    language: AstLanguage,
+    result: &'a mut AstSearchResult,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, node: &AstNode, parent: Option<&AstNode>) {
+        let key = self.key(node, parent);
+        if self.matcher.matches(node, key) {
+            self.result.total += 1;
+            if self.result.matches.len() < MAX_MATCHES {
+                self.result.matches.push(AstSearchMatch {
+                    path: node.path.clone(),
+                    node_type: node.node_type.clone(),
+                    key: key.map(ToString::to_string),
+                    value: node.value.clone().filter(|_| node.children.is_none()),
+                    range: node.range,
+                });
+            }
+        }
+        for child in node.children.iter().flatten() {
+            self.visit(child, Some(node));
+        }
+    }
+
+    /// Name a node is known by: its key when it is an object member, its
+    /// name when it is an XML element or attribute
+    fn key<'n>(&self, node: &'n AstNode, parent: Option<&AstNode>) -> Option<&'n str> {
+        match node.node_type {
+            AstNodeType::Element => node
+                .label
+                .strip_prefix('<')
+                .and_then(|label| label.split_once('>'))
+                .map(|(name, _)| name),
+            AstNodeType::Attribute => Some(node.label.trim_start_matches('@')),
+            _ if parent.is_some_and(|parent| parent.node_type == AstNodeType::Object) => {
+                if self.language == AstLanguage::Csv {
+                    // CSV cells are labelled `column: value`.
+                    node.label.split_once(": ").map(|(name, _)| name)
+                } else {
+                    Some(&node.label)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str =
+        r#"{"name": "kogu", "tags": ["json", "Yaml"], "owner": {"name": "Seiji", "id": 7}}"#;
+
+    fn query() -> AstSearchQuery {
+        AstSearchQuery::default()
+    }
+
+    fn paths(result: &AstSearchResult) -> Vec<&str> {
+        result.matches.iter().map(|m| m.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_by_key() {
+        let result = search_ast(
+            JSON,
+            AstLanguage::Json,
+            &AstSearchQuery {
+                key: Some("NAME".to_string()),
+                ..query()
+            },
+        );
+        assert_eq!(paths(&result), ["$.name", "$.owner.name"]);
+        assert_eq!(result.matches[1].key.as_deref(), Some("name"));
+        assert_eq!(result.matches[1].value, Some(serde_json::json!("Seiji")));
+    }
+
+    #[test]
+    fn test_search_by_value_substring_and_case() {
+        let mut search = AstSearchQuery {
+            value: Some("a".to_string()),
+            ..query()
+        };
+        let result = search_ast(JSON, AstLanguage::Json, &search);
+        assert_eq!(paths(&result), ["$.tags[1]"]);
+
+        search.value = Some("y".to_string());
+        search.case_sensitive = true;
+        assert!(search_ast(JSON, AstLanguage::Json, &search)
+            .matches
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_by_regex_and_node_type() {
+        let result = search_ast(
+            JSON,
+            AstLanguage::Json,
+            &AstSearchQuery {
+                value: Some(r"^\d+$".to_string()),
+                regex: true,
+                node_types: vec![AstNodeType::Number],
+                ..query()
+            },
+        );
+        assert_eq!(paths(&result), ["$.owner.id"]);
+
+        let objects = search_ast(
+            JSON,
+            AstLanguage::Json,
+            &AstSearchQuery {
+                node_types: vec![AstNodeType::Object, AstNodeType::Array],
+                ..query()
+            },
+        );
+        assert_eq!(objects.total, 3);
+    }
+
+    #[test]
+    fn test_search_xml_elements_and_attributes() {
+        let xml = r#"<catalog><book id="b1"><title>Rust</title></book><book id="b2"/></catalog>"#;
+        let elements = search_ast(
+            xml,
+            AstLanguage::Xml,
+            &AstSearchQuery {
+                key: Some("book".to_string()),
+                ..query()
+            },
+        );
+        assert_eq!(paths(&elements), ["$.catalog.book[0]", "$.catalog.book[1]"]);
+
+        let attributes = search_ast(
+            xml,
+            AstLanguage::Xml,
+            &AstSearchQuery {
+                key: Some("id".to_string()),
+                value: Some("2".to_string()),
+                ..query()
+            },
+        );
+        assert_eq!(paths(&attributes), ["$.catalog.book[1][@id]"]);
+    }
+
+    #[test]
+    fn test_search_csv_columns() {
+        let result = search_ast(
+            "name,age\nAlice,30\nBob,41\n",
+            AstLanguage::Csv,
+            &AstSearchQuery {
+                key: Some("age".to_string()),
+                ..query()
+            },
+        );
+        assert_eq!(result.total, 2);
+        assert_eq!(result.matches[0].key.as_deref(), Some("age"));
+    }
+
+    #[test]
+    fn test_search_partial_tree_and_errors() {
+        let result = search_ast(
+            r#"{"name": "kogu", "broken": }"#,
+            AstLanguage::Json,
+            &AstSearchQuery {
+                key: Some("name".to_string()),
+                ..query()
+            },
+        );
+        assert_eq!(paths(&result), ["$.name"]);
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        let empty = search_ast(JSON, AstLanguage::Json, &query());
+        assert_eq!(empty.errors.len(), 1);
+
+        let invalid = search_ast(
+            JSON,
+            AstLanguage::Json,
+            &AstSearchQuery {
+                key: Some("(".to_string()),
+                regex: true,
+                ..query()
+            },
+        );
+        assert!(invalid.errors[0].message.starts_with("Invalid key pattern"));
+    }
+}
@@ -0,0 +1,1127 @@
+//! nginx and Apache httpd config AST parsers with position tracking
+//!
+//! Both dialects become trees of directives. A directive with a block
+//! (`server { ... }`, `<VirtualHost *:80> ... </VirtualHost>`) is an
+//! [`AstNodeType::Object`] holding its arguments and nested directives;
+//! any other directive is a [`AstNodeType::Statement`] holding its
+//! arguments as [`AstNodeType::Literal`]s. Paths follow directive names,
+//! indexed when a name repeats among siblings (`$.http.server[1].listen`).
+//!
+//! Directive names missing from the dialect's list of known directives
+//! are reported as errors alongside the complete tree, so a misspelling
+//! shows up without hiding the rest of the file. Entries of nginx `map`,
+//! `types`, and similar blocks are data rather than directives and are
+//! not checked.
+
+use std::collections::HashMap;
+
+use super::{
+    child_path, truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange,
+};
+
+/// Blocks nested deeper than this are rejected, keeping tree building
+/// within the call stack
+const MAX_NESTING: usize = 128;
+
+/// Directives of the nginx core and the modules built by default, plus
+/// the commonly enabled SSL, HTTP/2, real IP, and stub status modules
+const NGINX_DIRECTIVES: &[&str] = &[
+    "absolute_redirect",
+    "accept_mutex",
+    "accept_mutex_delay",
+    "access_log",
+    "add_header",
+    "add_trailer",
+    "aio",
+    "alias",
+    "allow",
+    "auth_basic",
+    "auth_basic_user_file",
+    "auth_request",
+    "auth_request_set",
+    "autoindex",
+    "autoindex_exact_size",
+    "autoindex_format",
+    "autoindex_localtime",
+    "break",
+    "charset",
+    "charset_map",
+    "chunked_transfer_encoding",
+    "client_body_buffer_size",
+    "client_body_temp_path",
+    "client_body_timeout",
+    "client_header_buffer_size",
+    "client_header_timeout",
+    "client_max_body_size",
+    "daemon",
+    "default_type",
+    "deny",
+    "env",
+    "error_log",
+    "error_page",
+    "etag",
+    "events",
+    "expires",
+    "fastcgi_buffer_size",
+    "fastcgi_buffering",
+    "fastcgi_buffers",
+    "fastcgi_cache",
+    "fastcgi_cache_key",
+    "fastcgi_cache_path",
+    "fastcgi_cache_valid",
+    "fastcgi_connect_timeout",
+    "fastcgi_index",
+    "fastcgi_intercept_errors",
+    "fastcgi_param",
+    "fastcgi_pass",
+    "fastcgi_read_timeout",
+    "fastcgi_send_timeout",
+    "fastcgi_split_path_info",
+    "geo",
+    "grpc_pass",
+    "grpc_set_header",
+    "gzip",
+    "gzip_buffers",
+    "gzip_comp_level",
+    "gzip_disable",
+    "gzip_http_version",
+    "gzip_min_length",
+    "gzip_proxied",
+    "gzip_static",
+    "gzip_types",
+    "gzip_vary",
+    "hash",
+    "http",
+    "http2",
+    "http2_max_concurrent_streams",
+    "if",
+    "if_modified_since",
+    "ignore_invalid_headers",
+    "include",
+    "index",
+    "internal",
+    "ip_hash",
+    "keepalive",
+    "keepalive_requests",
+    "keepalive_timeout",
+    "large_client_header_buffers",
+    "least_conn",
+    "limit_conn",
+    "limit_conn_status",
+    "limit_conn_zone",
+    "limit_except",
+    "limit_rate",
+    "limit_rate_after",
+    "limit_req",
+    "limit_req_status",
+    "limit_req_zone",
+    "listen",
+    "load_module",
+    "location",
+    "log_format",
+    "log_not_found",
+    "mail",
+    "map",
+    "map_hash_bucket_size",
+    "map_hash_max_size",
+    "master_process",
+    "max_ranges",
+    "merge_slashes",
+    "mirror",
+    "multi_accept",
+    "open_file_cache",
+    "open_file_cache_errors",
+    "open_file_cache_min_uses",
+    "open_file_cache_valid",
+    "pcre_jit",
+    "pid",
+    "port_in_redirect",
+    "proxy_buffer_size",
+    "proxy_buffering",
+    "proxy_buffers",
+    "proxy_busy_buffers_size",
+    "proxy_cache",
+    "proxy_cache_bypass",
+    "proxy_cache_key",
+    "proxy_cache_lock",
+    "proxy_cache_methods",
+    "proxy_cache_path",
+    "proxy_cache_use_stale",
+    "proxy_cache_valid",
+    "proxy_connect_timeout",
+    "proxy_cookie_domain",
+    "proxy_cookie_path",
+    "proxy_hide_header",
+    "proxy_http_version",
+    "proxy_ignore_headers",
+    "proxy_intercept_errors",
+    "proxy_max_temp_file_size",
+    "proxy_next_upstream",
+    "proxy_no_cache",
+    "proxy_pass",
+    "proxy_pass_header",
+    "proxy_pass_request_body",
+    "proxy_pass_request_headers",
+    "proxy_read_timeout",
+    "proxy_redirect",
+    "proxy_request_buffering",
+    "proxy_send_timeout",
+    "proxy_set_body",
+    "proxy_set_header",
+    "proxy_ssl_server_name",
+    "proxy_ssl_verify",
+    "proxy_temp_path",
+    "real_ip_header",
+    "real_ip_recursive",
+    "recursive_error_pages",
+    "resolver",
+    "resolver_timeout",
+    "return",
+    "rewrite",
+    "rewrite_log",
+    "root",
+    "satisfy",
+    "send_timeout",
+    "sendfile",
+    "sendfile_max_chunk",
+    "server",
+    "server_name",
+    "server_name_in_redirect",
+    "server_names_hash_bucket_size",
+    "server_names_hash_max_size",
+    "server_tokens",
+    "set",
+    "set_real_ip_from",
+    "split_clients",
+    "ssi",
+    "ssl",
+    "ssl_buffer_size",
+    "ssl_certificate",
+    "ssl_certificate_key",
+    "ssl_ciphers",
+    "ssl_client_certificate",
+    "ssl_dhparam",
+    "ssl_ecdh_curve",
+    "ssl_prefer_server_ciphers",
+    "ssl_protocols",
+    "ssl_session_cache",
+    "ssl_session_tickets",
+    "ssl_session_timeout",
+    "ssl_stapling",
+    "ssl_stapling_verify",
+    "ssl_trusted_certificate",
+    "ssl_verify_client",
+    "ssl_verify_depth",
+    "stream",
+    "stub_status",
+    "sub_filter",
+    "sub_filter_once",
+    "sub_filter_types",
+    "tcp_nodelay",
+    "tcp_nopush",
+    "timer_resolution",
+    "try_files",
+    "types",
+    "types_hash_bucket_size",
+    "types_hash_max_size",
+    "underscores_in_headers",
+    "upstream",
+    "use",
+    "user",
+    "uwsgi_param",
+    "uwsgi_pass",
+    "valid_referers",
+    "worker_connections",
+    "worker_cpu_affinity",
+    "worker_priority",
+    "worker_processes",
+    "worker_rlimit_nofile",
+    "worker_shutdown_timeout",
+    "zone",
+];
+
+/// nginx blocks whose entries are key-value data rather than directives
+const NGINX_DATA_BLOCKS: &[&str] = &["charset_map", "geo", "map", "split_clients", "types"];
+
+/// Directives and sections of the Apache core and the modules most
+/// configs load, lowercased; Apache matches names case-insensitively
+const APACHE_DIRECTIVES: &[&str] = &[
+    "accessfilename",
+    "action",
+    "addcharset",
+    "adddefaultcharset",
+    "addencoding",
+    "addhandler",
+    "addicon",
+    "addiconbyencoding",
+    "addiconbytype",
+    "addlanguage",
+    "addoutputfilter",
+    "addoutputfilterbytype",
+    "addtype",
+    "alias",
+    "aliasmatch",
+    "allow",
+    "allowencodedslashes",
+    "allowoverride",
+    "authbasicprovider",
+    "authgroupfile",
+    "authname",
+    "authtype",
+    "authuserfile",
+    "browsermatch",
+    "cachedisable",
+    "cacheenable",
+    "customlog",
+    "defaulticon",
+    "define",
+    "deflatecompressionlevel",
+    "deny",
+    "directory",
+    "directoryindex",
+    "directorymatch",
+    "directoryslash",
+    "documentroot",
+    "else",
+    "elseif",
+    "enablemmap",
+    "enablesendfile",
+    "errordocument",
+    "errorlog",
+    "errorlogformat",
+    "expiresactive",
+    "expiresbytype",
+    "expiresdefault",
+    "fileetag",
+    "files",
+    "filesmatch",
+    "forcelanguagepriority",
+    "forcetype",
+    "group",
+    "header",
+    "headername",
+    "hostnamelookups",
+    "if",
+    "ifdefine",
+    "ifdirective",
+    "iffile",
+    "ifmodule",
+    "ifsection",
+    "ifversion",
+    "include",
+    "includeoptional",
+    "indexignore",
+    "indexoptions",
+    "keepalive",
+    "keepalivetimeout",
+    "languagepriority",
+    "limit",
+    "limitexcept",
+    "limitrequestbody",
+    "listen",
+    "listenbacklog",
+    "loadmodule",
+    "location",
+    "locationmatch",
+    "logformat",
+    "loglevel",
+    "macro",
+    "maxconnectionsperchild",
+    "maxkeepaliverequests",
+    "maxrequestworkers",
+    "maxspareservers",
+    "maxsparethreads",
+    "minspareservers",
+    "minsparethreads",
+    "mutex",
+    "options",
+    "order",
+    "passenv",
+    "pidfile",
+    "protocols",
+    "proxy",
+    "proxymatch",
+    "proxypass",
+    "proxypassmatch",
+    "proxypassreverse",
+    "proxypreservehost",
+    "proxyrequests",
+    "proxytimeout",
+    "readmename",
+    "redirect",
+    "redirectmatch",
+    "redirectpermanent",
+    "remoteipheader",
+    "remoteiptrustedproxy",
+    "requestheader",
+    "require",
+    "requireall",
+    "requireany",
+    "requirenone",
+    "rewritebase",
+    "rewritecond",
+    "rewriteengine",
+    "rewritemap",
+    "rewriteoptions",
+    "rewriterule",
+    "satisfy",
+    "scriptalias",
+    "scriptaliasmatch",
+    "serveradmin",
+    "serveralias",
+    "serverlimit",
+    "servername",
+    "serverroot",
+    "serversignature",
+    "servertokens",
+    "setenv",
+    "setenvif",
+    "setenvifnocase",
+    "sethandler",
+    "setoutputfilter",
+    "sslcertificatechainfile",
+    "sslcertificatefile",
+    "sslcertificatekeyfile",
+    "sslciphersuite",
+    "sslengine",
+    "sslhonorcipherorder",
+    "sslprotocol",
+    "sslproxyengine",
+    "sslstaplingcache",
+    "sslusestapling",
+    "startservers",
+    "threadlimit",
+    "threadsperchild",
+    "timeout",
+    "traceenable",
+    "typesconfig",
+    "undefine",
+    "unsetenv",
+    "use",
+    "usecanonicalname",
+    "user",
+    "userdir",
+    "virtualhost",
+];
+
+/// Server config dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Nginx,
+    Apache,
+}
+
+impl Dialect {
+    fn is_known(self, name: &str) -> bool {
+        match self {
+            Self::Nginx => NGINX_DIRECTIVES.contains(&name),
+            Self::Apache => APACHE_DIRECTIVES.contains(&name.to_ascii_lowercase().as_str()),
+        }
+    }
+
+    /// Whether entries inside a `name` block are data, not directives
+    fn holds_data(self, name: &str) -> bool {
+        self == Self::Nginx && NGINX_DATA_BLOCKS.contains(&name)
+    }
+
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Nginx => "nginx config",
+            Self::Apache => "Apache config",
+        }
+    }
+}
+
+/// Parse server config text in `dialect` to AST with position
+/// information. Unknown directives come back as errors next to the tree.
+pub fn parse(text: &str, dialect: Dialect) -> AstParseResult {
+    let parser = ServerConfigParser { text, dialect };
+    let items = match dialect {
+        Dialect::Nginx => parser.parse_nginx(),
+        Dialect::Apache => parser.parse_apache(),
+    };
+    match items {
+        Ok(items) => {
+            let mut warnings = Vec::new();
+            let ast = parser.root_node(items, &mut warnings);
+            if warnings.is_empty() {
+                AstParseResult::success(ast)
+            } else {
+                AstParseResult::partial(ast, warnings)
+            }
+        }
+        Err(e) => AstParseResult::failure(vec![e]),
+    }
+}
+
+/// Directive name or argument: its text with quotes removed, and the
+/// source span including them
+struct Word {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+enum Item {
+    Directive(Directive),
+    Comment { start: usize, end: usize },
+}
+
+struct Directive {
+    name: Word,
+    args: Vec<Word>,
+    start: usize,
+    end: usize,
+    /// Nested items of a block directive
+    block: Option<Vec<Item>>,
+}
+
+/// Directive for `words`, the first of which is its name
+fn directive(words: Vec<Word>, end: usize) -> Option<Directive> {
+    let mut words = words.into_iter();
+    let name = words.next()?;
+    Some(Directive {
+        start: name.start,
+        name,
+        args: words.collect(),
+        end,
+        block: None,
+    })
+}
+
+/// Block directive whose closing brace or tag has not been read yet
+struct Frame {
+    directive: Directive,
+    items: Vec<Item>,
+}
+
+/// Items read so far, and the blocks still open around the next one
+#[derive(Default)]
+struct Tree {
+    root: Vec<Item>,
+    open: Vec<Frame>,
+}
+
+impl Tree {
+    fn push(&mut self, item: Item) {
+        match self.open.last_mut() {
+            Some(frame) => frame.items.push(item),
+            None => self.root.push(item),
+        }
+    }
+
+    /// Whether another block may be opened inside the current one
+    const fn can_nest(&self) -> bool {
+        self.open.len() < MAX_NESTING
+    }
+
+    /// Close the innermost block at `end`; false when none is open.
+    fn close(&mut self, end: usize) -> bool {
+        let Some(frame) = self.open.pop() else {
+            return false;
+        };
+        let mut directive = frame.directive;
+        directive.end = end;
+        directive.block = Some(frame.items);
+        self.push(Item::Directive(directive));
+        true
+    }
+}
+
+struct ServerConfigParser<'a> {
+    text: &'a str,
+    dialect: Dialect,
+}
+
+impl ServerConfigParser<'_> {
+    fn range(&self, start: usize, end: usize) -> AstRange {
+        AstRange::from_offset(self.text, start, end)
+    }
+
+    fn error(&self, start: usize, end: usize, message: &str) -> AstParseError {
+        AstParseError::new(message).with_range(self.range(start, end))
+    }
+
+    /// End of the line containing `from` (before any `\r\n`)
+    fn line_end(&self, from: usize) -> usize {
+        let newline = self.text[from..]
+            .find('\n')
+            .map_or(self.text.len(), |i| from + i);
+        if self.text[from..newline].ends_with('\r') {
+            newline - 1
+        } else {
+            newline
+        }
+    }
+
+    /// Quoted word starting at `start`, with escaped quotes and
+    /// backslashes unescaped
+    fn quoted(&self, start: usize) -> Result<Word, AstParseError> {
+        let quote = char::from(self.text.as_bytes()[start]);
+        let mut text = String::new();
+        let mut chars = self.text[start + 1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, next)) if next == quote || next == '\\' => text.push(next),
+                    Some((_, next)) => {
+                        text.push('\\');
+                        text.push(next);
+                    }
+                    None => break,
+                },
+                c if c == quote => {
+                    return Ok(Word {
+                        text,
+                        start,
+                        end: start + 1 + i + 1,
+                    })
+                }
+                c => text.push(c),
+            }
+        }
+        Err(self.error(start, self.text.len(), "Unterminated quoted string"))
+    }
+
+    fn missing_semicolon(&self, name: &Word) -> AstParseError {
+        self.error(
+            name.start,
+            name.end,
+            &format!("Missing ';' after directive '{}'", name.text),
+        )
+    }
+
+    fn too_deep(&self, name: &Word) -> AstParseError {
+        self.error(
+            name.start,
+            name.end,
+            &format!("Blocks nested deeper than {MAX_NESTING} levels"),
+        )
+    }
+
+    /// nginx: directives end in `;` or open a `{ ... }` block, and `#`
+    /// starts a comment wherever a word could start.
+    fn parse_nginx(&self) -> Result<Vec<Item>, AstParseError> {
+        let bytes = self.text.as_bytes();
+        let mut tree = Tree::default();
+        let mut words: Vec<Word> = Vec::new();
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            match bytes[pos] {
+                b'#' => {
+                    let end = self.line_end(pos);
+                    if words.is_empty() {
+                        tree.push(Item::Comment { start: pos, end });
+                    }
+                    pos = end;
+                }
+                b';' => {
+                    let directive = directive(std::mem::take(&mut words), pos + 1)
+                        .ok_or_else(|| self.error(pos, pos + 1, "Unexpected ';'"))?;
+                    tree.push(Item::Directive(directive));
+                    pos += 1;
+                }
+                b'{' => {
+                    let directive = directive(std::mem::take(&mut words), pos + 1)
+                        .ok_or_else(|| self.error(pos, pos + 1, "Unexpected '{'"))?;
+                    if !tree.can_nest() {
+                        return Err(self.too_deep(&directive.name));
+                    }
+                    tree.open.push(Frame {
+                        directive,
+                        items: Vec::new(),
+                    });
+                    pos += 1;
+                }
+                b'}' => {
+                    if let Some(name) = words.first() {
+                        return Err(self.missing_semicolon(name));
+                    }
+                    if !tree.close(pos + 1) {
+                        return Err(self.error(pos, pos + 1, "Unexpected '}'"));
+                    }
+                    pos += 1;
+                }
+                b'"' | b'\'' => {
+                    let word = self.quoted(pos)?;
+                    pos = word.end;
+                    words.push(word);
+                }
+                c if c.is_ascii_whitespace() => pos += 1,
+                _ => {
+                    let word = self.nginx_word(pos);
+                    pos = word.end;
+                    words.push(word);
+                }
+            }
+        }
+
+        if let Some(name) = words.first() {
+            return Err(self.missing_semicolon(name));
+        }
+        if let Some(frame) = tree.open.last() {
+            let name = &frame.directive.name;
+            return Err(self.error(
+                name.start,
+                name.end,
+                &format!("Unclosed block '{}'", name.text),
+            ));
+        }
+        Ok(tree.root)
+    }
+
+    /// Unquoted nginx word starting at `start`; `${name}` variables keep
+    /// their braces
+    fn nginx_word(&self, start: usize) -> Word {
+        let bytes = self.text.as_bytes();
+        let mut end = start;
+        while end < bytes.len() {
+            match bytes[end] {
+                b'{' if end > start && bytes[end - 1] == b'$' => {
+                    end = self.text[end..]
+                        .find('}')
+                        .map_or(bytes.len(), |i| end + i + 1);
+                }
+                b';' | b'{' | b'}' | b'"' | b'\'' => break,
+                c if c.is_ascii_whitespace() => break,
+                _ => end += 1,
+            }
+        }
+        Word {
+            text: self.text[start..end].to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// Apache: one directive per line, lines ending in `\` continue, and
+    /// `<Section args>` ... `</Section>` wraps nested directives.
+    fn parse_apache(&self) -> Result<Vec<Item>, AstParseError> {
+        let bytes = self.text.as_bytes();
+        let mut tree = Tree::default();
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            if bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+                continue;
+            }
+            if bytes[pos] == b'#' {
+                let end = self.line_end(pos);
+                tree.push(Item::Comment { start: pos, end });
+                pos = end;
+                continue;
+            }
+            let start = pos;
+            let (words, next) = self.apache_line(start)?;
+            pos = next;
+            let Some(end) = words.last().map(|word| word.end) else {
+                continue;
+            };
+
+            if self.text[start..].starts_with("</") {
+                let first = &words[0];
+                let name = first.text[2..].trim_end_matches('>');
+                let Some(frame) = tree.open.last() else {
+                    return Err(self.error(
+                        first.start,
+                        first.end,
+                        &format!("Unexpected '</{name}>'"),
+                    ));
+                };
+                let open = &frame.directive.name.text;
+                if !open.eq_ignore_ascii_case(name) {
+                    return Err(self.error(
+                        first.start,
+                        first.end,
+                        &format!("Expected '</{open}>' but found '</{name}>'"),
+                    ));
+                }
+                tree.close(end);
+            } else if bytes[start] == b'<' {
+                let section = self.apache_section(words)?;
+                if !tree.can_nest() {
+                    return Err(self.too_deep(&section.name));
+                }
+                tree.open.push(Frame {
+                    directive: section,
+                    items: Vec::new(),
+                });
+            } else if let Some(directive) = directive(words, end) {
+                tree.push(Item::Directive(directive));
+            }
+        }
+
+        if let Some(frame) = tree.open.last() {
+            let name = &frame.directive.name;
+            return Err(self.error(
+                name.start,
+                name.end,
+                &format!("Unclosed section '<{}>'", name.text),
+            ));
+        }
+        Ok(tree.root)
+    }
+
+    /// Offset after a `\` line continuation at `pos`, if there is one
+    fn continuation_end(&self, pos: usize) -> Option<usize> {
+        let rest = &self.text[pos..];
+        if rest.starts_with("\\\n") {
+            Some(pos + 2)
+        } else if rest.starts_with("\\\r\n") {
+            Some(pos + 3)
+        } else {
+            None
+        }
+    }
+
+    /// Words of the Apache line starting at `start`, joined across
+    /// continuations, and the offset of the next line
+    fn apache_line(&self, start: usize) -> Result<(Vec<Word>, usize), AstParseError> {
+        let bytes = self.text.as_bytes();
+        let mut words = Vec::new();
+        let mut pos = start;
+        while pos < bytes.len() {
+            if let Some(next) = self.continuation_end(pos) {
+                pos = next;
+                continue;
+            }
+            match bytes[pos] {
+                b'\n' => return Ok((words, pos + 1)),
+                b'"' | b'\'' => {
+                    let word = self.quoted(pos)?;
+                    pos = word.end;
+                    words.push(word);
+                }
+                c if c.is_ascii_whitespace() => pos += 1,
+                _ => {
+                    let mut end = pos;
+                    while end < bytes.len()
+                        && !bytes[end].is_ascii_whitespace()
+                        && self.continuation_end(end).is_none()
+                    {
+                        end += 1;
+                    }
+                    words.push(Word {
+                        text: self.text[pos..end].to_string(),
+                        start: pos,
+                        end,
+                    });
+                    pos = end;
+                }
+            }
+        }
+        Ok((words, pos))
+    }
+
+    /// Section opened by `<Name args>`, with the `<` and `>` removed from
+    /// its words
+    fn apache_section(&self, mut words: Vec<Word>) -> Result<Directive, AstParseError> {
+        let start = words[0].start;
+        let end = words.last().map_or(start, |word| word.end);
+        if !self.text[..end].ends_with('>') {
+            return Err(self.error(
+                start,
+                end,
+                &format!("Missing '>' after '{}'", &words[0].text),
+            ));
+        }
+        if words.len() > 1 && words.last().is_some_and(|word| word.text == ">") {
+            words.pop();
+        } else if let Some(last) = words.last_mut() {
+            last.text.pop();
+            last.end -= 1;
+        }
+        let name = &mut words[0];
+        name.text.remove(0);
+        name.start += 1;
+        match directive(words, end) {
+            Some(mut section) if !section.name.text.is_empty() => {
+                section.start = start;
+                Ok(section)
+            }
+            _ => Err(self.error(start, end, "Missing section name")),
+        }
+    }
+
+    fn root_node(&self, items: Vec<Item>, warnings: &mut Vec<AstParseError>) -> AstNode {
+        let count = items
+            .iter()
+            .filter(|item| matches!(item, Item::Directive(_)))
+            .count();
+        let label = format!("{} ({count} directives)", self.dialect.title());
+        AstNode::new(
+            AstNodeType::Root,
+            "$".to_string(),
+            label,
+            self.range(0, self.text.len()),
+        )
+        .with_children(self.nodes(items, "$", true, warnings))
+    }
+
+    /// Nodes for `items` under `parent`; `check` reports directive names
+    /// the dialect does not know.
+    fn nodes(
+        &self,
+        items: Vec<Item>,
+        parent: &str,
+        check: bool,
+        warnings: &mut Vec<AstParseError>,
+    ) -> Vec<AstNode> {
+        let mut totals: HashMap<String, usize> = HashMap::new();
+        for item in &items {
+            if let Item::Directive(directive) = item {
+                *totals.entry(directive.name.text.clone()).or_insert(0) += 1;
+            }
+        }
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut nodes = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                Item::Comment { start, end } => nodes.push(AstNode::new(
+                    AstNodeType::Comment,
+                    format!("{parent}/#comment"),
+                    truncate_label(&self.text[start..end]),
+                    self.range(start, end),
+                )),
+                Item::Directive(directive) => {
+                    let name = &directive.name.text;
+                    let index = seen.entry(name.clone()).or_insert(0);
+                    let path = if totals.get(name).is_some_and(|&total| total > 1) {
+                        format!("{}[{index}]", child_path(parent, name))
+                    } else {
+                        child_path(parent, name)
+                    };
+                    *index += 1;
+                    nodes.push(self.directive_node(directive, path, check, warnings));
+                }
+            }
+        }
+        nodes
+    }
+
+    fn directive_node(
+        &self,
+        directive: Directive,
+        path: String,
+        check: bool,
+        warnings: &mut Vec<AstParseError>,
+    ) -> AstNode {
+        let Directive {
+            name,
+            args,
+            start,
+            end,
+            block,
+        } = directive;
+        if check && !self.dialect.is_known(&name.text) {
+            warnings.push(self.error(
+                name.start,
+                name.end,
+                &format!("Unknown directive '{}'", name.text),
+            ));
+        }
+        let label = std::iter::once(&name)
+            .chain(&args)
+            .map(|word| &self.text[word.start..word.end])
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut children: Vec<AstNode> = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                AstNode::new(
+                    AstNodeType::Literal,
+                    format!("{path}.args[{i}]"),
+                    truncate_label(&self.text[arg.start..arg.end]),
+                    self.range(arg.start, arg.end),
+                )
+                .with_value(serde_json::Value::String(arg.text.clone()))
+            })
+            .collect();
+
+        let Some(items) = block else {
+            return AstNode::new(
+                AstNodeType::Statement,
+                path,
+                truncate_label(&label),
+                self.range(start, end),
+            )
+            .with_children(children);
+        };
+        let check = check && !self.dialect.holds_data(&name.text);
+        children.extend(self.nodes(items, &path, check, warnings));
+        AstNode::new(
+            AstNodeType::Object,
+            path,
+            truncate_label(&label),
+            self.range(start, end),
+        )
+        .with_children(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NGINX: &str = r#"user nginx;
+# Connection handling
+events { worker_connections 1024; }
+
+http {
+    server {
+        listen 80;
+        listen [::]:80;
+        server_name example.com "www.example.com";
+        location ~* \.(png|jpg)$ {
+            root /var/www;
+        }
+    }
+    server {
+        listen 443 ssl;
+        return 301 https://${host}$request_uri;
+    }
+}
+"#;
+
+    const APACHE: &str = r#"# Main server
+ServerRoot "/etc/httpd"
+Listen 80
+<VirtualHost *:80>
+    ServerName example.com
+    <Directory "/var/www/html">
+        Options Indexes \
+            FollowSymLinks
+        Require all granted
+    </Directory>
+</virtualhost>
+"#;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    fn values(node: &AstNode) -> Vec<&str> {
+        children(node)
+            .iter()
+            .filter(|c| c.node_type == AstNodeType::Literal)
+            .filter_map(|c| c.value.as_ref()?.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_nginx_blocks_and_directives() {
+        let result = parse(NGINX, Dialect::Nginx);
+        assert!(result.errors.is_empty());
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "nginx config (3 directives)");
+
+        let root = children(&ast);
+        assert_eq!(root[0].path, "$.user");
+        assert_eq!(root[0].node_type, AstNodeType::Statement);
+        assert_eq!(root[1].node_type, AstNodeType::Comment);
+        assert_eq!(root[2].path, "$.events");
+        assert_eq!(children(&root[2])[0].path, "$.events.worker_connections");
+
+        let http = &root[3];
+        assert_eq!(http.node_type, AstNodeType::Object);
+        assert_eq!((http.range.start.line, http.range.end.line), (5, 18));
+        let server = &children(http)[0];
+        assert_eq!(server.path, "$.http.server[0]");
+        let directives = children(server);
+        assert_eq!(directives[1].path, "$.http.server[0].listen[1]");
+        assert_eq!(values(&directives[1]), ["[::]:80"]);
+        assert_eq!(
+            directives[2].label,
+            r#"server_name example.com "www.example.com""#
+        );
+        assert_eq!(values(&directives[2]), ["example.com", "www.example.com"]);
+
+        let location = &directives[3];
+        assert_eq!(location.path, "$.http.server[0].location");
+        assert_eq!(values(location), ["~*", r"\.(png|jpg)$"]);
+        assert_eq!(
+            (location.range.start.line, location.range.end.line),
+            (10, 12)
+        );
+
+        let redirect = &children(&children(http)[1])[1];
+        assert_eq!(values(redirect), ["301", "https://${host}$request_uri"]);
+    }
+
+    #[test]
+    fn test_nginx_unknown_directives() {
+        let text = "http {\n    map $uri $dest { /old /new; default \"\"; }\n    types { text/html html; }\n    server { listn 80; }\n}\n";
+        let result = parse(text, Dialect::Nginx);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].message.contains("'listn'"));
+        assert_eq!(result.errors[0].range.unwrap().start.line, 4);
+
+        let ast = result.ast.unwrap();
+        let types = &children(&children(&ast)[0])[1];
+        assert_eq!(children(types)[0].path, "$.http.types['text/html']");
+    }
+
+    #[test]
+    fn test_nginx_errors() {
+        let missing = parse("server { listen 80 }", Dialect::Nginx);
+        assert!(missing.ast.is_none());
+        assert!(missing.errors[0].message.contains("Missing ';'"));
+
+        let unclosed = parse("http {\n  server {}\n", Dialect::Nginx);
+        assert!(unclosed.errors[0].message.contains("Unclosed block 'http'"));
+
+        let stray = parse("events {}\n}", Dialect::Nginx);
+        assert_eq!(stray.errors[0].range.unwrap().start.line, 2);
+
+        let quote = parse("return 200 \"ok;\n", Dialect::Nginx);
+        assert!(quote.errors[0].message.contains("Unterminated"));
+    }
+
+    #[test]
+    fn test_apache_sections_and_continuations() {
+        let result = parse(APACHE, Dialect::Apache);
+        assert!(result.errors.is_empty());
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "Apache config (3 directives)");
+
+        let root = children(&ast);
+        assert_eq!(values(&root[1]), ["/etc/httpd"]);
+        let host = &root[3];
+        assert_eq!(host.path, "$.VirtualHost");
+        assert_eq!(host.label, "VirtualHost *:80");
+        assert_eq!(values(host), ["*:80"]);
+        assert_eq!((host.range.start.line, host.range.end.line), (4, 11));
+
+        let directory = &children(host)[2];
+        assert_eq!(directory.path, "$.VirtualHost.Directory");
+        assert_eq!(values(directory), ["/var/www/html"]);
+        let options = &children(directory)[1];
+        assert_eq!(options.path, "$.VirtualHost.Directory.Options");
+        assert_eq!(values(options), ["Indexes", "FollowSymLinks"]);
+        assert_eq!((options.range.start.line, options.range.end.line), (7, 8));
+    }
+
+    #[test]
+    fn test_apache_errors() {
+        let unknown = parse(
+            "servername example.com\nServerNam example.com\n",
+            Dialect::Apache,
+        );
+        assert_eq!(unknown.errors.len(), 1);
+        assert!(unknown.errors[0].message.contains("'ServerNam'"));
+        assert!(unknown.ast.is_some());
+
+        let mismatched = parse("<VirtualHost *:80>\n</Directory>\n", Dialect::Apache);
+        assert!(mismatched.errors[0]
+            .message
+            .contains("Expected '</VirtualHost>' but found '</Directory>'"));
+
+        let unclosed = parse("<IfModule mod_ssl.c>\nListen 443\n", Dialect::Apache);
+        assert!(unclosed.errors[0]
+            .message
+            .contains("Unclosed section '<IfModule>'"));
+    }
+}
@@ -1,19 +1,69 @@
 //! SQL AST parser with position tracking using sqlparser-rs
 
-use super::{AstNode, AstNodeType, AstParseError, AstParseResult, AstPosition, AstRange};
+use super::{
+    truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstPosition, AstRange,
+};
+use serde::{Deserialize, Serialize};
 use sqlparser::ast::{
     Expr, GroupByExpr, Query, Select, SelectItem, SetExpr, Spanned, Statement, TableFactor,
     TableWithJoins,
 };
-use sqlparser::dialect::GenericDialect;
+use sqlparser::dialect::{
+    BigQueryDialect, Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect,
+    SQLiteDialect, SnowflakeDialect,
+};
 use sqlparser::parser::Parser;
 use sqlparser::tokenizer::Span;
 
+/// SQL dialect used to parse a document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialect {
+    /// Permissive superset of the common dialects
+    #[default]
+    Generic,
+    /// `PostgreSQL`
+    #[serde(alias = "postgresql")]
+    Postgres,
+    /// `MySQL` / `MariaDB`
+    MySql,
+    /// `SQLite`
+    Sqlite,
+    /// Microsoft SQL Server (T-SQL)
+    #[serde(alias = "sqlserver")]
+    MsSql,
+    /// Google `BigQuery`
+    BigQuery,
+    /// Snowflake
+    Snowflake,
+}
+
+impl SqlDialect {
+    fn parser_dialect(self) -> Box<dyn Dialect> {
+        match self {
+            Self::Generic => Box::new(GenericDialect {}),
+            Self::Postgres => Box::new(PostgreSqlDialect {}),
+            Self::MySql => Box::new(MySqlDialect {}),
+            Self::Sqlite => Box::new(SQLiteDialect {}),
+            Self::MsSql => Box::new(MsSqlDialect {}),
+            Self::BigQuery => Box::new(BigQueryDialect),
+            Self::Snowflake => Box::new(SnowflakeDialect),
+        }
+    }
+}
+
 /// Parse SQL text to AST with position information
 pub fn parse(text: &str) -> AstParseResult {
-    let dialect = GenericDialect {};
+    parse_dialect(text, SqlDialect::default())
+}
+
+/// Parse SQL text in `dialect`; the result records the dialect used
+pub fn parse_dialect(text: &str, dialect: SqlDialect) -> AstParseResult {
+    parse_statements(text, dialect).with_dialect(dialect)
+}
 
-    match Parser::parse_sql(&dialect, text) {
+fn parse_statements(text: &str, dialect: SqlDialect) -> AstParseResult {
+    match Parser::parse_sql(dialect.parser_dialect().as_ref(), text) {
         Ok(statements) => {
             if statements.is_empty() {
                 return AstParseResult::success(create_empty_root(text));
@@ -47,6 +97,26 @@ pub fn parse(text: &str) -> AstParseResult {
     }
 }
 
+/// Each statement's source range (line and column only) and its
+/// pretty-printed SQL, for the formatter
+pub(super) fn pretty_statements(
+    text: &str,
+    dialect: SqlDialect,
+) -> Result<Vec<(AstRange, String)>, AstParseError> {
+    statements(text, dialect).map(|statements| {
+        statements
+            .iter()
+            .map(|stmt| (span_to_range(stmt.span()), format!("{stmt:#}")))
+            .collect()
+    })
+}
+
+/// Parsed statements of `text`, for analyses that walk the `sqlparser` tree
+pub(super) fn statements(text: &str, dialect: SqlDialect) -> Result<Vec<Statement>, AstParseError> {
+    Parser::parse_sql(dialect.parser_dialect().as_ref(), text)
+        .map_err(|e| AstParseError::new(e.to_string()))
+}
+
 fn create_empty_root(text: &str) -> AstNode {
     let range = AstRange::new(
         AstPosition::new(1, 1, 0),
@@ -60,7 +130,7 @@ fn create_empty_root(text: &str) -> AstNode {
     )
 }
 
-fn span_to_range(span: Span) -> AstRange {
+pub(super) fn span_to_range(span: Span) -> AstRange {
     AstRange::new(
         AstPosition::new(
             usize::try_from(span.start.line).unwrap_or(1),
@@ -919,7 +989,7 @@ fn expr_to_ast(expr: &Expr, path: &str, label_prefix: &str) -> AstNode {
         _ => AstNode::new(
             AstNodeType::Expression,
             path.to_string(),
-            format!("{label_prefix}: {}", truncate_string(&expr.to_string(), 50)),
+            format!("{label_prefix}: {}", truncate_label(&expr.to_string())),
             expr_range,
         ),
     }
@@ -1167,14 +1237,6 @@ fn compound_ident_to_ast(
     )
 }
 
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
-    } else {
-        s.to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1277,6 +1339,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_echoes_dialect() {
+        let result = parse("SELECT 1");
+        assert_eq!(result.dialect, Some(SqlDialect::Generic));
+
+        let result = parse_dialect("SELECT 1", SqlDialect::Postgres);
+        assert_eq!(result.dialect, Some(SqlDialect::Postgres));
+    }
+
+    #[test]
+    fn test_parse_mssql_bracket_identifiers() {
+        let result = parse_dialect("SELECT TOP 5 [name] FROM [dbo].[users]", SqlDialect::MsSql);
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert!(result.ast.is_some());
+        assert_eq!(result.dialect, Some(SqlDialect::MsSql));
+    }
+
+    #[test]
+    fn test_parse_postgres_dollar_quoted_string() {
+        let result = parse_dialect("SELECT $$it's quoted$$", SqlDialect::Postgres);
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+    }
+
+    #[test]
+    fn test_dialect_names() {
+        let names: Vec<SqlDialect> =
+            serde_json::from_str(r#"["postgres", "postgresql", "mysql", "sqlserver", "bigquery"]"#)
+                .unwrap();
+        assert_eq!(
+            names,
+            vec![
+                SqlDialect::Postgres,
+                SqlDialect::Postgres,
+                SqlDialect::MySql,
+                SqlDialect::MsSql,
+                SqlDialect::BigQuery,
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&SqlDialect::Snowflake).unwrap(),
+            r#""snowflake""#
+        );
+    }
+
     #[test]
     fn test_multiline_where_clause() {
         let sql = "SELECT *\nFROM users\nWHERE age > 18";
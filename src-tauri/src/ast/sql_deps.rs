@@ -0,0 +1,489 @@
+//! Tables, columns, CTEs, and bind parameters referenced by SQL statements
+//!
+//! Every reference is reported where it occurs, so the same table or
+//! column can appear several times. Table references that name a CTE in
+//! scope are left out; `ctes` lists the definitions. Columns are reported
+//! as written: the qualifier is the table name or alias in front of the
+//! column, and unqualified columns are not resolved to a table.
+
+use serde::Serialize;
+use sqlparser::ast::{
+    AssignmentTarget, Expr, FromTable, GroupByExpr, JoinConstraint, JoinOperator, LimitClause,
+    ObjectName, OrderByKind, Query, Select, SelectItem, SetExpr, Spanned, Statement, TableFactor,
+    TableWithJoins, UpdateTableFromKind, Value,
+};
+use sqlparser::tokenizer::Span;
+
+use super::format::LineIndex;
+use super::sql::{self, span_to_range};
+use super::sql_lint::operands;
+use super::{AstParseError, AstRange, SqlDialect};
+
+/// Whether a statement reads from or writes to a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableAccess {
+    Read,
+    /// Target of INSERT, UPDATE, or DELETE
+    Write,
+}
+
+/// Reference to a table or view
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlTableRef {
+    /// Name as written, including any schema (`sales.orders`)
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    pub access: TableAccess,
+    /// Zero-based index of the statement
+    pub statement: usize,
+    pub range: AstRange,
+}
+
+/// Reference to a column
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlColumnRef {
+    pub name: String,
+    /// Table name or alias the column is qualified with (`u` in `u.id`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualifier: Option<String>,
+    pub statement: usize,
+    pub range: AstRange,
+}
+
+/// Named item of a SQL statement: a CTE definition or a bind parameter
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlName {
+    /// CTE name, or the parameter as written (`?`, `$1`, `:id`)
+    pub name: String,
+    pub statement: usize,
+    pub range: AstRange,
+}
+
+/// Result of [`extract_sql_dependencies`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlDependencies {
+    pub tables: Vec<SqlTableRef>,
+    pub columns: Vec<SqlColumnRef>,
+    /// CTEs defined by WITH clauses
+    pub ctes: Vec<SqlName>,
+    pub parameters: Vec<SqlName>,
+    /// Parse errors; nothing is extracted when the input is invalid
+    pub errors: Vec<AstParseError>,
+    pub dialect: SqlDialect,
+}
+
+/// Collect the references made by the SQL statements in `text`, parsed
+/// as `dialect`
+pub fn extract_sql_dependencies(text: &str, dialect: SqlDialect) -> SqlDependencies {
+    let mut collector = Collector {
+        text,
+        index: LineIndex::new(text),
+        statement: 0,
+        scope: Vec::new(),
+        out: SqlDependencies {
+            tables: Vec::new(),
+            columns: Vec::new(),
+            ctes: Vec::new(),
+            parameters: Vec::new(),
+            errors: Vec::new(),
+            dialect,
+        },
+    };
+    match sql::statements(text, dialect) {
+        Ok(statements) => {
+            for (i, statement) in statements.iter().enumerate() {
+                collector.statement = i;
+                collector.visit_statement(statement);
+            }
+        }
+        Err(e) => collector.out.errors.push(e),
+    }
+    collector.out
+}
+
+struct Collector<'a> {
+    text: &'a str,
+    index: LineIndex,
+    statement: usize,
+    /// CTE names visible from the query being walked
+    scope: Vec<String>,
+    out: SqlDependencies,
+}
+
+impl Collector<'_> {
+    fn range(&self, span: Span) -> AstRange {
+        self.index.locate(self.text, span_to_range(span))
+    }
+
+    fn name(&self, name: String, span: Span) -> SqlName {
+        SqlName {
+            name,
+            statement: self.statement,
+            range: self.range(span),
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Query(query) => self.visit_query(query),
+            Statement::Insert(insert) => {
+                self.out.tables.push(SqlTableRef {
+                    name: insert.table.to_string(),
+                    alias: None,
+                    access: TableAccess::Write,
+                    statement: self.statement,
+                    range: self.range(insert.table.span()),
+                });
+                for column in &insert.columns {
+                    self.column_name(column);
+                }
+                if let Some(source) = &insert.source {
+                    self.visit_query(source);
+                }
+            }
+            Statement::Update(update) => {
+                self.visit_table(&update.table, TableAccess::Write);
+                for assignment in &update.assignments {
+                    match &assignment.target {
+                        AssignmentTarget::ColumnName(column) => self.column_name(column),
+                        AssignmentTarget::Tuple(columns) => {
+                            for column in columns {
+                                self.column_name(column);
+                            }
+                        }
+                    }
+                    self.visit_expr(&assignment.value);
+                }
+                if let Some(
+                    UpdateTableFromKind::BeforeSet(tables) | UpdateTableFromKind::AfterSet(tables),
+                ) = &update.from
+                {
+                    for table in tables {
+                        self.visit_table(table, TableAccess::Read);
+                    }
+                }
+                if let Some(selection) = &update.selection {
+                    self.visit_expr(selection);
+                }
+            }
+            Statement::Delete(delete) => {
+                let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) =
+                    &delete.from;
+                for table in tables {
+                    self.visit_table(table, TableAccess::Write);
+                }
+                for table in delete.using.iter().flatten() {
+                    self.visit_table(table, TableAccess::Read);
+                }
+                if let Some(selection) = &delete.selection {
+                    self.visit_expr(selection);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        let depth = self.scope.len();
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                let name = &cte.alias.name;
+                self.scope.push(name.value.clone());
+                let definition = self.name(name.value.clone(), name.span);
+                self.out.ctes.push(definition);
+                self.visit_query(&cte.query);
+            }
+        }
+        self.visit_set_expr(&query.body);
+        if let Some(order_by) = &query.order_by {
+            if let OrderByKind::Expressions(exprs) = &order_by.kind {
+                for order in exprs {
+                    self.visit_expr(&order.expr);
+                }
+            }
+        }
+        match &query.limit_clause {
+            Some(LimitClause::LimitOffset {
+                limit,
+                offset,
+                limit_by,
+            }) => {
+                for expr in limit.iter().chain(offset.iter().map(|o| &o.value)) {
+                    self.visit_expr(expr);
+                }
+                for expr in limit_by {
+                    self.visit_expr(expr);
+                }
+            }
+            Some(LimitClause::OffsetCommaLimit { offset, limit }) => {
+                self.visit_expr(offset);
+                self.visit_expr(limit);
+            }
+            None => {}
+        }
+        self.scope.truncate(depth);
+    }
+
+    fn visit_set_expr(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => self.visit_select(select),
+            SetExpr::Query(query) => self.visit_query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.visit_set_expr(left);
+                self.visit_set_expr(right);
+            }
+            SetExpr::Values(values) => {
+                for expr in values.rows.iter().flat_map(|row| &row.content) {
+                    self.visit_expr(expr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_select(&mut self, select: &Select) {
+        for item in &select.projection {
+            if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item {
+                self.visit_expr(expr);
+            }
+        }
+        for table in &select.from {
+            self.visit_table(table, TableAccess::Read);
+        }
+        if let Some(selection) = &select.selection {
+            self.visit_expr(selection);
+        }
+        if let GroupByExpr::Expressions(exprs, _) = &select.group_by {
+            for expr in exprs {
+                self.visit_expr(expr);
+            }
+        }
+        if let Some(having) = &select.having {
+            self.visit_expr(having);
+        }
+    }
+
+    /// `access` applies to the first table; joined tables are read
+    fn visit_table(&mut self, table: &TableWithJoins, access: TableAccess) {
+        self.visit_factor(&table.relation, access);
+        for join in &table.joins {
+            self.visit_factor(&join.relation, TableAccess::Read);
+            let (JoinOperator::Join(constraint)
+            | JoinOperator::Inner(constraint)
+            | JoinOperator::Left(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::Right(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint)) = &join.join_operator
+            else {
+                continue;
+            };
+            match constraint {
+                JoinConstraint::On(on) => self.visit_expr(on),
+                JoinConstraint::Using(columns) => {
+                    for column in columns {
+                        self.column_name(column);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_factor(&mut self, factor: &TableFactor, access: TableAccess) {
+        match factor {
+            TableFactor::Table { name, alias, .. } => {
+                let written = name.to_string();
+                if self
+                    .scope
+                    .iter()
+                    .any(|cte| cte.eq_ignore_ascii_case(&written))
+                {
+                    return;
+                }
+                self.out.tables.push(SqlTableRef {
+                    name: written,
+                    alias: alias.as_ref().map(|a| a.name.value.clone()),
+                    access,
+                    statement: self.statement,
+                    range: self.range(name.span()),
+                });
+            }
+            TableFactor::Derived { subquery, .. } => self.visit_query(subquery),
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.visit_table(table_with_joins, access),
+            _ => {}
+        }
+    }
+
+    /// Column named by an INSERT column list, SET target, or USING list
+    fn column_name(&mut self, column: &ObjectName) {
+        self.out.columns.push(SqlColumnRef {
+            name: column.to_string(),
+            qualifier: None,
+            statement: self.statement,
+            range: self.range(column.span()),
+        });
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Identifier(ident) => self.out.columns.push(SqlColumnRef {
+                name: ident.value.clone(),
+                qualifier: None,
+                statement: self.statement,
+                range: self.range(ident.span),
+            }),
+            Expr::CompoundIdentifier(idents) => {
+                let Some((column, qualifier)) = idents.split_last() else {
+                    return;
+                };
+                self.out.columns.push(SqlColumnRef {
+                    name: column.value.clone(),
+                    qualifier: (!qualifier.is_empty()).then(|| {
+                        qualifier
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(".")
+                    }),
+                    statement: self.statement,
+                    range: self.range(expr.span()),
+                });
+            }
+            Expr::Value(value) => {
+                if let Value::Placeholder(placeholder) = &value.value {
+                    let parameter = self.name(placeholder.clone(), value.span);
+                    self.out.parameters.push(parameter);
+                }
+            }
+            Expr::Subquery(query)
+            | Expr::Exists {
+                subquery: query, ..
+            } => self.visit_query(query),
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.visit_expr(expr);
+                self.visit_query(subquery);
+            }
+            _ => {
+                for operand in operands(expr) {
+                    self.visit_expr(operand);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(text: &str) -> SqlDependencies {
+        let result = extract_sql_dependencies(text, SqlDialect::Postgres);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        result
+    }
+
+    #[test]
+    fn test_tables_ctes_and_parameters() {
+        let text = "WITH recent AS (SELECT id, user_id FROM orders WHERE created > $1) \
+                    SELECT u.name, r.id FROM users u JOIN recent r ON r.user_id = u.id \
+                    WHERE u.active = $2";
+        let result = extract(text);
+
+        let tables: Vec<_> = result
+            .tables
+            .iter()
+            .map(|t| (t.name.as_str(), t.alias.as_deref(), t.access))
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                ("orders", None, TableAccess::Read),
+                ("users", Some("u"), TableAccess::Read),
+            ]
+        );
+        let users = &result.tables[1].range;
+        assert_eq!(&text[users.start.offset..users.end.offset], "users");
+
+        assert_eq!(result.ctes.len(), 1);
+        assert_eq!(result.ctes[0].name, "recent");
+        assert_eq!(result.ctes[0].range.start.offset, 5);
+
+        let parameters: Vec<_> = result.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(parameters, vec!["$1", "$2"]);
+        let second = &result.parameters[1].range;
+        assert_eq!(&text[second.start.offset..second.end.offset], "$2");
+    }
+
+    #[test]
+    fn test_columns_keep_their_qualifier() {
+        let result = extract(
+            "SELECT u.name, COUNT(o.id) FROM users u JOIN orders o USING (user_id) GROUP BY u.name",
+        );
+        let columns: Vec<_> = result
+            .columns
+            .iter()
+            .map(|c| (c.qualifier.as_deref(), c.name.as_str()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                (Some("u"), "name"),
+                (Some("o"), "id"),
+                (None, "user_id"),
+                (Some("u"), "name"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_targets() {
+        let result = extract(
+            "INSERT INTO logs (level, message) VALUES ($1, $2); \
+             UPDATE users SET name = $3 WHERE id = $4; \
+             DELETE FROM sessions WHERE expires < now()",
+        );
+        let tables: Vec<_> = result
+            .tables
+            .iter()
+            .map(|t| (t.statement, t.name.as_str(), t.access))
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                (0, "logs", TableAccess::Write),
+                (1, "users", TableAccess::Write),
+                (2, "sessions", TableAccess::Write),
+            ]
+        );
+        let columns: Vec<_> = result.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(columns, vec!["level", "message", "name", "id", "expires"]);
+        assert_eq!(result.parameters.len(), 4);
+    }
+
+    #[test]
+    fn test_subqueries_and_nested_cte_scope() {
+        let result = extract(
+            "SELECT id FROM accounts WHERE id IN (SELECT account_id FROM payments) \
+             AND EXISTS (WITH t AS (SELECT 1) SELECT * FROM t)",
+        );
+        let tables: Vec<_> = result.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(tables, vec!["accounts", "payments"]);
+        assert_eq!(result.ctes[0].name, "t");
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        let result = extract_sql_dependencies("SELECT * FROM (", SqlDialect::Generic);
+        assert!(result.tables.is_empty());
+        assert!(!result.errors.is_empty());
+    }
+}
@@ -0,0 +1,458 @@
+//! SQL formatter with configurable layout
+//!
+//! Statements are pretty-printed by `sqlparser`, which puts every clause
+//! keyword on its own line and every list item on an indented line below
+//! it, with keywords in upper case. That layout is then reshaped: clauses
+//! that fit within the line limit are joined back onto one line, list
+//! commas can lead instead of trail, and keywords can be lowercased.
+//! Comments are not kept, as the parser drops them.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use sqlparser::keywords::ALL_KEYWORDS;
+
+use super::format::{FormatOptions, FormatResult, LineIndex, Writer};
+use super::{sql, SqlDialect};
+
+/// Case for SQL keywords
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    /// Lower case; unquoted identifiers written in upper case that are
+    /// also keywords (`NAME`, `VALUE`) are lowercased with them
+    Lower,
+}
+
+/// Placement of list separators when a list spans several lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommaStyle {
+    /// `a,` / `b`
+    #[default]
+    Trailing,
+    /// `a` / `, b`
+    Leading,
+}
+
+/// Options for [`format_sql`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SqlFormatOptions {
+    pub keyword_case: KeywordCase,
+    /// Spaces per indentation level; 0 indents with tabs
+    pub indent: usize,
+    pub comma_style: CommaStyle,
+    /// Clauses that fit within this many columns stay on one line, with
+    /// tabs counted as four; 0 puts every list item on its own line
+    pub max_line_length: usize,
+}
+
+impl Default for SqlFormatOptions {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::Upper,
+            indent: 2,
+            comma_style: CommaStyle::Trailing,
+            max_line_length: 80,
+        }
+    }
+}
+
+/// Pretty-print the SQL statements in `text`, parsed as `dialect`
+///
+/// The source map has one entry per statement, with path `$` for a
+/// single statement and `$[i]` otherwise, as in the SQL AST.
+pub fn format_sql(text: &str, dialect: SqlDialect, options: &SqlFormatOptions) -> FormatResult {
+    let statements = match sql::pretty_statements(text, dialect) {
+        Ok(statements) => statements,
+        Err(e) => return FormatResult::failure(vec![e]),
+    };
+    let index = LineIndex::new(text);
+    let mut writer = Writer::new(&FormatOptions {
+        indent: options.indent,
+        ..FormatOptions::default()
+    });
+    let single = statements.len() == 1;
+    for (i, (span, pretty)) in statements.iter().enumerate() {
+        if i > 0 {
+            writer.push("\n");
+        }
+        let start = writer.out.len();
+        writer.push(&reshape(pretty, dialect, options));
+        writer.push(";");
+        let original = index.locate(text, *span);
+        let path = if single {
+            "$".to_string()
+        } else {
+            format!("$[{i}]")
+        };
+        writer.mark(&path, original, start);
+        writer.push("\n");
+    }
+    writer.finish()
+}
+
+/// A line of `sqlparser`'s pretty output
+#[derive(Debug, Clone)]
+struct Line {
+    /// Indentation level; `sqlparser` indents two spaces per level
+    depth: usize,
+    /// Content after the indentation, keeping an odd leading space
+    text: String,
+    /// Continuation of a multi-line literal, copied as is
+    verbatim: bool,
+}
+
+/// Lay out one pretty-printed statement according to `options`
+fn reshape(pretty: &str, dialect: SqlDialect, options: &SqlFormatOptions) -> String {
+    let literals = literal_ranges(pretty, dialect);
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for raw in pretty.split('\n') {
+        let verbatim = literals
+            .iter()
+            .any(|range| range.start < offset && offset < range.end);
+        let content = raw.trim_start_matches(' ');
+        let spaces = raw.len() - content.len();
+        lines.push(Line {
+            depth: if verbatim { 0 } else { spaces / 2 },
+            text: if verbatim {
+                raw.to_string()
+            } else {
+                format!("{}{content}", " ".repeat(spaces % 2))
+            },
+            verbatim,
+        });
+        offset += raw.len() + 1;
+    }
+
+    let (unit, unit_width) = if options.indent == 0 {
+        ("\t".to_string(), 4)
+    } else {
+        (" ".repeat(options.indent), options.indent)
+    };
+    if options.max_line_length > 0 {
+        lines = collapse(lines, options.max_line_length, unit_width);
+    }
+    if options.comma_style == CommaStyle::Leading {
+        lead_commas(&mut lines);
+    }
+
+    let mut out = String::with_capacity(pretty.len());
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        if !line.verbatim {
+            out.push_str(&unit.repeat(line.depth));
+        }
+        out.push_str(&line.text);
+    }
+    if options.keyword_case == KeywordCase::Lower {
+        out = lowercase_keywords(&out, dialect);
+    }
+    out
+}
+
+/// Join each block of lines (a line and the deeper lines under it, plus
+/// the closing parenthesis of an opening one) onto one line when the
+/// result fits in `max` columns, outermost first
+fn collapse(lines: Vec<Line>, max: usize, unit_width: usize) -> Vec<Line> {
+    let mut out = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let head = &lines[i];
+        let mut end = i
+            + 1
+            + lines[i + 1..]
+                .iter()
+                .take_while(|line| line.verbatim || line.depth > head.depth)
+                .count();
+        if head.text.ends_with('(')
+            && lines
+                .get(end)
+                .is_some_and(|line| line.depth == head.depth && line.text.starts_with(')'))
+        {
+            end += 1;
+        }
+        if end > i + 1 {
+            if let Some(joined) = join(&lines[i..end], max, unit_width) {
+                out.push(joined);
+                i = end;
+                continue;
+            }
+        }
+        out.push(head.clone());
+        i += 1;
+    }
+    out
+}
+
+fn join(block: &[Line], max: usize, unit_width: usize) -> Option<Line> {
+    if block.iter().any(|line| line.verbatim) {
+        return None;
+    }
+    let mut text = block[0].text.clone();
+    for line in &block[1..] {
+        let part = line.text.trim_start();
+        if !text.ends_with('(') && !part.starts_with(')') {
+            text.push(' ');
+        }
+        text.push_str(part);
+    }
+    let width = block[0].depth * unit_width + text.chars().count();
+    (width <= max).then(|| Line {
+        depth: block[0].depth,
+        text,
+        verbatim: false,
+    })
+}
+
+/// Move the comma ending a list item to the start of the next item
+fn lead_commas(lines: &mut [Line]) {
+    for i in 1..lines.len() {
+        let (before, after) = lines.split_at_mut(i);
+        let (previous, line) = (&mut before[i - 1], &mut after[0]);
+        if line.verbatim || line.depth != previous.depth || !previous.text.ends_with(',') {
+            continue;
+        }
+        previous.text.pop();
+        let content = line.text.trim_start();
+        line.text = format!(
+            "{}, {content}",
+            &line.text[..line.text.len() - content.len()]
+        );
+    }
+}
+
+/// Lowercase the upper-case keywords outside literals
+fn lowercase_keywords(text: &str, dialect: SqlDialect) -> String {
+    let mut out = text.to_string();
+    let mut literals = literal_ranges(text, dialect).into_iter().peekable();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < text.len() {
+        if let Some(range) = literals.next_if(|range| range.start <= i) {
+            i = i.max(range.end);
+            continue;
+        }
+        let Some(c) = text[i..].chars().next() else {
+            break;
+        };
+        if !(c.is_alphanumeric() || c == '_') {
+            i += c.len_utf8();
+            continue;
+        }
+        let end = text[i..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .map_or(text.len(), |n| i + n);
+        let word = &text[i..end];
+        // Parameters, variables, qualified names, and string prefixes
+        // (`@v`, `$1`, `:name`, `t.NAME`, `N'x'`) are never keywords.
+        let prefix = i.checked_sub(1).map(|p| bytes[p]);
+        let is_cast = i >= 2 && &bytes[i - 2..i] == b"::";
+        let qualified = matches!(prefix, Some(b'@' | b'$' | b'#' | b'.'))
+            || (prefix == Some(b':') && !is_cast)
+            || bytes.get(end) == Some(&b'\'');
+        if !qualified
+            && !c.is_ascii_digit()
+            && word.bytes().all(|b| b.is_ascii_uppercase() || b == b'_')
+            && ALL_KEYWORDS.binary_search(&word).is_ok()
+        {
+            out.replace_range(i..end, &word.to_ascii_lowercase());
+        }
+        i = end;
+    }
+    out
+}
+
+/// Byte ranges of the string literals and quoted identifiers in
+/// `sqlparser` output, quotes included
+fn literal_ranges(text: &str, dialect: SqlDialect) -> Vec<Range<usize>> {
+    let backslash_escapes = matches!(dialect, SqlDialect::MySql | SqlDialect::BigQuery);
+    let brackets = matches!(dialect, SqlDialect::MsSql | SqlDialect::Sqlite);
+    let bytes = text.as_bytes();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let close = match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => Some(quote),
+            b'[' if brackets => Some(b']'),
+            b'$' => {
+                if let Some(end) = dollar_quote_end(text, i) {
+                    ranges.push(i..end);
+                    i = end;
+                    continue;
+                }
+                None
+            }
+            _ => None,
+        };
+        let Some(close) = close else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        i += 1;
+        while i < bytes.len() {
+            if bytes[i] == b'\\' && backslash_escapes && close != b']' {
+                i += 2;
+            } else if bytes[i] == close {
+                i += 1;
+                // A doubled quote is an escaped quote.
+                if bytes.get(i) != Some(&close) || close == b']' {
+                    break;
+                }
+                i += 1;
+            } else {
+                i += 1;
+            }
+        }
+        i = i.min(bytes.len());
+        ranges.push(start..i);
+    }
+    ranges
+}
+
+/// End of a Postgres dollar-quoted string (`$tag$...$tag$`) starting
+/// at `start`, if there is one
+fn dollar_quote_end(text: &str, start: usize) -> Option<usize> {
+    let rest = &text[start + 1..];
+    let tag_len = rest.find('$')?;
+    let tag = &rest[..tag_len];
+    let valid = tag
+        .chars()
+        .enumerate()
+        .all(|(n, c)| c == '_' || c.is_alphabetic() || (n > 0 && c.is_ascii_digit()));
+    if !valid {
+        return None;
+    }
+    let delimiter = &text[start..start + tag_len + 2];
+    let body = start + delimiter.len();
+    text[body..]
+        .find(delimiter)
+        .map(|n| body + n + delimiter.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRETTY: &str = "SELECT\n  id,\n  name\nFROM\n  users\nWHERE\n  id = 1";
+
+    fn options(max_line_length: usize) -> SqlFormatOptions {
+        SqlFormatOptions {
+            max_line_length,
+            ..SqlFormatOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_short_clauses_are_joined() {
+        assert_eq!(
+            reshape(PRETTY, SqlDialect::Generic, &options(80)),
+            "SELECT id, name\nFROM users\nWHERE id = 1"
+        );
+        assert_eq!(reshape(PRETTY, SqlDialect::Generic, &options(0)), PRETTY);
+        // "SELECT id, name" is 15 columns.
+        assert!(reshape(PRETTY, SqlDialect::Generic, &options(14)).starts_with("SELECT\n  id,"));
+    }
+
+    #[test]
+    fn test_nested_blocks_join_inside_out() {
+        let pretty = "SELECT\n  *\nFROM\n  (\n    SELECT\n      a\n    FROM\n      t\n  ) AS s";
+        assert_eq!(
+            reshape(pretty, SqlDialect::Generic, &options(80)),
+            "SELECT *\nFROM (SELECT a FROM t) AS s"
+        );
+        assert_eq!(
+            reshape(pretty, SqlDialect::Generic, &options(20)),
+            "SELECT *\nFROM\n  (\n    SELECT a\n    FROM t\n  ) AS s"
+        );
+    }
+
+    #[test]
+    fn test_leading_commas_and_indent() {
+        let options = SqlFormatOptions {
+            indent: 4,
+            comma_style: CommaStyle::Leading,
+            max_line_length: 0,
+            ..SqlFormatOptions::default()
+        };
+        assert_eq!(
+            reshape(PRETTY, SqlDialect::Generic, &options),
+            "SELECT\n    id\n    , name\nFROM\n    users\nWHERE\n    id = 1"
+        );
+        let tabs = SqlFormatOptions {
+            indent: 0,
+            ..options
+        };
+        assert!(reshape(PRETTY, SqlDialect::Generic, &tabs).contains("\n\t, name\n"));
+    }
+
+    #[test]
+    fn test_lowercase_keywords_skips_literals_and_identifiers() {
+        let text = "SELECT t.NAME, 'SELECT', \"FROM\", @VALUE, N'X' FROM t WHERE a::INT = $1";
+        assert_eq!(
+            lowercase_keywords(text, SqlDialect::Generic),
+            "select t.NAME, 'SELECT', \"FROM\", @VALUE, N'X' from t where a::int = $1"
+        );
+        assert_eq!(
+            lowercase_keywords("SELECT [FROM] FROM t", SqlDialect::MsSql),
+            "select [FROM] from t"
+        );
+        assert_eq!(
+            lowercase_keywords("SELECT $q$ AND $q$ AND 'it''s' AS x", SqlDialect::Postgres),
+            "select $q$ AND $q$ and 'it''s' as x"
+        );
+    }
+
+    #[test]
+    fn test_multiline_literals_are_kept() {
+        let pretty = "SELECT\n  'a\n  b',\n  c\nFROM\n  t";
+        let text = reshape(
+            pretty,
+            SqlDialect::Generic,
+            &SqlFormatOptions {
+                indent: 4,
+                ..SqlFormatOptions::default()
+            },
+        );
+        assert_eq!(text, "SELECT\n    'a\n  b',\n    c\nFROM t");
+    }
+
+    #[test]
+    fn test_format_sql_maps_statements() {
+        let result = format_sql(
+            "select id, name from users where id = 1; delete from users",
+            SqlDialect::Postgres,
+            &SqlFormatOptions {
+                keyword_case: KeywordCase::Lower,
+                ..SqlFormatOptions::default()
+            },
+        );
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(
+            result.text.as_deref(),
+            Some("select id, name\nfrom users\nwhere id = 1;\n\ndelete from users;\n")
+        );
+        assert_eq!(result.source_map.len(), 2);
+        assert_eq!(result.source_map[1].path, "$[1]");
+        assert!(result.source_map[1].original.start.offset >= 41);
+        assert_eq!(result.source_map[1].formatted.start.line, 5);
+    }
+
+    #[test]
+    fn test_format_sql_reports_parse_errors() {
+        let result = format_sql(
+            "SELECT FROM",
+            SqlDialect::Generic,
+            &SqlFormatOptions::default(),
+        );
+        assert!(result.text.is_none());
+        assert!(!result.errors.is_empty());
+    }
+}
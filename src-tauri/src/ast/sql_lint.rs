@@ -0,0 +1,481 @@
+//! SQL lint and safety checks over the `sqlparser` tree
+//!
+//! Flags statements that are valid but usually a mistake: data changes
+//! without a WHERE clause, `SELECT *`, tables joined without a join
+//! condition, and predicates that keep the database from using an index
+//! on the column they test.
+
+use serde::Serialize;
+use sqlparser::ast::{
+    BinaryOperator, Expr, FunctionArg, FunctionArgExpr, FunctionArguments, JoinConstraint,
+    JoinOperator, Query, Select, SelectItem, SetExpr, Spanned, Statement, TableFactor,
+    TableWithJoins, UnaryOperator, Value,
+};
+use sqlparser::tokenizer::Span;
+
+use super::format::LineIndex;
+use super::sql::{self, span_to_range};
+use super::{AstParseError, AstRange, SqlDialect};
+
+/// Kind of problem reported by [`analyze_sql`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SqlRule {
+    /// DELETE that removes every row
+    DeleteWithoutWhere,
+    /// UPDATE that changes every row
+    UpdateWithoutWhere,
+    /// `*` or `t.*` in a select list
+    SelectStar,
+    /// Comma-separated tables, or a JOIN without ON or USING
+    ImplicitCrossJoin,
+    /// Predicate that wraps its column in a function or arithmetic, or a
+    /// LIKE pattern with a leading wildcard
+    NonSargable,
+}
+
+/// One finding of [`analyze_sql`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlWarning {
+    pub rule: SqlRule,
+    pub message: String,
+    /// Zero-based index of the statement the clause belongs to
+    pub statement: usize,
+    /// Source range of the offending clause
+    pub range: AstRange,
+}
+
+/// Result of [`analyze_sql`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlAnalysisResult {
+    pub warnings: Vec<SqlWarning>,
+    /// Parse errors; no warnings are reported when the input is invalid
+    pub errors: Vec<AstParseError>,
+    pub dialect: SqlDialect,
+}
+
+/// Check the SQL statements in `text`, parsed as `dialect`
+pub fn analyze_sql(text: &str, dialect: SqlDialect) -> SqlAnalysisResult {
+    let statements = match sql::statements(text, dialect) {
+        Ok(statements) => statements,
+        Err(e) => {
+            return SqlAnalysisResult {
+                warnings: Vec::new(),
+                errors: vec![e],
+                dialect,
+            }
+        }
+    };
+    let mut linter = Linter {
+        text,
+        index: LineIndex::new(text),
+        statement: 0,
+        warnings: Vec::new(),
+    };
+    for (i, statement) in statements.iter().enumerate() {
+        linter.statement = i;
+        linter.check_statement(statement);
+    }
+    SqlAnalysisResult {
+        warnings: linter.warnings,
+        errors: Vec::new(),
+        dialect,
+    }
+}
+
+struct Linter<'a> {
+    text: &'a str,
+    index: LineIndex,
+    statement: usize,
+    warnings: Vec<SqlWarning>,
+}
+
+impl Linter<'_> {
+    fn warn(&mut self, rule: SqlRule, span: Span, message: &str) {
+        self.warnings.push(SqlWarning {
+            rule,
+            message: message.to_string(),
+            statement: self.statement,
+            range: self.index.locate(self.text, span_to_range(span)),
+        });
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Query(query) => self.check_query(query, false),
+            Statement::Insert(insert) => {
+                if let Some(source) = &insert.source {
+                    self.check_query(source, false);
+                }
+            }
+            Statement::Update(update) => match &update.selection {
+                Some(selection) => self.check_predicate(selection),
+                None => self.warn(
+                    SqlRule::UpdateWithoutWhere,
+                    statement.span(),
+                    "UPDATE without WHERE changes every row",
+                ),
+            },
+            Statement::Delete(delete) => match &delete.selection {
+                Some(selection) => self.check_predicate(selection),
+                None => self.warn(
+                    SqlRule::DeleteWithoutWhere,
+                    statement.span(),
+                    "DELETE without WHERE removes every row",
+                ),
+            },
+            _ => {}
+        }
+    }
+
+    /// `exists` marks the body of an EXISTS test, where `SELECT *` is
+    /// idiomatic because no column is read
+    fn check_query(&mut self, query: &Query, exists: bool) {
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                self.check_query(&cte.query, false);
+            }
+        }
+        self.check_set_expr(&query.body, exists);
+    }
+
+    fn check_set_expr(&mut self, body: &SetExpr, exists: bool) {
+        match body {
+            SetExpr::Select(select) => self.check_select(select, exists),
+            SetExpr::Query(query) => self.check_query(query, exists),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.check_set_expr(left, exists);
+                self.check_set_expr(right, exists);
+            }
+            _ => {}
+        }
+    }
+
+    fn check_select(&mut self, select: &Select, exists: bool) {
+        for item in &select.projection {
+            match item {
+                SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) if !exists => {
+                    self.warn(
+                        SqlRule::SelectStar,
+                        item.span(),
+                        "SELECT * returns every column; list the columns needed",
+                    );
+                }
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    self.check_subqueries(expr);
+                }
+                _ => {}
+            }
+        }
+
+        for table in select.from.iter().skip(1) {
+            let message = if select.selection.is_some() {
+                "Comma-separated tables form an implicit join; use JOIN ... ON"
+            } else {
+                "Comma-separated tables without a WHERE clause form a cross join"
+            };
+            self.warn(SqlRule::ImplicitCrossJoin, table.relation.span(), message);
+        }
+        for table in &select.from {
+            self.check_table(table);
+        }
+
+        if let Some(selection) = &select.selection {
+            self.check_predicate(selection);
+        }
+        if let Some(having) = &select.having {
+            self.check_subqueries(having);
+        }
+    }
+
+    fn check_table(&mut self, table: &TableWithJoins) {
+        self.check_factor(&table.relation);
+        for join in &table.joins {
+            self.check_factor(&join.relation);
+            match &join.join_operator {
+                JoinOperator::Join(JoinConstraint::None)
+                | JoinOperator::Inner(JoinConstraint::None) => self.warn(
+                    SqlRule::ImplicitCrossJoin,
+                    join.span(),
+                    "JOIN without ON or USING is a cross join",
+                ),
+                JoinOperator::Join(JoinConstraint::On(on))
+                | JoinOperator::Inner(JoinConstraint::On(on))
+                | JoinOperator::Left(JoinConstraint::On(on))
+                | JoinOperator::LeftOuter(JoinConstraint::On(on))
+                | JoinOperator::Right(JoinConstraint::On(on))
+                | JoinOperator::RightOuter(JoinConstraint::On(on))
+                | JoinOperator::FullOuter(JoinConstraint::On(on)) => self.check_predicate(on),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_factor(&mut self, factor: &TableFactor) {
+        match factor {
+            TableFactor::Derived { subquery, .. } => self.check_query(subquery, false),
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.check_table(table_with_joins),
+            _ => {}
+        }
+    }
+
+    /// Check a WHERE or ON condition, term by term
+    fn check_predicate(&mut self, expr: &Expr) {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And | BinaryOperator::Or,
+                right,
+            } => {
+                self.check_predicate(left);
+                self.check_predicate(right);
+                return;
+            }
+            Expr::Nested(inner)
+            | Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr: inner,
+            } => {
+                self.check_predicate(inner);
+                return;
+            }
+            Expr::BinaryOp {
+                left,
+                op:
+                    BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq,
+                right,
+            } if !is_column(left)
+                && !is_column(right)
+                && (wraps_column(left) || wraps_column(right)) =>
+            {
+                self.warn(
+                    SqlRule::NonSargable,
+                    expr.span(),
+                    "Function or arithmetic on the column prevents index use; \
+                     move the computation to the other side",
+                );
+            }
+            Expr::Between { expr: inner, .. } | Expr::InList { expr: inner, .. }
+                if wraps_column(inner) =>
+            {
+                self.warn(
+                    SqlRule::NonSargable,
+                    expr.span(),
+                    "Function or arithmetic on the column prevents index use",
+                );
+            }
+            Expr::Like { pattern, .. } | Expr::ILike { pattern, .. }
+                if has_leading_wildcard(pattern) =>
+            {
+                self.warn(
+                    SqlRule::NonSargable,
+                    expr.span(),
+                    "LIKE pattern with a leading wildcard cannot use an index",
+                );
+            }
+            _ => {}
+        }
+        self.check_subqueries(expr);
+    }
+
+    /// Check the queries nested anywhere in `expr`
+    fn check_subqueries(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Subquery(query) => self.check_query(query, false),
+            Expr::Exists { subquery, .. } => self.check_query(subquery, true),
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.check_subqueries(expr);
+                self.check_query(subquery, false);
+            }
+            _ => {
+                for child in operands(expr) {
+                    self.check_subqueries(child);
+                }
+            }
+        }
+    }
+}
+
+/// Direct sub-expressions of the common expression kinds; queries
+/// nested in an expression are not included
+pub(super) fn operands(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryOp { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr) => vec![expr.as_ref()],
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr.as_ref(), low.as_ref(), high.as_ref()],
+        Expr::InList { expr, list, .. } => std::iter::once(expr.as_ref()).chain(list).collect(),
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            vec![expr.as_ref(), pattern.as_ref()]
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            else_result,
+            ..
+        } => operand
+            .as_deref()
+            .into_iter()
+            .chain(
+                conditions
+                    .iter()
+                    .flat_map(|when| [&when.condition, &when.result]),
+            )
+            .chain(else_result.as_deref())
+            .collect(),
+        Expr::Function(function) => match &function.args {
+            FunctionArguments::List(list) => list
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(e))
+                    | FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(e),
+                        ..
+                    } => Some(e),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+const fn is_column(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_))
+}
+
+/// Whether `expr` reads a column only through a function call, a cast,
+/// or arithmetic
+fn wraps_column(expr: &Expr) -> bool {
+    match expr {
+        Expr::Nested(inner) => wraps_column(inner),
+        Expr::Function(_) | Expr::Cast { .. } | Expr::BinaryOp { .. } => has_column(expr),
+        _ => false,
+    }
+}
+
+fn has_column(expr: &Expr) -> bool {
+    is_column(expr) || operands(expr).into_iter().any(has_column)
+}
+
+fn has_leading_wildcard(pattern: &Expr) -> bool {
+    match pattern {
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => {
+                s.starts_with(['%', '_'])
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(text: &str) -> Vec<SqlRule> {
+        let result = analyze_sql(text, SqlDialect::Generic);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        result.warnings.iter().map(|w| w.rule).collect()
+    }
+
+    fn flagged<'t>(text: &'t str, warning: &SqlWarning) -> &'t str {
+        &text[warning.range.start.offset..warning.range.end.offset]
+    }
+
+    #[test]
+    fn test_delete_and_update_without_where() {
+        assert_eq!(
+            rules("DELETE FROM users; UPDATE users SET active = false"),
+            vec![SqlRule::DeleteWithoutWhere, SqlRule::UpdateWithoutWhere]
+        );
+        assert!(
+            rules("DELETE FROM users WHERE id = 1; UPDATE users SET a = 1 WHERE id = 2").is_empty()
+        );
+
+        let result = analyze_sql("SELECT 1;\nDELETE FROM users", SqlDialect::Generic);
+        assert_eq!(result.warnings[0].statement, 1);
+        assert_eq!(result.warnings[0].range.start.line, 2);
+    }
+
+    #[test]
+    fn test_select_star() {
+        let text = "SELECT * FROM users";
+        let result = analyze_sql(text, SqlDialect::Generic);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].rule, SqlRule::SelectStar);
+        assert_eq!(flagged(text, &result.warnings[0]), "*");
+
+        assert_eq!(rules("SELECT u.* FROM users u"), vec![SqlRule::SelectStar]);
+        assert!(rules(
+            "SELECT COUNT(*) FROM users u WHERE EXISTS (SELECT * FROM orders o WHERE o.uid = u.id)"
+        )
+        .is_empty());
+        assert_eq!(
+            rules("SELECT id FROM (SELECT * FROM users) AS u"),
+            vec![SqlRule::SelectStar]
+        );
+    }
+
+    #[test]
+    fn test_implicit_cross_join() {
+        let text = "SELECT a.id FROM a, b WHERE a.id = b.id";
+        let result = analyze_sql(text, SqlDialect::Generic);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].rule, SqlRule::ImplicitCrossJoin);
+        assert_eq!(flagged(text, &result.warnings[0]), "b");
+
+        assert!(rules("SELECT a.id FROM a JOIN b ON a.id = b.id CROSS JOIN c").is_empty());
+    }
+
+    #[test]
+    fn test_non_sargable_predicates() {
+        let text = "SELECT id FROM t WHERE UPPER(name) = 'X' AND a = b + 1 AND c LIKE '%x'";
+        let result = analyze_sql(text, SqlDialect::Generic);
+        let found: Vec<_> = result
+            .warnings
+            .iter()
+            .map(|w| (w.rule, flagged(text, w)))
+            .collect();
+        assert_eq!(found.len(), 2, "{found:?}");
+        assert!(found.iter().all(|(rule, _)| *rule == SqlRule::NonSargable));
+        assert!(found[0].1.starts_with("UPPER(name)"));
+        assert!(found[1].1.starts_with("c LIKE"));
+
+        assert!(
+            rules("SELECT id FROM t WHERE d > NOW() - INTERVAL '1 day' AND n LIKE 'x%'").is_empty()
+        );
+        assert_eq!(
+            rules("SELECT id FROM t JOIN u ON YEAR(t.d) = u.y"),
+            Vec::<SqlRule>::new()
+        );
+        assert_eq!(
+            rules("SELECT id FROM t WHERE id IN (SELECT id FROM u WHERE price * 2 > 10)"),
+            vec![SqlRule::NonSargable]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_are_reported() {
+        let result = analyze_sql("SELECT * FROM (", SqlDialect::Generic);
+        assert!(result.warnings.is_empty());
+        assert!(!result.errors.is_empty());
+    }
+}
@@ -0,0 +1,830 @@
+//! Streaming JSON parse for files too large to hold in memory
+//!
+//! [`StreamedDocuments::open`] reads a JSON file through a buffered reader
+//! and builds only the top levels of the tree. Containers below the eager
+//! depth, or reached after the request's node budget ran out, come back
+//! collapsed: labelled with their item count but without children.
+//! [`StreamedDocuments::expand`] seeks back to a collapsed container and
+//! builds its next levels the same way. Between requests only the resume
+//! position of each collapsed container is kept, never the text.
+//!
+//! Paths, labels, and ranges match the in-memory JSON parser, so expanded
+//! nodes slot into the tree view unchanged. Unlike that parser, the first
+//! syntax error ends the read.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use super::{AstError, AstNode, AstNodeType, AstParseError, AstPosition, AstRange};
+
+/// Levels built when a file is opened, unless the caller asks otherwise
+pub const DEFAULT_EAGER_DEPTH: usize = 3;
+
+/// Deepest eager build accepted; the builder recurses once per level
+const MAX_EAGER_DEPTH: usize = 16;
+
+/// Nodes built per request. Containers reached after the budget runs out
+/// stay collapsed, so a flat array of millions of items costs one page of
+/// nodes per request rather than the whole file.
+const NODE_BUDGET: usize = 10_000;
+
+/// Files kept open for expansion at once
+const DOCUMENT_CAPACITY: usize = 4;
+
+/// Result of [`StreamedDocuments::open`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamedParseResult {
+    /// Top levels of the tree; collapsed containers have no children
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast: Option<AstNode>,
+    /// Paths of the containers [`StreamedDocuments::expand`] can build
+    pub collapsed: Vec<String>,
+    /// File size in bytes
+    pub size: u64,
+    pub errors: Vec<AstParseError>,
+}
+
+/// Result of [`StreamedDocuments::expand`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeExpansion {
+    /// Children built by this request, following any already shown
+    pub children: Vec<AstNode>,
+    /// Collapsed containers among `children`, plus the expanded container
+    /// itself while it has children left to build
+    pub collapsed: Vec<String>,
+    pub errors: Vec<AstParseError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Object,
+    Array,
+}
+
+impl Container {
+    const fn close(self) -> u8 {
+        match self {
+            Self::Object => b'}',
+            Self::Array => b']',
+        }
+    }
+}
+
+/// Where building a collapsed container continues
+#[derive(Debug, Clone, Copy)]
+struct Resume {
+    kind: Container,
+    /// The opening bracket when `next_index` is 0, otherwise the start of
+    /// child `next_index`
+    position: AstPosition,
+    next_index: usize,
+}
+
+/// File opened with [`StreamedDocuments::open`]
+struct Document {
+    path: PathBuf,
+    /// Size and modification time when opened; resume positions are only
+    /// valid while both are unchanged
+    size: u64,
+    modified: Option<SystemTime>,
+    depth: usize,
+    collapsed: HashMap<String, Resume>,
+}
+
+/// Files parsed in streaming mode, held in managed state. Clones share
+/// documents.
+#[derive(Clone, Default)]
+pub struct StreamedDocuments {
+    documents: Arc<Mutex<VecDeque<Document>>>,
+}
+
+impl StreamedDocuments {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the JSON file at `path`, building `depth` levels (default
+    /// [`DEFAULT_EAGER_DEPTH`]). Reopening a file discards the collapsed
+    /// nodes of its previous parse. The read stops once `token` is
+    /// cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AstError::Io`] when the file cannot be read and
+    /// [`AstError::Cancelled`] when `token` is cancelled mid-read.
+    pub fn open(
+        &self,
+        path: &Path,
+        depth: Option<usize>,
+        token: &CancellationToken,
+    ) -> Result<StreamedParseResult, AstError> {
+        let depth = depth
+            .unwrap_or(DEFAULT_EAGER_DEPTH)
+            .clamp(1, MAX_EAGER_DEPTH);
+        let file = File::open(path).map_err(|e| AstError::Io(e.to_string()))?;
+        let (size, modified) = stamp(&file)?;
+
+        let mut builder = Builder::new(
+            BufReader::new(file),
+            depth,
+            AstPosition::new(1, 1, 0),
+            Some(token),
+        );
+        let (ast, errors) = match builder.document() {
+            Ok((ast, errors)) => (Some(ast), errors),
+            Err(e) => {
+                builder.collapsed.clear();
+                (None, vec![e])
+            }
+        };
+        if token.is_cancelled() {
+            return Err(AstError::Cancelled);
+        }
+        let collapsed = builder.collapsed.iter().map(|(p, _)| p.clone()).collect();
+
+        let mut documents = self
+            .documents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        documents.retain(|document| document.path != path);
+        documents.push_front(Document {
+            path: path.to_path_buf(),
+            size,
+            modified,
+            depth,
+            collapsed: builder.collapsed.into_iter().collect(),
+        });
+        documents.truncate(DOCUMENT_CAPACITY);
+        drop(documents);
+
+        Ok(StreamedParseResult {
+            ast,
+            collapsed,
+            size,
+            errors,
+        })
+    }
+
+    /// Build the next levels of the collapsed container at `node_path` in
+    /// the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AstError::NotCollapsed`] when the file is not open or the
+    /// node is not collapsed, [`AstError::FileChanged`] when the file was
+    /// modified since it was opened, and [`AstError::Io`] when it cannot
+    /// be read.
+    pub fn expand(&self, path: &Path, node_path: &str) -> Result<NodeExpansion, AstError> {
+        let (resume, depth, size, modified) = {
+            let documents = self
+                .documents
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            documents
+                .iter()
+                .find(|document| document.path == path)
+                .and_then(|document| {
+                    document
+                        .collapsed
+                        .get(node_path)
+                        .map(|resume| (*resume, document.depth, document.size, document.modified))
+                })
+                .ok_or_else(|| AstError::NotCollapsed(node_path.to_string()))?
+        };
+
+        let mut file = File::open(path).map_err(|e| AstError::Io(e.to_string()))?;
+        if stamp(&file)? != (size, modified) {
+            return Err(AstError::FileChanged(path.display().to_string()));
+        }
+        file.seek(SeekFrom::Start(resume.position.offset as u64))
+            .map_err(|e| AstError::Io(e.to_string()))?;
+
+        let mut builder = Builder::new(BufReader::new(file), depth, resume.position, None);
+        let (children, errors) = match builder.resume(node_path, resume) {
+            Ok(children) => (children, Vec::new()),
+            Err(e) => {
+                builder.collapsed.clear();
+                (Vec::new(), vec![e])
+            }
+        };
+        let collapsed = builder.collapsed.iter().map(|(p, _)| p.clone()).collect();
+
+        let mut documents = self
+            .documents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(document) = documents.iter_mut().find(|document| document.path == path) {
+            if errors.is_empty() {
+                document.collapsed.remove(node_path);
+            }
+            document.collapsed.extend(builder.collapsed);
+        }
+        drop(documents);
+
+        Ok(NodeExpansion {
+            children,
+            collapsed,
+            errors,
+        })
+    }
+}
+
+fn stamp(file: &File) -> Result<(u64, Option<SystemTime>), AstError> {
+    let metadata = file.metadata().map_err(|e| AstError::Io(e.to_string()))?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/// Tree builder reading one byte at a time from a buffered reader
+struct Builder<'c, R> {
+    input: R,
+    /// Position of the next byte
+    position: AstPosition,
+    /// Levels to build below the starting container
+    depth: usize,
+    /// Nodes left to build in this request
+    budget: usize,
+    /// Containers left collapsed, in document order
+    collapsed: Vec<(String, Resume)>,
+    /// Checked before each node and each skipped item
+    cancel: Option<&'c CancellationToken>,
+}
+
+impl<'c, R: BufRead> Builder<'c, R> {
+    const fn new(
+        input: R,
+        depth: usize,
+        position: AstPosition,
+        cancel: Option<&'c CancellationToken>,
+    ) -> Self {
+        Self {
+            input,
+            position,
+            depth,
+            budget: NODE_BUDGET,
+            collapsed: Vec::new(),
+            cancel,
+        }
+    }
+
+    /// Root value of a whole file, and the error for trailing content
+    fn document(&mut self) -> Result<(AstNode, Vec<AstParseError>), AstParseError> {
+        let ast = self.value("$", 0)?;
+        self.skip_whitespace()?;
+        let errors = if self.peek()?.is_some() {
+            vec![self.error("Unexpected content after the document")]
+        } else {
+            Vec::new()
+        };
+        Ok((ast, errors))
+    }
+
+    /// Children of the container at `path`, continuing at `resume`
+    fn resume(&mut self, path: &str, resume: Resume) -> Result<Vec<AstNode>, AstParseError> {
+        if resume.next_index == 0 {
+            self.next()?;
+        }
+        let (children, _) = self.members(resume.kind, path, 0, resume.next_index)?;
+        Ok(children)
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, AstParseError> {
+        let position = self.position;
+        match self.input.fill_buf() {
+            Ok(buffer) => Ok(buffer.first().copied()),
+            Err(e) => Err(error_at(position, &format!("Could not read the file: {e}"))),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<u8>, AstParseError> {
+        let byte = self.peek()?;
+        if let Some(byte) = byte {
+            self.input.consume(1);
+            self.position.offset += 1;
+            if byte == b'\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+                self.position.utf16_column = 1;
+            } else {
+                self.position.column += 1;
+                // One unit per char, two for chars past the BMP, which
+                // take four bytes
+                match byte {
+                    0x80..=0xBF => {}
+                    0xF0.. => self.position.utf16_column += 2,
+                    _ => self.position.utf16_column += 1,
+                }
+            }
+        }
+        Ok(byte)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), AstParseError> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    fn error(&self, message: &str) -> AstParseError {
+        error_at(self.position, message)
+    }
+
+    /// Stop the read once the request is cancelled; the caller turns the
+    /// error into [`AstError::Cancelled`]
+    fn check_cancelled(&self) -> Result<(), AstParseError> {
+        if self.cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(self.error("Parsing cancelled"));
+        }
+        Ok(())
+    }
+
+    const fn range(&self, start: AstPosition) -> AstRange {
+        AstRange::new(start, self.position)
+    }
+
+    fn value(&mut self, path: &str, level: usize) -> Result<AstNode, AstParseError> {
+        self.skip_whitespace()?;
+        let start = self.position;
+        match self.peek()? {
+            Some(b'{') => self.container(Container::Object, path, level),
+            Some(b'[') => self.container(Container::Array, path, level),
+            Some(b'"') => {
+                let value = self.string()?;
+                let label = if value.chars().count() > 50 {
+                    format!("\"{}...\"", value.chars().take(47).collect::<String>())
+                } else {
+                    format!("\"{value}\"")
+                };
+                Ok(AstNode::new(
+                    AstNodeType::String,
+                    path.to_string(),
+                    label,
+                    self.range(start),
+                )
+                .with_value(serde_json::Value::String(value)))
+            }
+            Some(b't') => self.keyword("true", AstNodeType::Boolean, true.into(), path),
+            Some(b'f') => self.keyword("false", AstNodeType::Boolean, false.into(), path),
+            Some(b'n') => self.keyword("null", AstNodeType::Null, serde_json::Value::Null, path),
+            Some(b'-' | b'0'..=b'9') => self.number(path),
+            Some(byte) if byte.is_ascii() => {
+                Err(self.error(&format!("Unexpected character: '{}'", char::from(byte))))
+            }
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    /// Container at the reader's opening bracket: built when `level` is
+    /// within the eager depth and the budget allows, otherwise skipped and
+    /// recorded as collapsed
+    fn container(
+        &mut self,
+        kind: Container,
+        path: &str,
+        level: usize,
+    ) -> Result<AstNode, AstParseError> {
+        let start = self.position;
+        self.next()?;
+        let (children, count) = if level < self.depth && self.budget > 0 {
+            self.members(kind, path, level, 0)?
+        } else {
+            let count = self.skip_members(kind)?;
+            self.collapsed.push((
+                path.to_string(),
+                Resume {
+                    kind,
+                    position: start,
+                    next_index: 0,
+                },
+            ));
+            (Vec::new(), count)
+        };
+        let (node_type, label) = match kind {
+            Container::Object => (AstNodeType::Object, format!("{{}} ({count} properties)")),
+            Container::Array => (AstNodeType::Array, format!("[] ({count} items)")),
+        };
+        Ok(
+            AstNode::new(node_type, path.to_string(), label, self.range(start))
+                .with_children(children),
+        )
+    }
+
+    /// Children of the container at `path` (at `level`) from child
+    /// `first_index` through the closing bracket, and the number of
+    /// children from there on, built or not. Once the budget runs out the
+    /// rest are skipped and the container is recorded as collapsed from
+    /// the next child.
+    fn members(
+        &mut self,
+        kind: Container,
+        path: &str,
+        level: usize,
+        first_index: usize,
+    ) -> Result<(Vec<AstNode>, usize), AstParseError> {
+        let close = kind.close();
+        let mut children = Vec::new();
+        let mut index = first_index;
+        self.skip_whitespace()?;
+        if index == 0 && self.peek()? == Some(close) {
+            self.next()?;
+            return Ok((children, 0));
+        }
+        loop {
+            self.skip_whitespace()?;
+            self.check_cancelled()?;
+            if self.budget == 0 {
+                self.collapsed.push((
+                    path.to_string(),
+                    Resume {
+                        kind,
+                        position: self.position,
+                        next_index: index,
+                    },
+                ));
+                let rest = self.skip_members(kind)?;
+                return Ok((children, index - first_index + rest));
+            }
+            let child = match kind {
+                Container::Object => self.property(path, level)?,
+                Container::Array => self.value(&format!("{path}[{index}]"), level + 1)?,
+            };
+            self.budget = self.budget.saturating_sub(1);
+            children.push(child);
+            index += 1;
+
+            self.skip_whitespace()?;
+            match self.peek()? {
+                Some(b',') => {
+                    self.next()?;
+                }
+                Some(byte) if byte == close => {
+                    self.next()?;
+                    return Ok((children, index - first_index));
+                }
+                _ => {
+                    return Err(self.error(match kind {
+                        Container::Object => "Expected ',' or '}'",
+                        Container::Array => "Expected ',' or ']'",
+                    }))
+                }
+            }
+        }
+    }
+
+    /// `"key": value` member of the object at `path`
+    fn property(&mut self, path: &str, level: usize) -> Result<AstNode, AstParseError> {
+        if self.peek()? != Some(b'"') {
+            return Err(self.error("Expected string key"));
+        }
+        let start = self.position;
+        let key = self.string()?;
+        self.skip_whitespace()?;
+        if self.peek()? != Some(b':') {
+            return Err(self.error("Expected ':'"));
+        }
+        self.next()?;
+
+        let child_path = format!("{path}.{key}");
+        let value = self.value(&child_path, level + 1)?;
+        let mut property = AstNode::new(
+            AstNodeType::Property,
+            child_path,
+            key,
+            AstRange::new(start, value.range.end),
+        );
+        match value.node_type {
+            AstNodeType::Object | AstNodeType::Array => {
+                property = property.with_children(vec![value]);
+            }
+            _ => {
+                property.node_type = value.node_type;
+                property.value = value.value;
+            }
+        }
+        Ok(property)
+    }
+
+    /// Skip the remaining children of a container and its closing bracket,
+    /// returning how many children were skipped. Brackets must still
+    /// match, but values are not checked.
+    fn skip_members(&mut self, kind: Container) -> Result<usize, AstParseError> {
+        let mut open: Vec<u8> = Vec::new();
+        let mut count = 0;
+        let mut in_item = false;
+        loop {
+            let Some(byte) = self.peek()? else {
+                return Err(self.error("Unexpected end of input"));
+            };
+            match byte {
+                b' ' | b'\t' | b'\n' | b'\r' => {}
+                b',' if open.is_empty() => {
+                    self.check_cancelled()?;
+                    count += 1;
+                    in_item = false;
+                }
+                b'"' => {
+                    self.skip_string()?;
+                    in_item = true;
+                    continue;
+                }
+                b'{' => {
+                    open.push(b'}');
+                    in_item = true;
+                }
+                b'[' => {
+                    open.push(b']');
+                    in_item = true;
+                }
+                b'}' | b']' => match open.pop() {
+                    Some(expected) if expected == byte => {}
+                    None if byte == kind.close() => {
+                        self.next()?;
+                        return Ok(count + usize::from(in_item));
+                    }
+                    _ => return Err(self.error("Mismatched closing bracket")),
+                },
+                _ => in_item = true,
+            }
+            self.next()?;
+        }
+    }
+
+    fn skip_string(&mut self) -> Result<(), AstParseError> {
+        self.next()?;
+        loop {
+            match self.next()? {
+                Some(b'"') => return Ok(()),
+                Some(b'\\') => {
+                    self.next()?;
+                }
+                Some(_) => {}
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    /// Decoded string at the reader's opening quote
+    fn string(&mut self) -> Result<String, AstParseError> {
+        self.next()?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next()? {
+                Some(b'"') => return Ok(String::from_utf8_lossy(&bytes).into_owned()),
+                Some(b'\\') => {
+                    let decoded = match self.next()? {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\x08',
+                        Some(b'f') => '\x0c',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(decoded.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) => bytes.push(byte),
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    /// Character of a `\u` escape whose `\u` was consumed, joining a
+    /// surrogate pair written as two escapes
+    fn unicode_escape(&mut self) -> Result<char, AstParseError> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return Ok(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        if self.peek()? != Some(b'\\') {
+            return Ok(char::REPLACEMENT_CHARACTER);
+        }
+        self.next()?;
+        if self.next()? != Some(b'u') {
+            return Err(self.error("Invalid escape sequence"));
+        }
+        let low = self.hex4()?;
+        let code = 0x10000 + ((high - 0xD800) << 10) + low.wrapping_sub(0xDC00);
+        Ok((0xDC00..0xE000)
+            .contains(&low)
+            .then(|| char::from_u32(code))
+            .flatten()
+            .unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn hex4(&mut self) -> Result<u32, AstParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()?
+                .and_then(|byte| char::from(byte).to_digit(16))
+                .ok_or_else(|| self.error("Invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn keyword(
+        &mut self,
+        keyword: &str,
+        node_type: AstNodeType,
+        value: serde_json::Value,
+        path: &str,
+    ) -> Result<AstNode, AstParseError> {
+        let start = self.position;
+        for expected in keyword.bytes() {
+            if self.peek()? != Some(expected) {
+                return Err(self.error(&format!("Expected '{keyword}'")));
+            }
+            self.next()?;
+        }
+        Ok(AstNode::new(
+            node_type,
+            path.to_string(),
+            keyword.to_string(),
+            self.range(start),
+        )
+        .with_value(value))
+    }
+
+    fn number(&mut self, path: &str) -> Result<AstNode, AstParseError> {
+        let start = self.position;
+        let mut text = String::new();
+        while let Some(byte @ (b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) = self.peek()? {
+            text.push(char::from(byte));
+            self.next()?;
+        }
+        let value: serde_json::Number = text
+            .parse()
+            .map_err(|_| error_at(start, "Invalid number format"))?;
+        Ok(AstNode::new(
+            AstNodeType::Number,
+            path.to_string(),
+            text,
+            self.range(start),
+        )
+        .with_value(serde_json::Value::Number(value)))
+    }
+}
+
+fn error_at(position: AstPosition, message: &str) -> AstParseError {
+    AstParseError::new(message).with_range(AstRange::new(position, position))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use super::super::{parse_to_ast, AstLanguage};
+    use super::*;
+
+    fn build(text: &str, depth: usize) -> (AstNode, Vec<String>) {
+        let mut builder = Builder::new(Cursor::new(text), depth, AstPosition::new(1, 1, 0), None);
+        let (ast, errors) = builder.document().unwrap();
+        assert!(errors.is_empty());
+        let collapsed = builder.collapsed.into_iter().map(|(p, _)| p).collect();
+        (ast, collapsed)
+    }
+
+    fn json_file(text: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_matches_in_memory_parser_when_fully_built() {
+        let json = "{\n  \"name\": \"caf\u{e9} \\u00e9\",\n  \"tags\": [1, -2.5e3, true, null],\n  \"nested\": {\"empty\": {}, \"list\": []}\n}\n";
+        let (ast, collapsed) = build(json, 8);
+        assert!(collapsed.is_empty());
+        assert_eq!(Some(ast), parse_to_ast(json, AstLanguage::Json).ast);
+
+        let (emoji, _) = build(r#""\ud83d\ude00""#, 1);
+        assert_eq!(emoji.value, Some(serde_json::json!("\u{1f600}")));
+    }
+
+    #[test]
+    fn test_collapses_below_eager_depth() {
+        let json = r#"{"users": [{"id": 1, "roles": ["a", "b"]}, {"id": 2}], "count": 2}"#;
+        let (ast, collapsed) = build(json, 2);
+        assert_eq!(collapsed, ["$.users[0]", "$.users[1]"]);
+
+        let users = &ast.children.as_ref().unwrap()[0].children.as_ref().unwrap()[0];
+        let first = &users.children.as_ref().unwrap()[0];
+        assert_eq!(first.label, "{} (2 properties)");
+        assert!(first.children.is_none());
+        let start = json.find("{\"id\": 1").unwrap();
+        assert_eq!(first.range.start.offset, start);
+        assert_eq!(
+            &json[first.range.end.offset - 1..],
+            "}, {\"id\": 2}], \"count\": 2}"
+        );
+    }
+
+    #[test]
+    fn test_expand_collapsed_and_paged_containers() {
+        let items: Vec<String> = (0..NODE_BUDGET + 5)
+            .map(|i| format!("{{\"id\": {i}}}"))
+            .collect();
+        let json = format!("{{\"items\": [\n{}\n]}}", items.join(",\n"));
+        let file = json_file(&json);
+        let documents = StreamedDocuments::new();
+
+        let opened = documents
+            .open(file.path(), Some(2), &CancellationToken::new())
+            .unwrap();
+        assert!(opened.errors.is_empty());
+        let root = opened.ast.unwrap();
+        let items_node = &root.children.as_ref().unwrap()[0]
+            .children
+            .as_ref()
+            .unwrap()[0];
+        assert_eq!(items_node.label, format!("[] ({} items)", NODE_BUDGET + 5));
+        assert_eq!(items_node.children.as_ref().unwrap().len(), NODE_BUDGET);
+        assert_eq!(opened.collapsed.len(), NODE_BUDGET + 1);
+        assert_eq!(opened.collapsed.last().unwrap(), "$.items");
+
+        let page = documents.expand(file.path(), "$.items").unwrap();
+        assert_eq!(page.children.len(), 5);
+        assert_eq!(page.children[0].path, format!("$.items[{NODE_BUDGET}]"));
+        assert_eq!(page.children[0].range.start.line, NODE_BUDGET + 2);
+        assert!(!page.collapsed.contains(&"$.items".to_string()));
+
+        let item = documents.expand(file.path(), "$.items[3]").unwrap();
+        assert_eq!(item.children.len(), 1);
+        assert_eq!(item.children[0].path, "$.items[3].id");
+        assert_eq!(item.children[0].value, Some(serde_json::json!(3)));
+        assert_eq!(item.children[0].range.start.line, 5);
+
+        assert!(matches!(
+            documents.expand(file.path(), "$.items[3]"),
+            Err(AstError::NotCollapsed(_))
+        ));
+    }
+
+    #[test]
+    fn test_cancelled_open_keeps_no_document() {
+        let file = json_file(r#"{"items": [[1], [2]]}"#);
+        let documents = StreamedDocuments::new();
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            documents.open(file.path(), Some(1), &token),
+            Err(AstError::Cancelled)
+        ));
+        assert!(matches!(
+            documents.expand(file.path(), "$.items"),
+            Err(AstError::NotCollapsed(_))
+        ));
+    }
+
+    #[test]
+    fn test_changed_file_is_rejected() {
+        let file = json_file("[[1], [2]]");
+        let documents = StreamedDocuments::new();
+        documents
+            .open(file.path(), Some(1), &CancellationToken::new())
+            .unwrap();
+        let mut handle = file.reopen().unwrap();
+        handle.write_all(b"[[1], [2], [3]]").unwrap();
+        assert!(matches!(
+            documents.expand(file.path(), "$[0]"),
+            Err(AstError::FileChanged(_))
+        ));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let file = json_file(r#"{"a": [1, 2}"#);
+        let opened = StreamedDocuments::new()
+            .open(file.path(), Some(1), &CancellationToken::new())
+            .unwrap();
+        assert!(opened.ast.is_none());
+        assert_eq!(opened.errors[0].message, "Mismatched closing bracket");
+        assert_eq!(opened.errors[0].range.unwrap().start.column, 12);
+
+        let trailing = json_file("[1] 2");
+        let opened = StreamedDocuments::new()
+            .open(trailing.path(), None, &CancellationToken::new())
+            .unwrap();
+        assert!(opened.ast.is_some());
+        assert_eq!(
+            opened.errors[0].message,
+            "Unexpected content after the document"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Source text and value of one node, for the tree view's copy actions
+//!
+//! The node is looked up by path in the parsed tree. Its source is the
+//! slice of the document its range covers; a scalar member of a JSON,
+//! YAML, or TOML map spans its key as well, which is cut off. Its value is
+//! read the way [`convert_document`](super::convert_document) reads
+//! documents, so map entries keep their order and XML elements become
+//! maps of their attributes, children, and text.
+
+use serde::Serialize;
+
+use super::convert::{json_text, read_ast_value, read_xml_element, Value};
+use super::format::property_value;
+use super::{AstLanguage, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange};
+
+/// Result of [`get_subtree_text`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtreeText {
+    /// Source text of the node, exactly as written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Range of `source` in the document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<AstRange>,
+    /// Value to copy: a string as is, anything else as `json`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Value as pretty-printed JSON; absent for nodes that hold no data,
+    /// like SQL clauses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<String>,
+    /// Parse errors, and a missing node; a partial tree is still searched
+    pub errors: Vec<AstParseError>,
+}
+
+/// Source text and value of the node at `path` in `parsed`, the tree of
+/// `text` in `language`
+pub fn get_subtree_text(
+    text: &str,
+    language: AstLanguage,
+    parsed: &AstParseResult,
+    path: &str,
+) -> SubtreeText {
+    let mut errors = parsed.errors.clone();
+    let Some((found, parent)) = parsed.ast.as_ref().and_then(|root| find(root, None, path)) else {
+        errors.push(AstParseError::new(format!("No node at path '{path}'")));
+        return SubtreeText {
+            errors,
+            ..SubtreeText::default()
+        };
+    };
+    let node = property_value(found);
+
+    // YAML ranges start at the key line, for containers as well.
+    let scalar = !matches!(node.node_type, AstNodeType::Object | AstNodeType::Array);
+    let member = parent.is_some_and(|parent| parent.node_type == AstNodeType::Object)
+        && match language {
+            AstLanguage::Yaml => true,
+            AstLanguage::Json | AstLanguage::Toml => scalar,
+            _ => false,
+        };
+    let source = text
+        .get(node.range.start.offset..node.range.end.offset)
+        .map(|slice| {
+            if member {
+                member_value(slice, language)
+            } else {
+                slice
+            }
+        });
+    let range = source.map(|source| {
+        let end = node.range.end.offset;
+        AstRange::from_offset(text, end - source.len(), end)
+    });
+
+    let value = match node.node_type {
+        AstNodeType::Element if language == AstLanguage::Xml => read_xml_element(text, &node.path),
+        AstNodeType::Object | AstNodeType::Array => Some(read_ast_value(node)),
+        _ if node.value.is_some() => Some(read_ast_value(node)),
+        _ => None,
+    };
+    let json = value
+        .as_ref()
+        .map(|value| json_text(value, 2).trim_end().to_string());
+    let value = value.and_then(|value| match value {
+        Value::String(s) => Some(s),
+        _ => json.clone(),
+    });
+
+    SubtreeText {
+        source: source.map(str::to_string),
+        range,
+        value,
+        json,
+        errors,
+    }
+}
+
+/// Outermost node at `path`, with its parent
+fn find<'a>(
+    node: &'a AstNode,
+    parent: Option<&'a AstNode>,
+    path: &str,
+) -> Option<(&'a AstNode, Option<&'a AstNode>)> {
+    if node.path == path {
+        return Some((node, parent));
+    }
+    node.children
+        .iter()
+        .flatten()
+        .find_map(|child| find(child, Some(node), path))
+}
+
+/// `slice` past the key and separator of a `key: value` or `key = value`
+/// member; `slice` itself when it holds no separator, as when a YAML
+/// range already starts at the value
+fn member_value(slice: &str, language: AstLanguage) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in slice.char_indices() {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' && open == '"' {
+                escaped = true;
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        let rest = &slice[i + c.len_utf8()..];
+        let separator = match language {
+            AstLanguage::Toml => c == '=',
+            // A YAML key may hold a colon not followed by a space.
+            AstLanguage::Yaml => c == ':' && rest.chars().next().is_none_or(char::is_whitespace),
+            _ => c == ':',
+        };
+        if separator {
+            // A YAML block below its key keeps its indentation.
+            let rest = rest.trim_start_matches([' ', '\t']);
+            return match language {
+                AstLanguage::Yaml => rest
+                    .strip_prefix("\r\n")
+                    .or_else(|| rest.strip_prefix('\n'))
+                    .unwrap_or(rest),
+                _ => rest.trim_start(),
+            };
+        }
+        if c == '"' || c == '\'' {
+            quote = Some(c);
+        }
+    }
+    slice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_to_ast;
+    use super::*;
+
+    fn subtree(text: &str, language: AstLanguage, path: &str) -> SubtreeText {
+        get_subtree_text(text, language, &parse_to_ast(text, language), path)
+    }
+
+    #[test]
+    fn test_json_members_and_containers() {
+        let json = "{\"a:b\": \"x\\\"y\", \"n\":  1.50, \"obj\": {\"z\": [1, 2], \"a\": null}}";
+        let string = subtree(json, AstLanguage::Json, "$.a:b");
+        assert_eq!(string.source.as_deref(), Some("\"x\\\"y\""));
+        assert_eq!(string.value.as_deref(), Some("x\"y"));
+        assert_eq!(string.json.as_deref(), Some("\"x\\\"y\""));
+        assert_eq!(string.range.unwrap().start.offset, 8);
+
+        let number = subtree(json, AstLanguage::Json, "$.n");
+        assert_eq!(number.source.as_deref(), Some("1.50"));
+        assert_eq!(number.value.as_deref(), Some("1.5"));
+
+        let object = subtree(json, AstLanguage::Json, "$.obj");
+        assert_eq!(
+            object.source.as_deref(),
+            Some("{\"z\": [1, 2], \"a\": null}")
+        );
+        assert_eq!(
+            object.json.as_deref(),
+            Some("{\n  \"z\": [\n    1,\n    2\n  ],\n  \"a\": null\n}")
+        );
+        assert_eq!(object.value, object.json);
+        assert!(object.errors.is_empty());
+    }
+
+    #[test]
+    fn test_toml_and_yaml_members() {
+        let toml = "[server]\nurl = \"http://a = b\"\nports = [80, 443]\n";
+        let url = subtree(toml, AstLanguage::Toml, "$.server.url");
+        assert_eq!(url.source.as_deref(), Some("\"http://a = b\""));
+        assert_eq!(url.value.as_deref(), Some("http://a = b"));
+        let ports = subtree(toml, AstLanguage::Toml, "$.server.ports");
+        assert_eq!(ports.source.as_deref(), Some("[80, 443]"));
+
+        let yaml = "name: kogu\nurl: http://example.com\nserver:\n  port: 80\n";
+        let url = subtree(yaml, AstLanguage::Yaml, "$.url");
+        assert_eq!(url.source.as_deref(), Some("http://example.com"));
+        assert_eq!(url.value.as_deref(), Some("http://example.com"));
+        assert_eq!(url.json.as_deref(), Some("\"http://example.com\""));
+        let server = subtree(yaml, AstLanguage::Yaml, "$.server");
+        assert_eq!(server.source.as_deref(), Some("  port: 80"));
+        assert_eq!(server.value.as_deref(), Some("{\n  \"port\": 80\n}"));
+    }
+
+    #[test]
+    fn test_xml_element() {
+        let xml = "<root><item id=\"1\"><name>kogu</name></item></root>";
+        let item = subtree(xml, AstLanguage::Xml, "$.root.item");
+        assert_eq!(
+            item.source.as_deref(),
+            Some("<item id=\"1\"><name>kogu</name></item>")
+        );
+        assert_eq!(
+            item.json.as_deref(),
+            Some("{\n  \"@id\": \"1\",\n  \"name\": \"kogu\"\n}")
+        );
+    }
+
+    #[test]
+    fn test_missing_path() {
+        let result = subtree("[1]", AstLanguage::Json, "$[3]");
+        assert!(result.source.is_none());
+        assert_eq!(result.errors[0].message, "No node at path '$[3]'");
+    }
+}
@@ -0,0 +1,673 @@
+//! systemd unit file AST parser and linter with position tracking
+//!
+//! Sections become [`AstNodeType::Object`]s (`$.Service`) holding their
+//! assignments as leaf properties, indexed when a key repeats
+//! (`$.Service.ExecStartPre[1]`). Backslash continuations are joined as
+//! systemd joins them, and comments are kept so the tree mirrors the file.
+//!
+//! Alongside the tree the parser reports unknown sections, deprecated
+//! keys, keys placed in the wrong section, and mistakes that leave a
+//! `.service`, `.timer`, or `.socket` unit broken or impossible to enable.
+//! Malformed lines are reported and skipped, as systemd skips them.
+
+use std::collections::HashMap;
+
+use super::{
+    child_path, truncate_label, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange,
+};
+
+/// Sections systemd knows; `X-` sections are left to other tools
+const SECTIONS: [&str; 11] = [
+    "Unit",
+    "Install",
+    "Service",
+    "Socket",
+    "Timer",
+    "Mount",
+    "Automount",
+    "Swap",
+    "Path",
+    "Slice",
+    "Scope",
+];
+
+/// Sections that decide the unit type, in the order they are looked for
+const UNIT_TYPES: [&str; 9] = [
+    "Service",
+    "Socket",
+    "Timer",
+    "Mount",
+    "Automount",
+    "Swap",
+    "Path",
+    "Slice",
+    "Scope",
+];
+
+/// Deprecated keys and what replaces them
+const DEPRECATED: [(&str, &str); 15] = [
+    ("PermissionsStartOnly", "a + prefix on the Exec lines"),
+    ("MemoryLimit", "MemoryMax="),
+    ("CPUShares", "CPUWeight="),
+    ("StartupCPUShares", "StartupCPUWeight="),
+    ("BlockIOAccounting", "IOAccounting="),
+    ("BlockIOWeight", "IOWeight="),
+    ("StartupBlockIOWeight", "StartupIOWeight="),
+    ("BlockIODeviceWeight", "IODeviceWeight="),
+    ("BlockIOReadBandwidth", "IOReadBandwidthMax="),
+    ("BlockIOWriteBandwidth", "IOWriteBandwidthMax="),
+    ("OnFailureIsolate", "OnFailureJobMode="),
+    ("StartLimitInterval", "StartLimitIntervalSec= in [Unit]"),
+    ("RequiresOverridable", "Requires="),
+    ("RequisiteOverridable", "Requisite="),
+    ("TCPWrapName", "nothing; TCP wrappers support was removed"),
+];
+
+/// Keys only read from the [Install] section
+const INSTALL_KEYS: [&str; 6] = [
+    "WantedBy",
+    "RequiredBy",
+    "UpheldBy",
+    "Alias",
+    "Also",
+    "DefaultInstance",
+];
+
+/// Keys only read from the [Unit] section, besides `Condition*` and
+/// `Assert*`
+const UNIT_KEYS: [&str; 20] = [
+    "Description",
+    "Documentation",
+    "Wants",
+    "Requires",
+    "Requisite",
+    "BindsTo",
+    "PartOf",
+    "Upholds",
+    "Conflicts",
+    "Before",
+    "After",
+    "OnFailure",
+    "OnSuccess",
+    "PropagatesReloadTo",
+    "ReloadPropagatedFrom",
+    "JoinsNamespaceOf",
+    "RequiresMountsFor",
+    "DefaultDependencies",
+    "StartLimitIntervalSec",
+    "StartLimitBurst",
+];
+
+/// Words a shell would interpret but systemd passes on as arguments
+const SHELL_OPERATORS: [&str; 8] = ["|", "||", "&&", ">", ">>", "<", "2>&1", "&>"];
+
+/// Parse a systemd unit file to AST, with lint warnings as errors
+pub fn parse(text: &str) -> AstParseResult {
+    let mut parser = UnitParser {
+        text,
+        warnings: Vec::new(),
+    };
+    let (preamble, sections) = parser.read();
+    parser.lint(&preamble, &sections);
+    let ast = parser.root_node(preamble, sections);
+    if parser.warnings.is_empty() {
+        AstParseResult::success(ast)
+    } else {
+        AstParseResult::partial(ast, parser.warnings)
+    }
+}
+
+/// `Key=value` assignment, with continuation lines joined
+struct Assignment {
+    key: String,
+    value: String,
+    key_end: usize,
+    value_start: usize,
+    start: usize,
+    end: usize,
+}
+
+enum Item {
+    Comment { start: usize, end: usize },
+    Assignment(Assignment),
+}
+
+struct Section {
+    name: String,
+    start: usize,
+    header_end: usize,
+    items: Vec<Item>,
+}
+
+impl Section {
+    fn assignments(&self) -> impl Iterator<Item = &Assignment> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Assignment(assignment) => Some(assignment),
+            Item::Comment { .. } => None,
+        })
+    }
+
+    fn get(&self, key: &str) -> Option<&Assignment> {
+        self.assignments().filter(|a| a.key == key).last()
+    }
+
+    fn is_known(&self) -> bool {
+        SECTIONS.contains(&self.name.as_str())
+    }
+}
+
+struct UnitParser<'a> {
+    text: &'a str,
+    warnings: Vec<AstParseError>,
+}
+
+impl UnitParser<'_> {
+    fn range(&self, start: usize, end: usize) -> AstRange {
+        AstRange::from_offset(self.text, start, end)
+    }
+
+    fn warn(&mut self, start: usize, end: usize, message: impl Into<String>) {
+        let range = self.range(start, end);
+        self.warnings
+            .push(AstParseError::new(message).with_range(range));
+    }
+
+    /// Lines of the file as (start, end of content) pairs, `\r\n` aware
+    fn lines(&self) -> Vec<(usize, usize)> {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for line in self.text.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            lines.push((offset, offset + content.len()));
+            offset += line.len();
+        }
+        lines
+    }
+
+    /// Items before the first section header, and the sections
+    fn read(&mut self) -> (Vec<Item>, Vec<Section>) {
+        let lines = self.lines();
+        let mut preamble = Vec::new();
+        let mut sections: Vec<Section> = Vec::new();
+        let mut index = 0;
+        while let Some(&(line_start, line_end)) = lines.get(index) {
+            index += 1;
+            let line = &self.text[line_start..line_end];
+            let start = line_start + (line.len() - line.trim_start().len());
+            let content = line.trim();
+            let end = start + content.len();
+            if content.is_empty() {
+                continue;
+            }
+
+            let item = if content.starts_with(['#', ';']) {
+                Item::Comment { start, end }
+            } else if content.starts_with('[') {
+                match content.strip_suffix(']') {
+                    Some(name) => sections.push(Section {
+                        name: name[1..].to_string(),
+                        start,
+                        header_end: end,
+                        items: Vec::new(),
+                    }),
+                    None => self.warn(start, end, "Unterminated section header"),
+                }
+                continue;
+            } else {
+                let (assignment, consumed) = self.assignment(&lines[index - 1..], start);
+                index += consumed;
+                match assignment {
+                    Some(assignment) => Item::Assignment(assignment),
+                    None => continue,
+                }
+            };
+            match sections.last_mut() {
+                Some(section) => section.items.push(item),
+                None => preamble.push(item),
+            }
+        }
+        (preamble, sections)
+    }
+
+    /// Assignment starting at `start` on the first of `lines`, and the
+    /// number of continuation lines it took. Comment lines inside a
+    /// continuation are skipped, as systemd skips them.
+    fn assignment(
+        &mut self,
+        lines: &[(usize, usize)],
+        start: usize,
+    ) -> (Option<Assignment>, usize) {
+        let mut end = lines[0].1;
+        let mut consumed = 0;
+        let mut value_parts = Vec::new();
+        let mut part = &self.text[start..end];
+        loop {
+            let trimmed = part.trim_end();
+            let Some(joined) = trimmed.strip_suffix('\\') else {
+                value_parts.push(trimmed);
+                end = end - part.len() + trimmed.len();
+                break;
+            };
+            value_parts.push(joined);
+            let Some(&(next_start, next_end)) = lines.get(consumed + 1) else {
+                end = end - part.len() + joined.len();
+                break;
+            };
+            consumed += 1;
+            end = next_end;
+            part = &self.text[next_start..next_end];
+            if part.trim_start().starts_with(['#', ';']) {
+                part = "\\";
+            }
+        }
+        let logical = value_parts.join(" ");
+
+        let Some((key, value)) = logical.split_once('=') else {
+            self.warn(start, end, "Expected Key=Value");
+            return (None, consumed);
+        };
+        let key = key.trim_end();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            self.warn(start, end, "Expected Key=Value");
+            return (None, consumed);
+        }
+        let first_line = &self.text[start..lines[0].1];
+        let value_start = first_line.find('=').map_or(start, |eq| {
+            let after = &first_line[eq + 1..];
+            start + eq + 1 + (after.len() - after.trim_start().len())
+        });
+        let assignment = Assignment {
+            key: key.to_string(),
+            value: value.trim().to_string(),
+            key_end: start + key.len(),
+            value_start: value_start.min(end),
+            start,
+            end,
+        };
+        (Some(assignment), consumed)
+    }
+
+    fn lint(&mut self, preamble: &[Item], sections: &[Section]) {
+        for item in preamble {
+            if let Item::Assignment(a) = item {
+                self.warn(
+                    a.start,
+                    a.key_end,
+                    format!("{}= is outside of any section", a.key),
+                );
+            }
+        }
+
+        for section in sections {
+            let name = section.name.as_str();
+            if !section.is_known() {
+                if !name.starts_with("X-") {
+                    let message = SECTIONS
+                        .iter()
+                        .find(|known| known.eq_ignore_ascii_case(name))
+                        .map_or_else(
+                            || format!("Unknown section [{name}]"),
+                            |known| {
+                                format!("Unknown section [{name}]; section names are case-sensitive ([{known}])")
+                            },
+                        );
+                    self.warn(section.start, section.header_end, message);
+                }
+                continue;
+            }
+            for a in section.assignments() {
+                self.lint_key(name, a);
+            }
+            match name {
+                "Service" => self.lint_service(section),
+                "Timer" if !section.assignments().any(|a| a.key.starts_with("On")) => self.warn(
+                    section.start,
+                    section.header_end,
+                    "[Timer] has no OnCalendar= or other trigger",
+                ),
+                "Socket" if !section.assignments().any(|a| a.key.starts_with("Listen")) => self
+                    .warn(
+                        section.start,
+                        section.header_end,
+                        "[Socket] has no Listen*= setting",
+                    ),
+                _ => {}
+            }
+        }
+
+        let kind = sections
+            .iter()
+            .find(|section| ["Service", "Socket", "Timer"].contains(&section.name.as_str()));
+        let install = sections.iter().find(|section| section.name == "Install");
+        match (kind, install) {
+            (Some(kind), None) => self.warn(
+                kind.start,
+                kind.header_end,
+                "No [Install] section; the unit cannot be enabled with systemctl enable",
+            ),
+            (Some(kind), Some(install)) if kind.name == "Timer" => {
+                if let Some(wanted_by) = install
+                    .get("WantedBy")
+                    .filter(|a| a.value.split_whitespace().any(|t| t == "multi-user.target"))
+                {
+                    self.warn(
+                        wanted_by.value_start,
+                        wanted_by.end,
+                        "Timers are normally enabled with WantedBy=timers.target",
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Deprecated keys, keys in the wrong section, and deprecated values
+    fn lint_key(&mut self, section: &str, a: &Assignment) {
+        let key = a.key.as_str();
+        if let Some((_, replacement)) = DEPRECATED.iter().find(|(old, _)| *old == key) {
+            self.warn(
+                a.start,
+                a.key_end,
+                format!("{key}= is deprecated; use {replacement} instead"),
+            );
+        } else if section != "Install" && INSTALL_KEYS.contains(&key) {
+            self.warn(a.start, a.key_end, format!("{key}= belongs in [Install]"));
+        } else if section != "Unit"
+            && (UNIT_KEYS.contains(&key)
+                || key.starts_with("Condition")
+                || key.starts_with("Assert"))
+        {
+            self.warn(a.start, a.key_end, format!("{key}= belongs in [Unit]"));
+        } else if key == "KillMode" && a.value == "none" {
+            self.warn(
+                a.value_start,
+                a.end,
+                "KillMode=none is deprecated; processes are left running after stop",
+            );
+        }
+
+        if key.starts_with("Exec") && uses_shell_syntax(&a.value) {
+            self.warn(
+                a.value_start,
+                a.end,
+                format!(
+                    "Shell syntax in {key}= is not interpreted; run the command through /bin/sh -c"
+                ),
+            );
+        }
+    }
+
+    fn lint_service(&mut self, section: &Section) {
+        let oneshot = section.get("Type").is_some_and(|a| a.value == "oneshot");
+        // An empty assignment clears the commands set before it.
+        let mut exec_starts = Vec::new();
+        for a in section.assignments().filter(|a| a.key == "ExecStart") {
+            if a.value.is_empty() {
+                exec_starts.clear();
+            } else {
+                exec_starts.push(a);
+            }
+        }
+        match exec_starts.as_slice() {
+            [] if !oneshot => self.warn(
+                section.start,
+                section.header_end,
+                "[Service] has no ExecStart=",
+            ),
+            [_, extra, ..] if !oneshot => self.warn(
+                extra.start,
+                extra.key_end,
+                "Only Type=oneshot services may have more than one ExecStart=",
+            ),
+            _ => {}
+        }
+        if let Some(restart) = section
+            .get("Restart")
+            .filter(|a| oneshot && matches!(a.value.as_str(), "always" | "on-success"))
+        {
+            self.warn(
+                restart.value_start,
+                restart.end,
+                format!("Restart={} is not allowed with Type=oneshot", restart.value),
+            );
+        }
+    }
+
+    fn root_node(&self, preamble: Vec<Item>, sections: Vec<Section>) -> AstNode {
+        let kind = UNIT_TYPES
+            .iter()
+            .find(|kind| sections.iter().any(|section| section.name == **kind))
+            .map_or_else(|| "unit".to_string(), |kind| kind.to_ascii_lowercase());
+        let label = format!("systemd {kind} ({} sections)", sections.len());
+
+        let mut totals: HashMap<&str, usize> = HashMap::new();
+        for section in &sections {
+            *totals.entry(section.name.as_str()).or_insert(0) += 1;
+        }
+        let totals: HashMap<String, usize> = totals
+            .into_iter()
+            .map(|(name, total)| (name.to_string(), total))
+            .collect();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        let mut children = self.items(preamble, "$");
+        for section in sections {
+            let index = seen.entry(section.name.clone()).or_insert(0);
+            let path = if totals.get(&section.name).is_some_and(|&total| total > 1) {
+                format!("{}[{index}]", child_path("$", &section.name))
+            } else {
+                child_path("$", &section.name)
+            };
+            *index += 1;
+            children.push(self.section_node(section, path));
+        }
+        AstNode::new(
+            AstNodeType::Root,
+            "$".to_string(),
+            label,
+            self.range(0, self.text.len()),
+        )
+        .with_children(children)
+    }
+
+    fn section_node(&self, section: Section, path: String) -> AstNode {
+        let count = section.assignments().count();
+        let label = format!("[{}] ({count} keys)", section.name);
+        let end = section
+            .items
+            .last()
+            .map_or(section.header_end, |item| match item {
+                Item::Comment { end, .. } => *end,
+                Item::Assignment(a) => a.end,
+            });
+        AstNode::new(
+            AstNodeType::Object,
+            path.clone(),
+            label,
+            self.range(section.start, end),
+        )
+        .with_children(self.items(section.items, &path))
+    }
+
+    /// Nodes for `items` under `parent`, indexing keys that repeat
+    fn items(&self, items: Vec<Item>, parent: &str) -> Vec<AstNode> {
+        let mut totals: HashMap<String, usize> = HashMap::new();
+        for item in &items {
+            if let Item::Assignment(a) = item {
+                *totals.entry(a.key.clone()).or_insert(0) += 1;
+            }
+        }
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        items
+            .into_iter()
+            .map(|item| match item {
+                Item::Comment { start, end } => AstNode::new(
+                    AstNodeType::Comment,
+                    format!("{parent}/#comment"),
+                    truncate_label(&self.text[start..end]),
+                    self.range(start, end),
+                ),
+                Item::Assignment(a) => {
+                    let index = seen.entry(a.key.clone()).or_insert(0);
+                    let path = if totals.get(&a.key).is_some_and(|&total| total > 1) {
+                        format!("{}[{index}]", child_path(parent, &a.key))
+                    } else {
+                        child_path(parent, &a.key)
+                    };
+                    *index += 1;
+                    AstNode::new(AstNodeType::String, path, a.key, self.range(a.start, a.end))
+                        .with_value(serde_json::Value::String(a.value))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether an Exec command line relies on a shell: pipes, redirections,
+/// `&&` chains, or command substitution outside an explicit `sh -c`
+fn uses_shell_syntax(command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    let Some(program) = words
+        .next()
+        .map(|program| program.trim_start_matches(['@', '-', ':', '+', '!']))
+    else {
+        return false;
+    };
+    let shell = program
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| matches!(name, "sh" | "bash" | "dash" | "zsh" | "ksh"));
+    !shell && words.any(|word| SHELL_OPERATORS.contains(&word) || word.contains("$("))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn children(node: &AstNode) -> &[AstNode] {
+        node.children.as_deref().unwrap_or_default()
+    }
+
+    fn messages(result: &AstParseResult) -> Vec<&str> {
+        result.errors.iter().map(|e| e.message.as_str()).collect()
+    }
+
+    const SERVICE: &str = "# Web application\n[Unit]\nDescription=Demo app\nAfter=network.target\n\n[Service]\nType=simple\nExecStartPre=/usr/bin/mkdir -p /run/demo\nExecStartPre=/usr/bin/chown demo /run/demo\nExecStart=/usr/bin/demo \\\n    --port 8080 \\\n    --verbose\nEnvironment=\"A=1\" \"B=2\"\n\n[Install]\nWantedBy=multi-user.target\n";
+
+    #[test]
+    fn test_parse_sections_and_keys() {
+        let result = parse(SERVICE);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let ast = result.ast.unwrap();
+        assert_eq!(ast.label, "systemd service (3 sections)");
+
+        let nodes = children(&ast);
+        assert_eq!(nodes[0].node_type, AstNodeType::Comment);
+        let service = &nodes[2];
+        assert_eq!(service.path, "$.Service");
+        assert_eq!(service.label, "[Service] (5 keys)");
+
+        let keys = children(service);
+        assert_eq!(keys[1].path, "$.Service.ExecStartPre[0]");
+        assert_eq!(keys[2].path, "$.Service.ExecStartPre[1]");
+        let exec_start = &keys[3];
+        assert_eq!(exec_start.path, "$.Service.ExecStart");
+        assert_eq!(
+            exec_start.value,
+            Some(serde_json::json!(
+                "/usr/bin/demo      --port 8080      --verbose"
+            ))
+        );
+        assert_eq!(
+            (exec_start.range.start.line, exec_start.range.end.line),
+            (10, 12)
+        );
+        assert_eq!(keys[4].value, Some(serde_json::json!("\"A=1\" \"B=2\"")));
+    }
+
+    #[test]
+    fn test_warns_about_sections_and_keys() {
+        let text = "[unit]\nDescription=x\n[Service]\nExecStart=/bin/true\nMemoryLimit=1G\nWantedBy=multi-user.target\nAfter=network.target\nKillMode=none\n[X-Custom]\nAnything=goes\n";
+        let result = parse(text);
+        assert_eq!(
+            messages(&result),
+            [
+                "Unknown section [unit]; section names are case-sensitive ([Unit])",
+                "MemoryLimit= is deprecated; use MemoryMax= instead",
+                "WantedBy= belongs in [Install]",
+                "After= belongs in [Unit]",
+                "KillMode=none is deprecated; processes are left running after stop",
+                "No [Install] section; the unit cannot be enabled with systemctl enable",
+            ]
+        );
+        let memory = result.errors[1].range.unwrap();
+        assert_eq!(
+            (memory.start.line, memory.start.column, memory.end.column),
+            (5, 1, 12)
+        );
+        assert!(result.ast.is_some());
+    }
+
+    #[test]
+    fn test_warns_about_service_mistakes() {
+        let text = "[Service]\nType=oneshot\nRestart=always\nExecStart=/usr/bin/backup | gzip > /tmp/out\nExecStart=/bin/sh -c 'a | b'\n[Install]\nWantedBy=default.target\n";
+        let result = parse(text);
+        assert_eq!(
+            messages(&result),
+            [
+                "Shell syntax in ExecStart= is not interpreted; run the command through /bin/sh -c",
+                "Restart=always is not allowed with Type=oneshot",
+            ]
+        );
+
+        let result = parse(
+            "[Service]\nExecStart=/bin/a\nExecStart=/bin/b\n[Install]\nWantedBy=default.target\n",
+        );
+        assert_eq!(
+            messages(&result),
+            ["Only Type=oneshot services may have more than one ExecStart="]
+        );
+        assert_eq!(result.errors[0].range.unwrap().start.line, 3);
+
+        let result = parse("[Service]\nExecStart=/bin/a\nExecStart=\nExecStart=/bin/b\n[Install]\nWantedBy=default.target\n");
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_timer_and_socket_checks() {
+        let timer = parse("[Timer]\nPersistent=true\n[Install]\nWantedBy=multi-user.target\n");
+        assert_eq!(
+            messages(&timer),
+            [
+                "[Timer] has no OnCalendar= or other trigger",
+                "Timers are normally enabled with WantedBy=timers.target",
+            ]
+        );
+        assert_eq!(timer.ast.unwrap().label, "systemd timer (2 sections)");
+
+        let socket = parse("[Socket]\nAccept=yes\n");
+        assert_eq!(
+            messages(&socket),
+            [
+                "[Socket] has no Listen*= setting",
+                "No [Install] section; the unit cannot be enabled with systemctl enable",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_lines_are_skipped() {
+        let result = parse("Description=early\n[Unit\n[Unit]\nnot an assignment\nAfter=a.target\n");
+        assert_eq!(
+            messages(&result),
+            [
+                "Unterminated section header",
+                "Expected Key=Value",
+                "Description= is outside of any section",
+            ]
+        );
+        let ast = result.ast.unwrap();
+        assert_eq!(children(&ast)[0].path, "$.Description");
+        assert_eq!(children(&children(&ast)[1])[0].path, "$.Unit.After");
+    }
+}
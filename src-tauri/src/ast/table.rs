@@ -0,0 +1,499 @@
+//! Typed table model of CSV/TSV data for grid previews
+//!
+//! Unlike the CSV AST, which holds every cell with its range, the table
+//! model keeps only the column summaries and a window of sample rows.
+//! Records are read from a buffered reader a chunk at a time, so a file
+//! of hundreds of megabytes is summarised in constant memory. Column
+//! types are inferred as the CSV AST infers them, over every row, and
+//! empty cells count as nulls.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+use super::csv::{cell_value, detect_delimiter, is_boolean, is_number, ColumnType};
+use super::{AstError, AstParseError, AstPosition, AstRange};
+
+/// Bytes read from a file per chunk
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Start of the input used to detect the delimiter
+const DETECT_BYTES: usize = 64 * 1024;
+
+/// Most sample rows returned at once, whatever the options ask for
+const MAX_SAMPLE_ROWS: usize = 10_000;
+
+/// Options for [`parse_table`] and [`parse_table_file`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TableOptions {
+    /// Field separator; detected from the first lines when absent
+    pub delimiter: Option<char>,
+    /// Whether the first record names the columns
+    pub has_header: bool,
+    /// Index of the first data row to sample, for paging through the grid
+    pub offset: usize,
+    /// Data rows to sample, up to 10,000
+    pub sample_rows: usize,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            has_header: true,
+            offset: 0,
+            sample_rows: 100,
+        }
+    }
+}
+
+/// Column summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableColumn {
+    pub name: String,
+    /// "number", "boolean", "string", or "empty" when no cell has a value
+    pub column_type: &'static str,
+    /// Empty or missing cells
+    pub null_count: u64,
+}
+
+/// Result of [`parse_table`] and [`parse_table_file`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableModel {
+    pub columns: Vec<TableColumn>,
+    /// Sampled data rows, one value per column typed by its column;
+    /// empty and missing cells are null
+    pub rows: Vec<Vec<Value>>,
+    /// Data rows in the whole input
+    pub row_count: u64,
+    pub delimiter: char,
+    /// Bytes read
+    pub size: u64,
+    pub errors: Vec<AstParseError>,
+}
+
+/// Summarise CSV or TSV text as a table
+pub fn parse_table(text: &str, options: &TableOptions) -> TableModel {
+    read_table(text.as_bytes(), options, None)
+}
+
+/// Summarise a CSV or TSV file as a table, reading it a chunk at a time
+/// and stopping with [`AstError::Cancelled`] once `token` is cancelled
+pub fn parse_table_file(
+    path: &Path,
+    options: &TableOptions,
+    token: &CancellationToken,
+) -> Result<TableModel, AstError> {
+    let file = File::open(path).map_err(|e| AstError::Io(e.to_string()))?;
+    let table = read_table(
+        BufReader::with_capacity(CHUNK_SIZE, file),
+        options,
+        Some(token),
+    );
+    if token.is_cancelled() {
+        return Err(AstError::Cancelled);
+    }
+    Ok(table)
+}
+
+/// Summarise the records of `reader`, stopping early once `cancel` is
+/// cancelled; the caller discards the partial table
+fn read_table<R: BufRead>(
+    mut reader: R,
+    options: &TableOptions,
+    cancel: Option<&CancellationToken>,
+) -> TableModel {
+    let delimiter = match options.delimiter {
+        Some(delimiter) if delimiter.is_ascii() => u8::try_from(delimiter).unwrap_or(b','),
+        _ => reader.fill_buf().map_or(b',', |start| {
+            detect_delimiter(&String::from_utf8_lossy(
+                &start[..start.len().min(DETECT_BYTES)],
+            ))
+        }),
+    };
+    let mut records = Records::new(reader, delimiter);
+    let sample = options.offset..options.offset + options.sample_rows.min(MAX_SAMPLE_ROWS);
+    let mut summary = Summary::default();
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut header = None;
+
+    loop {
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            break;
+        }
+        let record = match records.next_record() {
+            Ok(Some(record)) => record,
+            Ok(None) => break,
+            Err(error) => {
+                errors.push(error);
+                break;
+            }
+        };
+        if options.has_header && header.is_none() {
+            header = Some(record);
+            continue;
+        }
+        let index = summary.add(&record);
+        if sample.contains(&index) {
+            rows.push(record);
+        }
+    }
+
+    let header = header.unwrap_or_default();
+    let width = summary.columns.len().max(header.len());
+    let columns: Vec<TableColumn> = (0..width)
+        .map(|index| {
+            let stats = summary.columns.get(index).copied().unwrap_or_default();
+            let name = header
+                .get(index)
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .map_or_else(|| format!("column {}", index + 1), ToString::to_string);
+            TableColumn {
+                name,
+                column_type: stats.column_type().as_str(),
+                null_count: summary.rows - stats.values,
+            }
+        })
+        .collect();
+    let types: Vec<ColumnType> = (0..width)
+        .map(|index| {
+            summary
+                .columns
+                .get(index)
+                .copied()
+                .unwrap_or_default()
+                .column_type()
+        })
+        .collect();
+    let rows = rows
+        .into_iter()
+        .map(|record| {
+            types
+                .iter()
+                .enumerate()
+                .map(|(index, &column_type)| {
+                    record
+                        .get(index)
+                        .map_or(Value::Null, |cell| cell_value(cell, column_type).1)
+                })
+                .collect()
+        })
+        .collect();
+
+    TableModel {
+        columns,
+        rows,
+        row_count: summary.rows,
+        delimiter: char::from(delimiter),
+        size: records.offset,
+        errors,
+    }
+}
+
+/// What the cells of one column have in common so far
+#[derive(Debug, Clone, Copy)]
+struct ColumnStats {
+    /// Non-empty cells
+    values: u64,
+    number: bool,
+    boolean: bool,
+}
+
+impl Default for ColumnStats {
+    fn default() -> Self {
+        Self {
+            values: 0,
+            number: true,
+            boolean: true,
+        }
+    }
+}
+
+impl ColumnStats {
+    const fn column_type(self) -> ColumnType {
+        if self.values == 0 {
+            ColumnType::Empty
+        } else if self.number {
+            ColumnType::Number
+        } else if self.boolean {
+            ColumnType::Boolean
+        } else {
+            ColumnType::String
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Summary {
+    rows: u64,
+    columns: Vec<ColumnStats>,
+}
+
+impl Summary {
+    /// Count a data row; returns its index
+    fn add(&mut self, record: &[String]) -> usize {
+        if self.columns.len() < record.len() {
+            self.columns.resize(record.len(), ColumnStats::default());
+        }
+        for (stats, cell) in self.columns.iter_mut().zip(record) {
+            let value = cell.trim();
+            if !value.is_empty() {
+                stats.values += 1;
+                stats.number &= is_number(value);
+                stats.boolean &= is_boolean(value);
+            }
+        }
+        let index = usize::try_from(self.rows).unwrap_or(usize::MAX);
+        self.rows += 1;
+        index
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    FieldStart,
+    Unquoted,
+    Quoted,
+    /// A quote inside a quoted field: the end of the field, or the first
+    /// half of a doubled quote
+    QuoteSeen,
+}
+
+/// RFC 4180 records read incrementally, as the CSV AST splits them:
+/// quoted fields may hold delimiters, newlines, and doubled quotes, text
+/// after a closing quote is kept, and blank lines are skipped.
+struct Records<R> {
+    reader: R,
+    delimiter: u8,
+    /// Bytes consumed so far
+    offset: u64,
+    line: usize,
+    line_start: u64,
+    /// The last record ended with `\r`; a following `\n` belongs to it
+    skip_newline: bool,
+}
+
+impl<R: BufRead> Records<R> {
+    const fn new(reader: R, delimiter: u8) -> Self {
+        Self {
+            reader,
+            delimiter,
+            offset: 0,
+            line: 1,
+            line_start: 0,
+            skip_newline: false,
+        }
+    }
+
+    fn position(&self, offset: u64) -> AstPosition {
+        let column = usize::try_from(offset - self.line_start).unwrap_or(usize::MAX);
+        AstPosition::new(
+            self.line,
+            column + 1,
+            usize::try_from(offset).unwrap_or(usize::MAX),
+        )
+    }
+
+    fn next_record(&mut self) -> Result<Option<Vec<String>>, AstParseError> {
+        let mut record = Vec::new();
+        let mut field = Vec::new();
+        let mut state = State::FieldStart;
+        // Whether the record has any content, to skip blank lines
+        let mut started = false;
+        let mut quote = AstPosition::new(1, 1, 0);
+
+        loop {
+            let chunk = self
+                .reader
+                .fill_buf()
+                .map_err(|e| AstParseError::new(e.to_string()))?;
+            if chunk.is_empty() {
+                if state == State::Quoted {
+                    let end = AstPosition::new(quote.line, quote.column + 1, quote.offset + 1);
+                    return Err(AstParseError::new("Unterminated quoted field")
+                        .with_range(AstRange::new(quote, end)));
+                }
+                if !started {
+                    return Ok(None);
+                }
+                record.push(String::from_utf8_lossy(&field).into_owned());
+                return Ok(Some(record));
+            }
+
+            let mut used = 0;
+            let mut complete = false;
+            for &byte in chunk {
+                let offset = self.offset + used as u64;
+                used += 1;
+                if byte == b'\n' {
+                    self.line += 1;
+                    self.line_start = offset + 1;
+                }
+                if std::mem::take(&mut self.skip_newline) && byte == b'\n' {
+                    continue;
+                }
+                match (state, byte) {
+                    (State::Quoted, b'"') => state = State::QuoteSeen,
+                    (State::Quoted, _) => field.push(byte),
+                    (State::QuoteSeen, b'"') => {
+                        field.push(b'"');
+                        state = State::Quoted;
+                    }
+                    (State::FieldStart, b'"') => {
+                        let column = usize::try_from(offset - self.line_start).unwrap_or(0);
+                        quote = AstPosition::new(
+                            self.line,
+                            column + 1,
+                            usize::try_from(offset).unwrap_or(usize::MAX),
+                        );
+                        started = true;
+                        state = State::Quoted;
+                    }
+                    (_, b'\n' | b'\r') => {
+                        self.skip_newline = byte == b'\r';
+                        if started {
+                            record.push(String::from_utf8_lossy(&field).into_owned());
+                            complete = true;
+                            break;
+                        }
+                    }
+                    (_, byte) if byte == self.delimiter => {
+                        record.push(
+                            String::from_utf8_lossy(&std::mem::take(&mut field)).into_owned(),
+                        );
+                        started = true;
+                        state = State::FieldStart;
+                    }
+                    (_, byte) => {
+                        field.push(byte);
+                        started = true;
+                        state = State::Unquoted;
+                    }
+                }
+            }
+            self.reader.consume(used);
+            self.offset += used as u64;
+            if complete {
+                return Ok(Some(record));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write as _;
+
+    #[test]
+    fn test_columns_types_and_nulls() {
+        let csv = "id,name,active,score\n1,Alice,true,9.5\n2,,false,\n3,Carol,TRUE,7\n";
+        let table = parse_table(csv, &TableOptions::default());
+        assert!(table.errors.is_empty());
+        assert_eq!(table.row_count, 3);
+        assert_eq!(table.delimiter, ',');
+        let summary: Vec<(&str, &str, u64)> = table
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.column_type, c.null_count))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("id", "number", 0),
+                ("name", "string", 1),
+                ("active", "boolean", 0),
+                ("score", "number", 1),
+            ]
+        );
+        assert_eq!(
+            table.rows[1],
+            [json!(2), Value::Null, json!(false), Value::Null]
+        );
+        assert_eq!(table.size, csv.len() as u64);
+    }
+
+    #[test]
+    fn test_sample_window_and_ragged_rows() {
+        let csv = "a\tb\n1\tx\n2\n3\ty\tz\n\n4\tw\n";
+        let options = TableOptions {
+            offset: 1,
+            sample_rows: 2,
+            ..TableOptions::default()
+        };
+        let table = parse_table(csv, &options);
+        assert_eq!(table.delimiter, '\t');
+        assert_eq!(table.row_count, 4);
+        assert_eq!(table.columns[2].name, "column 3");
+        assert_eq!(table.columns[2].null_count, 3);
+        assert_eq!(table.columns[1].null_count, 1);
+        assert_eq!(
+            table.rows,
+            [
+                vec![json!(2), Value::Null, Value::Null],
+                vec![json!(3), json!("y"), json!("z")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quoted_fields_across_chunks() {
+        let csv = "name;note\r\n\"Smith; John\";\"said \"\"hi\"\"\r\nthen left\"\r\nDoe;plain\r\n";
+        // A one-byte buffer splits every field across reads.
+        let table = read_table(
+            BufReader::with_capacity(1, csv.as_bytes()),
+            &TableOptions {
+                delimiter: Some(';'),
+                has_header: false,
+                ..TableOptions::default()
+            },
+            None,
+        );
+        assert!(table.errors.is_empty(), "{:?}", table.errors);
+        assert_eq!(table.columns[0].name, "column 1");
+        assert_eq!(
+            table.rows[1],
+            [json!("Smith; John"), json!("said \"hi\"\r\nthen left")]
+        );
+        assert_eq!(table.row_count, 3);
+    }
+
+    #[test]
+    fn test_unterminated_quote_and_files() {
+        let table = parse_table("a,b\n1,\"open\n2,3\n", &TableOptions::default());
+        assert_eq!(table.errors[0].message, "Unterminated quoted field");
+        let range = table.errors[0].range.unwrap();
+        assert_eq!((range.start.line, range.start.column), (2, 3));
+        assert_eq!(table.row_count, 0);
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "x,y\n1,2\n").unwrap();
+        let token = CancellationToken::new();
+        let table = parse_table_file(file.path(), &TableOptions::default(), &token).unwrap();
+        assert_eq!(table.rows, [vec![json!(1), json!(2)]]);
+        assert!(matches!(
+            parse_table_file(
+                Path::new("/nonexistent/table.csv"),
+                &TableOptions::default(),
+                &token
+            ),
+            Err(AstError::Io(_))
+        ));
+
+        token.cancel();
+        assert!(matches!(
+            parse_table_file(file.path(), &TableOptions::default(), &token),
+            Err(AstError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! TOML AST parser with position tracking
+//!
+//! Built on the span-preserving `toml::de::DeTable` parser. Standard
+//! tables (`[a.b]`), array-of-tables (`[[bin]]`), inline tables, and
+//! dotted keys (`a.b.c = 1`) all resolve to the same nested structure, so
+//! `[package]\nname = "x"` and `package.name = "x"` both yield the path
+//! `$.package.name`. Entries keep their source order.
+
+use std::ops::Range;
+
+use ::toml::de::{DeTable, DeValue};
+use ::toml::Spanned;
+
+use super::{
+    child_path, offset_to_position, AstNode, AstNodeType, AstParseError, AstParseResult, AstRange,
+};
+
+/// Parse TOML text to AST with position information
+pub fn parse(text: &str) -> AstParseResult {
+    match DeTable::parse(text) {
+        Ok(root) => {
+            let children = table_children(text, root.get_ref(), "$");
+            let range = AstRange::from_offset(text, 0, text.len());
+            let label = format!("{{}} ({} properties)", children.len());
+            AstParseResult::success(
+                AstNode::new(AstNodeType::Object, "$".to_string(), label, range)
+                    .with_children(children),
+            )
+        }
+        Err(e) => {
+            let mut error = AstParseError::new(e.message());
+            if let Some(span) = e.span() {
+                error = error.with_range(AstRange::from_offset(text, span.start, span.end));
+            }
+            AstParseResult::failure(vec![error])
+        }
+    }
+}
+
+/// Property nodes for a table's entries, in source order.
+fn table_children(text: &str, table: &DeTable<'_>, path: &str) -> Vec<AstNode> {
+    let mut entries: Vec<_> = table.iter().collect();
+    // The table map is key-ordered; restore document order.
+    entries.sort_by_key(|(key, _)| key.span().start);
+
+    entries
+        .into_iter()
+        .map(|(key, value)| {
+            let key_str = key.get_ref().as_ref();
+            let path = child_path(path, key_str);
+            let value_node = value_to_ast(text, value, &path);
+            let start = key.span().start.min(value_node.range.start.offset);
+            let range = AstRange::new(offset_to_position(text, start), value_node.range.end);
+
+            let mut prop_node =
+                AstNode::new(AstNodeType::Property, path, key_str.to_string(), range);
+            match value_node.node_type {
+                AstNodeType::Object | AstNodeType::Array => {
+                    prop_node = prop_node.with_children(vec![value_node]);
+                }
+                _ => {
+                    prop_node.value.clone_from(&value_node.value);
+                    prop_node.node_type = value_node.node_type;
+                }
+            }
+            prop_node
+        })
+        .collect()
+}
+
+/// Span of a value, widened to cover its children. Tables opened by a
+/// header are spanned by the header alone, and their entries follow it.
+fn covering_range(text: &str, span: Range<usize>, children: &[AstNode]) -> AstRange {
+    let end = children
+        .iter()
+        .map(|c| c.range.end.offset)
+        .fold(span.end, usize::max);
+    AstRange::from_offset(text, span.start, end)
+}
+
+fn value_to_ast(text: &str, value: &Spanned<DeValue<'_>>, path: &str) -> AstNode {
+    let span = value.span();
+    let raw = text.get(span.clone()).unwrap_or_default();
+    match value.get_ref() {
+        DeValue::Table(table) => {
+            let children = table_children(text, table, path);
+            let range = covering_range(text, span, &children);
+            let label = format!("{{}} ({} properties)", children.len());
+            AstNode::new(AstNodeType::Object, path.to_string(), label, range)
+                .with_children(children)
+        }
+        DeValue::Array(items) => {
+            let children: Vec<AstNode> = items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| value_to_ast(text, item, &format!("{path}[{index}]")))
+                .collect();
+            let range = covering_range(text, span, &children);
+            let label = format!("[] ({} items)", children.len());
+            AstNode::new(AstNodeType::Array, path.to_string(), label, range).with_children(children)
+        }
+        DeValue::String(s) => {
+            let label = if s.chars().count() > 50 {
+                format!("\"{}...\"", s.chars().take(47).collect::<String>())
+            } else {
+                format!("\"{s}\"")
+            };
+            AstNode::new(
+                AstNodeType::String,
+                path.to_string(),
+                label,
+                AstRange::from_offset(text, span.start, span.end),
+            )
+            .with_value(serde_json::Value::String(s.to_string()))
+        }
+        DeValue::Integer(n) => {
+            // Integers beyond i64 keep their source text.
+            let value = i64::from_str_radix(n.as_str(), n.radix())
+                .map_or_else(|_| serde_json::json!(raw), |v| serde_json::json!(v));
+            AstNode::new(
+                AstNodeType::Number,
+                path.to_string(),
+                raw.to_string(),
+                AstRange::from_offset(text, span.start, span.end),
+            )
+            .with_value(value)
+        }
+        DeValue::Float(f) => {
+            // JSON has no NaN or infinity; those keep their source text.
+            let value = f
+                .as_str()
+                .parse::<f64>()
+                .ok()
+                .and_then(|v| serde_json::Number::from_f64(v).map(serde_json::Value::Number))
+                .unwrap_or_else(|| serde_json::json!(raw));
+            AstNode::new(
+                AstNodeType::Number,
+                path.to_string(),
+                raw.to_string(),
+                AstRange::from_offset(text, span.start, span.end),
+            )
+            .with_value(value)
+        }
+        DeValue::Boolean(b) => AstNode::new(
+            AstNodeType::Boolean,
+            path.to_string(),
+            b.to_string(),
+            AstRange::from_offset(text, span.start, span.end),
+        )
+        .with_value(serde_json::Value::Bool(*b)),
+        DeValue::Datetime(dt) => AstNode::new(
+            AstNodeType::String,
+            path.to_string(),
+            dt.to_string(),
+            AstRange::from_offset(text, span.start, span.end),
+        )
+        .with_value(serde_json::Value::String(dt.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deepest node at `path` (a property's wrapped value shares its path).
+    fn find<'a>(node: &'a AstNode, path: &str) -> Option<&'a AstNode> {
+        node.children
+            .iter()
+            .flatten()
+            .find_map(|child| find(child, path))
+            .or_else(|| (node.path == path).then_some(node))
+    }
+
+    #[test]
+    fn test_parse_cargo_manifest() {
+        let toml = "[package]\nname = \"kogu\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n";
+        let ast = parse(toml).ast.unwrap();
+        let children = ast.children.as_ref().unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].path, "$.package");
+        assert_eq!(children[1].path, "$.dependencies");
+
+        let name = find(&ast, "$.package.name").unwrap();
+        assert_eq!(name.node_type, AstNodeType::String);
+        assert_eq!(name.value, Some(serde_json::json!("kogu")));
+        assert_eq!(name.range.start.line, 2);
+
+        let feature = find(&ast, "$.dependencies.serde.features[0]").unwrap();
+        assert_eq!(feature.value, Some(serde_json::json!("derive")));
+        assert_eq!(feature.range.start.line, 6);
+
+        // The [package] table spans from its header to its last entry.
+        let package = &children[0].children.as_ref().unwrap()[0];
+        assert_eq!(package.range.start.line, 1);
+        assert_eq!(package.range.end.line, 3);
+    }
+
+    #[test]
+    fn test_array_of_tables_and_source_order() {
+        let toml = "zeta = 1\nalpha = 2\n[[bin]]\nname = \"a\"\n[[bin]]\nname = \"b\"\n";
+        let ast = parse(toml).ast.unwrap();
+        let keys: Vec<&str> = ast
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(keys, ["zeta", "alpha", "bin"]);
+
+        let second = find(&ast, "$.bin[1].name").unwrap();
+        assert_eq!(second.value, Some(serde_json::json!("b")));
+        assert_eq!(second.range.start.line, 6);
+    }
+
+    #[test]
+    fn test_dotted_and_quoted_keys() {
+        let toml = "a.b.c = 0x1F\n\"key.with dots\" = 1.5\nwhen = 1979-05-27T07:32:00Z\n";
+        let ast = parse(toml).ast.unwrap();
+
+        let dotted = find(&ast, "$.a.b.c").unwrap();
+        assert_eq!(dotted.node_type, AstNodeType::Number);
+        assert_eq!(dotted.value, Some(serde_json::json!(31)));
+        assert_eq!(dotted.label, "c");
+
+        let quoted = find(&ast, "$['key.with dots']").unwrap();
+        assert_eq!(quoted.value, Some(serde_json::json!(1.5)));
+
+        let when = find(&ast, "$.when").unwrap();
+        assert_eq!(when.value, Some(serde_json::json!("1979-05-27T07:32:00Z")));
+    }
+
+    #[test]
+    fn test_parse_error_has_range() {
+        let result = parse("[package]\nname = \n");
+        assert!(result.ast.is_none());
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].range.unwrap().start.line, 2);
+
+        let duplicate = parse("a = 1\na = 2\n");
+        assert!(duplicate.ast.is_none());
+    }
+}
@@ -0,0 +1,1253 @@
+//! Type definitions generated from a JSON sample or a JSON Schema
+//!
+//! The input is first read into a language-neutral [`Type`] tree. For a
+//! sample, every object becomes a type and array items are merged, so a
+//! field missing from some items is optional and one that is sometimes
+//! null is nullable. For a schema, `required`, `type: [..., "null"]`,
+//! `anyOf`/`oneOf`/`allOf`, `additionalProperties`, and local `$ref`s
+//! are honoured. Objects are then named (identical shapes share a name)
+//! and emitted as Rust structs, TypeScript interfaces, Kotlin data
+//! classes, or Go structs, in field order.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use super::convert::{read_json, Value};
+use super::AstParseError;
+
+/// Language to generate type definitions in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetLanguage {
+    /// Structs deriving serde's `Serialize` and `Deserialize`
+    Rust,
+    /// Exported interfaces
+    TypeScript,
+    /// `kotlinx.serialization` data classes
+    Kotlin,
+    /// Structs with `json` tags
+    Go,
+}
+
+/// How the input document is read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeInput {
+    /// A schema when the root has `$schema`, or a `type` alongside
+    /// `properties` or `items`; a sample otherwise
+    #[default]
+    Auto,
+    Sample,
+    Schema,
+}
+
+/// Field names in the generated code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldNaming {
+    /// The target's convention (`snake_case` for Rust, `camelCase` for
+    /// Kotlin, `PascalCase` for Go), mapped back to the JSON key
+    #[default]
+    Idiomatic,
+    /// JSON keys as written, where the target allows it
+    Preserve,
+}
+
+/// Which fields are optional
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Optionality {
+    /// Fields missing from some sample items, or not `required` by the
+    /// schema
+    #[default]
+    Inferred,
+    AllOptional,
+    AllRequired,
+}
+
+/// Options for [`generate_types`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TypeGenOptions {
+    /// Name of the root type
+    pub root_name: String,
+    pub input: TypeInput,
+    pub field_naming: FieldNaming,
+    pub optionality: Optionality,
+}
+
+impl Default for TypeGenOptions {
+    fn default() -> Self {
+        Self {
+            root_name: "Root".to_string(),
+            input: TypeInput::Auto,
+            field_naming: FieldNaming::Idiomatic,
+            optionality: Optionality::Inferred,
+        }
+    }
+}
+
+/// Result of [`generate_types`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeGenResult {
+    /// Generated definitions; absent when the input is not valid JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// JSON errors, and schema references that could not be resolved
+    pub errors: Vec<AstParseError>,
+}
+
+/// Generate type definitions in `target` for the JSON sample or schema
+/// `text`
+pub fn generate_types(
+    text: &str,
+    target: TargetLanguage,
+    options: &TypeGenOptions,
+) -> TypeGenResult {
+    let document = match read_json(text) {
+        Ok(document) => document,
+        Err(errors) => return TypeGenResult { code: None, errors },
+    };
+    let root_name = pascal_case(&options.root_name);
+    let is_schema = match options.input {
+        TypeInput::Auto => looks_like_schema(&document),
+        TypeInput::Sample => false,
+        TypeInput::Schema => true,
+    };
+    let mut errors = Vec::new();
+    let root = if is_schema {
+        let mut reader = SchemaReader {
+            document: &document,
+            root_name: &root_name,
+            stack: Vec::new(),
+            errors: Vec::new(),
+        };
+        let root = reader.read(&document, &root_name);
+        errors = reader.errors;
+        root
+    } else {
+        sample_type(&document, &root_name)
+    };
+
+    let mut registry = Registry::default();
+    let root = registry.register(root);
+    let emitter = Emitter {
+        options,
+        definitions: &registry.definitions,
+    };
+    let alias = match &root.shape {
+        Shape::Named(name) if *name == root_name && !root.nullable => None,
+        _ => Some((root_name.as_str(), &root)),
+    };
+    let code = match target {
+        TargetLanguage::Rust => emitter.rust(alias),
+        TargetLanguage::TypeScript => emitter.typescript(alias),
+        TargetLanguage::Kotlin => emitter.kotlin(alias),
+        TargetLanguage::Go => emitter.go(alias),
+    };
+    TypeGenResult {
+        code: Some(code),
+        errors,
+    }
+}
+
+fn looks_like_schema(document: &Value) -> bool {
+    let Value::Map(entries) = document else {
+        return false;
+    };
+    get(entries, "$schema").is_some()
+        || (matches!(get(entries, "type"), Some(Value::String(_)))
+            && (get(entries, "properties").is_some() || get(entries, "items").is_some()))
+}
+
+fn get<'v>(entries: &'v [(String, Value)], key: &str) -> Option<&'v Value> {
+    entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+// ============================================================================
+// Type model
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// Nothing but nulls or empty arrays seen
+    Unknown,
+    /// Values of incompatible kinds
+    Any,
+    Bool,
+    Integer,
+    Float,
+    String,
+    List(Box<Type>),
+    /// Object with arbitrary keys and values of one type
+    Map(Box<Type>),
+    Object(Object),
+    /// Registered object type
+    Named(String),
+    /// Schema definition referring back to itself
+    Recursive(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Type {
+    shape: Shape,
+    nullable: bool,
+}
+
+impl Type {
+    const fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            nullable: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Object {
+    /// Name suggested by the key or definition the object came from
+    hint: String,
+    description: Option<String>,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    key: String,
+    ty: Type,
+    optional: bool,
+    description: Option<String>,
+}
+
+/// Type that fits values of both `a` and `b`
+fn merge(a: Type, b: Type) -> Type {
+    let nullable = a.nullable || b.nullable;
+    let shape = match (a.shape, b.shape) {
+        (Shape::Unknown, other) | (other, Shape::Unknown) => other,
+        (Shape::Integer, Shape::Float) | (Shape::Float, Shape::Integer) => Shape::Float,
+        (Shape::List(x), Shape::List(y)) => Shape::List(Box::new(merge(*x, *y))),
+        (Shape::Map(x), Shape::Map(y)) => Shape::Map(Box::new(merge(*x, *y))),
+        (Shape::Object(x), Shape::Object(y)) => Shape::Object(merge_objects(x, y, true)),
+        (x, y) if x == y => x,
+        _ => Shape::Any,
+    };
+    Type { shape, nullable }
+}
+
+/// Fields of both objects in first-seen order. With `missing_optional`,
+/// a field only one side has becomes optional.
+fn merge_objects(mut a: Object, b: Object, missing_optional: bool) -> Object {
+    let mut others = b.fields;
+    for field in &mut a.fields {
+        if let Some(index) = others.iter().position(|other| other.key == field.key) {
+            let other = others.remove(index);
+            let ty = std::mem::replace(&mut field.ty, Type::new(Shape::Unknown));
+            field.ty = merge(ty, other.ty);
+            field.optional |= other.optional;
+            field.description = field.description.take().or(other.description);
+        } else {
+            field.optional |= missing_optional;
+        }
+    }
+    for mut field in others {
+        field.optional |= missing_optional;
+        a.fields.push(field);
+    }
+    a.description = a.description.or(b.description);
+    a
+}
+
+fn sample_type(value: &Value, hint: &str) -> Type {
+    let shape = match value {
+        Value::Null => {
+            return Type {
+                shape: Shape::Unknown,
+                nullable: true,
+            }
+        }
+        Value::Bool(_) => Shape::Bool,
+        Value::Number(n) if n.is_f64() => Shape::Float,
+        Value::Number(_) => Shape::Integer,
+        Value::String(_) => Shape::String,
+        Value::List(items) => {
+            let item_hint = singular(hint);
+            let item = items
+                .iter()
+                .map(|item| sample_type(item, &item_hint))
+                .reduce(merge)
+                .unwrap_or(Type::new(Shape::Unknown));
+            Shape::List(Box::new(item))
+        }
+        Value::Map(entries) => Shape::Object(Object {
+            hint: hint.to_string(),
+            description: None,
+            fields: entries
+                .iter()
+                .map(|(key, value)| Field {
+                    key: key.clone(),
+                    ty: sample_type(value, key),
+                    optional: false,
+                    description: None,
+                })
+                .collect(),
+        }),
+    };
+    Type::new(shape)
+}
+
+/// Item name for an array under `hint`: `users` → `user`
+fn singular(hint: &str) -> String {
+    if let Some(stem) = hint.strip_suffix("ies").filter(|s| !s.is_empty()) {
+        return format!("{stem}y");
+    }
+    if hint.len() > 1 && hint.ends_with('s') && !hint.ends_with("ss") {
+        hint[..hint.len() - 1].to_string()
+    } else {
+        format!("{hint}Item")
+    }
+}
+
+struct SchemaReader<'s> {
+    document: &'s Value,
+    root_name: &'s str,
+    /// `$ref`s being read, to detect recursion
+    stack: Vec<String>,
+    errors: Vec<AstParseError>,
+}
+
+impl SchemaReader<'_> {
+    fn read(&mut self, schema: &Value, hint: &str) -> Type {
+        let Value::Map(schema) = schema else {
+            // `true` and `{}` accept anything; `false` accepts nothing.
+            return Type::new(Shape::Any);
+        };
+        if let Some(Value::String(reference)) = get(schema, "$ref") {
+            return self.reference(reference);
+        }
+        let hint = match get(schema, "title") {
+            Some(Value::String(title)) => title.as_str(),
+            _ => hint,
+        };
+        let description = match get(schema, "description") {
+            Some(Value::String(description)) => Some(description.clone()),
+            _ => None,
+        };
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::List(options)) = get(schema, key) {
+                if let Some(ty) = options.iter().map(|o| self.read(o, hint)).reduce(merge) {
+                    return with_description(ty, description);
+                }
+            }
+        }
+        if let Some(Value::List(parts)) = get(schema, "allOf") {
+            let combined =
+                parts
+                    .iter()
+                    .map(|p| self.read(p, hint))
+                    .reduce(|a, b| match (a.shape, b.shape) {
+                        (Shape::Object(x), Shape::Object(y)) => Type {
+                            shape: Shape::Object(merge_objects(x, y, false)),
+                            nullable: a.nullable && b.nullable,
+                        },
+                        (x, y) => merge(
+                            Type {
+                                shape: x,
+                                nullable: a.nullable,
+                            },
+                            Type {
+                                shape: y,
+                                nullable: b.nullable,
+                            },
+                        ),
+                    });
+            if let Some(ty) = combined {
+                return with_description(ty, description);
+            }
+        }
+
+        let mut types: Vec<&str> = match get(schema, "type") {
+            Some(Value::String(name)) => vec![name.as_str()],
+            Some(Value::List(names)) => names
+                .iter()
+                .filter_map(|name| match name {
+                    Value::String(name) => Some(name.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ if get(schema, "properties").is_some() => vec!["object"],
+            _ if get(schema, "items").is_some() => vec!["array"],
+            _ => Vec::new(),
+        };
+        let nullable = types.contains(&"null");
+        types.retain(|name| *name != "null");
+        let ty = if types.is_empty() {
+            match get(schema, "enum").or_else(|| get(schema, "const")) {
+                Some(Value::List(values)) => values
+                    .iter()
+                    .map(|value| sample_type(value, hint))
+                    .reduce(merge)
+                    .unwrap_or(Type::new(Shape::Any)),
+                Some(value) => sample_type(value, hint),
+                None if nullable => Type::new(Shape::Unknown),
+                None => Type::new(Shape::Any),
+            }
+        } else {
+            types
+                .iter()
+                .map(|name| Type::new(self.shape(schema, name, hint, description.clone())))
+                .reduce(merge)
+                .unwrap_or(Type::new(Shape::Any))
+        };
+        let ty = Type {
+            nullable: ty.nullable || nullable,
+            ..ty
+        };
+        with_description(ty, description)
+    }
+
+    fn shape(
+        &mut self,
+        schema: &[(String, Value)],
+        name: &str,
+        hint: &str,
+        description: Option<String>,
+    ) -> Shape {
+        match name {
+            "boolean" => Shape::Bool,
+            "integer" => Shape::Integer,
+            "number" => Shape::Float,
+            "string" => Shape::String,
+            "array" => {
+                let item = get(schema, "items").map_or(Type::new(Shape::Any), |items| {
+                    self.read(items, &singular(hint))
+                });
+                Shape::List(Box::new(item))
+            }
+            "object" => {
+                let required: Vec<&str> = match get(schema, "required") {
+                    Some(Value::List(names)) => names
+                        .iter()
+                        .filter_map(|name| match name {
+                            Value::String(name) => Some(name.as_str()),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                if let Some(Value::Map(properties)) = get(schema, "properties") {
+                    Shape::Object(Object {
+                        hint: hint.to_string(),
+                        description,
+                        fields: properties
+                            .iter()
+                            .map(|(key, property)| {
+                                let ty = self.read(property, key);
+                                let description = match property {
+                                    Value::Map(property) => match get(property, "description") {
+                                        Some(Value::String(d)) => Some(d.clone()),
+                                        _ => None,
+                                    },
+                                    _ => None,
+                                };
+                                Field {
+                                    key: key.clone(),
+                                    ty,
+                                    optional: !required.contains(&key.as_str()),
+                                    description,
+                                }
+                            })
+                            .collect(),
+                    })
+                } else {
+                    let values = match get(schema, "additionalProperties") {
+                        Some(Value::Map(values)) => {
+                            self.read(&Value::Map(values.clone()), &singular(hint))
+                        }
+                        _ => Type::new(Shape::Any),
+                    };
+                    Shape::Map(Box::new(values))
+                }
+            }
+            _ => Shape::Any,
+        }
+    }
+
+    /// Type of a local `$ref` (`#`, `#/$defs/Name`, `#/definitions/Name`)
+    fn reference(&mut self, reference: &str) -> Type {
+        let name = match reference.rsplit('/').next() {
+            Some("#") | None => self.root_name.to_string(),
+            Some(name) => pascal_case(name),
+        };
+        if self.stack.iter().any(|r| r == reference) {
+            return Type::new(Shape::Recursive(name));
+        }
+        let target = reference.strip_prefix('#').and_then(|pointer| {
+            pointer
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .try_fold(self.document, |value, segment| match value {
+                    Value::Map(entries) => {
+                        get(entries, &segment.replace("~1", "/").replace("~0", "~"))
+                    }
+                    _ => None,
+                })
+        });
+        let Some(target) = target else {
+            self.errors.push(AstParseError::new(format!(
+                "Cannot resolve $ref '{reference}'; only references within the document are supported"
+            )));
+            return Type::new(Shape::Any);
+        };
+        self.stack.push(reference.to_string());
+        let ty = self.read(target, &name);
+        self.stack.pop();
+        ty
+    }
+}
+
+fn with_description(mut ty: Type, description: Option<String>) -> Type {
+    if let Shape::Object(object) = &mut ty.shape {
+        if object.description.is_none() {
+            object.description = description;
+        }
+    }
+    ty
+}
+
+// ============================================================================
+// Naming
+// ============================================================================
+
+/// Named object type, in the order definitions are emitted
+struct Definition {
+    name: String,
+    description: Option<String>,
+    fields: Vec<Field>,
+}
+
+#[derive(Default)]
+struct Registry {
+    definitions: Vec<Definition>,
+    /// Fields of each registered object before naming, to share one name
+    /// between identical shapes
+    seen: Vec<(Vec<Field>, String)>,
+}
+
+impl Registry {
+    /// Replace every object in `ty` with a reference to a named definition
+    fn register(&mut self, ty: Type) -> Type {
+        let shape = match ty.shape {
+            Shape::Object(object) => {
+                if let Some((_, name)) = self
+                    .seen
+                    .iter()
+                    .find(|(fields, _)| *fields == object.fields)
+                {
+                    Shape::Named(name.clone())
+                } else {
+                    let name = self.unique_name(&object.hint);
+                    self.seen.push((object.fields.clone(), name.clone()));
+                    let index = self.definitions.len();
+                    self.definitions.push(Definition {
+                        name: name.clone(),
+                        description: object.description,
+                        fields: Vec::new(),
+                    });
+                    let fields = object
+                        .fields
+                        .into_iter()
+                        .map(|field| {
+                            let ty = self.register(field.ty);
+                            Field { ty, ..field }
+                        })
+                        .collect();
+                    self.definitions[index].fields = fields;
+                    Shape::Named(name)
+                }
+            }
+            Shape::List(item) => Shape::List(Box::new(self.register(*item))),
+            Shape::Map(values) => Shape::Map(Box::new(self.register(*values))),
+            other => other,
+        };
+        Type {
+            shape,
+            nullable: ty.nullable,
+        }
+    }
+
+    fn unique_name(&self, hint: &str) -> String {
+        let base = pascal_case(hint);
+        let taken = |name: &str| self.definitions.iter().any(|d| d.name == name);
+        if !taken(&base) {
+            return base;
+        }
+        (2..=self.definitions.len() + 2)
+            .map(|n| format!("{base}{n}"))
+            .find(|name| !taken(name))
+            .unwrap_or(base)
+    }
+}
+
+/// Words of a key, split at separators and case changes:
+/// `userID_list` → `user`, `ID`, `list`
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in s
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|p| !p.is_empty())
+    {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0
+                && c.is_uppercase()
+                && (chars[i - 1].is_lowercase()
+                    || chars[i - 1].is_ascii_digit()
+                    || chars.get(i + 1).is_some_and(|next| next.is_lowercase())
+                        && chars[i - 1].is_uppercase());
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.push(c);
+        }
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect()
+    })
+}
+
+fn pascal_case(s: &str) -> String {
+    let name: String = words(s).iter().map(|w| capitalize(w)).collect();
+    if name.is_empty() {
+        "Field".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("N{name}")
+    } else {
+        name
+    }
+}
+
+fn camel_case(s: &str) -> String {
+    let words = words(s);
+    let mut name = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i == 0 {
+            name.push_str(&word.to_lowercase());
+        } else {
+            name.push_str(&capitalize(word));
+        }
+    }
+    identifier_start(name)
+}
+
+fn snake_case(s: &str) -> String {
+    let name = words(s)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    identifier_start(name)
+}
+
+fn identifier_start(name: String) -> String {
+    if name.is_empty() {
+        "field".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// ============================================================================
+// Emitters
+// ============================================================================
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+const KOTLIN_KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+struct Emitter<'e> {
+    options: &'e TypeGenOptions,
+    definitions: &'e [Definition],
+}
+
+impl Emitter<'_> {
+    const fn optional(&self, field: &Field) -> bool {
+        match self.options.optionality {
+            Optionality::Inferred => field.optional,
+            Optionality::AllOptional => true,
+            Optionality::AllRequired => false,
+        }
+    }
+
+    fn uses(&self, alias: Option<(&str, &Type)>, test: fn(&Shape) -> bool) -> bool {
+        fn walk(ty: &Type, test: fn(&Shape) -> bool) -> bool {
+            test(&ty.shape)
+                || match &ty.shape {
+                    Shape::List(inner) | Shape::Map(inner) => walk(inner, test),
+                    _ => false,
+                }
+        }
+        alias.is_some_and(|(_, ty)| walk(ty, test))
+            || self
+                .definitions
+                .iter()
+                .flat_map(|d| &d.fields)
+                .any(|field| walk(&field.ty, test))
+    }
+
+    // ------------------------------------------------------------------------
+    // Rust
+    // ------------------------------------------------------------------------
+
+    fn rust(&self, alias: Option<(&str, &Type)>) -> String {
+        let mut out = String::new();
+        if self.uses(alias, |shape| matches!(shape, Shape::Map(_))) {
+            out.push_str("use std::collections::HashMap;\n\n");
+        }
+        out.push_str("use serde::{Deserialize, Serialize};\n");
+        if let Some((name, ty)) = alias {
+            let _ = writeln!(out, "\npub type {name} = {};", rust_type(ty, true));
+        }
+        for definition in self.definitions {
+            out.push('\n');
+            doc_comment(&mut out, definition.description.as_deref(), "", "///");
+            out.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+            let _ = writeln!(out, "pub struct {} {{", definition.name);
+            for field in &definition.fields {
+                let optional = self.optional(field);
+                let mut ty = rust_type(&field.ty, false);
+                if optional && !ty.starts_with("Option<") {
+                    ty = format!("Option<{ty}>");
+                }
+                let name = self.rust_field_name(&field.key);
+                let mut serde = Vec::new();
+                if name.trim_start_matches("r#") != field.key {
+                    serde.push(format!("rename = {}", quoted(&field.key)));
+                }
+                if optional {
+                    serde.push("default".to_string());
+                    serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+                }
+                doc_comment(&mut out, field.description.as_deref(), "    ", "///");
+                if !serde.is_empty() {
+                    let _ = writeln!(out, "    #[serde({})]", serde.join(", "));
+                }
+                let _ = writeln!(out, "    pub {name}: {ty},");
+            }
+            out.push_str("}\n");
+        }
+        out
+    }
+
+    fn rust_field_name(&self, key: &str) -> String {
+        let name = match self.options.field_naming {
+            FieldNaming::Preserve if is_identifier(key) => key.to_string(),
+            _ => snake_case(key),
+        };
+        match name.as_str() {
+            "self" | "Self" | "super" | "crate" | "_" => format!("{name}_"),
+            keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{name}"),
+            _ => name,
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // TypeScript
+    // ------------------------------------------------------------------------
+
+    fn typescript(&self, alias: Option<(&str, &Type)>) -> String {
+        let mut blocks = Vec::new();
+        if let Some((name, ty)) = alias {
+            blocks.push(format!("export type {name} = {};\n", ts_type(ty)));
+        }
+        for definition in self.definitions {
+            let mut out = String::new();
+            doc_comment(&mut out, definition.description.as_deref(), "", "/**");
+            let _ = writeln!(out, "export interface {} {{", definition.name);
+            for field in &definition.fields {
+                let key = if is_identifier(&field.key) {
+                    field.key.clone()
+                } else {
+                    format!("'{}'", field.key.replace('\\', "\\\\").replace('\'', "\\'"))
+                };
+                let optional = if self.optional(field) { "?" } else { "" };
+                doc_comment(&mut out, field.description.as_deref(), "  ", "/**");
+                let _ = writeln!(out, "  {key}{optional}: {};", ts_type(&field.ty));
+            }
+            out.push_str("}\n");
+            blocks.push(out);
+        }
+        blocks.join("\n")
+    }
+
+    // ------------------------------------------------------------------------
+    // Kotlin
+    // ------------------------------------------------------------------------
+
+    fn kotlin(&self, alias: Option<(&str, &Type)>) -> String {
+        let mut body = String::new();
+        let mut renamed = false;
+        if let Some((name, ty)) = alias {
+            let _ = writeln!(body, "\ntypealias {name} = {}", kotlin_type(ty));
+        }
+        for definition in self.definitions {
+            body.push('\n');
+            doc_comment(&mut body, definition.description.as_deref(), "", "/**");
+            body.push_str("@Serializable\n");
+            if definition.fields.is_empty() {
+                let _ = writeln!(body, "class {}", definition.name);
+                continue;
+            }
+            let _ = writeln!(body, "data class {}(", definition.name);
+            for field in &definition.fields {
+                let name = match self.options.field_naming {
+                    FieldNaming::Preserve if is_identifier(&field.key) => field.key.clone(),
+                    _ => camel_case(&field.key),
+                };
+                let mut ty = kotlin_type(&field.ty);
+                let optional = self.optional(field);
+                if optional && !ty.ends_with('?') {
+                    ty.push('?');
+                }
+                let default = if optional { " = null" } else { "" };
+                doc_comment(&mut body, field.description.as_deref(), "    ", "/**");
+                if name != field.key {
+                    renamed = true;
+                    let _ = writeln!(body, "    @SerialName({})", quoted(&field.key));
+                }
+                let name = if KOTLIN_KEYWORDS.contains(&name.as_str()) {
+                    format!("`{name}`")
+                } else {
+                    name
+                };
+                let _ = writeln!(body, "    val {name}: {ty}{default},");
+            }
+            body.push_str(")\n");
+        }
+        let mut out = String::new();
+        if renamed {
+            out.push_str("import kotlinx.serialization.SerialName\n");
+        }
+        out.push_str("import kotlinx.serialization.Serializable\n");
+        if self.uses(alias, |shape| matches!(shape, Shape::Any | Shape::Unknown)) {
+            out.push_str("import kotlinx.serialization.json.JsonElement\n");
+        }
+        out.push_str(&body);
+        out
+    }
+
+    // ------------------------------------------------------------------------
+    // Go
+    // ------------------------------------------------------------------------
+
+    fn go(&self, alias: Option<(&str, &Type)>) -> String {
+        let mut blocks = Vec::new();
+        if let Some((name, ty)) = alias {
+            blocks.push(format!("type {name} {}\n", go_type(ty, false, true)));
+        }
+        for definition in self.definitions {
+            let mut out = String::new();
+            if let Some(description) = &definition.description {
+                doc_comment(
+                    &mut out,
+                    Some(&format!("{} {description}", definition.name)),
+                    "",
+                    "//",
+                );
+            }
+            let _ = writeln!(out, "type {} struct {{", definition.name);
+            // gofmt aligns names, types, and tags in runs of fields that
+            // are not interrupted by comments.
+            let mut run: Vec<[String; 3]> = Vec::new();
+            for field in &definition.fields {
+                if field.description.is_some() {
+                    push_go_fields(&mut out, &mut run);
+                    doc_comment(&mut out, field.description.as_deref(), "\t", "//");
+                }
+                let optional = self.optional(field);
+                let name = match self.options.field_naming {
+                    FieldNaming::Preserve => {
+                        let sanitized = if is_identifier(&field.key) {
+                            field.key.clone()
+                        } else {
+                            snake_case(&field.key)
+                        };
+                        let mut chars = sanitized.chars();
+                        let name: String = chars
+                            .next()
+                            .into_iter()
+                            .flat_map(char::to_uppercase)
+                            .chain(chars)
+                            .collect();
+                        if name.starts_with('_') {
+                            format!("X{name}")
+                        } else {
+                            name
+                        }
+                    }
+                    FieldNaming::Idiomatic => pascal_case(&field.key),
+                };
+                let omit = if optional { ",omitempty" } else { "" };
+                run.push([
+                    name,
+                    go_type(&field.ty, optional, false),
+                    format!("`json:{}`", quoted(&format!("{}{omit}", field.key))),
+                ]);
+            }
+            push_go_fields(&mut out, &mut run);
+            out.push_str("}\n");
+            blocks.push(out);
+        }
+        blocks.join("\n")
+    }
+}
+
+fn push_go_fields(out: &mut String, run: &mut Vec<[String; 3]>) {
+    let name_width = run
+        .iter()
+        .map(|[name, ..]| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let type_width = run
+        .iter()
+        .map(|[_, ty, _]| ty.chars().count())
+        .max()
+        .unwrap_or(0);
+    for [name, ty, tag] in run.drain(..) {
+        let _ = writeln!(out, "\t{name:<name_width$} {ty:<type_width$} {tag}");
+    }
+}
+
+/// Write `text` as a comment, one comment line per text line
+fn doc_comment(out: &mut String, text: Option<&str>, indent: &str, marker: &str) {
+    let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) else {
+        return;
+    };
+    if marker == "/**" {
+        if text.contains('\n') {
+            let _ = writeln!(out, "{indent}/**");
+            for line in text.lines() {
+                out.push_str(format!("{indent} * {line}").trim_end());
+                out.push('\n');
+            }
+            let _ = writeln!(out, "{indent} */");
+        } else {
+            let _ = writeln!(out, "{indent}/** {text} */");
+        }
+    } else {
+        for line in text.lines() {
+            out.push_str(format!("{indent}{marker} {line}").trim_end());
+            out.push('\n');
+        }
+    }
+}
+
+fn quoted(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string()
+}
+
+/// Rust type of `ty`; recursive references are boxed unless
+/// `in_container` (a `Vec` or `HashMap` already adds the indirection)
+fn rust_type(ty: &Type, in_container: bool) -> String {
+    let base = match &ty.shape {
+        Shape::Unknown | Shape::Any | Shape::Object(_) => return "serde_json::Value".to_string(),
+        Shape::Bool => "bool".to_string(),
+        Shape::Integer => "i64".to_string(),
+        Shape::Float => "f64".to_string(),
+        Shape::String => "String".to_string(),
+        Shape::List(item) => format!("Vec<{}>", rust_type(item, true)),
+        Shape::Map(values) => format!("HashMap<String, {}>", rust_type(values, true)),
+        Shape::Recursive(name) if !in_container => format!("Box<{name}>"),
+        Shape::Named(name) | Shape::Recursive(name) => name.clone(),
+    };
+    if ty.nullable {
+        format!("Option<{base}>")
+    } else {
+        base
+    }
+}
+
+fn ts_type(ty: &Type) -> String {
+    let base = match &ty.shape {
+        Shape::Unknown | Shape::Any | Shape::Object(_) => return "unknown".to_string(),
+        Shape::Bool => "boolean".to_string(),
+        Shape::Integer | Shape::Float => "number".to_string(),
+        Shape::String => "string".to_string(),
+        Shape::List(item) => {
+            let item = ts_type(item);
+            if item.contains(' ') {
+                format!("({item})[]")
+            } else {
+                format!("{item}[]")
+            }
+        }
+        Shape::Map(values) => format!("Record<string, {}>", ts_type(values)),
+        Shape::Named(name) | Shape::Recursive(name) => name.clone(),
+    };
+    if ty.nullable {
+        format!("{base} | null")
+    } else {
+        base
+    }
+}
+
+fn kotlin_type(ty: &Type) -> String {
+    let base = match &ty.shape {
+        Shape::Unknown | Shape::Any | Shape::Object(_) => "JsonElement".to_string(),
+        Shape::Bool => "Boolean".to_string(),
+        Shape::Integer => "Long".to_string(),
+        Shape::Float => "Double".to_string(),
+        Shape::String => "String".to_string(),
+        Shape::List(item) => format!("List<{}>", kotlin_type(item)),
+        Shape::Map(values) => format!("Map<String, {}>", kotlin_type(values)),
+        Shape::Named(name) | Shape::Recursive(name) => name.clone(),
+    };
+    if ty.nullable || matches!(ty.shape, Shape::Unknown) {
+        format!("{base}?")
+    } else {
+        base
+    }
+}
+
+/// Go type of `ty`; nullable and optional scalars and structs become
+/// pointers, as do recursive references outside a slice or map
+fn go_type(ty: &Type, optional: bool, in_container: bool) -> String {
+    let pointer = ty.nullable || optional;
+    let base = match &ty.shape {
+        Shape::Unknown | Shape::Any | Shape::Object(_) => return "any".to_string(),
+        Shape::List(item) => return format!("[]{}", go_type(item, false, true)),
+        Shape::Map(values) => return format!("map[string]{}", go_type(values, false, true)),
+        Shape::Bool => "bool".to_string(),
+        Shape::Integer => "int64".to_string(),
+        Shape::Float => "float64".to_string(),
+        Shape::String => "string".to_string(),
+        Shape::Recursive(name) if !in_container => return format!("*{name}"),
+        Shape::Named(name) | Shape::Recursive(name) => name.clone(),
+    };
+    if pointer {
+        format!("*{base}")
+    } else {
+        base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "id": 1,
+        "userName": "kogu",
+        "score": 9.5,
+        "tags": ["a"],
+        "address": {"city": "Tokyo", "zip": null},
+        "orders": [
+            {"orderId": 1, "total": 10},
+            {"orderId": 2, "total": 12.5, "note": "gift"}
+        ]
+    }"#;
+
+    fn generate(text: &str, target: TargetLanguage) -> String {
+        let result = generate_types(text, target, &TypeGenOptions::default());
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        result.code.unwrap()
+    }
+
+    #[test]
+    fn test_rust_from_sample() {
+        assert_eq!(
+            generate(SAMPLE, TargetLanguage::Rust),
+            concat!(
+                "use serde::{Deserialize, Serialize};\n",
+                "\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct Root {\n",
+                "    pub id: i64,\n",
+                "    #[serde(rename = \"userName\")]\n    pub user_name: String,\n",
+                "    pub score: f64,\n    pub tags: Vec<String>,\n",
+                "    pub address: Address,\n    pub orders: Vec<Order>,\n}\n",
+                "\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct Address {\n",
+                "    pub city: String,\n    pub zip: serde_json::Value,\n}\n",
+                "\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct Order {\n",
+                "    #[serde(rename = \"orderId\")]\n    pub order_id: i64,\n",
+                "    pub total: f64,\n",
+                "    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n",
+                "    pub note: Option<String>,\n}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_typescript_from_sample() {
+        let code = generate(SAMPLE, TargetLanguage::TypeScript);
+        assert!(code.starts_with("export interface Root {\n  id: number;\n  userName: string;\n"));
+        assert!(code.contains("  orders: Order[];\n}\n\nexport interface Address {\n"));
+        assert!(code.contains("  zip: unknown;\n"));
+        assert!(code.contains("  note?: string;\n"));
+    }
+
+    #[test]
+    fn test_kotlin_from_sample() {
+        let code = generate(SAMPLE, TargetLanguage::Kotlin);
+        assert!(code.starts_with(
+            "import kotlinx.serialization.Serializable\nimport kotlinx.serialization.json.JsonElement\n"
+        ));
+        assert!(code.contains("@Serializable\ndata class Order(\n    val orderId: Long,\n    val total: Double,\n    val note: String? = null,\n)\n"));
+        assert!(code.contains("    val zip: JsonElement?,\n"));
+    }
+
+    #[test]
+    fn test_go_from_sample_aligns_fields() {
+        let code = generate(SAMPLE, TargetLanguage::Go);
+        assert!(code.contains(concat!(
+            "type Order struct {\n",
+            "\tOrderId int64   `json:\"orderId\"`\n",
+            "\tTotal   float64 `json:\"total\"`\n",
+            "\tNote    *string `json:\"note,omitempty\"`\n",
+            "}\n"
+        )));
+        assert!(code.contains("\tUserName string   `json:\"userName\"`\n"));
+    }
+
+    #[test]
+    fn test_identical_shapes_share_a_name_and_root_arrays_get_an_alias() {
+        let code = generate(
+            r#"[{"home": {"x": 1}, "work": {"x": 2}}]"#,
+            TargetLanguage::TypeScript,
+        );
+        assert_eq!(
+            code,
+            concat!(
+                "export type Root = RootItem[];\n\n",
+                "export interface RootItem {\n  home: Home;\n  work: Home;\n}\n\n",
+                "export interface Home {\n  x: number;\n}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_schema_required_nullable_and_refs() {
+        let schema = r##"{
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Person",
+            "description": "A person",
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string", "description": "Full name"},
+                "nickname": {"type": ["string", "null"]},
+                "labels": {"type": "object", "additionalProperties": {"type": "integer"}},
+                "parent": {"$ref": "#"},
+                "pets": {"type": "array", "items": {"$ref": "#/$defs/pet"}}
+            },
+            "$defs": {
+                "pet": {"type": "object", "properties": {"kind": {"enum": ["cat", "dog"]}}}
+            }
+        }"##;
+        let result = generate_types(
+            schema,
+            TargetLanguage::Rust,
+            &TypeGenOptions {
+                root_name: "Person".to_string(),
+                ..TypeGenOptions::default()
+            },
+        );
+        let code = result.code.unwrap();
+        assert!(code.starts_with("use std::collections::HashMap;\n\nuse serde"));
+        assert!(code.contains("/// A person\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct Person {\n    /// Full name\n    pub name: String,\n"));
+        assert!(code.contains("    pub nickname: Option<String>,\n"));
+        assert!(code.contains("    pub labels: Option<HashMap<String, i64>>,\n"));
+        assert!(code.contains("    pub parent: Option<Box<Person>>,\n"));
+        assert!(code.contains("    pub pets: Option<Vec<Pet>>,\n"));
+        assert!(code.contains("pub struct Pet {\n"));
+    }
+
+    #[test]
+    fn test_optionality_and_naming_options() {
+        let options = TypeGenOptions {
+            field_naming: FieldNaming::Preserve,
+            optionality: Optionality::AllOptional,
+            ..TypeGenOptions::default()
+        };
+        let result = generate_types(
+            r#"{"userName": "x", "type": 1}"#,
+            TargetLanguage::Rust,
+            &options,
+        );
+        let code = result.code.unwrap();
+        assert!(code.contains("    pub userName: Option<String>,\n"));
+        assert!(code.contains("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub r#type: Option<i64>,\n"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let invalid = generate_types("{", TargetLanguage::Go, &TypeGenOptions::default());
+        assert!(invalid.code.is_none());
+        let unresolved = generate_types(
+            r##"{"type": "object", "properties": {"a": {"$ref": "#/$defs/missing"}}}"##,
+            TargetLanguage::Go,
+            &TypeGenOptions::default(),
+        );
+        assert!(unresolved.code.is_some());
+        assert_eq!(unresolved.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("userID_list"), ["user", "ID", "list"]);
+        assert_eq!(snake_case("HTTPServer2Go"), "http_server2_go");
+        assert_eq!(camel_case("created-at"), "createdAt");
+        assert_eq!(pascal_case("2fa code"), "N2faCode");
+    }
+}
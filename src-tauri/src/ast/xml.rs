@@ -1,15 +1,34 @@
 //! XML AST parser with position tracking using roxmltree
-
-use super::{AstNode, AstNodeType, AstParseError, AstParseResult, AstPosition, AstRange};
-use roxmltree::{Document, Node};
-
-/// Parse XML text to AST with position information
+//!
+//! Element and attribute names keep the prefix they are written with, and
+//! nodes in a namespace carry its prefix and URI. Namespace declarations
+//! appear as `@xmlns` / `@xmlns:prefix` attribute nodes on the element that
+//! declares them. roxmltree rejects undeclared prefixes as parse errors.
+
+use super::{
+    AstNamespace, AstNode, AstNodeType, AstParseError, AstParseResult, AstPosition, AstRange,
+};
+use roxmltree::{Attribute, Document, Node, NodeType, ParsingOptions};
+
+/// Parse XML text to AST with position information. A DOCTYPE is
+/// accepted with a note that only its entities are used.
 pub fn parse(text: &str) -> AstParseResult {
-    match Document::parse(text) {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    match Document::parse_with_options(text, options) {
         Ok(doc) => {
             let root = doc.root_element();
-            let ast = node_to_ast(root, "$");
-            AstParseResult::success(ast)
+            let ast = node_to_ast(root, &format!("$.{}", raw_name(root)));
+            let notes = doctype_range(text, root.range().start)
+                .map(|range| {
+                    AstParseError::info("DOCTYPE ignored except for its entity declarations")
+                        .with_range(text_range(&doc, range))
+                })
+                .into_iter()
+                .collect();
+            AstParseResult::partial(ast, notes)
         }
         Err(e) => {
             let error = create_parse_error(&e);
@@ -18,7 +37,33 @@ pub fn parse(text: &str) -> AstParseResult {
     }
 }
 
-fn create_parse_error(error: &roxmltree::Error) -> AstParseError {
+/// Byte range of the DOCTYPE declaration in the prolog, the text before
+/// the root element at `root_start`
+fn doctype_range(text: &str, root_start: usize) -> Option<std::ops::Range<usize>> {
+    let prolog = &text[..root_start];
+    let mut pos = 0;
+    loop {
+        let rest = prolog[pos..].trim_start();
+        pos = prolog.len() - rest.len();
+        if rest.starts_with("<!--") {
+            pos += rest.find("-->")? + 3;
+        } else if rest.starts_with("<?") {
+            pos += rest.find("?>")? + 2;
+        } else if rest.starts_with("<!DOCTYPE") {
+            // An internal subset may hold `>` in its declarations.
+            let subset_end = match (rest.find('['), rest.find('>')) {
+                (Some(open), Some(close)) if open < close => rest.rfind(']')?,
+                _ => 0,
+            };
+            let end = subset_end + rest[subset_end..].find('>')? + 1;
+            return Some(pos..pos + end);
+        } else {
+            return None;
+        }
+    }
+}
+
+pub(super) fn create_parse_error(error: &roxmltree::Error) -> AstParseError {
     let pos = error.pos();
     let range = AstRange::new(
         AstPosition::new(pos.row as usize, pos.col as usize, 0),
@@ -27,15 +72,78 @@ fn create_parse_error(error: &roxmltree::Error) -> AstParseError {
     AstParseError::new(error.to_string()).with_range(range)
 }
 
-fn node_range(node: &Node<'_, '_>) -> AstRange {
-    let pos = node.document().text_pos_at(node.range().start);
-    let end_pos = node.document().text_pos_at(node.range().end);
+pub(super) fn node_range(node: &Node<'_, '_>) -> AstRange {
+    text_range(node.document(), node.range())
+}
+
+/// Convert a byte range of the source document to an [`AstRange`].
+pub(super) fn text_range(doc: &Document<'_>, range: std::ops::Range<usize>) -> AstRange {
+    let pos = doc.text_pos_at(range.start);
+    let end_pos = doc.text_pos_at(range.end);
     AstRange::new(
-        AstPosition::new(pos.row as usize, pos.col as usize, node.range().start),
-        AstPosition::new(end_pos.row as usize, end_pos.col as usize, node.range().end),
+        AstPosition::new(pos.row as usize, pos.col as usize, range.start),
+        AstPosition::new(end_pos.row as usize, end_pos.col as usize, range.end),
     )
 }
 
+/// Element name as written in the start tag, prefix included
+pub(super) fn raw_name<'input>(node: Node<'_, 'input>) -> &'input str {
+    let text = node.document().input_text();
+    let start = (node.range().start + 1).min(text.len());
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// Attribute name as written in the document, prefix included
+pub(super) fn raw_attribute_name<'input>(
+    node: Node<'_, 'input>,
+    attribute: &Attribute<'_, 'input>,
+) -> &'input str {
+    &node.document().input_text()[attribute.range_qname()]
+}
+
+/// Namespace of a name written as `qualified_name` that resolved to `uri`
+fn namespace_of(qualified_name: &str, uri: &str) -> AstNamespace {
+    AstNamespace {
+        prefix: qualified_name
+            .split_once(':')
+            .map(|(prefix, _)| prefix.to_string()),
+        uri: uri.to_string(),
+    }
+}
+
+/// AST path [`parse`] assigns to `node`: `$.root.item[1]` for elements,
+/// `{element}/#text` and `{element}/#comment` for their content, and
+/// `$` for the document itself.
+pub(super) fn node_path(node: Node<'_, '_>) -> String {
+    let parent_path = || node.parent().map_or_else(|| "$".to_string(), node_path);
+    match node.node_type() {
+        NodeType::Root => "$".to_string(),
+        NodeType::Element => {
+            let name = raw_name(node);
+            let Some(parent) = node.parent().filter(Node::is_element) else {
+                return format!("$.{name}");
+            };
+            let index = node
+                .prev_siblings()
+                .skip(1)
+                .filter(|s| s.is_element() && raw_name(*s) == name)
+                .count();
+            if index > 0 || has_multiple_children_with_name(&parent, name) {
+                format!("{}.{name}[{index}]", node_path(parent))
+            } else {
+                format!("{}.{name}", node_path(parent))
+            }
+        }
+        NodeType::Text => format!("{}/#text", parent_path()),
+        NodeType::Comment => format!("{}/#comment", parent_path()),
+        NodeType::PI => format!("{}/#pi", parent_path()),
+    }
+}
+
 fn process_text_child(child: &Node<'_, '_>, element_path: &str) -> Option<AstNode> {
     let text_content = child.text().unwrap_or("").trim();
     if text_content.is_empty() {
@@ -106,19 +214,48 @@ fn process_comment_child(child: &Node<'_, '_>, element_path: &str) -> AstNode {
 }
 
 fn process_attributes(node: &Node<'_, '_>, element_path: &str, range: AstRange) -> Vec<AstNode> {
-    node.attributes()
-        .map(|attr| {
-            let attr_path = format!("{element_path}[@{}]", attr.name());
-            let attr_range = AstRange::new(range.start, range.start);
-
-            AstNode::new(
-                AstNodeType::Attribute,
-                attr_path,
-                format!("@{}", attr.name()),
-                attr_range,
-            )
-            .with_value(serde_json::Value::String(attr.value().to_string()))
+    let attr_range = AstRange::new(range.start, range.start);
+    let declarations = declared_namespaces(*node).into_iter().map(|(prefix, uri)| {
+        let name = prefix.map_or_else(|| "xmlns".to_string(), |p| format!("xmlns:{p}"));
+        AstNode::new(
+            AstNodeType::Attribute,
+            format!("{element_path}[@{name}]"),
+            format!("@{name}"),
+            attr_range,
+        )
+        .with_value(serde_json::Value::String(uri.to_string()))
+    });
+    let attributes = node.attributes().map(|attr| {
+        let name = raw_attribute_name(*node, &attr);
+        let mut attr_node = AstNode::new(
+            AstNodeType::Attribute,
+            format!("{element_path}[@{name}]"),
+            format!("@{name}"),
+            attr_range,
+        )
+        .with_value(serde_json::Value::String(attr.value().to_string()));
+        attr_node.namespace = attr.namespace().map(|uri| namespace_of(name, uri));
+        attr_node
+    });
+    declarations.chain(attributes).collect()
+}
+
+/// Namespaces declared on `node` itself rather than inherited from its
+/// parent, as `(prefix, uri)`; the implicit `xml` prefix is left out.
+fn declared_namespaces<'a>(node: Node<'a, '_>) -> Vec<(Option<&'a str>, &'a str)> {
+    let inherited: Vec<(Option<&str>, &str)> = node
+        .parent()
+        .filter(Node::is_element)
+        .map(|parent| {
+            parent
+                .namespaces()
+                .map(|ns| (ns.name(), ns.uri()))
+                .collect()
         })
+        .unwrap_or_default();
+    node.namespaces()
+        .map(|ns| (ns.name(), ns.uri()))
+        .filter(|ns| ns.0 != Some("xml") && !inherited.contains(ns))
         .collect()
 }
 
@@ -147,15 +284,10 @@ fn node_to_ast(node: Node<'_, '_>, path: &str) -> AstNode {
     }
 }
 
-fn element_to_ast(node: Node<'_, '_>, path: &str, range: AstRange) -> AstNode {
-    let tag_name = node.tag_name().name();
-    let element_path = if path == "$" {
-        format!("$.{tag_name}")
-    } else {
-        format!("{path}.{tag_name}")
-    };
+fn element_to_ast(node: Node<'_, '_>, element_path: &str, range: AstRange) -> AstNode {
+    let tag_name = raw_name(node);
 
-    let mut children = process_attributes(&node, &element_path, range);
+    let mut children = process_attributes(&node, element_path, range);
 
     // Count child elements for path indexing
     let mut element_counts: std::collections::HashMap<&str, usize> =
@@ -165,7 +297,7 @@ fn element_to_ast(node: Node<'_, '_>, path: &str, range: AstRange) -> AstNode {
     for child in node.children() {
         match child.node_type() {
             roxmltree::NodeType::Element => {
-                let child_name = child.tag_name().name();
+                let child_name = raw_name(child);
                 let count = element_counts.entry(child_name).or_insert(0);
                 let child_path = if *count > 0 || has_multiple_children_with_name(&node, child_name)
                 {
@@ -175,32 +307,37 @@ fn element_to_ast(node: Node<'_, '_>, path: &str, range: AstRange) -> AstNode {
                 };
                 *count += 1;
 
-                let child_ast = node_to_ast(child, &element_path);
-                children.push(AstNode {
-                    path: child_path,
-                    ..child_ast
-                });
+                children.push(node_to_ast(child, &child_path));
             }
             roxmltree::NodeType::Text => {
-                if let Some(text_node) = process_text_child(&child, &element_path) {
+                if let Some(text_node) = process_text_child(&child, element_path) {
                     children.push(text_node);
                 }
             }
             roxmltree::NodeType::Comment => {
-                children.push(process_comment_child(&child, &element_path));
+                children.push(process_comment_child(&child, element_path));
             }
             _ => {}
         }
     }
 
-    let attr_count = node.attributes().count();
+    let attr_count = children
+        .iter()
+        .filter(|c| c.node_type == AstNodeType::Attribute)
+        .count();
     let element_child_count = children
         .iter()
         .filter(|c| c.node_type == AstNodeType::Element)
         .count();
     let label = build_element_label(tag_name, attr_count, element_child_count);
 
-    AstNode::new(AstNodeType::Element, element_path, label, range).with_children(children)
+    let mut element = AstNode::new(AstNodeType::Element, element_path.to_string(), label, range)
+        .with_children(children);
+    element.namespace = node
+        .tag_name()
+        .namespace()
+        .map(|uri| namespace_of(tag_name, uri));
+    element
 }
 
 fn text_node_to_ast(node: Node<'_, '_>, path: &str, range: AstRange) -> AstNode {
@@ -217,13 +354,14 @@ fn text_node_to_ast(node: Node<'_, '_>, path: &str, range: AstRange) -> AstNode
 
 fn has_multiple_children_with_name(node: &Node<'_, '_>, name: &str) -> bool {
     node.children()
-        .filter(|c| c.is_element() && c.tag_name().name() == name)
+        .filter(|c| c.is_element() && raw_name(*c) == name)
         .count()
         > 1
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::AstSeverity;
     use super::*;
 
     #[test]
@@ -263,6 +401,120 @@ mod tests {
         assert_eq!(ast.range.start.column, 1);
     }
 
+    #[test]
+    fn test_node_path_matches_parse() {
+        let xml = "<root><item/><item><name>x</name></item><!-- c --></root>";
+        let doc = Document::parse(xml).unwrap();
+        let ast = parse(xml).ast.unwrap();
+
+        let mut ast_paths = Vec::new();
+        let mut stack = vec![&ast];
+        while let Some(node) = stack.pop() {
+            if node.node_type != AstNodeType::Attribute {
+                ast_paths.push(node.path.clone());
+            }
+            stack.extend(node.children.iter().flatten());
+        }
+        for node in doc.root_element().descendants() {
+            assert!(ast_paths.contains(&node_path(node)), "{}", node_path(node));
+        }
+        assert_eq!(node_path(doc.root()), "$");
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let xml = r#"<soap:Envelope xmlns:soap="urn:soap" xmlns="urn:app"><soap:Body><order soap:mustUnderstand="1" id="7"/></soap:Body></soap:Envelope>"#;
+        let ast = parse(xml).ast.unwrap();
+        assert_eq!(ast.path, "$.soap:Envelope");
+        assert_eq!(ast.label, "<soap:Envelope> (2 attrs, 1 children)");
+        assert_eq!(
+            ast.namespace,
+            Some(AstNamespace {
+                prefix: Some("soap".to_string()),
+                uri: "urn:soap".to_string(),
+            })
+        );
+
+        let children = ast.children.unwrap();
+        let declarations: Vec<_> = children[..2]
+            .iter()
+            .map(|c| (c.path.as_str(), c.value.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            declarations,
+            [
+                (
+                    "$.soap:Envelope[@xmlns:soap]",
+                    serde_json::json!("urn:soap")
+                ),
+                ("$.soap:Envelope[@xmlns]", serde_json::json!("urn:app")),
+            ]
+        );
+
+        let body = &children[2];
+        assert_eq!(body.path, "$.soap:Envelope.soap:Body");
+        let order = &body.children.as_ref().unwrap()[0];
+        assert_eq!(order.path, "$.soap:Envelope.soap:Body.order");
+        let namespace = order.namespace.as_ref().unwrap();
+        assert_eq!(
+            (namespace.prefix.as_deref(), namespace.uri.as_str()),
+            (None, "urn:app")
+        );
+
+        // Unprefixed attributes are in no namespace; inherited declarations
+        // are not repeated.
+        let attributes = order.children.as_ref().unwrap();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(
+            attributes[0].path,
+            "$.soap:Envelope.soap:Body.order[@soap:mustUnderstand]"
+        );
+        assert_eq!(attributes[0].namespace.as_ref().unwrap().uri, "urn:soap");
+        assert_eq!(attributes[1].label, "@id");
+        assert!(attributes[1].namespace.is_none());
+
+        let doc = Document::parse(xml).unwrap();
+        let body_node = doc.root_element().first_child().unwrap();
+        assert_eq!(node_path(body_node), body.path);
+    }
+
+    #[test]
+    fn test_undeclared_prefix_is_an_error() {
+        for xml in [
+            "<svg:svg/>",
+            r#"<svg xmlns="urn:svg"><use xlink:href="a"/></svg>"#,
+        ] {
+            let result = parse(xml);
+            assert!(result.ast.is_none());
+            assert!(result.errors[0]
+                .message
+                .contains("unknown namespace prefix"));
+            assert!(result.errors[0].range.is_some());
+        }
+    }
+
+    #[test]
+    fn test_doctype_is_noted() {
+        let xml = "<?xml version=\"1.0\"?>\n<!-- c -->\n<!DOCTYPE note [<!ENTITY who \"kogu\">]>\n<note>&who;</note>";
+        let result = parse(xml);
+        assert!(!result.has_errors());
+        let note = &result.errors[0];
+        assert_eq!(note.severity, AstSeverity::Info);
+        let range = note.range.unwrap();
+        assert_eq!(
+            &xml[range.start.offset..range.end.offset],
+            "<!DOCTYPE note [<!ENTITY who \"kogu\">]>"
+        );
+        assert_eq!(range.start.line, 3);
+        let ast = result.ast.unwrap();
+        assert_eq!(
+            ast.children.unwrap()[0].value,
+            Some(serde_json::json!("kogu"))
+        );
+
+        assert!(parse("<note/>").errors.is_empty());
+    }
+
     #[test]
     fn test_parse_error() {
         let xml = "<root><unclosed>";
@@ -0,0 +1,543 @@
+//! Validation of XML documents against an XSD or a DTD
+//!
+//! The grammar-specific parts live in [`super::dtd`] and [`super::xsd`];
+//! this module holds the entry point, the error report (which reuses the
+//! XML AST paths and positions), and the content-model matcher both
+//! grammars compile their element content into.
+
+use std::collections::{BTreeSet, HashSet};
+
+use roxmltree::{Attribute, Document, Node, ParsingOptions};
+use serde::{Deserialize, Serialize};
+
+use super::xml::{create_parse_error, node_path, raw_attribute_name, raw_name, text_range};
+use super::{AstParseError, AstRange};
+
+/// Errors reported before validation stops.
+const MAX_ERRORS: usize = 1_000;
+
+/// Kind of schema a document is validated against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaKind {
+    /// W3C XML Schema 1.0
+    Xsd,
+    /// Document type definition, inline or pasted as the schema
+    Dtd,
+}
+
+/// Validity problem in the document
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationError {
+    pub message: String,
+    /// AST path of the offending element, attribute, or text node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<AstRange>,
+}
+
+impl From<AstParseError> for ValidationError {
+    fn from(error: AstParseError) -> Self {
+        Self {
+            message: error.message,
+            path: None,
+            range: error.range,
+        }
+    }
+}
+
+/// Result of validating an XML document
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XmlValidationResult {
+    /// The document is well-formed, the schema loaded cleanly, and no
+    /// validity errors were found
+    pub valid: bool,
+    /// Well-formedness and validity errors; ranges point into the document
+    pub errors: Vec<ValidationError>,
+    /// Problems in the schema itself; ranges point into the schema text
+    pub schema_errors: Vec<AstParseError>,
+}
+
+/// Validate the XML document `text` against `schema`. For DTDs the
+/// schema is optional: the document's internal subset is always used and
+/// a pasted DTD is read as its external subset.
+pub fn validate_xml(text: &str, schema: Option<&str>, kind: SchemaKind) -> XmlValidationResult {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let doc = match Document::parse_with_options(text, options) {
+        Ok(doc) => doc,
+        Err(e) => {
+            return XmlValidationResult {
+                errors: vec![create_parse_error(&e).into()],
+                ..XmlValidationResult::default()
+            }
+        }
+    };
+    let schema = schema.filter(|s| !s.trim().is_empty());
+    let mut report = Report::new(&doc);
+    let mut schema_errors = Vec::new();
+    match (kind, schema) {
+        (SchemaKind::Dtd, _) => super::dtd::validate(&doc, schema, &mut report, &mut schema_errors),
+        (SchemaKind::Xsd, Some(schema)) => {
+            super::xsd::validate(&doc, schema, &mut report, &mut schema_errors);
+        }
+        (SchemaKind::Xsd, None) => {
+            schema_errors.push(AstParseError::new("No XSD schema was given"));
+        }
+    }
+    let errors = report.finish();
+    XmlValidationResult {
+        valid: errors.is_empty() && schema_errors.is_empty(),
+        errors,
+        schema_errors,
+    }
+}
+
+/// Validity errors collected while walking a document, plus the ID and
+/// IDREF values seen so far
+pub(super) struct Report<'a, 'input> {
+    doc: &'a Document<'input>,
+    errors: Vec<ValidationError>,
+    ids: HashSet<String>,
+    idrefs: Vec<(String, Node<'a, 'input>, Attribute<'a, 'input>)>,
+}
+
+impl<'a, 'input> Report<'a, 'input> {
+    fn new(doc: &'a Document<'input>) -> Self {
+        Self {
+            doc,
+            errors: Vec::new(),
+            ids: HashSet::new(),
+            idrefs: Vec::new(),
+        }
+    }
+
+    /// Whether the error limit has been reached
+    pub(super) const fn is_full(&self) -> bool {
+        self.errors.len() >= MAX_ERRORS
+    }
+
+    fn push(&mut self, message: String, path: String, range: AstRange) {
+        if self.is_full() {
+            return;
+        }
+        self.errors.push(ValidationError {
+            message,
+            path: Some(path),
+            range: Some(range),
+        });
+    }
+
+    /// Report a problem found outside the element tree, such as in the
+    /// internal DTD subset
+    pub(super) fn parse_error(&mut self, error: AstParseError) {
+        if !self.is_full() {
+            self.errors.push(error.into());
+        }
+    }
+
+    /// Report a problem with an element, pointing at its start-tag name
+    pub(super) fn element(&mut self, node: Node<'a, 'input>, message: String) {
+        let start = node.range().start + 1;
+        let range = text_range(self.doc, start..start + raw_name(node).len());
+        self.push(message, node_path(node), range);
+    }
+
+    /// Report a problem with an attribute of `node`
+    pub(super) fn attribute(
+        &mut self,
+        node: Node<'a, 'input>,
+        attribute: Attribute<'a, 'input>,
+        message: String,
+    ) {
+        let path = format!(
+            "{}[@{}]",
+            node_path(node),
+            raw_attribute_name(node, &attribute)
+        );
+        self.push(message, path, text_range(self.doc, attribute.range()));
+    }
+
+    /// Report a problem with a text or other non-element node
+    pub(super) fn node(&mut self, node: Node<'a, 'input>, message: String) {
+        self.push(message, node_path(node), text_range(self.doc, node.range()));
+    }
+
+    /// Record an ID-typed attribute value, reporting duplicates
+    pub(super) fn id(
+        &mut self,
+        node: Node<'a, 'input>,
+        attribute: Attribute<'a, 'input>,
+        value: &str,
+    ) {
+        if !self.ids.insert(value.to_string()) {
+            self.attribute(node, attribute, format!("Duplicate ID '{value}'"));
+        }
+    }
+
+    /// Record an IDREF-typed attribute value, checked once the whole
+    /// document has been seen
+    pub(super) fn idref(
+        &mut self,
+        node: Node<'a, 'input>,
+        attribute: Attribute<'a, 'input>,
+        value: &str,
+    ) {
+        self.idrefs.push((value.to_string(), node, attribute));
+    }
+
+    fn finish(mut self) -> Vec<ValidationError> {
+        for (value, node, attribute) in std::mem::take(&mut self.idrefs) {
+            if !self.ids.contains(&value) {
+                self.attribute(
+                    node,
+                    attribute,
+                    format!("IDREF '{value}' does not match any ID"),
+                );
+            }
+        }
+        if self.is_full() {
+            self.errors.push(ValidationError {
+                message: format!("Validation stopped after {MAX_ERRORS} errors"),
+                path: None,
+                range: None,
+            });
+        }
+        self.errors
+    }
+}
+
+/// Concatenated text of the direct text children of `node`
+pub(super) fn direct_text(node: Node<'_, '_>) -> String {
+    node.children()
+        .filter(Node::is_text)
+        .filter_map(|child| child.text())
+        .collect()
+}
+
+/// First direct text child holding more than whitespace
+pub(super) fn significant_text<'a, 'input>(node: Node<'a, 'input>) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_text() && child.text().is_some_and(|t| !t.trim().is_empty()))
+}
+
+/// XML `Name` production, approximated for non-ASCII characters
+pub(super) fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == ':')
+        && chars.all(is_name_char)
+}
+
+/// XML `Nmtoken` production
+pub(super) fn is_nmtoken(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(is_name_char)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.' | '\u{B7}')
+}
+
+/// Occurrence-bounded piece of a content model
+#[derive(Debug, Clone)]
+pub(super) struct Particle {
+    pub term: Term,
+    pub min: u32,
+    /// Upper bound; `None` is unbounded
+    pub max: Option<u32>,
+}
+
+/// What a [`Particle`] matches. Leaves are indexes into a table owned by
+/// the grammar, which decides whether a leaf accepts a given element.
+#[derive(Debug, Clone)]
+pub(super) enum Term {
+    Leaf(usize),
+    Sequence(Vec<Particle>),
+    Choice(Vec<Particle>),
+    /// Members in any order, each at most once
+    All(Vec<Particle>),
+}
+
+/// Where a child sequence stopped matching a content model
+#[derive(Debug)]
+pub(super) struct Mismatch {
+    /// Index of the first child that could not be matched, or the number
+    /// of children when more were required
+    pub at: usize,
+    /// Leaves that would have been accepted at `at`
+    pub expected: Vec<usize>,
+}
+
+impl Particle {
+    pub(super) const fn once(term: Term) -> Self {
+        Self {
+            term,
+            min: 1,
+            max: Some(1),
+        }
+    }
+
+    /// Match `len` children against this particle, where `accepts(leaf, i)`
+    /// says whether a leaf matches child `i`
+    pub(super) fn check(
+        &self,
+        len: usize,
+        accepts: impl Fn(usize, usize) -> bool,
+    ) -> Result<(), Mismatch> {
+        let mut matcher = Matcher {
+            len,
+            accepts,
+            furthest: 0,
+            expected: Vec::new(),
+        };
+        if matcher.particle(self, 0).contains(&len) {
+            Ok(())
+        } else {
+            Err(Mismatch {
+                at: matcher.furthest,
+                expected: matcher.expected,
+            })
+        }
+    }
+
+    /// Leaves in model order
+    pub(super) fn leaves(&self) -> Vec<usize> {
+        match &self.term {
+            Term::Leaf(leaf) => vec![*leaf],
+            Term::Sequence(items) | Term::Choice(items) | Term::All(items) => {
+                items.iter().flat_map(Self::leaves).collect()
+            }
+        }
+    }
+}
+
+/// Position-set matcher: each step maps a start index to every index the
+/// particle can end at, so ambiguous models need no backtracking. The
+/// furthest index tried and the leaves tried there become the error.
+struct Matcher<F> {
+    len: usize,
+    accepts: F,
+    furthest: usize,
+    expected: Vec<usize>,
+}
+
+impl<F: Fn(usize, usize) -> bool> Matcher<F> {
+    fn reach(&mut self, at: usize) {
+        if at > self.furthest {
+            self.furthest = at;
+            self.expected.clear();
+        }
+    }
+
+    fn particle(&mut self, particle: &Particle, start: usize) -> BTreeSet<usize> {
+        let mut ends = BTreeSet::new();
+        if particle.min == 0 {
+            ends.insert(start);
+        }
+        let mut seen = ends.clone();
+        let mut frontier = BTreeSet::from([start]);
+        let mut count = 0;
+        while !frontier.is_empty() && particle.max.is_none_or(|max| count < max) {
+            let mut next = BTreeSet::new();
+            for &position in &frontier {
+                next.extend(self.term(&particle.term, position));
+            }
+            count += 1;
+            if count < particle.min {
+                if next == frontier {
+                    // Nullable term: further rounds below the minimum change nothing
+                    count = particle.min - 1;
+                }
+                frontier = next;
+                continue;
+            }
+            // Earlier arrivals have more repetitions left, so later ones add nothing
+            next.retain(|&position| seen.insert(position));
+            ends.extend(&next);
+            frontier = next;
+        }
+        ends
+    }
+
+    fn term(&mut self, term: &Term, start: usize) -> BTreeSet<usize> {
+        match term {
+            Term::Leaf(leaf) => {
+                if start < self.len && (self.accepts)(*leaf, start) {
+                    self.reach(start + 1);
+                    BTreeSet::from([start + 1])
+                } else {
+                    self.reach(start);
+                    if start == self.furthest && !self.expected.contains(leaf) {
+                        self.expected.push(*leaf);
+                    }
+                    BTreeSet::new()
+                }
+            }
+            Term::Sequence(items) => {
+                let mut positions = BTreeSet::from([start]);
+                for item in items {
+                    let mut next = BTreeSet::new();
+                    for &position in &positions {
+                        next.extend(self.particle(item, position));
+                    }
+                    positions = next;
+                    if positions.is_empty() {
+                        break;
+                    }
+                }
+                positions
+            }
+            Term::Choice(items) => {
+                let mut ends = BTreeSet::new();
+                for item in items {
+                    ends.extend(self.particle(item, start));
+                }
+                ends
+            }
+            Term::All(items) => self.all(items, start),
+        }
+    }
+
+    fn all(&mut self, items: &[Particle], start: usize) -> BTreeSet<usize> {
+        let required = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.min > 0)
+            .fold(0_u64, |mask, (i, _)| mask | 1 << i);
+        let mut states = BTreeSet::from([(start, 0_u64)]);
+        let mut frontier = vec![(start, 0_u64)];
+        while let Some((position, used)) = frontier.pop() {
+            for (i, item) in items.iter().enumerate().take(64) {
+                if used & 1 << i != 0 {
+                    continue;
+                }
+                for end in self.term(&item.term, position) {
+                    if states.insert((end, used | 1 << i)) {
+                        frontier.push((end, used | 1 << i));
+                    }
+                }
+            }
+        }
+        states
+            .into_iter()
+            .filter(|&(_, used)| used & required == required)
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
+
+/// Human-readable list of expected names, e.g. "<a>, <b>, or <c>"
+pub(super) fn expected_list(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{}, or {last}", init.join(", ")),
+    }
+}
+
+/// Error message for a child sequence that did not match, reported on
+/// the offending child or on the parent when children are missing
+pub(super) fn report_mismatch<'a, 'input>(
+    report: &mut Report<'a, 'input>,
+    parent: Node<'a, 'input>,
+    children: &[Node<'a, 'input>],
+    mismatch: &Mismatch,
+    expected: &[String],
+) {
+    let parent_name = raw_name(parent);
+    match children.get(mismatch.at) {
+        Some(&child) if expected.is_empty() => report.element(
+            child,
+            format!("Element <{}> is not allowed here; <{parent_name}> allows no further child elements", raw_name(child)),
+        ),
+        Some(&child) => report.element(
+            child,
+            format!(
+                "Element <{}> is not allowed here; expected {}",
+                raw_name(child),
+                expected_list(expected)
+            ),
+        ),
+        None => report.element(
+            parent,
+            format!(
+                "Element <{parent_name}> is incomplete; expected {}",
+                expected_list(expected)
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(leaf: usize, min: u32, max: Option<u32>) -> Particle {
+        Particle {
+            term: Term::Leaf(leaf),
+            min,
+            max,
+        }
+    }
+
+    fn check(particle: &Particle, children: &[usize]) -> Result<(), Mismatch> {
+        particle.check(children.len(), |leaf, i| children[i] == leaf)
+    }
+
+    #[test]
+    fn test_content_model_matching() {
+        // (a, b*, (c | d)?)
+        let model = Particle::once(Term::Sequence(vec![
+            leaf(0, 1, Some(1)),
+            leaf(1, 0, None),
+            Particle {
+                term: Term::Choice(vec![leaf(2, 1, Some(1)), leaf(3, 1, Some(1))]),
+                min: 0,
+                max: Some(1),
+            },
+        ]));
+        assert!(check(&model, &[0]).is_ok());
+        assert!(check(&model, &[0, 1, 1, 3]).is_ok());
+
+        let mismatch = check(&model, &[0, 2, 1]).unwrap_err();
+        assert_eq!(mismatch.at, 2);
+        assert!(mismatch.expected.is_empty());
+
+        let mismatch = check(&model, &[1]).unwrap_err();
+        assert_eq!((mismatch.at, mismatch.expected), (0, vec![0]));
+    }
+
+    #[test]
+    fn test_occurrence_bounds_and_all_groups() {
+        let model = leaf(0, 2, Some(3));
+        assert!(check(&model, &[0]).is_err());
+        assert!(check(&model, &[0, 0, 0]).is_ok());
+        assert_eq!(check(&model, &[0, 0, 0, 0]).unwrap_err().at, 3);
+
+        let all = Particle::once(Term::All(vec![leaf(0, 1, Some(1)), leaf(1, 0, Some(1))]));
+        assert!(check(&all, &[1, 0]).is_ok());
+        assert!(check(&all, &[0]).is_ok());
+        let mismatch = check(&all, &[1]).unwrap_err();
+        assert_eq!((mismatch.at, mismatch.expected), (1, vec![0]));
+    }
+
+    #[test]
+    fn test_not_well_formed() {
+        let result = validate_xml("<a><b></a>", None, SchemaKind::Dtd);
+        assert!(!result.valid);
+        assert!(result.errors[0].range.is_some());
+    }
+
+    #[test]
+    fn test_xsd_requires_schema() {
+        let result = validate_xml("<a/>", Some("  "), SchemaKind::Xsd);
+        assert!(!result.valid);
+        assert_eq!(result.schema_errors.len(), 1);
+    }
+}
@@ -0,0 +1,1763 @@
+//! `XPath` 1.0 evaluation over XML documents parsed with roxmltree
+//!
+//! Implements the full expression grammar (location paths on every axis
+//! except `namespace`, predicates, unions, arithmetic, and comparisons)
+//! and the core function library apart from `id()`. Variables are not
+//! supported. Prefixes resolve against the namespaces declared on the
+//! root element; as in `XPath` 1.0, an unprefixed name test only matches
+//! elements in no namespace.
+//!
+//! Matched nodes carry the same paths the XML AST parser assigns, so a
+//! tester can highlight a match in both the editor and the tree.
+
+use roxmltree::{Document, Node, NodeType};
+use serde::Serialize;
+
+use super::xml::{create_parse_error, node_path, node_range, raw_attribute_name, text_range};
+use super::{AstParseError, AstRange};
+
+/// Most matches returned; `total` still counts every selected node.
+const MAX_MATCHES: usize = 10_000;
+
+/// Kind of a node selected by an `XPath` expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum XPathNodeKind {
+    Root,
+    Element,
+    Attribute,
+    Text,
+    Comment,
+    ProcessingInstruction,
+}
+
+/// Node selected by an `XPath` expression
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XPathMatch {
+    pub kind: XPathNodeKind,
+    /// AST path of the node, as assigned by the XML parser
+    pub path: String,
+    /// Element or attribute local name, or processing-instruction target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `XPath` string-value of the node
+    pub value: String,
+    pub range: AstRange,
+}
+
+/// Result of evaluating an `XPath` expression
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XPathResult {
+    /// Selected nodes in document order when the result is a node-set
+    pub matches: Vec<XPathMatch>,
+    /// Number of selected nodes; `matches` is capped at 10,000
+    pub total: usize,
+    /// Number, string, or boolean result of a non-node-set expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// XML parse errors and expression errors. Expression error ranges
+    /// point into the expression, not the document.
+    pub errors: Vec<AstParseError>,
+}
+
+impl XPathResult {
+    fn failure(error: AstParseError) -> Self {
+        Self {
+            errors: vec![error],
+            ..Self::default()
+        }
+    }
+}
+
+/// Evaluate `expression` against the XML document `text`
+pub fn xpath_query(text: &str, expression: &str) -> XPathResult {
+    let expr = match Parser::new(expression).and_then(Parser::parse) {
+        Ok(expr) => expr,
+        Err(e) => return XPathResult::failure(e),
+    };
+    let doc = match Document::parse(text) {
+        Ok(doc) => doc,
+        Err(e) => return XPathResult::failure(create_parse_error(&e)),
+    };
+
+    let evaluator = Evaluator { doc: &doc };
+    let context = Context {
+        node: XNode::Node(doc.root()),
+        position: 1,
+        size: 1,
+    };
+    match evaluator.eval(&expr, &context) {
+        Ok(Value::Nodes(nodes)) => XPathResult {
+            total: nodes.len(),
+            matches: nodes.into_iter().take(MAX_MATCHES).map(to_match).collect(),
+            ..XPathResult::default()
+        },
+        Ok(Value::Number(n)) => XPathResult {
+            value: Some(serde_json::Number::from_f64(n).map_or_else(
+                || serde_json::Value::String(number_to_string(n)),
+                serde_json::Value::Number,
+            )),
+            ..XPathResult::default()
+        },
+        Ok(Value::String(s)) => XPathResult {
+            value: Some(serde_json::Value::String(s)),
+            ..XPathResult::default()
+        },
+        Ok(Value::Boolean(b)) => XPathResult {
+            value: Some(serde_json::Value::Bool(b)),
+            ..XPathResult::default()
+        },
+        Err(e) => XPathResult::failure(e),
+    }
+}
+
+fn to_match(node: XNode<'_, '_>) -> XPathMatch {
+    match node {
+        XNode::Attribute(owner, index) => {
+            let attr = owner.attributes().nth(index);
+            let name = attr.map(|a| a.name().to_string()).unwrap_or_default();
+            let raw_name = attr.map_or("", |a| raw_attribute_name(owner, &a));
+            XPathMatch {
+                kind: XPathNodeKind::Attribute,
+                path: format!("{}[@{raw_name}]", node_path(owner)),
+                name: Some(name),
+                value: string_value(node),
+                range: attr.map_or_else(
+                    || node_range(&owner),
+                    |a| text_range(owner.document(), a.range()),
+                ),
+            }
+        }
+        XNode::Node(n) => {
+            let (kind, name) = match n.node_type() {
+                NodeType::Root => (XPathNodeKind::Root, None),
+                NodeType::Element => (
+                    XPathNodeKind::Element,
+                    Some(n.tag_name().name().to_string()),
+                ),
+                NodeType::Text => (XPathNodeKind::Text, None),
+                NodeType::Comment => (XPathNodeKind::Comment, None),
+                NodeType::PI => (
+                    XPathNodeKind::ProcessingInstruction,
+                    n.pi().map(|pi| pi.target.to_string()),
+                ),
+            };
+            XPathMatch {
+                kind,
+                path: node_path(n),
+                name,
+                value: string_value(node),
+                range: node_range(&n),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lexer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Dot,
+    DotDot,
+    At,
+    Comma,
+    ColonColon,
+    Pipe,
+    Plus,
+    Minus,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Multiply,
+    And,
+    Or,
+    Div,
+    Mod,
+    /// `QName`, `*`, or `prefix:*`
+    Name(String),
+    Literal(String),
+    Number(f64),
+    Variable(String),
+}
+
+impl Token {
+    /// Whether `*` and operator names after this token are operators,
+    /// per the disambiguation rules of `XPath` 1.0 section 3.7.
+    const fn operator_follows(&self) -> bool {
+        !matches!(
+            self,
+            Self::At
+                | Self::ColonColon
+                | Self::LParen
+                | Self::LBracket
+                | Self::Comma
+                | Self::Slash
+                | Self::DoubleSlash
+                | Self::Pipe
+                | Self::Plus
+                | Self::Minus
+                | Self::Eq
+                | Self::NotEq
+                | Self::Lt
+                | Self::LtEq
+                | Self::Gt
+                | Self::GtEq
+                | Self::Multiply
+                | Self::And
+                | Self::Or
+                | Self::Div
+                | Self::Mod
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+const fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+const fn is_name_char(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-' || c == '.'
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Spanned>, AstParseError> {
+    let mut tokens: Vec<Spanned> = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let operator = tokens.last().is_some_and(|t| t.token.operator_follows());
+        chars.next();
+        let next = chars.peek().map(|&(_, c)| c);
+
+        let token = match c {
+            '/' if next == Some('/') => {
+                chars.next();
+                Token::DoubleSlash
+            }
+            '/' => Token::Slash,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '@' => Token::At,
+            ',' => Token::Comma,
+            '|' => Token::Pipe,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '=' => Token::Eq,
+            '!' if next == Some('=') => {
+                chars.next();
+                Token::NotEq
+            }
+            '<' | '>' if next == Some('=') => {
+                chars.next();
+                if c == '<' {
+                    Token::LtEq
+                } else {
+                    Token::GtEq
+                }
+            }
+            '<' => Token::Lt,
+            '>' => Token::Gt,
+            ':' if next == Some(':') => {
+                chars.next();
+                Token::ColonColon
+            }
+            '*' if operator => Token::Multiply,
+            '*' => Token::Name("*".to_string()),
+            '.' if next == Some('.') => {
+                chars.next();
+                Token::DotDot
+            }
+            '.' if !next.is_some_and(|c| c.is_ascii_digit()) => Token::Dot,
+            '"' | '\'' => lex_literal(expression, &mut chars, start, c)?,
+            c if c.is_ascii_digit() || c == '.' => lex_number(expression, &mut chars, start),
+            '$' => Token::Variable(take_name(expression, &mut chars, start + 1)),
+            c if is_name_start(c) => lex_name(expression, &mut chars, start, operator),
+            _ => {
+                return Err(expr_error(
+                    expression,
+                    start,
+                    start + c.len_utf8(),
+                    &format!("Unexpected character '{c}'"),
+                ));
+            }
+        };
+        let end = chars.peek().map_or(expression.len(), |&(i, _)| i);
+        tokens.push(Spanned { token, start, end });
+    }
+    Ok(tokens)
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+/// String literal whose opening `quote` is at byte `start`.
+fn lex_literal(
+    expression: &str,
+    chars: &mut Chars<'_>,
+    start: usize,
+    quote: char,
+) -> Result<Token, AstParseError> {
+    let Some(len) = expression[start + 1..].find(quote) else {
+        return Err(expr_error(
+            expression,
+            start,
+            expression.len(),
+            "Unterminated string literal",
+        ));
+    };
+    let close = start + 1 + len;
+    while chars.peek().is_some_and(|&(i, _)| i <= close) {
+        chars.next();
+    }
+    Ok(Token::Literal(expression[start + 1..close].to_string()))
+}
+
+/// Number (`12`, `1.5`, `.5`) starting at byte `start`.
+fn lex_number(expression: &str, chars: &mut Chars<'_>, start: usize) -> Token {
+    let mut end = start + 1;
+    while let Some(&(i, d)) = chars.peek() {
+        if !(d.is_ascii_digit() || (d == '.' && !expression[start..i].contains('.'))) {
+            break;
+        }
+        end = i + 1;
+        chars.next();
+    }
+    Token::Number(expression[start..end].parse().unwrap_or(f64::NAN))
+}
+
+/// Name test or operator name starting at byte `start`; `operator` is
+/// true where `and`, `or`, `div`, and `mod` are operators.
+fn lex_name(expression: &str, chars: &mut Chars<'_>, start: usize, operator: bool) -> Token {
+    let mut name = take_name(expression, chars, start);
+    // `prefix:local` or `prefix:*`, but not the `::` of an axis.
+    let mut lookahead = chars.clone();
+    if lookahead.next().is_some_and(|(_, c)| c == ':') {
+        match lookahead.peek() {
+            Some(&(i, '*')) => {
+                chars.nth(1);
+                name = expression[start..=i].to_string();
+            }
+            Some(&(i, c)) if is_name_start(c) => {
+                chars.next();
+                let local = take_name(expression, chars, i);
+                name = format!("{name}:{local}");
+            }
+            _ => {}
+        }
+    }
+    match name.as_str() {
+        "and" if operator => Token::And,
+        "or" if operator => Token::Or,
+        "div" if operator => Token::Div,
+        "mod" if operator => Token::Mod,
+        _ => Token::Name(name),
+    }
+}
+
+/// Consume the rest of a name that begins at byte `start`.
+fn take_name(expression: &str, chars: &mut Chars<'_>, start: usize) -> String {
+    while chars.peek().is_some_and(|&(_, c)| is_name_char(c)) {
+        chars.next();
+    }
+    let end = chars.peek().map_or(expression.len(), |&(i, _)| i);
+    expression[start..end].to_string()
+}
+
+fn expr_error(expression: &str, start: usize, end: usize, message: &str) -> AstParseError {
+    AstParseError::new(format!("Invalid XPath expression: {message}"))
+        .with_range(AstRange::from_offset(expression, start, end))
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Ancestor,
+    AncestorOrSelf,
+    Attribute,
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Following,
+    FollowingSibling,
+    Namespace,
+    Parent,
+    Preceding,
+    PrecedingSibling,
+    /// `self::`
+    Current,
+}
+
+impl Axis {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ancestor" => Self::Ancestor,
+            "ancestor-or-self" => Self::AncestorOrSelf,
+            "attribute" => Self::Attribute,
+            "child" => Self::Child,
+            "descendant" => Self::Descendant,
+            "descendant-or-self" => Self::DescendantOrSelf,
+            "following" => Self::Following,
+            "following-sibling" => Self::FollowingSibling,
+            "namespace" => Self::Namespace,
+            "parent" => Self::Parent,
+            "preceding" => Self::Preceding,
+            "preceding-sibling" => Self::PrecedingSibling,
+            "self" => Self::Current,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeTest {
+    /// `node()`
+    Node,
+    /// `text()`
+    Text,
+    /// `comment()`
+    Comment,
+    /// `processing-instruction()`, optionally with a target literal
+    ProcessingInstruction(Option<String>),
+    /// `*`, `prefix:*`, or a `QName`
+    Name {
+        prefix: Option<String>,
+        local: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Last,
+    Position,
+    Count,
+    LocalName,
+    NamespaceUri,
+    Name,
+    String,
+    Concat,
+    StartsWith,
+    Contains,
+    SubstringBefore,
+    SubstringAfter,
+    Substring,
+    StringLength,
+    NormalizeSpace,
+    Translate,
+    Boolean,
+    Not,
+    True,
+    False,
+    Lang,
+    Number,
+    Sum,
+    Floor,
+    Ceiling,
+    Round,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "last" => Self::Last,
+            "position" => Self::Position,
+            "count" => Self::Count,
+            "local-name" => Self::LocalName,
+            "namespace-uri" => Self::NamespaceUri,
+            "name" => Self::Name,
+            "string" => Self::String,
+            "concat" => Self::Concat,
+            "starts-with" => Self::StartsWith,
+            "contains" => Self::Contains,
+            "substring-before" => Self::SubstringBefore,
+            "substring-after" => Self::SubstringAfter,
+            "substring" => Self::Substring,
+            "string-length" => Self::StringLength,
+            "normalize-space" => Self::NormalizeSpace,
+            "translate" => Self::Translate,
+            "boolean" => Self::Boolean,
+            "not" => Self::Not,
+            "true" => Self::True,
+            "false" => Self::False,
+            "lang" => Self::Lang,
+            "number" => Self::Number,
+            "sum" => Self::Sum,
+            "floor" => Self::Floor,
+            "ceiling" => Self::Ceiling,
+            "round" => Self::Round,
+            _ => return None,
+        })
+    }
+
+    /// Minimum and maximum argument count (`None` for unbounded)
+    const fn arity(self) -> (usize, Option<usize>) {
+        match self {
+            Self::Last | Self::Position | Self::True | Self::False => (0, Some(0)),
+            Self::LocalName
+            | Self::NamespaceUri
+            | Self::Name
+            | Self::String
+            | Self::StringLength
+            | Self::NormalizeSpace
+            | Self::Number => (0, Some(1)),
+            Self::Count
+            | Self::Boolean
+            | Self::Not
+            | Self::Lang
+            | Self::Sum
+            | Self::Floor
+            | Self::Ceiling
+            | Self::Round => (1, Some(1)),
+            Self::StartsWith | Self::Contains | Self::SubstringBefore | Self::SubstringAfter => {
+                (2, Some(2))
+            }
+            Self::Substring => (2, Some(3)),
+            Self::Translate => (3, Some(3)),
+            Self::Concat => (2, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+enum PathStart {
+    /// `/...`
+    Root,
+    /// Relative location path
+    Context,
+    /// Filter expression followed by `/` or `//`
+    Expr(Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Binary(BinaryOp, Box<Self>, Box<Self>),
+    Negate(Box<Self>),
+    Union(Box<Self>, Box<Self>),
+    Literal(String),
+    Number(f64),
+    Function(Function, Vec<Self>),
+    /// Primary expression with predicates, such as `(//item)[1]`
+    Filter(Box<Self>, Vec<Self>),
+    Path(PathStart, Vec<Step>),
+}
+
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(expression: &'a str) -> Result<Self, AstParseError> {
+        Ok(Self {
+            expression,
+            tokens: tokenize(expression)?,
+            pos: 0,
+        })
+    }
+
+    fn parse(mut self) -> Result<Expr, AstParseError> {
+        if self.tokens.is_empty() {
+            return Err(self.error_at_end("Expression is empty"));
+        }
+        let expr = self.parse_or()?;
+        if self.pos < self.tokens.len() {
+            return Err(self.error_here("Unexpected token"));
+        }
+        Ok(expr)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|t| &t.token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token, what: &str) -> Result<(), AstParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error_here(&format!("Expected {what}")))
+        }
+    }
+
+    fn error_here(&self, message: &str) -> AstParseError {
+        self.tokens.get(self.pos).map_or_else(
+            || self.error_at_end(message),
+            |t| expr_error(self.expression, t.start, t.end, message),
+        )
+    }
+
+    fn error_at_end(&self, message: &str) -> AstParseError {
+        let end = self.expression.len();
+        expr_error(
+            self.expression,
+            end,
+            end,
+            &format!("{message} at end of expression"),
+        )
+    }
+
+    /// Parse a left-associative chain of `operand (op operand)*`.
+    fn parse_binary(
+        &mut self,
+        ops: &[(Token, BinaryOp)],
+        operand: fn(&mut Self) -> Result<Expr, AstParseError>,
+    ) -> Result<Expr, AstParseError> {
+        let mut left = operand(self)?;
+        while let Some(&(_, op)) = ops.iter().find(|(t, _)| self.peek() == Some(t)) {
+            self.pos += 1;
+            let right = operand(self)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, AstParseError> {
+        self.parse_binary(&[(Token::Or, BinaryOp::Or)], Self::parse_and)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, AstParseError> {
+        self.parse_binary(&[(Token::And, BinaryOp::And)], Self::parse_equality)
+    }
+
+    fn parse_equality(&mut self) -> Result<Expr, AstParseError> {
+        self.parse_binary(
+            &[(Token::Eq, BinaryOp::Eq), (Token::NotEq, BinaryOp::NotEq)],
+            Self::parse_relational,
+        )
+    }
+
+    fn parse_relational(&mut self) -> Result<Expr, AstParseError> {
+        self.parse_binary(
+            &[
+                (Token::Lt, BinaryOp::Lt),
+                (Token::LtEq, BinaryOp::LtEq),
+                (Token::Gt, BinaryOp::Gt),
+                (Token::GtEq, BinaryOp::GtEq),
+            ],
+            Self::parse_additive,
+        )
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, AstParseError> {
+        self.parse_binary(
+            &[(Token::Plus, BinaryOp::Add), (Token::Minus, BinaryOp::Sub)],
+            Self::parse_multiplicative,
+        )
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, AstParseError> {
+        self.parse_binary(
+            &[
+                (Token::Multiply, BinaryOp::Mul),
+                (Token::Div, BinaryOp::Div),
+                (Token::Mod, BinaryOp::Mod),
+            ],
+            Self::parse_unary,
+        )
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, AstParseError> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        let mut expr = self.parse_path()?;
+        while self.eat(&Token::Pipe) {
+            let right = self.parse_path()?;
+            expr = Expr::Union(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    /// Whether the next tokens begin a location step rather than a
+    /// filter expression.
+    fn starts_step(&self) -> bool {
+        match self.peek() {
+            Some(Token::Dot | Token::DotDot | Token::At) => true,
+            Some(Token::Name(name)) => {
+                let is_node_type = matches!(
+                    name.as_str(),
+                    "node" | "text" | "comment" | "processing-instruction"
+                );
+                is_node_type || self.peek_at(1) != Some(&Token::LParen)
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Expr, AstParseError> {
+        if self.eat(&Token::Slash) {
+            let steps = if self.starts_step() {
+                self.parse_relative(Vec::new())?
+            } else {
+                Vec::new()
+            };
+            return Ok(Expr::Path(PathStart::Root, steps));
+        }
+        if self.eat(&Token::DoubleSlash) {
+            let steps = self.parse_relative(vec![descendant_or_self()])?;
+            return Ok(Expr::Path(PathStart::Root, steps));
+        }
+        if self.starts_step() {
+            let steps = self.parse_relative(Vec::new())?;
+            return Ok(Expr::Path(PathStart::Context, steps));
+        }
+
+        let primary = self.parse_primary()?;
+        let predicates = self.parse_predicates()?;
+        let filter = if predicates.is_empty() {
+            primary
+        } else {
+            Expr::Filter(Box::new(primary), predicates)
+        };
+        let mut steps = Vec::new();
+        if self.eat(&Token::DoubleSlash) {
+            steps.push(descendant_or_self());
+        } else if !self.eat(&Token::Slash) {
+            return Ok(filter);
+        }
+        let steps = self.parse_relative(steps)?;
+        Ok(Expr::Path(PathStart::Expr(Box::new(filter)), steps))
+    }
+
+    /// Parse `step (('/' | '//') step)*`, appending to `steps`.
+    fn parse_relative(&mut self, mut steps: Vec<Step>) -> Result<Vec<Step>, AstParseError> {
+        loop {
+            steps.push(self.parse_step()?);
+            if self.eat(&Token::DoubleSlash) {
+                steps.push(descendant_or_self());
+            } else if !self.eat(&Token::Slash) {
+                return Ok(steps);
+            }
+        }
+    }
+
+    fn parse_step(&mut self) -> Result<Step, AstParseError> {
+        if self.eat(&Token::Dot) {
+            return Ok(Step {
+                axis: Axis::Current,
+                test: NodeTest::Node,
+                predicates: Vec::new(),
+            });
+        }
+        if self.eat(&Token::DotDot) {
+            return Ok(Step {
+                axis: Axis::Parent,
+                test: NodeTest::Node,
+                predicates: Vec::new(),
+            });
+        }
+
+        let axis = if self.eat(&Token::At) {
+            Axis::Attribute
+        } else if let (Some(Token::Name(name)), Some(Token::ColonColon)) =
+            (self.peek(), self.peek_at(1))
+        {
+            let Some(axis) = Axis::from_name(name) else {
+                return Err(self.error_here(&format!("Unknown axis '{name}'")));
+            };
+            self.pos += 2;
+            axis
+        } else {
+            Axis::Child
+        };
+        let test = self.parse_node_test()?;
+        let predicates = self.parse_predicates()?;
+        Ok(Step {
+            axis,
+            test,
+            predicates,
+        })
+    }
+
+    fn parse_node_test(&mut self) -> Result<NodeTest, AstParseError> {
+        let Some(Spanned {
+            token: Token::Name(name),
+            start,
+            end,
+        }) = self.tokens.get(self.pos).cloned()
+        else {
+            return Err(self.error_here("Expected a node test"));
+        };
+        self.pos += 1;
+
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let test = match name.as_str() {
+                "node" => NodeTest::Node,
+                "text" => NodeTest::Text,
+                "comment" => NodeTest::Comment,
+                "processing-instruction" => {
+                    let target = match self.peek().cloned() {
+                        Some(Token::Literal(target)) => {
+                            self.pos += 1;
+                            Some(target)
+                        }
+                        _ => None,
+                    };
+                    NodeTest::ProcessingInstruction(target)
+                }
+                _ => {
+                    return Err(expr_error(
+                        self.expression,
+                        start,
+                        end,
+                        &format!("'{name}()' is not a node test"),
+                    ));
+                }
+            };
+            self.expect(&Token::RParen, "')'")?;
+            return Ok(test);
+        }
+
+        let (prefix, local) = match name.split_once(':') {
+            Some((prefix, local)) => (Some(prefix.to_string()), local),
+            None => (None, name.as_str()),
+        };
+        let local = (local != "*").then(|| local.to_string());
+        Ok(NodeTest::Name { prefix, local })
+    }
+
+    fn parse_predicates(&mut self) -> Result<Vec<Expr>, AstParseError> {
+        let mut predicates = Vec::new();
+        while self.eat(&Token::LBracket) {
+            predicates.push(self.parse_or()?);
+            self.expect(&Token::RBracket, "']'")?;
+        }
+        Ok(predicates)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, AstParseError> {
+        let Some(current) = self.tokens.get(self.pos).cloned() else {
+            return Err(self.error_at_end("Unexpected end"));
+        };
+        match current.token {
+            Token::LParen => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                self.expect(&Token::RParen, "')'")?;
+                Ok(expr)
+            }
+            Token::Literal(value) => {
+                self.pos += 1;
+                Ok(Expr::Literal(value))
+            }
+            Token::Number(value) => {
+                self.pos += 1;
+                Ok(Expr::Number(value))
+            }
+            Token::Variable(name) => Err(expr_error(
+                self.expression,
+                current.start,
+                current.end,
+                &format!("Variables are not supported (${name})"),
+            )),
+            Token::Name(name) => self.parse_function_call(&name, current.start, current.end),
+            _ => Err(self.error_here("Unexpected token")),
+        }
+    }
+
+    fn parse_function_call(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<Expr, AstParseError> {
+        let Some(function) = Function::from_name(name) else {
+            return Err(expr_error(
+                self.expression,
+                start,
+                end,
+                &format!("Unknown function '{name}()'"),
+            ));
+        };
+        self.pos += 2;
+
+        let mut args = Vec::new();
+        if !self.eat(&Token::RParen) {
+            loop {
+                args.push(self.parse_or()?);
+                if self.eat(&Token::RParen) {
+                    break;
+                }
+                self.expect(&Token::Comma, "',' or ')'")?;
+            }
+        }
+
+        let (min, max) = function.arity();
+        if args.len() < min || max.is_some_and(|max| args.len() > max) {
+            let expected = match max {
+                Some(max) if max == min => format!("{min}"),
+                Some(max) => format!("{min} to {max}"),
+                None => format!("at least {min}"),
+            };
+            return Err(expr_error(
+                self.expression,
+                start,
+                end,
+                &format!("{name}() takes {expected} argument(s), got {}", args.len()),
+            ));
+        }
+        Ok(Expr::Function(function, args))
+    }
+}
+
+/// The step `//` abbreviates: `descendant-or-self::node()`
+const fn descendant_or_self() -> Step {
+    Step {
+        axis: Axis::DescendantOrSelf,
+        test: NodeTest::Node,
+        predicates: Vec::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluator
+// ---------------------------------------------------------------------------
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Node in the `XPath` data model: roxmltree keeps attributes outside its
+/// node tree, so they are addressed by owner element and index.
+#[derive(Clone, Copy)]
+enum XNode<'a, 'input> {
+    Node(Node<'a, 'input>),
+    Attribute(Node<'a, 'input>, usize),
+}
+
+impl<'a, 'input> XNode<'a, 'input> {
+    /// Document-order key: attributes sort after their owner and before
+    /// its children, whose ids are all larger.
+    fn order_key(self) -> (u32, usize) {
+        match self {
+            Self::Node(n) => (n.id().get(), 0),
+            Self::Attribute(owner, index) => (owner.id().get(), index + 1),
+        }
+    }
+
+    const fn as_node(self) -> Option<Node<'a, 'input>> {
+        match self {
+            Self::Node(n) => Some(n),
+            Self::Attribute(..) => None,
+        }
+    }
+
+    /// The node itself, or the owner element of an attribute.
+    const fn element(self) -> Node<'a, 'input> {
+        match self {
+            Self::Node(n) | Self::Attribute(n, _) => n,
+        }
+    }
+}
+
+enum Value<'a, 'input> {
+    Nodes(Vec<XNode<'a, 'input>>),
+    Number(f64),
+    String(String),
+    Boolean(bool),
+}
+
+struct Context<'a, 'input> {
+    node: XNode<'a, 'input>,
+    position: usize,
+    size: usize,
+}
+
+fn sort_document_order(nodes: &mut Vec<XNode<'_, '_>>) {
+    nodes.sort_by_key(|n| n.order_key());
+    nodes.dedup_by_key(|n| n.order_key());
+}
+
+fn string_value(node: XNode<'_, '_>) -> String {
+    match node {
+        XNode::Attribute(owner, index) => owner
+            .attributes()
+            .nth(index)
+            .map(|a| a.value().to_string())
+            .unwrap_or_default(),
+        XNode::Node(n) => match n.node_type() {
+            NodeType::Root | NodeType::Element => n
+                .descendants()
+                .filter(Node::is_text)
+                .filter_map(|t| t.text())
+                .collect(),
+            NodeType::Text | NodeType::Comment => n.text().unwrap_or_default().to_string(),
+            NodeType::PI => n
+                .pi()
+                .and_then(|pi| pi.value)
+                .unwrap_or_default()
+                .to_string(),
+        },
+    }
+}
+
+/// `XPath` `number()` of a string: optional sign, digits, and a decimal
+/// point only; anything else is NaN.
+fn string_to_number(s: &str) -> f64 {
+    let s = s.trim_matches([' ', '\t', '\n', '\r']);
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let valid = !digits.is_empty()
+        && digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    if valid {
+        s.parse().unwrap_or(f64::NAN)
+    } else {
+        f64::NAN
+    }
+}
+
+fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if n == 0.0 {
+        "0".to_string()
+    } else {
+        // Display never uses exponent notation and prints integers
+        // without a fraction, as XPath requires.
+        format!("{n}")
+    }
+}
+
+fn to_string(value: &Value<'_, '_>) -> String {
+    match value {
+        Value::Nodes(nodes) => nodes.first().map(|&n| string_value(n)).unwrap_or_default(),
+        Value::Number(n) => number_to_string(*n),
+        Value::String(s) => s.clone(),
+        Value::Boolean(b) => b.to_string(),
+    }
+}
+
+fn to_number(value: &Value<'_, '_>) -> f64 {
+    match value {
+        Value::Number(n) => *n,
+        Value::Boolean(b) => f64::from(u8::from(*b)),
+        Value::Nodes(_) | Value::String(_) => string_to_number(&to_string(value)),
+    }
+}
+
+fn to_boolean(value: &Value<'_, '_>) -> bool {
+    match value {
+        Value::Nodes(nodes) => !nodes.is_empty(),
+        Value::Number(n) => *n != 0.0 && !n.is_nan(),
+        Value::String(s) => !s.is_empty(),
+        Value::Boolean(b) => *b,
+    }
+}
+
+/// Compare two values that are not node-sets.
+#[allow(clippy::float_cmp)] // XPath `=` is exact IEEE 754 equality
+fn compare_scalars(op: BinaryOp, left: &Value<'_, '_>, right: &Value<'_, '_>) -> bool {
+    let numeric = |l: f64, r: f64| match op {
+        BinaryOp::Eq | BinaryOp::NotEq => l == r,
+        BinaryOp::Lt => l < r,
+        BinaryOp::LtEq => l <= r,
+        BinaryOp::Gt => l > r,
+        _ => l >= r,
+    };
+    if !matches!(op, BinaryOp::Eq | BinaryOp::NotEq) {
+        return numeric(to_number(left), to_number(right));
+    }
+    let equal = if matches!(left, Value::Boolean(_)) || matches!(right, Value::Boolean(_)) {
+        to_boolean(left) == to_boolean(right)
+    } else if matches!(left, Value::Number(_)) || matches!(right, Value::Number(_)) {
+        numeric(to_number(left), to_number(right))
+    } else {
+        to_string(left) == to_string(right)
+    };
+    equal == (op == BinaryOp::Eq)
+}
+
+/// Compare per `XPath` 1.0 section 3.4: a node-set compares true when any
+/// of its nodes does.
+fn compare(op: BinaryOp, left: &Value<'_, '_>, right: &Value<'_, '_>) -> bool {
+    let node_atom = |node: XNode<'_, '_>, other: &Value<'_, '_>| {
+        let s = string_value(node);
+        if matches!(other, Value::Number(_)) {
+            Value::Number(string_to_number(&s))
+        } else {
+            Value::String(s)
+        }
+    };
+    match (left, right) {
+        (Value::Nodes(l), Value::Nodes(r)) => {
+            let right: Vec<Value<'_, '_>> =
+                r.iter().map(|&n| Value::String(string_value(n))).collect();
+            l.iter().any(|&n| {
+                let left = Value::String(string_value(n));
+                right.iter().any(|r| compare_scalars(op, &left, r))
+            })
+        }
+        (Value::Nodes(_), Value::Boolean(_)) | (Value::Boolean(_), Value::Nodes(_)) => {
+            compare_scalars(
+                op,
+                &Value::Boolean(to_boolean(left)),
+                &Value::Boolean(to_boolean(right)),
+            )
+        }
+        (Value::Nodes(l), other) => l
+            .iter()
+            .any(|&n| compare_scalars(op, &node_atom(n, other), other)),
+        (other, Value::Nodes(r)) => r
+            .iter()
+            .any(|&n| compare_scalars(op, other, &node_atom(n, other))),
+        _ => compare_scalars(op, left, right),
+    }
+}
+
+/// `XPath` `round()`: halves round towards positive infinity.
+fn round_half_up(n: f64) -> f64 {
+    if n.is_finite() && n != 0.0 {
+        let rounded = (n + 0.5).floor();
+        // -0.5 <= n < 0 rounds to negative zero.
+        if rounded == 0.0 && n < 0.0 {
+            -0.0
+        } else {
+            rounded
+        }
+    } else {
+        n
+    }
+}
+
+/// `XPath` `substring()`: characters at 1-based positions `p` with
+/// `round(start) <= p < round(start) + round(length)`.
+fn substring(s: &str, start: f64, length: Option<f64>) -> String {
+    let first = round_half_up(start);
+    let last = length.map_or(f64::INFINITY, |len| first + round_half_up(len));
+    s.chars()
+        .zip(1u32..)
+        .filter(|&(_, p)| {
+            let p = f64::from(p);
+            p >= first && p < last
+        })
+        .map(|(c, _)| c)
+        .collect()
+}
+
+fn translate(s: &str, from: &str, to: &str) -> String {
+    let to: Vec<char> = to.chars().collect();
+    s.chars()
+        .filter_map(|c| {
+            from.chars()
+                .position(|f| f == c)
+                .map_or(Some(c), |i| to.get(i).copied())
+        })
+        .collect()
+}
+
+fn child_nodes<'a, 'input>(
+    nodes: impl Iterator<Item = Node<'a, 'input>>,
+) -> Vec<XNode<'a, 'input>> {
+    nodes.map(XNode::Node).collect()
+}
+
+/// Nodes after `node` in document order, excluding its descendants.
+fn following<'a, 'input>(node: Node<'a, 'input>) -> Vec<XNode<'a, 'input>> {
+    node.ancestors()
+        .flat_map(|a| a.next_siblings().skip(1))
+        .flat_map(|s| s.descendants())
+        .map(XNode::Node)
+        .collect()
+}
+
+/// Nodes before `node` excluding its ancestors, nearest first.
+fn preceding<'a, 'input>(node: Node<'a, 'input>) -> Vec<XNode<'a, 'input>> {
+    node.ancestors()
+        .flat_map(|a| a.prev_siblings().skip(1))
+        .flat_map(|s| s.descendants().collect::<Vec<_>>().into_iter().rev())
+        .map(XNode::Node)
+        .collect()
+}
+
+/// Nodes on `axis` from `node`, nearest first (reverse document order
+/// for the reverse axes).
+fn axis_nodes<'a, 'input>(axis: Axis, node: XNode<'a, 'input>) -> Vec<XNode<'a, 'input>> {
+    let n = match node {
+        XNode::Node(n) => n,
+        XNode::Attribute(owner, _) => {
+            return match axis {
+                Axis::Current => vec![node],
+                Axis::Parent => vec![XNode::Node(owner)],
+                Axis::Ancestor => child_nodes(owner.ancestors()),
+                Axis::AncestorOrSelf => std::iter::once(node)
+                    .chain(owner.ancestors().map(XNode::Node))
+                    .collect(),
+                Axis::Following => {
+                    let mut nodes = child_nodes(owner.descendants().skip(1));
+                    nodes.extend(following(owner));
+                    nodes
+                }
+                Axis::Preceding => preceding(owner),
+                _ => Vec::new(),
+            };
+        }
+    };
+    match axis {
+        Axis::Child => child_nodes(n.children()),
+        Axis::Descendant => child_nodes(n.descendants().skip(1)),
+        Axis::DescendantOrSelf => child_nodes(n.descendants()),
+        Axis::Parent => child_nodes(n.parent().into_iter()),
+        Axis::Ancestor => child_nodes(n.ancestors().skip(1)),
+        Axis::AncestorOrSelf => child_nodes(n.ancestors()),
+        Axis::FollowingSibling => child_nodes(n.next_siblings().skip(1)),
+        Axis::PrecedingSibling => child_nodes(n.prev_siblings().skip(1)),
+        Axis::Following => following(n),
+        Axis::Preceding => preceding(n),
+        Axis::Attribute => (0..n.attributes().len())
+            .map(|i| XNode::Attribute(n, i))
+            .collect(),
+        Axis::Current => vec![node],
+        Axis::Namespace => Vec::new(),
+    }
+}
+
+struct Evaluator<'a, 'input> {
+    doc: &'a Document<'input>,
+}
+
+impl<'a, 'input> Evaluator<'a, 'input> {
+    fn eval(
+        &self,
+        expr: &Expr,
+        ctx: &Context<'a, 'input>,
+    ) -> Result<Value<'a, 'input>, AstParseError> {
+        Ok(match expr {
+            Expr::Binary(BinaryOp::Or, left, right) => Value::Boolean(
+                to_boolean(&self.eval(left, ctx)?) || to_boolean(&self.eval(right, ctx)?),
+            ),
+            Expr::Binary(BinaryOp::And, left, right) => Value::Boolean(
+                to_boolean(&self.eval(left, ctx)?) && to_boolean(&self.eval(right, ctx)?),
+            ),
+            Expr::Binary(op, left, right) => {
+                let left = self.eval(left, ctx)?;
+                let right = self.eval(right, ctx)?;
+                let (l, r) = (to_number(&left), to_number(&right));
+                match op {
+                    BinaryOp::Add => Value::Number(l + r),
+                    BinaryOp::Sub => Value::Number(l - r),
+                    BinaryOp::Mul => Value::Number(l * r),
+                    BinaryOp::Div => Value::Number(l / r),
+                    // Rust's `%` truncates like XPath's `mod`.
+                    BinaryOp::Mod => Value::Number(l % r),
+                    _ => Value::Boolean(compare(*op, &left, &right)),
+                }
+            }
+            Expr::Negate(inner) => Value::Number(-to_number(&self.eval(inner, ctx)?)),
+            Expr::Union(left, right) => {
+                let mut nodes = self.node_set(left, ctx, "|")?;
+                nodes.extend(self.node_set(right, ctx, "|")?);
+                sort_document_order(&mut nodes);
+                Value::Nodes(nodes)
+            }
+            Expr::Literal(s) => Value::String(s.clone()),
+            Expr::Number(n) => Value::Number(*n),
+            Expr::Function(function, args) => self.call(*function, args, ctx)?,
+            Expr::Filter(primary, predicates) => {
+                let mut nodes = self.node_set(primary, ctx, "a predicate")?;
+                sort_document_order(&mut nodes);
+                for predicate in predicates {
+                    nodes = self.apply_predicate(nodes, predicate)?;
+                }
+                Value::Nodes(nodes)
+            }
+            Expr::Path(start, steps) => Value::Nodes(self.eval_path(start, steps, ctx)?),
+        })
+    }
+
+    fn node_set(
+        &self,
+        expr: &Expr,
+        ctx: &Context<'a, 'input>,
+        operator: &str,
+    ) -> Result<Vec<XNode<'a, 'input>>, AstParseError> {
+        match self.eval(expr, ctx)? {
+            Value::Nodes(nodes) => Ok(nodes),
+            _ => Err(AstParseError::new(format!(
+                "Invalid XPath expression: {operator} requires a node-set"
+            ))),
+        }
+    }
+
+    fn eval_path(
+        &self,
+        start: &PathStart,
+        steps: &[Step],
+        ctx: &Context<'a, 'input>,
+    ) -> Result<Vec<XNode<'a, 'input>>, AstParseError> {
+        let mut nodes = match start {
+            PathStart::Root => vec![XNode::Node(self.doc.root())],
+            PathStart::Context => vec![ctx.node],
+            PathStart::Expr(expr) => self.node_set(expr, ctx, "'/'")?,
+        };
+        for step in steps {
+            let namespace = match &step.test {
+                NodeTest::Name { prefix, .. } => self.resolve_prefix(prefix.as_deref())?,
+                _ => None,
+            };
+            let mut next = Vec::new();
+            for &node in &nodes {
+                let mut selected: Vec<XNode<'a, 'input>> = axis_nodes(step.axis, node)
+                    .into_iter()
+                    .filter(|&n| matches_test(&step.test, namespace, step.axis, n))
+                    .collect();
+                for predicate in &step.predicates {
+                    selected = self.apply_predicate(selected, predicate)?;
+                }
+                next.extend(selected);
+            }
+            sort_document_order(&mut next);
+            nodes = next;
+        }
+        Ok(nodes)
+    }
+
+    fn resolve_prefix(&self, prefix: Option<&str>) -> Result<Option<&'a str>, AstParseError> {
+        let Some(prefix) = prefix else {
+            return Ok(None);
+        };
+        if prefix == "xml" {
+            return Ok(Some(XML_NAMESPACE));
+        }
+        self.doc
+            .root_element()
+            .lookup_namespace_uri(Some(prefix))
+            .map(Some)
+            .ok_or_else(|| {
+                AstParseError::new(format!(
+                    "Invalid XPath expression: namespace prefix '{prefix}' is not declared on the root element"
+                ))
+            })
+    }
+
+    /// Keep the nodes for which `predicate` holds; a number predicate
+    /// selects by position within `nodes`.
+    #[allow(clippy::float_cmp)] // `[2.0]` selects position 2, `[1.5]` nothing
+    fn apply_predicate(
+        &self,
+        nodes: Vec<XNode<'a, 'input>>,
+        predicate: &Expr,
+    ) -> Result<Vec<XNode<'a, 'input>>, AstParseError> {
+        let size = nodes.len();
+        let mut kept = Vec::new();
+        for (i, node) in nodes.into_iter().enumerate() {
+            let ctx = Context {
+                node,
+                position: i + 1,
+                size,
+            };
+            let keep = match self.eval(predicate, &ctx)? {
+                Value::Number(n) => n == position_number(ctx.position),
+                value => to_boolean(&value),
+            };
+            if keep {
+                kept.push(node);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// First node of the optional node-set argument, or the context node.
+    fn node_argument(
+        &self,
+        function: &str,
+        args: &[Expr],
+        ctx: &Context<'a, 'input>,
+    ) -> Result<Option<XNode<'a, 'input>>, AstParseError> {
+        let Some(arg) = args.first() else {
+            return Ok(Some(ctx.node));
+        };
+        let mut nodes = self.node_set(arg, ctx, function)?;
+        sort_document_order(&mut nodes);
+        Ok(nodes.first().copied())
+    }
+
+    fn call(
+        &self,
+        function: Function,
+        args: &[Expr],
+        ctx: &Context<'a, 'input>,
+    ) -> Result<Value<'a, 'input>, AstParseError> {
+        let string_arg = |i: usize| -> Result<String, AstParseError> {
+            args.get(i).map_or_else(
+                || Ok(string_value(ctx.node)),
+                |arg| Ok(to_string(&self.eval(arg, ctx)?)),
+            )
+        };
+        let number_arg = |i: usize| -> Result<f64, AstParseError> {
+            args.get(i).map_or_else(
+                || Ok(string_to_number(&string_value(ctx.node))),
+                |arg| Ok(to_number(&self.eval(arg, ctx)?)),
+            )
+        };
+
+        Ok(match function {
+            Function::Last => Value::Number(position_number(ctx.size)),
+            Function::Position => Value::Number(position_number(ctx.position)),
+            Function::Count => Value::Number(position_number(
+                self.node_set(&args[0], ctx, "count()")?.len(),
+            )),
+            Function::LocalName | Function::NamespaceUri | Function::Name => {
+                let node = self.node_argument("a name function", args, ctx)?;
+                Value::String(node.map(|n| node_name(function, n)).unwrap_or_default())
+            }
+            Function::String => Value::String(string_arg(0)?),
+            Function::Concat => Value::String(
+                args.iter()
+                    .map(|arg| Ok(to_string(&self.eval(arg, ctx)?)))
+                    .collect::<Result<String, AstParseError>>()?,
+            ),
+            Function::StartsWith => Value::Boolean(string_arg(0)?.starts_with(&string_arg(1)?)),
+            Function::Contains => Value::Boolean(string_arg(0)?.contains(&string_arg(1)?)),
+            Function::SubstringBefore => {
+                let (s, pattern) = (string_arg(0)?, string_arg(1)?);
+                Value::String(
+                    s.find(&pattern)
+                        .map_or_else(String::new, |i| s[..i].to_string()),
+                )
+            }
+            Function::SubstringAfter => {
+                let (s, pattern) = (string_arg(0)?, string_arg(1)?);
+                Value::String(
+                    s.find(&pattern)
+                        .map_or_else(String::new, |i| s[i + pattern.len()..].to_string()),
+                )
+            }
+            Function::Substring => {
+                let length = if args.len() > 2 {
+                    Some(number_arg(2)?)
+                } else {
+                    None
+                };
+                Value::String(substring(&string_arg(0)?, number_arg(1)?, length))
+            }
+            Function::StringLength => {
+                Value::Number(position_number(string_arg(0)?.chars().count()))
+            }
+            Function::NormalizeSpace => Value::String(
+                string_arg(0)?
+                    .split_ascii_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            Function::Translate => {
+                Value::String(translate(&string_arg(0)?, &string_arg(1)?, &string_arg(2)?))
+            }
+            Function::Boolean => Value::Boolean(to_boolean(&self.eval(&args[0], ctx)?)),
+            Function::Not => Value::Boolean(!to_boolean(&self.eval(&args[0], ctx)?)),
+            Function::True => Value::Boolean(true),
+            Function::False => Value::Boolean(false),
+            Function::Lang => Value::Boolean(lang_matches(ctx.node, &string_arg(0)?)),
+            Function::Number => Value::Number(number_arg(0)?),
+            Function::Sum => Value::Number(
+                self.node_set(&args[0], ctx, "sum()")?
+                    .into_iter()
+                    .map(|n| string_to_number(&string_value(n)))
+                    .sum(),
+            ),
+            Function::Floor => Value::Number(number_arg(0)?.floor()),
+            Function::Ceiling => Value::Number(number_arg(0)?.ceil()),
+            Function::Round => Value::Number(round_half_up(number_arg(0)?)),
+        })
+    }
+}
+
+/// Context positions and sizes as `XPath` numbers.
+#[allow(clippy::cast_precision_loss)]
+const fn position_number(n: usize) -> f64 {
+    n as f64
+}
+
+fn matches_test(test: &NodeTest, namespace: Option<&str>, axis: Axis, node: XNode<'_, '_>) -> bool {
+    match test {
+        NodeTest::Node => true,
+        NodeTest::Text => node.as_node().is_some_and(|n| n.is_text()),
+        NodeTest::Comment => node.as_node().is_some_and(|n| n.is_comment()),
+        NodeTest::ProcessingInstruction(target) => node
+            .as_node()
+            .and_then(|n| n.pi())
+            .is_some_and(|pi| target.as_ref().is_none_or(|t| t == pi.target)),
+        NodeTest::Name { prefix, local } => {
+            // Name tests select the axis' principal node type.
+            let (node_namespace, name) = match (axis, node) {
+                (Axis::Attribute, XNode::Attribute(owner, index)) => {
+                    let Some(attr) = owner.attributes().nth(index) else {
+                        return false;
+                    };
+                    (attr.namespace(), attr.name())
+                }
+                (Axis::Attribute, _) | (_, XNode::Attribute(..)) => return false,
+                (_, XNode::Node(n)) if n.is_element() => {
+                    (n.tag_name().namespace(), n.tag_name().name())
+                }
+                _ => return false,
+            };
+            // A bare `*` matches every namespace; other tests compare the
+            // resolved prefix (none for unprefixed names).
+            let namespace_ok = (prefix.is_none() && local.is_none()) || node_namespace == namespace;
+            namespace_ok && local.as_ref().is_none_or(|l| l == name)
+        }
+    }
+}
+
+fn node_name(function: Function, node: XNode<'_, '_>) -> String {
+    let (namespace, local) = match node {
+        XNode::Attribute(owner, index) => match owner.attributes().nth(index) {
+            Some(attr) => (attr.namespace(), attr.name()),
+            None => return String::new(),
+        },
+        XNode::Node(n) if n.is_element() => (n.tag_name().namespace(), n.tag_name().name()),
+        XNode::Node(n) => {
+            let target = n.pi().map(|pi| pi.target.to_string()).unwrap_or_default();
+            return if function == Function::NamespaceUri {
+                String::new()
+            } else {
+                target
+            };
+        }
+    };
+    match function {
+        Function::LocalName => local.to_string(),
+        Function::NamespaceUri => namespace.unwrap_or_default().to_string(),
+        _ => {
+            let prefix = namespace.and_then(|uri| {
+                if uri == XML_NAMESPACE {
+                    Some("xml")
+                } else {
+                    node.element().lookup_prefix(uri)
+                }
+            });
+            match prefix {
+                Some(prefix) if !prefix.is_empty() => format!("{prefix}:{local}"),
+                _ => local.to_string(),
+            }
+        }
+    }
+}
+
+/// `XPath` `lang()`: the nearest `xml:lang` equals `lang` or starts with
+/// `lang-`, ignoring case.
+fn lang_matches(node: XNode<'_, '_>, lang: &str) -> bool {
+    node.element()
+        .ancestors()
+        .find_map(|a| a.attribute((XML_NAMESPACE, "lang")))
+        .is_some_and(|value| {
+            let value = value.to_ascii_lowercase();
+            let lang = lang.to_ascii_lowercase();
+            value == lang || value.starts_with(&format!("{lang}-"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOKS: &str = r#"<?xml version="1.0"?>
+<library xmlns:x="urn:extra">
+  <!-- catalogue -->
+  <book id="b1" lang="en"><title>Rust</title><price>30</price></book>
+  <book id="b2"><title>XML</title><price>12.5</price><x:note>old</x:note></book>
+  <magazine><title>Monthly</title></magazine>
+</library>"#;
+
+    fn paths(result: &XPathResult) -> Vec<&str> {
+        result.matches.iter().map(|m| m.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_location_paths_match_ast_paths() {
+        let result = xpath_query(BOOKS, "//book/title");
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            paths(&result),
+            ["$.library.book[0].title", "$.library.book[1].title"]
+        );
+        assert_eq!(result.matches[0].value, "Rust");
+        assert_eq!(result.matches[0].range.start.line, 4);
+
+        let result = xpath_query(BOOKS, "/library/magazine/title/text()");
+        assert_eq!(paths(&result), ["$.library.magazine.title/#text"]);
+        assert_eq!(result.matches[0].kind, XPathNodeKind::Text);
+    }
+
+    #[test]
+    fn test_attributes_and_predicates() {
+        let result = xpath_query(BOOKS, "//book[price > 20]/@id");
+        assert_eq!(paths(&result), ["$.library.book[0][@id]"]);
+        let id = &result.matches[0];
+        assert_eq!(id.kind, XPathNodeKind::Attribute);
+        assert_eq!(id.value, "b1");
+        assert_eq!(
+            &BOOKS[id.range.start.offset..id.range.end.offset],
+            r#"id="b1""#
+        );
+
+        assert_eq!(
+            paths(&xpath_query(BOOKS, "//book[last()]/@id")),
+            ["$.library.book[1][@id]"]
+        );
+        assert_eq!(
+            paths(&xpath_query(BOOKS, "(//title)[position() = 2]")),
+            ["$.library.book[1].title"]
+        );
+        assert_eq!(
+            paths(&xpath_query(BOOKS, "//title[../@lang = 'en'] | //magazine")),
+            ["$.library.book[0].title", "$.library.magazine"]
+        );
+        assert_eq!(
+            paths(&xpath_query(BOOKS, "//book[2]/preceding-sibling::*[1]")),
+            ["$.library.book[0]"]
+        );
+        assert_eq!(
+            paths(&xpath_query(BOOKS, "//comment()")),
+            ["$.library/#comment"]
+        );
+    }
+
+    #[test]
+    fn test_scalar_results() {
+        let value = |expr: &str| xpath_query(BOOKS, expr).value.unwrap();
+        assert_eq!(value("count(//book)"), serde_json::json!(2.0));
+        assert_eq!(value("sum(//price) div 2"), serde_json::json!(21.25));
+        assert_eq!(
+            value("concat(//book[1]/title, '-', 7 mod 3)"),
+            serde_json::json!("Rust-1")
+        );
+        assert_eq!(
+            value("normalize-space('  a   b ')"),
+            serde_json::json!("a b")
+        );
+        assert_eq!(
+            value("substring('12345', 1.5, 2.6)"),
+            serde_json::json!("234")
+        );
+        assert_eq!(
+            value("translate('bar', 'abc', 'AB')"),
+            serde_json::json!("BAr")
+        );
+        assert_eq!(
+            value("not(//missing) and //price = 12.5"),
+            serde_json::json!(true)
+        );
+        assert_eq!(value("string(1 div 0)"), serde_json::json!("Infinity"));
+        assert_eq!(value("round(-2.5)"), serde_json::json!(-2.0));
+        assert_eq!(value("name(//x:note)"), serde_json::json!("x:note"));
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let xml = r#"<feed xmlns="urn:atom" xmlns:m="urn:media"><entry><m:thumb url="a"/></entry></feed>"#;
+        assert!(xpath_query(xml, "//entry").matches.is_empty());
+        assert_eq!(
+            paths(&xpath_query(xml, "//m:thumb/@url")),
+            ["$.feed.entry.m:thumb[@url]"]
+        );
+        assert_eq!(xpath_query(xml, "//*[local-name() = 'entry']").total, 1);
+
+        let unknown = xpath_query(xml, "//z:entry");
+        assert!(unknown.errors[0].message.contains("'z'"));
+    }
+
+    #[test]
+    fn test_expression_errors_point_into_expression() {
+        let result = xpath_query(BOOKS, "//book[price >");
+        let range = result.errors[0].range.unwrap();
+        assert_eq!(range.start.offset, 14);
+
+        let result = xpath_query(BOOKS, "//book[frob(1)]");
+        assert!(result.errors[0]
+            .message
+            .contains("Unknown function 'frob()'"));
+        assert_eq!(result.errors[0].range.unwrap().start.column, 8);
+
+        let result = xpath_query(BOOKS, "substring('a')");
+        assert!(result.errors[0].message.contains("2 to 3 argument(s)"));
+
+        assert!(!xpath_query(BOOKS, "$var").errors.is_empty());
+        assert!(!xpath_query(BOOKS, "'open").errors.is_empty());
+    }
+
+    #[test]
+    fn test_xml_errors_are_reported() {
+        let result = xpath_query("<root><unclosed>", "/root");
+        assert!(result.matches.is_empty());
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_operator_disambiguation() {
+        let xml = "<r><div>4</div><mod>2</mod></r>";
+        assert_eq!(
+            xpath_query(xml, "/r/div div /r/mod").value,
+            Some(serde_json::json!(2.0))
+        );
+        assert_eq!(
+            xpath_query(xml, "count(/r/*) * 2").value,
+            Some(serde_json::json!(4.0))
+        );
+    }
+}
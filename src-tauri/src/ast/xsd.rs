@@ -0,0 +1,2064 @@
+//! XML Schema 1.0 validation
+//!
+//! The schema document is compiled into flat tables of element
+//! declarations, types, and content-model leaves, and each element of the
+//! instance is then checked against its declaration. Covers global and
+//! local elements and attributes, named and anonymous types, model and
+//! attribute groups, simple and complex content derivation, wildcards,
+//! `xsi:nil`, the built-in datatypes, and the constraining facets apart
+//! from `whiteSpace`. `xs:include`, `xs:import`, substitution groups,
+//! `xsi:type`, and identity constraints are not supported.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use roxmltree::{Attribute, Document, Node, ParsingOptions};
+
+use super::xml::{create_parse_error, raw_attribute_name, raw_name, text_range};
+use super::xml_validate::{
+    direct_text, is_name, is_nmtoken, report_mismatch, significant_text, Particle, Report, Term,
+};
+use super::AstParseError;
+
+const XS: &str = "http://www.w3.org/2001/XMLSchema";
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Deepest chain of type derivations or group references followed
+const MAX_DEPTH: usize = 32;
+
+/// Namespace URI and local name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct QName {
+    namespace: Option<String>,
+    local: String,
+}
+
+impl QName {
+    fn of(node: Node<'_, '_>) -> Self {
+        Self {
+            namespace: node.tag_name().namespace().map(str::to_string),
+            local: node.tag_name().name().to_string(),
+        }
+    }
+
+    fn matches(&self, namespace: Option<&str>, local: &str) -> bool {
+        self.local == local && self.namespace.as_deref() == namespace
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeRef {
+    Builtin(Builtin),
+    /// `xs:anyType`: any attributes and any content
+    AnyType,
+    /// Index into [`Schema::types`]
+    Def(usize),
+}
+
+#[derive(Debug)]
+struct ElementDecl {
+    name: QName,
+    ty: TypeRef,
+    nillable: bool,
+    is_abstract: bool,
+    /// `fixed` or `default` value, which also covers empty content
+    value: Option<String>,
+    fixed: bool,
+}
+
+/// Content-model leaf: an element declaration or a wildcard
+#[derive(Debug)]
+enum Leaf {
+    Element(usize),
+    Any(Wildcard),
+}
+
+#[derive(Debug, Clone)]
+struct Wildcard {
+    namespaces: NamespaceConstraint,
+    process: ProcessContents,
+}
+
+#[derive(Debug, Clone)]
+enum NamespaceConstraint {
+    Any,
+    /// `##other`: any namespace except the target and no namespace
+    Not(Option<String>),
+    List(Vec<Option<String>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcessContents {
+    Strict,
+    Lax,
+    Skip,
+}
+
+impl Wildcard {
+    fn allows(&self, namespace: Option<&str>) -> bool {
+        match &self.namespaces {
+            NamespaceConstraint::Any => true,
+            NamespaceConstraint::Not(target) => {
+                namespace.is_some() && namespace != target.as_deref()
+            }
+            NamespaceConstraint::List(list) => list.iter().any(|ns| ns.as_deref() == namespace),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Content {
+    Empty,
+    Simple(TypeRef),
+    Elements(Particle),
+}
+
+#[derive(Debug, Clone)]
+struct AttributeUse {
+    name: QName,
+    ty: TypeRef,
+    required: bool,
+    prohibited: bool,
+    fixed: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct ComplexType {
+    mixed: bool,
+    content: Content,
+    attributes: Vec<AttributeUse>,
+    any_attribute: Option<Wildcard>,
+    /// Complex base type still to be merged in by [`Schema::resolve`]
+    derivation: Option<Derivation>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Derivation {
+    base: usize,
+    extension: bool,
+    /// Simple type holding the facets of a `simpleContent` restriction,
+    /// whose base becomes the base type's simple content
+    facets: Option<usize>,
+}
+
+#[derive(Debug)]
+enum Variety {
+    Atomic { base: TypeRef, facets: Box<Facets> },
+    List { item: TypeRef },
+    Union(Vec<TypeRef>),
+}
+
+#[derive(Debug)]
+enum TypeDef {
+    Simple(Variety),
+    Complex(ComplexType),
+}
+
+#[derive(Debug, Default)]
+struct Facets {
+    enumeration: Vec<String>,
+    /// Compiled pattern and the schema's spelling of it
+    pattern: Option<(Regex, String)>,
+    min_inclusive: Option<String>,
+    max_inclusive: Option<String>,
+    min_exclusive: Option<String>,
+    max_exclusive: Option<String>,
+    length: Option<usize>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    total_digits: Option<usize>,
+    fraction_digits: Option<usize>,
+}
+
+/// Compiled schema
+#[derive(Debug, Default)]
+struct Schema {
+    decls: Vec<ElementDecl>,
+    elements: HashMap<QName, usize>,
+    attributes: HashMap<QName, AttributeUse>,
+    types: Vec<TypeDef>,
+    leaves: Vec<Leaf>,
+}
+
+/// Validate `doc` against the XSD `schema_text`
+pub(super) fn validate<'a, 'input>(
+    doc: &'a Document<'input>,
+    schema_text: &str,
+    report: &mut Report<'a, 'input>,
+    schema_errors: &mut Vec<AstParseError>,
+) {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let schema_doc = match Document::parse_with_options(schema_text, options) {
+        Ok(schema_doc) => schema_doc,
+        Err(e) => {
+            schema_errors.push(create_parse_error(&e));
+            return;
+        }
+    };
+    let mut compiler = Compiler::new(&schema_doc);
+    let schema_root = schema_doc.root_element();
+    if !is_xs(schema_root, "schema") {
+        compiler.error(schema_root, "The schema root must be <xs:schema>");
+        schema_errors.append(&mut compiler.errors);
+        return;
+    }
+    compiler.compile(schema_root);
+    let Compiler {
+        mut schema, errors, ..
+    } = compiler;
+    schema_errors.extend(errors);
+    for index in 0..schema.types.len() {
+        schema.resolve(index, 0, schema_errors);
+    }
+
+    let root = doc.root_element();
+    match schema.elements.get(&QName::of(root)) {
+        Some(&decl) => schema.validate_element(root, decl, report),
+        None => report.element(
+            root,
+            format!("No global element declaration matches <{}>", raw_name(root)),
+        ),
+    }
+}
+
+fn is_xs(node: Node<'_, '_>, local: &str) -> bool {
+    node.is_element() && node.tag_name().namespace() == Some(XS) && node.tag_name().name() == local
+}
+
+/// Schema-namespace children of `node`, annotations excluded
+fn xs_children<'a, 'input>(node: Node<'a, 'input>) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(|child| {
+        child.is_element()
+            && child.tag_name().namespace() == Some(XS)
+            && child.tag_name().name() != "annotation"
+    })
+}
+
+/// Compiles schema components. Named globals get their table slots in a
+/// first pass so references resolve regardless of declaration order.
+struct Compiler<'a, 'input> {
+    doc: &'a Document<'input>,
+    target: Option<String>,
+    elements_qualified: bool,
+    attributes_qualified: bool,
+    schema: Schema,
+    named_types: HashMap<QName, usize>,
+    groups: HashMap<QName, Node<'a, 'input>>,
+    attribute_groups: HashMap<QName, Node<'a, 'input>>,
+    global_attributes: HashMap<QName, Node<'a, 'input>>,
+    /// Group references being expanded, to catch circular definitions
+    expanding: Vec<QName>,
+    errors: Vec<AstParseError>,
+}
+
+impl<'a, 'input> Compiler<'a, 'input> {
+    fn new(doc: &'a Document<'input>) -> Self {
+        Self {
+            doc,
+            target: None,
+            elements_qualified: false,
+            attributes_qualified: false,
+            schema: Schema::default(),
+            named_types: HashMap::new(),
+            groups: HashMap::new(),
+            attribute_groups: HashMap::new(),
+            global_attributes: HashMap::new(),
+            expanding: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Report a problem with a schema component, pointing at its tag name
+    fn error(&mut self, node: Node<'_, '_>, message: impl Into<String>) {
+        let start = node.range().start + 1;
+        let range = text_range(self.doc, start..start + raw_name(node).len());
+        self.errors
+            .push(AstParseError::new(message).with_range(range));
+    }
+
+    fn global_name(&mut self, node: Node<'a, 'input>) -> Option<QName> {
+        let Some(local) = node.attribute("name") else {
+            self.error(
+                node,
+                format!("Global <xs:{}> needs a name", node.tag_name().name()),
+            );
+            return None;
+        };
+        Some(QName {
+            namespace: self.target.clone(),
+            local: local.to_string(),
+        })
+    }
+
+    fn compile(&mut self, root: Node<'a, 'input>) {
+        self.target = root.attribute("targetNamespace").map(str::to_string);
+        self.elements_qualified = root.attribute("elementFormDefault") == Some("qualified");
+        self.attributes_qualified = root.attribute("attributeFormDefault") == Some("qualified");
+
+        let mut pending = Vec::new();
+        for child in xs_children(root) {
+            let kind = child.tag_name().name();
+            match kind {
+                "element" | "complexType" | "simpleType" => {
+                    let Some(name) = self.global_name(child) else {
+                        continue;
+                    };
+                    let slot = if kind == "element" {
+                        self.schema.decls.len()
+                    } else {
+                        self.schema.types.len()
+                    };
+                    let taken = if kind == "element" {
+                        self.schema.decls.push(ElementDecl {
+                            name: name.clone(),
+                            ty: TypeRef::AnyType,
+                            nillable: false,
+                            is_abstract: false,
+                            value: None,
+                            fixed: false,
+                        });
+                        self.schema.elements.insert(name.clone(), slot).is_some()
+                    } else {
+                        // Placeholder of the right kind so derivations can tell
+                        // simple and complex bases apart before they compile
+                        self.schema.types.push(if kind == "simpleType" {
+                            TypeDef::Simple(Variety::Union(Vec::new()))
+                        } else {
+                            TypeDef::Complex(ComplexType::empty(false))
+                        });
+                        self.named_types.insert(name.clone(), slot).is_some()
+                    };
+                    if taken {
+                        self.error(child, format!("Duplicate global {kind} '{}'", name.local));
+                    }
+                    pending.push((child, slot));
+                }
+                "group" | "attributeGroup" | "attribute" => {
+                    let Some(name) = self.global_name(child) else {
+                        continue;
+                    };
+                    let table = match kind {
+                        "group" => &mut self.groups,
+                        "attributeGroup" => &mut self.attribute_groups,
+                        _ => &mut self.global_attributes,
+                    };
+                    if table.insert(name.clone(), child).is_some() {
+                        self.error(child, format!("Duplicate global {kind} '{}'", name.local));
+                    }
+                }
+                "include" | "import" | "redefine" | "override" => self.error(
+                    child,
+                    format!("<xs:{kind}> is not supported; combine the schemas into one document"),
+                ),
+                "notation" => {}
+                _ => self.error(child, format!("Unexpected <xs:{kind}> in <xs:schema>")),
+            }
+        }
+
+        for (node, slot) in pending {
+            match node.tag_name().name() {
+                "element" => {
+                    let decl = self.element_decl(node, true);
+                    self.schema.decls[slot] = decl;
+                }
+                "complexType" => {
+                    let ty = self.complex_type(node);
+                    self.schema.types[slot] = TypeDef::Complex(ty);
+                }
+                _ => {
+                    let ty = self.simple_type(node);
+                    self.schema.types[slot] = TypeDef::Simple(ty);
+                }
+            }
+        }
+        let attributes: Vec<_> = self
+            .global_attributes
+            .iter()
+            .map(|(k, v)| (k.clone(), *v))
+            .collect();
+        for (name, node) in attributes {
+            let attribute = self.attribute_use(node, true);
+            self.schema.attributes.insert(name, attribute);
+        }
+    }
+
+    /// Resolve the `QName` in attribute `attr` of `node` against the
+    /// namespace declarations in scope
+    fn qname_attr(&mut self, node: Node<'a, 'input>, attr: &str) -> Option<QName> {
+        let value = node.attribute(attr)?.trim();
+        self.resolve_qname(node, value)
+    }
+
+    fn resolve_qname(&mut self, node: Node<'a, 'input>, value: &str) -> Option<QName> {
+        let (prefix, local) = value
+            .split_once(':')
+            .map_or((None, value), |(p, l)| (Some(p), l));
+        let namespace = node.lookup_namespace_uri(prefix);
+        if let (Some(prefix), None) = (prefix, namespace) {
+            self.error(
+                node,
+                format!("Unknown namespace prefix '{prefix}' in '{value}'"),
+            );
+            return None;
+        }
+        Some(QName {
+            namespace: namespace.map(str::to_string),
+            local: local.to_string(),
+        })
+    }
+
+    fn type_by_name(&mut self, node: Node<'a, 'input>, name: &QName) -> TypeRef {
+        if name.namespace.as_deref() == Some(XS) {
+            if name.local == "anyType" {
+                return TypeRef::AnyType;
+            }
+            if let Some(builtin) = Builtin::from_name(&name.local) {
+                return TypeRef::Builtin(builtin);
+            }
+        } else if let Some(&index) = self.named_types.get(name) {
+            return TypeRef::Def(index);
+        }
+        self.error(node, format!("Type '{}' is not defined", name.local));
+        TypeRef::AnyType
+    }
+
+    /// Type named by attribute `attr`, else the inline type definition,
+    /// else `default`
+    fn type_of(&mut self, node: Node<'a, 'input>, attr: &str, default: TypeRef) -> TypeRef {
+        if let Some(name) = self.qname_attr(node, attr) {
+            return self.type_by_name(node, &name);
+        }
+        xs_children(node)
+            .find(|child| matches!(child.tag_name().name(), "simpleType" | "complexType"))
+            .map_or(default, |child| self.anonymous_type(child))
+    }
+
+    fn anonymous_type(&mut self, node: Node<'a, 'input>) -> TypeRef {
+        let ty = if node.tag_name().name() == "complexType" {
+            TypeDef::Complex(self.complex_type(node))
+        } else {
+            TypeDef::Simple(self.simple_type(node))
+        };
+        self.schema.types.push(ty);
+        TypeRef::Def(self.schema.types.len() - 1)
+    }
+
+    fn element_decl(&mut self, node: Node<'a, 'input>, global: bool) -> ElementDecl {
+        let qualified = node
+            .attribute("form")
+            .map_or(self.elements_qualified, |form| form == "qualified");
+        let name = QName {
+            namespace: if global || qualified {
+                self.target.clone()
+            } else {
+                None
+            },
+            local: node.attribute("name").unwrap_or_default().to_string(),
+        };
+        if name.local.is_empty() {
+            self.error(node, "Element declaration needs a name or ref");
+        }
+        let ty = self.type_of(node, "type", TypeRef::AnyType);
+        let fixed = node.attribute("fixed");
+        ElementDecl {
+            name,
+            ty,
+            nillable: node.attribute("nillable") == Some("true"),
+            is_abstract: node.attribute("abstract") == Some("true"),
+            value: fixed
+                .or_else(|| node.attribute("default"))
+                .map(str::to_string),
+            fixed: fixed.is_some(),
+        }
+    }
+
+    fn attribute_use(&mut self, node: Node<'a, 'input>, global: bool) -> AttributeUse {
+        let qualified = node
+            .attribute("form")
+            .map_or(self.attributes_qualified, |form| form == "qualified");
+        AttributeUse {
+            name: QName {
+                namespace: if global || qualified {
+                    self.target.clone()
+                } else {
+                    None
+                },
+                local: node.attribute("name").unwrap_or_default().to_string(),
+            },
+            ty: self.type_of(node, "type", TypeRef::Builtin(Builtin::AnySimple)),
+            required: node.attribute("use") == Some("required"),
+            prohibited: node.attribute("use") == Some("prohibited"),
+            fixed: node.attribute("fixed").map(str::to_string),
+        }
+    }
+
+    fn complex_type(&mut self, node: Node<'a, 'input>) -> ComplexType {
+        let mut ty = ComplexType::empty(node.attribute("mixed") == Some("true"));
+        for child in xs_children(node) {
+            match child.tag_name().name() {
+                "sequence" | "choice" | "all" | "group" => {
+                    if let Some(particle) = self.particle(child) {
+                        ty.content = Content::Elements(particle);
+                    }
+                }
+                "attribute" | "attributeGroup" | "anyAttribute" => {
+                    self.attribute_item(child, &mut ty);
+                }
+                "simpleContent" => self.simple_content(child, &mut ty),
+                "complexContent" => {
+                    if let Some(mixed) = child.attribute("mixed") {
+                        ty.mixed = mixed == "true";
+                    }
+                    self.complex_content(child, &mut ty);
+                }
+                other => self.error(
+                    child,
+                    format!("Unexpected <xs:{other}> in <xs:complexType>"),
+                ),
+            }
+        }
+        ty
+    }
+
+    /// Split a derivation step into its base type, reporting a missing base
+    fn derivation_step(
+        &mut self,
+        node: Node<'a, 'input>,
+    ) -> Option<(Node<'a, 'input>, bool, TypeRef)> {
+        let step = xs_children(node)
+            .find(|child| matches!(child.tag_name().name(), "extension" | "restriction"));
+        let Some(step) = step else {
+            self.error(node, "Expected <xs:extension> or <xs:restriction>");
+            return None;
+        };
+        let extension = step.tag_name().name() == "extension";
+        let Some(base) = self.qname_attr(step, "base") else {
+            self.error(step, "Derivation needs a base type");
+            return None;
+        };
+        Some((step, extension, self.type_by_name(step, &base)))
+    }
+
+    fn simple_content(&mut self, node: Node<'a, 'input>, ty: &mut ComplexType) {
+        let Some((step, extension, base)) = self.derivation_step(node) else {
+            return;
+        };
+        let complex_base = match base {
+            TypeRef::Def(index) => {
+                matches!(self.schema.types[index], TypeDef::Complex(_)).then_some(index)
+            }
+            TypeRef::Builtin(_) | TypeRef::AnyType => None,
+        };
+        if extension {
+            ty.content = Content::Simple(base);
+            if let Some(base) = complex_base {
+                ty.derivation = Some(Derivation {
+                    base,
+                    extension,
+                    facets: None,
+                });
+            }
+        } else {
+            let facets = self.facets(step);
+            let simple_base = if complex_base.is_some() {
+                TypeRef::Builtin(Builtin::AnySimple)
+            } else {
+                base
+            };
+            self.schema.types.push(TypeDef::Simple(Variety::Atomic {
+                base: simple_base,
+                facets,
+            }));
+            let index = self.schema.types.len() - 1;
+            ty.content = Content::Simple(TypeRef::Def(index));
+            if let Some(base) = complex_base {
+                ty.derivation = Some(Derivation {
+                    base,
+                    extension,
+                    facets: Some(index),
+                });
+            }
+        }
+        for child in xs_children(step) {
+            if matches!(
+                child.tag_name().name(),
+                "attribute" | "attributeGroup" | "anyAttribute"
+            ) {
+                self.attribute_item(child, ty);
+            }
+        }
+    }
+
+    fn complex_content(&mut self, node: Node<'a, 'input>, ty: &mut ComplexType) {
+        let Some((step, extension, base)) = self.derivation_step(node) else {
+            return;
+        };
+        for child in xs_children(step) {
+            match child.tag_name().name() {
+                "sequence" | "choice" | "all" | "group" => {
+                    if let Some(particle) = self.particle(child) {
+                        ty.content = Content::Elements(particle);
+                    }
+                }
+                "attribute" | "attributeGroup" | "anyAttribute" => self.attribute_item(child, ty),
+                other => self.error(child, format!("Unexpected <xs:{other}> in a derivation")),
+            }
+        }
+        match base {
+            TypeRef::Def(index) if matches!(self.schema.types[index], TypeDef::Complex(_)) => {
+                ty.derivation = Some(Derivation {
+                    base: index,
+                    extension,
+                    facets: None,
+                });
+            }
+            TypeRef::AnyType => {}
+            _ => self.error(step, "The base of complex content must be a complex type"),
+        }
+    }
+
+    fn attribute_item(&mut self, node: Node<'a, 'input>, ty: &mut ComplexType) {
+        match node.tag_name().name() {
+            "anyAttribute" => ty.any_attribute = Some(wildcard(node, self.target.as_deref())),
+            "attributeGroup" => {
+                let Some(name) = self.qname_attr(node, "ref") else {
+                    self.error(node, "Attribute group reference needs a ref");
+                    return;
+                };
+                let Some(&group) = self.attribute_groups.get(&name) else {
+                    self.error(
+                        node,
+                        format!("Attribute group '{}' is not defined", name.local),
+                    );
+                    return;
+                };
+                if self.expanding.contains(&name) || self.expanding.len() > MAX_DEPTH {
+                    self.error(
+                        node,
+                        format!("Attribute group '{}' refers to itself", name.local),
+                    );
+                    return;
+                }
+                self.expanding.push(name);
+                for child in xs_children(group) {
+                    self.attribute_item(child, ty);
+                }
+                self.expanding.pop();
+            }
+            _ => {
+                let attribute = if let Some(name) = self.qname_attr(node, "ref") {
+                    let Some(&global) = self.global_attributes.get(&name) else {
+                        self.error(node, format!("Attribute '{}' is not defined", name.local));
+                        return;
+                    };
+                    let mut attribute = self.attribute_use(global, true);
+                    attribute.required = node.attribute("use") == Some("required");
+                    attribute.prohibited = node.attribute("use") == Some("prohibited");
+                    if let Some(fixed) = node.attribute("fixed") {
+                        attribute.fixed = Some(fixed.to_string());
+                    }
+                    attribute
+                } else {
+                    self.attribute_use(node, false)
+                };
+                ty.attributes.push(attribute);
+            }
+        }
+    }
+
+    fn occurs(&mut self, node: Node<'a, 'input>) -> (u32, Option<u32>) {
+        let min = match node.attribute("minOccurs").map(str::parse) {
+            None => 1,
+            Some(Ok(min)) => min,
+            Some(Err(_)) => {
+                self.error(node, "minOccurs must be a non-negative integer");
+                1
+            }
+        };
+        let max = match node.attribute("maxOccurs") {
+            None => Some(1),
+            Some("unbounded") => None,
+            Some(max) => {
+                let parsed = max.parse().ok();
+                if parsed.is_none() {
+                    self.error(
+                        node,
+                        "maxOccurs must be a non-negative integer or 'unbounded'",
+                    );
+                }
+                parsed.or(Some(1))
+            }
+        };
+        if max.is_some_and(|max| max < min) {
+            self.error(node, "maxOccurs is less than minOccurs");
+            return (min, Some(min));
+        }
+        (min, max)
+    }
+
+    fn particle(&mut self, node: Node<'a, 'input>) -> Option<Particle> {
+        let (min, max) = self.occurs(node);
+        let term = match node.tag_name().name() {
+            "element" => {
+                let decl = if let Some(name) = self.qname_attr(node, "ref") {
+                    let Some(&decl) = self.schema.elements.get(&name) else {
+                        self.error(node, format!("Element '{}' is not defined", name.local));
+                        return None;
+                    };
+                    decl
+                } else {
+                    let decl = self.element_decl(node, false);
+                    self.schema.decls.push(decl);
+                    self.schema.decls.len() - 1
+                };
+                self.leaf(Leaf::Element(decl))
+            }
+            "any" => self.leaf(Leaf::Any(wildcard(node, self.target.as_deref()))),
+            kind @ ("sequence" | "choice" | "all") => {
+                let items: Vec<_> = xs_children(node)
+                    .filter_map(|child| self.particle(child))
+                    .collect();
+                match kind {
+                    "sequence" => Term::Sequence(items),
+                    "choice" => Term::Choice(items),
+                    _ if items.len() > 64 => {
+                        self.error(node, "<xs:all> groups are limited to 64 members");
+                        return None;
+                    }
+                    _ => Term::All(items),
+                }
+            }
+            "group" => self.group_ref(node)?,
+            other => {
+                self.error(node, format!("Unexpected <xs:{other}> in a content model"));
+                return None;
+            }
+        };
+        Some(Particle { term, min, max })
+    }
+
+    fn leaf(&mut self, leaf: Leaf) -> Term {
+        self.schema.leaves.push(leaf);
+        Term::Leaf(self.schema.leaves.len() - 1)
+    }
+
+    fn group_ref(&mut self, node: Node<'a, 'input>) -> Option<Term> {
+        let Some(name) = self.qname_attr(node, "ref") else {
+            self.error(node, "Group reference needs a ref");
+            return None;
+        };
+        let Some(&group) = self.groups.get(&name) else {
+            self.error(node, format!("Group '{}' is not defined", name.local));
+            return None;
+        };
+        if self.expanding.contains(&name) || self.expanding.len() > MAX_DEPTH {
+            self.error(node, format!("Group '{}' refers to itself", name.local));
+            return None;
+        }
+        self.expanding.push(name);
+        let term = xs_children(group)
+            .find(|child| matches!(child.tag_name().name(), "sequence" | "choice" | "all"))
+            .and_then(|model| self.particle(model))
+            .map(|particle| particle.term);
+        self.expanding.pop();
+        term
+    }
+
+    fn simple_type(&mut self, node: Node<'a, 'input>) -> Variety {
+        for child in xs_children(node) {
+            match child.tag_name().name() {
+                "restriction" => {
+                    let base = self.type_of(child, "base", TypeRef::Builtin(Builtin::AnySimple));
+                    let facets = self.facets(child);
+                    return Variety::Atomic { base, facets };
+                }
+                "list" => {
+                    let item =
+                        self.type_of(child, "itemType", TypeRef::Builtin(Builtin::AnySimple));
+                    return Variety::List { item };
+                }
+                "union" => {
+                    let mut members = Vec::new();
+                    for name in child
+                        .attribute("memberTypes")
+                        .unwrap_or_default()
+                        .split_whitespace()
+                    {
+                        if let Some(name) = self.resolve_qname(child, name) {
+                            members.push(self.type_by_name(child, &name));
+                        }
+                    }
+                    for inline in xs_children(child).filter(|c| c.tag_name().name() == "simpleType")
+                    {
+                        members.push(self.anonymous_type(inline));
+                    }
+                    return Variety::Union(members);
+                }
+                _ => {}
+            }
+        }
+        self.error(
+            node,
+            "Simple type needs <xs:restriction>, <xs:list>, or <xs:union>",
+        );
+        Variety::Atomic {
+            base: TypeRef::Builtin(Builtin::AnySimple),
+            facets: Box::default(),
+        }
+    }
+
+    fn facets(&mut self, node: Node<'a, 'input>) -> Box<Facets> {
+        let mut facets = Facets::default();
+        let mut patterns = Vec::new();
+        for child in xs_children(node) {
+            let value = child.attribute("value").unwrap_or_default();
+            let slot = match child.tag_name().name() {
+                "enumeration" => {
+                    facets.enumeration.push(value.to_string());
+                    continue;
+                }
+                "pattern" => {
+                    patterns.push((child, value));
+                    continue;
+                }
+                "minInclusive" => &mut facets.min_inclusive,
+                "maxInclusive" => &mut facets.max_inclusive,
+                "minExclusive" => &mut facets.min_exclusive,
+                "maxExclusive" => &mut facets.max_exclusive,
+                name
+                @ ("length" | "minLength" | "maxLength" | "totalDigits" | "fractionDigits") => {
+                    let slot = match name {
+                        "length" => &mut facets.length,
+                        "minLength" => &mut facets.min_length,
+                        "maxLength" => &mut facets.max_length,
+                        "totalDigits" => &mut facets.total_digits,
+                        _ => &mut facets.fraction_digits,
+                    };
+                    *slot = value.parse().ok();
+                    if slot.is_none() {
+                        self.error(child, format!("{name} must be a non-negative integer"));
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            *slot = Some(value.to_string());
+        }
+        // Patterns in one derivation step are alternatives
+        if !patterns.is_empty() {
+            let source = patterns
+                .iter()
+                .map(|(_, p)| *p)
+                .collect::<Vec<_>>()
+                .join("|");
+            let translated: Option<Vec<_>> =
+                patterns.iter().map(|(_, p)| translate_pattern(p)).collect();
+            match translated.map(|parts| Regex::new(&format!("^(?:{})$", parts.join("|")))) {
+                Some(Ok(regex)) => facets.pattern = Some((regex, source)),
+                _ => self.error(patterns[0].0, format!("Unsupported pattern '{source}'")),
+            }
+        }
+        Box::new(facets)
+    }
+}
+
+fn wildcard(node: Node<'_, '_>, target: Option<&str>) -> Wildcard {
+    let namespaces = match node.attribute("namespace").unwrap_or("##any").trim() {
+        "##any" => NamespaceConstraint::Any,
+        "##other" => NamespaceConstraint::Not(target.map(str::to_string)),
+        list => NamespaceConstraint::List(
+            list.split_whitespace()
+                .map(|ns| match ns {
+                    "##targetNamespace" => target.map(str::to_string),
+                    "##local" => None,
+                    uri => Some(uri.to_string()),
+                })
+                .collect(),
+        ),
+    };
+    let process = match node.attribute("processContents") {
+        Some("lax") => ProcessContents::Lax,
+        Some("skip") => ProcessContents::Skip,
+        _ => ProcessContents::Strict,
+    };
+    Wildcard {
+        namespaces,
+        process,
+    }
+}
+
+/// Rewrite an XSD regular expression in Rust `regex` syntax. XSD patterns
+/// are implicitly anchored and treat `^` and `$` as literals; character
+/// class subtraction has no equivalent and yields `None`.
+fn translate_pattern(pattern: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut in_class = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'i' if in_class => out.push_str("_:A-Za-z"),
+                'i' => out.push_str("[_:A-Za-z]"),
+                'c' if in_class => out.push_str("\\-._:A-Za-z0-9"),
+                'c' => out.push_str("[\\-._:A-Za-z0-9]"),
+                'I' | 'C' if in_class => return None,
+                'I' => out.push_str("[^_:A-Za-z]"),
+                'C' => out.push_str("[^\\-._:A-Za-z0-9]"),
+                escaped => {
+                    out.push('\\');
+                    out.push(escaped);
+                }
+            },
+            '[' if in_class => return None,
+            '[' => {
+                in_class = true;
+                out.push(c);
+            }
+            ']' => {
+                in_class = false;
+                out.push(c);
+            }
+            '^' | '$' if !in_class => {
+                out.push('\\');
+                out.push(c);
+            }
+            '&' | '~' if in_class => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+impl ComplexType {
+    const fn empty(mixed: bool) -> Self {
+        Self {
+            mixed,
+            content: Content::Empty,
+            attributes: Vec::new(),
+            any_attribute: None,
+            derivation: None,
+        }
+    }
+}
+
+impl Schema {
+    /// Merge a derived complex type with its (resolved) base
+    fn resolve(&mut self, index: usize, depth: usize, errors: &mut Vec<AstParseError>) {
+        let TypeDef::Complex(ty) = &mut self.types[index] else {
+            return;
+        };
+        let Some(derivation) = ty.derivation.take() else {
+            return;
+        };
+        if depth > MAX_DEPTH {
+            errors.push(AstParseError::new(
+                "Type derivation is circular or too deep",
+            ));
+            return;
+        }
+        self.resolve(derivation.base, depth + 1, errors);
+        let TypeDef::Complex(base) = &self.types[derivation.base] else {
+            return;
+        };
+        let base = base.clone();
+        if let (Some(facets), Content::Simple(simple)) = (derivation.facets, &base.content) {
+            if let TypeDef::Simple(Variety::Atomic { base, .. }) = &mut self.types[facets] {
+                *base = *simple;
+            }
+        }
+        let TypeDef::Complex(ty) = &mut self.types[index] else {
+            return;
+        };
+        let mut attributes = base.attributes;
+        for own in std::mem::take(&mut ty.attributes) {
+            attributes.retain(|inherited| inherited.name != own.name);
+            attributes.push(own);
+        }
+        ty.attributes = attributes;
+        if ty.any_attribute.is_none() {
+            ty.any_attribute = base.any_attribute;
+        }
+        if derivation.extension {
+            ty.mixed |= base.mixed;
+            ty.content = match (
+                base.content,
+                std::mem::replace(&mut ty.content, Content::Empty),
+            ) {
+                (Content::Elements(inherited), Content::Elements(own)) => {
+                    Content::Elements(Particle::once(Term::Sequence(vec![inherited, own])))
+                }
+                (inherited, Content::Empty) => inherited,
+                (_, own) => own,
+            };
+        }
+    }
+
+    fn leaf_accepts(&self, leaf: usize, node: Node<'_, '_>) -> bool {
+        let name = node.tag_name();
+        match &self.leaves[leaf] {
+            Leaf::Element(decl) => self.decls[*decl]
+                .name
+                .matches(name.namespace(), name.name()),
+            Leaf::Any(wildcard) => wildcard.allows(name.namespace()),
+        }
+    }
+
+    fn leaf_name(&self, leaf: usize) -> String {
+        match &self.leaves[leaf] {
+            Leaf::Element(decl) => format!("<{}>", self.decls[*decl].name.local),
+            Leaf::Any(_) => "any element".to_string(),
+        }
+    }
+
+    fn validate_element<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        decl: usize,
+        report: &mut Report<'a, 'input>,
+    ) {
+        if report.is_full() {
+            return;
+        }
+        let decl = &self.decls[decl];
+        let name = raw_name(node);
+        if decl.is_abstract {
+            report.element(
+                node,
+                format!("Element <{name}> is abstract and cannot appear in a document"),
+            );
+        }
+        if matches!(node.attribute((XSI, "nil")), Some("true" | "1")) {
+            if !decl.nillable {
+                report.element(node, format!("Element <{name}> is not nillable"));
+            } else if node.children().any(|c| c.is_element()) || significant_text(node).is_some() {
+                report.element(node, format!("Element <{name}> is nil but has content"));
+            }
+            return;
+        }
+        let complex = match decl.ty {
+            TypeRef::Def(index) => match &self.types[index] {
+                TypeDef::Complex(ty) => Some(ty),
+                TypeDef::Simple(_) => None,
+            },
+            TypeRef::Builtin(_) | TypeRef::AnyType => None,
+        };
+        match (decl.ty, complex) {
+            (TypeRef::AnyType, _) => self.validate_lax_children(node, report),
+            (_, Some(ty)) => self.validate_complex(node, ty, decl, report),
+            (simple, None) => {
+                self.validate_attributes(node, &[], None, report);
+                self.validate_simple_content(node, simple, decl, report);
+            }
+        }
+    }
+
+    fn validate_simple_content<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        ty: TypeRef,
+        decl: &ElementDecl,
+        report: &mut Report<'a, 'input>,
+    ) {
+        let name = raw_name(node);
+        if let Some(child) = node.children().find(Node::is_element) {
+            report.element(
+                child,
+                format!("Element <{name}> has simple content and cannot contain elements"),
+            );
+            return;
+        }
+        let text = direct_text(node);
+        if text.is_empty() && decl.value.is_some() {
+            return;
+        }
+        if let Err(message) = self.check_value(ty, &text) {
+            report.element(
+                node,
+                format!("Invalid content of element <{name}>: {message}"),
+            );
+        } else if let Some(fixed) = decl.value.as_deref().filter(|_| decl.fixed) {
+            if self.normalize(ty, &text) != self.normalize(ty, fixed) {
+                report.element(
+                    node,
+                    format!("Element <{name}> must have the fixed value '{fixed}'"),
+                );
+            }
+        }
+    }
+
+    fn validate_complex<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        ty: &ComplexType,
+        decl: &ElementDecl,
+        report: &mut Report<'a, 'input>,
+    ) {
+        let name = raw_name(node);
+        self.validate_attributes(node, &ty.attributes, ty.any_attribute.as_ref(), report);
+        let particle = match &ty.content {
+            Content::Simple(simple) => {
+                return self.validate_simple_content(node, *simple, decl, report)
+            }
+            Content::Empty => None,
+            Content::Elements(particle) => Some(particle),
+        };
+        if !ty.mixed {
+            if let Some(text) = significant_text(node) {
+                report.node(text, format!("Text is not allowed in element <{name}>"));
+            }
+        }
+        let children: Vec<_> = node.children().filter(Node::is_element).collect();
+        let Some(particle) = particle else {
+            if let Some(&child) = children.first() {
+                report.element(child, format!("Element <{name}> must not contain elements"));
+            }
+            return;
+        };
+        if let Err(mismatch) = particle.check(children.len(), |leaf, i| {
+            self.leaf_accepts(leaf, children[i])
+        }) {
+            let expected: Vec<_> = mismatch
+                .expected
+                .iter()
+                .map(|&leaf| self.leaf_name(leaf))
+                .collect();
+            report_mismatch(report, node, &children, &mismatch, &expected);
+        }
+        let leaves = particle.leaves();
+        for child in children {
+            // Declarations take precedence over wildcards that also match
+            let leaf = leaves
+                .iter()
+                .filter(|&&leaf| self.leaf_accepts(leaf, child))
+                .min_by_key(|&&leaf| matches!(self.leaves[leaf], Leaf::Any(_)));
+            match leaf.map(|&leaf| &self.leaves[leaf]) {
+                Some(Leaf::Element(decl)) => self.validate_element(child, *decl, report),
+                Some(Leaf::Any(wildcard)) => {
+                    self.validate_wildcard_element(child, wildcard.process, report);
+                }
+                // Already reported by the content-model check
+                None => {}
+            }
+        }
+    }
+
+    fn validate_wildcard_element<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        process: ProcessContents,
+        report: &mut Report<'a, 'input>,
+    ) {
+        if process == ProcessContents::Skip {
+            return;
+        }
+        match self.elements.get(&QName::of(node)) {
+            Some(&decl) => self.validate_element(node, decl, report),
+            None if process == ProcessContents::Strict => report.element(
+                node,
+                format!("No global element declaration matches <{}>", raw_name(node)),
+            ),
+            None => self.validate_lax_children(node, report),
+        }
+    }
+
+    /// Validate the children of an element whose content is not
+    /// constrained, wherever a global declaration applies
+    fn validate_lax_children<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        report: &mut Report<'a, 'input>,
+    ) {
+        for child in node.children().filter(Node::is_element) {
+            self.validate_wildcard_element(child, ProcessContents::Lax, report);
+        }
+    }
+
+    fn validate_attributes<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        uses: &[AttributeUse],
+        any: Option<&Wildcard>,
+        report: &mut Report<'a, 'input>,
+    ) {
+        let name = raw_name(node);
+        for attribute in node.attributes() {
+            let namespace = attribute.namespace();
+            if namespace == Some(XSI) {
+                continue;
+            }
+            let declared = uses
+                .iter()
+                .find(|u| !u.prohibited && u.name.matches(namespace, attribute.name()));
+            let global = || {
+                self.attributes.get(&QName {
+                    namespace: namespace.map(str::to_string),
+                    local: attribute.name().to_string(),
+                })
+            };
+            match (declared, any) {
+                (Some(declared), _) => self.validate_attribute(node, attribute, declared, report),
+                (None, Some(wildcard)) if wildcard.allows(namespace) => {
+                    if wildcard.process == ProcessContents::Skip {
+                        continue;
+                    }
+                    if let Some(global) = global() {
+                        self.validate_attribute(node, attribute, global, report);
+                    } else if wildcard.process == ProcessContents::Strict {
+                        report.attribute(
+                            node,
+                            attribute,
+                            format!(
+                                "No global attribute declaration matches '{}'",
+                                raw_attribute_name(node, &attribute)
+                            ),
+                        );
+                    }
+                }
+                (None, _) => report.attribute(
+                    node,
+                    attribute,
+                    format!(
+                        "Attribute '{}' is not allowed on element <{name}>",
+                        raw_attribute_name(node, &attribute)
+                    ),
+                ),
+            }
+        }
+        for required in uses.iter().filter(|u| u.required) {
+            let present = node
+                .attributes()
+                .any(|a| required.name.matches(a.namespace(), a.name()));
+            if !present {
+                report.element(
+                    node,
+                    format!(
+                        "Element <{name}> is missing required attribute '{}'",
+                        required.name.local
+                    ),
+                );
+            }
+        }
+    }
+
+    fn validate_attribute<'a, 'input>(
+        &self,
+        node: Node<'a, 'input>,
+        attribute: Attribute<'a, 'input>,
+        declared: &AttributeUse,
+        report: &mut Report<'a, 'input>,
+    ) {
+        let name = raw_attribute_name(node, &attribute);
+        let value = attribute.value();
+        if let Err(message) = self.check_value(declared.ty, value) {
+            report.attribute(
+                node,
+                attribute,
+                format!("Invalid value of attribute '{name}': {message}"),
+            );
+            return;
+        }
+        let normalized = self.normalize(declared.ty, value);
+        if let Some(fixed) = &declared.fixed {
+            if normalized != self.normalize(declared.ty, fixed) {
+                report.attribute(
+                    node,
+                    attribute,
+                    format!("Attribute '{name}' must have the fixed value '{fixed}'"),
+                );
+            }
+        }
+        match self.primitive(declared.ty) {
+            Some(Builtin::Id) => report.id(node, attribute, &normalized),
+            Some(Builtin::IdRef) => report.idref(node, attribute, &normalized),
+            Some(Builtin::IdRefs) => {
+                for token in normalized.split(' ') {
+                    report.idref(node, attribute, token);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Built-in type at the root of an atomic derivation chain, or the
+    /// built-in that a list type's items derive from for ID references
+    fn primitive(&self, ty: TypeRef) -> Option<Builtin> {
+        let mut ty = ty;
+        for _ in 0..MAX_DEPTH {
+            match ty {
+                TypeRef::Builtin(builtin) => return Some(builtin),
+                TypeRef::Def(index) => match &self.types[index] {
+                    TypeDef::Simple(Variety::Atomic { base, .. }) => ty = *base,
+                    TypeDef::Simple(Variety::List { item }) => {
+                        return (self.primitive(*item) == Some(Builtin::IdRef))
+                            .then_some(Builtin::IdRefs)
+                    }
+                    _ => return None,
+                },
+                TypeRef::AnyType => return None,
+            }
+        }
+        None
+    }
+
+    fn is_list(&self, ty: TypeRef) -> bool {
+        match ty {
+            TypeRef::Def(index) => match &self.types[index] {
+                TypeDef::Simple(Variety::List { .. }) => true,
+                TypeDef::Simple(Variety::Atomic { base, .. }) => self.is_list(*base),
+                _ => false,
+            },
+            TypeRef::Builtin(builtin) => matches!(
+                builtin,
+                Builtin::IdRefs | Builtin::Entities | Builtin::NmTokens
+            ),
+            TypeRef::AnyType => false,
+        }
+    }
+
+    /// Apply the type's whitespace rule: strings keep their whitespace,
+    /// normalized strings turn it into spaces, everything else collapses
+    fn normalize(&self, ty: TypeRef, value: &str) -> String {
+        match self.primitive(ty) {
+            Some(Builtin::String | Builtin::AnySimple) if !self.is_list(ty) => value.to_string(),
+            Some(Builtin::NormalizedString) => value.replace(['\t', '\n', '\r'], " "),
+            _ => value.split_whitespace().collect::<Vec<_>>().join(" "),
+        }
+    }
+
+    fn check_value(&self, ty: TypeRef, value: &str) -> Result<(), String> {
+        self.check_simple(ty, value, 0)
+    }
+
+    fn check_simple(&self, ty: TypeRef, value: &str, depth: usize) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Ok(());
+        }
+        let index = match ty {
+            TypeRef::AnyType => return Ok(()),
+            TypeRef::Builtin(builtin) => return builtin.check(&self.normalize(ty, value)),
+            TypeRef::Def(index) => index,
+        };
+        match &self.types[index] {
+            TypeDef::Complex(_) => Ok(()),
+            TypeDef::Simple(Variety::Atomic { base, facets }) => {
+                self.check_simple(*base, value, depth + 1)?;
+                let normalized = self.normalize(ty, value);
+                let measure = if self.is_list(*base) {
+                    normalized.split_whitespace().count()
+                } else {
+                    match self.primitive(*base) {
+                        Some(Builtin::HexBinary) => normalized.len() / 2,
+                        Some(Builtin::Base64Binary) => base64_len(&normalized),
+                        _ => normalized.chars().count(),
+                    }
+                };
+                let numeric = self.primitive(*base).is_some_and(Builtin::is_numeric);
+                facets.check(&normalized, measure, numeric)
+            }
+            TypeDef::Simple(Variety::List { item }) => {
+                for token in value.split_whitespace() {
+                    self.check_simple(*item, token, depth + 1)?;
+                }
+                Ok(())
+            }
+            TypeDef::Simple(Variety::Union(members)) => {
+                if members.is_empty()
+                    || members
+                        .iter()
+                        .any(|m| self.check_simple(*m, value, depth + 1).is_ok())
+                {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "'{}' matches none of the union's member types",
+                        value.trim()
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl Facets {
+    fn check(&self, value: &str, measure: usize, numeric: bool) -> Result<(), String> {
+        if !self.enumeration.is_empty() && !self.enumeration.iter().any(|e| e == value) {
+            return Err(format!(
+                "'{value}' is not one of: {}",
+                self.enumeration.join(", ")
+            ));
+        }
+        if let Some((regex, source)) = &self.pattern {
+            if !regex.is_match(value) {
+                return Err(format!("'{value}' does not match the pattern '{source}'"));
+            }
+        }
+        let length_checks = [
+            (
+                self.length,
+                measure != self.length.unwrap_or_default(),
+                "exactly",
+            ),
+            (
+                self.min_length,
+                measure < self.min_length.unwrap_or_default(),
+                "at least",
+            ),
+            (
+                self.max_length,
+                measure > self.max_length.unwrap_or_default(),
+                "at most",
+            ),
+        ];
+        for (limit, broken, relation) in length_checks {
+            if let (Some(limit), true) = (limit, broken) {
+                return Err(format!(
+                    "'{value}' has length {measure}; expected {relation} {limit}"
+                ));
+            }
+        }
+        let bounds = [
+            (&self.min_inclusive, std::cmp::Ordering::Less, "at least"),
+            (&self.max_inclusive, std::cmp::Ordering::Greater, "at most"),
+        ];
+        for (bound, outside, relation) in bounds {
+            if let Some(bound) = bound {
+                if compare(value, bound, numeric) == Some(outside) {
+                    return Err(format!("'{value}' must be {relation} {bound}"));
+                }
+            }
+        }
+        let exclusive = [
+            (
+                &self.min_exclusive,
+                std::cmp::Ordering::Greater,
+                "greater than",
+            ),
+            (&self.max_exclusive, std::cmp::Ordering::Less, "less than"),
+        ];
+        for (bound, inside, relation) in exclusive {
+            if let Some(bound) = bound {
+                if compare(value, bound, numeric).is_some_and(|order| order != inside) {
+                    return Err(format!("'{value}' must be {relation} {bound}"));
+                }
+            }
+        }
+        let (total, fraction) = digit_counts(value);
+        if self.total_digits.is_some_and(|limit| total > limit) {
+            return Err(format!(
+                "'{value}' has more than {} digits",
+                self.total_digits.unwrap_or_default()
+            ));
+        }
+        if self.fraction_digits.is_some_and(|limit| fraction > limit) {
+            return Err(format!(
+                "'{value}' has more than {} fraction digits",
+                self.fraction_digits.unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Order `value` against a facet bound: numerically for numeric types,
+/// lexically otherwise (which orders same-format ISO dates correctly)
+fn compare(value: &str, bound: &str, numeric: bool) -> Option<std::cmp::Ordering> {
+    if numeric {
+        let value: f64 = value.parse().ok()?;
+        value.partial_cmp(&bound.parse().ok()?)
+    } else {
+        Some(value.cmp(bound))
+    }
+}
+
+/// Significant digits and fraction digits of a decimal literal
+fn digit_counts(value: &str) -> (usize, usize) {
+    let unsigned = value.trim_start_matches(['+', '-']);
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let frac = frac.trim_end_matches('0');
+    let int = int.trim_start_matches('0');
+    (int.len() + frac.len(), frac.len())
+}
+
+fn base64_len(value: &str) -> usize {
+    let chars: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let padding = chars.iter().rev().take_while(|&&c| c == '=').count();
+    (chars.len() / 4 * 3).saturating_sub(padding)
+}
+
+/// Built-in XSD datatypes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Builtin {
+    AnySimple,
+    String,
+    NormalizedString,
+    Token,
+    Language,
+    Name,
+    NcName,
+    QName,
+    Id,
+    IdRef,
+    IdRefs,
+    Entity,
+    Entities,
+    NmToken,
+    NmTokens,
+    Notation,
+    AnyUri,
+    Boolean,
+    Decimal,
+    Integer,
+    NonPositiveInteger,
+    NegativeInteger,
+    NonNegativeInteger,
+    PositiveInteger,
+    Long,
+    Int,
+    Short,
+    Byte,
+    UnsignedLong,
+    UnsignedInt,
+    UnsignedShort,
+    UnsignedByte,
+    Float,
+    Double,
+    Duration,
+    DateTime,
+    Date,
+    Time,
+    GYear,
+    GYearMonth,
+    GMonth,
+    GMonthDay,
+    GDay,
+    HexBinary,
+    Base64Binary,
+}
+
+const BUILTINS: &[(&str, Builtin)] = &[
+    ("anySimpleType", Builtin::AnySimple),
+    ("string", Builtin::String),
+    ("normalizedString", Builtin::NormalizedString),
+    ("token", Builtin::Token),
+    ("language", Builtin::Language),
+    ("Name", Builtin::Name),
+    ("NCName", Builtin::NcName),
+    ("QName", Builtin::QName),
+    ("ID", Builtin::Id),
+    ("IDREF", Builtin::IdRef),
+    ("IDREFS", Builtin::IdRefs),
+    ("ENTITY", Builtin::Entity),
+    ("ENTITIES", Builtin::Entities),
+    ("NMTOKEN", Builtin::NmToken),
+    ("NMTOKENS", Builtin::NmTokens),
+    ("NOTATION", Builtin::Notation),
+    ("anyURI", Builtin::AnyUri),
+    ("boolean", Builtin::Boolean),
+    ("decimal", Builtin::Decimal),
+    ("integer", Builtin::Integer),
+    ("nonPositiveInteger", Builtin::NonPositiveInteger),
+    ("negativeInteger", Builtin::NegativeInteger),
+    ("nonNegativeInteger", Builtin::NonNegativeInteger),
+    ("positiveInteger", Builtin::PositiveInteger),
+    ("long", Builtin::Long),
+    ("int", Builtin::Int),
+    ("short", Builtin::Short),
+    ("byte", Builtin::Byte),
+    ("unsignedLong", Builtin::UnsignedLong),
+    ("unsignedInt", Builtin::UnsignedInt),
+    ("unsignedShort", Builtin::UnsignedShort),
+    ("unsignedByte", Builtin::UnsignedByte),
+    ("float", Builtin::Float),
+    ("double", Builtin::Double),
+    ("duration", Builtin::Duration),
+    ("dateTime", Builtin::DateTime),
+    ("date", Builtin::Date),
+    ("time", Builtin::Time),
+    ("gYear", Builtin::GYear),
+    ("gYearMonth", Builtin::GYearMonth),
+    ("gMonth", Builtin::GMonth),
+    ("gMonthDay", Builtin::GMonthDay),
+    ("gDay", Builtin::GDay),
+    ("hexBinary", Builtin::HexBinary),
+    ("base64Binary", Builtin::Base64Binary),
+];
+
+impl Builtin {
+    fn from_name(name: &str) -> Option<Self> {
+        BUILTINS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, builtin)| *builtin)
+    }
+
+    fn name(self) -> &'static str {
+        BUILTINS
+            .iter()
+            .find(|(_, builtin)| *builtin == self)
+            .map_or("anySimpleType", |(name, _)| name)
+    }
+
+    const fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::Decimal
+                | Self::Integer
+                | Self::NonPositiveInteger
+                | Self::NegativeInteger
+                | Self::NonNegativeInteger
+                | Self::PositiveInteger
+                | Self::Long
+                | Self::Int
+                | Self::Short
+                | Self::Byte
+                | Self::UnsignedLong
+                | Self::UnsignedInt
+                | Self::UnsignedShort
+                | Self::UnsignedByte
+                | Self::Float
+                | Self::Double
+        )
+    }
+
+    /// Check a whitespace-normalized lexical value
+    fn check(self, value: &str) -> Result<(), String> {
+        if self.accepts(value) {
+            Ok(())
+        } else {
+            Err(format!("'{value}' is not a valid xs:{}", self.name()))
+        }
+    }
+
+    fn accepts(self, v: &str) -> bool {
+        let is_ncname = |s: &str| is_name(s) && !s.contains(':');
+        let is_qname = |s: &str| {
+            s.split_once(':')
+                .map_or_else(|| is_ncname(s), |(p, l)| is_ncname(p) && is_ncname(l))
+        };
+        let tokens = || v.split(' ').filter(|t| !t.is_empty());
+        match self {
+            Self::AnySimple | Self::String | Self::AnyUri => true,
+            Self::NormalizedString => !v.contains(['\t', '\n', '\r']),
+            Self::Token => !v.contains(['\t', '\n', '\r']) && v.trim() == v && !v.contains("  "),
+            Self::Language => is_language(v),
+            Self::Name => is_name(v),
+            Self::NcName | Self::Id | Self::IdRef | Self::Entity => is_ncname(v),
+            Self::QName | Self::Notation => is_qname(v),
+            Self::IdRefs | Self::Entities => tokens().next().is_some() && tokens().all(is_ncname),
+            Self::NmToken => is_nmtoken(v),
+            Self::NmTokens => tokens().next().is_some() && tokens().all(is_nmtoken),
+            Self::Boolean => matches!(v, "true" | "false" | "1" | "0"),
+            Self::Decimal => is_decimal(v),
+            Self::Integer => is_integer(v),
+            Self::NonPositiveInteger => {
+                is_integer(v)
+                    && (v.starts_with('-') || v.trim_start_matches('+').bytes().all(|b| b == b'0'))
+            }
+            Self::NegativeInteger => {
+                is_integer(v) && v.starts_with('-') && !v[1..].bytes().all(|b| b == b'0')
+            }
+            Self::NonNegativeInteger => {
+                is_integer(v) && (!v.starts_with('-') || v[1..].bytes().all(|b| b == b'0'))
+            }
+            Self::PositiveInteger => {
+                is_integer(v)
+                    && !v.starts_with('-')
+                    && !v.trim_start_matches('+').bytes().all(|b| b == b'0')
+            }
+            Self::Long => in_range(v, i128::from(i64::MIN), i128::from(i64::MAX)),
+            Self::Int => in_range(v, i128::from(i32::MIN), i128::from(i32::MAX)),
+            Self::Short => in_range(v, i128::from(i16::MIN), i128::from(i16::MAX)),
+            Self::Byte => in_range(v, i128::from(i8::MIN), i128::from(i8::MAX)),
+            Self::UnsignedLong => in_range(v, 0, i128::from(u64::MAX)),
+            Self::UnsignedInt => in_range(v, 0, i128::from(u32::MAX)),
+            Self::UnsignedShort => in_range(v, 0, i128::from(u16::MAX)),
+            Self::UnsignedByte => in_range(v, 0, i128::from(u8::MAX)),
+            Self::Float | Self::Double => is_float(v),
+            Self::Duration => is_duration(v),
+            Self::DateTime => v.split_once('T').is_some_and(|(date, time)| {
+                date_part(date) == Some("") && time_part(time).is_some_and(is_timezone)
+            }),
+            Self::Date => date_part(v).is_some_and(is_timezone),
+            Self::Time => time_part(v).is_some_and(is_timezone),
+            Self::GYear => year_part(v).is_some_and(is_timezone),
+            Self::GYearMonth => year_part(v)
+                .and_then(|rest| rest.strip_prefix('-'))
+                .and_then(|rest| two_digits(rest, 1, 12))
+                .is_some_and(is_timezone),
+            Self::GMonth => v
+                .strip_prefix("--")
+                .and_then(|rest| two_digits(rest, 1, 12))
+                .is_some_and(is_timezone),
+            Self::GMonthDay => v
+                .strip_prefix("--")
+                .and_then(month_day)
+                .is_some_and(is_timezone),
+            Self::GDay => v
+                .strip_prefix("---")
+                .and_then(|rest| two_digits(rest, 1, 31))
+                .is_some_and(is_timezone),
+            Self::HexBinary => {
+                v.len().is_multiple_of(2) && v.bytes().all(|b| b.is_ascii_hexdigit())
+            }
+            Self::Base64Binary => is_base64(v),
+        }
+    }
+}
+
+fn is_integer(v: &str) -> bool {
+    let digits = v.strip_prefix(['+', '-']).unwrap_or(v);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn in_range(v: &str, min: i128, max: i128) -> bool {
+    is_integer(v) && v.parse::<i128>().is_ok_and(|n| (min..=max).contains(&n))
+}
+
+fn is_unsigned_decimal(v: &str) -> bool {
+    let (int, frac) = v.split_once('.').unwrap_or((v, ""));
+    !(int.is_empty() && frac.is_empty())
+        && int.bytes().all(|b| b.is_ascii_digit())
+        && frac.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_decimal(v: &str) -> bool {
+    is_unsigned_decimal(v.strip_prefix(['+', '-']).unwrap_or(v))
+}
+
+fn is_float(v: &str) -> bool {
+    if matches!(v, "INF" | "-INF" | "+INF" | "NaN") {
+        return true;
+    }
+    let (mantissa, exponent) = v
+        .split_once(['e', 'E'])
+        .map_or((v, None), |(m, e)| (m, Some(e)));
+    is_decimal(mantissa) && exponent.is_none_or(is_integer)
+}
+
+fn is_language(v: &str) -> bool {
+    v.split('-').enumerate().all(|(i, part)| {
+        (1..=8).contains(&part.len())
+            && part.bytes().all(|b| {
+                if i == 0 {
+                    b.is_ascii_alphabetic()
+                } else {
+                    b.is_ascii_alphanumeric()
+                }
+            })
+    })
+}
+
+fn is_base64(v: &str) -> bool {
+    let chars: Vec<u8> = v.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let padding = chars.iter().rev().take_while(|&&b| b == b'=').count();
+    chars.len().is_multiple_of(4)
+        && padding <= 2
+        && chars[..chars.len() - padding]
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+/// Two digits in `min..=max`, returning the rest of the string
+fn two_digits(s: &str, min: u32, max: u32) -> Option<&str> {
+    let digits = s.get(..2)?;
+    let n: u32 = digits
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| digits.parse().ok())??;
+    (min..=max).contains(&n).then(|| &s[2..])
+}
+
+/// Optional '-' and a year of at least four digits (no leading zero when
+/// longer), returning the rest
+fn year_part(s: &str) -> Option<&str> {
+    let unsigned = s.strip_prefix('-').unwrap_or(s);
+    let n = unsigned.bytes().take_while(u8::is_ascii_digit).count();
+    let valid = n >= 4 && !(n > 4 && unsigned.starts_with('0')) && &unsigned[..n] != "0000";
+    valid.then(|| &unsigned[n..])
+}
+
+fn month_day(s: &str) -> Option<&str> {
+    let rest = two_digits(s, 1, 12)?.strip_prefix('-')?;
+    two_digits(rest, 1, 31)
+}
+
+/// `YYYY-MM-DD`, returning the rest
+fn date_part(s: &str) -> Option<&str> {
+    month_day(year_part(s)?.strip_prefix('-')?)
+}
+
+/// `hh:mm:ss` with optional fraction, returning the rest
+fn time_part(s: &str) -> Option<&str> {
+    let rest = two_digits(s, 0, 24)?.strip_prefix(':')?;
+    let rest = two_digits(rest, 0, 59)?.strip_prefix(':')?;
+    let rest = two_digits(rest, 0, 59)?;
+    rest.strip_prefix('.').map_or(Some(rest), |fraction| {
+        let n = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        (n > 0).then(|| &fraction[n..])
+    })
+}
+
+fn is_timezone(s: &str) -> bool {
+    match s {
+        "" | "Z" => true,
+        _ => {
+            s.strip_prefix(['+', '-'])
+                .and_then(|rest| two_digits(rest, 0, 14))
+                .and_then(|rest| rest.strip_prefix(':'))
+                .and_then(|rest| two_digits(rest, 0, 59))
+                == Some("")
+        }
+    }
+}
+
+/// `-?PnYnMnDTnHnMnS` with at least one component, and a component after `T`
+fn is_duration(v: &str) -> bool {
+    let Some(body) = v.strip_prefix('-').unwrap_or(v).strip_prefix('P') else {
+        return false;
+    };
+    let (date, time) = body
+        .split_once('T')
+        .map_or((body, None), |(d, t)| (d, Some(t)));
+    let date_count = duration_components(date, "YMD");
+    let time_count = time.map_or(Some(0), |t| {
+        duration_components(t, "HMS").filter(|&n| n > 0)
+    });
+    matches!((date_count, time_count), (Some(d), Some(t)) if d + t > 0)
+}
+
+/// Number of `n<designator>` components in order, or `None` when malformed.
+/// Only seconds may have a fraction.
+fn duration_components(s: &str, designators: &str) -> Option<usize> {
+    let mut rest = s;
+    let mut allowed = designators;
+    let mut count = 0;
+    while !rest.is_empty() {
+        let n = rest
+            .bytes()
+            .take_while(|b| b.is_ascii_digit() || *b == b'.')
+            .count();
+        let number = &rest[..n];
+        let designator = rest[n..].chars().next()?;
+        let position = allowed.find(designator)?;
+        if !is_unsigned_decimal(number) || (number.contains('.') && designator != 'S') {
+            return None;
+        }
+        allowed = &allowed[position + 1..];
+        rest = &rest[n + 1..];
+        count += 1;
+    }
+    Some(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::xml_validate::{validate_xml, SchemaKind, XmlValidationResult};
+    use super::*;
+
+    const ORDER_XSD: &str = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+    targetNamespace="urn:shop" xmlns="urn:shop" elementFormDefault="qualified">
+  <xs:element name="order" type="OrderType"/>
+  <xs:complexType name="OrderType">
+    <xs:sequence>
+      <xs:element name="item" type="ItemType" maxOccurs="unbounded"/>
+      <xs:element name="note" type="xs:string" minOccurs="0" nillable="true"/>
+    </xs:sequence>
+    <xs:attribute name="id" type="xs:ID" use="required"/>
+    <xs:attribute name="status" type="Status" default="new"/>
+  </xs:complexType>
+  <xs:complexType name="ItemType">
+    <xs:simpleContent>
+      <xs:extension base="Sku">
+        <xs:attribute name="qty" type="xs:positiveInteger" use="required"/>
+        <xs:attribute name="price">
+          <xs:simpleType>
+            <xs:restriction base="xs:decimal">
+              <xs:minExclusive value="0"/>
+              <xs:fractionDigits value="2"/>
+            </xs:restriction>
+          </xs:simpleType>
+        </xs:attribute>
+      </xs:extension>
+    </xs:simpleContent>
+  </xs:complexType>
+  <xs:simpleType name="Sku">
+    <xs:restriction base="xs:token">
+      <xs:pattern value="[A-Z]{3}-\d+"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="Status">
+    <xs:restriction base="xs:string">
+      <xs:enumeration value="new"/>
+      <xs:enumeration value="shipped"/>
+    </xs:restriction>
+  </xs:simpleType>
+</xs:schema>"#;
+
+    fn validate(xml: &str, xsd: &str) -> XmlValidationResult {
+        validate_xml(xml, Some(xsd), SchemaKind::Xsd)
+    }
+
+    fn messages(result: &XmlValidationResult) -> Vec<&str> {
+        result.errors.iter().map(|e| e.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let xml = r#"<order xmlns="urn:shop" id="o1" status="shipped">
+  <item qty="2" price="9.99"> ABC-12 </item>
+  <item qty="1">XYZ-3</item>
+  <note xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true"/>
+</order>"#;
+        let result = validate(xml, ORDER_XSD);
+        assert!(
+            result.valid,
+            "{:?} {:?}",
+            result.errors, result.schema_errors
+        );
+    }
+
+    #[test]
+    fn test_content_and_value_errors() {
+        let xml = r#"<order xmlns="urn:shop" status="lost">
+  <note>first</note>
+  <item qty="0" price="1.234">abc-1</item>
+</order>"#;
+        let result = validate(xml, ORDER_XSD);
+        assert!(
+            result.schema_errors.is_empty(),
+            "{:?}",
+            result.schema_errors
+        );
+        assert_eq!(
+            messages(&result),
+            [
+                "Invalid value of attribute 'status': 'lost' is not one of: new, shipped",
+                "Element <order> is missing required attribute 'id'",
+                "Element <note> is not allowed here; expected <item>",
+                "Invalid value of attribute 'qty': '0' is not a valid xs:positiveInteger",
+                "Invalid value of attribute 'price': '1.234' has more than 2 fraction digits",
+                "Invalid content of element <item>: 'abc-1' does not match the pattern '[A-Z]{3}-\\d+'",
+            ]
+        );
+        let item = &result.errors[3];
+        assert_eq!(item.path.as_deref(), Some("$.order.item[@qty]"));
+        let range = item.range.as_ref().unwrap();
+        assert_eq!(&xml[range.start.offset..range.end.offset], r#"qty="0""#);
+    }
+
+    #[test]
+    fn test_namespace_mismatch() {
+        let result = validate(
+            r#"<order id="o1"><item qty="1">ABC-1</item></order>"#,
+            ORDER_XSD,
+        );
+        assert_eq!(
+            messages(&result),
+            ["No global element declaration matches <order>"]
+        );
+    }
+
+    #[test]
+    fn test_complex_content_extension_and_groups() {
+        let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="person" type="Employee"/>
+  <xs:group name="names">
+    <xs:sequence><xs:element name="first"/><xs:element name="last"/></xs:sequence>
+  </xs:group>
+  <xs:complexType name="Person">
+    <xs:group ref="names"/>
+    <xs:attributeGroup ref="audit"/>
+  </xs:complexType>
+  <xs:attributeGroup name="audit"><xs:attribute name="updated" type="xs:date"/></xs:attributeGroup>
+  <xs:complexType name="Employee">
+    <xs:complexContent>
+      <xs:extension base="Person">
+        <xs:choice>
+          <xs:element name="salary" type="xs:int"/>
+          <xs:element name="rate" type="xs:double"/>
+        </xs:choice>
+        <xs:anyAttribute processContents="skip"/>
+      </xs:extension>
+    </xs:complexContent>
+  </xs:complexType>
+</xs:schema>"#;
+        let ok = r#"<person updated="2024-02-29" x="1"><first/><last/><rate>1.5E3</rate></person>"#;
+        let result = validate(ok, xsd);
+        assert!(
+            result.valid,
+            "{:?} {:?}",
+            result.errors, result.schema_errors
+        );
+
+        let bad = r#"<person updated="2024-13-01"><first/><salary>9999999999</salary></person>"#;
+        assert_eq!(
+            messages(&validate(bad, xsd)),
+            [
+                "Invalid value of attribute 'updated': '2024-13-01' is not a valid xs:date",
+                "Element <salary> is not allowed here; expected <last>",
+                "Invalid content of element <salary>: '9999999999' is not a valid xs:int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:import namespace="urn:other"/>
+  <xs:element name="a" type="Missing"/>
+</xs:schema>"#;
+        let result = validate("<a/>", xsd);
+        assert_eq!(result.schema_errors.len(), 2);
+        assert_eq!(
+            result.schema_errors[1].message,
+            "Type 'Missing' is not defined"
+        );
+        assert_eq!(
+            result.schema_errors[1].range.as_ref().unwrap().start.line,
+            3
+        );
+
+        let result = validate("<a/>", "<schema/>");
+        assert_eq!(
+            result.schema_errors[0].message,
+            "The schema root must be <xs:schema>"
+        );
+    }
+
+    #[test]
+    fn test_builtin_lexical_spaces() {
+        let cases = [
+            (Builtin::DateTime, "2024-01-31T23:59:60", false),
+            (Builtin::DateTime, "2024-01-31T23:59:59.5+09:00", true),
+            (Builtin::Duration, "P1Y2MT3H", true),
+            (Builtin::Duration, "PT", false),
+            (Builtin::Duration, "P1.5D", false),
+            (Builtin::GMonthDay, "--02-29", true),
+            (Builtin::Float, "-1.5e-3", true),
+            (Builtin::Float, "inf", false),
+            (Builtin::UnsignedByte, "256", false),
+            (Builtin::Base64Binary, "SGVsbG8=", true),
+            (Builtin::Language, "en-US", true),
+            (Builtin::QName, "a:b:c", false),
+        ];
+        for (builtin, value, valid) in cases {
+            assert_eq!(builtin.accepts(value), valid, "{builtin:?} {value}");
+        }
+    }
+
+    #[test]
+    fn test_pattern_translation() {
+        assert_eq!(
+            translate_pattern(r"\i\c*").unwrap(),
+            r"[_:A-Za-z][\-._:A-Za-z0-9]*"
+        );
+        assert_eq!(translate_pattern("a^b$").unwrap(), r"a\^b\$");
+        assert!(translate_pattern("[a-z-[aeiou]]").is_none());
+    }
+}
@@ -1,10 +1,31 @@
 //! YAML AST parser with position tracking
 
+use super::parallel::{self, ItemSpan, PARALLEL_THRESHOLD};
 use super::{AstNode, AstNodeType, AstParseError, AstParseResult, AstPosition, AstRange};
+use tokio_util::sync::CancellationToken;
 use yaml_rust2::{Yaml, YamlLoader};
 
-/// Parse YAML text to AST with position information
-pub fn parse(text: &str) -> AstParseResult {
+/// Parse YAML text to AST with position information. A tree comes with
+/// warnings for indentation the parser accepted but that holds tabs.
+/// Once `cancel` is cancelled the parse stops at its next stage with an
+/// empty result the caller discards.
+pub fn parse(text: &str, cancel: Option<&CancellationToken>) -> AstParseResult {
+    let mut result = parse_document(text, cancel);
+    if result.ast.is_some() && !cancel.is_some_and(CancellationToken::is_cancelled) {
+        result.errors.extend(tab_indentation_warnings(text));
+    }
+    result
+}
+
+fn parse_document(text: &str, cancel: Option<&CancellationToken>) -> AstParseResult {
+    if text.len() >= PARALLEL_THRESHOLD {
+        if let Some(ast) = parse_sequence_parallel(text, cancel) {
+            return AstParseResult::success(ast);
+        }
+    }
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return AstParseResult::failure(Vec::new());
+    }
     // Use YamlLoader to parse with markers
     match YamlLoader::load_from_str(text) {
         Ok(docs) => {
@@ -24,6 +45,68 @@ pub fn parse(text: &str) -> AstParseResult {
     }
 }
 
+/// Top-level block sequence parsed one item per task on the rayon pool,
+/// each item's lines loaded as a one-item sequence of their own. `None`
+/// when the document has another shape or an item fails to load.
+fn parse_sequence_parallel(text: &str, cancel: Option<&CancellationToken>) -> Option<AstNode> {
+    let spans = split_sequence(text)?;
+    let children = parallel::build_items(&spans, cancel, |index, span| {
+        let fragment = &text[span.start.offset..span.end];
+        let docs = YamlLoader::load_from_str(fragment).ok()?;
+        let [Yaml::Array(items)] = docs.as_slice() else {
+            return None;
+        };
+        let [item] = items.as_slice() else {
+            return None;
+        };
+        let mut node = yaml_to_ast(fragment, item, &format!("$[{index}]"), 1);
+        parallel::shift_node(&mut node, span.start);
+        Some(node)
+    })?;
+
+    let range = calculate_range(text, &children, 0);
+    let label = format!("[] ({} items)", children.len());
+    Some(AstNode::new(AstNodeType::Array, "$".to_string(), label, range).with_children(children))
+}
+
+/// Spans of the items of a top-level block sequence: each runs from its
+/// unindented `- ` line to the next one. `None` for any other unindented
+/// content (keys, document markers, directives), for fewer than two items,
+/// and for documents with anchors or aliases, which can refer across items.
+fn split_sequence(text: &str) -> Option<Vec<ItemSpan>> {
+    let mut starts: Vec<AstPosition> = Vec::new();
+    let mut offset = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let content = line.trim_end();
+        let references = content
+            .split_whitespace()
+            .any(|token| token.starts_with(['&', '*']));
+        if references {
+            return None;
+        }
+        if content == "-" || content.starts_with("- ") {
+            starts.push(AstPosition::new(index + 1, 1, offset));
+        } else if content.is_empty() || content.trim_start().starts_with('#') {
+            // Blank and comment lines belong to the item before them.
+        } else if starts.is_empty() || !content.starts_with(' ') {
+            return None;
+        }
+        offset += line.len();
+    }
+    if starts.len() < 2 {
+        return None;
+    }
+
+    let ends = starts.iter().skip(1).map(|start| start.offset);
+    Some(
+        starts
+            .iter()
+            .zip(ends.chain([text.len()]))
+            .map(|(&start, end)| ItemSpan { start, end })
+            .collect(),
+    )
+}
+
 fn create_empty_root(text: &str) -> AstNode {
     let range = AstRange::new(
         AstPosition::new(1, 1, 0),
@@ -56,8 +139,10 @@ fn yaml_to_ast(text: &str, yaml: &Yaml, path: &str, start_line: usize) -> AstNod
                 let key_line = find_key_line(text, &key_str, start_line);
                 let child_ast = yaml_to_ast(text, value, &child_path, key_line);
 
-                let prop_range =
-                    AstRange::new(AstPosition::new(key_line, 1, 0), child_ast.range.end);
+                let prop_range = AstRange::new(
+                    AstPosition::new(key_line, 1, line_start_offset(text, key_line)),
+                    child_ast.range.end,
+                );
 
                 let mut prop_node = AstNode::new(
                     AstNodeType::Property,
@@ -192,6 +277,63 @@ fn find_key_line(text: &str, key: &str, start_line: usize) -> usize {
     start_line.max(1)
 }
 
+/// Warnings for lines whose indentation mixes in tabs. YAML indents with
+/// spaces only; a tab after them is read as separation, so the line may
+/// not nest where it looks like it does. Block scalar content is skipped,
+/// as tabs there are part of the value.
+fn tab_indentation_warnings(text: &str) -> Vec<AstParseError> {
+    let mut warnings = Vec::new();
+    let mut offset = 0;
+    // Indentation of the line that opened the current block scalar
+    let mut block_scalar: Option<usize> = None;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_start_matches([' ', '\t']);
+        let trimmed = content.trim_end();
+        let indent = line.len() - content.len();
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        if let Some(parent) = block_scalar {
+            if trimmed.is_empty() || spaces > parent {
+                continue;
+            }
+            block_scalar = None;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if line[..indent].contains('\t') {
+            warnings.push(
+                AstParseError::warning("Tab in indentation; YAML indents with spaces only")
+                    .with_range(AstRange::from_offset(text, start, start + indent)),
+            );
+        }
+        if opens_block_scalar(trimmed) {
+            block_scalar = Some(spaces);
+        }
+    }
+    warnings
+}
+
+/// Whether `line` ends in a `|` or `>` block scalar header, with its
+/// chomping and indentation indicators
+fn opens_block_scalar(line: &str) -> bool {
+    let line = line.split(" #").next().unwrap_or(line).trim_end();
+    let header = line.trim_end_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+    let Some(before) = header.strip_suffix(['|', '>']) else {
+        return false;
+    };
+    before.is_empty() || before.ends_with([' ', '\t'])
+}
+
+/// Offset of the start of `line` (1-indexed)
+fn line_start_offset(text: &str, line: usize) -> usize {
+    text.split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum()
+}
+
 fn estimate_value_range(text: &str, line: usize) -> AstRange {
     let lines: Vec<&str> = text.lines().collect();
     let line_idx = (line.saturating_sub(1)).min(lines.len().saturating_sub(1));
@@ -215,7 +357,7 @@ fn calculate_range(text: &str, children: &[AstNode], start_line: usize) -> AstRa
         return estimate_value_range(text, start_line);
     }
 
-    let start = AstPosition::new(start_line, 1, 0);
+    let start = AstPosition::new(start_line, 1, line_start_offset(text, start_line));
     let end = children.last().map_or(start, |c| c.range.end);
 
     AstRange::new(start, end)
@@ -223,12 +365,13 @@ fn calculate_range(text: &str, children: &[AstNode], start_line: usize) -> AstRa
 
 #[cfg(test)]
 mod tests {
+    use super::super::AstSeverity;
     use super::*;
 
     #[test]
     fn test_parse_simple_yaml() {
         let yaml = "name: John\nage: 30";
-        let result = parse(yaml);
+        let result = parse(yaml, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -240,7 +383,7 @@ mod tests {
     #[test]
     fn test_parse_nested_yaml() {
         let yaml = "user:\n  name: John\n  age: 30";
-        let result = parse(yaml);
+        let result = parse(yaml, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -251,7 +394,7 @@ mod tests {
     #[test]
     fn test_parse_array() {
         let yaml = "- item1\n- item2\n- item3";
-        let result = parse(yaml);
+        let result = parse(yaml, None);
 
         assert!(result.ast.is_some());
         let ast = result.ast.unwrap();
@@ -259,10 +402,65 @@ mod tests {
         assert_eq!(ast.children.as_ref().unwrap().len(), 3);
     }
 
+    /// Paths, labels, and values of `node` and its descendants
+    fn outline(node: &AstNode) -> Vec<(String, String, Option<serde_json::Value>)> {
+        let mut entries = vec![(node.path.clone(), node.label.clone(), node.value.clone())];
+        for child in node.children.iter().flatten() {
+            entries.extend(outline(child));
+        }
+        entries
+    }
+
+    #[test]
+    fn test_parallel_sequence_matches_sequential_parse() {
+        let yaml = "# export\n- id: 1\n  tags: [a, b]\n\n- plain\n- nested:\n    - x\n  # note\n  note: |\n    text\n-\n";
+        let parallel = parse_sequence_parallel(yaml, None).unwrap();
+        let sequential = {
+            let docs = YamlLoader::load_from_str(yaml).unwrap();
+            yaml_to_ast(yaml, &docs[0], "$", 0)
+        };
+        assert_eq!(outline(&parallel), outline(&sequential));
+
+        let items = parallel.children.as_ref().unwrap();
+        let lines: Vec<usize> = items.iter().map(|item| item.range.start.line).collect();
+        assert_eq!(lines, [2, 5, 6, 11]);
+        assert_eq!(items[1].range.start.offset, yaml.find("- plain").unwrap());
+        let tags = &items[0].children.as_ref().unwrap()[1];
+        assert_eq!(tags.range.start.line, 3);
+    }
+
+    #[test]
+    fn test_parallel_sequence_falls_back() {
+        assert!(split_sequence("a: 1\nb: 2\n").is_none());
+        assert!(split_sequence("- 1\n").is_none());
+        assert!(split_sequence("- &base {a: 1}\n- *base\n").is_none());
+        assert!(split_sequence("- 1\n---\n- 2\n").is_none());
+        assert!(split_sequence("  - 1\n  - 2\n").is_none());
+        assert!(parse_sequence_parallel("- [1,\n2]\n- 3\n", None).is_none());
+    }
+
+    #[test]
+    fn test_tab_indentation_warnings() {
+        let yaml = "a:\n  \tb: 1\ntext: |\n  \tkept\nc: 2\n";
+        let result = parse(yaml, None);
+        assert!(result.ast.is_some());
+        assert!(!result.has_errors());
+        assert_eq!(result.errors.len(), 1);
+        let warning = &result.errors[0];
+        assert_eq!(warning.severity, AstSeverity::Warning);
+        let range = warning.range.unwrap();
+        assert_eq!(
+            (range.start.line, range.start.offset, range.end.offset),
+            (2, 3, 6)
+        );
+
+        assert!(parse("a:\n  b: 1\n", None).errors.is_empty());
+    }
+
     #[test]
     fn test_parse_error() {
         let yaml = "key: [\nunbalanced";
-        let result = parse(yaml);
+        let result = parse(yaml, None);
 
         assert!(result.ast.is_none() || !result.errors.is_empty());
     }
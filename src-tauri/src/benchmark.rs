@@ -0,0 +1,464 @@
+//! Built-in benchmark harness
+//!
+//! Times representative operations on the current machine so users and CI
+//! can spot performance regressions on real hardware:
+//!
+//! | Tool         | `payload_size` means              | Default    |
+//! |--------------|-----------------------------------|------------|
+//! | `json-parse` | generated document size in bytes  | 1 MiB      |
+//! | `sql-parse`  | generated script size in bytes    | 256 KiB    |
+//! | `bcrypt`     | cost factor                       | 10         |
+//! | `port-scan`  | ports 1..=N probed on `127.0.0.1` | 1000       |
+//!
+//! Parsers run through the same AST entry point the editor uses. `BCrypt`
+//! runs in-process rather than through the worker so process start-up
+//! does not dominate the timing.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{parse_to_ast, AstLanguage};
+use crate::generators::bcrypt::{MAX_COST, MIN_COST};
+use crate::network::scanner::{run_scan, ScanState};
+use crate::network::types::{PortPreset, ScanMode, ScanProgress, ScanProgressSink, ScanRequest};
+
+/// Largest generated parser payload
+const MAX_PAYLOAD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Upper bound for `iterations`
+const MAX_ITERATIONS: u32 = 100;
+
+/// Concurrency used by the port scan benchmark
+const SCAN_CONCURRENCY: u32 = 500;
+
+/// Per-port connect timeout for the port scan benchmark
+const SCAN_TIMEOUT_MS: u32 = 250;
+
+/// Operation to benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BenchmarkTool {
+    /// Parse a generated JSON document to an AST.
+    JsonParse,
+    /// Parse a generated SQL script to an AST.
+    SqlParse,
+    /// Hash a password with `BCrypt`.
+    Bcrypt,
+    /// TCP connect scan against localhost.
+    PortScan,
+    /// Every benchmark above with its default size.
+    All,
+}
+
+impl BenchmarkTool {
+    const ALL: [Self; 4] = [
+        Self::JsonParse,
+        Self::SqlParse,
+        Self::Bcrypt,
+        Self::PortScan,
+    ];
+
+    const fn default_size(self) -> u64 {
+        match self {
+            Self::JsonParse => 1024 * 1024,
+            Self::SqlParse => 256 * 1024,
+            Self::Bcrypt => 10,
+            Self::PortScan | Self::All => 1000,
+        }
+    }
+
+    const fn default_iterations(self) -> u32 {
+        match self {
+            Self::JsonParse | Self::SqlParse => 5,
+            Self::Bcrypt => 3,
+            Self::PortScan | Self::All => 1,
+        }
+    }
+}
+
+/// Timing of one benchmark.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    /// Benchmark that ran.
+    pub tool: BenchmarkTool,
+    /// Effective payload size (see the module table for its unit).
+    pub payload_size: u64,
+    /// Timed iterations (a warm-up run is not counted).
+    pub iterations: u32,
+    /// Fastest iteration in milliseconds.
+    pub min_ms: f64,
+    /// Median iteration in milliseconds.
+    pub median_ms: f64,
+    /// Mean iteration in milliseconds.
+    pub mean_ms: f64,
+    /// Slowest iteration in milliseconds.
+    pub max_ms: f64,
+    /// Work per second based on the mean.
+    pub throughput: f64,
+    /// Unit of `throughput` (`MiB/s`, `hashes/s`, `ports/s`).
+    pub throughput_unit: &'static str,
+    /// Extra context, such as open ports found by the scan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Why the benchmark could not complete.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Benchmark run with the host it ran on.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    /// Application version.
+    pub app_version: &'static str,
+    /// Operating system.
+    pub os: &'static str,
+    /// CPU architecture.
+    pub arch: &'static str,
+    /// Logical CPUs available to the process.
+    pub cpu_count: usize,
+    /// Start time in milliseconds since the Unix epoch.
+    pub started_at: i64,
+    /// Wall time of the whole run in milliseconds.
+    pub total_ms: f64,
+    /// One entry per benchmark.
+    pub results: Vec<BenchmarkResult>,
+}
+
+/// Scan progress sink that drops every event.
+struct NullSink;
+
+impl ScanProgressSink for NullSink {
+    fn emit(&self, _progress: ScanProgress) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+}
+
+fn to_f64(n: u64) -> f64 {
+    f64::from(u32::try_from(n).unwrap_or(u32::MAX))
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Generate a JSON document of roughly `size` bytes.
+fn sample_json(size: u64) -> String {
+    let target = usize::try_from(size).unwrap_or(usize::MAX);
+    let mut out = String::with_capacity(target + 256);
+    out.push('[');
+    let mut i = 0u64;
+    while out.len() < target {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!(
+            r#"{{"id":{i},"name":"item-{i}","active":{},"score":{}.5,"tags":["a","b"],"meta":{{"owner":"user{}","note":null}}}}"#,
+            i.is_multiple_of(2),
+            i % 100,
+            i % 17
+        ));
+        i += 1;
+    }
+    out.push(']');
+    out
+}
+
+/// Generate a SQL script of roughly `size` bytes.
+fn sample_sql(size: u64) -> String {
+    let target = usize::try_from(size).unwrap_or(usize::MAX);
+    let mut out = String::with_capacity(target + 256);
+    let mut i = 0u64;
+    while out.len() < target {
+        if i.is_multiple_of(2) {
+            out.push_str(&format!(
+                "SELECT u.id, u.name, COUNT(o.id) AS orders FROM users u \
+                 LEFT JOIN orders o ON o.user_id = u.id WHERE u.id > {i} \
+                 GROUP BY u.id, u.name ORDER BY orders DESC LIMIT 10;\n"
+            ));
+        } else {
+            out.push_str(&format!(
+                "INSERT INTO events (id, kind, payload) VALUES ({i}, 'click', 'row {i}');\n"
+            ));
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Summarize `samples` (at least one) into a result.
+fn summarize(
+    tool: BenchmarkTool,
+    payload_size: u64,
+    samples: &mut [Duration],
+    work: f64,
+    throughput_unit: &'static str,
+) -> BenchmarkResult {
+    samples.sort_unstable();
+    let count = u32::try_from(samples.len()).unwrap_or(u32::MAX).max(1);
+    let total: Duration = samples.iter().sum();
+    let mean = total / count;
+    let mid = samples.len() / 2;
+    let median = if samples.len().is_multiple_of(2) && mid > 0 {
+        (samples[mid - 1] + samples[mid]) / 2
+    } else {
+        samples.get(mid).copied().unwrap_or_default()
+    };
+    let mean_secs = mean.as_secs_f64();
+    BenchmarkResult {
+        tool,
+        payload_size,
+        iterations: count,
+        min_ms: samples.first().copied().map_or(0.0, millis),
+        median_ms: millis(median),
+        mean_ms: millis(mean),
+        max_ms: samples.last().copied().map_or(0.0, millis),
+        throughput: if mean_secs > 0.0 {
+            work / mean_secs
+        } else {
+            0.0
+        },
+        throughput_unit,
+        detail: None,
+        error: None,
+    }
+}
+
+fn failed(tool: BenchmarkTool, payload_size: u64, error: String) -> BenchmarkResult {
+    BenchmarkResult {
+        tool,
+        payload_size,
+        iterations: 0,
+        min_ms: 0.0,
+        median_ms: 0.0,
+        mean_ms: 0.0,
+        max_ms: 0.0,
+        throughput: 0.0,
+        throughput_unit: "",
+        detail: None,
+        error: Some(error),
+    }
+}
+
+/// Time `op` once for warm-up and then `iterations` times.
+fn time_blocking(
+    iterations: u32,
+    mut op: impl FnMut() -> Result<(), String>,
+) -> Result<Vec<Duration>, String> {
+    op()?;
+    (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            op().map(|()| start.elapsed())
+        })
+        .collect()
+}
+
+fn bench_parser(tool: BenchmarkTool, size: u64, iterations: u32) -> BenchmarkResult {
+    if size == 0 || size > MAX_PAYLOAD_BYTES {
+        return failed(
+            tool,
+            size,
+            format!("Payload size must be between 1 and {MAX_PAYLOAD_BYTES} bytes"),
+        );
+    }
+    let (text, language) = if tool == BenchmarkTool::SqlParse {
+        (sample_sql(size), AstLanguage::Sql)
+    } else {
+        (sample_json(size), AstLanguage::Json)
+    };
+    let bytes = u64::try_from(text.len()).unwrap_or(u64::MAX);
+    let samples = time_blocking(iterations, || {
+        let result = parse_to_ast(&text, language);
+        result
+            .errors
+            .first()
+            .map_or(Ok(()), |e| Err(format!("Parse failed: {}", e.message)))
+    });
+    match samples {
+        Ok(mut samples) => summarize(
+            tool,
+            bytes,
+            &mut samples,
+            to_f64(bytes) / (1024.0 * 1024.0),
+            "MiB/s",
+        ),
+        Err(e) => failed(tool, bytes, e),
+    }
+}
+
+fn bench_bcrypt(cost: u64, iterations: u32) -> BenchmarkResult {
+    let tool = BenchmarkTool::Bcrypt;
+    let Some(cost) = u32::try_from(cost)
+        .ok()
+        .filter(|c| (MIN_COST..=MAX_COST).contains(c))
+    else {
+        return failed(
+            tool,
+            cost,
+            format!("Cost factor must be between {MIN_COST} and {MAX_COST}"),
+        );
+    };
+    // A warm-up hash at high cost would double the wall time for nothing.
+    let mut samples = Vec::new();
+    for _ in 0..iterations {
+        let start = Instant::now();
+        if let Err(e) = bcrypt::hash("kogu-benchmark", cost) {
+            return failed(tool, u64::from(cost), format!("BCrypt failed: {e}"));
+        }
+        samples.push(start.elapsed());
+    }
+    summarize(tool, u64::from(cost), &mut samples, 1.0, "hashes/s")
+}
+
+async fn bench_port_scan(ports: u64, iterations: u32) -> BenchmarkResult {
+    let tool = BenchmarkTool::PortScan;
+    let Some(last) = u16::try_from(ports).ok().filter(|p| *p > 0) else {
+        return failed(
+            tool,
+            ports,
+            "Port count must be between 1 and 65535".to_string(),
+        );
+    };
+    let mut samples = Vec::new();
+    let mut open_ports = 0;
+    for _ in 0..iterations {
+        let request = ScanRequest {
+            target: "127.0.0.1".to_string(),
+            mode: ScanMode::Custom,
+            port_preset: PortPreset::Custom,
+            port_range: Some(format!("1-{last}")),
+            concurrency: SCAN_CONCURRENCY,
+            timeout_ms: SCAN_TIMEOUT_MS,
+            resolution: None,
+        };
+        let (scan_state, _cancel_rx) = ScanState::new();
+        let start = Instant::now();
+        match run_scan(request, &NullSink, Arc::new(scan_state)).await {
+            Ok(results) => open_ports = results.total_open_ports,
+            Err(e) => return failed(tool, ports, e.to_string()),
+        }
+        samples.push(start.elapsed());
+    }
+    let mut result = summarize(tool, ports, &mut samples, to_f64(ports), "ports/s");
+    result.detail = Some(format!("{open_ports} open ports on 127.0.0.1"));
+    result
+}
+
+async fn bench_one(tool: BenchmarkTool, size: u64, iterations: u32) -> BenchmarkResult {
+    match tool {
+        BenchmarkTool::JsonParse | BenchmarkTool::SqlParse => {
+            tokio::task::spawn_blocking(move || bench_parser(tool, size, iterations))
+                .await
+                .unwrap_or_else(|e| failed(tool, size, format!("Benchmark task failed: {e}")))
+        }
+        BenchmarkTool::Bcrypt => {
+            tokio::task::spawn_blocking(move || bench_bcrypt(size, iterations))
+                .await
+                .unwrap_or_else(|e| failed(tool, size, format!("Benchmark task failed: {e}")))
+        }
+        BenchmarkTool::PortScan | BenchmarkTool::All => bench_port_scan(size, iterations).await,
+    }
+}
+
+/// Run `tool` (or every benchmark for [`BenchmarkTool::All`]) and report
+/// the timings. `payload_size` and `iterations` fall back to per-tool
+/// defaults; with `All`, `payload_size` is ignored.
+///
+/// # Errors
+///
+/// Returns an error string when `iterations` is out of range. Failures of
+/// individual benchmarks are reported in their result instead.
+pub async fn run(
+    tool: BenchmarkTool,
+    payload_size: Option<u64>,
+    iterations: Option<u32>,
+) -> Result<BenchmarkReport, String> {
+    if let Some(n) = iterations.filter(|n| *n == 0 || *n > MAX_ITERATIONS) {
+        return Err(format!(
+            "Iterations must be between 1 and {MAX_ITERATIONS}, got {n}"
+        ));
+    }
+    let started_at = now_ms();
+    let start = Instant::now();
+    let plan: Vec<(BenchmarkTool, u64)> = if tool == BenchmarkTool::All {
+        BenchmarkTool::ALL
+            .iter()
+            .map(|t| (*t, t.default_size()))
+            .collect()
+    } else {
+        vec![(tool, payload_size.unwrap_or_else(|| tool.default_size()))]
+    };
+
+    let mut results = Vec::with_capacity(plan.len());
+    for (tool, size) in plan {
+        let iterations = iterations.unwrap_or_else(|| tool.default_iterations());
+        results.push(bench_one(tool, size, iterations).await);
+    }
+
+    Ok(BenchmarkReport {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        cpu_count: std::thread::available_parallelism().map_or(1, std::num::NonZero::get),
+        started_at,
+        total_ms: millis(start.elapsed()),
+        results,
+    })
+}
+
+/// Time representative operations (JSON parse, SQL parse, `BCrypt`,
+/// localhost port scan) and return a report for regression tracking.
+///
+/// # Errors
+///
+/// Returns an error string when `iterations` is out of range.
+#[tauri::command]
+pub async fn run_benchmark(
+    tool: BenchmarkTool,
+    payload_size: Option<u64>,
+    iterations: Option<u32>,
+) -> Result<BenchmarkReport, String> {
+    run(tool, payload_size, iterations).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_payloads_reach_size_and_parse() {
+        let json = sample_json(4096);
+        assert!(json.len() >= 4096);
+        assert!(parse_to_ast(&json, AstLanguage::Json).errors.is_empty());
+        let sql = sample_sql(2048);
+        assert!(sql.len() >= 2048);
+        assert!(parse_to_ast(&sql, AstLanguage::Sql).errors.is_empty());
+
+        let result = bench_parser(BenchmarkTool::JsonParse, 2048, 2);
+        assert!(result.error.is_none());
+        assert_eq!(result.iterations, 2);
+        assert!(bench_parser(BenchmarkTool::SqlParse, 0, 1).error.is_some());
+    }
+
+    #[test]
+    fn summarizes_samples() {
+        let mut samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let result = summarize(BenchmarkTool::Bcrypt, 4, &mut samples, 1.0, "hashes/s");
+        assert!((result.min_ms - 1.0).abs() < f64::EPSILON);
+        assert!((result.median_ms - 2.5).abs() < f64::EPSILON);
+        assert!((result.mean_ms - 2.5).abs() < f64::EPSILON);
+        assert!((result.max_ms - 4.0).abs() < f64::EPSILON);
+        assert!((result.throughput - 400.0).abs() < 1e-9);
+        assert!(bench_bcrypt(3, 1).error.is_some());
+    }
+}
@@ -0,0 +1,11 @@
+//! Headless command-line interface for Kogu tools
+//!
+//! Thin entry point over [`kogu_lib::cli`], which shares the tool
+//! implementations with the desktop app. Run `kogu-cli help` for usage.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    kogu_lib::cli::run(&args)
+}
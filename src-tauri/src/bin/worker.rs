@@ -7,10 +7,15 @@
 //! - BCrypt hash generation and verification
 //! - SSH key generation
 //! - GPG key generation
+//! - Self-signed X.509 certificate and CSR generation
+//! - PGP message encryption, decryption, signing, and verification
 //!
 //! Communication protocol:
-//! - Input: JSON on stdin (single line)
-//! - Output: JSON on stdout (single line)
+//! - Input: one length-prefixed JSON frame on stdin (see `worker_protocol`),
+//!   or a single legacy JSON line
+//! - Output: JSON on stdout, framed the same way as the input
+//! - With `--serve`, the worker answers requests one after another until
+//!   stdin closes, so the app can keep it warm in a pool
 //!
 //! Input format:
 //! ```json
@@ -18,10 +23,21 @@
 //! { "type": "bcrypt", "operation": "verify", "password": "...", "hash": "..." }
 //! { "type": "ssh", "algorithm": "ed25519", "comment": "...", "passphrase": "..." }
 //! { "type": "gpg", "name": "...", "email": "...", "algorithm": "rsa4096", ... }
+//! { "type": "x509", "output": "certificate", "algorithm": "ecdsa_p256", "subject": {...}, ... }
+//! { "type": "pgp", "operation": "encrypt", "message": "...", "key": "-----BEGIN PGP ...", ... }
 //! ```
 
+#[path = "../pgp_message.rs"]
+mod pgp_message;
+#[path = "../worker_protocol.rs"]
+mod worker_protocol;
+#[path = "../x509_builder.rs"]
+mod x509_builder;
+
 use serde::{Deserialize, Serialize};
-use std::io::{self, BufRead, Write};
+use std::io::{self, Write};
+
+use worker_protocol::{read_message, write_frame, Message};
 
 // =============================================================================
 // Constants
@@ -43,6 +59,8 @@ enum Request {
     Bcrypt(BcryptRequest),
     Ssh(SshRequest),
     Gpg(GpgRequest),
+    X509(x509_builder::Params),
+    Pgp(pgp_message::Params),
 }
 
 /// BCrypt operation request
@@ -183,6 +201,26 @@ struct GpgKeyResult {
     method_used: String,
 }
 
+/// X.509 certificate or CSR result
+#[derive(Debug, Serialize)]
+struct X509Result {
+    success: bool,
+    algorithm: String,
+    pem: String,
+    der: String,
+    private_key: String,
+    fingerprint: String,
+}
+
+/// PGP message operation result
+#[derive(Debug, Serialize)]
+struct PgpMessageResult {
+    success: bool,
+    output: String,
+    valid: Option<bool>,
+    fingerprint: Option<String>,
+}
+
 /// Error response
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
@@ -578,6 +616,56 @@ fn format_fingerprint(fingerprint: &str) -> String {
         .join(" ")
 }
 
+// =============================================================================
+// X.509 Certificate Generation
+// =============================================================================
+
+fn handle_x509(params: &x509_builder::Params) -> String {
+    use base64::Engine as _;
+
+    let generated = match x509_builder::generate(params) {
+        Ok(generated) => generated,
+        Err(error) => {
+            return to_json(&ErrorResponse {
+                success: false,
+                error,
+            })
+        }
+    };
+    let label = match params.output {
+        x509_builder::Output::Certificate => "CERTIFICATE",
+        x509_builder::Output::Csr => "CERTIFICATE REQUEST",
+    };
+
+    to_json(&X509Result {
+        success: true,
+        algorithm: params.algorithm.display_name().to_string(),
+        pem: x509_builder::pem(label, &generated.der),
+        der: base64::engine::general_purpose::STANDARD.encode(&generated.der),
+        private_key: x509_builder::pem("PRIVATE KEY", &generated.private_key),
+        fingerprint: x509_builder::fingerprint(&generated.der),
+    })
+}
+
+// =============================================================================
+// PGP Messages
+// =============================================================================
+
+fn handle_pgp_message(params: &pgp_message::Params) -> String {
+    match pgp_message::process(params) {
+        Ok(processed) => to_json(&PgpMessageResult {
+            success: true,
+            output: processed.output,
+            valid: processed.valid,
+            fingerprint: processed.fingerprint.as_deref().map(format_fingerprint),
+        }),
+        Err(error) => to_json(&ErrorResponse {
+            success: false,
+            error,
+        }),
+    }
+}
+
 // =============================================================================
 // Utilities
 // =============================================================================
@@ -592,42 +680,58 @@ fn to_json<T: Serialize>(value: &T) -> String {
 // Main
 // =============================================================================
 
+/// Write `response` in the same framing the request arrived in.
+fn respond(framed: bool, response: &str) {
+    let mut stdout = io::stdout().lock();
+    let _ = if framed {
+        write_frame(&mut stdout, response.as_bytes())
+    } else {
+        writeln!(stdout, "{response}")
+    };
+}
+
 fn main() {
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
-
-    // Read single line from stdin
-    let mut input = String::new();
-    if stdin.lock().read_line(&mut input).is_err() {
-        let _ = writeln!(
-            stdout,
-            "{}",
-            to_json(&ErrorResponse {
-                success: false,
-                error: "Failed to read input".to_string(),
-            })
-        );
-        return;
+    // A pooled worker serves requests until the app closes its stdin
+    let serve = std::env::args().skip(1).any(|arg| arg == "--serve");
+    let mut stdin = io::stdin().lock();
+
+    loop {
+        // Read one request, framed or as a legacy single line
+        let message = match read_message(&mut stdin) {
+            Ok(message) => message,
+            Err(e) if serve && e.kind() == io::ErrorKind::UnexpectedEof => return,
+            Err(e) => {
+                respond(
+                    true,
+                    &to_json(&ErrorResponse {
+                        success: false,
+                        error: format!("Failed to read input: {e}"),
+                    }),
+                );
+                return;
+            }
+        };
+
+        respond(matches!(message, Message::Framed(_)), &handle(&message));
+        if !serve {
+            return;
+        }
     }
+}
 
-    // Parse request
-    let request: Request = match serde_json::from_str(&input) {
+/// Process one request and return its JSON response.
+fn handle(message: &Message) -> String {
+    let request: Request = match serde_json::from_slice(message.body()) {
         Ok(req) => req,
         Err(e) => {
-            let _ = writeln!(
-                stdout,
-                "{}",
-                to_json(&ErrorResponse {
-                    success: false,
-                    error: format!("Invalid request: {e}"),
-                })
-            );
-            return;
+            return to_json(&ErrorResponse {
+                success: false,
+                error: format!("Invalid request: {e}"),
+            })
         }
     };
 
-    // Process request and get response
-    let response = match request {
+    match request {
         Request::Bcrypt(BcryptRequest::Hash { password, cost }) => {
             handle_bcrypt_hash(&password, cost)
         }
@@ -636,8 +740,7 @@ fn main() {
         }
         Request::Ssh(req) => handle_ssh_keygen(req),
         Request::Gpg(req) => handle_gpg_keygen(req),
-    };
-
-    // Write response
-    let _ = writeln!(stdout, "{response}");
+        Request::X509(params) => handle_x509(&params),
+        Request::Pgp(params) => handle_pgp_message(&params),
+    }
 }
@@ -0,0 +1,447 @@
+//! Static catalog of tools and keyboard shortcuts
+//!
+//! Mirrors the page registry in `src/lib/services/pages.ts` and the
+//! shortcuts bound in `__root.tsx` / `tool-shell.tsx`, so backend features
+//! (global search, command registry) can refer to tools without a round
+//! trip through the frontend. Keep both sides in sync when adding a page.
+
+/// One navigable tool page.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolInfo {
+    /// Page identifier (e.g. `json-formatter`).
+    pub id: &'static str,
+    /// Display title.
+    pub title: &'static str,
+    /// Route path.
+    pub url: &'static str,
+    /// Alternative names users search for.
+    pub aliases: &'static [&'static str],
+}
+
+/// One keyboard shortcut. `Mod` is Cmd on macOS and Ctrl elsewhere.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyBinding {
+    /// Stable identifier.
+    pub id: &'static str,
+    /// Key combination in the frontend's hotkey notation.
+    pub keys: &'static str,
+    /// What the shortcut does.
+    pub description: &'static str,
+    /// Where the shortcut is active (`global` or `tool`).
+    pub scope: &'static str,
+}
+
+const fn tool(
+    id: &'static str,
+    title: &'static str,
+    url: &'static str,
+    aliases: &'static [&'static str],
+) -> ToolInfo {
+    ToolInfo {
+        id,
+        title,
+        url,
+        aliases,
+    }
+}
+
+/// Every tool page, in sidebar order.
+pub const TOOLS: &[ToolInfo] = &[
+    tool("dashboard", "Dashboard", "/", &["home"]),
+    tool(
+        "json-formatter",
+        "JSON Formatter",
+        "/json-formatter",
+        &["json", "prettify", "beautify"],
+    ),
+    tool(
+        "yaml-formatter",
+        "YAML Formatter",
+        "/yaml-formatter",
+        &["yaml", "yml"],
+    ),
+    tool(
+        "xml-formatter",
+        "XML Formatter",
+        "/xml-formatter",
+        &["xml", "xpath"],
+    ),
+    tool(
+        "sql-formatter",
+        "SQL Formatter",
+        "/sql-formatter",
+        &["sql", "query"],
+    ),
+    tool(
+        "base64-encoder",
+        "Base64 Encoder",
+        "/base64-encoder",
+        &["base64", "decode"],
+    ),
+    tool(
+        "url-encoder",
+        "URL Encoder",
+        "/url-encoder",
+        &["percent encoding", "urlencode"],
+    ),
+    tool(
+        "jwt-decoder",
+        "JWT Decoder",
+        "/jwt-decoder",
+        &["jwt", "token", "bearer"],
+    ),
+    tool(
+        "x509-decoder",
+        "X.509 Certificate Decoder",
+        "/x509-decoder",
+        &["certificate", "pem", "x509"],
+    ),
+    tool(
+        "escape-tool",
+        "Escape / Unescape",
+        "/escape-tool",
+        &["escape", "unescape", "html entities"],
+    ),
+    tool(
+        "rsa-tools",
+        "RSA Toolkit",
+        "/rsa-tools",
+        &["rsa", "keypair", "encrypt"],
+    ),
+    tool(
+        "string-compressor",
+        "String Compressor",
+        "/string-compressor",
+        &["gzip", "deflate", "brotli"],
+    ),
+    tool(
+        "uuid-generator",
+        "UUID Generator",
+        "/uuid-generator",
+        &["uuid", "guid"],
+    ),
+    tool(
+        "hash-generator",
+        "Hash Generator",
+        "/hash-generator",
+        &["sha256", "md5", "digest", "checksum"],
+    ),
+    tool(
+        "bcrypt-generator",
+        "BCrypt Generator",
+        "/bcrypt-generator",
+        &["bcrypt", "password hash"],
+    ),
+    tool(
+        "password-generator",
+        "Password Generator",
+        "/password-generator",
+        &["password", "passphrase"],
+    ),
+    tool(
+        "ssh-key-generator",
+        "SSH Key Generator",
+        "/ssh-key-generator",
+        &["ssh", "ed25519", "keygen"],
+    ),
+    tool(
+        "gpg-key-generator",
+        "GPG Key Generator",
+        "/gpg-key-generator",
+        &["gpg", "pgp", "openpgp"],
+    ),
+    tool(
+        "qr-code-generator",
+        "QR Code Generator",
+        "/qr-code-generator",
+        &["qr", "barcode"],
+    ),
+    tool(
+        "cron-expression-builder",
+        "Cron Expression Builder",
+        "/cron-expression-builder",
+        &["cron", "crontab", "schedule"],
+    ),
+    tool(
+        "date-timestamp-converter",
+        "Date / Timestamp Converter",
+        "/date-timestamp-converter",
+        &["epoch", "unix time", "timestamp"],
+    ),
+    tool(
+        "number-base-converter",
+        "Number Base Converter",
+        "/number-base-converter",
+        &["hex", "binary", "octal"],
+    ),
+    tool(
+        "image-converter",
+        "Image Converter",
+        "/image-converter",
+        &["png", "jpeg", "webp"],
+    ),
+    tool(
+        "lorem-ipsum",
+        "Lorem Ipsum",
+        "/lorem-ipsum",
+        &["placeholder text", "dummy text"],
+    ),
+    tool(
+        "curl-builder",
+        "cURL Builder",
+        "/curl-builder",
+        &["curl", "http command"],
+    ),
+    tool(
+        "semver-tools",
+        "SemVer Tools",
+        "/semver-tools",
+        &["semver", "version range"],
+    ),
+    tool(
+        "markdown-editor",
+        "Markdown Editor",
+        "/markdown-editor",
+        &["markdown", "md"],
+    ),
+    tool(
+        "diff-viewer",
+        "Diff Viewer",
+        "/diff-viewer",
+        &["diff", "compare text"],
+    ),
+    tool(
+        "string-case-converter",
+        "String Case Converter",
+        "/string-case-converter",
+        &["camelcase", "snake case", "kebab case"],
+    ),
+    tool(
+        "regex-tester",
+        "Regex Tester",
+        "/regex-tester",
+        &["regex", "regular expression"],
+    ),
+    tool(
+        "list-comparer",
+        "List Comparer",
+        "/list-comparer",
+        &["set difference", "intersection"],
+    ),
+    tool(
+        "cidr-calculator",
+        "CIDR Calculator",
+        "/cidr-calculator",
+        &["subnet", "netmask", "cidr"],
+    ),
+    tool(
+        "ip-converter",
+        "IP Address Converter",
+        "/ip-converter",
+        &["ipv4", "ipv6"],
+    ),
+    tool(
+        "mac-lookup",
+        "MAC Address Lookup",
+        "/mac-lookup",
+        &["oui", "vendor"],
+    ),
+    tool(
+        "network-interfaces",
+        "Network Interfaces",
+        "/network-interfaces",
+        &["ifconfig", "ipconfig", "adapters"],
+    ),
+    tool(
+        "network-scanner",
+        "Network Scanner",
+        "/network-scanner",
+        &["port scan", "nmap", "discovery"],
+    ),
+    tool(
+        "wifi-analyzer",
+        "Wi-Fi Analyzer",
+        "/wifi-analyzer",
+        &["wifi", "wireless", "ssid"],
+    ),
+    tool(
+        "mime-types",
+        "MIME Type Explorer",
+        "/mime-types",
+        &["mime", "content type"],
+    ),
+    tool(
+        "dns-lookup",
+        "DNS Lookup",
+        "/dns-lookup",
+        &["dig", "nslookup", "dns"],
+    ),
+    tool(
+        "http-status-codes",
+        "HTTP Status Codes",
+        "/http-status-codes",
+        &["status code", "404"],
+    ),
+    tool(
+        "rest-client",
+        "HTTP REST Client",
+        "/rest-client",
+        &["http", "api", "postman"],
+    ),
+    tool(
+        "tls-inspector",
+        "TLS Inspector",
+        "/tls-inspector",
+        &["ssl", "tls", "certificate chain"],
+    ),
+    tool(
+        "websocket-tester",
+        "WebSocket Tester",
+        "/websocket-tester",
+        &["websocket", "ws"],
+    ),
+    tool(
+        "webhook-receiver",
+        "Webhook Receiver",
+        "/webhook-receiver",
+        &["webhook", "callback", "mock server"],
+    ),
+    tool(
+        "file-inspector",
+        "File Inspector",
+        "/file-inspector",
+        &["file type", "magic bytes"],
+    ),
+    tool(
+        "folder-tree-visualizer",
+        "Folder Tree",
+        "/folder-tree-visualizer",
+        &["tree", "directory"],
+    ),
+    tool(
+        "hex-editor",
+        "Hex Editor",
+        "/hex-editor",
+        &["hexdump", "binary editor"],
+    ),
+    tool(
+        "archive-inspector",
+        "Archive Inspector",
+        "/archive-inspector",
+        &["zip", "tar", "unzip"],
+    ),
+    tool(
+        "path-tool",
+        "Path Tool",
+        "/path-tool",
+        &["file path", "normalize path"],
+    ),
+    tool(
+        "csv-tool",
+        "CSV / TSV Tool",
+        "/csv-tool",
+        &["csv", "tsv", "spreadsheet"],
+    ),
+    tool(
+        "encoding-converter",
+        "Encoding Converter",
+        "/encoding-converter",
+        &["charset", "utf-8", "shift_jis"],
+    ),
+    tool(
+        "test-data-generator",
+        "Test Data Generator",
+        "/test-data-generator",
+        &["fake data", "mock data"],
+    ),
+    tool(
+        "file-watch",
+        "File Watch",
+        "/file-watch",
+        &["tail", "watch file"],
+    ),
+    tool(
+        "duplicate-finder",
+        "Duplicate Finder",
+        "/duplicate-finder",
+        &["duplicates", "dedupe"],
+    ),
+    tool(
+        "drive-info",
+        "Drive Info",
+        "/drive-info",
+        &["disk usage", "volumes"],
+    ),
+    tool(
+        "settings",
+        "Settings",
+        "/settings",
+        &["preferences", "options"],
+    ),
+];
+
+/// Keyboard shortcuts registered by the frontend.
+pub const KEYBINDINGS: &[KeyBinding] = &[
+    KeyBinding {
+        id: "command-palette",
+        keys: "Mod+K",
+        description: "Open the command palette",
+        scope: "global",
+    },
+    KeyBinding {
+        id: "navigate-back",
+        keys: "Mod+[",
+        description: "Go back",
+        scope: "global",
+    },
+    KeyBinding {
+        id: "navigate-forward",
+        keys: "Mod+]",
+        description: "Go forward",
+        scope: "global",
+    },
+    KeyBinding {
+        id: "shortcuts-help",
+        keys: "?",
+        description: "Show keyboard shortcuts",
+        scope: "global",
+    },
+    KeyBinding {
+        id: "toggle-rail",
+        keys: "Mod+,",
+        description: "Toggle the options rail",
+        scope: "tool",
+    },
+    KeyBinding {
+        id: "run-primary",
+        keys: "Mod+Enter",
+        description: "Run the primary action",
+        scope: "tool",
+    },
+    KeyBinding {
+        id: "switch-tab",
+        keys: "Mod+1..9",
+        description: "Switch to tab by position",
+        scope: "tool",
+    },
+];
+
+/// Look up a tool by its page identifier.
+pub fn find_tool(id: &str) -> Option<&'static ToolInfo> {
+    TOOLS.iter().find(|t| t.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn ids_and_urls_are_unique() {
+        let ids: HashSet<_> = TOOLS.iter().map(|t| t.id).collect();
+        let urls: HashSet<_> = TOOLS.iter().map(|t| t.url).collect();
+        assert_eq!(ids.len(), TOOLS.len());
+        assert_eq!(urls.len(), TOOLS.len());
+        assert_eq!(find_tool("dns-lookup").map(|t| t.url), Some("/dns-lookup"));
+    }
+}
@@ -0,0 +1,518 @@
+//! CRC and Adler-32 checksum Tauri commands.
+//!
+//! Sits beside [`crate::hash_text`] in the hash tool's command set, for
+//! the non-cryptographic checksums firmware images and wire protocols
+//! carry. Every CRC is computed by one table-driven engine parameterised
+//! the Rocksoft way (width, polynomial, init, reflect in/out, xorout), so
+//! the presets are just catalogue entries and a custom CRC is one more
+//! set of parameters.
+//!
+//! Input is text, hex, or base64 so a frame copied from a logic analyser
+//! or a packet dump can be checked byte-exact.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Rocksoft model parameters of a CRC. The 64-bit values travel as hex
+/// strings (`"0x42F0E1EBA9EA3693"`) since a JavaScript number cannot hold
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrcParams {
+    /// Register width in bits, 8 to 64.
+    pub width: u32,
+    /// Generator polynomial, normal (MSB-first) form without the top bit.
+    #[serde(with = "hex_u64")]
+    pub poly: u64,
+    #[serde(with = "hex_u64")]
+    pub init: u64,
+    /// Reflect each input byte.
+    pub refin: bool,
+    /// Reflect the final register before `xorout`.
+    pub refout: bool,
+    #[serde(with = "hex_u64")]
+    pub xorout: u64,
+}
+
+/// Named checksum from the catalogue.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumPreset {
+    /// Identifier passed back in `presets`, e.g. `crc-32c`.
+    pub id: &'static str,
+    /// Catalogue name, e.g. "CRC-32/ISCSI".
+    pub name: &'static str,
+    /// CRC parameters; `None` for Adler-32.
+    pub params: Option<CrcParams>,
+    /// Checksum of the ASCII string "123456789".
+    #[serde(with = "hex_u64")]
+    pub check: u64,
+}
+
+/// How the input string is turned into bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputEncoding {
+    #[default]
+    Utf8,
+    Hex,
+    Base64,
+}
+
+/// One computed checksum.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumValue {
+    /// Preset id, or `custom`.
+    pub id: String,
+    pub width: u32,
+    /// Upper-case hex, zero-padded to the width.
+    pub hex: String,
+    /// Decimal string; a 64-bit value does not fit a JavaScript number.
+    pub decimal: String,
+}
+
+/// Checksums of a single payload.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumResult {
+    pub values: Vec<ChecksumValue>,
+    /// Byte length of the decoded input.
+    pub size_bytes: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn crc(
+    id: &'static str,
+    name: &'static str,
+    width: u32,
+    poly: u64,
+    init: u64,
+    reflect: bool,
+    xorout: u64,
+    check: u64,
+) -> ChecksumPreset {
+    ChecksumPreset {
+        id,
+        name,
+        params: Some(CrcParams {
+            width,
+            poly,
+            init,
+            refin: reflect,
+            refout: reflect,
+            xorout,
+        }),
+        check,
+    }
+}
+
+/// Supported presets, with parameters and check values from Greg Cook's
+/// catalogue of parametrised CRC algorithms.
+pub const PRESETS: &[ChecksumPreset] = &[
+    crc("crc-8", "CRC-8/SMBUS", 8, 0x07, 0, false, 0, 0xF4),
+    crc("crc-8-maxim", "CRC-8/MAXIM-DOW", 8, 0x31, 0, true, 0, 0xA1),
+    crc("crc-16-arc", "CRC-16/ARC", 16, 0x8005, 0, true, 0, 0xBB3D),
+    crc(
+        "crc-16-ccitt-false",
+        "CRC-16/IBM-3740",
+        16,
+        0x1021,
+        0xFFFF,
+        false,
+        0,
+        0x29B1,
+    ),
+    crc(
+        "crc-16-xmodem",
+        "CRC-16/XMODEM",
+        16,
+        0x1021,
+        0,
+        false,
+        0,
+        0x31C3,
+    ),
+    crc(
+        "crc-16-kermit",
+        "CRC-16/KERMIT",
+        16,
+        0x1021,
+        0,
+        true,
+        0,
+        0x2189,
+    ),
+    crc(
+        "crc-16-modbus",
+        "CRC-16/MODBUS",
+        16,
+        0x8005,
+        0xFFFF,
+        true,
+        0,
+        0x4B37,
+    ),
+    crc(
+        "crc-32",
+        "CRC-32/ISO-HDLC",
+        32,
+        0x04C1_1DB7,
+        0xFFFF_FFFF,
+        true,
+        0xFFFF_FFFF,
+        0xCBF4_3926,
+    ),
+    crc(
+        "crc-32-bzip2",
+        "CRC-32/BZIP2",
+        32,
+        0x04C1_1DB7,
+        0xFFFF_FFFF,
+        false,
+        0xFFFF_FFFF,
+        0xFC89_1918,
+    ),
+    crc(
+        "crc-32-mpeg2",
+        "CRC-32/MPEG-2",
+        32,
+        0x04C1_1DB7,
+        0xFFFF_FFFF,
+        false,
+        0,
+        0x0376_E6E7,
+    ),
+    crc(
+        "crc-32c",
+        "CRC-32/ISCSI",
+        32,
+        0x1EDC_6F41,
+        0xFFFF_FFFF,
+        true,
+        0xFFFF_FFFF,
+        0xE306_9283,
+    ),
+    crc(
+        "crc-64-ecma",
+        "CRC-64/ECMA-182",
+        64,
+        0x42F0_E1EB_A9EA_3693,
+        0,
+        false,
+        0,
+        0x6C40_DF5F_0B49_7347,
+    ),
+    crc(
+        "crc-64-xz",
+        "CRC-64/XZ",
+        64,
+        0x42F0_E1EB_A9EA_3693,
+        u64::MAX,
+        true,
+        u64::MAX,
+        0x995D_C9BB_DF19_39FA,
+    ),
+    crc(
+        "crc-64-go-iso",
+        "CRC-64/GO-ISO",
+        64,
+        0x1B,
+        u64::MAX,
+        true,
+        u64::MAX,
+        0xB909_56C7_75A4_1001,
+    ),
+    ChecksumPreset {
+        id: "adler-32",
+        name: "Adler-32",
+        params: None,
+        check: 0x091E_01DE,
+    },
+];
+
+/// List the checksum presets with their parameters, so the UI can show
+/// them and pre-fill the custom form.
+#[tauri::command]
+pub fn checksum_presets() -> Vec<ChecksumPreset> {
+    PRESETS.to_vec()
+}
+
+/// Compute checksums of one payload.
+///
+/// # Arguments
+///
+/// - `input` — the payload, in the encoding named by `encoding`.
+/// - `encoding` — `utf8` (default), `hex`, or `base64`.
+/// - `presets` — preset ids from [`PRESETS`]; unknown ids are dropped.
+/// - `custom` — parameters of an extra CRC, reported as `custom`.
+///
+/// # Errors
+///
+/// Returns a `String` when the input does not decode, the custom
+/// parameters are out of range, or the `spawn_blocking` worker fails to
+/// join.
+#[tauri::command]
+pub async fn checksum_compute(
+    input: String,
+    encoding: Option<InputEncoding>,
+    presets: Vec<String>,
+    custom: Option<CrcParams>,
+) -> Result<ChecksumResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let bytes = decode_input(&input, encoding.unwrap_or_default())?;
+        if let Some(params) = &custom {
+            validate(params)?;
+        }
+        let mut values: Vec<_> = presets
+            .iter()
+            .filter_map(|id| PRESETS.iter().find(|preset| preset.id == id.as_str()))
+            .map(|preset| {
+                let (width, value) = preset.params.as_ref().map_or_else(
+                    || (32, u64::from(adler32(&bytes))),
+                    |params| (params.width, Crc::new(params).checksum(&bytes)),
+                );
+                checksum_value(preset.id, width, value)
+            })
+            .collect();
+        if let Some(params) = &custom {
+            values.push(checksum_value(
+                "custom",
+                params.width,
+                Crc::new(params).checksum(&bytes),
+            ));
+        }
+        Ok(ChecksumResult {
+            values,
+            size_bytes: bytes.len() as u64,
+        })
+    })
+    .await
+    .map_err(|e| format!("checksum worker join failed: {e}"))?
+}
+
+fn validate(params: &CrcParams) -> Result<(), String> {
+    if !(8..=64).contains(&params.width) {
+        return Err(format!(
+            "CRC width must be between 8 and 64 bits, got {}",
+            params.width
+        ));
+    }
+    let mask = width_mask(params.width);
+    for (name, value) in [
+        ("poly", params.poly),
+        ("init", params.init),
+        ("xorout", params.xorout),
+    ] {
+        if value & !mask != 0 {
+            return Err(format!(
+                "CRC {name} 0x{value:X} does not fit in {} bits",
+                params.width
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn decode_input(input: &str, encoding: InputEncoding) -> Result<Vec<u8>, String> {
+    let compact = || -> String {
+        input
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != ':')
+            .collect()
+    };
+    match encoding {
+        InputEncoding::Utf8 => Ok(input.as_bytes().to_vec()),
+        InputEncoding::Hex => {
+            let hex = compact();
+            let hex = hex
+                .strip_prefix("0x")
+                .or_else(|| hex.strip_prefix("0X"))
+                .unwrap_or(&hex);
+            hex::decode(hex).map_err(|e| format!("Invalid hex input: {e}"))
+        }
+        InputEncoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(compact())
+            .map_err(|e| format!("Invalid base64 input: {e}")),
+    }
+}
+
+fn checksum_value(id: &str, width: u32, value: u64) -> ChecksumValue {
+    let digits = width.div_ceil(4) as usize;
+    ChecksumValue {
+        id: id.to_string(),
+        width,
+        hex: format!("{value:0digits$X}"),
+        decimal: value.to_string(),
+    }
+}
+
+const fn width_mask(width: u32) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+/// Table-driven CRC over any width from 8 to 64 bits.
+struct Crc {
+    params: CrcParams,
+    table: [u64; 256],
+}
+
+impl Crc {
+    fn new(params: &CrcParams) -> Self {
+        let top = 1u64 << (params.width - 1);
+        let mask = width_mask(params.width);
+        let mut table = [0u64; 256];
+        for (byte, entry) in (0u64..).zip(table.iter_mut()) {
+            let mut register = byte << (params.width - 8);
+            for _ in 0..8 {
+                register = if register & top == 0 {
+                    register << 1
+                } else {
+                    (register << 1) ^ params.poly
+                };
+            }
+            *entry = register & mask;
+        }
+        Self {
+            params: *params,
+            table,
+        }
+    }
+
+    fn checksum(&self, bytes: &[u8]) -> u64 {
+        let width = self.params.width;
+        let mask = width_mask(width);
+        let mut register = self.params.init;
+        for &byte in bytes {
+            let byte = if self.params.refin {
+                byte.reverse_bits()
+            } else {
+                byte
+            };
+            let index = ((register >> (width - 8)) ^ u64::from(byte)) & 0xFF;
+            register = ((register << 8) ^ self.table[index as usize]) & mask;
+        }
+        if self.params.refout {
+            register = register.reverse_bits() >> (64 - width);
+        }
+        (register ^ self.params.xorout) & mask
+    }
+}
+
+/// `u64` as a `0x`-prefixed hex string
+mod hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    // serde's `with` contract passes the field by reference.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{value:X}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let digits = text.trim();
+        let digits = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+            .unwrap_or(digits);
+        u64::from_str_radix(digits, 16)
+            .map_err(|e| serde::de::Error::custom(format!("invalid hex value \"{text}\": {e}")))
+    }
+}
+
+/// Adler-32 (RFC 1950)
+fn adler32(bytes: &[u8]) -> u32 {
+    const MODULUS: u32 = 65_521;
+    // Largest run that cannot overflow `b` before the modulo (zlib's NMAX).
+    const CHUNK: usize = 5552;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(CHUNK) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK_INPUT: &[u8] = b"123456789";
+
+    #[test]
+    fn presets_match_catalogue_check_values() {
+        for preset in PRESETS {
+            let value = preset.params.as_ref().map_or_else(
+                || u64::from(adler32(CHECK_INPUT)),
+                |params| Crc::new(params).checksum(CHECK_INPUT),
+            );
+            assert_eq!(value, preset.check, "{}", preset.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn computes_presets_and_custom_over_hex_input() {
+        let custom = CrcParams {
+            width: 12,
+            poly: 0x80F,
+            init: 0,
+            refin: false,
+            refout: true,
+            xorout: 0,
+        };
+        let result = checksum_compute(
+            "31 32 33 34 35 36 37 38 39".to_string(),
+            Some(InputEncoding::Hex),
+            vec!["crc-16-modbus".into(), "adler-32".into(), "nope".into()],
+            Some(custom),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.size_bytes, 9);
+        let hexes: Vec<_> = result.values.iter().map(|v| v.hex.as_str()).collect();
+        // CRC-12/UMTS check value.
+        assert_eq!(hexes, ["4B37", "091E01DE", "DAF"]);
+        assert_eq!(result.values[1].decimal, "152961502");
+    }
+
+    #[test]
+    fn params_travel_as_hex_strings() {
+        let json = serde_json::to_value(&PRESETS[12]).unwrap();
+        assert_eq!(json["params"]["poly"], "0x42F0E1EBA9EA3693");
+        assert_eq!(json["check"], "0x995DC9BBDF1939FA");
+        let params: CrcParams = serde_json::from_str(
+            r#"{"width":16,"poly":"1021","init":"0xffff","refin":false,"refout":false,"xorout":"0"}"#,
+        )
+        .unwrap();
+        assert_eq!(params, PRESETS[3].params.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_out_of_range_input() {
+        let wide = CrcParams {
+            width: 8,
+            poly: 0x107,
+            init: 0,
+            refin: false,
+            refout: false,
+            xorout: 0,
+        };
+        assert!(checksum_compute(String::new(), None, vec![], Some(wide))
+            .await
+            .is_err());
+        assert!(
+            checksum_compute("zz".into(), Some(InputEncoding::Hex), vec![], None)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,446 @@
+//! Headless command-line front end.
+//!
+//! Backs the `kogu-cli` binary, which exposes the core tools as subcommands
+//! so the same Rust logic the desktop app uses can run in scripts and CI.
+//! Every subcommand prints a single JSON document on stdout; failures print
+//! `{"error": "..."}` on stderr and exit non-zero.
+//!
+//! ```text
+//! kogu-cli parse <json|yaml|xml|sql> [FILE]
+//! kogu-cli format <json|yaml|toml> [FILE] [--indent N] [--minify]
+//! kogu-cli convert <json|yaml|toml> <json|yaml|toml> [FILE]
+//! kogu-cli hash [FILE] [--algorithms md5,sha1,sha256,...]
+//! kogu-cli uuid [--count N]
+//! kogu-cli scan <TARGET> [--ports SPEC] [--timeout-ms N] [--concurrency N]
+//! kogu-cli discover <TARGET>... [--methods a,b] [--timeout-ms N] [--concurrency N]
+//! kogu-cli bench [TOOL] [--size N] [--iterations N]
+//! kogu-cli mcp [--http PORT] [--allow a,b]
+//! ```
+//!
+//! `FILE` defaults to stdin; `-` also reads stdin. `mcp` is the exception to
+//! the one-document rule: it runs an MCP server that exchanges JSON-RPC
+//! messages on stdin/stdout (or serves them over loopback HTTP) until
+//! stopped.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::ast::{parse_to_ast, AstLanguage};
+use crate::benchmark::{self, BenchmarkTool};
+use crate::document::DocFormat;
+use crate::mcp::{self, McpServer};
+use crate::network::discovery::{merge_discovery_results, UnifiedDiscovery};
+use crate::network::scanner::{collect_scan, ScanState};
+use crate::network::types::{PortPreset, ScanMode};
+use crate::network::{
+    DiscoveryEvent, DiscoveryEventSink, DiscoveryMethod, DiscoveryOptions, ScanRequest,
+};
+
+/// Options that take no value
+const BOOLEAN_FLAGS: &[&str] = &["minify"];
+
+/// Upper bound for `uuid --count`
+const MAX_UUID_COUNT: usize = 10_000;
+
+/// Usage text printed for `help` and invalid invocations
+const USAGE: &str = "\
+Usage: kogu-cli <COMMAND> [ARGS]
+
+Commands:
+  parse <json|yaml|xml|sql> [FILE]                   Parse to an AST
+  format <json|yaml|toml> [FILE] [--indent N] [--minify]
+  convert <FROM> <TO> [FILE]                         Convert between json, yaml, toml
+  hash [FILE] [--algorithms md5,sha1,sha256,...]     Hash file or stdin bytes
+  uuid [--count N]                                   Generate v4 UUIDs
+  scan <TARGET> [--ports SPEC] [--timeout-ms N] [--concurrency N]
+  discover <TARGET>... [--methods a,b] [--timeout-ms N] [--concurrency N]
+  bench [json-parse|sql-parse|bcrypt|port-scan|all] [--size N] [--iterations N]
+  mcp [--http PORT] [--allow a,b]                    Run an MCP server on stdio or HTTP
+
+FILE defaults to stdin. Output is JSON on stdout.";
+
+/// Failure of a CLI invocation
+#[derive(Debug)]
+enum CliError {
+    /// Bad arguments; usage is printed
+    Usage(String),
+    /// The tool itself failed
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+type CliResult = Result<Value, CliError>;
+
+/// Parsed positional arguments and `--name value` options
+#[derive(Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(raw: &[String]) -> Result<Self, CliError> {
+        let mut args = Self::default();
+        let mut iter = raw.iter();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                args.positional.push(arg.clone());
+                continue;
+            };
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name, value.to_string()),
+                None if BOOLEAN_FLAGS.contains(&name) => (name, "true".to_string()),
+                None => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| CliError::Usage(format!("--{name} requires a value")))?;
+                    (name, value.clone())
+                }
+            };
+            args.options.insert(name.to_string(), value);
+        }
+        Ok(args)
+    }
+
+    fn positional(&self, index: usize, what: &str) -> Result<&str, CliError> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| CliError::Usage(format!("Missing {what}")))
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        self.options.get(name).map_or(Ok(default), |value| {
+            value
+                .parse()
+                .map_err(|_| CliError::Usage(format!("--{name} expects a number, got {value}")))
+        })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.get(name).is_some_and(|v| v == "true")
+    }
+
+    /// Read the input file at `index`, or stdin when absent or `-`.
+    fn read_input(&self, index: usize) -> Result<Vec<u8>, CliError> {
+        match self.positional.get(index).map(String::as_str) {
+            None | Some("-") => {
+                let mut buf = Vec::new();
+                io::stdin()
+                    .read_to_end(&mut buf)
+                    .map_err(|e| format!("Failed to read stdin: {e}"))?;
+                Ok(buf)
+            }
+            Some(path) => {
+                Ok(std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?)
+            }
+        }
+    }
+
+    fn read_text(&self, index: usize) -> Result<String, CliError> {
+        String::from_utf8(self.read_input(index)?)
+            .map_err(|_| CliError::Failed("Input is not valid UTF-8".to_string()))
+    }
+}
+
+/// Serialize a result into a JSON value.
+fn to_value(value: impl Serialize) -> CliResult {
+    serde_json::to_value(value).map_err(|e| CliError::Failed(format!("Serialization failed: {e}")))
+}
+
+/// Parse a document format name, reporting unknown names as usage errors.
+fn doc_format(name: &str) -> Result<DocFormat, CliError> {
+    name.parse().map_err(CliError::Usage)
+}
+
+fn cmd_parse(args: &Args) -> CliResult {
+    let language: AstLanguage = args
+        .positional(0, "language")?
+        .parse()
+        .map_err(|e: crate::ast::AstError| CliError::Usage(e.to_string()))?;
+    let text = args.read_text(1)?;
+    let result = parse_to_ast(&text, language);
+    if !result.has_errors() {
+        to_value(result)
+    } else {
+        Err(CliError::Failed(
+            result
+                .errors
+                .iter()
+                .map(|e| e.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+        ))
+    }
+}
+
+fn cmd_format(args: &Args) -> CliResult {
+    let format = doc_format(args.positional(0, "format")?)?;
+    let value = format.read(&args.read_text(1)?)?;
+    let output = format.write(&value, args.number("indent", 2)?, args.flag("minify"))?;
+    Ok(json!({ "output": output }))
+}
+
+fn cmd_convert(args: &Args) -> CliResult {
+    let from = doc_format(args.positional(0, "source format")?)?;
+    let to = doc_format(args.positional(1, "target format")?)?;
+    let value = from.read(&args.read_text(2)?)?;
+    let output = to.write(&value, 2, false)?;
+    Ok(json!({ "output": output }))
+}
+
+fn cmd_hash(args: &Args) -> CliResult {
+    let algorithms: Vec<String> = args
+        .options
+        .get("algorithms")
+        .map_or("sha256", String::as_str)
+        .split(',')
+        .map(|a| a.trim().to_string())
+        .collect();
+    let bytes = args.read_input(0)?;
+    let hashes = crate::hash_text::digest_bytes(&bytes, &algorithms);
+    if hashes.is_empty() {
+        return Err(CliError::Usage("No supported algorithm given".to_string()));
+    }
+    Ok(json!({ "sizeBytes": bytes.len(), "hashes": hashes }))
+}
+
+fn cmd_uuid(args: &Args) -> CliResult {
+    let count: usize = args.number("count", 1)?;
+    if count == 0 || count > MAX_UUID_COUNT {
+        return Err(CliError::Usage(format!(
+            "--count must be between 1 and {MAX_UUID_COUNT}"
+        )));
+    }
+    let uuids: Vec<String> = (0..count)
+        .map(|_| uuid::Uuid::new_v4().to_string())
+        .collect();
+    Ok(json!({ "uuids": uuids }))
+}
+
+/// Discovery sink that drops every event; the CLI only prints the result
+struct SilentSink;
+
+impl DiscoveryEventSink for SilentSink {
+    fn send(&self, _event: DiscoveryEvent) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn runtime() -> Result<tokio::runtime::Runtime, CliError> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| CliError::Failed(format!("Failed to start async runtime: {e}")))
+}
+
+fn cmd_scan(args: &Args) -> CliResult {
+    let target = args.positional(0, "target")?.to_string();
+    let port_range = args.options.get("ports").cloned();
+    let request = ScanRequest {
+        target,
+        mode: if port_range.is_some() {
+            ScanMode::Custom
+        } else {
+            ScanMode::Quick
+        },
+        port_preset: PortPreset::Custom,
+        port_range,
+        concurrency: args.number("concurrency", 100)?,
+        timeout_ms: args.number("timeout-ms", 1000)?,
+        resolution: None,
+    };
+    let (scan_state, _cancel_rx) = ScanState::new();
+    let results = runtime()?
+        .block_on(collect_scan(request, Arc::new(scan_state)))
+        .map_err(String::from)?;
+    to_value(results)
+}
+
+fn cmd_discover(args: &Args) -> CliResult {
+    if args.positional.is_empty() {
+        return Err(CliError::Usage("Missing target".to_string()));
+    }
+    let targets = crate::parse_targets_for_discovery(&args.positional);
+    if targets.is_empty() {
+        return Err(CliError::Failed(
+            "No valid IP addresses provided".to_string(),
+        ));
+    }
+    let mut options = DiscoveryOptions {
+        timeout_ms: args.number("timeout-ms", 1000)?,
+        concurrency: args.number("concurrency", 100)?,
+        ..DiscoveryOptions::default()
+    };
+    if let Some(methods) = args.options.get("methods") {
+        options.methods = methods
+            .split(',')
+            .map(|m| {
+                serde_json::from_value::<DiscoveryMethod>(Value::String(m.trim().to_string()))
+                    .map_err(|_| CliError::Usage(format!("Unknown discovery method: {m}")))
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    let (scan_state, _cancel_rx) = ScanState::new();
+    let results = runtime()?.block_on(crate::network::discover_hosts(
+        &targets,
+        &options,
+        &SilentSink,
+        &Arc::new(scan_state),
+    ));
+    let hosts = merge_discovery_results(&results);
+    to_value(UnifiedDiscovery { results, hosts })
+}
+
+fn cmd_bench(args: &Args) -> CliResult {
+    let tool = args.positional.first().map_or("all", String::as_str);
+    let tool: BenchmarkTool = serde_json::from_value(Value::String(tool.to_string()))
+        .map_err(|_| CliError::Usage(format!("Unknown benchmark: {tool}")))?;
+    let size = args
+        .options
+        .contains_key("size")
+        .then(|| args.number("size", 0))
+        .transpose()?;
+    let iterations = args
+        .options
+        .contains_key("iterations")
+        .then(|| args.number("iterations", 0))
+        .transpose()?;
+    let report = runtime()?.block_on(benchmark::run(tool, size, iterations))?;
+    to_value(report)
+}
+
+/// Run the MCP server until stdin closes (stdio) or the process is killed
+/// (HTTP). Returns `Null` because the protocol owns stdout.
+fn cmd_mcp(args: &Args) -> CliResult {
+    let allow: Option<Vec<String>> = args
+        .options
+        .get("allow")
+        .map(|list| list.split(',').map(|a| a.trim().to_string()).collect());
+    let server = McpServer::new(allow.as_deref()).map_err(CliError::Usage)?;
+    let runtime = runtime()?;
+    if !args.options.contains_key("http") {
+        mcp::serve_stdio(&server, &runtime)?;
+        return Ok(Value::Null);
+    }
+    let port = args.number("http", 0)?;
+    runtime.block_on(async move {
+        let (listener, router) = mcp::bind_http(server, port).await?;
+        let _ = writeln!(
+            io::stderr(),
+            "MCP server listening on {}",
+            mcp::endpoint(&listener)?
+        );
+        axum::serve(listener, router)
+            .await
+            .map_err(|e| format!("MCP server failed: {e}"))
+    })?;
+    Ok(Value::Null)
+}
+
+fn dispatch(raw: &[String]) -> CliResult {
+    let Some((command, rest)) = raw.split_first() else {
+        return Err(CliError::Usage("Missing command".to_string()));
+    };
+    let args = Args::parse(rest)?;
+    match command.as_str() {
+        "parse" => cmd_parse(&args),
+        "format" => cmd_format(&args),
+        "convert" => cmd_convert(&args),
+        "hash" => cmd_hash(&args),
+        "uuid" => cmd_uuid(&args),
+        "scan" => cmd_scan(&args),
+        "discover" => cmd_discover(&args),
+        "bench" => cmd_bench(&args),
+        "mcp" => cmd_mcp(&args),
+        other => Err(CliError::Usage(format!("Unknown command: {other}"))),
+    }
+}
+
+/// Run the CLI with `args` (excluding the program name).
+pub fn run(args: &[String]) -> ExitCode {
+    if args
+        .first()
+        .is_some_and(|a| matches!(a.as_str(), "help" | "--help" | "-h"))
+    {
+        let _ = writeln!(io::stdout(), "{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match dispatch(args) {
+        Ok(Value::Null) => ExitCode::SUCCESS,
+        Ok(value) => {
+            let _ = writeln!(
+                io::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&value).unwrap_or_default()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(CliError::Usage(message)) => {
+            let _ = writeln!(io::stderr(), "{}\n\n{USAGE}", json!({ "error": message }));
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            let _ = writeln!(io::stderr(), "{}", json!({ "error": message }));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Args {
+        Args::parse(&raw.iter().map(|s| (*s).to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn parses_positionals_options_and_flags() {
+        let parsed = args(&["json", "in.json", "--indent", "4", "--minify", "--x=y"]);
+        assert_eq!(parsed.positional, ["json", "in.json"]);
+        assert_eq!(parsed.number("indent", 2).unwrap(), 4);
+        assert!(parsed.flag("minify"));
+        assert_eq!(parsed.options.get("x").map(String::as_str), Some("y"));
+        assert!(Args::parse(&["--indent".to_string()]).is_err());
+    }
+
+    #[test]
+    fn converts_between_document_formats() {
+        let value = DocFormat::Yaml
+            .read("name: kogu\nports: [80, 443]\n")
+            .unwrap();
+        let toml = DocFormat::Toml.write(&value, 2, false).unwrap();
+        assert!(toml.contains("name = \"kogu\""));
+        let json = DocFormat::Json.write(&value, 4, false).unwrap();
+        assert!(json.contains("\n    \"name\": \"kogu\""));
+        let minified = DocFormat::Json.write(&value, 2, true).unwrap();
+        assert_eq!(minified, r#"{"name":"kogu","ports":[80,443]}"#);
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_formats() {
+        assert!(matches!(
+            dispatch(&["frobnicate".to_string()]),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(doc_format("ini"), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn generates_requested_uuid_count() {
+        let value = cmd_uuid(&args(&["--count", "3"])).unwrap();
+        assert_eq!(value["uuids"].as_array().map(Vec::len), Some(3));
+        assert!(cmd_uuid(&args(&["--count", "0"])).is_err());
+    }
+}
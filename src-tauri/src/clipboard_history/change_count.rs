@@ -0,0 +1,42 @@
+// FFI module: the pasteboard and clipboard counters are Objective-C and
+// Win32 calls requiring `unsafe`.
+#![allow(unsafe_code)]
+
+//! Clipboard change counters.
+//!
+//! macOS and Windows bump a counter on every clipboard write, so the
+//! watcher can tell that nothing changed without reading the clipboard.
+//! X11 and Wayland keep no such counter; [`current`] returns `None` there
+//! and the watcher falls back to rationing bitmap reads.
+
+/// Current value of the platform's clipboard change counter.
+#[cfg(target_os = "macos")]
+pub fn current() -> Option<u64> {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+
+    // `generalPasteboard` returns the shared pasteboard and `changeCount`
+    // is a plain `NSInteger` getter; neither has preconditions.
+    let pasteboard: Option<Retained<AnyObject>> =
+        unsafe { msg_send![class!(NSPasteboard), generalPasteboard] };
+    let pasteboard = pasteboard?;
+    let count: isize = unsafe { msg_send![&*pasteboard, changeCount] };
+    u64::try_from(count).ok()
+}
+
+/// Current value of the platform's clipboard change counter.
+#[cfg(target_os = "windows")]
+pub fn current() -> Option<u64> {
+    use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+
+    // No arguments; 0 means the window station has no clipboard access.
+    let count = unsafe { GetClipboardSequenceNumber() };
+    (count != 0).then_some(u64::from(count))
+}
+
+/// Current value of the platform's clipboard change counter.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const fn current() -> Option<u64> {
+    None
+}
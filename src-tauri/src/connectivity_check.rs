@@ -0,0 +1,442 @@
+//! Captive portal and internet connectivity detection.
+//!
+//! Runs the same plain-HTTP probes operating systems use to decide whether
+//! a network is online (`generate_204`, `connecttest.txt`,
+//! `hotspot-detect.html`), separately over IPv4 and IPv6. A probe that
+//! returns anything other than its well-known answer, typically a redirect
+//! or a login page, indicates a captive portal intercepting traffic.
+//!
+//! Resolving a random, unregistered name through the system resolver also
+//! detects DNS hijacking: a resolver that answers for a name that cannot
+//! exist is rewriting NXDOMAIN responses.
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum portal page bytes kept for display
+const MAX_BODY_PREVIEW: usize = 512;
+
+/// Expected answer from a connectivity probe
+#[derive(Debug, Clone, Copy)]
+enum Expectation {
+    /// HTTP 204 with an empty body
+    NoContent,
+    /// HTTP 200 whose body contains the given marker
+    BodyContains(&'static str),
+}
+
+/// A well-known connectivity check endpoint
+struct ProbeEndpoint {
+    /// Display name
+    name: &'static str,
+    /// Host name
+    host: &'static str,
+    /// Request path
+    path: &'static str,
+    /// Expected answer when no portal intercepts
+    expect: Expectation,
+}
+
+/// Endpoints probed over each address family
+const ENDPOINTS: [ProbeEndpoint; 3] = [
+    ProbeEndpoint {
+        name: "Google",
+        host: "connectivitycheck.gstatic.com",
+        path: "/generate_204",
+        expect: Expectation::NoContent,
+    },
+    ProbeEndpoint {
+        name: "Microsoft",
+        host: "www.msftconnecttest.com",
+        path: "/connecttest.txt",
+        expect: Expectation::BodyContains("Microsoft Connect Test"),
+    },
+    ProbeEndpoint {
+        name: "Apple",
+        host: "captive.apple.com",
+        path: "/hotspot-detect.html",
+        expect: Expectation::BodyContains("Success"),
+    },
+];
+
+/// Request payload sent from the frontend.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityRequest {
+    /// Per-probe timeout in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// Address family a probe was pinned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    /// IPv4
+    Ipv4,
+    /// IPv6
+    Ipv6,
+}
+
+impl AddressFamily {
+    const fn matches(self, ip: &IpAddr) -> bool {
+        match self {
+            Self::Ipv4 => ip.is_ipv4(),
+            Self::Ipv6 => ip.is_ipv6(),
+        }
+    }
+}
+
+/// Outcome of a single probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProbeOutcome {
+    /// The endpoint returned its well-known answer.
+    Online,
+    /// Something other than the endpoint answered.
+    CaptivePortal {
+        /// HTTP status received.
+        status: u16,
+        /// `Location` header of a redirect, usually the portal login page.
+        redirect: Option<String>,
+        /// Leading bytes of the intercepted body.
+        body_preview: String,
+    },
+    /// The host has no address in this family.
+    NoAddress,
+    /// The request failed (timeout, refused, unreachable).
+    Failed {
+        /// Failure description.
+        error: String,
+    },
+}
+
+/// A probe result for one endpoint over one family.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeResult {
+    /// Endpoint display name.
+    pub endpoint: String,
+    /// Probed URL.
+    pub url: String,
+    /// Address the request was pinned to.
+    pub address: Option<String>,
+    /// Classified outcome.
+    pub outcome: ProbeOutcome,
+    /// Request duration in milliseconds.
+    pub elapsed_ms: u128,
+}
+
+/// Overall state of one address family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FamilyState {
+    /// At least one probe reached the internet unmodified.
+    Online,
+    /// Probes were answered, but by an interceptor.
+    CaptivePortal,
+    /// Probes had addresses but none was answered.
+    Offline,
+    /// No probe host resolved to an address in this family.
+    Unavailable,
+}
+
+/// Connectivity over one address family.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FamilyConnectivity {
+    /// Address family.
+    pub family: AddressFamily,
+    /// Aggregated state.
+    pub state: FamilyState,
+    /// Individual probe results.
+    pub probes: Vec<ProbeResult>,
+}
+
+/// DNS hijacking check result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsHijackCheck {
+    /// Random name that should not resolve.
+    pub name: String,
+    /// Whether the resolver answered for it.
+    pub hijacked: bool,
+    /// Addresses returned for the nonexistent name.
+    pub addresses: Vec<String>,
+}
+
+/// Aggregate connectivity report.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityResult {
+    /// IPv4 results.
+    pub ipv4: FamilyConnectivity,
+    /// IPv6 results.
+    pub ipv6: FamilyConnectivity,
+    /// DNS hijacking check.
+    pub dns: DnsHijackCheck,
+    /// Whether any probe was intercepted.
+    pub captive_portal: bool,
+    /// First portal redirect target seen, when any.
+    pub portal_url: Option<String>,
+    /// Wall-clock time of the whole check.
+    pub elapsed_ms: u128,
+}
+
+/// Classify a probe response against the endpoint's expected answer.
+fn classify(
+    expect: Expectation,
+    status: u16,
+    redirect: Option<String>,
+    body: &str,
+) -> ProbeOutcome {
+    let online = match expect {
+        Expectation::NoContent => status == 204 && body.is_empty(),
+        Expectation::BodyContains(marker) => status == 200 && body.contains(marker),
+    };
+    if online {
+        ProbeOutcome::Online
+    } else {
+        ProbeOutcome::CaptivePortal {
+            status,
+            redirect,
+            body_preview: body.chars().take(MAX_BODY_PREVIEW).collect(),
+        }
+    }
+}
+
+/// Aggregate per-endpoint outcomes into a family state.
+fn family_state(probes: &[ProbeResult]) -> FamilyState {
+    let has = |pred: fn(&ProbeOutcome) -> bool| probes.iter().any(|p| pred(&p.outcome));
+    if has(|o| matches!(o, ProbeOutcome::Online)) {
+        FamilyState::Online
+    } else if has(|o| matches!(o, ProbeOutcome::CaptivePortal { .. })) {
+        FamilyState::CaptivePortal
+    } else if has(|o| matches!(o, ProbeOutcome::Failed { .. })) {
+        FamilyState::Offline
+    } else {
+        FamilyState::Unavailable
+    }
+}
+
+/// Probe one endpoint with the connection pinned to `family`.
+async fn run_probe(
+    endpoint: &ProbeEndpoint,
+    family: AddressFamily,
+    limit: Duration,
+) -> ProbeResult {
+    let url = format!("http://{}{}", endpoint.host, endpoint.path);
+    let started = Instant::now();
+    let mut result = ProbeResult {
+        endpoint: endpoint.name.to_string(),
+        url: url.clone(),
+        address: None,
+        outcome: ProbeOutcome::NoAddress,
+        elapsed_ms: 0,
+    };
+
+    let addr = match tokio::time::timeout(limit, tokio::net::lookup_host((endpoint.host, 80))).await
+    {
+        Ok(Ok(mut addrs)) => addrs.find(|a| family.matches(&a.ip())),
+        Ok(Err(_)) => None,
+        Err(_) => {
+            result.outcome = ProbeOutcome::Failed {
+                error: "DNS resolution timed out".to_string(),
+            };
+            return result;
+        }
+    };
+    let Some(addr) = addr else {
+        return result;
+    };
+    result.address = Some(addr.ip().to_string());
+
+    result.outcome = fetch(endpoint, &url, addr, limit)
+        .await
+        .unwrap_or_else(|error| ProbeOutcome::Failed { error });
+    result.elapsed_ms = started.elapsed().as_millis();
+    result
+}
+
+/// Issue the probe request against a fixed address without following
+/// redirects, so a portal's redirect is observed rather than followed.
+async fn fetch(
+    endpoint: &ProbeEndpoint,
+    url: &str,
+    addr: SocketAddr,
+    limit: Duration,
+) -> Result<ProbeOutcome, String> {
+    let client = reqwest::Client::builder()
+        .resolve(endpoint.host, addr)
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(limit)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    let status = response.status().as_u16();
+    let redirect = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response body: {e}"))?;
+
+    Ok(classify(endpoint.expect, status, redirect, &body))
+}
+
+/// Probe every endpoint over `family`.
+async fn check_family(family: AddressFamily, limit: Duration) -> FamilyConnectivity {
+    let probes = futures::future::join_all(
+        ENDPOINTS
+            .iter()
+            .map(|endpoint| run_probe(endpoint, family, limit)),
+    )
+    .await;
+    FamilyConnectivity {
+        family,
+        state: family_state(&probes),
+        probes,
+    }
+}
+
+/// Resolve a random name under `.com` that cannot plausibly be registered.
+async fn check_dns_hijack(limit: Duration) -> DnsHijackCheck {
+    let name = format!("kogu-nx-{}.com", uuid::Uuid::new_v4().simple());
+    let addresses =
+        match tokio::time::timeout(limit, tokio::net::lookup_host((name.as_str(), 80))).await {
+            Ok(Ok(addrs)) => addrs.map(|a| a.ip().to_string()).collect(),
+            _ => Vec::new(),
+        };
+    DnsHijackCheck {
+        name,
+        hijacked: !addresses.is_empty(),
+        addresses,
+    }
+}
+
+/// Detect captive portals, DNS hijacking, and per-family internet reachability.
+///
+/// # Errors
+///
+/// Returns an error string when the timeout is zero.
+#[tauri::command]
+pub async fn check_connectivity(req: ConnectivityRequest) -> Result<ConnectivityResult, String> {
+    if req.timeout_ms == 0 {
+        return Err("Timeout must be greater than zero".to_string());
+    }
+    let started = Instant::now();
+    let limit = Duration::from_millis(u64::from(req.timeout_ms));
+
+    let (ipv4, ipv6, dns) = tokio::join!(
+        check_family(AddressFamily::Ipv4, limit),
+        check_family(AddressFamily::Ipv6, limit),
+        check_dns_hijack(limit),
+    );
+
+    let portal_url = ipv4
+        .probes
+        .iter()
+        .chain(&ipv6.probes)
+        .find_map(|p| match &p.outcome {
+            ProbeOutcome::CaptivePortal { redirect, .. } => redirect.clone(),
+            _ => None,
+        });
+    let captive_portal = ipv4.state == FamilyState::CaptivePortal
+        || ipv6.state == FamilyState::CaptivePortal
+        || portal_url.is_some();
+
+    Ok(ConnectivityResult {
+        ipv4,
+        ipv6,
+        dns,
+        captive_portal,
+        portal_url,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(outcome: ProbeOutcome) -> ProbeResult {
+        ProbeResult {
+            endpoint: "Google".to_string(),
+            url: "http://connectivitycheck.gstatic.com/generate_204".to_string(),
+            address: None,
+            outcome,
+            elapsed_ms: 0,
+        }
+    }
+
+    #[test]
+    fn classifies_expected_answers_as_online() {
+        assert_eq!(
+            classify(Expectation::NoContent, 204, None, ""),
+            ProbeOutcome::Online
+        );
+        assert_eq!(
+            classify(
+                Expectation::BodyContains("Success"),
+                200,
+                None,
+                "<HTML><BODY>Success</BODY></HTML>"
+            ),
+            ProbeOutcome::Online
+        );
+    }
+
+    #[test]
+    fn classifies_redirects_and_rewritten_pages_as_portal() {
+        let outcome = classify(
+            Expectation::NoContent,
+            302,
+            Some("http://portal.example/login".to_string()),
+            "",
+        );
+        assert!(matches!(
+            outcome,
+            ProbeOutcome::CaptivePortal { status: 302, redirect: Some(ref r), .. } if r == "http://portal.example/login"
+        ));
+
+        let outcome = classify(Expectation::NoContent, 200, None, "<html>Sign in</html>");
+        assert!(matches!(
+            outcome,
+            ProbeOutcome::CaptivePortal { status: 200, .. }
+        ));
+    }
+
+    #[test]
+    fn family_state_prefers_online_over_portal() {
+        let portal = ProbeOutcome::CaptivePortal {
+            status: 302,
+            redirect: None,
+            body_preview: String::new(),
+        };
+        let failed = ProbeOutcome::Failed {
+            error: "timed out".to_string(),
+        };
+        assert_eq!(
+            family_state(&[probe(portal.clone()), probe(ProbeOutcome::Online)]),
+            FamilyState::Online
+        );
+        assert_eq!(
+            family_state(&[probe(failed.clone()), probe(portal)]),
+            FamilyState::CaptivePortal
+        );
+        assert_eq!(family_state(&[probe(failed)]), FamilyState::Offline);
+        assert_eq!(
+            family_state(&[probe(ProbeOutcome::NoAddress)]),
+            FamilyState::Unavailable
+        );
+    }
+}
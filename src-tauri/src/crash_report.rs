@@ -0,0 +1,365 @@
+//! Local crash reports with opt-in submission
+//!
+//! A panic hook writes one JSON report per panic to the `crashes` folder
+//! in the app data directory. Reports carry the panic message, location,
+//! backtrace, OS, app version, and the last command invoked from the
+//! frontend. The home directory and user name are replaced before the
+//! report touches disk, so nothing leaves the machine that the user has
+//! not already seen in the review dialog.
+//!
+//! Nothing is sent automatically. [`crash_report_submit`] requires explicit
+//! consent and only builds a prefilled GitHub issue URL for the frontend
+//! to open; the user reviews and files the issue themselves.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+const CRASH_DIR: &str = "crashes";
+
+/// Issue tracker that submitted reports are filed against
+const ISSUE_URL: &str = "https://github.com/seijikohara/kogu/issues/new";
+
+/// Characters of backtrace included in the issue body; URLs longer than
+/// ~8 KB are rejected by GitHub
+const MAX_ISSUE_BACKTRACE: usize = 4000;
+
+/// Reports kept on disk; older ones are deleted on the next crash
+const MAX_REPORTS: usize = 20;
+
+/// Name of the most recent command invoked from the frontend.
+static LAST_COMMAND: Mutex<String> = Mutex::new(String::new());
+
+/// Record the command about to run, for inclusion in crash reports.
+pub fn note_command(command: &str) {
+    if let Ok(mut last) = LAST_COMMAND.try_lock() {
+        last.clear();
+        last.push_str(command);
+    }
+}
+
+/// One crash report as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    /// Report id (UUID).
+    pub id: String,
+    /// Crash time in milliseconds since the Unix epoch.
+    pub created_at: u64,
+    /// Application version.
+    pub app_version: String,
+    /// Operating system family (e.g. `macos`).
+    pub os: String,
+    /// Operating system version.
+    pub os_version: String,
+    /// CPU architecture.
+    pub arch: String,
+    /// Name of the panicking thread.
+    pub thread: String,
+    /// Panic message.
+    pub message: String,
+    /// Source location of the panic.
+    pub location: Option<String>,
+    /// Last command invoked from the frontend before the crash.
+    pub last_command: Option<String>,
+    /// Captured backtrace.
+    pub backtrace: String,
+    /// Whether the user has submitted the report.
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+/// Managed state pointing at the crash report directory.
+pub struct CrashReportState {
+    dir: PathBuf,
+}
+
+/// Values replaced in every report.
+#[derive(Clone, Default)]
+struct Redactor {
+    home: Option<String>,
+    user: Option<String>,
+}
+
+impl Redactor {
+    fn from_env() -> Self {
+        let var = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|n| std::env::var(n).ok())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            home: var(&["HOME", "USERPROFILE"]),
+            user: var(&["USER", "USERNAME"]),
+        }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        if let Some(home) = &self.home {
+            out = out.replace(home.as_str(), "~");
+        }
+        // Short names ("a", "dev") would mangle unrelated text.
+        if let Some(user) = self.user.as_ref().filter(|u| u.len() >= 3) {
+            out = out.replace(user.as_str(), "<user>");
+        }
+        crate::redaction::redact(&out).into_owned()
+    }
+}
+
+fn unix_ms_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+fn panic_message(info: &std::panic::PanicHookInfo<'_>) -> String {
+    info.payload()
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
+
+fn build_report(info: &std::panic::PanicHookInfo<'_>, redactor: &Redactor) -> CrashReport {
+    let last_command = LAST_COMMAND
+        .try_lock()
+        .ok()
+        .map(|c| c.clone())
+        .filter(|c| !c.is_empty());
+    CrashReport {
+        id: uuid::Uuid::new_v4().to_string(),
+        created_at: unix_ms_now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        os_version: tauri_plugin_os::version().to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_string(),
+        message: redactor.redact(&panic_message(info)),
+        location: info
+            .location()
+            .map(|l| redactor.redact(&format!("{}:{}:{}", l.file(), l.line(), l.column()))),
+        last_command,
+        backtrace: redactor.redact(&std::backtrace::Backtrace::force_capture().to_string()),
+        submitted: false,
+    }
+}
+
+/// Path of one report. Ids are UUIDs generated here, so only the UUID
+/// character set is accepted to keep the path inside `dir`.
+fn report_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(format!("Invalid crash report id: {id}"));
+    }
+    Ok(dir.join(format!("{id}.json")))
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {e}"))?;
+    std::fs::write(report_path(dir, &report.id)?, json)
+        .map_err(|e| format!("Failed to write crash report: {e}"))
+}
+
+fn load_report(dir: &Path, id: &str) -> Result<CrashReport, String> {
+    let content = std::fs::read_to_string(report_path(dir, id)?)
+        .map_err(|e| format!("Failed to read crash report {id}: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Corrupt crash report {id}: {e}"))
+}
+
+/// All readable reports, newest first.
+fn list_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let content = std::fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str(&content).ok()
+        })
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+fn prune_reports(dir: &Path) {
+    for report in list_reports(dir).into_iter().skip(MAX_REPORTS) {
+        if let Ok(path) = report_path(dir, &report.id) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Prefilled "new issue" URL for a report.
+fn issue_url(report: &CrashReport) -> Result<String, String> {
+    let backtrace: String = report.backtrace.chars().take(MAX_ISSUE_BACKTRACE).collect();
+    let truncated = if backtrace.len() < report.backtrace.len() {
+        "\n… (truncated)"
+    } else {
+        ""
+    };
+    let body = format!(
+        "**Version:** {}\n**OS:** {} {} ({})\n**Last command:** {}\n**Location:** {}\n\n\
+         **Message:**\n```\n{}\n```\n\n**Backtrace:**\n```\n{backtrace}{truncated}\n```\n",
+        report.app_version,
+        report.os,
+        report.os_version,
+        report.arch,
+        report.last_command.as_deref().unwrap_or("unknown"),
+        report.location.as_deref().unwrap_or("unknown"),
+        report.message,
+    );
+    let title = format!("Crash: {}", report.message.lines().next().unwrap_or(""));
+    reqwest::Url::parse_with_params(
+        ISSUE_URL,
+        &[
+            ("title", title.as_str()),
+            ("body", body.as_str()),
+            ("labels", "bug"),
+        ],
+    )
+    .map(String::from)
+    .map_err(|e| format!("Failed to build issue URL: {e}"))
+}
+
+impl CrashReportState {
+    /// Install the panic hook writing reports under `data_dir`.
+    ///
+    /// The previous hook still runs afterwards, so panics keep reaching
+    /// stderr in development.
+    pub fn install(data_dir: &Path) -> Self {
+        let dir = data_dir.join(CRASH_DIR);
+        let _ = std::fs::create_dir_all(&dir);
+        let redactor = Redactor::from_env();
+        let hook_dir = dir.clone();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let report = build_report(info, &redactor);
+            tracing::error!(
+                report_id = %report.id,
+                location = report.location.as_deref().unwrap_or("unknown"),
+                "panic: {}",
+                report.message
+            );
+            if write_report(&hook_dir, &report).is_ok() {
+                prune_reports(&hook_dir);
+            }
+            previous(info);
+        }));
+        Self { dir }
+    }
+}
+
+/// List stored crash reports, newest first.
+#[tauri::command]
+pub fn crash_reports_list(state: tauri::State<'_, CrashReportState>) -> Vec<CrashReport> {
+    list_reports(&state.dir)
+}
+
+/// Delete one crash report.
+///
+/// # Errors
+///
+/// Returns an error string when the id is invalid or the file cannot be
+/// removed.
+#[tauri::command]
+pub fn crash_report_delete(
+    id: String,
+    state: tauri::State<'_, CrashReportState>,
+) -> Result<(), String> {
+    std::fs::remove_file(report_path(&state.dir, &id)?)
+        .map_err(|e| format!("Failed to delete crash report {id}: {e}"))
+}
+
+/// Prepare a reviewed crash report for submission.
+///
+/// Returns a prefilled GitHub issue URL for the frontend to open and marks
+/// the report as submitted. `consent` must be `true`; it is set by the
+/// review dialog after the user has read the report contents.
+///
+/// # Errors
+///
+/// Returns an error string when consent is missing or the report cannot
+/// be read or updated.
+#[tauri::command]
+pub fn crash_report_submit(
+    id: String,
+    consent: bool,
+    state: tauri::State<'_, CrashReportState>,
+) -> Result<String, String> {
+    if !consent {
+        return Err("Crash reports are only submitted with explicit consent".to_string());
+    }
+    let mut report = load_report(&state.dir, &id)?;
+    let url = issue_url(&report)?;
+    report.submitted = true;
+    write_report(&state.dir, &report)?;
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(id: &str, created_at: u64) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            created_at,
+            app_version: "0.0.6".to_string(),
+            os: "linux".to_string(),
+            os_version: "6.1".to_string(),
+            arch: "x86_64".to_string(),
+            thread: "main".to_string(),
+            message: "index out of bounds".to_string(),
+            location: Some("src/lib.rs:1:1".to_string()),
+            last_command: Some("parse_to_ast".to_string()),
+            backtrace: "0: kogu_lib::run".to_string(),
+            submitted: false,
+        }
+    }
+
+    #[test]
+    fn redacts_home_and_user() {
+        let redactor = Redactor {
+            home: Some("/home/alice".to_string()),
+            user: Some("alice".to_string()),
+        };
+        assert_eq!(
+            redactor.redact("/home/alice/.cargo/src/x.rs by alice"),
+            "~/.cargo/src/x.rs by <user>"
+        );
+        let short = Redactor {
+            home: None,
+            user: Some("a".to_string()),
+        };
+        assert_eq!(short.redact("a panic"), "a panic");
+    }
+
+    #[test]
+    fn stores_lists_and_rejects_bad_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        write_report(dir.path(), &sample("0000-aaaa", 1)).unwrap();
+        write_report(dir.path(), &sample("0000-bbbb", 2)).unwrap();
+
+        let ids: Vec<_> = list_reports(dir.path()).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, ["0000-bbbb", "0000-aaaa"]);
+        assert_eq!(load_report(dir.path(), "0000-aaaa").unwrap().created_at, 1);
+        assert!(report_path(dir.path(), "../settings").is_err());
+    }
+
+    #[test]
+    fn builds_issue_url() {
+        let url = issue_url(&sample("0000-aaaa", 1)).unwrap();
+        assert!(url.starts_with(ISSUE_URL));
+        assert!(url.contains("title=Crash%3A+index+out+of+bounds"));
+        assert!(url.contains("parse_to_ast"));
+    }
+}
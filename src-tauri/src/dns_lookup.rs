@@ -112,7 +112,7 @@ fn build_opts(timeout_ms: u32) -> ResolverOpts {
 /// the platform resolver configuration; the named presets resolve through
 /// the bundled IP set over UDP+TCP; any other value is parsed as a bare
 /// IPv4 / IPv6 literal that is wired up as both a UDP and a TCP server.
-fn build_resolver(spec: &str, timeout_ms: u32) -> Result<TokioResolver, String> {
+pub(crate) fn build_resolver(spec: &str, timeout_ms: u32) -> Result<TokioResolver, String> {
     let opts = build_opts(timeout_ms);
 
     if spec == "system" {
@@ -0,0 +1,70 @@
+//! Structured document formats shared by conversion features
+//!
+//! Reads JSON, YAML, and TOML into a `serde_json::Value` and writes a
+//! value back out, so any pair of formats converts through one model.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Structured document format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocFormat {
+    /// JSON
+    Json,
+    /// YAML (`yml` is accepted as an alias)
+    #[serde(alias = "yml")]
+    Yaml,
+    /// TOML
+    Toml,
+}
+
+impl std::str::FromStr for DocFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            other => Err(format!("Unsupported format: {other}")),
+        }
+    }
+}
+
+impl DocFormat {
+    /// Parse `text` in this format.
+    pub fn read(self, text: &str) -> Result<Value, String> {
+        match self {
+            Self::Json => serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {e}")),
+            Self::Yaml => serde_yaml::from_str(text).map_err(|e| format!("Invalid YAML: {e}")),
+            Self::Toml => toml::from_str(text).map_err(|e| format!("Invalid TOML: {e}")),
+        }
+    }
+
+    /// Serialize `value` in this format. `indent` and `minify` apply to
+    /// JSON only.
+    pub fn write(self, value: &Value, indent: usize, minify: bool) -> Result<String, String> {
+        match self {
+            Self::Json if minify => {
+                serde_json::to_string(value).map_err(|e| format!("JSON output failed: {e}"))
+            }
+            Self::Json => {
+                let indent = " ".repeat(indent);
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                let mut out = Vec::new();
+                let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+                value
+                    .serialize(&mut serializer)
+                    .map_err(|e| format!("JSON output failed: {e}"))?;
+                String::from_utf8(out).map_err(|e| format!("JSON output failed: {e}"))
+            }
+            Self::Yaml => {
+                serde_yaml::to_string(value).map_err(|e| format!("YAML output failed: {e}"))
+            }
+            Self::Toml => {
+                toml::to_string_pretty(value).map_err(|e| format!("TOML output failed: {e}"))
+            }
+        }
+    }
+}
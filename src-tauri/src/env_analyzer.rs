@@ -0,0 +1,436 @@
+//! Environment and PATH analyzer
+//!
+//! GUI apps on macOS and Linux do not inherit what the user's shell profile
+//! sets up, which is the classic "works in the terminal, not in the app"
+//! problem. [`analyze_environment`] captures this process's environment
+//! and, on Unix, the environment of a login shell (`$SHELL -l -c env`),
+//! diffs the two, and inspects `PATH` for missing directories, duplicates,
+//! and commands shadowed by an earlier directory.
+//!
+//! Values are masked the same way as in the system info report.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::system_info::{mask_env, EnvVar};
+
+/// Longest wait for the login shell to print its environment
+const SHELL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Variables that always differ between a shell and the app
+const VOLATILE_VARS: &[&str] = &["_", "SHLVL", "PWD", "OLDPWD", "PS1", "COLUMNS", "LINES"];
+
+/// How a variable differs between the app and the login shell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvChange {
+    /// Only the login shell has it.
+    Added,
+    /// Only the app has it.
+    Removed,
+    /// Both have it with different values.
+    Changed,
+}
+
+/// One differing variable.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvDiff {
+    /// Variable name.
+    pub name: String,
+    /// Kind of difference.
+    pub change: EnvChange,
+    /// Masked value in the app process.
+    pub process_value: Option<String>,
+    /// Masked value in the login shell.
+    pub shell_value: Option<String>,
+}
+
+/// One `PATH` entry.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathEntry {
+    /// Directory as written in `PATH`.
+    pub dir: String,
+    /// Whether the directory exists.
+    pub exists: bool,
+    /// Index of an earlier entry naming the same directory.
+    pub duplicate_of: Option<usize>,
+    /// Number of executables in the directory.
+    pub executables: usize,
+}
+
+/// A command found in more than one `PATH` directory.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowedCommand {
+    /// Command name.
+    pub name: String,
+    /// Every match in `PATH` order; the first one wins.
+    pub paths: Vec<String>,
+}
+
+/// `PATH` analysis of the app process.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathAnalysis {
+    /// Entries in order.
+    pub entries: Vec<PathEntry>,
+    /// Entries that do not exist.
+    pub missing: Vec<String>,
+    /// Commands present in several directories.
+    pub shadowed: Vec<ShadowedCommand>,
+    /// Directories on the login shell's `PATH` but not on the app's.
+    pub missing_from_process: Vec<String>,
+}
+
+/// Result of [`analyze_environment`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvReport {
+    /// App process environment, sorted by name.
+    pub process_env: Vec<EnvVar>,
+    /// Shell that was started, e.g. `/bin/zsh`.
+    pub shell: Option<String>,
+    /// Login shell environment, when it could be captured.
+    pub shell_env: Option<Vec<EnvVar>>,
+    /// Why the login shell environment is missing.
+    pub shell_error: Option<String>,
+    /// Differences between the two environments.
+    pub diff: Vec<EnvDiff>,
+    /// `PATH` analysis.
+    pub path: PathAnalysis,
+}
+
+fn process_env() -> BTreeMap<String, String> {
+    std::env::vars_os()
+        .map(|(k, v)| {
+            (
+                k.to_string_lossy().into_owned(),
+                v.to_string_lossy().into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Parse `env -0` output: `NAME=value` records separated by NUL.
+fn parse_env_output(output: &[u8]) -> BTreeMap<String, String> {
+    output
+        .split(|&b| b == 0)
+        .filter_map(|record| {
+            let record = String::from_utf8_lossy(record);
+            let (name, value) = record.split_once('=')?;
+            (!name.is_empty() && !name.contains(char::is_whitespace))
+                .then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Run the user's login shell and capture its environment.
+#[cfg(unix)]
+fn login_shell_env() -> Result<(String, BTreeMap<String, String>), String> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+
+    let shell = std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string());
+    let mut child = Command::new(&shell)
+        .args(["-l", "-c", "env -0"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {shell}: {e}"))?;
+
+    // Drain stdout on a thread so a large environment cannot fill the pipe.
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| format!("Failed to capture {shell} output"))?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    // A profile that waits for input must not hang the command.
+    let deadline = Instant::now() + SHELL_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{shell} did not finish within {}s",
+                    SHELL_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("Failed to wait for {shell}: {e}")),
+        }
+    };
+    let output = reader
+        .join()
+        .map_err(|_| format!("Reading {shell} output panicked"))?
+        .map_err(|e| format!("Failed to read {shell} output: {e}"))?;
+    if !status.success() {
+        return Err(format!("{shell} exited with {status}"));
+    }
+    Ok((shell, parse_env_output(&output)))
+}
+
+#[cfg(not(unix))]
+fn login_shell_env() -> Result<(String, BTreeMap<String, String>), String> {
+    Err("Login shell capture is only available on macOS and Linux".to_string())
+}
+
+fn diff_env(process: &BTreeMap<String, String>, shell: &BTreeMap<String, String>) -> Vec<EnvDiff> {
+    let masked =
+        |name: &str, value: Option<&String>| value.map(|v| mask_env(name.to_string(), v).value);
+    let mut names: Vec<&String> = process.keys().chain(shell.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| !VOLATILE_VARS.contains(&name.as_str()))
+        .filter_map(|name| {
+            let (p, s) = (process.get(name), shell.get(name));
+            let change = match (p, s) {
+                (Some(p), Some(s)) if p == s => return None,
+                (Some(_), Some(_)) => EnvChange::Changed,
+                (None, Some(_)) => EnvChange::Added,
+                (Some(_), None) => EnvChange::Removed,
+                (None, None) => return None,
+            };
+            Some(EnvDiff {
+                name: name.clone(),
+                change,
+                process_value: masked(name, p),
+                shell_value: masked(name, s),
+            })
+        })
+        .collect()
+}
+
+/// Whether `path` is a file the OS would run as a command.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".into());
+    path.is_file()
+        && path.extension().and_then(OsStr::to_str).is_some_and(|ext| {
+            extensions
+                .split(';')
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Name a command is invoked by: the file name, minus the extension on
+/// Windows.
+fn command_name(path: &Path) -> Option<String> {
+    let name = if cfg!(windows) {
+        path.file_stem()
+    } else {
+        path.file_name()
+    };
+    name.and_then(OsStr::to_str).map(|n| {
+        if cfg!(windows) {
+            n.to_ascii_lowercase()
+        } else {
+            n.to_string()
+        }
+    })
+}
+
+fn analyze_path(path: &str, shell_path: Option<&str>) -> PathAnalysis {
+    let dirs: Vec<String> = std::env::split_paths(path)
+        .map(|p| p.to_string_lossy().into_owned())
+        .filter(|dir| !dir.is_empty())
+        .collect();
+
+    let mut entries = Vec::with_capacity(dirs.len());
+    let mut seen: HashMap<PathBuf, usize> = HashMap::new();
+    let mut commands: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for (index, dir) in dirs.iter().enumerate() {
+        let exists = Path::new(dir).is_dir();
+        let key = std::fs::canonicalize(dir).unwrap_or_else(|_| PathBuf::from(dir));
+        let duplicate_of = seen.get(&key).copied();
+        let mut executables = 0;
+        if exists && duplicate_of.is_none() {
+            seen.insert(key, index);
+            for file in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+                let file = file.path();
+                if !is_executable(&file) {
+                    continue;
+                }
+                executables += 1;
+                if let Some(name) = command_name(&file) {
+                    commands.entry(name).or_default().push(file);
+                }
+            }
+        }
+        entries.push(PathEntry {
+            dir: dir.clone(),
+            exists,
+            duplicate_of,
+            executables,
+        });
+    }
+
+    let shadowed = commands
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .filter(|(_, paths)| {
+            // Links to one binary (e.g. /usr/bin/python -> python3.12) are harmless.
+            let mut targets: Vec<PathBuf> = paths
+                .iter()
+                .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.clone()))
+                .collect();
+            targets.sort();
+            targets.dedup();
+            targets.len() > 1
+        })
+        .map(|(name, paths)| ShadowedCommand {
+            name,
+            paths: paths
+                .iter()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect(),
+        })
+        .collect();
+
+    let missing_from_process = shell_path
+        .map(|shell_path| {
+            std::env::split_paths(shell_path)
+                .map(|p| p.to_string_lossy().into_owned())
+                .filter(|dir| !dir.is_empty() && !dirs.contains(dir))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PathAnalysis {
+        missing: entries
+            .iter()
+            .filter(|e| !e.exists)
+            .map(|e| e.dir.clone())
+            .collect(),
+        entries,
+        shadowed,
+        missing_from_process,
+    }
+}
+
+/// Capture and compare the app and login-shell environments and analyze
+/// `PATH`. `include_shell` defaults to `true`.
+#[tauri::command(async)]
+pub fn analyze_environment(include_shell: Option<bool>) -> EnvReport {
+    let process = process_env();
+    let shell = if include_shell.unwrap_or(true) {
+        Some(login_shell_env())
+    } else {
+        None
+    };
+    let (shell, shell_env, shell_error) = match shell {
+        Some(Ok((shell, env))) => (Some(shell), Some(env), None),
+        Some(Err(e)) => (None, None, Some(e)),
+        None => (None, None, None),
+    };
+
+    let path_var = if cfg!(windows) { "Path" } else { "PATH" };
+    let find_path = |env: &BTreeMap<String, String>| {
+        env.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(path_var))
+            .map(|(_, v)| v.clone())
+    };
+    let process_path = find_path(&process).unwrap_or_default();
+    let shell_path = shell_env.as_ref().and_then(find_path);
+
+    let mask_all = |env: &BTreeMap<String, String>| -> Vec<EnvVar> {
+        env.iter()
+            .map(|(name, value)| mask_env(name.clone(), value))
+            .collect()
+    };
+    EnvReport {
+        process_env: mask_all(&process),
+        diff: shell_env
+            .as_ref()
+            .map(|env| diff_env(&process, env))
+            .unwrap_or_default(),
+        shell_env: shell_env.as_ref().map(mask_all),
+        shell,
+        shell_error,
+        path: analyze_path(&process_path, shell_path.as_deref()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn diffs_environments_and_masks_values() {
+        let process = env(&[("HOME", "/home/dev"), ("PATH", "/usr/bin"), ("SHLVL", "1")]);
+        let shell = env(&[
+            ("HOME", "/home/dev"),
+            ("PATH", "/opt/homebrew/bin:/usr/bin"),
+            ("NPM_TOKEN", "abc"),
+            ("SHLVL", "2"),
+        ]);
+        let diff = diff_env(&process, &shell);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].name, "NPM_TOKEN");
+        assert_eq!(diff[0].change, EnvChange::Added);
+        assert_eq!(diff[0].shell_value.as_deref(), Some("[REDACTED]"));
+        assert_eq!(diff[1].name, "PATH");
+        assert_eq!(diff[1].change, EnvChange::Changed);
+
+        let parsed = parse_env_output(b"A=1\0B=x=y\0MULTI=line\nnext\0");
+        assert_eq!(parsed["B"], "x=y");
+        assert_eq!(parsed["MULTI"], "line\nnext");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finds_missing_duplicate_and_shadowed_entries() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        for dir in [&first, &second] {
+            let tool = dir.path().join("python");
+            std::fs::write(&tool, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let (a, b) = (
+            first.path().to_str().unwrap(),
+            second.path().to_str().unwrap(),
+        );
+        let path = format!("{a}:/does/not/exist:{b}:{a}");
+        let analysis = analyze_path(&path, Some(&format!("{a}:/opt/extra")));
+
+        assert_eq!(analysis.missing, ["/does/not/exist"]);
+        assert_eq!(analysis.entries[3].duplicate_of, Some(0));
+        assert_eq!(analysis.entries[0].executables, 1);
+        assert_eq!(analysis.shadowed.len(), 1);
+        assert_eq!(analysis.shadowed[0].name, "python");
+        assert_eq!(analysis.missing_from_process, ["/opt/extra"]);
+    }
+}
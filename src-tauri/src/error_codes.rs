@@ -0,0 +1,241 @@
+//! Stable error codes and the English message catalog
+//!
+//! Errors crossing the IPC boundary serialize as
+//! `{ code, message, params }` instead of a bare English string. `code` is
+//! a stable dotted identifier (`generator.ssh_key`, `network.invalid_port`)
+//! that the frontend can branch on or look up in its own translations;
+//! `params` carries the values substituted into the template; `message` is
+//! the rendered English text for callers that do not localize.
+//!
+//! [`CATALOG`] is the single source of English templates. Each template
+//! uses `{name}` placeholders matching the error's params.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// English message templates keyed by error code.
+pub const CATALOG: &[(&str, &str)] = &[
+    // Generators
+    ("generator.bcrypt", "BCrypt error: {detail}"),
+    ("generator.ssh_key", "SSH key generation error: {detail}"),
+    ("generator.gpg", "GPG key generation error: {detail}"),
+    ("generator.password_hash", "Password hash error: {detail}"),
+    ("generator.jwt", "JWT error: {detail}"),
+    ("generator.x509", "X.509 generation error: {detail}"),
+    ("generator.otp", "OTP error: {detail}"),
+    ("generator.symmetric", "Encryption error: {detail}"),
+    ("generator.signing", "Signing error: {detail}"),
+    ("generator.cli_execution", "CLI execution error: {detail}"),
+    ("generator.invalid_parameter", "Invalid parameter: {detail}"),
+    ("generator.worker", "Worker error: {detail}"),
+    ("generator.cancelled", "Operation cancelled"),
+    // AST
+    (
+        "ast.unsupported_language",
+        "Unsupported language: {language}",
+    ),
+    (
+        "ast.input_too_large",
+        "Document is too large to parse ({size} bytes, limit {limit})",
+    ),
+    ("ast.cancelled", "Parsing cancelled"),
+    ("ast.internal", "Parser failed: {detail}"),
+    ("ast.io", "Could not read the file: {detail}"),
+    (
+        "ast.file_changed",
+        "{path} changed since it was opened; open it again",
+    ),
+    (
+        "ast.not_collapsed",
+        "{path} is not a collapsed node of an open file",
+    ),
+    // Network
+    (
+        "network.invalid_range_format",
+        "Invalid range format: {range}",
+    ),
+    ("network.invalid_port", "Invalid port number: {port}"),
+    ("network.invalid_range", "Invalid range: {start} > {end}"),
+    ("network.port_zero", "Port 0 is not valid"),
+    ("network.no_ports", "No valid ports specified"),
+    (
+        "network.custom_preset_requires_range",
+        "Custom preset requires port_range",
+    ),
+    ("network.invalid_cidr", "Invalid CIDR notation: {detail}"),
+    (
+        "network.unresolved_host",
+        "Could not resolve hostname: {target}",
+    ),
+    ("network.invalid_zone", "{detail}"),
+    (
+        "network.zone_required",
+        "Link-local IPv6 targets need a zone ID (e.g. {target}%en0)",
+    ),
+    ("network.cancelled", "Scan cancelled"),
+];
+
+/// An error with a stable code and named parameters.
+pub trait ErrorCode {
+    /// Stable dotted identifier; must have an entry in [`CATALOG`].
+    fn code(&self) -> &'static str;
+
+    /// Values for the template placeholders.
+    fn params(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+/// Wire form of a coded error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodedError {
+    /// Stable error code.
+    pub code: &'static str,
+    /// Rendered English message.
+    pub message: String,
+    /// Template parameters.
+    pub params: BTreeMap<&'static str, String>,
+}
+
+impl CodedError {
+    /// Build the wire form of `error`.
+    pub fn from_error(error: &impl ErrorCode) -> Self {
+        let code = error.code();
+        let params: BTreeMap<&'static str, String> = error.params().into_iter().collect();
+        Self {
+            code,
+            message: render(code, &params),
+            params,
+        }
+    }
+}
+
+/// Render the English message for `code`, substituting `params`.
+///
+/// Unknown codes render as the code itself so a missing catalog entry is
+/// visible rather than silently empty.
+pub fn render(code: &str, params: &BTreeMap<&'static str, String>) -> String {
+    let Some((_, template)) = CATALOG.iter().find(|(c, _)| *c == code) else {
+        return code.to_string();
+    };
+    params
+        .iter()
+        .fold((*template).to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Serialize `error` as a [`CodedError`].
+///
+/// # Errors
+///
+/// Propagates serializer errors.
+pub fn serialize_coded<E, S>(error: &E, serializer: S) -> Result<S::Ok, S::Error>
+where
+    E: ErrorCode,
+    S: serde::ser::Serializer,
+{
+    CodedError::from_error(error).serialize(serializer)
+}
+
+/// Return the English message catalog keyed by error code, so the
+/// frontend can fall back to it for codes it has no translation for.
+#[tauri::command]
+pub fn get_error_catalog() -> BTreeMap<&'static str, &'static str> {
+    CATALOG.iter().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sample;
+
+    impl ErrorCode for Sample {
+        fn code(&self) -> &'static str {
+            "network.invalid_range"
+        }
+
+        fn params(&self) -> Vec<(&'static str, String)> {
+            vec![("start", "90".to_string()), ("end", "80".to_string())]
+        }
+    }
+
+    #[test]
+    fn renders_templates_and_serializes() {
+        let coded = CodedError::from_error(&Sample);
+        assert_eq!(coded.message, "Invalid range: 90 > 80");
+        let json = serde_json::to_value(&coded).unwrap();
+        assert_eq!(json["code"], "network.invalid_range");
+        assert_eq!(json["params"]["start"], "90");
+        assert_eq!(render("missing.code", &BTreeMap::new()), "missing.code");
+    }
+
+    #[test]
+    fn catalog_codes_are_unique() {
+        let catalog = get_error_catalog();
+        assert_eq!(catalog.len(), CATALOG.len());
+    }
+
+    fn assert_in_sync(error: &(impl ErrorCode + std::fmt::Display)) {
+        let coded = CodedError::from_error(error);
+        assert!(CATALOG.iter().any(|(code, _)| *code == coded.code));
+        assert_eq!(coded.message, error.to_string(), "{}", coded.code);
+    }
+
+    #[test]
+    fn catalog_matches_display_messages() {
+        use crate::ast::AstError;
+        use crate::generators::GeneratorError;
+        use crate::network::error::NetworkError;
+
+        let detail = || "boom".to_string();
+        for error in [
+            GeneratorError::Bcrypt(detail()),
+            GeneratorError::SshKey(detail()),
+            GeneratorError::Gpg(detail()),
+            GeneratorError::PasswordHash(detail()),
+            GeneratorError::Jwt(detail()),
+            GeneratorError::X509(detail()),
+            GeneratorError::Otp(detail()),
+            GeneratorError::Symmetric(detail()),
+            GeneratorError::Signing(detail()),
+            GeneratorError::CliExecution(detail()),
+            GeneratorError::InvalidParameter(detail()),
+            GeneratorError::Worker(detail()),
+            GeneratorError::Cancelled,
+        ] {
+            assert_in_sync(&error);
+        }
+        for error in [
+            AstError::UnsupportedLanguage("cobol".to_string()),
+            AstError::InputTooLarge {
+                size: 40_000_000,
+                limit: 33_554_432,
+            },
+            AstError::Cancelled,
+            AstError::Internal(detail()),
+            AstError::Io(detail()),
+            AstError::FileChanged("/tmp/big.json".to_string()),
+            AstError::NotCollapsed("$.items[3]".to_string()),
+        ] {
+            assert_in_sync(&error);
+        }
+        for error in [
+            NetworkError::InvalidRangeFormat("1-2-3".to_string()),
+            NetworkError::InvalidPort("http".to_string()),
+            NetworkError::InvalidRange { start: 90, end: 80 },
+            NetworkError::PortZero,
+            NetworkError::NoPorts,
+            NetworkError::CustomPresetRequiresRange,
+            NetworkError::InvalidCidr(detail()),
+            NetworkError::UnresolvedHost("nowhere.invalid".to_string()),
+            NetworkError::InvalidZone(detail()),
+            NetworkError::ZoneRequired("fe80::1".to_string()),
+            NetworkError::Cancelled,
+        ] {
+            assert_in_sync(&error);
+        }
+    }
+}
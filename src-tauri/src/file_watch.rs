@@ -5,22 +5,41 @@
 //! frontend stores so it can later request a graceful stop. Filesystem
 //! events are serialised into a normalised payload and emitted on the
 //! `file-watch-event` Tauri event channel.
+//!
+//! [`watch_file`] builds on the same registry to turn Kogu into a live
+//! validator: it re-runs a parse, validate, or convert step whenever a
+//! single file changes and emits the result on `file-watch-result`.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use notify::event::{ModifyKind, RenameMode};
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
+use crate::ast::{parse_to_ast, AstLanguage, AstParseError, AstSeverity};
+use crate::document::DocFormat;
+
 /// Tauri event name used for streaming filesystem changes to the
 /// frontend.
 const EVENT_NAME: &str = "file-watch-event";
 
+/// Tauri event name used for re-run results of [`watch_file`].
+const RESULT_EVENT_NAME: &str = "file-watch-result";
+
+/// Quiet period before a changed file is re-read. Editors often save
+/// with several writes or a write-then-rename, which this coalesces.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Largest file [`watch_file`] will re-read.
+const MAX_WATCHED_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
 /// Normalised classification for a filesystem event. The frontend
 /// filters and renders events by this discriminator instead of the
 /// platform-specific `notify::EventKind`.
@@ -49,6 +68,50 @@ struct WatchEventPayload {
     timestamp: i64,
 }
 
+/// Step re-run by [`watch_file`] on every change.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WatchTool {
+    /// Parse to an AST; the result carries the tree and any errors.
+    Parse {
+        /// Document language.
+        language: AstLanguage,
+    },
+    /// Parse and report errors only.
+    Validate {
+        /// Document language.
+        language: AstLanguage,
+    },
+    /// Convert between structured formats; the result carries the text.
+    Convert {
+        /// Format of the watched file.
+        from: DocFormat,
+        /// Output format.
+        to: DocFormat,
+    },
+}
+
+/// Payload sent each time [`watch_file`] re-runs its tool.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchResultPayload {
+    /// Watch identifier returned by [`watch_file`].
+    watch_id: String,
+    /// Watched file.
+    path: String,
+    /// Run counter, starting at 0 for the initial run.
+    revision: u64,
+    /// Run time as Unix milliseconds.
+    timestamp: i64,
+    /// Whether the file parsed (and converted) without errors.
+    valid: bool,
+    /// Parse, conversion, or read errors.
+    errors: Vec<AstParseError>,
+    /// AST for `parse`, converted text for `convert`, absent otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<Value>,
+}
+
 /// Application state holding every running watcher keyed by watch ID.
 #[derive(Default)]
 pub struct FileWatchState {
@@ -173,3 +236,172 @@ pub fn file_watch_stop(
         .remove(&watch_id);
     Ok(())
 }
+
+/// Read the watched file, refusing anything too large to re-parse on
+/// every keystroke-driven save.
+fn read_watched(path: &Path) -> Result<String, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+        .len();
+    if size > MAX_WATCHED_FILE_BYTES {
+        return Err(format!(
+            "File is too large to watch ({size} bytes, limit {MAX_WATCHED_FILE_BYTES})"
+        ));
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+/// Run `tool` on `text`, returning `(errors, output)`.
+fn evaluate(tool: WatchTool, text: &str) -> (Vec<AstParseError>, Option<Value>) {
+    match tool {
+        WatchTool::Parse { language } => {
+            let result = parse_to_ast(text, language);
+            let output = result.ast.and_then(|ast| serde_json::to_value(ast).ok());
+            (result.errors, output)
+        }
+        WatchTool::Validate { language } => (parse_to_ast(text, language).errors, None),
+        WatchTool::Convert { from, to } => {
+            match from.read(text).and_then(|v| to.write(&v, 2, false)) {
+                Ok(converted) => (Vec::new(), Some(Value::String(converted))),
+                Err(e) => (vec![AstParseError::new(e)], None),
+            }
+        }
+    }
+}
+
+/// Worker loop for one [`watch_file`] watcher. Runs once up front, then
+/// after every debounced change, skipping saves that left the content
+/// unchanged. Exits when the watcher (and with it the sender) is dropped.
+fn run_watch_loop(
+    changes: &Receiver<()>,
+    app: &AppHandle,
+    watch_id: &str,
+    path: &Path,
+    tool: WatchTool,
+) {
+    let mut last: Option<Result<String, String>> = None;
+    let mut revision = 0;
+    loop {
+        let current = read_watched(path);
+        if last.as_ref() != Some(&current) {
+            let (errors, output) = match &current {
+                Ok(text) => evaluate(tool, text),
+                Err(e) => (vec![AstParseError::new(e.clone())], None),
+            };
+            let payload = WatchResultPayload {
+                watch_id: watch_id.to_string(),
+                path: path.to_string_lossy().into_owned(),
+                revision,
+                timestamp: unix_ms_now(),
+                valid: !errors.iter().any(|e| e.severity == AstSeverity::Error),
+                errors,
+                output,
+            };
+            let _ = app.emit(RESULT_EVENT_NAME, payload);
+            revision += 1;
+            last = Some(current);
+        }
+
+        if changes.recv().is_err() {
+            return;
+        }
+        while changes.recv_timeout(DEBOUNCE).is_ok() {}
+    }
+}
+
+/// Watch a single file and re-run `tool` on it whenever it changes,
+/// emitting each result on `file-watch-result`. The first result is
+/// emitted right away. Stop with [`file_watch_stop`].
+///
+/// The parent directory is watched rather than the file itself so that
+/// editors which save by writing a temporary file and renaming it over
+/// the original keep triggering updates.
+///
+/// # Errors
+///
+/// Returns a stringified error when `path` is not a file or the watcher
+/// cannot be started.
+#[tauri::command]
+pub fn watch_file(
+    path: String,
+    tool: WatchTool,
+    app: AppHandle,
+    state: tauri::State<'_, FileWatchState>,
+) -> Result<String, String> {
+    let target =
+        std::fs::canonicalize(&path).map_err(|e| format!("Path does not exist: {path} ({e})"))?;
+    if !target.is_file() {
+        return Err(format!("Not a file: {path}"));
+    }
+    let (Some(dir), Some(file_name)) = (target.parent(), target.file_name()) else {
+        return Err(format!("Not a file: {path}"));
+    };
+    let file_name = file_name.to_os_string();
+
+    let watch_id = Uuid::new_v4().to_string();
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        let touches_file = event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == Some(file_name.as_os_str()));
+        if touches_file && classify(event.kind).is_some() {
+            let _ = tx.send(());
+        }
+    })
+    .map_err(|e| format!("Failed to create watcher: {e}"))?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to start watching {path}: {e}"))?;
+
+    let loop_id = watch_id.clone();
+    std::thread::Builder::new()
+        .name("kogu-watch-file".to_string())
+        .spawn(move || run_watch_loop(&rx, &app, &loop_id, &target, tool))
+        .map_err(|e| format!("Failed to start watch thread: {e}"))?;
+
+    state
+        .watchers
+        .lock()
+        .map_err(|e| format!("Watcher registry lock poisoned: {e}"))?
+        .insert(watch_id.clone(), watcher);
+
+    Ok(watch_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_each_tool() {
+        let language = AstLanguage::Json;
+        let (errors, output) = evaluate(WatchTool::Parse { language }, r#"{"a": 1}"#);
+        assert!(errors.is_empty());
+        assert!(output.is_some());
+
+        let (errors, output) = evaluate(WatchTool::Validate { language }, r#"{"a": "#);
+        assert!(!errors.is_empty());
+        assert!(output.is_none());
+
+        let tool = WatchTool::Convert {
+            from: DocFormat::Yaml,
+            to: DocFormat::Json,
+        };
+        let (errors, output) = evaluate(tool, "a: 1\n");
+        assert!(errors.is_empty());
+        assert_eq!(output, Some(Value::String("{\n  \"a\": 1\n}".to_string())));
+    }
+
+    #[test]
+    fn refuses_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read_watched(&dir.path().join("missing.json")).is_err());
+        let file = dir.path().join("config.json");
+        std::fs::write(&file, "{}").unwrap();
+        assert_eq!(read_watched(&file).unwrap(), "{}");
+    }
+}
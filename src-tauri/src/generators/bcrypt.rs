@@ -63,6 +63,7 @@ pub async fn generate_hash_isolated(
     password: String,
     cost: u32,
     state: &WorkerProcessState,
+    job_id: Option<String>,
 ) -> Result<BcryptHashResult, GeneratorError> {
     if !(MIN_COST..=MAX_COST).contains(&cost) {
         return Err(GeneratorError::InvalidParameter(format!(
@@ -71,7 +72,7 @@ pub async fn generate_hash_isolated(
     }
 
     let request = BcryptHashRequest::new(password, cost);
-    let response: BcryptHashResponse = worker::execute(app, &request, state).await?;
+    let response: BcryptHashResponse = worker::execute(app, &request, state, job_id).await?;
 
     if response.success {
         Ok(BcryptHashResult {
@@ -96,9 +97,10 @@ pub async fn verify_hash_isolated(
     password: String,
     hash: String,
     state: &WorkerProcessState,
+    job_id: Option<String>,
 ) -> Result<BcryptVerifyResult, GeneratorError> {
     let request = BcryptVerifyRequest::new(password, hash);
-    let response: BcryptVerifyResponse = worker::execute(app, &request, state).await?;
+    let response: BcryptVerifyResponse = worker::execute(app, &request, state, job_id).await?;
 
     if response.success {
         Ok(BcryptVerifyResult {
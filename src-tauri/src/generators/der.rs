@@ -0,0 +1,182 @@
+//! Minimal PEM and DER helpers shared by the key tools
+//!
+//! Just enough to take apart and assemble the PKCS#8, SEC1, and SPKI
+//! structures that `ring` and `ssh-key` do not read or write themselves.
+//! RSA structures go through the `rsa` crate and certificates through
+//! `x509-parser`.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use std::fmt::Write as _;
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OID: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+
+/// rsaEncryption, 1.2.840.113549.1.1.1
+pub const OID_RSA_ENCRYPTION: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01];
+/// id-ecPublicKey, 1.2.840.10045.2.1
+pub const OID_EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+/// prime256v1 (P-256), 1.2.840.10045.3.1.7
+pub const OID_P256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+/// secp384r1 (P-384), 1.3.132.0.34
+pub const OID_P384: &[u8] = &[0x2B, 0x81, 0x04, 0x00, 0x22];
+/// id-Ed25519, 1.3.101.112
+pub const OID_ED25519: &[u8] = &[0x2B, 0x65, 0x70];
+
+/// SEC1 `ECPrivateKey` (RFC 5915) fields
+pub struct Sec1PrivateKey<'a> {
+    /// Big-endian private scalar
+    pub private: &'a [u8],
+    /// Curve OID, absent when the key sits inside PKCS#8
+    pub curve: Option<&'a [u8]>,
+    /// Uncompressed public point, if present
+    pub public: Option<&'a [u8]>,
+}
+
+/// Label and DER body of the first PEM block in `text`
+pub fn pem_decode(text: &str) -> Option<(String, Vec<u8>)> {
+    let begin = text.find("-----BEGIN ")?;
+    let rest = &text[begin + "-----BEGIN ".len()..];
+    let (label, rest) = rest.split_once("-----")?;
+    let end = rest.find(&format!("-----END {label}-----"))?;
+    let body: String = rest[..end]
+        .lines()
+        .filter(|line| !line.contains(':'))
+        .flat_map(str::split_whitespace)
+        .collect();
+    let der = STANDARD.decode(body).ok()?;
+    Some((label.to_string(), der))
+}
+
+/// PEM armor with 64-column lines and a trailing newline
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+    let body = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {label}-----\n");
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    let _ = writeln!(pem, "-----END {label}-----");
+    pem
+}
+
+/// Tag, content, and the bytes after one DER element
+pub fn tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (usize::from(first), rest)
+    } else {
+        let count = usize::from(first & 0x7F);
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | usize::from(b));
+        (len, &rest[count..])
+    };
+    (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+}
+
+/// Content and the bytes after a DER element that must have `tag`
+pub fn element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    tlv(input).and_then(|(found, content, rest)| (found == tag).then_some((content, rest)))
+}
+
+/// Content of a leading SEQUENCE
+pub fn sequence(input: &[u8]) -> Option<&[u8]> {
+    element(input, SEQUENCE).map(|(content, _)| content)
+}
+
+/// One DER element
+#[allow(clippy::cast_possible_truncation)] // a usize length has at most 8 bytes
+pub fn write(tag: u8, content: &[u8]) -> Vec<u8> {
+    let len = content.len().to_be_bytes();
+    let significant = &len[len.iter().take_while(|&&b| b == 0).count()..];
+    let mut out = vec![tag];
+    match significant {
+        [] => out.push(0),
+        [short] if *short < 0x80 => out.push(*short),
+        long => {
+            out.push(0x80 | long.len() as u8);
+            out.extend_from_slice(long);
+        }
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// BIT STRING with no unused bits
+pub fn write_bits(content: &[u8]) -> Vec<u8> {
+    write(BIT_STRING, &[&[0], content].concat())
+}
+
+pub fn parse_sec1(der: &[u8]) -> Option<Sec1PrivateKey<'_>> {
+    let body = sequence(der)?;
+    let (_version, rest) = element(body, INTEGER)?;
+    let (private, mut rest) = element(rest, OCTET_STRING)?;
+    let mut key = Sec1PrivateKey {
+        private,
+        curve: None,
+        public: None,
+    };
+    while !rest.is_empty() {
+        let (tag, content, next) = tlv(rest)?;
+        match tag {
+            0xA0 => key.curve = Some(element(content, OID)?.0),
+            // Skip the unused-bits byte of the BIT STRING.
+            0xA1 => key.public = element(content, BIT_STRING)?.0.get(1..),
+            _ => {}
+        }
+        rest = next;
+    }
+    Some(key)
+}
+
+/// SEC1 `ECPrivateKey`, naming its curve only when `curve` is given
+pub fn write_sec1(private: &[u8], curve: Option<&[u8]>, public: &[u8]) -> Vec<u8> {
+    let mut body = write(INTEGER, &[1]);
+    body.extend(write(OCTET_STRING, private));
+    if let Some(curve) = curve {
+        body.extend(write(0xA0, &write(OID, curve)));
+    }
+    body.extend(write(0xA1, &write_bits(public)));
+    write(SEQUENCE, &body)
+}
+
+/// `AlgorithmIdentifier`; `parameter` is an OID when present
+pub fn write_algorithm(oid: &[u8], parameter: Option<&[u8]>) -> Vec<u8> {
+    let mut body = write(OID, oid);
+    if let Some(parameter) = parameter {
+        body.extend(write(OID, parameter));
+    }
+    write(SEQUENCE, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_read_round_trip() {
+        let long = vec![0xAB; 300];
+        let der = write(
+            SEQUENCE,
+            &[write(INTEGER, &[1]), write(OCTET_STRING, &long)].concat(),
+        );
+        assert_eq!(&der[..4], &[0x30, 0x82, 0x01, 0x33]);
+        let body = sequence(&der).unwrap();
+        let (version, rest) = element(body, INTEGER).unwrap();
+        assert_eq!(version, &[1]);
+        assert_eq!(element(rest, OCTET_STRING).unwrap().0, long.as_slice());
+        assert!(element(rest, INTEGER).is_none());
+
+        let pem = pem_encode("TEST", &der);
+        assert!(pem.lines().all(|line| line.len() <= 64));
+        assert_eq!(pem_decode(&pem), Some(("TEST".to_string(), der)));
+    }
+}
@@ -1,12 +1,20 @@
 //! GPG/PGP key generation with CLI, library, and process isolation support
+//!
+//! Also encrypts, decrypts, signs, and verifies armored PGP messages
+//! in the worker process, and lists the user IDs and subkeys of a pasted
+//! key block.
 
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
-use super::worker::{self, GpgKeyRequest, GpgKeyResponse, WorkerProcessState};
+use super::worker::{
+    self, GpgKeyRequest, GpgKeyResponse, PgpMessageRequest, PgpMessageResponse, WorkerProcessState,
+};
 use super::{GenerationMethod, GeneratorError};
 
+use std::io::Write;
 #[cfg(test)]
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 /// GPG key algorithm options
@@ -75,6 +83,10 @@ pub struct GpgKeyOptions {
     /// Generation method (CLI or Library)
     #[serde(default)]
     pub method: GenerationMethod,
+    /// Also import the generated key into the user's keyring with the
+    /// `gpg` CLI
+    #[serde(default)]
+    pub import_to_keyring: bool,
 }
 
 /// Result of GPG key generation
@@ -98,6 +110,117 @@ pub struct GpgKeyResult {
     pub method_used: String,
 }
 
+/// PGP message operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgpMessageOperation {
+    /// Encrypt to a public key
+    Encrypt,
+    /// Decrypt with a secret key
+    Decrypt,
+    /// Sign into a cleartext signed message
+    Clearsign,
+    /// Sign into a detached signature
+    DetachSign,
+    /// Verify a cleartext signed, signed, or detached-signed message
+    Verify,
+}
+
+impl PgpMessageOperation {
+    /// Name of the operation in the worker request
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Encrypt => "encrypt",
+            Self::Decrypt => "decrypt",
+            Self::Clearsign => "clearsign",
+            Self::DetachSign => "detach_sign",
+            Self::Verify => "verify",
+        }
+    }
+}
+
+/// Options for a PGP message operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgpMessageOptions {
+    /// Text to encrypt, sign, or verify, or the armored message to decrypt
+    /// or verify
+    pub message: String,
+    /// Armored public key (encrypt, verify) or secret key (decrypt, sign)
+    pub key: String,
+    /// Passphrase of the secret key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<String>,
+    /// Armored detached signature over `message` (verify only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// Result of a PGP message operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgpMessageResult {
+    /// Armored message or signature, decrypted text, or the verified text
+    pub output: String,
+    /// Whether the signature is good (verify only)
+    pub valid: Option<bool>,
+    /// Fingerprint of the key encrypted to, signed with, or that made the
+    /// verified signature
+    pub fingerprint: Option<String>,
+}
+
+/// Capability of a PGP key, from the key flags of its self-signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PgpKeyCapability {
+    Certify,
+    Sign,
+    Encrypt,
+    Authenticate,
+}
+
+/// User ID of a PGP key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgpUserId {
+    /// User ID, usually "Name (Comment) <email>"
+    pub user_id: String,
+    /// Whether this is the primary user ID, as `gpg` picks it
+    pub primary: bool,
+    /// Whether the key owner revoked this user ID
+    pub revoked: bool,
+}
+
+/// Primary key or subkey of a PGP key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgpKeyPart {
+    /// Key type, e.g. "RSA", "ECDSA NIST P-256", or "Ed25519"
+    pub algorithm: String,
+    /// Key size in bits
+    pub bits: usize,
+    /// Fingerprint in groups of four hex digits
+    pub fingerprint: String,
+    /// Long key ID
+    pub key_id: String,
+    /// Creation time in Unix seconds
+    pub created: u64,
+    /// Expiry in Unix seconds; absent when the key does not expire
+    pub expires: Option<u64>,
+    pub capabilities: Vec<PgpKeyCapability>,
+    pub revoked: bool,
+}
+
+/// Details of a pasted PGP key block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgpKeyInfo {
+    /// Whether the block holds the secret key
+    pub is_private: bool,
+    /// Whether the secret key is passphrase-protected
+    pub encrypted: bool,
+    pub user_ids: Vec<PgpUserId>,
+    pub primary_key: PgpKeyPart,
+    pub subkeys: Vec<PgpKeyPart>,
+    /// Whether every self-signature binding the user IDs and subkeys to
+    /// the primary key verifies
+    pub signatures_valid: bool,
+}
+
 // =============================================================================
 // Synchronous Operations (for testing only)
 // =============================================================================
@@ -123,7 +246,64 @@ pub fn generate_key(options: GpgKeyOptions) -> Result<GpgKeyResult, GeneratorErr
     }
 }
 
+/// Temporary GNUPGHOME, removed together with its agent on drop
+#[cfg(test)]
+struct EphemeralGnupgHome {
+    path: PathBuf,
+}
+
+#[cfg(test)]
+impl EphemeralGnupgHome {
+    /// Create a private, empty home directory under the system temp dir
+    fn create() -> Result<Self, GeneratorError> {
+        let path = std::env::temp_dir().join(format!(
+            "kogu-gnupg-{}-{:016x}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let mut builder = std::fs::DirBuilder::new();
+        // gpg refuses to use a home directory other users can read.
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(&path).map_err(|e| {
+            GeneratorError::CliExecution(format!("Failed to create GNUPGHOME: {e}"))
+        })?;
+        Ok(Self { path })
+    }
+
+    /// `gpg --batch` bound to this home
+    fn gpg(&self) -> Command {
+        let mut cmd = Command::new("gpg");
+        cmd.arg("--homedir").arg(&self.path).arg("--batch");
+        cmd.stdin(Stdio::null());
+        cmd
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+impl Drop for EphemeralGnupgHome {
+    fn drop(&mut self) {
+        // Generation starts a gpg-agent for this home; stop it before the
+        // directory (and its socket) disappears.
+        let _ = Command::new("gpgconf")
+            .arg("--homedir")
+            .arg(&self.path)
+            .args(["--kill", "gpg-agent"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
 /// Generate GPG key pair using gpg CLI
+///
+/// The key is generated and exported in a throwaway GNUPGHOME so the
+/// user's keyring is untouched unless `import_to_keyring` is set.
 #[cfg(test)]
 fn generate_with_cli(options: GpgKeyOptions) -> Result<GpgKeyResult, GeneratorError> {
     // Destructure to consume ownership
@@ -134,6 +314,7 @@ fn generate_with_cli(options: GpgKeyOptions) -> Result<GpgKeyResult, GeneratorEr
         algorithm,
         passphrase,
         method: _,
+        import_to_keyring,
     } = options;
 
     // Build user_id string
@@ -148,27 +329,25 @@ fn generate_with_cli(options: GpgKeyOptions) -> Result<GpgKeyResult, GeneratorEr
         passphrase.as_deref(),
     );
 
-    // Create temporary batch file
-    let temp_dir = std::env::temp_dir();
-    let batch_file = temp_dir.join(format!("kogu_gpg_batch_{}", std::process::id()));
+    let home = EphemeralGnupgHome::create()?;
 
+    // The batch file holds the passphrase, so it lives in the private home
+    // and goes away with it.
+    let batch_file = home.path().join("keygen.batch");
     std::fs::write(&batch_file, &batch_content)
         .map_err(|e| GeneratorError::CliExecution(format!("Failed to create batch file: {e}")))?;
 
     // Generate key using batch mode
-    let mut cmd = Command::new("gpg");
-    cmd.args(["--batch", "--gen-key"]);
+    let mut cmd = home.gpg();
+    cmd.arg("--gen-key");
     cmd.arg(&batch_file);
-    cmd.stdin(Stdio::null());
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
-    let output = cmd.output().map_err(|e| {
-        let _ = std::fs::remove_file(&batch_file);
-        GeneratorError::CliExecution(format!("Failed to execute gpg: {e}"))
-    })?;
+    let output = cmd
+        .output()
+        .map_err(|e| GeneratorError::CliExecution(format!("Failed to execute gpg: {e}")))?;
 
-    // Cleanup batch file
     let _ = std::fs::remove_file(&batch_file);
 
     if !output.status.success() {
@@ -178,18 +357,20 @@ fn generate_with_cli(options: GpgKeyOptions) -> Result<GpgKeyResult, GeneratorEr
         )));
     }
 
-    // Extract fingerprint from output
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let fingerprint = extract_fingerprint_from_output(&stderr).unwrap_or_else(|| {
-        // Try to get fingerprint from gpg --list-keys
-        get_fingerprint_by_email(&email).unwrap_or_else(|| "Unknown".to_string())
-    });
+    // The home holds only the new key, so its fingerprint is unambiguous.
+    let fingerprint = get_fingerprint(&home).ok_or_else(|| {
+        GeneratorError::CliExecution("Generated key not found in GNUPGHOME".to_string())
+    })?;
 
     // Export public key
-    let public_key = export_key(&email, false)?;
+    let public_key = export_key(&home, &fingerprint, false, passphrase.as_deref())?;
 
     // Export private key
-    let private_key = export_key(&email, true)?;
+    let private_key = export_key(&home, &fingerprint, true, passphrase.as_deref())?;
+
+    if import_to_keyring {
+        import_key(&private_key, passphrase.as_deref())?;
+    }
 
     // Build batch command for display
     let gpg_command_batch = build_batch_command_from_parts(
@@ -226,6 +407,7 @@ fn generate_with_library(options: GpgKeyOptions) -> Result<GpgKeyResult, Generat
         algorithm,
         passphrase,
         method: _,
+        import_to_keyring: _,
     } = options;
 
     // Build user_id string
@@ -371,19 +553,29 @@ fn build_batch_command_from_parts(
     format!("gpg --batch --gen-key <<'EOF'\n{batch_content}\nEOF")
 }
 
-/// Export a key by email
+/// Export a key by fingerprint from the ephemeral home
 #[cfg(test)]
-fn export_key(email: &str, secret: bool) -> Result<String, GeneratorError> {
-    let mut cmd = Command::new("gpg");
+fn export_key(
+    home: &EphemeralGnupgHome,
+    fingerprint: &str,
+    secret: bool,
+    passphrase: Option<&str>,
+) -> Result<String, GeneratorError> {
+    let mut cmd = home.gpg();
     cmd.args(["--armor"]);
 
     if secret {
+        // Exporting a protected secret key asks for its passphrase; supply
+        // it directly instead of through a pinentry dialog.
+        if let Some(pass) = passphrase.filter(|p| !p.is_empty()) {
+            cmd.args(["--pinentry-mode", "loopback", "--passphrase", pass]);
+        }
         cmd.arg("--export-secret-keys");
     } else {
         cmd.arg("--export");
     }
 
-    cmd.arg(email);
+    cmd.arg(fingerprint);
 
     let output = cmd
         .output()
@@ -399,62 +591,103 @@ fn export_key(email: &str, secret: bool) -> Result<String, GeneratorError> {
     }
 }
 
-/// Extract fingerprint from gpg output
-#[cfg(test)]
-fn extract_fingerprint_from_output(output: &str) -> Option<String> {
-    // Look for fingerprint in gpg output
-    for line in output.lines() {
-        if line.contains("key") && line.contains("marked as ultimately trusted") {
-            // Extract key ID from line like "gpg: key ABCD1234 marked as ultimately trusted"
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            for (i, part) in parts.iter().enumerate() {
-                if *part == "key" && i + 1 < parts.len() {
-                    return Some(parts[i + 1].to_string());
-                }
-            }
+/// Import an armored key into the user's own keyring
+///
+/// gpg-agent needs the passphrase to take over a protected secret key. It
+/// goes in on stdin, never on the command line, so the key itself is read
+/// from a private temporary file; that file only ever holds the key in
+/// its passphrase-protected form.
+fn import_key(armored: &str, passphrase: Option<&str>) -> Result<(), GeneratorError> {
+    let import_error =
+        |e: std::io::Error| GeneratorError::CliExecution(format!("Failed to import key: {e}"));
+    let mut cmd = Command::new("gpg");
+    cmd.args(["--batch", "--import"]);
+    let (key_file, stdin_data) = match passphrase.filter(|p| !p.is_empty()) {
+        Some(pass) => {
+            let path = std::env::temp_dir().join(format!(
+                "kogu-gpg-import-{}-{:016x}.asc",
+                std::process::id(),
+                rand::random::<u64>()
+            ));
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            options
+                .open(&path)
+                .and_then(|mut file| file.write_all(armored.as_bytes()))
+                .map_err(import_error)?;
+            cmd.args(["--pinentry-mode", "loopback", "--passphrase-fd", "0"])
+                .arg(&path);
+            (Some(path), format!("{pass}\n"))
         }
+        None => (None, armored.to_string()),
+    };
+
+    let result = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(stdin_data.as_bytes())?;
+            }
+            child.wait_with_output()
+        });
+    if let Some(path) = key_file {
+        let _ = std::fs::remove_file(path);
+    }
+    let output = result.map_err(import_error)?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(GeneratorError::CliExecution(format!(
+            "Failed to import key: {stderr}"
+        )))
     }
-    None
 }
 
-/// Get fingerprint by email using gpg --list-keys
+/// Fingerprint of the only secret key in the ephemeral home
 #[cfg(test)]
-fn get_fingerprint_by_email(email: &str) -> Option<String> {
-    let output = Command::new("gpg")
-        .args(["--list-keys", "--fingerprint", email])
+fn get_fingerprint(home: &EphemeralGnupgHome) -> Option<String> {
+    let output = home
+        .gpg()
+        .args(["--list-secret-keys", "--with-colons"])
         .output()
         .ok()?;
 
     if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            let trimmed = line.trim();
-            // Fingerprint line looks like: "Key fingerprint = XXXX XXXX XXXX..."
-            if trimmed.starts_with("Key fingerprint")
-                || trimmed
-                    .chars()
-                    .all(|c| c.is_ascii_hexdigit() || c.is_whitespace())
-            {
-                let fp: String = trimmed.chars().filter(char::is_ascii_hexdigit).collect();
-                if fp.len() >= 16 {
-                    return Some(fp);
-                }
-            }
-        }
+        fingerprint_from_colons(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        None
     }
-    None
 }
 
-/// Format fingerprint with spaces for readability
+/// Primary key fingerprint from `--with-colons` listing output
+///
+/// The first `fpr` record after the `sec`/`pub` record belongs to the
+/// primary key; later ones are subkeys.
 #[cfg(test)]
-fn format_fingerprint(fingerprint: &str) -> String {
-    fingerprint
-        .chars()
-        .collect::<Vec<_>>()
-        .chunks(4)
-        .map(|chunk| chunk.iter().collect::<String>())
-        .collect::<Vec<_>>()
-        .join(" ")
+fn fingerprint_from_colons(listing: &str) -> Option<String> {
+    let mut in_primary = false;
+    for line in listing.lines() {
+        let mut fields = line.split(':');
+        match fields.next() {
+            Some("sec" | "pub") => in_primary = true,
+            Some("fpr") if in_primary => {
+                return fields
+                    .nth(8)
+                    .filter(|fp| !fp.is_empty())
+                    .map(str::to_string);
+            }
+            Some("ssb" | "sub") => in_primary = false,
+            _ => {}
+        }
+    }
+    None
 }
 
 // =============================================================================
@@ -468,6 +701,7 @@ pub async fn generate_key_isolated(
     app: &AppHandle,
     options: GpgKeyOptions,
     state: &WorkerProcessState,
+    job_id: Option<String>,
 ) -> Result<GpgKeyResult, GeneratorError> {
     // Validate input before sending to worker
     if options.name.trim().is_empty() {
@@ -489,6 +723,9 @@ pub async fn generate_key_isolated(
         GpgKeyAlgorithm::EcdsaP384 => "ecdsa_p384",
     };
 
+    let import_passphrase = options
+        .import_to_keyring
+        .then(|| options.passphrase.clone());
     let request = GpgKeyRequest::new(
         options.name,
         options.email,
@@ -496,9 +733,15 @@ pub async fn generate_key_isolated(
         algorithm_str,
         options.passphrase,
     );
-    let response: GpgKeyResponse = worker::execute(app, &request, state).await?;
+    let response: GpgKeyResponse = worker::execute(app, &request, state, job_id).await?;
 
     if response.success {
+        if let Some(passphrase) = import_passphrase {
+            let private_key = response.private_key.clone().unwrap_or_default();
+            tokio::task::spawn_blocking(move || import_key(&private_key, passphrase.as_deref()))
+                .await
+                .map_err(|e| GeneratorError::CliExecution(format!("Key import failed: {e}")))??;
+        }
         Ok(GpgKeyResult {
             algorithm: response.algorithm.unwrap_or_default(),
             user_id: response.user_id.unwrap_or_default(),
@@ -522,6 +765,237 @@ pub async fn generate_key_isolated(
     }
 }
 
+/// Encrypt, decrypt, sign, or verify a PGP message using process
+/// isolation
+///
+/// Decrypting and signing unlock the secret key, whose passphrase
+/// derivation can take a while, so these run in the worker like key
+/// generation.
+pub async fn process_message_isolated(
+    app: &AppHandle,
+    options: PgpMessageOptions,
+    operation: PgpMessageOperation,
+    state: &WorkerProcessState,
+    job_id: Option<String>,
+) -> Result<PgpMessageResult, GeneratorError> {
+    if options.key.trim().is_empty() {
+        return Err(GeneratorError::InvalidParameter(
+            "A key is required".to_string(),
+        ));
+    }
+    if options.message.is_empty() {
+        return Err(GeneratorError::InvalidParameter(
+            "A message is required".to_string(),
+        ));
+    }
+
+    let request = PgpMessageRequest::new(operation.as_str(), &options);
+    let response: PgpMessageResponse = worker::execute(app, &request, state, job_id).await?;
+
+    if response.success {
+        Ok(PgpMessageResult {
+            output: response.output.unwrap_or_default(),
+            valid: response.valid,
+            fingerprint: response.fingerprint,
+        })
+    } else {
+        Err(GeneratorError::Gpg(
+            response
+                .error
+                .unwrap_or_else(|| "Unknown error".to_string()),
+        ))
+    }
+}
+
+// =============================================================================
+// Key Inspection
+// =============================================================================
+
+/// Parse an armored public or private key block, as `gpg --show-keys`
+///
+/// Only the first key of the block is read. Expiry and capabilities come
+/// from the newest self-signature of the primary key or subkey.
+pub fn parse_key(armored: &str) -> Result<PgpKeyInfo, GeneratorError> {
+    use pgp::composed::{Deserializable, SignedPublicKey, SignedSecretKey};
+    use pgp::packet::SignatureType;
+    use pgp::types::KeyDetails;
+
+    let invalid = |e: pgp::errors::Error| GeneratorError::Gpg(format!("Not a PGP key: {e}"));
+    let (key, is_private, encrypted) = if armored.contains("BEGIN PGP PRIVATE KEY BLOCK") {
+        let (key, _) = SignedSecretKey::from_string(armored).map_err(invalid)?;
+        let encrypted = key.primary_key.secret_params().is_encrypted();
+        (key.to_public_key(), true, encrypted)
+    } else {
+        let (key, _) = SignedPublicKey::from_string(armored).map_err(invalid)?;
+        (key, false, false)
+    };
+
+    let primary = &key.primary_key;
+    let is_self = |signature: &&pgp::packet::Signature| {
+        signature
+            .issuer_key_id()
+            .contains(&&primary.legacy_key_id())
+            || signature
+                .issuer_fingerprint()
+                .contains(&&primary.fingerprint())
+    };
+    let revoked_by = |signatures: &[pgp::packet::Signature], typ: SignatureType| {
+        signatures
+            .iter()
+            .filter(is_self)
+            .any(|signature| signature.typ() == Some(typ))
+    };
+
+    let mut user_ids: Vec<PgpUserId> = key
+        .details
+        .users
+        .iter()
+        .map(|user| PgpUserId {
+            user_id: String::from_utf8_lossy(user.id.id()).into_owned(),
+            primary: user.is_primary(),
+            revoked: revoked_by(&user.signatures, SignatureType::CertRevocation),
+        })
+        .collect();
+    // Without a primary flag, gpg shows the first valid user ID first.
+    if !user_ids.iter().any(|user| user.primary) {
+        if let Some(user) = user_ids.iter_mut().find(|user| !user.revoked) {
+            user.primary = true;
+        }
+    }
+
+    let self_signature = key
+        .details
+        .direct_signatures
+        .iter()
+        .chain(key.details.users.iter().flat_map(|user| &user.signatures))
+        .filter(is_self)
+        .filter(|signature| signature.typ() != Some(SignatureType::CertRevocation))
+        .max_by_key(|signature| signature.created());
+    let primary_key = key_part(
+        primary,
+        self_signature,
+        !key.details.revocation_signatures.is_empty(),
+    );
+
+    let subkeys = key
+        .public_subkeys
+        .iter()
+        .map(|subkey| {
+            let binding = subkey
+                .signatures
+                .iter()
+                .filter(|signature| signature.typ() == Some(SignatureType::SubkeyBinding))
+                .max_by_key(|signature| signature.created());
+            let revoked = revoked_by(&subkey.signatures, SignatureType::SubkeyRevocation);
+            key_part(&subkey.key, binding, revoked)
+        })
+        .collect();
+
+    Ok(PgpKeyInfo {
+        is_private,
+        encrypted,
+        user_ids,
+        primary_key,
+        subkeys,
+        signatures_valid: key.verify_bindings().is_ok(),
+    })
+}
+
+fn key_part(
+    key: &impl pgp::types::KeyDetails,
+    self_signature: Option<&pgp::packet::Signature>,
+    revoked: bool,
+) -> PgpKeyPart {
+    let (algorithm, bits) = key_algorithm(key.public_params());
+    let created = u64::from(key.created_at().as_secs());
+    let flags = self_signature.map(pgp::packet::Signature::key_flags);
+    let mut capabilities = flags.map_or_else(Vec::new, |flags| {
+        [
+            (flags.certify(), PgpKeyCapability::Certify),
+            (flags.sign(), PgpKeyCapability::Sign),
+            (
+                flags.encrypt_comms() || flags.encrypt_storage(),
+                PgpKeyCapability::Encrypt,
+            ),
+            (flags.authentication(), PgpKeyCapability::Authenticate),
+        ]
+        .into_iter()
+        .filter_map(|(set, capability)| set.then_some(capability))
+        .collect()
+    });
+    // Keys from before key flags existed can do what their algorithm can.
+    if capabilities.is_empty() {
+        if key.algorithm().can_sign() {
+            capabilities.push(PgpKeyCapability::Sign);
+        }
+        if key.algorithm().can_encrypt() {
+            capabilities.push(PgpKeyCapability::Encrypt);
+        }
+    }
+
+    PgpKeyPart {
+        algorithm,
+        bits,
+        fingerprint: format_fingerprint(&format!("{:X}", key.fingerprint())),
+        key_id: hex::encode_upper(key.legacy_key_id()),
+        created,
+        expires: self_signature
+            .and_then(pgp::packet::Signature::key_expiration_time)
+            .map(|lifetime| u64::from(lifetime.as_secs()))
+            .filter(|&lifetime| lifetime > 0)
+            .map(|lifetime| created + lifetime),
+        capabilities,
+        revoked,
+    }
+}
+
+/// Display name and size of a key's algorithm
+fn key_algorithm(params: &pgp::types::PublicParams) -> (String, usize) {
+    use pgp::crypto::ecc_curve::ECCCurve;
+    use pgp::ser::Serialize as _;
+    use pgp::types::PublicParams;
+
+    let curve = |prefix: &str, curve: ECCCurve| {
+        let name = match curve {
+            ECCCurve::Curve25519Legacy => "Curve25519",
+            ECCCurve::Ed25519Legacy => "Ed25519",
+            ref other => other.name(),
+        };
+        (format!("{prefix}{name}"), usize::from(curve.nbits()))
+    };
+    // RSA, DSA, and ElGamal parameters start with the modulus or prime as
+    // an MPI, whose two-byte header is its length in bits.
+    let modulus_bits = || match params.to_bytes().as_deref() {
+        Ok([high, low, ..]) => usize::from(u16::from_be_bytes([*high, *low])),
+        _ => 0,
+    };
+
+    match params {
+        PublicParams::RSA(_) => ("RSA".to_string(), modulus_bits()),
+        PublicParams::DSA(_) => ("DSA".to_string(), modulus_bits()),
+        PublicParams::Elgamal(_) => ("ElGamal".to_string(), modulus_bits()),
+        PublicParams::ECDSA(ecdsa) => curve("ECDSA ", ecdsa.curve()),
+        PublicParams::ECDH(ecdh) => curve("ECDH ", ecdh.curve()),
+        PublicParams::EdDSALegacy(eddsa) => curve("", eddsa.curve()),
+        PublicParams::Ed25519(_) => ("Ed25519".to_string(), 256),
+        PublicParams::X25519(_) => ("X25519".to_string(), 255),
+        PublicParams::Ed448(_) => ("Ed448".to_string(), 448),
+        PublicParams::X448(_) => ("X448".to_string(), 448),
+        PublicParams::Unknown { .. } => ("Unknown".to_string(), 0),
+    }
+}
+
+/// Format fingerprint with spaces for readability
+fn format_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(4)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -560,6 +1034,7 @@ mod tests {
             algorithm: GpgKeyAlgorithm::Rsa4096,
             passphrase: None,
             method: GenerationMethod::Library,
+            import_to_keyring: false,
         };
         let result = generate_key(options);
         assert!(result.is_err());
@@ -574,8 +1049,155 @@ mod tests {
             algorithm: GpgKeyAlgorithm::Rsa4096,
             passphrase: None,
             method: GenerationMethod::Library,
+            import_to_keyring: false,
         };
         let result = generate_key(options);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_fingerprint_from_colons() {
+        let listing = "\
+sec:u:255:22:1111222233334444:1700000000:::u:::scESC:::+:::ed25519:::0:
+fpr:::::::::AAAABBBBCCCCDDDDEEEEFFFF1111222233334444:
+grp:::::::::0123456789ABCDEF0123456789ABCDEF01234567:
+uid:u::::1700000000::HASH::John Doe <john@example.com>::::::::::0:
+ssb:u:255:18:5555666677778888:1700000000::::::e:::+:::cv25519::
+fpr:::::::::99998888777766665555444433332222111100FF:
+";
+        assert_eq!(
+            fingerprint_from_colons(listing).as_deref(),
+            Some("AAAABBBBCCCCDDDDEEEEFFFF1111222233334444")
+        );
+        assert_eq!(fingerprint_from_colons(""), None);
+    }
+
+    #[test]
+    fn test_ephemeral_home_is_removed_on_drop() {
+        let home = EphemeralGnupgHome::create().unwrap();
+        let path = home.path().to_path_buf();
+        std::fs::write(path.join("keygen.batch"), "%commit").unwrap();
+        assert!(path.is_dir());
+        drop(home);
+        assert!(!path.exists());
+    }
+
+    /// `gpg --quick-gen-key` ed25519 certify-only key expiring 2030-01-01,
+    /// with a signing subkey expiring 2028-06-30, an encryption subkey, and
+    /// a revoked second user ID
+    const CAROL_PUBLIC_KEY: &str = r"-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEatJh+hYJKwYBBAHaRw8BAQdAL/WxtiZHeeQqEoKpvOHP3VXe/HX33sXx1zoq
+LBJhNn20IUNhcm9sIEV4YW1wbGUgPGNhcm9sQGV4YW1wbGUuY29tPoiWBBMWCAA+
+FiEE1+fPeOyuvtO/b1qb1zQlMhHkuVkFAmrSYfoCGwEFCQYKH0YFCwkIBwIGFQoJ
+CAsCBBYCAwECHgECF4AACgkQ1zQlMhHkuVlPGgD9EAWwIM8OoukX0tXYIEcS34G2
+gkgy6FHji+Me401X1acBALraXJ2HbqCxa204xjbXGtIVm/QFPRtlJk7qW8M0UxIP
+tB1DYXJvbCBPbGQgPGNhcm9sQG9sZC5leGFtcGxlPoh4BDAWCAAgFiEE1+fPeOyu
+vtO/b1qb1zQlMhHkuVkFAmrSYfsCHSAACgkQ1zQlMhHkuVlNXwEAq/TmZGsY2BZT
+sxU3l1tWbJUhwNioZgBLfOhRJQFhkJUA/Aw4gk3sdNFX4DW1615KB6Qqv4ETDMnY
+p/1NjSLGNwoDiJYEExYIAD4WIQTX58947K6+079vWpvXNCUyEeS5WQUCatJh+gIb
+AQUJBgofRgULCQgHAgYVCgkICwIEFgIDAQIeAQIXgAAKCRDXNCUyEeS5WVDPAP9b
+XdrMbLnzBVT1jdtlqXAPg0ngsZm9n+hHLvSXF+tuwwEAvxpBHkRQakpacLSOPhou
+OxuWkbM4FRKCvDfovqsrWw24MwRq0mH6FgkrBgEEAdpHDwEBB0CyW4HQJxyA7p3X
+Ap4MTfsBjr14aUwewZfVydaBHiaGNoj1BBgWCAAmFiEE1+fPeOyuvtO/b1qb1zQl
+MhHkuVkFAmrSYfoCGwIFCQM1BkYAgQkQ1zQlMhHkuVl2IAQZFggAHRYhBBGE9AzP
+7s28QcAWuacKVZnD5c7eBQJq0mH6AAoJEKcKVZnD5c7eYPkA/3SGV5UNtYn+bU5x
+rVx3Lr18boEL5JiUw7F5TdIwaYJ7AQDvOzosTCKfJkBQFqec2iTIdqmKzT8EYFfx
+ZGsWoKAcAnYRAQDyap2VpYuGyrc9w/FIkIiHjM8s9OA7p/md0nbOHnGYhQD+MsoG
+VGTS2AgszCH7yX1W4pcRDCruOtKap4dGWA2rhAO4OARq0mH6EgorBgEEAZdVAQUB
+AQdAjFX88A5QWByjnS1+o7Gv2XJ8uEupmGgwalJ2sKFbzgADAQgHiHgEGBYIACAW
+IQTX58947K6+079vWpvXNCUyEeS5WQUCatJh+gIbDAAKCRDXNCUyEeS5Wbd6APsH
+hFl5iO9pbUEICMjWW5V68dfrmkhZ5hh9depdsu9+QQD/fN+2dbJIh8cZrs63LVp2
+YB2+VM3aOXJ1SEdMcxw1ewE=
+=vvu9
+-----END PGP PUBLIC KEY BLOCK-----";
+
+    #[test]
+    fn test_parse_key() {
+        let info = parse_key(CAROL_PUBLIC_KEY).unwrap();
+        assert!(!info.is_private);
+        assert!(info.signatures_valid);
+
+        let user_ids: Vec<_> = info
+            .user_ids
+            .iter()
+            .map(|user| (user.user_id.as_str(), user.primary, user.revoked))
+            .collect();
+        assert_eq!(
+            user_ids,
+            [
+                ("Carol Example <carol@example.com>", true, false),
+                ("Carol Old <carol@old.example>", false, true),
+            ]
+        );
+
+        let primary = &info.primary_key;
+        assert_eq!(primary.algorithm, "Ed25519");
+        assert_eq!(
+            primary.fingerprint,
+            "D7E7 CF78 ECAE BED3 BF6F 5A9B D734 2532 11E4 B959"
+        );
+        assert_eq!(primary.key_id, "D734253211E4B959");
+        assert_eq!(primary.created, 1_792_172_538);
+        assert_eq!(primary.expires, Some(1_893_499_200));
+        assert_eq!(primary.capabilities, [PgpKeyCapability::Certify]);
+
+        let subkeys: Vec<_> = info
+            .subkeys
+            .iter()
+            .map(|subkey| {
+                (
+                    subkey.algorithm.as_str(),
+                    subkey.key_id.as_str(),
+                    subkey.expires,
+                    subkey.capabilities.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            subkeys,
+            [
+                (
+                    "Ed25519",
+                    "A70A5599C3E5CEDE",
+                    Some(1_845_979_200),
+                    &[PgpKeyCapability::Sign][..]
+                ),
+                (
+                    "ECDH Curve25519",
+                    "50CE60A2C9C7B012",
+                    None,
+                    &[PgpKeyCapability::Encrypt][..]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_private_key() {
+        let generated = generate_with_library(GpgKeyOptions {
+            name: "John Doe".to_string(),
+            email: "john@example.com".to_string(),
+            comment: None,
+            algorithm: GpgKeyAlgorithm::EcdsaP256,
+            passphrase: Some("pw".to_string()),
+            method: GenerationMethod::Library,
+            import_to_keyring: false,
+        })
+        .unwrap();
+
+        let info = parse_key(&generated.private_key).unwrap();
+        assert!(info.is_private);
+        assert!(info.encrypted);
+        assert_eq!(info.user_ids[0].user_id, "John Doe <john@example.com>");
+        assert_eq!(info.primary_key.algorithm, "ECDSA NIST P-256");
+        assert_eq!(info.primary_key.bits, 256);
+        assert_eq!(info.primary_key.fingerprint, generated.fingerprint);
+        assert_eq!(
+            parse_key(&generated.public_key).unwrap().primary_key.key_id,
+            info.primary_key.key_id
+        );
+
+        assert!(parse_key("not a key").is_err());
+    }
 }
@@ -0,0 +1,770 @@
+//! JSON Web Token decoding, verification, and signing
+//!
+//! Decodes the header and claims of a compact JWS token and explains the
+//! registered claims, including whether `exp` and `nbf` hold at the time of
+//! decoding. Signatures are verified with `ring` for HS256/384/512, RS256,
+//! ES256, and `EdDSA`, against an HMAC secret, a PEM public key or
+//! certificate, a JWK, or a JWK Set given inline or fetched from a URL.
+//! Tokens are signed with the same algorithms from an HMAC secret or a
+//! PKCS#8 PEM private key.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use ring::hmac;
+use ring::rand::SystemRandom;
+use ring::signature;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use x509_parser::oid_registry::{
+    OID_EC_P256, OID_KEY_TYPE_EC_PUBLIC_KEY, OID_PKCS1_RSAENCRYPTION, OID_SIG_ED25519,
+};
+use x509_parser::prelude::FromDer;
+use x509_parser::x509::SubjectPublicKeyInfo;
+
+use super::GeneratorError;
+
+/// Largest JWK Set read from a URL, in bytes
+const MAX_JWKS_BYTES: usize = 1024 * 1024;
+/// Time allowed for fetching a JWK Set
+const JWKS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Seconds in a day
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// JWS signature algorithm, named as in the `alg` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JwtAlgorithm {
+    HS256,
+    HS384,
+    HS512,
+    RS256,
+    ES256,
+    EdDSA,
+}
+
+impl JwtAlgorithm {
+    /// Algorithm named by an `alg` header value
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "HS256" => Some(Self::HS256),
+            "HS384" => Some(Self::HS384),
+            "HS512" => Some(Self::HS512),
+            "RS256" => Some(Self::RS256),
+            "ES256" => Some(Self::ES256),
+            "EdDSA" => Some(Self::EdDSA),
+            _ => None,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::HS256 => "HS256",
+            Self::HS384 => "HS384",
+            Self::HS512 => "HS512",
+            Self::RS256 => "RS256",
+            Self::ES256 => "ES256",
+            Self::EdDSA => "EdDSA",
+        }
+    }
+
+    const fn hmac(self) -> Option<hmac::Algorithm> {
+        match self {
+            Self::HS256 => Some(hmac::HMAC_SHA256),
+            Self::HS384 => Some(hmac::HMAC_SHA384),
+            Self::HS512 => Some(hmac::HMAC_SHA512),
+            Self::RS256 | Self::ES256 | Self::EdDSA => None,
+        }
+    }
+
+    /// JWK `kty` of the keys this algorithm signs with
+    const fn key_type(self) -> &'static str {
+        match self {
+            Self::HS256 | Self::HS384 | Self::HS512 => "oct",
+            Self::RS256 => "RSA",
+            Self::ES256 => "EC",
+            Self::EdDSA => "OKP",
+        }
+    }
+}
+
+/// Whether the time claims of a token hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JwtTimeStatus {
+    /// Within `nbf` and `exp`, or without either
+    Valid,
+    /// Past `exp`
+    Expired,
+    /// Before `nbf`
+    NotYetValid,
+}
+
+/// Explanation of one claim of the payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtClaim {
+    pub name: String,
+    pub value: Value,
+    /// Meaning of a registered claim; `None` for a custom claim
+    pub description: Option<String>,
+    /// Reading of a time claim: "2026-01-01 00:00:00 UTC (in 3 days)"
+    pub detail: Option<String>,
+}
+
+/// Header, claims, and signature of a decoded token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedJwt {
+    pub header: Value,
+    pub payload: Value,
+    /// Signature as written, base64url
+    pub signature: String,
+    pub claims: Vec<JwtClaim>,
+    pub status: JwtTimeStatus,
+    /// Seconds until `exp` at decoding, negative once expired
+    pub expires_in: Option<i64>,
+}
+
+/// Outcome of checking a token's signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtVerifyResult {
+    pub valid: bool,
+    /// `alg` of the token header
+    pub algorithm: String,
+    /// `kid` of the JWK the token was checked against, if any
+    pub key_id: Option<String>,
+    pub message: String,
+}
+
+/// Decode `token` without checking its signature, reading time claims
+/// against the current time
+pub fn decode(token: &str) -> Result<DecodedJwt, GeneratorError> {
+    decode_at(token, unix_now())
+}
+
+fn decode_at(token: &str, now: i64) -> Result<DecodedJwt, GeneratorError> {
+    let parts = split(token)?;
+    let header = decode_json(parts.header, "header")?;
+    let payload = decode_json(parts.payload, "payload")?;
+    let claims = payload
+        .as_object()
+        .map(|object| {
+            object
+                .iter()
+                .map(|(name, value)| explain_claim(name, value, now))
+                .collect()
+        })
+        .unwrap_or_default();
+    let time = |name| payload.get(name).and_then(Value::as_i64);
+    let expires_in = time("exp").map(|exp| exp - now);
+    let status = if expires_in.is_some_and(|left| left <= 0) {
+        JwtTimeStatus::Expired
+    } else if time("nbf").is_some_and(|nbf| nbf > now) {
+        JwtTimeStatus::NotYetValid
+    } else {
+        JwtTimeStatus::Valid
+    };
+    Ok(DecodedJwt {
+        header,
+        payload,
+        signature: parts.signature.to_string(),
+        claims,
+        status,
+        expires_in,
+    })
+}
+
+/// Check the signature of `token` against `key`: an HMAC secret, a PEM
+/// public key or certificate, a JWK, or a JWK Set
+pub fn verify(token: &str, key: &str) -> Result<JwtVerifyResult, GeneratorError> {
+    let key = key.trim();
+    if key.starts_with('{') {
+        let jwk = serde_json::from_str(key)
+            .map_err(|e| GeneratorError::Jwt(format!("Invalid JWK: {e}")))?;
+        return verify_with_jwk(token, &jwk);
+    }
+    let parts = split(token)?;
+    let algorithm = header_algorithm(parts.header)?;
+    let key = if let Some(hmac) = algorithm.hmac() {
+        if key.starts_with("-----BEGIN") {
+            return Err(GeneratorError::Jwt(format!(
+                "{} takes a shared secret, not a PEM key",
+                algorithm.name()
+            )));
+        }
+        VerifyingKey::Hmac(hmac, key.as_bytes().to_vec())
+    } else {
+        pem_public_key(key)?
+    };
+    Ok(check(&parts, algorithm, &key, None))
+}
+
+/// Check the signature of `token` against the JWK Set at `url`
+pub async fn verify_with_jwks_url(
+    token: &str,
+    url: &str,
+) -> Result<JwtVerifyResult, GeneratorError> {
+    let jwks = fetch_jwks(url).await?;
+    verify_with_jwk(token, &jwks)
+}
+
+/// Sign `claims`, a JSON object, into a compact token
+pub fn encode(
+    claims: &Value,
+    algorithm: JwtAlgorithm,
+    key: &str,
+    key_id: Option<&str>,
+) -> Result<String, GeneratorError> {
+    if !claims.is_object() {
+        return Err(GeneratorError::Jwt("Claims must be a JSON object".into()));
+    }
+    let mut header = Map::new();
+    header.insert("alg".into(), algorithm.name().into());
+    header.insert("typ".into(), "JWT".into());
+    if let Some(kid) = key_id.filter(|kid| !kid.is_empty()) {
+        header.insert("kid".into(), kid.into());
+    }
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(Value::Object(header).to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature = sign(signing_input.as_bytes(), algorithm, key)?;
+    Ok(format!(
+        "{signing_input}.{}",
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// The three segments of a compact token
+struct TokenParts<'a> {
+    header: &'a str,
+    payload: &'a str,
+    signature: &'a str,
+    /// Header and payload with the dot between them, as signed
+    signing_input: &'a str,
+}
+
+fn split(token: &str) -> Result<TokenParts<'_>, GeneratorError> {
+    let token = token.trim();
+    let segments: Vec<_> = token.split('.').collect();
+    let [header, payload, signature] = segments[..] else {
+        return Err(GeneratorError::Jwt(format!(
+            "Expected 3 dot-separated segments, found {}",
+            segments.len()
+        )));
+    };
+    Ok(TokenParts {
+        header,
+        payload,
+        signature,
+        signing_input: &token[..header.len() + 1 + payload.len()],
+    })
+}
+
+fn decode_segment(segment: &str, what: &str) -> Result<Vec<u8>, GeneratorError> {
+    URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|e| GeneratorError::Jwt(format!("Invalid base64url in {what}: {e}")))
+}
+
+fn decode_json(segment: &str, what: &str) -> Result<Value, GeneratorError> {
+    serde_json::from_slice(&decode_segment(segment, what)?)
+        .map_err(|e| GeneratorError::Jwt(format!("Invalid JSON in {what}: {e}")))
+}
+
+fn header_algorithm(header: &str) -> Result<JwtAlgorithm, GeneratorError> {
+    let header = decode_json(header, "header")?;
+    let name = header
+        .get("alg")
+        .and_then(Value::as_str)
+        .ok_or_else(|| GeneratorError::Jwt("Header has no alg".into()))?;
+    JwtAlgorithm::from_name(name)
+        .ok_or_else(|| GeneratorError::Jwt(format!("Unsupported algorithm: {name}")))
+}
+
+fn header_key_id(header: &str) -> Result<Option<String>, GeneratorError> {
+    Ok(decode_json(header, "header")?
+        .get("kid")
+        .and_then(Value::as_str)
+        .map(str::to_string))
+}
+
+/// Key a signature is checked against
+enum VerifyingKey {
+    Hmac(hmac::Algorithm, Vec<u8>),
+    /// PKCS#1 `RSAPublicKey` DER
+    RsaDer(Vec<u8>),
+    /// Modulus and exponent, big-endian
+    RsaComponents {
+        n: Vec<u8>,
+        e: Vec<u8>,
+    },
+    /// Uncompressed P-256 point
+    EcP256(Vec<u8>),
+    Ed25519(Vec<u8>),
+}
+
+impl VerifyingKey {
+    /// Whether this key can check signatures of `algorithm`
+    fn fits(&self, algorithm: JwtAlgorithm) -> bool {
+        match self {
+            Self::Hmac(..) => algorithm.hmac().is_some(),
+            Self::RsaDer(_) | Self::RsaComponents { .. } => algorithm == JwtAlgorithm::RS256,
+            Self::EcP256(_) => algorithm == JwtAlgorithm::ES256,
+            Self::Ed25519(_) => algorithm == JwtAlgorithm::EdDSA,
+        }
+    }
+}
+
+fn check(
+    parts: &TokenParts<'_>,
+    algorithm: JwtAlgorithm,
+    key: &VerifyingKey,
+    key_id: Option<String>,
+) -> JwtVerifyResult {
+    let result = |valid: bool, message: String| JwtVerifyResult {
+        valid,
+        algorithm: algorithm.name().to_string(),
+        key_id: key_id.clone(),
+        message,
+    };
+    if !key.fits(algorithm) {
+        return result(
+            false,
+            format!("Key does not fit the {} algorithm", algorithm.name()),
+        );
+    }
+    let Ok(signature) = decode_segment(parts.signature, "signature") else {
+        return result(false, "Signature is not valid base64url".into());
+    };
+    let message = parts.signing_input.as_bytes();
+    let verified =
+        match key {
+            VerifyingKey::Hmac(hmac, secret) => {
+                // Header alg picks the digest; a JWK `oct` key carries none.
+                let hmac = algorithm.hmac().unwrap_or(*hmac);
+                hmac::verify(&hmac::Key::new(hmac, secret), message, &signature)
+            }
+            VerifyingKey::RsaDer(der) => {
+                signature::UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA256, der)
+                    .verify(message, &signature)
+            }
+            VerifyingKey::RsaComponents { n, e } => signature::RsaPublicKeyComponents { n, e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, &signature),
+            VerifyingKey::EcP256(point) => {
+                signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                    .verify(message, &signature)
+            }
+            VerifyingKey::Ed25519(raw) => {
+                signature::UnparsedPublicKey::new(&signature::ED25519, raw)
+                    .verify(message, &signature)
+            }
+        };
+    if verified.is_ok() {
+        result(true, "Signature verified".into())
+    } else {
+        result(false, "Signature does not match".into())
+    }
+}
+
+/// Check `token` against a JWK, or the key of a JWK Set its `kid` names
+fn verify_with_jwk(token: &str, jwk: &Value) -> Result<JwtVerifyResult, GeneratorError> {
+    let parts = split(token)?;
+    let algorithm = header_algorithm(parts.header)?;
+    let jwk = match jwk.get("keys").and_then(Value::as_array) {
+        Some(keys) => select_jwk(keys, algorithm, header_key_id(parts.header)?.as_deref())?,
+        None => jwk,
+    };
+    let key = jwk_public_key(jwk)?;
+    let key_id = jwk.get("kid").and_then(Value::as_str).map(str::to_string);
+    Ok(check(&parts, algorithm, &key, key_id))
+}
+
+/// Key of a JWK Set with the token's `kid`, or without one, the first
+/// signing key of the algorithm's type
+fn select_jwk<'a>(
+    keys: &'a [Value],
+    algorithm: JwtAlgorithm,
+    key_id: Option<&str>,
+) -> Result<&'a Value, GeneratorError> {
+    let field = |key: &'a Value, name| key.get(name).and_then(Value::as_str);
+    if let Some(kid) = key_id {
+        return keys
+            .iter()
+            .find(|key| field(key, "kid") == Some(kid))
+            .ok_or_else(|| GeneratorError::Jwt(format!("No key with kid \"{kid}\" in JWK Set")));
+    }
+    keys.iter()
+        .find(|key| {
+            field(key, "kty") == Some(algorithm.key_type())
+                && field(key, "use") != Some("enc")
+                && field(key, "alg").is_none_or(|alg| alg == algorithm.name())
+        })
+        .ok_or_else(|| GeneratorError::Jwt(format!("No {} key in JWK Set", algorithm.key_type())))
+}
+
+fn jwk_public_key(jwk: &Value) -> Result<VerifyingKey, GeneratorError> {
+    let member = |name: &str| -> Result<Vec<u8>, GeneratorError> {
+        let value = jwk
+            .get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(|| GeneratorError::Jwt(format!("JWK has no \"{name}\"")))?;
+        decode_segment(value, &format!("JWK \"{name}\""))
+    };
+    let kty = jwk.get("kty").and_then(Value::as_str).unwrap_or_default();
+    let crv = jwk.get("crv").and_then(Value::as_str).unwrap_or_default();
+    match (kty, crv) {
+        ("oct", _) => Ok(VerifyingKey::Hmac(hmac::HMAC_SHA256, member("k")?)),
+        ("RSA", _) => Ok(VerifyingKey::RsaComponents {
+            n: member("n")?,
+            e: member("e")?,
+        }),
+        ("EC", "P-256") => {
+            let mut point = vec![0x04];
+            point.extend(member("x")?);
+            point.extend(member("y")?);
+            Ok(VerifyingKey::EcP256(point))
+        }
+        ("OKP", "Ed25519") => Ok(VerifyingKey::Ed25519(member("x")?)),
+        ("EC" | "OKP", _) => Err(GeneratorError::Jwt(format!("Unsupported curve: {crv}"))),
+        _ => Err(GeneratorError::Jwt(format!("Unsupported key type: {kty}"))),
+    }
+}
+
+/// Public key of a PEM `PUBLIC KEY`, `RSA PUBLIC KEY`, or `CERTIFICATE`
+fn pem_public_key(pem: &str) -> Result<VerifyingKey, GeneratorError> {
+    let (label, der) = parse_pem(pem)?;
+    match label.as_str() {
+        "RSA PUBLIC KEY" => Ok(VerifyingKey::RsaDer(der)),
+        "PUBLIC KEY" => spki_public_key(&der),
+        "CERTIFICATE" => {
+            let (_, certificate) = x509_parser::parse_x509_certificate(&der)
+                .map_err(|e| GeneratorError::Jwt(format!("Invalid certificate: {e}")))?;
+            spki_public_key(certificate.tbs_certificate.subject_pki.raw)
+        }
+        _ => Err(GeneratorError::Jwt(format!(
+            "Expected a public key or certificate, found {label}"
+        ))),
+    }
+}
+
+fn spki_public_key(der: &[u8]) -> Result<VerifyingKey, GeneratorError> {
+    let (_, spki) = SubjectPublicKeyInfo::from_der(der)
+        .map_err(|e| GeneratorError::Jwt(format!("Invalid public key: {e}")))?;
+    let key = spki.subject_public_key.data.to_vec();
+    let algorithm = &spki.algorithm;
+    if algorithm.algorithm == OID_PKCS1_RSAENCRYPTION {
+        Ok(VerifyingKey::RsaDer(key))
+    } else if algorithm.algorithm == OID_SIG_ED25519 {
+        Ok(VerifyingKey::Ed25519(key))
+    } else if algorithm.algorithm == OID_KEY_TYPE_EC_PUBLIC_KEY {
+        let curve = algorithm.parameters.as_ref().and_then(|p| p.as_oid().ok());
+        if curve.as_ref() == Some(&OID_EC_P256) {
+            Ok(VerifyingKey::EcP256(key))
+        } else {
+            Err(GeneratorError::Jwt(
+                "Only P-256 EC keys are supported".into(),
+            ))
+        }
+    } else {
+        Err(GeneratorError::Jwt(format!(
+            "Unsupported public key algorithm: {}",
+            algorithm.algorithm
+        )))
+    }
+}
+
+/// Label and DER body of the first PEM block in `text`
+fn parse_pem(text: &str) -> Result<(String, Vec<u8>), GeneratorError> {
+    let invalid = || GeneratorError::Jwt("Key is not valid PEM".into());
+    let begin = text.find("-----BEGIN ").ok_or_else(invalid)?;
+    let rest = &text[begin + "-----BEGIN ".len()..];
+    let (label, rest) = rest.split_once("-----").ok_or_else(invalid)?;
+    let end = rest
+        .find(&format!("-----END {label}-----"))
+        .ok_or_else(invalid)?;
+    let body: String = rest[..end]
+        .lines()
+        .filter(|line| !line.contains(':'))
+        .flat_map(str::split_whitespace)
+        .collect();
+    let der = STANDARD.decode(body).map_err(|_| invalid())?;
+    Ok((label.to_string(), der))
+}
+
+fn sign(message: &[u8], algorithm: JwtAlgorithm, key: &str) -> Result<Vec<u8>, GeneratorError> {
+    if let Some(hmac) = algorithm.hmac() {
+        if key.is_empty() {
+            return Err(GeneratorError::Jwt("Secret must not be empty".into()));
+        }
+        let tag = hmac::sign(&hmac::Key::new(hmac, key.as_bytes()), message);
+        return Ok(tag.as_ref().to_vec());
+    }
+    let (label, der) = parse_pem(key)?;
+    let rng = SystemRandom::new();
+    let rejected = |e: ring::error::KeyRejected| {
+        GeneratorError::Jwt(format!("Key does not fit {}: {e}", algorithm.name()))
+    };
+    let failed = |_: ring::error::Unspecified| GeneratorError::Jwt("Signing failed".into());
+    match (algorithm, label.as_str()) {
+        (JwtAlgorithm::RS256, "PRIVATE KEY" | "RSA PRIVATE KEY") => {
+            let pair = if label == "PRIVATE KEY" {
+                signature::RsaKeyPair::from_pkcs8(&der)
+            } else {
+                signature::RsaKeyPair::from_der(&der)
+            }
+            .map_err(rejected)?;
+            let mut signature = vec![0; pair.public().modulus_len()];
+            pair.sign(&signature::RSA_PKCS1_SHA256, &rng, message, &mut signature)
+                .map_err(failed)?;
+            Ok(signature)
+        }
+        (JwtAlgorithm::ES256, "PRIVATE KEY") => {
+            let pair = signature::EcdsaKeyPair::from_pkcs8(
+                &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+                &der,
+                &rng,
+            )
+            .map_err(rejected)?;
+            Ok(pair.sign(&rng, message).map_err(failed)?.as_ref().to_vec())
+        }
+        (JwtAlgorithm::EdDSA, "PRIVATE KEY") => {
+            let pair =
+                signature::Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der).map_err(rejected)?;
+            Ok(pair.sign(message).as_ref().to_vec())
+        }
+        _ => Err(GeneratorError::Jwt(format!(
+            "{} takes a PKCS#8 PEM private key, found {label}",
+            algorithm.name()
+        ))),
+    }
+}
+
+async fn fetch_jwks(url: &str) -> Result<Value, GeneratorError> {
+    let url = reqwest::Url::parse(url)
+        .map_err(|e| GeneratorError::Jwt(format!("Invalid JWKS URL: {e}")))?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .timeout(JWKS_TIMEOUT)
+        .build()
+        .map_err(|e| GeneratorError::Jwt(format!("Failed to build HTTP client: {e}")))?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| GeneratorError::Jwt(format!("JWKS request failed: {e}")))?;
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| GeneratorError::Jwt(format!("Failed to read JWKS: {e}")))?
+    {
+        if body.len() + chunk.len() > MAX_JWKS_BYTES {
+            return Err(GeneratorError::Jwt(format!(
+                "JWKS exceeds {MAX_JWKS_BYTES} bytes"
+            )));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let jwks: Value = serde_json::from_slice(&body)
+        .map_err(|e| GeneratorError::Jwt(format!("Invalid JWKS: {e}")))?;
+    if jwks.get("keys").is_some_and(Value::is_array) {
+        Ok(jwks)
+    } else {
+        Err(GeneratorError::Jwt("JWKS has no \"keys\" array".into()))
+    }
+}
+
+fn explain_claim(name: &str, value: &Value, now: i64) -> JwtClaim {
+    let description = match name {
+        "iss" => Some("Issuer"),
+        "sub" => Some("Subject"),
+        "aud" => Some("Audience"),
+        "exp" => Some("Expiration time"),
+        "nbf" => Some("Not valid before"),
+        "iat" => Some("Issued at"),
+        "jti" => Some("Token ID"),
+        "azp" => Some("Authorized party"),
+        "auth_time" => Some("Time of authentication"),
+        "nonce" => Some("Nonce binding the token to a request"),
+        "scope" => Some("Granted scopes"),
+        _ => None,
+    };
+    let detail = match name {
+        "exp" | "nbf" | "iat" | "auth_time" => value
+            .as_i64()
+            .map(|time| format!("{} ({})", format_time(time), relative(time - now))),
+        _ => None,
+    };
+    JwtClaim {
+        name: name.to_string(),
+        value: value.clone(),
+        description: description.map(str::to_string),
+        detail,
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+}
+
+/// `seconds` from now, as "in 3 hours" or "2 days ago"
+fn relative(seconds: i64) -> String {
+    let magnitude = seconds.unsigned_abs();
+    let (count, unit) = match magnitude {
+        0..60 => (magnitude, "second"),
+        60..3600 => (magnitude / 60, "minute"),
+        3600..86_400 => (magnitude / 3600, "hour"),
+        _ => (magnitude / 86_400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if seconds < 0 {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
+
+/// UNIX time as "YYYY-MM-DD HH:MM:SS UTC"
+fn format_time(time: i64) -> String {
+    let (days, secs) = (
+        time.div_euclid(SECONDS_PER_DAY),
+        time.rem_euclid(SECONDS_PER_DAY),
+    );
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::KeyPair as _;
+
+    use super::*;
+
+    /// The example token of jwt.io, signed with "your-256-bit-secret"
+    const HS256_TOKEN: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+        eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+        SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+
+    fn pem(label: &str, der: &[u8]) -> String {
+        format!(
+            "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+            STANDARD.encode(der)
+        )
+    }
+
+    #[test]
+    fn test_decode_explains_claims() {
+        let decoded = decode_at(HS256_TOKEN, 1_516_239_022 + 90).unwrap();
+        assert_eq!(decoded.header["alg"], "HS256");
+        assert_eq!(decoded.payload["name"], "John Doe");
+        assert_eq!(decoded.status, JwtTimeStatus::Valid);
+        let iat = decoded.claims.iter().find(|c| c.name == "iat").unwrap();
+        assert_eq!(
+            iat.detail.as_deref(),
+            Some("2018-01-18 01:30:22 UTC (1 minute ago)")
+        );
+        let name = decoded.claims.iter().find(|c| c.name == "name").unwrap();
+        assert!(name.description.is_none());
+
+        let claims = serde_json::json!({"exp": 100, "nbf": 50});
+        let token = encode(&claims, JwtAlgorithm::HS256, "k", None).unwrap();
+        assert_eq!(
+            decode_at(&token, 10).unwrap().status,
+            JwtTimeStatus::NotYetValid
+        );
+        let expired = decode_at(&token, 200).unwrap();
+        assert_eq!(expired.status, JwtTimeStatus::Expired);
+        assert_eq!(expired.expires_in, Some(-100));
+    }
+
+    #[test]
+    fn test_hmac_verify_and_sign() {
+        assert!(verify(HS256_TOKEN, "your-256-bit-secret").unwrap().valid);
+        assert!(!verify(HS256_TOKEN, "wrong").unwrap().valid);
+        let jwk = format!(
+            r#"{{"kty":"oct","k":"{}"}}"#,
+            URL_SAFE_NO_PAD.encode("your-256-bit-secret")
+        );
+        assert!(verify(HS256_TOKEN, &jwk).unwrap().valid);
+
+        let claims = serde_json::json!({"sub": "1234567890"});
+        for algorithm in [JwtAlgorithm::HS384, JwtAlgorithm::HS512] {
+            let token = encode(&claims, algorithm, "secret", Some("k1")).unwrap();
+            let result = verify(&token, "secret").unwrap();
+            assert!(result.valid);
+            assert_eq!(result.algorithm, algorithm.name());
+        }
+        assert!(verify("a.b", "secret").is_err());
+    }
+
+    #[test]
+    fn test_asymmetric_round_trip() {
+        let rng = SystemRandom::new();
+        let claims = serde_json::json!({"sub": "alice"});
+
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let token = encode(
+            &claims,
+            JwtAlgorithm::EdDSA,
+            &pem("PRIVATE KEY", pkcs8.as_ref()),
+            None,
+        )
+        .unwrap();
+        let mut spki = hex::decode("302a300506032b6570032100").unwrap();
+        spki.extend_from_slice(pair.public_key().as_ref());
+        assert!(verify(&token, &pem("PUBLIC KEY", &spki)).unwrap().valid);
+        assert!(verify(&token, "secret").is_err());
+
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &rng,
+        )
+        .unwrap();
+        let pair = signature::EcdsaKeyPair::from_pkcs8(
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            pkcs8.as_ref(),
+            &rng,
+        )
+        .unwrap();
+        let token = encode(
+            &claims,
+            JwtAlgorithm::ES256,
+            &pem("PRIVATE KEY", pkcs8.as_ref()),
+            Some("ec-1"),
+        )
+        .unwrap();
+        let point = pair.public_key().as_ref();
+        let jwks = serde_json::json!({"keys": [
+            {"kty": "OKP", "crv": "Ed25519", "kid": "ed-1", "x": "AA"},
+            {
+                "kty": "EC",
+                "crv": "P-256",
+                "kid": "ec-1",
+                "x": URL_SAFE_NO_PAD.encode(&point[1..33]),
+                "y": URL_SAFE_NO_PAD.encode(&point[33..]),
+            },
+        ]});
+        let result = verify(&token, &jwks.to_string()).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.key_id.as_deref(), Some("ec-1"));
+    }
+}
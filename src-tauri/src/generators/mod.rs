@@ -1,17 +1,31 @@
 //! Cryptographic key generators module
 //!
-//! This module provides `BCrypt` hashing, SSH key generation, and GPG key generation
-//! with process isolation for cancellable operations.
+//! This module provides `BCrypt` hashing, SSH key generation, GPG key generation,
+//! PGP message encryption and signing, and X.509 certificate generation with
+//! process isolation for cancellable operations, PGP key inspection,
+//! identification and verification of password hashes in the other
+//! common formats, JWT decoding, verification, and signing, HOTP/TOTP
+//! one-time passwords, AES/ChaCha20 symmetric encryption, and RSA/ECDSA/Ed25519
+//! signing and verification.
 
 pub mod bcrypt;
 pub mod cli;
+mod der;
 pub mod gpg;
+pub mod jwt;
+pub mod otp;
+pub mod password_hash;
+pub mod signing;
 pub mod ssh;
+pub mod symmetric;
 pub mod worker;
+pub mod x509;
 
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::error_codes::{serialize_coded, ErrorCode};
+
 /// Common error type for all generators
 #[derive(Debug, Error)]
 pub enum GeneratorError {
@@ -24,6 +38,24 @@ pub enum GeneratorError {
     #[error("GPG key generation error: {0}")]
     Gpg(String),
 
+    #[error("Password hash error: {0}")]
+    PasswordHash(String),
+
+    #[error("JWT error: {0}")]
+    Jwt(String),
+
+    #[error("X.509 generation error: {0}")]
+    X509(String),
+
+    #[error("OTP error: {0}")]
+    Otp(String),
+
+    #[error("Encryption error: {0}")]
+    Symmetric(String),
+
+    #[error("Signing error: {0}")]
+    Signing(String),
+
     #[cfg(test)]
     #[error("CLI execution error: {0}")]
     CliExecution(String),
@@ -38,12 +70,52 @@ pub enum GeneratorError {
     Cancelled,
 }
 
+impl ErrorCode for GeneratorError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Bcrypt(_) => "generator.bcrypt",
+            Self::SshKey(_) => "generator.ssh_key",
+            Self::Gpg(_) => "generator.gpg",
+            Self::PasswordHash(_) => "generator.password_hash",
+            Self::Jwt(_) => "generator.jwt",
+            Self::X509(_) => "generator.x509",
+            Self::Otp(_) => "generator.otp",
+            Self::Symmetric(_) => "generator.symmetric",
+            Self::Signing(_) => "generator.signing",
+            #[cfg(test)]
+            Self::CliExecution(_) => "generator.cli_execution",
+            Self::InvalidParameter(_) => "generator.invalid_parameter",
+            Self::Worker(_) => "generator.worker",
+            Self::Cancelled => "generator.cancelled",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Bcrypt(detail)
+            | Self::SshKey(detail)
+            | Self::Gpg(detail)
+            | Self::PasswordHash(detail)
+            | Self::Jwt(detail)
+            | Self::X509(detail)
+            | Self::Otp(detail)
+            | Self::Symmetric(detail)
+            | Self::Signing(detail)
+            | Self::InvalidParameter(detail)
+            | Self::Worker(detail) => vec![("detail", detail.clone())],
+            #[cfg(test)]
+            Self::CliExecution(detail) => vec![("detail", detail.clone())],
+            Self::Cancelled => Vec::new(),
+        }
+    }
+}
+
 impl Serialize for GeneratorError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        serialize_coded(self, serializer)
     }
 }
 
@@ -0,0 +1,599 @@
+//! Password hash identification and verification
+//!
+//! Recognizes bcrypt, Argon2, scrypt, PBKDF2, and SHA-crypt hashes from
+//! their PHC string (`$argon2id$v=19$m=65536,t=3,p=4$salt$hash`) or
+//! legacy modular crypt string (`$2b$10$...`, `$6$rounds=5000$salt$hash`),
+//! and extracts their parameters. Verification of bcrypt goes to the
+//! worker process like the other `BCrypt` operations; the other formats are
+//! verified in-process, with parameters capped so a pasted hash cannot
+//! demand unbounded time or memory.
+
+use std::num::NonZeroU32;
+
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine as _;
+use ring::pbkdf2;
+use scrypt::Scrypt;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::worker::WorkerProcessState;
+use super::GeneratorError;
+
+/// Most PBKDF2 iterations or SHA-crypt rounds verified
+const MAX_ITERATIONS: u64 = 10_000_000;
+/// Most memory an scrypt or Argon2 verification may take, in bytes
+const MAX_MEMORY: u64 = 1 << 30;
+/// Highest scrypt or Argon2 parallelism verified
+const MAX_PARALLELISM: u64 = 64;
+/// Most Argon2 passes verified
+const MAX_PASSES: u64 = 64;
+
+/// SHA-crypt rounds when the hash names none
+const SHA_CRYPT_DEFAULT_ROUNDS: u64 = 5000;
+/// Fewest SHA-crypt rounds; hashes naming fewer are verified with these
+const SHA_CRYPT_MIN_ROUNDS: u64 = 1000;
+/// Alphabet of crypt's base-64 encoding
+const CRYPT_ALPHABET: &[u8; 64] =
+    b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Password hashing scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PasswordHashAlgorithm {
+    Bcrypt,
+    Argon2,
+    Scrypt,
+    Pbkdf2,
+    ShaCrypt,
+}
+
+/// Shape of a hash string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashStringFormat {
+    /// PHC string format, with named parameters
+    Phc,
+    /// Modular crypt format, with positional parameters
+    Crypt,
+}
+
+/// Numeric parameter of a hash, named as in the string (`cost`, `m`, `i`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashParameter {
+    pub name: String,
+    pub value: u64,
+}
+
+/// What a password hash string holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordHashInfo {
+    pub algorithm: PasswordHashAlgorithm,
+    /// Variant as written: "2b", "argon2id", "pbkdf2-sha256", "sha512-crypt"
+    pub variant: String,
+    pub format: HashStringFormat,
+    /// Cost parameters, in the order they are written
+    pub parameters: Vec<HashParameter>,
+    /// Salt as encoded in the string
+    pub salt: String,
+    /// Length of the hash output in bytes
+    pub hash_length: usize,
+}
+
+impl PasswordHashInfo {
+    fn parameter(&self, name: &str) -> Option<u64> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .map(|parameter| parameter.value)
+    }
+}
+
+/// Result of verifying a password against a hash of any recognized format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordHashVerifyResult {
+    pub algorithm: PasswordHashAlgorithm,
+    /// Whether the password matches the hash
+    pub valid: bool,
+    /// Human-readable result message
+    pub message: String,
+}
+
+impl PasswordHashVerifyResult {
+    fn new(algorithm: PasswordHashAlgorithm, valid: bool) -> Self {
+        Self {
+            algorithm,
+            valid,
+            message: if valid {
+                "Password matches the hash".to_string()
+            } else {
+                "Password does not match the hash".to_string()
+            },
+        }
+    }
+}
+
+fn invalid(detail: impl Into<String>) -> GeneratorError {
+    GeneratorError::PasswordHash(detail.into())
+}
+
+// =============================================================================
+// Identification
+// =============================================================================
+
+/// Recognize the format of `hash` and extract its parameters
+///
+/// # Arguments
+/// * `hash` - A bcrypt, Argon2, scrypt, PBKDF2, or SHA-crypt hash string
+///
+/// # Returns
+/// The algorithm, variant, parameters, and salt of the hash
+pub fn identify(hash: &str) -> Result<PasswordHashInfo, GeneratorError> {
+    let fields: Vec<&str> = hash.trim().split('$').collect();
+    let (Some(&""), Some(&id)) = (fields.first(), fields.get(1)) else {
+        return Err(invalid("Unrecognized password hash format"));
+    };
+    let rest = &fields[2..];
+    match id {
+        "2a" | "2b" | "2x" | "2y" => identify_bcrypt(id, rest),
+        "argon2i" | "argon2d" | "argon2id" => identify_phc(PasswordHashAlgorithm::Argon2, id, rest),
+        "scrypt" => identify_phc(PasswordHashAlgorithm::Scrypt, id, rest),
+        "pbkdf2" | "pbkdf2-sha1" | "pbkdf2-sha256" | "pbkdf2-sha512" => identify_pbkdf2(id, rest),
+        "5" | "6" => identify_sha_crypt(id, rest),
+        _ => Err(invalid(format!(
+            "Unrecognized password hash identifier '${id}$'"
+        ))),
+    }
+}
+
+/// `$2b$10$` followed by a 22-character salt and a 31-character hash
+fn identify_bcrypt(id: &str, fields: &[&str]) -> Result<PasswordHashInfo, GeneratorError> {
+    let [cost, encoded] = fields else {
+        return Err(invalid(
+            "A bcrypt hash has the form $2b$<cost>$<salt><hash>",
+        ));
+    };
+    let cost: u64 = cost
+        .parse()
+        .map_err(|_| invalid(format!("Invalid bcrypt cost '{cost}'")))?;
+    if encoded.len() != 53 || !encoded.bytes().all(|byte| CRYPT_ALPHABET.contains(&byte)) {
+        return Err(invalid("A bcrypt salt and hash take 53 characters"));
+    }
+    Ok(PasswordHashInfo {
+        algorithm: PasswordHashAlgorithm::Bcrypt,
+        variant: id.to_string(),
+        format: HashStringFormat::Crypt,
+        parameters: vec![HashParameter {
+            name: "cost".to_string(),
+            value: cost,
+        }],
+        salt: encoded[..22].to_string(),
+        hash_length: 23,
+    })
+}
+
+/// `$id[$v=version]$name=value,...$salt$hash`
+fn identify_phc(
+    algorithm: PasswordHashAlgorithm,
+    id: &str,
+    fields: &[&str],
+) -> Result<PasswordHashInfo, GeneratorError> {
+    let (version, fields) = match fields.split_first() {
+        Some((first, rest)) if first.starts_with("v=") => (Some(*first), rest),
+        _ => (None, fields),
+    };
+    let [params, salt, hash] = fields else {
+        return Err(invalid(format!(
+            "A {id} hash has the form ${id}$<parameters>$<salt>$<hash>"
+        )));
+    };
+    let mut parameters = version.map(parse_params).transpose()?.unwrap_or_default();
+    parameters.extend(parse_params(params)?);
+    decode_b64(salt).ok_or_else(|| invalid("The salt is not valid base64"))?;
+    let hash_length = decode_b64(hash)
+        .ok_or_else(|| invalid("The hash is not valid base64"))?
+        .len();
+    Ok(PasswordHashInfo {
+        algorithm,
+        variant: id.to_string(),
+        format: HashStringFormat::Phc,
+        parameters,
+        salt: (*salt).to_string(),
+        hash_length,
+    })
+}
+
+/// PHC (`$pbkdf2-sha256$i=600000,l=32$salt$hash`) or passlib
+/// (`$pbkdf2-sha256$29000$salt$hash`) PBKDF2 hash
+fn identify_pbkdf2(id: &str, fields: &[&str]) -> Result<PasswordHashInfo, GeneratorError> {
+    if fields.first().is_some_and(|field| field.contains('=')) {
+        return identify_phc(PasswordHashAlgorithm::Pbkdf2, id, fields);
+    }
+    let [rounds, salt, hash] = fields else {
+        return Err(invalid(format!(
+            "A {id} hash has the form ${id}$<rounds>$<salt>$<hash>"
+        )));
+    };
+    let rounds: u64 = rounds
+        .parse()
+        .map_err(|_| invalid(format!("Invalid PBKDF2 rounds '{rounds}'")))?;
+    decode_b64(salt).ok_or_else(|| invalid("The salt is not valid base64"))?;
+    let hash_length = decode_b64(hash)
+        .ok_or_else(|| invalid("The hash is not valid base64"))?
+        .len();
+    Ok(PasswordHashInfo {
+        algorithm: PasswordHashAlgorithm::Pbkdf2,
+        variant: id.to_string(),
+        format: HashStringFormat::Crypt,
+        parameters: vec![HashParameter {
+            name: "rounds".to_string(),
+            value: rounds,
+        }],
+        salt: (*salt).to_string(),
+        hash_length,
+    })
+}
+
+/// `$5$[rounds=N$]salt$hash` (SHA-256) or `$6$...` (SHA-512)
+fn identify_sha_crypt(id: &str, fields: &[&str]) -> Result<PasswordHashInfo, GeneratorError> {
+    let (rounds, fields) = match fields.split_first() {
+        Some((first, rest)) if first.starts_with("rounds=") => (Some(*first), rest),
+        _ => (None, fields),
+    };
+    let [salt, hash] = fields else {
+        return Err(invalid(format!(
+            "A SHA-crypt hash has the form ${id}$[rounds=<n>$]<salt>$<hash>"
+        )));
+    };
+    let (variant, length) = if id == "5" {
+        ("sha256-crypt", 43)
+    } else {
+        ("sha512-crypt", 86)
+    };
+    if hash.len() != length || !hash.bytes().all(|byte| CRYPT_ALPHABET.contains(&byte)) {
+        return Err(invalid(format!(
+            "A {variant} hash takes {length} characters"
+        )));
+    }
+    let parameters = rounds.map(parse_params).transpose()?.unwrap_or_default();
+    Ok(PasswordHashInfo {
+        algorithm: PasswordHashAlgorithm::ShaCrypt,
+        variant: variant.to_string(),
+        format: HashStringFormat::Crypt,
+        parameters,
+        salt: (*salt).to_string(),
+        hash_length: if id == "5" { 32 } else { 64 },
+    })
+}
+
+/// `name=value,...` with numeric values
+fn parse_params(field: &str) -> Result<Vec<HashParameter>, GeneratorError> {
+    field
+        .split(',')
+        .map(|pair| {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("Invalid parameter '{pair}'")))?;
+            let value = value
+                .parse()
+                .map_err(|_| invalid(format!("Parameter '{name}' is not a number")))?;
+            Ok(HashParameter {
+                name: name.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Standard base64 without padding, also in passlib's variant with `.`
+/// for `+`
+fn decode_b64(encoded: &str) -> Option<Vec<u8>> {
+    STANDARD_NO_PAD
+        .decode(encoded.trim_end_matches('=').replace('.', "+"))
+        .ok()
+}
+
+// =============================================================================
+// Verification
+// =============================================================================
+
+/// Verify `password` against a hash of any recognized format
+///
+/// bcrypt hashes are verified by the worker process, so the operation can
+/// be cancelled; the others are verified on the blocking thread pool.
+pub async fn verify_isolated(
+    app: &AppHandle,
+    password: String,
+    hash: String,
+    state: &WorkerProcessState,
+    job_id: Option<String>,
+) -> Result<PasswordHashVerifyResult, GeneratorError> {
+    let info = identify(&hash)?;
+    if info.algorithm == PasswordHashAlgorithm::Bcrypt {
+        let result =
+            super::bcrypt::verify_hash_isolated(app, password, hash, state, job_id).await?;
+        return Ok(PasswordHashVerifyResult {
+            algorithm: info.algorithm,
+            valid: result.valid,
+            message: result.message,
+        });
+    }
+    tokio::task::spawn_blocking(move || verify(&password, hash.trim(), &info))
+        .await
+        .map_err(|e| GeneratorError::Worker(e.to_string()))?
+}
+
+/// Verify `password` against `hash`, identified as `info`, in-process
+fn verify(
+    password: &str,
+    hash: &str,
+    info: &PasswordHashInfo,
+) -> Result<PasswordHashVerifyResult, GeneratorError> {
+    let valid = match info.algorithm {
+        PasswordHashAlgorithm::Bcrypt => {
+            bcrypt::verify(password, hash).map_err(|e| GeneratorError::Bcrypt(e.to_string()))?
+        }
+        PasswordHashAlgorithm::Argon2 => verify_argon2(password, hash, info)?,
+        PasswordHashAlgorithm::Scrypt => verify_scrypt(password, hash, info)?,
+        PasswordHashAlgorithm::Pbkdf2 => verify_pbkdf2(password, hash, info)?,
+        PasswordHashAlgorithm::ShaCrypt => verify_sha_crypt(password, hash, info)?,
+    };
+    Ok(PasswordHashVerifyResult::new(info.algorithm, valid))
+}
+
+/// `value` when it is at most `max`
+fn capped(name: &str, value: u64, max: u64) -> Result<u64, GeneratorError> {
+    if value > max {
+        return Err(GeneratorError::InvalidParameter(format!(
+            "{name} of {value} is above the verifiable limit of {max}"
+        )));
+    }
+    Ok(value)
+}
+
+fn required(info: &PasswordHashInfo, name: &str) -> Result<u64, GeneratorError> {
+    info.parameter(name)
+        .ok_or_else(|| invalid(format!("Missing parameter '{name}'")))
+}
+
+/// Salt and expected output of a base64 PHC or passlib hash
+fn salt_and_output(hash: &str) -> Result<(Vec<u8>, Vec<u8>), GeneratorError> {
+    let mut fields = hash.rsplit('$');
+    let output = fields.next().and_then(decode_b64);
+    let salt = fields.next().and_then(decode_b64);
+    salt.zip(output)
+        .ok_or_else(|| invalid("The salt or hash is not valid base64"))
+}
+
+fn verify_argon2(
+    password: &str,
+    hash: &str,
+    info: &PasswordHashInfo,
+) -> Result<bool, GeneratorError> {
+    capped("Memory", required(info, "m")? * 1024, MAX_MEMORY)?;
+    capped("Iterations", required(info, "t")?, MAX_PASSES)?;
+    capped("Parallelism", required(info, "p")?, MAX_PARALLELISM)?;
+    let parsed = PasswordHash::new(hash).map_err(|e| invalid(e.to_string()))?;
+    match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+        Ok(()) => Ok(true),
+        Err(argon2::password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(invalid(e.to_string())),
+    }
+}
+
+fn verify_scrypt(
+    password: &str,
+    hash: &str,
+    info: &PasswordHashInfo,
+) -> Result<bool, GeneratorError> {
+    let log_n = required(info, "ln")?;
+    let r = required(info, "r")?;
+    let p = capped("Parallelism", required(info, "p")?, MAX_PARALLELISM)?;
+    if !(1..64).contains(&log_n) || r == 0 || p == 0 {
+        return Err(GeneratorError::InvalidParameter(
+            "scrypt needs 0 < ln < 64, r > 0, and p > 0".to_string(),
+        ));
+    }
+    let memory = 128u64
+        .checked_mul(r)
+        .and_then(|bytes| bytes.checked_mul(1 << log_n))
+        .unwrap_or(u64::MAX);
+    capped("Memory", memory, MAX_MEMORY)?;
+    // passlib writes `.` for `+` in the salt and hash.
+    let hash = hash.replace('.', "+");
+    let parsed = PasswordHash::new(&hash).map_err(|e| invalid(e.to_string()))?;
+    match Scrypt.verify_password(password.as_bytes(), &parsed) {
+        Ok(()) => Ok(true),
+        Err(scrypt::password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(invalid(e.to_string())),
+    }
+}
+
+fn verify_pbkdf2(
+    password: &str,
+    hash: &str,
+    info: &PasswordHashInfo,
+) -> Result<bool, GeneratorError> {
+    let rounds = match info.format {
+        HashStringFormat::Phc => required(info, "i")?,
+        HashStringFormat::Crypt => required(info, "rounds")?,
+    };
+    let rounds = capped("Iterations", rounds, MAX_ITERATIONS)?;
+    let rounds = u32::try_from(rounds)
+        .ok()
+        .and_then(NonZeroU32::new)
+        .ok_or_else(|| GeneratorError::InvalidParameter("PBKDF2 needs i > 0".to_string()))?;
+    let (salt, expected) = salt_and_output(hash)?;
+    let algorithm = match info.variant.as_str() {
+        "pbkdf2-sha256" => pbkdf2::PBKDF2_HMAC_SHA256,
+        "pbkdf2-sha512" => pbkdf2::PBKDF2_HMAC_SHA512,
+        _ => pbkdf2::PBKDF2_HMAC_SHA1,
+    };
+    Ok(pbkdf2::verify(algorithm, rounds, &salt, password.as_bytes(), &expected).is_ok())
+}
+
+fn verify_sha_crypt(
+    password: &str,
+    hash: &str,
+    info: &PasswordHashInfo,
+) -> Result<bool, GeneratorError> {
+    let rounds = info.parameter("rounds").unwrap_or(SHA_CRYPT_DEFAULT_ROUNDS);
+    capped("Rounds", rounds, MAX_ITERATIONS)?;
+    // crypt raises fewer rounds to the minimum rather than refusing them.
+    let hash = if rounds < SHA_CRYPT_MIN_ROUNDS {
+        hash.replacen(
+            &format!("$rounds={rounds}$"),
+            &format!("$rounds={SHA_CRYPT_MIN_ROUNDS}$"),
+            1,
+        )
+    } else {
+        hash.to_string()
+    };
+    // The hash was identified above, so only a mismatch fails the check.
+    let checked = if info.variant == "sha256-crypt" {
+        sha_crypt::sha256_check(password, &hash)
+    } else {
+        sha_crypt::sha512_check(password, &hash)
+    };
+    Ok(checked.is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use argon2::password_hash::SaltString;
+    use argon2::PasswordHasher;
+
+    use super::*;
+
+    fn verified(password: &str, hash: &str) -> bool {
+        verify(password, hash, &identify(hash).unwrap())
+            .unwrap()
+            .valid
+    }
+
+    #[test]
+    fn test_identify_formats() {
+        let bcrypt =
+            identify("$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW").unwrap();
+        assert_eq!(bcrypt.algorithm, PasswordHashAlgorithm::Bcrypt);
+        assert_eq!(bcrypt.parameter("cost"), Some(12));
+        assert_eq!(bcrypt.salt, "R9h/cIPz0gi.URNNX3kh2O");
+
+        let argon2 =
+            identify("$argon2id$v=19$m=65536,t=3,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG")
+                .unwrap();
+        assert_eq!(argon2.variant, "argon2id");
+        assert_eq!(argon2.format, HashStringFormat::Phc);
+        let names: Vec<&str> = argon2.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["v", "m", "t", "p"]);
+        assert_eq!(argon2.hash_length, 24);
+
+        let passlib = identify("$pbkdf2-sha256$29000$N2YMIWQsBWBMae09x1jrPQ$g8fvDoT.lkmyp0YeiJ4rMfXbqzCzT.tuaxjn6lWDD4g").unwrap();
+        assert_eq!(passlib.format, HashStringFormat::Crypt);
+        assert_eq!(passlib.parameter("rounds"), Some(29000));
+
+        let sha512 = identify("$6$rounds=10000$saltstringsaltst$OW1/O6BYHV6BcXZu8QVeXbDWra3Oeqh0sbHbbMCVNSnCM/UrjmM0Dp8vOuZeHBy/YTBmSK6H9qs/y3RnOaw5v.").unwrap();
+        assert_eq!(sha512.variant, "sha512-crypt");
+        assert_eq!(sha512.parameter("rounds"), Some(10000));
+
+        assert!(identify("$1$abc$def").is_err());
+        assert!(identify("plain text").is_err());
+    }
+
+    #[test]
+    fn test_pbkdf2_and_scrypt_vectors() {
+        // RFC 6070 and RFC 7914, as passlib and PHC strings
+        let rfc6070 = format!(
+            "$pbkdf2$2${}${}",
+            STANDARD_NO_PAD.encode(b"salt"),
+            STANDARD_NO_PAD
+                .encode(hex::decode("ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957").unwrap())
+                .replace('+', ".")
+        );
+        assert!(verified("password", &rfc6070));
+        assert!(!verified("passwort", &rfc6070));
+
+        let rfc7914 = format!(
+            "$scrypt$ln=10,r=8,p=16${}${}",
+            STANDARD_NO_PAD.encode(b"NaCl"),
+            STANDARD_NO_PAD.encode(
+                hex::decode(
+                    "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+                     2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+                )
+                .unwrap()
+            )
+        );
+        assert!(verified("password", &rfc7914));
+        assert!(!verified("Password", &rfc7914));
+    }
+
+    #[test]
+    fn test_verify_sha_crypt() {
+        assert!(verified(
+            "Hello world!",
+            "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5"
+        ));
+        assert!(verified(
+            "Hello world!",
+            "$6$rounds=10000$saltstringsaltst$OW1/O6BYHV6BcXZu8QVeXbDWra3Oeqh0sbHbbMCVNSnCM/UrjmM0Dp8vOuZeHBy/YTBmSK6H9qs/y3RnOaw5v."
+        ));
+        // Rounds below the minimum are raised to 1000, as crypt does
+        assert!(verified(
+            "the minimum number is still observed",
+            "$5$rounds=10$roundstoolow$yfvwcWrQ8l/K0DAWyuPMDNHpIVlTQebY9l/gL972bIC"
+        ));
+        assert!(!verified(
+            "Hello world",
+            "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5"
+        ));
+    }
+
+    #[test]
+    fn test_verify_phc_hashes() {
+        let salt = b"somesalt";
+        let mut derived = [0u8; 32];
+        let params = scrypt::Params::new(4, 8, 1, derived.len()).unwrap();
+        scrypt::scrypt(b"secret", salt, &params, &mut derived).unwrap();
+        let hash = format!(
+            "$scrypt$ln=4,r=8,p=1${}${}",
+            STANDARD_NO_PAD.encode(salt),
+            STANDARD_NO_PAD.encode(derived)
+        );
+        assert!(verified("secret", &hash));
+        assert!(!verified("Secret", &hash));
+
+        let mut derived = [0u8; 64];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA512,
+            NonZeroU32::new(1000).unwrap(),
+            salt,
+            b"secret",
+            &mut derived,
+        );
+        let hash = format!(
+            "$pbkdf2-sha512$i=1000,l=64${}${}",
+            STANDARD_NO_PAD.encode(salt),
+            STANDARD_NO_PAD.encode(derived).replace('+', ".")
+        );
+        assert!(verified("secret", &hash));
+
+        let params = argon2::Params::new(64, 1, 1, None).unwrap();
+        let salt = SaltString::from_b64("c29tZXNhbHQ").unwrap();
+        let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+            .hash_password(b"secret", &salt)
+            .unwrap()
+            .to_string();
+        assert_eq!(identify(&argon2).unwrap().parameter("m"), Some(64));
+        assert!(verified("secret", &argon2));
+        assert!(!verified("secret!", &argon2));
+    }
+
+    #[test]
+    fn test_costly_hashes_are_refused() {
+        let hash = "$pbkdf2-sha256$i=99999999,l=32$c29tZXNhbHQ$AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let error = verify("x", hash, &identify(hash).unwrap()).unwrap_err();
+        assert!(matches!(error, GeneratorError::InvalidParameter(_)));
+    }
+}
@@ -0,0 +1,656 @@
+//! Symmetric encryption: AES-GCM, AES-CBC, and ChaCha20-Poly1305
+//!
+//! Encrypts and decrypts text or whole files under a raw key or a key
+//! derived from a passphrase with PBKDF2-HMAC-SHA256 or Argon2id. Nothing
+//! is hidden in a container format: the output is the bare ciphertext (with
+//! the 16-byte tag appended for the AEAD ciphers), and the IV or nonce and
+//! the salt are returned alongside so they can be stored or sent however
+//! the other side expects.
+
+use std::num::NonZeroU32;
+use std::path::Path;
+
+use argon2::Argon2;
+use base64::Engine;
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use super::GeneratorError;
+
+/// PBKDF2 iterations when none are given (OWASP's 2023 figure for SHA-256)
+const DEFAULT_PBKDF2_ITERATIONS: u32 = 600_000;
+/// Most PBKDF2 iterations accepted
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+/// Length of a generated salt, in bytes
+const SALT_BYTES: usize = 16;
+/// Largest file encrypted or decrypted, which is held in memory whole
+const MAX_FILE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Cipher and mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymmetricCipher {
+    #[serde(rename = "aes-128-gcm")]
+    Aes128Gcm,
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+    #[serde(rename = "aes-128-cbc")]
+    Aes128Cbc,
+    #[serde(rename = "aes-256-cbc")]
+    Aes256Cbc,
+    #[serde(rename = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+}
+
+impl SymmetricCipher {
+    const fn key_len(self) -> usize {
+        match self {
+            Self::Aes128Gcm | Self::Aes128Cbc => 16,
+            Self::Aes256Gcm | Self::Aes256Cbc | Self::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    /// Length of the IV (CBC) or nonce (AEAD)
+    const fn iv_len(self) -> usize {
+        match self {
+            Self::Aes128Cbc | Self::Aes256Cbc => 16,
+            Self::Aes128Gcm | Self::Aes256Gcm | Self::ChaCha20Poly1305 => aead::NONCE_LEN,
+        }
+    }
+
+    const fn is_aead(self) -> bool {
+        !matches!(self, Self::Aes128Cbc | Self::Aes256Cbc)
+    }
+}
+
+/// Text encoding of ciphertext, keys, IVs, and salts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryEncoding {
+    #[default]
+    Base64,
+    Hex,
+}
+
+impl BinaryEncoding {
+    pub(super) fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+            Self::Hex => hex::encode(bytes),
+        }
+    }
+
+    pub(super) fn decode(self, text: &str, what: &str) -> Result<Vec<u8>, GeneratorError> {
+        let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        match self {
+            Self::Base64 => base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .map_err(|e| {
+                    GeneratorError::InvalidParameter(format!("Invalid base64 {what}: {e}"))
+                }),
+            Self::Hex => hex::decode(text)
+                .map_err(|e| GeneratorError::InvalidParameter(format!("Invalid hex {what}: {e}"))),
+        }
+    }
+}
+
+/// Key derivation function for passphrase keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyDerivation {
+    /// PBKDF2-HMAC-SHA256
+    Pbkdf2,
+    /// Argon2id with the `argon2` crate's default cost (19 MiB, 2 passes)
+    Argon2id,
+}
+
+/// Where the key comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SymmetricKey {
+    /// Key bytes in the options' encoding
+    Raw { key: String },
+    /// Key derived from a passphrase
+    Passphrase {
+        passphrase: String,
+        kdf: KeyDerivation,
+        /// Salt in the options' encoding; random when encrypting without one
+        salt: Option<String>,
+        /// PBKDF2 iterations, 600,000 when omitted; unused by Argon2id
+        iterations: Option<u32>,
+    },
+}
+
+/// Options for encryption and decryption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymmetricOptions {
+    pub cipher: SymmetricCipher,
+    pub key: SymmetricKey,
+    /// IV or nonce in the options' encoding; random when encrypting without
+    /// one, required when decrypting
+    pub iv: Option<String>,
+    /// Additional authenticated data (AEAD ciphers only), as UTF-8 text
+    pub aad: Option<String>,
+    /// Encoding of ciphertext, raw key, IV, and salt
+    #[serde(default)]
+    pub encoding: BinaryEncoding,
+}
+
+/// Result of text encryption or decryption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymmetricResult {
+    pub cipher: SymmetricCipher,
+    /// Encoded ciphertext when encrypting, plaintext when decrypting
+    pub output: String,
+    /// IV or nonce used, encoded
+    pub iv: String,
+    /// Salt used for a passphrase key, encoded
+    pub salt: Option<String>,
+    /// Key derived from a passphrase, encoded
+    pub derived_key: Option<String>,
+}
+
+/// Result of file encryption or decryption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymmetricFileResult {
+    pub cipher: SymmetricCipher,
+    pub output_path: String,
+    pub bytes_written: u64,
+    /// IV or nonce used, encoded
+    pub iv: String,
+    /// Salt used for a passphrase key, encoded
+    pub salt: Option<String>,
+    /// Key derived from a passphrase, encoded
+    pub derived_key: Option<String>,
+}
+
+/// Key, IV, and salt resolved from the options
+struct Material {
+    key: Vec<u8>,
+    iv: Vec<u8>,
+    salt: Option<Vec<u8>>,
+}
+
+impl Material {
+    fn describe(&self, options: &SymmetricOptions) -> (String, Option<String>, Option<String>) {
+        let encoding = options.encoding;
+        (
+            encoding.encode(&self.iv),
+            self.salt.as_deref().map(|salt| encoding.encode(salt)),
+            self.salt.as_ref().map(|_| encoding.encode(&self.key)),
+        )
+    }
+}
+
+// =============================================================================
+// Text and File Operations
+// =============================================================================
+
+/// Encrypt UTF-8 text; the ciphertext is returned in the options' encoding
+pub async fn encrypt_text(
+    text: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricResult, GeneratorError> {
+    blocking(move || {
+        let material = resolve(&options, true)?;
+        let ciphertext = seal(&options, &material, text.as_bytes())?;
+        let (iv, salt, derived_key) = material.describe(&options);
+        Ok(SymmetricResult {
+            cipher: options.cipher,
+            output: options.encoding.encode(&ciphertext),
+            iv,
+            salt,
+            derived_key,
+        })
+    })
+    .await
+}
+
+/// Decrypt ciphertext given in the options' encoding back to UTF-8 text
+pub async fn decrypt_text(
+    ciphertext: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricResult, GeneratorError> {
+    blocking(move || {
+        let material = resolve(&options, false)?;
+        let ciphertext = options.encoding.decode(&ciphertext, "ciphertext")?;
+        let plaintext = open(&options, &material, ciphertext)?;
+        let output = String::from_utf8(plaintext).map_err(|_| {
+            GeneratorError::Symmetric(
+                "Decrypted data is not UTF-8 text; decrypt it to a file instead".to_string(),
+            )
+        })?;
+        let (iv, salt, derived_key) = material.describe(&options);
+        Ok(SymmetricResult {
+            cipher: options.cipher,
+            output,
+            iv,
+            salt,
+            derived_key,
+        })
+    })
+    .await
+}
+
+/// Encrypt the file at `input_path` into `output_path`
+pub async fn encrypt_file(
+    input_path: String,
+    output_path: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricFileResult, GeneratorError> {
+    blocking(move || {
+        let material = resolve(&options, true)?;
+        let plaintext = read_file(&input_path)?;
+        let ciphertext = seal(&options, &material, &plaintext)?;
+        write_file(&output_path, &ciphertext, &options, &material)
+    })
+    .await
+}
+
+/// Decrypt the file at `input_path` into `output_path`
+pub async fn decrypt_file(
+    input_path: String,
+    output_path: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricFileResult, GeneratorError> {
+    blocking(move || {
+        let material = resolve(&options, false)?;
+        let ciphertext = read_file(&input_path)?;
+        let plaintext = open(&options, &material, ciphertext)?;
+        write_file(&output_path, &plaintext, &options, &material)
+    })
+    .await
+}
+
+/// Run key derivation and the cipher on the blocking thread pool
+async fn blocking<T, F>(f: F) -> Result<T, GeneratorError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, GeneratorError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| GeneratorError::Worker(e.to_string()))?
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, GeneratorError> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| GeneratorError::Symmetric(format!("Cannot read {path}: {e}")))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(GeneratorError::InvalidParameter(format!(
+            "File is {size} bytes; the limit is {MAX_FILE_BYTES}"
+        )));
+    }
+    std::fs::read(path).map_err(|e| GeneratorError::Symmetric(format!("Cannot read {path}: {e}")))
+}
+
+fn write_file(
+    path: &str,
+    bytes: &[u8],
+    options: &SymmetricOptions,
+    material: &Material,
+) -> Result<SymmetricFileResult, GeneratorError> {
+    std::fs::write(Path::new(path), bytes)
+        .map_err(|e| GeneratorError::Symmetric(format!("Cannot write {path}: {e}")))?;
+    let (iv, salt, derived_key) = material.describe(options);
+    Ok(SymmetricFileResult {
+        cipher: options.cipher,
+        output_path: path.to_string(),
+        bytes_written: bytes.len() as u64,
+        iv,
+        salt,
+        derived_key,
+    })
+}
+
+// =============================================================================
+// Keys and Ciphers
+// =============================================================================
+
+/// Resolve the key, IV, and salt; `encrypting` allows a random IV and salt
+fn resolve(options: &SymmetricOptions, encrypting: bool) -> Result<Material, GeneratorError> {
+    let cipher = options.cipher;
+    let encoding = options.encoding;
+    if options.aad.is_some() && !cipher.is_aead() {
+        return Err(GeneratorError::InvalidParameter(
+            "Additional authenticated data needs an AEAD cipher (GCM or ChaCha20-Poly1305)"
+                .to_string(),
+        ));
+    }
+
+    let iv = match options.iv.as_deref().filter(|iv| !iv.trim().is_empty()) {
+        Some(iv) => encoding.decode(iv, "IV")?,
+        None if encrypting => random_bytes(cipher.iv_len())?,
+        None => {
+            return Err(GeneratorError::InvalidParameter(
+                "The IV or nonce used for encryption is required".to_string(),
+            ))
+        }
+    };
+    if iv.len() != cipher.iv_len() {
+        return Err(GeneratorError::InvalidParameter(format!(
+            "The IV or nonce must be {} bytes, got {}",
+            cipher.iv_len(),
+            iv.len()
+        )));
+    }
+
+    let (key, salt) = match &options.key {
+        SymmetricKey::Raw { key } => (encoding.decode(key, "key")?, None),
+        SymmetricKey::Passphrase {
+            passphrase,
+            kdf,
+            salt,
+            iterations,
+        } => {
+            if passphrase.is_empty() {
+                return Err(GeneratorError::InvalidParameter(
+                    "Passphrase is empty".to_string(),
+                ));
+            }
+            let salt = match salt.as_deref().filter(|salt| !salt.trim().is_empty()) {
+                Some(salt) => encoding.decode(salt, "salt")?,
+                None if encrypting => random_bytes(SALT_BYTES)?,
+                None => {
+                    return Err(GeneratorError::InvalidParameter(
+                        "The salt used for encryption is required".to_string(),
+                    ))
+                }
+            };
+            let key = derive_key(passphrase, &salt, *kdf, *iterations, cipher.key_len())?;
+            (key, Some(salt))
+        }
+    };
+    if key.len() != cipher.key_len() {
+        return Err(GeneratorError::InvalidParameter(format!(
+            "The key must be {} bytes, got {}",
+            cipher.key_len(),
+            key.len()
+        )));
+    }
+    Ok(Material { key, iv, salt })
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    kdf: KeyDerivation,
+    iterations: Option<u32>,
+    len: usize,
+) -> Result<Vec<u8>, GeneratorError> {
+    let mut key = vec![0u8; len];
+    match kdf {
+        KeyDerivation::Pbkdf2 => {
+            let iterations = iterations.unwrap_or(DEFAULT_PBKDF2_ITERATIONS);
+            let iterations = NonZeroU32::new(iterations)
+                .filter(|n| n.get() <= MAX_PBKDF2_ITERATIONS)
+                .ok_or_else(|| {
+                    GeneratorError::InvalidParameter(format!(
+                        "PBKDF2 iterations must be between 1 and {MAX_PBKDF2_ITERATIONS}"
+                    ))
+                })?;
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                iterations,
+                salt,
+                passphrase.as_bytes(),
+                &mut key,
+            );
+        }
+        KeyDerivation::Argon2id => {
+            Argon2::default()
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|e| GeneratorError::Symmetric(format!("Key derivation failed: {e}")))?;
+        }
+    }
+    Ok(key)
+}
+
+fn seal(
+    options: &SymmetricOptions,
+    material: &Material,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, GeneratorError> {
+    let (key, iv) = (material.key.as_slice(), material.iv.as_slice());
+    match options.cipher {
+        SymmetricCipher::Aes128Cbc => Ok(cbc::Encryptor::<aes::Aes128>::new_from_slices(key, iv)
+            .map_err(|e| GeneratorError::Symmetric(e.to_string()))?
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext)),
+        SymmetricCipher::Aes256Cbc => Ok(cbc::Encryptor::<aes::Aes256>::new_from_slices(key, iv)
+            .map_err(|e| GeneratorError::Symmetric(e.to_string()))?
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext)),
+        cipher => {
+            let mut buffer = plaintext.to_vec();
+            aead_key(cipher, key)?
+                .seal_in_place_append_tag(
+                    nonce(iv)?,
+                    Aad::from(options.aad.as_deref().unwrap_or_default()),
+                    &mut buffer,
+                )
+                .map_err(|_| GeneratorError::Symmetric("Encryption failed".to_string()))?;
+            Ok(buffer)
+        }
+    }
+}
+
+fn open(
+    options: &SymmetricOptions,
+    material: &Material,
+    mut ciphertext: Vec<u8>,
+) -> Result<Vec<u8>, GeneratorError> {
+    let (key, iv) = (material.key.as_slice(), material.iv.as_slice());
+    let bad_padding = |_| {
+        GeneratorError::Symmetric(
+            "Decryption failed: bad padding (wrong key or IV, or corrupted data)".to_string(),
+        )
+    };
+    match options.cipher {
+        SymmetricCipher::Aes128Cbc => cbc::Decryptor::<aes::Aes128>::new_from_slices(key, iv)
+            .map_err(|e| GeneratorError::Symmetric(e.to_string()))?
+            .decrypt_padded_vec_mut::<Pkcs7>(&ciphertext)
+            .map_err(bad_padding),
+        SymmetricCipher::Aes256Cbc => cbc::Decryptor::<aes::Aes256>::new_from_slices(key, iv)
+            .map_err(|e| GeneratorError::Symmetric(e.to_string()))?
+            .decrypt_padded_vec_mut::<Pkcs7>(&ciphertext)
+            .map_err(bad_padding),
+        cipher => {
+            let len = aead_key(cipher, key)?
+                .open_in_place(
+                    nonce(iv)?,
+                    Aad::from(options.aad.as_deref().unwrap_or_default()),
+                    &mut ciphertext,
+                )
+                .map_err(|_| {
+                    GeneratorError::Symmetric(
+                        "Decryption failed: authentication tag mismatch (wrong key, nonce, or \
+                         associated data, or tampered data)"
+                            .to_string(),
+                    )
+                })?
+                .len();
+            ciphertext.truncate(len);
+            Ok(ciphertext)
+        }
+    }
+}
+
+fn aead_key(cipher: SymmetricCipher, key: &[u8]) -> Result<LessSafeKey, GeneratorError> {
+    let algorithm = match cipher {
+        SymmetricCipher::Aes128Gcm => &aead::AES_128_GCM,
+        SymmetricCipher::Aes256Gcm => &aead::AES_256_GCM,
+        _ => &aead::CHACHA20_POLY1305,
+    };
+    UnboundKey::new(algorithm, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| GeneratorError::Symmetric("Invalid key".to_string()))
+}
+
+fn nonce(iv: &[u8]) -> Result<Nonce, GeneratorError> {
+    Nonce::try_assume_unique_for_key(iv)
+        .map_err(|_| GeneratorError::Symmetric("Invalid nonce".to_string()))
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, GeneratorError> {
+    let mut bytes = vec![0u8; len];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| GeneratorError::Symmetric("Failed to read random bytes".to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(cipher: SymmetricCipher, key: &str, iv: Option<&str>) -> SymmetricOptions {
+        SymmetricOptions {
+            cipher,
+            key: SymmetricKey::Raw {
+                key: key.to_string(),
+            },
+            iv: iv.map(str::to_string),
+            aad: None,
+            encoding: BinaryEncoding::Hex,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_known_vectors() {
+        // NIST SP 800-38A F.2.1, first block; PKCS#7 adds a second.
+        let options = raw(
+            SymmetricCipher::Aes128Cbc,
+            "2b7e151628aed2a6abf7158809cf4f3c",
+            Some("000102030405060708090a0b0c0d0e0f"),
+        );
+        let material = resolve(&options, true).unwrap();
+        let plaintext = hex::decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+        let ciphertext = seal(&options, &material, &plaintext).unwrap();
+        assert_eq!(
+            hex::encode(&ciphertext[..16]),
+            "7649abac8119b246cee98e9b12e9197d"
+        );
+        assert_eq!(ciphertext.len(), 32);
+
+        // GCM test case 1: zero key and IV, empty plaintext gives the bare tag.
+        let options = raw(
+            SymmetricCipher::Aes128Gcm,
+            &"00".repeat(16),
+            Some(&"00".repeat(12)),
+        );
+        let result = encrypt_text(String::new(), options).await.unwrap();
+        assert_eq!(result.output, "58e2fccefa7e3061367f1d57a4e7455a");
+
+        // PBKDF2-HMAC-SHA256 of "password" and "salt", one iteration.
+        let key = derive_key("password", b"salt", KeyDerivation::Pbkdf2, Some(1), 32).unwrap();
+        assert_eq!(
+            hex::encode(key),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_round_trips_with_passphrase() {
+        for cipher in [
+            SymmetricCipher::Aes128Gcm,
+            SymmetricCipher::Aes256Gcm,
+            SymmetricCipher::Aes128Cbc,
+            SymmetricCipher::Aes256Cbc,
+            SymmetricCipher::ChaCha20Poly1305,
+        ] {
+            let mut options = SymmetricOptions {
+                cipher,
+                key: SymmetricKey::Passphrase {
+                    passphrase: "correct horse".to_string(),
+                    kdf: KeyDerivation::Pbkdf2,
+                    salt: None,
+                    iterations: Some(1_000),
+                },
+                iv: None,
+                aad: cipher.is_aead().then(|| "header".to_string()),
+                encoding: BinaryEncoding::Base64,
+            };
+            let sealed = encrypt_text("秘密のメッセージ".to_string(), options.clone())
+                .await
+                .unwrap();
+            options.iv = Some(sealed.iv.clone());
+            options.key = SymmetricKey::Passphrase {
+                passphrase: "correct horse".to_string(),
+                kdf: KeyDerivation::Pbkdf2,
+                salt: sealed.salt.clone(),
+                iterations: Some(1_000),
+            };
+            let opened = decrypt_text(sealed.output.clone(), options.clone())
+                .await
+                .unwrap();
+            assert_eq!(opened.output, "秘密のメッセージ");
+            assert_eq!(opened.derived_key, sealed.derived_key);
+
+            if cipher.is_aead() {
+                options.aad = Some("other".to_string());
+                assert!(decrypt_text(sealed.output, options).await.is_err());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_round_trip_with_argon2() {
+        let dir = std::env::temp_dir().join(format!("kogu-symmetric-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("plain.bin");
+        let sealed = dir.join("sealed.bin");
+        let opened = dir.join("opened.bin");
+        let data: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+        std::fs::write(&plain, &data).unwrap();
+
+        let key = |salt| SymmetricKey::Passphrase {
+            passphrase: "hunter2".to_string(),
+            kdf: KeyDerivation::Argon2id,
+            salt,
+            iterations: None,
+        };
+        let mut options = SymmetricOptions {
+            cipher: SymmetricCipher::ChaCha20Poly1305,
+            key: key(None),
+            iv: None,
+            aad: None,
+            encoding: BinaryEncoding::Hex,
+        };
+        let result = encrypt_file(
+            plain.display().to_string(),
+            sealed.display().to_string(),
+            options.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.bytes_written, 10_016);
+
+        options.iv = Some(result.iv);
+        options.key = key(result.salt);
+        decrypt_file(
+            sealed.display().to_string(),
+            opened.display().to_string(),
+            options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read(&opened).unwrap(), data);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validation() {
+        let short_key = raw(SymmetricCipher::Aes256Gcm, "00", None);
+        assert!(resolve(&short_key, true).is_err());
+
+        let missing_iv = raw(SymmetricCipher::Aes128Cbc, &"00".repeat(16), None);
+        assert!(resolve(&missing_iv, false).is_err());
+        assert_eq!(resolve(&missing_iv, true).unwrap().iv.len(), 16);
+
+        let mut cbc_aad = missing_iv;
+        cbc_aad.aad = Some("x".to_string());
+        assert!(resolve(&cbc_aad, true).is_err());
+    }
+}
@@ -1,57 +1,236 @@
 //! Unified worker process management for CPU-intensive operations
 //!
-//! This module provides process isolation for `BCrypt`, SSH, and GPG operations,
-//! enabling true cancellation via process termination.
+//! This module provides process isolation for `BCrypt`, SSH, GPG, and X.509 operations,
+//! enabling true cancellation via process termination. Requests and
+//! responses travel as length-prefixed frames ([`crate::worker_protocol`]),
+//! so armored keys and other multi-line payloads pass through intact.
+//!
+//! By default requests go to a pool of warm workers started with
+//! `--serve`, which answer one request after another and so skip the
+//! process start-up of a fresh sidecar. Cancelling a job still kills its
+//! worker; the pool starts a replacement.
 
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
 
 use serde::{Deserialize, Serialize};
+use tauri::async_runtime::Receiver;
 use tauri::AppHandle;
-use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent, TerminatedPayload};
 use tauri_plugin_shell::ShellExt;
+use tokio::sync::mpsc::error::TryRecvError;
 
+use super::gpg::PgpMessageOptions;
+use super::x509::X509Options;
 use super::GeneratorError;
+use crate::worker_protocol::{frame_len, read_message, write_frame};
+
+/// Warm workers kept by default
+pub const DEFAULT_POOL_SIZE: usize = 2;
+
+/// Most warm workers the pool keeps
+pub const MAX_POOL_SIZE: usize = 8;
 
 // =============================================================================
 // Process State Management
 // =============================================================================
 
-/// State for managing the worker process
-#[derive(Default)]
+/// State for managing worker processes, one per running job
+///
+/// Each isolated operation runs in its own worker process under a job ID,
+/// so a key generation and a hash benchmark can run side by side and be
+/// cancelled independently.
 pub struct WorkerProcessState {
-    /// Currently running child process
-    child: Mutex<Option<CommandChild>>,
+    /// Running jobs by ID; `None` until the job's process has spawned
+    jobs: Mutex<HashMap<String, Option<CommandChild>>>,
+    /// Idle warm workers
+    pool: Mutex<Vec<PooledWorker>>,
+    /// Idle workers to keep; 0 spawns a fresh worker per request
+    pool_size: AtomicUsize,
+}
+
+impl Default for WorkerProcessState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WorkerProcessState {
     /// Create a new process state
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
-            child: Mutex::new(None),
+            jobs: Mutex::new(HashMap::new()),
+            pool: Mutex::new(Vec::new()),
+            pool_size: AtomicUsize::new(DEFAULT_POOL_SIZE),
         }
     }
 
-    /// Store a child process handle
-    fn set_child(&self, child: CommandChild) {
-        let mut guard = self
-            .child
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, Option<CommandChild>>> {
+        self.jobs
             .lock()
-            .unwrap_or_else(std::sync::PoisonError::into_inner);
-        *guard = Some(child);
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
 
-    /// Take the child process handle (removes it from state)
-    fn take_child(&self) -> Option<CommandChild> {
-        let mut guard = self
-            .child
+    fn pool(&self) -> MutexGuard<'_, Vec<PooledWorker>> {
+        self.pool
             .lock()
-            .unwrap_or_else(std::sync::PoisonError::into_inner);
-        guard.take()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Reserve a slot for a job, under a new UUID when no ID is given
+    fn begin(&self, job_id: Option<String>) -> Result<JobSlot<'_>, GeneratorError> {
+        let id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let mut jobs = self.jobs();
+        if jobs.contains_key(&id) {
+            return Err(GeneratorError::Worker(format!(
+                "Worker job {id} is already running"
+            )));
+        }
+        jobs.insert(id.clone(), None);
+        drop(jobs);
+        Ok(JobSlot { state: self, id })
+    }
+
+    /// Kill the worker process of a job
+    ///
+    /// Returns `true` if the job was running. A job cancelled before its
+    /// process spawned is killed as soon as it does.
+    pub fn kill(&self, job_id: &str) -> bool {
+        self.jobs()
+            .remove(job_id)
+            .is_some_and(|child| child.is_none_or(|child| child.kill().is_ok()))
+    }
+
+    /// Kill the worker processes of all running jobs
+    pub fn kill_all(&self) -> bool {
+        let jobs: Vec<_> = self.jobs().drain().collect();
+        let any = !jobs.is_empty();
+        for child in jobs.into_iter().filter_map(|(_, child)| child) {
+            let _ = child.kill();
+        }
+        any
+    }
+
+    /// Number of warm workers the pool keeps
+    pub fn pool_size(&self) -> usize {
+        self.pool_size.load(Ordering::Relaxed)
+    }
+
+    /// Change how many warm workers the pool keeps, stopping any beyond
+    /// the new size; 0 turns pooling off
+    pub fn set_pool_size(&self, size: usize) -> Result<(), GeneratorError> {
+        if size > MAX_POOL_SIZE {
+            return Err(GeneratorError::InvalidParameter(format!(
+                "Pool size must be at most {MAX_POOL_SIZE}, got {size}"
+            )));
+        }
+        self.pool_size.store(size, Ordering::Relaxed);
+        let surplus: Vec<_> = {
+            let mut pool = self.pool();
+            let keep = size.min(pool.len());
+            pool.drain(keep..).collect()
+        };
+        for worker in surplus {
+            let _ = worker.child.kill();
+        }
+        Ok(())
+    }
+
+    /// Start warm workers until the pool is full
+    pub fn warm(&self, app: &AppHandle) -> Result<(), GeneratorError> {
+        let missing = self.pool_size().saturating_sub(self.pool().len());
+        for _ in 0..missing {
+            self.checkin(PooledWorker::spawn(app)?);
+        }
+        Ok(())
+    }
+
+    /// Take a live warm worker from the pool
+    fn checkout(&self) -> Option<PooledWorker> {
+        loop {
+            let mut worker = self.pool().pop()?;
+            if worker.is_alive() {
+                return Some(worker);
+            }
+        }
+    }
+
+    /// Return a worker to the pool, or stop it if the pool is full
+    fn checkin(&self, worker: PooledWorker) {
+        let mut pool = self.pool();
+        if pool.len() < self.pool_size() {
+            pool.push(worker);
+        } else {
+            drop(pool);
+            let _ = worker.child.kill();
+        }
+    }
+}
+
+/// A job's entry in [`WorkerProcessState`], removed when the request
+/// finishes or its command future is dropped
+struct JobSlot<'a> {
+    state: &'a WorkerProcessState,
+    id: String,
+}
+
+impl JobSlot<'_> {
+    /// Store the job's process handle for cancellation
+    ///
+    /// Returns `false`, having killed the process, if the job was
+    /// cancelled while it spawned.
+    fn attach(&self, child: CommandChild) -> bool {
+        let mut jobs = self.state.jobs();
+        if let Some(slot) = jobs.get_mut(&self.id) {
+            *slot = Some(child);
+            true
+        } else {
+            drop(jobs);
+            let _ = child.kill();
+            false
+        }
+    }
+
+    /// Take back the process handle; `None` if the job was cancelled
+    fn detach(&self) -> Option<CommandChild> {
+        self.state.jobs().get_mut(&self.id).and_then(Option::take)
     }
+}
 
-    /// Kill the current child process if running
-    pub fn kill(&self) -> bool {
-        self.take_child().is_some_and(|child| child.kill().is_ok())
+impl Drop for JobSlot<'_> {
+    fn drop(&mut self) {
+        // A process still attached here belongs to an abandoned request
+        let slot = self.state.jobs().remove(&self.id);
+        if let Some(Some(child)) = slot {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// A worker started with `--serve`, waiting for its next request
+struct PooledWorker {
+    rx: Receiver<CommandEvent>,
+    child: CommandChild,
+}
+
+impl PooledWorker {
+    fn spawn(app: &AppHandle) -> Result<Self, GeneratorError> {
+        let (rx, child) = spawn_sidecar(app, true)?;
+        Ok(Self { rx, child })
+    }
+
+    /// Whether the process is still running, judging by the events it
+    /// sent while idle
+    fn is_alive(&mut self) -> bool {
+        loop {
+            match self.rx.try_recv() {
+                Ok(CommandEvent::Terminated(_) | CommandEvent::Error(_))
+                | Err(TryRecvError::Disconnected) => return false,
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => return true,
+            }
+        }
     }
 }
 
@@ -60,96 +239,102 @@ impl WorkerProcessState {
 // =============================================================================
 
 /// Execute a request on the worker sidecar
+///
+/// The request runs as job `job_id`, or under a fresh UUID when none is
+/// given; [`WorkerProcessState::kill`] with the same ID cancels it.
 pub async fn execute<Req, Res>(
     app: &AppHandle,
     request: &Req,
     state: &WorkerProcessState,
+    job_id: Option<String>,
 ) -> Result<Res, GeneratorError>
 where
     Req: Serialize + Sync,
     Res: for<'de> Deserialize<'de>,
 {
-    // Serialize request
-    let request_json =
-        serde_json::to_string(request).map_err(|e| GeneratorError::Worker(e.to_string()))?;
-
-    // Spawn worker and get output
-    let output = spawn_worker(app, &request_json, state).await?;
-
-    // Parse response
-    serde_json::from_str(&output).map_err(|e| GeneratorError::Worker(e.to_string()))
+    // Serialize and frame the request
+    let body = serde_json::to_vec(request).map_err(|e| GeneratorError::Worker(e.to_string()))?;
+    let mut frame = Vec::with_capacity(body.len() + 8);
+    write_frame(&mut frame, &body).map_err(|e| GeneratorError::Worker(e.to_string()))?;
+
+    // Run on a warm worker, or spawn one for this request alone
+    let job = state.begin(job_id)?;
+    let output = if state.pool_size() == 0 {
+        spawn_worker(app, &frame, &job).await?
+    } else {
+        dispatch_pooled(app, &frame, state, &job).await?
+    };
+
+    // Unframe the response; an older worker answers with a single line
+    let message = read_message(&mut output.as_slice())
+        .map_err(|e| GeneratorError::Worker(format!("Malformed worker response: {e}")))?;
+    serde_json::from_slice(message.body()).map_err(|e| GeneratorError::Worker(e.to_string()))
 }
 
-/// Spawn the worker sidecar and execute a request
-async fn spawn_worker(
+/// Create and spawn the worker sidecar
+fn spawn_sidecar(
     app: &AppHandle,
-    request_json: &str,
-    state: &WorkerProcessState,
-) -> Result<String, GeneratorError> {
-    // Kill any existing process first
-    state.kill();
-
+    serve: bool,
+) -> Result<(Receiver<CommandEvent>, CommandChild), GeneratorError> {
     // Create the sidecar command
     let sidecar_command = app
         .shell()
         .sidecar("worker")
         .map_err(|e| GeneratorError::Worker(format!("Failed to create sidecar command: {e}")))?;
-
-    // Spawn the sidecar process
-    let (mut rx, mut child) = sidecar_command
+    let sidecar_command = if serve {
+        sidecar_command.arg("--serve")
+    } else {
+        sidecar_command
+    };
+
+    // Spawn the sidecar process; raw output keeps frames byte-exact
+    sidecar_command
+        .set_raw_out(true)
         .spawn()
-        .map_err(|e| GeneratorError::Worker(format!("Failed to spawn sidecar: {e}")))?;
+        .map_err(|e| GeneratorError::Worker(format!("Failed to spawn sidecar: {e}")))
+}
+
+/// Spawn the worker sidecar and execute a request
+async fn spawn_worker(
+    app: &AppHandle,
+    request: &[u8],
+    job: &JobSlot<'_>,
+) -> Result<Vec<u8>, GeneratorError> {
+    let (mut rx, mut child) = spawn_sidecar(app, false)?;
 
-    // Write request to stdin (with newline for line-based reading)
-    let input = format!("{request_json}\n");
+    // Write the framed request to stdin
     child
-        .write(input.as_bytes())
+        .write(request)
         .map_err(|e| GeneratorError::Worker(format!("Failed to write to stdin: {e}")))?;
 
     // Store child handle for potential cancellation
-    state.set_child(child);
+    if !job.attach(child) {
+        return Err(GeneratorError::Cancelled);
+    }
 
     // Collect output from the process
-    let mut output = String::new();
+    let mut output = Vec::new();
     let mut stderr_output = String::new();
 
     while let Some(event) = rx.recv().await {
         match event {
-            CommandEvent::Stdout(line_bytes) => {
-                let line = String::from_utf8_lossy(&line_bytes);
-                output.push_str(&line);
-            }
+            CommandEvent::Stdout(bytes) => output.extend_from_slice(&bytes),
             CommandEvent::Stderr(line_bytes) => {
                 let line = String::from_utf8_lossy(&line_bytes);
                 stderr_output.push_str(&line);
             }
             CommandEvent::Error(e) => {
                 // Clear child from state
-                state.take_child();
+                job.detach();
                 return Err(GeneratorError::Worker(format!("Process error: {e}")));
             }
             CommandEvent::Terminated(status) => {
                 // Clear child from state
-                state.take_child();
+                job.detach();
 
                 // Check if process exited successfully (code 0)
-                let success = status.code == Some(0);
-                if !success {
-                    // Check if it was killed (signal termination indicates cancellation)
-                    if status.signal.is_some() {
-                        return Err(GeneratorError::Cancelled);
-                    }
-                    // Include stderr in error message if available
-                    let error_msg = if stderr_output.trim().is_empty() {
-                        format!("Worker exited with code: {:?}", status.code)
-                    } else {
-                        format!(
-                            "Worker exited with code {:?}: {}",
-                            status.code,
-                            stderr_output.trim()
-                        )
-                    };
-                    return Err(GeneratorError::Worker(error_msg));
+                if status.code != Some(0) {
+                    return Err(exit_error(&status, &stderr_output));
                 }
                 break;
             }
@@ -157,13 +342,114 @@ async fn spawn_worker(
         }
     }
 
-    if output.trim().is_empty() {
+    if output.trim_ascii().is_empty() {
         return Err(GeneratorError::Worker("No output from worker".to_string()));
     }
 
     Ok(output)
 }
 
+/// Execute a request on a warm worker, starting one if none is idle
+///
+/// The worker goes back to the pool once its response frame is complete.
+/// A worker lost to cancellation or a crash is replaced so the pool stays
+/// warm.
+async fn dispatch_pooled(
+    app: &AppHandle,
+    request: &[u8],
+    state: &WorkerProcessState,
+    job: &JobSlot<'_>,
+) -> Result<Vec<u8>, GeneratorError> {
+    let worker = match state.checkout() {
+        Some(worker) => worker,
+        None => PooledWorker::spawn(app)?,
+    };
+    let result = run_pooled(worker, request, state, job).await;
+    if result.is_err() {
+        let _ = state.warm(app);
+    }
+    result
+}
+
+async fn run_pooled(
+    worker: PooledWorker,
+    request: &[u8],
+    state: &WorkerProcessState,
+    job: &JobSlot<'_>,
+) -> Result<Vec<u8>, GeneratorError> {
+    let PooledWorker { mut rx, mut child } = worker;
+
+    // Write the framed request to stdin
+    if let Err(e) = child.write(request) {
+        let _ = child.kill();
+        return Err(GeneratorError::Worker(format!(
+            "Failed to write to stdin: {e}"
+        )));
+    }
+
+    // Store child handle for potential cancellation
+    if !job.attach(child) {
+        return Err(GeneratorError::Cancelled);
+    }
+
+    // Collect output until the response frame is complete; the worker
+    // keeps running, so there is no end of output to wait for
+    let mut output = Vec::new();
+    let mut stderr_output = String::new();
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(bytes) => {
+                output.extend_from_slice(&bytes);
+                let complete = frame_len(&output).map_err(|e| {
+                    GeneratorError::Worker(format!("Malformed worker response: {e}"))
+                })?;
+                if complete.is_some() {
+                    // A job cancelled at this moment has already killed
+                    // the worker, which then stays out of the pool
+                    if let Some(child) = job.detach() {
+                        state.checkin(PooledWorker { rx, child });
+                    }
+                    return Ok(output);
+                }
+            }
+            CommandEvent::Stderr(line_bytes) => {
+                let line = String::from_utf8_lossy(&line_bytes);
+                stderr_output.push_str(&line);
+            }
+            CommandEvent::Error(e) => {
+                return Err(GeneratorError::Worker(format!("Process error: {e}")));
+            }
+            CommandEvent::Terminated(status) => {
+                job.detach();
+                return Err(exit_error(&status, &stderr_output));
+            }
+            _ => {}
+        }
+    }
+
+    Err(GeneratorError::Worker("No output from worker".to_string()))
+}
+
+/// Error for a worker that exited before answering
+fn exit_error(status: &TerminatedPayload, stderr_output: &str) -> GeneratorError {
+    // Check if it was killed (signal termination indicates cancellation)
+    if status.signal.is_some() {
+        return GeneratorError::Cancelled;
+    }
+    // Include stderr in error message if available
+    let error_msg = if stderr_output.trim().is_empty() {
+        format!("Worker exited with code: {:?}", status.code)
+    } else {
+        format!(
+            "Worker exited with code {:?}: {}",
+            status.code,
+            stderr_output.trim()
+        )
+    };
+    GeneratorError::Worker(error_msg)
+}
+
 // =============================================================================
 // BCrypt Types
 // =============================================================================
@@ -320,3 +606,143 @@ pub struct GpgKeyResponse {
     pub method_used: Option<String>,
     pub error: Option<String>,
 }
+
+/// PGP message operation request
+#[derive(Debug, Serialize)]
+pub struct PgpMessageRequest<'a> {
+    #[serde(rename = "type")]
+    pub request_type: &'static str,
+    /// `encrypt`, `decrypt`, `clearsign`, `detach_sign`, or `verify`
+    pub operation: &'static str,
+    #[serde(flatten)]
+    pub options: &'a PgpMessageOptions,
+}
+
+impl<'a> PgpMessageRequest<'a> {
+    pub const fn new(operation: &'static str, options: &'a PgpMessageOptions) -> Self {
+        Self {
+            request_type: "pgp",
+            operation,
+            options,
+        }
+    }
+}
+
+/// PGP message operation response
+#[derive(Debug, Deserialize)]
+pub struct PgpMessageResponse {
+    pub success: bool,
+    pub output: Option<String>,
+    pub valid: Option<bool>,
+    pub fingerprint: Option<String>,
+    pub error: Option<String>,
+}
+
+// =============================================================================
+// X.509 Types
+// =============================================================================
+
+/// Certificate or CSR generation request
+#[derive(Debug, Serialize)]
+pub struct X509Request<'a> {
+    #[serde(rename = "type")]
+    pub request_type: &'static str,
+    /// "certificate" or "csr"
+    pub output: &'static str,
+    #[serde(flatten)]
+    pub options: &'a X509Options,
+}
+
+impl<'a> X509Request<'a> {
+    pub const fn new(output: &'static str, options: &'a X509Options) -> Self {
+        Self {
+            request_type: "x509",
+            output,
+            options,
+        }
+    }
+}
+
+/// Certificate or CSR generation response
+#[derive(Debug, Deserialize)]
+pub struct X509Response {
+    pub success: bool,
+    pub algorithm: Option<String>,
+    pub pem: Option<String>,
+    pub der: Option<String>,
+    pub private_key: Option<String>,
+    pub fingerprint: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_ids() {
+        let state = WorkerProcessState::new();
+        let first = state.begin(Some("keygen".to_string())).unwrap();
+        let second = state.begin(None).unwrap();
+        assert!(uuid::Uuid::parse_str(&second.id).is_ok());
+        assert!(state.begin(Some("keygen".to_string())).is_err());
+
+        // Dropping a finished job frees its ID
+        drop(first);
+        let again = state.begin(Some("keygen".to_string())).unwrap();
+
+        // Cancelling one job leaves the others running
+        assert!(state.kill("keygen"));
+        assert!(!state.kill("keygen"));
+        assert!(state.jobs().contains_key(&second.id));
+        drop(again);
+
+        assert!(state.kill_all());
+        assert!(!state.kill_all());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_worker_is_checked_in_after_a_complete_frame() {
+        use tauri_plugin_shell::ShellExt;
+
+        let app = tauri::test::mock_builder()
+            .plugin(tauri_plugin_shell::init())
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        // `cat` echoes the request frame, which is also a complete response
+        // frame, and keeps running like a `--serve` worker.
+        let (rx, child) = app
+            .shell()
+            .command("cat")
+            .set_raw_out(true)
+            .spawn()
+            .unwrap();
+
+        let state = WorkerProcessState::new();
+        let job = state.begin(None).unwrap();
+        let mut frame = Vec::new();
+        write_frame(&mut frame, br#"{"type":"bcrypt"}"#).unwrap();
+        let output = run_pooled(PooledWorker { rx, child }, &frame, &state, &job)
+            .await
+            .unwrap();
+        assert_eq!(output, frame);
+        drop(job);
+
+        let mut worker = state.checkout().unwrap();
+        assert!(worker.is_alive());
+        assert!(state.checkout().is_none());
+        let _ = worker.child.kill();
+    }
+
+    #[test]
+    fn test_pool_size() {
+        let state = WorkerProcessState::new();
+        assert_eq!(state.pool_size(), DEFAULT_POOL_SIZE);
+        assert!(state.set_pool_size(MAX_POOL_SIZE + 1).is_err());
+        assert_eq!(state.pool_size(), DEFAULT_POOL_SIZE);
+        state.set_pool_size(0).unwrap();
+        assert_eq!(state.pool_size(), 0);
+        assert!(state.checkout().is_none());
+    }
+}
@@ -0,0 +1,384 @@
+//! Self-signed X.509 certificate and certificate request generation
+//!
+//! The key pair is generated and the certificate or CSR signed in the
+//! worker process, so generating a large RSA key can be cancelled. The
+//! result carries the equivalent `openssl req` command.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::worker::{self, WorkerProcessState, X509Request, X509Response};
+use super::GeneratorError;
+
+/// Longest validity accepted, in days (100 years)
+const MAX_VALIDITY_DAYS: u32 = 36_500;
+
+/// Key algorithm of the generated key pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum X509KeyAlgorithm {
+    Rsa2048,
+    Rsa3072,
+    Rsa4096,
+    EcdsaP256,
+    EcdsaP384,
+    Ed25519,
+}
+
+impl X509KeyAlgorithm {
+    /// `-newkey` argument, and `-pkeyopt` for a curve, of `openssl req`
+    const fn openssl_key_args(self) -> &'static str {
+        match self {
+            Self::Rsa2048 => "-newkey rsa:2048",
+            Self::Rsa3072 => "-newkey rsa:3072",
+            Self::Rsa4096 => "-newkey rsa:4096",
+            Self::EcdsaP256 => "-newkey ec -pkeyopt ec_paramgen_curve:P-256",
+            Self::EcdsaP384 => "-newkey ec -pkeyopt ec_paramgen_curve:P-384 -sha384",
+            Self::Ed25519 => "-newkey ed25519",
+        }
+    }
+}
+
+/// Key usage bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum X509KeyUsage {
+    DigitalSignature,
+    NonRepudiation,
+    KeyEncipherment,
+    DataEncipherment,
+    KeyAgreement,
+    KeyCertSign,
+    CrlSign,
+}
+
+impl X509KeyUsage {
+    const fn openssl_name(self) -> &'static str {
+        match self {
+            Self::DigitalSignature => "digitalSignature",
+            Self::NonRepudiation => "nonRepudiation",
+            Self::KeyEncipherment => "keyEncipherment",
+            Self::DataEncipherment => "dataEncipherment",
+            Self::KeyAgreement => "keyAgreement",
+            Self::KeyCertSign => "keyCertSign",
+            Self::CrlSign => "cRLSign",
+        }
+    }
+}
+
+/// Extended key usage purpose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum X509ExtendedKeyUsage {
+    ServerAuth,
+    ClientAuth,
+    CodeSigning,
+    EmailProtection,
+    TimeStamping,
+}
+
+impl X509ExtendedKeyUsage {
+    const fn openssl_name(self) -> &'static str {
+        match self {
+            Self::ServerAuth => "serverAuth",
+            Self::ClientAuth => "clientAuth",
+            Self::CodeSigning => "codeSigning",
+            Self::EmailProtection => "emailProtection",
+            Self::TimeStamping => "timeStamping",
+        }
+    }
+}
+
+/// Distinguished name of the subject; empty attributes are left out
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct X509Subject {
+    #[serde(default)]
+    pub common_name: String,
+    pub organization: Option<String>,
+    pub organizational_unit: Option<String>,
+    pub locality: Option<String>,
+    pub state: Option<String>,
+    /// Two-letter country code
+    pub country: Option<String>,
+}
+
+/// Options for certificate and CSR generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct X509Options {
+    /// Key algorithm to use
+    pub algorithm: X509KeyAlgorithm,
+    pub subject: X509Subject,
+    /// DNS names, IP addresses, email addresses, or URIs
+    #[serde(default)]
+    pub subject_alt_names: Vec<String>,
+    #[serde(default)]
+    pub key_usages: Vec<X509KeyUsage>,
+    #[serde(default)]
+    pub extended_key_usages: Vec<X509ExtendedKeyUsage>,
+    /// Days the certificate is valid from now; unused for a CSR
+    #[serde(default = "default_validity_days")]
+    pub validity_days: u32,
+    /// Mark the certificate as a CA in its basic constraints
+    #[serde(default)]
+    pub is_ca: bool,
+}
+
+const fn default_validity_days() -> u32 {
+    365
+}
+
+/// What to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X509Output {
+    Certificate,
+    Csr,
+}
+
+impl X509Output {
+    /// Name of the output in the worker request
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Certificate => "certificate",
+            Self::Csr => "csr",
+        }
+    }
+}
+
+/// Result of certificate or CSR generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct X509Result {
+    /// Algorithm used
+    pub algorithm: String,
+    /// Certificate or CSR in PEM format
+    pub pem: String,
+    /// Certificate or CSR in DER format, base64-encoded
+    pub der: String,
+    /// PKCS#8 private key in PEM format
+    pub private_key: String,
+    /// SHA-256 fingerprint of the DER
+    pub fingerprint: String,
+    /// Equivalent openssl command
+    pub openssl_command: String,
+}
+
+// =============================================================================
+// Process-Isolated Operations
+// =============================================================================
+
+/// Generate a key pair and a self-signed certificate or CSR using process
+/// isolation
+///
+/// Spawns a separate process for the computation, allowing true cancellation.
+pub async fn generate_isolated(
+    app: &AppHandle,
+    options: X509Options,
+    output: X509Output,
+    state: &WorkerProcessState,
+    job_id: Option<String>,
+) -> Result<X509Result, GeneratorError> {
+    validate(&options, output)?;
+
+    let request = X509Request::new(output.as_str(), &options);
+    let response: X509Response = worker::execute(app, &request, state, job_id).await?;
+
+    if response.success {
+        Ok(X509Result {
+            algorithm: response.algorithm.unwrap_or_default(),
+            pem: response.pem.unwrap_or_default(),
+            der: response.der.unwrap_or_default(),
+            private_key: response.private_key.unwrap_or_default(),
+            fingerprint: response.fingerprint.unwrap_or_default(),
+            openssl_command: build_openssl_command(&options, output),
+        })
+    } else {
+        Err(GeneratorError::X509(
+            response
+                .error
+                .unwrap_or_else(|| "Unknown error".to_string()),
+        ))
+    }
+}
+
+fn validate(options: &X509Options, output: X509Output) -> Result<(), GeneratorError> {
+    let has_san = options
+        .subject_alt_names
+        .iter()
+        .any(|name| !name.trim().is_empty());
+    if options.subject.common_name.trim().is_empty() && !has_san {
+        return Err(GeneratorError::InvalidParameter(
+            "A common name or subject alternative name is required".to_string(),
+        ));
+    }
+    let country = options.subject.country.as_deref().map(str::trim);
+    if let Some(country) = country.filter(|country| !country.is_empty()) {
+        if country.len() != 2 || !country.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(GeneratorError::InvalidParameter(format!(
+                "Country must be a two-letter code, got \"{country}\""
+            )));
+        }
+    }
+    if output == X509Output::Certificate
+        && !(1..=MAX_VALIDITY_DAYS).contains(&options.validity_days)
+    {
+        return Err(GeneratorError::InvalidParameter(format!(
+            "Validity must be between 1 and {MAX_VALIDITY_DAYS} days, got {}",
+            options.validity_days
+        )));
+    }
+    Ok(())
+}
+
+/// `openssl req` command producing the same key and certificate or CSR
+fn build_openssl_command(options: &X509Options, output: X509Output) -> String {
+    let mut args = vec!["openssl req".to_string()];
+    if output == X509Output::Certificate {
+        args.push("-x509".to_string());
+    }
+    args.push(options.algorithm.openssl_key_args().to_string());
+    args.push("-nodes -keyout key.pem".to_string());
+    args.push(match output {
+        X509Output::Certificate => format!("-out cert.pem -days {}", options.validity_days),
+        X509Output::Csr => "-out request.csr".to_string(),
+    });
+    args.push(format!(
+        "-subj {}",
+        shell_quote(&openssl_subject(&options.subject))
+    ));
+
+    let mut extensions = vec![if options.is_ca {
+        "basicConstraints=critical,CA:TRUE".to_string()
+    } else {
+        "basicConstraints=critical,CA:FALSE".to_string()
+    }];
+    if !options.key_usages.is_empty() {
+        let usages: Vec<_> = options
+            .key_usages
+            .iter()
+            .map(|usage| usage.openssl_name())
+            .collect();
+        extensions.push(format!("keyUsage=critical,{}", usages.join(",")));
+    }
+    if !options.extended_key_usages.is_empty() {
+        let usages: Vec<_> = options
+            .extended_key_usages
+            .iter()
+            .map(|usage| usage.openssl_name())
+            .collect();
+        extensions.push(format!("extendedKeyUsage={}", usages.join(",")));
+    }
+    let names: Vec<_> = options
+        .subject_alt_names
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(openssl_alt_name)
+        .collect();
+    if !names.is_empty() {
+        extensions.push(format!("subjectAltName={}", names.join(",")));
+    }
+    for extension in extensions {
+        args.push(format!("-addext {}", shell_quote(&extension)));
+    }
+    args.join(" \\\n  ")
+}
+
+/// `-subj` value, with `/` and `=` in values escaped
+fn openssl_subject(subject: &X509Subject) -> String {
+    let attributes = [
+        ("C", subject.country.as_deref()),
+        ("ST", subject.state.as_deref()),
+        ("L", subject.locality.as_deref()),
+        ("O", subject.organization.as_deref()),
+        ("OU", subject.organizational_unit.as_deref()),
+        ("CN", Some(subject.common_name.as_str())),
+    ];
+    let subject: String = attributes
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = value.map(str::trim).filter(|value| !value.is_empty())?;
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('/', "\\/")
+                .replace('=', "\\=");
+            Some(format!("/{key}={value}"))
+        })
+        .collect();
+    if subject.is_empty() {
+        "/".to_string()
+    } else {
+        subject
+    }
+}
+
+/// SAN entry in openssl's `type:value` form, typed as the worker types it
+fn openssl_alt_name(name: &str) -> String {
+    if name.parse::<std::net::IpAddr>().is_ok() {
+        format!("IP:{name}")
+    } else if name.contains("://") {
+        format!("URI:{name}")
+    } else if name.contains('@') {
+        format!("email:{name}")
+    } else {
+        format!("DNS:{name}")
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> X509Options {
+        X509Options {
+            algorithm: X509KeyAlgorithm::EcdsaP256,
+            subject: X509Subject {
+                common_name: "example.com".to_string(),
+                organization: Some("R/D Team".to_string()),
+                country: Some("JP".to_string()),
+                ..X509Subject::default()
+            },
+            subject_alt_names: vec!["example.com".to_string(), "10.0.0.1".to_string()],
+            key_usages: vec![X509KeyUsage::DigitalSignature],
+            extended_key_usages: vec![X509ExtendedKeyUsage::ServerAuth],
+            validity_days: 90,
+            is_ca: false,
+        }
+    }
+
+    #[test]
+    fn test_openssl_command() {
+        let cmd = build_openssl_command(&options(), X509Output::Certificate);
+        assert!(cmd.starts_with("openssl req \\\n  -x509"));
+        assert!(cmd.contains("-newkey ec -pkeyopt ec_paramgen_curve:P-256"));
+        assert!(cmd.contains("-days 90"));
+        assert!(cmd.contains(r"-subj '/C=JP/O=R\/D Team/CN=example.com'"));
+        assert!(cmd.contains("-addext 'subjectAltName=DNS:example.com,IP:10.0.0.1'"));
+        assert!(cmd.contains("-addext 'keyUsage=critical,digitalSignature'"));
+
+        let csr = build_openssl_command(&options(), X509Output::Csr);
+        assert!(!csr.contains("-x509") && !csr.contains("-days"));
+        assert!(csr.contains("-out request.csr"));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(validate(&options(), X509Output::Certificate).is_ok());
+
+        let mut invalid = options();
+        invalid.subject.country = Some("JPN".to_string());
+        assert!(validate(&invalid, X509Output::Certificate).is_err());
+
+        let mut invalid = options();
+        invalid.validity_days = 0;
+        assert!(validate(&invalid, X509Output::Certificate).is_err());
+        assert!(validate(&invalid, X509Output::Csr).is_ok());
+
+        let mut invalid = options();
+        invalid.subject.common_name.clear();
+        invalid.subject_alt_names.clear();
+        assert!(validate(&invalid, X509Output::Csr).is_err());
+    }
+}
@@ -0,0 +1,420 @@
+//! Global fuzzy search for the command palette
+//!
+//! Matches one query against tool names and aliases, saved port monitor
+//! profiles, history entries, and keyboard shortcuts, and returns a single
+//! ranked list. Each result carries the character spans that matched so
+//! the palette can highlight them.
+//!
+//! Matching is subsequence-based (every query character must appear in
+//! order). Scores reward matches at word boundaries and consecutive runs,
+//! and penalize gaps, so `jf` ranks "JSON Formatter" above "Hex Editor".
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::catalog::{self, KEYBINDINGS, TOOLS};
+use crate::history::{HistoryEntry, HistoryState};
+use crate::network::monitor::{self, PortMonitorSummary};
+use crate::network::NetworkScannerState;
+
+/// Default number of results returned
+const DEFAULT_LIMIT: usize = 30;
+
+/// Maximum number of results returned
+const MAX_LIMIT: usize = 200;
+
+/// History entries considered per search
+const HISTORY_CANDIDATES: u32 = 500;
+
+/// Score per matched character
+const SCORE_MATCH: i64 = 16;
+
+/// Bonus for a match at the start of a word
+const BONUS_BOUNDARY: i64 = 10;
+
+/// Bonus for a match directly after the previous one
+const BONUS_CONSECUTIVE: i64 = 6;
+
+/// Bonus when the text starts with the whole query
+const BONUS_PREFIX: i64 = 20;
+
+/// Bonus when the text equals the query
+const BONUS_EXACT: i64 = 40;
+
+/// Penalty for matching through an alias instead of the title
+const PENALTY_ALIAS: i64 = 5;
+
+/// Source of a search result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    /// A tool page.
+    Tool,
+    /// A keyboard shortcut.
+    Keybinding,
+    /// A saved port monitor profile.
+    ScanProfile,
+    /// A history entry.
+    History,
+}
+
+/// Half-open range of matched characters (`start..end`, in chars).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchSpan {
+    /// First matched character index.
+    pub start: usize,
+    /// One past the last matched character index.
+    pub end: usize,
+}
+
+/// One ranked search result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// Result source.
+    pub kind: SearchKind,
+    /// Identifier within the source (tool id, monitor id, history row id, shortcut id).
+    pub id: String,
+    /// Display title.
+    pub title: String,
+    /// Secondary line (route, target, keys).
+    pub subtitle: Option<String>,
+    /// Tool page to open when the result is chosen, if any.
+    pub tool: Option<String>,
+    /// Text the query matched; the title or one of its aliases.
+    pub matched_text: String,
+    /// Matched character ranges within `matched_text`.
+    pub spans: Vec<MatchSpan>,
+    /// Ranking score; higher is better.
+    pub score: i64,
+}
+
+/// Searchable item before matching.
+struct Candidate {
+    kind: SearchKind,
+    id: String,
+    title: String,
+    subtitle: Option<String>,
+    tool: Option<String>,
+    aliases: Vec<String>,
+    bonus: i64,
+}
+
+/// Result of matching a query against one string.
+#[derive(Debug, PartialEq, Eq)]
+struct FuzzyMatch {
+    score: i64,
+    positions: Vec<usize>,
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_boundary(text: &[char], pos: usize) -> bool {
+    let Some(prev) = pos.checked_sub(1).map(|p| text[p]) else {
+        return true;
+    };
+    let cur = text[pos];
+    !prev.is_alphanumeric()
+        || (prev.is_lowercase() && cur.is_uppercase())
+        || (!prev.is_ascii_digit() && cur.is_ascii_digit())
+}
+
+/// Match `query` (already folded, without whitespace) against `text`.
+///
+/// Finds the first window containing the query as a subsequence, then
+/// tightens its start by scanning backwards from the window end, so
+/// `fmt` in "Format" prefers the compact match over a scattered one.
+fn fuzzy_match(query: &[char], text: &str) -> Option<FuzzyMatch> {
+    if query.is_empty() {
+        return None;
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = chars.iter().copied().map(fold).collect();
+
+    let mut qi = 0;
+    let mut end = 0;
+    for (i, &c) in folded.iter().enumerate() {
+        if c == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                end = i;
+                break;
+            }
+        }
+    }
+    if qi < query.len() {
+        return None;
+    }
+
+    let mut start = end;
+    let mut remaining = query.len();
+    for i in (0..=end).rev() {
+        if folded[i] == query[remaining - 1] {
+            remaining -= 1;
+            if remaining == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    let mut positions = Vec::with_capacity(query.len());
+    let mut qi = 0;
+    for (i, &c) in folded.iter().enumerate().take(end + 1).skip(start) {
+        if qi < query.len() && c == query[qi] {
+            positions.push(i);
+            qi += 1;
+        }
+    }
+
+    let mut score = -i64::try_from(start.min(8)).unwrap_or(8);
+    let mut prev: Option<usize> = None;
+    for &pos in &positions {
+        score += SCORE_MATCH;
+        if is_boundary(&chars, pos) {
+            score += BONUS_BOUNDARY;
+        }
+        match prev {
+            Some(p) if pos == p + 1 => score += BONUS_CONSECUTIVE,
+            Some(p) => score -= 3 + i64::try_from((pos - p - 1).min(5)).unwrap_or(5),
+            None => {}
+        }
+        prev = Some(pos);
+    }
+    if folded.starts_with(query) {
+        score += BONUS_PREFIX;
+        if folded.len() == query.len() {
+            score += BONUS_EXACT;
+        }
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Collapse sorted character positions into contiguous spans.
+fn to_spans(positions: &[usize]) -> Vec<MatchSpan> {
+    let mut spans: Vec<MatchSpan> = Vec::new();
+    for &pos in positions {
+        match spans.last_mut() {
+            Some(span) if span.end == pos => span.end = pos + 1,
+            _ => spans.push(MatchSpan {
+                start: pos,
+                end: pos + 1,
+            }),
+        }
+    }
+    spans
+}
+
+fn tool_candidates() -> impl Iterator<Item = Candidate> {
+    TOOLS.iter().map(|t| Candidate {
+        kind: SearchKind::Tool,
+        id: t.id.to_string(),
+        title: t.title.to_string(),
+        subtitle: Some(t.url.to_string()),
+        tool: Some(t.id.to_string()),
+        aliases: std::iter::once(t.id)
+            .chain(t.aliases.iter().copied())
+            .map(str::to_string)
+            .collect(),
+        bonus: 0,
+    })
+}
+
+fn keybinding_candidates() -> impl Iterator<Item = Candidate> {
+    KEYBINDINGS.iter().map(|k| Candidate {
+        kind: SearchKind::Keybinding,
+        id: k.id.to_string(),
+        title: k.description.to_string(),
+        subtitle: Some(k.keys.to_string()),
+        tool: None,
+        aliases: vec![k.keys.to_string()],
+        bonus: 0,
+    })
+}
+
+fn profile_candidates(monitors: Vec<PortMonitorSummary>) -> impl Iterator<Item = Candidate> {
+    monitors.into_iter().map(|m| Candidate {
+        kind: SearchKind::ScanProfile,
+        subtitle: Some(format!("{} · {}", m.config.target, m.config.ports)),
+        aliases: vec![m.config.target.clone()],
+        title: m.config.name,
+        id: m.id,
+        tool: Some("network-scanner".to_string()),
+        bonus: 0,
+    })
+}
+
+fn history_candidates(entries: Vec<HistoryEntry>) -> impl Iterator<Item = Candidate> {
+    entries.into_iter().map(|e| {
+        let tool_title =
+            catalog::find_tool(&e.tool).map_or_else(|| e.tool.clone(), |t| t.title.to_string());
+        Candidate {
+            kind: SearchKind::History,
+            id: e.id.to_string(),
+            title: e.label.unwrap_or_else(|| tool_title.clone()),
+            subtitle: Some(tool_title.clone()),
+            aliases: vec![tool_title],
+            tool: Some(e.tool),
+            bonus: if e.pinned { 4 } else { 0 },
+        }
+    })
+}
+
+/// Score `candidates` against `query` and return the best `limit` results.
+fn rank(
+    query: &str,
+    candidates: impl Iterator<Item = Candidate>,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results: Vec<SearchResult> = candidates
+        .filter_map(|c| {
+            let title_match = fuzzy_match(&query, &c.title).map(|m| (m, c.title.clone()));
+            let alias_match = c
+                .aliases
+                .iter()
+                .filter_map(|a| {
+                    fuzzy_match(&query, a).map(|m| {
+                        (
+                            FuzzyMatch {
+                                score: m.score - PENALTY_ALIAS,
+                                positions: m.positions,
+                            },
+                            a.clone(),
+                        )
+                    })
+                })
+                .max_by_key(|(m, _)| m.score);
+            let (best, matched_text) = match (title_match, alias_match) {
+                (Some(t), Some(a)) => {
+                    if a.0.score > t.0.score {
+                        a
+                    } else {
+                        t
+                    }
+                }
+                (Some(t), None) => t,
+                (None, Some(a)) => a,
+                (None, None) => return None,
+            };
+            Some(SearchResult {
+                kind: c.kind,
+                id: c.id,
+                title: c.title,
+                subtitle: c.subtitle,
+                tool: c.tool,
+                spans: to_spans(&best.positions),
+                matched_text,
+                score: best.score + c.bonus,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.kind.cmp(&b.kind))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    results.truncate(limit);
+    results
+}
+
+/// Fuzzy-search tools, scan profiles, history, and keyboard shortcuts.
+///
+/// An empty query returns no results. Sources that fail to load (missing
+/// monitor directory, locked history database) are skipped rather than
+/// failing the whole search.
+#[tauri::command(async)]
+pub fn global_search(
+    query: String,
+    limit: Option<u32>,
+    app: AppHandle,
+    history: State<'_, HistoryState>,
+    scanner: State<'_, NetworkScannerState>,
+) -> Vec<SearchResult> {
+    let limit = limit
+        .and_then(|l| usize::try_from(l).ok())
+        .unwrap_or(DEFAULT_LIMIT)
+        .min(MAX_LIMIT);
+    if query.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let monitors = monitor::port_monitor_list(app, scanner).unwrap_or_default();
+    let entries = history.list(HISTORY_CANDIDATES, false).unwrap_or_default();
+
+    let candidates = tool_candidates()
+        .chain(keybinding_candidates())
+        .chain(profile_candidates(monitors))
+        .chain(history_candidates(entries));
+    rank(&query, candidates, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(s: &str) -> Vec<char> {
+        s.chars().map(fold).collect()
+    }
+
+    #[test]
+    fn matches_subsequence_with_spans() {
+        let m = fuzzy_match(&q("jsf"), "JSON Formatter").unwrap();
+        assert_eq!(m.positions, vec![0, 1, 5]);
+        assert_eq!(
+            to_spans(&m.positions),
+            vec![
+                MatchSpan { start: 0, end: 2 },
+                MatchSpan { start: 5, end: 6 }
+            ]
+        );
+        assert!(fuzzy_match(&q("xyz"), "JSON Formatter").is_none());
+    }
+
+    #[test]
+    fn tightens_scattered_matches() {
+        // The first "f" is in "Diff"; the tightened window starts at "Fo".
+        let m = fuzzy_match(&q("fo"), "Diff Format").unwrap();
+        assert_eq!(m.positions, vec![5, 6]);
+    }
+
+    #[test]
+    fn boundary_and_prefix_matches_rank_higher() {
+        let boundary = fuzzy_match(&q("hg"), "Hash Generator").unwrap();
+        let inner = fuzzy_match(&q("hg"), "Lightweight").unwrap();
+        assert!(boundary.score > inner.score);
+
+        let exact = fuzzy_match(&q("dns"), "dns").unwrap();
+        let prefix = fuzzy_match(&q("dns"), "DNS Lookup").unwrap();
+        assert!(exact.score > prefix.score);
+    }
+
+    #[test]
+    fn ranks_tools_and_reports_alias_matches() {
+        let results = rank("json fmt", tool_candidates(), 5);
+        assert_eq!(results[0].id, "json-formatter");
+        assert_eq!(results[0].matched_text, "JSON Formatter");
+
+        let results = rank("nslookup", tool_candidates(), 5);
+        assert_eq!(results[0].id, "dns-lookup");
+        assert_eq!(results[0].matched_text, "nslookup");
+
+        let results = rank("palette", keybinding_candidates(), 5);
+        assert_eq!(results[0].subtitle.as_deref(), Some("Mod+K"));
+        assert!(rank("   ", tool_candidates(), 5).is_empty());
+    }
+}
@@ -0,0 +1,955 @@
+//! gRPC client commands backed by server reflection.
+//!
+//! The gRPC counterpart to [`crate::rest_client`]. [`grpc_list_services`]
+//! walks the target's reflection service to enumerate services, methods,
+//! and message schemas; [`grpc_invoke`] encodes a JSON request against the
+//! reflected input descriptor, performs the call, and streams every decoded
+//! response message back to the frontend through a Tauri channel.
+//!
+//! Both `grpc.reflection.v1` and the older `grpc.reflection.v1alpha` service
+//! are supported. The two protocols are wire-identical, so the v1 message
+//! types are reused and only the request path differs. Unary and
+//! server-streaming methods can be invoked; client-streaming and
+//! bidirectional methods are listed but rejected at invoke time.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use prost_reflect::prost::Message as _;
+use prost_reflect::prost_types::FileDescriptorProto;
+use prost_reflect::{
+    Cardinality, DescriptorPool, DynamicMessage, Kind, MessageDescriptor, MethodDescriptor,
+    SerializeOptions,
+};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::Status;
+use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::v1::{ServerReflectionRequest, ServerReflectionResponse};
+
+/// Reflection RPC paths, tried in order. Servers built on older gRPC
+/// releases only expose the `v1alpha` service.
+const REFLECTION_PATHS: [&str; 2] = [
+    "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo",
+];
+
+/// Maximum nesting depth expanded when building a request template. Deeper
+/// (or recursive) message fields are rendered as an empty object.
+const MAX_TEMPLATE_DEPTH: usize = 4;
+
+/// Connection parameters shared by every gRPC command.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcTarget {
+    /// Server address. Accepts `http://host:port`, `https://host:port`, or a
+    /// bare `host:port` (treated as plaintext).
+    pub url: String,
+    /// Request metadata (gRPC headers) as ordered key/value pairs. Keys ending
+    /// in `-bin` carry base64 values, matching the gRPC convention.
+    pub metadata: Vec<(String, String)>,
+    /// Connect and per-call timeout in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// One field of a reflected message.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcFieldInfo {
+    /// Field name as declared in the `.proto` file.
+    pub name: String,
+    /// Name used in the proto3 JSON mapping (lowerCamelCase).
+    pub json_name: String,
+    /// Field number.
+    pub number: u32,
+    /// Scalar type name (e.g. `string`, `int64`) or the fully qualified
+    /// message / enum name.
+    pub type_name: String,
+    /// `optional`, `required`, `repeated`, or `map`.
+    pub label: String,
+}
+
+/// Flat schema of a reflected message type.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcMessageSchema {
+    /// Fully qualified message name (e.g. `helloworld.HelloRequest`).
+    pub full_name: String,
+    /// Fields in declaration order.
+    pub fields: Vec<GrpcFieldInfo>,
+}
+
+/// One RPC method exposed by a service.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcMethodInfo {
+    /// Method name (e.g. `SayHello`).
+    pub name: String,
+    /// Fully qualified method name (e.g. `helloworld.Greeter.SayHello`).
+    pub full_name: String,
+    /// Whether the client sends a stream of messages.
+    pub client_streaming: bool,
+    /// Whether the server replies with a stream of messages.
+    pub server_streaming: bool,
+    /// Request message schema.
+    pub input: GrpcMessageSchema,
+    /// Response message schema.
+    pub output: GrpcMessageSchema,
+    /// JSON skeleton of the request message, pre-filled with default values.
+    pub request_template: serde_json::Value,
+}
+
+/// One reflected service.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcServiceInfo {
+    /// Fully qualified service name (e.g. `helloworld.Greeter`).
+    pub name: String,
+    /// Methods in declaration order.
+    pub methods: Vec<GrpcMethodInfo>,
+}
+
+/// Result of [`grpc_list_services`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcServiceCatalog {
+    /// Reflection protocol version that answered (`v1` or `v1alpha`).
+    pub reflection_version: String,
+    /// Services in the order the server listed them. The reflection service
+    /// itself is omitted.
+    pub services: Vec<GrpcServiceInfo>,
+    /// Wall-clock time spent connecting and reflecting, in milliseconds.
+    pub elapsed_ms: u128,
+}
+
+/// Invocation payload sent from the frontend.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcInvokeRequest {
+    /// Server connection parameters.
+    pub target: GrpcTarget,
+    /// Fully qualified service name.
+    pub service: String,
+    /// Method name within `service`.
+    pub method: String,
+    /// Request message encoded as proto3 JSON. Empty input sends the default
+    /// (all-fields-unset) message.
+    pub body: String,
+}
+
+/// Streaming event emitted while a call is in flight.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum GrpcStreamEvent {
+    /// Response headers arrived.
+    Headers {
+        /// Initial response metadata.
+        metadata: Vec<(String, String)>,
+    },
+    /// One decoded response message.
+    Message {
+        /// Zero-based index of the message within the response stream.
+        index: u32,
+        /// Message decoded to proto3 JSON.
+        body: serde_json::Value,
+        /// Encoded protobuf size in bytes.
+        size_bytes: u64,
+    },
+}
+
+/// Final outcome of [`grpc_invoke`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcInvokeResult {
+    /// gRPC status code (`0` = OK).
+    pub status_code: i32,
+    /// Canonical status name (e.g. `OK`, `NOT_FOUND`).
+    pub status_name: String,
+    /// Status message supplied by the server, if any.
+    pub status_message: String,
+    /// Number of response messages delivered through the channel.
+    pub message_count: u32,
+    /// Trailing metadata, when the server sent any.
+    pub trailers: Vec<(String, String)>,
+    /// Whether the call was stopped by `cancel_op` before completion.
+    pub cancelled: bool,
+    /// Wall-clock time from connect to stream end, in milliseconds.
+    pub elapsed_ms: u128,
+}
+
+// =============================================================================
+// Connection
+// =============================================================================
+
+/// Prefix bare `host:port` addresses with `http://` so they parse as URIs.
+fn normalize_url(url: &str) -> String {
+    let trimmed = url.trim();
+    if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("http://{trimmed}")
+    }
+}
+
+/// Open an HTTP/2 channel to the target, enabling TLS for `https://` URLs.
+async fn connect(target: &GrpcTarget) -> Result<Channel, String> {
+    let url = normalize_url(&target.url);
+    let timeout = Duration::from_millis(u64::from(target.timeout_ms));
+
+    let endpoint = Endpoint::from_shared(url.clone())
+        .map_err(|e| format!("Invalid gRPC URL '{url}': {e}"))?
+        .connect_timeout(timeout)
+        .timeout(timeout);
+
+    let endpoint = if url.starts_with("https://") {
+        endpoint
+            .tls_config(ClientTlsConfig::new().with_native_roots())
+            .map_err(|e| format!("Failed to configure TLS: {e}"))?
+    } else {
+        endpoint
+    };
+
+    endpoint
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to connect to {url}: {e}"))
+}
+
+/// Copy user-supplied key/value pairs into request metadata.
+fn apply_metadata(map: &mut MetadataMap, pairs: &[(String, String)]) -> Result<(), String> {
+    for (key, value) in pairs.iter().filter(|(k, _)| !k.trim().is_empty()) {
+        let key = key.trim().to_ascii_lowercase();
+        if key.ends_with("-bin") {
+            let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, value)
+                .map_err(|e| format!("Metadata '{key}' is not valid base64: {e}"))?;
+            let name = MetadataKey::from_bytes(key.as_bytes())
+                .map_err(|e| format!("Invalid metadata key '{key}': {e}"))?;
+            map.insert_bin(name, MetadataValue::from_bytes(&bytes));
+        } else {
+            let name = MetadataKey::from_bytes(key.as_bytes())
+                .map_err(|e| format!("Invalid metadata key '{key}': {e}"))?;
+            let value = value
+                .parse()
+                .map_err(|e| format!("Invalid metadata value for '{key}': {e}"))?;
+            map.insert(name, value);
+        }
+    }
+    Ok(())
+}
+
+/// Flatten metadata into displayable key/value pairs. Binary values are
+/// rendered as base64, mirroring how they were supplied.
+fn metadata_pairs(map: &MetadataMap) -> Vec<(String, String)> {
+    map.clone()
+        .into_headers()
+        .iter()
+        .map(|(k, v)| {
+            let value = if k.as_str().ends_with("-bin") {
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, v.as_bytes())
+            } else {
+                String::from_utf8_lossy(v.as_bytes()).into_owned()
+            };
+            (k.to_string(), value)
+        })
+        .collect()
+}
+
+// =============================================================================
+// Reflection
+// =============================================================================
+
+/// Thin wrapper around a reflection stream bound to one protocol version.
+struct ReflectionClient {
+    grpc: tonic::client::Grpc<Channel>,
+    path: &'static str,
+    metadata: Vec<(String, String)>,
+}
+
+impl ReflectionClient {
+    /// Send a single reflection request and return its response payload.
+    async fn query(&mut self, request: MessageRequest) -> Result<MessageResponse, Status> {
+        self.grpc
+            .ready()
+            .await
+            .map_err(|e| Status::unavailable(format!("Service was not ready: {e}")))?;
+
+        let message = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(request),
+        };
+        let mut request = tonic::Request::new(futures::stream::iter(vec![message]));
+        apply_metadata(request.metadata_mut(), &self.metadata).map_err(Status::invalid_argument)?;
+
+        let codec =
+            tonic_prost::ProstCodec::<ServerReflectionRequest, ServerReflectionResponse>::default();
+        let path = http::uri::PathAndQuery::from_static(self.path);
+        let mut stream = self
+            .grpc
+            .streaming(request, path, codec)
+            .await?
+            .into_inner();
+
+        let response = stream
+            .message()
+            .await?
+            .ok_or_else(|| Status::internal("Reflection stream closed without a response"))?;
+
+        match response.message_response {
+            Some(MessageResponse::ErrorResponse(err)) => Err(Status::new(
+                tonic::Code::from_i32(err.error_code),
+                err.error_message,
+            )),
+            Some(other) => Ok(other),
+            None => Err(Status::internal("Empty reflection response")),
+        }
+    }
+
+    /// List the fully qualified names of every registered service.
+    async fn list_services(&mut self) -> Result<Vec<String>, Status> {
+        match self
+            .query(MessageRequest::ListServices(String::new()))
+            .await?
+        {
+            MessageResponse::ListServicesResponse(list) => {
+                Ok(list.service.into_iter().map(|s| s.name).collect())
+            }
+            _ => Err(Status::internal(
+                "Unexpected reflection response to list_services",
+            )),
+        }
+    }
+
+    /// Fetch the file descriptors returned for a symbol or file name.
+    async fn files(&mut self, request: MessageRequest) -> Result<Vec<FileDescriptorProto>, Status> {
+        match self.query(request).await? {
+            MessageResponse::FileDescriptorResponse(resp) => resp
+                .file_descriptor_proto
+                .iter()
+                .map(|bytes| {
+                    FileDescriptorProto::decode(bytes.as_slice())
+                        .map_err(|e| Status::internal(format!("Invalid file descriptor: {e}")))
+                })
+                .collect(),
+            _ => Err(Status::internal(
+                "Unexpected reflection response to file request",
+            )),
+        }
+    }
+}
+
+/// Connect a reflection client, preferring `v1` and falling back to
+/// `v1alpha` when the server reports the newer service as unimplemented.
+async fn open_reflection(
+    channel: Channel,
+    metadata: &[(String, String)],
+) -> Result<(ReflectionClient, Vec<String>, &'static str), String> {
+    let mut last_error = String::new();
+    for (path, version) in REFLECTION_PATHS.into_iter().zip(["v1", "v1alpha"]) {
+        let mut client = ReflectionClient {
+            grpc: tonic::client::Grpc::new(channel.clone()),
+            path,
+            metadata: metadata.to_vec(),
+        };
+        match client.list_services().await {
+            Ok(services) => return Ok((client, services, version)),
+            Err(status) if status.code() == tonic::Code::Unimplemented => {
+                last_error = status.message().to_string();
+            }
+            Err(status) => return Err(format!("Reflection failed: {}", status.message())),
+        }
+    }
+    Err(format!(
+        "Server does not expose gRPC reflection: {last_error}"
+    ))
+}
+
+/// Resolve every service's descriptors (and their transitive imports) into
+/// a single pool.
+async fn build_pool(
+    client: &mut ReflectionClient,
+    services: &[String],
+) -> Result<DescriptorPool, String> {
+    let mut files: HashMap<String, FileDescriptorProto> = HashMap::new();
+
+    for service in services {
+        let protos = client
+            .files(MessageRequest::FileContainingSymbol(service.clone()))
+            .await
+            .map_err(|e| format!("Failed to resolve '{service}': {}", e.message()))?;
+        for proto in protos {
+            files.entry(proto.name().to_string()).or_insert(proto);
+        }
+    }
+
+    // Servers usually return the transitive closure, but the protocol only
+    // guarantees the defining file; fetch any missing imports by name.
+    loop {
+        let missing: HashSet<String> = files
+            .values()
+            .flat_map(|f| f.dependency.iter())
+            .filter(|dep| !files.contains_key(*dep))
+            .cloned()
+            .collect();
+        if missing.is_empty() {
+            break;
+        }
+        for name in missing {
+            let protos = client
+                .files(MessageRequest::FileByFilename(name.clone()))
+                .await
+                .map_err(|e| format!("Failed to resolve import '{name}': {}", e.message()))?;
+            if !protos.iter().any(|p| p.name() == name) {
+                return Err(format!("Server did not return imported file '{name}'"));
+            }
+            for proto in protos {
+                files.entry(proto.name().to_string()).or_insert(proto);
+            }
+        }
+    }
+
+    let mut pool = DescriptorPool::new();
+    pool.add_file_descriptor_protos(files.into_values())
+        .map_err(|e| format!("Invalid descriptors from server: {e}"))?;
+    Ok(pool)
+}
+
+/// Whether a service name belongs to the reflection service itself.
+fn is_reflection_service(name: &str) -> bool {
+    name.starts_with("grpc.reflection.")
+}
+
+// =============================================================================
+// Schema rendering
+// =============================================================================
+
+/// Human-readable type name of a field kind.
+fn kind_name(kind: &Kind) -> String {
+    match kind {
+        Kind::Double => "double".to_string(),
+        Kind::Float => "float".to_string(),
+        Kind::Int32 => "int32".to_string(),
+        Kind::Int64 => "int64".to_string(),
+        Kind::Uint32 => "uint32".to_string(),
+        Kind::Uint64 => "uint64".to_string(),
+        Kind::Sint32 => "sint32".to_string(),
+        Kind::Sint64 => "sint64".to_string(),
+        Kind::Fixed32 => "fixed32".to_string(),
+        Kind::Fixed64 => "fixed64".to_string(),
+        Kind::Sfixed32 => "sfixed32".to_string(),
+        Kind::Sfixed64 => "sfixed64".to_string(),
+        Kind::Bool => "bool".to_string(),
+        Kind::String => "string".to_string(),
+        Kind::Bytes => "bytes".to_string(),
+        Kind::Message(m) => m.full_name().to_string(),
+        Kind::Enum(e) => e.full_name().to_string(),
+    }
+}
+
+/// Build the flat schema of a message.
+fn message_schema(desc: &MessageDescriptor) -> GrpcMessageSchema {
+    let fields = desc
+        .fields()
+        .map(|field| {
+            let label = if field.is_map() {
+                "map"
+            } else {
+                match field.cardinality() {
+                    Cardinality::Optional => "optional",
+                    Cardinality::Required => "required",
+                    Cardinality::Repeated => "repeated",
+                }
+            };
+            let type_name = match field.kind() {
+                Kind::Message(entry) if field.is_map() => format!(
+                    "map<{}, {}>",
+                    kind_name(&entry.map_entry_key_field().kind()),
+                    kind_name(&entry.map_entry_value_field().kind())
+                ),
+                kind => kind_name(&kind),
+            };
+            GrpcFieldInfo {
+                name: field.name().to_string(),
+                json_name: field.json_name().to_string(),
+                number: field.number(),
+                type_name,
+                label: label.to_string(),
+            }
+        })
+        .collect();
+
+    GrpcMessageSchema {
+        full_name: desc.full_name().to_string(),
+        fields,
+    }
+}
+
+/// Default proto3 JSON value for a single (non-repeated) field of `kind`.
+fn template_value(kind: &Kind, depth: usize) -> serde_json::Value {
+    use serde_json::{json, Value};
+
+    match kind {
+        Kind::Double | Kind::Float => json!(0.0),
+        Kind::Int32 | Kind::Uint32 | Kind::Sint32 | Kind::Fixed32 | Kind::Sfixed32 => json!(0),
+        // proto3 JSON encodes 64-bit integers as strings.
+        Kind::Int64 | Kind::Uint64 | Kind::Sint64 | Kind::Fixed64 | Kind::Sfixed64 => json!("0"),
+        Kind::Bool => json!(false),
+        Kind::String | Kind::Bytes => json!(""),
+        Kind::Enum(e) => e
+            .values()
+            .next()
+            .map_or(Value::Null, |v| Value::String(v.name().to_string())),
+        Kind::Message(m) => match m.full_name() {
+            "google.protobuf.Timestamp" => json!("1970-01-01T00:00:00Z"),
+            "google.protobuf.Duration" => json!("0s"),
+            "google.protobuf.FieldMask" => json!(""),
+            "google.protobuf.Value" => Value::Null,
+            "google.protobuf.ListValue" => json!([]),
+            "google.protobuf.StringValue" | "google.protobuf.BytesValue" => json!(""),
+            "google.protobuf.BoolValue" => json!(false),
+            "google.protobuf.Int64Value" | "google.protobuf.UInt64Value" => json!("0"),
+            "google.protobuf.Int32Value"
+            | "google.protobuf.UInt32Value"
+            | "google.protobuf.FloatValue"
+            | "google.protobuf.DoubleValue" => json!(0),
+            _ => message_template(m, depth + 1),
+        },
+    }
+}
+
+/// JSON skeleton of `desc` with every field set to its default value.
+fn message_template(desc: &MessageDescriptor, depth: usize) -> serde_json::Value {
+    if depth > MAX_TEMPLATE_DEPTH {
+        return serde_json::Value::Object(serde_json::Map::new());
+    }
+    let fields = desc
+        .fields()
+        .map(|field| {
+            let value = if field.is_map() {
+                serde_json::Value::Object(serde_json::Map::new())
+            } else if field.is_list() {
+                serde_json::Value::Array(vec![template_value(&field.kind(), depth)])
+            } else {
+                template_value(&field.kind(), depth)
+            };
+            (field.json_name().to_string(), value)
+        })
+        .collect();
+    serde_json::Value::Object(fields)
+}
+
+/// Describe one method for the catalog.
+fn method_info(method: &MethodDescriptor) -> GrpcMethodInfo {
+    GrpcMethodInfo {
+        name: method.name().to_string(),
+        full_name: method.full_name().to_string(),
+        client_streaming: method.is_client_streaming(),
+        server_streaming: method.is_server_streaming(),
+        input: message_schema(&method.input()),
+        output: message_schema(&method.output()),
+        request_template: message_template(&method.input(), 0),
+    }
+}
+
+// =============================================================================
+// Dynamic codec
+// =============================================================================
+
+/// Codec that encodes and decodes [`DynamicMessage`]s against a reflected
+/// response descriptor, so calls work without generated stubs.
+#[derive(Clone)]
+struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+struct DynamicEncoder;
+
+struct DynamicDecoder {
+    output: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder {
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("Failed to encode request: {e}")))
+    }
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.output.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("Failed to decode response: {e}")))
+    }
+}
+
+/// Parse a proto3 JSON body into a message of type `desc`.
+fn parse_request_body(desc: &MessageDescriptor, body: &str) -> Result<DynamicMessage, String> {
+    if body.trim().is_empty() {
+        return Ok(DynamicMessage::new(desc.clone()));
+    }
+    let mut deserializer = serde_json::Deserializer::from_str(body);
+    let message = DynamicMessage::deserialize(desc.clone(), &mut deserializer)
+        .map_err(|e| format!("Request does not match {}: {e}", desc.full_name()))?;
+    deserializer
+        .end()
+        .map_err(|e| format!("Trailing characters after request JSON: {e}"))?;
+    Ok(message)
+}
+
+/// Render a decoded message as proto3 JSON, keeping default-valued fields so
+/// the full response shape is visible.
+fn message_to_json(message: &DynamicMessage) -> Result<serde_json::Value, String> {
+    let options = SerializeOptions::new().skip_default_fields(false);
+    message
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|e| format!("Failed to render response: {e}"))
+}
+
+/// Canonical upper-snake name of a status code.
+fn status_name(code: tonic::Code) -> String {
+    let name = match code {
+        tonic::Code::Ok => "OK",
+        tonic::Code::Cancelled => "CANCELLED",
+        tonic::Code::Unknown => "UNKNOWN",
+        tonic::Code::InvalidArgument => "INVALID_ARGUMENT",
+        tonic::Code::DeadlineExceeded => "DEADLINE_EXCEEDED",
+        tonic::Code::NotFound => "NOT_FOUND",
+        tonic::Code::AlreadyExists => "ALREADY_EXISTS",
+        tonic::Code::PermissionDenied => "PERMISSION_DENIED",
+        tonic::Code::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        tonic::Code::FailedPrecondition => "FAILED_PRECONDITION",
+        tonic::Code::Aborted => "ABORTED",
+        tonic::Code::OutOfRange => "OUT_OF_RANGE",
+        tonic::Code::Unimplemented => "UNIMPLEMENTED",
+        tonic::Code::Internal => "INTERNAL",
+        tonic::Code::Unavailable => "UNAVAILABLE",
+        tonic::Code::DataLoss => "DATA_LOSS",
+        tonic::Code::Unauthenticated => "UNAUTHENTICATED",
+    };
+    name.to_string()
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+/// List services, methods, and message schemas via server reflection.
+///
+/// # Errors
+///
+/// Returns an error string for invalid URLs, connection failures, servers
+/// without a reflection service, or malformed descriptors.
+#[tauri::command]
+pub async fn grpc_list_services(target: GrpcTarget) -> Result<GrpcServiceCatalog, String> {
+    let started = Instant::now();
+    let channel = connect(&target).await?;
+    let (mut client, names, version) = open_reflection(channel, &target.metadata).await?;
+
+    let names: Vec<String> = names
+        .into_iter()
+        .filter(|n| !is_reflection_service(n))
+        .collect();
+    let pool = build_pool(&mut client, &names).await?;
+
+    let services = names
+        .iter()
+        .filter_map(|name| pool.get_service_by_name(name))
+        .map(|service| GrpcServiceInfo {
+            name: service.full_name().to_string(),
+            methods: service.methods().map(|m| method_info(&m)).collect(),
+        })
+        .collect();
+
+    Ok(GrpcServiceCatalog {
+        reflection_version: version.to_string(),
+        services,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+/// Invoke a unary or server-streaming method with a JSON-encoded request.
+///
+/// Each response message is decoded to JSON and pushed through `on_event` as
+/// soon as it arrives. The call registers under `op_id` so the frontend can
+/// stop a long-running stream through `cancel_op`. A non-OK gRPC status is
+/// reported in the result rather than as an error, the same way the REST
+/// client surfaces HTTP error statuses.
+///
+/// # Errors
+///
+/// Returns an error string for connection or reflection failures, unknown
+/// services or methods, streaming-request methods, and request bodies that
+/// do not match the input message.
+#[tauri::command]
+pub async fn grpc_invoke(
+    op_id: String,
+    req: GrpcInvokeRequest,
+    on_event: tauri::ipc::Channel<GrpcStreamEvent>,
+    state: tauri::State<'_, crate::cancellation::OperationRegistry>,
+) -> Result<GrpcInvokeResult, String> {
+    let token = Arc::new(CancellationToken::new());
+    state.register(op_id.clone(), token.clone());
+
+    let result = run_invoke(&req, &on_event, &token).await;
+    state.remove(&op_id);
+    result
+}
+
+async fn run_invoke(
+    req: &GrpcInvokeRequest,
+    on_event: &tauri::ipc::Channel<GrpcStreamEvent>,
+    token: &CancellationToken,
+) -> Result<GrpcInvokeResult, String> {
+    let started = Instant::now();
+    let channel = connect(&req.target).await?;
+
+    let (mut client, _, _) = open_reflection(channel.clone(), &req.target.metadata).await?;
+    let pool = build_pool(&mut client, std::slice::from_ref(&req.service)).await?;
+
+    let service = pool
+        .get_service_by_name(&req.service)
+        .ok_or_else(|| format!("Unknown service: {}", req.service))?;
+    let method = service
+        .methods()
+        .find(|m| m.name() == req.method)
+        .ok_or_else(|| format!("Unknown method: {}/{}", req.service, req.method))?;
+    if method.is_client_streaming() {
+        return Err(format!(
+            "{} is a client-streaming method; only unary and server-streaming calls are supported",
+            method.full_name()
+        ));
+    }
+
+    let message = parse_request_body(&method.input(), &req.body)?;
+    let mut request = tonic::Request::new(message);
+    apply_metadata(request.metadata_mut(), &req.target.metadata)?;
+
+    let path = http::uri::PathAndQuery::try_from(format!("/{}/{}", req.service, req.method))
+        .map_err(|e| format!("Invalid method path: {e}"))?;
+    let codec = DynamicCodec {
+        output: method.output(),
+    };
+
+    let mut grpc = tonic::client::Grpc::new(channel);
+    grpc.ready()
+        .await
+        .map_err(|e| format!("Service was not ready: {e}"))?;
+
+    let finish = |status: &Status, count: u32, trailers, cancelled| GrpcInvokeResult {
+        status_code: i32::from(status.code()),
+        status_name: status_name(status.code()),
+        status_message: status.message().to_string(),
+        message_count: count,
+        trailers,
+        cancelled,
+        elapsed_ms: started.elapsed().as_millis(),
+    };
+
+    let response = match grpc.server_streaming(request, path, codec).await {
+        Ok(response) => response,
+        Err(status) => return Ok(finish(&status, 0, metadata_pairs(status.metadata()), false)),
+    };
+
+    let _ = on_event.send(GrpcStreamEvent::Headers {
+        metadata: metadata_pairs(response.metadata()),
+    });
+
+    let mut stream = response.into_inner();
+    let mut count: u32 = 0;
+    loop {
+        let next = tokio::select! {
+            () = token.cancelled() => {
+                let status = Status::cancelled("Cancelled by user");
+                return Ok(finish(&status, count, Vec::new(), true));
+            }
+            next = stream.message() => next,
+        };
+        match next {
+            Ok(Some(message)) => {
+                let body = message_to_json(&message)?;
+                let _ = on_event.send(GrpcStreamEvent::Message {
+                    index: count,
+                    body,
+                    size_bytes: message.encoded_len() as u64,
+                });
+                count = count.saturating_add(1);
+            }
+            Ok(None) => break,
+            Err(status) => {
+                return Ok(finish(
+                    &status,
+                    count,
+                    metadata_pairs(status.metadata()),
+                    false,
+                ))
+            }
+        }
+    }
+
+    let trailers = stream
+        .trailers()
+        .await
+        .ok()
+        .flatten()
+        .map(|t| metadata_pairs(&t))
+        .unwrap_or_default();
+    Ok(finish(&Status::ok(""), count, trailers, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_reflect::prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto,
+    };
+
+    fn field(name: &str, number: i32, ty: Type, label: Label) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(label as i32),
+            json_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn test_pool() -> DescriptorPool {
+        let inner = DescriptorProto {
+            name: Some("Inner".to_string()),
+            field: vec![field("flag", 1, Type::Bool, Label::Optional)],
+            ..Default::default()
+        };
+        let mut nested = field("inner", 4, Type::Message, Label::Optional);
+        nested.type_name = Some(".test.Inner".to_string());
+        let outer = DescriptorProto {
+            name: Some("Outer".to_string()),
+            field: vec![
+                field("name", 1, Type::String, Label::Optional),
+                field("count", 2, Type::Int64, Label::Optional),
+                field("tags", 3, Type::String, Label::Repeated),
+                nested,
+            ],
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("test".to_string()),
+            message_type: vec![inner, outer],
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        };
+        let mut pool = DescriptorPool::new();
+        pool.add_file_descriptor_protos([file]).unwrap();
+        pool
+    }
+
+    #[test]
+    fn normalize_url_adds_plaintext_scheme() {
+        assert_eq!(normalize_url("localhost:50051"), "http://localhost:50051");
+        assert_eq!(normalize_url(" https://api:443 "), "https://api:443");
+    }
+
+    #[test]
+    fn reflection_service_is_filtered() {
+        assert!(is_reflection_service("grpc.reflection.v1.ServerReflection"));
+        assert!(!is_reflection_service("helloworld.Greeter"));
+    }
+
+    #[test]
+    fn message_template_fills_defaults() {
+        let pool = test_pool();
+        let outer = pool.get_message_by_name("test.Outer").unwrap();
+        let template = message_template(&outer, 0);
+        assert_eq!(
+            template,
+            serde_json::json!({
+                "name": "",
+                "count": "0",
+                "tags": [""],
+                "inner": { "flag": false },
+            })
+        );
+    }
+
+    #[test]
+    fn message_schema_reports_labels_and_types() {
+        let pool = test_pool();
+        let schema = message_schema(&pool.get_message_by_name("test.Outer").unwrap());
+        assert_eq!(schema.full_name, "test.Outer");
+        let tags = schema.fields.iter().find(|f| f.name == "tags").unwrap();
+        assert_eq!(tags.label, "repeated");
+        let inner = schema.fields.iter().find(|f| f.name == "inner").unwrap();
+        assert_eq!(inner.type_name, "test.Inner");
+    }
+
+    #[test]
+    fn request_body_round_trips_through_json() {
+        let pool = test_pool();
+        let outer = pool.get_message_by_name("test.Outer").unwrap();
+        let message = parse_request_body(&outer, r#"{"name":"kogu","count":"7"}"#).unwrap();
+        let json = message_to_json(&message).unwrap();
+        assert_eq!(json["name"], "kogu");
+        assert_eq!(json["count"], "7");
+    }
+
+    #[test]
+    fn request_body_rejects_unknown_fields() {
+        let pool = test_pool();
+        let outer = pool.get_message_by_name("test.Outer").unwrap();
+        assert!(parse_request_body(&outer, r#"{"nope":1}"#).is_err());
+    }
+
+    #[test]
+    fn empty_request_body_is_default_message() {
+        let pool = test_pool();
+        let outer = pool.get_message_by_name("test.Outer").unwrap();
+        let message = parse_request_body(&outer, "  ").unwrap();
+        assert_eq!(message.encoded_len(), 0);
+    }
+
+    #[test]
+    fn binary_metadata_requires_base64() {
+        let mut map = MetadataMap::new();
+        let pairs = vec![("trace-bin".to_string(), "not base64!".to_string())];
+        assert!(apply_metadata(&mut map, &pairs).is_err());
+
+        let pairs = vec![
+            ("Authorization".to_string(), "Bearer abc".to_string()),
+            ("trace-bin".to_string(), "AQID".to_string()),
+        ];
+        apply_metadata(&mut map, &pairs).unwrap();
+        let rendered = metadata_pairs(&map);
+        assert!(rendered.contains(&("authorization".to_string(), "Bearer abc".to_string())));
+        assert!(rendered.contains(&("trace-bin".to_string(), "AQID".to_string())));
+    }
+}
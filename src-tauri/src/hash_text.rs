@@ -52,8 +52,13 @@ pub async fn hash_text_batch(
 }
 
 fn compute_hashes(text: &str, algorithms: &[String]) -> BTreeMap<String, String> {
+    digest_bytes(text.as_bytes(), algorithms)
+}
+
+/// Hex digests of raw bytes under each known algorithm id; unknown ids are
+/// dropped. Shared with the headless CLI, which hashes files byte-exact.
+pub(crate) fn digest_bytes(bytes: &[u8], algorithms: &[String]) -> BTreeMap<String, String> {
     let mut out = BTreeMap::new();
-    let bytes = text.as_bytes();
     for raw in algorithms {
         let id = raw.to_ascii_lowercase();
         let digest = match id.as_str() {
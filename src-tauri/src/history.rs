@@ -0,0 +1,430 @@
+//! Tool usage history and favorites store (SQLite-based)
+//!
+//! Records recent tool invocations so the frontend can show a
+//! "recents / favorites" rail across every tool. The database lives next
+//! to the other app data:
+//! - macOS: `~/Library/Application Support/io.github.seijikohara.kogu/history.sqlite3`
+//! - Windows: `%APPDATA%/io.github.seijikohara.kogu/history.sqlite3`
+//! - Linux: `~/.local/share/io.github.seijikohara.kogu/history.sqlite3`
+//!
+//! Inputs are never stored. Each entry keeps a truncated SHA-256 of the
+//! input, which is enough to collapse repeated invocations on the same
+//! input into one entry without the history leaking its contents. Labels
+//! are passed through [`crate::redaction`] before they are written.
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const HISTORY_FILENAME: &str = "history.sqlite3";
+
+/// Hex characters of the input digest kept per entry
+const INPUT_HASH_LEN: usize = 16;
+
+/// Unpinned entries kept; older ones are pruned on insert
+const MAX_UNPINNED_ENTRIES: i64 = 1000;
+
+/// Maximum entries returned by one list or search call
+const MAX_PAGE: u32 = 500;
+
+/// Maximum stored label length in characters
+const MAX_LABEL_CHARS: usize = 120;
+
+/// Mask secrets in a label and cap its length. Redaction runs first so a
+/// truncated secret cannot slip past the patterns.
+fn clean_label(label: &str) -> String {
+    crate::redaction::redact(label)
+        .chars()
+        .take(MAX_LABEL_CHARS)
+        .collect()
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    tool        TEXT    NOT NULL,
+    input_hash  TEXT    NOT NULL,
+    label       TEXT,
+    created_at  INTEGER NOT NULL,
+    last_used   INTEGER NOT NULL,
+    use_count   INTEGER NOT NULL DEFAULT 1,
+    pinned      INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (tool, input_hash)
+);
+CREATE INDEX IF NOT EXISTS history_last_used ON history (last_used DESC);
+";
+
+/// One recorded tool invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    /// Row id, used to pin or delete the entry.
+    pub id: i64,
+    /// Tool identifier (e.g. `json-formatter`).
+    pub tool: String,
+    /// Truncated SHA-256 of the input.
+    pub input_hash: String,
+    /// Optional display label supplied by the frontend.
+    pub label: Option<String>,
+    /// First use, in milliseconds since the Unix epoch.
+    pub created_at: i64,
+    /// Most recent use, in milliseconds since the Unix epoch.
+    pub last_used: i64,
+    /// Number of times the same tool ran on the same input.
+    pub use_count: i64,
+    /// Whether the entry is a favorite.
+    pub pinned: bool,
+}
+
+/// Managed state holding the history database connection.
+pub struct HistoryState {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryState {
+    /// Open (or create) the history database in `data_dir`.
+    pub fn open(data_dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Failed to create data directory: {e}"))?;
+        let conn = Connection::open(data_dir.join(HISTORY_FILENAME))
+            .map_err(|e| format!("Failed to open history database: {e}"))?;
+        Self::init(conn)
+    }
+
+    /// Open a throwaway in-memory database, used when the file cannot be
+    /// opened so that history degrades to per-session instead of failing.
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| format!("Failed to open history database: {e}"))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to initialize history schema: {e}"))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| format!("History lock poisoned: {e}"))?;
+        f(&conn).map_err(|e| format!("History database error: {e}"))
+    }
+
+    /// Record an invocation, merging it with an earlier one on the same input.
+    pub fn record(
+        &self,
+        tool: &str,
+        input: &str,
+        label: Option<&str>,
+    ) -> Result<HistoryEntry, String> {
+        let hash = input_hash(input);
+        let label = label.map(clean_label);
+        let now = now_ms();
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO history (tool, input_hash, label, created_at, last_used)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT (tool, input_hash) DO UPDATE SET
+                     last_used = excluded.last_used,
+                     use_count = use_count + 1,
+                     label = COALESCE(excluded.label, label)",
+                params![tool, hash, label, now],
+            )?;
+            conn.execute(
+                "DELETE FROM history WHERE pinned = 0 AND id NOT IN (
+                     SELECT id FROM history WHERE pinned = 0
+                     ORDER BY last_used DESC LIMIT ?1)",
+                params![MAX_UNPINNED_ENTRIES],
+            )?;
+            conn.query_row(
+                "SELECT * FROM history WHERE tool = ?1 AND input_hash = ?2",
+                params![tool, hash],
+                entry_from_row,
+            )
+        })
+    }
+
+    /// Most recent entries, favorites first; only favorites when `pinned_only`.
+    pub fn list(&self, limit: u32, pinned_only: bool) -> Result<Vec<HistoryEntry>, String> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT * FROM history WHERE (?1 = 0 OR pinned = 1)
+                 ORDER BY pinned DESC, last_used DESC LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(params![pinned_only, limit.min(MAX_PAGE)], entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    /// Entries whose tool or label contains `query` (case-insensitive).
+    pub fn search(&self, query: &str, limit: u32) -> Result<Vec<HistoryEntry>, String> {
+        let pattern = format!("%{}%", escape_like(query));
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT * FROM history
+                 WHERE tool LIKE ?1 ESCAPE '\\' OR label LIKE ?1 ESCAPE '\\'
+                 ORDER BY pinned DESC, last_used DESC LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(params![pattern, limit.min(MAX_PAGE)], entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    /// Pin or unpin an entry. Returns the updated entry, or `None` when the
+    /// id does not exist.
+    pub fn set_pinned(&self, id: i64, pinned: bool) -> Result<Option<HistoryEntry>, String> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE history SET pinned = ?2 WHERE id = ?1",
+                params![id, pinned],
+            )?;
+            conn.query_row(
+                "SELECT * FROM history WHERE id = ?1",
+                params![id],
+                entry_from_row,
+            )
+            .optional()
+        })
+    }
+
+    /// Every entry, for backups.
+    pub fn export_all(&self) -> Result<Vec<HistoryEntry>, String> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT * FROM history ORDER BY last_used DESC")?;
+            let rows = stmt.query_map([], entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    /// Restore entries from a backup and return how many were written.
+    ///
+    /// With `replace` the current history is discarded first. Otherwise
+    /// entries merge with existing ones on the same tool and input: the
+    /// later use wins, counts take the maximum, and a favorite on either
+    /// side stays a favorite. Row ids are reassigned.
+    pub fn import(&self, entries: &[HistoryEntry], replace: bool) -> Result<usize, String> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            if replace {
+                tx.execute("DELETE FROM history", [])?;
+            }
+            let mut written = 0;
+            for entry in entries {
+                written += tx.execute(
+                    "INSERT INTO history
+                         (tool, input_hash, label, created_at, last_used, use_count, pinned)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                     ON CONFLICT (tool, input_hash) DO UPDATE SET
+                         label = COALESCE(excluded.label, label),
+                         created_at = MIN(created_at, excluded.created_at),
+                         last_used = MAX(last_used, excluded.last_used),
+                         use_count = MAX(use_count, excluded.use_count),
+                         pinned = MAX(pinned, excluded.pinned)",
+                    params![
+                        entry.tool,
+                        entry.input_hash,
+                        entry.label.as_deref().map(clean_label),
+                        entry.created_at,
+                        entry.last_used,
+                        entry.use_count,
+                        entry.pinned
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(written)
+        })
+    }
+
+    /// Delete entries last used before `before_ms` (all entries when
+    /// `None`). Pinned entries survive unless `include_pinned` is set.
+    /// Returns the number of deleted entries.
+    pub fn purge(&self, before_ms: Option<i64>, include_pinned: bool) -> Result<usize, String> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM history WHERE last_used < ?1 AND (?2 = 1 OR pinned = 0)",
+                params![before_ms.unwrap_or(i64::MAX), include_pinned],
+            )
+        })
+    }
+}
+
+fn entry_from_row(row: &Row<'_>) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        tool: row.get("tool")?,
+        input_hash: row.get("input_hash")?,
+        label: row.get("label")?,
+        created_at: row.get("created_at")?,
+        last_used: row.get("last_used")?,
+        use_count: row.get("use_count")?,
+        pinned: row.get("pinned")?,
+    })
+}
+
+/// Truncated hex SHA-256 of the input.
+fn input_hash(input: &str) -> String {
+    let mut hex = hex::encode(Sha256::digest(input.as_bytes()));
+    hex.truncate(INPUT_HASH_LEN);
+    hex
+}
+
+/// Escape `LIKE` wildcards so the query matches literally.
+fn escape_like(query: &str) -> String {
+    query
+        .chars()
+        .flat_map(|c| match c {
+            '%' | '_' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Record a tool invocation in the history.
+#[tauri::command]
+pub fn history_record(
+    tool: String,
+    input: String,
+    label: Option<String>,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<HistoryEntry, String> {
+    state.record(&tool, &input, label.as_deref())
+}
+
+/// List recent history entries, favorites first.
+#[tauri::command]
+pub fn history_list(
+    limit: u32,
+    pinned_only: bool,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<Vec<HistoryEntry>, String> {
+    state.list(limit, pinned_only)
+}
+
+/// Search history entries by tool id or label.
+#[tauri::command]
+pub fn history_search(
+    query: String,
+    limit: u32,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<Vec<HistoryEntry>, String> {
+    state.search(&query, limit)
+}
+
+/// Pin or unpin a history entry.
+#[tauri::command]
+pub fn history_pin(
+    id: i64,
+    pinned: bool,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<Option<HistoryEntry>, String> {
+    state.set_pinned(id, pinned)
+}
+
+/// Delete history entries last used before `before_ms` (all when omitted).
+#[tauri::command]
+pub fn history_purge(
+    before_ms: Option<i64>,
+    include_pinned: bool,
+    state: tauri::State<'_, HistoryState>,
+) -> Result<usize, String> {
+    state.purge(before_ms, include_pinned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> HistoryState {
+        HistoryState::open_in_memory().unwrap()
+    }
+
+    #[test]
+    fn repeated_input_merges_into_one_entry() {
+        let history = state();
+        let first = history
+            .record("json-formatter", "{}", Some("Empty"))
+            .unwrap();
+        let second = history.record("json-formatter", "{}", None).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.use_count, 2);
+        assert_eq!(second.label.as_deref(), Some("Empty"));
+        assert_eq!(second.input_hash.len(), INPUT_HASH_LEN);
+
+        history.record("json-formatter", "[]", None).unwrap();
+        assert_eq!(history.list(10, false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn pinned_entries_list_first_and_survive_purge() {
+        let history = state();
+        let pinned = history.record("hash", "a", None).unwrap();
+        history.record("uuid", "b", None).unwrap();
+        history.set_pinned(pinned.id, true).unwrap();
+
+        let list = history.list(10, false).unwrap();
+        assert_eq!(list[0].id, pinned.id);
+        assert_eq!(history.list(10, true).unwrap().len(), 1);
+
+        assert_eq!(history.purge(None, false).unwrap(), 1);
+        assert_eq!(history.list(10, false).unwrap().len(), 1);
+        assert_eq!(history.purge(None, true).unwrap(), 1);
+        assert!(history.set_pinned(pinned.id, false).unwrap().is_none());
+    }
+
+    #[test]
+    fn search_matches_tool_and_label_literally() {
+        let history = state();
+        history
+            .record("json-formatter", "1", Some("100% done"))
+            .unwrap();
+        history.record("yaml-formatter", "2", None).unwrap();
+        assert_eq!(history.search("formatter", 10).unwrap().len(), 2);
+        assert_eq!(history.search("JSON", 10).unwrap().len(), 1);
+        assert_eq!(history.search("100%", 10).unwrap().len(), 1);
+        assert_eq!(history.search("_", 10).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn import_merges_or_replaces() {
+        let source = state();
+        let fav = source.record("hash", "a", None).unwrap();
+        source.set_pinned(fav.id, true).unwrap();
+        source.record("uuid", "b", None).unwrap();
+        let backup = source.export_all().unwrap();
+
+        let target = state();
+        target.record("hash", "a", Some("Local")).unwrap();
+        target.record("regex", "c", None).unwrap();
+        assert_eq!(target.import(&backup, false).unwrap(), 2);
+        let merged = target.list(10, false).unwrap();
+        assert_eq!(merged.len(), 3);
+        assert!(merged[0].pinned);
+        assert_eq!(merged[0].label.as_deref(), Some("Local"));
+
+        assert_eq!(target.import(&backup, true).unwrap(), 2);
+        assert_eq!(target.list(10, false).unwrap().len(), 2);
+    }
+}
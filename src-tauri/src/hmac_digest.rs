@@ -0,0 +1,315 @@
+//! HMAC and keyed-hash Tauri command.
+//!
+//! Computes HMAC-SHA-1/256/512 (RFC 2104) and BLAKE3 in keyed mode over a
+//! UTF-8 message, next to [`crate::hash_text`]'s unkeyed digests. The key
+//! can be given as text, hex, or base64 and the MAC comes back as hex or
+//! base64, so values copied from API docs or webhook dashboards can be
+//! pasted as they are.
+//!
+//! When an expected MAC is supplied it is compared in constant time
+//! (`ring::hmac::verify`, or BLAKE3's constant-time `Hash` equality), the
+//! same way a server should check a webhook signature.
+
+use base64::Engine;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+
+/// BLAKE3 keyed mode takes exactly this many key bytes.
+const BLAKE3_KEY_BYTES: usize = 32;
+
+/// Keyed hash algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HmacAlgorithm {
+    HmacSha1,
+    HmacSha256,
+    HmacSha512,
+    Blake3,
+}
+
+/// How the key string is turned into key bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyEncoding {
+    #[default]
+    Utf8,
+    Hex,
+    Base64,
+}
+
+/// Text form of the MAC, and of the expected MAC when verifying.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Key and output encodings; UTF-8 key and hex output when omitted.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacEncodings {
+    #[serde(default)]
+    pub key: KeyEncoding,
+    #[serde(default)]
+    pub output: OutputEncoding,
+}
+
+/// MAC of a message, and the verification outcome when requested.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacResult {
+    pub algorithm: HmacAlgorithm,
+    /// MAC in the requested output encoding.
+    pub mac: String,
+    /// Key length after decoding, for the UI's "N-byte key" badge.
+    pub key_bytes: usize,
+    /// Whether the expected MAC matches; `None` when none was given.
+    pub verified: Option<bool>,
+}
+
+/// Compute the MAC of `message` under `key`, and optionally check it
+/// against `expected`.
+///
+/// # Arguments
+///
+/// - `message` — the message (UTF-8 from the renderer).
+/// - `key` — the key, in the encoding named by `encodings.key`.
+/// - `algorithm` — `hmac-sha1`, `hmac-sha256`, `hmac-sha512`, or `blake3`.
+/// - `encodings` — key and output encodings.
+/// - `expected` — a MAC to verify, in the output encoding.
+///
+/// # Errors
+///
+/// Returns a `String` when the key does not decode, a BLAKE3 key is not
+/// 32 bytes, or the `spawn_blocking` worker fails to join. A malformed
+/// expected MAC is a failed verification, not an error.
+#[tauri::command]
+pub async fn hmac_digest(
+    message: String,
+    key: String,
+    algorithm: HmacAlgorithm,
+    encodings: Option<HmacEncodings>,
+    expected: Option<String>,
+) -> Result<HmacResult, String> {
+    tokio::task::spawn_blocking(move || {
+        compute(
+            message.as_bytes(),
+            &key,
+            algorithm,
+            encodings.unwrap_or_default(),
+            expected.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("hmac worker join failed: {e}"))?
+}
+
+fn compute(
+    message: &[u8],
+    key: &str,
+    algorithm: HmacAlgorithm,
+    encodings: HmacEncodings,
+    expected: Option<&str>,
+) -> Result<HmacResult, String> {
+    let key = decode_key(key, encodings.key)?;
+    let expected = expected
+        .map(str::trim)
+        .filter(|expected| !expected.is_empty())
+        .map(|expected| decode_mac(expected, encodings.output));
+
+    let (mac, verified) = if algorithm == HmacAlgorithm::Blake3 {
+        let key: [u8; BLAKE3_KEY_BYTES] = key.as_slice().try_into().map_err(|_| {
+            format!(
+                "BLAKE3 keyed mode needs a {BLAKE3_KEY_BYTES}-byte key, got {} bytes",
+                key.len()
+            )
+        })?;
+        let hash = blake3::keyed_hash(&key, message);
+        let verified = expected.map(|expected| {
+            expected
+                .and_then(|bytes| <[u8; blake3::OUT_LEN]>::try_from(bytes).ok())
+                .is_some_and(|bytes| blake3::Hash::from(bytes) == hash)
+        });
+        (hash.as_bytes().to_vec(), verified)
+    } else {
+        let ring_algorithm = match algorithm {
+            HmacAlgorithm::HmacSha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+            HmacAlgorithm::HmacSha256 => hmac::HMAC_SHA256,
+            _ => hmac::HMAC_SHA512,
+        };
+        let signing_key = hmac::Key::new(ring_algorithm, &key);
+        let tag = hmac::sign(&signing_key, message);
+        let verified = expected.map(|expected| {
+            expected.is_some_and(|bytes| hmac::verify(&signing_key, message, &bytes).is_ok())
+        });
+        (tag.as_ref().to_vec(), verified)
+    };
+
+    Ok(HmacResult {
+        algorithm,
+        mac: match encodings.output {
+            OutputEncoding::Hex => hex::encode(&mac),
+            OutputEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(&mac),
+        },
+        key_bytes: key.len(),
+        verified,
+    })
+}
+
+fn decode_key(key: &str, encoding: KeyEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        KeyEncoding::Utf8 => Ok(key.as_bytes().to_vec()),
+        KeyEncoding::Hex => {
+            hex::decode(strip_whitespace(key)).map_err(|e| format!("Invalid hex key: {e}"))
+        }
+        KeyEncoding::Base64 => {
+            decode_base64(&strip_whitespace(key)).ok_or_else(|| "Invalid base64 key".to_string())
+        }
+    }
+}
+
+/// Expected MAC bytes; `None` when the text does not decode.
+fn decode_mac(mac: &str, encoding: OutputEncoding) -> Option<Vec<u8>> {
+    let mac = strip_whitespace(mac);
+    match encoding {
+        OutputEncoding::Hex => hex::decode(mac).ok(),
+        OutputEncoding::Base64 => decode_base64(&mac),
+    }
+}
+
+/// Standard or URL-safe base64, padded or not — webhook providers use
+/// all four.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+    let unpadded = text.trim_end_matches('=');
+    STANDARD.decode(text).ok().or_else(|| {
+        URL_SAFE_NO_PAD
+            .decode(unpadded.replace('+', "-").replace('/', "_"))
+            .ok()
+    })
+}
+
+fn strip_whitespace(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    fn mac(algorithm: HmacAlgorithm, key: &str, encodings: HmacEncodings) -> String {
+        compute(MESSAGE, key, algorithm, encodings, None)
+            .unwrap()
+            .mac
+    }
+
+    #[test]
+    fn matches_known_hmac_vectors() {
+        let encodings = HmacEncodings::default();
+        assert_eq!(
+            mac(HmacAlgorithm::HmacSha1, "key", encodings),
+            "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+        );
+        assert_eq!(
+            mac(HmacAlgorithm::HmacSha256, "key", encodings),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert!(mac(HmacAlgorithm::HmacSha512, "key", encodings).starts_with("b42af09057bac1e2"));
+
+        // "key" in hex and base64 gives the same MAC as the text key.
+        let hex_key = HmacEncodings {
+            key: KeyEncoding::Hex,
+            output: OutputEncoding::Base64,
+        };
+        assert_eq!(
+            mac(HmacAlgorithm::HmacSha256, "6b 65 79", hex_key),
+            "97yD9DBThCSxMpjmqm+xQ+9NWaFJRhdZl0edvC0aPNg="
+        );
+        let base64_key = HmacEncodings {
+            key: KeyEncoding::Base64,
+            ..HmacEncodings::default()
+        };
+        assert_eq!(
+            mac(HmacAlgorithm::HmacSha1, "a2V5", base64_key),
+            "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+        );
+        assert!(compute(MESSAGE, "zz", HmacAlgorithm::HmacSha1, hex_key, None).is_err());
+    }
+
+    #[test]
+    fn blake3_keyed_mode_requires_32_byte_key() {
+        let key = "00".repeat(BLAKE3_KEY_BYTES);
+        let hex_key = HmacEncodings {
+            key: KeyEncoding::Hex,
+            ..HmacEncodings::default()
+        };
+        assert_eq!(
+            mac(HmacAlgorithm::Blake3, &key, hex_key),
+            blake3::keyed_hash(&[0; BLAKE3_KEY_BYTES], MESSAGE)
+                .to_hex()
+                .to_string()
+        );
+        let err = compute(
+            MESSAGE,
+            "short",
+            HmacAlgorithm::Blake3,
+            HmacEncodings::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("32-byte key"));
+    }
+
+    #[test]
+    fn verifies_expected_mac() {
+        let encodings = HmacEncodings::default();
+        let verify = |algorithm, key: &str, expected| {
+            compute(MESSAGE, key, algorithm, encodings, Some(expected))
+                .unwrap()
+                .verified
+        };
+        assert_eq!(
+            verify(
+                HmacAlgorithm::HmacSha1,
+                "key",
+                " DE7C9B85B8B78AA6BC8A7A36F70A90701C9DB4D9\n"
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            verify(
+                HmacAlgorithm::HmacSha1,
+                "key",
+                "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d8"
+            ),
+            Some(false)
+        );
+        assert_eq!(
+            verify(HmacAlgorithm::HmacSha1, "key", "not hex"),
+            Some(false)
+        );
+        let key = "k".repeat(BLAKE3_KEY_BYTES);
+        let expected = mac(HmacAlgorithm::Blake3, &key, encodings);
+        assert_eq!(verify(HmacAlgorithm::Blake3, &key, &expected), Some(true));
+        assert_eq!(
+            verify(HmacAlgorithm::Blake3, &key, &expected[..10]),
+            Some(false)
+        );
+        assert_eq!(
+            compute(
+                MESSAGE,
+                "key",
+                HmacAlgorithm::HmacSha256,
+                encodings,
+                Some("  ")
+            )
+            .unwrap()
+            .verified,
+            None
+        );
+    }
+}
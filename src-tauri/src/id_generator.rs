@@ -0,0 +1,464 @@
+//! ULID, Nano ID, KSUID, and Snowflake ID generation Tauri commands.
+//!
+//! Companion to [`crate::uuid_generator`] for the identifier formats
+//! that are not UUIDs. Generation runs on `tokio::task::spawn_blocking`
+//! so a batch of [`MAX_COUNT`] stays off the async runtime, and every
+//! time-based format stays strictly increasing within a batch: ULIDs
+//! increment their random part within a millisecond, Snowflakes their
+//! sequence.
+//!
+//! `decode_id` recovers the creation time of a ULID, KSUID, or
+//! Snowflake. Nano IDs are pure randomness and carry no timestamp.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+/// Most IDs generated in one call.
+pub const MAX_COUNT: usize = 100_000;
+
+/// URL-safe alphabet of the reference Nano ID implementation.
+const NANOID_ALPHABET: &str = "useandom-26T198340PX75pxJACKVERYMINDBUSHWOLF_GQZbfghjklqvwyzrict";
+const NANOID_DEFAULT_LENGTH: usize = 21;
+const NANOID_MAX_LENGTH: usize = 256;
+
+/// Crockford base32 alphabet of ULIDs.
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Base62 alphabet of KSUIDs.
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// KSUID timestamps count seconds from 2014-05-13 16:53:20 UTC.
+const KSUID_EPOCH_SECONDS: i64 = 1_400_000_000;
+const KSUID_BYTES: usize = 20;
+const KSUID_LENGTH: usize = 27;
+
+/// Twitter's Snowflake epoch, 2010-11-04 01:42:54.657 UTC.
+const SNOWFLAKE_DEFAULT_EPOCH: i64 = 1_288_834_974_657;
+const SNOWFLAKE_NODE_BITS: u32 = 10;
+const SNOWFLAKE_SEQUENCE_BITS: u32 = 12;
+
+/// Identifier format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdKind {
+    Ulid,
+    Nanoid,
+    Ksuid,
+    Snowflake,
+}
+
+/// Format-specific options; each format reads only its own fields.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdOptions {
+    /// Nano ID alphabet; the URL-safe alphabet when omitted.
+    pub alphabet: Option<String>,
+    /// Nano ID length; 21 when omitted.
+    pub length: Option<usize>,
+    /// Snowflake epoch in UNIX milliseconds; Twitter's when omitted.
+    pub epoch: Option<i64>,
+    /// Snowflake node (worker) ID, 0 to 1023.
+    pub node: Option<u16>,
+}
+
+/// Fields decoded from an identifier.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedId {
+    pub kind: IdKind,
+    /// Creation time in UNIX milliseconds; KSUIDs have second precision.
+    pub unix_millis: i64,
+    /// Random part of a ULID or KSUID, as lower-case hex.
+    pub random: Option<String>,
+    /// Node ID of a Snowflake.
+    pub node: Option<u16>,
+    /// Per-millisecond sequence of a Snowflake.
+    pub sequence: Option<u16>,
+}
+
+/// Generate `count` identifiers of `kind`.
+///
+/// # Arguments
+///
+/// - `kind` — `ulid`, `nanoid`, `ksuid`, or `snowflake`.
+/// - `count` — number of IDs, 1 to [`MAX_COUNT`].
+/// - `options` — Nano ID alphabet and length, Snowflake epoch and node.
+///
+/// # Errors
+///
+/// Returns a `String` for a count or option out of range, or a failed
+/// random source.
+#[tauri::command]
+pub async fn generate_ids(
+    kind: IdKind,
+    count: usize,
+    options: Option<IdOptions>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        generate(kind, count, &options.unwrap_or_default(), unix_millis())
+    })
+    .await
+    .map_err(|e| format!("id worker join failed: {e}"))?
+}
+
+/// Decode the creation time and embedded fields of a ULID, KSUID, or
+/// Snowflake.
+///
+/// # Errors
+///
+/// Returns a `String` when `id` is malformed for `kind`, or `kind` is
+/// `nanoid`.
+#[tauri::command]
+pub fn decode_id(kind: IdKind, id: &str, epoch: Option<i64>) -> Result<DecodedId, String> {
+    let id = id.trim();
+    match kind {
+        IdKind::Ulid => decode_ulid(id),
+        IdKind::Ksuid => decode_ksuid(id),
+        IdKind::Snowflake => decode_snowflake(id, epoch.unwrap_or(SNOWFLAKE_DEFAULT_EPOCH)),
+        IdKind::Nanoid => Err("Nano IDs are random and carry no timestamp".to_string()),
+    }
+}
+
+fn generate(
+    kind: IdKind,
+    count: usize,
+    options: &IdOptions,
+    now_millis: i64,
+) -> Result<Vec<String>, String> {
+    if !(1..=MAX_COUNT).contains(&count) {
+        return Err(format!("Count must be between 1 and {MAX_COUNT}"));
+    }
+    let rng = SystemRandom::new();
+    match kind {
+        IdKind::Ulid => {
+            let seed = random_bytes(&rng, 10)?;
+            let mut millis = u128::try_from(now_millis).unwrap_or_default();
+            // Keep the top bit clear so the batch can count up within one
+            // millisecond without overflowing into the next.
+            let mut randomness = seed
+                .iter()
+                .fold(0u128, |acc, &b| (acc << 8) | u128::from(b))
+                >> 1;
+            let mut ids = Vec::with_capacity(count);
+            for _ in 0..count {
+                ids.push(encode_ulid((millis << 80) | randomness));
+                randomness += 1;
+                if randomness >> 80 != 0 {
+                    millis += 1;
+                    randomness = 0;
+                }
+            }
+            Ok(ids)
+        }
+        IdKind::Nanoid => {
+            let alphabet: Vec<char> = options
+                .alphabet
+                .as_deref()
+                .filter(|alphabet| !alphabet.is_empty())
+                .unwrap_or(NANOID_ALPHABET)
+                .chars()
+                .collect();
+            let length = options.length.unwrap_or(NANOID_DEFAULT_LENGTH);
+            validate_nanoid(&alphabet, length)?;
+            (0..count)
+                .map(|_| nanoid(&rng, &alphabet, length))
+                .collect()
+        }
+        IdKind::Ksuid => {
+            let seconds = u32::try_from(now_millis / 1000 - KSUID_EPOCH_SECONDS)
+                .map_err(|_| "Clock is outside the KSUID range".to_string())?;
+            let payload = random_bytes(&rng, count * 16)?;
+            Ok(payload
+                .chunks_exact(16)
+                .map(|chunk| {
+                    let mut bytes = [0; KSUID_BYTES];
+                    bytes[..4].copy_from_slice(&seconds.to_be_bytes());
+                    bytes[4..].copy_from_slice(chunk);
+                    encode_base62(&bytes)
+                })
+                .collect())
+        }
+        IdKind::Snowflake => {
+            let epoch = options.epoch.unwrap_or(SNOWFLAKE_DEFAULT_EPOCH);
+            let node = options.node.unwrap_or_default();
+            if node >> SNOWFLAKE_NODE_BITS != 0 {
+                return Err(format!(
+                    "Node must be between 0 and {}",
+                    (1 << SNOWFLAKE_NODE_BITS) - 1
+                ));
+            }
+            let mut elapsed = u64::try_from(now_millis - epoch)
+                .map_err(|_| "Epoch must not be in the future".to_string())?;
+            let mut sequence = 0u64;
+            let mut ids = Vec::with_capacity(count);
+            for _ in 0..count {
+                if elapsed >> 41 != 0 {
+                    return Err("Timestamp overflows 41 bits for this epoch".to_string());
+                }
+                let id = (elapsed << (SNOWFLAKE_NODE_BITS + SNOWFLAKE_SEQUENCE_BITS))
+                    | (u64::from(node) << SNOWFLAKE_SEQUENCE_BITS)
+                    | sequence;
+                ids.push(id.to_string());
+                sequence += 1;
+                if sequence >> SNOWFLAKE_SEQUENCE_BITS != 0 {
+                    elapsed += 1;
+                    sequence = 0;
+                }
+            }
+            Ok(ids)
+        }
+    }
+}
+
+fn validate_nanoid(alphabet: &[char], length: usize) -> Result<(), String> {
+    if !(2..=256).contains(&alphabet.len()) {
+        return Err("Alphabet must have between 2 and 256 characters".to_string());
+    }
+    let mut unique = alphabet.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+    if unique.len() != alphabet.len() {
+        return Err("Alphabet must not repeat characters".to_string());
+    }
+    if !(1..=NANOID_MAX_LENGTH).contains(&length) {
+        return Err(format!("Length must be between 1 and {NANOID_MAX_LENGTH}"));
+    }
+    Ok(())
+}
+
+/// Nano ID drawn without modulo bias: each random byte is masked to the
+/// smallest power of two covering the alphabet and rejected when out of
+/// range.
+fn nanoid(rng: &SystemRandom, alphabet: &[char], length: usize) -> Result<String, String> {
+    let mask = alphabet.len().next_power_of_two() - 1;
+    let mut id = String::with_capacity(length);
+    let mut produced = 0;
+    while produced < length {
+        for byte in random_bytes(rng, length * 2)? {
+            if let Some(&c) = alphabet.get(usize::from(byte) & mask) {
+                id.push(c);
+                produced += 1;
+                if produced == length {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(id)
+}
+
+fn encode_ulid(value: u128) -> String {
+    (0..26)
+        .map(|i| {
+            let index = (value >> (125 - 5 * i)) & 0x1F;
+            char::from(CROCKFORD[usize::try_from(index).unwrap_or_default()])
+        })
+        .collect()
+}
+
+fn decode_ulid(id: &str) -> Result<DecodedId, String> {
+    if id.len() != 26 {
+        return Err(format!("A ULID has 26 characters, got {}", id.len()));
+    }
+    let mut value = 0u128;
+    for (i, c) in id.chars().enumerate() {
+        // Crockford base32 reads I and L as 1 and O as 0.
+        let c = match c.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        };
+        let digit = CROCKFORD
+            .iter()
+            .position(|&b| char::from(b) == c)
+            .ok_or_else(|| format!("Invalid ULID character '{c}'"))?;
+        if i == 0 && digit > 7 {
+            return Err("ULID is larger than 128 bits".to_string());
+        }
+        value = (value << 5) | digit as u128;
+    }
+    Ok(DecodedId {
+        kind: IdKind::Ulid,
+        unix_millis: i64::try_from(value >> 80).unwrap_or(i64::MAX),
+        random: Some(format!("{:020x}", value & ((1 << 80) - 1))),
+        node: None,
+        sequence: None,
+    })
+}
+
+/// Base62 of a big-endian number, left-padded with zeros to 27 digits
+fn encode_base62(bytes: &[u8; KSUID_BYTES]) -> String {
+    let mut number = bytes.to_vec();
+    let mut digits = Vec::with_capacity(KSUID_LENGTH);
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in &mut number {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = u8::try_from(value / 62).unwrap_or_default();
+            remainder = value % 62;
+        }
+        digits.push(BASE62[usize::try_from(remainder).unwrap_or_default()]);
+    }
+    digits.resize(KSUID_LENGTH, b'0');
+    digits.iter().rev().map(|&b| char::from(b)).collect()
+}
+
+fn decode_ksuid(id: &str) -> Result<DecodedId, String> {
+    if id.len() != KSUID_LENGTH {
+        return Err(format!(
+            "A KSUID has {KSUID_LENGTH} characters, got {}",
+            id.len()
+        ));
+    }
+    let mut bytes = [0u8; KSUID_BYTES];
+    for c in id.bytes() {
+        let digit = BASE62
+            .iter()
+            .position(|&b| b == c)
+            .ok_or_else(|| format!("Invalid KSUID character '{}'", char::from(c)))?;
+        let mut carry = u32::try_from(digit).unwrap_or_default();
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 62 + carry;
+            *byte = (value & 0xFF) as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err("KSUID is larger than 160 bits".to_string());
+        }
+    }
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    Ok(DecodedId {
+        kind: IdKind::Ksuid,
+        unix_millis: (i64::from(seconds) + KSUID_EPOCH_SECONDS) * 1000,
+        random: Some(hex::encode(&bytes[4..])),
+        node: None,
+        sequence: None,
+    })
+}
+
+fn decode_snowflake(id: &str, epoch: i64) -> Result<DecodedId, String> {
+    let value: u64 = id
+        .parse()
+        .map_err(|_| format!("A Snowflake is a decimal 64-bit integer, got \"{id}\""))?;
+    let elapsed = value >> (SNOWFLAKE_NODE_BITS + SNOWFLAKE_SEQUENCE_BITS);
+    let node = (value >> SNOWFLAKE_SEQUENCE_BITS) & ((1 << SNOWFLAKE_NODE_BITS) - 1);
+    let sequence = value & ((1 << SNOWFLAKE_SEQUENCE_BITS) - 1);
+    Ok(DecodedId {
+        kind: IdKind::Snowflake,
+        unix_millis: i64::try_from(elapsed).unwrap_or(i64::MAX) + epoch,
+        random: None,
+        node: u16::try_from(node).ok(),
+        sequence: u16::try_from(sequence).ok(),
+    })
+}
+
+fn random_bytes(rng: &SystemRandom, len: usize) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![0; len];
+    rng.fill(&mut bytes)
+        .map_err(|_| "Failed to read random bytes".to_string())?;
+    Ok(bytes)
+}
+
+fn unix_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2022-02-22 19:22:22 UTC
+    const NOW: i64 = 1_645_557_742_000;
+
+    #[test]
+    fn test_ulid_round_trip_and_order() {
+        let ids = generate(IdKind::Ulid, 5_000, &IdOptions::default(), NOW).unwrap();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ids.iter().all(|id| id.len() == 26));
+        assert_eq!(decode_ulid(&ids[0]).unwrap().unix_millis, NOW);
+
+        let decoded = decode_id(IdKind::Ulid, "01ARZ3NDEKTSV4RRFFQ69G5FAV", None).unwrap();
+        assert_eq!(decoded.unix_millis, 1_469_922_850_259);
+        assert_eq!(
+            decode_ulid("01arz3ndektsv4rrffq69g5fav").unwrap().random,
+            decoded.random
+        );
+        assert!(decode_ulid("81ARZ3NDEKTSV4RRFFQ69G5FAV").is_err());
+    }
+
+    #[test]
+    fn test_ksuid_vector() {
+        let decoded = decode_id(IdKind::Ksuid, "0ujtsYcgvSTl8PAuAdqWYSMnLOv", None).unwrap();
+        assert_eq!(decoded.unix_millis, 1_507_608_047_000);
+        assert_eq!(
+            decoded.random.as_deref(),
+            Some("b5a1cd34b5f99d1154fb6853345c9735")
+        );
+
+        let ids = generate(IdKind::Ksuid, 3, &IdOptions::default(), NOW).unwrap();
+        assert!(ids.iter().all(|id| id.len() == KSUID_LENGTH));
+        assert_eq!(decode_ksuid(&ids[0]).unwrap().unix_millis, NOW);
+        assert_eq!(encode_base62(&[0; KSUID_BYTES]), "0".repeat(KSUID_LENGTH));
+        assert_eq!(
+            encode_base62(&[0xFF; KSUID_BYTES]),
+            "aWgEPTl1tmebfsQzFP4bxwgy80V"
+        );
+    }
+
+    #[test]
+    fn test_snowflake() {
+        let decoded = decode_id(IdKind::Snowflake, "1212092628029698048", None).unwrap();
+        assert_eq!(decoded.unix_millis, 1_577_820_376_771);
+
+        let options = IdOptions {
+            epoch: Some(1_420_070_400_000),
+            node: Some(513),
+            ..IdOptions::default()
+        };
+        let ids = generate(IdKind::Snowflake, 5_000, &options, NOW).unwrap();
+        let values: Vec<u64> = ids.iter().map(|id| id.parse().unwrap()).collect();
+        assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        let first = decode_snowflake(&ids[0], 1_420_070_400_000).unwrap();
+        assert_eq!(
+            (first.unix_millis, first.node, first.sequence),
+            (NOW, Some(513), Some(0))
+        );
+        let next = decode_snowflake(&ids[4096], 1_420_070_400_000).unwrap();
+        assert_eq!((next.unix_millis, next.sequence), (NOW + 1, Some(0)));
+
+        let invalid = IdOptions {
+            node: Some(1024),
+            ..IdOptions::default()
+        };
+        assert!(generate(IdKind::Snowflake, 1, &invalid, NOW).is_err());
+    }
+
+    #[test]
+    fn test_nanoid() {
+        let ids = generate(IdKind::Nanoid, 100, &IdOptions::default(), NOW).unwrap();
+        assert!(ids
+            .iter()
+            .all(|id| id.chars().count() == NANOID_DEFAULT_LENGTH
+                && id.chars().all(|c| NANOID_ALPHABET.contains(c))));
+
+        let options = IdOptions {
+            alphabet: Some("abc".to_string()),
+            length: Some(8),
+            ..IdOptions::default()
+        };
+        let ids = generate(IdKind::Nanoid, 10, &options, NOW).unwrap();
+        assert!(ids
+            .iter()
+            .all(|id| id.len() == 8 && id.chars().all(|c| "abc".contains(c))));
+
+        let repeated = IdOptions {
+            alphabet: Some("aab".to_string()),
+            ..IdOptions::default()
+        };
+        assert!(generate(IdKind::Nanoid, 1, &repeated, NOW).is_err());
+        assert!(decode_id(IdKind::Nanoid, "V1StGXR8_Z5jdHi6B-myT", None).is_err());
+    }
+}
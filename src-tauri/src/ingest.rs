@@ -0,0 +1,401 @@
+//! Drag-and-drop ingestion
+//!
+//! [`ingest_file`] sniffs a dropped file and routes it to the tool that
+//! can open it, returning the payload that tool needs so drop-anything-
+//! anywhere behaves the same on every page. Detection checks, in order:
+//!
+//! 1. Magic bytes (images, archives, executables, PDF, SQLite)
+//! 2. Content heuristics for UTF-8 text (JSON, XML, PEM, JWT, SQL, YAML)
+//! 3. The file extension
+//!
+//! Text is routed to the matching formatter or decoder, images to the
+//! image converter, archives to the archive inspector, and anything else
+//! binary to the hex editor.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use base64::Engine as _;
+use serde::Serialize;
+
+use crate::archive_inspect::{self, ArchiveInfo};
+use crate::catalog;
+use crate::hex_editor::{self, HexFileInfo};
+
+/// Leading bytes read for sniffing.
+const SNIFF_BYTES: u64 = 8192;
+
+/// Largest text file returned inline; bigger files go to the hex editor.
+const MAX_TEXT_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Largest image returned inline.
+const MAX_IMAGE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Which evidence decided the detected type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetectionSource {
+    /// Signature at a fixed offset.
+    Magic,
+    /// Structure of the text content.
+    Content,
+    /// File name extension.
+    Extension,
+    /// Nothing matched; generic text or binary.
+    Fallback,
+}
+
+/// Detected file type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedType {
+    /// Short type name (`json`, `png`, `zip`, `binary`, ...).
+    pub kind: &'static str,
+    /// MIME type.
+    pub mime: &'static str,
+    /// Evidence used.
+    pub source: DetectionSource,
+}
+
+/// Data the target tool opens with.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IngestPayload {
+    /// UTF-8 text for editor-based tools.
+    Text {
+        /// File content.
+        content: String,
+    },
+    /// Image bytes for the image converter.
+    Image {
+        /// Base64-encoded file content.
+        data_b64: String,
+    },
+    /// Archive listing for the archive inspector.
+    Archive(ArchiveInfo),
+    /// File metadata for the hex editor, which reads ranges lazily.
+    Hex(HexFileInfo),
+}
+
+/// Result of [`ingest_file`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestResult {
+    /// Path of the ingested file.
+    pub path: String,
+    /// Final path component.
+    pub filename: String,
+    /// File size in bytes.
+    pub size_bytes: u64,
+    /// Detected type.
+    pub detected: DetectedType,
+    /// Catalog id of the tool the file is routed to.
+    pub tool: &'static str,
+    /// Route of that tool.
+    pub url: &'static str,
+    /// Data for the tool.
+    pub payload: IngestPayload,
+}
+
+const fn detected(kind: &'static str, mime: &'static str, source: DetectionSource) -> DetectedType {
+    DetectedType { kind, mime, source }
+}
+
+/// Two-byte signatures that plain text can start with ("BMW", "MZ-80");
+/// only trusted when the content is not text.
+const WEAK_SIGNATURES: &[(&[u8], &str, &str)] = &[
+    (b"BM", "bmp", "image/bmp"),
+    (b"MZ", "pe", "application/vnd.microsoft.portable-executable"),
+];
+
+/// Identify binary formats by signature.
+fn sniff_magic(head: &[u8]) -> Option<DetectedType> {
+    const SIGNATURES: &[(&[u8], &str, &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "png", "image/png"),
+        (b"\xFF\xD8\xFF", "jpeg", "image/jpeg"),
+        (b"GIF87a", "gif", "image/gif"),
+        (b"GIF89a", "gif", "image/gif"),
+        (b"\x00\x00\x01\x00", "ico", "image/x-icon"),
+        (b"II*\x00", "tiff", "image/tiff"),
+        (b"MM\x00*", "tiff", "image/tiff"),
+        (b"%PDF-", "pdf", "application/pdf"),
+        (b"PK\x03\x04", "zip", "application/zip"),
+        (b"\x1F\x8B", "gzip", "application/gzip"),
+        (b"BZh", "bzip2", "application/x-bzip2"),
+        (b"\xFD7zXZ\x00", "xz", "application/x-xz"),
+        (b"7z\xBC\xAF\x27\x1C", "7z", "application/x-7z-compressed"),
+        (b"\x7FELF", "elf", "application/x-elf"),
+        (b"SQLite format 3\x00", "sqlite", "application/vnd.sqlite3"),
+    ];
+    if head.len() >= 12 && head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+        return Some(detected("webp", "image/webp", DetectionSource::Magic));
+    }
+    if head.get(257..262) == Some(b"ustar") {
+        return Some(detected("tar", "application/x-tar", DetectionSource::Magic));
+    }
+    SIGNATURES
+        .iter()
+        .find(|(magic, _, _)| head.starts_with(magic))
+        .map(|(_, kind, mime)| detected(kind, mime, DetectionSource::Magic))
+}
+
+/// Decode `head` as UTF-8, tolerating a character cut off by the read
+/// limit. `None` for binary content.
+fn as_text(head: &[u8], complete: bool) -> Option<&str> {
+    if head.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(head) {
+        Ok(text) => Some(text),
+        Err(e) if !complete && e.error_len().is_none() => {
+            std::str::from_utf8(head.get(..e.valid_up_to())?).ok()
+        }
+        Err(_) => None,
+    }
+}
+
+/// Identify text formats by their structure. `complete` is true when
+/// `text` is the whole file, which allows a full JSON parse.
+fn sniff_text(text: &str, complete: bool) -> Option<DetectedType> {
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    let content = |kind, mime| Some(detected(kind, mime, DetectionSource::Content));
+
+    if trimmed.starts_with("-----BEGIN CERTIFICATE-----") {
+        return content("certificate", "application/x-pem-file");
+    }
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        let parses = serde_json::from_str::<serde_json::Value>(trimmed).is_ok();
+        if parses || !complete {
+            return content("json", "application/json");
+        }
+    }
+    if trimmed.starts_with("<?xml") || (trimmed.starts_with('<') && trimmed.contains("</")) {
+        return content("xml", "application/xml");
+    }
+    let single_line = trimmed.trim_end();
+    if !single_line.contains(char::is_whitespace)
+        && single_line.starts_with("eyJ")
+        && single_line.matches('.').count() == 2
+    {
+        return content("jwt", "application/jwt");
+    }
+    let first_word = trimmed
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    if matches!(
+        first_word.as_str(),
+        "SELECT" | "INSERT" | "UPDATE" | "DELETE" | "CREATE" | "ALTER" | "DROP" | "WITH"
+    ) {
+        return content("sql", "application/sql");
+    }
+    if trimmed.starts_with("---\n") || trimmed.starts_with("%YAML") {
+        return content("yaml", "application/yaml");
+    }
+    None
+}
+
+/// Identify a file by its extension.
+fn sniff_extension(extension: &str) -> Option<DetectedType> {
+    let (kind, mime) = match extension {
+        "json" | "jsonc" | "geojson" => ("json", "application/json"),
+        "yaml" | "yml" => ("yaml", "application/yaml"),
+        "xml" | "xsd" | "xsl" | "svg" | "plist" => ("xml", "application/xml"),
+        "sql" => ("sql", "application/sql"),
+        "csv" => ("csv", "text/csv"),
+        "tsv" => ("tsv", "text/tab-separated-values"),
+        "md" | "markdown" => ("markdown", "text/markdown"),
+        "pem" | "crt" | "cer" => ("certificate", "application/x-pem-file"),
+        "jwt" => ("jwt", "application/jwt"),
+        "tar" => ("tar", "application/x-tar"),
+        _ => return None,
+    };
+    Some(detected(kind, mime, DetectionSource::Extension))
+}
+
+/// Detect the type of a file from its first bytes and extension.
+fn detect(head: &[u8], complete: bool, extension: &str) -> DetectedType {
+    if let Some(found) = sniff_magic(head) {
+        return found;
+    }
+    let Some(text) = as_text(head, complete) else {
+        return WEAK_SIGNATURES
+            .iter()
+            .find(|(magic, _, _)| head.starts_with(magic))
+            .map_or_else(
+                || {
+                    detected(
+                        "binary",
+                        "application/octet-stream",
+                        DetectionSource::Fallback,
+                    )
+                },
+                |(_, kind, mime)| detected(kind, mime, DetectionSource::Magic),
+            );
+    };
+    // Extensions that name a specific text format win over content
+    // guesses (a `.csv` whose first cell starts with `{` is still CSV).
+    let by_extension = sniff_extension(extension);
+    if let Some(found) = by_extension.filter(|d| matches!(d.kind, "csv" | "tsv" | "markdown")) {
+        return found;
+    }
+    sniff_text(text, complete)
+        .or(by_extension)
+        .unwrap_or_else(|| detected("text", "text/plain", DetectionSource::Fallback))
+}
+
+/// Catalog id of the tool that opens `kind`.
+fn route(kind: &str) -> &'static str {
+    match kind {
+        "json" => "json-formatter",
+        "yaml" => "yaml-formatter",
+        "xml" => "xml-formatter",
+        "sql" => "sql-formatter",
+        "csv" | "tsv" => "csv-tool",
+        "markdown" => "markdown-editor",
+        "certificate" => "x509-decoder",
+        "jwt" => "jwt-decoder",
+        "png" | "jpeg" | "gif" | "bmp" | "ico" | "tiff" | "webp" => "image-converter",
+        "zip" | "gzip" | "bzip2" | "xz" | "7z" | "tar" => "archive-inspector",
+        "text" => "encoding-converter",
+        _ => "hex-editor",
+    }
+}
+
+/// Load the payload for `tool`.
+fn load_payload(tool: &str, path: &str, size: u64) -> Result<IngestPayload, String> {
+    match tool {
+        "image-converter" if size <= MAX_IMAGE_BYTES => {
+            let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            Ok(IngestPayload::Image {
+                data_b64: base64::engine::general_purpose::STANDARD.encode(bytes),
+            })
+        }
+        "archive-inspector" => {
+            archive_inspect::archive_open(path.to_string()).map(IngestPayload::Archive)
+        }
+        "hex-editor" | "image-converter" => {
+            hex_editor::hex_open(path.to_string()).map(IngestPayload::Hex)
+        }
+        _ if size <= MAX_TEXT_BYTES => {
+            let content =
+                std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            Ok(IngestPayload::Text { content })
+        }
+        _ => Err(format!("File is too large to open as text ({size} bytes)")),
+    }
+}
+
+/// Sniff the file at `path`, pick the tool that opens it, and return the
+/// payload for that tool. Files the chosen tool cannot take (a gzip that
+/// is not a tarball, text too large for an editor) fall back to the hex
+/// editor.
+///
+/// # Errors
+///
+/// Returns an error string when the path is not a readable file.
+#[tauri::command(async)]
+pub fn ingest_file(path: String) -> Result<IngestResult, String> {
+    let file_path = Path::new(&path);
+    let metadata =
+        std::fs::metadata(file_path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a regular file: {path}"));
+    }
+    let size_bytes = metadata.len();
+
+    let mut head = Vec::new();
+    File::open(file_path)
+        .and_then(|f| f.take(SNIFF_BYTES).read_to_end(&mut head))
+        .map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let complete = u64::try_from(head.len()).is_ok_and(|n| n >= size_bytes);
+    let extension = file_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let detected = detect(&head, complete, &extension);
+    let mut tool = route(detected.kind);
+    let payload = match load_payload(tool, &path, size_bytes) {
+        Ok(payload) => payload,
+        Err(_) => {
+            tool = "hex-editor";
+            IngestPayload::Hex(hex_editor::hex_open(path.clone())?)
+        }
+    };
+    let url = catalog::find_tool(tool).map_or("/", |t| t.url);
+
+    Ok(IngestResult {
+        filename: file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path,
+        size_bytes,
+        detected,
+        tool,
+        url,
+        payload,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(head: &[u8], extension: &str) -> &'static str {
+        detect(head, true, extension).kind
+    }
+
+    #[test]
+    fn detects_by_magic_content_and_extension() {
+        assert_eq!(kind(b"\x89PNG\r\n\x1a\n....", "txt"), "png");
+        assert_eq!(kind(b"RIFF\x00\x00\x00\x00WEBPVP8 ", ""), "webp");
+        assert_eq!(kind(b"PK\x03\x04rest", "docx"), "zip");
+        assert_eq!(kind(br#"{"a": [1, 2]}"#, "txt"), "json");
+        assert_eq!(kind(b"<?xml version=\"1.0\"?><a/>", ""), "xml");
+        assert_eq!(
+            kind(b"-----BEGIN CERTIFICATE-----\nMIIB", "txt"),
+            "certificate"
+        );
+        assert_eq!(kind(b"eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.sig", ""), "jwt");
+        assert_eq!(kind(b"select * from users;", ""), "sql");
+        assert_eq!(kind(b"name: kogu\n", "yml"), "yaml");
+        assert_eq!(kind(b"{a},b\n1,2\n", "csv"), "csv");
+        assert_eq!(kind(b"just some notes", ""), "text");
+        assert_eq!(kind(b"BMW service log", ""), "text");
+        assert_eq!(kind(b"BM\x36\x00\x00\x00\x00\x00", ""), "bmp");
+        assert_eq!(kind(b"\x00\x01\x02garbage", ""), "binary");
+    }
+
+    #[test]
+    fn tolerates_truncated_utf8_and_json() {
+        // "é" split across the sniff boundary.
+        let head = b"{\"name\": \"caf\xC3";
+        assert_eq!(as_text(head, false), Some("{\"name\": \"caf"));
+        assert_eq!(detect(head, false, "").kind, "json");
+        assert!(as_text(head, true).is_none());
+    }
+
+    #[test]
+    fn routes_files_to_their_tool() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("data.json");
+        std::fs::write(&json, r#"{"ok": true}"#).unwrap();
+        let result = ingest_file(json.to_string_lossy().into_owned()).unwrap();
+        assert_eq!(result.tool, "json-formatter");
+        assert_eq!(result.url, "/json-formatter");
+        assert!(
+            matches!(result.payload, IngestPayload::Text { ref content } if content.contains("ok"))
+        );
+
+        // A bare gzip stream is not a tarball, so it lands in the hex editor.
+        let gz = dir.path().join("blob.gz");
+        std::fs::write(&gz, b"\x1F\x8B\x08\x00garbage").unwrap();
+        let result = ingest_file(gz.to_string_lossy().into_owned()).unwrap();
+        assert_eq!(result.detected.kind, "gzip");
+        assert_eq!(result.tool, "hex-editor");
+    }
+}
@@ -0,0 +1,1035 @@
+//! Builtin functions and `@format` strings
+//!
+//! Builtins that take filter arguments or produce several outputs are
+//! native; most derived ones are written in jq in [`PRELUDE`], as in jq
+//! itself.
+
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use regex::{Regex, RegexBuilder};
+use serde_json::{Map, Value};
+
+use super::eval::{error, number, Env, Flow, Interp, Out, Path, PathOut, Res};
+use super::parser::Ast;
+use super::value::{
+    compare, contains, delpaths, getpath, indices_of, preview, setpath, split, to_json, to_string,
+    truthy, type_name,
+};
+
+/// Builtins defined in jq, loaded before the user program.
+pub const PRELUDE: &str = r#"
+def range($upto): range(0; $upto; 1);
+def range($from; $upto): range($from; $upto; 1);
+def map(f): [.[] | f];
+def select(f): if f then . else empty end;
+def recurse: recurse(.[]?);
+def recurse(f; cond): def r: ., (f | select(cond) | r); r;
+def values: select(. != null);
+def nulls: select(. == null);
+def booleans: select(type == "boolean");
+def numbers: select(type == "number");
+def strings: select(type == "string");
+def arrays: select(type == "array");
+def objects: select(type == "object");
+def iterables: select(type | . == "array" or . == "object");
+def scalars: select(type | . != "array" and . != "object");
+def finites: select(isinfinite or isnan | not);
+def normals: select(isnormal);
+def add: reduce .[] as $x (null; . + $x);
+def add(f): reduce f as $x (null; . + $x);
+def any: reduce .[] as $x (false; . or $x);
+def all: reduce .[] as $x (true; . and $x);
+def any(f): reduce (.[] | f) as $x (false; . or $x);
+def all(f): reduce (.[] | f) as $x (true; . and $x);
+def isempty(g): first((g | false), true);
+def any(g; cond): isempty(first(g | cond | select(.))) | not;
+def all(g; cond): isempty(first(g | cond | select(. | not)));
+def in(xs): . as $x | xs | has($x);
+def inside(xs): . as $x | xs | contains($x);
+def map_values(f): .[] |= f;
+def del(f): delpaths([path(f)]);
+def paths: path(..) | select(length > 0);
+def paths(node_filter): . as $dot | paths | select(. as $p | $dot | getpath($p) | node_filter);
+def leaf_paths: paths(scalars);
+def pick(pathexps): . as $top | reduce path(pathexps) as $p (null; setpath($p; $top | getpath($p)));
+def to_entries: [keys_unsorted[] as $k | {key: $k, value: .[$k]}];
+def from_entries: reduce .[] as $x ({};
+    . + { ($x | if .key == null then .k // .name // .Name // .K // .Key else .key end
+             | if type == "string" then . else tojson end):
+          ($x | if has("value") then .value else .v end) });
+def with_entries(f): to_entries | map(f) | from_entries;
+def join($x): reduce .[] as $i (null;
+    (if . == null then "" else . + $x end)
+    + ($i | if . == null then "" elif type == "string" then . else tojson end)) // "";
+def first: .[0];
+def last: .[-1];
+def nth($n): .[$n];
+def nth($n; f): if $n < 0 then error("Out of bounds negative array index") else last(limit($n + 1; f)) end;
+def index($i): indices($i) | .[0];
+def rindex($i): indices($i) | .[-1:][0];
+def toarray: if type == "array" then . else [.] end;
+def walk(f): def w: if type == "object" then map_values(w) elif type == "array" then map(w) else . end | f; w;
+def transpose: if . == [] then [] else . as $in | (map(length) | max) as $max
+    | [range(0; $max) as $j | [range(0; $in | length) as $i | $in[$i][$j]]] end;
+def combinations: if length == 0 then [] else .[0][] as $x | (.[1:] | combinations) as $w | [$x] + $w end;
+def combinations(n): . as $dot | [range(n)] | map($dot) | combinations;
+def env: $ENV;
+def debug(msg): .;
+def test(re): test(re; null);
+def match(re): match(re; null);
+def capture(re): capture(re; null);
+def scan(re; $flags): match(re; "g" + $flags)
+    | if (.captures | length > 0) then [.captures | .[] | .string] else .string end;
+def scan(re): scan(re; null);
+def splits($re; flags): split($re; flags) | .[];
+def splits($re): splits($re; null);
+def sub(re; str): sub(re; str; "");
+def gsub(re; str): sub(re; str; "g");
+def gsub(re; str; flags): sub(re; str; flags + "g");
+def IN(s): any(s == .; .);
+def IN(src; s): any(src == s; .);
+def INDEX(stream; idx_expr): reduce stream as $row ({}; .[$row | idx_expr | tostring] |= $row);
+def INDEX(idx_expr): INDEX(.[]; idx_expr);
+"#;
+
+/// Call a native builtin.
+pub fn call<'p>(
+    interp: &Interp<'_>,
+    name: &str,
+    args: &'p [Ast],
+    input: Value,
+    env: &Env<'p>,
+    out: Out<'_>,
+) -> Res {
+    if args.is_empty() {
+        if let Some(result) = value0(name, &input) {
+            return out(result?);
+        }
+    }
+    if let [arg] = args {
+        if let Some(f) = value1(name) {
+            return interp.eval(arg, input.clone(), env, &mut |a| out(f(&input, a)?));
+        }
+    }
+    match (name, args) {
+        ("empty", []) => Ok(()),
+        ("error", []) => Err(Flow::Error(input)),
+        ("error", [message]) => interp.eval(message, input, env, &mut |m| Err(Flow::Error(m))),
+        ("path", [f]) => interp.paths(f, Vec::new(), input, env, &mut |(path, _)| {
+            out(Value::Array(path))
+        }),
+        ("setpath", [path, new]) => interp.eval(new, input.clone(), env, &mut |v| {
+            interp.eval(path, input.clone(), env, &mut |p| {
+                out(setpath(input.clone(), &as_path(p)?, v.clone())?)
+            })
+        }),
+        ("recurse" | "repeat", [f]) => interp.recurse(Some(f), input, env, out),
+        ("limit", [n, f]) => interp.eval(n, input.clone(), env, &mut |n| {
+            limit(interp, limit_count(&n)?, f, &input, env, out)
+        }),
+        ("first", [f]) => interp.first(f, input, env)?.map_or(Ok(()), out),
+        ("last", [f]) => {
+            let mut last = None;
+            interp.eval(f, input, env, &mut |v| {
+                last = Some(v);
+                Ok(())
+            })?;
+            last.map_or(Ok(()), out)
+        }
+        ("until" | "while", [condition, update]) => iterate_while(
+            interp,
+            name == "while",
+            (condition, update),
+            input,
+            env,
+            out,
+        ),
+        ("range", [from, upto, by]) => interp.eval(from, input.clone(), env, &mut |from| {
+            interp.eval(upto, input.clone(), env, &mut |upto| {
+                interp.eval(by, input.clone(), env, &mut |by| {
+                    range(interp, &from, &upto, &by, out)
+                })
+            })
+        }),
+        ("pow", [base, exponent]) => interp.eval(base, input.clone(), env, &mut |b| {
+            interp.eval(
+                exponent,
+                input.clone(),
+                env,
+                &mut |e| match (b.as_f64(), e.as_f64()) {
+                    (Some(b), Some(e)) => out(number(b.powf(e))),
+                    _ => Err(error("pow/2 requires numeric arguments")),
+                },
+            )
+        }),
+        ("sort_by" | "group_by" | "unique_by" | "min_by" | "max_by", [f]) => {
+            out(by_key(interp, name, f, input, env)?)
+        }
+        ("test" | "match" | "capture" | "split", [re, flags]) => {
+            interp.eval(re, input.clone(), env, &mut |re| {
+                interp.eval(flags, input.clone(), env, &mut |flags| {
+                    regex_call(name, &input, &re, &flags, out)
+                })
+            })
+        }
+        ("sub", [re, replacement, flags]) => interp.eval(re, input.clone(), env, &mut |re| {
+            interp.eval(flags, input.clone(), env, &mut |flags| {
+                let Value::String(text) = &input else {
+                    return Err(not_a_string(
+                        &input,
+                        "cannot be matched, as it is not a string",
+                    ));
+                };
+                let matches = sub_matches(text, &re, &flags)?;
+                substitute(
+                    interp,
+                    replacement,
+                    env,
+                    text,
+                    &matches,
+                    0,
+                    String::new(),
+                    out,
+                )
+            })
+        }),
+        ("input" | "inputs", []) => Err(error(format!(
+            "{name} is not supported; each input document runs the program separately"
+        ))),
+        _ => Err(error(format!("{name}/{} is not defined", args.len()))),
+    }
+}
+
+/// Native builtins usable in path expressions; `None` for the rest.
+pub fn call_paths<'p>(
+    interp: &Interp<'_>,
+    name: &str,
+    args: &'p [Ast],
+    (path, value): (&Path, &Value),
+    env: &Env<'p>,
+    out: PathOut<'_>,
+) -> Option<Res> {
+    Some(match (name, args) {
+        ("empty", []) => Ok(()),
+        ("error", _) => call(interp, name, args, value.clone(), env, &mut |_| Ok(())),
+        ("getpath", [p]) => interp.eval(p, value.clone(), env, &mut |p| {
+            let p = as_path(p)?;
+            let found = getpath(value, &p).unwrap_or_default();
+            let mut full = path.clone();
+            full.extend(p);
+            out((full, found))
+        }),
+        ("recurse", [f]) => recurse_paths(interp, f, (path.clone(), value.clone()), env, out),
+        ("limit", [n, f]) => interp.eval(n, value.clone(), env, &mut |n| {
+            let mut remaining = limit_count(&n)?;
+            interp.with_stop(|stop| {
+                if remaining == 0 {
+                    return Ok(());
+                }
+                interp.paths(f, path.clone(), value.clone(), env, &mut |pv| {
+                    out(pv)?;
+                    remaining -= 1;
+                    if remaining == 0 {
+                        Err(Flow::Break(stop))
+                    } else {
+                        Ok(())
+                    }
+                })
+            })
+        }),
+        ("first" | "last", [f]) => edge_path(interp, name == "first", f, (path, value), env, out),
+        _ => return None,
+    })
+}
+
+/// `recurse(f)` in a path expression
+fn recurse_paths<'p>(
+    interp: &Interp<'_>,
+    f: &'p Ast,
+    start: (Path, Value),
+    env: &Env<'p>,
+    out: PathOut<'_>,
+) -> Res {
+    let mut stack = vec![start];
+    while let Some((path, value)) = stack.pop() {
+        interp.check_cancelled()?;
+        let mut children = Vec::new();
+        interp.paths(f, path.clone(), value.clone(), env, &mut |child| {
+            children.push(child);
+            Ok(())
+        })?;
+        out((path, value))?;
+        children.reverse();
+        stack.extend(children);
+    }
+    Ok(())
+}
+
+/// `first(f)` or `last(f)` in a path expression
+fn edge_path<'p>(
+    interp: &Interp<'_>,
+    first: bool,
+    f: &'p Ast,
+    (path, value): (&Path, &Value),
+    env: &Env<'p>,
+    out: PathOut<'_>,
+) -> Res {
+    let mut found = None;
+    interp.with_stop(|stop| {
+        interp.paths(f, path.clone(), value.clone(), env, &mut |pv| {
+            found = Some(pv);
+            if first {
+                Err(Flow::Break(stop))
+            } else {
+                Ok(())
+            }
+        })
+    })?;
+    found.map_or(Ok(()), out)
+}
+
+fn not_a_string(value: &Value, reason: &str) -> Flow {
+    error(format!(
+        "{} ({}) {reason}",
+        type_name(value),
+        preview(value)
+    ))
+}
+
+fn as_path(path: Value) -> Result<Path, Flow> {
+    match path {
+        Value::Array(path) => Ok(path),
+        _ => Err(error("Path must be specified as an array")),
+    }
+}
+
+/// `limit(n; f)`: the first `n` outputs of `f`
+fn limit<'p>(
+    interp: &Interp<'_>,
+    n: usize,
+    f: &'p Ast,
+    input: &Value,
+    env: &Env<'p>,
+    out: Out<'_>,
+) -> Res {
+    if n == 0 {
+        return Ok(());
+    }
+    let mut remaining = n;
+    interp.with_stop(|stop| {
+        interp.eval(f, input.clone(), env, &mut |v| {
+            out(v)?;
+            remaining -= 1;
+            if remaining == 0 {
+                Err(Flow::Break(stop))
+            } else {
+                Ok(())
+            }
+        })
+    })
+}
+
+/// `until(cond; update)` emits the first value satisfying `cond`;
+/// `while(cond; update)` emits every value until `cond` fails.
+fn iterate_while<'p>(
+    interp: &Interp<'_>,
+    emit_all: bool,
+    (condition, update): (&'p Ast, &'p Ast),
+    input: Value,
+    env: &Env<'p>,
+    out: Out<'_>,
+) -> Res {
+    let mut value = input;
+    loop {
+        interp.check_cancelled()?;
+        let holds = interp
+            .first(condition, value.clone(), env)?
+            .is_some_and(|c| truthy(&c));
+        if emit_all {
+            if !holds {
+                return Ok(());
+            }
+            out(value.clone())?;
+        } else if holds {
+            return out(value);
+        }
+        match interp.first(update, value, env)? {
+            Some(next) => value = next,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Matches for `sub`: each match range with its named captures.
+fn sub_matches(text: &str, re: &Value, flags: &Value) -> Result<Vec<(Range<usize>, Value)>, Flow> {
+    let (regex, global, skip_empty) = compile(re, flags)?;
+    let names: Vec<_> = regex.capture_names().collect();
+    let mut matches = Vec::new();
+    for captures in regex.captures_iter(text) {
+        let Some(whole) = captures.get(0) else {
+            continue;
+        };
+        if skip_empty && whole.is_empty() {
+            continue;
+        }
+        let mut groups = Map::new();
+        for (i, name) in names.iter().enumerate() {
+            if let Some(name) = name {
+                let value = captures
+                    .get(i)
+                    .map_or(Value::Null, |m| Value::String(m.as_str().to_string()));
+                groups.insert((*name).to_string(), value);
+            }
+        }
+        matches.push((whole.range(), Value::Object(groups)));
+        if !global {
+            break;
+        }
+    }
+    Ok(matches)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn limit_count(n: &Value) -> Result<usize, Flow> {
+    n.as_f64()
+        .map(|n| n.max(0.0).ceil() as usize)
+        .ok_or_else(|| error("Invalid limit: not a number"))
+}
+
+fn range(interp: &Interp<'_>, from: &Value, upto: &Value, by: &Value, out: Out<'_>) -> Res {
+    let (Some(mut x), Some(upto), Some(by)) = (from.as_f64(), upto.as_f64(), by.as_f64()) else {
+        return Err(error("Range bounds must be numeric"));
+    };
+    while (by > 0.0 && x < upto) || (by < 0.0 && x > upto) {
+        interp.check_cancelled()?;
+        out(number(x))?;
+        x += by;
+    }
+    Ok(())
+}
+
+fn math(input: &Value, f: fn(f64) -> f64) -> Result<Value, Flow> {
+    input
+        .as_f64()
+        .map(|n| number(f(n)))
+        .ok_or_else(|| not_a_string(input, "number required"))
+}
+
+fn array_input<'v>(input: &'v Value, action: &str) -> Result<&'v Vec<Value>, Flow> {
+    match input {
+        Value::Array(items) => Ok(items),
+        other => Err(not_a_string(
+            other,
+            &format!("cannot be {action}, as it is not an array"),
+        )),
+    }
+}
+
+fn flatten_into(items: &[Value], depth: f64, acc: &mut Vec<Value>) {
+    for item in items {
+        match item {
+            Value::Array(inner) if depth > 0.0 => flatten_into(inner, depth - 1.0, acc),
+            other => acc.push(other.clone()),
+        }
+    }
+}
+
+fn flatten(input: &Value, depth: f64) -> Result<Value, Flow> {
+    if depth < 0.0 {
+        return Err(error("flatten depth must not be negative"));
+    }
+    let items = array_input(input, "flattened")?;
+    let mut flat = Vec::new();
+    flatten_into(items, depth, &mut flat);
+    Ok(Value::Array(flat))
+}
+
+/// Builtins with no arguments and exactly one output
+#[allow(clippy::too_many_lines)]
+fn value0(name: &str, input: &Value) -> Option<Result<Value, Flow>> {
+    let string = || match input {
+        Value::String(s) => Ok(s.as_str()),
+        other => Err(not_a_string(other, &format!("cannot be used with {name}"))),
+    };
+    Some(match name {
+        "not" => Ok(Value::Bool(!truthy(input))),
+        "length" => match input {
+            Value::Null => Ok(Value::from(0)),
+            Value::Bool(_) => Err(not_a_string(input, "has no length")),
+            Value::Number(n) => Ok(number(n.as_f64().unwrap_or(0.0).abs())),
+            Value::String(s) => Ok(Value::from(s.chars().count())),
+            Value::Array(items) => Ok(Value::from(items.len())),
+            Value::Object(map) => Ok(Value::from(map.len())),
+        },
+        "utf8bytelength" => string().map(|s| Value::from(s.len())),
+        "keys" | "keys_unsorted" => match input {
+            Value::Object(map) => Ok(Value::Array(
+                map.keys().map(|k| Value::String(k.clone())).collect(),
+            )),
+            Value::Array(items) => Ok(Value::Array((0..items.len()).map(Value::from).collect())),
+            other => Err(not_a_string(other, "has no keys")),
+        },
+        "type" => Ok(Value::String(type_name(input).to_string())),
+        "tostring" => Ok(Value::String(to_string(input))),
+        "tojson" => Ok(Value::String(to_json(input))),
+        "fromjson" => string().and_then(|s| {
+            serde_json::from_str(s).map_err(|e| error(format!("{e} (while parsing '{s}')")))
+        }),
+        "tonumber" => match input {
+            Value::Number(_) => Ok(input.clone()),
+            Value::String(s) => s
+                .parse::<f64>()
+                .map(number)
+                .map_err(|_| error(format!("Cannot parse '{s}' as a number"))),
+            other => Err(not_a_string(other, "cannot be parsed as a number")),
+        },
+        "ascii_downcase" => string().map(|s| Value::String(s.to_ascii_lowercase())),
+        "ascii_upcase" => string().map(|s| Value::String(s.to_ascii_uppercase())),
+        "trim" => string().map(|s| Value::String(s.trim().to_string())),
+        "ltrim" => string().map(|s| Value::String(s.trim_start().to_string())),
+        "rtrim" => string().map(|s| Value::String(s.trim_end().to_string())),
+        "explode" => {
+            string().map(|s| Value::Array(s.chars().map(|c| Value::from(u32::from(c))).collect()))
+        }
+        "implode" => implode(input),
+        "sort" => array_input(input, "sorted").map(|items| {
+            let mut items = items.clone();
+            items.sort_by(compare);
+            Value::Array(items)
+        }),
+        "unique" => array_input(input, "sorted").map(|items| {
+            let mut items = items.clone();
+            items.sort_by(compare);
+            items.dedup_by(|a, b| compare(a, b).is_eq());
+            Value::Array(items)
+        }),
+        "min" => array_input(input, "compared").map(|items| {
+            items
+                .iter()
+                .min_by(|a, b| compare(a, b))
+                .cloned()
+                .unwrap_or_default()
+        }),
+        "max" => array_input(input, "compared").map(|items| {
+            items
+                .iter()
+                .max_by(|a, b| compare(a, b))
+                .cloned()
+                .unwrap_or_default()
+        }),
+        "reverse" => match input {
+            Value::Null => Ok(Value::Array(Vec::new())),
+            Value::String(s) => Ok(Value::String(s.chars().rev().collect())),
+            Value::Array(items) => Ok(Value::Array(items.iter().rev().cloned().collect())),
+            other => Err(not_a_string(
+                other,
+                "cannot be reversed, as it is not an array",
+            )),
+        },
+        "flatten" => flatten(input, f64::INFINITY),
+        "abs" => input
+            .as_f64()
+            .map(|n| number(n.abs()))
+            .ok_or_else(|| not_a_string(input, "has no absolute value")),
+        "floor" => math(input, f64::floor),
+        "ceil" => math(input, f64::ceil),
+        "round" => math(input, f64::round),
+        "trunc" => math(input, f64::trunc),
+        "fabs" => math(input, f64::abs),
+        "sqrt" => math(input, f64::sqrt),
+        "exp" => math(input, f64::exp),
+        "exp2" => math(input, f64::exp2),
+        "exp10" => math(input, |n| 10f64.powf(n)),
+        "log" => math(input, f64::ln),
+        "log2" => math(input, f64::log2),
+        "log10" => math(input, f64::log10),
+        "sin" => math(input, f64::sin),
+        "cos" => math(input, f64::cos),
+        "tan" => math(input, f64::tan),
+        "asin" => math(input, f64::asin),
+        "acos" => math(input, f64::acos),
+        "atan" => math(input, f64::atan),
+        "infinite" => Ok(number(f64::INFINITY)),
+        "nan" | "input_filename" => Ok(Value::Null),
+        // JSON has no infinities; `infinite` is stored as the largest double.
+        "isinfinite" => {
+            math(input, |n| n).map(|n| Value::Bool(n.as_f64().is_some_and(|n| n.abs() >= f64::MAX)))
+        }
+        "isnan" => math(input, |n| n).map(|_| Value::Bool(false)),
+        "isnormal" => math(input, |n| n).map(|n| {
+            Value::Bool(
+                n.as_f64()
+                    .is_some_and(|n| n.is_normal() && n.abs() < f64::MAX),
+            )
+        }),
+        "now" => Ok(number(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |d| d.as_secs_f64()),
+        )),
+        "debug" | "stderr" => Ok(input.clone()),
+        _ => return None,
+    })
+}
+
+fn implode(input: &Value) -> Result<Value, Flow> {
+    let invalid = || error("implode input must be an array of codepoints");
+    let Value::Array(items) = input else {
+        return Err(invalid());
+    };
+    items
+        .iter()
+        .map(|item| {
+            item.as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .and_then(char::from_u32)
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<String, _>>()
+        .map(Value::String)
+}
+
+#[allow(clippy::cast_precision_loss)]
+const fn len_f64(len: usize) -> f64 {
+    len as f64
+}
+
+type Value1 = fn(&Value, Value) -> Result<Value, Flow>;
+
+/// Builtins with one value argument and one output per argument value
+fn value1(name: &str) -> Option<Value1> {
+    Some(match name {
+        "has" => |input, key| match (input, &key) {
+            (Value::Object(map), Value::String(k)) => Ok(Value::Bool(map.contains_key(k))),
+            (Value::Array(items), Value::Number(n)) => Ok(Value::Bool(
+                n.as_f64()
+                    .is_some_and(|n| n >= 0.0 && n < len_f64(items.len())),
+            )),
+            _ => Err(error(format!(
+                "Cannot check whether {} has a {} key",
+                type_name(input),
+                type_name(&key)
+            ))),
+        },
+        "contains" => |input, b| contains(input, &b).map(Value::Bool),
+        "getpath" => |input, path| getpath(input, &as_path(path)?),
+        "delpaths" => |input, paths| match paths {
+            Value::Array(paths) => delpaths(input.clone(), paths),
+            _ => Err(error("Paths must be specified as an array")),
+        },
+        "ltrimstr" => |input, prefix| match (input, &prefix) {
+            (Value::String(s), Value::String(p)) => Ok(Value::String(
+                s.strip_prefix(p.as_str()).unwrap_or(s).to_string(),
+            )),
+            _ => Ok(input.clone()),
+        },
+        "rtrimstr" => |input, suffix| match (input, &suffix) {
+            (Value::String(s), Value::String(p)) => Ok(Value::String(
+                s.strip_suffix(p.as_str()).unwrap_or(s).to_string(),
+            )),
+            _ => Ok(input.clone()),
+        },
+        "startswith" => |input, prefix| match (input, &prefix) {
+            (Value::String(s), Value::String(p)) => Ok(Value::Bool(s.starts_with(p.as_str()))),
+            _ => Err(error("startswith() requires string inputs")),
+        },
+        "endswith" => |input, suffix| match (input, &suffix) {
+            (Value::String(s), Value::String(p)) => Ok(Value::Bool(s.ends_with(p.as_str()))),
+            _ => Err(error("endswith() requires string inputs")),
+        },
+        "split" => |input, separator| match (input, &separator) {
+            (Value::String(s), Value::String(sep)) => Ok(split(s, sep)),
+            _ => Err(error("split input and separator must be strings")),
+        },
+        "indices" => indices,
+        "flatten" => |input, depth| {
+            let depth = depth
+                .as_f64()
+                .ok_or_else(|| error("flatten depth must be a number"))?;
+            flatten(input, depth)
+        },
+        "format" => |input, name| match &name {
+            Value::String(name) => format(name, input).map(Value::String),
+            other => Err(not_a_string(other, "is not a valid format")),
+        },
+        _ => return None,
+    })
+}
+
+/// `indices(x)`: code point offsets in strings, element positions in arrays
+fn indices(input: &Value, needle: Value) -> Result<Value, Flow> {
+    match (input, &needle) {
+        (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+        (Value::String(s), Value::String(t)) => {
+            if t.is_empty() {
+                return Ok(Value::Null);
+            }
+            Ok(Value::Array(
+                s.char_indices()
+                    .enumerate()
+                    .filter(|(_, (byte, _))| s[*byte..].starts_with(t.as_str()))
+                    .map(|(i, _)| Value::from(i))
+                    .collect(),
+            ))
+        }
+        (Value::Array(items), Value::Array(needle)) => Ok(indices_of(items, needle)),
+        (Value::Array(items), needle) => Ok(indices_of(items, std::slice::from_ref(needle))),
+        _ => Err(error(format!(
+            "Cannot determine indices of {} in {}",
+            type_name(&needle),
+            type_name(input)
+        ))),
+    }
+}
+
+/// `sort_by`, `group_by`, `unique_by`, `min_by`, and `max_by`: elements
+/// keyed by the array of all outputs of `f`.
+fn by_key<'p>(
+    interp: &Interp<'_>,
+    name: &str,
+    f: &'p Ast,
+    input: Value,
+    env: &Env<'p>,
+) -> Result<Value, Flow> {
+    let items = array_input(&input, "sorted")?;
+    let mut keyed = items
+        .iter()
+        .map(|item| {
+            Ok((
+                Value::Array(interp.collect(f, item.clone(), env)?),
+                item.clone(),
+            ))
+        })
+        .collect::<Result<Vec<_>, Flow>>()?;
+    match name {
+        "min_by" => {
+            return Ok(keyed
+                .into_iter()
+                .min_by(|a, b| compare(&a.0, &b.0))
+                .map(|(_, item)| item)
+                .unwrap_or_default());
+        }
+        "max_by" => {
+            return Ok(keyed
+                .into_iter()
+                .max_by(|a, b| compare(&a.0, &b.0))
+                .map(|(_, item)| item)
+                .unwrap_or_default());
+        }
+        _ => {}
+    }
+    keyed.sort_by(|a, b| compare(&a.0, &b.0));
+    if name == "sort_by" {
+        return Ok(Value::Array(
+            keyed.into_iter().map(|(_, item)| item).collect(),
+        ));
+    }
+    let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+    for (key, item) in keyed {
+        match groups.last_mut() {
+            Some((last, group)) if compare(last, &key).is_eq() => group.push(item),
+            _ => groups.push((key, vec![item])),
+        }
+    }
+    Ok(Value::Array(if name == "unique_by" {
+        groups
+            .into_iter()
+            .filter_map(|(_, group)| group.into_iter().next())
+            .collect()
+    } else {
+        groups
+            .into_iter()
+            .map(|(_, group)| Value::Array(group))
+            .collect()
+    }))
+}
+
+/// Compile a jq regex with its flag string. Returns the regex, whether
+/// matching is global, and whether empty matches are skipped.
+fn compile(re: &Value, flags: &Value) -> Result<(Regex, bool, bool), Flow> {
+    let Value::String(pattern) = re else {
+        return Err(not_a_string(re, "cannot be matched, as it is not a string"));
+    };
+    let flags = match flags {
+        Value::Null => "",
+        Value::String(flags) => flags,
+        other => return Err(not_a_string(other, "is not a string")),
+    };
+    let mut builder = RegexBuilder::new(pattern);
+    let (mut global, mut skip_empty) = (false, false);
+    for flag in flags.chars() {
+        match flag {
+            'g' => global = true,
+            'n' => skip_empty = true,
+            'i' => {
+                builder.case_insensitive(true);
+            }
+            'x' => {
+                builder.ignore_whitespace(true);
+            }
+            's' => {
+                builder.dot_matches_new_line(true);
+            }
+            'p' => {
+                builder.dot_matches_new_line(true).multi_line(true);
+            }
+            'l' => {}
+            _ => return Err(error(format!("{flags} is not a valid modifier string"))),
+        }
+    }
+    let regex = builder
+        .build()
+        .map_err(|e| error(format!("{pattern} is not a valid regex: {e}")))?;
+    Ok((regex, global, skip_empty))
+}
+
+fn code_points(text: &str, byte: usize) -> usize {
+    text[..byte].chars().count()
+}
+
+/// jq match objects for `regex` in `text`
+fn match_objects(regex: &Regex, text: &str, global: bool, skip_empty: bool) -> Vec<Value> {
+    let names: Vec<_> = regex.capture_names().collect();
+    let span = |range: Range<usize>| {
+        (
+            Value::from(code_points(text, range.start)),
+            Value::from(text[range].chars().count()),
+        )
+    };
+    let mut results = Vec::new();
+    for captures in regex.captures_iter(text) {
+        let Some(whole) = captures.get(0) else {
+            continue;
+        };
+        if skip_empty && whole.is_empty() {
+            continue;
+        }
+        let groups = names
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, name)| {
+                let mut group = Map::new();
+                let (offset, length, string) = captures.get(i).map_or_else(
+                    || (Value::from(-1), Value::from(0), Value::Null),
+                    |m| {
+                        let (offset, length) = span(m.range());
+                        (offset, length, Value::String(m.as_str().to_string()))
+                    },
+                );
+                group.insert("offset".into(), offset);
+                group.insert("length".into(), length);
+                group.insert("string".into(), string);
+                group.insert(
+                    "name".into(),
+                    name.map_or(Value::Null, |n| Value::String(n.to_string())),
+                );
+                Value::Object(group)
+            })
+            .collect();
+        let (offset, length) = span(whole.range());
+        let mut object = Map::new();
+        object.insert("offset".into(), offset);
+        object.insert("length".into(), length);
+        object.insert("string".into(), Value::String(whole.as_str().to_string()));
+        object.insert("captures".into(), Value::Array(groups));
+        results.push(Value::Object(object));
+        if !global {
+            break;
+        }
+    }
+    results
+}
+
+/// `test`, `match`, `capture`, and `split` with flags
+fn regex_call(name: &str, input: &Value, re: &Value, flags: &Value, out: Out<'_>) -> Res {
+    let Value::String(text) = input else {
+        return Err(not_a_string(
+            input,
+            "cannot be matched, as it is not a string",
+        ));
+    };
+    let (regex, global, skip_empty) = compile(re, flags)?;
+    match name {
+        "test" => out(Value::Bool(regex.is_match(text))),
+        "split" => out(Value::Array(
+            regex
+                .split(text)
+                .map(|part| Value::String(part.to_string()))
+                .collect(),
+        )),
+        "capture" => match_objects(&regex, text, global, skip_empty)
+            .into_iter()
+            .try_for_each(|m| {
+                let mut named = Map::new();
+                for capture in m["captures"].as_array().into_iter().flatten() {
+                    if let Value::String(name) = &capture["name"] {
+                        named.insert(name.clone(), capture["string"].clone());
+                    }
+                }
+                out(Value::Object(named))
+            }),
+        _ => match_objects(&regex, text, global, skip_empty)
+            .into_iter()
+            .try_for_each(out),
+    }
+}
+
+/// Rebuild `text` with each match replaced by the outputs of
+/// `replacement`, run on the match's named captures.
+#[allow(clippy::too_many_arguments)]
+fn substitute<'p>(
+    interp: &Interp<'_>,
+    replacement: &'p Ast,
+    env: &Env<'p>,
+    text: &str,
+    matches: &[(Range<usize>, Value)],
+    position: usize,
+    acc: String,
+    out: Out<'_>,
+) -> Res {
+    let Some(((range, captures), rest)) = matches.split_first() else {
+        return out(Value::String(acc + &text[position..]));
+    };
+    interp.eval(replacement, captures.clone(), env, &mut |r| {
+        let Value::String(r) = r else {
+            return Err(not_a_string(&r, "cannot be added to a string"));
+        };
+        let acc = format!("{acc}{}{r}", &text[position..range.start]);
+        substitute(interp, replacement, env, text, rest, range.end, acc, out)
+    })
+}
+
+/// Apply `@name` to a value.
+pub fn format(name: &str, value: &Value) -> Result<String, Flow> {
+    Ok(match name {
+        "text" => to_string(value),
+        "json" => to_json(value),
+        "html" => to_string(value).chars().fold(String::new(), |mut acc, c| {
+            match c {
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '&' => acc.push_str("&amp;"),
+                '\'' => acc.push_str("&#39;"),
+                '"' => acc.push_str("&quot;"),
+                c => acc.push(c),
+            }
+            acc
+        }),
+        "uri" => to_string(value).bytes().fold(String::new(), |mut acc, b| {
+            if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+                acc.push(char::from(b));
+            } else {
+                let _ = write!(acc, "%{b:02X}");
+            }
+            acc
+        }),
+        "csv" | "tsv" => row(name, value)?,
+        "sh" => match value {
+            Value::Array(items) => items
+                .iter()
+                .map(shell_quote)
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" "),
+            other => shell_quote(other)?,
+        },
+        "base64" => STANDARD.encode(to_string(value)),
+        "base64d" => {
+            let text = to_string(value);
+            let bytes = STANDARD_NO_PAD
+                .decode(text.trim_end_matches('='))
+                .map_err(|_| not_a_string(value, "is not valid base64 data"))?;
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        "base32" => base32_encode(to_string(value).as_bytes()),
+        "base32d" => {
+            let bytes = base32_decode(&to_string(value))
+                .ok_or_else(|| not_a_string(value, "is not valid base32 data"))?;
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        _ => return Err(error(format!("{name} is not a valid format"))),
+    })
+}
+
+/// `@csv` and `@tsv` rows
+fn row(name: &str, value: &Value) -> Result<String, Flow> {
+    let Value::Array(items) = value else {
+        return Err(not_a_string(
+            value,
+            &format!("cannot be {name}-formatted, only an array can be"),
+        ));
+    };
+    let cells = items
+        .iter()
+        .map(|item| match item {
+            Value::Null => Ok(String::new()),
+            Value::Bool(_) | Value::Number(_) => Ok(to_json(item)),
+            Value::String(s) if name == "csv" => Ok(format!("\"{}\"", s.replace('"', "\"\""))),
+            Value::String(s) => Ok(s
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")),
+            other => Err(not_a_string(
+                other,
+                &format!("is not valid in a {name} row"),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(cells.join(if name == "csv" { "," } else { "\t" }))
+}
+
+fn shell_quote(value: &Value) -> Result<String, Flow> {
+    match value {
+        Value::String(s) => Ok(format!("'{}'", s.replace('\'', "'\\''"))),
+        Value::Array(_) | Value::Object(_) => {
+            Err(not_a_string(value, "can not be escaped for shell"))
+        }
+        other => Ok(to_json(other)),
+    }
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(5) {
+        let mut block = [0u8; 5];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = block.iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+        let symbols = (chunk.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < symbols {
+                let index = (bits >> (35 - i * 5)) & 0x1F;
+                encoded.push(char::from(
+                    BASE32_ALPHABET[usize::try_from(index).unwrap_or(0)],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bits = 0u64;
+    let mut count = 0u32;
+    let mut bytes = Vec::new();
+    for c in text.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())?;
+        bits = (bits << 5) | u64::try_from(value).ok()?;
+        count += 5;
+        if count >= 8 {
+            count -= 8;
+            bytes.push(u8::try_from((bits >> count) & 0xFF).ok()?);
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,875 @@
+//! Evaluator for parsed jq programs
+//!
+//! Filters run in continuation-passing style: every output is handed to a
+//! sink as soon as it is produced, so generators like `range(1e9)` stream
+//! instead of materializing. Errors, `break`, and cancellation unwind
+//! through the sinks as [`Flow`] values.
+//!
+//! Path expressions (the left side of `|=`, `path(f)`, `del(f)`) run in a
+//! second mode that tracks the path of every output alongside its value.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use serde_json::{Map, Value};
+use tokio_util::sync::CancellationToken;
+
+use super::builtins;
+use super::parser::{AssignOp, Ast, FuncDef, ObjectEntry, Pattern, StrSegment};
+use super::value::{
+    binop, delpaths, entries, getpath, index, iterate, negate, preview, setpath, slice, to_string,
+    truthy, type_name,
+};
+
+/// Nesting limit for evaluation; deep recursion in user functions ends with
+/// an error instead of overflowing the stack.
+const MAX_DEPTH: usize = 2_000;
+
+/// Break id reserved for errors raised downstream of a `try` body.
+const DOWNSTREAM: usize = usize::MAX;
+
+/// Non-local exit from a filter
+#[derive(Debug)]
+pub enum Flow {
+    /// Runtime error or `error(v)`; caught by `try` and `?`
+    Error(Value),
+    /// `break $label`, or an early stop from `limit` and `first`
+    Break(usize),
+    /// The operation was cancelled
+    Cancelled,
+}
+
+pub type Res = Result<(), Flow>;
+pub type Path = Vec<Value>;
+pub type Out<'o> = &'o mut dyn FnMut(Value) -> Res;
+pub type PathOut<'o> = &'o mut dyn FnMut((Path, Value)) -> Res;
+
+/// Runtime error with a string message
+pub fn error(message: impl Into<String>) -> Flow {
+    Flow::Error(Value::String(message.into()))
+}
+
+/// JSON number for `n`, as an integer when it is one. Infinities clamp to
+/// the largest finite double and NaN becomes null, as in jq's output.
+#[allow(clippy::cast_possible_truncation)]
+pub fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        return Value::from(n as i64);
+    }
+    let n = if n.is_infinite() {
+        f64::MAX.copysign(n)
+    } else {
+        n
+    };
+    serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+}
+
+/// Lexical scope: a persistent list of bindings.
+#[derive(Clone, Default)]
+pub struct Env<'p>(Option<Rc<Frame<'p>>>);
+
+struct Frame<'p> {
+    binding: Binding<'p>,
+    parent: Env<'p>,
+}
+
+enum Binding<'p> {
+    Var(&'p str, Value),
+    Func(&'p FuncDef),
+    /// Filter argument, evaluated in the caller's scope
+    Closure(&'p str, &'p Ast, Env<'p>),
+    Label(&'p str, usize),
+}
+
+/// Resolved function call target
+pub enum Callable<'p> {
+    /// User or prelude definition and the scope it was defined in
+    Func(&'p FuncDef, Env<'p>),
+    /// Filter argument and its caller's scope
+    Closure(&'p Ast, Env<'p>),
+}
+
+impl<'p> Env<'p> {
+    fn push(&self, binding: Binding<'p>) -> Self {
+        Self(Some(Rc::new(Frame {
+            binding,
+            parent: self.clone(),
+        })))
+    }
+
+    /// Scope with `defs` added in order, so each sees itself and earlier
+    /// definitions.
+    pub fn with_defs(&self, defs: &'p [Rc<FuncDef>]) -> Self {
+        defs.iter()
+            .fold(self.clone(), |env, def| env.push(Binding::Func(def)))
+    }
+
+    fn frames(&self) -> impl Iterator<Item = (&Frame<'p>, &Self)> {
+        std::iter::successors(self.0.as_ref().map(|f| (&**f, self)), |(frame, _)| {
+            frame.parent.0.as_ref().map(|f| (&**f, &frame.parent))
+        })
+    }
+
+    fn var(&self, name: &str) -> Option<&Value> {
+        self.frames().find_map(|(frame, _)| match &frame.binding {
+            Binding::Var(var, value) if *var == name => Some(value),
+            _ => None,
+        })
+    }
+
+    fn label(&self, name: &str) -> Option<usize> {
+        self.frames().find_map(|(frame, _)| match &frame.binding {
+            Binding::Label(label, id) if *label == name => Some(*id),
+            _ => None,
+        })
+    }
+
+    fn function(&self, name: &str, arity: usize) -> Option<Callable<'p>> {
+        self.frames().find_map(|(frame, env)| match &frame.binding {
+            Binding::Func(def) if def.name == name && def.params.len() == arity => {
+                Some(Callable::Func(def, env.clone()))
+            }
+            Binding::Closure(param, body, closure_env) if arity == 0 && *param == name => {
+                Some(Callable::Closure(body, closure_env.clone()))
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Interpreter state shared by one run
+pub struct Interp<'c> {
+    cancel: Option<&'c CancellationToken>,
+    depth: Cell<usize>,
+    next_label: Cell<usize>,
+}
+
+impl<'c> Interp<'c> {
+    pub const fn new(cancel: Option<&'c CancellationToken>) -> Self {
+        Self {
+            cancel,
+            depth: Cell::new(0),
+            next_label: Cell::new(0),
+        }
+    }
+
+    /// Fail with [`Flow::Cancelled`] once the operation is cancelled.
+    pub fn check_cancelled(&self) -> Res {
+        if self.cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(Flow::Cancelled);
+        }
+        Ok(())
+    }
+
+    fn enter(&self) -> Result<usize, Flow> {
+        self.check_cancelled()?;
+        let depth = self.depth.get();
+        if depth >= MAX_DEPTH {
+            return Err(error("Maximum recursion depth exceeded"));
+        }
+        self.depth.set(depth + 1);
+        Ok(depth)
+    }
+
+    fn new_label(&self) -> usize {
+        let id = self.next_label.get();
+        self.next_label.set(id + 1);
+        id
+    }
+
+    /// Run `body` with a fresh break id; breaking with it ends `body`
+    /// without error.
+    pub fn with_stop(&self, body: impl FnOnce(usize) -> Res) -> Res {
+        let id = self.new_label();
+        match body(id) {
+            Err(Flow::Break(b)) if b == id => Ok(()),
+            result => result,
+        }
+    }
+
+    /// All outputs of `ast`
+    pub fn collect<'p>(
+        &self,
+        ast: &'p Ast,
+        input: Value,
+        env: &Env<'p>,
+    ) -> Result<Vec<Value>, Flow> {
+        let mut values = Vec::new();
+        self.eval(ast, input, env, &mut |v| {
+            values.push(v);
+            Ok(())
+        })?;
+        Ok(values)
+    }
+
+    /// First output of `ast`, without evaluating the rest
+    pub fn first<'p>(
+        &self,
+        ast: &'p Ast,
+        input: Value,
+        env: &Env<'p>,
+    ) -> Result<Option<Value>, Flow> {
+        let mut found = None;
+        self.with_stop(|stop| {
+            self.eval(ast, input, env, &mut |v| {
+                found = Some(v);
+                Err(Flow::Break(stop))
+            })
+        })?;
+        Ok(found)
+    }
+
+    /// Outputs `input`, then recursively everything `f` produces from it,
+    /// depth first. Uses an explicit stack so deep documents do not
+    /// exhaust the call stack.
+    pub fn recurse<'p>(
+        &self,
+        f: Option<&'p Ast>,
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        let mut stack = vec![input];
+        while let Some(value) = stack.pop() {
+            self.check_cancelled()?;
+            let mut children = match f {
+                Some(f) => self.collect(f, value.clone(), env)?,
+                None => match &value {
+                    Value::Array(items) => items.clone(),
+                    Value::Object(map) => map.values().cloned().collect(),
+                    _ => Vec::new(),
+                },
+            };
+            out(value)?;
+            children.reverse();
+            stack.extend(children);
+        }
+        Ok(())
+    }
+
+    /// Evaluate `ast` on `input`, sending each output to `out`.
+    pub fn eval<'p>(&self, ast: &'p Ast, input: Value, env: &Env<'p>, out: Out<'_>) -> Res {
+        let depth = self.enter()?;
+        let result = self.eval_inner(ast, input, env, out);
+        self.depth.set(depth);
+        result
+    }
+
+    fn eval_inner<'p>(&self, ast: &'p Ast, input: Value, env: &Env<'p>, out: Out<'_>) -> Res {
+        match ast {
+            Ast::Identity => out(input),
+            Ast::RecurseAll => self.recurse(None, input, env, out),
+            Ast::Literal(value) => out(value.clone()),
+            Ast::Str(format, segments) => {
+                self.eval_string(format.as_deref(), segments, &input, env, String::new(), out)
+            }
+            Ast::Format(name) => out(Value::String(builtins::format(name, &input)?)),
+            Ast::Index(target, key) => {
+                let keys = self.collect(key, input.clone(), env)?;
+                self.eval(target, input, env, &mut |t| {
+                    keys.iter().try_for_each(|k| out(index(&t, k)?))
+                })
+            }
+            Ast::Slice(target, from, to) => {
+                self.eval_slice(target, (from.as_deref(), to.as_deref()), input, env, out)
+            }
+            Ast::Iterate(target) => self.eval(target, input, env, &mut |t| {
+                iterate(t)?.into_iter().try_for_each(&mut *out)
+            }),
+            Ast::Try(body, handler) => {
+                let caught = catch(out, |out| self.eval(body, input, env, out))?;
+                match (caught, handler) {
+                    (Some(error), Some(handler)) => self.eval(handler, error, env, out),
+                    _ => Ok(()),
+                }
+            }
+            Ast::Array(None) => out(Value::Array(Vec::new())),
+            Ast::Array(Some(inner)) => out(Value::Array(self.collect(inner, input, env)?)),
+            Ast::Object(entries) => self.eval_object(entries, &input, env, Map::new(), out),
+            Ast::Neg(inner) => self.eval(inner, input, env, &mut |v| out(negate(&v)?)),
+            Ast::Pipe(left, right) => {
+                self.eval(left, input, env, &mut |v| self.eval(right, v, env, out))
+            }
+            Ast::Comma(left, right) => {
+                self.eval(left, input.clone(), env, out)?;
+                self.eval(right, input, env, out)
+            }
+            Ast::Binary(op, left, right) => self.eval(right, input.clone(), env, &mut |r| {
+                self.eval(left, input.clone(), env, &mut |l| {
+                    out(binop(*op, l, r.clone())?)
+                })
+            }),
+            Ast::And(left, right) => self.eval(left, input.clone(), env, &mut |l| {
+                if truthy(&l) {
+                    self.eval(right, input.clone(), env, &mut |r| {
+                        out(Value::Bool(truthy(&r)))
+                    })
+                } else {
+                    out(Value::Bool(false))
+                }
+            }),
+            Ast::Or(left, right) => self.eval(left, input.clone(), env, &mut |l| {
+                if truthy(&l) {
+                    out(Value::Bool(true))
+                } else {
+                    self.eval(right, input.clone(), env, &mut |r| {
+                        out(Value::Bool(truthy(&r)))
+                    })
+                }
+            }),
+            Ast::Alt(left, right) => self.eval_alt(left, right, input, env, out),
+            Ast::Assign(op, lhs, rhs) => self.assign(*op, lhs, rhs, input, env, out),
+            Ast::If(branches, otherwise) => {
+                self.eval_if(branches, otherwise.as_deref(), input, env, out)
+            }
+            Ast::Reduce(source, pattern, init, update) => {
+                self.eval_reduce(source, pattern, init, update, input, env, out)
+            }
+            Ast::Foreach(source, pattern, init, update, extract) => self.eval_foreach(
+                source,
+                pattern,
+                (init, update, extract.as_deref()),
+                input,
+                env,
+                out,
+            ),
+            Ast::Bind(source, pattern, body) => self.eval(source, input.clone(), env, &mut |v| {
+                let scope = self.bind(pattern, v, &input, env)?;
+                self.eval(body, input.clone(), &scope, out)
+            }),
+            Ast::Var(name) => match env.var(name) {
+                Some(value) => out(value.clone()),
+                None if name == "ENV" => out(Value::Object(Map::new())),
+                None => Err(error(format!("${name} is not defined"))),
+            },
+            Ast::Call(name, args) => self.call(name, args, input, env, out),
+            Ast::Def(defs, body) => self.eval(body, input, &env.with_defs(defs), out),
+            Ast::Label(name, body) => self
+                .with_stop(|id| self.eval(body, input, &env.push(Binding::Label(name, id)), out)),
+            Ast::Break(name) => Err(env.label(name).map_or_else(
+                || error(format!("$*label-{name} is not defined")),
+                Flow::Break,
+            )),
+        }
+    }
+
+    fn eval_slice<'p>(
+        &self,
+        target: &'p Ast,
+        (from, to): (Option<&'p Ast>, Option<&'p Ast>),
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        let froms = self.collect_bound(from, &input, env)?;
+        let tos = self.collect_bound(to, &input, env)?;
+        self.eval(target, input, env, &mut |t| {
+            for from in &froms {
+                for to in &tos {
+                    out(slice(&t, from, to)?)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// `a // b`: the truthy outputs of `a`, or all of `b` when there are
+    /// none. Errors raised by `a` count as no output.
+    fn eval_alt<'p>(
+        &self,
+        left: &'p Ast,
+        right: &'p Ast,
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        let mut found = false;
+        catch(out, |out| {
+            self.eval(left, input.clone(), env, &mut |v| {
+                if truthy(&v) {
+                    found = true;
+                    out(v)
+                } else {
+                    Ok(())
+                }
+            })
+        })?;
+        if found {
+            Ok(())
+        } else {
+            self.eval(right, input, env, out)
+        }
+    }
+
+    /// Slice bound outputs; a missing bound is a single `null`.
+    fn collect_bound<'p>(
+        &self,
+        bound: Option<&'p Ast>,
+        input: &Value,
+        env: &Env<'p>,
+    ) -> Result<Vec<Value>, Flow> {
+        bound.map_or_else(
+            || Ok(vec![Value::Null]),
+            |bound| self.collect(bound, input.clone(), env),
+        )
+    }
+
+    /// Interpolated string; the last interpolation varies slowest, as in jq.
+    fn eval_string<'p>(
+        &self,
+        format: Option<&str>,
+        segments: &'p [StrSegment],
+        input: &Value,
+        env: &Env<'p>,
+        suffix: String,
+        out: Out<'_>,
+    ) -> Res {
+        let Some((last, rest)) = segments.split_last() else {
+            return out(Value::String(suffix));
+        };
+        match last {
+            StrSegment::Lit(text) => {
+                self.eval_string(format, rest, input, env, format!("{text}{suffix}"), out)
+            }
+            StrSegment::Interp(ast) => self.eval(ast, input.clone(), env, &mut |v| {
+                let text = match format {
+                    Some(format) => builtins::format(format, &v)?,
+                    None => to_string(&v),
+                };
+                self.eval_string(format, rest, input, env, text + &suffix, out)
+            }),
+        }
+    }
+
+    fn eval_object<'p>(
+        &self,
+        entries: &'p [ObjectEntry],
+        input: &Value,
+        env: &Env<'p>,
+        map: Map<String, Value>,
+        out: Out<'_>,
+    ) -> Res {
+        let Some((entry, rest)) = entries.split_first() else {
+            return out(Value::Object(map));
+        };
+        self.eval(&entry.key, input.clone(), env, &mut |key| {
+            let Value::String(key) = key else {
+                return Err(error(format!(
+                    "Object keys must be strings, not {}",
+                    type_name(&key)
+                )));
+            };
+            self.eval(&entry.value, input.clone(), env, &mut |value| {
+                let mut map = map.clone();
+                map.insert(key.clone(), value);
+                self.eval_object(rest, input, env, map, out)
+            })
+        })
+    }
+
+    fn eval_if<'p>(
+        &self,
+        branches: &'p [(Ast, Ast)],
+        otherwise: Option<&'p Ast>,
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        let Some(((condition, body), rest)) = branches.split_first() else {
+            return match otherwise {
+                Some(otherwise) => self.eval(otherwise, input, env, out),
+                None => out(input),
+            };
+        };
+        self.eval(condition, input.clone(), env, &mut |c| {
+            if truthy(&c) {
+                self.eval(body, input.clone(), env, out)
+            } else {
+                self.eval_if(rest, otherwise, input.clone(), env, out)
+            }
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn eval_reduce<'p>(
+        &self,
+        source: &'p Ast,
+        pattern: &'p Pattern,
+        init: &'p Ast,
+        update: &'p Ast,
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        self.eval(init, input.clone(), env, &mut |init| {
+            let mut acc = init;
+            self.eval(source, input.clone(), env, &mut |item| {
+                let scope = self.bind(pattern, item, &input, env)?;
+                let mut last = Value::Null;
+                self.eval(update, std::mem::take(&mut acc), &scope, &mut |v| {
+                    last = v;
+                    Ok(())
+                })?;
+                acc = last;
+                Ok(())
+            })?;
+            out(acc)
+        })
+    }
+
+    fn eval_foreach<'p>(
+        &self,
+        source: &'p Ast,
+        pattern: &'p Pattern,
+        (init, update, extract): (&'p Ast, &'p Ast, Option<&'p Ast>),
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        self.eval(init, input.clone(), env, &mut |init| {
+            let mut state = init;
+            self.eval(source, input.clone(), env, &mut |item| {
+                let scope = self.bind(pattern, item, &input, env)?;
+                for next in self.collect(update, state.clone(), &scope)? {
+                    state = next.clone();
+                    match extract {
+                        Some(extract) => self.eval(extract, next, &scope, out)?,
+                        None => out(next)?,
+                    }
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Destructure `value` into variables.
+    fn bind<'p>(
+        &self,
+        pattern: &'p Pattern,
+        value: Value,
+        input: &Value,
+        env: &Env<'p>,
+    ) -> Result<Env<'p>, Flow> {
+        match pattern {
+            Pattern::Var(name) => Ok(env.push(Binding::Var(name, value))),
+            Pattern::Named(name, inner) => {
+                let scope = env.push(Binding::Var(name, value.clone()));
+                self.bind(inner, value, input, &scope)
+            }
+            Pattern::Array(items) => {
+                let mut scope = env.clone();
+                for (i, item) in items.iter().enumerate() {
+                    let element = index(&value, &Value::from(i))?;
+                    scope = self.bind(item, element, input, &scope)?;
+                }
+                Ok(scope)
+            }
+            Pattern::Object(fields) => {
+                let mut scope = env.clone();
+                for (key, item) in fields {
+                    let key = self.first(key, input.clone(), &scope)?.unwrap_or_default();
+                    if !key.is_string() {
+                        return Err(error(format!(
+                            "Cannot index {} with {}",
+                            type_name(&value),
+                            type_name(&key)
+                        )));
+                    }
+                    let field = index(&value, &key)?;
+                    scope = self.bind(item, field, input, &scope)?;
+                }
+                Ok(scope)
+            }
+        }
+    }
+
+    fn call<'p>(
+        &self,
+        name: &str,
+        args: &'p [Ast],
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        match env.function(name, args.len()) {
+            Some(Callable::Func(def, scope)) => {
+                self.eval(&def.body, input, &bind_params(def, args, &scope, env), out)
+            }
+            Some(Callable::Closure(body, scope)) => self.eval(body, input, &scope, out),
+            None => builtins::call(self, name, args, input, env, out),
+        }
+    }
+
+    /// `lhs = rhs`, `lhs |= rhs`, and the arithmetic update forms
+    fn assign<'p>(
+        &self,
+        op: AssignOp,
+        lhs: &'p Ast,
+        rhs: &'p Ast,
+        input: Value,
+        env: &Env<'p>,
+        out: Out<'_>,
+    ) -> Res {
+        let mut paths = Vec::new();
+        self.paths(lhs, Vec::new(), input.clone(), env, &mut |(path, _)| {
+            paths.push(path);
+            Ok(())
+        })?;
+        if op == AssignOp::Update {
+            // Paths whose update is `empty` are deleted afterwards, so
+            // array indices of the remaining paths stay valid.
+            let mut result = input;
+            let mut deleted = Vec::new();
+            for path in paths {
+                let old = getpath(&result, &path)?;
+                match self.first(rhs, old, env)? {
+                    Some(new) => result = setpath(result, &path, new)?,
+                    None => deleted.push(Value::Array(path)),
+                }
+            }
+            return out(delpaths(result, deleted)?);
+        }
+        self.eval(rhs, input.clone(), env, &mut |value| {
+            let mut result = input.clone();
+            for path in &paths {
+                let new = match op {
+                    AssignOp::Set | AssignOp::Update => value.clone(),
+                    AssignOp::Arith(op) => binop(op, getpath(&result, path)?, value.clone())?,
+                    AssignOp::Alt => {
+                        let old = getpath(&result, path)?;
+                        if truthy(&old) {
+                            old
+                        } else {
+                            value.clone()
+                        }
+                    }
+                };
+                result = setpath(result, path, new)?;
+            }
+            out(result)
+        })
+    }
+
+    /// Evaluate `ast` as a path expression starting from `(path, value)`.
+    pub fn paths<'p>(
+        &self,
+        ast: &'p Ast,
+        path: Path,
+        value: Value,
+        env: &Env<'p>,
+        out: PathOut<'_>,
+    ) -> Res {
+        let depth = self.enter()?;
+        let result = self.paths_inner(ast, path, value, env, out);
+        self.depth.set(depth);
+        result
+    }
+
+    fn paths_inner<'p>(
+        &self,
+        ast: &'p Ast,
+        path: Path,
+        value: Value,
+        env: &Env<'p>,
+        out: PathOut<'_>,
+    ) -> Res {
+        match ast {
+            Ast::Identity => out((path, value)),
+            Ast::RecurseAll => {
+                let mut stack = vec![(path, value)];
+                while let Some((path, value)) = stack.pop() {
+                    let children = match &value {
+                        Value::Array(_) | Value::Object(_) => entries(value.clone())?,
+                        _ => Vec::new(),
+                    };
+                    out((path.clone(), value))?;
+                    stack.extend(children.into_iter().rev().map(|(key, child)| {
+                        let mut child_path = path.clone();
+                        child_path.push(key);
+                        (child_path, child)
+                    }));
+                }
+                Ok(())
+            }
+            Ast::Index(target, key) => {
+                let keys = self.collect(key, value.clone(), env)?;
+                self.paths(target, path, value, env, &mut |(path, v)| {
+                    keys.iter().try_for_each(|key| {
+                        let child = if v.is_null() {
+                            Value::Null
+                        } else {
+                            index(&v, key)?
+                        };
+                        let mut child_path = path.clone();
+                        child_path.push(key.clone());
+                        out((child_path, child))
+                    })
+                })
+            }
+            Ast::Slice(target, from, to) => self.paths_slice(
+                target,
+                (from.as_deref(), to.as_deref()),
+                path,
+                value,
+                env,
+                out,
+            ),
+            Ast::Iterate(target) => self.paths(target, path, value, env, &mut |(path, v)| {
+                entries(v)?.into_iter().try_for_each(|(key, child)| {
+                    let mut child_path = path.clone();
+                    child_path.push(key);
+                    out((child_path, child))
+                })
+            }),
+            Ast::Try(body, None) => {
+                catch(out, |out| self.paths(body, path, value, env, out))?;
+                Ok(())
+            }
+            Ast::Pipe(left, right) => self.paths(left, path, value, env, &mut |(path, v)| {
+                self.paths(right, path, v, env, out)
+            }),
+            Ast::Comma(left, right) => {
+                self.paths(left, path.clone(), value.clone(), env, out)?;
+                self.paths(right, path, value, env, out)
+            }
+            Ast::Alt(left, right) => {
+                let mut found = false;
+                catch(out, |out| {
+                    self.paths(left, path.clone(), value.clone(), env, &mut |(p, v)| {
+                        if truthy(&v) {
+                            found = true;
+                            out((p, v))
+                        } else {
+                            Ok(())
+                        }
+                    })
+                })?;
+                if found {
+                    Ok(())
+                } else {
+                    self.paths(right, path, value, env, out)
+                }
+            }
+            Ast::If(branches, otherwise) => {
+                self.paths_if(branches, otherwise.as_deref(), path, value, env, out)
+            }
+            Ast::Bind(source, pattern, body) => self.eval(source, value.clone(), env, &mut |v| {
+                let scope = self.bind(pattern, v, &value, env)?;
+                self.paths(body, path.clone(), value.clone(), &scope, out)
+            }),
+            Ast::Call(name, args) => match env.function(name, args.len()) {
+                Some(Callable::Func(def, scope)) => {
+                    let scope = bind_params(def, args, &scope, env);
+                    self.paths(&def.body, path, value, &scope, out)
+                }
+                Some(Callable::Closure(body, scope)) => self.paths(body, path, value, &scope, out),
+                None => builtins::call_paths(self, name, args, (&path, &value), env, out)
+                    .unwrap_or_else(|| self.eval(ast, value, env, &mut invalid_path)),
+            },
+            Ast::Def(defs, body) => self.paths(body, path, value, &env.with_defs(defs), out),
+            Ast::Label(name, body) => self.with_stop(|id| {
+                self.paths(body, path, value, &env.push(Binding::Label(name, id)), out)
+            }),
+            Ast::Break(_) => self.eval(ast, value, env, &mut |_| Ok(())),
+            other => self.eval(other, value, env, &mut invalid_path),
+        }
+    }
+
+    /// Slices appear in paths as `{"start": s, "end": e}` keys.
+    fn paths_slice<'p>(
+        &self,
+        target: &'p Ast,
+        (from, to): (Option<&'p Ast>, Option<&'p Ast>),
+        path: Path,
+        value: Value,
+        env: &Env<'p>,
+        out: PathOut<'_>,
+    ) -> Res {
+        let froms = self.collect_bound(from, &value, env)?;
+        let tos = self.collect_bound(to, &value, env)?;
+        self.paths(target, path, value, env, &mut |(path, v)| {
+            for from in &froms {
+                for to in &tos {
+                    let mut range = Map::new();
+                    range.insert("start".to_string(), from.clone());
+                    range.insert("end".to_string(), to.clone());
+                    let mut child_path = path.clone();
+                    child_path.push(Value::Object(range));
+                    out((child_path, slice(&v, from, to)?))?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn paths_if<'p>(
+        &self,
+        branches: &'p [(Ast, Ast)],
+        otherwise: Option<&'p Ast>,
+        path: Path,
+        value: Value,
+        env: &Env<'p>,
+        out: PathOut<'_>,
+    ) -> Res {
+        let Some(((condition, body), rest)) = branches.split_first() else {
+            return match otherwise {
+                Some(otherwise) => self.paths(otherwise, path, value, env, out),
+                None => out((path, value)),
+            };
+        };
+        self.eval(condition, value.clone(), env, &mut |c| {
+            if truthy(&c) {
+                self.paths(body, path.clone(), value.clone(), env, out)
+            } else {
+                self.paths_if(rest, otherwise, path.clone(), value.clone(), env, out)
+            }
+        })
+    }
+}
+
+/// Sink for values reaching a path-only position
+fn invalid_path(value: Value) -> Res {
+    Err(error(format!(
+        "Invalid path expression with result {}",
+        preview(&value)
+    )))
+}
+
+/// Run `body`, returning the error it raised, if any. Errors raised by
+/// `out` (downstream of `body`) are not caught.
+fn catch<T>(
+    out: &mut dyn FnMut(T) -> Res,
+    body: impl FnOnce(&mut dyn FnMut(T) -> Res) -> Res,
+) -> Result<Option<Value>, Flow> {
+    let mut downstream = None;
+    let result = body(&mut |v| {
+        out(v).map_err(|flow| {
+            downstream = Some(flow);
+            Flow::Break(DOWNSTREAM)
+        })
+    });
+    if let Some(flow) = downstream {
+        return Err(flow);
+    }
+    match result {
+        Ok(()) => Ok(None),
+        Err(Flow::Error(e)) => Ok(Some(e)),
+        Err(flow) => Err(flow),
+    }
+}
+
+/// Scope for a call to `def`: its definition scope plus each filter
+/// argument bound as a closure over the caller's scope.
+fn bind_params<'p>(
+    def: &'p FuncDef,
+    args: &'p [Ast],
+    scope: &Env<'p>,
+    caller: &Env<'p>,
+) -> Env<'p> {
+    def.params
+        .iter()
+        .zip(args)
+        .fold(scope.clone(), |env, (param, arg)| {
+            env.push(Binding::Closure(param, arg, caller.clone()))
+        })
+}
@@ -0,0 +1,399 @@
+//! Tokenizer for jq programs
+//!
+//! String literals are split into literal runs and `\(...)` interpolations.
+//! Each interpolation is tokenized in place, so the parser can handle it
+//! like any other expression and error positions stay absolute.
+
+use super::program_error;
+use crate::ast::AstParseError;
+
+/// Punctuation and operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sym {
+    Dot,
+    DotDot,
+    Pipe,
+    Comma,
+    Colon,
+    Semicolon,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    Question,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Alt,
+    Eq,
+    EqEq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    PipeEq,
+    PlusEq,
+    MinusEq,
+    StarEq,
+    SlashEq,
+    PercentEq,
+    AltEq,
+}
+
+/// Part of a string literal
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrPart {
+    Lit(String),
+    /// Tokens of a `\(...)` interpolation, without the parentheses
+    Interp(Vec<Token>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Sym(Sym),
+    /// `.name`
+    Field(String),
+    /// Identifier or keyword (`map`, `if`, `and`)
+    Ident(String),
+    /// `$name`
+    Var(String),
+    /// `@base64`
+    Format(String),
+    Number(f64),
+    Str(Vec<StrPart>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+const fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_'
+}
+
+const fn is_ident_char(c: u8) -> bool {
+    is_ident_start(c) || c.is_ascii_digit()
+}
+
+struct Lexer<'a> {
+    program: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+/// Split `program` into tokens.
+pub fn tokenize(program: &str) -> Result<Vec<Token>, AstParseError> {
+    let mut lexer = Lexer {
+        program,
+        bytes: program.as_bytes(),
+        pos: 0,
+    };
+    let tokens = lexer.tokens(false)?;
+    Ok(tokens)
+}
+
+impl Lexer<'_> {
+    fn error(&self, start: usize, end: usize, message: impl Into<String>) -> AstParseError {
+        program_error(self.program, start, end, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.pos + offset).copied()
+    }
+
+    /// Tokens up to the end of input, or up to the `)` closing an
+    /// interpolation when `interpolation` is set.
+    fn tokens(&mut self, interpolation: bool) -> Result<Vec<Token>, AstParseError> {
+        let mut tokens = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            self.skip_trivia();
+            let Some(c) = self.peek() else {
+                return if interpolation {
+                    Err(self.error(self.pos, self.pos, "Unterminated string interpolation"))
+                } else {
+                    Ok(tokens)
+                };
+            };
+            if interpolation && c == b')' && depth == 0 {
+                self.pos += 1;
+                return Ok(tokens);
+            }
+            let token = self.token()?;
+            match token.kind {
+                TokenKind::Sym(Sym::LParen) => depth += 1,
+                TokenKind::Sym(Sym::RParen) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            tokens.push(token);
+        }
+    }
+
+    fn skip_trivia(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if c == b'#' {
+                while self.peek().is_some_and(|c| c != b'\n') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_ident_char) {
+            self.pos += 1;
+        }
+        self.program[start..self.pos].to_string()
+    }
+
+    fn token(&mut self) -> Result<Token, AstParseError> {
+        let start = self.pos;
+        let c = self.bytes[self.pos];
+        let kind = match c {
+            b'"' => TokenKind::Str(self.string()?),
+            b'.' if self.peek_at(1).is_some_and(is_ident_start) => {
+                self.pos += 1;
+                TokenKind::Field(self.ident())
+            }
+            b'.' if self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) => self.number(),
+            c if c.is_ascii_digit() => self.number(),
+            c if is_ident_start(c) => TokenKind::Ident(self.ident()),
+            b'$' if self.peek_at(1).is_some_and(is_ident_start) => {
+                self.pos += 1;
+                TokenKind::Var(self.ident())
+            }
+            b'@' if self.peek_at(1).is_some_and(is_ident_start) => {
+                self.pos += 1;
+                TokenKind::Format(self.ident())
+            }
+            _ => TokenKind::Sym(self.symbol()?),
+        };
+        Ok(Token {
+            kind,
+            start,
+            end: self.pos,
+        })
+    }
+
+    fn symbol(&mut self) -> Result<Sym, AstParseError> {
+        const SYMBOLS: &[(&str, Sym)] = &[
+            ("//=", Sym::AltEq),
+            ("..", Sym::DotDot),
+            ("|=", Sym::PipeEq),
+            ("+=", Sym::PlusEq),
+            ("-=", Sym::MinusEq),
+            ("*=", Sym::StarEq),
+            ("/=", Sym::SlashEq),
+            ("%=", Sym::PercentEq),
+            ("==", Sym::EqEq),
+            ("!=", Sym::NotEq),
+            ("<=", Sym::LtEq),
+            (">=", Sym::GtEq),
+            ("//", Sym::Alt),
+            (".", Sym::Dot),
+            ("|", Sym::Pipe),
+            (",", Sym::Comma),
+            (":", Sym::Colon),
+            (";", Sym::Semicolon),
+            ("(", Sym::LParen),
+            (")", Sym::RParen),
+            ("[", Sym::LBracket),
+            ("]", Sym::RBracket),
+            ("{", Sym::LBrace),
+            ("}", Sym::RBrace),
+            ("?", Sym::Question),
+            ("+", Sym::Plus),
+            ("-", Sym::Minus),
+            ("*", Sym::Star),
+            ("/", Sym::Slash),
+            ("%", Sym::Percent),
+            ("=", Sym::Eq),
+            ("<", Sym::Lt),
+            (">", Sym::Gt),
+        ];
+        let rest = &self.program[self.pos..];
+        if let Some(&(text, sym)) = SYMBOLS.iter().find(|(text, _)| rest.starts_with(text)) {
+            self.pos += text.len();
+            return Ok(sym);
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        Err(self.error(
+            self.pos,
+            self.pos + c.len_utf8(),
+            format!("Unexpected character '{c}'"),
+        ))
+    }
+
+    fn number(&mut self) -> TokenKind {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == b'.') {
+            self.pos += 1;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let sign = usize::from(matches!(self.peek_at(1), Some(b'+' | b'-')));
+            if self.peek_at(1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                self.pos += 1 + sign;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+        }
+        TokenKind::Number(self.program[start..self.pos].parse().unwrap_or(f64::NAN))
+    }
+
+    /// String literal starting at the opening quote.
+    fn string(&mut self) -> Result<Vec<StrPart>, AstParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        loop {
+            let Some(c) = self.program[self.pos..].chars().next() else {
+                return Err(self.error(start, self.pos, "Unterminated string literal"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '"' => break,
+                '\\' => {
+                    let escape_start = self.pos - 1;
+                    let Some(escaped) = self.peek() else {
+                        return Err(self.error(start, self.pos, "Unterminated string literal"));
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        b'(' => {
+                            if !literal.is_empty() {
+                                parts.push(StrPart::Lit(std::mem::take(&mut literal)));
+                            }
+                            parts.push(StrPart::Interp(self.tokens(true)?));
+                        }
+                        b'n' => literal.push('\n'),
+                        b't' => literal.push('\t'),
+                        b'r' => literal.push('\r'),
+                        b'b' => literal.push('\u{8}'),
+                        b'f' => literal.push('\u{c}'),
+                        b'"' | b'\\' | b'/' => literal.push(char::from(escaped)),
+                        b'u' => literal.push(self.unicode_escape(escape_start)?),
+                        _ => {
+                            return Err(self.error(
+                                escape_start,
+                                self.pos,
+                                "Invalid escape in string literal",
+                            ));
+                        }
+                    }
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() || parts.is_empty() {
+            parts.push(StrPart::Lit(literal));
+        }
+        Ok(parts)
+    }
+
+    /// `\uXXXX`, combining a surrogate pair when one follows.
+    fn unicode_escape(&mut self, escape_start: usize) -> Result<char, AstParseError> {
+        let hex = |lexer: &mut Self| -> Option<u32> {
+            let digits = lexer.program.get(lexer.pos..lexer.pos + 4)?;
+            let value = u32::from_str_radix(digits, 16).ok()?;
+            lexer.pos += 4;
+            Some(value)
+        };
+        let invalid = |lexer: &Self| lexer.error(escape_start, lexer.pos, "Invalid \\u escape");
+        let Some(high) = hex(self) else {
+            return Err(invalid(self));
+        };
+        let code =
+            if (0xD800..0xDC00).contains(&high) && self.program[self.pos..].starts_with("\\u") {
+                self.pos += 2;
+                let Some(low) = hex(self) else {
+                    return Err(invalid(self));
+                };
+                0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+            } else {
+                high
+            };
+        Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(program: &str) -> Vec<TokenKind> {
+        tokenize(program)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_fields_operators_and_numbers() {
+        assert_eq!(
+            kinds(".foo[] | .bar //= 1.5e2 # comment"),
+            vec![
+                TokenKind::Field("foo".into()),
+                TokenKind::Sym(Sym::LBracket),
+                TokenKind::Sym(Sym::RBracket),
+                TokenKind::Sym(Sym::Pipe),
+                TokenKind::Field("bar".into()),
+                TokenKind::Sym(Sym::AltEq),
+                TokenKind::Number(150.0),
+            ]
+        );
+        assert_eq!(
+            kinds("$x @csv .."),
+            vec![
+                TokenKind::Var("x".into()),
+                TokenKind::Format("csv".into()),
+                TokenKind::Sym(Sym::DotDot),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_interpolation() {
+        let tokens = tokenize(r#""a\(.b + "\(1)")cé""#).unwrap();
+        let TokenKind::Str(parts) = &tokens[0].kind else {
+            unreachable!("expected a string");
+        };
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], StrPart::Lit("a".into()));
+        let StrPart::Interp(inner) = &parts[1] else {
+            unreachable!("expected an interpolation");
+        };
+        assert_eq!(inner[0].kind, TokenKind::Field("b".into()));
+        assert_eq!(inner[0].start, 4);
+        assert_eq!(parts[2], StrPart::Lit("cé".into()));
+    }
+
+    #[test]
+    fn test_errors_have_positions() {
+        let error = tokenize(".a | \"open").unwrap_err();
+        assert!(error.message.contains("Unterminated"));
+        assert_eq!(error.range.unwrap().start.offset, 5);
+
+        let error = tokenize(".a ^ 1").unwrap_err();
+        assert_eq!(error.range.unwrap().start.column, 4);
+    }
+}
@@ -0,0 +1,507 @@
+//! jq-style JSON transformation
+//!
+//! A self-contained interpreter for the jq language, backing the JQ
+//! playground. `transform_json` runs a program against every JSON value in
+//! the input and streams each result to the frontend as soon as it is
+//! produced. Syntax errors in the program carry ranges into the program
+//! text so the editor can underline them.
+//!
+//! Objects are `serde_json` maps, so object keys come out sorted rather
+//! than in insertion order. `input`/`inputs`, `$__loc__`, `?//`, and the
+//! date functions are not supported.
+//!
+//! The interpreter is kept here rather than built on `jaq`, which has no
+//! recursion limit and no way to interrupt a running filter: under jaq,
+//! `def f: 1 + f; f` overflows the stack and aborts the app, and
+//! `until(false; .)` spins until the app exits. Here every call counts
+//! against a depth limit, and calls and loop steps check the cancellation
+//! token.
+
+mod builtins;
+mod eval;
+mod lexer;
+mod parser;
+mod value;
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tokio_util::sync::CancellationToken;
+
+use crate::ast::{check_input_size, AstError, AstParseError, AstRange};
+use eval::{Env, Flow, Interp};
+
+/// Upper bound on results streamed by one run.
+const MAX_RESULTS: usize = 100_000;
+
+/// Stack size of the evaluator thread. Evaluation recurses once per
+/// nested filter, which the default 2 MiB blocking-pool stack cannot hold
+/// at the evaluator's depth limit.
+const EVAL_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Where a transform error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformErrorKind {
+    /// The program failed to parse; `range` points into the program
+    Program,
+    /// An input document is not valid JSON; `range` points into the input
+    Input,
+    /// The program raised an error while running on an input
+    Runtime,
+}
+
+/// Error reported by `transform_json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformError {
+    pub kind: TransformErrorKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<AstRange>,
+    /// Zero-based index of the input document being processed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_index: Option<usize>,
+}
+
+impl TransformError {
+    fn parse(kind: TransformErrorKind, error: AstParseError, input_index: Option<usize>) -> Self {
+        Self {
+            kind,
+            message: error.message,
+            range: error.range,
+            input_index,
+        }
+    }
+}
+
+/// Outcome of a run; the results themselves are streamed separately
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformSummary {
+    /// Number of results emitted
+    pub count: usize,
+    /// Number of input documents the program ran on
+    pub inputs: usize,
+    /// Output stopped at the result limit
+    pub truncated: bool,
+    pub errors: Vec<TransformError>,
+}
+
+/// Parse error at `start..end` of the program text
+fn program_error(
+    program: &str,
+    start: usize,
+    end: usize,
+    message: impl Into<String>,
+) -> AstParseError {
+    AstParseError::new(message).with_range(AstRange::from_offset(program, start, end))
+}
+
+/// Range of a `serde_json` error, from its 1-based line and column.
+fn input_error_range(text: &str, error: &serde_json::Error) -> AstRange {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(error.line().saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let mut offset = (line_start + error.column().saturating_sub(1)).min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    AstRange::from_offset(text, offset, offset)
+}
+
+/// Run `program` on every JSON value in `text`, passing each result to
+/// `emit`. Whitespace-only input runs the program once on `null`.
+pub fn transform(
+    text: &str,
+    program: &str,
+    cancel: Option<&CancellationToken>,
+    emit: &mut dyn FnMut(Value),
+) -> TransformSummary {
+    let mut summary = TransformSummary::default();
+    let ast = match parser::parse(program) {
+        Ok(ast) => ast,
+        Err(error) => {
+            summary.errors.push(TransformError::parse(
+                TransformErrorKind::Program,
+                error,
+                None,
+            ));
+            return summary;
+        }
+    };
+    let prelude = match parser::parse_defs(builtins::PRELUDE) {
+        Ok(defs) => defs,
+        Err(error) => {
+            summary.errors.push(TransformError {
+                kind: TransformErrorKind::Runtime,
+                message: format!("Invalid builtin definitions: {}", error.message),
+                range: None,
+                input_index: None,
+            });
+            return summary;
+        }
+    };
+    let env = Env::default().with_defs(&prelude);
+    let interp = Interp::new(cancel);
+
+    let documents: Box<dyn Iterator<Item = serde_json::Result<Value>>> = if text.trim().is_empty() {
+        Box::new(std::iter::once(Ok(Value::Null)))
+    } else {
+        Box::new(serde_json::Deserializer::from_str(text).into_iter())
+    };
+    for (index, document) in documents.enumerate() {
+        let input = match document {
+            Ok(input) => input,
+            Err(error) => {
+                summary.errors.push(TransformError {
+                    kind: TransformErrorKind::Input,
+                    message: format!("Invalid JSON input: {error}"),
+                    range: Some(input_error_range(text, &error)),
+                    input_index: Some(index),
+                });
+                break;
+            }
+        };
+        summary.inputs += 1;
+        let result = interp.with_stop(|stop| {
+            interp.eval(&ast, input, &env, &mut |value| {
+                if summary.count == MAX_RESULTS {
+                    summary.truncated = true;
+                    return Err(Flow::Break(stop));
+                }
+                summary.count += 1;
+                emit(value);
+                Ok(())
+            })
+        });
+        let message = match result {
+            Ok(()) if summary.truncated => break,
+            Ok(()) => continue,
+            Err(Flow::Cancelled) => break,
+            Err(Flow::Error(Value::String(message))) => message,
+            Err(Flow::Error(value)) => format!("{} (not a string)", value::to_json(&value)),
+            Err(Flow::Break(_)) => "break without a matching label".to_string(),
+        };
+        summary.errors.push(TransformError {
+            kind: TransformErrorKind::Runtime,
+            message,
+            range: None,
+            input_index: Some(index),
+        });
+    }
+    summary
+}
+
+/// Run a jq program over JSON input, streaming results
+///
+/// Every JSON value in `text` (whitespace-separated documents are allowed)
+/// is run through `program`; each result is sent on `on_result` as soon as
+/// it is produced. A runtime error on one input is recorded and the next
+/// input still runs, as with the `jq` CLI. Cancel a runaway program through
+/// `cancel_op` with the same `op_id`.
+///
+/// # Returns
+/// `TransformSummary` with the result count and any program, input, or
+/// runtime errors
+#[tauri::command]
+pub async fn transform_json(
+    text: String,
+    program: String,
+    on_result: Channel<Value>,
+    op_id: Option<String>,
+    state: tauri::State<'_, crate::cancellation::OperationRegistry>,
+) -> Result<TransformSummary, AstError> {
+    check_input_size(&text)?;
+
+    let token = Arc::new(CancellationToken::new());
+    if let Some(id) = &op_id {
+        state.register(id.clone(), Arc::clone(&token));
+    }
+    let worker_token = Arc::clone(&token);
+    let job = tokio::task::spawn_blocking(move || {
+        let worker = std::thread::Builder::new()
+            .name("jq-eval".into())
+            .stack_size(EVAL_STACK_SIZE)
+            .spawn(move || {
+                transform(&text, &program, Some(&worker_token), &mut |value| {
+                    // The frontend went away; stop producing results.
+                    if on_result.send(value).is_err() {
+                        worker_token.cancel();
+                    }
+                })
+            })
+            .map_err(|e| AstError::Internal(e.to_string()))?;
+        worker
+            .join()
+            .map_err(|_| AstError::Internal("jq evaluator panicked".to_string()))
+    });
+    let result = tokio::select! {
+        () = token.cancelled() => Err(AstError::Cancelled),
+        joined = job => joined.map_err(|e| AstError::Internal(e.to_string())).and_then(|r| r),
+    };
+    if let Some(id) = &op_id {
+        state.remove(id);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(text: &str, program: &str) -> (Vec<Value>, TransformSummary) {
+        let mut results = Vec::new();
+        let summary = transform(text, program, None, &mut |v| results.push(v));
+        (results, summary)
+    }
+
+    fn outputs(text: &str, program: &str) -> Vec<Value> {
+        let (results, summary) = run(text, program);
+        assert!(summary.errors.is_empty(), "{program}: {:?}", summary.errors);
+        results
+    }
+
+    #[test]
+    fn test_paths_and_iteration() {
+        let doc = r#"{"users": [{"name": "ann", "age": 31}, {"name": "bob", "age": 17}]}"#;
+        assert_eq!(
+            outputs(doc, ".users[].name"),
+            vec![json!("ann"), json!("bob")]
+        );
+        assert_eq!(
+            outputs(doc, "[.users[] | select(.age >= 18) | .name]"),
+            vec![json!(["ann"])]
+        );
+        assert_eq!(outputs(doc, ".users[-1].age"), vec![json!(17)]);
+        assert_eq!(outputs(doc, ".missing?.x"), vec![json!(null)]);
+        assert_eq!(outputs("[1,2,3,4]", ".[1:3]"), vec![json!([2, 3])]);
+        assert_eq!(outputs("", "1, 2"), vec![json!(1), json!(2)]);
+        assert_eq!(
+            outputs("1 2 3", ". * 10"),
+            vec![json!(10), json!(20), json!(30)]
+        );
+    }
+
+    #[test]
+    fn test_construction_and_generators() {
+        assert_eq!(
+            outputs(r#"{"a": 1, "b": [2, 3]}"#, "{a, b: .b[]}"),
+            vec![json!({"a": 1, "b": 2}), json!({"a": 1, "b": 3})]
+        );
+        assert_eq!(
+            outputs("null", "[(1,2) + (10,20)]"),
+            vec![json!([11, 12, 21, 22])]
+        );
+        assert_eq!(
+            outputs(r#"{"a": "x"}"#, r#""v=\(.a) \(1 + 1)""#),
+            vec![json!("v=x 2")]
+        );
+        assert_eq!(
+            outputs("null", "[limit(3; range(100))]"),
+            vec![json!([0, 1, 2])]
+        );
+        assert_eq!(
+            outputs("null", "[range(0; 10; 3)]"),
+            vec![json!([0, 3, 6, 9])]
+        );
+        assert_eq!(
+            outputs("null", "reduce range(5) as $x (0; . + $x)"),
+            vec![json!(10)]
+        );
+        assert_eq!(
+            outputs("null", "[foreach (1, 2, 3) as $x (0; . + $x; [$x, .])]"),
+            vec![json!([[1, 1], [2, 3], [3, 6]])]
+        );
+        assert_eq!(
+            outputs("[[1, 2], [3, 4]]", ". as [[$a], [$b, $c]] | $a + $b + $c"),
+            vec![json!(8)]
+        );
+        assert_eq!(
+            outputs(
+                "null",
+                "label $out | range(10) | if . == 2 then break $out else . end"
+            ),
+            vec![json!(0), json!(1)]
+        );
+    }
+
+    #[test]
+    fn test_builtins() {
+        let doc = r#"[{"k": "b", "v": 2}, {"k": "a", "v": 1}, {"k": "b", "v": 3}]"#;
+        assert_eq!(outputs(doc, "map(.v) | add"), vec![json!(6)]);
+        assert_eq!(
+            outputs(doc, "group_by(.k) | map(length)"),
+            vec![json!([1, 2])]
+        );
+        assert_eq!(outputs(doc, "sort_by(.v) | .[0].k"), vec![json!("a")]);
+        assert_eq!(outputs(doc, "max_by(.v).v"), vec![json!(3)]);
+        assert_eq!(
+            outputs(r#"{"a": 1, "b": 2}"#, "to_entries | map(.key)"),
+            vec![json!(["a", "b"])]
+        );
+        assert_eq!(
+            outputs(r#"{"a": 1}"#, "with_entries(.value += 1)"),
+            vec![json!({"a": 2})]
+        );
+        assert_eq!(
+            outputs(r#""a-b-c""#, r#"split("-") | join("+")"#),
+            vec![json!("a+b+c")]
+        );
+        assert_eq!(
+            outputs(r#""test 123 abc 45""#, r#"[scan("[0-9]+")]"#),
+            vec![json!(["123", "45"])]
+        );
+        assert_eq!(
+            outputs(r#""hello world""#, r#"gsub("o"; "0")"#),
+            vec![json!("hell0 w0rld")]
+        );
+        assert_eq!(
+            outputs(r#""xyz-42""#, r#"capture("(?<name>[a-z]+)-(?<n>\\d+)")"#),
+            vec![json!({"name": "xyz", "n": "42"})]
+        );
+        assert_eq!(
+            outputs("[1, [2, [3]]]", "flatten, flatten(1)"),
+            vec![json!([1, 2, 3]), json!([1, 2, [3]])]
+        );
+        assert_eq!(
+            outputs(r#"[1, "a,b", null]"#, "@csv, @tsv"),
+            vec![json!("1,\"a,b\","), json!("1\ta,b\t")]
+        );
+        assert_eq!(
+            outputs(r#""hi""#, "@base64, (@base64 | @base64d), @base32"),
+            vec![json!("aGk="), json!("hi"), json!("NBUQ====")]
+        );
+        assert_eq!(
+            outputs(
+                "null",
+                "def fac: if . <= 1 then 1 else . * (. - 1 | fac) end; 10 | fac"
+            ),
+            vec![json!(3_628_800)]
+        );
+    }
+
+    #[test]
+    fn test_assignment_and_deletion() {
+        let doc = r#"{"a": {"b": 1}, "list": [1, 2, 3, 4]}"#;
+        assert_eq!(outputs(doc, ".a.b |= . + 1 | .a.b"), vec![json!(2)]);
+        assert_eq!(
+            outputs(doc, ".list[] += 10 | .list"),
+            vec![json!([11, 12, 13, 14])]
+        );
+        assert_eq!(
+            outputs(doc, ".a.c = .list[0] | .a"),
+            vec![json!({"b": 1, "c": 1})]
+        );
+        assert_eq!(
+            outputs(doc, "del(.list[] | select(. % 2 == 0)) | .list"),
+            vec![json!([1, 3])]
+        );
+        assert_eq!(
+            outputs(doc, ".list |= map(select(. > 2))| .list"),
+            vec![json!([3, 4])]
+        );
+        assert_eq!(
+            outputs(doc, "[paths(type == \"number\")]"),
+            vec![json!([
+                ["a", "b"],
+                ["list", 0],
+                ["list", 1],
+                ["list", 2],
+                ["list", 3]
+            ])]
+        );
+        assert_eq!(outputs(doc, ".x //= 5 | .x"), vec![json!(5)]);
+    }
+
+    #[test]
+    fn test_try_and_runtime_errors() {
+        assert_eq!(
+            outputs("null", r#"try error("boom") catch ."#),
+            vec![json!("boom")]
+        );
+        assert_eq!(
+            outputs("[1, \"a\", 2]", "[.[] | tonumber?]"),
+            vec![json!([1, 2])]
+        );
+        assert_eq!(outputs("null", r#"(error("x")) // 7"#), vec![json!(7)]);
+
+        // An error after a try body is not caught by it
+        let (_, summary) = run("null", r#"try 1 catch 2 | error("after")"#);
+        assert_eq!(summary.errors[0].message, "after");
+
+        let (results, summary) = run(r#"{"a": 1} [2] {"a": 3}"#, ".a");
+        assert_eq!(results, vec![json!(1), json!(3)]);
+        assert_eq!(summary.inputs, 3);
+        assert_eq!(summary.errors[0].kind, TransformErrorKind::Runtime);
+        assert_eq!(summary.errors[0].input_index, Some(1));
+        assert_eq!(summary.errors[0].message, r#"Cannot index array with "a""#);
+    }
+
+    #[test]
+    fn test_program_and_input_errors_have_ranges() {
+        let (results, summary) = run("{}", ".a | map(.b");
+        assert!(results.is_empty());
+        assert_eq!(summary.errors[0].kind, TransformErrorKind::Program);
+        assert!(summary.errors[0].range.is_some());
+
+        let (results, summary) = run("{\"a\": 1}\n{\"a\": }", ".a");
+        assert_eq!(results, vec![json!(1)]);
+        let error = &summary.errors[0];
+        assert_eq!(error.kind, TransformErrorKind::Input);
+        assert_eq!(error.input_index, Some(1));
+        assert_eq!(error.range.as_ref().unwrap().start.line, 2);
+
+        let (_, summary) = run("null", "undefined_fn(1)");
+        assert_eq!(summary.errors[0].message, "undefined_fn/1 is not defined");
+    }
+
+    #[test]
+    fn test_limits_and_cancellation() {
+        let (results, summary) = run("null", "range(1e9)");
+        assert_eq!(results.len(), MAX_RESULTS);
+        assert!(summary.truncated);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut count = 0;
+        let summary = transform("null", "repeat(.)", Some(&token), &mut |_| count += 1);
+        assert_eq!(count, 0);
+        assert!(summary.errors.is_empty());
+
+        // A loop that never produces a result still stops when cancelled
+        let token = CancellationToken::new();
+        let summary = std::thread::scope(|scope| {
+            let worker =
+                scope.spawn(|| transform("null", "until(false; .)", Some(&token), &mut |_| {}));
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            token.cancel();
+            worker.join().unwrap()
+        });
+        assert!(summary.errors.is_empty());
+    }
+
+    #[test]
+    fn test_deep_recursion_is_an_error() {
+        let worker = std::thread::Builder::new()
+            .stack_size(EVAL_STACK_SIZE)
+            .spawn(|| {
+                [
+                    run("0", "def f: . + 1 | f; f").1,
+                    run("0", "def f: 1 + f; f").1,
+                ]
+            })
+            .unwrap();
+        for summary in worker.join().unwrap() {
+            assert_eq!(
+                summary.errors[0].message,
+                "Maximum recursion depth exceeded"
+            );
+        }
+    }
+}
@@ -0,0 +1,847 @@
+//! Recursive-descent parser for jq programs
+//!
+//! Precedence follows jq, loosest first: `|`, `,`, `//`, assignment
+//! operators, `or`, `and`, comparisons, `+ -`, `* / %`, unary minus, and
+//! postfix suffixes (`.name`, `[...]`, `?`). `Term as $x | body` binds
+//! everything after the pipe.
+
+use std::rc::Rc;
+
+use serde_json::Value;
+
+use super::lexer::{tokenize, StrPart, Sym, Token, TokenKind};
+use super::program_error;
+use crate::ast::AstParseError;
+
+/// Binary operators evaluated on values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// Update-assignment operators (`|=` and the arithmetic forms)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignOp {
+    /// `=`: set every path to each output of the right side
+    Set,
+    /// `|=`: run the right side on each path's value
+    Update,
+    /// `+=`, `-=`, ...: combine each path's value with the right side
+    Arith(BinOp),
+    /// `//=`
+    Alt,
+}
+
+/// Destructuring pattern after `as`
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Var(String),
+    Array(Vec<Self>),
+    /// `{key: pattern}` entries; `$name` alone binds and matches `name`
+    Object(Vec<(Ast, Self)>),
+    /// `$name: pattern` inside an object pattern: binds the whole value
+    /// and destructures it
+    Named(String, Box<Self>),
+}
+
+/// Object construction entry
+#[derive(Debug, Clone)]
+pub struct ObjectEntry {
+    pub key: Ast,
+    pub value: Ast,
+}
+
+/// String literal part after parsing
+#[derive(Debug, Clone)]
+pub enum StrSegment {
+    Lit(String),
+    Interp(Ast),
+}
+
+/// `def name(params): body;`
+#[derive(Debug)]
+pub struct FuncDef {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Ast,
+}
+
+#[derive(Debug, Clone)]
+pub enum Ast {
+    Identity,
+    /// `..`
+    RecurseAll,
+    Literal(Value),
+    /// Possibly interpolated string, with an optional `@format` applied to
+    /// the interpolated values
+    Str(Option<String>, Vec<StrSegment>),
+    /// `@format` applied to `.`
+    Format(String),
+    /// `target[key]`; `.name` is `Identity[name]`
+    Index(Box<Self>, Box<Self>),
+    /// `target[from:to]`
+    Slice(Box<Self>, Option<Box<Self>>, Option<Box<Self>>),
+    /// `target[]`
+    Iterate(Box<Self>),
+    /// `f?`
+    Try(Box<Self>, Option<Box<Self>>),
+    Array(Option<Box<Self>>),
+    Object(Vec<ObjectEntry>),
+    Neg(Box<Self>),
+    Pipe(Box<Self>, Box<Self>),
+    Comma(Box<Self>, Box<Self>),
+    Binary(BinOp, Box<Self>, Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Alt(Box<Self>, Box<Self>),
+    Assign(AssignOp, Box<Self>, Box<Self>),
+    /// `if c then a elif ... else b end`
+    If(Vec<(Self, Self)>, Option<Box<Self>>),
+    Reduce(Box<Self>, Pattern, Box<Self>, Box<Self>),
+    Foreach(Box<Self>, Pattern, Box<Self>, Box<Self>, Option<Box<Self>>),
+    /// `source as pattern | body`
+    Bind(Box<Self>, Pattern, Box<Self>),
+    Var(String),
+    /// Builtin, user function, or filter parameter
+    Call(String, Vec<Self>),
+    /// Definitions in scope for `body`
+    Def(Vec<Rc<FuncDef>>, Box<Self>),
+    Label(String, Box<Self>),
+    Break(String),
+}
+
+/// Parse a jq program.
+pub fn parse(program: &str) -> Result<Ast, AstParseError> {
+    let tokens = tokenize(program)?;
+    let mut parser = Parser {
+        program,
+        tokens: &tokens,
+        pos: 0,
+    };
+    if tokens.is_empty() {
+        return Ok(Ast::Identity);
+    }
+    let ast = parser.parse_pipe()?;
+    parser.expect_end()?;
+    Ok(ast)
+}
+
+/// Parse a sequence of definitions (the jq-defined builtins).
+pub fn parse_defs(program: &str) -> Result<Vec<Rc<FuncDef>>, AstParseError> {
+    let tokens = tokenize(program)?;
+    let mut parser = Parser {
+        program,
+        tokens: &tokens,
+        pos: 0,
+    };
+    let mut defs = Vec::new();
+    while parser.peek_ident("def") {
+        defs.push(Rc::new(parser.parse_def()?));
+    }
+    parser.expect_end()?;
+    Ok(defs)
+}
+
+const KEYWORDS: &[&str] = &[
+    "def", "if", "then", "elif", "else", "end", "as", "reduce", "foreach", "try", "catch", "label",
+    "and", "or", "__loc__", "import", "include",
+];
+
+struct Parser<'a> {
+    program: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn peek_sym(&self, sym: Sym) -> bool {
+        self.peek() == Some(&TokenKind::Sym(sym))
+    }
+
+    fn peek_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(TokenKind::Ident(ident)) if ident == name)
+    }
+
+    fn eat_sym(&mut self, sym: Sym) -> bool {
+        let found = self.peek_sym(sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        let found = self.peek_ident(name);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn error(&self, message: impl Into<String>) -> AstParseError {
+        let message = message.into();
+        if let Some(token) = self.tokens.get(self.pos) {
+            return program_error(self.program, token.start, token.end, message);
+        }
+        let end = self.tokens.last().map_or(self.program.len(), |t| t.end);
+        program_error(
+            self.program,
+            end,
+            end,
+            format!("{message} at end of program"),
+        )
+    }
+
+    fn expect_sym(&mut self, sym: Sym, what: &str) -> Result<(), AstParseError> {
+        if self.eat_sym(sym) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected {what}")))
+        }
+    }
+
+    fn expect_ident(&mut self, name: &str) -> Result<(), AstParseError> {
+        if self.eat_ident(name) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected '{name}'")))
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), AstParseError> {
+        if self.pos < self.tokens.len() {
+            return Err(self.error("Unexpected token"));
+        }
+        Ok(())
+    }
+
+    fn expect_var(&mut self) -> Result<String, AstParseError> {
+        if let Some(TokenKind::Var(name)) = self.peek() {
+            let name = name.clone();
+            self.pos += 1;
+            Ok(name)
+        } else {
+            Err(self.error("Expected a $variable"))
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<String, AstParseError> {
+        match self.peek() {
+            Some(TokenKind::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("Expected a name")),
+        }
+    }
+
+    /// `def ...; ... | pipe`, `a | b`
+    fn parse_pipe(&mut self) -> Result<Ast, AstParseError> {
+        if self.peek_ident("def") {
+            let mut defs = Vec::new();
+            while self.peek_ident("def") {
+                defs.push(Rc::new(self.parse_def()?));
+            }
+            let body = self.parse_pipe()?;
+            return Ok(Ast::Def(defs, Box::new(body)));
+        }
+        let left = self.parse_comma()?;
+        if self.eat_sym(Sym::Pipe) {
+            let right = self.parse_pipe()?;
+            return Ok(Ast::Pipe(Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    /// Pipe without commas, as allowed for object values.
+    fn parse_pipe_no_comma(&mut self) -> Result<Ast, AstParseError> {
+        let left = self.parse_alt()?;
+        if self.eat_sym(Sym::Pipe) {
+            let right = self.parse_pipe_no_comma()?;
+            return Ok(Ast::Pipe(Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_def(&mut self) -> Result<FuncDef, AstParseError> {
+        self.expect_ident("def")?;
+        let name = self.expect_name()?;
+        let mut params = Vec::new();
+        let mut value_params = Vec::new();
+        if self.eat_sym(Sym::LParen) {
+            loop {
+                match self.peek() {
+                    Some(TokenKind::Var(var)) => {
+                        // `$x` is sugar for a filter param `x` bound as `x as $x`.
+                        value_params.push(var.clone());
+                        params.push(var.clone());
+                        self.pos += 1;
+                    }
+                    _ => params.push(self.expect_name()?),
+                }
+                if self.eat_sym(Sym::RParen) {
+                    break;
+                }
+                self.expect_sym(Sym::Semicolon, "';' or ')'")?;
+            }
+        }
+        self.expect_sym(Sym::Colon, "':'")?;
+        let mut body = self.parse_pipe()?;
+        self.expect_sym(Sym::Semicolon, "';' after function body")?;
+        for var in value_params.into_iter().rev() {
+            body = Ast::Bind(
+                Box::new(Ast::Call(var.clone(), Vec::new())),
+                Pattern::Var(var),
+                Box::new(body),
+            );
+        }
+        Ok(FuncDef { name, params, body })
+    }
+
+    fn parse_comma(&mut self) -> Result<Ast, AstParseError> {
+        let mut left = self.parse_alt()?;
+        while self.eat_sym(Sym::Comma) {
+            let right = self.parse_alt()?;
+            left = Ast::Comma(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_alt(&mut self) -> Result<Ast, AstParseError> {
+        let left = self.parse_assign()?;
+        if self.eat_sym(Sym::Alt) {
+            let right = self.parse_alt()?;
+            return Ok(Ast::Alt(Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_assign(&mut self) -> Result<Ast, AstParseError> {
+        let left = self.parse_or()?;
+        let op = match self.peek() {
+            Some(TokenKind::Sym(Sym::Eq)) => AssignOp::Set,
+            Some(TokenKind::Sym(Sym::PipeEq)) => AssignOp::Update,
+            Some(TokenKind::Sym(Sym::PlusEq)) => AssignOp::Arith(BinOp::Add),
+            Some(TokenKind::Sym(Sym::MinusEq)) => AssignOp::Arith(BinOp::Sub),
+            Some(TokenKind::Sym(Sym::StarEq)) => AssignOp::Arith(BinOp::Mul),
+            Some(TokenKind::Sym(Sym::SlashEq)) => AssignOp::Arith(BinOp::Div),
+            Some(TokenKind::Sym(Sym::PercentEq)) => AssignOp::Arith(BinOp::Mod),
+            Some(TokenKind::Sym(Sym::AltEq)) => AssignOp::Alt,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.parse_alt()?;
+        Ok(Ast::Assign(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_or(&mut self) -> Result<Ast, AstParseError> {
+        let mut left = self.parse_and()?;
+        while self.eat_ident("or") {
+            let right = self.parse_and()?;
+            left = Ast::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Ast, AstParseError> {
+        let mut left = self.parse_comparison()?;
+        while self.eat_ident("and") {
+            let right = self.parse_comparison()?;
+            left = Ast::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Ast, AstParseError> {
+        let left = self.parse_additive()?;
+        let op = match self.peek() {
+            Some(TokenKind::Sym(Sym::EqEq)) => BinOp::Eq,
+            Some(TokenKind::Sym(Sym::NotEq)) => BinOp::NotEq,
+            Some(TokenKind::Sym(Sym::Lt)) => BinOp::Lt,
+            Some(TokenKind::Sym(Sym::LtEq)) => BinOp::LtEq,
+            Some(TokenKind::Sym(Sym::Gt)) => BinOp::Gt,
+            Some(TokenKind::Sym(Sym::GtEq)) => BinOp::GtEq,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.parse_additive()?;
+        Ok(Ast::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_additive(&mut self) -> Result<Ast, AstParseError> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let op = if self.eat_sym(Sym::Plus) {
+                BinOp::Add
+            } else if self.eat_sym(Sym::Minus) {
+                BinOp::Sub
+            } else {
+                return Ok(left);
+            };
+            let right = self.parse_multiplicative()?;
+            left = Ast::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Ast, AstParseError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = if self.eat_sym(Sym::Star) {
+                BinOp::Mul
+            } else if self.eat_sym(Sym::Slash) {
+                BinOp::Div
+            } else if self.eat_sym(Sym::Percent) {
+                BinOp::Mod
+            } else {
+                return Ok(left);
+            };
+            let right = self.parse_unary()?;
+            left = Ast::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Ast, AstParseError> {
+        if self.eat_sym(Sym::Minus) {
+            return Ok(Ast::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_postfix(true)
+    }
+
+    /// Term with suffixes; with `allow_bind`, a following `as` pattern
+    /// binds the rest of the pipe.
+    fn parse_postfix(&mut self, allow_bind: bool) -> Result<Ast, AstParseError> {
+        let mut term = self.parse_term()?;
+        loop {
+            match self.peek() {
+                Some(TokenKind::Field(name)) => {
+                    let key = Ast::Literal(Value::String(name.clone()));
+                    self.pos += 1;
+                    term = Ast::Pipe(
+                        Box::new(term),
+                        Box::new(Ast::Index(Box::new(Ast::Identity), Box::new(key))),
+                    );
+                }
+                Some(TokenKind::Sym(Sym::Dot))
+                    if matches!(
+                        self.tokens.get(self.pos + 1).map(|t| &t.kind),
+                        Some(TokenKind::Str(_) | TokenKind::Sym(Sym::LBracket))
+                    ) =>
+                {
+                    self.pos += 1;
+                    if !self.peek_sym(Sym::LBracket) {
+                        let key = self.parse_term()?;
+                        term = Ast::Pipe(
+                            Box::new(term),
+                            Box::new(Ast::Index(Box::new(Ast::Identity), Box::new(key))),
+                        );
+                    }
+                }
+                Some(TokenKind::Sym(Sym::LBracket)) => {
+                    self.pos += 1;
+                    term = self.parse_bracket_suffix(term)?;
+                }
+                Some(TokenKind::Sym(Sym::Question)) => {
+                    self.pos += 1;
+                    term = Ast::Try(Box::new(term), None);
+                }
+                Some(TokenKind::Ident(name)) if allow_bind && name == "as" => {
+                    self.pos += 1;
+                    let pattern = self.parse_pattern()?;
+                    self.expect_sym(Sym::Pipe, "'|' after pattern")?;
+                    let body = self.parse_pipe()?;
+                    return Ok(Ast::Bind(Box::new(term), pattern, Box::new(body)));
+                }
+                _ => return Ok(term),
+            }
+        }
+    }
+
+    /// `[]`, `[e]`, `[e:]`, `[:e]`, `[e:e]` after `target`; the opening
+    /// bracket is consumed.
+    fn parse_bracket_suffix(&mut self, target: Ast) -> Result<Ast, AstParseError> {
+        if self.eat_sym(Sym::RBracket) {
+            return Ok(Ast::Iterate(Box::new(target)));
+        }
+        let from = if self.peek_sym(Sym::Colon) {
+            None
+        } else {
+            Some(Box::new(self.parse_pipe()?))
+        };
+        if self.eat_sym(Sym::Colon) {
+            let to = if self.peek_sym(Sym::RBracket) {
+                None
+            } else {
+                Some(Box::new(self.parse_pipe()?))
+            };
+            self.expect_sym(Sym::RBracket, "']'")?;
+            return Ok(Ast::Slice(Box::new(target), from, to));
+        }
+        self.expect_sym(Sym::RBracket, "']'")?;
+        let Some(index) = from else {
+            return Err(self.error("Expected an index"));
+        };
+        Ok(Ast::Index(Box::new(target), index))
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, AstParseError> {
+        if let Some(TokenKind::Var(_)) = self.peek() {
+            return Ok(Pattern::Var(self.expect_var()?));
+        }
+        if self.eat_sym(Sym::LBracket) {
+            let mut items = Vec::new();
+            loop {
+                items.push(self.parse_pattern()?);
+                if self.eat_sym(Sym::RBracket) {
+                    return Ok(Pattern::Array(items));
+                }
+                self.expect_sym(Sym::Comma, "',' or ']'")?;
+            }
+        }
+        if self.eat_sym(Sym::LBrace) {
+            let mut entries = Vec::new();
+            loop {
+                entries.push(self.parse_object_pattern_entry()?);
+                if self.eat_sym(Sym::RBrace) {
+                    return Ok(Pattern::Object(entries));
+                }
+                self.expect_sym(Sym::Comma, "',' or '}'")?;
+            }
+        }
+        Err(self.error("Expected a pattern"))
+    }
+
+    fn parse_object_pattern_entry(&mut self) -> Result<(Ast, Pattern), AstParseError> {
+        if let Some(TokenKind::Var(name)) = self.peek() {
+            let name = name.clone();
+            self.pos += 1;
+            let key = Ast::Literal(Value::String(name.clone()));
+            if self.eat_sym(Sym::Colon) {
+                // `{$name: pattern}` binds `$name` and destructures it too.
+                let inner = self.parse_pattern()?;
+                return Ok((key, Pattern::Named(name, Box::new(inner))));
+            }
+            return Ok((key, Pattern::Var(name)));
+        }
+        let key = match self.peek().cloned() {
+            Some(TokenKind::Ident(name)) => {
+                self.pos += 1;
+                Ast::Literal(Value::String(name))
+            }
+            Some(TokenKind::Str(_)) => self.parse_term()?,
+            Some(TokenKind::Sym(Sym::LParen)) => {
+                self.pos += 1;
+                let key = self.parse_pipe()?;
+                self.expect_sym(Sym::RParen, "')'")?;
+                key
+            }
+            _ => return Err(self.error("Expected an object pattern key")),
+        };
+        self.expect_sym(Sym::Colon, "':'")?;
+        Ok((key, self.parse_pattern()?))
+    }
+
+    fn parse_term(&mut self) -> Result<Ast, AstParseError> {
+        let tokens = self.tokens;
+        let Some(token) = tokens.get(self.pos) else {
+            return Err(self.error("Unexpected end"));
+        };
+        self.pos += 1;
+        Ok(match &token.kind {
+            TokenKind::Sym(Sym::Dot) => {
+                if let Some(TokenKind::Str(_)) = self.peek() {
+                    let key = self.parse_term()?;
+                    Ast::Index(Box::new(Ast::Identity), Box::new(key))
+                } else {
+                    Ast::Identity
+                }
+            }
+            TokenKind::Sym(Sym::DotDot) => Ast::RecurseAll,
+            TokenKind::Field(name) => Ast::Index(
+                Box::new(Ast::Identity),
+                Box::new(Ast::Literal(Value::String(name.clone()))),
+            ),
+            TokenKind::Number(n) => Ast::Literal(super::eval::number(*n)),
+            TokenKind::Str(parts) => self.parse_string(None, parts)?,
+            TokenKind::Format(name) => {
+                if let Some(TokenKind::Str(parts)) = tokens.get(self.pos).map(|t| &t.kind) {
+                    self.pos += 1;
+                    self.parse_string(Some(name.clone()), parts)?
+                } else {
+                    Ast::Format(name.clone())
+                }
+            }
+            TokenKind::Var(name) => Ast::Var(name.clone()),
+            TokenKind::Sym(Sym::LParen) => {
+                let inner = self.parse_pipe()?;
+                self.expect_sym(Sym::RParen, "')'")?;
+                inner
+            }
+            TokenKind::Sym(Sym::LBracket) => {
+                if self.eat_sym(Sym::RBracket) {
+                    Ast::Array(None)
+                } else {
+                    let inner = self.parse_pipe()?;
+                    self.expect_sym(Sym::RBracket, "']'")?;
+                    Ast::Array(Some(Box::new(inner)))
+                }
+            }
+            TokenKind::Sym(Sym::LBrace) => self.parse_object()?,
+            TokenKind::Ident(name) => self.parse_keyword_or_call(name)?,
+            TokenKind::Sym(_) => {
+                self.pos -= 1;
+                return Err(self.error("Unexpected token"));
+            }
+        })
+    }
+
+    fn parse_keyword_or_call(&mut self, name: &str) -> Result<Ast, AstParseError> {
+        Ok(match name {
+            "true" => Ast::Literal(Value::Bool(true)),
+            "false" => Ast::Literal(Value::Bool(false)),
+            "null" => Ast::Literal(Value::Null),
+            "if" => self.parse_if()?,
+            "try" => {
+                let body = self.parse_postfix(false)?;
+                let handler = if self.eat_ident("catch") {
+                    Some(Box::new(self.parse_postfix(false)?))
+                } else {
+                    None
+                };
+                Ast::Try(Box::new(body), handler)
+            }
+            "reduce" => {
+                let source = self.parse_postfix(false)?;
+                self.expect_ident("as")?;
+                let pattern = self.parse_pattern()?;
+                self.expect_sym(Sym::LParen, "'('")?;
+                let init = self.parse_pipe()?;
+                self.expect_sym(Sym::Semicolon, "';'")?;
+                let update = self.parse_pipe()?;
+                self.expect_sym(Sym::RParen, "')'")?;
+                Ast::Reduce(Box::new(source), pattern, Box::new(init), Box::new(update))
+            }
+            "foreach" => {
+                let source = self.parse_postfix(false)?;
+                self.expect_ident("as")?;
+                let pattern = self.parse_pattern()?;
+                self.expect_sym(Sym::LParen, "'('")?;
+                let init = self.parse_pipe()?;
+                self.expect_sym(Sym::Semicolon, "';'")?;
+                let update = self.parse_pipe()?;
+                let extract = if self.eat_sym(Sym::Semicolon) {
+                    Some(Box::new(self.parse_pipe()?))
+                } else {
+                    None
+                };
+                self.expect_sym(Sym::RParen, "')'")?;
+                Ast::Foreach(
+                    Box::new(source),
+                    pattern,
+                    Box::new(init),
+                    Box::new(update),
+                    extract,
+                )
+            }
+            "label" => {
+                let label = self.expect_var()?;
+                self.expect_sym(Sym::Pipe, "'|' after label")?;
+                Ast::Label(label, Box::new(self.parse_pipe()?))
+            }
+            "break" => Ast::Break(self.expect_var()?),
+            "def" => {
+                self.pos -= 1;
+                self.parse_pipe()?
+            }
+            _ if KEYWORDS.contains(&name) => {
+                self.pos -= 1;
+                return Err(self.error(format!("Unexpected keyword '{name}'")));
+            }
+            _ => {
+                let mut args = Vec::new();
+                if self.eat_sym(Sym::LParen) {
+                    loop {
+                        args.push(self.parse_pipe()?);
+                        if self.eat_sym(Sym::RParen) {
+                            break;
+                        }
+                        self.expect_sym(Sym::Semicolon, "';' or ')'")?;
+                    }
+                }
+                Ast::Call(name.to_string(), args)
+            }
+        })
+    }
+
+    fn parse_if(&mut self) -> Result<Ast, AstParseError> {
+        let mut branches = Vec::new();
+        loop {
+            let condition = self.parse_pipe()?;
+            self.expect_ident("then")?;
+            let body = self.parse_pipe()?;
+            branches.push((condition, body));
+            if !self.eat_ident("elif") {
+                break;
+            }
+        }
+        let otherwise = if self.eat_ident("else") {
+            Some(Box::new(self.parse_pipe()?))
+        } else {
+            None
+        };
+        self.expect_ident("end")?;
+        Ok(Ast::If(branches, otherwise))
+    }
+
+    fn parse_string(
+        &self,
+        format: Option<String>,
+        parts: &[StrPart],
+    ) -> Result<Ast, AstParseError> {
+        let mut segments = Vec::new();
+        for part in parts {
+            match part {
+                StrPart::Lit(text) => segments.push(StrSegment::Lit(text.clone())),
+                StrPart::Interp(tokens) => {
+                    let mut inner = Parser {
+                        program: self.program,
+                        tokens,
+                        pos: 0,
+                    };
+                    if tokens.is_empty() {
+                        return Err(self.error("Empty string interpolation"));
+                    }
+                    let ast = inner.parse_pipe()?;
+                    inner.expect_end()?;
+                    segments.push(StrSegment::Interp(ast));
+                }
+            }
+        }
+        if format.is_none() {
+            if let [StrSegment::Lit(text)] = segments.as_slice() {
+                return Ok(Ast::Literal(Value::String(text.clone())));
+            }
+        }
+        Ok(Ast::Str(format, segments))
+    }
+
+    fn parse_object(&mut self) -> Result<Ast, AstParseError> {
+        let mut entries = Vec::new();
+        if self.eat_sym(Sym::RBrace) {
+            return Ok(Ast::Object(entries));
+        }
+        loop {
+            entries.push(self.parse_object_entry()?);
+            if self.eat_sym(Sym::RBrace) {
+                return Ok(Ast::Object(entries));
+            }
+            self.expect_sym(Sym::Comma, "',' or '}'")?;
+        }
+    }
+
+    fn parse_object_entry(&mut self) -> Result<ObjectEntry, AstParseError> {
+        let (key, shorthand) = match self.peek().cloned() {
+            Some(TokenKind::Var(name)) => {
+                self.pos += 1;
+                let key = Ast::Literal(Value::String(name.clone()));
+                (key, Some(Ast::Var(name)))
+            }
+            Some(TokenKind::Ident(name)) => {
+                self.pos += 1;
+                let key = Ast::Literal(Value::String(name));
+                let value = Ast::Index(Box::new(Ast::Identity), Box::new(key.clone()));
+                (key, Some(value))
+            }
+            Some(TokenKind::Number(_)) => {
+                return Err(self.error("Object keys must be strings"));
+            }
+            Some(TokenKind::Str(_) | TokenKind::Format(_)) => {
+                let key = self.parse_term()?;
+                let value = Ast::Index(Box::new(Ast::Identity), Box::new(key.clone()));
+                (key, Some(value))
+            }
+            Some(TokenKind::Sym(Sym::LParen)) => {
+                self.pos += 1;
+                let key = self.parse_pipe()?;
+                self.expect_sym(Sym::RParen, "')'")?;
+                (key, None)
+            }
+            _ => return Err(self.error("Expected an object key")),
+        };
+        if self.eat_sym(Sym::Colon) {
+            let value = self.parse_pipe_no_comma()?;
+            return Ok(ObjectEntry { key, value });
+        }
+        let Some(value) = shorthand else {
+            return Err(self.error("Expected ':' after computed key"));
+        };
+        Ok(ObjectEntry { key, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let ast = parse(".a, .b | .c // 1").unwrap();
+        let Ast::Pipe(left, right) = ast else {
+            unreachable!("expected a pipe at the top");
+        };
+        assert!(matches!(*left, Ast::Comma(..)));
+        assert!(matches!(*right, Ast::Alt(..)));
+
+        let ast = parse("1 + 2 * 3 == 7 and true").unwrap();
+        assert!(matches!(ast, Ast::And(..)));
+    }
+
+    #[test]
+    fn test_bind_takes_rest_of_pipe() {
+        let ast = parse(".[] as [$a, $b] | $a + $b").unwrap();
+        let Ast::Bind(_, Pattern::Array(items), body) = ast else {
+            unreachable!("expected a binding");
+        };
+        assert_eq!(items.len(), 2);
+        assert!(matches!(*body, Ast::Binary(BinOp::Add, ..)));
+    }
+
+    #[test]
+    fn test_definitions_and_calls() {
+        let ast = parse("def inc($n): . + $n; [.[] | inc(1)]").unwrap();
+        let Ast::Def(defs, _) = ast else {
+            unreachable!("expected definitions");
+        };
+        assert_eq!(defs[0].params, ["n"]);
+        assert!(matches!(defs[0].body, Ast::Bind(..)));
+    }
+
+    #[test]
+    fn test_errors_have_positions() {
+        let error = parse(".a | map(.b").unwrap_err();
+        assert!(error.message.contains("end of program"));
+
+        let error = parse("if . then 1 else 2").unwrap_err();
+        assert!(error.message.contains("'end'"));
+
+        let error = parse(".a | {1: 2}").unwrap_err();
+        assert_eq!(error.range.unwrap().start.offset, 6);
+
+        let error = parse(r#""x\(.a |)""#).unwrap_err();
+        assert_eq!(error.range.unwrap().start.offset, 8);
+    }
+}
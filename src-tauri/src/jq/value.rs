@@ -0,0 +1,605 @@
+//! jq value semantics on `serde_json::Value`: ordering, arithmetic,
+//! indexing, and path updates
+
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+
+use super::eval::{error, number, Flow};
+use super::parser::BinOp;
+
+/// Largest array index `setpath` will grow an array to.
+const MAX_ARRAY_INDEX: usize = 10_000_000;
+
+pub const fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// jq truthiness: everything but `false` and `null`
+pub const fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+pub fn as_f64(value: &Value) -> Option<f64> {
+    value.as_f64()
+}
+
+/// Compact JSON text of `value`
+pub fn to_json(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// `tostring`: strings as-is, everything else as JSON
+pub fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => to_json(other),
+    }
+}
+
+/// Value preview for error messages, truncated like jq's.
+pub fn preview(value: &Value) -> String {
+    let json = to_json(value);
+    if json.chars().count() > 11 {
+        format!("{}...", json.chars().take(10).collect::<String>())
+    } else {
+        json
+    }
+}
+
+const fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    }
+}
+
+/// jq's total order: null < false < true < numbers < strings < arrays <
+/// objects; objects compare their sorted key sets first, then values.
+pub fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (
+                x.as_f64().unwrap_or(f64::NAN),
+                y.as_f64().unwrap_or(f64::NAN),
+            );
+            x.partial_cmp(&y)
+                .unwrap_or_else(|| x.is_nan().cmp(&y.is_nan()).reverse())
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(a, b)| compare(a, b))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| x.len().cmp(&y.len())),
+        (Value::Object(x), Value::Object(y)) => x.keys().cmp(y.keys()).then_with(|| {
+            x.values()
+                .zip(y.values())
+                .map(|(a, b)| compare(a, b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        }),
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+fn binop_error(a: &Value, b: &Value, verb: &str) -> Flow {
+    error(format!(
+        "{} ({}) and {} ({}) cannot be {verb}",
+        type_name(a),
+        preview(a),
+        type_name(b),
+        preview(b)
+    ))
+}
+
+/// Recursive object merge used by `*`
+fn deep_merge(mut left: Map<String, Value>, right: Map<String, Value>) -> Map<String, Value> {
+    for (key, value) in right {
+        let merged = match (left.remove(&key), value) {
+            (Some(Value::Object(l)), Value::Object(r)) => Value::Object(deep_merge(l, r)),
+            (_, value) => value,
+        };
+        left.insert(key, merged);
+    }
+    left
+}
+
+/// Unary minus
+pub fn negate(value: &Value) -> Result<Value, Flow> {
+    as_f64(value).map(|n| number(-n)).ok_or_else(|| {
+        error(format!(
+            "{} ({}) cannot be negated",
+            type_name(value),
+            preview(value)
+        ))
+    })
+}
+
+/// Apply an arithmetic or comparison operator.
+pub fn binop(op: BinOp, a: Value, b: Value) -> Result<Value, Flow> {
+    let numbers = as_f64(&a).zip(as_f64(&b));
+    Ok(match op {
+        BinOp::Eq => Value::Bool(compare(&a, &b).is_eq()),
+        BinOp::NotEq => Value::Bool(compare(&a, &b).is_ne()),
+        BinOp::Lt => Value::Bool(compare(&a, &b).is_lt()),
+        BinOp::LtEq => Value::Bool(compare(&a, &b).is_le()),
+        BinOp::Gt => Value::Bool(compare(&a, &b).is_gt()),
+        BinOp::GtEq => Value::Bool(compare(&a, &b).is_ge()),
+        BinOp::Add => match (a, b) {
+            (Value::Null, other) | (other, Value::Null) => other,
+            (Value::String(x), Value::String(y)) => Value::String(x + &y),
+            (Value::Array(mut x), Value::Array(y)) => {
+                x.extend(y);
+                Value::Array(x)
+            }
+            (Value::Object(mut x), Value::Object(y)) => {
+                x.extend(y);
+                Value::Object(x)
+            }
+            (a, b) => match numbers {
+                Some((x, y)) => number(x + y),
+                None => return Err(binop_error(&a, &b, "added")),
+            },
+        },
+        BinOp::Sub => match (a, b) {
+            (Value::Array(x), Value::Array(y)) => Value::Array(
+                x.into_iter()
+                    .filter(|item| !y.iter().any(|other| compare(item, other).is_eq()))
+                    .collect(),
+            ),
+            (a, b) => match numbers {
+                Some((x, y)) => number(x - y),
+                None => return Err(binop_error(&a, &b, "subtracted")),
+            },
+        },
+        BinOp::Mul => match (a, b) {
+            (Value::Object(x), Value::Object(y)) => Value::Object(deep_merge(x, y)),
+            (Value::String(s), Value::Number(n)) | (Value::Number(n), Value::String(s)) => {
+                let times = n.as_f64().unwrap_or(0.0);
+                if times > 0.0 {
+                    repeat(&s, times)?
+                } else {
+                    Value::Null
+                }
+            }
+            (a, b) => match numbers {
+                Some((x, y)) => number(x * y),
+                None => return Err(binop_error(&a, &b, "multiplied")),
+            },
+        },
+        BinOp::Div => match (a, b) {
+            (Value::String(x), Value::String(y)) => split(&x, &y),
+            (a, b) => match numbers {
+                Some((_, 0.0)) => {
+                    return Err(binop_error(&a, &b, "divided because the divisor is zero"));
+                }
+                Some((x, y)) => number(x / y),
+                None => return Err(binop_error(&a, &b, "divided")),
+            },
+        },
+        BinOp::Mod => match numbers {
+            Some((x, y)) => {
+                let (x, y) = (truncate(x), truncate(y));
+                match x.checked_rem(y.checked_abs().unwrap_or(i64::MAX)) {
+                    Some(rem) if y != 0 => Value::from(rem),
+                    _ => return Err(binop_error(&a, &b, "divided because the divisor is zero")),
+                }
+            }
+            None => return Err(binop_error(&a, &b, "divided")),
+        },
+    })
+}
+
+#[allow(clippy::cast_possible_truncation)] // jq truncates `%` operands to integers
+const fn truncate(n: f64) -> i64 {
+    n as i64
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn repeat(s: &str, times: f64) -> Result<Value, Flow> {
+    let count = times.ceil() as usize;
+    if s.len().saturating_mul(count) > 256 * 1024 * 1024 {
+        return Err(error("Repeat string result too long"));
+    }
+    Ok(Value::String(s.repeat(count)))
+}
+
+/// `a / b` on strings
+pub fn split(s: &str, separator: &str) -> Value {
+    if s.is_empty() {
+        return Value::Array(Vec::new());
+    }
+    let parts: Vec<Value> = if separator.is_empty() {
+        s.chars().map(|c| Value::String(c.to_string())).collect()
+    } else {
+        s.split(separator)
+            .map(|part| Value::String(part.to_string()))
+            .collect()
+    };
+    Value::Array(parts)
+}
+
+/// Resolve a possibly negative, possibly fractional array index.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn array_index(n: f64, len: usize) -> Option<usize> {
+    let n = n.floor();
+    let index = if n < 0.0 { len as f64 + n } else { n };
+    (index >= 0.0 && index < len as f64).then_some(index as usize)
+}
+
+fn index_error(target: &Value, key: &Value) -> Flow {
+    match key {
+        Value::String(key) => error(format!("Cannot index {} with \"{key}\"", type_name(target))),
+        key => error(format!(
+            "Cannot index {} with {}",
+            type_name(target),
+            type_name(key)
+        )),
+    }
+}
+
+/// `target[key]`
+pub fn index(target: &Value, key: &Value) -> Result<Value, Flow> {
+    match (target, key) {
+        (Value::Object(map), Value::String(k)) => Ok(map.get(k).cloned().unwrap_or(Value::Null)),
+        (Value::Array(items), Value::Number(n)) => Ok(n
+            .as_f64()
+            .and_then(|n| array_index(n, items.len()))
+            .map_or(Value::Null, |i| items[i].clone())),
+        (Value::Null, Value::String(_) | Value::Number(_) | Value::Object(_)) => Ok(Value::Null),
+        (Value::Array(items), Value::Array(needle)) => Ok(indices_of(items, needle)),
+        (Value::Array(_) | Value::String(_), Value::Object(range)) => {
+            let bound = |name: &str| range.get(name).cloned().unwrap_or(Value::Null);
+            slice(target, &bound("start"), &bound("end"))
+        }
+        _ => Err(index_error(target, key)),
+    }
+}
+
+/// Positions where `needle` occurs as a contiguous run in `items`
+pub fn indices_of(items: &[Value], needle: &[Value]) -> Value {
+    if needle.is_empty() {
+        return Value::Null;
+    }
+    Value::Array(
+        items
+            .windows(needle.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                window
+                    .iter()
+                    .zip(needle)
+                    .all(|(a, b)| compare(a, b).is_eq())
+            })
+            .map(|(i, _)| Value::from(i))
+            .collect(),
+    )
+}
+
+/// Clamp slice bounds to `0..=len`.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn slice_bounds(from: &Value, to: &Value, len: usize) -> Result<(usize, usize), Flow> {
+    let resolve = |bound: &Value, default: usize, round_up: bool| -> Result<usize, Flow> {
+        match bound {
+            Value::Null => Ok(default),
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or(0.0);
+                let n = if round_up { n.ceil() } else { n.floor() };
+                let n = if n < 0.0 { len as f64 + n } else { n };
+                Ok(n.clamp(0.0, len as f64) as usize)
+            }
+            _ => Err(error(
+                "Start and end indices of an array slice must be numbers",
+            )),
+        }
+    };
+    let start = resolve(from, 0, false)?;
+    let end = resolve(to, len, true)?;
+    Ok((start, end.max(start)))
+}
+
+/// `target[from:to]` on arrays and strings (by code point)
+pub fn slice(target: &Value, from: &Value, to: &Value) -> Result<Value, Flow> {
+    match target {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let (start, end) = slice_bounds(from, to, items.len())?;
+            Ok(Value::Array(items[start..end].to_vec()))
+        }
+        Value::String(s) => {
+            let (start, end) = slice_bounds(from, to, s.chars().count())?;
+            Ok(Value::String(
+                s.chars().skip(start).take(end - start).collect(),
+            ))
+        }
+        other => Err(error(format!(
+            "Cannot index {} with object",
+            type_name(other)
+        ))),
+    }
+}
+
+/// Elements of an array or values of an object
+pub fn iterate(value: Value) -> Result<Vec<Value>, Flow> {
+    match value {
+        Value::Array(items) => Ok(items),
+        Value::Object(map) => Ok(map.into_iter().map(|(_, v)| v).collect()),
+        other => Err(error(format!(
+            "Cannot iterate over {}{}",
+            type_name(&other),
+            if other.is_null() {
+                String::new()
+            } else {
+                format!(" ({})", preview(&other))
+            }
+        ))),
+    }
+}
+
+/// Elements or values paired with their index or key
+pub fn entries(value: Value) -> Result<Vec<(Value, Value)>, Flow> {
+    match value {
+        Value::Array(items) => Ok(items
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (Value::from(i), v))
+            .collect()),
+        Value::Object(map) => Ok(map
+            .into_iter()
+            .map(|(k, v)| (Value::String(k), v))
+            .collect()),
+        other => iterate(other).map(|_| Vec::new()),
+    }
+}
+
+/// `getpath(path)`: null where the path runs off the value
+pub fn getpath(value: &Value, path: &[Value]) -> Result<Value, Flow> {
+    let mut current = value.clone();
+    for key in path {
+        if current.is_null() {
+            return Ok(Value::Null);
+        }
+        current = index(&current, key)?;
+    }
+    Ok(current)
+}
+
+/// `setpath(path; new)`, creating objects and arrays along the way
+pub fn setpath(root: Value, path: &[Value], new: Value) -> Result<Value, Flow> {
+    let Some((key, rest)) = path.split_first() else {
+        return Ok(new);
+    };
+    match (root, key) {
+        (Value::Null, Value::String(_)) => setpath(Value::Object(Map::new()), path, new),
+        (Value::Null, Value::Number(_) | Value::Object(_)) => {
+            setpath(Value::Array(Vec::new()), path, new)
+        }
+        (Value::Object(mut map), Value::String(k)) => {
+            let child = map.remove(k).unwrap_or(Value::Null);
+            map.insert(k.clone(), setpath(child, rest, new)?);
+            Ok(Value::Object(map))
+        }
+        (Value::Array(mut items), Value::Number(n)) => {
+            let n = n.as_f64().unwrap_or(0.0);
+            let i = set_index(n, items.len())?;
+            if i >= items.len() {
+                items.resize(i + 1, Value::Null);
+            }
+            let child = std::mem::take(&mut items[i]);
+            items[i] = setpath(child, rest, new)?;
+            Ok(Value::Array(items))
+        }
+        (Value::Array(mut items), Value::Object(range)) => {
+            let bound = |name: &str| range.get(name).cloned().unwrap_or(Value::Null);
+            let (start, end) = slice_bounds(&bound("start"), &bound("end"), items.len())?;
+            let current = Value::Array(items[start..end].to_vec());
+            let Value::Array(replacement) = setpath(current, rest, new)? else {
+                return Err(error(
+                    "A slice of an array can only be assigned another array",
+                ));
+            };
+            items.splice(start..end, replacement);
+            Ok(Value::Array(items))
+        }
+        (root, key) => Err(index_error(&root, key)),
+    }
+}
+
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn set_index(n: f64, len: usize) -> Result<usize, Flow> {
+    let n = n.floor();
+    let index = if n < 0.0 { len as f64 + n } else { n };
+    if index < 0.0 {
+        return Err(error("Out of bounds negative array index"));
+    }
+    if index > MAX_ARRAY_INDEX as f64 {
+        return Err(error("Array index too large"));
+    }
+    Ok(index as usize)
+}
+
+/// Delete one path; missing paths are ignored.
+fn delpath(root: Value, path: &[Value]) -> Result<Value, Flow> {
+    let Some((key, rest)) = path.split_first() else {
+        return Ok(Value::Null);
+    };
+    if root.is_null() {
+        return Ok(Value::Null);
+    }
+    if !rest.is_empty() {
+        let child = index(&root, key)?;
+        if child.is_null() {
+            return Ok(root);
+        }
+        return setpath(root, std::slice::from_ref(key), delpath(child, rest)?);
+    }
+    match (root, key) {
+        (Value::Object(mut map), Value::String(k)) => {
+            map.remove(k);
+            Ok(Value::Object(map))
+        }
+        (Value::Array(mut items), Value::Number(n)) => {
+            if let Some(i) = n.as_f64().and_then(|n| array_index(n, items.len())) {
+                items.remove(i);
+            }
+            Ok(Value::Array(items))
+        }
+        (Value::Array(mut items), Value::Object(range)) => {
+            let bound = |name: &str| range.get(name).cloned().unwrap_or(Value::Null);
+            let (start, end) = slice_bounds(&bound("start"), &bound("end"), items.len())?;
+            items.drain(start..end);
+            Ok(Value::Array(items))
+        }
+        (root, key) => Err(error(format!(
+            "Cannot delete field at {} of {}",
+            type_name(key),
+            type_name(&root)
+        ))),
+    }
+}
+
+/// `delpaths(paths)`: deepest and last paths first so earlier deletions
+/// do not shift later ones.
+pub fn delpaths(root: Value, mut paths: Vec<Value>) -> Result<Value, Flow> {
+    paths.sort_by(|a, b| compare(b, a));
+    let mut root = root;
+    for path in paths {
+        let Value::Array(path) = path else {
+            return Err(error("Path must be specified as an array"));
+        };
+        root = delpath(root, &path)?;
+    }
+    Ok(root)
+}
+
+/// `contains(b)`: substring for strings, recursive containment otherwise.
+/// Only the top-level types must agree; nested mismatches are just `false`.
+pub fn contains(a: &Value, b: &Value) -> Result<bool, Flow> {
+    if type_rank(a) == type_rank(b) || (a.is_boolean() && b.is_boolean()) {
+        Ok(contained(a, b))
+    } else {
+        Err(error(format!(
+            "{} ({}) and {} ({}) cannot have their containment checked",
+            type_name(a),
+            preview(a),
+            type_name(b),
+            preview(b)
+        )))
+    }
+}
+
+fn contained(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(x), Value::Object(y)) => y
+            .iter()
+            .all(|(key, value)| x.get(key).is_some_and(|inner| contained(inner, value))),
+        (Value::Array(x), Value::Array(y)) => y
+            .iter()
+            .all(|needle| x.iter().any(|item| contained(item, needle))),
+        (Value::String(x), Value::String(y)) => x.contains(y.as_str()),
+        (a, b) => a == b || (a.is_number() && b.is_number() && compare(a, b).is_eq()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ordering_and_equality() {
+        let mut values = vec![
+            json!({"a": 1}),
+            json!([1]),
+            json!("a"),
+            json!(2),
+            json!(true),
+            json!(null),
+        ];
+        values.sort_by(compare);
+        assert_eq!(
+            values,
+            vec![
+                json!(null),
+                json!(true),
+                json!(2),
+                json!("a"),
+                json!([1]),
+                json!({"a": 1})
+            ]
+        );
+        assert!(compare(&json!(1), &json!(1.0)).is_eq());
+        assert!(compare(&json!({"a": 2}), &json!({"b": 1})).is_lt());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(binop(BinOp::Add, json!(null), json!(1)).unwrap(), json!(1));
+        assert_eq!(
+            binop(BinOp::Sub, json!([1, 2, 1, 3]), json!([1])).unwrap(),
+            json!([2, 3])
+        );
+        assert_eq!(
+            binop(BinOp::Mul, json!({"a": {"b": 1}}), json!({"a": {"c": 2}})).unwrap(),
+            json!({"a": {"b": 1, "c": 2}})
+        );
+        assert_eq!(
+            binop(BinOp::Div, json!("a,b"), json!(",")).unwrap(),
+            json!(["a", "b"])
+        );
+        assert_eq!(binop(BinOp::Mod, json!(-5), json!(3)).unwrap(), json!(-2));
+        assert!(binop(BinOp::Div, json!(1), json!(0)).is_err());
+        assert!(binop(BinOp::Add, json!({}), json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_paths() {
+        let value = setpath(Value::Null, &[json!("a"), json!(2)], json!(true)).unwrap();
+        assert_eq!(value, json!({"a": [null, null, true]}));
+        assert_eq!(
+            getpath(&value, &[json!("a"), json!(-1)]).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            getpath(&value, &[json!("x"), json!("y")]).unwrap(),
+            json!(null)
+        );
+
+        let value = delpaths(
+            json!([1, 2, 3, {"k": 1}]),
+            vec![json!([0]), json!([3, "k"])],
+        )
+        .unwrap();
+        assert_eq!(value, json!([2, 3, {}]));
+        assert_eq!(
+            slice(&json!("héllo"), &json!(1), &json!(-1)).unwrap(),
+            json!("éll")
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Secure export of generated keys to disk.
+//!
+//! `save_key_material` writes a private key readable by its owner alone:
+//! mode 0600 on Unix, and on Windows an ACL that grants only the current
+//! user, set with `icacls` as the OpenSSH for Windows documentation
+//! recommends. The optional public key goes to the `.pub` sibling with
+//! ordinary permissions, the layout `ssh-keygen` uses.
+//!
+//! Each file is written to a temporary sibling that already carries its
+//! final permissions and then renamed into place, so the key is never
+//! readable by others, not even briefly, and a replaced key file does not
+//! keep the old file's permissions. Existing files are only replaced once
+//! the caller confirms with [`SaveMode::Overwrite`].
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// What to do when a destination file already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveMode {
+    /// Write only new files; report existing ones instead.
+    Create,
+    /// Replace existing files; the user has confirmed.
+    Overwrite,
+}
+
+/// Outcome of `save_key_material`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "status",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SaveKeyOutcome {
+    /// The files were written.
+    Saved {
+        /// Private key file.
+        path: String,
+        /// Public key file, when a public key was given.
+        public_path: Option<String>,
+    },
+    /// Nothing was written: these files exist, and replacing them needs
+    /// confirmation.
+    Exists {
+        /// Existing destination files.
+        paths: Vec<String>,
+    },
+}
+
+/// Write private key material to `path` with owner-only permissions.
+///
+/// # Arguments
+///
+/// - `content` — the private key, usually PEM or OpenSSH armor.
+/// - `path` — absolute destination path.
+/// - `mode` — `create` reports existing files instead of replacing them;
+///   `overwrite` replaces them.
+/// - `public_key` — when given, written to `<path>.pub`.
+///
+/// # Errors
+///
+/// Returns a `String` for empty content, a relative path, or a failed
+/// write or permission change.
+// Runs off the main thread: the rename and, on Windows, `icacls` touch
+// the disk.
+#[tauri::command(async)]
+pub fn save_key_material(
+    content: String,
+    path: String,
+    mode: SaveMode,
+    public_key: Option<String>,
+) -> Result<SaveKeyOutcome, String> {
+    save(&content, Path::new(&path), mode, public_key.as_deref())
+}
+
+fn save(
+    content: &str,
+    path: &Path,
+    mode: SaveMode,
+    public_key: Option<&str>,
+) -> Result<SaveKeyOutcome, String> {
+    if content.trim().is_empty() {
+        return Err("Key material is empty".to_string());
+    }
+    if !path.is_absolute() {
+        return Err("Path must be absolute".to_string());
+    }
+    let public_path = public_key.map(|_| public_sibling(path)).transpose()?;
+
+    if mode == SaveMode::Create {
+        let paths: Vec<String> = std::iter::once(path)
+            .chain(public_path.as_deref())
+            .filter(|path| path.exists())
+            .map(|path| path.display().to_string())
+            .collect();
+        if !paths.is_empty() {
+            return Ok(SaveKeyOutcome::Exists { paths });
+        }
+    }
+
+    write_file(path, content.as_bytes(), true)?;
+    if let (Some(public_path), Some(public_key)) = (&public_path, public_key) {
+        write_file(public_path, public_key.as_bytes(), false)?;
+    }
+    Ok(SaveKeyOutcome::Saved {
+        path: path.display().to_string(),
+        public_path: public_path.map(|path| path.display().to_string()),
+    })
+}
+
+/// `id_ed25519` → `id_ed25519.pub`
+fn public_sibling(path: &Path) -> Result<PathBuf, String> {
+    let mut name = path
+        .file_name()
+        .ok_or("Path has no file name")?
+        .to_os_string();
+    name.push(".pub");
+    Ok(path.with_file_name(name))
+}
+
+/// Write `contents` to a temporary sibling of `path` and rename it into
+/// place
+fn write_file(path: &Path, contents: &[u8], private: bool) -> Result<(), String> {
+    let name = path.file_name().ok_or("Path has no file name")?;
+    let temp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        uuid::Uuid::new_v4().simple()
+    ));
+    let result = write_temp(&temp, contents, private).and_then(|()| {
+        fs::rename(&temp, path).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_temp(temp: &Path, contents: &[u8], private: bool) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(if private { 0o600 } else { 0o644 });
+    }
+    let mut file = options
+        .open(temp)
+        .map_err(|e| format!("Failed to create {}: {e}", temp.display()))?;
+
+    // The file is still empty while it has the directory's inherited ACL
+    #[cfg(windows)]
+    if private {
+        restrict_to_owner(temp)?;
+    }
+    #[cfg(not(windows))]
+    let _ = private;
+
+    file.write_all(contents)
+        .and_then(|()| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {e}", temp.display()))
+}
+
+/// Replace the inherited ACL of `path` with full control for the current
+/// user alone
+#[cfg(windows)]
+fn restrict_to_owner(path: &Path) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+
+    /// Keeps `icacls` from flashing a console window
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let user = std::env::var("USERNAME").map_err(|_| "Cannot determine the current user")?;
+    let user = match std::env::var("USERDOMAIN") {
+        Ok(domain) => format!("{domain}\\{user}"),
+        Err(_) => user,
+    };
+    let output = std::process::Command::new("icacls")
+        .arg(path)
+        .args(["/inheritance:r", "/grant:r"])
+        .arg(format!("{user}:F"))
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to run icacls: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to restrict permissions: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_confirm_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id_ed25519");
+
+        let saved = save("PRIVATE", &path, SaveMode::Create, Some("ssh-ed25519 AAAA")).unwrap();
+        let public_path = dir.path().join("id_ed25519.pub");
+        assert_eq!(
+            saved,
+            SaveKeyOutcome::Saved {
+                path: path.display().to_string(),
+                public_path: Some(public_path.display().to_string()),
+            }
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "PRIVATE");
+        assert_eq!(
+            fs::read_to_string(&public_path).unwrap(),
+            "ssh-ed25519 AAAA"
+        );
+
+        // Nothing is replaced until the overwrite is confirmed
+        let exists = save("NEW", &path, SaveMode::Create, Some("ssh-ed25519 BBBB")).unwrap();
+        assert_eq!(
+            exists,
+            SaveKeyOutcome::Exists {
+                paths: vec![
+                    path.display().to_string(),
+                    public_path.display().to_string()
+                ],
+            }
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "PRIVATE");
+
+        save("NEW", &path, SaveMode::Overwrite, None).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "NEW");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        assert!(save(" ", &path, SaveMode::Overwrite, None).is_err());
+        assert!(save("KEY", Path::new("id_rsa"), SaveMode::Create, None).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_key_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id_rsa");
+        fs::write(&path, "OLD").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        save("KEY", &path, SaveMode::Overwrite, Some("ssh-rsa AAAA")).unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(&dir.path().join("id_rsa.pub")) & 0o600, 0o600);
+    }
+}
@@ -0,0 +1,140 @@
+//! OS keychain storage for generated secrets.
+//!
+//! Passwords, passphrases, and private keys produced by the generators
+//! can be kept in the platform credential store (the macOS Keychain,
+//! Windows Credential Manager, or the freedesktop Secret Service on
+//! Linux) instead of the frontend's state. The frontend keeps only a
+//! secret's kind and name, and fetches the secret back when it is needed.
+//!
+//! Items are stored under the app identifier as the service, with
+//! `<kind>:<name>` as the account, so they are easy to find and remove
+//! in the platform's own keychain app.
+
+use keyring::Entry;
+use serde::Deserialize;
+use tauri::AppHandle;
+
+/// Longest accepted secret name, in characters.
+const MAX_NAME_LENGTH: usize = 128;
+
+/// What a stored secret is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretKind {
+    Password,
+    Passphrase,
+    PrivateKey,
+}
+
+impl SecretKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Password => "password",
+            Self::Passphrase => "passphrase",
+            Self::PrivateKey => "private-key",
+        }
+    }
+}
+
+/// Store `secret` under `name`, replacing any secret already stored there.
+///
+/// # Errors
+///
+/// Returns a `String` for an invalid name, an empty secret, or a
+/// credential store that refuses the item, for instance a private key
+/// beyond Windows Credential Manager's 2560-byte limit.
+// Runs off the main thread: the store may wait on an unlock prompt.
+#[tauri::command(async)]
+pub fn keychain_store(
+    kind: SecretKind,
+    name: String,
+    secret: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    if secret.is_empty() {
+        return Err("Secret is empty".to_string());
+    }
+    entry(&app, kind, &name)?
+        .set_password(&secret)
+        .map_err(describe)
+}
+
+/// Fetch the secret stored under `name`, or `None` when there is none.
+///
+/// # Errors
+///
+/// Returns a `String` for an invalid name or an unavailable or locked
+/// credential store.
+#[tauri::command(async)]
+pub fn keychain_retrieve(
+    kind: SecretKind,
+    name: String,
+    app: AppHandle,
+) -> Result<Option<String>, String> {
+    match entry(&app, kind, &name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(describe(e)),
+    }
+}
+
+/// Remove the secret stored under `name`; `false` when there was none.
+///
+/// # Errors
+///
+/// Returns a `String` for an invalid name or an unavailable or locked
+/// credential store.
+#[tauri::command(async)]
+pub fn keychain_delete(kind: SecretKind, name: String, app: AppHandle) -> Result<bool, String> {
+    match entry(&app, kind, &name)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(describe(e)),
+    }
+}
+
+fn entry(app: &AppHandle, kind: SecretKind, name: &str) -> Result<Entry, String> {
+    Entry::new(&app.config().identifier, &account(kind, name)?).map_err(describe)
+}
+
+/// Keychain account of a secret: its kind and trimmed name
+fn account(kind: SecretKind, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name is required".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!("Name must be at most {MAX_NAME_LENGTH} characters"));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Name may not contain control characters".to_string());
+    }
+    Ok(format!("{}:{name}", kind.as_str()))
+}
+
+fn describe(error: keyring::Error) -> String {
+    match error {
+        keyring::Error::NoStorageAccess(e) => format!("Keychain is not available: {e}"),
+        keyring::Error::TooLong(field, limit) => {
+            format!("The {field} is too long for this keychain (limit {limit})")
+        }
+        e => format!("Keychain error: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account() {
+        assert_eq!(
+            account(SecretKind::PrivateKey, " deploy key ").unwrap(),
+            "private-key:deploy key"
+        );
+        assert_eq!(account(SecretKind::Password, "db").unwrap(), "password:db");
+        assert!(account(SecretKind::Passphrase, "  ").is_err());
+        assert!(account(SecretKind::Passphrase, "a\nb").is_err());
+        assert!(account(SecretKind::Password, &"x".repeat(MAX_NAME_LENGTH + 1)).is_err());
+    }
+}
@@ -46,40 +46,89 @@
 //! This library provides the Rust backend for the Kogu desktop application,
 //! including AST parsing functionality for JSON, YAML, XML, and SQL.
 
+mod actions;
+mod app_backup;
 mod archive_inspect;
 mod ast;
+mod benchmark;
 mod cancellation;
+mod catalog;
+mod checksum;
+pub mod cli;
+mod clipboard_history;
+mod connectivity_check;
+mod crash_report;
 mod dns_lookup;
+mod document;
 mod drive_info;
 mod duplicate_finder;
+mod env_analyzer;
+mod error_codes;
 mod file_inspect;
 mod file_watch;
 mod folder_tree;
 mod generators;
+mod global_search;
+mod grpc_client;
 mod hash_batch;
 mod hash_text;
 mod hex_editor;
+mod history;
+mod hmac_digest;
+mod id_generator;
+mod ingest;
+mod jq;
+mod key_export;
+mod keychain;
+mod logging;
+mod mcp;
 #[cfg(target_os = "macos")]
 mod menu;
+mod mock_server;
 mod network;
+mod network_health;
+mod qr;
+mod random_generator;
+mod redaction;
 mod rest_client;
+mod robots_sitemap;
+mod scheduler;
 mod settings;
+mod ssh_key_list;
 mod string_compress;
+mod system_info;
+mod tcp_latency;
+mod tls_capabilities;
 mod tls_inspect;
+mod update_check;
+mod uuid_generator;
 mod webhook;
 mod websocket;
+mod worker_protocol;
 
 use tauri::Manager;
 #[cfg(any(target_os = "macos", target_os = "windows"))]
 use tauri_plugin_decorum::WebviewWindowExt;
 
-use ast::{AstLanguage, AstParseResult};
+use ast::{AstLanguage, AstParseResponse, AstWireFormat};
 use generators::{
     bcrypt::{BcryptCostInfo, BcryptHashResult, BcryptVerifyResult},
     cli::CliAvailability,
-    gpg::{GpgKeyOptions, GpgKeyResult},
-    ssh::{SshKeyOptions, SshKeyResult},
+    gpg::{
+        GpgKeyOptions, GpgKeyResult, PgpKeyInfo, PgpMessageOperation, PgpMessageOptions,
+        PgpMessageResult,
+    },
+    jwt::{DecodedJwt, JwtAlgorithm, JwtVerifyResult},
+    otp::{OtpAlgorithm, OtpCodes, OtpParams, OtpVerifyResult},
+    password_hash::{PasswordHashInfo, PasswordHashVerifyResult},
+    signing::{SignOptions, SignResult, SignatureVerifyResult, SigningInput, VerifyOptions},
+    ssh::{
+        SshCertInfo, SshCertSignOptions, SshCertSignResult, SshKeyConvertOptions,
+        SshKeyConvertResult, SshKeyInfo, SshKeyOptions, SshKeyResult,
+    },
+    symmetric::{SymmetricFileResult, SymmetricOptions, SymmetricResult},
     worker::WorkerProcessState,
+    x509::{X509Options, X509Output, X509Result},
 };
 use network::{
     DiscoveryEvent, DiscoveryEventSink, DiscoveryMethod, DiscoveryOptions, MdnsDiscoveryRequest,
@@ -95,10 +144,30 @@ fn greet(name: &str) -> String {
 // Worker Commands (with process isolation for true cancellation)
 // =============================================================================
 
-/// Cancel any ongoing worker operation by killing the worker process
+/// Cancel a worker job by killing its worker process
+///
+/// Each worker command takes an optional `job_id`; cancelling with that ID
+/// stops only that job, and cancelling without one stops every job.
+#[tauri::command]
+fn cancel_worker_operation(
+    job_id: Option<String>,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> bool {
+    job_id.map_or_else(|| state.kill_all(), |id| state.kill(&id))
+}
+
+/// Set how many warm workers to keep, and start them
+///
+/// Warm workers answer requests without the start-up delay of a fresh
+/// process; 0 spawns a new worker for every request instead.
 #[tauri::command]
-fn cancel_worker_operation(state: tauri::State<'_, WorkerProcessState>) -> bool {
-    state.kill()
+fn set_worker_pool_size(
+    size: usize,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<(), String> {
+    state.set_pool_size(size).map_err(|e| e.to_string())?;
+    state.warm(&app).map_err(|e| e.to_string())
 }
 
 // =============================================================================
@@ -110,10 +179,11 @@ fn cancel_worker_operation(state: tauri::State<'_, WorkerProcessState>) -> bool
 async fn generate_bcrypt_hash(
     password: String,
     cost: u32,
+    job_id: Option<String>,
     app: tauri::AppHandle,
     state: tauri::State<'_, WorkerProcessState>,
 ) -> Result<BcryptHashResult, String> {
-    generators::bcrypt::generate_hash_isolated(&app, password, cost, &state)
+    generators::bcrypt::generate_hash_isolated(&app, password, cost, &state, job_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -123,10 +193,11 @@ async fn generate_bcrypt_hash(
 async fn verify_bcrypt_hash(
     password: String,
     hash: String,
+    job_id: Option<String>,
     app: tauri::AppHandle,
     state: tauri::State<'_, WorkerProcessState>,
 ) -> Result<BcryptVerifyResult, String> {
-    generators::bcrypt::verify_hash_isolated(&app, password, hash, &state)
+    generators::bcrypt::verify_hash_isolated(&app, password, hash, &state, job_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -137,6 +208,205 @@ fn get_bcrypt_cost_info(cost: u32) -> BcryptCostInfo {
     generators::bcrypt::get_cost_info(cost)
 }
 
+// =============================================================================
+// Password Hash Commands
+// =============================================================================
+
+/// Identify a bcrypt, Argon2, scrypt, PBKDF2, or SHA-crypt hash and extract
+/// its parameters
+#[tauri::command]
+fn identify_password_hash(hash: &str) -> Result<PasswordHashInfo, String> {
+    generators::password_hash::identify(hash).map_err(|e| e.to_string())
+}
+
+/// Verify a password against a hash of any format `identify_password_hash`
+/// recognizes (bcrypt is cancellable via process termination)
+#[tauri::command]
+async fn verify_password_hash(
+    password: String,
+    hash: String,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<PasswordHashVerifyResult, String> {
+    generators::password_hash::verify_isolated(&app, password, hash, &state, job_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// JWT Commands
+// =============================================================================
+
+/// Decode a JWT and explain its claims, without checking the signature
+#[tauri::command]
+fn decode_jwt(token: &str) -> Result<DecodedJwt, String> {
+    generators::jwt::decode(token).map_err(|e| e.to_string())
+}
+
+/// Verify the signature of a JWT
+///
+/// # Arguments
+/// * `token` - Compact JWT
+/// * `key` - HMAC secret, PEM public key or certificate, JWK, or JWK Set
+/// * `jwks_url` - URL of a JWK Set, used when no `key` is given
+///
+/// # Returns
+/// Whether the signature matches, and the JWK it was checked against
+#[tauri::command]
+async fn verify_jwt(
+    token: String,
+    key: Option<String>,
+    jwks_url: Option<String>,
+) -> Result<JwtVerifyResult, String> {
+    let result = match (key, jwks_url) {
+        (Some(key), _) => generators::jwt::verify(&token, &key),
+        (None, Some(url)) => generators::jwt::verify_with_jwks_url(&token, &url).await,
+        (None, None) => return Err("A key or JWKS URL is required".to_string()),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Create a signed JWT from a JSON object of claims
+#[tauri::command]
+fn create_jwt(
+    claims: serde_json::Value,
+    algorithm: JwtAlgorithm,
+    key: &str,
+    key_id: Option<String>,
+) -> Result<String, String> {
+    generators::jwt::encode(&claims, algorithm, key, key_id.as_deref()).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// OTP Commands
+// =============================================================================
+
+/// Generate a random base32 secret for HOTP or TOTP
+#[tauri::command]
+fn generate_otp_secret(bytes: Option<usize>) -> Result<String, String> {
+    generators::otp::generate_secret(bytes).map_err(|e| e.to_string())
+}
+
+/// Compute the current and adjacent HOTP or TOTP codes
+#[tauri::command]
+fn generate_otp_codes(params: OtpParams, window: Option<u32>) -> Result<OtpCodes, String> {
+    generators::otp::codes(&params, window).map_err(|e| e.to_string())
+}
+
+/// Check a TOTP code against a secret, allowing for clock drift
+///
+/// # Arguments
+/// * `code` - Code shown by the authenticator; its length sets the digits
+/// * `secret` - Base32 secret
+/// * `algorithm` - HMAC hash, SHA1 when omitted
+/// * `period` - Time step in seconds, 30 when omitted
+/// * `window` - Steps of drift accepted either side, 1 when omitted
+///
+/// # Returns
+/// Whether the code matches, and how many steps it is off
+#[tauri::command]
+fn verify_totp(
+    code: &str,
+    secret: &str,
+    algorithm: Option<OtpAlgorithm>,
+    period: Option<u64>,
+    window: Option<u32>,
+) -> Result<OtpVerifyResult, String> {
+    generators::otp::verify_totp(code, secret, algorithm, period, window).map_err(|e| e.to_string())
+}
+
+/// Read the parameters of an `otpauth://` URI
+#[tauri::command]
+fn parse_otpauth_uri(uri: &str) -> Result<OtpParams, String> {
+    generators::otp::parse_uri(uri).map_err(|e| e.to_string())
+}
+
+/// Write OTP parameters as an `otpauth://` URI
+#[tauri::command]
+fn build_otpauth_uri(params: OtpParams) -> Result<String, String> {
+    generators::otp::build_uri(&params).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// Symmetric Encryption Commands
+// =============================================================================
+
+/// Encrypt text with AES-GCM, AES-CBC, or ChaCha20-Poly1305
+#[tauri::command]
+async fn encrypt_text(text: String, options: SymmetricOptions) -> Result<SymmetricResult, String> {
+    generators::symmetric::encrypt_text(text, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Decrypt ciphertext back to text
+#[tauri::command]
+async fn decrypt_text(
+    ciphertext: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricResult, String> {
+    generators::symmetric::decrypt_text(ciphertext, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Encrypt a file
+///
+/// # Arguments
+/// * `input_path` - File to encrypt
+/// * `output_path` - File the bare ciphertext is written to
+/// * `options` - Cipher, key, and optional IV and associated data
+#[tauri::command]
+async fn encrypt_file(
+    input_path: String,
+    output_path: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricFileResult, String> {
+    generators::symmetric::encrypt_file(input_path, output_path, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Decrypt a file of bare ciphertext
+#[tauri::command]
+async fn decrypt_file(
+    input_path: String,
+    output_path: String,
+    options: SymmetricOptions,
+) -> Result<SymmetricFileResult, String> {
+    generators::symmetric::decrypt_file(input_path, output_path, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// Signing Commands
+// =============================================================================
+
+/// Sign text or a file with a PEM or OpenSSH private key
+///
+/// # Arguments
+/// * `input` - Text, or the path of a file, to sign
+/// * `options` - Scheme, digest, private key, and signature encoding
+#[tauri::command]
+async fn sign_data(input: SigningInput, options: SignOptions) -> Result<SignResult, String> {
+    generators::signing::sign(input, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Verify a signature with a public key or certificate
+#[tauri::command]
+async fn verify_signature(
+    input: SigningInput,
+    options: VerifyOptions,
+) -> Result<SignatureVerifyResult, String> {
+    generators::signing::verify(input, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // =============================================================================
 // SSH Key Commands
 // =============================================================================
@@ -145,10 +415,49 @@ fn get_bcrypt_cost_info(cost: u32) -> BcryptCostInfo {
 #[tauri::command]
 async fn generate_ssh_keypair(
     options: SshKeyOptions,
+    job_id: Option<String>,
     app: tauri::AppHandle,
     state: tauri::State<'_, WorkerProcessState>,
 ) -> Result<SshKeyResult, String> {
-    generators::ssh::generate_key_isolated(&app, options, &state)
+    generators::ssh::generate_key_isolated(&app, options, &state, job_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Convert an SSH key between OpenSSH, PEM, PKCS#8, and RFC 4716
+///
+/// # Arguments
+/// * `options` - Key, its passphrase, target format, and new passphrase
+#[tauri::command]
+async fn convert_ssh_key(options: SshKeyConvertOptions) -> Result<SshKeyConvertResult, String> {
+    generators::ssh::convert_key(options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Show the type, size, and fingerprints of a pasted SSH key
+#[tauri::command]
+async fn inspect_ssh_key(key: String) -> Result<SshKeyInfo, String> {
+    generators::ssh::inspect_key(key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sign a user or host SSH certificate with a CA key, as `ssh-keygen -s`
+///
+/// # Arguments
+/// * `options` - CA key, public key, principals, validity, and options
+#[tauri::command]
+async fn sign_ssh_certificate(options: SshCertSignOptions) -> Result<SshCertSignResult, String> {
+    generators::ssh::sign_certificate(options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List the contents of a `-cert.pub` line, as `ssh-keygen -L`
+#[tauri::command]
+async fn inspect_ssh_certificate(certificate: String) -> Result<SshCertInfo, String> {
+    generators::ssh::inspect_certificate(certificate)
         .await
         .map_err(|e| e.to_string())
 }
@@ -161,10 +470,127 @@ async fn generate_ssh_keypair(
 #[tauri::command]
 async fn generate_gpg_keypair(
     options: GpgKeyOptions,
+    job_id: Option<String>,
     app: tauri::AppHandle,
     state: tauri::State<'_, WorkerProcessState>,
 ) -> Result<GpgKeyResult, String> {
-    generators::gpg::generate_key_isolated(&app, options, &state)
+    generators::gpg::generate_key_isolated(&app, options, &state, job_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Encrypt text to a PGP public key
+#[tauri::command]
+async fn encrypt_gpg_message(
+    options: PgpMessageOptions,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<PgpMessageResult, String> {
+    generators::gpg::process_message_isolated(
+        &app,
+        options,
+        PgpMessageOperation::Encrypt,
+        &state,
+        job_id,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Decrypt an armored PGP message with a secret key
+#[tauri::command]
+async fn decrypt_gpg_message(
+    options: PgpMessageOptions,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<PgpMessageResult, String> {
+    generators::gpg::process_message_isolated(
+        &app,
+        options,
+        PgpMessageOperation::Decrypt,
+        &state,
+        job_id,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Clearsign text, or make a detached signature over it
+#[tauri::command]
+async fn sign_gpg_message(
+    options: PgpMessageOptions,
+    detached: bool,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<PgpMessageResult, String> {
+    let operation = if detached {
+        PgpMessageOperation::DetachSign
+    } else {
+        PgpMessageOperation::Clearsign
+    };
+    generators::gpg::process_message_isolated(&app, options, operation, &state, job_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Verify a clearsigned or signed message, or text against a detached
+/// signature
+#[tauri::command]
+async fn verify_gpg_signature(
+    options: PgpMessageOptions,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<PgpMessageResult, String> {
+    generators::gpg::process_message_isolated(
+        &app,
+        options,
+        PgpMessageOperation::Verify,
+        &state,
+        job_id,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// List the user IDs, subkeys, and capabilities of a pasted PGP public or
+/// private key block
+#[tauri::command]
+fn parse_pgp_key(armored: &str) -> Result<PgpKeyInfo, String> {
+    generators::gpg::parse_key(armored).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// X.509 Commands
+// =============================================================================
+
+/// Generate a key pair and a self-signed certificate (cancellable via process
+/// termination)
+#[tauri::command]
+async fn generate_self_signed_certificate(
+    options: X509Options,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<X509Result, String> {
+    generators::x509::generate_isolated(&app, options, X509Output::Certificate, &state, job_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Generate a key pair and a certificate signing request (cancellable via
+/// process termination)
+#[tauri::command]
+async fn generate_certificate_request(
+    options: X509Options,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, WorkerProcessState>,
+) -> Result<X509Result, String> {
+    generators::x509::generate_isolated(&app, options, X509Output::Csr, &state, job_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -258,21 +684,33 @@ fn parse_targets_for_discovery(targets: &[String]) -> Vec<IpAddr> {
 }
 
 /// Start a network port scan in-process with streaming progress events.
+///
+/// Hosts reach the frontend only as `host_discovered` events and are
+/// appended to the scan history as they arrive; the command itself returns
+/// just the totals.
 #[tauri::command]
 async fn start_network_scan(
     request: ScanRequest,
     scan_id: String,
     app: tauri::AppHandle,
     state: tauri::State<'_, NetworkScannerState>,
-) -> Result<network::types::ScanResults, String> {
+) -> Result<network::types::ScanSummary, network::error::NetworkError> {
     let (scan_state_raw, _cancel_rx) = ScanState::new();
     let scan_state = Arc::new(scan_state_raw);
     let token = Arc::new(CancellationToken::new());
     state.register(scan_id.clone(), Arc::clone(&token));
     link_cancellation(Arc::clone(&token), Arc::clone(&scan_state));
 
-    let sink = EmitterProgressSink { app: app.clone() };
-    let result = network::scanner::run_scan(request, &sink, Arc::clone(&scan_state)).await;
+    let emitter = EmitterProgressSink { app: app.clone() };
+    let history =
+        network::scan_history::HistorySink::open(&app, &scan_id, &request.target, &emitter)
+            .inspect_err(|e| tracing::warn!(error = %e, "Scan history unavailable"))
+            .ok();
+    let sink: &dyn ScanProgressSink = match &history {
+        Some(history) => history,
+        None => &emitter,
+    };
+    let result = network::scanner::run_scan(request, sink, Arc::clone(&scan_state)).await;
 
     state.remove(&scan_id);
     result
@@ -292,12 +730,13 @@ fn cancel_op(op_id: String, state: tauri::State<'_, cancellation::OperationRegis
 }
 
 /// Get comprehensive network interface information.
-#[tauri::command]
-fn get_detailed_network_interfaces() -> Vec<serde_json::Value> {
+///
+/// Backed by netdev: MAC, MTU, link speeds, byte counters, gateway, and DNS
+/// servers per interface. Runs off the main thread because gateway and DNS
+/// enumeration can shell out or query the routing table.
+#[tauri::command(async)]
+fn get_detailed_network_interfaces() -> Vec<network::types::DetailedNetworkInterface> {
     network::interfaces::get_detailed_interfaces()
-        .into_iter()
-        .filter_map(|iface| serde_json::to_value(iface).ok())
-        .collect()
 }
 
 /// Get local network interfaces (basic enumeration via `if-addrs`).
@@ -326,6 +765,33 @@ async fn discover_hosts(
     on_event: tauri::ipc::Channel<DiscoveryEvent>,
     discovery_id: String,
     state: tauri::State<'_, NetworkScannerState>,
+) -> Result<Vec<network::discovery::DiscoveryResult>, String> {
+    run_discovery(targets, options, on_event, discovery_id, &state).await
+}
+
+/// Discover hosts like [`discover_hosts`], additionally returning one
+/// deduplicated row per physical host merged across all methods.
+#[tauri::command]
+async fn discover_hosts_unified(
+    targets: Vec<String>,
+    options: DiscoveryOptions,
+    on_event: tauri::ipc::Channel<DiscoveryEvent>,
+    discovery_id: String,
+    state: tauri::State<'_, NetworkScannerState>,
+) -> Result<network::discovery::UnifiedDiscovery, String> {
+    let results = run_discovery(targets, options, on_event, discovery_id, &state).await?;
+    let hosts = network::discovery::merge_discovery_results(&results);
+    Ok(network::discovery::UnifiedDiscovery { results, hosts })
+}
+
+/// Shared body of the discovery commands: parse targets, register the
+/// cancellation token, and run every requested method.
+async fn run_discovery(
+    targets: Vec<String>,
+    options: DiscoveryOptions,
+    on_event: tauri::ipc::Channel<DiscoveryEvent>,
+    discovery_id: String,
+    state: &NetworkScannerState,
 ) -> Result<Vec<network::discovery::DiscoveryResult>, String> {
     let ip_targets = parse_targets_for_discovery(&targets);
     if ip_targets.is_empty() {
@@ -361,6 +827,13 @@ fn get_discovery_methods() -> Vec<(String, bool)> {
         .collect()
 }
 
+/// Re-check discovery method availability. Nothing is cached, so this
+/// returns the same list as `get_discovery_methods`.
+#[tauri::command]
+fn refresh_privileges() -> Vec<(String, bool)> {
+    get_discovery_methods()
+}
+
 /// Check whether a specific discovery method is available on the current host.
 #[tauri::command]
 fn check_discovery_privilege(method: DiscoveryMethod) -> bool {
@@ -372,17 +845,547 @@ fn check_discovery_privilege(method: DiscoveryMethod) -> bool {
 /// # Arguments
 /// * `text` - The source text to parse
 /// * `language` - The language identifier ("json", "yaml", "xml", "sql")
+/// * `format` - Wire format: "tree" (default) or the flat "compact" form
+/// * `op_id` - Optional operation id; `cancel_op` with it abandons the parse
+/// * `dialect` - SQL dialect ("postgres", "mysql", "sqlite", "mssql",
+///   "bigquery", "snowflake"); generic when omitted, ignored for other languages
 ///
 /// # Returns
-/// `AstParseResult` containing the AST and any errors
-// Runs off the main thread: parsing a large document into an AST is
-// CPU-bound and would otherwise block the webview event loop. Async
-// Tauri commands require owned arguments, hence `String` over `&str`.
-#[tauri::command(async)]
-fn parse_to_ast(text: String, language: String) -> Result<AstParseResult, String> {
-    let lang: AstLanguage = language.parse().map_err(|e: ast::AstError| e.to_string())?;
+/// `AstParseResult` containing the AST and any errors, flattened when
+/// `format` is "compact". SQL results echo the dialect used
+// Parsing runs on the blocking pool so multi-MB documents never hold up
+// the async runtime that serves other commands. The parser checks the
+// token as it goes, so a superseded parse stops early and is never
+// cached. Unchanged text is served from the cache.
+#[tauri::command]
+async fn parse_to_ast(
+    text: String,
+    language: String,
+    format: Option<AstWireFormat>,
+    op_id: Option<String>,
+    dialect: Option<ast::SqlDialect>,
+    state: tauri::State<'_, cancellation::OperationRegistry>,
+    cache: tauri::State<'_, ast::AstCache>,
+) -> Result<AstParseResponse, ast::AstError> {
+    let lang: AstLanguage = language.parse()?;
+    ast::check_input_size(&text)?;
+    let format = format.unwrap_or_default();
+
+    let token = Arc::new(CancellationToken::new());
+    if let Some(id) = &op_id {
+        state.register(id.clone(), Arc::clone(&token));
+    }
+    let cache = cache.inner().clone();
+    let job_token = Arc::clone(&token);
+    let result = tokio::task::spawn_blocking(move || {
+        cache
+            .parse(&text, lang, dialect, Some(&job_token))
+            .map(|tree| AstParseResponse::encode(tree, format))
+    })
+    .await
+    .map_err(|e| ast::AstError::Internal(e.to_string()))
+    .and_then(|result| result);
+    if let Some(id) = &op_id {
+        state.remove(id);
+    }
+    result
+}
+
+/// Parse a JSON file too large to load whole, building only its top levels
+///
+/// # Arguments
+/// * `file_path` - Path of the JSON file
+/// * `depth` - Levels to build up front; 3 when omitted
+/// * `op_id` - Optional operation id; `cancel_op` with it stops the parse
+///
+/// # Returns
+/// `StreamedParseResult` with the top of the tree and the paths of the
+/// collapsed containers, which `expand_node` builds on demand
+#[tauri::command]
+async fn parse_json_file(
+    file_path: String,
+    depth: Option<usize>,
+    op_id: Option<String>,
+    state: tauri::State<'_, cancellation::OperationRegistry>,
+    documents: tauri::State<'_, ast::StreamedDocuments>,
+) -> Result<ast::StreamedParseResult, ast::AstError> {
+    let token = Arc::new(CancellationToken::new());
+    if let Some(id) = &op_id {
+        state.register(id.clone(), Arc::clone(&token));
+    }
+    let documents = documents.inner().clone();
+    let job_token = Arc::clone(&token);
+    let result = tokio::task::spawn_blocking(move || {
+        documents.open(std::path::Path::new(&file_path), depth, &job_token)
+    })
+    .await
+    .map_err(|e| ast::AstError::Internal(e.to_string()))
+    .and_then(|result| result);
+    if let Some(id) = &op_id {
+        state.remove(id);
+    }
+    result
+}
+
+/// Summarise CSV or TSV text as a typed table for a grid preview
+///
+/// # Arguments
+/// * `text` - CSV or TSV text
+/// * `options` - Delimiter, header, and sample window; defaults when omitted
+///
+/// # Returns
+/// `TableModel` with each column's name, inferred type, and null count,
+/// the sampled rows, and the total row count
+#[tauri::command]
+async fn parse_table(
+    text: String,
+    options: Option<ast::TableOptions>,
+) -> Result<ast::TableModel, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::parse_table(&text, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Summarise a CSV or TSV file as a typed table, reading it in chunks
+///
+/// # Arguments
+/// * `file_path` - Path of the file; its size is not limited
+/// * `options` - Delimiter, header, and sample window; defaults when omitted
+/// * `op_id` - Optional operation id; `cancel_op` with it stops the read
+///
+/// # Returns
+/// `TableModel` with each column's name, inferred type, and null count,
+/// the sampled rows, and the total row count
+#[tauri::command]
+async fn parse_table_file(
+    file_path: String,
+    options: Option<ast::TableOptions>,
+    op_id: Option<String>,
+    state: tauri::State<'_, cancellation::OperationRegistry>,
+) -> Result<ast::TableModel, ast::AstError> {
+    let token = Arc::new(CancellationToken::new());
+    if let Some(id) = &op_id {
+        state.register(id.clone(), Arc::clone(&token));
+    }
+    let options = options.unwrap_or_default();
+    let job_token = Arc::clone(&token);
+    let result = tokio::task::spawn_blocking(move || {
+        ast::parse_table_file(std::path::Path::new(&file_path), &options, &job_token)
+    })
+    .await
+    .map_err(|e| ast::AstError::Internal(e.to_string()))
+    .and_then(|result| result);
+    if let Some(id) = &op_id {
+        state.remove(id);
+    }
+    result
+}
+
+/// Build the next levels of a container left collapsed by `parse_json_file`
+///
+/// # Arguments
+/// * `file_path` - Path of the file, as passed to `parse_json_file`
+/// * `path` - AST path of the collapsed container
+///
+/// # Returns
+/// `NodeExpansion` with the container's next children and the paths of
+/// those still collapsed. Large containers are built a page at a time and
+/// stay collapsed until their last page.
+#[tauri::command]
+async fn expand_node(
+    file_path: String,
+    path: String,
+    documents: tauri::State<'_, ast::StreamedDocuments>,
+) -> Result<ast::NodeExpansion, ast::AstError> {
+    let documents = documents.inner().clone();
+    tokio::task::spawn_blocking(move || documents.expand(std::path::Path::new(&file_path), &path))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))?
+}
+
+/// Find the deepest AST node at a cursor position
+///
+/// # Arguments
+/// * `text` - The document, as last passed to `parse_to_ast`
+/// * `language` - The language identifier ("json", "yaml", "xml", ...)
+/// * `offset` - Offset into `text`, in `encoding` units
+/// * `dialect` - SQL dialect the document was parsed with
+/// * `encoding` - Unit of `offset`: "utf-8" bytes (default) or "utf-16"
+///   code units, as Monaco counts
+///
+/// # Returns
+/// `PathAtOffset` with the node's path and the breadcrumb from the root.
+/// The tree cached by `parse_to_ast` is reused when the text is unchanged.
+#[tauri::command]
+async fn path_at_offset(
+    text: String,
+    language: String,
+    offset: usize,
+    dialect: Option<ast::SqlDialect>,
+    encoding: Option<ast::PositionEncoding>,
+    cache: tauri::State<'_, ast::AstCache>,
+) -> Result<ast::PathAtOffset, ast::AstError> {
+    let lang: AstLanguage = language.parse()?;
+    ast::check_input_size(&text)?;
+    let cache = cache.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let offset = ast::byte_offset(&text, offset, encoding.unwrap_or_default());
+        cache
+            .parse(&text, lang, dialect, None)
+            .map(|tree| ast::path_at_offset(&tree, offset))
+    })
+    .await
+    .map_err(|e| ast::AstError::Internal(e.to_string()))?
+}
+
+/// Get the exact source text and value of one AST node
+///
+/// # Arguments
+/// * `text` - The document, as last passed to `parse_to_ast`
+/// * `language` - The language identifier ("json", "yaml", "xml", ...)
+/// * `path` - AST path of the node
+/// * `dialect` - SQL dialect the document was parsed with
+///
+/// # Returns
+/// `SubtreeText` with the node's source slice, its value as copied, and
+/// its value as pretty-printed JSON. The tree cached by `parse_to_ast` is
+/// reused when the text is unchanged.
+#[tauri::command]
+async fn get_subtree_text(
+    text: String,
+    language: String,
+    path: String,
+    dialect: Option<ast::SqlDialect>,
+    cache: tauri::State<'_, ast::AstCache>,
+) -> Result<ast::SubtreeText, ast::AstError> {
+    let lang: AstLanguage = language.parse()?;
+    ast::check_input_size(&text)?;
+    let cache = cache.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        cache
+            .parse(&text, lang, dialect, None)
+            .map(|tree| ast::get_subtree_text(&text, lang, &tree, &path))
+    })
+    .await
+    .map_err(|e| ast::AstError::Internal(e.to_string()))?
+}
+
+/// Evaluate an `XPath` 1.0 expression against an XML document
+///
+/// # Arguments
+/// * `text` - The XML document
+/// * `expression` - The `XPath` expression
+///
+/// # Returns
+/// `XPathResult` with the selected nodes (AST paths and source ranges) or
+/// the scalar value, plus any XML or expression errors
+#[tauri::command]
+async fn xpath_query(text: String, expression: String) -> Result<ast::XPathResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    tokio::task::spawn_blocking(move || ast::xpath_query(&text, &expression))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Search a document's AST by key name, value, and node type
+///
+/// # Arguments
+/// * `text` - The document to search
+/// * `language` - The language identifier ("json", "yaml", "xml", ...)
+/// * `query` - Key name, value text or pattern, and node types to match
+///
+/// # Returns
+/// `AstSearchResult` with the paths and ranges of matching nodes, plus
+/// parse errors and invalid patterns
+#[tauri::command]
+async fn search_ast(
+    text: String,
+    language: String,
+    query: ast::AstSearchQuery,
+) -> Result<ast::AstSearchResult, ast::AstError> {
+    let lang: AstLanguage = language.parse()?;
+    ast::check_input_size(&text)?;
+    tokio::task::spawn_blocking(move || ast::search_ast(&text, lang, &query))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Profile a document's structure and values
+///
+/// # Arguments
+/// * `text` - The document to profile
+/// * `language` - The language identifier ("json", "yaml", "xml", ...)
+///
+/// # Returns
+/// `DocumentProfile` with node counts per type, depth, the largest arrays
+/// and objects, string and number statistics, and parse errors
+#[tauri::command]
+async fn profile_document(
+    text: String,
+    language: String,
+) -> Result<ast::DocumentProfile, ast::AstError> {
+    let lang: AstLanguage = language.parse()?;
+    ast::check_input_size(&text)?;
+    tokio::task::spawn_blocking(move || ast::profile_document(&text, lang))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Pretty-print a JSON, YAML, XML, or SQL document
+///
+/// # Arguments
+/// * `text` - The document to format
+/// * `language` - The language identifier ("json", "yaml", "xml", "sql")
+/// * `options` - Indent, quote style, and key sorting; defaults when omitted
+///
+/// # Returns
+/// `FormatResult` with the formatted text and a source map from original
+/// to formatted ranges, or the parse errors when the input is invalid
+#[tauri::command]
+async fn format_document(
+    text: String,
+    language: String,
+    options: Option<ast::FormatOptions>,
+) -> Result<ast::FormatResult, ast::AstError> {
+    let lang: AstLanguage = language.parse()?;
+    ast::check_input_size(&text)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::format_document(&text, lang, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))?
+}
+
+/// Pretty-print SQL statements
+///
+/// # Arguments
+/// * `text` - One or more SQL statements
+/// * `dialect` - SQL dialect to parse with; generic when omitted
+/// * `options` - Keyword case, indent, comma style, and maximum line
+///   length; defaults when omitted
+///
+/// # Returns
+/// `FormatResult` with the formatted SQL and each statement's original
+/// and formatted range, or the parse errors when the input is invalid
+#[tauri::command]
+async fn format_sql(
+    text: String,
+    dialect: Option<ast::SqlDialect>,
+    options: Option<ast::SqlFormatOptions>,
+) -> Result<ast::FormatResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let dialect = dialect.unwrap_or_default();
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::format_sql(&text, dialect, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Report risky patterns in SQL statements
+///
+/// # Arguments
+/// * `text` - One or more SQL statements
+/// * `dialect` - SQL dialect to parse with; generic when omitted
+///
+/// # Returns
+/// `SqlAnalysisResult` with one warning per DELETE or UPDATE without
+/// WHERE, `SELECT *`, implicit cross join, or non-sargable predicate,
+/// each with the range of the offending clause
+#[tauri::command]
+async fn analyze_sql(
+    text: String,
+    dialect: Option<ast::SqlDialect>,
+) -> Result<ast::SqlAnalysisResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let dialect = dialect.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::analyze_sql(&text, dialect))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// List the tables, columns, CTEs, and bind parameters SQL statements use
+///
+/// # Arguments
+/// * `text` - One or more SQL statements
+/// * `dialect` - SQL dialect to parse with; generic when omitted
+///
+/// # Returns
+/// `SqlDependencies` with every reference and its source range, or the
+/// parse errors when the input is invalid
+#[tauri::command]
+async fn extract_sql_dependencies(
+    text: String,
+    dialect: Option<ast::SqlDialect>,
+) -> Result<ast::SqlDependencies, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let dialect = dialect.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::extract_sql_dependencies(&text, dialect))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Parse EXPLAIN output into a tree of cost-annotated plan nodes
+///
+/// # Arguments
+/// * `text` - `PostgreSQL` `EXPLAIN (FORMAT JSON)` or `MySQL`
+///   `EXPLAIN FORMAT=JSON` output, or `SQLite` `EXPLAIN QUERY PLAN` rows
+/// * `dialect` - Database that produced the output; recognised from the
+///   output when omitted
+///
+/// # Returns
+/// `AstParseResult` whose root holds one plan node per operation, labelled
+/// with its estimated and actual cost, and the statement-level figures
+#[tauri::command]
+async fn parse_explain_plan(
+    text: String,
+    dialect: Option<ast::SqlDialect>,
+) -> Result<ast::AstParseResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let dialect = dialect.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::parse_explain_plan(&text, dialect))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Convert a document between JSON, YAML, TOML, XML, and CSV
+///
+/// # Arguments
+/// * `text` - The document to convert
+/// * `from` - Language of `text` ("json", "yaml", "toml", "xml", "csv")
+/// * `to` - Language to convert to, from the same set
+/// * `options` - Indent, XML attribute and text keys, XML root element,
+///   and CSV delimiter; defaults when omitted
+///
+/// # Returns
+/// `ConvertResult` with the converted text and a warning for each kind of
+/// information the target format could not keep, or the parse errors when
+/// the input is invalid
+#[tauri::command]
+async fn convert_document(
+    text: String,
+    from: String,
+    to: String,
+    options: Option<ast::ConvertOptions>,
+) -> Result<ast::ConvertResult, ast::AstError> {
+    let from: AstLanguage = from.parse()?;
+    let to: AstLanguage = to.parse()?;
+    ast::check_input_size(&text)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::convert_document(&text, from, to, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))?
+}
+
+/// Flatten nested JSON into an object of key paths and leaf values
+///
+/// # Arguments
+/// * `text` - A JSON object or array
+/// * `options` - Key separator, array handling, and output indent;
+///   defaults when omitted
+///
+/// # Returns
+/// `FlattenResult` with the flat JSON object, or the parse errors and key
+/// collisions that prevent flattening
+#[tauri::command]
+async fn flatten_json(
+    text: String,
+    options: Option<ast::FlattenOptions>,
+) -> Result<ast::FlattenResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::flatten_json(&text, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Rebuild nested JSON from an object of flattened key paths
+///
+/// # Arguments
+/// * `text` - A JSON object whose keys are flattened paths
+/// * `options` - The separator and array handling the keys were flattened
+///   with, and the output indent; defaults when omitted
+///
+/// # Returns
+/// `FlattenResult` with the nested JSON, or the parse errors and
+/// conflicting keys that prevent unflattening
+#[tauri::command]
+async fn unflatten_json(
+    text: String,
+    options: Option<ast::FlattenOptions>,
+) -> Result<ast::FlattenResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::unflatten_json(&text, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
 
-    Ok(ast::parse_to_ast(&text, lang))
+/// Generate type definitions from a JSON sample or a JSON Schema
+///
+/// # Arguments
+/// * `text` - A JSON sample document or a JSON Schema
+/// * `target_language` - "rust", "typescript", "kotlin", or "go"
+/// * `options` - Root type name, input kind, field naming, and
+///   optionality; defaults when omitted
+///
+/// # Returns
+/// `TypeGenResult` with the generated code, or the parse errors that
+/// prevent generation
+#[tauri::command]
+async fn generate_types(
+    text: String,
+    target_language: ast::TargetLanguage,
+    options: Option<ast::TypeGenOptions>,
+) -> Result<ast::TypeGenResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || ast::generate_types(&text, target_language, &options))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Validate an XML document against an XSD or a DTD
+///
+/// # Arguments
+/// * `text` - The XML document
+/// * `schema` - XSD text, or a DTD read as the external subset; optional
+///   for DTDs, where the document's internal subset is always used
+/// * `schema_kind` - "xsd" or "dtd"
+///
+/// # Returns
+/// `XmlValidationResult` with validity errors (AST paths and ranges into
+/// the document) and schema errors (ranges into the schema)
+#[tauri::command]
+async fn validate_xml(
+    text: String,
+    schema: Option<String>,
+    schema_kind: ast::SchemaKind,
+) -> Result<ast::XmlValidationResult, ast::AstError> {
+    ast::check_input_size(&text)?;
+    if let Some(schema) = &schema {
+        ast::check_input_size(schema)?;
+    }
+    tokio::task::spawn_blocking(move || ast::validate_xml(&text, schema.as_deref(), schema_kind))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
+}
+
+/// Analyze an OpenAPI 3.x document
+///
+/// # Arguments
+/// * `text` - The document, as JSON or YAML
+///
+/// # Returns
+/// `OpenApiAnalysis` with the title and version, a tree of paths,
+/// operations, parameters, responses, and schemas with source ranges, and
+/// the spec violations found
+#[tauri::command]
+async fn analyze_openapi(text: String) -> Result<ast::OpenApiAnalysis, ast::AstError> {
+    ast::check_input_size(&text)?;
+    tokio::task::spawn_blocking(move || ast::analyze_openapi(&text))
+        .await
+        .map_err(|e| ast::AstError::Internal(e.to_string()))
 }
 
 /// Bootstrap routine executed inside the Tauri builder's `setup`
@@ -410,6 +1413,13 @@ fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         main_window.set_traffic_lights_inset(12.0, 10.0)?;
     }
 
+    // Structured logging goes first so the rest of setup can log.
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve app log directory: {e}"))?;
+    app.manage(logging::LogState::init(&log_dir)?);
+
     // Initialize settings from config directory.
     let config_dir = app
         .path()
@@ -417,6 +1427,34 @@ fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         .map_err(|e| format!("Failed to resolve app config directory: {e}"))?;
     app.manage(settings::SettingsState::load(&config_dir));
 
+    // Tool history falls back to an in-memory store when the database file
+    // cannot be opened, so a locked or corrupt file never blocks startup.
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {e}"))?;
+    app.manage(
+        history::HistoryState::open(&data_dir)
+            .or_else(|_| history::HistoryState::open_in_memory())?,
+    );
+
+    // Panics from here on leave a redacted local crash report behind.
+    app.manage(crash_report::CrashReportState::install(&data_dir));
+
+    // A downloaded OUI registry replaces the bundled vendor table.
+    network::oui_registry::load_cached(&data_dir);
+
+    // Scheduled tasks fire from a background loop while the app is open.
+    app.manage(scheduler::SchedulerState::load(&data_dir));
+    scheduler::start(app.handle().clone());
+
+    // Start the warm workers now so the first key generation or hash
+    // skips the worker's process start-up. Requests spawn workers on
+    // demand if this fails.
+    if let Err(e) = app.state::<WorkerProcessState>().warm(app.handle()) {
+        tracing::warn!(error = %e, "Failed to start warm workers");
+    }
+
     // Warm the system font caches from a background task so the
     // first Settings open returns instantly. font-kit's `all_families`
     // + per-family `is_monospace` walk would otherwise block the UI
@@ -471,75 +1509,216 @@ pub fn run() {
         base
     };
 
+    let handler = tauri::generate_handler![
+        greet,
+        parse_to_ast,
+        parse_json_file,
+        expand_node,
+        parse_table,
+        parse_table_file,
+        path_at_offset,
+        get_subtree_text,
+        xpath_query,
+        search_ast,
+        profile_document,
+        format_document,
+        format_sql,
+        analyze_sql,
+        extract_sql_dependencies,
+        parse_explain_plan,
+        convert_document,
+        flatten_json,
+        unflatten_json,
+        generate_types,
+        validate_xml,
+        analyze_openapi,
+        jq::transform_json,
+        cancel_worker_operation,
+        set_worker_pool_size,
+        generate_bcrypt_hash,
+        verify_bcrypt_hash,
+        get_bcrypt_cost_info,
+        identify_password_hash,
+        verify_password_hash,
+        decode_jwt,
+        verify_jwt,
+        create_jwt,
+        generate_otp_secret,
+        generate_otp_codes,
+        verify_totp,
+        parse_otpauth_uri,
+        build_otpauth_uri,
+        encrypt_text,
+        decrypt_text,
+        encrypt_file,
+        decrypt_file,
+        sign_data,
+        verify_signature,
+        generate_ssh_keypair,
+        convert_ssh_key,
+        inspect_ssh_key,
+        sign_ssh_certificate,
+        inspect_ssh_certificate,
+        generate_gpg_keypair,
+        encrypt_gpg_message,
+        decrypt_gpg_message,
+        sign_gpg_message,
+        verify_gpg_signature,
+        parse_pgp_key,
+        generate_self_signed_certificate,
+        generate_certificate_request,
+        check_cli_availability,
+        start_network_scan,
+        cancel_network_scan,
+        network::scan_history::scan_history_list,
+        network::scan_history::scan_history_get,
+        network::scan_history::scan_history_delete,
+        cancel_op,
+        get_detailed_network_interfaces,
+        get_local_network_interfaces,
+        network_health::network_health_check,
+        connectivity_check::check_connectivity,
+        discover_mdns_services,
+        discover_hosts,
+        discover_hosts_unified,
+        cancel_discovery,
+        get_discovery_methods,
+        check_discovery_privilege,
+        refresh_privileges,
+        network::oui::lookup_oui_vendor,
+        network::oui::get_oui_database_info,
+        network::oui_registry::update_oui_database,
+        history::history_record,
+        history::history_list,
+        history::history_search,
+        history::history_pin,
+        history::history_purge,
+        global_search::global_search,
+        actions::list_actions,
+        actions::invoke_action,
+        logging::logs_tail,
+        logging::logs_export,
+        logging::logs_directory,
+        crash_report::crash_reports_list,
+        crash_report::crash_report_delete,
+        crash_report::crash_report_submit,
+        update_check::check_for_updates,
+        clipboard_history::clipboard_watch_start,
+        clipboard_history::clipboard_watch_stop,
+        clipboard_history::clipboard_history_list,
+        clipboard_history::clipboard_history_clear,
+        clipboard_history::clipboard_set_exclusions,
+        clipboard_history::clipboard_analyze,
+        app_backup::export_app_data,
+        app_backup::import_app_data,
+        error_codes::get_error_catalog,
+        benchmark::run_benchmark,
+        redaction::redact_text,
+        redaction::redaction_rules,
+        ingest::ingest_file,
+        rest_client::rest_client_send,
+        robots_sitemap::robots_fetch,
+        robots_sitemap::robots_check,
+        robots_sitemap::sitemap_fetch,
+        grpc_client::grpc_list_services,
+        grpc_client::grpc_invoke,
+        websocket::ws_connect,
+        websocket::ws_send,
+        websocket::ws_close,
+        dns_lookup::dns_lookup,
+        duplicate_finder::duplicate_scan,
+        duplicate_finder::duplicate_delete,
+        duplicate_finder::duplicate_replace_with_link,
+        network::wifi::start_wifi_scan,
+        network::wifi::cancel_wifi_scan,
+        network::link_local::list_link_local_neighbors,
+        network::listening::list_listening_ports,
+        network::monitor::port_monitor_start,
+        network::monitor::port_monitor_stop,
+        network::monitor::port_monitor_list,
+        network::monitor::port_monitor_get,
+        network::monitor::port_monitor_delete,
+        drive_info::drives_list,
+        drive_info::folder_size_scan,
+        system_info::system_info,
+        env_analyzer::analyze_environment,
+        scheduler::scheduler_list,
+        scheduler::scheduler_save,
+        scheduler::scheduler_delete,
+        scheduler::scheduler_run_now,
+        scheduler::scheduler_preview,
+        file_inspect::file_inspect,
+        hash_batch::hash_file_batch,
+        hash_text::hash_text_batch,
+        hmac_digest::hmac_digest,
+        checksum::checksum_presets,
+        checksum::checksum_compute,
+        string_compress::string_compress,
+        string_compress::string_decompress,
+        uuid_generator::generate_uuids,
+        uuid_generator::inspect_uuid,
+        id_generator::generate_ids,
+        id_generator::decode_id,
+        random_generator::generate_random,
+        key_export::save_key_material,
+        keychain::keychain_store,
+        keychain::keychain_retrieve,
+        keychain::keychain_delete,
+        qr::generate_qr,
+        qr::decode_qr,
+        ssh_key_list::analyze_ssh_key_list,
+        file_watch::file_watch_start,
+        file_watch::file_watch_stop,
+        file_watch::watch_file,
+        folder_tree::folder_walk,
+        folder_tree::folder_largest_files,
+        hex_editor::hex_open,
+        hex_editor::hex_read,
+        hex_editor::hex_save,
+        archive_inspect::archive_open,
+        archive_inspect::archive_read_entry,
+        archive_inspect::archive_extract,
+        archive_inspect::archive_extract_entry,
+        tls_inspect::tls_inspect,
+        tls_capabilities::tls_capabilities,
+        tcp_latency::tcp_latency_benchmark,
+        webhook::webhook_start,
+        webhook::webhook_stop,
+        webhook::webhook_status,
+        mock_server::mock_server_start,
+        mock_server::mock_server_update_route,
+        mock_server::mock_server_stop,
+        mock_server::mock_server_status,
+        mcp::mcp_server_start,
+        mcp::mcp_server_stop,
+        mcp::mcp_server_status,
+        mcp::mcp_tool_list,
+        settings::get_settings,
+        settings::update_settings,
+        settings::reset_settings,
+        settings::get_system_fonts,
+        settings::get_monospace_system_fonts,
+        settings::get_settings_file_path,
+    ];
+
     builder
         .manage(WorkerProcessState::new())
         .manage(NetworkScannerState::new())
         .manage(websocket::WebSocketState::new())
         .manage(webhook::WebhookState::new())
+        .manage(mock_server::MockServerState::new())
+        .manage(mcp::McpServerState::new())
         .manage(file_watch::FileWatchState::new())
+        .manage(clipboard_history::ClipboardState::new())
         .manage(cancellation::OperationRegistry::new())
+        .manage(ast::AstCache::new())
+        .manage(ast::StreamedDocuments::new())
         .setup(setup_app)
-        .invoke_handler(tauri::generate_handler![
-            greet,
-            parse_to_ast,
-            cancel_worker_operation,
-            generate_bcrypt_hash,
-            verify_bcrypt_hash,
-            get_bcrypt_cost_info,
-            generate_ssh_keypair,
-            generate_gpg_keypair,
-            check_cli_availability,
-            start_network_scan,
-            cancel_network_scan,
-            cancel_op,
-            get_detailed_network_interfaces,
-            get_local_network_interfaces,
-            discover_mdns_services,
-            discover_hosts,
-            cancel_discovery,
-            get_discovery_methods,
-            check_discovery_privilege,
-            network::oui::lookup_oui_vendor,
-            network::oui::get_oui_database_info,
-            rest_client::rest_client_send,
-            websocket::ws_connect,
-            websocket::ws_send,
-            websocket::ws_close,
-            dns_lookup::dns_lookup,
-            duplicate_finder::duplicate_scan,
-            duplicate_finder::duplicate_delete,
-            duplicate_finder::duplicate_replace_with_link,
-            network::wifi::start_wifi_scan,
-            network::wifi::cancel_wifi_scan,
-            drive_info::drives_list,
-            drive_info::folder_size_scan,
-            file_inspect::file_inspect,
-            hash_batch::hash_file_batch,
-            hash_text::hash_text_batch,
-            string_compress::string_compress,
-            string_compress::string_decompress,
-            file_watch::file_watch_start,
-            file_watch::file_watch_stop,
-            folder_tree::folder_walk,
-            folder_tree::folder_largest_files,
-            hex_editor::hex_open,
-            hex_editor::hex_read,
-            hex_editor::hex_save,
-            archive_inspect::archive_open,
-            archive_inspect::archive_read_entry,
-            archive_inspect::archive_extract,
-            archive_inspect::archive_extract_entry,
-            tls_inspect::tls_inspect,
-            webhook::webhook_start,
-            webhook::webhook_stop,
-            webhook::webhook_status,
-            settings::get_settings,
-            settings::update_settings,
-            settings::reset_settings,
-            settings::get_system_fonts,
-            settings::get_monospace_system_fonts,
-            settings::get_settings_file_path,
-        ])
+        // Remember the command being invoked so crash reports can name it.
+        .invoke_handler(move |invoke| {
+            crash_report::note_command(invoke.message.command());
+            handler(invoke)
+        })
         .run(tauri::generate_context!())
         .unwrap_or_else(|e| {
             use std::io::Write;
@@ -0,0 +1,399 @@
+//! Structured logging with an in-app log viewer
+//!
+//! Installs a `tracing` subscriber that writes one JSON object per line to
+//! daily-rotated files in the platform log directory:
+//! - macOS: `~/Library/Logs/io.github.seijikohara.kogu/`
+//! - Windows: `%LOCALAPPDATA%/io.github.seijikohara.kogu/logs/`
+//! - Linux: `~/.local/share/io.github.seijikohara.kogu/logs/`
+//!
+//! The level defaults to `info` (`debug` for this crate) and can be
+//! overridden with the `KOGU_LOG` environment variable using
+//! `tracing-subscriber` filter syntax. Secrets are masked by
+//! [`crate::redaction`] before a line reaches the file. The commands below
+//! read the files back so users can inspect, filter, and export logs when
+//! reporting why a scan or key generation failed.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use crate::redaction;
+
+/// Log file name prefix; files are named `kogu.YYYY-MM-DD.log`
+const LOG_PREFIX: &str = "kogu";
+
+/// Log file name suffix
+const LOG_SUFFIX: &str = "log";
+
+/// Rotated files kept on disk
+const MAX_LOG_FILES: usize = 7;
+
+/// Environment variable overriding the default filter
+const FILTER_ENV: &str = "KOGU_LOG";
+
+/// Filter used when [`FILTER_ENV`] is unset or invalid
+const DEFAULT_FILTER: &str = "info,kogu_lib=debug";
+
+/// Default number of records returned by [`logs_tail`]
+const DEFAULT_TAIL: usize = 500;
+
+/// Maximum number of records returned by [`logs_tail`]
+const MAX_TAIL: usize = 5000;
+
+/// Managed state keeping the background log writer alive.
+///
+/// Dropping the guard flushes and stops the writer thread, so the state
+/// lives for the whole application lifetime.
+pub struct LogState {
+    dir: PathBuf,
+    _guard: Option<WorkerGuard>,
+}
+
+impl LogState {
+    /// Install the global subscriber writing to `dir`.
+    ///
+    /// When a subscriber is already installed (tests, embedding), the
+    /// existing one is kept and the viewer still reads from `dir`.
+    pub fn init(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create log directory: {e}"))?;
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_PREFIX)
+            .filename_suffix(LOG_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| format!("Failed to open log file: {e}"))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        let filter =
+            EnvFilter::try_from_env(FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+        let file_layer = tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(false)
+            .with_writer(RedactingMakeWriter(writer));
+        let installed = tracing_subscriber::registry()
+            .with(filter)
+            .with(file_layer)
+            .try_init()
+            .is_ok();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            _guard: installed.then_some(guard),
+        })
+    }
+
+    /// Directory holding the log files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Writer that masks secrets before passing bytes on.
+///
+/// The fmt layer formats each event into a buffer and writes it in one
+/// call, so every `write` sees a whole line.
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) => {
+                self.0.write_all(redaction::redact(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// [`MakeWriter`] wrapping every writer in a [`RedactingWriter`].
+struct RedactingMakeWriter<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+/// One parsed log line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    /// RFC 3339 UTC timestamp.
+    pub timestamp: String,
+    /// Level name (`TRACE`, `DEBUG`, `INFO`, `WARN`, `ERROR`).
+    pub level: String,
+    /// Module path that emitted the event.
+    pub target: String,
+    /// Event message.
+    pub message: String,
+    /// Remaining structured fields.
+    pub fields: Map<String, Value>,
+}
+
+/// Filter applied when reading logs back.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    /// Minimum level to include (e.g. `warn`).
+    #[serde(default)]
+    pub min_level: Option<String>,
+    /// Substring the target must contain (e.g. `network`).
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Case-insensitive substring of the message or field values.
+    #[serde(default)]
+    pub contains: Option<String>,
+    /// Only records at or after this RFC 3339 timestamp.
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+fn severity(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "TRACE" => 0,
+        "DEBUG" => 1,
+        "WARN" => 3,
+        "ERROR" => 4,
+        _ => 2,
+    }
+}
+
+fn parse_record(line: &str) -> Option<LogRecord> {
+    let Value::Object(mut fields) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let mut take = |key: &str| match fields.remove(key) {
+        Some(Value::String(s)) => s,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogRecord {
+        timestamp: take("timestamp"),
+        level: take("level"),
+        target: take("target"),
+        message: take("message"),
+        fields,
+    })
+}
+
+impl LogFilter {
+    fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(min) = &self.min_level {
+            if severity(&record.level) < severity(min) {
+                return false;
+            }
+        }
+        if let Some(target) = &self.target {
+            if !record.target.contains(target.as_str()) {
+                return false;
+            }
+        }
+        // Timestamps share one fixed-width UTC format, so string order is
+        // chronological order.
+        if let Some(since) = &self.since {
+            if record.timestamp.as_str() < since.as_str() {
+                return false;
+            }
+        }
+        if let Some(needle) = &self.contains {
+            let needle = needle.to_lowercase();
+            let in_fields = record
+                .fields
+                .values()
+                .any(|v| v.to_string().to_lowercase().contains(&needle));
+            if !record.message.to_lowercase().contains(&needle) && !in_fields {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Log files in `dir`, newest first.
+fn log_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read log directory: {e}"))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(LOG_PREFIX) && n.ends_with(LOG_SUFFIX))
+        })
+        .collect();
+    // Date-stamped names sort chronologically.
+    files.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(files)
+}
+
+/// Up to `limit` matching records, newest first.
+fn read_records(dir: &Path, filter: &LogFilter, limit: usize) -> Result<Vec<LogRecord>, String> {
+    let mut records = Vec::new();
+    for path in log_files(dir)? {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for record in content.lines().rev().filter_map(parse_record) {
+            if filter.matches(&record) {
+                records.push(record);
+                if records.len() >= limit {
+                    return Ok(records);
+                }
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Return the most recent log records matching `filter`, newest first.
+///
+/// # Errors
+///
+/// Returns an error string when the log directory cannot be read.
+#[tauri::command(async)]
+pub fn logs_tail(
+    filter: Option<LogFilter>,
+    limit: Option<u32>,
+    state: tauri::State<'_, LogState>,
+) -> Result<Vec<LogRecord>, String> {
+    let limit = limit
+        .and_then(|l| usize::try_from(l).ok())
+        .unwrap_or(DEFAULT_TAIL)
+        .min(MAX_TAIL);
+    read_records(state.dir(), &filter.unwrap_or_default(), limit)
+}
+
+/// Write every record matching `filter` to `path` as JSON lines, oldest
+/// first, and return the number of records written.
+///
+/// # Errors
+///
+/// Returns an error string when the logs cannot be read or the export
+/// file cannot be written.
+#[tauri::command(async)]
+pub fn logs_export(
+    path: String,
+    filter: Option<LogFilter>,
+    state: tauri::State<'_, LogState>,
+) -> Result<usize, String> {
+    let mut records = read_records(state.dir(), &filter.unwrap_or_default(), usize::MAX)?;
+    records.reverse();
+    let mut out = String::new();
+    for record in &records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize log record: {e}"))?;
+        out.push_str(&redaction::redact(&line));
+        out.push('\n');
+    }
+    std::fs::write(&path, out).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    Ok(records.len())
+}
+
+/// Return the directory holding the log files.
+#[tauri::command]
+pub fn logs_directory(state: tauri::State<'_, LogState>) -> String {
+    state.dir().to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(ts: &str, level: &str, target: &str, message: &str) -> String {
+        format!(
+            r#"{{"timestamp":"{ts}","level":"{level}","message":"{message}","host":"10.0.0.1","target":"{target}"}}"#
+        )
+    }
+
+    #[test]
+    fn parses_flattened_json_lines() {
+        let record = parse_record(&line(
+            "2026-01-02T03:04:05.000000Z",
+            "WARN",
+            "kogu_lib::network::discovery::ssdp",
+            "bind failed",
+        ))
+        .unwrap();
+        assert_eq!(record.level, "WARN");
+        assert_eq!(record.message, "bind failed");
+        assert_eq!(record.fields.get("host"), Some(&Value::from("10.0.0.1")));
+        assert!(parse_record("not json").is_none());
+    }
+
+    #[test]
+    fn filters_by_level_target_text_and_time() {
+        let record = parse_record(&line(
+            "2026-01-02T03:04:05.000000Z",
+            "WARN",
+            "kogu_lib::network",
+            "Scan failed",
+        ))
+        .unwrap();
+        let filter = |f: LogFilter| f.matches(&record);
+
+        assert!(filter(LogFilter {
+            min_level: Some("info".into()),
+            ..LogFilter::default()
+        }));
+        assert!(!filter(LogFilter {
+            min_level: Some("error".into()),
+            ..LogFilter::default()
+        }));
+        assert!(filter(LogFilter {
+            target: Some("network".into()),
+            contains: Some("scan FAILED".into()),
+            ..LogFilter::default()
+        }));
+        assert!(filter(LogFilter {
+            contains: Some("10.0.0".into()),
+            ..LogFilter::default()
+        }));
+        assert!(!filter(LogFilter {
+            since: Some("2026-01-03T00:00:00Z".into()),
+            ..LogFilter::default()
+        }));
+    }
+
+    #[test]
+    fn reads_newest_records_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("kogu.2026-01-01.log"),
+            [
+                line("2026-01-01T00:00:00Z", "INFO", "a", "one"),
+                line("2026-01-01T00:00:01Z", "INFO", "a", "two"),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("kogu.2026-01-02.log"),
+            line("2026-01-02T00:00:00Z", "INFO", "a", "three"),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("other.txt"), "ignored").unwrap();
+
+        let records = read_records(dir.path(), &LogFilter::default(), 2).unwrap();
+        let messages: Vec<_> = records.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, ["three", "two"]);
+    }
+}
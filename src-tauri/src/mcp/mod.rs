@@ -0,0 +1,514 @@
+//! Model Context Protocol server exposing Kogu tools to local AI agents.
+//!
+//! The server speaks JSON-RPC 2.0 and implements the MCP `initialize`,
+//! `ping`, `tools/list`, and `tools/call` methods. It runs either over
+//! stdio (`kogu-cli mcp`) or over HTTP on a loopback listener started from
+//! the app with [`mcp_server_start`].
+//!
+//! Only side-effect free tools are enabled by default: formatting and
+//! conversion, hashing, UUIDs, subnet math, and certificate inspection.
+//! Callers may narrow the set with an allowlist; network scanning is never
+//! exposed unless it is named in that allowlist explicitly.
+//!
+//! Like the mock server and webhook receiver, the HTTP listener binds to
+//! `127.0.0.1` only and additionally rejects requests whose `Origin` is not
+//! a loopback host, so a web page cannot reach it through DNS rebinding.
+
+pub mod tools;
+
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::{oneshot, Mutex};
+
+/// MCP revision implemented by this server
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// HTTP path the server answers on
+const HTTP_PATH: &str = "/mcp";
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// JSON-RPC dispatcher with a fixed set of allowed tools.
+#[derive(Debug, Clone)]
+pub struct McpServer {
+    allowed: BTreeSet<&'static str>,
+}
+
+impl McpServer {
+    /// Build a server exposing `allow`, or the default-enabled tools when
+    /// `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string when the allowlist names an unknown tool or
+    /// is empty.
+    pub fn new(allow: Option<&[String]>) -> Result<Self, String> {
+        let allowed: BTreeSet<&'static str> = match allow {
+            None => tools::TOOLS
+                .iter()
+                .filter(|t| t.default_enabled)
+                .map(|t| t.name)
+                .collect(),
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    tools::find(name.trim())
+                        .map(|t| t.name)
+                        .ok_or_else(|| format!("Unknown MCP tool: {name}"))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        if allowed.is_empty() {
+            return Err("At least one MCP tool must be allowed".to_string());
+        }
+        Ok(Self { allowed })
+    }
+
+    /// Names of the exposed tools.
+    pub fn tool_names(&self) -> Vec<String> {
+        self.allowed.iter().map(ToString::to_string).collect()
+    }
+
+    /// Handle one JSON-RPC message. Returns `None` for notifications.
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let Some(object) = message.as_object() else {
+            return Some(error_response(
+                &Value::Null,
+                INVALID_REQUEST,
+                "Expected a JSON-RPC request object",
+            ));
+        };
+        let id = object.get("id").cloned();
+        let Some(method) = object.get("method").and_then(Value::as_str) else {
+            return id.map(|id| error_response(&id, INVALID_REQUEST, "Missing method"));
+        };
+        let params = object.get("params").cloned().unwrap_or(Value::Null);
+        // Notifications carry no id and never get a response.
+        let id = id?;
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "kogu", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.list_tools() })),
+            "tools/call" => self.call_tool(&params).await,
+            other => Err((METHOD_NOT_FOUND, format!("Method not found: {other}"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(&id, code, &message),
+        })
+    }
+
+    /// Handle one raw line or HTTP body.
+    pub async fn handle_text(&self, text: &str) -> Option<Value> {
+        match serde_json::from_str(text) {
+            Ok(message) => self.handle(message).await,
+            Err(e) => Some(error_response(
+                &Value::Null,
+                PARSE_ERROR,
+                &format!("Parse error: {e}"),
+            )),
+        }
+    }
+
+    fn list_tools(&self) -> Vec<Value> {
+        tools::TOOLS
+            .iter()
+            .filter(|t| self.allowed.contains(t.name))
+            .map(|t| {
+                json!({
+                    "name": t.name,
+                    "description": t.description,
+                    "inputSchema": t.input_schema(),
+                })
+            })
+            .collect()
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing tool name".to_string()))?;
+        if !self.allowed.contains(name) {
+            return Err((INVALID_PARAMS, format!("Tool not available: {name}")));
+        }
+        let args = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        // Tool failures are reported in the result so the agent can see them.
+        Ok(match tools::call(name, &args).await {
+            Ok(output) => json!({
+                "content": [{ "type": "text", "text": output.to_string() }],
+                "structuredContent": output,
+                "isError": false,
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        })
+    }
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+// =============================================================================
+// Transports
+// =============================================================================
+
+/// Serve newline-delimited JSON-RPC on stdin/stdout until stdin closes.
+///
+/// # Errors
+///
+/// Returns an error string when stdin or stdout fails.
+pub fn serve_stdio(server: &McpServer, runtime: &tokio::runtime::Runtime) -> Result<(), String> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {e}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = runtime.block_on(server.handle_text(&line)) {
+            writeln!(stdout, "{response}")
+                .and_then(|()| stdout.flush())
+                .map_err(|e| format!("Failed to write stdout: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Build a loopback `SocketAddr`; the only place the bind address is made.
+fn bind_loopback(port: u16) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], port))
+}
+
+/// `true` when the request has no `Origin` or a loopback one.
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    let host = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .trim_end_matches('/');
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.split_once(']').map(|(h, _)| h))
+        .unwrap_or_else(|| host.rsplit_once(':').map_or(host, |(h, _)| h));
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+async fn handle_http(
+    State(server): State<Arc<McpServer>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if !origin_allowed(&headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    match server.handle_text(&body).await {
+        Some(response) => {
+            let mut res = response.to_string().into_response();
+            res.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            res
+        }
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// Bind the HTTP transport on `127.0.0.1:port` (`0` picks a free port).
+///
+/// # Errors
+///
+/// Returns an error string when the port cannot be bound.
+pub async fn bind_http(
+    server: McpServer,
+    port: u16,
+) -> Result<(tokio::net::TcpListener, Router), String> {
+    let bind_addr = bind_loopback(port);
+    let listener = tokio::net::TcpListener::bind(bind_addr)
+        .await
+        .map_err(|e| format!("Failed to bind {bind_addr}: {e}"))?;
+    let router = Router::new()
+        .route(HTTP_PATH, post(handle_http))
+        .with_state(Arc::new(server));
+    Ok((listener, router))
+}
+
+/// Endpoint URL for a bound listener.
+///
+/// # Errors
+///
+/// Returns an error string when the bound address cannot be read.
+pub fn endpoint(listener: &tokio::net::TcpListener) -> Result<String, String> {
+    let bound = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read bound address: {e}"))?;
+    Ok(format!("http://{bound}{HTTP_PATH}"))
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Per-server task handle.
+struct RunningServer {
+    shutdown: oneshot::Sender<()>,
+    address: String,
+    tools: Vec<String>,
+}
+
+/// Tauri-managed state. `None` when no server is running.
+#[derive(Default)]
+pub struct McpServerState {
+    inner: Mutex<Option<RunningServer>>,
+}
+
+impl McpServerState {
+    /// Construct an empty state ready to host a single server.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Reported state of the HTTP server.
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerStatus {
+    /// `true` when the server is running.
+    pub running: bool,
+    /// Endpoint URL when running.
+    pub address: Option<String>,
+    /// Exposed tool names when running.
+    pub tools: Vec<String>,
+}
+
+/// One tool the server can expose.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolInfo {
+    /// Tool name.
+    pub name: String,
+    /// Description shown to agents.
+    pub description: String,
+    /// Whether the tool is exposed without an explicit allowlist.
+    pub default_enabled: bool,
+}
+
+/// Start the MCP server over HTTP on loopback, replacing a running one.
+///
+/// # Errors
+///
+/// Returns an error string when the allowlist names an unknown tool or the
+/// port cannot be bound.
+#[tauri::command]
+pub async fn mcp_server_start(
+    state: tauri::State<'_, McpServerState>,
+    port: Option<u16>,
+    allowed_tools: Option<Vec<String>>,
+) -> Result<McpServerStatus, String> {
+    let server = McpServer::new(allowed_tools.as_deref())?;
+    let tools = server.tool_names();
+
+    {
+        let mut guard = state.inner.lock().await;
+        if let Some(existing) = guard.take() {
+            let _ = existing.shutdown.send(());
+        }
+    }
+
+    let (listener, router) = bind_http(server, port.unwrap_or(0)).await?;
+    let address = endpoint(&listener)?;
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+    });
+
+    *state.inner.lock().await = Some(RunningServer {
+        shutdown: shutdown_tx,
+        address: address.clone(),
+        tools: tools.clone(),
+    });
+    Ok(McpServerStatus {
+        running: true,
+        address: Some(address),
+        tools,
+    })
+}
+
+/// Stop the MCP server if one is running. No-op when already stopped.
+///
+/// # Errors
+///
+/// Currently never returns an error; the signature mirrors `mock_server_stop`.
+#[tauri::command]
+pub async fn mcp_server_stop(state: tauri::State<'_, McpServerState>) -> Result<(), String> {
+    let taken = state.inner.lock().await.take();
+    if let Some(existing) = taken {
+        let _ = existing.shutdown.send(());
+    }
+    Ok(())
+}
+
+/// Report whether the MCP server is running and which tools it exposes.
+///
+/// # Errors
+///
+/// Currently never returns an error; the signature mirrors `mock_server_status`.
+#[tauri::command]
+pub async fn mcp_server_status(
+    state: tauri::State<'_, McpServerState>,
+) -> Result<McpServerStatus, String> {
+    let guard = state.inner.lock().await;
+    Ok(guard
+        .as_ref()
+        .map_or_else(McpServerStatus::default, |running| McpServerStatus {
+            running: true,
+            address: Some(running.address.clone()),
+            tools: running.tools.clone(),
+        }))
+}
+
+/// List every tool the MCP server can expose.
+#[tauri::command]
+pub fn mcp_tool_list() -> Vec<McpToolInfo> {
+    tools::TOOLS
+        .iter()
+        .map(|t| McpToolInfo {
+            name: t.name.to_string(),
+            description: t.description.to_string(),
+            default_enabled: t.default_enabled,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(server: &McpServer, message: Value) -> Value {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(server.handle(message))
+            .unwrap()
+    }
+
+    #[test]
+    fn speaks_initialize_list_and_call() {
+        let server = McpServer::new(None).unwrap();
+        let init = call(
+            &server,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        );
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        let list = call(
+            &server,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        );
+        let names: Vec<&str> = list["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        assert!(names.contains(&"subnet_calc"));
+        assert!(!names.contains(&"scan_ports"));
+
+        let result = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                "params": { "name": "subnet_calc", "arguments": { "cidr": "10.0.0.0/30" } },
+            }),
+        );
+        assert_eq!(result["result"]["isError"], false);
+        assert_eq!(result["result"]["structuredContent"]["usableHosts"], "2");
+
+        let failed = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0", "id": 4, "method": "tools/call",
+                "params": { "name": "subnet_calc", "arguments": {} },
+            }),
+        );
+        assert_eq!(failed["result"]["isError"], true);
+    }
+
+    #[test]
+    fn enforces_allowlist() {
+        let server = McpServer::new(Some(&["hash_text".to_string()])).unwrap();
+        let denied = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                "params": { "name": "scan_ports", "arguments": { "target": "127.0.0.1" } },
+            }),
+        );
+        assert_eq!(denied["error"]["code"], INVALID_PARAMS);
+        assert!(McpServer::new(Some(&["rm_rf".to_string()])).is_err());
+        assert!(McpServer::new(Some(&[])).is_err());
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let note = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(runtime.block_on(server.handle(note)).is_none());
+    }
+
+    #[test]
+    fn accepts_only_loopback_origins() {
+        let mut headers = HeaderMap::new();
+        assert!(origin_allowed(&headers));
+        for (origin, ok) in [
+            ("http://localhost:5173", true),
+            ("http://127.0.0.1", true),
+            ("http://[::1]:8080", true),
+            ("https://evil.example", false),
+            ("http://localhost.evil.example", false),
+        ] {
+            headers.insert(header::ORIGIN, HeaderValue::from_static(origin));
+            assert_eq!(origin_allowed(&headers), ok, "{origin}");
+        }
+    }
+}
@@ -0,0 +1,447 @@
+//! Tools exposed over MCP
+//!
+//! Each tool wraps logic the desktop app already uses. Everything here is
+//! local and side-effect free except `scan_ports`, which touches the
+//! network and is therefore off unless explicitly allowlisted.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use ipnetwork::IpNetwork;
+use serde::Serialize;
+use serde_json::{json, Value};
+use x509_parser::pem::Pem;
+
+use crate::document::DocFormat;
+use crate::network::scanner::{collect_scan, ScanState};
+use crate::network::types::{PortPreset, ScanMode, ScanRequest};
+
+/// Upper bound for `generate_uuids.count`
+const MAX_UUIDS: u64 = 1000;
+
+/// Largest text accepted by the text tools
+const MAX_TEXT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Static description of one tool.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolSpec {
+    /// Tool name used in `tools/call`.
+    pub name: &'static str,
+    /// Description shown to the agent.
+    pub description: &'static str,
+    /// Whether the tool is enabled without an explicit allowlist.
+    pub default_enabled: bool,
+    input_schema: fn() -> Value,
+}
+
+impl ToolSpec {
+    /// JSON Schema of the tool arguments.
+    pub fn input_schema(&self) -> Value {
+        (self.input_schema)()
+    }
+}
+
+/// Every tool the server knows, in listing order.
+pub const TOOLS: &[ToolSpec] = &[
+    ToolSpec {
+        name: "format_document",
+        description: "Pretty-print or minify a JSON, YAML, or TOML document.",
+        default_enabled: true,
+        input_schema: format_schema,
+    },
+    ToolSpec {
+        name: "convert_document",
+        description: "Convert a document between JSON, YAML, and TOML.",
+        default_enabled: true,
+        input_schema: convert_schema,
+    },
+    ToolSpec {
+        name: "hash_text",
+        description: "Hex digests of UTF-8 text (md5, sha1, sha224, sha256, sha384, sha512).",
+        default_enabled: true,
+        input_schema: hash_schema,
+    },
+    ToolSpec {
+        name: "generate_uuids",
+        description: "Generate random (v4) UUIDs.",
+        default_enabled: true,
+        input_schema: uuid_schema,
+    },
+    ToolSpec {
+        name: "subnet_calc",
+        description: "Network, broadcast, mask, and host range of an IPv4 or IPv6 CIDR block.",
+        default_enabled: true,
+        input_schema: subnet_schema,
+    },
+    ToolSpec {
+        name: "inspect_certificate",
+        description: "Decode PEM X.509 certificates: subject, issuer, validity, SANs, fingerprint.",
+        default_enabled: true,
+        input_schema: certificate_schema,
+    },
+    ToolSpec {
+        name: "scan_ports",
+        description: "TCP connect scan of a host. Disabled unless explicitly allowlisted.",
+        default_enabled: false,
+        input_schema: scan_schema,
+    },
+];
+
+/// Look up a tool by name.
+pub fn find(name: &str) -> Option<&'static ToolSpec> {
+    TOOLS.iter().find(|t| t.name == name)
+}
+
+fn format_names() -> Value {
+    json!({ "type": "string", "enum": ["json", "yaml", "toml"] })
+}
+
+fn format_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "format": format_names(),
+            "text": { "type": "string" },
+            "indent": { "type": "integer", "minimum": 0, "maximum": 8, "default": 2 },
+            "minify": { "type": "boolean", "default": false },
+        },
+        "required": ["format", "text"],
+    })
+}
+
+fn convert_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "from": format_names(),
+            "to": format_names(),
+            "text": { "type": "string" },
+        },
+        "required": ["from", "to", "text"],
+    })
+}
+
+fn hash_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "text": { "type": "string" },
+            "algorithms": {
+                "type": "array",
+                "items": { "type": "string" },
+                "default": ["sha256"],
+            },
+        },
+        "required": ["text"],
+    })
+}
+
+fn uuid_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "count": { "type": "integer", "minimum": 1, "maximum": MAX_UUIDS, "default": 1 },
+        },
+    })
+}
+
+fn subnet_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": { "cidr": { "type": "string", "examples": ["192.168.1.0/24"] } },
+        "required": ["cidr"],
+    })
+}
+
+fn certificate_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": { "pem": { "type": "string" } },
+        "required": ["pem"],
+    })
+}
+
+fn scan_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "target": { "type": "string" },
+            "ports": { "type": "string", "default": "1-1024" },
+        },
+        "required": ["target"],
+    })
+}
+
+fn arg_str<'a>(args: &'a Value, name: &str) -> Result<&'a str, String> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing string argument: {name}"))
+}
+
+fn arg_text<'a>(args: &'a Value, name: &str) -> Result<&'a str, String> {
+    let text = arg_str(args, name)?;
+    if text.len() > MAX_TEXT_BYTES {
+        return Err(format!("{name} exceeds {MAX_TEXT_BYTES} bytes"));
+    }
+    Ok(text)
+}
+
+fn arg_format(args: &Value, name: &str) -> Result<DocFormat, String> {
+    arg_str(args, name)?.parse()
+}
+
+fn format_document(args: &Value) -> Result<Value, String> {
+    let format = arg_format(args, "format")?;
+    let indent = args
+        .get("indent")
+        .and_then(Value::as_u64)
+        .map_or(2, |n| usize::try_from(n.min(8)).unwrap_or(2));
+    let minify = args.get("minify").and_then(Value::as_bool).unwrap_or(false);
+    let value = format.read(arg_text(args, "text")?)?;
+    Ok(json!({ "output": format.write(&value, indent, minify)? }))
+}
+
+fn convert_document(args: &Value) -> Result<Value, String> {
+    let from = arg_format(args, "from")?;
+    let to = arg_format(args, "to")?;
+    let value = from.read(arg_text(args, "text")?)?;
+    Ok(json!({ "output": to.write(&value, 2, false)? }))
+}
+
+fn hash_text(args: &Value) -> Result<Value, String> {
+    let algorithms: Vec<String> = match args.get("algorithms").and_then(Value::as_array) {
+        Some(list) => list
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        None => vec!["sha256".to_string()],
+    };
+    let hashes = crate::hash_text::digest_bytes(arg_text(args, "text")?.as_bytes(), &algorithms);
+    if hashes.is_empty() {
+        return Err("No supported algorithm given".to_string());
+    }
+    Ok(json!({ "hashes": hashes }))
+}
+
+fn generate_uuids(args: &Value) -> Result<Value, String> {
+    let count = args.get("count").and_then(Value::as_u64).unwrap_or(1);
+    if count == 0 || count > MAX_UUIDS {
+        return Err(format!("count must be between 1 and {MAX_UUIDS}"));
+    }
+    let uuids: Vec<String> = (0..count)
+        .map(|_| uuid::Uuid::new_v4().to_string())
+        .collect();
+    Ok(json!({ "uuids": uuids }))
+}
+
+/// Result of `subnet_calc`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubnetInfo {
+    network: IpAddr,
+    prefix: u8,
+    netmask: IpAddr,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    broadcast: Option<IpAddr>,
+    first_host: IpAddr,
+    last_host: IpAddr,
+    /// Decimal string; IPv6 blocks exceed every JSON number type.
+    total_addresses: String,
+    usable_hosts: String,
+}
+
+fn subnet_calc(args: &Value) -> Result<Value, String> {
+    let cidr = arg_str(args, "cidr")?.trim();
+    let network: IpNetwork = cidr
+        .parse()
+        .map_err(|e| format!("Invalid CIDR notation: {e}"))?;
+    let prefix = network.prefix();
+    let info = match network {
+        IpNetwork::V4(v4) => {
+            let total = 1u64 << (32 - u32::from(prefix));
+            let (first, last, usable) = match prefix {
+                32 => (v4.network(), v4.network(), 1),
+                31 => (v4.network(), v4.broadcast(), 2),
+                _ => (
+                    v4.network().to_bits().saturating_add(1).into(),
+                    v4.broadcast().to_bits().saturating_sub(1).into(),
+                    total - 2,
+                ),
+            };
+            SubnetInfo {
+                network: v4.network().into(),
+                prefix,
+                netmask: v4.mask().into(),
+                broadcast: (prefix < 31).then(|| v4.broadcast().into()),
+                first_host: first.into(),
+                last_host: last.into(),
+                total_addresses: total.to_string(),
+                usable_hosts: usable.to_string(),
+            }
+        }
+        IpNetwork::V6(v6) => {
+            let host_bits = 128 - u32::from(prefix);
+            let total = 1u128.checked_shl(host_bits).map_or_else(
+                || "340282366920938463463374607431768211456".to_string(),
+                |n| n.to_string(),
+            );
+            let last =
+                v6.network().to_bits() | u128::MAX.checked_shr(u32::from(prefix)).unwrap_or(0);
+            SubnetInfo {
+                network: v6.network().into(),
+                prefix,
+                netmask: v6.mask().into(),
+                broadcast: None,
+                first_host: v6.network().into(),
+                last_host: std::net::Ipv6Addr::from_bits(last).into(),
+                usable_hosts: total.clone(),
+                total_addresses: total,
+            }
+        }
+    };
+    serde_json::to_value(info).map_err(|e| format!("Failed to serialize result: {e}"))
+}
+
+/// Summary of one certificate for `inspect_certificate`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CertificateSummary {
+    subject: String,
+    issuer: String,
+    serial: String,
+    not_before: i64,
+    not_after: i64,
+    subject_alt_names: Vec<String>,
+    signature_algorithm: String,
+    is_ca: bool,
+    self_signed: bool,
+    sha256_fingerprint: String,
+}
+
+fn inspect_certificate(args: &Value) -> Result<Value, String> {
+    use sha2::{Digest, Sha256};
+
+    let pem = arg_text(args, "pem")?;
+    let mut certificates = Vec::new();
+    for block in Pem::iter_from_buffer(pem.as_bytes()) {
+        let block = block.map_err(|e| format!("Invalid PEM: {e}"))?;
+        if block.label != "CERTIFICATE" {
+            continue;
+        }
+        let cert = block
+            .parse_x509()
+            .map_err(|e| format!("Invalid certificate: {e}"))?;
+        let subject_alt_names = cert
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|san| {
+                san.value
+                    .general_names
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        certificates.push(CertificateSummary {
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            serial: cert.raw_serial_as_string(),
+            not_before: cert.validity().not_before.timestamp(),
+            not_after: cert.validity().not_after.timestamp(),
+            subject_alt_names,
+            signature_algorithm: cert.signature_algorithm.algorithm.to_id_string(),
+            is_ca: cert.is_ca(),
+            self_signed: cert.subject() == cert.issuer(),
+            sha256_fingerprint: hex::encode(Sha256::digest(&block.contents)),
+        });
+    }
+    if certificates.is_empty() {
+        return Err("No PEM certificate found".to_string());
+    }
+    Ok(json!({ "certificates": certificates }))
+}
+
+async fn scan_ports(args: &Value) -> Result<Value, String> {
+    let request = ScanRequest {
+        target: arg_str(args, "target")?.to_string(),
+        mode: ScanMode::Custom,
+        port_preset: PortPreset::Custom,
+        port_range: Some(
+            args.get("ports")
+                .and_then(Value::as_str)
+                .unwrap_or("1-1024")
+                .to_string(),
+        ),
+        concurrency: 100,
+        timeout_ms: 1000,
+        resolution: None,
+    };
+    let (scan_state, _cancel_rx) = ScanState::new();
+    let results = collect_scan(request, Arc::new(scan_state))
+        .await
+        .map_err(String::from)?;
+    serde_json::to_value(results).map_err(|e| format!("Failed to serialize result: {e}"))
+}
+
+/// Run tool `name` with `args`. The caller checks the allowlist.
+pub async fn call(name: &str, args: &Value) -> Result<Value, String> {
+    match name {
+        "format_document" => format_document(args),
+        "convert_document" => convert_document(args),
+        "hash_text" => hash_text(args),
+        "generate_uuids" => generate_uuids(args),
+        "subnet_calc" => subnet_calc(args),
+        "inspect_certificate" => inspect_certificate(args),
+        "scan_ports" => scan_ports(args).await,
+        other => Err(format!("Unknown tool: {other}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_tool_has_an_object_schema() {
+        for tool in TOOLS {
+            assert_eq!(tool.input_schema()["type"], "object", "{}", tool.name);
+        }
+        assert!(!find("scan_ports").unwrap().default_enabled);
+    }
+
+    #[test]
+    fn computes_ipv4_and_ipv6_subnets() {
+        let v4 = subnet_calc(&json!({ "cidr": "192.168.1.77/24" })).unwrap();
+        assert_eq!(v4["network"], "192.168.1.0");
+        assert_eq!(v4["netmask"], "255.255.255.0");
+        assert_eq!(v4["broadcast"], "192.168.1.255");
+        assert_eq!(v4["firstHost"], "192.168.1.1");
+        assert_eq!(v4["lastHost"], "192.168.1.254");
+        assert_eq!(v4["usableHosts"], "254");
+
+        let v6 = subnet_calc(&json!({ "cidr": "2001:db8::/126" })).unwrap();
+        assert_eq!(v6["lastHost"], "2001:db8::3");
+        assert_eq!(v6["totalAddresses"], "4");
+        assert!(subnet_calc(&json!({ "cidr": "nope" })).is_err());
+    }
+
+    #[test]
+    fn formats_converts_and_hashes() {
+        let out =
+            format_document(&json!({ "format": "json", "text": "{\"a\":1}", "minify": false }))
+                .unwrap();
+        assert_eq!(out["output"], "{\n  \"a\": 1\n}");
+        let out = convert_document(&json!({ "from": "json", "to": "yaml", "text": "{\"a\":1}" }))
+            .unwrap();
+        assert_eq!(out["output"], "a: 1\n");
+        let out = hash_text(&json!({ "text": "" })).unwrap();
+        assert_eq!(
+            out["hashes"]["sha256"],
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert!(generate_uuids(&json!({ "count": 0 })).is_err());
+        assert!(inspect_certificate(&json!({ "pem": "not a cert" })).is_err());
+    }
+}
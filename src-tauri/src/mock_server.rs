@@ -0,0 +1,878 @@
+//! Mock API server generated from an OpenAPI document.
+//!
+//! [`mock_server_start`] parses an OpenAPI 3.x or Swagger 2.0 document (JSON
+//! or YAML), builds one mock route per documented operation, and serves them
+//! from a loopback HTTP listener. Each route answers with the first
+//! documented example for its success response, or with sample data
+//! generated from the response schema when no example exists.
+//!
+//! Routes can be tuned while the server runs through
+//! [`mock_server_update_route`]: an added latency and an error rate with a
+//! configurable status let frontend code be exercised against slow and
+//! flaky backends. Every served request is emitted to the frontend via the
+//! `mock_server_request` event.
+//!
+//! Like the webhook receiver, the listener binds to `127.0.0.1` only.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::any;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::Emitter;
+use tokio::sync::{oneshot, Mutex, RwLock};
+
+/// Tauri event name carrying each served request.
+const MOCK_REQUEST_EVENT: &str = "mock_server_request";
+
+/// HTTP methods an OpenAPI path item may declare, in document order.
+const OPERATION_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Maximum schema nesting expanded when generating sample data. Deeper (or
+/// recursive) schemas collapse to `null`.
+const MAX_SAMPLE_DEPTH: usize = 8;
+
+/// Upper bound on injected latency so a typo cannot hang a request forever.
+const MAX_LATENCY_MS: u32 = 60_000;
+
+/// Per-route behaviour that can be changed while the server runs.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockRouteBehavior {
+    /// Extra delay before responding, in milliseconds.
+    pub latency_ms: u32,
+    /// Probability in `0.0..=1.0` that the route answers with `error_status`
+    /// instead of its mock response.
+    pub error_rate: f64,
+    /// Status returned for injected failures. Defaults to `500`.
+    pub error_status: Option<u16>,
+}
+
+/// One mock route derived from an OpenAPI operation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockRouteInfo {
+    /// Upper-case HTTP method.
+    pub method: String,
+    /// Path template as written in the document (e.g. `/pets/{id}`).
+    pub path: String,
+    /// `operationId`, when the document declares one.
+    pub operation_id: Option<String>,
+    /// Status code served on success.
+    pub status: u16,
+    /// Content type of the mock body.
+    pub content_type: String,
+    /// `true` when the body comes from a documented example, `false` when it
+    /// was generated from the schema.
+    pub from_example: bool,
+    /// Current latency / error injection settings.
+    pub behavior: MockRouteBehavior,
+}
+
+/// Start payload sent from the frontend.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockServerStartRequest {
+    /// OpenAPI document as JSON or YAML text.
+    pub spec: String,
+    /// Port to bind. `None` picks an ephemeral port.
+    pub port: Option<u16>,
+    /// Latency applied to every route unless overridden later.
+    pub default_latency_ms: u32,
+}
+
+/// Result returned after a successful start.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockServerStartResult {
+    /// Full origin including the document's base path
+    /// (e.g. `http://127.0.0.1:54321/v1`).
+    pub address: String,
+    /// Bound port number.
+    pub port: u16,
+    /// Document title from `info.title`.
+    pub title: String,
+    /// Routes being served.
+    pub routes: Vec<MockRouteInfo>,
+}
+
+/// Reported state for the status command.
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MockServerStatus {
+    /// `true` when a server task is currently running.
+    pub running: bool,
+    /// Bound origin when running.
+    pub address: Option<String>,
+    /// Routes being served when running.
+    pub routes: Vec<MockRouteInfo>,
+}
+
+/// Event payload describing one served request.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct MockRequestEvent {
+    timestamp_ms: u128,
+    method: String,
+    path: String,
+    query: String,
+    /// Matched path template, `None` when no route matched.
+    route: Option<String>,
+    status: u16,
+    injected_error: bool,
+    latency_ms: u32,
+}
+
+/// A route with its pre-rendered response body.
+#[derive(Debug, Clone)]
+struct MockRoute {
+    info: MockRouteInfo,
+    segments: Vec<PathSegment>,
+    body: String,
+}
+
+/// One `/`-separated piece of a path template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Literal(String),
+    Param,
+}
+
+/// State shared with the request handler.
+#[derive(Clone)]
+struct HandlerState {
+    app: tauri::AppHandle,
+    base_path: String,
+    routes: Arc<RwLock<Vec<MockRoute>>>,
+}
+
+/// Per-server task handle.
+struct RunningServer {
+    shutdown: oneshot::Sender<()>,
+    address: String,
+    routes: Arc<RwLock<Vec<MockRoute>>>,
+}
+
+/// Tauri-managed state. `None` when no server is running.
+#[derive(Default)]
+pub struct MockServerState {
+    inner: Mutex<Option<RunningServer>>,
+}
+
+impl MockServerState {
+    /// Construct an empty state ready to host a single server.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Build a loopback `SocketAddr`; the only place the bind address is made.
+fn bind_loopback(port: u16) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], port))
+}
+
+/// Return current wall-clock time in epoch milliseconds.
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis())
+}
+
+// =============================================================================
+// Document parsing
+// =============================================================================
+
+/// Parse the document as JSON first, then YAML.
+fn parse_spec(text: &str) -> Result<Value, String> {
+    serde_json::from_str(text).or_else(|_| {
+        serde_yaml::from_str::<Value>(text).map_err(|e| format!("Invalid OpenAPI document: {e}"))
+    })
+}
+
+/// Extract the path prefix every route is served under: `basePath` for
+/// Swagger 2.0, or the path component of the first `servers` URL.
+fn base_path(spec: &Value) -> String {
+    let raw = spec
+        .get("basePath")
+        .and_then(Value::as_str)
+        .or_else(|| {
+            spec.pointer("/servers/0/url")
+                .and_then(Value::as_str)
+                .map(|url| {
+                    url.split_once("://")
+                        .map_or(url, |(_, rest)| rest.find('/').map_or("", |i| &rest[i..]))
+                })
+        })
+        .unwrap_or("");
+    raw.trim_end_matches('/').to_string()
+}
+
+/// Split a path template into matchable segments.
+fn parse_template(path: &str) -> Vec<PathSegment> {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            if s.starts_with('{') && s.ends_with('}') {
+                PathSegment::Param
+            } else {
+                PathSegment::Literal(s.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Whether `path` matches the template segments.
+fn matches_template(segments: &[PathSegment], path: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    parts.len() == segments.len()
+        && segments.iter().zip(parts).all(|(seg, part)| match seg {
+            PathSegment::Literal(lit) => lit == part,
+            PathSegment::Param => true,
+        })
+}
+
+/// Follow a local `$ref` (`#/components/schemas/X`, `#/definitions/X`, ...).
+fn resolve_ref<'a>(spec: &'a Value, node: &'a Value) -> &'a Value {
+    let mut current = node;
+    // Bounded so a self-referencing alias cannot loop forever.
+    for _ in 0..16 {
+        match current
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| spec.pointer(pointer))
+        {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+/// Pick the response served on success: the lowest documented 2xx, then
+/// `default`, then whatever is listed first.
+fn pick_response(responses: &Map<String, Value>) -> Option<(u16, &Value)> {
+    let mut numbered: Vec<(u16, &Value)> = responses
+        .iter()
+        .filter_map(|(code, resp)| code.parse::<u16>().ok().map(|c| (c, resp)))
+        .collect();
+    numbered.sort_by_key(|(c, _)| *c);
+
+    numbered
+        .iter()
+        .find(|(c, _)| (200..300).contains(c))
+        .copied()
+        .or_else(|| responses.get("default").map(|r| (200, r)))
+        .or_else(|| numbered.first().copied())
+}
+
+/// Produce `(content_type, body, from_example)` for a response object.
+fn render_response(spec: &Value, response: &Value) -> (String, Option<Value>, bool) {
+    let response = resolve_ref(spec, response);
+
+    // OpenAPI 3: `content: { <media type>: { example | examples | schema } }`.
+    if let Some(content) = response.get("content").and_then(Value::as_object) {
+        let Some((media_type, media)) = content
+            .iter()
+            .find(|(k, _)| k.contains("json"))
+            .or_else(|| content.iter().next())
+        else {
+            return ("application/json".to_string(), None, false);
+        };
+        if let Some(example) = media.get("example") {
+            return (media_type.clone(), Some(example.clone()), true);
+        }
+        if let Some(example) = media
+            .get("examples")
+            .and_then(Value::as_object)
+            .and_then(|m| m.values().next())
+            .map(|e| resolve_ref(spec, e))
+            .and_then(|e| e.get("value"))
+        {
+            return (media_type.clone(), Some(example.clone()), true);
+        }
+        let body = media
+            .get("schema")
+            .map(|schema| sample_from_schema(spec, schema, 0));
+        return (media_type.clone(), body, false);
+    }
+
+    // Swagger 2.0: `examples: { <mime>: value }` and a top-level `schema`.
+    if let Some((mime, example)) = response
+        .get("examples")
+        .and_then(Value::as_object)
+        .and_then(|m| m.iter().next())
+    {
+        return (mime.clone(), Some(example.clone()), true);
+    }
+    let body = response
+        .get("schema")
+        .map(|schema| sample_from_schema(spec, schema, 0));
+    ("application/json".to_string(), body, false)
+}
+
+/// Generate representative sample data for a JSON schema.
+fn sample_from_schema(spec: &Value, schema: &Value, depth: usize) -> Value {
+    if depth > MAX_SAMPLE_DEPTH {
+        return Value::Null;
+    }
+    let schema = resolve_ref(spec, schema);
+
+    if let Some(value) = schema.get("example").or_else(|| schema.get("default")) {
+        return value.clone();
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|v| v.first())
+    {
+        return first.clone();
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let merged = parts
+            .iter()
+            .map(|p| sample_from_schema(spec, p, depth + 1))
+            .fold(Map::new(), |mut acc, part| {
+                if let Value::Object(fields) = part {
+                    acc.extend(fields);
+                }
+                acc
+            });
+        return Value::Object(merged);
+    }
+    if let Some(first) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+        .and_then(|v| v.first())
+    {
+        return sample_from_schema(spec, first, depth + 1);
+    }
+
+    let schema_type = schema.get("type").and_then(|t| match t {
+        // OpenAPI 3.1 allows `type: [string, "null"]`.
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null"),
+        other => other.as_str(),
+    });
+    let schema_type = schema_type.or_else(|| {
+        if schema.get("properties").is_some() {
+            Some("object")
+        } else if schema.get("items").is_some() {
+            Some("array")
+        } else {
+            None
+        }
+    });
+
+    match schema_type {
+        Some("object") => {
+            let fields = schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|props| {
+                    props
+                        .iter()
+                        .map(|(name, prop)| {
+                            (name.clone(), sample_from_schema(spec, prop, depth + 1))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Value::Object(fields)
+        }
+        Some("array") => schema.get("items").map_or_else(
+            || json!([]),
+            |items| json!([sample_from_schema(spec, items, depth + 1)]),
+        ),
+        Some("string") => sample_string(schema.get("format").and_then(Value::as_str)),
+        Some("integer") => schema.get("minimum").cloned().unwrap_or_else(|| json!(0)),
+        Some("number") => schema.get("minimum").cloned().unwrap_or_else(|| json!(0.0)),
+        Some("boolean") => json!(true),
+        _ => Value::Null,
+    }
+}
+
+/// Sample value for a string schema, honouring common `format`s.
+fn sample_string(format: Option<&str>) -> Value {
+    let sample = match format {
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "00:00:00",
+        Some("email") => "user@example.com",
+        Some("uuid") => "00000000-0000-4000-8000-000000000000",
+        Some("uri" | "url") => "https://example.com",
+        Some("hostname") => "example.com",
+        Some("ipv4") => "192.0.2.1",
+        Some("ipv6") => "2001:db8::1",
+        Some("byte") => "c3RyaW5n",
+        _ => "string",
+    };
+    Value::String(sample.to_string())
+}
+
+/// Build every mock route declared by the document. Literal-only routes sort
+/// ahead of templated ones so `/pets/mine` wins over `/pets/{id}`.
+fn build_routes(spec: &Value, default_latency_ms: u32) -> Result<Vec<MockRoute>, String> {
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "Document has no `paths` object".to_string())?;
+
+    let mut routes: Vec<MockRoute> = paths
+        .iter()
+        .flat_map(|(path, item)| {
+            let item = resolve_ref(spec, item);
+            OPERATION_METHODS.iter().filter_map(move |method| {
+                let operation = item.get(*method)?;
+                let (status, content_type, body, from_example) = operation
+                    .get("responses")
+                    .and_then(Value::as_object)
+                    .and_then(pick_response)
+                    .map_or_else(
+                        || (200, "application/json".to_string(), None, false),
+                        |(status, response)| {
+                            let (ct, body, from_example) = render_response(spec, response);
+                            (status, ct, body, from_example)
+                        },
+                    );
+                let body = match body {
+                    None => String::new(),
+                    Some(Value::String(s)) if !content_type.contains("json") => s,
+                    Some(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+                };
+                Some(MockRoute {
+                    info: MockRouteInfo {
+                        method: method.to_uppercase(),
+                        path: path.clone(),
+                        operation_id: operation
+                            .get("operationId")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        status,
+                        content_type,
+                        from_example,
+                        behavior: MockRouteBehavior {
+                            latency_ms: default_latency_ms,
+                            ..MockRouteBehavior::default()
+                        },
+                    },
+                    segments: parse_template(path),
+                    body,
+                })
+            })
+        })
+        .collect();
+
+    if routes.is_empty() {
+        return Err("Document declares no operations".to_string());
+    }
+    routes.sort_by_key(|r| {
+        r.segments
+            .iter()
+            .filter(|s| **s == PathSegment::Param)
+            .count()
+    });
+    Ok(routes)
+}
+
+// =============================================================================
+// Serving
+// =============================================================================
+
+/// Attach permissive CORS headers; the mock is consumed from a dev server on
+/// another origin.
+fn with_cors(mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    headers.insert("access-control-allow-origin", HeaderValue::from_static("*"));
+    headers.insert(
+        "access-control-allow-methods",
+        HeaderValue::from_static("GET, POST, PUT, PATCH, DELETE, HEAD, OPTIONS"),
+    );
+    headers.insert(
+        "access-control-allow-headers",
+        HeaderValue::from_static("*"),
+    );
+    response
+}
+
+/// JSON error body used for unmatched routes and injected failures.
+fn error_response(status: StatusCode, message: &str) -> Response {
+    let body = json!({ "error": message, "status": status.as_u16() }).to_string();
+    let mut response = (status, body).into_response();
+    response.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// Request handler: match a route, apply its behaviour, and respond.
+async fn handle(State(state): State<HandlerState>, request: Request) -> Response {
+    let method = request.method().clone();
+    let full_path = request.uri().path().to_string();
+    let query = request.uri().query().unwrap_or("").to_string();
+    let path = full_path
+        .strip_prefix(state.base_path.as_str())
+        .unwrap_or(&full_path)
+        .to_string();
+
+    let matched = {
+        let routes = state.routes.read().await;
+        routes
+            .iter()
+            .find(|r| r.info.method == method.as_str() && matches_template(&r.segments, &path))
+            .cloned()
+    };
+
+    let (response, route, injected_error, latency_ms) = match matched {
+        Some(route) => {
+            let behavior = &route.info.behavior;
+            let latency_ms = behavior.latency_ms.min(MAX_LATENCY_MS);
+            if latency_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(u64::from(latency_ms))).await;
+            }
+            let inject = behavior.error_rate > 0.0 && rand::random::<f64>() < behavior.error_rate;
+            let response = if inject {
+                let status = behavior
+                    .error_status
+                    .and_then(|s| StatusCode::from_u16(s).ok())
+                    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                error_response(status, "Injected failure")
+            } else {
+                let status = StatusCode::from_u16(route.info.status).unwrap_or(StatusCode::OK);
+                let mut response = (status, route.body.clone()).into_response();
+                if let Ok(ct) = HeaderValue::from_str(&route.info.content_type) {
+                    response
+                        .headers_mut()
+                        .insert(axum::http::header::CONTENT_TYPE, ct);
+                }
+                response
+            };
+            (response, Some(route.info.path), inject, latency_ms)
+        }
+        // Answer CORS preflights for any path the document does not cover.
+        None if method == Method::OPTIONS => {
+            (StatusCode::NO_CONTENT.into_response(), None, false, 0)
+        }
+        None => (
+            error_response(
+                StatusCode::NOT_FOUND,
+                &format!("No mock route for {method} {path}"),
+            ),
+            None,
+            false,
+            0,
+        ),
+    };
+
+    let _ = state.app.emit(
+        MOCK_REQUEST_EVENT,
+        MockRequestEvent {
+            timestamp_ms: now_ms(),
+            method: method.to_string(),
+            path: full_path,
+            query,
+            route,
+            status: response.status().as_u16(),
+            injected_error,
+            latency_ms,
+        },
+    );
+
+    with_cors(response)
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+/// Parse an OpenAPI document and start serving mock responses. Stops any
+/// previously running mock server first.
+///
+/// # Errors
+///
+/// Returns an error string when the document cannot be parsed, declares no
+/// operations, or the requested port cannot be bound.
+#[tauri::command]
+pub async fn mock_server_start(
+    state: tauri::State<'_, MockServerState>,
+    app: tauri::AppHandle,
+    req: MockServerStartRequest,
+) -> Result<MockServerStartResult, String> {
+    let spec = parse_spec(&req.spec)?;
+    let routes = build_routes(&spec, req.default_latency_ms)?;
+    let base = base_path(&spec);
+    let title = spec
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .unwrap_or("Mock API")
+        .to_string();
+
+    {
+        let mut guard = state.inner.lock().await;
+        if let Some(existing) = guard.take() {
+            let _ = existing.shutdown.send(());
+        }
+    }
+
+    let infos: Vec<MockRouteInfo> = routes.iter().map(|r| r.info.clone()).collect();
+    let routes = Arc::new(RwLock::new(routes));
+    let handler_state = HandlerState {
+        app,
+        base_path: base.clone(),
+        routes: Arc::clone(&routes),
+    };
+    let router = Router::new()
+        .fallback(any(handle))
+        .with_state(handler_state);
+
+    let bind_addr = bind_loopback(req.port.unwrap_or(0));
+    let listener = tokio::net::TcpListener::bind(bind_addr)
+        .await
+        .map_err(|e| format!("Failed to bind {bind_addr}: {e}"))?;
+    let bound = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read bound address: {e}"))?;
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+    });
+
+    let address = format!("http://{bound}{base}");
+    {
+        let mut guard = state.inner.lock().await;
+        *guard = Some(RunningServer {
+            shutdown: shutdown_tx,
+            address: address.clone(),
+            routes,
+        });
+    }
+
+    Ok(MockServerStartResult {
+        address,
+        port: bound.port(),
+        title,
+        routes: infos,
+    })
+}
+
+/// Change the latency / error injection settings of one running route.
+///
+/// # Errors
+///
+/// Returns an error string when no server is running, the route does not
+/// exist, or `error_rate` is outside `0.0..=1.0`.
+#[tauri::command]
+pub async fn mock_server_update_route(
+    state: tauri::State<'_, MockServerState>,
+    method: String,
+    path: String,
+    behavior: MockRouteBehavior,
+) -> Result<MockRouteInfo, String> {
+    if !(0.0..=1.0).contains(&behavior.error_rate) {
+        return Err(format!(
+            "Error rate must be between 0 and 1, got {}",
+            behavior.error_rate
+        ));
+    }
+    let routes = state
+        .inner
+        .lock()
+        .await
+        .as_ref()
+        .map(|running| Arc::clone(&running.routes))
+        .ok_or_else(|| "Mock server is not running".to_string())?;
+
+    let mut routes = routes.write().await;
+    let route = routes
+        .iter_mut()
+        .find(|r| r.info.method.eq_ignore_ascii_case(&method) && r.info.path == path)
+        .ok_or_else(|| format!("No mock route for {method} {path}"))?;
+    route.info.behavior = behavior;
+    Ok(route.info.clone())
+}
+
+/// Stop the mock server if one is running. No-op when already stopped.
+///
+/// # Errors
+///
+/// Currently never returns an error; the signature mirrors `webhook_stop`.
+#[tauri::command]
+pub async fn mock_server_stop(state: tauri::State<'_, MockServerState>) -> Result<(), String> {
+    let taken = state.inner.lock().await.take();
+    if let Some(existing) = taken {
+        let _ = existing.shutdown.send(());
+    }
+    Ok(())
+}
+
+/// Report whether the mock server is running and which routes it serves.
+///
+/// # Errors
+///
+/// Currently never returns an error; the signature mirrors `webhook_status`.
+#[tauri::command]
+pub async fn mock_server_status(
+    state: tauri::State<'_, MockServerState>,
+) -> Result<MockServerStatus, String> {
+    let guard = state.inner.lock().await;
+    let Some(running) = guard.as_ref() else {
+        return Ok(MockServerStatus::default());
+    };
+    let routes = running
+        .routes
+        .read()
+        .await
+        .iter()
+        .map(|r| r.info.clone())
+        .collect();
+    Ok(MockServerStatus {
+        running: true,
+        address: Some(running.address.clone()),
+        routes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = r##"
+openapi: 3.0.3
+info:
+  title: Petstore
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+  /pets/{id}:
+    get:
+      responses:
+        "404":
+          description: missing
+        "200":
+          content:
+            application/json:
+              example: { "id": 7, "name": "Rex" }
+  /pets/mine:
+    delete:
+      responses:
+        "204":
+          description: gone
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id: { type: integer, format: int64 }
+        name: { type: string }
+        born: { type: string, format: date }
+"##;
+
+    #[test]
+    fn bind_loopback_uses_127_0_0_1() {
+        assert_eq!(bind_loopback(0).ip().to_string(), "127.0.0.1");
+    }
+
+    #[test]
+    fn base_path_reads_servers_and_swagger_base_path() {
+        assert_eq!(base_path(&parse_spec(PETSTORE).unwrap()), "/v1");
+        assert_eq!(base_path(&json!({ "basePath": "/api/" })), "/api");
+        assert_eq!(base_path(&json!({ "servers": [{ "url": "/" }] })), "");
+    }
+
+    #[test]
+    fn templates_match_literal_and_param_segments() {
+        let segments = parse_template("/pets/{id}/toys");
+        assert!(matches_template(&segments, "/pets/42/toys"));
+        assert!(!matches_template(&segments, "/pets/42"));
+        assert!(!matches_template(&segments, "/owners/42/toys"));
+    }
+
+    #[test]
+    fn routes_prefer_examples_and_generate_from_schema() {
+        let routes = build_routes(&parse_spec(PETSTORE).unwrap(), 0).unwrap();
+        assert_eq!(routes.len(), 3);
+
+        let by_id = routes.iter().find(|r| r.info.path == "/pets/{id}").unwrap();
+        assert_eq!(by_id.info.status, 200);
+        assert!(by_id.info.from_example);
+        let body: Value = serde_json::from_str(&by_id.body).unwrap();
+        assert_eq!(body["name"], "Rex");
+
+        let list = routes.iter().find(|r| r.info.path == "/pets").unwrap();
+        assert!(!list.info.from_example);
+        assert_eq!(list.info.operation_id.as_deref(), Some("listPets"));
+        let body: Value = serde_json::from_str(&list.body).unwrap();
+        assert_eq!(
+            body,
+            json!([{ "id": 0, "name": "string", "born": "2024-01-01" }])
+        );
+    }
+
+    #[test]
+    fn literal_routes_sort_before_templated_routes() {
+        let routes = build_routes(&parse_spec(PETSTORE).unwrap(), 0).unwrap();
+        let mine = routes.iter().position(|r| r.info.path == "/pets/mine");
+        let by_id = routes.iter().position(|r| r.info.path == "/pets/{id}");
+        assert!(mine < by_id);
+    }
+
+    #[test]
+    fn sample_from_schema_merges_all_of_and_stops_on_recursion() {
+        let spec = json!({
+            "definitions": {
+                "Node": {
+                    "type": "object",
+                    "properties": { "next": { "$ref": "#/definitions/Node" } }
+                }
+            }
+        });
+        let merged = sample_from_schema(
+            &spec,
+            &json!({ "allOf": [
+                { "properties": { "a": { "type": "boolean" } } },
+                { "properties": { "b": { "enum": ["x", "y"] } } }
+            ]}),
+            0,
+        );
+        assert_eq!(merged, json!({ "a": true, "b": "x" }));
+
+        // Must terminate despite the self-reference.
+        let node = sample_from_schema(&spec, &json!({ "$ref": "#/definitions/Node" }), 0);
+        assert!(node.get("next").is_some());
+    }
+
+    #[test]
+    fn documents_without_operations_are_rejected() {
+        assert!(build_routes(&json!({ "paths": {} }), 0).is_err());
+        assert!(build_routes(&json!({}), 0).is_err());
+    }
+}
@@ -40,7 +40,7 @@ pub(super) fn arp_cache_discovery(targets: &[IpAddr]) -> DiscoveryResult {
         hosts.push(entry.ip.clone());
 
         // Look up vendor from OUI
-        let vendor = oui::lookup_vendor(&entry.mac).map(String::from);
+        let vendor = oui::lookup_vendor(&entry.mac);
 
         host_metadata.insert(
             entry.ip.clone(),
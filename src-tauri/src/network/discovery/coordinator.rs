@@ -234,8 +234,6 @@ async fn resolve_hostnames_for_results(
     results: &mut [DiscoveryResult],
     options: &DiscoveryOptions,
 ) {
-    use netbios::resolve_netbios_name;
-
     let timeout_duration = Duration::from_millis(u64::from(options.timeout_ms));
 
     // Collect all unique discovered IPs
@@ -362,37 +360,13 @@ async fn resolve_hostnames_for_results(
         }
     }
 
-    // Phase 4: Resolve NetBIOS names concurrently (Windows hosts)
-    let semaphore = Arc::new(Semaphore::new(options.concurrency as usize));
-    let mut handles: Vec<tokio::task::JoinHandle<Option<(String, String)>>> = Vec::new();
-
-    for ip in all_ips {
-        let sem = Arc::clone(&semaphore);
-        let timeout = timeout_duration;
-
-        let handle: tokio::task::JoinHandle<Option<(String, String)>> = tokio::spawn(async move {
-            let _permit = sem.acquire().await.ok()?;
-
-            // NetBIOS resolution is blocking, run in a blocking task
-            let result = tokio::task::spawn_blocking(move || resolve_netbios_name(ip, timeout))
-                .await
-                .ok()
-                .flatten();
-
-            result.map(|name| (ip.to_string(), name))
-        });
-
-        handles.push(handle);
-    }
-
-    // Collect results
-    let mut netbios_names: std::collections::HashMap<String, String> =
-        std::collections::HashMap::new();
-    for handle in handles {
-        if let Ok(Some((ip, name))) = handle.await {
-            netbios_names.insert(ip, name);
-        }
-    }
+    // Phase 4: Resolve NetBIOS names (Windows hosts), all queries sharing one socket
+    let netbios_names: std::collections::HashMap<String, String> =
+        netbios::resolve_netbios_names(&all_ips, timeout_duration)
+            .await
+            .into_iter()
+            .map(|(ip, name)| (ip.to_string(), name))
+            .collect();
 
     // Phase 5: Try LLMNR for IPs without a hostname (fallback resolution)
     let llmnr_timeout = Duration::from_millis(u64::from(options.timeout_ms).min(2000));
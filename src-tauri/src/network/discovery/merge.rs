@@ -0,0 +1,549 @@
+//! Cross-method host unification
+//!
+//! Each discovery method reports hosts independently, keyed by IP. A single
+//! device commonly appears several times: once per address family, and once
+//! per method that saw it. This module groups those per-IP observations into
+//! physical hosts with a union-find over identity signals — normalized
+//! hostname, MAC address, UPnP UDN, and WS-Discovery endpoint reference —
+//! then merges the collected [`HostMetadata`] into one row per host while
+//! recording which method reported which address.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use serde::Serialize;
+
+use super::super::banner::ServiceBanner;
+use super::super::snmp::SnmpDeviceInfo;
+use super::super::types::SsdpDeviceInfo;
+use super::super::ws_discovery::WsDiscoveryInfo;
+use super::types::{DiscoveryResult, HostMetadata, MdnsServiceInfo};
+
+/// Suffixes stripped during hostname normalization
+const HOSTNAME_STRIP_SUFFIXES: [&str; 3] = [".local", ".lan", ".home.arpa"];
+
+/// Hostname sources in order of preference
+const HOSTNAME_SOURCE_PRIORITY: [&str; 7] =
+    ["mdns", "dns", "llmnr", "netbios", "snmp", "tls", "ssdp"];
+
+/// Which method reported a host, and under which addresses
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodProvenance {
+    /// Discovery method name (e.g. `arp_cache`, `mdns`)
+    pub method: String,
+    /// Addresses of this host the method reported
+    pub ips: Vec<String>,
+    /// Duration of the method run in milliseconds
+    pub duration_ms: u64,
+    /// Error reported by the method run, if any
+    pub error: Option<String>,
+}
+
+/// One physical host assembled from every method that saw it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedHost {
+    /// Stable key: `host:<hostname>` when named, otherwise `ip:<primary ip>`
+    pub id: String,
+    /// All addresses, IPv4 first, each family in numeric order
+    pub ips: Vec<String>,
+    /// Preferred hostname across sources
+    pub hostname: Option<String>,
+    /// Source of the preferred hostname
+    pub hostname_source: Option<String>,
+    /// `NetBIOS` name
+    pub netbios_name: Option<String>,
+    /// MAC address
+    pub mac_address: Option<String>,
+    /// Vendor name from OUI lookup
+    pub vendor: Option<String>,
+    /// mDNS services advertised by this host
+    pub mdns_services: Vec<MdnsServiceInfo>,
+    /// SSDP/UPnP device information, merged field by field
+    pub ssdp_device: Option<SsdpDeviceInfo>,
+    /// WS-Discovery device information
+    pub ws_discovery: Option<WsDiscoveryInfo>,
+    /// SNMP device information
+    pub snmp_info: Option<SnmpDeviceInfo>,
+    /// TLS certificate Subject Alternative Names
+    pub tls_names: Vec<String>,
+    /// Service banners collected via TCP banner-grab
+    pub service_banners: Vec<ServiceBanner>,
+    /// Per-method provenance, in method completion order
+    pub discoveries: Vec<MethodProvenance>,
+}
+
+/// Per-method results together with the unified host table
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedDiscovery {
+    /// Raw results, one per discovery method
+    pub results: Vec<DiscoveryResult>,
+    /// Deduplicated hosts built from `results`
+    pub hosts: Vec<UnifiedHost>,
+}
+
+/// Everything learned about one IP before grouping
+#[derive(Default)]
+struct Observation<'a> {
+    hostnames: Vec<(String, Option<String>)>,
+    signals: Vec<String>,
+    metadata: Vec<&'a HostMetadata>,
+    /// Indices into the discovery result slice
+    results: Vec<usize>,
+}
+
+/// Minimal union-find over observation indices
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a != root_b {
+            self.parent[root_b] = root_a;
+        }
+    }
+}
+
+/// Normalize a hostname for cross-source comparison: lowercase, drop a
+/// trailing dot, then drop one `.local` / `.lan` / `.home.arpa` suffix.
+fn normalize_hostname(hostname: &str) -> Option<String> {
+    let lowered = hostname.trim().trim_end_matches('.').to_lowercase();
+    if lowered.is_empty() {
+        return None;
+    }
+    let stripped = HOSTNAME_STRIP_SUFFIXES
+        .iter()
+        .find_map(|suffix| lowered.strip_suffix(suffix))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    Some(stripped.unwrap_or(lowered))
+}
+
+/// Normalize a MAC address to lowercase with `:` separators
+fn normalize_mac(mac: &str) -> Option<String> {
+    let normalized = mac.trim().to_lowercase().replace('-', ":");
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+fn hostname_source_rank(source: Option<&str>) -> usize {
+    source
+        .and_then(|s| {
+            HOSTNAME_SOURCE_PRIORITY
+                .iter()
+                .position(|p| p.eq_ignore_ascii_case(s))
+        })
+        .unwrap_or(usize::MAX)
+}
+
+/// Sort key: IPv4 before IPv6, numeric within a family, unparsable last
+fn ip_sort_key(ip: &str) -> (u8, u128, String) {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => (0, u128::from(u32::from(v4)), String::new()),
+        Ok(IpAddr::V6(v6)) => (1, u128::from(v6), String::new()),
+        Err(_) => (2, 0, ip.to_string()),
+    }
+}
+
+impl<'a> Observation<'a> {
+    fn record_hostname(&mut self, hostname: Option<&str>, source: Option<&str>) {
+        let Some(value) = hostname.map(str::trim).filter(|h| !h.is_empty()) else {
+            return;
+        };
+        let entry = (value.to_string(), source.map(str::to_string));
+        if !self.hostnames.contains(&entry) {
+            if let Some(normalized) = normalize_hostname(value) {
+                self.signals.push(format!("hostname:{normalized}"));
+            }
+            self.hostnames.push(entry);
+        }
+    }
+
+    fn record_signal(&mut self, kind: &str, value: Option<&str>) {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            self.signals.push(format!("{kind}:{value}"));
+        }
+    }
+
+    fn record_metadata(&mut self, metadata: &'a HostMetadata) {
+        self.record_hostname(
+            metadata.hostname.as_deref(),
+            metadata.hostname_source.as_deref(),
+        );
+        // NetBIOS and SNMP names live outside `hostname` but identify the
+        // host just as well.
+        self.record_hostname(metadata.netbios_name.as_deref(), Some("netbios"));
+        self.record_hostname(
+            metadata
+                .snmp_info
+                .as_ref()
+                .and_then(|s| s.sys_name.as_deref()),
+            Some("snmp"),
+        );
+        if let Some(mac) = metadata.mac_address.as_deref().and_then(normalize_mac) {
+            self.signals.push(format!("mac:{mac}"));
+        }
+        // UDN and EPR are globally unique device IDs that tie together
+        // hostname-less devices advertising on both address families.
+        self.record_signal(
+            "udn",
+            metadata.ssdp_device.as_ref().and_then(|d| d.udn.as_deref()),
+        );
+        self.record_signal(
+            "epr",
+            metadata
+                .ws_discovery
+                .as_ref()
+                .and_then(|w| w.endpoint_reference.as_deref()),
+        );
+        self.metadata.push(metadata);
+    }
+}
+
+/// Gather per-IP observations, preserving first-seen order of IPs
+fn collect_observations(results: &[DiscoveryResult]) -> (Vec<String>, Vec<Observation<'_>>) {
+    let mut order: Vec<String> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut observations: Vec<Observation<'_>> = Vec::new();
+
+    for (result_idx, result) in results.iter().enumerate() {
+        for ip in &result.hosts {
+            let idx = *index.entry(ip.as_str()).or_insert_with(|| {
+                order.push(ip.clone());
+                observations.push(Observation::default());
+                observations.len() - 1
+            });
+            let observation = &mut observations[idx];
+            observation.results.push(result_idx);
+            // The legacy hostname map carries no source; attribute it to
+            // the method that reported it.
+            observation.record_hostname(
+                result.hostnames.get(ip).map(String::as_str),
+                Some(result.method.as_str()),
+            );
+            if let Some(metadata) = result.host_metadata.get(ip) {
+                observation.record_metadata(metadata);
+            }
+        }
+    }
+
+    (order, observations)
+}
+
+/// Merge SSDP device info field by field, keeping already-known values
+fn merge_ssdp(existing: Option<SsdpDeviceInfo>, incoming: &SsdpDeviceInfo) -> SsdpDeviceInfo {
+    let Some(base) = existing else {
+        return incoming.clone();
+    };
+    SsdpDeviceInfo {
+        friendly_name: base
+            .friendly_name
+            .or_else(|| incoming.friendly_name.clone()),
+        manufacturer: base.manufacturer.or_else(|| incoming.manufacturer.clone()),
+        model_name: base.model_name.or_else(|| incoming.model_name.clone()),
+        model_number: base.model_number.or_else(|| incoming.model_number.clone()),
+        device_type: base.device_type.or_else(|| incoming.device_type.clone()),
+        location: base.location.or_else(|| incoming.location.clone()),
+        server: base.server.or_else(|| incoming.server.clone()),
+        udn: base.udn.or_else(|| incoming.udn.clone()),
+    }
+}
+
+/// Apply one metadata record to a host; earlier values win for scalars
+fn apply_metadata(host: &mut UnifiedHost, metadata: &HostMetadata) {
+    host.netbios_name = host
+        .netbios_name
+        .take()
+        .or_else(|| metadata.netbios_name.clone());
+    host.mac_address = host
+        .mac_address
+        .take()
+        .or_else(|| metadata.mac_address.clone());
+    host.vendor = host.vendor.take().or_else(|| metadata.vendor.clone());
+    host.ws_discovery = host
+        .ws_discovery
+        .take()
+        .or_else(|| metadata.ws_discovery.clone());
+    host.snmp_info = host.snmp_info.take().or_else(|| metadata.snmp_info.clone());
+    if let Some(ssdp) = &metadata.ssdp_device {
+        host.ssdp_device = Some(merge_ssdp(host.ssdp_device.take(), ssdp));
+    }
+
+    for name in &metadata.tls_names {
+        if !host.tls_names.contains(name) {
+            host.tls_names.push(name.clone());
+        }
+    }
+    for banner in &metadata.banners {
+        if !host.service_banners.contains(banner) {
+            host.service_banners.push(banner.clone());
+        }
+    }
+    for service in &metadata.mdns_services {
+        let known = host.mdns_services.iter().any(|s| {
+            s.instance_name == service.instance_name && s.service_type == service.service_type
+        });
+        if !known {
+            host.mdns_services.push(service.clone());
+        }
+    }
+}
+
+/// Build one host from a group of observation indices
+fn build_host(
+    group: &[usize],
+    order: &[String],
+    observations: &[Observation<'_>],
+    results: &[DiscoveryResult],
+) -> UnifiedHost {
+    let mut ips: Vec<String> = group.iter().map(|&i| order[i].clone()).collect();
+    ips.sort_by_key(|ip| ip_sort_key(ip));
+
+    // Lowest source rank wins; ties keep the earliest observation.
+    let best_hostname = group
+        .iter()
+        .flat_map(|&i| observations[i].hostnames.iter())
+        .enumerate()
+        .min_by_key(|(pos, (_, source))| (hostname_source_rank(source.as_deref()), *pos))
+        .map(|(_, entry)| entry.clone());
+    let (hostname, hostname_source) = best_hostname.map_or((None, None), |(h, s)| (Some(h), s));
+
+    let id = match (&hostname, ips.first()) {
+        (Some(name), _) => format!("host:{}", name.to_lowercase()),
+        (None, Some(ip)) => format!("ip:{ip}"),
+        (None, None) => String::new(),
+    };
+
+    let mut host = UnifiedHost {
+        id,
+        ips,
+        hostname,
+        hostname_source,
+        netbios_name: None,
+        mac_address: None,
+        vendor: None,
+        mdns_services: Vec::new(),
+        ssdp_device: None,
+        ws_discovery: None,
+        snmp_info: None,
+        tls_names: Vec::new(),
+        service_banners: Vec::new(),
+        discoveries: Vec::new(),
+    };
+
+    for &i in group {
+        for metadata in &observations[i].metadata {
+            apply_metadata(&mut host, metadata);
+        }
+    }
+
+    let mut provenance: Vec<(usize, Vec<String>)> = Vec::new();
+    for &i in group {
+        for &result_idx in &observations[i].results {
+            match provenance.iter_mut().find(|(idx, _)| *idx == result_idx) {
+                Some((_, ips)) => ips.push(order[i].clone()),
+                None => provenance.push((result_idx, vec![order[i].clone()])),
+            }
+        }
+    }
+    provenance.sort_by_key(|(idx, _)| *idx);
+    host.discoveries = provenance
+        .into_iter()
+        .map(|(idx, mut ips)| {
+            ips.sort_by_key(|ip| ip_sort_key(ip));
+            let result = &results[idx];
+            MethodProvenance {
+                method: result.method.clone(),
+                ips,
+                duration_ms: result.duration_ms,
+                error: result.error.clone(),
+            }
+        })
+        .collect();
+
+    host
+}
+
+/// Unify per-method discovery results into one row per physical host.
+///
+/// IPs sharing a normalized hostname, MAC address, UPnP UDN, or
+/// WS-Discovery endpoint reference are treated as the same device. The
+/// returned hosts are ordered by their primary address.
+pub fn merge_discovery_results(results: &[DiscoveryResult]) -> Vec<UnifiedHost> {
+    let (order, observations) = collect_observations(results);
+    let mut dsu = DisjointSet::new(observations.len());
+
+    let mut anchors: HashMap<&str, usize> = HashMap::new();
+    for (idx, observation) in observations.iter().enumerate() {
+        for signal in &observation.signals {
+            match anchors.get(signal.as_str()) {
+                Some(&anchor) => dsu.union(anchor, idx),
+                None => {
+                    anchors.insert(signal.as_str(), idx);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for idx in 0..observations.len() {
+        let root = dsu.find(idx);
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, members)) => members.push(idx),
+            None => groups.push((root, vec![idx])),
+        }
+    }
+
+    let mut hosts: Vec<UnifiedHost> = groups
+        .iter()
+        .map(|(_, members)| build_host(members, &order, &observations, results))
+        .collect();
+    hosts.sort_by_key(|h| h.ips.first().map(|ip| ip_sort_key(ip)));
+    hosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(method: &str, hosts: &[(&str, HostMetadata)]) -> DiscoveryResult {
+        DiscoveryResult {
+            method: method.to_string(),
+            hosts: hosts.iter().map(|(ip, _)| (*ip).to_string()).collect(),
+            hostnames: HashMap::new(),
+            host_metadata: hosts
+                .iter()
+                .map(|(ip, meta)| ((*ip).to_string(), meta.clone()))
+                .collect(),
+            unreachable: Vec::new(),
+            duration_ms: 10,
+            error: None,
+            requires_privileges: false,
+        }
+    }
+
+    fn named(hostname: &str, source: &str) -> HostMetadata {
+        HostMetadata {
+            hostname: Some(hostname.to_string()),
+            hostname_source: Some(source.to_string()),
+            ..HostMetadata::default()
+        }
+    }
+
+    fn with_mac(mac: &str) -> HostMetadata {
+        HostMetadata {
+            mac_address: Some(mac.to_string()),
+            vendor: Some("Acme".to_string()),
+            ..HostMetadata::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_hostnames_and_macs() {
+        assert_eq!(normalize_hostname("NAS.local."), Some("nas".to_string()));
+        assert_eq!(
+            normalize_hostname("printer.lan"),
+            Some("printer".to_string())
+        );
+        assert_eq!(normalize_hostname(".local"), Some(".local".to_string()));
+        assert_eq!(normalize_hostname("  "), None);
+        assert_eq!(
+            normalize_mac("AA-BB-CC-DD-EE-FF"),
+            Some("aa:bb:cc:dd:ee:ff".to_string())
+        );
+    }
+
+    #[test]
+    fn merges_ipv4_and_ipv6_by_hostname() {
+        let results = vec![
+            result(
+                "arp_cache",
+                &[("192.168.1.20", with_mac("aa:bb:cc:dd:ee:ff"))],
+            ),
+            result(
+                "mdns",
+                &[
+                    ("fe80::1", named("nas.local", "mdns")),
+                    ("192.168.1.20", named("NAS", "dns")),
+                ],
+            ),
+        ];
+
+        let hosts = merge_discovery_results(&results);
+        assert_eq!(hosts.len(), 1);
+        let host = &hosts[0];
+        assert_eq!(host.ips, vec!["192.168.1.20", "fe80::1"]);
+        assert_eq!(host.hostname.as_deref(), Some("nas.local"));
+        assert_eq!(host.hostname_source.as_deref(), Some("mdns"));
+        assert_eq!(host.mac_address.as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+        assert_eq!(host.vendor.as_deref(), Some("Acme"));
+        assert_eq!(host.discoveries.len(), 2);
+        assert_eq!(host.discoveries[0].method, "arp_cache");
+        assert_eq!(host.discoveries[1].ips, vec!["192.168.1.20", "fe80::1"]);
+    }
+
+    #[test]
+    fn merges_by_mac_and_keeps_unrelated_hosts_apart() {
+        let results = vec![result(
+            "arp_cache",
+            &[
+                ("10.0.0.2", with_mac("AA:BB:CC:00:00:01")),
+                ("10.0.0.10", HostMetadata::default()),
+                ("fd00::2", with_mac("aa-bb-cc-00-00-01")),
+            ],
+        )];
+
+        let hosts = merge_discovery_results(&results);
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].ips, vec!["10.0.0.2", "fd00::2"]);
+        assert_eq!(hosts[0].id, "ip:10.0.0.2");
+        assert_eq!(hosts[1].ips, vec!["10.0.0.10"]);
+    }
+
+    #[test]
+    fn merges_ssdp_devices_by_udn() {
+        let device = |name: Option<&str>| HostMetadata {
+            ssdp_device: Some(SsdpDeviceInfo {
+                friendly_name: name.map(str::to_string),
+                manufacturer: Some("Acme".to_string()),
+                model_name: None,
+                model_number: None,
+                device_type: None,
+                location: None,
+                server: None,
+                udn: Some("uuid:1234".to_string()),
+            }),
+            ..HostMetadata::default()
+        };
+        let results = vec![result(
+            "ssdp",
+            &[
+                ("192.168.1.5", device(None)),
+                ("fd00::5", device(Some("TV"))),
+            ],
+        )];
+
+        let hosts = merge_discovery_results(&results);
+        assert_eq!(hosts.len(), 1);
+        let ssdp = hosts[0].ssdp_device.as_ref().unwrap();
+        assert_eq!(ssdp.friendly_name.as_deref(), Some("TV"));
+        assert_eq!(ssdp.manufacturer.as_deref(), Some("Acme"));
+    }
+}
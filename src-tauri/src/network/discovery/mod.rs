@@ -8,12 +8,16 @@
 //! - UDP Scan (probes common UDP ports)
 //! - WS-Discovery (discovers Windows devices and printers)
 //! - ARP Cache (reads the OS ARP table)
+//!
+//! Per-method results can be unified into one row per physical host with
+//! [`merge_discovery_results`].
 
 mod arp;
 mod coordinator;
 mod dns;
 mod llmnr_method;
 mod mdns;
+mod merge;
 mod privileges;
 mod snmp_method;
 mod ssdp;
@@ -23,6 +27,7 @@ mod udp_scan;
 mod ws;
 
 pub use coordinator::discover_hosts;
+pub use merge::{merge_discovery_results, UnifiedDiscovery, UnifiedHost};
 pub use privileges::{check_privileges, get_available_methods};
 pub use types::{
     DiscoveryEvent, DiscoveryEventSink, DiscoveryMethod, DiscoveryOptions, DiscoveryResult,
@@ -208,7 +208,7 @@ async fn ssdp_collect_responses(
     let socket = match tokio::net::UdpSocket::bind(bind_addr).await {
         Ok(s) => s,
         Err(e) => {
-            eprintln!("SSDP: Failed to bind {bind_addr}: {e}");
+            tracing::warn!(bind_addr, error = %e, "SSDP: failed to bind");
             return;
         }
     };
@@ -0,0 +1,96 @@
+//! Network scanner errors with stable codes
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::error_codes::{serialize_coded, ErrorCode};
+
+/// Errors from target and port parsing and from the port scanner.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NetworkError {
+    #[error("Invalid range format: {0}")]
+    InvalidRangeFormat(String),
+
+    #[error("Invalid port number: {0}")]
+    InvalidPort(String),
+
+    #[error("Invalid range: {start} > {end}")]
+    InvalidRange { start: u16, end: u16 },
+
+    #[error("Port 0 is not valid")]
+    PortZero,
+
+    #[error("No valid ports specified")]
+    NoPorts,
+
+    #[error("Custom preset requires port_range")]
+    CustomPresetRequiresRange,
+
+    #[error("Invalid CIDR notation: {0}")]
+    InvalidCidr(String),
+
+    #[error("Could not resolve hostname: {0}")]
+    UnresolvedHost(String),
+
+    #[error("{0}")]
+    InvalidZone(String),
+
+    #[error("Link-local IPv6 targets need a zone ID (e.g. {0}%en0)")]
+    ZoneRequired(String),
+
+    #[error("Scan cancelled")]
+    Cancelled,
+}
+
+impl ErrorCode for NetworkError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidRangeFormat(_) => "network.invalid_range_format",
+            Self::InvalidPort(_) => "network.invalid_port",
+            Self::InvalidRange { .. } => "network.invalid_range",
+            Self::PortZero => "network.port_zero",
+            Self::NoPorts => "network.no_ports",
+            Self::CustomPresetRequiresRange => "network.custom_preset_requires_range",
+            Self::InvalidCidr(_) => "network.invalid_cidr",
+            Self::UnresolvedHost(_) => "network.unresolved_host",
+            Self::InvalidZone(_) => "network.invalid_zone",
+            Self::ZoneRequired(_) => "network.zone_required",
+            Self::Cancelled => "network.cancelled",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::InvalidRangeFormat(range) => vec![("range", range.clone())],
+            Self::InvalidPort(port) => vec![("port", port.clone())],
+            Self::InvalidRange { start, end } => {
+                vec![("start", start.to_string()), ("end", end.to_string())]
+            }
+            Self::InvalidCidr(detail) | Self::InvalidZone(detail) => {
+                vec![("detail", detail.clone())]
+            }
+            Self::UnresolvedHost(target) | Self::ZoneRequired(target) => {
+                vec![("target", target.clone())]
+            }
+            Self::PortZero | Self::NoPorts | Self::CustomPresetRequiresRange | Self::Cancelled => {
+                Vec::new()
+            }
+        }
+    }
+}
+
+impl Serialize for NetworkError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        serialize_coded(self, serializer)
+    }
+}
+
+/// Callers that still report plain strings keep the English message.
+impl From<NetworkError> for String {
+    fn from(error: NetworkError) -> Self {
+        error.to_string()
+    }
+}
@@ -296,7 +296,7 @@ pub async fn discover_mdns_services(
             Ok(receiver) => receivers.push((full_type, receiver)),
             Err(e) => {
                 // Log but continue with other service types
-                eprintln!("Failed to browse {full_type}: {e}");
+                tracing::warn!(service_type = %full_type, error = %e, "mDNS: failed to browse");
             }
         }
     }
@@ -309,20 +309,16 @@ pub async fn discover_mdns_services(
             // Non-blocking receive with small timeout
             if let Ok(event) = receiver.recv_timeout(Duration::from_millis(50)) {
                 match &event {
-                    #[allow(unused_variables)]
                     ServiceEvent::ServiceFound(svc_type, instance) => {
                         // Service found, waiting for resolution
-                        #[cfg(debug_assertions)]
-                        eprintln!("[mDNS] ServiceFound: {} (type: {})", instance, svc_type);
+                        tracing::debug!(%instance, service_type = %svc_type, "mDNS: service found");
                     }
-                    #[allow(unused_variables)]
                     ServiceEvent::ServiceResolved(info) => {
-                        #[cfg(debug_assertions)]
-                        eprintln!(
-                            "[mDNS] ServiceResolved: {} at {} (addresses: {:?})",
-                            info.get_fullname(),
-                            info.get_hostname(),
-                            info.get_addresses()
+                        tracing::debug!(
+                            fullname = info.get_fullname(),
+                            hostname = info.get_hostname(),
+                            addresses = ?info.get_addresses(),
+                            "mDNS: service resolved"
                         );
                     }
                     _ => {}
@@ -0,0 +1,248 @@
+//! IPv6 link-local addressing helpers
+//!
+//! Link-local addresses (`fe80::/10`) are only unique per link, so every
+//! socket that talks to one needs a scope (zone) identifying the outgoing
+//! interface. Users write the zone as a `%` suffix, either as an interface
+//! name (`fe80::1%en0`) or as a numeric index (`fe80::1%4`).
+//!
+//! This module parses zone suffixes, resolves them to scope IDs, builds
+//! scoped socket addresses, and enumerates link-local neighbors per
+//! interface from the OS NDP cache.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::process::Command;
+
+use serde::Serialize;
+
+use super::arp_cache::read_ndp_cache;
+
+/// Link-local neighbors known on one interface
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkLocalInterface {
+    /// Interface name (e.g. `en0`, `eth0`)
+    pub name: String,
+    /// Human-readable interface name (Windows)
+    pub friendly_name: Option<String>,
+    /// Interface index, used as the IPv6 scope ID
+    pub scope_id: u32,
+    /// This host's own link-local addresses on the interface
+    pub local_addresses: Vec<String>,
+    /// Neighbors found in the NDP cache for this interface
+    pub neighbors: Vec<LinkLocalNeighbor>,
+}
+
+/// A link-local neighbor
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkLocalNeighbor {
+    /// Address with zone suffix, ready to paste into a scan target
+    pub address: String,
+    /// Neighbor MAC address
+    pub mac: String,
+}
+
+/// Whether `ip` is an IPv6 unicast link-local address (`fe80::/10`).
+pub const fn is_link_local_v6(ip: &Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xffc0) == 0xfe80
+}
+
+/// Split a `%zone` suffix off a target string.
+///
+/// Accepts the zone both before and after a CIDR prefix
+/// (`fe80::%en0/64` and `fe80::/64%en0`); the returned target has the zone
+/// removed but keeps the prefix.
+pub fn split_zone(target: &str) -> (String, Option<&str>) {
+    let Some(pct) = target.find('%') else {
+        return (target.to_string(), None);
+    };
+    let (head, rest) = target.split_at(pct);
+    let rest = &rest[1..];
+    match rest.find('/') {
+        Some(slash) => (format!("{head}{}", &rest[slash..]), Some(&rest[..slash])),
+        None => (head.to_string(), Some(rest)),
+    }
+}
+
+/// Resolve a zone (interface name or numeric index) to a scope ID.
+///
+/// # Errors
+///
+/// Returns an error when the zone is empty or names no local interface.
+pub fn resolve_zone(zone: &str) -> Result<u32, String> {
+    let zone = zone.trim();
+    if zone.is_empty() {
+        return Err("Empty IPv6 zone ID".to_string());
+    }
+    if let Ok(index) = zone.parse::<u32>() {
+        return Ok(index);
+    }
+    netdev::get_interfaces()
+        .into_iter()
+        .find(|iface| {
+            iface.name == zone
+                || iface
+                    .friendly_name
+                    .as_deref()
+                    .is_some_and(|f| f.eq_ignore_ascii_case(zone))
+        })
+        .map(|iface| iface.index)
+        .ok_or_else(|| format!("Unknown network interface for zone: {zone}"))
+}
+
+/// Build a socket address, attaching `scope_id` to IPv6 link-local targets.
+///
+/// Global addresses ignore the scope so that one scope can be applied to a
+/// whole target list without affecting routable hosts.
+pub fn scoped_socket_addr(ip: IpAddr, port: u16, scope_id: u32) -> SocketAddr {
+    match ip {
+        IpAddr::V6(v6) if is_link_local_v6(&v6) => {
+            SocketAddr::V6(SocketAddrV6::new(v6, port, 0, scope_id))
+        }
+        _ => SocketAddr::new(ip, port),
+    }
+}
+
+/// Ask every node on the link to answer an ICMPv6 echo so the NDP cache
+/// fills up before it is read. Uses the system `ping`, which is permitted
+/// for unprivileged users on every supported platform.
+fn ping_all_nodes(zone: &str) {
+    let target = format!("ff02::1%{zone}");
+    #[cfg(target_os = "windows")]
+    let args = ["-6", "-n", "2", target.as_str()];
+    #[cfg(target_os = "macos")]
+    let args = ["-c", "2", "-i", "1", target.as_str()];
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let args = ["-6", "-c", "2", target.as_str()];
+
+    #[cfg(target_os = "macos")]
+    let program = "ping6";
+    #[cfg(not(target_os = "macos"))]
+    let program = "ping";
+
+    // Best effort: replies only matter for their side effect on the cache.
+    let _ = Command::new(program).args(args).output();
+}
+
+/// Enumerate link-local neighbors per interface.
+///
+/// With `active`, an all-nodes multicast ping is sent on every
+/// multicast-capable interface first so that silent neighbors appear in the
+/// cache. Interfaces without a link-local address are omitted.
+///
+/// # Errors
+///
+/// Returns an error when the NDP cache cannot be read.
+pub fn enumerate_link_local_neighbors(active: bool) -> Result<Vec<LinkLocalInterface>, String> {
+    let interfaces: Vec<_> = netdev::get_interfaces()
+        .into_iter()
+        .filter(|iface| !iface.is_loopback() && iface.is_up())
+        .filter(|iface| iface.ipv6.iter().any(|net| is_link_local_v6(&net.addr())))
+        .collect();
+
+    if active {
+        interfaces
+            .iter()
+            .filter(|iface| iface.is_multicast())
+            .for_each(|iface| ping_all_nodes(&iface.index.to_string()));
+    }
+
+    let mut by_interface: HashMap<String, Vec<LinkLocalNeighbor>> = HashMap::new();
+    for entry in read_ndp_cache().map_err(|e| format!("Failed to read NDP cache: {e}"))? {
+        let Ok(ip) = entry.ip.parse::<Ipv6Addr>() else {
+            continue;
+        };
+        let Some(interface) = entry.interface.filter(|_| is_link_local_v6(&ip)) else {
+            continue;
+        };
+        by_interface
+            .entry(interface.clone())
+            .or_default()
+            .push(LinkLocalNeighbor {
+                address: format!("{ip}%{interface}"),
+                mac: entry.mac,
+            });
+    }
+
+    Ok(interfaces
+        .into_iter()
+        .map(|iface| {
+            let neighbors = by_interface
+                .remove(&iface.name)
+                .or_else(|| {
+                    iface
+                        .friendly_name
+                        .as_ref()
+                        .and_then(|f| by_interface.remove(f))
+                })
+                .unwrap_or_default();
+            LinkLocalInterface {
+                local_addresses: iface
+                    .ipv6
+                    .iter()
+                    .map(|net| net.addr())
+                    .filter(is_link_local_v6)
+                    .map(|ip| format!("{ip}%{}", iface.name))
+                    .collect(),
+                scope_id: iface.index,
+                name: iface.name,
+                friendly_name: iface.friendly_name,
+                neighbors,
+            }
+        })
+        .collect())
+}
+
+/// List link-local IPv6 neighbors grouped by interface.
+#[tauri::command]
+pub async fn list_link_local_neighbors(active: bool) -> Result<Vec<LinkLocalInterface>, String> {
+    tokio::task::spawn_blocking(move || enumerate_link_local_neighbors(active))
+        .await
+        .map_err(|e| format!("Neighbor enumeration task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_link_local_range() {
+        assert!(is_link_local_v6(&"fe80::1".parse().unwrap()));
+        assert!(is_link_local_v6(&"febf::1".parse().unwrap()));
+        assert!(!is_link_local_v6(&"fec0::1".parse().unwrap()));
+        assert!(!is_link_local_v6(&"2001:db8::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn splits_zone_with_and_without_prefix() {
+        assert_eq!(
+            split_zone("fe80::1%en0"),
+            ("fe80::1".to_string(), Some("en0"))
+        );
+        assert_eq!(
+            split_zone("fe80::%eth0/120"),
+            ("fe80::/120".to_string(), Some("eth0"))
+        );
+        assert_eq!(
+            split_zone("fe80::/120%eth0"),
+            ("fe80::/120".to_string(), Some("eth0"))
+        );
+        assert_eq!(split_zone("10.0.0.1"), ("10.0.0.1".to_string(), None));
+    }
+
+    #[test]
+    fn numeric_zone_is_used_directly() {
+        assert_eq!(resolve_zone("4"), Ok(4));
+        assert!(resolve_zone("").is_err());
+    }
+
+    #[test]
+    fn scope_only_applies_to_link_local() {
+        let ll = scoped_socket_addr("fe80::1".parse().unwrap(), 22, 7);
+        assert!(matches!(ll, SocketAddr::V6(v6) if v6.scope_id() == 7));
+
+        let global = scoped_socket_addr("2001:db8::1".parse().unwrap(), 22, 7);
+        assert!(matches!(global, SocketAddr::V6(v6) if v6.scope_id() == 0));
+    }
+}
@@ -0,0 +1,585 @@
+//! Local listening sockets and their owning processes
+//!
+//! Answers "what is on port 5432" without a scan: enumerates the TCP
+//! listeners and bound UDP sockets of this machine together with the PID,
+//! process name, and user that own them.
+//!
+//! Platform-specific:
+//! - Linux: reads `/proc/net/{tcp,tcp6,udp,udp6}` and maps socket inodes to
+//!   PIDs through `/proc/<pid>/fd`. Sockets of other users' processes keep
+//!   their port but have no process unless Kogu runs as root.
+//! - macOS: parses `lsof -nP -F` output. Without root `lsof` only sees the
+//!   current user's processes.
+//! - Windows: parses `netstat -ano` and names PIDs with `sysinfo`.
+//!
+//! The port scanner uses [`annotate_ports`] to attach the owning process to
+//! open ports found on a local address.
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use serde::{Deserialize, Serialize};
+
+use super::ports::get_service_name;
+use super::types::PortInfo;
+
+/// Transport protocol of a socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+}
+
+/// Process owning a socket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketProcess {
+    /// Process ID
+    pub pid: u32,
+    /// Executable name
+    pub name: Option<String>,
+    /// Owning user name
+    pub user: Option<String>,
+}
+
+/// A listening TCP socket or bound UDP socket
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningSocket {
+    /// Transport protocol
+    pub protocol: SocketProtocol,
+    /// Bound address; `0.0.0.0` / `::` mean every interface
+    pub address: IpAddr,
+    /// Bound port
+    pub port: u16,
+    /// Well-known service name for the port
+    pub service: Option<String>,
+    /// Owning process when visible to this user
+    pub process: Option<SocketProcess>,
+}
+
+impl ListeningSocket {
+    fn new(
+        protocol: SocketProtocol,
+        address: IpAddr,
+        port: u16,
+        process: Option<SocketProcess>,
+    ) -> Self {
+        Self {
+            protocol,
+            address,
+            port,
+            service: get_service_name(port).map(String::from),
+            process,
+        }
+    }
+
+    /// Whether a connection to `ip:port` over TCP would reach this socket.
+    fn accepts_tcp(&self, ip: IpAddr, port: u16) -> bool {
+        self.protocol == SocketProtocol::Tcp
+            && self.port == port
+            && (self.address == ip || self.address.is_unspecified())
+    }
+}
+
+/// Enumerate listening sockets, sorted by port, protocol, and address.
+///
+/// # Errors
+///
+/// Returns an error string when the OS socket table cannot be read.
+pub fn list_listening_sockets() -> Result<Vec<ListeningSocket>, String> {
+    let mut sockets = read_platform_sockets()?;
+    sockets.sort_by(|a, b| {
+        (
+            a.port,
+            a.protocol,
+            a.address,
+            a.process.as_ref().map(|p| p.pid),
+        )
+            .cmp(&(
+                b.port,
+                b.protocol,
+                b.address,
+                b.process.as_ref().map(|p| p.pid),
+            ))
+    });
+    sockets.dedup_by(|a, b| {
+        a.port == b.port
+            && a.protocol == b.protocol
+            && a.address == b.address
+            && a.process == b.process
+    });
+    Ok(sockets)
+}
+
+fn read_platform_sockets() -> Result<Vec<ListeningSocket>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::read_sockets()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::read_sockets()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        windows::read_sockets()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        Err("Listing sockets is not supported on this platform".to_string())
+    }
+}
+
+/// Whether `ip` belongs to this machine (loopback or an interface address).
+pub fn is_local_address(ip: IpAddr) -> bool {
+    ip.is_loopback()
+        || if_addrs::get_if_addrs()
+            .map(|addrs| addrs.iter().any(|a| a.ip() == ip))
+            .unwrap_or(false)
+}
+
+/// Attach the owning process to each open port of local host `ip`.
+pub fn annotate_ports(ports: &mut [PortInfo], ip: IpAddr, sockets: &[ListeningSocket]) {
+    for port in ports {
+        // Prefer an exact address match over a wildcard listener.
+        port.process = sockets
+            .iter()
+            .filter(|s| s.accepts_tcp(ip, port.port))
+            .min_by_key(|s| s.address.is_unspecified())
+            .and_then(|s| s.process.clone());
+    }
+}
+
+/// Split `host:port`, accepting `[v6]:port`, `*:port`, and zone suffixes.
+fn parse_endpoint(endpoint: &str) -> Option<(IpAddr, u16)> {
+    let (host, port) = endpoint.rsplit_once(':')?;
+    let port = port.parse().ok()?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let host = host.split_once('%').map_or(host, |(h, _)| h);
+    if host == "*" {
+        return Some((IpAddr::V4(Ipv4Addr::UNSPECIFIED), port));
+    }
+    Some((host.parse().ok()?, port))
+}
+
+/// List listening sockets, optionally only those bound to `port`.
+#[tauri::command]
+pub async fn list_listening_ports(port: Option<u16>) -> Result<Vec<ListeningSocket>, String> {
+    let sockets = tokio::task::spawn_blocking(list_listening_sockets)
+        .await
+        .map_err(|e| format!("Socket enumeration task failed: {e}"))??;
+    Ok(match port {
+        Some(port) => sockets.into_iter().filter(|s| s.port == port).collect(),
+        None => sockets,
+    })
+}
+
+// =============================================================================
+// Linux: /proc/net
+// =============================================================================
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use super::{ListeningSocket, SocketProcess, SocketProtocol};
+
+    /// `st` value of a TCP socket in LISTEN state
+    const TCP_LISTEN: &str = "0A";
+
+    const TABLES: [(&str, SocketProtocol); 4] = [
+        ("/proc/net/tcp", SocketProtocol::Tcp),
+        ("/proc/net/tcp6", SocketProtocol::Tcp),
+        ("/proc/net/udp", SocketProtocol::Udp),
+        ("/proc/net/udp6", SocketProtocol::Udp),
+    ];
+
+    /// One row of a `/proc/net` table.
+    #[derive(Debug, PartialEq, Eq)]
+    pub(super) struct ProcEntry {
+        pub address: IpAddr,
+        pub port: u16,
+        pub uid: u32,
+        pub inode: u64,
+    }
+
+    pub(super) fn read_sockets() -> Result<Vec<ListeningSocket>, String> {
+        let mut entries = Vec::new();
+        for (path, protocol) in TABLES {
+            let text = match std::fs::read_to_string(path) {
+                Ok(text) => text,
+                Err(e) if path == TABLES[0].0 => {
+                    return Err(format!("Failed to read {path}: {e}"));
+                }
+                // The IPv6 tables are absent when IPv6 is disabled.
+                Err(_) => continue,
+            };
+            entries.extend(
+                parse_table(&text, protocol)
+                    .into_iter()
+                    .map(|entry| (protocol, entry)),
+            );
+        }
+        let owners = socket_owners();
+        let users = user_names();
+        Ok(entries
+            .into_iter()
+            .map(|(protocol, entry)| {
+                let process = owners.get(&entry.inode).map(|&pid| SocketProcess {
+                    pid,
+                    name: std::fs::read_to_string(format!("/proc/{pid}/comm"))
+                        .ok()
+                        .map(|name| name.trim_end().to_string()),
+                    user: users.get(&entry.uid).cloned(),
+                });
+                ListeningSocket::new(protocol, entry.address, entry.port, process)
+            })
+            .collect())
+    }
+
+    /// Parse listening rows of a `/proc/net/{tcp,udp}[6]` table.
+    ///
+    /// Columns: `sl local_address rem_address st ... uid timeout inode`.
+    /// UDP sockets count as listening when they have no remote peer.
+    pub(super) fn parse_table(text: &str, protocol: SocketProtocol) -> Vec<ProcEntry> {
+        text.lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (local, remote, state) = (fields.get(1)?, fields.get(2)?, fields.get(3)?);
+                let listening = match protocol {
+                    SocketProtocol::Tcp => *state == TCP_LISTEN,
+                    SocketProtocol::Udp => remote.ends_with(":0000"),
+                };
+                if !listening {
+                    return None;
+                }
+                let (address, port) = local.split_once(':')?;
+                Some(ProcEntry {
+                    address: parse_hex_address(address)?,
+                    port: u16::from_str_radix(port, 16).ok()?,
+                    uid: fields.get(7)?.parse().ok()?,
+                    inode: fields.get(9)?.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Decode the kernel's hex address: 32-bit words in host byte order.
+    fn parse_hex_address(hex: &str) -> Option<IpAddr> {
+        let word = |i: usize| -> Option<[u8; 4]> {
+            let chunk = hex.get(i * 8..i * 8 + 8)?;
+            u32::from_str_radix(chunk, 16).ok().map(u32::to_ne_bytes)
+        };
+        match hex.len() {
+            8 => Some(IpAddr::V4(Ipv4Addr::from(word(0)?))),
+            32 => {
+                let mut octets = [0u8; 16];
+                for i in 0..4 {
+                    octets[i * 4..i * 4 + 4].copy_from_slice(&word(i)?);
+                }
+                Some(IpAddr::V6(Ipv6Addr::from(octets)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Map socket inodes to PIDs via `/proc/<pid>/fd/* -> socket:[inode]`.
+    fn socket_owners() -> HashMap<u64, u32> {
+        let mut owners = HashMap::new();
+        let Ok(procs) = std::fs::read_dir("/proc") else {
+            return owners;
+        };
+        for entry in procs.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+                continue;
+            };
+            let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                let Ok(target) = std::fs::read_link(fd.path()) else {
+                    continue;
+                };
+                let target = target.to_string_lossy();
+                if let Some(inode) = target
+                    .strip_prefix("socket:[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|inode| inode.parse().ok())
+                {
+                    owners.entry(inode).or_insert(pid);
+                }
+            }
+        }
+        owners
+    }
+
+    /// UID to user name from `/etc/passwd`.
+    fn user_names() -> HashMap<u32, String> {
+        std::fs::read_to_string("/etc/passwd")
+            .map(|text| {
+                text.lines()
+                    .filter_map(|line| {
+                        let mut fields = line.split(':');
+                        let name = fields.next()?;
+                        let uid = fields.nth(1)?.parse().ok()?;
+                        Some((uid, name.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+// =============================================================================
+// macOS: lsof
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::{parse_endpoint, ListeningSocket, SocketProcess, SocketProtocol};
+
+    pub(super) fn read_sockets() -> Result<Vec<ListeningSocket>, String> {
+        let output = std::process::Command::new("lsof")
+            .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-iUDP", "-F", "pcLPn"])
+            .output()
+            .map_err(|e| format!("`lsof` not available: {e}"))?;
+        // lsof exits 1 when nothing matched.
+        if !output.status.success() && !output.stdout.is_empty() {
+            return Err(format!(
+                "`lsof` failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse `lsof -F pcLPn` output: `p`/`c`/`L` open a process set, `P`
+    /// and `n` describe each of its files.
+    pub(super) fn parse_lsof(output: &str) -> Vec<ListeningSocket> {
+        let mut sockets = Vec::new();
+        let mut process: Option<SocketProcess> = None;
+        let mut protocol = None;
+        for line in output.lines() {
+            let (tag, value) = line.split_at(line.len().min(1));
+            match tag {
+                "p" => {
+                    process = value.parse().ok().map(|pid| SocketProcess {
+                        pid,
+                        name: None,
+                        user: None,
+                    });
+                }
+                "c" => {
+                    if let Some(p) = process.as_mut() {
+                        p.name = Some(value.to_string());
+                    }
+                }
+                "L" => {
+                    if let Some(p) = process.as_mut() {
+                        p.user = Some(value.to_string());
+                    }
+                }
+                "f" => protocol = None,
+                "P" => {
+                    protocol = match value {
+                        "TCP" => Some(SocketProtocol::Tcp),
+                        "UDP" => Some(SocketProtocol::Udp),
+                        _ => None,
+                    };
+                }
+                "n" if !value.contains("->") => {
+                    if let (Some(protocol), Some((address, port))) =
+                        (protocol, parse_endpoint(value))
+                    {
+                        sockets.push(ListeningSocket::new(
+                            protocol,
+                            address,
+                            port,
+                            process.clone(),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        sockets
+    }
+}
+
+// =============================================================================
+// Windows: netstat
+// =============================================================================
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System};
+
+    use super::{parse_endpoint, ListeningSocket, SocketProcess, SocketProtocol};
+
+    pub(super) fn read_sockets() -> Result<Vec<ListeningSocket>, String> {
+        let output = std::process::Command::new("netstat")
+            .arg("-ano")
+            .output()
+            .map_err(|e| format!("`netstat` not available: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "`netstat` failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        let sys = System::new_with_specifics(
+            RefreshKind::nothing().with_processes(ProcessRefreshKind::nothing()),
+        );
+        Ok(parse_netstat(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .map(|(protocol, address, port, pid)| {
+                let name = sys
+                    .process(Pid::from_u32(pid))
+                    .map(|p| p.name().to_string_lossy().into_owned());
+                let process = (pid != 0).then_some(SocketProcess {
+                    pid,
+                    name,
+                    user: None,
+                });
+                ListeningSocket::new(protocol, address, port, process)
+            })
+            .collect())
+    }
+
+    /// Parse `netstat -ano` rows. The state column is localized, so TCP
+    /// listeners are recognized by their unset foreign port instead.
+    pub(super) fn parse_netstat(output: &str) -> Vec<(SocketProtocol, std::net::IpAddr, u16, u32)> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let protocol = match *fields.first()? {
+                    "TCP" => SocketProtocol::Tcp,
+                    "UDP" => SocketProtocol::Udp,
+                    _ => return None,
+                };
+                let foreign = fields.get(2)?;
+                let listening = match protocol {
+                    SocketProtocol::Tcp => foreign.ends_with(":0"),
+                    SocketProtocol::Udp => *foreign == "*:*",
+                };
+                if !listening {
+                    return None;
+                }
+                let (address, port) = parse_endpoint(fields.get(1)?)?;
+                let pid = fields.last()?.parse().ok()?;
+                Some((protocol, address, port, pid))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::types::PortState;
+
+    fn open_port(port: u16) -> PortInfo {
+        PortInfo {
+            port,
+            state: PortState::Open,
+            service: None,
+            banner: None,
+            tls_cert: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn parses_endpoints() {
+        assert_eq!(
+            parse_endpoint("127.0.0.1:631"),
+            Some(("127.0.0.1".parse().unwrap(), 631))
+        );
+        assert_eq!(
+            parse_endpoint("[::1]:5432"),
+            Some(("::1".parse().unwrap(), 5432))
+        );
+        assert_eq!(
+            parse_endpoint("[fe80::1%4]:123"),
+            Some(("fe80::1".parse().unwrap(), 123))
+        );
+        assert_eq!(
+            parse_endpoint("*:22"),
+            Some((IpAddr::V4(Ipv4Addr::UNSPECIFIED), 22))
+        );
+        assert_eq!(parse_endpoint("*:*"), None);
+    }
+
+    #[test]
+    fn annotates_ports_preferring_exact_address() {
+        let owner = |pid| {
+            Some(SocketProcess {
+                pid,
+                name: Some(format!("p{pid}")),
+                user: None,
+            })
+        };
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        let sockets = [
+            ListeningSocket::new(
+                SocketProtocol::Tcp,
+                "0.0.0.0".parse().unwrap(),
+                80,
+                owner(1),
+            ),
+            ListeningSocket::new(SocketProtocol::Tcp, loopback, 80, owner(2)),
+            ListeningSocket::new(SocketProtocol::Tcp, "::".parse().unwrap(), 5432, owner(3)),
+            ListeningSocket::new(SocketProtocol::Udp, loopback, 53, owner(4)),
+        ];
+        let mut ports = [open_port(80), open_port(5432), open_port(53)];
+        annotate_ports(&mut ports, loopback, &sockets);
+        assert_eq!(ports[0].process.as_ref().map(|p| p.pid), Some(2));
+        assert_eq!(ports[1].process.as_ref().map(|p| p.pid), Some(3));
+        assert!(ports[2].process.is_none());
+        assert!(is_local_address(loopback));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parses_proc_net_tables() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   999        0 4242 1 0 100 0 0 10 0
+   1: 0100007F:1538 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000   999        0 4243 1 0 100 0 0 10 0";
+        let entries = linux::parse_table(tcp, SocketProtocol::Tcp);
+        assert_eq!(
+            entries,
+            [linux::ProcEntry {
+                address: "127.0.0.1".parse().unwrap(),
+                port: 5432,
+                uid: 999,
+                inode: 4242,
+            }]
+        );
+
+        let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000001000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 77 1 0 100 0 0 10 0";
+        let entries = linux::parse_table(tcp6, SocketProtocol::Tcp);
+        assert_eq!(entries[0].address, "::1".parse::<IpAddr>().unwrap());
+        assert_eq!(entries[0].port, 22);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn lists_own_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sockets = list_listening_sockets().unwrap();
+        let found = sockets
+            .iter()
+            .find(|s| s.port == port && s.protocol == SocketProtocol::Tcp)
+            .unwrap();
+        assert_eq!(
+            found.process.as_ref().map(|p| p.pid),
+            Some(std::process::id())
+        );
+    }
+}
@@ -12,7 +12,6 @@
     clippy::default_trait_access,
     clippy::needless_continue,
     clippy::uninlined_format_args,
-    clippy::print_stderr,
     clippy::if_same_then_else,
     clippy::match_same_arms,
     clippy::single_match,
@@ -43,7 +42,7 @@
 //! Provides TCP port scanning functionality with:
 //! - Single IP and CIDR range support (IPv4 and IPv6)
 //! - Parallel scanning with configurable concurrency
-//! - Service detection via banner grabbing
+//! - Service detection via banner grabbing (with STARTTLS for mail/FTP)
 //! - DNS reverse lookup
 //! - Real-time progress events
 //! - Local network interface discovery
@@ -57,13 +56,20 @@
 mod arp_cache;
 mod banner;
 pub mod discovery;
+pub mod error;
 pub mod interfaces;
+pub mod link_local;
+pub mod listening;
 mod llmnr;
+pub mod monitor;
 mod netbios;
 pub mod oui;
+pub mod oui_registry;
 mod ports;
+pub mod scan_history;
 pub mod scanner;
 mod snmp;
+mod starttls;
 mod tls_info;
 pub mod types;
 pub mod wifi;
@@ -125,6 +131,14 @@ impl NetworkScannerState {
         }
     }
 
+    /// Whether an operation with this ID is currently registered.
+    pub fn contains(&self, operation_id: &str) -> bool {
+        self.tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .contains_key(operation_id)
+    }
+
     /// Remove a completed operation.
     pub fn remove(&self, operation_id: &str) {
         self.tokens
@@ -0,0 +1,740 @@
+//! Continuous port monitoring.
+//!
+//! A monitor re-scans a fixed target/port set on an interval and compares
+//! each pass against the previous one. Whenever a port changes state (for
+//! example `open` → `closed`) or its banner changes, a
+//! [`PortMonitorEvent::PortChanged`] event is emitted on
+//! `port-monitor-event`, turning the scanner into a lightweight uptime and
+//! exposure monitor.
+//!
+//! Monitors are persisted as one JSON file per monitor under
+//! `<app data>/port-monitors/`. The file holds the configuration, the most
+//! recent snapshot, and a bounded change history, so a monitor resumed
+//! after an application restart diffs against the last observed state
+//! instead of reporting every port as new.
+//!
+//! Running monitors register their cancellation token with
+//! [`NetworkScannerState`] under the monitor id, so stopping a monitor is
+//! the same operation as cancelling a scan.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::ports::parse_port_range;
+use super::scanner::{parse_targets, scan_port};
+use super::types::PortState;
+use super::NetworkScannerState;
+
+/// Tauri event name used for monitor progress and change alerts.
+const EVENT_NAME: &str = "port-monitor-event";
+
+/// Directory (under the app data dir) holding one JSON file per monitor.
+const MONITOR_DIR: &str = "port-monitors";
+
+/// Shortest accepted re-scan interval.
+const MIN_INTERVAL_SECS: u32 = 10;
+
+/// Upper bound on `hosts × ports` per pass. Monitoring is meant for a
+/// curated set of endpoints, not for repeating a full subnet sweep.
+const MAX_PROBES_PER_PASS: usize = 4096;
+
+/// Number of change records retained in the persisted history.
+const MAX_CHANGE_HISTORY: usize = 500;
+
+/// User-supplied monitor definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortMonitorConfig {
+    /// Existing monitor id to resume, or `None` to create a new monitor.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Display name shown in the monitor list.
+    pub name: String,
+    /// Target IP, hostname, or CIDR notation.
+    pub target: String,
+    /// Port specification (e.g. `22,80,443` or `8000-8100`).
+    pub ports: String,
+    /// Seconds between the start of consecutive passes.
+    pub interval_secs: u32,
+    /// Per-port connect timeout in milliseconds.
+    pub timeout_ms: u32,
+    /// Maximum concurrent connection attempts.
+    pub concurrency: u32,
+}
+
+/// State of one port at the time of a pass.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortObservation {
+    /// Host IP address.
+    pub ip: String,
+    /// TCP port number.
+    pub port: u16,
+    /// Observed state.
+    pub state: PortState,
+    /// Service banner, when the port is open and sent one.
+    pub banner: Option<String>,
+}
+
+/// What changed between two passes for one port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortChangeKind {
+    /// The port state differs (e.g. `open` → `closed`).
+    State,
+    /// The state is unchanged but the banner differs.
+    Banner,
+}
+
+/// One detected change, kept in the persisted history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortChange {
+    /// Unix milliseconds of the pass that detected the change.
+    pub timestamp_ms: u64,
+    /// Host IP address.
+    pub ip: String,
+    /// TCP port number.
+    pub port: u16,
+    /// Kind of change.
+    pub kind: PortChangeKind,
+    /// State in the previous pass.
+    pub previous_state: PortState,
+    /// State in the current pass.
+    pub current_state: PortState,
+    /// Banner in the previous pass.
+    pub previous_banner: Option<String>,
+    /// Banner in the current pass.
+    pub current_banner: Option<String>,
+}
+
+/// Persisted monitor: definition, last snapshot, and change history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortMonitorRecord {
+    /// Stable monitor id (UUID v4).
+    pub id: String,
+    /// Monitor definition. `config.id` always equals `id`.
+    pub config: PortMonitorConfig,
+    /// Observations from the most recent completed pass.
+    #[serde(default)]
+    pub last_snapshot: Vec<PortObservation>,
+    /// Unix milliseconds of the most recent completed pass.
+    #[serde(default)]
+    pub last_run_ms: Option<u64>,
+    /// Number of completed passes.
+    #[serde(default)]
+    pub iterations: u64,
+    /// Most recent changes, oldest first, capped at [`MAX_CHANGE_HISTORY`].
+    #[serde(default)]
+    pub changes: Vec<PortChange>,
+}
+
+/// List entry returned by [`port_monitor_list`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortMonitorSummary {
+    /// Monitor id.
+    pub id: String,
+    /// Monitor definition.
+    pub config: PortMonitorConfig,
+    /// Whether a background task is currently re-scanning.
+    pub running: bool,
+    /// Unix milliseconds of the most recent completed pass.
+    pub last_run_ms: Option<u64>,
+    /// Number of completed passes.
+    pub iterations: u64,
+    /// Open ports in the most recent pass.
+    pub open_ports: u32,
+    /// Total changes recorded in the history.
+    pub change_count: u32,
+}
+
+/// Event emitted on [`EVENT_NAME`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PortMonitorEvent {
+    /// A port changed since the previous pass.
+    PortChanged {
+        /// Monitor id.
+        monitor_id: String,
+        /// The detected change.
+        change: PortChange,
+    },
+    /// A pass finished.
+    PassCompleted {
+        /// Monitor id.
+        monitor_id: String,
+        /// Pass counter after this pass.
+        iteration: u64,
+        /// Unix milliseconds when the pass finished.
+        timestamp_ms: u64,
+        /// Open ports observed in this pass.
+        open_ports: u32,
+        /// Changes detected in this pass.
+        changes: u32,
+    },
+    /// A pass or the persistence step failed; the monitor keeps running.
+    Error {
+        /// Monitor id.
+        monitor_id: String,
+        /// Human-readable error message.
+        message: String,
+    },
+}
+
+/// Current Unix time in milliseconds.
+fn unix_ms_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+// =============================================================================
+// Snapshot diffing
+// =============================================================================
+
+/// Compare two snapshots and list every port whose state or banner changed.
+///
+/// Ports absent from `previous` are treated as a baseline and never reported,
+/// so the first pass of a new monitor produces no alerts.
+fn diff_snapshots(
+    previous: &[PortObservation],
+    current: &[PortObservation],
+    timestamp_ms: u64,
+) -> Vec<PortChange> {
+    current
+        .iter()
+        .filter_map(|now| {
+            let before = previous
+                .iter()
+                .find(|p| p.ip == now.ip && p.port == now.port)?;
+            let kind = if before.state != now.state {
+                PortChangeKind::State
+            } else if now.state == PortState::Open && before.banner != now.banner {
+                PortChangeKind::Banner
+            } else {
+                return None;
+            };
+            Some(PortChange {
+                timestamp_ms,
+                ip: now.ip.clone(),
+                port: now.port,
+                kind,
+                previous_state: before.state,
+                current_state: now.state,
+                previous_banner: before.banner.clone(),
+                current_banner: now.banner.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Append `changes` to the history, dropping the oldest entries beyond the cap.
+fn append_history(history: &mut Vec<PortChange>, changes: &[PortChange]) {
+    history.extend_from_slice(changes);
+    let overflow = history.len().saturating_sub(MAX_CHANGE_HISTORY);
+    history.drain(..overflow);
+}
+
+// =============================================================================
+// Persistence
+// =============================================================================
+
+/// Resolve (and create) the directory holding monitor records.
+fn monitor_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {e}"))?
+        .join(MONITOR_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create monitor directory: {e}"))?;
+    Ok(dir)
+}
+
+/// Path of one monitor's record. Ids are UUIDs generated here, so only the
+/// UUID character set is accepted to keep the path inside `dir`.
+fn record_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(format!("Invalid monitor id: {id}"));
+    }
+    Ok(dir.join(format!("{id}.json")))
+}
+
+fn load_record(dir: &Path, id: &str) -> Result<PortMonitorRecord, String> {
+    let content = std::fs::read_to_string(record_path(dir, id)?)
+        .map_err(|e| format!("Failed to read monitor {id}: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Corrupt monitor record {id}: {e}"))
+}
+
+fn save_record(dir: &Path, record: &PortMonitorRecord) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(record)
+        .map_err(|e| format!("Failed to serialize monitor: {e}"))?;
+    std::fs::write(record_path(dir, &record.id)?, content)
+        .map_err(|e| format!("Failed to write monitor {}: {e}", record.id))
+}
+
+/// Every readable monitor record, for backups.
+pub(crate) fn export_records(app: &AppHandle) -> Result<Vec<PortMonitorRecord>, String> {
+    let dir = monitor_dir(app)?;
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("Failed to read monitor directory: {e}"))?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let content = std::fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str::<PortMonitorRecord>(&content).ok()
+        })
+        .collect())
+}
+
+/// Restore monitor records from a backup and return how many were written.
+///
+/// With `replace` every existing monitor is stopped and deleted first.
+/// Otherwise a record whose id already exists is only taken when its last
+/// pass is more recent. Running monitors are never overwritten in merge
+/// mode.
+pub(crate) fn import_records(
+    app: &AppHandle,
+    records: Vec<PortMonitorRecord>,
+    replace: bool,
+    state: &NetworkScannerState,
+) -> Result<usize, String> {
+    let dir = monitor_dir(app)?;
+    if replace {
+        for existing in export_records(app)? {
+            state.cancel(&existing.id);
+            std::fs::remove_file(record_path(&dir, &existing.id)?)
+                .map_err(|e| format!("Failed to delete monitor {}: {e}", existing.id))?;
+        }
+    }
+    let mut written = 0;
+    for mut record in records {
+        if !replace {
+            if state.contains(&record.id) {
+                continue;
+            }
+            if let Ok(existing) = load_record(&dir, &record.id) {
+                if existing.last_run_ms >= record.last_run_ms {
+                    continue;
+                }
+            }
+        }
+        record.config.id = Some(record.id.clone());
+        save_record(&dir, &record)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+// =============================================================================
+// Scanning
+// =============================================================================
+
+/// Validate a definition and expand it to the concrete probe list.
+fn resolve_probes(config: &PortMonitorConfig) -> Result<Vec<SocketAddr>, String> {
+    if config.interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!(
+            "Interval must be at least {MIN_INTERVAL_SECS} seconds"
+        ));
+    }
+    let targets = parse_targets(&config.target)?;
+    let ports = parse_port_range(&config.ports)?;
+    let total = targets.hosts.len().saturating_mul(ports.len());
+    if total == 0 {
+        return Err("Monitor has no host/port pairs to probe".to_string());
+    }
+    if total > MAX_PROBES_PER_PASS {
+        return Err(format!(
+            "Monitor would probe {total} host/port pairs per pass; the limit is {MAX_PROBES_PER_PASS}"
+        ));
+    }
+    let targets = &targets;
+    Ok(targets
+        .hosts
+        .iter()
+        .flat_map(|ip| {
+            ports
+                .iter()
+                .map(move |port| targets.socket_addr(*ip, *port))
+        })
+        .collect())
+}
+
+/// Probe every address once, returning observations sorted by ip and port.
+async fn run_pass(
+    probes: &[SocketAddr],
+    timeout: Duration,
+    concurrency: u32,
+    token: &CancellationToken,
+) -> Vec<PortObservation> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1) as usize));
+    let handles: Vec<_> = probes
+        .iter()
+        .map(|addr| {
+            let addr = *addr;
+            let sem = Arc::clone(&semaphore);
+            let token = token.clone();
+            tokio::spawn(async move {
+                let _permit = sem.acquire().await.ok()?;
+                if token.is_cancelled() {
+                    return None;
+                }
+                let (state, banner, _) = scan_port(addr, timeout).await?;
+                Some(PortObservation {
+                    ip: addr.ip().to_string(),
+                    port: addr.port(),
+                    state,
+                    banner,
+                })
+            })
+        })
+        .collect();
+
+    let mut observations = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(Some(observation)) = handle.await {
+            observations.push(observation);
+        }
+    }
+    observations.sort_by(|a, b| (&a.ip, a.port).cmp(&(&b.ip, b.port)));
+    observations
+}
+
+/// Diff a finished pass against the previous one, persist the record, and
+/// emit the change and completion events. Returns `(open ports, changes)`.
+fn record_pass(
+    app: &AppHandle,
+    dir: &Path,
+    record: &mut PortMonitorRecord,
+    snapshot: Vec<PortObservation>,
+) -> (u32, u32) {
+    let monitor_id = record.id.clone();
+    let now = unix_ms_now();
+    let changes = diff_snapshots(&record.last_snapshot, &snapshot, now);
+    for change in &changes {
+        let _ = app.emit(
+            EVENT_NAME,
+            PortMonitorEvent::PortChanged {
+                monitor_id: monitor_id.clone(),
+                change: change.clone(),
+            },
+        );
+    }
+
+    append_history(&mut record.changes, &changes);
+    record.iterations = record.iterations.saturating_add(1);
+    record.last_run_ms = Some(now);
+    let open_ports = snapshot
+        .iter()
+        .filter(|o| o.state == PortState::Open)
+        .count() as u32;
+    record.last_snapshot = snapshot;
+
+    if let Err(message) = save_record(dir, record) {
+        let _ = app.emit(
+            EVENT_NAME,
+            PortMonitorEvent::Error {
+                monitor_id: monitor_id.clone(),
+                message,
+            },
+        );
+    }
+
+    let changes = changes.len() as u32;
+    let _ = app.emit(
+        EVENT_NAME,
+        PortMonitorEvent::PassCompleted {
+            monitor_id,
+            iteration: record.iterations,
+            timestamp_ms: now,
+            open_ports,
+            changes,
+        },
+    );
+    (open_ports, changes)
+}
+
+/// Run one pass of the saved profile `monitor_id` without starting its
+/// loop, as the scheduler does. Returns `(open ports, changes)`.
+///
+/// # Errors
+///
+/// Returns an error string when the profile is unknown, invalid, or
+/// currently running as a monitor.
+pub(crate) async fn run_profile_once(
+    app: &AppHandle,
+    monitor_id: &str,
+) -> Result<(u32, u32), String> {
+    if app.state::<NetworkScannerState>().contains(monitor_id) {
+        return Err(format!("Monitor {monitor_id} is already running"));
+    }
+    let dir = monitor_dir(app)?;
+    let mut record = load_record(&dir, monitor_id)?;
+    let probes = resolve_probes(&record.config)?;
+    let timeout = Duration::from_millis(u64::from(record.config.timeout_ms));
+    let snapshot = run_pass(
+        &probes,
+        timeout,
+        record.config.concurrency,
+        &CancellationToken::new(),
+    )
+    .await;
+    Ok(record_pass(app, &dir, &mut record, snapshot))
+}
+
+/// Background loop: scan, diff, persist, emit, sleep; until cancelled.
+async fn monitor_loop(
+    app: AppHandle,
+    dir: PathBuf,
+    mut record: PortMonitorRecord,
+    probes: Vec<SocketAddr>,
+    token: Arc<CancellationToken>,
+) {
+    let interval = Duration::from_secs(u64::from(record.config.interval_secs));
+    let timeout = Duration::from_millis(u64::from(record.config.timeout_ms));
+
+    loop {
+        let started = tokio::time::Instant::now();
+        let snapshot = run_pass(&probes, timeout, record.config.concurrency, &token).await;
+        if token.is_cancelled() {
+            break;
+        }
+
+        record_pass(&app, &dir, &mut record, snapshot);
+
+        tokio::select! {
+            () = token.cancelled() => break,
+            () = tokio::time::sleep_until(started + interval) => {}
+        }
+    }
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+/// Create (or resume) a monitor and start its background re-scan loop.
+///
+/// Passing an existing `config.id` resumes that monitor with the supplied
+/// definition while keeping its snapshot and history.
+///
+/// # Errors
+///
+/// Returns an error string when the definition is invalid, the monitor is
+/// already running, or its record cannot be read or written.
+#[tauri::command]
+pub async fn port_monitor_start(
+    app: AppHandle,
+    config: PortMonitorConfig,
+    state: tauri::State<'_, NetworkScannerState>,
+) -> Result<PortMonitorRecord, String> {
+    let probes = resolve_probes(&config)?;
+    let dir = monitor_dir(&app)?;
+
+    let record = match config.id.clone() {
+        Some(id) => {
+            if state.contains(&id) {
+                return Err(format!("Monitor {id} is already running"));
+            }
+            PortMonitorRecord {
+                config: PortMonitorConfig {
+                    id: Some(id.clone()),
+                    ..config
+                },
+                ..load_record(&dir, &id)?
+            }
+        }
+        None => {
+            let id = Uuid::new_v4().to_string();
+            PortMonitorRecord {
+                id: id.clone(),
+                config: PortMonitorConfig {
+                    id: Some(id),
+                    ..config
+                },
+                last_snapshot: Vec::new(),
+                last_run_ms: None,
+                iterations: 0,
+                changes: Vec::new(),
+            }
+        }
+    };
+    save_record(&dir, &record)?;
+
+    let token = Arc::new(CancellationToken::new());
+    state.register(record.id.clone(), Arc::clone(&token));
+    tokio::spawn(monitor_loop(app, dir, record.clone(), probes, token));
+
+    Ok(record)
+}
+
+/// Stop a running monitor. Its record stays on disk.
+#[tauri::command]
+pub fn port_monitor_stop(monitor_id: String, state: tauri::State<'_, NetworkScannerState>) -> bool {
+    state.cancel(&monitor_id)
+}
+
+/// List every saved monitor, running or not.
+///
+/// # Errors
+///
+/// Returns an error string when the monitor directory cannot be read.
+/// Unreadable individual records are skipped.
+#[tauri::command]
+pub fn port_monitor_list(
+    app: AppHandle,
+    state: tauri::State<'_, NetworkScannerState>,
+) -> Result<Vec<PortMonitorSummary>, String> {
+    let dir = monitor_dir(&app)?;
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("Failed to read monitor directory: {e}"))?;
+
+    let mut summaries: Vec<PortMonitorSummary> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let content = std::fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str::<PortMonitorRecord>(&content).ok()
+        })
+        .map(|record| PortMonitorSummary {
+            running: state.contains(&record.id),
+            open_ports: record
+                .last_snapshot
+                .iter()
+                .filter(|o| o.state == PortState::Open)
+                .count() as u32,
+            change_count: record.changes.len() as u32,
+            last_run_ms: record.last_run_ms,
+            iterations: record.iterations,
+            id: record.id,
+            config: record.config,
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.config.name.cmp(&b.config.name));
+    Ok(summaries)
+}
+
+/// Load one monitor's full record (snapshot and change history).
+///
+/// # Errors
+///
+/// Returns an error string when the record does not exist or is corrupt.
+#[tauri::command]
+pub fn port_monitor_get(app: AppHandle, monitor_id: String) -> Result<PortMonitorRecord, String> {
+    load_record(&monitor_dir(&app)?, &monitor_id)
+}
+
+/// Stop (if running) and permanently delete a monitor.
+///
+/// # Errors
+///
+/// Returns an error string when the record cannot be removed.
+#[tauri::command]
+pub fn port_monitor_delete(
+    app: AppHandle,
+    monitor_id: String,
+    state: tauri::State<'_, NetworkScannerState>,
+) -> Result<(), String> {
+    state.cancel(&monitor_id);
+    let path = record_path(&monitor_dir(&app)?, &monitor_id)?;
+    std::fs::remove_file(path).map_err(|e| format!("Failed to delete monitor {monitor_id}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obs(port: u16, state: PortState, banner: Option<&str>) -> PortObservation {
+        PortObservation {
+            ip: "192.0.2.1".to_string(),
+            port,
+            state,
+            banner: banner.map(str::to_string),
+        }
+    }
+
+    fn config(target: &str, ports: &str, interval_secs: u32) -> PortMonitorConfig {
+        PortMonitorConfig {
+            id: None,
+            name: "test".to_string(),
+            target: target.to_string(),
+            ports: ports.to_string(),
+            interval_secs,
+            timeout_ms: 500,
+            concurrency: 10,
+        }
+    }
+
+    #[test]
+    fn diff_reports_state_and_banner_changes() {
+        let previous = vec![
+            obs(22, PortState::Open, Some("SSH-2.0-OpenSSH_8.9")),
+            obs(80, PortState::Open, None),
+            obs(443, PortState::Closed, None),
+        ];
+        let current = vec![
+            obs(22, PortState::Open, Some("SSH-2.0-OpenSSH_9.6")),
+            obs(80, PortState::Closed, None),
+            obs(443, PortState::Closed, None),
+        ];
+
+        let changes = diff_snapshots(&previous, &current, 1);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].port, 22);
+        assert_eq!(changes[0].kind, PortChangeKind::Banner);
+        assert_eq!(changes[1].port, 80);
+        assert_eq!(changes[1].kind, PortChangeKind::State);
+        assert_eq!(changes[1].previous_state, PortState::Open);
+        assert_eq!(changes[1].current_state, PortState::Closed);
+    }
+
+    #[test]
+    fn first_pass_is_a_silent_baseline() {
+        let current = vec![obs(22, PortState::Open, None)];
+        assert!(diff_snapshots(&[], &current, 1).is_empty());
+    }
+
+    #[test]
+    fn history_is_capped_dropping_oldest() {
+        let change = diff_snapshots(
+            &[obs(1, PortState::Open, None)],
+            &[obs(1, PortState::Closed, None)],
+            0,
+        );
+        let mut history = Vec::new();
+        for _ in 0..MAX_CHANGE_HISTORY + 5 {
+            append_history(&mut history, &change);
+        }
+        assert_eq!(history.len(), MAX_CHANGE_HISTORY);
+    }
+
+    #[test]
+    fn resolve_probes_expands_hosts_and_ports() {
+        let probes = resolve_probes(&config("192.0.2.0/30", "22,80", 60)).unwrap();
+        // /30 excludes network and broadcast: 2 hosts × 2 ports.
+        assert_eq!(probes.len(), 4);
+    }
+
+    #[test]
+    fn resolve_probes_rejects_short_intervals_and_huge_sets() {
+        assert!(resolve_probes(&config("192.0.2.1", "22", 1)).is_err());
+        assert!(resolve_probes(&config("10.0.0.0/16", "1-1024", 60)).is_err());
+    }
+
+    #[test]
+    fn record_path_rejects_traversal() {
+        let dir = Path::new("/tmp/monitors");
+        assert!(record_path(dir, "../settings").is_err());
+        assert!(record_path(dir, "").is_err());
+        assert!(record_path(dir, "0b6a7c1e-8f4e-4d5b-9a1c-2f3e4d5c6b7a").is_ok());
+    }
+}
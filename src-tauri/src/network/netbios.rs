@@ -2,10 +2,25 @@
 //!
 //! Resolves IP addresses to NetBIOS names using UDP port 137.
 //! This is commonly used in Windows networks.
+//!
+//! Queries run on a non-blocking tokio socket. A batch of hosts shares one
+//! socket: every query gets its own transaction ID and responses are
+//! matched by source address and transaction ID as they arrive, so a
+//! whole range is resolved within a single timeout window.
 
-use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// NetBIOS Name Service port
+const NBNS_PORT: u16 = 137;
+
+/// Queries sent before draining responses that have already arrived
+const SEND_CHUNK: usize = 64;
+
 /// NetBIOS name query result
 #[derive(Debug, Clone)]
 pub struct NetbiosName {
@@ -20,50 +35,117 @@ pub struct NetbiosName {
 /// Resolve an IP address to its NetBIOS name
 ///
 /// Sends a NetBIOS Node Status Request to the target IP and parses the response.
-pub fn resolve_netbios_name(ip: IpAddr, timeout: Duration) -> Option<String> {
-    let ipv4 = match ip {
-        IpAddr::V4(v4) => v4,
-        IpAddr::V6(_) => return None, // NetBIOS doesn't support IPv6
-    };
+pub async fn resolve_netbios_name(ip: IpAddr, timeout: Duration) -> Option<String> {
+    resolve_netbios_names(&[ip], timeout).await.remove(&ip)
+}
 
-    let names = query_netbios_names(ipv4, timeout).ok()?;
+/// Resolve many IP addresses to NetBIOS names over one shared socket
+///
+/// IPv6 addresses are skipped since NetBIOS doesn't support them. Hosts
+/// that do not answer within `timeout` of the last query are absent from
+/// the result.
+pub async fn resolve_netbios_names(ips: &[IpAddr], timeout: Duration) -> HashMap<IpAddr, String> {
+    let targets: Vec<SocketAddr> = ips
+        .iter()
+        .filter_map(|ip| match ip {
+            IpAddr::V4(v4) => Some(SocketAddr::new(IpAddr::V4(*v4), NBNS_PORT)),
+            IpAddr::V6(_) => None,
+        })
+        .collect();
+
+    query_node_status_batch(&targets, timeout)
+        .await
+        .into_iter()
+        .filter_map(|(addr, names)| Some((addr.ip(), preferred_name(names)?)))
+        .collect()
+}
 
-    // Find the first unique workstation or file server name
+/// Pick the first unique workstation or file server name
+fn preferred_name(names: Vec<NetbiosName>) -> Option<String> {
     names
         .into_iter()
         .find(|n| !n.is_group && (n.name_type == 0x00 || n.name_type == 0x20))
         .map(|n| n.name)
 }
 
-/// Query all NetBIOS names from a host
-fn query_netbios_names(
-    ip: Ipv4Addr,
+/// Query all NetBIOS names from each target over one socket
+async fn query_node_status_batch(
+    targets: &[SocketAddr],
     timeout: Duration,
-) -> Result<Vec<NetbiosName>, std::io::Error> {
-    let socket = UdpSocket::bind("0.0.0.0:0")?;
-    socket.set_read_timeout(Some(timeout))?;
-    socket.set_write_timeout(Some(timeout))?;
+) -> HashMap<SocketAddr, Vec<NetbiosName>> {
+    let mut results = HashMap::new();
+    if targets.is_empty() {
+        return results;
+    }
+    let Ok(socket) = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))).await else {
+        return results;
+    };
+
+    // Outstanding queries by target, keyed to the transaction ID sent.
+    let mut pending: HashMap<SocketAddr, u16> = HashMap::with_capacity(targets.len());
+    let base_txid: u16 = rand::random();
+    let mut buf = [0u8; 1024];
 
-    let target = SocketAddr::new(IpAddr::V4(ip), 137);
+    for (chunk_index, chunk) in targets.chunks(SEND_CHUNK).enumerate() {
+        for (offset, &target) in chunk.iter().enumerate() {
+            if pending.contains_key(&target) {
+                continue;
+            }
+            let seq = chunk_index * SEND_CHUNK + offset;
+            let txid = base_txid.wrapping_add(u16::try_from(seq & 0xFFFF).unwrap_or(0));
+            let request = build_node_status_request(txid);
+            let sent = tokio::time::timeout(timeout, socket.send_to(&request, target)).await;
+            if matches!(sent, Ok(Ok(_))) {
+                pending.insert(target, txid);
+            }
+        }
 
-    // Build NetBIOS Node Status Request packet
-    let request = build_node_status_request();
-    socket.send_to(&request, target)?;
+        // Drain responses that are already waiting so the receive buffer
+        // does not overflow while the rest of a large range is sent.
+        while let Ok((len, from)) = socket.try_recv_from(&mut buf) {
+            accept_response(&buf[..len], from, &mut pending, &mut results);
+        }
+    }
 
-    // Receive response
-    let mut buf = [0u8; 1024];
-    let (len, _) = socket.recv_from(&mut buf)?;
+    let deadline = Instant::now() + timeout;
+    while !pending.is_empty() {
+        match tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+            Ok(Ok((len, from))) => accept_response(&buf[..len], from, &mut pending, &mut results),
+            // ICMP port-unreachable surfaces as a per-packet error on some
+            // platforms; keep listening for the other hosts.
+            Ok(Err(_)) => {}
+            Err(_) => break,
+        }
+    }
 
-    // Parse response
-    parse_node_status_response(&buf[..len])
+    results
+}
+
+/// Record a response if it answers an outstanding query
+fn accept_response(
+    data: &[u8],
+    from: SocketAddr,
+    pending: &mut HashMap<SocketAddr, u16>,
+    results: &mut HashMap<SocketAddr, Vec<NetbiosName>>,
+) {
+    if data.len() < 2 {
+        return;
+    }
+    let txid = u16::from_be_bytes([data[0], data[1]]);
+    if pending.get(&from) != Some(&txid) {
+        return;
+    }
+    if let Ok(names) = parse_node_status_response(data) {
+        pending.remove(&from);
+        results.insert(from, names);
+    }
 }
 
 /// Build a NetBIOS Node Status Request packet
-fn build_node_status_request() -> Vec<u8> {
+fn build_node_status_request(txid: u16) -> Vec<u8> {
     let mut packet = Vec::with_capacity(50);
 
-    // Transaction ID (random)
-    let txid: u16 = rand::random();
+    // Transaction ID
     packet.extend_from_slice(&txid.to_be_bytes());
 
     // Flags: 0x0000 (query, opcode 0, no recursion)
@@ -254,11 +336,73 @@ mod tests {
 
     #[test]
     fn test_build_request() {
-        let request = build_node_status_request();
+        let request = build_node_status_request(0x1234);
         // Should be 50 bytes
         assert_eq!(request.len(), 50);
         // Question count should be 1
         assert_eq!(request[4], 0);
         assert_eq!(request[5], 1);
+        assert_eq!(&request[..2], &[0x12, 0x34]);
+    }
+
+    /// Node status response naming `name` (unique, workstation type)
+    fn node_status_response(txid: u16, name: &str) -> Vec<u8> {
+        let mut packet = txid.to_be_bytes().to_vec();
+        // Flags, QDCOUNT 0, ANCOUNT 1, NSCOUNT 0, ARCOUNT 0
+        packet.extend_from_slice(&[0x84, 0x00, 0, 0, 0, 1, 0, 0, 0, 0]);
+        // Answer name: same encoded wildcard as the request
+        packet.extend_from_slice(&build_node_status_request(0)[12..46]);
+        // TYPE, CLASS, TTL
+        packet.extend_from_slice(&[0x00, 0x21, 0x00, 0x01, 0, 0, 0, 0]);
+        let mut entry = format!("{name:<15}").into_bytes();
+        entry.extend_from_slice(&[0x00, 0x04, 0x00]);
+        let rdata_len = u16::try_from(1 + entry.len() + 6).unwrap();
+        packet.extend_from_slice(&rdata_len.to_be_bytes());
+        packet.push(1);
+        packet.extend_from_slice(&entry);
+        // Unit ID (MAC address)
+        packet.extend_from_slice(&[0; 6]);
+        packet
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let names = parse_node_status_response(&node_status_response(7, "DESKTOP-01")).unwrap();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[0].name, "DESKTOP-01");
+        assert_eq!(names[0].name_type, 0x00);
+        assert!(!names[0].is_group);
+    }
+
+    #[tokio::test]
+    async fn test_batch_matches_responses_by_txid() {
+        let responders = [
+            std::net::UdpSocket::bind("127.0.0.1:0").unwrap(),
+            std::net::UdpSocket::bind("127.0.0.1:0").unwrap(),
+        ];
+        let targets: Vec<SocketAddr> = responders.iter().map(|s| s.local_addr().unwrap()).collect();
+        let mut handles = Vec::new();
+        for (i, responder) in responders.into_iter().enumerate() {
+            handles.push(std::thread::spawn(move || {
+                let mut buf = [0u8; 128];
+                let (_, from) = responder.recv_from(&mut buf).unwrap();
+                let txid = u16::from_be_bytes([buf[0], buf[1]]);
+                // A stale answer first, then the real one.
+                responder
+                    .send_to(&node_status_response(txid.wrapping_add(1), "STALE"), from)
+                    .unwrap();
+                responder
+                    .send_to(&node_status_response(txid, &format!("HOST-{i}")), from)
+                    .unwrap();
+            }));
+        }
+
+        let results = query_node_status_batch(&targets, Duration::from_secs(2)).await;
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[&targets[0]][0].name, "HOST-0");
+        assert_eq!(results[&targets[1]][0].name, "HOST-1");
     }
 }
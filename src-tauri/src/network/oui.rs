@@ -1,11 +1,15 @@
 //! OUI (Organizationally Unique Identifier) vendor lookup
 //!
 //! Provides MAC address vendor identification using a built-in database
-//! of common vendor prefixes (first 3 bytes of MAC address).
+//! of common vendor prefixes (first 3 bytes of MAC address). A full IEEE
+//! registry downloaded through [`super::oui_registry`] replaces it when
+//! present.
 
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
+use super::oui_registry;
+
 /// Common OUI prefixes and their vendor names
 /// Format: "XX:XX:XX" -> "Vendor Name"
 static OUI_DATABASE: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
@@ -1502,7 +1506,16 @@ static OUI_DATABASE: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::n
 ///
 /// Returns the vendor name if found in the database, otherwise None.
 /// The MAC address should be in format "XX:XX:XX:XX:XX:XX" (uppercase or lowercase).
-pub fn lookup_vendor(mac: &str) -> Option<&'static str> {
+/// A downloaded IEEE registry, when present, takes precedence over the
+/// bundled table.
+pub fn lookup_vendor(mac: &str) -> Option<String> {
+    if let Some(registry) = oui_registry::current() {
+        return registry.lookup(mac).map(|m| m.vendor.to_string());
+    }
+    lookup_bundled(mac).map(String::from)
+}
+
+fn lookup_bundled(mac: &str) -> Option<&'static str> {
     // Normalize: uppercase and take first 3 octets
     let normalized = mac.to_uppercase();
     let prefix = if normalized.len() >= 8 {
@@ -1524,15 +1537,17 @@ pub struct OuiLookupResult {
     pub prefix: Option<String>,
 }
 
-/// Metadata about the bundled OUI database surfaced in the UI.
+/// Metadata about the active OUI database surfaced in the UI.
 #[derive(serde::Serialize)]
 pub struct OuiDatabaseInfo {
     /// Number of OUI prefixes contained in the database.
     pub entries: usize,
-    /// Last-updated marker for the bundled snapshot (UTC date in `YYYY-MM-DD`).
-    pub updated: &'static str,
+    /// Dataset date of the active database (UTC date in `YYYY-MM-DD`).
+    pub updated: String,
     /// Short human-readable source description.
-    pub source: &'static str,
+    pub source: String,
+    /// Whether lookups use the bundled snapshot rather than a download.
+    pub bundled: bool,
 }
 
 /// Bundled snapshot date for the OUI database. Bump when the dataset is
@@ -1540,11 +1555,20 @@ pub struct OuiDatabaseInfo {
 const OUI_UPDATED: &str = "2025-09-01";
 const OUI_SOURCE: &str = "IEEE OUI registry (curated subset)";
 
-/// Look up the vendor for a MAC address from the bundled IEEE OUI database.
+/// Look up the vendor for a MAC address from the active OUI database.
 ///
-/// Returns the vendor name and matched 3-octet prefix when found.
+/// Returns the vendor name and matched prefix when found. The prefix is the
+/// 3-octet OUI, or a longer MA-M/MA-S block from a downloaded registry.
 #[tauri::command]
 pub fn lookup_oui_vendor(mac: String) -> OuiLookupResult {
+    if let Some(registry) = oui_registry::current() {
+        let found = registry.lookup(&mac);
+        return OuiLookupResult {
+            vendor: found.as_ref().map(|m| m.vendor.to_string()),
+            prefix: found.map(|m| m.prefix),
+        };
+    }
+
     let normalized = mac.to_uppercase();
     let prefix = if normalized.len() >= 8 {
         normalized[..8].to_string()
@@ -1567,14 +1591,23 @@ pub fn lookup_oui_vendor(mac: String) -> OuiLookupResult {
     )
 }
 
-/// Return metadata about the bundled OUI database for the freshness badge.
+/// Return metadata about the active OUI database for the freshness badge.
 #[tauri::command]
 pub fn get_oui_database_info() -> OuiDatabaseInfo {
-    OuiDatabaseInfo {
-        entries: OUI_DATABASE.len(),
-        updated: OUI_UPDATED,
-        source: OUI_SOURCE,
-    }
+    oui_registry::current().map_or_else(
+        || OuiDatabaseInfo {
+            entries: OUI_DATABASE.len(),
+            updated: OUI_UPDATED.to_string(),
+            source: OUI_SOURCE.to_string(),
+            bundled: true,
+        },
+        |registry| OuiDatabaseInfo {
+            entries: registry.entry_count(),
+            updated: registry.updated.clone(),
+            source: oui_registry::REGISTRY_SOURCE.to_string(),
+            bundled: false,
+        },
+    )
 }
 
 #[cfg(test)]
@@ -1583,19 +1616,25 @@ mod tests {
 
     #[test]
     fn test_lookup_known_vendor() {
-        assert_eq!(lookup_vendor("00:03:93:AA:BB:CC"), Some("Apple"));
-        assert_eq!(lookup_vendor("00:0C:29:11:22:33"), Some("VMware"));
-        assert_eq!(lookup_vendor("B8:27:EB:AA:BB:CC"), Some("Raspberry Pi"));
+        assert_eq!(lookup_vendor("00:03:93:AA:BB:CC").as_deref(), Some("Apple"));
+        assert_eq!(
+            lookup_vendor("00:0C:29:11:22:33").as_deref(),
+            Some("VMware")
+        );
+        assert_eq!(
+            lookup_vendor("B8:27:EB:AA:BB:CC").as_deref(),
+            Some("Raspberry Pi")
+        );
     }
 
     #[test]
     fn test_lookup_case_insensitive() {
-        assert_eq!(lookup_vendor("00:03:93:aa:bb:cc"), Some("Apple"));
-        assert_eq!(lookup_vendor("00:03:93:AA:BB:CC"), Some("Apple"));
+        assert_eq!(lookup_vendor("00:03:93:aa:bb:cc").as_deref(), Some("Apple"));
+        assert_eq!(lookup_vendor("00:03:93:AA:BB:CC").as_deref(), Some("Apple"));
     }
 
     #[test]
     fn test_lookup_unknown_vendor() {
-        assert_eq!(lookup_vendor("FF:FF:FF:FF:FF:FF"), None);
+        assert_eq!(lookup_vendor("FF:FF:FF:FF:FF:FF").as_deref(), None);
     }
 }
@@ -0,0 +1,361 @@
+//! Downloadable IEEE OUI registry
+//!
+//! The table in [`super::oui`] is a curated subset frozen at build time.
+//! [`update_oui_database`] downloads the full IEEE MA-L, MA-M, and MA-S
+//! registries, writes them to `<app data>/oui-registry.json` through a
+//! temporary file and a rename, and swaps them in for every lookup. The
+//! file is read back on startup by [`load_cached`]. Without it, or when the
+//! download fails, lookups keep using the bundled table.
+//!
+//! MA-M (28-bit) and MA-S (36-bit) blocks are carved out of MA-L
+//! assignments held by the IEEE itself, so lookups try the longest prefix
+//! first.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// Registry CSVs published by the IEEE, longest block first
+const REGISTRY_URLS: [&str; 3] = [
+    "https://standards-oui.ieee.org/oui36/oui36.csv",
+    "https://standards-oui.ieee.org/oui28/mam.csv",
+    "https://standards-oui.ieee.org/oui/oui.csv",
+];
+
+/// Registry file under the app data dir
+const REGISTRY_FILE: &str = "oui-registry.json";
+
+/// Source label for the downloaded registry
+pub const REGISTRY_SOURCE: &str = "IEEE registry (MA-L, MA-M, MA-S)";
+
+/// The full MA-L file is about 6 MB; allow slow links
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Prefix lengths in bits, longest first
+const BLOCK_BITS: [u32; 3] = [36, 28, 24];
+
+/// A downloaded MA-L assignment count below this means a truncated or
+/// unexpected file, which must not replace a working registry
+const MIN_MA_L_ENTRIES: usize = 10_000;
+
+/// On-disk form: assignment hex (6, 7, or 9 digits) to organization name.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryFile {
+    updated: String,
+    assignments: BTreeMap<String, String>,
+}
+
+/// Loaded registry, indexed per block size.
+#[derive(Debug)]
+pub struct OuiRegistry {
+    /// Dataset date (`YYYY-MM-DD`).
+    pub updated: String,
+    /// Prefix value to organization, one map per [`BLOCK_BITS`] entry.
+    blocks: [HashMap<u64, String>; 3],
+}
+
+/// Match for a MAC address.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RegistryMatch<'a> {
+    /// Organization name.
+    pub vendor: &'a str,
+    /// Matched prefix in colon-uppercase form; MA-M and MA-S prefixes end
+    /// in a single hex digit (`70:B3:D5:1`, `70:B3:D5:12:3`).
+    pub prefix: String,
+}
+
+static REGISTRY: LazyLock<RwLock<Option<Arc<OuiRegistry>>>> = LazyLock::new(|| RwLock::new(None));
+
+impl OuiRegistry {
+    fn from_file(file: &RegistryFile) -> Self {
+        let mut blocks: [HashMap<u64, String>; 3] = Default::default();
+        for (assignment, vendor) in &file.assignments {
+            let Ok(value) = u64::from_str_radix(assignment, 16) else {
+                continue;
+            };
+            let bits = u32::try_from(assignment.len() * 4).unwrap_or(0);
+            if let Some(i) = BLOCK_BITS.iter().position(|b| *b == bits) {
+                blocks[i].insert(value, vendor.clone());
+            }
+        }
+        Self {
+            updated: file.updated.clone(),
+            blocks,
+        }
+    }
+
+    /// Number of assignments across all blocks.
+    pub fn entry_count(&self) -> usize {
+        self.blocks.iter().map(HashMap::len).sum()
+    }
+
+    /// Longest-prefix match for `mac` in any common notation.
+    pub fn lookup(&self, mac: &str) -> Option<RegistryMatch<'_>> {
+        let hex: String = mac.chars().filter(char::is_ascii_hexdigit).collect();
+        if hex.len() < 12 {
+            return None;
+        }
+        let value = u64::from_str_radix(&hex[..12], 16).ok()?;
+        BLOCK_BITS
+            .iter()
+            .zip(&self.blocks)
+            .find_map(|(bits, block)| {
+                let vendor = block.get(&(value >> (48 - bits)))?;
+                let digits = &hex[..usize::try_from(bits / 4).unwrap_or(6)];
+                Some(RegistryMatch {
+                    vendor,
+                    prefix: format_prefix(digits),
+                })
+            })
+    }
+}
+
+/// `70B3D5123` -> `70:B3:D5:12:3`
+fn format_prefix(digits: &str) -> String {
+    let upper = digits.to_ascii_uppercase();
+    upper
+        .as_bytes()
+        .chunks(2)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// The downloaded registry, when one is loaded.
+pub fn current() -> Option<Arc<OuiRegistry>> {
+    REGISTRY.read().ok().and_then(|guard| guard.clone())
+}
+
+fn install(registry: OuiRegistry) {
+    if let Ok(mut guard) = REGISTRY.write() {
+        *guard = Some(Arc::new(registry));
+    }
+}
+
+/// Split one CSV document into records, honoring quoted fields that
+/// contain commas, doubled quotes, or line breaks.
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !quoted => {}
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Extract `assignment -> organization` pairs from an IEEE registry CSV
+/// (`Registry,Assignment,Organization Name,Organization Address`).
+fn parse_registry_csv(text: &str) -> Vec<(String, String)> {
+    csv_records(text)
+        .into_iter()
+        .skip(1)
+        .filter_map(|record| {
+            let assignment = record.get(1)?.trim().to_ascii_uppercase();
+            let vendor = record.get(2)?.trim();
+            let valid = matches!(assignment.len(), 6 | 7 | 9)
+                && assignment.chars().all(|c| c.is_ascii_hexdigit())
+                && !vendor.is_empty();
+            valid.then(|| (assignment, vendor.to_string()))
+        })
+        .collect()
+}
+
+/// `Last-Modified: Wed, 04 Jun 2025 10:00:00 GMT` -> `2025-06-04`
+fn http_date_to_iso(value: &str) -> Option<String> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = value.split_whitespace().skip(1);
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month_name)? + 1;
+    let year: u32 = parts.next()?.parse().ok()?;
+    Some(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+fn today_iso() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (year, month, day, ..) = super::scanner::timestamp_to_datetime(secs);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn registry_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(REGISTRY_FILE)
+}
+
+/// Load a previously downloaded registry from `data_dir`. A missing or
+/// unreadable file leaves the bundled table in use.
+pub fn load_cached(data_dir: &Path) {
+    let file = std::fs::read_to_string(registry_path(data_dir))
+        .ok()
+        .and_then(|text| serde_json::from_str::<RegistryFile>(&text).ok());
+    if let Some(file) = file {
+        install(OuiRegistry::from_file(&file));
+    }
+}
+
+/// Write `file` next to its final path and rename it into place, so a
+/// crash mid-write never leaves a truncated registry behind.
+fn write_atomically(data_dir: &Path, file: &RegistryFile) -> Result<(), String> {
+    std::fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Failed to create data directory: {e}"))?;
+    let path = registry_path(data_dir);
+    let tmp = path.with_extension("json.tmp");
+    let content =
+        serde_json::to_vec(file).map_err(|e| format!("Failed to serialize OUI registry: {e}"))?;
+    std::fs::write(&tmp, content).map_err(|e| format!("Failed to write OUI registry: {e}"))?;
+    std::fs::rename(&tmp, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to replace OUI registry: {e}")
+    })
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<(String, Option<String>), String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    let modified = response
+        .headers()
+        .get(reqwest::header::LAST_MODIFIED)
+        .and_then(|v| v.to_str().ok())
+        .and_then(http_date_to_iso);
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {url}: {e}"))?;
+    Ok((text, modified))
+}
+
+/// Download the IEEE registries and switch lookups over to them.
+///
+/// The dataset date is the newest `Last-Modified` among the three files,
+/// or today when the server sends none.
+///
+/// # Errors
+///
+/// Returns an error string when a download fails, the MA-L file looks
+/// truncated, or the registry cannot be written. The previous registry (or
+/// the bundled table) stays in use.
+#[tauri::command]
+pub async fn update_oui_database(app: AppHandle) -> Result<super::oui::OuiDatabaseInfo, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {e}"))?;
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+
+    let mut assignments = BTreeMap::new();
+    let mut updated: Option<String> = None;
+    for url in REGISTRY_URLS {
+        let (text, modified) = download(&client, url).await?;
+        let entries = parse_registry_csv(&text);
+        if url.ends_with("/oui.csv") && entries.len() < MIN_MA_L_ENTRIES {
+            return Err(format!(
+                "MA-L registry has only {} entries; keeping the current database",
+                entries.len()
+            ));
+        }
+        assignments.extend(entries);
+        updated = updated.max(modified);
+    }
+
+    let file = RegistryFile {
+        updated: updated.unwrap_or_else(today_iso),
+        assignments,
+    };
+    write_atomically(&data_dir, &file)?;
+    install(OuiRegistry::from_file(&file));
+    Ok(super::oui::get_oui_database_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MA_L: &str = "Registry,Assignment,Organization Name,Organization Address\r\n\
+        MA-L,0050C2,IEEE Registration Authority,\"445 Hoes Lane Piscataway NJ 08854, US\"\r\n\
+        MA-L,70B3D5,IEEE Registration Authority,\"445 Hoes Lane\r\nPiscataway NJ US\"\r\n\
+        MA-L,001B63,\"Apple, Inc.\",1 Infinite Loop Cupertino CA US\r\n";
+
+    #[test]
+    fn parses_quoted_csv_records() {
+        let entries = parse_registry_csv(MA_L);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[2],
+            ("001B63".to_string(), "Apple, Inc.".to_string())
+        );
+        assert_eq!(
+            http_date_to_iso("Wed, 04 Jun 2025 10:00:00 GMT").as_deref(),
+            Some("2025-06-04")
+        );
+    }
+
+    #[test]
+    fn prefers_the_longest_block_and_survives_reload() {
+        let mut assignments: BTreeMap<String, String> =
+            parse_registry_csv(MA_L).into_iter().collect();
+        assignments.insert("70B3D5123".to_string(), "Tiny Sensors".to_string());
+        assignments.insert("70B3D51".to_string(), "Medium Devices".to_string());
+        let file = RegistryFile {
+            updated: "2025-06-04".to_string(),
+            assignments,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        write_atomically(dir.path(), &file).unwrap();
+        let text = std::fs::read_to_string(registry_path(dir.path())).unwrap();
+        let registry = OuiRegistry::from_file(&serde_json::from_str(&text).unwrap());
+
+        assert_eq!(registry.entry_count(), 5);
+        let hit = |mac| registry.lookup(mac).map(|m| (m.vendor, m.prefix));
+        assert_eq!(
+            hit("70:b3:d5:12:34:56"),
+            Some(("Tiny Sensors", "70:B3:D5:12:3".to_string()))
+        );
+        assert_eq!(
+            hit("70-B3-D5-1F-00-00"),
+            Some(("Medium Devices", "70:B3:D5:1".to_string()))
+        );
+        assert_eq!(
+            hit("70b3.d5ff.0000"),
+            Some(("IEEE Registration Authority", "70:B3:D5".to_string()))
+        );
+        assert_eq!(hit("00:1B:63:AA:BB:CC").map(|m| m.0), Some("Apple, Inc."));
+        assert_eq!(hit("FF:FF:FF:FF:FF:FF"), None);
+    }
+}
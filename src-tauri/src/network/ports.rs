@@ -1,5 +1,7 @@
 //! Port definitions and service name mappings
 
+use super::error::NetworkError;
+
 /// Well-known port to service name mapping
 pub const WELL_KNOWN_SERVICES: &[(u16, &str)] = &[
     (21, "ftp"),
@@ -76,7 +78,7 @@ pub fn get_service_name(port: u16) -> Option<&'static str> {
 /// # Errors
 ///
 /// Returns an error if the port range is invalid
-pub fn parse_port_range(input: &str) -> Result<Vec<u16>, String> {
+pub fn parse_port_range(input: &str) -> Result<Vec<u16>, NetworkError> {
     let mut ports = Vec::new();
 
     for part in input.split(',') {
@@ -88,20 +90,20 @@ pub fn parse_port_range(input: &str) -> Result<Vec<u16>, String> {
         if part.contains('-') {
             let range_parts: Vec<&str> = part.split('-').collect();
             if range_parts.len() != 2 {
-                return Err(format!("Invalid range format: {part}"));
+                return Err(NetworkError::InvalidRangeFormat(part.to_string()));
             }
 
             let start: u16 = range_parts[0]
                 .trim()
                 .parse()
-                .map_err(|_| format!("Invalid port number: {}", range_parts[0]))?;
+                .map_err(|_| NetworkError::InvalidPort(range_parts[0].to_string()))?;
             let end: u16 = range_parts[1]
                 .trim()
                 .parse()
-                .map_err(|_| format!("Invalid port number: {}", range_parts[1]))?;
+                .map_err(|_| NetworkError::InvalidPort(range_parts[1].to_string()))?;
 
             if start > end {
-                return Err(format!("Invalid range: {start} > {end}"));
+                return Err(NetworkError::InvalidRange { start, end });
             }
 
             for port in start..=end {
@@ -112,9 +114,9 @@ pub fn parse_port_range(input: &str) -> Result<Vec<u16>, String> {
         } else {
             let port: u16 = part
                 .parse()
-                .map_err(|_| format!("Invalid port number: {part}"))?;
+                .map_err(|_| NetworkError::InvalidPort(part.to_string()))?;
             if port == 0 {
-                return Err("Port 0 is not valid".to_string());
+                return Err(NetworkError::PortZero);
             }
             if !ports.contains(&port) {
                 ports.push(port);
@@ -123,7 +125,7 @@ pub fn parse_port_range(input: &str) -> Result<Vec<u16>, String> {
     }
 
     if ports.is_empty() {
-        return Err("No valid ports specified".to_string());
+        return Err(NetworkError::NoPorts);
     }
 
     ports.sort_unstable();
@@ -0,0 +1,529 @@
+//! Persisted port scan history.
+//!
+//! Each scan started from the app is written to
+//! `<app data>/scan-history/<scan id>.jsonl` while it runs: a `started`
+//! line, one `host` line per host with open ports as soon as it is found,
+//! and a final `completed` line with the totals. Results therefore never
+//! have to be held in memory or re-sent in one piece, and a scan that is
+//! cancelled or crashes still leaves every host found so far on disk.
+//!
+//! Only the most recent [`MAX_SCANS`] scans are kept.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::types::{HostResult, ScanProgress, ScanProgressSink, ScanResults, ScanSummary};
+
+/// Directory (under the app data dir) holding one file per scan.
+const HISTORY_DIR: &str = "scan-history";
+
+/// Number of scans kept; older files are pruned when a scan starts.
+const MAX_SCANS: usize = 50;
+
+/// One line of a scan history file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+enum HistoryRecord {
+    /// Written when the scan starts.
+    Started {
+        /// Scan target as entered.
+        target: String,
+        /// Start time (ISO 8601).
+        start_time: String,
+    },
+    /// A host with open ports.
+    Host {
+        /// The discovered host.
+        host: HostResult,
+    },
+    /// Written when the scan finishes.
+    Completed {
+        /// Scan totals.
+        summary: ScanSummary,
+    },
+}
+
+/// A stored scan, without its hosts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanHistoryEntry {
+    /// Scan id, as passed to `start_network_scan`.
+    pub scan_id: String,
+    /// Scan target as entered.
+    pub target: String,
+    /// Start time (ISO 8601).
+    pub start_time: String,
+    /// Hosts stored for the scan.
+    pub stored_hosts: u32,
+    /// Totals, or `None` if the scan was cancelled or did not finish.
+    pub summary: Option<ScanSummary>,
+}
+
+/// A stored scan with every readable record, as carried in a backup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredScan {
+    /// Scan id.
+    pub scan_id: String,
+    records: Vec<HistoryRecord>,
+}
+
+/// Resolve (and create) the directory holding scan files.
+fn history_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {e}"))?
+        .join(HISTORY_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create scan history directory: {e}"))?;
+    Ok(dir)
+}
+
+/// Path of one scan's file. Scan ids are UUIDs generated by the frontend,
+/// so only the UUID character set is accepted to keep the path inside `dir`.
+fn scan_path(dir: &Path, scan_id: &str) -> Result<PathBuf, String> {
+    if scan_id.is_empty() || !scan_id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(format!("Invalid scan id: {scan_id}"));
+    }
+    Ok(dir.join(format!("{scan_id}.jsonl")))
+}
+
+/// Delete all but the newest `keep` scan files.
+fn prune(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, path) in files.into_iter().skip(keep) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Progress sink that appends hosts and totals to a scan file before
+/// forwarding every event to `inner`.
+///
+/// Write failures are logged and stop further writes; they never fail the
+/// scan itself.
+pub struct HistorySink<'a> {
+    inner: &'a dyn ScanProgressSink,
+    writer: Mutex<Option<BufWriter<File>>>,
+}
+
+impl<'a> HistorySink<'a> {
+    /// Start a history file for `scan_id` in `dir` and prune old scans.
+    fn create(
+        dir: &Path,
+        scan_id: &str,
+        target: &str,
+        start_time: String,
+        inner: &'a dyn ScanProgressSink,
+    ) -> Result<Self, String> {
+        let path = scan_path(dir, scan_id)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create scan history file: {e}"))?;
+        prune(dir, MAX_SCANS);
+        let sink = Self {
+            inner,
+            writer: Mutex::new(Some(BufWriter::new(file))),
+        };
+        sink.append(&HistoryRecord::Started {
+            target: target.to_string(),
+            start_time,
+        });
+        Ok(sink)
+    }
+
+    /// Start a history file under the app data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or file cannot be created, or the
+    /// scan id is not a UUID.
+    pub fn open(
+        app: &AppHandle,
+        scan_id: &str,
+        target: &str,
+        inner: &'a dyn ScanProgressSink,
+    ) -> Result<Self, String> {
+        Self::create(
+            &history_dir(app)?,
+            scan_id,
+            target,
+            super::scanner::chrono_format_now(),
+            inner,
+        )
+    }
+
+    fn append(&self, record: &HistoryRecord) {
+        let mut guard = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(writer) = guard.as_mut() else {
+            return;
+        };
+        let written = serde_json::to_writer(&mut *writer, record)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writer.write_all(b"\n"))
+            .and_then(|()| writer.flush());
+        if let Err(e) = written {
+            tracing::warn!(error = %e, "Scan history write failed; disabling history for this scan");
+            *guard = None;
+        }
+    }
+}
+
+impl ScanProgressSink for HistorySink<'_> {
+    fn emit(&self, progress: ScanProgress) -> Result<(), String> {
+        match &progress {
+            ScanProgress::HostDiscovered { host } => {
+                self.append(&HistoryRecord::Host { host: host.clone() });
+            }
+            ScanProgress::Completed { summary } => {
+                self.append(&HistoryRecord::Completed {
+                    summary: summary.clone(),
+                });
+            }
+            _ => {}
+        }
+        self.inner.emit(progress)
+    }
+}
+
+/// Read the header and totals of one scan file, counting its hosts
+/// without keeping them.
+fn read_entry(path: &Path) -> Option<ScanHistoryEntry> {
+    let scan_id = path.file_stem()?.to_str()?.to_string();
+    let reader = BufReader::new(File::open(path).ok()?);
+    let mut lines = reader.lines();
+    let HistoryRecord::Started { target, start_time } =
+        serde_json::from_str(&lines.next()?.ok()?).ok()?
+    else {
+        return None;
+    };
+    let mut stored_hosts = 0;
+    let mut summary = None;
+    for line in lines.map_while(Result::ok) {
+        // Host lines are only counted; parsing them would defeat the point.
+        if line.starts_with(r#"{"type":"host""#) {
+            stored_hosts += 1;
+        } else if let Ok(HistoryRecord::Completed { summary: totals }) = serde_json::from_str(&line)
+        {
+            summary = Some(totals);
+        }
+    }
+    Some(ScanHistoryEntry {
+        scan_id,
+        target,
+        start_time,
+        stored_hosts,
+        summary,
+    })
+}
+
+/// Load one scan file with all of its hosts.
+fn read_scan(path: &Path) -> Result<ScanResults, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to read scan: {e}"))?;
+    let mut hosts = Vec::new();
+    let mut start = None;
+    let mut summary = None;
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // A scan interrupted mid-write may end in a partial line.
+        match serde_json::from_str(&line) {
+            Ok(HistoryRecord::Started { start_time, .. }) => start = Some(start_time),
+            Ok(HistoryRecord::Host { host }) => hosts.push(host),
+            Ok(HistoryRecord::Completed { summary: totals }) => summary = Some(totals),
+            Err(_) => {}
+        }
+    }
+    // Unfinished scans get totals from what was stored.
+    let summary = summary.unwrap_or_else(|| ScanSummary {
+        total_hosts_scanned: 0,
+        hosts_with_open_ports: u32::try_from(hosts.len()).unwrap_or(u32::MAX),
+        total_open_ports: u32::try_from(hosts.iter().map(|h| h.ports.len()).sum::<usize>())
+            .unwrap_or(u32::MAX),
+        scan_duration_ms: 0,
+        start_time: start.unwrap_or_default(),
+        end_time: String::new(),
+    });
+    Ok(ScanResults { hosts, summary })
+}
+
+fn list_entries(dir: &Path) -> Result<Vec<ScanHistoryEntry>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read scan history directory: {e}"))?;
+    let mut scans: Vec<ScanHistoryEntry> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|entry| read_entry(&entry.path()))
+        .collect();
+    scans.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    Ok(scans)
+}
+
+fn export_from(dir: &Path) -> Result<Vec<StoredScan>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read scan history directory: {e}"))?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| {
+            let scan_id = path.file_stem()?.to_str()?.to_string();
+            let file = File::open(&path).ok()?;
+            // Partial lines from interrupted scans are dropped.
+            let records = BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .collect();
+            Some(StoredScan { scan_id, records })
+        })
+        .collect())
+}
+
+fn import_into(dir: &Path, mut scans: Vec<StoredScan>, replace: bool) -> Result<usize, String> {
+    if replace {
+        for scan in export_from(dir)? {
+            std::fs::remove_file(scan_path(dir, &scan.scan_id)?)
+                .map_err(|e| format!("Failed to delete scan {}: {e}", scan.scan_id))?;
+        }
+    }
+    // Oldest first, so file times keep pruning in start order.
+    scans.sort_by_cached_key(|scan| match scan.records.first() {
+        Some(HistoryRecord::Started { start_time, .. }) => start_time.clone(),
+        _ => String::new(),
+    });
+    let mut written = 0;
+    for scan in &scans {
+        let path = scan_path(dir, &scan.scan_id)?;
+        if !replace && path.exists() {
+            continue;
+        }
+        let mut content = String::new();
+        for record in &scan.records {
+            let line = serde_json::to_string(record)
+                .map_err(|e| format!("Failed to serialize scan {}: {e}", scan.scan_id))?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write scan {}: {e}", scan.scan_id))?;
+        written += 1;
+    }
+    prune(dir, MAX_SCANS);
+    Ok(written)
+}
+
+/// Every stored scan, for backups.
+pub(crate) fn export_scans(app: &AppHandle) -> Result<Vec<StoredScan>, String> {
+    export_from(&history_dir(app)?)
+}
+
+/// Restore scans from a backup and return how many were written.
+///
+/// With `replace` every stored scan is deleted first. Otherwise a scan
+/// whose id is already stored is kept as is.
+pub(crate) fn import_scans(
+    app: &AppHandle,
+    scans: Vec<StoredScan>,
+    replace: bool,
+) -> Result<usize, String> {
+    import_into(&history_dir(app)?, scans, replace)
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// List stored scans, newest first.
+#[tauri::command(async)]
+pub fn scan_history_list(app: AppHandle) -> Result<Vec<ScanHistoryEntry>, String> {
+    list_entries(&history_dir(&app)?)
+}
+
+/// Load a stored scan with all of its hosts.
+#[tauri::command(async)]
+pub fn scan_history_get(app: AppHandle, scan_id: String) -> Result<ScanResults, String> {
+    read_scan(&scan_path(&history_dir(&app)?, &scan_id)?)
+}
+
+/// Delete a stored scan.
+#[tauri::command(async)]
+pub fn scan_history_delete(app: AppHandle, scan_id: String) -> Result<(), String> {
+    std::fs::remove_file(scan_path(&history_dir(&app)?, &scan_id)?)
+        .map_err(|e| format!("Failed to delete scan {scan_id}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::types::PortInfo;
+    use super::super::types::PortState;
+    use super::*;
+
+    struct NullSink;
+
+    impl ScanProgressSink for NullSink {
+        fn emit(&self, _: ScanProgress) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    const SCAN_ID: &str = "0f8fad5b-d9cb-469f-a165-70867728950e";
+
+    fn host(ip: &str, port: u16) -> HostResult {
+        HostResult {
+            ip: ip.to_string(),
+            hostname: None,
+            ports: vec![PortInfo {
+                port,
+                state: PortState::Open,
+                service: Some("http".to_string()),
+                banner: None,
+                tls_cert: None,
+                process: None,
+            }],
+            scan_duration_ms: 5,
+        }
+    }
+
+    fn summary() -> ScanSummary {
+        ScanSummary {
+            total_hosts_scanned: 254,
+            hosts_with_open_ports: 2,
+            total_open_ports: 2,
+            scan_duration_ms: 1000,
+            start_time: "2024-01-01T00:00:00Z".to_string(),
+            end_time: "2024-01-01T00:00:01Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn streams_hosts_and_totals_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = HistorySink::create(
+            dir.path(),
+            SCAN_ID,
+            "10.0.0.0/24",
+            "2024-01-01T00:00:00Z".to_string(),
+            &NullSink,
+        )
+        .unwrap();
+        sink.emit(ScanProgress::HostDiscovered {
+            host: host("10.0.0.1", 80),
+        })
+        .unwrap();
+
+        // Hosts are on disk before the scan finishes.
+        let partial = list_entries(dir.path()).unwrap();
+        assert_eq!(partial[0].stored_hosts, 1);
+        assert!(partial[0].summary.is_none());
+
+        sink.emit(ScanProgress::HostDiscovered {
+            host: host("10.0.0.2", 443),
+        })
+        .unwrap();
+        sink.emit(ScanProgress::Completed { summary: summary() })
+            .unwrap();
+        drop(sink);
+
+        let entries = list_entries(dir.path()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].scan_id, SCAN_ID);
+        assert_eq!(entries[0].target, "10.0.0.0/24");
+        assert_eq!(entries[0].stored_hosts, 2);
+        assert_eq!(
+            entries[0].summary.as_ref().unwrap().total_hosts_scanned,
+            254
+        );
+
+        let scan = read_scan(&scan_path(dir.path(), SCAN_ID).unwrap()).unwrap();
+        assert_eq!(scan.hosts.len(), 2);
+        assert_eq!(scan.hosts[1].ports[0].port, 443);
+        assert_eq!(scan.summary.total_open_ports, 2);
+    }
+
+    #[test]
+    fn unfinished_scans_keep_stored_hosts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = scan_path(dir.path(), SCAN_ID).unwrap();
+        let started = serde_json::to_string(&HistoryRecord::Started {
+            target: "10.0.0.1".to_string(),
+            start_time: "2024-01-01T00:00:00Z".to_string(),
+        })
+        .unwrap();
+        let found = serde_json::to_string(&HistoryRecord::Host {
+            host: host("10.0.0.1", 22),
+        })
+        .unwrap();
+        std::fs::write(&path, format!("{started}\n{found}\n{{\"type\":\"ho")).unwrap();
+
+        let scan = read_scan(&path).unwrap();
+        assert_eq!(scan.hosts.len(), 1);
+        assert_eq!(scan.summary.hosts_with_open_ports, 1);
+        assert_eq!(scan.summary.start_time, "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn prunes_oldest_scans_and_rejects_bad_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..3 {
+            std::fs::write(dir.path().join(format!("{i}.jsonl")), "").unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        prune(dir.path(), 2);
+        assert!(!dir.path().join("0.jsonl").exists());
+        assert!(dir.path().join("2.jsonl").exists());
+
+        assert!(scan_path(dir.path(), "../etc/passwd").is_err());
+        assert!(scan_path(dir.path(), "").is_err());
+    }
+
+    #[test]
+    fn exports_and_imports_stored_scans() {
+        let source = tempfile::tempdir().unwrap();
+        let sink = HistorySink::create(
+            source.path(),
+            SCAN_ID,
+            "10.0.0.1",
+            "2024-01-01T00:00:00Z".to_string(),
+            &NullSink,
+        )
+        .unwrap();
+        sink.emit(ScanProgress::HostDiscovered {
+            host: host("10.0.0.1", 22),
+        })
+        .unwrap();
+        drop(sink);
+        let scans = export_from(source.path()).unwrap();
+        assert_eq!(scans.len(), 1);
+
+        let target = tempfile::tempdir().unwrap();
+        let other = "1b4e28ba-2fa1-11d2-883f-0016d3cca427";
+        std::fs::write(target.path().join(format!("{other}.jsonl")), "").unwrap();
+        assert_eq!(import_into(target.path(), scans.clone(), false).unwrap(), 1);
+        // Merging again keeps the stored copy.
+        assert_eq!(import_into(target.path(), scans, false).unwrap(), 0);
+        assert_eq!(list_entries(target.path()).unwrap()[0].stored_hosts, 1);
+
+        // Replacing with no scans clears the history.
+        assert_eq!(import_into(target.path(), Vec::new(), true).unwrap(), 0);
+        assert!(std::fs::read_dir(target.path()).unwrap().next().is_none());
+    }
+}
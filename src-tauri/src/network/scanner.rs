@@ -1,28 +1,33 @@
 //! Network scanner implementation
 
-use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
 
+use futures::stream::{FuturesUnordered, StreamExt};
 use ipnetwork::IpNetwork;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
 use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
 use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
-use tokio::sync::{broadcast, Semaphore};
+use tokio::sync::broadcast;
 use tokio::time::timeout;
 use tokio_rustls::TlsConnector;
 
+use super::error::NetworkError;
+use super::link_local::{is_link_local_v6, resolve_zone, scoped_socket_addr, split_zone};
+use super::listening::{annotate_ports, is_local_address, list_listening_sockets, ListeningSocket};
+use super::llmnr;
 use super::netbios::resolve_netbios_name;
 use super::ports::{
     get_service_name, parse_port_range, DATABASE_PORTS, QUICK_SCAN_PORTS, WEB_PORTS,
 };
+use super::starttls::{self, StartTlsProtocol};
 use super::types::{
     HostResult, HostnameResolutionOptions, PortInfo, PortPreset, PortState, ScanMode, ScanProgress,
-    ScanProgressSink, ScanRequest, ScanResults, TlsCertInfo,
+    ScanProgressSink, ScanRequest, ScanResults, ScanSummary, TlsCertInfo,
 };
 
 // =============================================================================
@@ -125,16 +130,21 @@ impl ScanState {
 // =============================================================================
 
 /// Run a network scan
+///
+/// Hosts with open ports are streamed to `progress_sink` as
+/// [`ScanProgress::HostDiscovered`] events and not kept here, so memory
+/// stays flat however large the range is. Only the totals are returned.
 pub async fn run_scan(
     request: ScanRequest,
     progress_sink: &dyn ScanProgressSink,
     scan_state: Arc<ScanState>,
-) -> Result<ScanResults, String> {
+) -> Result<ScanSummary, NetworkError> {
     let start_time = Instant::now();
     let start_time_str = chrono_format_now();
 
     // Parse targets (IPs from CIDR or single IP/hostname)
-    let targets = parse_targets(&request.target)?;
+    let scan_targets = parse_targets(&request.target)?;
+    let targets = &scan_targets.hosts;
     let total_hosts = targets.len() as u32;
 
     // Resolve ports based on mode and preset
@@ -149,21 +159,23 @@ pub async fn run_scan(
 
     // Scan configuration
     let timeout_duration = Duration::from_millis(u64::from(request.timeout_ms));
-    let semaphore = Arc::new(Semaphore::new(request.concurrency as usize));
+    let concurrency = request.concurrency as usize;
 
     // Scan state
     let scanned_hosts = Arc::new(AtomicU32::new(0));
-    let mut results: Vec<HostResult> = Vec::new();
+    let mut discovered_hosts: u32 = 0;
     let mut total_discovered_ports: u32 = 0;
+    // Local socket table, read once when the first local host has open ports
+    let mut local_sockets: Option<Vec<ListeningSocket>> = None;
 
     // Scan each host
     for (index, target_ip) in targets.iter().enumerate() {
         if scan_state.is_cancelled() {
-            return Err("Scan cancelled".to_string());
+            return Err(NetworkError::Cancelled);
         }
 
         let host_start = Instant::now();
-        let ip_str = target_ip.to_string();
+        let ip_str = scan_targets.display(*target_ip);
 
         // Get next IP for preview (if available)
         let next_ip = targets.get(index + 1).map(ToString::to_string);
@@ -172,31 +184,45 @@ pub async fn run_scan(
         let hostname = resolve_hostname_with_options(target_ip, &request.resolution).await;
 
         // Scan all ports for this host
-        let open_ports = scan_host_ports(
+        let mut open_ports = scan_host_ports(
             *target_ip,
+            scan_targets.scope_id,
             &ports,
             timeout_duration,
-            Arc::clone(&semaphore),
-            Arc::clone(&scan_state),
+            concurrency,
+            &scan_state,
+            progress_sink,
         )
         .await;
 
+        // Name the owning process of ports open on this machine
+        if !open_ports.is_empty() && is_local_address(*target_ip) {
+            let sockets = match local_sockets.take() {
+                Some(sockets) => sockets,
+                None => tokio::task::spawn_blocking(list_listening_sockets)
+                    .await
+                    .ok()
+                    .and_then(Result::ok)
+                    .unwrap_or_default(),
+            };
+            annotate_ports(&mut open_ports, *target_ip, &sockets);
+            local_sockets = Some(sockets);
+        }
+
         // If we found open ports, emit host discovered event first (before progress)
         if !open_ports.is_empty() {
             total_discovered_ports += open_ports.len() as u32;
 
+            discovered_hosts += 1;
+
             let host_result = HostResult {
-                ip: ip_str.clone(),
-                hostname: hostname.clone(),
-                ports: open_ports.clone(),
+                ip: ip_str,
+                hostname,
+                ports: open_ports,
                 scan_duration_ms: host_start.elapsed().as_millis() as u64,
             };
 
-            let _ = progress_sink.emit(ScanProgress::HostDiscovered {
-                host: host_result.clone(),
-            });
-
-            results.push(host_result);
+            let _ = progress_sink.emit(ScanProgress::HostDiscovered { host: host_result });
         }
 
         // Update progress after host discovery
@@ -209,47 +235,126 @@ pub async fn run_scan(
             total_hosts,
             percentage,
             current_ip: next_ip,
-            discovered_hosts: results.len() as u32,
+            discovered_hosts,
             discovered_ports: total_discovered_ports,
         });
     }
 
-    let end_time_str = chrono_format_now();
-    let scan_duration_ms = start_time.elapsed().as_millis() as u64;
-
-    let total_open_ports: usize = results.iter().map(|h| h.ports.len()).sum();
-
-    let scan_results = ScanResults {
-        hosts: results.clone(),
+    let summary = ScanSummary {
         total_hosts_scanned: total_hosts,
-        hosts_with_open_ports: results.len() as u32,
-        total_open_ports: total_open_ports as u32,
-        scan_duration_ms,
+        hosts_with_open_ports: discovered_hosts,
+        total_open_ports: total_discovered_ports,
+        scan_duration_ms: start_time.elapsed().as_millis() as u64,
         start_time: start_time_str,
-        end_time: end_time_str,
+        end_time: chrono_format_now(),
     };
 
     // Emit completed event
     let _ = progress_sink.emit(ScanProgress::Completed {
-        results: scan_results.clone(),
+        summary: summary.clone(),
     });
 
-    Ok(scan_results)
+    Ok(summary)
+}
+
+/// Sink that keeps discovered hosts for [`collect_scan`]
+#[derive(Default)]
+struct CollectingSink {
+    hosts: Mutex<Vec<HostResult>>,
+}
+
+impl ScanProgressSink for CollectingSink {
+    fn emit(&self, progress: ScanProgress) -> Result<(), String> {
+        if let ScanProgress::HostDiscovered { host } = progress {
+            self.hosts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(host);
+        }
+        Ok(())
+    }
+}
+
+/// Run a scan without progress events and return every discovered host.
+///
+/// For one-shot callers (CLI, MCP) that need the full result as one value.
+pub async fn collect_scan(
+    request: ScanRequest,
+    scan_state: Arc<ScanState>,
+) -> Result<ScanResults, NetworkError> {
+    let sink = CollectingSink::default();
+    let summary = run_scan(request, &sink, scan_state).await?;
+    let hosts = sink
+        .hosts
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner);
+    Ok(ScanResults { hosts, summary })
 }
 
 // =============================================================================
 // Target & Port Parsing
 // =============================================================================
 
-/// Parse target string into list of IP addresses
-fn parse_targets(target: &str) -> Result<Vec<IpAddr>, String> {
-    let target = target.trim();
+/// Hosts parsed from a target string, plus the IPv6 zone they share
+pub(super) struct ScanTargets {
+    /// Target hosts
+    pub(super) hosts: Vec<IpAddr>,
+    /// Scope ID applied to link-local IPv6 hosts (0 when no zone was given)
+    pub(super) scope_id: u32,
+    /// Zone as written by the user, kept for display
+    zone: Option<String>,
+}
+
+impl ScanTargets {
+    /// Socket address for `ip:port`, carrying the scope for link-local hosts
+    pub(super) fn socket_addr(&self, ip: IpAddr, port: u16) -> SocketAddr {
+        scoped_socket_addr(ip, port, self.scope_id)
+    }
+
+    /// Display form of `ip`, with the `%zone` suffix for link-local hosts
+    pub(super) fn display(&self, ip: IpAddr) -> String {
+        match (ip, self.zone.as_deref()) {
+            (IpAddr::V6(v6), Some(zone)) if is_link_local_v6(&v6) => format!("{v6}%{zone}"),
+            _ => ip.to_string(),
+        }
+    }
+}
+
+/// Parse target string into list of IP addresses.
+///
+/// IPv6 link-local targets must carry a zone suffix (`fe80::1%en0` or
+/// `fe80::1%4`); the zone is resolved to a scope ID once for the whole
+/// target.
+pub(super) fn parse_targets(target: &str) -> Result<ScanTargets, NetworkError> {
+    let (target, zone) = split_zone(target.trim());
+    let scope_id = zone
+        .map(resolve_zone)
+        .transpose()
+        .map_err(NetworkError::InvalidZone)?
+        .unwrap_or(0);
+    let hosts = parse_target_hosts(&target)?;
+
+    let needs_zone = hosts
+        .iter()
+        .any(|ip| matches!(ip, IpAddr::V6(v6) if is_link_local_v6(v6)));
+    if needs_zone && zone.is_none() {
+        return Err(NetworkError::ZoneRequired(target));
+    }
+
+    Ok(ScanTargets {
+        hosts,
+        scope_id,
+        zone: zone.map(str::to_string),
+    })
+}
 
+/// Expand a zone-free target (IP, CIDR, or hostname) into host addresses
+fn parse_target_hosts(target: &str) -> Result<Vec<IpAddr>, NetworkError> {
     // Try parsing as CIDR
     if target.contains('/') {
         let network: IpNetwork = target
             .parse()
-            .map_err(|e| format!("Invalid CIDR notation: {e}"))?;
+            .map_err(|e| NetworkError::InvalidCidr(e.to_string()))?;
 
         // For IPv4 networks with prefix < 31, exclude network and broadcast addresses
         if let IpNetwork::V4(v4net) = network {
@@ -272,20 +377,16 @@ fn parse_targets(target: &str) -> Result<Vec<IpAddr>, String> {
 
     // Try resolving as hostname
     let socket_addr = format!("{target}:0");
-    match socket_addr.to_socket_addrs() {
-        Ok(mut addrs) => {
-            if let Some(addr) = addrs.next() {
-                Ok(vec![addr.ip()])
-            } else {
-                Err(format!("Could not resolve hostname: {target}"))
-            }
-        }
-        Err(e) => Err(format!("Could not resolve hostname: {e}")),
-    }
+    socket_addr
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .map(|addr| vec![addr.ip()])
+        .ok_or_else(|| NetworkError::UnresolvedHost(target.to_string()))
 }
 
 /// Resolve ports based on scan mode and preset
-fn resolve_ports(request: &ScanRequest) -> Result<Vec<u16>, String> {
+fn resolve_ports(request: &ScanRequest) -> Result<Vec<u16>, NetworkError> {
     match request.mode {
         ScanMode::Quick => Ok(QUICK_SCAN_PORTS.to_vec()),
         ScanMode::Full => Ok((1..=65535).collect()),
@@ -298,7 +399,7 @@ fn resolve_ports(request: &ScanRequest) -> Result<Vec<u16>, String> {
                     PortPreset::WellKnown => Ok((1..=1024).collect()),
                     PortPreset::Web => Ok(WEB_PORTS.to_vec()),
                     PortPreset::Database => Ok(DATABASE_PORTS.to_vec()),
-                    PortPreset::Custom => Err("Custom preset requires port_range".to_string()),
+                    PortPreset::Custom => Err(NetworkError::CustomPresetRequiresRange),
                 }
             }
         }
@@ -309,70 +410,205 @@ fn resolve_ports(request: &ScanRequest) -> Result<Vec<u16>, String> {
 // Port Scanning
 // =============================================================================
 
-/// Scan all ports on a single host
+/// Minimum spacing between [`ScanProgress::PortProgress`] events for one host
+const PORT_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Throttles per-host port progress and tracks the port block completed
+/// since the last emitted event.
+struct PortProgressTracker {
+    total: u32,
+    scanned: u32,
+    block: Option<(u16, u16)>,
+    last_emit: Instant,
+    emitted: bool,
+}
+
+impl PortProgressTracker {
+    fn new(total: u32, now: Instant) -> Self {
+        Self {
+            total,
+            scanned: 0,
+            block: None,
+            last_emit: now,
+            emitted: false,
+        }
+    }
+
+    /// Record a finished port and return the event to emit, if one is due.
+    ///
+    /// Events are spaced by [`PORT_PROGRESS_INTERVAL`]. A final 100% event is
+    /// only sent when an intermediate one went out, so fast hosts produce no
+    /// port-level events at all.
+    fn record(&mut self, ip: IpAddr, port: u16, now: Instant) -> Option<ScanProgress> {
+        self.scanned += 1;
+        self.block = Some(
+            self.block
+                .map_or((port, port), |(lo, hi)| (lo.min(port), hi.max(port))),
+        );
+
+        let finished = self.scanned >= self.total;
+        let due = now.duration_since(self.last_emit) >= PORT_PROGRESS_INTERVAL;
+        if !(due || (finished && self.emitted)) {
+            return None;
+        }
+
+        let (block_start, block_end) = self.block.take()?;
+        self.last_emit = now;
+        self.emitted = true;
+        #[allow(clippy::cast_precision_loss)]
+        let percentage = (self.scanned as f32 / self.total.max(1) as f32) * 100.0;
+        Some(ScanProgress::PortProgress {
+            ip: ip.to_string(),
+            scanned_ports: self.scanned,
+            total_ports: self.total,
+            percentage,
+            block_start,
+            block_end,
+        })
+    }
+}
+
+/// Scan all ports on a single host.
+///
+/// At most `concurrency` probes are in flight at once, polled from one
+/// `FuturesUnordered` rather than spawned as a task per port, so a Full
+/// scan holds `concurrency` futures instead of 65,535 task handles. Each
+/// finished probe frees its slot for the next port; after cancellation no
+/// new probes start and the in-flight ones run out.
+///
+/// Probes only connect. Banners and certificates for the open ports are
+/// collected afterwards by [`enrich_open_ports`], so a slow service never
+/// holds a probe slot.
 async fn scan_host_ports(
     ip: IpAddr,
+    scope_id: u32,
     ports: &[u16],
     timeout_duration: Duration,
-    semaphore: Arc<Semaphore>,
-    scan_state: Arc<ScanState>,
+    concurrency: usize,
+    scan_state: &ScanState,
+    progress_sink: &dyn ScanProgressSink,
 ) -> Vec<PortInfo> {
-    let mut handles = Vec::with_capacity(ports.len());
-
-    for &port in ports {
-        let sem = Arc::clone(&semaphore);
-        let state = Arc::clone(&scan_state);
-        let addr = SocketAddr::new(ip, port);
-
-        let handle = tokio::spawn(async move {
-            if state.is_cancelled() {
-                return None;
-            }
+    let probe = |port: u16| async move {
+        let addr = scoped_socket_addr(ip, port, scope_id);
+        (port, probe_port(addr, timeout_duration).await)
+    };
 
-            let _permit = sem.acquire().await.ok()?;
+    let mut pending = ports.iter().copied();
+    let mut in_flight: FuturesUnordered<_> = pending
+        .by_ref()
+        .take(concurrency.max(1))
+        .map(probe)
+        .collect();
+    let mut open_ports = Vec::new();
+    let mut tracker = PortProgressTracker::new(ports.len() as u32, Instant::now());
 
-            if state.is_cancelled() {
-                return None;
+    while let Some((port, state)) = in_flight.next().await {
+        if !scan_state.is_cancelled() {
+            if let Some(next) = pending.next() {
+                in_flight.push(probe(next));
             }
+        }
+        if let Some(event) = tracker.record(ip, port, Instant::now()) {
+            let _ = progress_sink.emit(event);
+        }
+        if state == PortState::Open {
+            open_ports.push(PortInfo {
+                port,
+                state: PortState::Open,
+                service: get_service_name(port).map(String::from),
+                banner: None,
+                tls_cert: None,
+                process: None,
+            });
+        }
+    }
 
-            let result = scan_port(addr, timeout_duration).await;
-            Some((port, result))
-        });
+    open_ports.sort_by_key(|p| p.port);
+    if !scan_state.is_cancelled() {
+        enrich_open_ports(
+            ip,
+            scope_id,
+            &mut open_ports,
+            timeout_duration,
+            concurrency,
+            scan_state,
+        )
+        .await;
+    }
+    open_ports
+}
 
-        handles.push(handle);
+/// Connect-only probe
+async fn probe_port(addr: SocketAddr, timeout_duration: Duration) -> PortState {
+    match timeout(timeout_duration, TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => PortState::Open,
+        // Connection refused = closed
+        Ok(Err(_)) => PortState::Closed,
+        // Timeout = filtered
+        Err(_) => PortState::Filtered,
     }
+}
 
-    let mut port_results: HashMap<u16, (PortState, Option<String>, Option<TlsCertInfo>)> =
-        HashMap::new();
+/// Overall enrichment time per host, as a multiple of the probe timeout
+const ENRICHMENT_BUDGET_FACTOR: u32 = 2;
+
+/// Upper bound on concurrent banner grabs per host
+const MAX_ENRICHMENT_CONCURRENCY: usize = 32;
+
+/// Fill in banners and TLS certificates for a host's open ports.
+///
+/// Grabs run with bounded concurrency, each on a fresh connection with the
+/// probe timeout, and the whole phase shares one budget of
+/// [`ENRICHMENT_BUDGET_FACTOR`] × the timeout. Ports not reached when the
+/// budget runs out keep their bare `open` entry, so a host with many slow
+/// services adds at most the budget to the scan instead of one timeout per
+/// port.
+async fn enrich_open_ports(
+    ip: IpAddr,
+    scope_id: u32,
+    open_ports: &mut [PortInfo],
+    timeout_duration: Duration,
+    concurrency: usize,
+    scan_state: &ScanState,
+) {
+    let deadline =
+        tokio::time::Instant::now() + timeout_duration.saturating_mul(ENRICHMENT_BUDGET_FACTOR);
+    let grab = |index: usize, port: u16| async move {
+        let addr = scoped_socket_addr(ip, port, scope_id);
+        let grabbed = match timeout(timeout_duration, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => grab_banner_and_tls(stream, timeout_duration).await,
+            _ => (None, None),
+        };
+        (index, grabbed)
+    };
 
-    for handle in handles {
-        if let Ok(Some((port, result))) = handle.await {
-            if let Some((state, banner, tls_cert)) = result {
-                if state == PortState::Open {
-                    port_results.insert(port, (state, banner, tls_cert));
-                }
+    let mut pending = open_ports
+        .iter()
+        .map(|p| p.port)
+        .enumerate()
+        .collect::<Vec<_>>()
+        .into_iter();
+    let mut in_flight: FuturesUnordered<_> = pending
+        .by_ref()
+        .take(concurrency.clamp(1, MAX_ENRICHMENT_CONCURRENCY))
+        .map(|(index, port)| grab(index, port))
+        .collect();
+
+    while let Ok(Some((index, (banner, tls_cert)))) =
+        tokio::time::timeout_at(deadline, in_flight.next()).await
+    {
+        if !scan_state.is_cancelled() {
+            if let Some((next_index, next_port)) = pending.next() {
+                in_flight.push(grab(next_index, next_port));
             }
         }
+        open_ports[index].banner = banner;
+        open_ports[index].tls_cert = tls_cert;
     }
-
-    // Convert to sorted PortInfo list
-    let mut open_ports: Vec<PortInfo> = port_results
-        .into_iter()
-        .map(|(port, (state, banner, tls_cert))| PortInfo {
-            port,
-            state,
-            service: get_service_name(port).map(String::from),
-            banner,
-            tls_cert,
-        })
-        .collect();
-
-    open_ports.sort_by_key(|p| p.port);
-    open_ports
 }
 
 /// Scan a single port with banner grabbing and optional TLS cert extraction
-async fn scan_port(
+pub(super) async fn scan_port(
     addr: SocketAddr,
     timeout_duration: Duration,
 ) -> Option<(PortState, Option<String>, Option<TlsCertInfo>)> {
@@ -428,7 +664,12 @@ async fn grab_banner_and_tls(
         return (banner, None);
     }
 
-    // Other ports: try to read initial banner (SSH, FTP, SMTP, etc.)
+    // Mail and FTP ports: read the greeting, then upgrade via STARTTLS
+    if let Some(protocol) = StartTlsProtocol::for_port(port) {
+        return grab_starttls_info(stream, protocol, timeout_duration).await;
+    }
+
+    // Other ports: try to read initial banner (SSH, Telnet, etc.)
     let banner = grab_raw_banner(stream, timeout_duration).await;
     (banner, None)
 }
@@ -438,46 +679,84 @@ async fn grab_tls_info(
     stream: TcpStream,
     timeout_duration: Duration,
 ) -> (Option<String>, Option<TlsCertInfo>) {
-    let addr = match stream.peer_addr() {
-        Ok(a) => a,
-        Err(_) => return (None, None),
+    let Some(tls_stream) = tls_handshake(stream, timeout_duration).await else {
+        return (None, None);
+    };
+    let tls_cert = leaf_cert_info(&tls_stream);
+
+    // Try to grab HTTP banner over TLS
+    let banner = grab_http_banner_tls(tls_stream, timeout_duration).await;
+
+    (banner, tls_cert)
+}
+
+/// Read a plaintext greeting, request a STARTTLS upgrade, and extract the
+/// certificate presented after the upgrade
+async fn grab_starttls_info(
+    stream: TcpStream,
+    protocol: StartTlsProtocol,
+    timeout_duration: Duration,
+) -> (Option<String>, Option<TlsCertInfo>) {
+    let Some(outcome) = starttls::negotiate(stream, protocol, timeout_duration).await else {
+        return (None, None);
     };
 
-    // Build TLS config with accept-all verifier
-    let provider = Arc::new(rustls::crypto::ring::default_provider());
-    let config =
-        match ClientConfig::builder_with_provider(provider).with_safe_default_protocol_versions() {
-            Ok(builder) => builder
+    let tls_cert = match outcome.upgraded {
+        Some(stream) => tls_handshake(stream, timeout_duration)
+            .await
+            .and_then(|tls_stream| leaf_cert_info(&tls_stream)),
+        None => None,
+    };
+    let banner = Some(outcome.banner).filter(|b| !b.is_empty());
+
+    (banner, tls_cert)
+}
+
+/// Client config with the accept-all verifier, built once and shared by
+/// every handshake in every scan
+fn accept_all_config() -> Option<Arc<ClientConfig>> {
+    static CONFIG: std::sync::OnceLock<Option<Arc<ClientConfig>>> = std::sync::OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let provider = Arc::new(rustls::crypto::ring::default_provider());
+            let config = ClientConfig::builder_with_provider(provider)
+                .with_safe_default_protocol_versions()
+                .ok()?
                 .dangerous()
                 .with_custom_certificate_verifier(Arc::new(AcceptAllVerifier))
-                .with_no_client_auth(),
-            Err(_) => return (None, None),
-        };
+                .with_no_client_auth();
+            Some(Arc::new(config))
+        })
+        .clone()
+}
 
-    let connector = TlsConnector::from(Arc::new(config));
+/// Complete a TLS handshake over `stream` using the accept-all verifier
+async fn tls_handshake(
+    stream: TcpStream,
+    timeout_duration: Duration,
+) -> Option<tokio_rustls::client::TlsStream<TcpStream>> {
+    let addr = stream.peer_addr().ok()?;
+    let connector = TlsConnector::from(accept_all_config()?);
 
     // Use IP address as server name (we're scanning by IP, not hostname)
     let ip_addr = rustls::pki_types::IpAddr::from(addr.ip());
     let server_name = ServerName::from(ip_addr);
 
     // TLS handshake with timeout
-    let tls_stream = match timeout(timeout_duration, connector.connect(server_name, stream)).await {
-        Ok(Ok(s)) => s,
-        _ => return (None, None),
-    };
+    timeout(timeout_duration, connector.connect(server_name, stream))
+        .await
+        .ok()?
+        .ok()
+}
 
-    // Extract certificate info from the connection
-    let tls_cert = tls_stream
+/// Extract certificate info for the leaf certificate of a TLS connection
+fn leaf_cert_info(tls_stream: &tokio_rustls::client::TlsStream<TcpStream>) -> Option<TlsCertInfo> {
+    tls_stream
         .get_ref()
         .1
         .peer_certificates()
         .and_then(|certs| certs.first())
-        .and_then(|cert| parse_x509_cert(cert.as_ref()));
-
-    // Try to grab HTTP banner over TLS
-    let banner = grab_http_banner_tls(tls_stream, timeout_duration).await;
-
-    (banner, tls_cert)
+        .and_then(|cert| parse_x509_cert(cert.as_ref()))
 }
 
 /// Parse X.509 certificate DER bytes to extract key information
@@ -634,6 +913,32 @@ async fn grab_raw_banner(stream: TcpStream, timeout_duration: Duration) -> Optio
 // Hostname Resolution
 // =============================================================================
 
+/// Hostname resolution method, in the order they are tried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HostnameMethod {
+    /// DNS reverse lookup (most reliable for internet hosts)
+    Dns,
+    /// mDNS resolution (for .local hosts)
+    Mdns,
+    /// `NetBIOS` resolution (for Windows hosts)
+    Netbios,
+    /// LLMNR resolution (Windows hosts that do not answer `NetBIOS` or mDNS)
+    Llmnr,
+}
+
+/// Methods enabled in `opts`, in the order they are tried
+fn hostname_methods(opts: &HostnameResolutionOptions) -> Vec<HostnameMethod> {
+    [
+        (opts.dns, HostnameMethod::Dns),
+        (opts.mdns, HostnameMethod::Mdns),
+        (opts.netbios, HostnameMethod::Netbios),
+        (opts.llmnr, HostnameMethod::Llmnr),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, method)| enabled.then_some(method))
+    .collect()
+}
+
 /// Resolve IP to hostname using configured methods
 async fn resolve_hostname_with_options(
     ip: &IpAddr,
@@ -642,35 +947,19 @@ async fn resolve_hostname_with_options(
     let opts = options.as_ref().cloned().unwrap_or_default();
     let timeout_duration = Duration::from_millis(u64::from(opts.timeout_ms));
 
-    // Skip if all methods disabled
-    if !opts.dns && !opts.mdns && !opts.netbios {
-        return None;
-    }
-
-    let ip_owned = *ip;
-
-    // Try each method in order of reliability
-    // 1. DNS reverse lookup (most reliable for internet hosts)
-    if opts.dns {
-        if let Some(name) = resolve_dns(ip_owned, timeout_duration).await {
-            return Some(name);
-        }
-    }
-
-    // 2. mDNS resolution (for .local hosts)
-    if opts.mdns {
-        if let Some(name) = resolve_mdns(ip_owned, timeout_duration).await {
-            return Some(name);
-        }
-    }
-
-    // 3. NetBIOS resolution (for Windows hosts)
-    if opts.netbios {
-        if let Some(name) = resolve_netbios(ip_owned, timeout_duration).await {
-            return Some(name);
+    for method in hostname_methods(&opts) {
+        let name = match method {
+            HostnameMethod::Dns => resolve_dns(*ip, timeout_duration).await,
+            HostnameMethod::Mdns => resolve_mdns(*ip, timeout_duration).await,
+            HostnameMethod::Netbios => resolve_netbios(*ip, timeout_duration).await,
+            HostnameMethod::Llmnr => {
+                llmnr::resolve_hostname(&ip.to_string(), timeout_duration).await
+            }
+        };
+        if name.is_some() {
+            return name;
         }
     }
-
     None
 }
 
@@ -713,10 +1002,7 @@ async fn resolve_mdns(ip: IpAddr, timeout_duration: Duration) -> Option<String>
 
 /// Resolve IP to hostname via NetBIOS Name Service
 async fn resolve_netbios(ip: IpAddr, timeout_duration: Duration) -> Option<String> {
-    tokio::task::spawn_blocking(move || resolve_netbios_name(ip, timeout_duration))
-        .await
-        .ok()
-        .flatten()
+    resolve_netbios_name(ip, timeout_duration).await
 }
 
 // =============================================================================
@@ -724,7 +1010,7 @@ async fn resolve_netbios(ip: IpAddr, timeout_duration: Duration) -> Option<Strin
 // =============================================================================
 
 /// Get current time as ISO 8601 string
-fn chrono_format_now() -> String {
+pub(super) fn chrono_format_now() -> String {
     use std::time::SystemTime;
     let now = SystemTime::now();
     let duration = now
@@ -742,7 +1028,7 @@ fn chrono_format_now() -> String {
 }
 
 /// Convert Unix timestamp to datetime components
-fn timestamp_to_datetime(timestamp: u64) -> (u32, u32, u32, u32, u32, u32) {
+pub(super) fn timestamp_to_datetime(timestamp: u64) -> (u32, u32, u32, u32, u32, u32) {
     const SECS_PER_MIN: u64 = 60;
     const SECS_PER_HOUR: u64 = 3600;
     const SECS_PER_DAY: u64 = 86400;
@@ -799,3 +1085,171 @@ fn days_to_ymd(days: u64) -> (u32, u32, u32) {
 const fn is_leap_year(year: u32) -> bool {
     (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn llmnr_option_adds_the_last_resolution_method() {
+        let defaults = HostnameResolutionOptions::default();
+        assert_eq!(
+            hostname_methods(&defaults),
+            [HostnameMethod::Dns, HostnameMethod::Mdns]
+        );
+
+        // Older frontends omit the field, which keeps LLMNR off
+        let opts: HostnameResolutionOptions =
+            serde_json::from_str(r#"{"dns": false, "mdns": false, "netbios": true}"#).unwrap();
+        assert_eq!(hostname_methods(&opts), [HostnameMethod::Netbios]);
+
+        let opts = HostnameResolutionOptions {
+            llmnr: true,
+            ..opts
+        };
+        assert_eq!(
+            hostname_methods(&opts),
+            [HostnameMethod::Netbios, HostnameMethod::Llmnr]
+        );
+
+        // LLMNR alone still resolves instead of skipping resolution
+        let opts = HostnameResolutionOptions {
+            netbios: false,
+            ..opts
+        };
+        assert_eq!(hostname_methods(&opts), [HostnameMethod::Llmnr]);
+    }
+
+    #[test]
+    fn port_progress_is_silent_for_fast_hosts() {
+        let start = Instant::now();
+        let mut tracker = PortProgressTracker::new(3, start);
+        assert!([22, 80, 443]
+            .into_iter()
+            .all(|port| tracker.record(IP, port, start).is_none()));
+    }
+
+    #[test]
+    fn port_progress_reports_block_and_final_event() {
+        let start = Instant::now();
+        let mut tracker = PortProgressTracker::new(3, start);
+        assert!(tracker.record(IP, 80, start).is_none());
+
+        let later = start + PORT_PROGRESS_INTERVAL;
+        assert!(matches!(
+            tracker.record(IP, 22, later),
+            Some(ScanProgress::PortProgress {
+                scanned_ports: 2,
+                block_start: 22,
+                block_end: 80,
+                ..
+            })
+        ));
+
+        // The last port completes the host and flushes a 100% event.
+        assert!(matches!(
+            tracker.record(IP, 443, later),
+            Some(ScanProgress::PortProgress {
+                percentage,
+                block_start: 443,
+                block_end: 443,
+                ..
+            }) if (percentage - 100.0).abs() < f32::EPSILON
+        ));
+    }
+
+    struct NullSink;
+
+    impl ScanProgressSink for NullSink {
+        fn emit(&self, _: ScanProgress) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// Ports that were just bound and released, so nothing listens on them.
+    async fn closed_ports(count: usize) -> Vec<u16> {
+        let mut listeners = Vec::new();
+        for _ in 0..count {
+            listeners.push(tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap());
+        }
+        listeners
+            .iter()
+            .map(|l| l.local_addr().unwrap().port())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn bounded_port_scan_finds_open_ports_and_stops_on_cancel() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap().port();
+        let mut ports = vec![open];
+        ports.extend(closed_ports(19).await);
+        let timeout = Duration::from_millis(300);
+
+        let (state, _rx) = ScanState::new();
+        let found = scan_host_ports(IP, 0, &ports, timeout, 4, &state, &NullSink).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].port, open);
+        assert_eq!(found[0].state, PortState::Open);
+
+        // Cancelled up front: only the first window of probes runs, so the
+        // open port at the end of the list is never reached.
+        state.cancel();
+        let reversed: Vec<u16> = ports.iter().rev().copied().collect();
+        let found = scan_host_ports(IP, 0, &reversed, timeout, 4, &state, &NullSink).await;
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn enrichment_grabs_banners_within_the_host_budget() {
+        // One service greets immediately; the others accept and stay silent.
+        let greeter = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let greeter_port = greeter.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = greeter.accept().await {
+                let _ = stream.write_all(b"SSH-2.0-Test\r\n").await;
+            }
+        });
+        let mut silent = Vec::new();
+        for _ in 0..8 {
+            silent.push(tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap());
+        }
+
+        let mut ports: Vec<u16> = silent
+            .iter()
+            .map(|l| l.local_addr().unwrap().port())
+            .collect();
+        ports.push(greeter_port);
+        let mut open: Vec<PortInfo> = ports
+            .iter()
+            .map(|&port| PortInfo {
+                port,
+                state: PortState::Open,
+                service: None,
+                banner: None,
+                tls_cert: None,
+                process: None,
+            })
+            .collect();
+
+        // Nine silent grabs one at a time would take 9 × 200ms; the budget
+        // caps the phase at 2 × 200ms.
+        let timeout = Duration::from_millis(200);
+        let (state, _rx) = ScanState::new();
+        let start = Instant::now();
+        enrich_open_ports(IP, 0, &mut open, timeout, 1, &state).await;
+        assert!(start.elapsed() < Duration::from_millis(1000));
+
+        let (state, _rx) = ScanState::new();
+        enrich_open_ports(IP, 0, &mut open, timeout, 16, &state).await;
+        let greeted = open.iter().find(|p| p.port == greeter_port).unwrap();
+        assert_eq!(greeted.banner.as_deref(), Some("SSH-2.0-Test"));
+        assert!(open
+            .iter()
+            .filter(|p| p.port != greeter_port)
+            .all(|p| p.banner.is_none()));
+    }
+}
@@ -0,0 +1,316 @@
+//! STARTTLS negotiation for mail and file-transfer services
+//!
+//! SMTP, IMAP, POP3, and FTP usually listen in plaintext and upgrade to TLS
+//! on request. A passive banner read only captures the plaintext greeting,
+//! which often says little more than "ESMTP ready". This module speaks just
+//! enough of each protocol to request the upgrade so the scanner can
+//! complete a TLS handshake and read the server certificate.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// Maximum response lines read for a single command
+const MAX_RESPONSE_LINES: usize = 32;
+
+/// Maximum banner length kept from the greeting
+const MAX_BANNER_CHARS: usize = 200;
+
+/// Client name announced in the SMTP `EHLO`
+const EHLO_NAME: &str = "kogu.local";
+
+/// Plaintext protocols with an in-band TLS upgrade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartTlsProtocol {
+    /// SMTP `STARTTLS` (RFC 3207)
+    Smtp,
+    /// IMAP `STARTTLS` (RFC 3501)
+    Imap,
+    /// POP3 `STLS` (RFC 2595)
+    Pop3,
+    /// FTP `AUTH TLS` (RFC 4217)
+    Ftp,
+}
+
+impl StartTlsProtocol {
+    /// Protocol conventionally served on `port`, if it supports an upgrade
+    pub const fn for_port(port: u16) -> Option<Self> {
+        match port {
+            25 | 587 | 2525 => Some(Self::Smtp),
+            143 => Some(Self::Imap),
+            110 => Some(Self::Pop3),
+            21 => Some(Self::Ftp),
+            _ => None,
+        }
+    }
+}
+
+/// Result of a STARTTLS attempt
+pub struct StartTlsOutcome {
+    /// First line of the plaintext greeting
+    pub banner: String,
+    /// The raw stream, ready for a TLS handshake, when the server accepted
+    /// the upgrade request
+    pub upgraded: Option<TcpStream>,
+}
+
+/// Parse an SMTP/FTP style reply line into its code and whether it is the
+/// final line of the reply (`250 ok` vs. the continuation form `250-ok`).
+fn reply_code(line: &str) -> Option<(u16, bool)> {
+    let code = line.get(..3)?.parse().ok()?;
+    let is_last = line.as_bytes().get(3) != Some(&b'-');
+    Some((code, is_last))
+}
+
+/// Whether an `EHLO` reply advertises the `STARTTLS` extension
+fn advertises_starttls(lines: &[String]) -> bool {
+    lines.iter().any(|line| {
+        line.get(4..)
+            .is_some_and(|ext| ext.trim().eq_ignore_ascii_case("STARTTLS"))
+    })
+}
+
+/// Trim a greeting line into a displayable banner
+fn banner_from(line: &str) -> String {
+    line.trim().chars().take(MAX_BANNER_CHARS).collect()
+}
+
+/// Line-oriented exchange over a buffered stream with a shared timeout
+struct Session {
+    reader: BufReader<TcpStream>,
+    timeout: Duration,
+}
+
+impl Session {
+    async fn read_line(&mut self) -> Option<String> {
+        let mut line = String::new();
+        match timeout(self.timeout, self.reader.read_line(&mut line)).await {
+            Ok(Ok(n)) if n > 0 => Some(line.trim_end().to_string()),
+            _ => None,
+        }
+    }
+
+    async fn send(&mut self, command: &str) -> Option<()> {
+        let stream = self.reader.get_mut();
+        timeout(self.timeout, stream.write_all(command.as_bytes()))
+            .await
+            .ok()?
+            .ok()
+    }
+
+    /// Read a (possibly multi-line) numeric reply, returning its code and lines
+    async fn read_reply(&mut self) -> Option<(u16, Vec<String>)> {
+        let mut lines = Vec::new();
+        for _ in 0..MAX_RESPONSE_LINES {
+            let line = self.read_line().await?;
+            let (code, is_last) = reply_code(&line)?;
+            lines.push(line);
+            if is_last {
+                return Some((code, lines));
+            }
+        }
+        None
+    }
+
+    /// Read until the tagged IMAP completion for `tag`
+    async fn read_tagged(&mut self, tag: &str) -> Option<String> {
+        for _ in 0..MAX_RESPONSE_LINES {
+            let line = self.read_line().await?;
+            if let Some(status) = line.strip_prefix(tag) {
+                return Some(status.trim().to_string());
+            }
+        }
+        None
+    }
+
+    /// Hand back the raw stream for the TLS handshake.
+    ///
+    /// Refuses when the server already sent bytes past the upgrade reply;
+    /// those would belong to the TLS layer and be lost with the buffer.
+    fn into_stream(self) -> Option<TcpStream> {
+        self.reader
+            .buffer()
+            .is_empty()
+            .then(|| self.reader.into_inner())
+    }
+}
+
+/// Read the greeting and request a TLS upgrade.
+///
+/// Returns `None` only when no greeting arrives. A server that refuses the
+/// upgrade still yields its banner with `upgraded: None`.
+pub async fn negotiate(
+    stream: TcpStream,
+    protocol: StartTlsProtocol,
+    timeout_duration: Duration,
+) -> Option<StartTlsOutcome> {
+    let mut session = Session {
+        reader: BufReader::new(stream),
+        timeout: timeout_duration,
+    };
+
+    let (banner, accepted) = match protocol {
+        StartTlsProtocol::Smtp => {
+            let (code, greeting) = session.read_reply().await?;
+            let banner = banner_from(&greeting[0]);
+            let accepted = code == 220 && smtp_upgrade(&mut session).await.is_some();
+            (banner, accepted)
+        }
+        StartTlsProtocol::Ftp => {
+            let (code, greeting) = session.read_reply().await?;
+            let banner = banner_from(&greeting[0]);
+            let accepted = code == 220 && ftp_upgrade(&mut session).await.is_some();
+            (banner, accepted)
+        }
+        StartTlsProtocol::Imap => {
+            let greeting = session.read_line().await?;
+            let accepted = greeting.starts_with("* OK") && imap_upgrade(&mut session).await;
+            (banner_from(&greeting), accepted)
+        }
+        StartTlsProtocol::Pop3 => {
+            let greeting = session.read_line().await?;
+            let accepted = greeting.starts_with("+OK") && pop3_upgrade(&mut session).await;
+            (banner_from(&greeting), accepted)
+        }
+    };
+
+    Some(StartTlsOutcome {
+        banner,
+        upgraded: if accepted {
+            session.into_stream()
+        } else {
+            None
+        },
+    })
+}
+
+async fn smtp_upgrade(session: &mut Session) -> Option<()> {
+    session.send(&format!("EHLO {EHLO_NAME}\r\n")).await?;
+    let (code, lines) = session.read_reply().await?;
+    if code != 250 || !advertises_starttls(&lines) {
+        return None;
+    }
+    session.send("STARTTLS\r\n").await?;
+    let (code, _) = session.read_reply().await?;
+    (code == 220).then_some(())
+}
+
+async fn ftp_upgrade(session: &mut Session) -> Option<()> {
+    session.send("AUTH TLS\r\n").await?;
+    let (code, _) = session.read_reply().await?;
+    (code == 234).then_some(())
+}
+
+async fn imap_upgrade(session: &mut Session) -> bool {
+    const TAG: &str = "k1";
+    if session.send(&format!("{TAG} STARTTLS\r\n")).await.is_none() {
+        return false;
+    }
+    session
+        .read_tagged(TAG)
+        .await
+        .is_some_and(|status| status.starts_with("OK"))
+}
+
+async fn pop3_upgrade(session: &mut Session) -> bool {
+    if session.send("STLS\r\n").await.is_none() {
+        return false;
+    }
+    session
+        .read_line()
+        .await
+        .is_some_and(|reply| reply.starts_with("+OK"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn reply_code_distinguishes_continuation_lines() {
+        assert_eq!(reply_code("250-mail.example.com"), Some((250, false)));
+        assert_eq!(reply_code("250 STARTTLS"), Some((250, true)));
+        assert_eq!(reply_code("220"), Some((220, true)));
+        assert_eq!(reply_code("* OK"), None);
+    }
+
+    #[test]
+    fn detects_starttls_extension() {
+        let lines = vec![
+            "250-mail.example.com".to_string(),
+            "250-PIPELINING".to_string(),
+            "250 STARTTLS".to_string(),
+        ];
+        assert!(advertises_starttls(&lines));
+        assert!(!advertises_starttls(&lines[..2]));
+    }
+
+    #[test]
+    fn maps_well_known_ports() {
+        assert_eq!(
+            StartTlsProtocol::for_port(587),
+            Some(StartTlsProtocol::Smtp)
+        );
+        assert_eq!(
+            StartTlsProtocol::for_port(143),
+            Some(StartTlsProtocol::Imap)
+        );
+        assert_eq!(StartTlsProtocol::for_port(443), None);
+    }
+
+    /// Serve a scripted SMTP dialogue and return what the client sent.
+    async fn fake_smtp(script: &'static [&'static str]) -> (StartTlsOutcome, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            for (i, reply) in script.iter().enumerate() {
+                socket.write_all(reply.as_bytes()).await.unwrap();
+                // Collect the client's next command; the final reply ends the dialogue.
+                if i + 1 < script.len() {
+                    let mut buf = [0u8; 256];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    received.extend_from_slice(&buf[..n]);
+                }
+            }
+            String::from_utf8_lossy(&received).into_owned()
+        });
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let outcome = negotiate(stream, StartTlsProtocol::Smtp, Duration::from_secs(2))
+            .await
+            .unwrap();
+        (outcome, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn smtp_upgrade_follows_ehlo_and_starttls() {
+        let (outcome, sent) = fake_smtp(&[
+            "220 mail.example.com ESMTP Postfix\r\n",
+            "250-mail.example.com\r\n250 STARTTLS\r\n",
+            "220 2.0.0 Ready to start TLS\r\n",
+        ])
+        .await;
+        assert_eq!(outcome.banner, "220 mail.example.com ESMTP Postfix");
+        assert!(outcome.upgraded.is_some());
+        assert!(sent.contains("EHLO kogu.local\r\n"));
+        assert!(sent.contains("STARTTLS\r\n"));
+    }
+
+    #[tokio::test]
+    async fn smtp_without_starttls_keeps_banner() {
+        let (outcome, sent) = fake_smtp(&[
+            "220 legacy.example.com ESMTP\r\n",
+            "250-legacy.example.com\r\n250 SIZE 1000\r\n",
+        ])
+        .await;
+        assert_eq!(outcome.banner, "220 legacy.example.com ESMTP");
+        assert!(outcome.upgraded.is_none());
+        assert!(!sent.contains("STARTTLS"));
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::listening::SocketProcess;
+
 // =============================================================================
 // Network Interface Types
 // =============================================================================
@@ -286,6 +288,9 @@ pub struct HostnameResolutionOptions {
     /// Enable `NetBIOS` name resolution (Windows)
     #[serde(default)]
     pub netbios: bool,
+    /// Enable LLMNR multicast PTR resolution (UDP 5355, Windows)
+    #[serde(default)]
+    pub llmnr: bool,
     /// Resolution timeout in milliseconds
     #[serde(default = "default_resolution_timeout")]
     pub timeout_ms: u32,
@@ -305,6 +310,7 @@ impl Default for HostnameResolutionOptions {
             dns: true,
             mdns: true,
             netbios: false,
+            llmnr: false,
             timeout_ms: default_resolution_timeout(),
         }
     }
@@ -331,14 +337,14 @@ pub struct ScanRequest {
 }
 
 /// TLS certificate information extracted from HTTPS ports
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TlsCertInfo {
     /// Common Name (CN) from the certificate subject
     #[serde(skip_serializing_if = "Option::is_none")]
     pub common_name: Option<String>,
     /// Subject Alternative Names (SAN)
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub subject_alt_names: Vec<String>,
     /// Certificate issuer (CN or Organization)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -348,7 +354,7 @@ pub struct TlsCertInfo {
 }
 
 /// Information about a scanned port
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PortInfo {
     /// Port number
@@ -362,10 +368,13 @@ pub struct PortInfo {
     /// TLS certificate info (for HTTPS ports)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tls_cert: Option<TlsCertInfo>,
+    /// Owning process, when the host is this machine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process: Option<SocketProcess>,
 }
 
 /// Result for a single host
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HostResult {
     /// IP address
@@ -378,12 +387,11 @@ pub struct HostResult {
     pub scan_duration_ms: u64,
 }
 
-/// Final scan results
-#[derive(Debug, Clone, Serialize)]
+/// Totals for a finished scan. Host results are not repeated here: they
+/// are streamed as [`ScanProgress::HostDiscovered`] events as they are found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ScanResults {
-    /// All scanned hosts with open ports
-    pub hosts: Vec<HostResult>,
+pub struct ScanSummary {
     /// Total hosts scanned
     pub total_hosts_scanned: u32,
     /// Hosts with at least one open port
@@ -398,6 +406,18 @@ pub struct ScanResults {
     pub end_time: String,
 }
 
+/// Final scan results with every host, for callers that need them in one
+/// value (CLI, MCP, scan history)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanResults {
+    /// All scanned hosts with open ports
+    pub hosts: Vec<HostResult>,
+    /// Scan totals
+    #[serde(flatten)]
+    pub summary: ScanSummary,
+}
+
 // =============================================================================
 // Scan Progress Sink Trait
 // =============================================================================
@@ -447,9 +467,25 @@ pub enum ScanProgress {
         /// Total open ports found so far
         discovered_ports: u32,
     },
+    /// Per-host port progress, emitted at a throttled rate while a host's
+    /// ports are being probed
+    PortProgress {
+        /// IP of the host being scanned
+        ip: String,
+        /// Ports probed so far on this host
+        scanned_ports: u32,
+        /// Total ports to probe on this host
+        total_ports: u32,
+        /// Percentage of this host's ports probed
+        percentage: f32,
+        /// Lowest port completed since the previous port progress event
+        block_start: u16,
+        /// Highest port completed since the previous port progress event
+        block_end: u16,
+    },
     /// Scan completed
     Completed {
-        /// Final results
-        results: ScanResults,
+        /// Scan totals
+        summary: ScanSummary,
     },
 }
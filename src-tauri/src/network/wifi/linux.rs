@@ -188,7 +188,7 @@ async fn build_network(
     let is_connected = active_bssids.iter().any(|b| b == &bssid);
 
     Ok(Some(WifiNetwork {
-        vendor: oui::lookup_vendor(&bssid).map(String::from),
+        vendor: oui::lookup_vendor(&bssid),
         bssid,
         ssid,
         rssi_dbm,
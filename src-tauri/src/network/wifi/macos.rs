@@ -179,7 +179,7 @@ fn scan_blocking() -> Result<Vec<WifiNetwork>, WifiError> {
         let is_connected = current_bssid.as_ref().is_some_and(|cur| cur == &bssid);
 
         out.push(WifiNetwork {
-            vendor: oui::lookup_vendor(&bssid).map(String::from),
+            vendor: oui::lookup_vendor(&bssid),
             bssid,
             ssid,
             rssi_dbm: rssi,
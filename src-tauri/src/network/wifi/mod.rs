@@ -181,7 +181,7 @@ fn fixture_networks() -> Option<Vec<WifiNetwork>> {
                 channel_width_mhz: row.width,
                 band: row.band,
                 security: row.security,
-                vendor: super::oui::lookup_vendor(row.bssid).map(String::from),
+                vendor: super::oui::lookup_vendor(row.bssid),
                 is_connected: row.is_connected,
             })
             .collect(),
@@ -0,0 +1,484 @@
+//! Gateway and upstream health check command.
+//!
+//! A one-click "is it my LAN or my ISP" diagnostic. The command finds the
+//! default gateway from interface data, measures round-trip time and loss
+//! to it and to a configurable upstream host, and times a DNS resolution.
+//! Comparing the three localizes the fault: a lossy gateway points at the
+//! local network, a healthy gateway with a lossy upstream points past the
+//! router, and healthy paths with slow resolution point at DNS.
+//!
+//! Round trips are measured with TCP connects rather than ICMP so no
+//! elevated privileges are required. A refused connection still completes
+//! a full round trip, so it counts as a reply.
+
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::dns_lookup::build_resolver;
+use crate::network::interfaces::get_detailed_interfaces;
+
+/// Ports tried, in order, to find one the gateway answers on
+const GATEWAY_PORTS: [u16; 4] = [53, 80, 443, 22];
+
+/// Pause between consecutive probes to the same target
+const PROBE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Upper bound on probes per target
+const MAX_PROBES: u32 = 50;
+
+/// Loss above this percentage marks a path as unhealthy
+const LOSS_THRESHOLD_PERCENT: f64 = 20.0;
+
+/// Average gateway RTT above this marks the LAN as degraded
+const GATEWAY_SLOW_MS: f64 = 100.0;
+
+/// Average upstream RTT above this marks the upstream path as degraded
+const UPSTREAM_SLOW_MS: f64 = 300.0;
+
+/// DNS resolution slower than this is reported as a DNS problem
+const DNS_SLOW_MS: f64 = 500.0;
+
+/// Request payload sent from the frontend.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkHealthRequest {
+    /// Upstream host (DNS name or IP literal) beyond the gateway.
+    pub upstream_host: String,
+    /// TCP port probed on the upstream host.
+    pub upstream_port: u16,
+    /// Name resolved to time DNS.
+    pub dns_name: String,
+    /// Resolver selector, as accepted by the DNS lookup tool
+    /// (`"system"`, `"cloudflare"`, `"google"`, `"quad9"`, or an IP).
+    pub resolver: String,
+    /// Probes sent to each target (1 to 50).
+    pub probe_count: u32,
+    /// Per-probe timeout in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// Round-trip statistics for one target.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeStats {
+    /// Probed address as `ip:port`.
+    pub target: String,
+    /// Probes sent.
+    pub sent: u32,
+    /// Probes answered (connect accepted or refused).
+    pub received: u32,
+    /// Percentage of probes without an answer.
+    pub loss_percent: f64,
+    /// Fastest round trip.
+    pub min_ms: Option<f64>,
+    /// Mean round trip.
+    pub avg_ms: Option<f64>,
+    /// Slowest round trip.
+    pub max_ms: Option<f64>,
+    /// Mean absolute difference between consecutive round trips.
+    pub jitter_ms: Option<f64>,
+}
+
+/// Default gateway identified from interface data.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayHealth {
+    /// Interface carrying the default route.
+    pub interface: String,
+    /// Gateway IP address.
+    pub ip: String,
+    /// Gateway MAC address, when known.
+    pub mac_address: Option<String>,
+    /// Round-trip statistics; `None` when no probed port answered.
+    pub stats: Option<ProbeStats>,
+}
+
+/// DNS resolution timing.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsHealth {
+    /// Name that was resolved.
+    pub name: String,
+    /// Resolver selector used.
+    pub resolver: String,
+    /// Resolution time, when the lookup completed.
+    pub elapsed_ms: Option<f64>,
+    /// Addresses returned.
+    pub addresses: Vec<String>,
+    /// Failure description.
+    pub error: Option<String>,
+}
+
+/// Where the diagnostic places the problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthVerdict {
+    /// Gateway, upstream, and DNS all look healthy.
+    Healthy,
+    /// No default gateway was found; the host is not on a routed network.
+    NoGateway,
+    /// The gateway is unreachable, lossy, or slow: a LAN / Wi-Fi problem.
+    LocalNetwork,
+    /// The gateway is fine but the upstream is not: an ISP / WAN problem.
+    Upstream,
+    /// Both paths are fine but name resolution fails or is slow.
+    Dns,
+}
+
+/// Aggregate health check result.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkHealthResult {
+    /// Default gateway and its statistics.
+    pub gateway: Option<GatewayHealth>,
+    /// Upstream round-trip statistics.
+    pub upstream: Option<ProbeStats>,
+    /// Upstream resolution or probe error.
+    pub upstream_error: Option<String>,
+    /// DNS resolution timing.
+    pub dns: DnsHealth,
+    /// Overall verdict.
+    pub verdict: HealthVerdict,
+    /// One-sentence human-readable explanation of the verdict.
+    pub summary: String,
+    /// Wall-clock time of the whole check.
+    pub elapsed_ms: u128,
+}
+
+/// Convert a duration to fractional milliseconds.
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// One TCP round trip. Returns the RTT when the peer answered, whether by
+/// accepting or refusing the connection.
+async fn tcp_rtt(addr: SocketAddr, limit: Duration) -> Option<f64> {
+    let started = Instant::now();
+    match timeout(limit, TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => Some(as_ms(started.elapsed())),
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => Some(as_ms(started.elapsed())),
+        _ => None,
+    }
+}
+
+/// Summarize a probe run. `None` entries are lost probes.
+fn summarize(target: String, samples: &[Option<f64>]) -> ProbeStats {
+    let rtts: Vec<f64> = samples.iter().flatten().copied().collect();
+    let sent = u32::try_from(samples.len()).unwrap_or(u32::MAX);
+    let received = u32::try_from(rtts.len()).unwrap_or(u32::MAX);
+    let loss_percent = if sent == 0 {
+        0.0
+    } else {
+        f64::from(sent - received) / f64::from(sent) * 100.0
+    };
+
+    let avg_ms = (received > 0).then(|| rtts.iter().sum::<f64>() / f64::from(received));
+    let jitter_ms = (received > 1).then(|| {
+        rtts.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>() / f64::from(received - 1)
+    });
+
+    ProbeStats {
+        target,
+        sent,
+        received,
+        loss_percent,
+        min_ms: rtts.iter().copied().reduce(f64::min),
+        avg_ms,
+        max_ms: rtts.iter().copied().reduce(f64::max),
+        jitter_ms,
+    }
+}
+
+/// Send `count` probes to `addr`, spaced by [`PROBE_INTERVAL`].
+async fn probe(addr: SocketAddr, count: u32, limit: Duration) -> ProbeStats {
+    let mut samples = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        if i > 0 {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+        samples.push(tcp_rtt(addr, limit).await);
+    }
+    summarize(addr.to_string(), &samples)
+}
+
+/// Find the default gateway and probe the first port it answers on.
+async fn check_gateway(count: u32, limit: Duration) -> Option<GatewayHealth> {
+    let interfaces = tokio::task::spawn_blocking(get_detailed_interfaces)
+        .await
+        .ok()?;
+    let (interface, gateway) = interfaces
+        .into_iter()
+        .filter(|iface| iface.state_flags.is_default)
+        .find_map(|iface| iface.gateway.map(|gw| (iface.name, gw)))?;
+    let ip: IpAddr = gateway
+        .ipv4_addresses
+        .iter()
+        .chain(&gateway.ipv6_addresses)
+        .find_map(|ip| ip.parse().ok())?;
+
+    let mut port = None;
+    for candidate in GATEWAY_PORTS {
+        if tcp_rtt(SocketAddr::new(ip, candidate), limit)
+            .await
+            .is_some()
+        {
+            port = Some(candidate);
+            break;
+        }
+    }
+    let stats = match port {
+        Some(port) => Some(probe(SocketAddr::new(ip, port), count, limit).await),
+        None => None,
+    };
+
+    Some(GatewayHealth {
+        interface,
+        ip: ip.to_string(),
+        mac_address: gateway.mac_address,
+        stats,
+    })
+}
+
+/// Resolve the upstream host and probe it.
+async fn check_upstream(
+    host: &str,
+    port: u16,
+    count: u32,
+    limit: Duration,
+) -> Result<ProbeStats, String> {
+    let addr = timeout(limit, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| format!("Resolving {host} timed out"))?
+        .map_err(|e| format!("Failed to resolve {host}: {e}"))?
+        .next()
+        .ok_or_else(|| format!("No addresses found for {host}"))?;
+    Ok(probe(addr, count, limit).await)
+}
+
+/// Time a resolution of `name` through the selected resolver.
+async fn check_dns(name: &str, resolver: &str, timeout_ms: u32) -> DnsHealth {
+    let mut health = DnsHealth {
+        name: name.to_string(),
+        resolver: resolver.to_string(),
+        elapsed_ms: None,
+        addresses: Vec::new(),
+        error: None,
+    };
+    let resolver_impl = match build_resolver(resolver, timeout_ms) {
+        Ok(r) => r,
+        Err(e) => {
+            health.error = Some(format!("Invalid resolver: {e}"));
+            return health;
+        }
+    };
+
+    let started = Instant::now();
+    match resolver_impl.lookup_ip(name).await {
+        Ok(lookup) => {
+            health.elapsed_ms = Some(as_ms(started.elapsed()));
+            health.addresses = lookup.iter().map(|ip| ip.to_string()).collect();
+        }
+        Err(e) => health.error = Some(format!("Resolution failed: {e}")),
+    }
+    health
+}
+
+/// Whether a path is lossy, slower than `slow_ms`, or never answered.
+fn is_unhealthy(stats: &ProbeStats, slow_ms: f64) -> bool {
+    stats.loss_percent > LOSS_THRESHOLD_PERCENT || stats.avg_ms.is_none_or(|avg| avg > slow_ms)
+}
+
+/// Localize the fault from the three measurements.
+fn diagnose(
+    gateway: Option<&GatewayHealth>,
+    upstream: Option<&ProbeStats>,
+    dns: &DnsHealth,
+) -> (HealthVerdict, String) {
+    let Some(gateway) = gateway else {
+        return (
+            HealthVerdict::NoGateway,
+            "No default gateway found; this host has no routed network connection.".to_string(),
+        );
+    };
+    match &gateway.stats {
+        None => {
+            return (
+                HealthVerdict::LocalNetwork,
+                format!("Gateway {} did not answer on any probed port.", gateway.ip),
+            )
+        }
+        Some(stats) if is_unhealthy(stats, GATEWAY_SLOW_MS) => {
+            return (
+                HealthVerdict::LocalNetwork,
+                format!(
+                    "Gateway {} shows {:.0}% loss; the problem is on the local network.",
+                    gateway.ip, stats.loss_percent
+                ),
+            )
+        }
+        Some(_) => {}
+    }
+    match upstream {
+        Some(stats) if !is_unhealthy(stats, UPSTREAM_SLOW_MS) => {}
+        Some(stats) => {
+            return (
+                HealthVerdict::Upstream,
+                format!(
+                    "Gateway is healthy but {} shows {:.0}% loss; the problem is upstream of the router.",
+                    stats.target, stats.loss_percent
+                ),
+            )
+        }
+        None => {
+            return (
+                HealthVerdict::Upstream,
+                "Gateway is healthy but the upstream host is unreachable.".to_string(),
+            )
+        }
+    }
+    match (dns.elapsed_ms, &dns.error) {
+        (_, Some(error)) => (
+            HealthVerdict::Dns,
+            format!("Connectivity is fine but DNS is failing: {error}"),
+        ),
+        (Some(ms), None) if ms > DNS_SLOW_MS => (
+            HealthVerdict::Dns,
+            format!("Connectivity is fine but DNS took {ms:.0} ms."),
+        ),
+        _ => (
+            HealthVerdict::Healthy,
+            "Gateway, upstream, and DNS all look healthy.".to_string(),
+        ),
+    }
+}
+
+/// Run the gateway, upstream, and DNS checks concurrently and diagnose.
+///
+/// # Errors
+///
+/// Returns an error string when the probe count is out of range.
+#[tauri::command]
+pub async fn network_health_check(
+    req: NetworkHealthRequest,
+) -> Result<NetworkHealthResult, String> {
+    if req.probe_count == 0 || req.probe_count > MAX_PROBES {
+        return Err(format!("Probe count must be between 1 and {MAX_PROBES}"));
+    }
+    let started = Instant::now();
+    let limit = Duration::from_millis(u64::from(req.timeout_ms));
+
+    let (gateway, upstream, dns) = tokio::join!(
+        check_gateway(req.probe_count, limit),
+        check_upstream(
+            &req.upstream_host,
+            req.upstream_port,
+            req.probe_count,
+            limit
+        ),
+        check_dns(&req.dns_name, &req.resolver, req.timeout_ms),
+    );
+    let (upstream, upstream_error) = match upstream {
+        Ok(stats) => (Some(stats), None),
+        Err(e) => (None, Some(e)),
+    };
+    let (verdict, summary) = diagnose(gateway.as_ref(), upstream.as_ref(), &dns);
+
+    Ok(NetworkHealthResult {
+        gateway,
+        upstream,
+        upstream_error,
+        dns,
+        verdict,
+        summary,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(samples: &[Option<f64>]) -> ProbeStats {
+        summarize("192.0.2.1:53".to_string(), samples)
+    }
+
+    fn gateway(samples: &[Option<f64>]) -> GatewayHealth {
+        GatewayHealth {
+            interface: "en0".to_string(),
+            ip: "192.0.2.1".to_string(),
+            mac_address: None,
+            stats: Some(stats(samples)),
+        }
+    }
+
+    fn dns(elapsed_ms: Option<f64>, error: Option<&str>) -> DnsHealth {
+        DnsHealth {
+            name: "example.com".to_string(),
+            resolver: "system".to_string(),
+            elapsed_ms,
+            addresses: Vec::new(),
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn summarize_computes_loss_and_jitter() {
+        let s = stats(&[Some(10.0), None, Some(14.0), Some(12.0)]);
+        assert_eq!((s.sent, s.received), (4, 3));
+        assert!((s.loss_percent - 25.0).abs() < f64::EPSILON);
+        assert_eq!(s.min_ms, Some(10.0));
+        assert_eq!(s.max_ms, Some(14.0));
+        assert_eq!(s.avg_ms, Some(12.0));
+        assert_eq!(s.jitter_ms, Some(3.0));
+    }
+
+    #[test]
+    fn summarize_handles_total_loss() {
+        let s = stats(&[None, None]);
+        assert!((s.loss_percent - 100.0).abs() < f64::EPSILON);
+        assert_eq!(s.avg_ms, None);
+        assert_eq!(s.jitter_ms, None);
+    }
+
+    #[test]
+    fn diagnose_blames_lan_for_lossy_gateway() {
+        let gw = gateway(&[Some(2.0), None, None]);
+        let (verdict, _) = diagnose(Some(&gw), None, &dns(Some(5.0), None));
+        assert_eq!(verdict, HealthVerdict::LocalNetwork);
+    }
+
+    #[test]
+    fn diagnose_blames_upstream_when_gateway_is_healthy() {
+        let gw = gateway(&[Some(2.0), Some(3.0)]);
+        let up = stats(&[None, None]);
+        let (verdict, _) = diagnose(Some(&gw), Some(&up), &dns(Some(5.0), None));
+        assert_eq!(verdict, HealthVerdict::Upstream);
+    }
+
+    #[test]
+    fn diagnose_reports_dns_and_healthy() {
+        let gw = gateway(&[Some(2.0)]);
+        let up = stats(&[Some(20.0)]);
+        assert_eq!(
+            diagnose(Some(&gw), Some(&up), &dns(None, Some("NXDOMAIN"))).0,
+            HealthVerdict::Dns
+        );
+        assert_eq!(
+            diagnose(Some(&gw), Some(&up), &dns(Some(900.0), None)).0,
+            HealthVerdict::Dns
+        );
+        assert_eq!(
+            diagnose(Some(&gw), Some(&up), &dns(Some(15.0), None)).0,
+            HealthVerdict::Healthy
+        );
+        assert_eq!(
+            diagnose(None, Some(&up), &dns(Some(15.0), None)).0,
+            HealthVerdict::NoGateway
+        );
+    }
+}
@@ -0,0 +1,525 @@
+//! QR code generation and decoding Tauri commands.
+//!
+//! Renders text payloads such as `otpauth://` URIs, `WireGuard` configs,
+//! Wi-Fi `WIFI:` strings, and public keys as SVG markup or PNG images on
+//! the Rust side, so large payloads and exports do not depend on the
+//! webview's canvas. The frontend QR tool's styling (dot shapes, logos)
+//! stays in `qr-code-styling`; this renders plain, scanner-friendly
+//! square modules.
+//!
+//! `decode_qr` goes the other way: it finds QR, Data Matrix, and Code 128
+//! symbols in a dropped image file, image bytes, or the clipboard bitmap.
+
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use image::{ImageFormat, Luma, RgbaImage};
+use qrcode::render::{svg, Renderer};
+use qrcode::types::QrError;
+use qrcode::{EcLevel, QrCode};
+use rxing::{BarcodeFormat, DecodeHints, Exceptions};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Default image size in pixels.
+pub const DEFAULT_SIZE: u32 = 256;
+/// Largest image size in pixels.
+pub const MAX_SIZE: u32 = 4096;
+/// Default quiet zone in modules, the minimum the QR specification asks for.
+pub const DEFAULT_QUIET_ZONE: u32 = 4;
+/// Largest quiet zone in modules.
+pub const MAX_QUIET_ZONE: u32 = 16;
+/// Largest image accepted for decoding, in pixels.
+pub const MAX_DECODE_PIXELS: u64 = 40_000_000;
+
+/// Image format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+/// Error correction level: the share of the symbol that can be damaged
+/// and still read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum QrErrorCorrection {
+    /// About 7%.
+    L,
+    /// About 15%.
+    #[default]
+    M,
+    /// About 25%.
+    Q,
+    /// About 30%.
+    H,
+}
+
+impl From<QrErrorCorrection> for EcLevel {
+    fn from(level: QrErrorCorrection) -> Self {
+        match level {
+            QrErrorCorrection::L => Self::L,
+            QrErrorCorrection::M => Self::M,
+            QrErrorCorrection::Q => Self::Q,
+            QrErrorCorrection::H => Self::H,
+        }
+    }
+}
+
+/// Rendering options; every field has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QrOptions {
+    /// SVG or PNG; SVG when omitted.
+    pub format: Option<QrFormat>,
+    /// Error correction level; M when omitted.
+    pub error_correction: Option<QrErrorCorrection>,
+    /// Largest width and height in pixels; the image is the biggest
+    /// whole number of pixels per module that fits, so it may be smaller.
+    pub size: Option<u32>,
+    /// Light border around the symbol, in modules.
+    pub quiet_zone: Option<u32>,
+}
+
+/// A rendered QR code.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QrImage {
+    pub format: QrFormat,
+    /// SVG markup, or base64 PNG bytes.
+    pub data: String,
+    /// `image/svg+xml` or `image/png`.
+    pub mime_type: &'static str,
+    /// Image width and height in pixels.
+    pub size: u32,
+    /// QR version, 1 to 40; the symbol is `17 + 4 × version` modules wide.
+    pub version: i16,
+    /// Symbol width in modules, without the quiet zone.
+    pub modules: usize,
+}
+
+/// Render `payload` as a QR code.
+///
+/// # Errors
+///
+/// Returns a `String` for an empty payload, one too long for a QR code
+/// at the chosen error correction level, or a size or quiet zone out of
+/// range.
+#[tauri::command]
+pub async fn generate_qr(payload: String, options: Option<QrOptions>) -> Result<QrImage, String> {
+    tokio::task::spawn_blocking(move || generate(&payload, &options.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("QR worker join failed: {e}"))?
+}
+
+fn generate(payload: &str, options: &QrOptions) -> Result<QrImage, String> {
+    if payload.is_empty() {
+        return Err("Payload is empty".to_string());
+    }
+    let size = options.size.unwrap_or(DEFAULT_SIZE);
+    if !(16..=MAX_SIZE).contains(&size) {
+        return Err(format!("Size must be between 16 and {MAX_SIZE} pixels"));
+    }
+    let quiet_zone = options.quiet_zone.unwrap_or(DEFAULT_QUIET_ZONE);
+    if quiet_zone > MAX_QUIET_ZONE {
+        return Err(format!(
+            "Quiet zone must be at most {MAX_QUIET_ZONE} modules"
+        ));
+    }
+    let level = options.error_correction.unwrap_or_default();
+    let code = QrCode::with_error_correction_level(payload, level.into()).map_err(|e| match e {
+        QrError::DataTooLong => format!(
+            "Payload of {} bytes is too long for a QR code at error correction {level:?}",
+            payload.len()
+        ),
+        e => e.to_string(),
+    })?;
+
+    let colors = code.to_colors();
+    let modules = code.width();
+    let format = options.format.unwrap_or_default();
+    let (data, mime_type, size) = match format {
+        QrFormat::Svg => {
+            let svg = Renderer::<svg::Color<'_>>::new(&colors, modules, quiet_zone)
+                .max_dimensions(size, size)
+                .build();
+            let rendered = rendered_size(modules, quiet_zone, size);
+            (svg, "image/svg+xml", rendered)
+        }
+        QrFormat::Png => {
+            let image = Renderer::<Luma<u8>>::new(&colors, modules, quiet_zone)
+                .max_dimensions(size, size)
+                .build();
+            let mut png = Cursor::new(Vec::new());
+            image
+                .write_to(&mut png, ImageFormat::Png)
+                .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+            (
+                STANDARD.encode(png.into_inner()),
+                "image/png",
+                image.width(),
+            )
+        }
+    };
+
+    let version = match code.version() {
+        qrcode::Version::Normal(version) | qrcode::Version::Micro(version) => version,
+    };
+    Ok(QrImage {
+        format,
+        data,
+        mime_type,
+        size,
+        version,
+        modules,
+    })
+}
+
+/// Pixel size the renderer picks for `max_dimensions(size, size)`: whole
+/// pixels per module, at least one
+fn rendered_size(modules: usize, quiet_zone: u32, size: u32) -> u32 {
+    let total = u32::try_from(modules).unwrap_or(u32::MAX) + 2 * quiet_zone;
+    (size / total).max(1) * total
+}
+
+/// Where `decode_qr` reads the image from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ImageSource {
+    /// An image file, such as one dropped onto the window.
+    Path { path: String },
+    /// Base64 bytes of a PNG, JPEG, GIF, BMP, or WebP file.
+    Bytes { data: String },
+    /// The bitmap on the system clipboard.
+    Clipboard,
+}
+
+/// Barcode symbology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Symbology {
+    QrCode,
+    DataMatrix,
+    Code128,
+}
+
+impl Symbology {
+    const ALL: [Self; 3] = [Self::QrCode, Self::DataMatrix, Self::Code128];
+
+    const fn format(self) -> BarcodeFormat {
+        match self {
+            Self::QrCode => BarcodeFormat::QR_CODE,
+            Self::DataMatrix => BarcodeFormat::DATA_MATRIX,
+            Self::Code128 => BarcodeFormat::CODE_128,
+        }
+    }
+
+    fn from_format(format: BarcodeFormat) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|symbology| symbology.format() == format)
+    }
+}
+
+/// A point in image pixels, from the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Where a symbol sits in the image.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePosition {
+    /// Points the detector located: the finder patterns of a QR code,
+    /// the corners of a Data Matrix, or the ends of the scan line
+    /// across a Code 128 bar code.
+    pub points: Vec<Point>,
+    /// Bounding box of `points`.
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A symbol found in an image.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedCode {
+    pub payload: String,
+    pub symbology: Symbology,
+    pub position: CodePosition,
+}
+
+/// Find and decode the QR, Data Matrix, and Code 128 symbols in an image.
+///
+/// Returns an empty list when the image holds no readable symbol.
+///
+/// # Errors
+///
+/// Returns a `String` when the image cannot be read or decoded, is too
+/// large, or the clipboard holds no image.
+#[tauri::command]
+pub async fn decode_qr(image: ImageSource, app: AppHandle) -> Result<Vec<DecodedCode>, String> {
+    // The clipboard is read here rather than on the blocking thread
+    let clipboard = match image {
+        ImageSource::Clipboard => Some(clipboard_image(&app)?),
+        ImageSource::Path { .. } | ImageSource::Bytes { .. } => None,
+    };
+    tokio::task::spawn_blocking(move || {
+        let rgba = match clipboard {
+            Some(rgba) => rgba,
+            None => load(&image)?,
+        };
+        decode(&rgba)
+    })
+    .await
+    .map_err(|e| format!("QR worker join failed: {e}"))?
+}
+
+fn clipboard_image(app: &AppHandle) -> Result<RgbaImage, String> {
+    let bitmap = app
+        .clipboard()
+        .read_image()
+        .map_err(|_| "Clipboard does not hold an image".to_string())?;
+    RgbaImage::from_raw(bitmap.width(), bitmap.height(), bitmap.rgba().to_vec())
+        .ok_or_else(|| "Clipboard image is malformed".to_string())
+}
+
+fn load(source: &ImageSource) -> Result<RgbaImage, String> {
+    let bytes = match source {
+        ImageSource::Path { path } => {
+            std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?
+        }
+        ImageSource::Bytes { data } => STANDARD
+            .decode(data.trim())
+            .map_err(|e| format!("Invalid base64 image data: {e}"))?,
+        ImageSource::Clipboard => unreachable!("clipboard bitmaps are read by `decode_qr`"),
+    };
+    let reader = || {
+        image::ImageReader::new(Cursor::new(bytes.as_slice()))
+            .with_guessed_format()
+            .map_err(|e| format!("Failed to read image: {e}"))
+    };
+    // Check the header before allocating the pixels
+    let (width, height) = reader()?
+        .into_dimensions()
+        .map_err(|e| format!("Unsupported image: {e}"))?;
+    check_dimensions(width, height)?;
+    Ok(reader()?
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {e}"))?
+        .into_rgba8())
+}
+
+fn check_dimensions(width: u32, height: u32) -> Result<(), String> {
+    if u64::from(width) * u64::from(height) > MAX_DECODE_PIXELS {
+        return Err(format!(
+            "Image of {width}×{height} pixels is too large to scan"
+        ));
+    }
+    Ok(())
+}
+
+fn decode(rgba: &RgbaImage) -> Result<Vec<DecodedCode>, String> {
+    check_dimensions(rgba.width(), rgba.height())?;
+    let mut hints = DecodeHints {
+        PossibleFormats: Some(
+            Symbology::ALL
+                .map(Symbology::format)
+                .into_iter()
+                .collect::<HashSet<_>>(),
+        ),
+        TryHarder: Some(true),
+        ..DecodeHints::default()
+    };
+    let results = match rxing::helpers::detect_multiple_in_luma_with_hints(
+        luma(rgba),
+        rgba.width(),
+        rgba.height(),
+        &mut hints,
+    ) {
+        Ok(results) => results,
+        Err(Exceptions::NotFoundException(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to scan image: {e}")),
+    };
+    Ok(results
+        .iter()
+        .filter_map(|result| {
+            Some(DecodedCode {
+                payload: result.getText().to_string(),
+                symbology: Symbology::from_format(*result.getBarcodeFormat())?,
+                position: position(result.getPoints()),
+            })
+        })
+        .collect())
+}
+
+/// Grayscale pixels with transparency flattened onto white, so a dark
+/// symbol on a transparent background still reads as dark on light
+fn luma(rgba: &RgbaImage) -> Vec<u8> {
+    rgba.pixels()
+        .map(|pixel| {
+            let [r, g, b, a] = pixel.0.map(u32::from);
+            // ITU-R BT.601 weights, scaled by 1000
+            let gray = (299 * r + 587 * g + 114 * b) / 1000;
+            let flattened = (gray * a + 255 * (255 - a)) / 255;
+            u8::try_from(flattened).unwrap_or(u8::MAX)
+        })
+        .collect()
+}
+
+fn position(points: &[rxing::Point]) -> CodePosition {
+    let points: Vec<Point> = points.iter().map(|p| Point { x: p.x, y: p.y }).collect();
+    let (mut left, mut top) = (f32::INFINITY, f32::INFINITY);
+    let (mut right, mut bottom) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for point in &points {
+        left = left.min(point.x);
+        top = top.min(point.y);
+        right = right.max(point.x);
+        bottom = bottom.max(point.y);
+    }
+    if points.is_empty() {
+        (left, top, right, bottom) = (0.0, 0.0, 0.0, 0.0);
+    }
+    CodePosition {
+        points,
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let qr = generate(
+            "otpauth://totp/Kogu:alice?secret=JBSWY3DPEHPK3PXP&issuer=Kogu",
+            &QrOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(qr.mime_type, "image/svg+xml");
+        assert!(qr.data.contains("<svg"));
+        assert_eq!(qr.modules, 17 + 4 * usize::try_from(qr.version).unwrap());
+        let total = u32::try_from(qr.modules).unwrap() + 2 * DEFAULT_QUIET_ZONE;
+        assert_eq!(qr.size % total, 0);
+        assert!(qr.size <= DEFAULT_SIZE);
+        assert!(qr
+            .data
+            .contains(&format!(r#"width="{0}" height="{0}""#, qr.size)));
+    }
+
+    #[test]
+    fn test_png_and_options() {
+        let options = QrOptions {
+            format: Some(QrFormat::Png),
+            error_correction: Some(QrErrorCorrection::H),
+            size: Some(300),
+            quiet_zone: Some(2),
+        };
+        let qr = generate("WIFI:T:WPA;S:home;P:secret;;", &options).unwrap();
+        let png = STANDARD.decode(&qr.data).unwrap();
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert_eq!(image.width(), qr.size);
+        assert_eq!(qr.size % (u32::try_from(qr.modules).unwrap() + 4), 0);
+
+        // A higher level needs a larger symbol for the same payload
+        let low = QrOptions {
+            error_correction: Some(QrErrorCorrection::L),
+            ..options
+        };
+        assert!(
+            generate("WIFI:T:WPA;S:home;P:secret;;", &low)
+                .unwrap()
+                .version
+                < qr.version
+        );
+
+        assert!(generate("", &QrOptions::default()).is_err());
+        assert!(generate(&"x".repeat(3000), &options).is_err());
+        let oversized = QrOptions {
+            size: Some(MAX_SIZE + 1),
+            ..QrOptions::default()
+        };
+        assert!(generate("x", &oversized).is_err());
+    }
+
+    #[test]
+    fn test_decode_generated() {
+        let payload = "otpauth://totp/Kogu:alice?secret=JBSWY3DPEHPK3PXP&issuer=Kogu";
+        let options = QrOptions {
+            format: Some(QrFormat::Png),
+            ..QrOptions::default()
+        };
+        let qr = generate(payload, &options).unwrap();
+        let source = ImageSource::Bytes { data: qr.data };
+        let codes = decode(&load(&source).unwrap()).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].payload, payload);
+        assert_eq!(codes[0].symbology, Symbology::QrCode);
+        let position = &codes[0].position;
+        assert!(position.points.len() >= 3);
+        #[allow(clippy::cast_precision_loss)]
+        let size = qr.size as f32;
+        assert!(position.x > 0.0 && position.x + position.width < size);
+        assert!(position.y > 0.0 && position.y + position.height < size);
+    }
+
+    #[test]
+    fn test_decode_transparent_and_blank() {
+        // Dark modules on a transparent background, as canvas exports have
+        let code = QrCode::new("WIFI:T:WPA;S:home;P:secret;;").unwrap();
+        let gray = Renderer::<Luma<u8>>::new(&code.to_colors(), code.width(), 4)
+            .module_dimensions(6, 6)
+            .build();
+        let rgba = RgbaImage::from_fn(gray.width(), gray.height(), |x, y| {
+            let dark = gray.get_pixel(x, y).0[0] < 128;
+            image::Rgba([0, 0, 0, if dark { 255 } else { 0 }])
+        });
+        let codes = decode(&rgba).unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].payload, "WIFI:T:WPA;S:home;P:secret;;");
+
+        let blank = RgbaImage::from_pixel(64, 64, image::Rgba([255; 4]));
+        assert!(decode(&blank).unwrap().is_empty());
+        assert!(load(&ImageSource::Bytes {
+            data: STANDARD.encode(b"not an image")
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_decode_symbologies() {
+        use rxing::Writer as _;
+
+        for (symbology, payload, width, height) in [
+            (Symbology::DataMatrix, "SN 0042-7731", 160, 160),
+            (Symbology::Code128, "KOGU-128", 320, 80),
+        ] {
+            let matrix = rxing::MultiFormatWriter
+                .encode(payload, &symbology.format(), width, height)
+                .unwrap();
+            // A light margin around the symbol, as on a label
+            let rgba =
+                RgbaImage::from_fn(matrix.getWidth() + 40, matrix.getHeight() + 40, |x, y| {
+                    let dark = (20..matrix.getWidth() + 20).contains(&x)
+                        && (20..matrix.getHeight() + 20).contains(&y)
+                        && matrix.get(x - 20, y - 20);
+                    image::Rgba(if dark { [0, 0, 0, 255] } else { [255; 4] })
+                });
+            let codes = decode(&rgba).unwrap();
+            assert_eq!(codes.len(), 1, "{symbology:?}");
+            assert_eq!(codes[0].payload, payload);
+            assert_eq!(codes[0].symbology, symbology);
+        }
+    }
+}
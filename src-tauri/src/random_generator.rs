@@ -0,0 +1,305 @@
+//! Secure random bytes and test-data secret generation Tauri commands.
+//!
+//! Companion to [`crate::id_generator`] for raw randomness: tokens, salts,
+//! and keys as hex, base64, base58, or random (version 4) UUIDs, in place
+//! of `openssl rand -hex 32` and friends. API keys follow GitHub's token
+//! layout: a prefix, a base62 random body, and a six-character base62
+//! CRC-32 of the body, so a secret scanner can tell a real key from a
+//! look-alike without a lookup.
+//!
+//! Bytes come from `ring`'s `SystemRandom`, the operating system's
+//! generator.
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+/// Most values generated in one call.
+pub const MAX_COUNT: usize = 10_000;
+/// Most random bytes in one value.
+pub const MAX_BYTES: usize = 1024;
+
+const BASE58: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// Base62 digits of an API key checksum; 62^6 exceeds 2^32.
+const CHECKSUM_LENGTH: usize = 6;
+const API_KEY_DEFAULT_PREFIX: &str = "key_";
+const API_KEY_MAX_PREFIX: usize = 32;
+
+/// Output encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RandomFormat {
+    Hex,
+    Base64,
+    /// URL-safe base64 without padding.
+    Base64url,
+    /// Bitcoin alphabet.
+    Base58,
+    /// Random UUID; always 16 bytes, 122 of them random.
+    Uuid,
+    /// Prefix, base62 body, and base62 CRC-32 checksum.
+    Apikey,
+}
+
+/// Format-specific options; each format reads only its own fields.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomOptions {
+    /// Upper-case hex digits.
+    pub uppercase: Option<bool>,
+    /// API key prefix, e.g. `ghp_`; `key_` when omitted.
+    pub prefix: Option<String>,
+}
+
+/// Generate `count` random values of `bytes` bytes each.
+///
+/// # Arguments
+///
+/// - `bytes` — random bytes per value, 1 to [`MAX_BYTES`]; ignored for
+///   `uuid`. An API key body has at least this much entropy.
+/// - `format` — `hex`, `base64`, `base64url`, `base58`, `uuid`, or
+///   `apikey`.
+/// - `count` — number of values, 1 to [`MAX_COUNT`].
+/// - `options` — hex case and API key prefix.
+///
+/// # Errors
+///
+/// Returns a `String` for a size, count, or prefix out of range, or a
+/// failed random source.
+#[tauri::command]
+pub async fn generate_random(
+    bytes: usize,
+    format: RandomFormat,
+    count: usize,
+    options: Option<RandomOptions>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        generate(bytes, format, count, &options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("random worker join failed: {e}"))?
+}
+
+fn generate(
+    bytes: usize,
+    format: RandomFormat,
+    count: usize,
+    options: &RandomOptions,
+) -> Result<Vec<String>, String> {
+    if !(1..=MAX_COUNT).contains(&count) {
+        return Err(format!("Count must be between 1 and {MAX_COUNT}"));
+    }
+    if format != RandomFormat::Uuid && !(1..=MAX_BYTES).contains(&bytes) {
+        return Err(format!("Size must be between 1 and {MAX_BYTES} bytes"));
+    }
+    let rng = SystemRandom::new();
+    match format {
+        RandomFormat::Hex => {
+            let uppercase = options.uppercase.unwrap_or(false);
+            (0..count)
+                .map(|_| {
+                    let value = random_bytes(&rng, bytes)?;
+                    Ok(if uppercase {
+                        hex::encode_upper(value)
+                    } else {
+                        hex::encode(value)
+                    })
+                })
+                .collect()
+        }
+        RandomFormat::Base64 => (0..count)
+            .map(|_| random_bytes(&rng, bytes).map(|value| STANDARD.encode(value)))
+            .collect(),
+        RandomFormat::Base64url => (0..count)
+            .map(|_| random_bytes(&rng, bytes).map(|value| URL_SAFE_NO_PAD.encode(value)))
+            .collect(),
+        RandomFormat::Base58 => (0..count)
+            .map(|_| random_bytes(&rng, bytes).map(|value| encode_base58(&value)))
+            .collect(),
+        RandomFormat::Uuid => (0..count)
+            .map(|_| {
+                let value = random_bytes(&rng, 16)?;
+                let mut uuid = [0u8; 16];
+                uuid.copy_from_slice(&value);
+                Ok(uuid::Builder::from_random_bytes(uuid)
+                    .into_uuid()
+                    .to_string())
+            })
+            .collect(),
+        RandomFormat::Apikey => {
+            let prefix = options.prefix.as_deref().unwrap_or(API_KEY_DEFAULT_PREFIX);
+            validate_prefix(prefix)?;
+            let length = base62_length(bytes);
+            (0..count).map(|_| api_key(&rng, prefix, length)).collect()
+        }
+    }
+}
+
+fn validate_prefix(prefix: &str) -> Result<(), String> {
+    if prefix.len() > API_KEY_MAX_PREFIX {
+        return Err(format!(
+            "Prefix must be at most {API_KEY_MAX_PREFIX} characters"
+        ));
+    }
+    if !prefix
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err("Prefix may contain only letters, digits, '_', and '-'".to_string());
+    }
+    Ok(())
+}
+
+/// Base62 digits that carry at least `bytes` bytes of entropy; each digit
+/// holds log2(62) ≈ 5.954 bits, rounded down to 5.95 here.
+const fn base62_length(bytes: usize) -> usize {
+    (bytes * 800).div_ceil(595)
+}
+
+/// API key with a uniformly random base62 body of `length` digits
+fn api_key(rng: &SystemRandom, prefix: &str, length: usize) -> Result<String, String> {
+    let mut body = String::with_capacity(length);
+    while body.len() < length {
+        // Bytes are masked to six bits and the two values past the
+        // alphabet rejected, so every digit is equally likely.
+        for byte in random_bytes(rng, length)? {
+            if let Some(&digit) = BASE62.get(usize::from(byte & 0x3F)) {
+                body.push(char::from(digit));
+                if body.len() == length {
+                    break;
+                }
+            }
+        }
+    }
+    let checksum = checksum(&body);
+    Ok(format!("{prefix}{body}{checksum}"))
+}
+
+/// Six base62 digits of the CRC-32 of an API key body
+fn checksum(body: &str) -> String {
+    let mut value = crc32(body.as_bytes());
+    let mut digits = [b'0'; CHECKSUM_LENGTH];
+    for digit in digits.iter_mut().rev() {
+        *digit = BASE62[(value % 62) as usize];
+        value /= 62;
+    }
+    digits.iter().map(|&b| char::from(b)).collect()
+}
+
+/// CRC-32/ISO-HDLC, the checksum of zip and Ethernet
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ 0xEDB8_8320
+            };
+        }
+    }
+    !crc
+}
+
+/// Base58 of a big-endian number, with a `1` for each leading zero byte
+fn encode_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut number = bytes[zeros..].to_vec();
+    let mut digits = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in &mut number {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = u8::try_from(value / 58).unwrap_or_default();
+            remainder = value % 58;
+        }
+        digits.push(BASE58[remainder as usize]);
+    }
+    digits.resize(digits.len() + zeros, b'1');
+    digits.iter().rev().map(|&b| char::from(b)).collect()
+}
+
+fn random_bytes(rng: &SystemRandom, len: usize) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![0; len];
+    rng.fill(&mut bytes)
+        .map_err(|_| "Failed to read random bytes".to_string())?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodings() {
+        let options = RandomOptions::default();
+        let hex = generate(32, RandomFormat::Hex, 3, &options).unwrap();
+        assert!(hex.iter().all(|value| value.len() == 64
+            && value
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase())));
+        assert_ne!(hex[0], hex[1]);
+        let upper = RandomOptions {
+            uppercase: Some(true),
+            ..RandomOptions::default()
+        };
+        assert!(generate(4, RandomFormat::Hex, 1, &upper).unwrap()[0]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase()));
+
+        assert_eq!(
+            generate(32, RandomFormat::Base64, 1, &options).unwrap()[0].len(),
+            44
+        );
+        assert_eq!(
+            generate(32, RandomFormat::Base64url, 1, &options).unwrap()[0].len(),
+            43
+        );
+
+        let uuid = &generate(0, RandomFormat::Uuid, 1, &options).unwrap()[0];
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!("89ab".contains(&uuid[19..20]));
+
+        assert!(generate(0, RandomFormat::Hex, 1, &options).is_err());
+        assert!(generate(16, RandomFormat::Hex, MAX_COUNT + 1, &options).is_err());
+    }
+
+    #[test]
+    fn test_base58_vectors() {
+        assert_eq!(encode_base58(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode_base58(&[0, 0, 0x28, 0x7F, 0xB4, 0xCD]), "11233QC4");
+        assert_eq!(encode_base58(&[0, 0]), "11");
+        assert_eq!(encode_base58(&[]), "");
+    }
+
+    #[test]
+    fn test_api_key() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(base62_length(22), 30);
+
+        let options = RandomOptions {
+            prefix: Some("ghp_".to_string()),
+            ..RandomOptions::default()
+        };
+        let keys = generate(22, RandomFormat::Apikey, 50, &options).unwrap();
+        for key in &keys {
+            assert_eq!(key.len(), 4 + 30 + CHECKSUM_LENGTH);
+            let body = &key[4..34];
+            assert!(key.starts_with("ghp_"));
+            assert!(body.bytes().all(|b| b.is_ascii_alphanumeric()));
+            assert_eq!(&key[34..], checksum(body));
+        }
+
+        let default = &generate(16, RandomFormat::Apikey, 1, &RandomOptions::default()).unwrap()[0];
+        assert!(default.starts_with(API_KEY_DEFAULT_PREFIX));
+        let invalid = RandomOptions {
+            prefix: Some("bad prefix".to_string()),
+            ..RandomOptions::default()
+        };
+        assert!(generate(16, RandomFormat::Apikey, 1, &invalid).is_err());
+    }
+}
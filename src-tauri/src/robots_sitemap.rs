@@ -0,0 +1,645 @@
+//! robots.txt and sitemap fetcher commands.
+//!
+//! Fetches a site's `robots.txt`, parses it into per-agent groups with
+//! their allow/disallow rules and crawl delays, and answers whether a given
+//! path may be crawled by a given agent. Matching follows RFC 9309: the
+//! group with the most specific user-agent applies, the longest matching
+//! rule wins, and `Allow` wins a tie.
+//!
+//! Sitemaps (`urlset`) and sitemap indexes (`sitemapindex`) are parsed for
+//! URL counts and the spread of their `lastmod` dates; an index can be
+//! followed one level to summarize every child sitemap.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Largest robots.txt body read; RFC 9309 requires parsing at least 500 KiB
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+
+/// Largest sitemap body read; the sitemap protocol caps files at 50 MiB
+const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+
+/// URLs returned verbatim from a sitemap; the rest are only counted
+const MAX_SAMPLE_URLS: usize = 1000;
+
+/// Child sitemaps followed from an index
+const MAX_INDEX_CHILDREN: usize = 50;
+
+/// Request payload for fetching robots.txt.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsFetchRequest {
+    /// Site URL; only the scheme, host, and port are used.
+    pub url: String,
+    /// Request timeout in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// A single allow or disallow rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsRule {
+    /// `true` for `Allow`, `false` for `Disallow`.
+    pub allow: bool,
+    /// Path pattern, possibly with `*` wildcards and a trailing `$`.
+    pub pattern: String,
+    /// 1-based source line.
+    pub line: usize,
+}
+
+/// Rules shared by one or more user agents.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsGroup {
+    /// User-agent tokens the group applies to.
+    pub user_agents: Vec<String>,
+    /// Allow and disallow rules in file order.
+    pub rules: Vec<RobotsRule>,
+    /// `Crawl-delay` in seconds, when set.
+    pub crawl_delay: Option<f64>,
+}
+
+/// A line the parser did not understand.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsWarning {
+    /// 1-based source line.
+    pub line: usize,
+    /// Description of the problem.
+    pub message: String,
+}
+
+/// Parsed robots.txt.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedRobots {
+    /// Agent groups in file order.
+    pub groups: Vec<RobotsGroup>,
+    /// `Sitemap` URLs declared anywhere in the file.
+    pub sitemaps: Vec<String>,
+    /// Unrecognized or misplaced lines.
+    pub warnings: Vec<RobotsWarning>,
+}
+
+/// Result of fetching robots.txt.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsReport {
+    /// URL that was fetched.
+    pub url: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// Raw file content, for later path checks.
+    pub content: String,
+    /// Parsed structure.
+    pub parsed: ParsedRobots,
+}
+
+/// Request payload for checking a path against robots.txt content.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsCheckRequest {
+    /// robots.txt content.
+    pub content: String,
+    /// Crawler user-agent (product token or full header value).
+    pub user_agent: String,
+    /// URL path, optionally with a query string.
+    pub path: String,
+}
+
+/// Whether a path may be crawled.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsVerdict {
+    /// Whether crawling is allowed.
+    pub allowed: bool,
+    /// User-agent tokens of the group that applied; empty when none did.
+    pub group_agents: Vec<String>,
+    /// Rule that decided the verdict; `None` means allowed by default.
+    pub matched_rule: Option<RobotsRule>,
+    /// Crawl delay of the applied group.
+    pub crawl_delay: Option<f64>,
+}
+
+/// Request payload for fetching a sitemap.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SitemapFetchRequest {
+    /// Sitemap or sitemap index URL.
+    pub url: String,
+    /// Whether to fetch the child sitemaps of an index.
+    pub follow_index: bool,
+    /// Per-request timeout in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// Root element of a sitemap document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SitemapKind {
+    /// `<urlset>`: a list of page URLs.
+    UrlSet,
+    /// `<sitemapindex>`: a list of other sitemaps.
+    Index,
+}
+
+/// One `<url>` or `<sitemap>` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SitemapEntry {
+    /// `<loc>` value.
+    pub loc: String,
+    /// `<lastmod>` value, when present.
+    pub lastmod: Option<String>,
+}
+
+/// Distribution of `lastmod` dates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastmodSpread {
+    /// Entries carrying a `lastmod`.
+    pub with_lastmod: usize,
+    /// Earliest date (`YYYY-MM-DD`).
+    pub earliest: Option<String>,
+    /// Latest date (`YYYY-MM-DD`).
+    pub latest: Option<String>,
+    /// Entry count per year, ascending.
+    pub by_year: BTreeMap<String, usize>,
+}
+
+/// Summary of a child sitemap followed from an index.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SitemapChild {
+    /// Child sitemap URL.
+    pub loc: String,
+    /// URLs listed in the child.
+    pub url_count: usize,
+    /// Child `lastmod` distribution.
+    pub lastmod: LastmodSpread,
+    /// Fetch or parse failure.
+    pub error: Option<String>,
+}
+
+/// Result of fetching a sitemap.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SitemapReport {
+    /// URL that was fetched.
+    pub url: String,
+    /// Document kind.
+    pub kind: SitemapKind,
+    /// Entries in this document.
+    pub entry_count: usize,
+    /// First entries, up to 1000.
+    pub entries: Vec<SitemapEntry>,
+    /// `lastmod` distribution of this document's entries.
+    pub lastmod: LastmodSpread,
+    /// Followed children of an index (empty for a `urlset`).
+    pub children: Vec<SitemapChild>,
+    /// Total URLs across this document or all followed children.
+    pub total_urls: usize,
+}
+
+/// Split `field: value`, dropping comments and surrounding whitespace.
+fn split_directive(line: &str) -> Option<(String, &str)> {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    let (key, value) = line.split_once(':')?;
+    Some((key.trim().to_ascii_lowercase(), value.trim()))
+}
+
+/// Parse robots.txt content.
+pub(crate) fn parse_robots(content: &str) -> ParsedRobots {
+    let mut parsed = ParsedRobots::default();
+    let mut current: Option<RobotsGroup> = None;
+    // A user-agent line after rules starts a new group; consecutive ones share it.
+    let mut collecting_agents = false;
+
+    for (index, raw) in content.lines().enumerate() {
+        let line = index + 1;
+        if raw.split('#').next().unwrap_or_default().trim().is_empty() {
+            continue;
+        }
+        let Some((key, value)) = split_directive(raw) else {
+            parsed.warnings.push(RobotsWarning {
+                line,
+                message: "Line is not a `field: value` directive".to_string(),
+            });
+            continue;
+        };
+        match key.as_str() {
+            "user-agent" => {
+                if !collecting_agents {
+                    parsed.groups.extend(current.take());
+                    current = Some(RobotsGroup::default());
+                    collecting_agents = true;
+                }
+                if let Some(group) = current.as_mut() {
+                    group.user_agents.push(value.to_string());
+                }
+            }
+            "allow" | "disallow" => {
+                collecting_agents = false;
+                let Some(group) = current.as_mut() else {
+                    parsed.warnings.push(RobotsWarning {
+                        line,
+                        message: format!("`{key}` before any `user-agent`"),
+                    });
+                    continue;
+                };
+                // An empty Disallow allows everything and matches nothing.
+                if !value.is_empty() {
+                    group.rules.push(RobotsRule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                        line,
+                    });
+                }
+            }
+            "crawl-delay" => {
+                collecting_agents = false;
+                match (current.as_mut(), value.parse::<f64>()) {
+                    (Some(group), Ok(delay)) if delay >= 0.0 => group.crawl_delay = Some(delay),
+                    _ => parsed.warnings.push(RobotsWarning {
+                        line,
+                        message: format!("Invalid or misplaced crawl-delay: {value}"),
+                    }),
+                }
+            }
+            "sitemap" => parsed.sitemaps.push(value.to_string()),
+            other => parsed.warnings.push(RobotsWarning {
+                line,
+                message: format!("Unknown directive: {other}"),
+            }),
+        }
+    }
+    parsed.groups.extend(current);
+    parsed
+}
+
+/// Length of the match of `pattern` against `path`, or `None` when it does
+/// not match. `*` matches any sequence; a trailing `$` anchors the end.
+fn pattern_match_len(pattern: &str, path: &str) -> Option<usize> {
+    let (pattern, anchored) = pattern
+        .strip_suffix('$')
+        .map_or((pattern, false), |p| (p, true));
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut pos = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            if !path.starts_with(part) {
+                return None;
+            }
+            pos = part.len();
+        } else if i == parts.len() - 1 && anchored {
+            if path.len() < pos + part.len() || !path.ends_with(part) {
+                return None;
+            }
+            pos = path.len();
+        } else {
+            pos += path[pos..].find(part)? + part.len();
+        }
+    }
+    if anchored && pos != path.len() {
+        return None;
+    }
+    Some(pattern.len())
+}
+
+/// Pick the group for `user_agent`: the longest agent token contained in
+/// the product name, falling back to `*`.
+fn select_group<'a>(groups: &'a [RobotsGroup], user_agent: &str) -> Option<&'a RobotsGroup> {
+    let product = user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    groups
+        .iter()
+        .filter_map(|group| {
+            group
+                .user_agents
+                .iter()
+                .filter(|ua| *ua != "*" && product.contains(&ua.to_ascii_lowercase()))
+                .map(String::len)
+                .max()
+                .map(|len| (len, group))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, group)| group)
+        .or_else(|| {
+            groups
+                .iter()
+                .find(|group| group.user_agents.iter().any(|ua| ua == "*"))
+        })
+}
+
+/// Decide whether `path` may be crawled by `user_agent`.
+pub(crate) fn check_path(parsed: &ParsedRobots, user_agent: &str, path: &str) -> RobotsVerdict {
+    let Some(group) = select_group(&parsed.groups, user_agent) else {
+        return RobotsVerdict {
+            allowed: true,
+            group_agents: Vec::new(),
+            matched_rule: None,
+            crawl_delay: None,
+        };
+    };
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    };
+    let matched = group
+        .rules
+        .iter()
+        .filter_map(|rule| pattern_match_len(&rule.pattern, &path).map(|len| (len, rule)))
+        .max_by(|(a_len, a), (b_len, b)| a_len.cmp(b_len).then(a.allow.cmp(&b.allow)))
+        .map(|(_, rule)| rule.clone());
+    RobotsVerdict {
+        allowed: matched.as_ref().is_none_or(|rule| rule.allow),
+        group_agents: group.user_agents.clone(),
+        matched_rule: matched,
+        crawl_delay: group.crawl_delay,
+    }
+}
+
+/// Parse a sitemap or sitemap index document.
+fn parse_sitemap(xml: &str) -> Result<(SitemapKind, Vec<SitemapEntry>), String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("Invalid sitemap XML: {e}"))?;
+    let root = doc.root_element();
+    let (kind, item) = match root.tag_name().name() {
+        "urlset" => (SitemapKind::UrlSet, "url"),
+        "sitemapindex" => (SitemapKind::Index, "sitemap"),
+        other => return Err(format!("Unexpected sitemap root element: <{other}>")),
+    };
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|c| c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    let entries = root
+        .children()
+        .filter(|n| n.tag_name().name() == item)
+        .filter_map(|n| {
+            Some(SitemapEntry {
+                loc: child_text(n, "loc")?,
+                lastmod: child_text(n, "lastmod"),
+            })
+        })
+        .collect();
+    Ok((kind, entries))
+}
+
+/// Summarize `lastmod` values by their date part.
+fn lastmod_spread(entries: &[SitemapEntry]) -> LastmodSpread {
+    let mut spread = LastmodSpread::default();
+    for date in entries
+        .iter()
+        .filter_map(|e| e.lastmod.as_deref())
+        .filter_map(|lastmod| lastmod.get(..10).or_else(|| lastmod.get(..4)))
+    {
+        spread.with_lastmod += 1;
+        if spread.earliest.as_deref().is_none_or(|e| date < e) {
+            spread.earliest = Some(date.to_string());
+        }
+        if spread.latest.as_deref().is_none_or(|l| date > l) {
+            spread.latest = Some(date.to_string());
+        }
+        *spread.by_year.entry(date[..4].to_string()).or_default() += 1;
+    }
+    spread
+}
+
+fn build_client(timeout_ms: u32) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .timeout(Duration::from_millis(u64::from(timeout_ms)))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+/// GET `url`, reading at most `limit` bytes of body.
+async fn fetch_text(
+    client: &reqwest::Client,
+    url: &str,
+    limit: usize,
+) -> Result<(u16, String), String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    let status = response.status().as_u16();
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read response body: {e}"))?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(format!("Response exceeds {limit} bytes"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// Fetch and parse a site's robots.txt.
+///
+/// # Errors
+///
+/// Returns an error string for an invalid URL or a failed request. A
+/// non-2xx status is reported with empty content rather than as an error,
+/// since a missing robots.txt means everything is allowed.
+#[tauri::command]
+pub async fn robots_fetch(req: RobotsFetchRequest) -> Result<RobotsReport, String> {
+    let base = reqwest::Url::parse(&req.url).map_err(|e| format!("Invalid URL: {e}"))?;
+    let url = base
+        .join("/robots.txt")
+        .map_err(|e| format!("Invalid URL: {e}"))?
+        .to_string();
+    let client = build_client(req.timeout_ms)?;
+    let (status, body) = fetch_text(&client, &url, MAX_ROBOTS_BYTES).await?;
+    let content = if (200..300).contains(&status) {
+        body
+    } else {
+        String::new()
+    };
+    Ok(RobotsReport {
+        parsed: parse_robots(&content),
+        url,
+        status,
+        content,
+    })
+}
+
+/// Check whether a path may be crawled by a user agent.
+#[tauri::command]
+pub fn robots_check(req: RobotsCheckRequest) -> RobotsVerdict {
+    check_path(&parse_robots(&req.content), &req.user_agent, &req.path)
+}
+
+/// Fetch one sitemap document and parse it.
+async fn load_sitemap(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<(SitemapKind, Vec<SitemapEntry>), String> {
+    if std::path::Path::new(url)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+    {
+        return Err("Gzip-compressed sitemaps are not supported".to_string());
+    }
+    let (status, body) = fetch_text(client, url, MAX_SITEMAP_BYTES).await?;
+    if !(200..300).contains(&status) {
+        return Err(format!("HTTP {status}"));
+    }
+    parse_sitemap(&body)
+}
+
+/// Fetch a sitemap or sitemap index and summarize it.
+///
+/// # Errors
+///
+/// Returns an error string when the top-level document cannot be fetched
+/// or parsed. Failures of followed children are reported per child.
+#[tauri::command]
+pub async fn sitemap_fetch(req: SitemapFetchRequest) -> Result<SitemapReport, String> {
+    let client = build_client(req.timeout_ms)?;
+    let (kind, entries) = load_sitemap(&client, &req.url).await?;
+
+    let mut children = Vec::new();
+    if kind == SitemapKind::Index && req.follow_index {
+        let fetches = entries.iter().take(MAX_INDEX_CHILDREN).map(|entry| {
+            let client = &client;
+            async move {
+                let mut child = SitemapChild {
+                    loc: entry.loc.clone(),
+                    url_count: 0,
+                    lastmod: LastmodSpread::default(),
+                    error: None,
+                };
+                match load_sitemap(client, &entry.loc).await {
+                    Ok((SitemapKind::UrlSet, urls)) => {
+                        child.url_count = urls.len();
+                        child.lastmod = lastmod_spread(&urls);
+                    }
+                    Ok((SitemapKind::Index, _)) => {
+                        child.error = Some("Nested sitemap indexes are not followed".to_string());
+                    }
+                    Err(e) => child.error = Some(e),
+                }
+                child
+            }
+        });
+        children = futures::future::join_all(fetches).await;
+    }
+
+    let total_urls = match kind {
+        SitemapKind::UrlSet => entries.len(),
+        SitemapKind::Index => children.iter().map(|c| c.url_count).sum(),
+    };
+    Ok(SitemapReport {
+        url: req.url,
+        kind,
+        entry_count: entries.len(),
+        lastmod: lastmod_spread(&entries),
+        entries: entries.into_iter().take(MAX_SAMPLE_URLS).collect(),
+        children,
+        total_urls,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# example
+User-agent: Googlebot
+User-agent: Bingbot
+Disallow: /private/
+Allow: /private/public$
+Crawl-delay: 2
+
+User-agent: *
+Disallow: /
+Allow: /*.css$
+
+Sitemap: https://example.com/sitemap.xml
+Noindex: /x
+";
+
+    #[test]
+    fn parses_groups_sitemaps_and_warnings() {
+        let parsed = parse_robots(ROBOTS);
+        assert_eq!(parsed.groups.len(), 2);
+        assert_eq!(parsed.groups[0].user_agents, ["Googlebot", "Bingbot"]);
+        assert_eq!(parsed.groups[0].rules.len(), 2);
+        assert_eq!(parsed.groups[0].crawl_delay, Some(2.0));
+        assert_eq!(parsed.sitemaps, ["https://example.com/sitemap.xml"]);
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].line, 13);
+    }
+
+    #[test]
+    fn selects_specific_group_before_wildcard() {
+        let parsed = parse_robots(ROBOTS);
+        let verdict = check_path(&parsed, "Googlebot/2.1", "/index.html");
+        assert!(verdict.allowed);
+        assert_eq!(verdict.crawl_delay, Some(2.0));
+
+        let verdict = check_path(&parsed, "OtherBot", "/index.html");
+        assert!(!verdict.allowed);
+        assert_eq!(verdict.group_agents, ["*"]);
+    }
+
+    #[test]
+    fn longest_match_wins_and_anchors_apply() {
+        let parsed = parse_robots(ROBOTS);
+        assert!(!check_path(&parsed, "bingbot", "/private/data").allowed);
+        assert!(check_path(&parsed, "bingbot", "/private/public").allowed);
+        assert!(!check_path(&parsed, "bingbot", "/private/public/more").allowed);
+        assert!(check_path(&parsed, "OtherBot", "/assets/site.css").allowed);
+        assert!(!check_path(&parsed, "OtherBot", "/assets/site.css?v=1").allowed);
+    }
+
+    #[test]
+    fn allow_wins_equal_length_tie() {
+        let parsed = parse_robots("User-agent: *\nDisallow: /page\nAllow: /page\n");
+        assert!(check_path(&parsed, "any", "/page").allowed);
+    }
+
+    #[test]
+    fn parses_urlset_with_lastmod_spread() {
+        let xml = r#"<?xml version="1.0"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://example.com/a</loc><lastmod>2023-05-01</lastmod></url>
+  <url><loc>https://example.com/b</loc><lastmod>2024-01-15T10:00:00+00:00</lastmod></url>
+  <url><loc>https://example.com/c</loc></url>
+</urlset>"#;
+        let (kind, entries) = parse_sitemap(xml).unwrap();
+        assert_eq!(kind, SitemapKind::UrlSet);
+        assert_eq!(entries.len(), 3);
+        let spread = lastmod_spread(&entries);
+        assert_eq!(spread.with_lastmod, 2);
+        assert_eq!(spread.earliest.as_deref(), Some("2023-05-01"));
+        assert_eq!(spread.latest.as_deref(), Some("2024-01-15"));
+        assert_eq!(spread.by_year.get("2024"), Some(&1));
+    }
+
+    #[test]
+    fn parses_sitemap_index() {
+        let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/s1.xml</loc></sitemap>
+</sitemapindex>"#;
+        let (kind, entries) = parse_sitemap(xml).unwrap();
+        assert_eq!(kind, SitemapKind::Index);
+        assert_eq!(entries[0].loc, "https://example.com/s1.xml");
+        assert!(parse_sitemap("<rss/>").is_err());
+    }
+}
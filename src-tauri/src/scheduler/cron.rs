@@ -0,0 +1,278 @@
+//! Five-field cron expressions
+//!
+//! Supports `minute hour day-of-month month day-of-week` with `*`, lists,
+//! ranges, steps (`*/15`, `1-5/2`), month and weekday names, and the
+//! `@hourly`, `@daily`, `@weekly`, `@monthly`, and `@yearly` macros. As in
+//! Vixie cron, when both day fields are restricted a time matches if either
+//! one does. Times are evaluated against a fixed UTC offset.
+
+use std::str::FromStr;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Bounds and value names of the five fields, in order
+const FIELDS: [(u32, u32, &[&str]); 5] = [
+    (0, 59, &[]),
+    (0, 23, &[]),
+    (1, 31, &[]),
+    (1, 12, &MONTH_NAMES),
+    (0, 7, &WEEKDAY_NAMES),
+];
+
+/// How far ahead [`CronSchedule::next_after`] searches
+const SEARCH_LIMIT_SECS: i64 = 5 * 366 * 86_400;
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+/// Parse one field into a bitmask of allowed values in `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let upper = text.to_ascii_uppercase();
+        if let Some(i) = names.iter().position(|n| *n == upper) {
+            return Ok(min + u32::try_from(i).unwrap_or(0));
+        }
+        text.parse::<u32>()
+            .map_err(|_| format!("Invalid cron value: {text}"))
+    };
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid cron step: {item}"))?,
+            ),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (value(a)?, value(b)?),
+                // `5/10` means `5-max/10`.
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("Cron field {item} is outside {min}-{max}"));
+        }
+        let mut v = start;
+        while v <= end {
+            mask |= 1 << v;
+            v += step;
+        }
+    }
+    Ok(mask)
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Cron expression needs 5 fields, got {}",
+                fields.len()
+            ));
+        };
+        let narrow = |mask: u64| u32::try_from(mask).map_err(|_| "Cron field out of range");
+        // Day-of-week 7 is an alias for Sunday.
+        let weekdays = parse_field(weekday, 0, 7, &WEEKDAY_NAMES)?;
+        let weekdays = (weekdays | (weekdays >> 7)) & 0x7f;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: narrow(parse_field(hour, 0, 23, &[])?)?,
+            days: narrow(parse_field(day, 1, 31, &[])?)?,
+            months: u16::try_from(parse_field(month, 1, 12, &MONTH_NAMES)?)
+                .map_err(|_| "Cron field out of range")?,
+            weekdays: u8::try_from(weekdays).map_err(|_| "Cron field out of range")?,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+}
+
+/// Civil date from days since 1970-01-01 (proleptic Gregorian).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = u32::try_from(doy - (153 * mp + 2) / 5 + 1).unwrap_or(1);
+    let month = u32::try_from(if mp < 10 { mp + 3 } else { mp - 9 }).unwrap_or(1);
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 of the first day of `month` in `year`.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+impl CronSchedule {
+    /// Check field `index` of an expression (0 for minute, 4 for
+    /// day-of-week) on its own.
+    pub fn check_field(index: usize, field: &str) -> Result<(), String> {
+        let &(min, max, names) = FIELDS
+            .get(index)
+            .ok_or_else(|| format!("Cron expressions have no field {index}"))?;
+        parse_field(field, min, max, names).map(|_| ())
+    }
+
+    fn day_matches(&self, day: u32, weekday: u32) -> bool {
+        let dom = self.days & (1 << day) != 0;
+        let dow = self.weekdays & (1 << weekday) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// First matching time strictly after `after` (Unix seconds), with the
+    /// expression read in a zone `utc_offset_minutes` ahead of UTC.
+    pub fn next_after(&self, after: i64, utc_offset_minutes: i32) -> Option<i64> {
+        let offset = i64::from(utc_offset_minutes) * 60;
+        // Local seconds, rounded up to the next whole minute.
+        let mut t = (after + offset).div_euclid(60) * 60 + 60;
+        let limit = t + SEARCH_LIMIT_SECS;
+        while t < limit {
+            let days = t.div_euclid(86_400);
+            let (year, month, day) = civil_from_days(days);
+            if self.months & (1 << month) == 0 {
+                let (year, month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                t = days_from_civil(year, month, 1) * 86_400;
+                continue;
+            }
+            let weekday = u32::try_from((days + 4).rem_euclid(7)).unwrap_or(0);
+            if !self.day_matches(day, weekday) {
+                t = (days + 1) * 86_400;
+                continue;
+            }
+            let secs = t.rem_euclid(86_400);
+            let hour = u32::try_from(secs / 3600).unwrap_or(0);
+            if self.hours & (1 << hour) == 0 {
+                t = days * 86_400 + i64::from(hour + 1) * 3600;
+                continue;
+            }
+            let minute = (secs % 3600) / 60;
+            if self.minutes & (1 << minute) == 0 {
+                t += 60;
+                continue;
+            }
+            return Some(t - offset);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-01T00:00:00Z, a Monday
+    const JAN_1_2024: i64 = 1_704_067_200;
+
+    fn next(expr: &str, after: i64) -> i64 {
+        expr.parse::<CronSchedule>()
+            .unwrap()
+            .next_after(after, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn converts_civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(JAN_1_2024 / 86_400), (2024, 1, 1));
+        assert_eq!(
+            days_from_civil(2024, 3, 1) - days_from_civil(2024, 2, 1),
+            29
+        );
+    }
+
+    #[test]
+    fn finds_next_matching_times() {
+        assert_eq!(next("*/15 * * * *", JAN_1_2024), JAN_1_2024 + 15 * 60);
+        assert_eq!(next("@hourly", JAN_1_2024 + 1), JAN_1_2024 + 3600);
+        assert_eq!(
+            next("30 9 * * 1-5", JAN_1_2024),
+            JAN_1_2024 + 9 * 3600 + 1800
+        );
+        // Saturday 2024-01-06.
+        assert_eq!(next("0 0 * * sat", JAN_1_2024), JAN_1_2024 + 5 * 86_400);
+        // Both day fields restricted: the 15th or any Sunday (7 = Sunday).
+        assert_eq!(next("0 0 15 * 7", JAN_1_2024), JAN_1_2024 + 6 * 86_400);
+        // Leap day.
+        assert_eq!(
+            next("0 12 29 feb *", JAN_1_2024),
+            days_from_civil(2024, 2, 29) * 86_400 + 12 * 3600
+        );
+        // 09:00 at UTC+9 is 00:00 UTC.
+        let tokyo = "0 9 * * *".parse::<CronSchedule>().unwrap();
+        assert_eq!(tokyo.next_after(JAN_1_2024 - 60, 540), Some(JAN_1_2024));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "0 0 0 * *",
+            "*/0 * * * *",
+            "a b c d e",
+        ] {
+            assert!(expr.parse::<CronSchedule>().is_err(), "{expr}");
+        }
+        assert!(CronSchedule::check_field(3, "jan-jun/2").is_ok());
+        assert!(CronSchedule::check_field(4, "8").is_err());
+        assert!(CronSchedule::check_field(5, "*").is_err());
+        assert_eq!(
+            "0 0 31 2 *"
+                .parse::<CronSchedule>()
+                .unwrap()
+                .next_after(0, 0),
+            None
+        );
+    }
+}
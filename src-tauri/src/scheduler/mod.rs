@@ -0,0 +1,697 @@
+//! Scheduled task runner
+//!
+//! Runs saved actions on cron schedules while the app is open: a pass of a
+//! saved scan profile, an HTTP health check, or a TLS certificate expiry
+//! check. Tasks are kept in `<app data>/scheduled-tasks.json`.
+//!
+//! A background loop wakes at every minute boundary and starts the tasks
+//! that are due; a task still running from its previous slot is skipped.
+//! Each run updates the task's last result, is recorded in tool history
+//! (one entry per task, relabelled with the latest outcome), and is emitted
+//! on `scheduled-task-event` so the frontend can raise a notification.
+//!
+//! Missed slots while the app was closed are not replayed; the next run is
+//! computed from the current time on startup.
+
+mod cron;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+pub use cron::CronSchedule;
+
+use crate::history::HistoryState;
+use crate::network::monitor;
+use crate::tls_inspect::{self, TlsInspectRequest};
+
+/// Tauri event carrying each finished run
+const EVENT_NAME: &str = "scheduled-task-event";
+
+/// File (under the app data dir) holding every task
+const TASKS_FILE: &str = "scheduled-tasks.json";
+
+/// Default request timeout for health and certificate checks
+const DEFAULT_TIMEOUT_MS: u32 = 10_000;
+
+/// Default warning threshold for certificate expiry
+const DEFAULT_WARN_DAYS: u32 = 14;
+
+/// Upper bound for [`scheduler_preview`]
+const MAX_PREVIEW: u32 = 20;
+
+/// What a task does when it fires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ScheduledAction {
+    /// One pass of a saved port monitor (scan profile).
+    ScanProfile {
+        /// Port monitor id.
+        monitor_id: String,
+    },
+    /// `GET` a URL and check the status code.
+    UrlHealth {
+        /// URL to request.
+        url: String,
+        /// Required status; any 2xx or 3xx passes when `None`.
+        expected_status: Option<u16>,
+        /// Request timeout in milliseconds.
+        timeout_ms: Option<u32>,
+    },
+    /// Check how long a server's TLS certificate stays valid.
+    CertificateExpiry {
+        /// Host name.
+        host: String,
+        /// TLS port, 443 by default.
+        port: Option<u16>,
+        /// Fail when fewer days remain.
+        warn_days: Option<u32>,
+    },
+}
+
+impl ScheduledAction {
+    /// Catalog tool the run is recorded under in history.
+    const fn history_tool(&self) -> &'static str {
+        match self {
+            Self::ScanProfile { .. } => "network-scanner",
+            Self::UrlHealth { .. } => "rest-client",
+            Self::CertificateExpiry { .. } => "tls-inspector",
+        }
+    }
+}
+
+/// Outcome of one run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRunResult {
+    /// Whether the check passed.
+    pub ok: bool,
+    /// One-line description of the outcome.
+    pub summary: String,
+    /// Action-specific details.
+    pub detail: Value,
+    /// Unix milliseconds when the run finished.
+    pub finished_ms: u64,
+    /// Run time in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Task definition sent by the frontend.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskInput {
+    /// Existing task to update, or `None` to create one.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Display name.
+    pub name: String,
+    /// Cron expression (`*/5 * * * *`, `@daily`, ...).
+    pub schedule: String,
+    /// Minutes the schedule's zone is ahead of UTC.
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    /// Whether the task fires on its schedule.
+    pub enabled: bool,
+    /// What to run.
+    pub action: ScheduledAction,
+}
+
+/// A saved task with its run state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    /// Stable id (UUID v4).
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Cron expression.
+    pub schedule: String,
+    /// Minutes the schedule's zone is ahead of UTC.
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    /// Whether the task fires on its schedule.
+    pub enabled: bool,
+    /// What to run.
+    pub action: ScheduledAction,
+    /// Unix milliseconds of the next scheduled run.
+    #[serde(default)]
+    pub next_run_ms: Option<u64>,
+    /// Result of the most recent run.
+    #[serde(default)]
+    pub last_result: Option<TaskRunResult>,
+}
+
+/// Event emitted on [`EVENT_NAME`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskEvent {
+    /// Task id.
+    pub task_id: String,
+    /// Task name.
+    pub name: String,
+    /// Run outcome.
+    pub result: TaskRunResult,
+}
+
+/// Managed scheduler state.
+pub struct SchedulerState {
+    path: PathBuf,
+    tasks: Mutex<Vec<ScheduledTask>>,
+    running: Mutex<HashSet<String>>,
+}
+
+fn unix_ms_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Next run in Unix milliseconds after `now_ms`, or `None` when disabled.
+fn next_run(task: &ScheduledTask, now_ms: u64) -> Option<u64> {
+    if !task.enabled {
+        return None;
+    }
+    let schedule: CronSchedule = task.schedule.parse().ok()?;
+    let now = i64::try_from(now_ms / 1000).ok()?;
+    schedule
+        .next_after(now, task.utc_offset_minutes)
+        .and_then(|secs| u64::try_from(secs).ok())
+        .map(|secs| secs * 1000)
+}
+
+impl SchedulerState {
+    /// Load tasks from `data_dir`. A missing or unreadable file starts
+    /// empty; next runs are recomputed from now.
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(TASKS_FILE);
+        let now = unix_ms_now();
+        let mut tasks: Vec<ScheduledTask> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        for task in &mut tasks {
+            task.next_run_ms = next_run(task, now);
+        }
+        Self {
+            path,
+            tasks: Mutex::new(tasks),
+            running: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn with_tasks<T>(&self, f: impl FnOnce(&mut Vec<ScheduledTask>) -> T) -> Result<T, String> {
+        let mut tasks = self
+            .tasks
+            .lock()
+            .map_err(|e| format!("Scheduler lock poisoned: {e}"))?;
+        let result = f(&mut tasks);
+        let content = serde_json::to_string_pretty(&*tasks)
+            .map_err(|e| format!("Failed to serialize scheduled tasks: {e}"))?;
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create data directory: {e}"))?;
+        }
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write scheduled tasks: {e}"))?;
+        Ok(result)
+    }
+
+    fn list(&self) -> Vec<ScheduledTask> {
+        self.tasks
+            .lock()
+            .map(|tasks| tasks.clone())
+            .unwrap_or_default()
+    }
+
+    /// Every task, for backups.
+    pub(crate) fn export_all(&self) -> Vec<ScheduledTask> {
+        self.list()
+    }
+
+    /// Restore tasks from a backup and return how many were written.
+    ///
+    /// With `replace` the current tasks are dropped first; otherwise a
+    /// backup task overwrites the task with the same id. Next runs are
+    /// recomputed from now.
+    pub(crate) fn import(
+        &self,
+        imported: Vec<ScheduledTask>,
+        replace: bool,
+    ) -> Result<usize, String> {
+        let now = unix_ms_now();
+        self.with_tasks(|tasks| {
+            if replace {
+                tasks.clear();
+            }
+            let written = imported.len();
+            for mut task in imported {
+                task.next_run_ms = next_run(&task, now);
+                match tasks.iter().position(|t| t.id == task.id) {
+                    Some(i) => tasks[i] = task,
+                    None => tasks.push(task),
+                }
+            }
+            written
+        })
+    }
+
+    /// Claim the tasks due at `now_ms`, advancing their next run.
+    fn take_due(&self, now_ms: u64) -> Result<Vec<ScheduledTask>, String> {
+        let mut running = self
+            .running
+            .lock()
+            .map_err(|e| format!("Scheduler lock poisoned: {e}"))?;
+        self.with_tasks(|tasks| {
+            let mut due = Vec::new();
+            for task in tasks.iter_mut() {
+                if task.next_run_ms.is_none_or(|next| next > now_ms) {
+                    continue;
+                }
+                task.next_run_ms = next_run(task, now_ms);
+                if running.insert(task.id.clone()) {
+                    due.push(task.clone());
+                }
+            }
+            due
+        })
+    }
+
+    /// Store a finished run and release the task.
+    fn finish(&self, id: &str, result: &TaskRunResult) -> Result<(), String> {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(id);
+        }
+        self.with_tasks(|tasks| {
+            if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+                task.last_result = Some(result.clone());
+            }
+        })
+    }
+}
+
+// =============================================================================
+// Actions
+// =============================================================================
+
+async fn url_health(url: &str, expected: Option<u16>, timeout_ms: u32) -> Result<Value, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(u64::from(timeout_ms)))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+    let started = Instant::now();
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    let status = response.status();
+    let ok = expected.map_or_else(
+        || status.is_success() || status.is_redirection(),
+        |expected| status.as_u16() == expected,
+    );
+    let detail = json!({
+        "status": status.as_u16(),
+        "latencyMs": u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    });
+    if ok {
+        Ok(detail)
+    } else {
+        Err(format!("{url} answered {status}"))
+    }
+}
+
+async fn certificate_expiry(host: &str, port: u16, warn_days: u32) -> Result<Value, String> {
+    let inspected = tls_inspect::tls_inspect(TlsInspectRequest {
+        host: host.to_string(),
+        port,
+        sni: None,
+        timeout_ms: DEFAULT_TIMEOUT_MS,
+    })
+    .await?;
+    let leaf = inspected
+        .peer_chain_base64
+        .first()
+        .ok_or("Server sent no certificate")?;
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(leaf)
+        .map_err(|e| format!("Invalid certificate encoding: {e}"))?;
+    let (_, cert) =
+        X509Certificate::from_der(&der).map_err(|e| format!("Invalid certificate: {e}"))?;
+    let not_after = cert.validity().not_after.timestamp();
+    let now = i64::try_from(unix_ms_now() / 1000).unwrap_or(i64::MAX);
+    let days_left = (not_after - now).div_euclid(86_400);
+    if days_left < i64::from(warn_days) {
+        return Err(format!(
+            "Certificate for {host}:{port} expires in {days_left} days"
+        ));
+    }
+    Ok(json!({
+        "subject": cert.subject().to_string(),
+        "notAfter": not_after,
+        "daysLeft": days_left,
+    }))
+}
+
+/// Run `action` and describe the outcome.
+async fn execute(app: &AppHandle, action: &ScheduledAction) -> Result<(String, Value), String> {
+    match action {
+        ScheduledAction::ScanProfile { monitor_id } => {
+            let (open_ports, changes) = monitor::run_profile_once(app, monitor_id).await?;
+            Ok((
+                format!("{open_ports} open ports, {changes} changes"),
+                json!({ "openPorts": open_ports, "changes": changes }),
+            ))
+        }
+        ScheduledAction::UrlHealth {
+            url,
+            expected_status,
+            timeout_ms,
+        } => {
+            let detail = url_health(
+                url,
+                *expected_status,
+                timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+            )
+            .await?;
+            Ok((format!("{url} answered {}", detail["status"]), detail))
+        }
+        ScheduledAction::CertificateExpiry {
+            host,
+            port,
+            warn_days,
+        } => {
+            let detail = certificate_expiry(
+                host,
+                port.unwrap_or(443),
+                warn_days.unwrap_or(DEFAULT_WARN_DAYS),
+            )
+            .await?;
+            Ok((
+                format!("Certificate valid for {} more days", detail["daysLeft"]),
+                detail,
+            ))
+        }
+    }
+}
+
+/// Run a task, store and record its result, and emit it.
+async fn run_task(app: &AppHandle, task: &ScheduledTask) -> TaskRunResult {
+    let started = Instant::now();
+    let (ok, summary, detail) = match execute(app, &task.action).await {
+        Ok((summary, detail)) => (true, summary, detail),
+        Err(message) => (false, message, Value::Null),
+    };
+    let result = TaskRunResult {
+        ok,
+        summary,
+        detail,
+        finished_ms: unix_ms_now(),
+        duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    };
+
+    let state = app.state::<SchedulerState>();
+    let _ = state.finish(&task.id, &result);
+    let label = format!(
+        "Scheduled {}: {} ({})",
+        task.name,
+        result.summary,
+        if ok { "ok" } else { "failed" }
+    );
+    let _ = app.state::<HistoryState>().record(
+        task.action.history_tool(),
+        &format!("scheduled-task:{}", task.id),
+        Some(&label),
+    );
+    let _ = app.emit(
+        EVENT_NAME,
+        ScheduledTaskEvent {
+            task_id: task.id.clone(),
+            name: task.name.clone(),
+            result: result.clone(),
+        },
+    );
+    result
+}
+
+/// Start the background loop that fires due tasks at each minute boundary.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let now = unix_ms_now();
+            let wait = 60_000 - now % 60_000;
+            tokio::time::sleep(Duration::from_millis(wait)).await;
+
+            let due = app
+                .state::<SchedulerState>()
+                .take_due(unix_ms_now())
+                .unwrap_or_default();
+            for task in due {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    run_task(&app, &task).await;
+                });
+            }
+        }
+    });
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+/// List every scheduled task.
+#[tauri::command]
+pub fn scheduler_list(state: tauri::State<'_, SchedulerState>) -> Vec<ScheduledTask> {
+    state.list()
+}
+
+/// Create or update a task.
+///
+/// # Errors
+///
+/// Returns an error string when the cron expression is invalid, the task
+/// to update does not exist, or the task file cannot be written.
+#[tauri::command]
+pub fn scheduler_save(
+    task: ScheduledTaskInput,
+    state: tauri::State<'_, SchedulerState>,
+) -> Result<ScheduledTask, String> {
+    task.schedule.parse::<CronSchedule>()?;
+    if task.name.trim().is_empty() {
+        return Err("Task name is required".to_string());
+    }
+    let now = unix_ms_now();
+    state.with_tasks(|tasks| {
+        let existing = task
+            .id
+            .as_ref()
+            .map(|id| {
+                tasks
+                    .iter()
+                    .position(|t| &t.id == id)
+                    .ok_or_else(|| format!("Unknown scheduled task: {id}"))
+            })
+            .transpose()?;
+        let mut saved = ScheduledTask {
+            id: task
+                .id
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            name: task.name.trim().to_string(),
+            schedule: task.schedule.trim().to_string(),
+            utc_offset_minutes: task.utc_offset_minutes,
+            enabled: task.enabled,
+            action: task.action,
+            next_run_ms: None,
+            last_result: existing.and_then(|i| tasks[i].last_result.clone()),
+        };
+        saved.next_run_ms = next_run(&saved, now);
+        match existing {
+            Some(i) => tasks[i] = saved.clone(),
+            None => tasks.push(saved.clone()),
+        }
+        Ok(saved)
+    })?
+}
+
+/// Delete a task. Returns whether it existed.
+///
+/// # Errors
+///
+/// Returns an error string when the task file cannot be written.
+#[tauri::command]
+pub fn scheduler_delete(
+    id: String,
+    state: tauri::State<'_, SchedulerState>,
+) -> Result<bool, String> {
+    state.with_tasks(|tasks| {
+        let before = tasks.len();
+        tasks.retain(|t| t.id != id);
+        tasks.len() != before
+    })
+}
+
+/// Run a task immediately, outside its schedule.
+///
+/// # Errors
+///
+/// Returns an error string when the task does not exist or is already
+/// running. A failing check is reported in the result, not as an error.
+#[tauri::command]
+pub async fn scheduler_run_now(
+    id: String,
+    app: AppHandle,
+    state: tauri::State<'_, SchedulerState>,
+) -> Result<TaskRunResult, String> {
+    let task = state
+        .list()
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Unknown scheduled task: {id}"))?;
+    let claimed = state
+        .running
+        .lock()
+        .map_err(|e| format!("Scheduler lock poisoned: {e}"))?
+        .insert(id.clone());
+    if !claimed {
+        return Err(format!("Task {} is already running", task.name));
+    }
+    Ok(run_task(&app, &task).await)
+}
+
+/// Next `count` fire times (Unix milliseconds) of a cron expression, for
+/// validating a schedule as it is typed.
+///
+/// # Errors
+///
+/// Returns an error string when the expression is invalid.
+#[tauri::command]
+pub fn scheduler_preview(
+    schedule: String,
+    utc_offset_minutes: Option<i32>,
+    count: Option<u32>,
+) -> Result<Vec<u64>, String> {
+    let parsed: CronSchedule = schedule.parse()?;
+    let offset = utc_offset_minutes.unwrap_or(0);
+    let mut after = i64::try_from(unix_ms_now() / 1000).unwrap_or(i64::MAX);
+    let mut times = Vec::new();
+    for _ in 0..count.unwrap_or(5).min(MAX_PREVIEW) {
+        let Some(next) = parsed.next_after(after, offset) else {
+            break;
+        };
+        times.push(u64::try_from(next).unwrap_or(0) * 1000);
+        after = next;
+    }
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(schedule: &str, enabled: bool) -> ScheduledTask {
+        ScheduledTask {
+            id: "t1".to_string(),
+            name: "Health".to_string(),
+            schedule: schedule.to_string(),
+            utc_offset_minutes: 0,
+            enabled,
+            action: ScheduledAction::UrlHealth {
+                url: "http://127.0.0.1/".to_string(),
+                expected_status: None,
+                timeout_ms: None,
+            },
+            next_run_ms: None,
+            last_result: None,
+        }
+    }
+
+    #[test]
+    fn claims_due_tasks_once_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SchedulerState::load(dir.path());
+        state
+            .with_tasks(|tasks| {
+                let mut t = task("* * * * *", true);
+                t.next_run_ms = Some(60_000);
+                tasks.push(t);
+                tasks.push(task("* * * * *", false));
+            })
+            .unwrap();
+
+        let due = state.take_due(60_000).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(state.list()[0].next_run_ms, Some(120_000));
+        // Still running: the next slot is skipped.
+        assert!(state.take_due(120_000).unwrap().is_empty());
+
+        let result = TaskRunResult {
+            ok: true,
+            summary: "ok".to_string(),
+            detail: Value::Null,
+            finished_ms: 1,
+            duration_ms: 1,
+        };
+        state.finish("t1", &result).unwrap();
+        let reloaded = SchedulerState::load(dir.path());
+        assert_eq!(reloaded.list().len(), 2);
+        assert!(reloaded.list()[0]
+            .last_result
+            .as_ref()
+            .is_some_and(|r| r.ok));
+        assert_eq!(next_run(&reloaded.list()[1], 0), None);
+    }
+
+    #[test]
+    fn imports_merge_by_id_and_replace_clears() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SchedulerState::load(dir.path());
+        state
+            .with_tasks(|tasks| tasks.push(task("0 * * * *", true)))
+            .unwrap();
+
+        let mut renamed = task("0 * * * *", true);
+        renamed.name = "Renamed".to_string();
+        let mut other = task("0 * * * *", false);
+        other.id = "t2".to_string();
+        assert_eq!(state.import(vec![renamed, other], false).unwrap(), 2);
+        let tasks = state.export_all();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].name, "Renamed");
+        assert!(tasks[0].next_run_ms.is_some());
+
+        assert_eq!(state.import(Vec::new(), true).unwrap(), 0);
+        assert!(SchedulerState::load(dir.path()).list().is_empty());
+    }
+
+    #[test]
+    fn actions_round_trip_with_kind_tag() {
+        let action: ScheduledAction = serde_json::from_value(json!({
+            "kind": "certificateExpiry",
+            "host": "example.com",
+            "warnDays": 30,
+        }))
+        .unwrap();
+        assert!(matches!(
+            action,
+            ScheduledAction::CertificateExpiry {
+                warn_days: Some(30),
+                port: None,
+                ..
+            }
+        ));
+        assert_eq!(action.history_tool(), "tls-inspector");
+    }
+}
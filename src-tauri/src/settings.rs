@@ -32,6 +32,9 @@ pub struct AppSettings {
     /// Font configuration
     #[serde(default)]
     pub font: FontSettings,
+    /// Secret redaction for logs, history, and exports
+    #[serde(default)]
+    pub redaction: RedactionSettings,
 }
 
 /// Font family and size preferences
@@ -66,6 +69,30 @@ impl Default for FontSettings {
     }
 }
 
+/// Secret redaction preferences (see [`crate::redaction`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionSettings {
+    /// Whether secrets are masked before anything is written to disk
+    #[serde(default = "default_redaction_enabled")]
+    pub enabled: bool,
+    /// Extra regular expressions; a named group `secret` masks only that group
+    #[serde(default)]
+    pub custom_patterns: Vec<String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_redaction_enabled(),
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+const fn default_redaction_enabled() -> bool {
+    true
+}
+
 const fn default_ui_size() -> u32 {
     13
 }
@@ -92,6 +119,9 @@ impl SettingsState {
     pub fn load(config_dir: &std::path::Path) -> Self {
         let file_path = config_dir.join(SETTINGS_FILENAME);
         let settings = load_from_file(&file_path);
+        // A bad custom pattern keeps the built-in rules rather than
+        // blocking startup.
+        let _ = crate::redaction::configure(&settings.redaction);
 
         Self {
             settings: Mutex::new(settings),
@@ -103,6 +133,28 @@ impl SettingsState {
     pub fn file_path(&self) -> &std::path::Path {
         &self.file_path
     }
+
+    /// Get a copy of the current settings
+    pub fn current(&self) -> Result<AppSettings, String> {
+        let settings = self
+            .settings
+            .lock()
+            .map_err(|e| format!("Settings lock poisoned: {e}"))?;
+        Ok(settings.clone())
+    }
+
+    /// Replace the settings and save them to disk
+    pub fn replace(&self, settings: AppSettings) -> Result<(), String> {
+        crate::redaction::configure(&settings.redaction)?;
+        save_to_file(&self.file_path, &settings)?;
+
+        *self
+            .settings
+            .lock()
+            .map_err(|e| format!("Settings lock poisoned: {e}"))? = settings;
+
+        Ok(())
+    }
 }
 
 /// Load settings from a TOML file, returning defaults on any error
@@ -183,11 +235,7 @@ fn enumerate_monospace_fonts() -> &'static [String] {
 /// Get the current application settings
 #[tauri::command]
 pub fn get_settings(state: tauri::State<'_, SettingsState>) -> Result<AppSettings, String> {
-    let settings = state
-        .settings
-        .lock()
-        .map_err(|e| format!("Settings lock poisoned: {e}"))?;
-    Ok(settings.clone())
+    state.current()
 }
 
 /// Update application settings (auto-saves to disk)
@@ -196,14 +244,7 @@ pub fn update_settings(
     settings: AppSettings,
     state: tauri::State<'_, SettingsState>,
 ) -> Result<(), String> {
-    save_to_file(&state.file_path, &settings)?;
-
-    *state
-        .settings
-        .lock()
-        .map_err(|e| format!("Settings lock poisoned: {e}"))? = settings;
-
-    Ok(())
+    state.replace(settings)
 }
 
 /// Reset all settings to defaults, delete the settings file, and resize window
@@ -221,6 +262,7 @@ pub fn reset_settings(
 
     // Reset in-memory settings
     let defaults = AppSettings::default();
+    crate::redaction::configure(&defaults.redaction)?;
     {
         let mut current = state
             .settings
@@ -310,6 +352,7 @@ mod tests {
                 code_size: 12,
                 google_fonts_enabled: true,
             },
+            redaction: RedactionSettings::default(),
         };
 
         let toml_str = toml::to_string_pretty(&settings).unwrap();
@@ -376,6 +419,7 @@ key = "value"
                 code_size: 11,
                 google_fonts_enabled: false,
             },
+            redaction: RedactionSettings::default(),
         };
 
         save_to_file(&path, &settings).unwrap();
@@ -0,0 +1,319 @@
+//! `authorized_keys` and `known_hosts` analyzer Tauri command.
+//!
+//! Parses pasted `authorized_keys` or `known_hosts` content line by line
+//! with `ssh-key`'s entry parsers and lists each key's type, size,
+//! fingerprint, and comment, with its `authorized_keys` options or
+//! `known_hosts` host patterns and marker. DSA keys, which OpenSSH no
+//! longer accepts, and RSA keys under 2048 bits are flagged, as are
+//! entries that repeat an earlier one, so a file that has grown over the
+//! years can be cleaned up.
+//!
+//! Every entry and every line that does not parse carries the line's range
+//! in editor coordinates, so the UI can highlight, rewrite, or delete it
+//! in place.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use ssh_key::known_hosts::HostPatterns;
+use ssh_key::public::KeyData;
+use ssh_key::{HashAlg, PublicKey};
+
+use crate::ast::{AstPosition, AstRange};
+use crate::generators::ssh::{algorithm_name, key_bits};
+
+/// RSA keys shorter than this are flagged, following NIST SP 800-131A.
+const MIN_RSA_BITS: usize = 2048;
+
+/// Which file the content comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SshKeyListKind {
+    AuthorizedKeys,
+    KnownHosts,
+}
+
+/// Reason an entry is flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SshKeyIssue {
+    /// DSA, disabled by default since OpenSSH 7.0.
+    Dsa,
+    /// RSA under 2048 bits.
+    WeakRsa,
+    /// Same key (and, in `known_hosts`, the same hosts) as an earlier line.
+    Duplicate,
+}
+
+/// One key line.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshKeyListEntry {
+    /// 1-based line number.
+    pub line: usize,
+    /// The whole line, without its line break.
+    pub range: AstRange,
+    /// Key type, e.g. "Ed25519" or "RSA".
+    pub algorithm: String,
+    pub bits: usize,
+    /// `SHA256:` fingerprint as `ssh-keygen -l` prints it.
+    pub fingerprint: String,
+    pub comment: String,
+    /// `authorized_keys` options, e.g. `from="10.0.0.*"` or `no-pty`.
+    pub options: Vec<String>,
+    /// `known_hosts` host patterns; empty when the host name is hashed.
+    pub hosts: Vec<String>,
+    /// Whether the `known_hosts` host name is hashed (`|1|...`).
+    pub hashed: bool,
+    /// `@cert-authority` or `@revoked`.
+    pub marker: Option<String>,
+    pub issues: Vec<SshKeyIssue>,
+    /// Line of the earlier entry this one repeats.
+    pub duplicate_of: Option<usize>,
+}
+
+/// A line that is neither blank, a comment, nor a valid entry.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshKeyListError {
+    pub line: usize,
+    pub range: AstRange,
+    pub message: String,
+}
+
+/// Result of [`analyze_ssh_key_list`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshKeyListReport {
+    pub kind: SshKeyListKind,
+    /// Key lines in file order.
+    pub entries: Vec<SshKeyListEntry>,
+    /// Lines that failed to parse.
+    pub errors: Vec<SshKeyListError>,
+}
+
+/// The parts of a line that differ between the two file kinds.
+struct ParsedLine {
+    public_key: PublicKey,
+    options: Vec<String>,
+    hosts: Vec<String>,
+    hashed: bool,
+    marker: Option<String>,
+    /// Host field and marker, which make a `known_hosts` entry distinct.
+    scope: String,
+}
+
+/// Analyze `authorized_keys` or `known_hosts` content.
+///
+/// # Arguments
+///
+/// - `text` — the file content.
+/// - `kind` — `authorized-keys` or `known-hosts`.
+#[tauri::command]
+pub fn analyze_ssh_key_list(text: String, kind: SshKeyListKind) -> SshKeyListReport {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    // (fingerprint, scope) of each entry, mapped to its line
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+
+    let mut offset = 0;
+    for (index, raw) in text.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        let content = raw.trim_end_matches(['\n', '\r']);
+        let range = line_range(line_number, offset, content);
+        offset += raw.len();
+
+        let trimmed = content.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let parsed = match parse_line(trimmed, kind) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                errors.push(SshKeyListError {
+                    line: line_number,
+                    range,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let key_data = parsed.public_key.key_data();
+        let algorithm = algorithm_name(key_data);
+        let bits = key_bits(key_data);
+        let fingerprint = parsed.public_key.fingerprint(HashAlg::Sha256).to_string();
+
+        let mut issues = Vec::new();
+        match key_data {
+            KeyData::Dsa(_) => issues.push(SshKeyIssue::Dsa),
+            KeyData::Rsa(_) if bits < MIN_RSA_BITS => issues.push(SshKeyIssue::WeakRsa),
+            _ => {}
+        }
+        let duplicate_of = seen
+            .get(&(fingerprint.clone(), parsed.scope.clone()))
+            .copied();
+        if duplicate_of.is_some() {
+            issues.push(SshKeyIssue::Duplicate);
+        } else {
+            seen.insert((fingerprint.clone(), parsed.scope), line_number);
+        }
+
+        entries.push(SshKeyListEntry {
+            line: line_number,
+            range,
+            algorithm,
+            bits,
+            fingerprint,
+            comment: parsed.public_key.comment().to_string(),
+            options: parsed.options,
+            hosts: parsed.hosts,
+            hashed: parsed.hashed,
+            marker: parsed.marker,
+            issues,
+            duplicate_of,
+        });
+    }
+
+    SshKeyListReport {
+        kind,
+        entries,
+        errors,
+    }
+}
+
+fn parse_line(line: &str, kind: SshKeyListKind) -> Result<ParsedLine, String> {
+    let invalid = |e: ssh_key::Error| format!("Not a valid entry: {e}");
+    match kind {
+        SshKeyListKind::AuthorizedKeys => {
+            let entry = ssh_key::authorized_keys::Entry::from_str(line).map_err(invalid)?;
+            Ok(ParsedLine {
+                options: entry.config_opts().iter().map(str::to_string).collect(),
+                public_key: entry.public_key().clone(),
+                hosts: Vec::new(),
+                hashed: false,
+                marker: None,
+                scope: String::new(),
+            })
+        }
+        SshKeyListKind::KnownHosts => {
+            let entry = ssh_key::known_hosts::Entry::from_str(line).map_err(invalid)?;
+            let marker = entry.marker().map(|marker| marker.as_str().to_string());
+            let (hosts, hashed) = match entry.host_patterns() {
+                HostPatterns::Patterns(patterns) => (patterns.clone(), false),
+                HostPatterns::HashedName { .. } => (Vec::new(), true),
+            };
+            Ok(ParsedLine {
+                scope: format!(
+                    "{} {}",
+                    marker.as_deref().unwrap_or_default(),
+                    entry.host_patterns().to_string()
+                ),
+                public_key: entry.public_key().clone(),
+                options: Vec::new(),
+                hosts,
+                hashed,
+                marker,
+            })
+        }
+    }
+}
+
+/// Range of one line starting at byte `offset`.
+fn line_range(line: usize, offset: usize, content: &str) -> AstRange {
+    AstRange::new(
+        AstPosition::new(line, 1, offset),
+        AstPosition::new(line, content.len() + 1, offset + content.len())
+            .with_utf16_column(content.encode_utf16().count() + 1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILeDTqlno1NuJzjpsSiwvN1SGLNGh+xwLzQCogPLSzQd";
+    const ECDSA: &str = "ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBMIZCA4J1fY3ZGHaepJzVvGFtqsBDMqAi6dZWF1l+gHasO9RYjMOS/2x/YTeKrWCWMXV7Neghi3pTQONi+zkmLQ=";
+    const RSA_1024: &str = "ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAAAgQC5cSehL7K947i0iaGTXJAfxbgy0YU2j7MV7hq9MF0IZGN7mw3a3MeC7iMVsl8NYYBwOlpF9SUsxW40db9fM4Tx+RE1NwHHAqZf157jLk16iEUnz49PEiGXsYE3D4FHjl4NXxzi4JgZ1DfRq7NFyrrYXhAaD5WGRIoD15ZrXEusjQ==";
+    const DSA: &str = "ssh-dss AAAAB3NzaC1kc3MAAACBANZtOKNzozPJNhxkfYMhZC+5bqO1OryzBQIHDmhTahInGdXmJSBWjLYrwXp64zl5pf6cCoX/UTqts14+Es9KMjEYCpGS4fHKnOJ8CEvAa1rdK21e3BQbrKYHMrEus7f7O2NEc/KV8Bx+8yI6gCOBUAEMMT4Im1K6H69bev+mKKQtAAAAFQDspQ/s97o9BgHu4cM+qzUIuMWfFwAAAIEAioK1TainHkT4Z3ZH/CuUCIEMtfZ8J5VerEZi3PpY4p/YPV8jbFggXYXtMPV5ivmw2ZbPs3k4WgMxDC9+sdrU79wB9Mu+929ZYy0IZeJ0tGpAP4landN3Y0pIxtpN1MlUD2DXPSSP7D89QlrjUp2rPhCK+KKB5nZLRuY9k/r3CTQAAACBALj3BA+vBAUDTpe2K7yLeXMvmCaFuaOcvDlaBBqFSWZaAZckhpGCqMyS6/POCEq5iMBIbneQEx1cqL3CJNinM9oxUe+YUH1VibvB6SG/xJ2fAdr5xHcBx8ZAj5JwDJehhIE7lE4qs0L5RX8kVfugH2wU5xn/GtzOhSEeT8Iq3U1Q";
+
+    #[test]
+    fn test_authorized_keys() {
+        let text = format!(
+            "# deploy keys\n\
+             {ED25519} user@host\n\
+             from=\"10.0.0.*\",no-pty {ECDSA} ci@runner\n\
+             \n\
+             {RSA_1024} old@laptop\n\
+             {DSA} dsa@box\n\
+             command=\"backup\" {ED25519} user@host again\n\
+             ssh-ed25519 not-base64\n"
+        );
+        let report = analyze_ssh_key_list(text, SshKeyListKind::AuthorizedKeys);
+        let summary: Vec<_> = report
+            .entries
+            .iter()
+            .map(|e| (e.line, e.algorithm.as_str(), e.bits, e.issues.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (2, "Ed25519", 256, [].as_slice()),
+                (3, "ECDSA P-256", 256, &[]),
+                (5, "RSA", 1024, &[SshKeyIssue::WeakRsa]),
+                (6, "DSA", 1024, &[SshKeyIssue::Dsa]),
+                (7, "Ed25519", 256, &[SshKeyIssue::Duplicate]),
+            ]
+        );
+
+        let ed25519 = &report.entries[0];
+        assert_eq!(
+            ed25519.fingerprint,
+            "SHA256:ghxmnCHplhp3JVdgwA55AQTReDzkAc2prfx9nKms7h0"
+        );
+        assert_eq!(ed25519.comment, "user@host");
+        assert_eq!(report.entries[1].options, ["from=\"10.0.0.*\"", "no-pty"]);
+        assert_eq!(
+            report.entries[2].fingerprint,
+            "SHA256:R6+pjjRbgO+GKZhHh0sonOlLwhsqdhBHVCGnQbiAoBw"
+        );
+        assert_eq!(report.entries[4].duplicate_of, Some(2));
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 8);
+    }
+
+    #[test]
+    fn test_known_hosts() {
+        let text = format!(
+            "github.com,140.82.112.3 {ED25519}\n\
+             |1|F1E1KeoE/eEWhi10WpGv4OdiO6Y=|3988QV0VE8wmZL7suNrYQLITLCg= {ECDSA}\n\
+             @cert-authority *.example.com {ED25519}\n\
+             gitlab.com {ED25519}\n\
+             github.com,140.82.112.3 {ED25519}\n"
+        );
+        let report = analyze_ssh_key_list(text, SshKeyListKind::KnownHosts);
+        assert!(report.errors.is_empty());
+        let entries = &report.entries;
+        assert_eq!(entries[0].hosts, ["github.com", "140.82.112.3"]);
+        assert!(entries[1].hashed && entries[1].hosts.is_empty());
+        assert_eq!(entries[2].marker.as_deref(), Some("@cert-authority"));
+        // The same key for other hosts, or as a CA, is not a duplicate.
+        let duplicates: Vec<_> = entries.iter().map(|e| e.duplicate_of).collect();
+        assert_eq!(duplicates, [None, None, None, None, Some(1)]);
+    }
+
+    #[test]
+    fn test_line_ranges() {
+        let text = format!("# ü\r\n{ED25519} café\r\n");
+        let report = analyze_ssh_key_list(text.clone(), SshKeyListKind::AuthorizedKeys);
+        let range = report.entries[0].range;
+        let line = &text[range.start.offset..range.end.offset];
+        assert_eq!(line, format!("{ED25519} café"));
+        assert_eq!((range.start.line, range.start.column), (2, 1));
+        assert_eq!(range.end.column, line.len() + 1);
+        assert_eq!(range.end.utf16_column, line.len());
+    }
+}
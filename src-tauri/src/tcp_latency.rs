@@ -0,0 +1,487 @@
+//! TCP connect latency benchmark command.
+//!
+//! Repeatedly opens TCP connections to a `host:port` and times each phase
+//! separately: DNS resolution, TCP connect, and (optionally) the TLS
+//! handshake. The per-phase distributions make it easy to tell whether
+//! slowness comes from name resolution, the network path, or the service's
+//! TLS stack.
+//!
+//! Each attempt is streamed to the frontend through a [`Channel`] as soon as
+//! it finishes; the command returns the aggregate statistics and a histogram
+//! of total attempt times once every attempt has run or the operation is
+//! cancelled.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
+
+use crate::tls_inspect::{build_client_config, resolve_sni};
+
+/// Upper bound on attempts per run.
+const MAX_ATTEMPTS: u32 = 1000;
+
+/// Number of equal-width histogram buckets.
+const HISTOGRAM_BUCKETS: u32 = 20;
+
+/// Request payload sent from the frontend.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TcpLatencyRequest {
+    /// Target host (DNS name or IP literal).
+    pub host: String,
+    /// Target TCP port.
+    pub port: u16,
+    /// Number of connection attempts (1 to 1000).
+    pub count: u32,
+    /// Pause between attempts in milliseconds.
+    pub interval_ms: u32,
+    /// Per-phase timeout in milliseconds.
+    pub timeout_ms: u32,
+    /// Perform a TLS handshake after the TCP connect.
+    #[serde(default)]
+    pub tls: bool,
+    /// Optional SNI override. Defaults to `host` when `None` or empty.
+    #[serde(default)]
+    pub sni: Option<String>,
+    /// Re-resolve the host before every attempt instead of only once.
+    /// When `false`, the DNS phase is measured on the first attempt only.
+    #[serde(default)]
+    pub resolve_each_attempt: bool,
+}
+
+/// Timing of a single connection attempt, streamed as it completes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencySample {
+    /// Zero-based attempt index.
+    pub index: u32,
+    /// Address that was connected to, when resolution succeeded.
+    pub remote_addr: Option<String>,
+    /// DNS resolution time, when resolution ran for this attempt.
+    pub dns_ms: Option<f64>,
+    /// TCP connect time, when the connect succeeded.
+    pub connect_ms: Option<f64>,
+    /// TLS handshake time, when TLS was requested and succeeded.
+    pub tls_ms: Option<f64>,
+    /// Sum of every measured phase.
+    pub total_ms: f64,
+    /// Failure description; `None` for successful attempts.
+    pub error: Option<String>,
+}
+
+/// Summary statistics for one phase across all successful measurements.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseStats {
+    /// Number of measurements.
+    pub count: u32,
+    /// Fastest measurement.
+    pub min_ms: f64,
+    /// Slowest measurement.
+    pub max_ms: f64,
+    /// Arithmetic mean.
+    pub mean_ms: f64,
+    /// Population standard deviation.
+    pub stddev_ms: f64,
+    /// Median (nearest-rank).
+    pub p50_ms: f64,
+    /// 95th percentile (nearest-rank).
+    pub p95_ms: f64,
+    /// 99th percentile (nearest-rank).
+    pub p99_ms: f64,
+}
+
+/// One histogram bucket covering `[lower_ms, upper_ms)`.
+/// The last bucket also includes `upper_ms`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBucket {
+    /// Inclusive lower bound.
+    pub lower_ms: f64,
+    /// Exclusive upper bound (inclusive for the last bucket).
+    pub upper_ms: f64,
+    /// Number of attempts that fall in this bucket.
+    pub count: u32,
+}
+
+/// Aggregate benchmark result.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TcpLatencyResult {
+    /// Echo of the requested host.
+    pub host: String,
+    /// Echo of the requested port.
+    pub port: u16,
+    /// Attempts that ran (fewer than requested when cancelled).
+    pub attempts: u32,
+    /// Attempts that completed every requested phase.
+    pub successes: u32,
+    /// Attempts that failed in any phase.
+    pub failures: u32,
+    /// DNS phase statistics, when the host needed resolution.
+    pub dns: Option<PhaseStats>,
+    /// TCP connect phase statistics.
+    pub connect: Option<PhaseStats>,
+    /// TLS handshake phase statistics, when TLS was requested.
+    pub tls: Option<PhaseStats>,
+    /// Statistics of total successful attempt time.
+    pub total: Option<PhaseStats>,
+    /// Histogram of total successful attempt time.
+    pub histogram: Vec<HistogramBucket>,
+    /// Phase with the largest median (`dns`, `connect`, or `tls`).
+    pub slowest_phase: Option<String>,
+    /// `true` if the run was cancelled before all attempts finished.
+    pub cancelled: bool,
+    /// Wall-clock time of the whole run, including intervals.
+    pub elapsed_ms: u128,
+}
+
+/// Convert a duration to fractional milliseconds.
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[f64], pct: usize) -> f64 {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+/// Compute summary statistics, or `None` when there are no measurements.
+fn phase_stats(values: &[f64]) -> Option<PhaseStats> {
+    let count = u32::try_from(values.len()).ok().filter(|n| *n > 0)?;
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let n = f64::from(count);
+    let mean = sorted.iter().sum::<f64>() / n;
+    let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+    Some(PhaseStats {
+        count,
+        min_ms: sorted[0],
+        max_ms: sorted[sorted.len() - 1],
+        mean_ms: mean,
+        stddev_ms: variance.sqrt(),
+        p50_ms: percentile(&sorted, 50),
+        p95_ms: percentile(&sorted, 95),
+        p99_ms: percentile(&sorted, 99),
+    })
+}
+
+/// Split `[min, max]` into equal-width buckets and count the values in each.
+fn histogram(values: &[f64]) -> Vec<HistogramBucket> {
+    let Some(min) = values.iter().copied().reduce(f64::min) else {
+        return Vec::new();
+    };
+    let max = values.iter().copied().fold(min, f64::max);
+    if max <= min {
+        return vec![HistogramBucket {
+            lower_ms: min,
+            upper_ms: max,
+            count: u32::try_from(values.len()).unwrap_or(u32::MAX),
+        }];
+    }
+
+    let width = (max - min) / f64::from(HISTOGRAM_BUCKETS);
+    (0..HISTOGRAM_BUCKETS)
+        .map(|i| {
+            let lower = min + width * f64::from(i);
+            let is_last = i + 1 == HISTOGRAM_BUCKETS;
+            let upper = if is_last {
+                max
+            } else {
+                min + width * f64::from(i + 1)
+            };
+            let count = values
+                .iter()
+                .filter(|v| **v >= lower && (**v < upper || (is_last && **v <= upper)))
+                .count();
+            HistogramBucket {
+                lower_ms: lower,
+                upper_ms: upper,
+                count: u32::try_from(count).unwrap_or(u32::MAX),
+            }
+        })
+        .collect()
+}
+
+/// Name of the phase with the largest median.
+fn slowest_phase(phases: &[(&str, Option<&PhaseStats>)]) -> Option<String> {
+    phases
+        .iter()
+        .filter_map(|(name, stats)| stats.map(|s| (*name, s.p50_ms)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(name, _)| name.to_string())
+}
+
+/// Resolve `host:port`, returning the first address and the lookup time.
+async fn resolve(host: &str, port: u16, limit: Duration) -> Result<(SocketAddr, f64), String> {
+    let started = Instant::now();
+    let mut addrs = timeout(limit, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| "DNS resolution timed out".to_string())?
+        .map_err(|e| format!("DNS resolution: {e}"))?;
+    let addr = addrs
+        .next()
+        .ok_or_else(|| format!("No addresses found for {host}"))?;
+    Ok((addr, as_ms(started.elapsed())))
+}
+
+/// Run the connect and optional TLS phases against an already-resolved address.
+///
+/// Failures are recorded on the sample rather than returned, so a failed
+/// handshake still reports the connect time that preceded it.
+async fn connect_once(
+    sample: &mut LatencySample,
+    addr: SocketAddr,
+    limit: Duration,
+    tls: Option<(&TlsConnector, &ServerName<'static>)>,
+) {
+    let started = Instant::now();
+    let stream = match timeout(limit, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => {
+            sample.error = Some(format!("TCP connect: {e}"));
+            return;
+        }
+        Err(_) => {
+            sample.error = Some("TCP connect timed out".to_string());
+            return;
+        }
+    };
+    sample.connect_ms = Some(as_ms(started.elapsed()));
+
+    let Some((connector, server_name)) = tls else {
+        return;
+    };
+
+    let started = Instant::now();
+    match timeout(limit, connector.connect(server_name.clone(), stream)).await {
+        Ok(Ok(mut tls_stream)) => {
+            sample.tls_ms = Some(as_ms(started.elapsed()));
+            let _ = tls_stream.shutdown().await;
+        }
+        Ok(Err(e)) => sample.error = Some(format!("TLS handshake: {e}")),
+        Err(_) => sample.error = Some("TLS handshake timed out".to_string()),
+    }
+}
+
+/// Benchmark TCP (and optionally TLS) connection setup against `host:port`.
+///
+/// Each attempt is sent on `on_sample` as it completes. Cancel through the
+/// shared `cancel_op` command with the same `op_id`; statistics over the
+/// attempts that did run are still returned.
+///
+/// # Errors
+///
+/// Returns an error string for an invalid attempt count or SNI, or when the
+/// TLS client configuration cannot be built.
+#[tauri::command]
+pub async fn tcp_latency_benchmark(
+    op_id: String,
+    req: TcpLatencyRequest,
+    on_sample: Channel<LatencySample>,
+    state: tauri::State<'_, crate::cancellation::OperationRegistry>,
+) -> Result<TcpLatencyResult, String> {
+    let token = Arc::new(CancellationToken::new());
+    state.register(op_id.clone(), token.clone());
+    let result = run_benchmark(req, &on_sample, &token).await;
+    state.remove(&op_id);
+    result
+}
+
+async fn run_benchmark(
+    req: TcpLatencyRequest,
+    on_sample: &Channel<LatencySample>,
+    token: &CancellationToken,
+) -> Result<TcpLatencyResult, String> {
+    if req.count == 0 || req.count > MAX_ATTEMPTS {
+        return Err(format!(
+            "Attempt count must be between 1 and {MAX_ATTEMPTS}"
+        ));
+    }
+
+    let started = Instant::now();
+    let limit = Duration::from_millis(u64::from(req.timeout_ms));
+    let interval = Duration::from_millis(u64::from(req.interval_ms));
+
+    let tls = if req.tls {
+        let sni = resolve_sni(req.sni.as_deref(), &req.host);
+        let server_name = ServerName::try_from(sni).map_err(|e| format!("Invalid SNI: {e}"))?;
+        Some((
+            TlsConnector::from(Arc::new(build_client_config()?)),
+            server_name,
+        ))
+    } else {
+        None
+    };
+
+    // IP literals need no resolution; the DNS phase is omitted entirely.
+    let literal = req
+        .host
+        .parse::<std::net::IpAddr>()
+        .ok()
+        .map(|ip| SocketAddr::new(ip, req.port));
+    let mut cached = literal;
+
+    let mut samples: Vec<LatencySample> = Vec::with_capacity(req.count as usize);
+    let mut cancelled = false;
+
+    for index in 0..req.count {
+        if index > 0 && !interval.is_zero() {
+            tokio::select! {
+                () = token.cancelled() => {}
+                () = tokio::time::sleep(interval) => {}
+            }
+        }
+        if token.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+
+        let mut sample = LatencySample {
+            index,
+            remote_addr: None,
+            dns_ms: None,
+            connect_ms: None,
+            tls_ms: None,
+            total_ms: 0.0,
+            error: None,
+        };
+
+        let addr = match cached.filter(|_| literal.is_some() || !req.resolve_each_attempt) {
+            Some(addr) => Some(addr),
+            None => match resolve(&req.host, req.port, limit).await {
+                Ok((addr, dns_ms)) => {
+                    sample.dns_ms = Some(dns_ms);
+                    cached = Some(addr);
+                    Some(addr)
+                }
+                Err(e) => {
+                    sample.error = Some(e);
+                    None
+                }
+            },
+        };
+
+        if let Some(addr) = addr {
+            sample.remote_addr = Some(addr.to_string());
+            let tls_ref = tls.as_ref().map(|(c, n)| (c, n));
+            tokio::select! {
+                () = token.cancelled() => {
+                    cancelled = true;
+                    break;
+                }
+                () = connect_once(&mut sample, addr, limit, tls_ref) => {}
+            }
+        }
+
+        sample.total_ms = [sample.dns_ms, sample.connect_ms, sample.tls_ms]
+            .into_iter()
+            .flatten()
+            .sum();
+        let _ = on_sample.send(sample.clone());
+        samples.push(sample);
+    }
+
+    let ok: Vec<&LatencySample> = samples.iter().filter(|s| s.error.is_none()).collect();
+    let collect = |f: fn(&LatencySample) -> Option<f64>| -> Vec<f64> {
+        samples.iter().filter_map(f).collect()
+    };
+    let totals: Vec<f64> = ok.iter().map(|s| s.total_ms).collect();
+
+    let dns = phase_stats(&collect(|s| s.dns_ms));
+    let connect = phase_stats(&collect(|s| s.connect_ms));
+    let tls_stats = phase_stats(&collect(|s| s.tls_ms));
+    let slowest = slowest_phase(&[
+        ("dns", dns.as_ref()),
+        ("connect", connect.as_ref()),
+        ("tls", tls_stats.as_ref()),
+    ]);
+
+    let attempts = u32::try_from(samples.len()).unwrap_or(u32::MAX);
+    let successes = u32::try_from(ok.len()).unwrap_or(u32::MAX);
+
+    Ok(TcpLatencyResult {
+        host: req.host,
+        port: req.port,
+        attempts,
+        successes,
+        failures: attempts - successes,
+        dns,
+        connect,
+        tls: tls_stats,
+        total: phase_stats(&totals),
+        histogram: histogram(&totals),
+        slowest_phase: slowest,
+        cancelled,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert!((percentile(&values, 50) - 50.0).abs() < f64::EPSILON);
+        assert!((percentile(&values, 95) - 95.0).abs() < f64::EPSILON);
+        assert!((percentile(&values, 99) - 99.0).abs() < f64::EPSILON);
+        assert!((percentile(&[7.0], 99) - 7.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn phase_stats_computes_mean_and_spread() {
+        let stats = phase_stats(&[4.0, 2.0, 6.0, 8.0]).unwrap();
+        assert_eq!(stats.count, 4);
+        assert!((stats.min_ms - 2.0).abs() < f64::EPSILON);
+        assert!((stats.max_ms - 8.0).abs() < f64::EPSILON);
+        assert!((stats.mean_ms - 5.0).abs() < f64::EPSILON);
+        assert!((stats.stddev_ms - 5.0_f64.sqrt()).abs() < 1e-9);
+        assert!(phase_stats(&[]).is_none());
+    }
+
+    #[test]
+    fn histogram_counts_every_value_once() {
+        let values = [1.0, 1.5, 2.0, 9.0, 10.0];
+        let buckets = histogram(&values);
+        assert_eq!(buckets.len(), HISTOGRAM_BUCKETS as usize);
+        assert_eq!(buckets.iter().map(|b| b.count).sum::<u32>(), 5);
+        assert_eq!(buckets.last().unwrap().count, 1);
+    }
+
+    #[test]
+    fn histogram_collapses_identical_values() {
+        let buckets = histogram(&[3.0, 3.0, 3.0]);
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].count, 3);
+        assert!(histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn slowest_phase_picks_largest_median() {
+        let fast = phase_stats(&[1.0, 2.0]).unwrap();
+        let slow = phase_stats(&[40.0, 50.0]).unwrap();
+        assert_eq!(
+            slowest_phase(&[
+                ("dns", Some(&fast)),
+                ("connect", None),
+                ("tls", Some(&slow))
+            ]),
+            Some("tls".to_string())
+        );
+        assert_eq!(slowest_phase(&[("dns", None)]), None);
+    }
+}
@@ -0,0 +1,851 @@
+//! TLS handshake capability analyzer command.
+//!
+//! Where the TLS inspector performs one handshake and reports what was
+//! negotiated, this analyzer probes a server with many `ClientHello`
+//! variations to map everything it is willing to negotiate: protocol
+//! versions, cipher suites per version, ALPN protocols, and session
+//! resumption. The findings are graded against a modern baseline.
+//!
+//! rustls only speaks TLS 1.2 and 1.3, and refuses weak suites, so TLS
+//! 1.0–1.2 are probed with a hand-built `ClientHello` and only the
+//! `ServerHello` is parsed; the handshake is abandoned before any key
+//! exchange. TLS 1.3, ALPN, and resumption use real rustls handshakes.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, HandshakeKind, SupportedCipherSuite};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
+
+use crate::tls_inspect::{build_client_config, build_restricted_client_config, resolve_sni};
+
+/// Record content type for handshake messages
+const CONTENT_HANDSHAKE: u8 = 0x16;
+
+/// Record content type for alerts
+const CONTENT_ALERT: u8 = 0x15;
+
+/// Handshake message type of a `ServerHello`
+const HANDSHAKE_SERVER_HELLO: u8 = 0x02;
+
+/// Maximum TLS record payload length
+const MAX_RECORD_LEN: usize = 16_384 + 2048;
+
+/// How long to wait for post-handshake session tickets before reconnecting
+const TICKET_WAIT: Duration = Duration::from_millis(500);
+
+/// ALPN protocols probed when the request lists none
+const DEFAULT_ALPN: [&str; 3] = ["h2", "http/1.1", "http/1.0"];
+
+/// Legacy protocol versions probed with a hand-built `ClientHello`
+const LEGACY_VERSIONS: [(u16, &str); 3] = [
+    (0x0301, "TLSv1_0"),
+    (0x0302, "TLSv1_1"),
+    (0x0303, "TLSv1_2"),
+];
+
+/// How a cipher suite measures against a modern baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CipherStrength {
+    /// AEAD with forward secrecy.
+    Recommended,
+    /// AEAD with finite-field DHE key exchange.
+    Secure,
+    /// CBC mode or no forward secrecy.
+    Weak,
+    /// Broken: RC4, DES/3DES, NULL, export-grade, or anonymous.
+    Insecure,
+}
+
+/// Cipher suites offered in legacy probes: IANA code, name, strength.
+const LEGACY_SUITES: &[(u16, &str, CipherStrength)] = &[
+    (
+        0xC02B,
+        "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+        CipherStrength::Recommended,
+    ),
+    (
+        0xC02C,
+        "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+        CipherStrength::Recommended,
+    ),
+    (
+        0xC02F,
+        "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+        CipherStrength::Recommended,
+    ),
+    (
+        0xC030,
+        "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+        CipherStrength::Recommended,
+    ),
+    (
+        0xCCA9,
+        "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+        CipherStrength::Recommended,
+    ),
+    (
+        0xCCA8,
+        "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        CipherStrength::Recommended,
+    ),
+    (
+        0x009E,
+        "TLS_DHE_RSA_WITH_AES_128_GCM_SHA256",
+        CipherStrength::Secure,
+    ),
+    (
+        0x009F,
+        "TLS_DHE_RSA_WITH_AES_256_GCM_SHA384",
+        CipherStrength::Secure,
+    ),
+    (
+        0xCCAA,
+        "TLS_DHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        CipherStrength::Secure,
+    ),
+    (
+        0xC023,
+        "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA256",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC024,
+        "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA384",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC027,
+        "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC028,
+        "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC009,
+        "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC00A,
+        "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC013,
+        "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC014,
+        "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0x0067,
+        "TLS_DHE_RSA_WITH_AES_128_CBC_SHA256",
+        CipherStrength::Weak,
+    ),
+    (
+        0x006B,
+        "TLS_DHE_RSA_WITH_AES_256_CBC_SHA256",
+        CipherStrength::Weak,
+    ),
+    (
+        0x0033,
+        "TLS_DHE_RSA_WITH_AES_128_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0x0039,
+        "TLS_DHE_RSA_WITH_AES_256_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0x009C,
+        "TLS_RSA_WITH_AES_128_GCM_SHA256",
+        CipherStrength::Weak,
+    ),
+    (
+        0x009D,
+        "TLS_RSA_WITH_AES_256_GCM_SHA384",
+        CipherStrength::Weak,
+    ),
+    (
+        0x003C,
+        "TLS_RSA_WITH_AES_128_CBC_SHA256",
+        CipherStrength::Weak,
+    ),
+    (
+        0x003D,
+        "TLS_RSA_WITH_AES_256_CBC_SHA256",
+        CipherStrength::Weak,
+    ),
+    (0x002F, "TLS_RSA_WITH_AES_128_CBC_SHA", CipherStrength::Weak),
+    (0x0035, "TLS_RSA_WITH_AES_256_CBC_SHA", CipherStrength::Weak),
+    (
+        0x0041,
+        "TLS_RSA_WITH_CAMELLIA_128_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0x0084,
+        "TLS_RSA_WITH_CAMELLIA_256_CBC_SHA",
+        CipherStrength::Weak,
+    ),
+    (
+        0xC012,
+        "TLS_ECDHE_RSA_WITH_3DES_EDE_CBC_SHA",
+        CipherStrength::Insecure,
+    ),
+    (
+        0x0016,
+        "TLS_DHE_RSA_WITH_3DES_EDE_CBC_SHA",
+        CipherStrength::Insecure,
+    ),
+    (
+        0x000A,
+        "TLS_RSA_WITH_3DES_EDE_CBC_SHA",
+        CipherStrength::Insecure,
+    ),
+    (
+        0xC011,
+        "TLS_ECDHE_RSA_WITH_RC4_128_SHA",
+        CipherStrength::Insecure,
+    ),
+    (0x0005, "TLS_RSA_WITH_RC4_128_SHA", CipherStrength::Insecure),
+    (0x0004, "TLS_RSA_WITH_RC4_128_MD5", CipherStrength::Insecure),
+    (0x0009, "TLS_RSA_WITH_DES_CBC_SHA", CipherStrength::Insecure),
+    (
+        0x0003,
+        "TLS_RSA_EXPORT_WITH_RC4_40_MD5",
+        CipherStrength::Insecure,
+    ),
+    (
+        0x0008,
+        "TLS_RSA_EXPORT_WITH_DES40_CBC_SHA",
+        CipherStrength::Insecure,
+    ),
+    (
+        0x0034,
+        "TLS_DH_anon_WITH_AES_128_CBC_SHA",
+        CipherStrength::Insecure,
+    ),
+    (
+        0x0018,
+        "TLS_DH_anon_WITH_RC4_128_MD5",
+        CipherStrength::Insecure,
+    ),
+    (0x003B, "TLS_RSA_WITH_NULL_SHA256", CipherStrength::Insecure),
+    (0x0002, "TLS_RSA_WITH_NULL_SHA", CipherStrength::Insecure),
+    (0x0001, "TLS_RSA_WITH_NULL_MD5", CipherStrength::Insecure),
+];
+
+/// Request payload sent from the frontend.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsCapabilitiesRequest {
+    /// Target host (DNS name or IP literal).
+    pub host: String,
+    /// Target TCP port.
+    pub port: u16,
+    /// Optional SNI override. Defaults to `host` when `None` or empty.
+    pub sni: Option<String>,
+    /// ALPN protocols to test. Defaults to `h2`, `http/1.1`, and `http/1.0`.
+    #[serde(default)]
+    pub alpn: Vec<String>,
+    /// Per-connection timeout in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// Support for one protocol version.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionSupport {
+    /// Version name (e.g. `TLSv1_2`).
+    pub version: String,
+    /// Whether the server negotiated this version.
+    pub supported: bool,
+}
+
+/// A cipher suite the server accepted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CipherSupport {
+    /// Protocol version it was accepted under.
+    pub version: String,
+    /// IANA cipher suite name.
+    pub name: String,
+    /// Strength against a modern baseline.
+    pub strength: CipherStrength,
+}
+
+/// Support for one ALPN protocol.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlpnSupport {
+    /// ALPN protocol identifier.
+    pub protocol: String,
+    /// Whether the server selected it when offered alone.
+    pub supported: bool,
+}
+
+/// Session resumption test result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumptionSupport {
+    /// Whether the second handshake resumed the first session.
+    pub supported: bool,
+    /// Protocol version of the resumed handshake.
+    pub version: Option<String>,
+    /// Failure description when either handshake failed.
+    pub error: Option<String>,
+}
+
+/// Full capability report.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsCapabilitiesResult {
+    /// Echo of the requested host.
+    pub host: String,
+    /// Echo of the requested port.
+    pub port: u16,
+    /// Effective SNI sent in every `ClientHello`.
+    pub sni: String,
+    /// Protocol version support, oldest first.
+    pub versions: Vec<VersionSupport>,
+    /// Accepted cipher suites, in server preference order per version.
+    pub cipher_suites: Vec<CipherSupport>,
+    /// ALPN protocol support.
+    pub alpn: Vec<AlpnSupport>,
+    /// Session resumption support.
+    pub resumption: ResumptionSupport,
+    /// Letter grade from `A+` to `F`.
+    pub grade: String,
+    /// Human-readable reasons behind the grade.
+    pub findings: Vec<String>,
+    /// Wall-clock time of the whole analysis.
+    pub elapsed_ms: u128,
+}
+
+/// Server reply to a hand-built `ClientHello`.
+#[derive(Debug, PartialEq, Eq)]
+enum HelloReply {
+    /// `ServerHello` with the negotiated version and cipher suite.
+    Accepted { version: u16, cipher: u16 },
+    /// Alert, unparseable reply, or closed connection.
+    Rejected,
+}
+
+/// Append `len` as a big-endian two-byte length field.
+fn push_len16(out: &mut Vec<u8>, len: usize) {
+    out.extend_from_slice(&u16::try_from(len).unwrap_or(u16::MAX).to_be_bytes());
+}
+
+/// Append a TLS extension with a two-byte length prefix.
+fn push_extension(out: &mut Vec<u8>, kind: u16, data: &[u8]) {
+    out.extend_from_slice(&kind.to_be_bytes());
+    push_len16(out, data.len());
+    out.extend_from_slice(data);
+}
+
+/// Build a `ClientHello` record offering `version` and `suites`.
+///
+/// The SNI extension is omitted for IP literals, which RFC 6066 forbids.
+fn build_client_hello(version: u16, suites: &[u16], sni: &str) -> Vec<u8> {
+    let mut extensions = Vec::new();
+    if sni.parse::<std::net::IpAddr>().is_err() {
+        let name = sni.as_bytes();
+        let mut data = Vec::with_capacity(name.len() + 5);
+        push_len16(&mut data, name.len() + 3);
+        data.push(0);
+        push_len16(&mut data, name.len());
+        data.extend_from_slice(name);
+        push_extension(&mut extensions, 0x0000, &data);
+    }
+    // supported_groups: x25519, secp256r1, secp384r1
+    push_extension(&mut extensions, 0x000a, &[0, 6, 0, 0x1d, 0, 0x17, 0, 0x18]);
+    // ec_point_formats: uncompressed
+    push_extension(&mut extensions, 0x000b, &[1, 0]);
+    // signature_algorithms: ECDSA, RSA-PSS, and PKCS#1 with SHA-2, then SHA-1
+    push_extension(
+        &mut extensions,
+        0x000d,
+        &[
+            0, 18, 4, 3, 5, 3, 6, 3, 8, 4, 8, 5, 8, 6, 4, 1, 5, 1, 6, 1, 2, 1,
+        ],
+    );
+    // extended_master_secret
+    push_extension(&mut extensions, 0x0017, &[]);
+    // renegotiation_info (empty, initial handshake)
+    push_extension(&mut extensions, 0xff01, &[0]);
+
+    let mut body = Vec::new();
+    body.extend_from_slice(&version.to_be_bytes());
+    body.extend_from_slice(&rand::random::<[u8; 32]>());
+    body.push(0); // empty session ID
+    push_len16(&mut body, suites.len() * 2);
+    for suite in suites {
+        body.extend_from_slice(&suite.to_be_bytes());
+    }
+    body.extend_from_slice(&[1, 0]); // null compression only
+    push_len16(&mut body, extensions.len());
+    body.extend_from_slice(&extensions);
+
+    let mut handshake = vec![0x01];
+    handshake.extend_from_slice(&u32::try_from(body.len()).unwrap_or(u32::MAX).to_be_bytes()[1..]);
+    handshake.extend_from_slice(&body);
+
+    let mut record = vec![CONTENT_HANDSHAKE, 0x03, 0x01];
+    push_len16(&mut record, handshake.len());
+    record.extend_from_slice(&handshake);
+    record
+}
+
+/// Parse the first record a server sent in reply to a `ClientHello`.
+fn parse_server_hello(content_type: u8, payload: &[u8]) -> HelloReply {
+    if content_type != CONTENT_HANDSHAKE || payload.first() != Some(&HANDSHAKE_SERVER_HELLO) {
+        return HelloReply::Rejected;
+    }
+    // type(1) + length(3), then version(2) + random(32)
+    let Some(&session_id_len) = payload.get(38) else {
+        return HelloReply::Rejected;
+    };
+    let cipher_at = 39 + usize::from(session_id_len);
+    match (payload.get(4..6), payload.get(cipher_at..cipher_at + 2)) {
+        (Some(version), Some(cipher)) => HelloReply::Accepted {
+            version: u16::from_be_bytes([version[0], version[1]]),
+            cipher: u16::from_be_bytes([cipher[0], cipher[1]]),
+        },
+        _ => HelloReply::Rejected,
+    }
+}
+
+/// Send a hand-built `ClientHello` and read the server's first record.
+async fn legacy_hello(
+    addr: &str,
+    sni: &str,
+    version: u16,
+    suites: &[u16],
+    limit: Duration,
+) -> HelloReply {
+    let exchange = async {
+        let mut stream = TcpStream::connect(addr).await.ok()?;
+        stream
+            .write_all(&build_client_hello(version, suites, sni))
+            .await
+            .ok()?;
+        let mut header = [0u8; 5];
+        stream.read_exact(&mut header).await.ok()?;
+        let len = usize::from(u16::from_be_bytes([header[3], header[4]]));
+        if header[0] == CONTENT_ALERT || len > MAX_RECORD_LEN {
+            return Some(HelloReply::Rejected);
+        }
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload).await.ok()?;
+        Some(parse_server_hello(header[0], &payload))
+    };
+    timeout(limit, exchange)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(HelloReply::Rejected)
+}
+
+/// Enumerate the suites accepted under a legacy `version` in server
+/// preference order, by repeatedly removing the suite the server chose.
+async fn enumerate_legacy(
+    addr: &str,
+    sni: &str,
+    version: u16,
+    version_name: &str,
+    limit: Duration,
+    token: &CancellationToken,
+) -> Result<Vec<CipherSupport>, String> {
+    let mut remaining: Vec<u16> = LEGACY_SUITES.iter().map(|(code, ..)| *code).collect();
+    let mut accepted = Vec::new();
+    while !remaining.is_empty() {
+        if token.is_cancelled() {
+            return Err("TLS analysis cancelled".to_string());
+        }
+        let HelloReply::Accepted {
+            version: negotiated,
+            cipher,
+        } = legacy_hello(addr, sni, version, &remaining, limit).await
+        else {
+            break;
+        };
+        let Some(pos) = remaining.iter().position(|c| *c == cipher) else {
+            break;
+        };
+        if negotiated != version {
+            break;
+        }
+        remaining.remove(pos);
+        if let Some((_, name, strength)) = LEGACY_SUITES.iter().find(|(code, ..)| *code == cipher) {
+            accepted.push(CipherSupport {
+                version: version_name.to_string(),
+                name: (*name).to_string(),
+                strength: *strength,
+            });
+        }
+    }
+    Ok(accepted)
+}
+
+/// Complete a rustls handshake with `config`, returning the stream.
+async fn handshake(
+    addr: &str,
+    server_name: &ServerName<'static>,
+    config: Arc<ClientConfig>,
+    limit: Duration,
+) -> Result<tokio_rustls::client::TlsStream<TcpStream>, String> {
+    let connect = async {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|e| format!("TCP connect: {e}"))?;
+        TlsConnector::from(config)
+            .connect(server_name.clone(), stream)
+            .await
+            .map_err(|e| format!("TLS handshake: {e}"))
+    };
+    timeout(limit, connect)
+        .await
+        .map_err(|_| "TLS handshake timed out".to_string())?
+}
+
+/// Probe each TLS 1.3 suite rustls implements, one at a time.
+async fn enumerate_tls13(
+    addr: &str,
+    server_name: &ServerName<'static>,
+    limit: Duration,
+    token: &CancellationToken,
+) -> Result<Vec<CipherSupport>, String> {
+    let mut accepted = Vec::new();
+    let suites = rustls::crypto::ring::ALL_CIPHER_SUITES
+        .iter()
+        .filter(|suite| matches!(suite, SupportedCipherSuite::Tls13(_)));
+    for suite in suites {
+        if token.is_cancelled() {
+            return Err("TLS analysis cancelled".to_string());
+        }
+        let config = build_restricted_client_config(vec![*suite], &[&rustls::version::TLS13])?;
+        if let Ok(mut stream) = handshake(addr, server_name, Arc::new(config), limit).await {
+            let _ = stream.shutdown().await;
+            accepted.push(CipherSupport {
+                version: "TLSv1_3".to_string(),
+                name: format!("{:?}", suite.suite()),
+                strength: CipherStrength::Recommended,
+            });
+        }
+    }
+    Ok(accepted)
+}
+
+/// Offer each ALPN protocol alone and record whether the server selects it.
+async fn probe_alpn(
+    addr: &str,
+    server_name: &ServerName<'static>,
+    protocols: &[String],
+    limit: Duration,
+) -> Result<Vec<AlpnSupport>, String> {
+    let mut results = Vec::with_capacity(protocols.len());
+    for protocol in protocols {
+        let mut config = build_client_config()?;
+        config.alpn_protocols = vec![protocol.as_bytes().to_vec()];
+        let supported = match handshake(addr, server_name, Arc::new(config), limit).await {
+            Ok(mut stream) => {
+                let selected = stream.get_ref().1.alpn_protocol() == Some(protocol.as_bytes());
+                let _ = stream.shutdown().await;
+                selected
+            }
+            Err(_) => false,
+        };
+        results.push(AlpnSupport {
+            protocol: protocol.clone(),
+            supported,
+        });
+    }
+    Ok(results)
+}
+
+/// Connect twice with a shared session cache and check whether the second
+/// handshake resumed the first session.
+async fn probe_resumption(
+    addr: &str,
+    server_name: &ServerName<'static>,
+    limit: Duration,
+) -> Result<ResumptionSupport, String> {
+    let config = Arc::new(build_client_config()?);
+    let mut result = ResumptionSupport {
+        supported: false,
+        version: None,
+        error: None,
+    };
+
+    match handshake(addr, server_name, config.clone(), limit).await {
+        Ok(mut first) => {
+            // TLS 1.3 tickets arrive after the handshake; reading lets rustls
+            // process them. Most servers send nothing else unprompted.
+            let mut buf = [0u8; 1024];
+            let _ = timeout(TICKET_WAIT, first.read(&mut buf)).await;
+            let _ = first.shutdown().await;
+        }
+        Err(e) => {
+            result.error = Some(e);
+            return Ok(result);
+        }
+    }
+
+    match handshake(addr, server_name, config, limit).await {
+        Ok(mut second) => {
+            let session = second.get_ref().1;
+            result.supported = session.handshake_kind() == Some(HandshakeKind::Resumed);
+            result.version = session.protocol_version().map(|v| format!("{v:?}"));
+            let _ = second.shutdown().await;
+        }
+        Err(e) => result.error = Some(e),
+    }
+    Ok(result)
+}
+
+/// Grade the capabilities against a modern baseline.
+///
+/// TLS 1.3 with only AEAD, forward-secret suites earns `A+`. Missing TLS 1.3
+/// caps the grade at `A`, legacy versions or weak suites at `B`, and any
+/// insecure suite at `C`. A server without TLS 1.2 or 1.3 gets `F`.
+fn grade(
+    versions: &[VersionSupport],
+    ciphers: &[CipherSupport],
+    resumption: &ResumptionSupport,
+) -> (String, Vec<String>) {
+    let supports = |name: &str| versions.iter().any(|v| v.version == name && v.supported);
+    let mut findings = Vec::new();
+    let mut grade = "A+";
+
+    if !supports("TLSv1_2") && !supports("TLSv1_3") {
+        findings.push("Neither TLS 1.2 nor TLS 1.3 is supported".to_string());
+        return ("F".to_string(), findings);
+    }
+    if !supports("TLSv1_3") {
+        findings.push("TLS 1.3 is not supported".to_string());
+        grade = "A";
+    }
+    for legacy in ["TLSv1_0", "TLSv1_1"] {
+        if supports(legacy) {
+            findings.push(format!("Deprecated protocol {legacy} is enabled"));
+            grade = "B";
+        }
+    }
+    let weakest = ciphers.iter().map(|c| c.strength).max();
+    if weakest == Some(CipherStrength::Weak) {
+        findings.push("CBC-mode or non-forward-secret cipher suites are accepted".to_string());
+        grade = "B";
+    }
+    if weakest == Some(CipherStrength::Insecure) {
+        let names: Vec<&str> = ciphers
+            .iter()
+            .filter(|c| c.strength == CipherStrength::Insecure)
+            .map(|c| c.name.as_str())
+            .collect();
+        findings.push(format!(
+            "Insecure cipher suites accepted: {}",
+            names.join(", ")
+        ));
+        grade = "C";
+    }
+    if !resumption.supported {
+        findings.push("Session resumption is not supported".to_string());
+    }
+    (grade.to_string(), findings)
+}
+
+/// Map the TLS versions, cipher suites, ALPN protocols, and session
+/// resumption a server supports, and grade them.
+///
+/// Cancel through the shared `cancel_op` command with the same `op_id`.
+///
+/// # Errors
+///
+/// Returns an error string for an invalid SNI, an unreachable target, or
+/// cancellation.
+#[tauri::command]
+pub async fn tls_capabilities(
+    op_id: String,
+    req: TlsCapabilitiesRequest,
+    state: tauri::State<'_, crate::cancellation::OperationRegistry>,
+) -> Result<TlsCapabilitiesResult, String> {
+    let token = Arc::new(CancellationToken::new());
+    state.register(op_id.clone(), token.clone());
+    let result = run_analysis(req, &token).await;
+    state.remove(&op_id);
+    result
+}
+
+async fn run_analysis(
+    req: TlsCapabilitiesRequest,
+    token: &CancellationToken,
+) -> Result<TlsCapabilitiesResult, String> {
+    let started = Instant::now();
+    let limit = Duration::from_millis(u64::from(req.timeout_ms));
+    let sni = resolve_sni(req.sni.as_deref(), &req.host);
+    let server_name = ServerName::try_from(sni.clone()).map_err(|e| format!("Invalid SNI: {e}"))?;
+    let addr = format!("{}:{}", req.host, req.port);
+
+    timeout(limit, TcpStream::connect(&addr))
+        .await
+        .map_err(|_| "Connection timed out".to_string())?
+        .map_err(|e| format!("TCP connect: {e}"))?;
+
+    let mut versions = Vec::new();
+    let mut cipher_suites = Vec::new();
+    for (code, name) in LEGACY_VERSIONS {
+        let accepted = enumerate_legacy(&addr, &sni, code, name, limit, token).await?;
+        versions.push(VersionSupport {
+            version: name.to_string(),
+            supported: !accepted.is_empty(),
+        });
+        cipher_suites.extend(accepted);
+    }
+    let tls13 = enumerate_tls13(&addr, &server_name, limit, token).await?;
+    versions.push(VersionSupport {
+        version: "TLSv1_3".to_string(),
+        supported: !tls13.is_empty(),
+    });
+    cipher_suites.extend(tls13);
+
+    if token.is_cancelled() {
+        return Err("TLS analysis cancelled".to_string());
+    }
+    let protocols: Vec<String> = if req.alpn.is_empty() {
+        DEFAULT_ALPN.iter().map(|p| (*p).to_string()).collect()
+    } else {
+        req.alpn
+    };
+    let alpn = probe_alpn(&addr, &server_name, &protocols, limit).await?;
+    let resumption = probe_resumption(&addr, &server_name, limit).await?;
+    let (grade, findings) = grade(&versions, &cipher_suites, &resumption);
+
+    Ok(TlsCapabilitiesResult {
+        host: req.host,
+        port: req.port,
+        sni,
+        versions,
+        cipher_suites,
+        alpn,
+        resumption,
+        grade,
+        findings,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(supported: &[&str]) -> Vec<VersionSupport> {
+        ["TLSv1_0", "TLSv1_1", "TLSv1_2", "TLSv1_3"]
+            .iter()
+            .map(|v| VersionSupport {
+                version: (*v).to_string(),
+                supported: supported.contains(v),
+            })
+            .collect()
+    }
+
+    fn cipher(strength: CipherStrength) -> CipherSupport {
+        CipherSupport {
+            version: "TLSv1_2".to_string(),
+            name: "TLS_RSA_WITH_RC4_128_SHA".to_string(),
+            strength,
+        }
+    }
+
+    const RESUMED: ResumptionSupport = ResumptionSupport {
+        supported: true,
+        version: None,
+        error: None,
+    };
+
+    #[test]
+    fn client_hello_has_consistent_lengths() {
+        let record = build_client_hello(0x0303, &[0xC02F, 0x002F], "example.com");
+        assert_eq!(record[0], CONTENT_HANDSHAKE);
+        let record_len = usize::from(u16::from_be_bytes([record[3], record[4]]));
+        assert_eq!(record_len, record.len() - 5);
+        assert_eq!(record[5], 0x01);
+        let hs_len =
+            (usize::from(record[6]) << 16) | (usize::from(record[7]) << 8) | usize::from(record[8]);
+        assert_eq!(hs_len, record_len - 4);
+        assert_eq!(&record[9..11], &[0x03, 0x03]);
+        // The SNI host name is embedded verbatim.
+        assert!(record.windows(11).any(|w| w == b"example.com"));
+    }
+
+    #[test]
+    fn client_hello_omits_sni_for_ip_literals() {
+        let with_ip = build_client_hello(0x0303, &[0xC02F], "192.0.2.1");
+        let with_name = build_client_hello(0x0303, &[0xC02F], "a.example");
+        assert!(with_ip.len() < with_name.len());
+    }
+
+    #[test]
+    fn parses_server_hello() {
+        let mut payload = vec![HANDSHAKE_SERVER_HELLO, 0, 0, 0x46, 0x03, 0x02];
+        payload.extend_from_slice(&[0u8; 32]);
+        payload.push(4);
+        payload.extend_from_slice(&[1, 2, 3, 4]);
+        payload.extend_from_slice(&[0xC0, 0x14, 0x00]);
+        assert_eq!(
+            parse_server_hello(CONTENT_HANDSHAKE, &payload),
+            HelloReply::Accepted {
+                version: 0x0302,
+                cipher: 0xC014
+            }
+        );
+        assert_eq!(
+            parse_server_hello(CONTENT_ALERT, &[2, 40]),
+            HelloReply::Rejected
+        );
+        assert_eq!(
+            parse_server_hello(CONTENT_HANDSHAKE, &payload[..20]),
+            HelloReply::Rejected
+        );
+    }
+
+    #[test]
+    fn grades_modern_configuration_highest() {
+        let ciphers = [cipher(CipherStrength::Recommended)];
+        let (grade, findings) = grade(&versions(&["TLSv1_2", "TLSv1_3"]), &ciphers, &RESUMED);
+        assert_eq!(grade, "A+");
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn grades_legacy_and_insecure_configurations_down() {
+        let ciphers = [cipher(CipherStrength::Recommended)];
+        assert_eq!(
+            grade(
+                &versions(&["TLSv1_0", "TLSv1_2", "TLSv1_3"]),
+                &ciphers,
+                &RESUMED
+            )
+            .0,
+            "B"
+        );
+        let ciphers = [cipher(CipherStrength::Insecure)];
+        assert_eq!(grade(&versions(&["TLSv1_2"]), &ciphers, &RESUMED).0, "C");
+        assert_eq!(grade(&versions(&["TLSv1_0"]), &ciphers, &RESUMED).0, "F");
+    }
+}
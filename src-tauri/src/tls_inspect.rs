@@ -13,8 +13,12 @@ use std::time::{Duration, Instant};
 
 use base64::Engine as _;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
 use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
-use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use rustls::{
+    ClientConfig, DigitallySignedStruct, SignatureScheme, SupportedCipherSuite,
+    SupportedProtocolVersion,
+};
 use serde::{Deserialize, Serialize};
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
@@ -113,10 +117,25 @@ impl ServerCertVerifier for AcceptAllVerifier {
 
 /// Build a permissive TLS client config so the inspector can observe broken
 /// certificates without the default verifier rejecting them.
-fn build_client_config() -> Result<ClientConfig, String> {
-    let provider = Arc::new(rustls::crypto::ring::default_provider());
+pub(crate) fn build_client_config() -> Result<ClientConfig, String> {
+    build_restricted_client_config(
+        rustls::crypto::ring::DEFAULT_CIPHER_SUITES.to_vec(),
+        rustls::DEFAULT_VERSIONS,
+    )
+}
+
+/// Build a permissive TLS client config that only offers the given cipher
+/// suites and protocol versions, for probing what a server accepts.
+pub(crate) fn build_restricted_client_config(
+    cipher_suites: Vec<SupportedCipherSuite>,
+    versions: &[&'static SupportedProtocolVersion],
+) -> Result<ClientConfig, String> {
+    let provider = Arc::new(CryptoProvider {
+        cipher_suites,
+        ..rustls::crypto::ring::default_provider()
+    });
     let mut config = ClientConfig::builder_with_provider(provider)
-        .with_safe_default_protocol_versions()
+        .with_protocol_versions(versions)
         .map_err(|e| format!("Failed to build TLS config: {e}"))?
         .dangerous()
         .with_custom_certificate_verifier(Arc::new(AcceptAllVerifier))
@@ -126,7 +145,7 @@ fn build_client_config() -> Result<ClientConfig, String> {
 }
 
 /// Resolve the effective SNI value, falling back to the host when missing or empty.
-fn resolve_sni(sni: Option<&str>, host: &str) -> String {
+pub(crate) fn resolve_sni(sni: Option<&str>, host: &str) -> String {
     sni.map(str::trim)
         .filter(|s| !s.is_empty())
         .map_or_else(|| host.to_string(), str::to_string)
@@ -0,0 +1,302 @@
+//! Update availability check against GitHub releases
+//!
+//! Queries the project's GitHub releases, picks the newest release for the
+//! requested channel, and compares it with the running version so the
+//! frontend can show an update banner. Nothing is downloaded or installed;
+//! the result carries the release notes and a platform-matched download
+//! link for the user to open.
+//!
+//! Channels:
+//! - `stable`: full releases only
+//! - `beta`: also pre-releases with a semver tag (`v1.2.0-beta.1`);
+//!   the rolling `nightly` tag has no version and is ignored
+
+use std::time::Duration;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+/// GitHub releases API endpoint
+const RELEASES_URL: &str = "https://api.github.com/repos/seijikohara/kogu/releases?per_page=30";
+
+/// Default request timeout in milliseconds
+const DEFAULT_TIMEOUT_MS: u32 = 10_000;
+
+/// Maximum release notes length in characters
+const MAX_NOTES_CHARS: usize = 20_000;
+
+/// Release channel to check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Full releases only.
+    #[default]
+    Stable,
+    /// Full releases and versioned pre-releases.
+    Beta,
+}
+
+/// Result of an update check.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    /// Channel that was checked.
+    pub channel: UpdateChannel,
+    /// Version of the running application.
+    pub current_version: String,
+    /// Newest version on the channel, if any release was found.
+    pub latest_version: Option<String>,
+    /// Whether `latest_version` is newer than `current_version`.
+    pub update_available: bool,
+    /// Release title.
+    pub release_name: Option<String>,
+    /// Release notes (Markdown).
+    pub release_notes: Option<String>,
+    /// Publication time (RFC 3339).
+    pub published_at: Option<String>,
+    /// Release page URL.
+    pub release_url: Option<String>,
+    /// Installer matching this platform and architecture, if one was found.
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    name: Option<String>,
+    body: Option<String>,
+    html_url: String,
+    published_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+fn parse_tag(tag: &str) -> Option<Version> {
+    Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()
+}
+
+/// Newest release on `channel` together with its parsed version.
+fn latest_release(
+    releases: Vec<GithubRelease>,
+    channel: UpdateChannel,
+) -> Option<(Version, GithubRelease)> {
+    releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter(|r| channel == UpdateChannel::Beta || !r.prerelease)
+        .filter_map(|r| parse_tag(&r.tag_name).map(|v| (v, r)))
+        .filter(|(v, _)| channel == UpdateChannel::Beta || v.pre.is_empty())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+}
+
+/// Installer suffixes in order of preference for `os`.
+fn installer_suffixes(os: &str) -> &'static [&'static str] {
+    match os {
+        "macos" => &[".dmg"],
+        "windows" => &["-setup.exe", ".msi"],
+        "linux" => &[".AppImage", ".deb", ".rpm"],
+        _ => &[],
+    }
+}
+
+/// Architecture spellings used by the release bundler for `arch`.
+fn arch_tokens(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "x86_64" => &["x64", "x86_64", "amd64"],
+        "aarch64" => &["aarch64", "arm64"],
+        _ => &[],
+    }
+}
+
+/// Pick the installer asset for `os`/`arch`, preferring an exact
+/// architecture match and falling back to any asset with the right suffix.
+fn pick_asset<'a>(assets: &'a [GithubAsset], os: &str, arch: &str) -> Option<&'a GithubAsset> {
+    let tokens = arch_tokens(arch);
+    installer_suffixes(os).iter().find_map(|suffix| {
+        let candidates: Vec<&GithubAsset> =
+            assets.iter().filter(|a| a.name.ends_with(suffix)).collect();
+        candidates
+            .iter()
+            .find(|a| tokens.iter().any(|t| a.name.contains(t)))
+            .or_else(|| candidates.first())
+            .copied()
+    })
+}
+
+fn build_info(
+    channel: UpdateChannel,
+    current: &Version,
+    latest: Option<(Version, GithubRelease)>,
+) -> UpdateInfo {
+    let Some((version, release)) = latest else {
+        return UpdateInfo {
+            channel,
+            current_version: current.to_string(),
+            latest_version: None,
+            update_available: false,
+            release_name: None,
+            release_notes: None,
+            published_at: None,
+            release_url: None,
+            download_url: None,
+        };
+    };
+    let download_url = pick_asset(
+        &release.assets,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+    .map(|a| a.browser_download_url.clone());
+    UpdateInfo {
+        channel,
+        current_version: current.to_string(),
+        update_available: version > *current,
+        latest_version: Some(version.to_string()),
+        release_name: release.name,
+        release_notes: release
+            .body
+            .map(|b| b.chars().take(MAX_NOTES_CHARS).collect()),
+        published_at: release.published_at,
+        release_url: Some(release.html_url),
+        download_url,
+    }
+}
+
+/// Check the release feed for a newer version on `channel`.
+///
+/// # Errors
+///
+/// Returns an error string when the release feed cannot be fetched or
+/// parsed.
+#[tauri::command]
+pub async fn check_for_updates(
+    channel: Option<UpdateChannel>,
+    timeout_ms: Option<u32>,
+) -> Result<UpdateInfo, String> {
+    let channel = channel.unwrap_or_default();
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))
+        .map_err(|e| format!("Invalid application version: {e}"))?;
+
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("kogu/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_millis(u64::from(
+            timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+        )))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+    let response = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch release feed: {e}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Release feed returned HTTP {status}"));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read release feed: {e}"))?;
+    let releases: Vec<GithubRelease> =
+        serde_json::from_str(&body).map_err(|e| format!("Invalid release feed: {e}"))?;
+
+    Ok(build_info(
+        channel,
+        &current,
+        latest_release(releases, channel),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool, assets: &[&str]) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag.to_string(),
+            name: Some(format!("Kogu {tag}")),
+            body: Some("notes".to_string()),
+            html_url: format!("https://example.com/{tag}"),
+            published_at: None,
+            draft: false,
+            prerelease,
+            assets: assets
+                .iter()
+                .map(|n| GithubAsset {
+                    name: (*n).to_string(),
+                    browser_download_url: format!("https://example.com/{n}"),
+                })
+                .collect(),
+        }
+    }
+
+    fn feed() -> Vec<GithubRelease> {
+        vec![
+            release("nightly", true, &[]),
+            release("v0.2.0-beta.1", true, &[]),
+            release("v0.1.0", false, &[]),
+            release("v0.0.6", false, &[]),
+        ]
+    }
+
+    #[test]
+    fn selects_latest_per_channel() {
+        let (stable, _) = latest_release(feed(), UpdateChannel::Stable).unwrap();
+        assert_eq!(stable.to_string(), "0.1.0");
+        let (beta, _) = latest_release(feed(), UpdateChannel::Beta).unwrap();
+        assert_eq!(beta.to_string(), "0.2.0-beta.1");
+        assert!(latest_release(vec![release("nightly", true, &[])], UpdateChannel::Beta).is_none());
+    }
+
+    #[test]
+    fn compares_against_current_version() {
+        let current = Version::parse("0.1.0").unwrap();
+        let info = build_info(
+            UpdateChannel::Stable,
+            &current,
+            latest_release(feed(), UpdateChannel::Stable),
+        );
+        assert!(!info.update_available);
+        let info = build_info(
+            UpdateChannel::Beta,
+            &current,
+            latest_release(feed(), UpdateChannel::Beta),
+        );
+        assert!(info.update_available);
+        assert_eq!(info.release_notes.as_deref(), Some("notes"));
+    }
+
+    #[test]
+    fn picks_platform_installer() {
+        let assets = release(
+            "v0.1.0",
+            false,
+            &[
+                "Kogu_0.1.0_aarch64.dmg",
+                "Kogu_0.1.0_x64.dmg",
+                "Kogu_0.1.0_x64_en-US.msi",
+                "Kogu_0.1.0_x64-setup.exe",
+                "Kogu_0.1.0_amd64.deb",
+                "Kogu_0.1.0_amd64.AppImage",
+            ],
+        )
+        .assets;
+        let name = |os, arch| pick_asset(&assets, os, arch).map(|a| a.name.as_str());
+        assert_eq!(name("macos", "aarch64"), Some("Kogu_0.1.0_aarch64.dmg"));
+        assert_eq!(name("macos", "x86_64"), Some("Kogu_0.1.0_x64.dmg"));
+        assert_eq!(name("windows", "x86_64"), Some("Kogu_0.1.0_x64-setup.exe"));
+        assert_eq!(name("linux", "x86_64"), Some("Kogu_0.1.0_amd64.AppImage"));
+        assert_eq!(name("freebsd", "x86_64"), None);
+    }
+}
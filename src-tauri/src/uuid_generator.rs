@@ -0,0 +1,444 @@
+//! Bulk UUID generation and inspection Tauri commands.
+//!
+//! Generates versions 1, 3, 4, 5, and 7 (RFC 9562) in batches of up to
+//! [`MAX_COUNT`], which the renderer-side `uuid` package cannot produce
+//! without stalling the UI. Version 1 takes a random multicast node ID
+//! rather than a MAC address, and both time-based versions stay strictly
+//! increasing within a batch: v1 advances the timestamp by one tick, v7
+//! increments its random bits as a counter (RFC 9562, section 6.2).
+//!
+//! `inspect_uuid` reports the version, variant, and for v1/v6/v7 the
+//! embedded timestamp.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use md5::{Digest as Md5Digest, Md5};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use uuid::Uuid;
+
+/// Most UUIDs generated in one call.
+pub const MAX_COUNT: usize = 100_000;
+
+/// 100-nanosecond ticks from the Gregorian epoch (1582-10-15) to the
+/// UNIX epoch.
+const GREGORIAN_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+
+/// Bits of a v7 UUID after the timestamp that are not version or variant.
+const V7_RANDOM_BITS: u32 = 74;
+
+/// UUID version to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UuidVersion {
+    V1,
+    V3,
+    V4,
+    V5,
+    V7,
+    Nil,
+}
+
+/// Text form of the generated UUIDs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UuidStyle {
+    /// `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    #[default]
+    Hyphenated,
+    /// `67e5504410b1426f9247bb680e5fe0c8`
+    Simple,
+    /// `{67e55044-10b1-426f-9247-bb680e5fe0c8}`
+    Braced,
+    /// `urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8`
+    Urn,
+}
+
+/// Formatting of the generated UUIDs.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UuidFormat {
+    #[serde(default)]
+    pub style: UuidStyle,
+    /// Upper-case hex digits; a URN keeps its lower-case prefix.
+    #[serde(default)]
+    pub uppercase: bool,
+}
+
+/// Fields decoded from a UUID.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UuidInspection {
+    /// Canonical lower-case hyphenated form.
+    pub uuid: String,
+    /// Version nibble; meaningful only for the RFC 9562 variant.
+    pub version: u8,
+    /// Human-readable version, e.g. "Time-ordered (v7)".
+    pub version_name: String,
+    /// "RFC 9562", "NCS", "Microsoft", or "Future".
+    pub variant: String,
+    /// Embedded timestamp of v1, v6, and v7, in UNIX milliseconds.
+    pub unix_millis: Option<i64>,
+    /// Clock sequence of v1 and v6.
+    pub clock_sequence: Option<u16>,
+    /// Node ID of v1 and v6, as colon-separated hex.
+    pub node: Option<String>,
+}
+
+/// Generate `count` UUIDs of `version`.
+///
+/// # Arguments
+///
+/// - `version` — `v1`, `v3`, `v4`, `v5`, `v7`, or `nil`.
+/// - `count` — number of UUIDs, 1 to [`MAX_COUNT`].
+/// - `namespace` — namespace UUID, required for v3 and v5.
+/// - `name` — name hashed into the namespace, required for v3 and v5.
+/// - `format` — text form; hyphenated lower case when omitted.
+///
+/// # Errors
+///
+/// Returns a `String` for a count out of range, a missing or invalid
+/// namespace or name, or a failed random source.
+#[tauri::command]
+pub async fn generate_uuids(
+    version: UuidVersion,
+    count: usize,
+    namespace: Option<String>,
+    name: Option<String>,
+    format: Option<UuidFormat>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        let uuids = generate(
+            version,
+            count,
+            namespace.as_deref(),
+            name.as_deref(),
+            unix_nanos(),
+        )?;
+        let format = format.unwrap_or_default();
+        Ok(uuids.iter().map(|uuid| encode(uuid, format)).collect())
+    })
+    .await
+    .map_err(|e| format!("uuid worker join failed: {e}"))?
+}
+
+/// Decode the version, variant, and embedded fields of a UUID in any of
+/// the hyphenated, simple, braced, or URN forms.
+///
+/// # Errors
+///
+/// Returns a `String` when `uuid` does not parse.
+#[tauri::command]
+pub fn inspect_uuid(uuid: &str) -> Result<UuidInspection, String> {
+    let uuid = Uuid::try_parse(uuid.trim()).map_err(|e| format!("Invalid UUID: {e}"))?;
+    Ok(inspect(&uuid))
+}
+
+fn generate(
+    version: UuidVersion,
+    count: usize,
+    namespace: Option<&str>,
+    name: Option<&str>,
+    now_nanos: u128,
+) -> Result<Vec<Uuid>, String> {
+    if !(1..=MAX_COUNT).contains(&count) {
+        return Err(format!("Count must be between 1 and {MAX_COUNT}"));
+    }
+    let rng = SystemRandom::new();
+    let random = |len: usize| -> Result<Vec<u8>, String> {
+        let mut bytes = vec![0; len];
+        rng.fill(&mut bytes)
+            .map_err(|_| "Failed to read random bytes".to_string())?;
+        Ok(bytes)
+    };
+    match version {
+        UuidVersion::Nil => Ok(vec![Uuid::nil(); count]),
+        UuidVersion::V4 => {
+            let bytes = random(count * 16)?;
+            Ok(bytes
+                .chunks_exact(16)
+                .map(|chunk| {
+                    let mut random = [0; 16];
+                    random.copy_from_slice(chunk);
+                    uuid::Builder::from_random_bytes(random).into_uuid()
+                })
+                .collect())
+        }
+        UuidVersion::V3 | UuidVersion::V5 => {
+            let namespace = namespace
+                .filter(|namespace| !namespace.trim().is_empty())
+                .ok_or("Namespace is required for v3 and v5")?;
+            let namespace = Uuid::try_parse(namespace.trim())
+                .map_err(|e| format!("Namespace must be a valid UUID: {e}"))?;
+            let name = name
+                .filter(|name| !name.is_empty())
+                .ok_or("Name is required for v3 and v5")?;
+            let uuid = name_based(version, &namespace, name);
+            // Name-based UUIDs are deterministic; repeat for the requested shape.
+            Ok(vec![uuid; count])
+        }
+        UuidVersion::V1 => {
+            let seed = random(8)?;
+            let clock_sequence = u16::from_be_bytes([seed[0], seed[1]]) & 0x3FFF;
+            let mut node = [0; 6];
+            node.copy_from_slice(&seed[2..8]);
+            // A random node ID sets the multicast bit (RFC 9562, section 6.10).
+            node[0] |= 0x01;
+            let start = u64::try_from(now_nanos / 100).unwrap_or(u64::MAX) + GREGORIAN_OFFSET;
+            Ok((0..count as u64)
+                .map(|i| v1(start + i, clock_sequence, node))
+                .collect())
+        }
+        UuidVersion::V7 => {
+            let seed = random(16)?;
+            let mut millis = u64::try_from(now_nanos / 1_000_000).unwrap_or(u64::MAX);
+            let mut counter =
+                u128::from_be_bytes(seed.try_into().unwrap_or_default()) >> (128 - V7_RANDOM_BITS);
+            // Leave room to count up before the next millisecond.
+            counter >>= 1;
+            let mut uuids = Vec::with_capacity(count);
+            for _ in 0..count {
+                uuids.push(v7(millis, counter));
+                counter += 1;
+                if counter >> V7_RANDOM_BITS != 0 {
+                    millis += 1;
+                    counter = 0;
+                }
+            }
+            Ok(uuids)
+        }
+    }
+}
+
+fn name_based(version: UuidVersion, namespace: &Uuid, name: &str) -> Uuid {
+    let mut digest = [0; 16];
+    if version == UuidVersion::V3 {
+        let mut hasher = Md5::new();
+        hasher.update(namespace.as_bytes());
+        hasher.update(name.as_bytes());
+        digest.copy_from_slice(&hasher.finalize());
+        uuid::Builder::from_md5_bytes(digest).into_uuid()
+    } else {
+        let mut hasher = Sha1::new();
+        hasher.update(namespace.as_bytes());
+        hasher.update(name.as_bytes());
+        digest.copy_from_slice(&hasher.finalize()[..16]);
+        uuid::Builder::from_sha1_bytes(digest).into_uuid()
+    }
+}
+
+/// v1 from 100-nanosecond Gregorian `ticks`
+fn v1(ticks: u64, clock_sequence: u16, node: [u8; 6]) -> Uuid {
+    let low = u32::try_from(ticks & 0xFFFF_FFFF).unwrap_or_default();
+    let mid = u16::try_from((ticks >> 32) & 0xFFFF).unwrap_or_default();
+    let high = u16::try_from((ticks >> 48) & 0x0FFF).unwrap_or_default() | 0x1000;
+    let [seq_high, seq_low] = (clock_sequence | 0x8000).to_be_bytes();
+    let tail = [
+        seq_high, seq_low, node[0], node[1], node[2], node[3], node[4], node[5],
+    ];
+    Uuid::from_fields(low, mid, high, &tail)
+}
+
+/// v7 from UNIX `millis` and a 74-bit `counter` in the random bits
+fn v7(millis: u64, counter: u128) -> Uuid {
+    let rand_a = (counter >> 62) & 0x0FFF;
+    let rand_b = counter & 0x3FFF_FFFF_FFFF_FFFF;
+    let value = (u128::from(millis & 0xFFFF_FFFF_FFFF) << 80)
+        | (0x7 << 76)
+        | (rand_a << 64)
+        | (0b10 << 62)
+        | rand_b;
+    Uuid::from_u128(value)
+}
+
+fn encode(uuid: &Uuid, format: UuidFormat) -> String {
+    let mut buffer = Uuid::encode_buffer();
+    let text: &str = match (format.style, format.uppercase) {
+        (UuidStyle::Hyphenated, false) => uuid.hyphenated().encode_lower(&mut buffer),
+        (UuidStyle::Hyphenated, true) => uuid.hyphenated().encode_upper(&mut buffer),
+        (UuidStyle::Simple, false) => uuid.simple().encode_lower(&mut buffer),
+        (UuidStyle::Simple, true) => uuid.simple().encode_upper(&mut buffer),
+        (UuidStyle::Braced, false) => uuid.braced().encode_lower(&mut buffer),
+        (UuidStyle::Braced, true) => uuid.braced().encode_upper(&mut buffer),
+        (UuidStyle::Urn, false) => uuid.urn().encode_lower(&mut buffer),
+        (UuidStyle::Urn, true) => uuid.urn().encode_upper(&mut buffer),
+    };
+    text.to_string()
+}
+
+fn inspect(uuid: &Uuid) -> UuidInspection {
+    let bytes = uuid.as_bytes();
+    let version = bytes[6] >> 4;
+    let variant = match bytes[8] {
+        0x00..=0x7F => "NCS",
+        0x80..=0xBF => "RFC 9562",
+        0xC0..=0xDF => "Microsoft",
+        _ => "Future",
+    };
+    let rfc = variant == "RFC 9562";
+    let version_name = if uuid.is_nil() {
+        "Nil".to_string()
+    } else if uuid.is_max() {
+        "Max".to_string()
+    } else if !rfc {
+        format!("Non-RFC variant ({variant})")
+    } else {
+        let kind = match version {
+            1 => "Time-based",
+            2 => "DCE Security",
+            3 => "Name-based, MD5",
+            4 => "Random",
+            5 => "Name-based, SHA-1",
+            6 => "Reordered time-based",
+            7 => "Time-ordered",
+            8 => "Custom",
+            _ => "Unknown",
+        };
+        format!("{kind} (v{version})")
+    };
+
+    let (high, low) = uuid.as_u64_pair();
+    let gregorian = |ticks: u64| {
+        i64::try_from(ticks / 10_000).unwrap_or(i64::MAX)
+            - i64::try_from(GREGORIAN_OFFSET / 10_000).unwrap_or_default()
+    };
+    let unix_millis = match (rfc, version) {
+        (true, 1) => {
+            let ticks = ((high & 0x0FFF) << 48) | (((high >> 16) & 0xFFFF) << 32) | (high >> 32);
+            Some(gregorian(ticks))
+        }
+        (true, 6) => {
+            let ticks = ((high >> 16) << 12) | (high & 0x0FFF);
+            Some(gregorian(ticks))
+        }
+        (true, 7) => Some(i64::try_from(high >> 16).unwrap_or(i64::MAX)),
+        _ => None,
+    };
+    let (clock_sequence, node) = if rfc && matches!(version, 1 | 6) {
+        let sequence = u16::try_from((low >> 48) & 0x3FFF).unwrap_or_default();
+        let node = bytes[10..]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(":");
+        (Some(sequence), Some(node))
+    } else {
+        (None, None)
+    };
+
+    UuidInspection {
+        uuid: uuid.hyphenated().to_string(),
+        version,
+        version_name,
+        variant: variant.to_string(),
+        unix_millis,
+        clock_sequence,
+        node,
+    }
+}
+
+fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMESPACE_DNS: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+    /// 2022-02-22 19:22:22 UTC, the time of the RFC 9562 examples
+    const EXAMPLE_MILLIS: i64 = 1_645_557_742_000;
+
+    #[test]
+    fn test_name_based_vectors() {
+        let v3 = generate(
+            UuidVersion::V3,
+            2,
+            Some(NAMESPACE_DNS),
+            Some("www.example.com"),
+            0,
+        )
+        .unwrap();
+        assert_eq!(v3[0].to_string(), "5df41881-3aed-3515-88a7-2f4a814cf09e");
+        assert_eq!(v3[0], v3[1]);
+        let v5 = generate(
+            UuidVersion::V5,
+            1,
+            Some(NAMESPACE_DNS),
+            Some("www.example.com"),
+            0,
+        )
+        .unwrap();
+        assert_eq!(v5[0].to_string(), "2ed6657d-e927-568b-95e1-2665a8aea6a2");
+        assert!(generate(UuidVersion::V5, 1, Some("dns"), Some("x"), 0).is_err());
+        assert!(generate(UuidVersion::V3, 1, Some(NAMESPACE_DNS), None, 0).is_err());
+    }
+
+    #[test]
+    fn test_time_based_are_ordered_and_decode() {
+        let nanos = u128::try_from(EXAMPLE_MILLIS).unwrap() * 1_000_000;
+        for version in [UuidVersion::V1, UuidVersion::V7] {
+            let uuids = generate(version, 10_000, None, None, nanos).unwrap();
+            let inspection = inspect(&uuids[0]);
+            assert_eq!(inspection.unix_millis, Some(EXAMPLE_MILLIS));
+            assert_eq!(inspection.variant, "RFC 9562");
+            if version == UuidVersion::V7 {
+                assert!(uuids.windows(2).all(|pair| pair[0] < pair[1]));
+            } else {
+                assert_eq!(inspection.version, 1);
+                assert!(inspection
+                    .node
+                    .unwrap()
+                    .starts_with(|c: char| c.is_ascii_hexdigit()));
+                let mut sorted = uuids.clone();
+                sorted.sort_unstable();
+                sorted.dedup();
+                assert_eq!(sorted.len(), uuids.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_inspect_rfc_examples() {
+        let v1 = inspect_uuid("C232AB00-9414-11EC-B3C8-9F6BDECED846").unwrap();
+        assert_eq!(v1.version_name, "Time-based (v1)");
+        assert_eq!(v1.unix_millis, Some(EXAMPLE_MILLIS));
+        assert_eq!(v1.clock_sequence, Some(0x33C8));
+        assert_eq!(v1.node.as_deref(), Some("9f:6b:de:ce:d8:46"));
+        let v6 = inspect_uuid("urn:uuid:1EC9414C-232A-6B00-B3C8-9F6BDECED846").unwrap();
+        assert_eq!(v6.unix_millis, Some(EXAMPLE_MILLIS));
+        let v7 = inspect_uuid("{017F22E2-79B0-7CC3-98C4-DC0C0C07398F}").unwrap();
+        assert_eq!(v7.unix_millis, Some(EXAMPLE_MILLIS));
+        assert_eq!(
+            inspect_uuid("00000000-0000-0000-0000-000000000000")
+                .unwrap()
+                .version_name,
+            "Nil"
+        );
+        assert!(inspect_uuid("not-a-uuid").is_err());
+    }
+
+    #[test]
+    fn test_formats() {
+        let uuid = Uuid::try_parse(NAMESPACE_DNS).unwrap();
+        let format = |style, uppercase| encode(&uuid, UuidFormat { style, uppercase });
+        assert_eq!(
+            format(UuidStyle::Simple, true),
+            "6BA7B8109DAD11D180B400C04FD430C8"
+        );
+        assert_eq!(
+            format(UuidStyle::Braced, false),
+            "{6ba7b810-9dad-11d1-80b4-00c04fd430c8}"
+        );
+        assert_eq!(
+            format(UuidStyle::Urn, true),
+            "urn:uuid:6BA7B810-9DAD-11D1-80B4-00C04FD430C8"
+        );
+        assert!(generate(UuidVersion::V4, MAX_COUNT + 1, None, None, 0).is_err());
+        let v4 = generate(UuidVersion::V4, 3, None, None, 0).unwrap();
+        assert!(v4.iter().all(|uuid| uuid.get_version_num() == 4));
+    }
+}
@@ -0,0 +1,208 @@
+//! Framed stdin/stdout protocol between the app and the worker sidecar
+//!
+//! Every message is an 8-byte header followed by the body: the magic
+//! `KW`, the protocol version, the body encoding (`J` for JSON), and the
+//! body length as a big-endian `u32`. Unlike the original single-line
+//! JSON protocol, bodies may contain newlines and run to
+//! [`MAX_FRAME_LEN`] bytes.
+//!
+//! A message that does not start with the magic is read as one legacy
+//! JSON line, so a new worker still serves an older app and a new app
+//! still understands an older worker during the transition.
+//!
+//! This file is compiled into both the app library and the worker binary.
+
+use std::io::{self, BufRead, Write};
+
+/// First two header bytes
+const MAGIC: [u8; 2] = *b"KW";
+
+/// Header version this build writes and accepts
+const VERSION: u8 = 1;
+
+/// Body encoding tag for JSON
+const ENCODING_JSON: u8 = b'J';
+
+/// Header size in bytes
+const HEADER_LEN: usize = 8;
+
+/// Largest accepted body (64 MiB)
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// One message read from the peer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Message {
+    /// Body of a framed message.
+    Framed(Vec<u8>),
+    /// A legacy newline-terminated JSON line, without the newline.
+    Line(Vec<u8>),
+}
+
+impl Message {
+    /// The JSON body, whichever way it arrived.
+    pub fn body(&self) -> &[u8] {
+        match self {
+            Self::Framed(body) | Self::Line(body) => body,
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Write `body` as one framed JSON message and flush.
+///
+/// # Errors
+///
+/// Returns an error when `body` exceeds [`MAX_FRAME_LEN`] or the write
+/// fails.
+pub fn write_frame(writer: &mut impl Write, body: &[u8]) -> io::Result<()> {
+    if body.len() > MAX_FRAME_LEN {
+        return Err(invalid(format!(
+            "Message of {} bytes exceeds the {MAX_FRAME_LEN}-byte limit",
+            body.len()
+        )));
+    }
+    let len = u32::try_from(body.len()).map_err(|e| invalid(e.to_string()))?;
+    let mut header = [0u8; HEADER_LEN];
+    header[..2].copy_from_slice(&MAGIC);
+    header[2] = VERSION;
+    header[3] = ENCODING_JSON;
+    header[4..].copy_from_slice(&len.to_be_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(body)?;
+    writer.flush()
+}
+
+/// Validate a frame header and return its body length.
+fn body_len(header: [u8; HEADER_LEN]) -> io::Result<usize> {
+    if header[..2] != MAGIC {
+        return Err(invalid("Invalid frame header".to_string()));
+    }
+    if header[2] != VERSION {
+        return Err(invalid(format!(
+            "Unsupported protocol version {}",
+            header[2]
+        )));
+    }
+    if header[3] != ENCODING_JSON {
+        return Err(invalid(format!(
+            "Unsupported body encoding {:?}",
+            char::from(header[3])
+        )));
+    }
+    let len = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let len = usize::try_from(len).map_err(|e| invalid(e.to_string()))?;
+    if len > MAX_FRAME_LEN {
+        return Err(invalid(format!(
+            "Frame of {len} bytes exceeds the {MAX_FRAME_LEN}-byte limit"
+        )));
+    }
+    Ok(len)
+}
+
+/// Length of the frame at the start of `buffer`, header included, once
+/// all of it has arrived.
+///
+/// A long-lived peer keeps its output open between messages, so the app
+/// collects output until this returns a length instead of waiting for
+/// end of input. The worker binary never reads this way.
+///
+/// # Errors
+///
+/// Returns an error when the buffered header is not a valid frame header.
+#[allow(dead_code)]
+pub fn frame_len(buffer: &[u8]) -> io::Result<Option<usize>> {
+    let Some(header) = buffer.first_chunk::<HEADER_LEN>() else {
+        return Ok(None);
+    };
+    let len = HEADER_LEN + body_len(*header)?;
+    Ok((buffer.len() >= len).then_some(len))
+}
+
+/// Read the next message, framed or legacy.
+///
+/// # Errors
+///
+/// Returns an error on end of input, an unknown version or encoding, an
+/// oversized frame, or a truncated body.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Message> {
+    let first = reader.fill_buf()?.first().copied();
+    match first {
+        None => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "No input from peer",
+        )),
+        Some(byte) if byte == MAGIC[0] => {
+            let mut header = [0u8; HEADER_LEN];
+            reader.read_exact(&mut header)?;
+            let len = body_len(header)?;
+            let mut body = vec![0u8; len];
+            reader.read_exact(&mut body)?;
+            Ok(Message::Framed(body))
+        }
+        Some(_) => {
+            let mut line = Vec::new();
+            reader.read_until(b'\n', &mut line)?;
+            while line.last().is_some_and(|b| matches!(b, b'\n' | b'\r')) {
+                line.pop();
+            }
+            Ok(Message::Line(line))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_frames_with_newlines() {
+        let body = br#"{"key":"-----BEGIN PGP-----\nabc\n-----END PGP-----"}"#;
+        let mut wire = Vec::new();
+        write_frame(&mut wire, body).unwrap();
+        write_frame(&mut wire, b"{}").unwrap();
+        assert_eq!(&wire[..4], b"KW\x01J");
+
+        let mut reader = &wire[..];
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Message::Framed(body.to_vec())
+        );
+        assert_eq!(read_message(&mut reader).unwrap().body(), b"{}");
+        assert!(read_message(&mut reader).is_err());
+    }
+
+    #[test]
+    fn reads_legacy_lines_and_rejects_bad_frames() {
+        let mut reader = &b"{\"type\":\"bcrypt\"}\r\n"[..];
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Message::Line(b"{\"type\":\"bcrypt\"}".to_vec())
+        );
+
+        let mut oversized = b"KW\x01J".to_vec();
+        oversized.extend_from_slice(&u32::MAX.to_be_bytes());
+        assert!(read_message(&mut &oversized[..]).is_err());
+
+        let truncated = b"KW\x01J\x00\x00\x00\x09{}";
+        assert!(read_message(&mut &truncated[..]).is_err());
+
+        let cbor = b"KW\x01C\x00\x00\x00\x00";
+        assert!(read_message(&mut &cbor[..]).is_err());
+    }
+
+    #[test]
+    fn measures_frames_as_they_arrive() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"{\"success\":true}").unwrap();
+        assert_eq!(frame_len(&wire[..5]).unwrap(), None);
+        assert_eq!(frame_len(&wire[..wire.len() - 1]).unwrap(), None);
+        assert_eq!(frame_len(&wire).unwrap(), Some(wire.len()));
+
+        wire.extend_from_slice(b"KW");
+        assert_eq!(frame_len(&wire).unwrap(), Some(wire.len() - 2));
+        assert!(frame_len(b"{\"success\":true}\n").is_err());
+    }
+}
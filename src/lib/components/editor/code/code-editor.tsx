@@ -23,10 +23,13 @@ import {
 	FlaskConical,
 	FolderOpen,
 	HardDrive,
+	Info,
 	ListTree,
+	type LucideIcon,
 	Rows3,
 	TextCursorInput,
 	Trash2,
+	TriangleAlert,
 } from 'lucide-react';
 import { LogicalPosition } from '@tauri-apps/api/dpi';
 import { Menu, MenuItem, PredefinedMenuItem } from '@tauri-apps/api/menu';
@@ -52,8 +55,10 @@ import {
 	type AstLanguage,
 	type AstNode,
 	type AstParseError,
+	type AstSeverity,
 	buildLineToPathMap,
 	buildPathToLineMap,
+	cancelAstParse,
 	findLineByPath,
 	findPathByLine,
 	type LineToPathMap,
@@ -413,26 +418,54 @@ interface ParseErrorsListProps {
 	readonly errors: readonly AstParseError[];
 }
 
+const SEVERITY_STYLES: Record<
+	AstSeverity,
+	{ readonly title: string; readonly icon: LucideIcon; readonly box: string; readonly text: string }
+> = {
+	error: {
+		title: 'Syntax Error',
+		icon: CircleAlert,
+		box: 'border-destructive/50 bg-destructive/10 text-destructive',
+		text: 'text-destructive/80',
+	},
+	warning: {
+		title: 'Warning',
+		icon: TriangleAlert,
+		box: 'border-warning/40 bg-warning/10 text-warning',
+		text: 'text-warning/80',
+	},
+	info: {
+		title: 'Note',
+		icon: Info,
+		box: 'border-info/40 bg-info/10 text-info',
+		text: 'text-info/80',
+	},
+};
+
 function ParseErrorsList({ errors }: ParseErrorsListProps) {
 	return (
 		<div className="flex flex-col gap-2">
-			{errors.map((error) => (
-				<div
-					key={`${error.range?.start.line ?? 0}:${error.range?.start.column ?? 0}-${error.message}`}
-					className="flex items-start gap-2 rounded-md border border-destructive/50 bg-destructive/10 p-3 text-sm text-destructive"
-				>
-					<CircleAlert className="mt-0.5 h-4 w-4 shrink-0" />
-					<div className="flex flex-col gap-1">
-						<span className="font-medium">Syntax Error</span>
-						<span className="text-destructive/80">{error.message}</span>
-						{error.range ? (
-							<span className="text-xs text-muted-foreground">
-								Line {error.range.start.line}, Column {error.range.start.column}
-							</span>
-						) : null}
+			{errors.map((error) => {
+				const style = SEVERITY_STYLES[error.severity ?? 'error'];
+				const Icon = style.icon;
+				return (
+					<div
+						key={`${error.range?.start.line ?? 0}:${error.range?.start.column ?? 0}-${error.message}`}
+						className={cn('flex items-start gap-2 rounded-md border p-3 text-sm', style.box)}
+					>
+						<Icon className="mt-0.5 h-4 w-4 shrink-0" />
+						<div className="flex flex-col gap-1">
+							<span className="font-medium">{style.title}</span>
+							<span className={style.text}>{error.message}</span>
+							{error.range ? (
+								<span className="text-xs text-muted-foreground">
+									Line {error.range.start.line}, Column {error.range.start.utf16Column}
+								</span>
+							) : null}
+						</div>
 					</div>
-				</div>
-			))}
+				);
+			})}
 		</div>
 	);
 }
@@ -538,7 +571,10 @@ export const CodeEditor = forwardRef<CodeEditorHandle, CodeEditorProps>(function
 		// Cancellation flag in a const ref so the cleanup closure can flip
 		// `.cancelled` without a `let` binding.
 		const lifecycle = { cancelled: false };
-		parseToAst(value, language).then((result) => {
+		// A superseded parse is abandoned on the backend too, so fast typing
+		// in a large document does not queue up stale results.
+		const opId = crypto.randomUUID();
+		parseToAst(value, language, opId).then((result) => {
 			if (lifecycle.cancelled) return;
 			setCurrentAst(result.ast);
 			setParseErrors(result.errors);
@@ -552,6 +588,7 @@ export const CodeEditor = forwardRef<CodeEditorHandle, CodeEditorProps>(function
 		});
 		return () => {
 			lifecycle.cancelled = true;
+			cancelAstParse(opId);
 		};
 	}, [value, editorMode]);
 
@@ -739,13 +776,17 @@ export const CodeEditor = forwardRef<CodeEditorHandle, CodeEditorProps>(function
 
 	const renderTreePanel = () => {
 		if (currentAst) {
+			// Diagnostics that came with a tree do not block it
 			return (
-				<TreeView
-					node={currentAst}
-					maxInitialDepth={maxTreeDepth}
-					selectedPath={selectedTreePath}
-					onSelect={handleTreeSelect}
-				/>
+				<div className="flex flex-col gap-2">
+					{parseErrors.length > 0 ? <ParseErrorsList errors={parseErrors} /> : null}
+					<TreeView
+						node={currentAst}
+						maxInitialDepth={maxTreeDepth}
+						selectedPath={selectedTreePath}
+						onSelect={handleTreeSelect}
+					/>
+				</div>
 			);
 		}
 		if (parseErrors.length > 0) {
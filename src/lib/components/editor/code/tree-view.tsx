@@ -62,6 +62,7 @@ const TYPE_ICON_MAP: Partial<Record<AstNodeType, LucideIcon>> = {
 	table: Table,
 	table_row: Minus,
 	horizontal_rule: Minus,
+	front_matter: FileCode,
 };
 
 interface TypeStyle {
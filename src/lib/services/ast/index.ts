@@ -1,18 +1,100 @@
 export {
+	analyzeOpenApi,
+	analyzeSql,
 	buildLineToPathMap,
 	buildPathToLineMap,
+	cancelAstParse,
+	convertDocument,
+	expandCompactAst,
+	expandNode,
+	extractSqlDependencies,
 	findLineByPath,
 	findPathByLine,
+	flattenJson,
+	formatDocument,
+	formatSql,
+	generateTypes,
+	getSubtreeText,
+	parseExplainPlan,
+	parseJsonFile,
+	parseTable,
+	parseTableFile,
 	parseToAst,
+	pathAtOffset,
+	profileDocument,
+	searchAst,
+	transformJson,
+	unflattenJson,
+	validateXml,
+	xpathQuery,
 } from './parser.js';
 export type {
+	ArrayHandling,
 	AstLanguage,
+	AstNamespace,
 	AstNode,
+	AstNodeSummary,
 	AstNodeType,
 	AstParseError,
 	AstParseResult,
 	AstPosition,
 	AstRange,
+	AstSearchMatch,
+	AstSearchQuery,
+	AstSearchResult,
+	AstSeverity,
+	CommaStyle,
+	CompactAst,
+	CompactAstParseResult,
+	ContainerSize,
+	ConvertOptions,
+	ConvertResult,
+	ConvertWarning,
+	DocumentProfile,
+	FieldNaming,
+	FlattenOptions,
+	FlattenResult,
+	FormatOptions,
+	FormatResult,
+	KeywordCase,
+	LengthBucket,
 	LineToPathMap,
+	NodeExpansion,
+	NumberStats,
+	OpenApiAnalysis,
+	Optionality,
+	PathAtOffset,
 	PathToLineMap,
+	PositionEncoding,
+	QuoteStyle,
+	SchemaKind,
+	SourceMapping,
+	SqlAnalysisResult,
+	SqlColumnRef,
+	SqlDependencies,
+	SqlDialect,
+	SqlFormatOptions,
+	SqlName,
+	SqlRule,
+	SqlTableRef,
+	SqlWarning,
+	StreamedParseResult,
+	StringStats,
+	SubtreeText,
+	TableAccess,
+	TableColumn,
+	TableModel,
+	TableOptions,
+	TargetLanguage,
+	TransformError,
+	TransformErrorKind,
+	TransformSummary,
+	TypeGenOptions,
+	TypeGenResult,
+	TypeInput,
+	XmlValidationError,
+	XmlValidationResult,
+	XPathMatch,
+	XPathNodeKind,
+	XPathResult,
 } from './types.js';
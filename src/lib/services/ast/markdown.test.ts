@@ -0,0 +1,198 @@
+import { describe, expect, it } from 'vitest';
+import { extractFrontMatter, parseMarkdownToAst, spliceFrontMatter } from './markdown';
+import type { AstNode, AstParseResult, AstPosition } from './types';
+
+const position = (line: number, column: number, utf16Column: number, offset: number): AstPosition => ({
+	line,
+	column,
+	utf16Column,
+	offset,
+});
+
+const childAt = (node: AstNode | null | undefined, index: number): AstNode | undefined =>
+	node?.children?.[index];
+
+// ============================================================================
+// extractFrontMatter
+// ============================================================================
+
+describe('extractFrontMatter', () => {
+	it('detects YAML front matter closed by ---', () => {
+		const text = '---\ntitle: Post\n---\n# Body\n';
+		const frontMatter = extractFrontMatter(text);
+
+		expect(frontMatter).toEqual({
+			language: 'yaml',
+			content: 'title: Post\n',
+			contentOffset: 4,
+			endLine: 3,
+			endOffset: 19,
+		});
+	});
+
+	it('detects YAML front matter closed by ...', () => {
+		const text = '---\ntitle: Post\n...\nBody\n';
+		const frontMatter = extractFrontMatter(text);
+
+		expect(frontMatter?.language).toBe('yaml');
+		expect(frontMatter?.content).toBe('title: Post\n');
+		expect(frontMatter?.endLine).toBe(3);
+	});
+
+	it('detects TOML front matter between +++ fences', () => {
+		const text = '+++\ntitle = "Post"\ndraft = true\n+++\nBody\n';
+		const frontMatter = extractFrontMatter(text);
+
+		expect(frontMatter?.language).toBe('toml');
+		expect(frontMatter?.content).toBe('title = "Post"\ndraft = true\n');
+		expect(frontMatter?.endLine).toBe(4);
+	});
+
+	it('does not close TOML front matter with ...', () => {
+		expect(extractFrontMatter('+++\ntitle = "Post"\n...\n')).toBeNull();
+	});
+
+	it('ends the block before the CR of a CRLF closing fence', () => {
+		const text = '---\r\ntitle: Post\r\n---\r\nBody\r\n';
+		const frontMatter = extractFrontMatter(text);
+
+		expect(frontMatter?.content).toBe('title: Post\r\n');
+		expect(frontMatter?.contentOffset).toBe(5);
+		expect(frontMatter?.endLine).toBe(3);
+		expect(frontMatter?.endOffset).toBe(text.indexOf('---\r\nBody') + 3);
+	});
+
+	it('returns null without an opening or closing fence', () => {
+		expect(extractFrontMatter('# Title\n---\n')).toBeNull();
+		expect(extractFrontMatter('---\ntitle: Post\n')).toBeNull();
+	});
+});
+
+// ============================================================================
+// parseMarkdownToAst
+// ============================================================================
+
+describe('parseMarkdownToAst', () => {
+	it('returns the front matter block it found', () => {
+		const text = '+++\ntitle = "Post"\n+++\n# Title\n';
+		const result = parseMarkdownToAst(text);
+
+		expect(result.frontMatter).toEqual(extractFrontMatter(text));
+		expect(childAt(result.ast, 0)?.type).toBe('front_matter');
+		expect(childAt(result.ast, 0)?.label).toBe('Front Matter (TOML)');
+	});
+
+	it('counts offsets after non-ASCII text in bytes', () => {
+		const text = '---\ntitle: "café"\n---\n# Ünïcode\n';
+		const result = parseMarkdownToAst(text);
+
+		const frontMatter = childAt(result.ast, 0);
+		expect(frontMatter?.range.end).toEqual(position(3, 1, 1, 22));
+		const heading = childAt(result.ast, 1);
+		expect(heading?.range.start).toEqual(position(4, 1, 1, 23));
+		expect(heading?.range.end.offset).toBe(23 + '# Ünïcode'.length + 2);
+		expect(result.ast?.range.end.offset).toBe(new TextEncoder().encode(text).length);
+	});
+
+	it('parses CRLF documents after the front matter', () => {
+		const result = parseMarkdownToAst('---\r\ntitle: Post\r\n---\r\nBody\r\n');
+
+		expect(childAt(result.ast, 0)?.range.end).toEqual(position(3, 1, 1, 21));
+		expect(childAt(result.ast, 1)?.range.start).toEqual(position(4, 1, 1, 23));
+	});
+});
+
+// ============================================================================
+// spliceFrontMatter
+// ============================================================================
+
+describe('spliceFrontMatter', () => {
+	const splice = (text: string, parsed: AstParseResult): AstParseResult => {
+		const { frontMatter, ...markdown } = parseMarkdownToAst(text);
+		if (!frontMatter) throw new Error('expected front matter');
+		return spliceFrontMatter(text, markdown, frontMatter, parsed);
+	};
+
+	it('moves paths below $.frontMatter and lines below the opening fence', () => {
+		const parsed: AstParseResult = {
+			ast: {
+				type: 'object',
+				path: '$',
+				label: '{} (1 properties)',
+				range: { start: position(1, 1, 1, 0), end: position(2, 1, 1, 6) },
+				children: [
+					{
+						type: 'number',
+						path: '$.a',
+						label: 'a',
+						range: { start: position(1, 1, 1, 0), end: position(1, 5, 5, 4) },
+					},
+				],
+			},
+			errors: [],
+		};
+		const spliced = splice('---\na: 1\n---\n', parsed);
+
+		const node = childAt(childAt(spliced.ast, 0), 0);
+		expect(node?.path).toBe('$.frontMatter.a');
+		expect(node?.range).toEqual({ start: position(2, 1, 1, 4), end: position(2, 5, 5, 8) });
+	});
+
+	it('keeps byte and UTF-16 columns after non-ASCII text apart', () => {
+		// `key` follows the line `title: "café"`; the closing quote ends at
+		// byte column 15 but UTF-16 column 14.
+		const text = '---\ntitle: "café"\nkey: 1\n---\nnaïve body\n';
+		const parsed: AstParseResult = {
+			ast: {
+				type: 'object',
+				path: '$',
+				label: '{} (2 properties)',
+				range: { start: position(1, 1, 1, 0), end: position(3, 1, 1, 22) },
+				children: [
+					{
+						type: 'string',
+						path: '$.title',
+						label: 'title',
+						range: { start: position(1, 1, 1, 0), end: position(1, 15, 14, 14) },
+					},
+					{
+						type: 'number',
+						path: '$.key',
+						label: 'key',
+						range: { start: position(2, 1, 1, 15), end: position(2, 7, 7, 21) },
+					},
+				],
+			},
+			errors: [
+				{
+					message: 'warning',
+					range: { start: position(2, 6, 6, 20), end: position(2, 7, 7, 21) },
+				},
+			],
+		};
+		const spliced = splice(text, parsed);
+
+		const [title, key] = childAt(spliced.ast, 0)?.children ?? [];
+		expect(title?.range.end).toEqual(position(2, 15, 14, 18));
+		expect(key?.range.start).toEqual(position(3, 1, 1, 19));
+		expect(spliced.errors[0]?.range?.start).toEqual(position(3, 6, 6, 24));
+		expect(childAt(spliced.ast, 1)?.range.start.offset).toBe(30);
+	});
+
+	it('offsets front matter behind a byte order mark by its three bytes', () => {
+		const parsed: AstParseResult = {
+			ast: {
+				type: 'number',
+				path: '$',
+				label: '1',
+				range: { start: position(1, 4, 4, 3), end: position(1, 5, 5, 4) },
+			},
+			errors: [],
+		};
+		const spliced = splice('\uFEFF---\na: 1\n---\n', parsed);
+
+		const node = childAt(childAt(spliced.ast, 0), 0);
+		expect(node?.path).toBe('$.frontMatter');
+		expect(node?.range.start).toEqual(position(2, 4, 4, 10));
+	});
+});
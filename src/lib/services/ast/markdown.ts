@@ -3,7 +3,14 @@
  * Parses Markdown text into AstNode structure for tree view display.
  */
 
-import type { AstNode, AstNodeType, AstParseResult, AstPosition, AstRange } from './types.js';
+import type {
+	AstNode,
+	AstNodeType,
+	AstParseError,
+	AstParseResult,
+	AstPosition,
+	AstRange,
+} from './types.js';
 import { getErrorMessage } from '@/lib/utils';
 
 /** Markdown block types for parsing */
@@ -19,12 +26,28 @@ interface MarkdownBlock {
 	readonly children?: readonly MarkdownBlock[];
 }
 
+/** YAML (`---`) or TOML (`+++`) block opening a Markdown document */
+export interface FrontMatter {
+	readonly language: 'yaml' | 'toml';
+	/** Text between the fences */
+	readonly content: string;
+	/** Offset of `content` in the document */
+	readonly contentOffset: number;
+	/** Last line of the block, closing fence included (1-indexed) */
+	readonly endLine: number;
+	/** Offset of the end of the closing fence */
+	readonly endOffset: number;
+}
+
 /**
- * Convert line and column to AstPosition.
+ * Convert line and column to AstPosition. Markdown nodes start and end at
+ * column 1, where both columns agree; offsets are counted in UTF-16 code
+ * units while parsing and converted to bytes by {@link withByteOffsets}.
  */
 const createPosition = (line: number, column: number, offset: number): AstPosition => ({
 	line,
 	column,
+	utf16Column: column,
 	offset,
 });
 
@@ -103,18 +126,47 @@ const formatListLabel = (ordered: boolean, itemCount: number): string => {
 };
 
 /**
- * Parse Markdown text into blocks.
+ * Detect front matter: a `---` (YAML) or `+++` (TOML) fence on the first
+ * line, closed by the same fence (or `...` for YAML) on a later line.
+ */
+export const extractFrontMatter = (text: string): FrontMatter | null => {
+	const lines = text.split('\n');
+	const fence = (lines[0] ?? '').replace(/^\uFEFF/, '').trimEnd();
+	const language = fence === '---' ? 'yaml' : fence === '+++' ? 'toml' : null;
+	if (!language) return null;
+
+	const closing = lines.findIndex((line, i) => {
+		const trimmed = line.trimEnd();
+		return i > 0 && (trimmed === fence || (language === 'yaml' && trimmed === '...'));
+	});
+	if (closing < 0) return null;
+
+	const lineOffsets = calculateLineOffsets(text);
+	const contentOffset = getLineOffset(lineOffsets, 2);
+	const closingOffset = getLineOffset(lineOffsets, closing + 1);
+	return {
+		language,
+		content: text.slice(contentOffset, closingOffset),
+		contentOffset,
+		endLine: closing + 1,
+		endOffset: closingOffset + (lines[closing] ?? '').replace(/\r$/, '').length,
+	};
+};
+
+/**
+ * Parse Markdown text into blocks, starting at `firstLine` (0-indexed).
  */
 const parseBlocks = (
 	text: string,
-	lineOffsets: readonly number[]
+	lineOffsets: readonly number[],
+	firstLine = 0
 ): { blocks: readonly MarkdownBlock[]; listCounters: Map<number, number> } => {
 	const lines = text.split('\n');
 	const blocks: MarkdownBlock[] = [];
 	const listCounters = new Map<number, number>();
 	// Outer scan cursor plus a per-block end tracker (reused inside each
 	// multi-line block branch). Both bindings are const; only fields mutate.
-	const cursor = { i: 0 };
+	const cursor = { i: firstLine };
 	const end = { line: 0, offset: 0 };
 
 	while (cursor.i < lines.length) {
@@ -532,32 +584,133 @@ const blockToNode = (block: MarkdownBlock, path: string, index: number): AstNode
 	return createNode(block.type, nodePath, label, range, children);
 };
 
+/**
+ * Node standing for the front matter block; its children are spliced in by
+ * {@link spliceFrontMatter} once the block has been parsed.
+ */
+const frontMatterNode = (frontMatter: FrontMatter): AstNode =>
+	createNode(
+		'front_matter',
+		'$.frontMatter',
+		`Front Matter (${frontMatter.language.toUpperCase()})`,
+		createRange(1, 1, 0, frontMatter.endLine, 1, frontMatter.endOffset)
+	);
+
+/**
+ * Map string indices in `text` to UTF-8 byte offsets, as the backend
+ * parsers count them.
+ */
+const byteOffsetMapper = (text: string): ((index: number) => number) => {
+	const offsets: number[] = [];
+	const state = { bytes: 0 };
+	for (const char of text) {
+		const codePoint = char.codePointAt(0) ?? 0;
+		const bytes = codePoint < 0x80 ? 1 : codePoint < 0x800 ? 2 : codePoint < 0x10000 ? 3 : 4;
+		for (let i = 0; i < char.length; i++) offsets.push(state.bytes);
+		state.bytes += bytes;
+	}
+	return (index) => offsets[index] ?? state.bytes;
+};
+
+/**
+ * Convert the offsets of `node` and its descendants from string indices
+ * to bytes.
+ */
+const withByteOffsets = (node: AstNode, toBytes: (index: number) => number): AstNode => ({
+	...node,
+	range: {
+		start: { ...node.range.start, offset: toBytes(node.range.start.offset) },
+		end: { ...node.range.end, offset: toBytes(node.range.end.offset) },
+	},
+	children: node.children?.map((child) => withByteOffsets(child, toBytes)),
+});
+
+/**
+ * Splice the parsed front matter under its `front_matter` node, moving its
+ * paths below `$.frontMatter` and its ranges into the Markdown document.
+ * The block starts on the second line, so only lines and offsets move;
+ * both columns are kept as the backend counted them.
+ */
+export const spliceFrontMatter = (
+	text: string,
+	markdown: AstParseResult,
+	frontMatter: FrontMatter,
+	parsed: AstParseResult
+): AstParseResult => {
+	const base = new TextEncoder().encode(text.slice(0, frontMatter.contentOffset)).length;
+	const position = (start: AstPosition): AstPosition => ({
+		...start,
+		line: start.line + 1,
+		offset: base + start.offset,
+	});
+	const range = ({ start, end }: AstRange): AstRange => ({
+		start: position(start),
+		end: position(end),
+	});
+	const relocate = (node: AstNode): AstNode => ({
+		...node,
+		path: `$.frontMatter${node.path.slice(1)}`,
+		range: range(node.range),
+		children: node.children?.map(relocate),
+	});
+
+	const root = parsed.ast;
+	const spliced = root ? (root.children ?? [root]).map(relocate) : undefined;
+	const errors: AstParseError[] = parsed.errors.map((error) =>
+		error.range ? { ...error, range: range(error.range) } : error
+	);
+	const ast = markdown.ast && {
+		...markdown.ast,
+		children: markdown.ast.children?.map((node) =>
+			node.type === 'front_matter' && spliced && spliced.length > 0
+				? { ...node, children: spliced }
+				: node
+		),
+	};
+	return { ast, errors: [...markdown.errors, ...errors] };
+};
+
+/** Markdown parse result, with the front matter block it found */
+export interface MarkdownParseResult extends AstParseResult {
+	/** Front matter opening the document, to parse as YAML or TOML */
+	readonly frontMatter: FrontMatter | null;
+}
+
 /**
  * Parse Markdown text to AST structure.
  */
-export const parseMarkdownToAst = (text: string): AstParseResult => {
+export const parseMarkdownToAst = (text: string): MarkdownParseResult => {
 	if (!text.trim()) {
 		return {
 			ast: null,
 			errors: [],
+			frontMatter: null,
 		};
 	}
 
 	try {
 		const lineOffsets = calculateLineOffsets(text);
-		const { blocks } = parseBlocks(text, lineOffsets);
+		const frontMatter = extractFrontMatter(text);
+		const { blocks } = parseBlocks(text, lineOffsets, frontMatter?.endLine ?? 0);
 		const groupedBlocks = groupListItems(blocks);
 
-		const children = groupedBlocks.map((block, index) => blockToNode(block, `$[${index}]`, index));
+		const children = [
+			...(frontMatter ? [frontMatterNode(frontMatter)] : []),
+			...groupedBlocks.map((block, index) => blockToNode(block, `$[${index}]`, index)),
+		];
 
 		const lastLine = text.split('\n').length;
 		const rootRange = createRange(1, 1, 0, lastLine, 1, text.length);
 
-		const ast = createNode('document', '$', 'Document', rootRange, children);
+		const ast = withByteOffsets(
+			createNode('document', '$', 'Document', rootRange, children),
+			byteOffsetMapper(text)
+		);
 
 		return {
 			ast,
 			errors: [],
+			frontMatter,
 		};
 	} catch (error) {
 		return {
@@ -567,6 +720,7 @@ export const parseMarkdownToAst = (text: string): AstParseResult => {
 					message: getErrorMessage(error),
 				},
 			],
+			frontMatter: null,
 		};
 	}
 };
@@ -3,35 +3,142 @@
  * or TypeScript parser (for Markdown)
  */
 
-import { parseMarkdownToAst } from './markdown.js';
+import { parseMarkdownToAst, spliceFrontMatter } from './markdown.js';
 import { getErrorMessage } from '@/lib/utils';
 import type {
 	AstLanguage,
+	AstNamespace,
 	AstNode,
+	AstNodeType,
 	AstParseResult,
+	AstPosition,
+	AstRange,
+	AstSearchQuery,
+	AstSearchResult,
+	CompactAst,
+	CompactAstParseResult,
+	ConvertOptions,
+	ConvertResult,
+	DocumentProfile,
+	FlattenOptions,
+	FlattenResult,
+	FormatOptions,
+	FormatResult,
 	LineToPathMap,
+	NodeExpansion,
+	OpenApiAnalysis,
+	PathAtOffset,
 	PathToLineMap,
+	PositionEncoding,
+	SchemaKind,
+	SqlAnalysisResult,
+	SqlDependencies,
+	SqlDialect,
+	SqlFormatOptions,
+	StreamedParseResult,
+	SubtreeText,
+	TableModel,
+	TableOptions,
+	TargetLanguage,
+	TransformSummary,
+	TypeGenOptions,
+	TypeGenResult,
+	XmlValidationResult,
+	XPathResult,
 } from './types.js';
 
+interface MutableAstNode {
+	type: AstNodeType;
+	path: string;
+	label: string;
+	value?: unknown;
+	range: AstRange;
+	children?: MutableAstNode[];
+	namespace?: AstNamespace;
+}
+
+/**
+ * Rebuild the nested tree from the backend's compact wire format.
+ * Nodes arrive in pre-order, so every parent exists before its children.
+ */
+export const expandCompactAst = (compact: CompactAst): AstNode | null => {
+	const { strings, nodes, ranges } = compact;
+	const values = new Map(compact.values);
+	const namespaces = new Map(compact.namespaces ?? []);
+	const num = (array: readonly number[], index: number): number => array[index] ?? 0;
+	const str = (index: number): string => strings[num(nodes, index)] ?? '';
+	const position = (at: number): AstPosition => ({
+		line: num(ranges, at),
+		column: num(ranges, at + 1),
+		utf16Column: num(ranges, at + 2),
+		offset: num(ranges, at + 3),
+	});
+	const built: MutableAstNode[] = [];
+	for (let i = 0; i * 4 < nodes.length; i++) {
+		const n = i * 4;
+		const r = i * 8;
+		const node: MutableAstNode = {
+			type: str(n) as AstNodeType,
+			path: str(n + 1),
+			label: str(n + 2),
+			range: { start: position(r), end: position(r + 4) },
+		};
+		if (values.has(i)) node.value = values.get(i);
+		const namespace = namespaces.get(i);
+		if (namespace) node.namespace = namespace;
+		built.push(node);
+		const parent = built[num(nodes, n + 3) - 1];
+		if (parent) {
+			if (parent.children) parent.children.push(node);
+			else parent.children = [node];
+		}
+	}
+	return built[0] ?? null;
+};
+
 /**
  * Parse text to AST
  * Uses TypeScript parser for Markdown, Rust parser for other languages
  * Only works in browser environment with Tauri (for non-Markdown)
+ * Pass `opId` to make the backend parse abandonable with {@link cancelAstParse}
+ * Pass `dialect` to parse SQL with dialect-specific syntax (ignored for other languages)
  */
-export const parseToAst = async (text: string, language: AstLanguage): Promise<AstParseResult> => {
+export const parseToAst = async (
+	text: string,
+	language: AstLanguage,
+	opId?: string,
+	dialect?: SqlDialect
+): Promise<AstParseResult> => {
 	if (typeof window === 'undefined') {
 		return { ast: null, errors: [] };
 	}
 
-	// Use TypeScript parser for Markdown (no Rust backend support yet)
+	// Use TypeScript parser for Markdown (no Rust backend support yet);
+	// its front matter goes to the YAML or TOML parser
 	if (language === 'markdown') {
-		return parseMarkdownToAst(text);
+		const { frontMatter, ...result } = parseMarkdownToAst(text);
+		if (!frontMatter || !result.ast) {
+			return result;
+		}
+		const parsed = await parseToAst(frontMatter.content, frontMatter.language, opId);
+		return spliceFrontMatter(text, result, frontMatter, parsed);
 	}
 
 	try {
 		// Dynamic import to avoid SSR issues
 		const { invoke } = await import('@tauri-apps/api/core');
-		return await invoke<AstParseResult>('parse_to_ast', { text, language });
+		const result = await invoke<CompactAstParseResult>('parse_to_ast', {
+			text,
+			language,
+			format: 'compact',
+			opId,
+			dialect,
+		});
+		return {
+			ast: result.ast ? expandCompactAst(result.ast) : null,
+			errors: result.errors,
+			...(result.dialect ? { dialect: result.dialect } : {}),
+		};
 	} catch (error) {
 		return {
 			ast: null,
@@ -44,6 +151,434 @@ export const parseToAst = async (text: string, language: AstLanguage): Promise<A
 	}
 };
 
+/**
+ * Abandon a backend parse started with `opId`; its promise resolves with a
+ * "Parsing cancelled" error.
+ */
+export const cancelAstParse = async (opId: string): Promise<void> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		await invoke<boolean>('cancel_op', { opId });
+	} catch {
+		// The parse already finished or Tauri is unavailable.
+	}
+};
+
+/**
+ * Parse a JSON file too large to load whole. Only the top `depth` levels
+ * are built; `expandNode` builds the collapsed containers on demand.
+ */
+export const parseJsonFile = async (
+	filePath: string,
+	depth?: number,
+	opId?: string
+): Promise<StreamedParseResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<StreamedParseResult>('parse_json_file', { filePath, depth, opId });
+	} catch (error) {
+		return { collapsed: [], size: 0, errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Build the next levels of a container `parseJsonFile` left collapsed.
+ * Large containers arrive a page at a time; append the children to those
+ * already shown.
+ */
+export const expandNode = async (filePath: string, path: string): Promise<NodeExpansion> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<NodeExpansion>('expand_node', { filePath, path });
+	} catch (error) {
+		return { children: [], collapsed: [], errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Summarise CSV or TSV text as a typed table: column names, inferred types,
+ * null counts, and a window of sample rows for a grid preview.
+ */
+export const parseTable = async (text: string, options?: TableOptions): Promise<TableModel> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<TableModel>('parse_table', { text, options });
+	} catch (error) {
+		return {
+			columns: [],
+			rows: [],
+			rowCount: 0,
+			delimiter: ',',
+			size: 0,
+			errors: [{ message: getErrorMessage(error) }],
+		};
+	}
+};
+
+/**
+ * Summarise a CSV or TSV file of any size as a typed table. The file is read
+ * in chunks; only the sampled rows are returned.
+ */
+export const parseTableFile = async (
+	filePath: string,
+	options?: TableOptions,
+	opId?: string
+): Promise<TableModel> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<TableModel>('parse_table_file', { filePath, options, opId });
+	} catch (error) {
+		return {
+			columns: [],
+			rows: [],
+			rowCount: 0,
+			delimiter: ',',
+			size: 0,
+			errors: [{ message: getErrorMessage(error) }],
+		};
+	}
+};
+
+/**
+ * Evaluate an XPath 1.0 expression against an XML document.
+ * Matches carry the same paths as the XML AST for tree highlighting.
+ */
+export const xpathQuery = async (text: string, expression: string): Promise<XPathResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<XPathResult>('xpath_query', { text, expression });
+	} catch (error) {
+		return { matches: [], total: 0, errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Find nodes by key name, value text or pattern, and node type, for
+ * find-in-structure in the tree view.
+ */
+export const searchAst = async (
+	text: string,
+	language: AstLanguage,
+	query: AstSearchQuery
+): Promise<AstSearchResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<AstSearchResult>('search_ast', { text, language, query });
+	} catch (error) {
+		return { matches: [], total: 0, errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Find the deepest node at an offset, with the breadcrumb from the root. The
+ * offset counts UTF-8 bytes unless `encoding` is `'utf-16'`, the unit of
+ * Monaco's `getOffsetAt`. The backend reuses the tree from the last
+ * `parseToAst` of the same text.
+ */
+export const pathAtOffset = async (
+	text: string,
+	language: AstLanguage,
+	offset: number,
+	dialect?: SqlDialect,
+	encoding?: PositionEncoding
+): Promise<PathAtOffset> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<PathAtOffset>('path_at_offset', {
+			text,
+			language,
+			offset,
+			dialect,
+			encoding,
+		});
+	} catch (error) {
+		return { breadcrumb: [], errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Get the exact source text and value of the node at `path`, for "copy value"
+ * and "copy subtree as JSON". The backend reuses the tree from the last
+ * `parseToAst` of the same text.
+ */
+export const getSubtreeText = async (
+	text: string,
+	language: AstLanguage,
+	path: string,
+	dialect?: SqlDialect
+): Promise<SubtreeText> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<SubtreeText>('get_subtree_text', { text, language, path, dialect });
+	} catch (error) {
+		return { errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Profile a document: node counts per type, depth, the largest arrays and
+ * objects, and string and number statistics.
+ */
+export const profileDocument = async (
+	text: string,
+	language: AstLanguage
+): Promise<DocumentProfile> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<DocumentProfile>('profile_document', { text, language });
+	} catch (error) {
+		return {
+			totalBytes: text.length,
+			totalLines: 0,
+			totalNodes: 0,
+			nodeCounts: {},
+			maxDepth: 0,
+			largestArrays: [],
+			largestObjects: [],
+			strings: {
+				count: 0,
+				totalLength: 0,
+				minLength: null,
+				maxLength: null,
+				distinct: 0,
+				lengthBuckets: [],
+			},
+			numbers: {
+				count: 0,
+				integers: 0,
+				floats: 0,
+				negative: 0,
+				min: null,
+				max: null,
+				mean: null,
+			},
+			errors: [{ message: getErrorMessage(error) }],
+		};
+	}
+};
+
+/**
+ * Pretty-print a JSON, YAML, XML, or SQL document. The source map relates
+ * each node's original range to its range in the formatted text.
+ */
+export const formatDocument = async (
+	text: string,
+	language: AstLanguage,
+	options?: FormatOptions
+): Promise<FormatResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<FormatResult>('format_document', { text, language, options });
+	} catch (error) {
+		return { sourceMap: [], errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Pretty-print SQL statements in the given dialect. The source map has one
+ * entry per statement (`$`, or `$[i]` for several).
+ */
+export const formatSql = async (
+	text: string,
+	dialect?: SqlDialect,
+	options?: SqlFormatOptions
+): Promise<FormatResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<FormatResult>('format_sql', { text, dialect, options });
+	} catch (error) {
+		return { sourceMap: [], errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Report DELETE/UPDATE without WHERE, `SELECT *`, implicit cross joins, and
+ * non-sargable predicates in SQL statements.
+ */
+export const analyzeSql = async (
+	text: string,
+	dialect: SqlDialect = 'generic'
+): Promise<SqlAnalysisResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<SqlAnalysisResult>('analyze_sql', { text, dialect });
+	} catch (error) {
+		return { warnings: [], errors: [{ message: getErrorMessage(error) }], dialect };
+	}
+};
+
+/**
+ * List the tables, columns, CTEs, and bind parameters referenced by SQL
+ * statements, with their source ranges.
+ */
+export const extractSqlDependencies = async (
+	text: string,
+	dialect: SqlDialect = 'generic'
+): Promise<SqlDependencies> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<SqlDependencies>('extract_sql_dependencies', { text, dialect });
+	} catch (error) {
+		return {
+			tables: [],
+			columns: [],
+			ctes: [],
+			parameters: [],
+			errors: [{ message: getErrorMessage(error) }],
+			dialect,
+		};
+	}
+};
+
+/**
+ * Parse PostgreSQL or MySQL JSON EXPLAIN output, or SQLite EXPLAIN QUERY PLAN
+ * rows, into a tree of plan nodes labelled with their costs. The format is
+ * recognised from the output when no dialect is given.
+ */
+export const parseExplainPlan = async (
+	text: string,
+	dialect?: SqlDialect
+): Promise<AstParseResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<AstParseResult>('parse_explain_plan', { text, dialect });
+	} catch (error) {
+		return { ast: null, errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Convert a document between JSON, YAML, TOML, XML, and CSV. Key order is
+ * kept; anything the target cannot represent is reported as a warning.
+ */
+export const convertDocument = async (
+	text: string,
+	from: AstLanguage,
+	to: AstLanguage,
+	options?: ConvertOptions
+): Promise<ConvertResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<ConvertResult>('convert_document', { text, from, to, options });
+	} catch (error) {
+		return { warnings: [], errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Flatten nested JSON into an object of key paths (`a.b[0]`) and leaf
+ * values, keeping key order.
+ */
+export const flattenJson = async (
+	text: string,
+	options?: FlattenOptions
+): Promise<FlattenResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<FlattenResult>('flatten_json', { text, options });
+	} catch (error) {
+		return { keys: 0, errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Rebuild nested JSON from flattened key paths; use the options the keys
+ * were flattened with.
+ */
+export const unflattenJson = async (
+	text: string,
+	options?: FlattenOptions
+): Promise<FlattenResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<FlattenResult>('unflatten_json', { text, options });
+	} catch (error) {
+		return { keys: 0, errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Generate Rust, TypeScript, Kotlin, or Go type definitions from a JSON
+ * sample or a JSON Schema.
+ */
+export const generateTypes = async (
+	text: string,
+	targetLanguage: TargetLanguage,
+	options?: TypeGenOptions
+): Promise<TypeGenResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<TypeGenResult>('generate_types', { text, targetLanguage, options });
+	} catch (error) {
+		return { errors: [{ message: getErrorMessage(error) }] };
+	}
+};
+
+/**
+ * Validate an XML document against a pasted XSD, or against its inline DTD
+ * plus an optional pasted external DTD.
+ */
+export const validateXml = async (
+	text: string,
+	schema: string | undefined,
+	schemaKind: SchemaKind
+): Promise<XmlValidationResult> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<XmlValidationResult>('validate_xml', { text, schema, schemaKind });
+	} catch (error) {
+		return { valid: false, errors: [{ message: getErrorMessage(error) }], schemaErrors: [] };
+	}
+};
+
+/**
+ * Analyze an OpenAPI 3.x document (JSON or YAML): its operation and schema
+ * tree, and the places it breaks the specification.
+ */
+export const analyzeOpenApi = async (text: string): Promise<OpenApiAnalysis> => {
+	try {
+		const { invoke } = await import('@tauri-apps/api/core');
+		return await invoke<OpenApiAnalysis>('analyze_openapi', { text });
+	} catch (error) {
+		return {
+			valid: false,
+			operationCount: 0,
+			schemaCount: 0,
+			errors: [{ message: getErrorMessage(error) }],
+		};
+	}
+};
+
+/**
+ * Run a jq program over JSON input. Each result is passed to `onResult` as
+ * soon as the backend produces it; pass `opId` to allow `cancelAstParse`.
+ */
+export const transformJson = async (
+	text: string,
+	program: string,
+	onResult: (value: unknown) => void,
+	opId?: string
+): Promise<TransformSummary> => {
+	try {
+		const { Channel, invoke } = await import('@tauri-apps/api/core');
+		const channel = new Channel<unknown>();
+		channel.onmessage = onResult;
+		return await invoke<TransformSummary>('transform_json', {
+			text,
+			program,
+			onResult: channel,
+			opId,
+		});
+	} catch (error) {
+		return {
+			count: 0,
+			inputs: 0,
+			truncated: false,
+			errors: [{ kind: 'runtime', message: getErrorMessage(error) }],
+		};
+	}
+};
+
 /**
  * Build path to line map from AST node
  * Used for tree → editor synchronization
@@ -4,7 +4,24 @@
  */
 
 /** Supported languages for AST parsing */
-export type AstLanguage = 'json' | 'yaml' | 'xml' | 'sql' | 'markdown';
+export type AstLanguage =
+	| 'json'
+	| 'yaml'
+	| 'xml'
+	| 'sql'
+	| 'toml'
+	| 'proto'
+	| 'csv'
+	| 'ini'
+	| 'properties'
+	| 'dotenv'
+	| 'dockerfile'
+	| 'nginx'
+	| 'apacheconf'
+	| 'crontab'
+	| 'systemd'
+	| 'html'
+	| 'markdown';
 
 /** AST node type */
 export type AstNodeType =
@@ -43,6 +60,7 @@ export type AstNodeType =
 	| 'table'
 	| 'table_row'
 	| 'horizontal_rule'
+	| 'front_matter'
 	// Fallback
 	| 'unknown';
 
@@ -50,12 +68,17 @@ export type AstNodeType =
 export interface AstPosition {
 	/** Line number (1-indexed) */
 	readonly line: number;
-	/** Column number (1-indexed) */
+	/** Column number in UTF-8 bytes (1-indexed) */
 	readonly column: number;
-	/** Character offset from start of text (0-indexed) */
+	/** Column number in UTF-16 code units (1-indexed), as Monaco counts */
+	readonly utf16Column: number;
+	/** Byte offset from start of text (0-indexed) */
 	readonly offset: number;
 }
 
+/** Unit of an offset passed to the backend */
+export type PositionEncoding = 'utf-8' | 'utf-16';
+
 /** Range in the source text */
 export interface AstRange {
 	readonly start: AstPosition;
@@ -76,20 +99,538 @@ export interface AstNode {
 	readonly range: AstRange;
 	/** Child nodes */
 	readonly children?: readonly AstNode[];
+	/** Namespace of an XML element or attribute (XML only) */
+	readonly namespace?: AstNamespace;
+}
+
+/** XML namespace an element or attribute name resolves to */
+export interface AstNamespace {
+	/** Prefix as written (absent for the default namespace) */
+	readonly prefix?: string;
+	readonly uri: string;
 }
 
+/** SQL dialect used by the SQL parser (`generic` when not specified) */
+export type SqlDialect =
+	| 'generic'
+	| 'postgres'
+	| 'mysql'
+	| 'sqlite'
+	| 'mssql'
+	| 'bigquery'
+	| 'snowflake';
+
 /** Result of AST parsing */
 export interface AstParseResult {
-	/** Root AST node (null if parsing failed) */
+	/** Root AST node (null if parsing failed; partial alongside errors for JSON) */
 	readonly ast: AstNode | null;
 	/** Parse errors */
 	readonly errors: readonly AstParseError[];
+	/** SQL dialect the text was parsed with (SQL only) */
+	readonly dialect?: SqlDialect;
+}
+
+/**
+ * Flat AST sent by the backend when `parse_to_ast` is called with `format: 'compact'`.
+ * Nodes are in pre-order; node `i` is `nodes[i * 4 .. i * 4 + 4]` (type, path, and label
+ * indices into `strings`, then parent index + 1 with 0 for the root) and
+ * `ranges[i * 8 .. i * 8 + 8]` (start line, column, UTF-16 column, and offset, then the
+ * same for the end).
+ */
+export interface CompactAst {
+	readonly strings: readonly string[];
+	readonly nodes: readonly number[];
+	readonly ranges: readonly number[];
+	readonly values: readonly (readonly [number, unknown])[];
+	/** `[node index, namespace]` pairs for namespaced XML nodes */
+	readonly namespaces?: readonly (readonly [number, AstNamespace])[];
 }
 
+/** `parse_to_ast` response in the compact wire format */
+export interface CompactAstParseResult {
+	readonly format: 'compact';
+	readonly ast: CompactAst | null;
+	readonly errors: readonly AstParseError[];
+	readonly dialect?: SqlDialect;
+}
+
+/** How serious a parse error is; warnings and notes leave the document valid */
+export type AstSeverity = 'error' | 'warning' | 'info';
+
 /** AST parse error */
 export interface AstParseError {
 	readonly message: string;
 	readonly range?: AstRange;
+	/** Set by the backend; errors built on the frontend are errors */
+	readonly severity?: AstSeverity;
+	/** Other parts of the document involved, like the first of two duplicate keys */
+	readonly relatedRanges?: readonly AstRange[];
+}
+
+/** Kind of node selected by an XPath expression */
+export type XPathNodeKind =
+	| 'root'
+	| 'element'
+	| 'attribute'
+	| 'text'
+	| 'comment'
+	| 'processingInstruction';
+
+/** Node selected by an XPath expression */
+export interface XPathMatch {
+	readonly kind: XPathNodeKind;
+	/** AST path of the node, as assigned by the XML parser */
+	readonly path: string;
+	/** Element or attribute local name, or processing-instruction target */
+	readonly name?: string;
+	/** XPath string-value of the node */
+	readonly value: string;
+	readonly range: AstRange;
+}
+
+/** Result of `xpath_query` */
+export interface XPathResult {
+	/** Selected nodes in document order (at most 10,000) */
+	readonly matches: readonly XPathMatch[];
+	/** Number of selected nodes, including any beyond `matches` */
+	readonly total: number;
+	/** Number, string, or boolean result of a non-node-set expression */
+	readonly value?: number | string | boolean;
+	/** XML and expression errors; expression error ranges point into the expression */
+	readonly errors: readonly AstParseError[];
+}
+
+/** Criteria for `search_ast`; a node matches when it meets every criterion given */
+export interface AstSearchQuery {
+	/** Property, element, or attribute name to match exactly (a pattern with `regex`) */
+	readonly key?: string;
+	/** Text to find in scalar values (a pattern with `regex`) */
+	readonly value?: string;
+	readonly regex?: boolean;
+	readonly caseSensitive?: boolean;
+	/** Node types to match; any type when empty */
+	readonly nodeTypes?: readonly AstNodeType[];
+}
+
+/** Node matching a `search_ast` query */
+export interface AstSearchMatch {
+	readonly path: string;
+	readonly nodeType: AstNodeType;
+	readonly key?: string;
+	readonly value?: unknown;
+	readonly range: AstRange;
+}
+
+/** Result of `search_ast` */
+export interface AstSearchResult {
+	/** Matching nodes in document order (at most 10,000) */
+	readonly matches: readonly AstSearchMatch[];
+	/** Number of matching nodes, including any beyond `matches` */
+	readonly total: number;
+	/** Parse errors (the partial tree is still searched) and invalid patterns */
+	readonly errors: readonly AstParseError[];
+}
+
+/** Node on the way from the root to a cursor position */
+export interface AstNodeSummary {
+	readonly path: string;
+	readonly label: string;
+	readonly nodeType: AstNodeType;
+	readonly range: AstRange;
+}
+
+/** Result of `parse_json_file` */
+export interface StreamedParseResult {
+	/** Top levels of the tree; collapsed containers have no children */
+	readonly ast?: AstNode;
+	/** Paths of the containers `expand_node` can build */
+	readonly collapsed: readonly string[];
+	/** File size in bytes */
+	readonly size: number;
+	readonly errors: readonly AstParseError[];
+}
+
+/** Options for `parse_table` and `parse_table_file` */
+export interface TableOptions {
+	/** Field separator; detected from the first lines when omitted */
+	readonly delimiter?: string;
+	/** Whether the first record names the columns (default true) */
+	readonly hasHeader?: boolean;
+	/** Index of the first data row to sample (default 0) */
+	readonly offset?: number;
+	/** Data rows to sample (default 100, at most 10,000) */
+	readonly sampleRows?: number;
+}
+
+/** Column summary of a table */
+export interface TableColumn {
+	readonly name: string;
+	/** Type shared by the column's non-empty cells */
+	readonly columnType: 'number' | 'boolean' | 'string' | 'empty';
+	/** Empty or missing cells */
+	readonly nullCount: number;
+}
+
+/** Result of `parse_table` and `parse_table_file` */
+export interface TableModel {
+	readonly columns: readonly TableColumn[];
+	/** Sampled rows, one value per column; empty cells are null */
+	readonly rows: readonly (readonly unknown[])[];
+	/** Data rows in the whole input */
+	readonly rowCount: number;
+	readonly delimiter: string;
+	/** Bytes read */
+	readonly size: number;
+	readonly errors: readonly AstParseError[];
+}
+
+/** Result of `expand_node` */
+export interface NodeExpansion {
+	/** Children built by this request, following any already shown */
+	readonly children: readonly AstNode[];
+	/** Collapsed containers among `children`, plus the expanded one while it has more */
+	readonly collapsed: readonly string[];
+	readonly errors: readonly AstParseError[];
+}
+
+/** Result of `path_at_offset` */
+export interface PathAtOffset {
+	/** Path of the deepest node containing the offset */
+	readonly path?: string;
+	/** Nodes from the root down to the deepest one; empty outside the tree */
+	readonly breadcrumb: readonly AstNodeSummary[];
+	/** Parse errors (a partial tree is still searched) */
+	readonly errors: readonly AstParseError[];
+}
+
+/** Result of `get_subtree_text` */
+export interface SubtreeText {
+	/** Source text of the node, exactly as written */
+	readonly source?: string;
+	/** Range of `source` in the document */
+	readonly range?: AstRange;
+	/** Value to copy: a string as is, anything else as `json` */
+	readonly value?: string;
+	/** Value as pretty-printed JSON; absent for nodes that hold no data */
+	readonly json?: string;
+	/** Parse errors, and a missing node (a partial tree is still searched) */
+	readonly errors: readonly AstParseError[];
+}
+
+/** Array, object, or XML element with its number of children */
+export interface ContainerSize {
+	readonly path: string;
+	readonly nodeType: AstNodeType;
+	/** Items, members, or child nodes */
+	readonly size: number;
+	readonly range: AstRange;
+}
+
+/** Number of strings whose length falls in `min..=max` */
+export interface LengthBucket {
+	readonly min: number;
+	/** Absent for the open-ended last bucket */
+	readonly max?: number;
+	readonly count: number;
+}
+
+/** String values reported by `profile_document`; lengths are in characters */
+export interface StringStats {
+	readonly count: number;
+	/** Sum of all lengths, for the average */
+	readonly totalLength: number;
+	readonly minLength: number | null;
+	readonly maxLength: number | null;
+	/** Distinct values, up to the first 100,000 strings */
+	readonly distinct: number;
+	readonly lengthBuckets: readonly LengthBucket[];
+}
+
+/** Numeric values reported by `profile_document` */
+export interface NumberStats {
+	readonly count: number;
+	readonly integers: number;
+	readonly floats: number;
+	readonly negative: number;
+	readonly min: number | null;
+	readonly max: number | null;
+	readonly mean: number | null;
+}
+
+/** Result of `profile_document` */
+export interface DocumentProfile {
+	/** Size of the text in bytes */
+	readonly totalBytes: number;
+	readonly totalLines: number;
+	readonly totalNodes: number;
+	/** Node count per node type */
+	readonly nodeCounts: Readonly<Partial<Record<AstNodeType, number>>>;
+	readonly maxDepth: number;
+	/** Path of the first node at `maxDepth` */
+	readonly deepestPath?: string;
+	/** Arrays with the most items, largest first (at most 10) */
+	readonly largestArrays: readonly ContainerSize[];
+	/** Objects and XML elements with the most children, largest first (at most 10) */
+	readonly largestObjects: readonly ContainerSize[];
+	readonly strings: StringStats;
+	readonly numbers: NumberStats;
+	/** Parse errors (the partial tree is still profiled) */
+	readonly errors: readonly AstParseError[];
+}
+
+/** Where a `transform_json` error came from */
+export type TransformErrorKind = 'program' | 'input' | 'runtime';
+
+/** Error reported by `transform_json` */
+export interface TransformError {
+	readonly kind: TransformErrorKind;
+	readonly message: string;
+	/** Points into the program for `program` errors, into the input for `input` errors */
+	readonly range?: AstRange;
+	/** Zero-based index of the input document being processed */
+	readonly inputIndex?: number;
+}
+
+/** Summary of a `transform_json` run; results are streamed separately */
+export interface TransformSummary {
+	/** Number of results emitted (at most 100,000) */
+	readonly count: number;
+	/** Number of input documents the program ran on */
+	readonly inputs: number;
+	/** Output stopped at the result limit */
+	readonly truncated: boolean;
+	readonly errors: readonly TransformError[];
+}
+
+/** Quote character for YAML strings and XML attributes */
+export type QuoteStyle = 'double' | 'single';
+
+/** Options for `format_document` */
+export interface FormatOptions {
+	/** Spaces per level (default 2); 0 indents with tabs */
+	readonly indent?: number;
+	readonly quoteStyle?: QuoteStyle;
+	/** Sort object keys and XML attributes */
+	readonly sortKeys?: boolean;
+}
+
+/** Range of a node before and after formatting */
+export interface SourceMapping {
+	readonly path: string;
+	readonly original: AstRange;
+	readonly formatted: AstRange;
+}
+
+/** Result of `format_document` */
+export interface FormatResult {
+	/** Formatted document; absent when the input failed to parse */
+	readonly text?: string;
+	readonly sourceMap: readonly SourceMapping[];
+	readonly errors: readonly AstParseError[];
+}
+
+/** Case for SQL keywords; `lower` also lowercases upper-case identifiers that are keywords */
+export type KeywordCase = 'upper' | 'lower';
+
+/** Placement of commas in lists that span several lines */
+export type CommaStyle = 'trailing' | 'leading';
+
+/** Options for `format_sql` */
+export interface SqlFormatOptions {
+	/** Keyword case (default `upper`) */
+	readonly keywordCase?: KeywordCase;
+	/** Spaces per level (default 2); 0 indents with tabs */
+	readonly indent?: number;
+	/** Comma placement (default `trailing`) */
+	readonly commaStyle?: CommaStyle;
+	/** Clauses that fit within this many columns stay on one line (default 80); 0 never joins */
+	readonly maxLineLength?: number;
+}
+
+/** Check reported by `analyze_sql` */
+export type SqlRule =
+	| 'delete-without-where'
+	| 'update-without-where'
+	| 'select-star'
+	| 'implicit-cross-join'
+	| 'non-sargable';
+
+/** Risky pattern found in a SQL statement */
+export interface SqlWarning {
+	readonly rule: SqlRule;
+	readonly message: string;
+	/** Zero-based index of the statement */
+	readonly statement: number;
+	/** Range of the offending clause */
+	readonly range: AstRange;
+}
+
+/** Result of `analyze_sql` */
+export interface SqlAnalysisResult {
+	readonly warnings: readonly SqlWarning[];
+	/** Parse errors; no warnings are reported when the input is invalid */
+	readonly errors: readonly AstParseError[];
+	readonly dialect: SqlDialect;
+}
+
+/** Whether a statement reads from or writes to a table */
+export type TableAccess = 'read' | 'write';
+
+/** Table or view referenced by a SQL statement */
+export interface SqlTableRef {
+	/** Name as written, including any schema */
+	readonly name: string;
+	readonly alias?: string;
+	readonly access: TableAccess;
+	/** Zero-based index of the statement */
+	readonly statement: number;
+	readonly range: AstRange;
+}
+
+/** Column referenced by a SQL statement */
+export interface SqlColumnRef {
+	readonly name: string;
+	/** Table name or alias in front of the column (`u` in `u.id`) */
+	readonly qualifier?: string;
+	readonly statement: number;
+	readonly range: AstRange;
+}
+
+/** CTE definition or bind parameter (`?`, `$1`, `:id`) */
+export interface SqlName {
+	readonly name: string;
+	readonly statement: number;
+	readonly range: AstRange;
+}
+
+/** Result of `extract_sql_dependencies`; every occurrence is listed */
+export interface SqlDependencies {
+	readonly tables: readonly SqlTableRef[];
+	readonly columns: readonly SqlColumnRef[];
+	readonly ctes: readonly SqlName[];
+	readonly parameters: readonly SqlName[];
+	readonly errors: readonly AstParseError[];
+	readonly dialect: SqlDialect;
+}
+
+/** Options for `convert_document` */
+export interface ConvertOptions {
+	/** Spaces per level (default 2); 0 indents JSON and XML with tabs */
+	readonly indent?: number;
+	/** Key prefix for XML attributes (default `@`) */
+	readonly attributePrefix?: string;
+	/** Key for the text of XML elements with attributes or children (default `#text`) */
+	readonly textKey?: string;
+	/** Root element for XML output that is not a single-key map (default `root`) */
+	readonly rootElement?: string;
+	/** CSV output delimiter (default `,`) */
+	readonly delimiter?: string;
+}
+
+/** Information lost or changed by a conversion */
+export interface ConvertWarning {
+	readonly message: string;
+	/** Path of the first value affected */
+	readonly path: string;
+}
+
+/** Result of `convert_document` */
+export interface ConvertResult {
+	/** Converted document; absent when the input failed to parse or cannot be written */
+	readonly text?: string;
+	readonly warnings: readonly ConvertWarning[];
+	readonly errors: readonly AstParseError[];
+}
+
+/** How arrays appear in flattened keys: `a[0]`, `a.0`, or kept as array values */
+export type ArrayHandling = 'brackets' | 'separator' | 'preserve';
+
+/** Options for `flatten_json` and `unflatten_json` */
+export interface FlattenOptions {
+	/** Separator between object keys (default `.`) */
+	readonly separator?: string;
+	/** Array handling (default `brackets`) */
+	readonly arrayHandling?: ArrayHandling;
+	/** Spaces per level (default 2); 0 indents with tabs */
+	readonly indent?: number;
+}
+
+/** Result of `flatten_json` and `unflatten_json` */
+export interface FlattenResult {
+	/** Output JSON; absent when the input is invalid or its keys collide */
+	readonly text?: string;
+	/** Number of flattened keys */
+	readonly keys: number;
+	readonly errors: readonly AstParseError[];
+}
+
+/** Language `generate_types` emits */
+export type TargetLanguage = 'rust' | 'typescript' | 'kotlin' | 'go';
+
+/** How `generate_types` reads its input; `auto` detects a JSON Schema */
+export type TypeInput = 'auto' | 'sample' | 'schema';
+
+/** Field names: the target's convention, or JSON keys as written */
+export type FieldNaming = 'idiomatic' | 'preserve';
+
+/** Which generated fields are optional */
+export type Optionality = 'inferred' | 'allOptional' | 'allRequired';
+
+/** Options for `generate_types` */
+export interface TypeGenOptions {
+	/** Name of the root type (default `Root`) */
+	readonly rootName?: string;
+	/** Input kind (default `auto`) */
+	readonly input?: TypeInput;
+	/** Field naming (default `idiomatic`) */
+	readonly fieldNaming?: FieldNaming;
+	/** Optionality (default `inferred`) */
+	readonly optionality?: Optionality;
+}
+
+/** Result of `generate_types` */
+export interface TypeGenResult {
+	/** Generated definitions; absent when the input is not valid JSON */
+	readonly code?: string;
+	/** JSON errors, and schema references that could not be resolved */
+	readonly errors: readonly AstParseError[];
+}
+
+/** Schema language for `validate_xml` */
+export type SchemaKind = 'xsd' | 'dtd';
+
+/** Validity problem in an XML document */
+export interface XmlValidationError {
+	readonly message: string;
+	/** AST path of the offending element, attribute, or text node */
+	readonly path?: string;
+	readonly range?: AstRange;
+}
+
+/** Result of `validate_xml` */
+export interface XmlValidationResult {
+	readonly valid: boolean;
+	/** Well-formedness and validity errors; ranges point into the document */
+	readonly errors: readonly XmlValidationError[];
+	/** Problems in the schema itself; ranges point into the schema text */
+	readonly schemaErrors: readonly AstParseError[];
+}
+
+/** Result of `analyze_openapi` */
+export interface OpenApiAnalysis {
+	/** The document parsed and follows the specification */
+	readonly valid: boolean;
+	/** `openapi` version the document declares */
+	readonly openapi?: string;
+	readonly title?: string;
+	/** `info.version`, the version of the API itself */
+	readonly version?: string;
+	/** Paths with their operations, then component schemas */
+	readonly tree?: AstNode;
+	readonly operationCount: number;
+	readonly schemaCount: number;
+	/** Parse errors and specification violations; ranges point into the document */
+	readonly errors: readonly XmlValidationError[];
 }
 
 /** Map of path to line number for tree↔editor synchronization */
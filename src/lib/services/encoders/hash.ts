@@ -61,3 +61,105 @@ export const compareHashes = (hash1: string, hash2: string): boolean =>
 	hash1.toLowerCase().trim() === hash2.toLowerCase().trim();
 
 export const SAMPLE_TEXT_FOR_HASH = 'Hello, World!';
+
+export type HmacAlgorithm = 'hmac-sha1' | 'hmac-sha256' | 'hmac-sha512' | 'blake3';
+export type HmacKeyEncoding = 'utf8' | 'hex' | 'base64';
+export type HmacOutputEncoding = 'hex' | 'base64';
+
+export const HMAC_ALGORITHMS: readonly { algorithm: HmacAlgorithm; label: string }[] = [
+	{ algorithm: 'hmac-sha1', label: 'HMAC-SHA1' },
+	{ algorithm: 'hmac-sha256', label: 'HMAC-SHA256' },
+	{ algorithm: 'hmac-sha512', label: 'HMAC-SHA512' },
+	{ algorithm: 'blake3', label: 'BLAKE3 (keyed)' },
+];
+
+export interface HmacOptions {
+	readonly keyEncoding?: HmacKeyEncoding;
+	readonly outputEncoding?: HmacOutputEncoding;
+	/** MAC to verify in constant time, in the output encoding. */
+	readonly expected?: string;
+}
+
+export interface HmacResult {
+	readonly algorithm: HmacAlgorithm;
+	readonly mac: string;
+	readonly keyBytes: number;
+	/** `null` when no expected MAC was given. */
+	readonly verified: boolean | null;
+}
+
+/**
+ * Compute an HMAC, or a BLAKE3 keyed hash (32-byte key), via the Rust
+ * `hmac_digest` command (see `src-tauri/src/hmac_digest.rs`).
+ */
+export const hmacDigest = (
+	message: string,
+	key: string,
+	algorithm: HmacAlgorithm,
+	options: HmacOptions = {},
+): Promise<HmacResult> =>
+	invoke<HmacResult>('hmac_digest', {
+		message,
+		key,
+		algorithm,
+		encodings: { key: options.keyEncoding ?? 'utf8', output: options.outputEncoding ?? 'hex' },
+		expected: options.expected ?? null,
+	});
+
+export type ChecksumInputEncoding = 'utf8' | 'hex' | 'base64';
+
+/**
+ * Rocksoft model parameters of a CRC (width 8 to 64 bits). `poly`, `init`,
+ * and `xorout` are hex strings (`0x` optional) so 64-bit values survive.
+ */
+export interface CrcParams {
+	readonly width: number;
+	readonly poly: string;
+	readonly init: string;
+	readonly refin: boolean;
+	readonly refout: boolean;
+	readonly xorout: string;
+}
+
+export interface ChecksumPreset {
+	readonly id: string;
+	readonly name: string;
+	/** `null` for Adler-32. */
+	readonly params: CrcParams | null;
+	/** Checksum of "123456789", as `0x`-prefixed hex. */
+	readonly check: string;
+}
+
+export interface ChecksumValue {
+	readonly id: string;
+	readonly width: number;
+	readonly hex: string;
+	/** Decimal string; 64-bit values exceed `Number.MAX_SAFE_INTEGER`. */
+	readonly decimal: string;
+}
+
+export interface ChecksumResult {
+	readonly values: readonly ChecksumValue[];
+	readonly sizeBytes: number;
+}
+
+/** CRC and Adler-32 presets from the Rust `checksum_presets` command. */
+export const checksumPresets = (): Promise<readonly ChecksumPreset[]> =>
+	invoke<ChecksumPreset[]>('checksum_presets');
+
+/**
+ * Compute CRC / Adler-32 checksums via the Rust `checksum_compute`
+ * command (see `src-tauri/src/checksum.rs`). A `custom` CRC is reported
+ * with id `custom` after the presets.
+ */
+export const computeChecksums = (
+	input: string,
+	presets: readonly string[],
+	options: { encoding?: ChecksumInputEncoding; custom?: CrcParams } = {},
+): Promise<ChecksumResult> =>
+	invoke<ChecksumResult>('checksum_compute', {
+		input,
+		encoding: options.encoding ?? 'utf8',
+		presets,
+		custom: options.custom ?? null,
+	});
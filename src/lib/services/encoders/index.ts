@@ -25,8 +25,30 @@ export {
 	SAMPLE_TEXT_FOR_BASE64,
 	validateBase64,
 } from './base64';
-export type { HashAlgorithm, HashResult } from './hash';
-export { compareHashes, generateAllHashes, HASH_ALGORITHMS, SAMPLE_TEXT_FOR_HASH } from './hash';
+export type {
+	ChecksumInputEncoding,
+	ChecksumPreset,
+	ChecksumResult,
+	ChecksumValue,
+	CrcParams,
+	HashAlgorithm,
+	HashResult,
+	HmacAlgorithm,
+	HmacKeyEncoding,
+	HmacOptions,
+	HmacOutputEncoding,
+	HmacResult,
+} from './hash';
+export {
+	checksumPresets,
+	compareHashes,
+	computeChecksums,
+	generateAllHashes,
+	HASH_ALGORITHMS,
+	HMAC_ALGORITHMS,
+	hmacDigest,
+	SAMPLE_TEXT_FOR_HASH,
+} from './hash';
 export type { BatchHashAlgo, FileHashResult, ShasumEntry, VerifyOutcome } from './hash-batch';
 export {
 	BATCH_HASH_ALGO_LABELS,
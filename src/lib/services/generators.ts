@@ -26,6 +26,225 @@ export interface BcryptCostInfo {
 	readonly security_level: string;
 }
 
+// =============================================================================
+// Password Hash Types
+// =============================================================================
+
+export type PasswordHashAlgorithm = 'bcrypt' | 'argon2' | 'scrypt' | 'pbkdf2' | 'sha-crypt';
+
+/** `phc` for `$id$name=value,...$salt$hash`, `crypt` for positional parameters */
+export type HashStringFormat = 'phc' | 'crypt';
+
+export interface HashParameter {
+	readonly name: string;
+	readonly value: number;
+}
+
+export interface PasswordHashInfo {
+	readonly algorithm: PasswordHashAlgorithm;
+	/** Variant as written: "2b", "argon2id", "pbkdf2-sha256", "sha512-crypt" */
+	readonly variant: string;
+	readonly format: HashStringFormat;
+	/** Cost parameters in the order they are written (cost, m, t, p, ln, r, i, rounds) */
+	readonly parameters: readonly HashParameter[];
+	/** Salt as encoded in the hash */
+	readonly salt: string;
+	/** Length of the hash output in bytes */
+	readonly hash_length: number;
+}
+
+export interface PasswordHashVerifyResult {
+	readonly algorithm: PasswordHashAlgorithm;
+	readonly valid: boolean;
+	readonly message: string;
+}
+
+// =============================================================================
+// JWT Types
+// =============================================================================
+
+export type JwtAlgorithm = 'HS256' | 'HS384' | 'HS512' | 'RS256' | 'ES256' | 'EdDSA';
+
+/** Whether `exp` and `nbf` hold at the time of decoding */
+export type JwtTimeStatus = 'valid' | 'expired' | 'not-yet-valid';
+
+export interface JwtClaim {
+	readonly name: string;
+	readonly value: unknown;
+	/** Meaning of a registered claim; null for a custom claim */
+	readonly description: string | null;
+	/** Reading of a time claim: "2026-01-01 00:00:00 UTC (in 3 days)" */
+	readonly detail: string | null;
+}
+
+export interface DecodedJwt {
+	readonly header: Record<string, unknown>;
+	readonly payload: unknown;
+	/** Signature as written, base64url */
+	readonly signature: string;
+	readonly claims: readonly JwtClaim[];
+	readonly status: JwtTimeStatus;
+	/** Seconds until `exp` at decoding, negative once expired */
+	readonly expires_in: number | null;
+}
+
+export interface JwtVerifyResult {
+	readonly valid: boolean;
+	/** `alg` of the token header */
+	readonly algorithm: string;
+	/** `kid` of the JWK the token was checked against */
+	readonly key_id: string | null;
+	readonly message: string;
+}
+
+// =============================================================================
+// OTP Types
+// =============================================================================
+
+export type OtpAlgorithm = 'SHA1' | 'SHA256' | 'SHA512';
+
+export type OtpKind = 'totp' | 'hotp';
+
+/** Everything an `otpauth://` URI holds */
+export interface OtpParams {
+	readonly kind: OtpKind;
+	/** Base32 secret */
+	readonly secret: string;
+	readonly algorithm: OtpAlgorithm;
+	/** Code length, 6 to 8 */
+	readonly digits: number;
+	/** TOTP time step in seconds */
+	readonly period: number;
+	/** HOTP counter */
+	readonly counter: number;
+	readonly issuer: string | null;
+	/** Account name, usually an email address */
+	readonly account: string;
+}
+
+export interface OtpCode {
+	/** Steps from the current one: -1 for the previous code, 1 for the next */
+	readonly offset: number;
+	readonly counter: number;
+	readonly code: string;
+}
+
+export interface OtpCodes {
+	readonly current: string;
+	readonly next: string;
+	/** Seconds until `next` becomes current; null for HOTP */
+	readonly seconds_remaining: number | null;
+	readonly window: readonly OtpCode[];
+}
+
+export interface OtpVerifyResult {
+	readonly valid: boolean;
+	/** Steps the code is off the current one; negative when the authenticator is behind */
+	readonly drift: number | null;
+	readonly message: string;
+}
+
+// =============================================================================
+// Symmetric Encryption Types
+// =============================================================================
+
+export type SymmetricCipher =
+	| 'aes-128-gcm'
+	| 'aes-256-gcm'
+	| 'aes-128-cbc'
+	| 'aes-256-cbc'
+	| 'chacha20-poly1305';
+
+/** Encoding of ciphertext, raw key, IV, and salt */
+export type BinaryEncoding = 'base64' | 'hex';
+
+export type KeyDerivation = 'pbkdf2' | 'argon2id';
+
+export type SymmetricKey =
+	| { readonly type: 'raw'; readonly key: string }
+	| {
+			readonly type: 'passphrase';
+			readonly passphrase: string;
+			readonly kdf: KeyDerivation;
+			/** Random when encrypting without one */
+			readonly salt?: string;
+			/** PBKDF2 iterations, 600,000 when omitted */
+			readonly iterations?: number;
+	  };
+
+export interface SymmetricOptions {
+	readonly cipher: SymmetricCipher;
+	readonly key: SymmetricKey;
+	/** IV or nonce; random when encrypting without one, required when decrypting */
+	readonly iv?: string;
+	/** Additional authenticated data (AEAD ciphers only) */
+	readonly aad?: string;
+	readonly encoding: BinaryEncoding;
+}
+
+export interface SymmetricResult {
+	readonly cipher: SymmetricCipher;
+	/** Encoded ciphertext when encrypting, plaintext when decrypting */
+	readonly output: string;
+	readonly iv: string;
+	readonly salt: string | null;
+	readonly derived_key: string | null;
+}
+
+export interface SymmetricFileResult {
+	readonly cipher: SymmetricCipher;
+	readonly output_path: string;
+	readonly bytes_written: number;
+	readonly iv: string;
+	readonly salt: string | null;
+	readonly derived_key: string | null;
+}
+
+// =============================================================================
+// Signing Types
+// =============================================================================
+
+export type SignatureScheme = 'rsa_pkcs1v15' | 'rsa_pss' | 'ecdsa' | 'ed25519';
+
+/** Ignored by Ed25519; ECDSA P-256 needs SHA-256 and P-384 needs SHA-384 */
+export type SignatureDigest = 'sha256' | 'sha384' | 'sha512';
+
+export type SigningInput =
+	| { readonly type: 'text'; readonly text: string }
+	| { readonly type: 'file'; readonly path: string };
+
+export interface SignOptions {
+	readonly scheme: SignatureScheme;
+	readonly digest: SignatureDigest;
+	/** PKCS#8, PKCS#1, SEC1, or OpenSSH PEM */
+	readonly private_key: string;
+	/** Encrypted OpenSSH keys only */
+	readonly passphrase?: string;
+	readonly encoding: BinaryEncoding;
+}
+
+export interface VerifyOptions {
+	readonly scheme: SignatureScheme;
+	readonly digest: SignatureDigest;
+	/** SPKI or PKCS#1 PEM, a certificate, or an OpenSSH public key line */
+	readonly public_key: string;
+	readonly signature: string;
+	readonly encoding: BinaryEncoding;
+}
+
+export interface SignResult {
+	readonly scheme: SignatureScheme;
+	/** e.g. "RSA 2048", "ECDSA P-256", "Ed25519" */
+	readonly key_type: string;
+	readonly signature: string;
+}
+
+export interface SignatureVerifyResult {
+	readonly valid: boolean;
+	readonly key_type: string;
+	readonly message: string;
+}
+
 // =============================================================================
 // SSH Key Types
 // =============================================================================
@@ -94,6 +313,106 @@ export const SSH_ALGORITHMS = [
 	},
 ] as const;
 
+/**
+ * SSH key encodings: 'pem' is PKCS#1 for RSA and SEC1 for ECDSA private keys,
+ * as `ssh-keygen -m PEM` writes them; 'rfc4716' holds public keys only.
+ */
+export type SshKeyFormat = 'openssh' | 'pem' | 'pkcs8' | 'rfc4716';
+
+export interface SshKeyConvertOptions {
+	readonly key: string;
+	/** Passphrase of an encrypted OpenSSH private key */
+	readonly passphrase?: string;
+	readonly format: SshKeyFormat;
+	/** Encrypts an OpenSSH private key output; leave empty to strip the passphrase */
+	readonly new_passphrase?: string;
+}
+
+export interface SshKeyConvertResult {
+	readonly format: SshKeyFormat;
+	/** Null for public-key input and RFC 4716 */
+	readonly private_key: string | null;
+	readonly public_key: string;
+}
+
+export interface SshKeyInfo {
+	readonly algorithm: string;
+	readonly bits: number;
+	readonly format: SshKeyFormat;
+	readonly is_private: boolean;
+	readonly encrypted: boolean;
+	readonly comment: string;
+	readonly fingerprint_sha256: string;
+	readonly fingerprint_md5: string;
+	/** authorized_keys line */
+	readonly public_key: string;
+}
+
+export type SshCertType = 'user' | 'host';
+
+/** Default extensions `ssh-keygen -s` grants user certificates. */
+export const SSH_CERT_DEFAULT_USER_EXTENSIONS = [
+	'permit-X11-forwarding',
+	'permit-agent-forwarding',
+	'permit-port-forwarding',
+	'permit-pty',
+	'permit-user-rc',
+] as const;
+
+export interface SshCertSignOptions {
+	/** CA private key in any supported format */
+	readonly ca_key: string;
+	readonly ca_passphrase?: string;
+	/** Public key to certify */
+	readonly public_key: string;
+	readonly cert_type: SshCertType;
+	/** Key identity (`-I`) */
+	readonly key_id: string;
+	/** Users or hosts (`-n`); empty means any principal */
+	readonly principals?: readonly string[];
+	/** Unix seconds; always valid when omitted */
+	readonly valid_after?: number;
+	/** Unix seconds; valid forever when omitted */
+	readonly valid_before?: number;
+	readonly serial?: number;
+	/** User certificates only */
+	readonly force_command?: string;
+	/** Comma-separated CIDR blocks; user certificates only */
+	readonly source_address?: string;
+	/** Defaults to SSH_CERT_DEFAULT_USER_EXTENSIONS for user certificates, none for host */
+	readonly extensions?: readonly string[];
+}
+
+export interface SshCertInfo {
+	/** e.g. 'ssh-ed25519-cert-v01@openssh.com' */
+	readonly key_type: string;
+	readonly cert_type: SshCertType;
+	readonly algorithm: string;
+	readonly fingerprint: string;
+	readonly ca_algorithm: string;
+	readonly ca_fingerprint: string;
+	/** e.g. 'rsa-sha2-512' */
+	readonly signature_algorithm: string;
+	readonly key_id: string;
+	readonly serial: number;
+	/** Empty when valid for any principal */
+	readonly principals: readonly string[];
+	/** Null when always valid */
+	readonly valid_after: number | null;
+	/** Null when valid forever */
+	readonly valid_before: number | null;
+	readonly critical_options: Readonly<Record<string, string>>;
+	readonly extensions: Readonly<Record<string, string>>;
+	readonly comment: string;
+	readonly signature_valid: boolean;
+}
+
+export interface SshCertSignResult {
+	/** -cert.pub line */
+	readonly certificate: string;
+	readonly info: SshCertInfo;
+}
+
 // =============================================================================
 // GPG Key Types
 // =============================================================================
@@ -107,6 +426,8 @@ export interface GpgKeyOptions {
 	readonly comment?: string;
 	readonly passphrase?: string;
 	readonly method: GenerationMethod;
+	/** Also import the key into the user's keyring (needs the gpg CLI). */
+	readonly import_to_keyring?: boolean;
 }
 
 export interface GpgKeyResult {
@@ -120,6 +441,60 @@ export interface GpgKeyResult {
 	readonly method_used: string;
 }
 
+export interface PgpMessageOptions {
+	/** Text to encrypt, sign, or verify, or the armored message to decrypt or verify. */
+	readonly message: string;
+	/** Armored public key (encrypt, verify) or secret key (decrypt, sign). */
+	readonly key: string;
+	readonly passphrase?: string;
+	/** Armored detached signature over `message` (verify only). */
+	readonly signature?: string;
+}
+
+export interface PgpMessageResult {
+	/** Armored message or signature, decrypted text, or the verified text. */
+	readonly output: string;
+	/** Whether the signature is good (verify only). */
+	readonly valid: boolean | null;
+	/** Fingerprint of the key encrypted to, signed with, or that made the verified signature. */
+	readonly fingerprint: string | null;
+}
+
+/** Capability of a PGP key, from the key flags of its self-signature. */
+export type PgpKeyCapability = 'certify' | 'sign' | 'encrypt' | 'authenticate';
+
+export interface PgpUserId {
+	readonly user_id: string;
+	readonly primary: boolean;
+	readonly revoked: boolean;
+}
+
+export interface PgpKeyPart {
+	/** Key type, e.g. "RSA", "ECDSA NIST P-256", or "Ed25519". */
+	readonly algorithm: string;
+	readonly bits: number;
+	/** Fingerprint in groups of four hex digits. */
+	readonly fingerprint: string;
+	readonly key_id: string;
+	/** Creation time in Unix seconds. */
+	readonly created: number;
+	/** Expiry in Unix seconds; null when the key does not expire. */
+	readonly expires: number | null;
+	readonly capabilities: readonly PgpKeyCapability[];
+	readonly revoked: boolean;
+}
+
+export interface PgpKeyInfo {
+	readonly is_private: boolean;
+	/** Whether the secret key is passphrase-protected. */
+	readonly encrypted: boolean;
+	readonly user_ids: readonly PgpUserId[];
+	readonly primary_key: PgpKeyPart;
+	readonly subkeys: readonly PgpKeyPart[];
+	/** Whether every self-signature binding the user IDs and subkeys verifies. */
+	readonly signatures_valid: boolean;
+}
+
 export const GPG_ALGORITHMS = [
 	{
 		value: 'ecdsa_p256' as const,
@@ -153,6 +528,70 @@ export const GPG_ALGORITHMS = [
 	},
 ] as const;
 
+// =============================================================================
+// X.509 Types
+// =============================================================================
+
+export type X509KeyAlgorithm =
+	| 'rsa2048'
+	| 'rsa3072'
+	| 'rsa4096'
+	| 'ecdsa_p256'
+	| 'ecdsa_p384'
+	| 'ed25519';
+
+export type X509KeyUsage =
+	| 'digital_signature'
+	| 'non_repudiation'
+	| 'key_encipherment'
+	| 'data_encipherment'
+	| 'key_agreement'
+	| 'key_cert_sign'
+	| 'crl_sign';
+
+export type X509ExtendedKeyUsage =
+	| 'server_auth'
+	| 'client_auth'
+	| 'code_signing'
+	| 'email_protection'
+	| 'time_stamping';
+
+/** Distinguished name of the subject; empty attributes are left out */
+export interface X509Subject {
+	readonly common_name: string;
+	readonly organization?: string;
+	readonly organizational_unit?: string;
+	readonly locality?: string;
+	readonly state?: string;
+	/** Two-letter country code */
+	readonly country?: string;
+}
+
+export interface X509Options {
+	readonly algorithm: X509KeyAlgorithm;
+	readonly subject: X509Subject;
+	/** DNS names, IP addresses, email addresses, or URIs */
+	readonly subject_alt_names: readonly string[];
+	readonly key_usages: readonly X509KeyUsage[];
+	readonly extended_key_usages: readonly X509ExtendedKeyUsage[];
+	/** Days the certificate is valid from now; unused for a CSR */
+	readonly validity_days: number;
+	readonly is_ca: boolean;
+}
+
+export interface X509Result {
+	readonly algorithm: string;
+	/** Certificate or CSR in PEM format */
+	readonly pem: string;
+	/** Certificate or CSR in DER format, base64-encoded */
+	readonly der: string;
+	/** PKCS#8 private key in PEM format */
+	readonly private_key: string;
+	/** SHA-256 fingerprint of the DER */
+	readonly fingerprint: string;
+	readonly openssl_command: string;
+}
+
 // =============================================================================
 // GPG Helper Functions
 // =============================================================================
@@ -203,18 +642,20 @@ export const DEFAULT_BCRYPT_COST = 10;
  */
 export const generateBcryptHash = async (
 	password: string,
-	cost: number
+	cost: number,
+	jobId?: string
 ): Promise<BcryptHashResult> =>
-	invoke<BcryptHashResult>('generate_bcrypt_hash', { password, cost });
+	invoke<BcryptHashResult>('generate_bcrypt_hash', { password, cost, jobId });
 
 /**
  * Verify a password against a BCrypt hash.
  */
 export const verifyBcryptHash = async (
 	password: string,
-	hash: string
+	hash: string,
+	jobId?: string
 ): Promise<BcryptVerifyResult> =>
-	invoke<BcryptVerifyResult>('verify_bcrypt_hash', { password, hash });
+	invoke<BcryptVerifyResult>('verify_bcrypt_hash', { password, hash, jobId });
 
 /**
  * Get information about a BCrypt cost factor.
@@ -223,11 +664,186 @@ export const getBcryptCostInfo = async (cost: number): Promise<BcryptCostInfo> =
 	invoke<BcryptCostInfo>('get_bcrypt_cost_info', { cost });
 
 /**
- * Cancel any ongoing worker operation (BCrypt, SSH, GPG).
+ * Cancel a worker operation (BCrypt, SSH, GPG, X.509) by the job ID it was
+ * started with, or every running operation when no ID is given.
  * This kills the worker process, providing true cancellation.
  */
-export const cancelWorkerOperation = async (): Promise<boolean> =>
-	invoke<boolean>('cancel_worker_operation');
+export const cancelWorkerOperation = async (jobId?: string): Promise<boolean> =>
+	invoke<boolean>('cancel_worker_operation', { jobId });
+
+export const DEFAULT_WORKER_POOL_SIZE = 2;
+export const MAX_WORKER_POOL_SIZE = 8;
+
+/**
+ * Set how many warm worker processes to keep, and start them. Warm workers
+ * skip process start-up; 0 spawns a fresh worker for every operation.
+ */
+export const setWorkerPoolSize = async (size: number): Promise<void> =>
+	invoke<void>('set_worker_pool_size', { size });
+
+// =============================================================================
+// Password Hash Functions
+// =============================================================================
+
+/**
+ * Identify a bcrypt, Argon2, scrypt, PBKDF2, or SHA-crypt hash and extract its
+ * parameters.
+ */
+export const identifyPasswordHash = async (hash: string): Promise<PasswordHashInfo> =>
+	invoke<PasswordHashInfo>('identify_password_hash', { hash });
+
+/**
+ * Verify a password against a hash of any format `identifyPasswordHash`
+ * recognizes. bcrypt runs on the worker and can be cancelled.
+ */
+export const verifyPasswordHash = async (
+	password: string,
+	hash: string,
+	jobId?: string
+): Promise<PasswordHashVerifyResult> =>
+	invoke<PasswordHashVerifyResult>('verify_password_hash', { password, hash, jobId });
+
+// =============================================================================
+// JWT Functions
+// =============================================================================
+
+/**
+ * Decode a JWT and explain its claims, without checking the signature.
+ */
+export const decodeJwt = async (token: string): Promise<DecodedJwt> =>
+	invoke<DecodedJwt>('decode_jwt', { token });
+
+/**
+ * Verify the signature of a JWT against a key (HMAC secret, PEM public key or
+ * certificate, JWK, or JWK Set) or the JWK Set at `jwksUrl`.
+ */
+export const verifyJwt = async (
+	token: string,
+	key: string | null,
+	jwksUrl: string | null = null
+): Promise<JwtVerifyResult> => invoke<JwtVerifyResult>('verify_jwt', { token, key, jwksUrl });
+
+/**
+ * Create a signed JWT. HMAC algorithms take a secret; the others a PKCS#8 PEM
+ * private key.
+ */
+export const createJwt = async (
+	claims: Record<string, unknown>,
+	algorithm: JwtAlgorithm,
+	key: string,
+	keyId: string | null = null
+): Promise<string> => invoke<string>('create_jwt', { claims, algorithm, key, keyId });
+
+// =============================================================================
+// OTP Functions
+// =============================================================================
+
+/**
+ * Generate a random base32 secret (20 bytes unless `bytes` is given).
+ */
+export const generateOtpSecret = async (bytes: number | null = null): Promise<string> =>
+	invoke<string>('generate_otp_secret', { bytes });
+
+/**
+ * Compute the current and adjacent HOTP or TOTP codes.
+ */
+export const generateOtpCodes = async (
+	params: OtpParams,
+	window: number | null = null
+): Promise<OtpCodes> => invoke<OtpCodes>('generate_otp_codes', { params, window });
+
+/**
+ * Check a TOTP code against a secret, allowing `window` steps of clock drift.
+ * The code's length sets the digit count.
+ */
+export const verifyTotp = async (
+	code: string,
+	secret: string,
+	options: {
+		readonly algorithm?: OtpAlgorithm;
+		readonly period?: number;
+		readonly window?: number;
+	} = {}
+): Promise<OtpVerifyResult> =>
+	invoke<OtpVerifyResult>('verify_totp', {
+		code,
+		secret,
+		algorithm: options.algorithm ?? null,
+		period: options.period ?? null,
+		window: options.window ?? null,
+	});
+
+/**
+ * Read the parameters of an `otpauth://` URI.
+ */
+export const parseOtpauthUri = async (uri: string): Promise<OtpParams> =>
+	invoke<OtpParams>('parse_otpauth_uri', { uri });
+
+/**
+ * Write OTP parameters as an `otpauth://` URI.
+ */
+export const buildOtpauthUri = async (params: OtpParams): Promise<string> =>
+	invoke<string>('build_otpauth_uri', { params });
+
+// =============================================================================
+// Symmetric Encryption Functions
+// =============================================================================
+
+/**
+ * Encrypt UTF-8 text. The IV/nonce and salt used are returned with the
+ * ciphertext; both are needed to decrypt.
+ */
+export const encryptText = async (
+	text: string,
+	options: SymmetricOptions
+): Promise<SymmetricResult> => invoke<SymmetricResult>('encrypt_text', { text, options });
+
+/**
+ * Decrypt ciphertext back to UTF-8 text.
+ */
+export const decryptText = async (
+	ciphertext: string,
+	options: SymmetricOptions
+): Promise<SymmetricResult> => invoke<SymmetricResult>('decrypt_text', { ciphertext, options });
+
+/**
+ * Encrypt a file into `outputPath` as bare ciphertext.
+ */
+export const encryptFile = async (
+	inputPath: string,
+	outputPath: string,
+	options: SymmetricOptions
+): Promise<SymmetricFileResult> =>
+	invoke<SymmetricFileResult>('encrypt_file', { inputPath, outputPath, options });
+
+/**
+ * Decrypt a file of bare ciphertext into `outputPath`.
+ */
+export const decryptFile = async (
+	inputPath: string,
+	outputPath: string,
+	options: SymmetricOptions
+): Promise<SymmetricFileResult> =>
+	invoke<SymmetricFileResult>('decrypt_file', { inputPath, outputPath, options });
+
+// =============================================================================
+// Signing Functions
+// =============================================================================
+
+/**
+ * Sign text or a file with a private key.
+ */
+export const signData = async (input: SigningInput, options: SignOptions): Promise<SignResult> =>
+	invoke<SignResult>('sign_data', { input, options });
+
+/**
+ * Verify a signature over text or a file with a public key.
+ */
+export const verifySignature = async (
+	input: SigningInput,
+	options: VerifyOptions
+): Promise<SignatureVerifyResult> =>
+	invoke<SignatureVerifyResult>('verify_signature', { input, options });
 
 // =============================================================================
 // SSH Key Functions
@@ -236,8 +852,34 @@ export const cancelWorkerOperation = async (): Promise<boolean> =>
 /**
  * Generate an SSH key pair.
  */
-export const generateSshKeyPair = async (options: SshKeyOptions): Promise<SshKeyResult> =>
-	invoke<SshKeyResult>('generate_ssh_keypair', { options });
+export const generateSshKeyPair = async (
+	options: SshKeyOptions,
+	jobId?: string
+): Promise<SshKeyResult> => invoke<SshKeyResult>('generate_ssh_keypair', { options, jobId });
+
+/**
+ * Convert an SSH key to another format, optionally changing its passphrase.
+ */
+export const convertSshKey = async (options: SshKeyConvertOptions): Promise<SshKeyConvertResult> =>
+	invoke<SshKeyConvertResult>('convert_ssh_key', { options });
+
+/**
+ * Inspect a pasted SSH key. Encrypted OpenSSH keys need no passphrase.
+ */
+export const inspectSshKey = async (key: string): Promise<SshKeyInfo> =>
+	invoke<SshKeyInfo>('inspect_ssh_key', { key });
+
+/**
+ * Sign a user or host certificate with a CA key, as `ssh-keygen -s` does.
+ */
+export const signSshCertificate = async (options: SshCertSignOptions): Promise<SshCertSignResult> =>
+	invoke<SshCertSignResult>('sign_ssh_certificate', { options });
+
+/**
+ * List a certificate's contents and check its CA signature.
+ */
+export const inspectSshCertificate = async (certificate: string): Promise<SshCertInfo> =>
+	invoke<SshCertInfo>('inspect_ssh_certificate', { certificate });
 
 // =============================================================================
 // GPG Key Functions
@@ -246,8 +888,72 @@ export const generateSshKeyPair = async (options: SshKeyOptions): Promise<SshKey
 /**
  * Generate a GPG key pair.
  */
-export const generateGpgKeyPair = async (options: GpgKeyOptions): Promise<GpgKeyResult> =>
-	invoke<GpgKeyResult>('generate_gpg_keypair', { options });
+export const generateGpgKeyPair = async (
+	options: GpgKeyOptions,
+	jobId?: string
+): Promise<GpgKeyResult> => invoke<GpgKeyResult>('generate_gpg_keypair', { options, jobId });
+
+/**
+ * Encrypt text to a PGP public key.
+ */
+export const encryptGpgMessage = async (
+	options: PgpMessageOptions,
+	jobId?: string
+): Promise<PgpMessageResult> => invoke<PgpMessageResult>('encrypt_gpg_message', { options, jobId });
+
+/**
+ * Decrypt an armored PGP message with a secret key.
+ */
+export const decryptGpgMessage = async (
+	options: PgpMessageOptions,
+	jobId?: string
+): Promise<PgpMessageResult> => invoke<PgpMessageResult>('decrypt_gpg_message', { options, jobId });
+
+/**
+ * Clearsign text, or make a detached signature over it.
+ */
+export const signGpgMessage = async (
+	options: PgpMessageOptions,
+	detached: boolean,
+	jobId?: string
+): Promise<PgpMessageResult> =>
+	invoke<PgpMessageResult>('sign_gpg_message', { options, detached, jobId });
+
+/**
+ * Verify a clearsigned or signed message, or text against a detached signature.
+ */
+export const verifyGpgSignature = async (
+	options: PgpMessageOptions,
+	jobId?: string
+): Promise<PgpMessageResult> =>
+	invoke<PgpMessageResult>('verify_gpg_signature', { options, jobId });
+
+/**
+ * List the user IDs, subkeys, and capabilities of a PGP public or private key block.
+ */
+export const parsePgpKey = async (armored: string): Promise<PgpKeyInfo> =>
+	invoke<PgpKeyInfo>('parse_pgp_key', { armored });
+
+// =============================================================================
+// X.509 Functions
+// =============================================================================
+
+/**
+ * Generate a key pair and a self-signed certificate.
+ */
+export const generateSelfSignedCertificate = async (
+	options: X509Options,
+	jobId?: string
+): Promise<X509Result> =>
+	invoke<X509Result>('generate_self_signed_certificate', { options, jobId });
+
+/**
+ * Generate a key pair and a certificate signing request.
+ */
+export const generateCertificateRequest = async (
+	options: X509Options,
+	jobId?: string
+): Promise<X509Result> => invoke<X509Result>('generate_certificate_request', { options, jobId });
 
 // =============================================================================
 // CLI Availability Functions
@@ -0,0 +1,54 @@
+/**
+ * ID generator service.
+ *
+ * Wraps the Rust `generate_ids` / `decode_id` commands (see
+ * `src-tauri/src/id_generator.rs`) for ULIDs, Nano IDs, KSUIDs, and
+ * Snowflake IDs. Snowflakes are 64-bit and travel as decimal strings so
+ * they never lose precision in a JavaScript number.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type IdKind = 'ulid' | 'nanoid' | 'ksuid' | 'snowflake';
+
+export interface IdKindInfo {
+	readonly kind: IdKind;
+	readonly label: string;
+	readonly description: string;
+}
+
+export const ID_KINDS: readonly IdKindInfo[] = [
+	{ kind: 'ulid', label: 'ULID', description: 'Sortable, 128-bit, Crockford base32' },
+	{ kind: 'nanoid', label: 'Nano ID', description: 'Random, custom alphabet and length' },
+	{ kind: 'ksuid', label: 'KSUID', description: 'Sortable, 160-bit, base62' },
+	{ kind: 'snowflake', label: 'Snowflake', description: '64-bit time, node, and sequence' },
+] as const;
+
+export const NANOID_DEFAULT_ALPHABET =
+	'useandom-26T198340PX75pxJACKVERYMINDBUSHWOLF_GQZbfghjklqvwyzrict';
+export const NANOID_DEFAULT_LENGTH = 21;
+export const SNOWFLAKE_TWITTER_EPOCH = 1_288_834_974_657;
+export const SNOWFLAKE_DISCORD_EPOCH = 1_420_070_400_000;
+export const SNOWFLAKE_MAX_NODE = 1023;
+export const MAX_ID_COUNT = 100_000;
+
+export interface IdOptions {
+	readonly alphabet?: string;
+	readonly length?: number;
+	readonly epoch?: number;
+	readonly node?: number;
+}
+
+export interface DecodedId {
+	readonly kind: IdKind;
+	readonly unixMillis: number;
+	readonly random: string | null;
+	readonly node: number | null;
+	readonly sequence: number | null;
+}
+
+export const generateIds = (kind: IdKind, count: number, options?: IdOptions): Promise<string[]> =>
+	invoke<string[]>('generate_ids', { kind, count, options: options ?? null });
+
+/** Extract the creation time of a ULID, KSUID, or Snowflake. */
+export const decodeId = (kind: IdKind, id: string, epoch?: number): Promise<DecodedId> =>
+	invoke<DecodedId>('decode_id', { kind, id, epoch: epoch ?? null });
@@ -0,0 +1,34 @@
+/**
+ * Key export service.
+ *
+ * Wraps the Rust `save_key_material` command (see
+ * `src-tauri/src/key_export.rs`), which writes a private key readable by
+ * its owner alone (mode 0600, or an owner-only ACL on Windows) and the
+ * public key to the `.pub` sibling. Existing files are reported rather
+ * than replaced until the user confirms.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+/** `create` reports existing files; `overwrite` replaces them after confirmation. */
+export type SaveMode = 'create' | 'overwrite';
+
+export type SaveKeyOutcome =
+	| { readonly status: 'saved'; readonly path: string; readonly publicPath: string | null }
+	| { readonly status: 'exists'; readonly paths: readonly string[] };
+
+/**
+ * Save a private key to an absolute `path`, and `publicKey` to `<path>.pub`
+ * when given. With mode `create`, nothing is written if either file exists.
+ */
+export const saveKeyMaterial = (
+	content: string,
+	path: string,
+	mode: SaveMode,
+	publicKey?: string
+): Promise<SaveKeyOutcome> =>
+	invoke<SaveKeyOutcome>('save_key_material', {
+		content,
+		path,
+		mode,
+		publicKey: publicKey ?? null,
+	});
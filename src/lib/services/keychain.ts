@@ -0,0 +1,25 @@
+/**
+ * OS keychain service.
+ *
+ * Wraps the Rust keychain commands (see `src-tauri/src/keychain.rs`),
+ * which keep generated secrets in the macOS Keychain, Windows Credential
+ * Manager, or the Secret Service, so the frontend holds only a secret's
+ * kind and name.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type SecretKind = 'password' | 'passphrase' | 'privateKey';
+
+export const MAX_SECRET_NAME_LENGTH = 128;
+
+/** Store a secret under `name`, replacing any secret already stored there. */
+export const keychainStore = (kind: SecretKind, name: string, secret: string): Promise<void> =>
+	invoke<void>('keychain_store', { kind, name, secret });
+
+/** Fetch a stored secret; `null` when none is stored under `name`. */
+export const keychainRetrieve = (kind: SecretKind, name: string): Promise<string | null> =>
+	invoke<string | null>('keychain_retrieve', { kind, name });
+
+/** Remove a stored secret; `false` when none was stored under `name`. */
+export const keychainDelete = (kind: SecretKind, name: string): Promise<boolean> =>
+	invoke<boolean>('keychain_delete', { kind, name });
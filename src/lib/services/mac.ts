@@ -221,6 +221,8 @@ export interface OuiDatabaseInfo {
 	readonly entries: number;
 	readonly updated: string;
 	readonly source: string;
+	/** `true` while lookups use the bundled snapshot rather than a downloaded registry. */
+	readonly bundled: boolean;
 }
 
 /**
@@ -234,6 +236,14 @@ export const getOuiDatabaseInfo = async (): Promise<OuiDatabaseInfo | null> => {
 	}
 };
 
+/**
+ * Download the full IEEE registry (MA-L, MA-M, MA-S) and switch lookups to it.
+ *
+ * Rejects with the backend error message when offline; the previous database stays active.
+ */
+export const updateOuiDatabase = (): Promise<OuiDatabaseInfo> =>
+	invoke<OuiDatabaseInfo>('update_oui_database');
+
 export interface RandomMacOptions {
 	readonly locallyAdministered: boolean;
 	readonly vendorPrefix?: string;
@@ -68,6 +68,8 @@ export interface HostnameResolutionOptions {
 	readonly mdns: boolean;
 	/** Enable NetBIOS name resolution (Windows) */
 	readonly netbios: boolean;
+	/** Enable LLMNR multicast PTR resolution (Windows) */
+	readonly llmnr?: boolean;
 	/** Resolution timeout in milliseconds */
 	readonly timeoutMs: number;
 }
@@ -103,6 +105,25 @@ export interface PortInfo {
 	readonly banner?: string;
 	/** TLS certificate info (for HTTPS ports) */
 	readonly tlsCert?: TlsCertInfo;
+	/** Owning process, when the scanned host is this machine */
+	readonly process?: SocketProcess;
+}
+
+/** Process owning a local socket */
+export interface SocketProcess {
+	readonly pid: number;
+	readonly name?: string;
+	readonly user?: string;
+}
+
+/** A listening TCP socket or bound UDP socket on this machine */
+export interface ListeningSocket {
+	readonly protocol: 'tcp' | 'udp';
+	/** Bound address; `0.0.0.0` / `::` mean every interface */
+	readonly address: string;
+	readonly port: number;
+	readonly service?: string;
+	readonly process?: SocketProcess;
 }
 
 export interface HostResult {
@@ -112,8 +133,8 @@ export interface HostResult {
 	readonly scanDurationMs: number;
 }
 
-export interface ScanResults {
-	readonly hosts: readonly HostResult[];
+/** Scan totals; hosts arrive separately as `host_discovered` events. */
+export interface ScanSummary {
 	readonly totalHostsScanned: number;
 	readonly hostsWithOpenPorts: number;
 	readonly totalOpenPorts: number;
@@ -122,6 +143,20 @@ export interface ScanResults {
 	readonly endTime: string;
 }
 
+export interface ScanResults extends ScanSummary {
+	readonly hosts: readonly HostResult[];
+}
+
+/** A scan stored in the scan history, without its hosts. */
+export interface ScanHistoryEntry {
+	readonly scanId: string;
+	readonly target: string;
+	readonly startTime: string;
+	readonly storedHosts: number;
+	/** Null when the scan was cancelled or did not finish. */
+	readonly summary: ScanSummary | null;
+}
+
 // =============================================================================
 // Progress Event Types
 // =============================================================================
@@ -138,7 +173,16 @@ export type ScanProgress =
 			discovered_hosts: number;
 			discovered_ports: number;
 	  }
-	| { type: 'completed'; results: ScanResults }
+	| {
+			type: 'port_progress';
+			ip: string;
+			scanned_ports: number;
+			total_ports: number;
+			percentage: number;
+			block_start: number;
+			block_end: number;
+	  }
+	| { type: 'completed'; summary: ScanSummary }
 	| { type: 'error'; message: string };
 
 // =============================================================================
@@ -484,7 +528,7 @@ export const DEFAULT_SYN_PORTS = [22, 80, 443, 445, 3389] as const;
 export const startNetworkScan = async (
 	request: ScanRequest,
 	scanId: string
-): Promise<ScanResults> => invoke<ScanResults>('start_network_scan', { request, scanId });
+): Promise<ScanSummary> => invoke<ScanSummary>('start_network_scan', { request, scanId });
 
 /**
  * Cancel a running network scan.
@@ -492,12 +536,37 @@ export const startNetworkScan = async (
 export const cancelNetworkScan = async (scanId: string): Promise<boolean> =>
 	invoke<boolean>('cancel_network_scan', { scanId });
 
+/**
+ * List stored scans, newest first.
+ */
+export const listScanHistory = async (): Promise<readonly ScanHistoryEntry[]> =>
+	invoke<readonly ScanHistoryEntry[]>('scan_history_list');
+
+/**
+ * Load a stored scan with all of its hosts.
+ */
+export const getScanHistory = async (scanId: string): Promise<ScanResults> =>
+	invoke<ScanResults>('scan_history_get', { scanId });
+
+/**
+ * Delete a stored scan.
+ */
+export const deleteScanHistory = async (scanId: string): Promise<void> =>
+	invoke<void>('scan_history_delete', { scanId });
+
 /**
  * Get local network interfaces.
  */
 export const getLocalNetworkInterfaces = async (): Promise<LocalNetworkInfo> =>
 	invoke<LocalNetworkInfo>('get_local_network_interfaces');
 
+/**
+ * List listening sockets on this machine with their owning processes,
+ * optionally only those bound to `port`.
+ */
+export const listListeningPorts = async (port?: number): Promise<readonly ListeningSocket[]> =>
+	invoke<ListeningSocket[]>('list_listening_ports', { port });
+
 /**
  * Discover mDNS/Bonjour services on the local network.
  */
@@ -554,6 +623,14 @@ export const getDiscoveryMethods = async (): Promise<readonly [string, boolean][
 export const checkDiscoveryPrivilege = async (method: DiscoveryMethod): Promise<boolean> =>
 	invoke<boolean>('check_discovery_privilege', { method });
 
+/**
+ * Re-check discovery method availability and return the
+ * [method_name, is_available] pairs. Availability is not cached, so this
+ * matches `getDiscoveryMethods`.
+ */
+export const refreshPrivileges = async (): Promise<readonly [string, boolean][]> =>
+	invoke<[string, boolean][]>('refresh_privileges');
+
 // =============================================================================
 // mDNS Service Types
 // =============================================================================
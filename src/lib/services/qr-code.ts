@@ -3,8 +3,13 @@
  * Wraps the `qr-code-styling` package to provide rich styling
  * (dot/corner shapes, gradients, logo overlay) plus content-type
  * encoders for common payloads (Wi-Fi, vCard, calendar, etc.).
+ * `generateQr` renders plain SVG or PNG codes in Rust instead (see
+ * `src-tauri/src/qr.rs`), for OTP URIs, WireGuard configs, and keys,
+ * and `decodeQr` reads QR, Data Matrix, and Code 128 symbols back.
  */
 
+import { invoke } from '@tauri-apps/api/core';
+import { fileToBase64 } from '@/lib/services/encoders/base64';
 import QRCodeStyling, {
 	type CornerDotType,
 	type CornerSquareType,
@@ -296,3 +301,78 @@ export const buildQrOptions = (data: string, style: StyleOptions): Options => {
 
 export const createQr = (data: string, style: StyleOptions): QRCodeStyling =>
 	new QRCodeStyling(buildQrOptions(data, style));
+
+// =============================================================================
+// Rust Renderer
+// =============================================================================
+
+export type QrImageFormat = 'svg' | 'png';
+
+export const DEFAULT_QR_SIZE = 256;
+export const MAX_QR_SIZE = 4096;
+export const DEFAULT_QUIET_ZONE = 4;
+export const MAX_QUIET_ZONE = 16;
+
+export interface QrRenderOptions {
+	readonly format?: QrImageFormat;
+	readonly errorCorrection?: ErrorCorrectionLevel;
+	/** Largest width and height in pixels; whole pixels per module, so it may come out smaller. */
+	readonly size?: number;
+	/** Light border in modules. */
+	readonly quietZone?: number;
+}
+
+export interface QrImage {
+	readonly format: QrImageFormat;
+	/** SVG markup, or base64 PNG bytes. */
+	readonly data: string;
+	readonly mimeType: 'image/svg+xml' | 'image/png';
+	readonly size: number;
+	readonly version: number;
+	readonly modules: number;
+}
+
+/** Render `payload` as a plain square-module QR code in Rust. */
+export const generateQr = (payload: string, options?: QrRenderOptions): Promise<QrImage> =>
+	invoke<QrImage>('generate_qr', { payload, options: options ?? null });
+
+/** `data:` URL of a rendered code, for an `<img>` or a download link. */
+export const qrImageDataUrl = (image: QrImage): string =>
+	image.format === 'png'
+		? `data:image/png;base64,${image.data}`
+		: `data:image/svg+xml;charset=utf-8,${encodeURIComponent(image.data)}`;
+
+// =============================================================================
+// Rust Decoder
+// =============================================================================
+
+/** Image to scan: a file path, base64 file bytes, or the clipboard bitmap. */
+export type QrImageSource =
+	| { readonly kind: 'path'; readonly path: string }
+	| { readonly kind: 'bytes'; readonly data: string }
+	| { readonly kind: 'clipboard' };
+
+export type Symbology = 'qrCode' | 'dataMatrix' | 'code128';
+
+export interface DecodedCode {
+	readonly payload: string;
+	readonly symbology: Symbology;
+	/** Located points and their bounding box, in image pixels. */
+	readonly position: {
+		readonly points: readonly { readonly x: number; readonly y: number }[];
+		readonly x: number;
+		readonly y: number;
+		readonly width: number;
+		readonly height: number;
+	};
+}
+
+/** Find and decode the QR, Data Matrix, and Code 128 symbols in an image; empty when none. */
+export const decodeQr = (image: QrImageSource): Promise<DecodedCode[]> =>
+	invoke<DecodedCode[]>('decode_qr', { image });
+
+/** Scan source for a dropped or picked image file. */
+export const imageFileSource = async (file: File): Promise<QrImageSource> => {
+	const dataUrl = await fileToBase64(file);
+	return { kind: 'bytes', data: dataUrl.slice(dataUrl.indexOf(',') + 1) };
+};
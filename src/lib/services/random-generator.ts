@@ -0,0 +1,44 @@
+/**
+ * Random data generator service.
+ *
+ * Wraps the Rust `generate_random` command (see
+ * `src-tauri/src/random_generator.rs`) for secure random tokens, salts,
+ * and keys as hex, base64, base58, or UUIDs, and for API keys with a
+ * prefix and a CRC-32 checksum in GitHub's token layout.
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type RandomFormat = 'hex' | 'base64' | 'base64url' | 'base58' | 'uuid' | 'apikey';
+
+export interface RandomFormatInfo {
+	readonly format: RandomFormat;
+	readonly label: string;
+	readonly description: string;
+}
+
+export const RANDOM_FORMATS: readonly RandomFormatInfo[] = [
+	{ format: 'hex', label: 'Hex', description: 'Two digits per byte, like openssl rand -hex' },
+	{ format: 'base64', label: 'Base64', description: 'Standard alphabet with padding' },
+	{ format: 'base64url', label: 'Base64url', description: 'URL-safe alphabet, no padding' },
+	{ format: 'base58', label: 'Base58', description: 'Bitcoin alphabet, no look-alikes' },
+	{ format: 'uuid', label: 'UUID', description: 'Random version 4 UUID' },
+	{ format: 'apikey', label: 'API key', description: 'Prefix, base62 body, CRC-32 checksum' },
+] as const;
+
+export const API_KEY_DEFAULT_PREFIX = 'key_';
+export const MAX_RANDOM_BYTES = 1024;
+export const MAX_RANDOM_COUNT = 10_000;
+
+export interface RandomOptions {
+	readonly uppercase?: boolean;
+	readonly prefix?: string;
+}
+
+/** Generate `count` values of `bytes` random bytes each; `bytes` is ignored for UUIDs. */
+export const generateRandom = (
+	bytes: number,
+	format: RandomFormat,
+	count: number,
+	options?: RandomOptions
+): Promise<string[]> =>
+	invoke<string[]>('generate_random', { bytes, format, count, options: options ?? null });
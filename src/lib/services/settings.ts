@@ -32,9 +32,18 @@ export interface FontSettings {
 	readonly google_fonts_enabled: boolean;
 }
 
+export interface RedactionSettings {
+	/** Whether secrets are masked before anything is written to disk */
+	readonly enabled: boolean;
+	/** Extra regular expressions; a named group `secret` masks only that group */
+	readonly custom_patterns: readonly string[];
+}
+
 export interface AppSettings {
 	/** Font configuration */
 	readonly font: FontSettings;
+	/** Secret redaction for logs, history, and exports */
+	readonly redaction: RedactionSettings;
 }
 
 export const DEFAULT_FONT_SETTINGS: FontSettings = {
@@ -45,8 +54,14 @@ export const DEFAULT_FONT_SETTINGS: FontSettings = {
 	google_fonts_enabled: false,
 } as const;
 
+export const DEFAULT_REDACTION_SETTINGS: RedactionSettings = {
+	enabled: true,
+	custom_patterns: [],
+} as const;
+
 export const DEFAULT_SETTINGS: AppSettings = {
 	font: DEFAULT_FONT_SETTINGS,
+	redaction: DEFAULT_REDACTION_SETTINGS,
 } as const;
 
 // =============================================================================
@@ -0,0 +1,48 @@
+/**
+ * `authorized_keys` / `known_hosts` analyzer service.
+ *
+ * Wraps the Rust `analyze_ssh_key_list` command (see
+ * `src-tauri/src/ssh_key_list.rs`). Each entry and each unparsable line
+ * carries the range of its whole line, so the editor can highlight,
+ * rewrite, or remove it in place.
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type { AstRange } from './ast/types.js';
+
+export type SshKeyListKind = 'authorized-keys' | 'known-hosts';
+
+/** `dsa` and `weak-rsa` (under 2048 bits) keys, and entries repeating an earlier line. */
+export type SshKeyIssue = 'dsa' | 'weak-rsa' | 'duplicate';
+
+export interface SshKeyListEntry {
+	readonly line: number;
+	readonly range: AstRange;
+	readonly algorithm: string;
+	readonly bits: number;
+	readonly fingerprint: string;
+	readonly comment: string;
+	/** `authorized_keys` options such as `no-pty` or `from="..."`. */
+	readonly options: readonly string[];
+	/** `known_hosts` host patterns; empty when hashed. */
+	readonly hosts: readonly string[];
+	readonly hashed: boolean;
+	readonly marker: '@cert-authority' | '@revoked' | null;
+	readonly issues: readonly SshKeyIssue[];
+	/** Line number of the earlier entry this one repeats. */
+	readonly duplicateOf: number | null;
+}
+
+export interface SshKeyListError {
+	readonly line: number;
+	readonly range: AstRange;
+	readonly message: string;
+}
+
+export interface SshKeyListReport {
+	readonly kind: SshKeyListKind;
+	readonly entries: readonly SshKeyListEntry[];
+	readonly errors: readonly SshKeyListError[];
+}
+
+export const analyzeSshKeyList = (text: string, kind: SshKeyListKind): Promise<SshKeyListReport> =>
+	invoke<SshKeyListReport>('analyze_ssh_key_list', { text, kind });
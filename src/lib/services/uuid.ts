@@ -1,9 +1,12 @@
 /**
  * UUID generation service.
  * Wraps the `uuid` npm package with project-specific options
- * (formatting, version metadata, namespace presets).
+ * (formatting, version metadata, namespace presets). Bulk generation and
+ * inspection delegate to the Rust `generate_uuids` / `inspect_uuid`
+ * commands (see `src-tauri/src/uuid_generator.rs`).
  */
 
+import { invoke } from '@tauri-apps/api/core';
 import { NIL, v1, v3, v4, v5, v7, validate, version as detectVersion } from 'uuid';
 
 export type UuidVersion = 'v1' | 'v3' | 'v4' | 'v5' | 'v7' | 'nil';
@@ -109,3 +112,42 @@ export const generateUuids = (options: UuidGenerateOptions): readonly string[] =
 	const raw = Array.from({ length: count }, () => generateOneUuid(version, namespace, name));
 	return raw.map((value) => formatUuid(value, format));
 };
+
+// ===== Bulk Generation (Rust) =====
+
+export const MAX_BULK_COUNT = 100_000;
+
+export type UuidStyle = 'hyphenated' | 'simple' | 'braced' | 'urn';
+
+export interface UuidBulkOptions {
+	readonly version: UuidVersion;
+	readonly count: number;
+	readonly namespace?: string;
+	readonly name?: string;
+	readonly style?: UuidStyle;
+	readonly uppercase?: boolean;
+}
+
+export interface UuidInspection {
+	readonly uuid: string;
+	readonly version: number;
+	readonly versionName: string;
+	readonly variant: string;
+	readonly unixMillis: number | null;
+	readonly clockSequence: number | null;
+	readonly node: string | null;
+}
+
+/** Generate up to {@link MAX_BULK_COUNT} UUIDs off the renderer thread. */
+export const generateUuidsBulk = (options: UuidBulkOptions): Promise<string[]> =>
+	invoke<string[]>('generate_uuids', {
+		version: options.version,
+		count: options.count,
+		namespace: options.namespace ?? null,
+		name: options.name ?? null,
+		format: { style: options.style ?? 'hyphenated', uppercase: options.uppercase ?? false },
+	});
+
+/** Decode the version, variant, and embedded timestamp of a UUID. */
+export const inspectUuid = (uuid: string): Promise<UuidInspection> =>
+	invoke<UuidInspection>('inspect_uuid', { uuid });
@@ -9,8 +9,25 @@
 // When the caller does not have a specific fallback, omit the argument
 // and the function falls back to coercing the value via `String(e)`,
 // matching the prior service-layer behavior.
+//
+// Coded backend errors (`{ code, message, params }`, see
+// `src-tauri/src/error_codes.rs`) carry a rendered English message and are
+// treated like Error instances.
+export interface BackendError {
+	readonly code: string;
+	readonly message: string;
+	readonly params: Readonly<Record<string, string>>;
+}
+
+export const isBackendError = (e: unknown): e is BackendError =>
+	typeof e === 'object' &&
+	e !== null &&
+	typeof (e as { code?: unknown }).code === 'string' &&
+	typeof (e as { message?: unknown }).message === 'string';
+
 export const getErrorMessage = (e: unknown, fallback?: string): string => {
 	if (e instanceof Error) return e.message;
+	if (isBackendError(e)) return e.message;
 	if (fallback !== undefined) return fallback;
 	if (typeof e === 'string') return e;
 	return String(e);
@@ -48,6 +48,7 @@ function BcryptGeneratorPage() {
 	const [isGenerating, setIsGenerating] = useState(false);
 	const [generateError, setGenerateError] = useState<string | null>(null);
 	const generateCancelledRef = useRef(false);
+	const generateJobIdRef = useRef<string | null>(null);
 
 	const [verifyPassword, setVerifyPassword] = useState('');
 	const [verifyHash, setVerifyHash] = useState('');
@@ -55,6 +56,7 @@ function BcryptGeneratorPage() {
 	const [isVerifying, setIsVerifying] = useState(false);
 	const [verifyError, setVerifyError] = useState<string | null>(null);
 	const verifyCancelledRef = useRef(false);
+	const verifyJobIdRef = useRef<string | null>(null);
 
 	const [showOptions, setShowOptions] = usePersistedRail('bcrypt-generator');
 	const [costInfo, setCostInfo] = useState<BcryptCostInfo | null>(null);
@@ -107,7 +109,9 @@ function BcryptGeneratorPage() {
 		startTimer();
 
 		try {
-			const result = await generateBcryptHash(password, cost);
+			const jobId = crypto.randomUUID();
+			generateJobIdRef.current = jobId;
+			const result = await generateBcryptHash(password, cost, jobId);
 			if (!generateCancelledRef.current) {
 				setHashResult(result);
 				setFlashCounter((c) => c + 1);
@@ -127,7 +131,7 @@ function BcryptGeneratorPage() {
 
 	const handleCancelGenerate = async () => {
 		generateCancelledRef.current = true;
-		await cancelWorkerOperation();
+		if (generateJobIdRef.current) await cancelWorkerOperation(generateJobIdRef.current);
 		stopTimer();
 		setIsGenerating(false);
 		toast.info('Hash generation cancelled');
@@ -142,7 +146,9 @@ function BcryptGeneratorPage() {
 		startTimer();
 
 		try {
-			const result = await verifyBcryptHash(verifyPassword, verifyHash);
+			const jobId = crypto.randomUUID();
+			verifyJobIdRef.current = jobId;
+			const result = await verifyBcryptHash(verifyPassword, verifyHash, jobId);
 			if (!verifyCancelledRef.current) {
 				setVerifyResult(result);
 				if (result.valid) {
@@ -165,7 +171,7 @@ function BcryptGeneratorPage() {
 
 	const handleCancelVerify = async () => {
 		verifyCancelledRef.current = true;
-		await cancelWorkerOperation();
+		if (verifyJobIdRef.current) await cancelWorkerOperation(verifyJobIdRef.current);
 		stopTimer();
 		setIsVerifying(false);
 		toast.info('Verification cancelled');
@@ -54,12 +54,14 @@ function GpgKeyGeneratorPage() {
 	const [algorithm, setAlgorithm] = useState<GpgKeyAlgorithm>('rsa4096');
 	const [passphrase, setPassphrase] = useState('');
 	const [method, setMethod] = useState<GenerationMethod>('library');
+	const [importToKeyring, setImportToKeyring] = useState(false);
 
 	const [keyResult, setKeyResult] = useState<GpgKeyResult | null>(null);
 	const [isGenerating, setIsGenerating] = useState(false);
 	const [error, setError] = useState<string | null>(null);
 	const [flashCounter, setFlashCounter] = useState(0);
 	const isCancelledRef = useRef(false);
+	const jobIdRef = useRef<string | null>(null);
 
 	const [cliAvailability, setCliAvailability] = useState<CliAvailability | null>(null);
 
@@ -106,14 +108,20 @@ function GpgKeyGeneratorPage() {
 		startTimer();
 
 		try {
-			const result = await generateGpgKeyPair({
-				name: name.trim(),
-				email: email.trim(),
-				comment: comment.trim() || undefined,
-				algorithm,
-				passphrase: passphrase || undefined,
-				method,
-			});
+			const jobId = crypto.randomUUID();
+			jobIdRef.current = jobId;
+			const result = await generateGpgKeyPair(
+				{
+					name: name.trim(),
+					email: email.trim(),
+					comment: comment.trim() || undefined,
+					algorithm,
+					passphrase: passphrase || undefined,
+					method,
+					import_to_keyring: importToKeyring && gpgAvailable,
+				},
+				jobId
+			);
 			if (!isCancelledRef.current) {
 				setKeyResult(result);
 				setFlashCounter((c) => c + 1);
@@ -133,7 +141,7 @@ function GpgKeyGeneratorPage() {
 
 	const handleCancel = async () => {
 		isCancelledRef.current = true;
-		await cancelWorkerOperation();
+		if (jobIdRef.current) await cancelWorkerOperation(jobIdRef.current);
 		stopTimer();
 		setIsGenerating(false);
 		toast.info('Key generation cancelled');
@@ -260,6 +268,16 @@ function GpgKeyGeneratorPage() {
 								{cliAvailability.gpg_path}
 							</p>
 						) : null}
+						<div className="mt-2">
+							<FormCheckbox
+								label="Import into GPG keyring"
+								hint="Adds the new key to your local gpg keyring"
+								checked={importToKeyring && gpgAvailable}
+								onCheckedChange={setImportToKeyring}
+								disabled={!gpgAvailable}
+								size="compact"
+							/>
+						</div>
 					</FormSection>
 
 					<FormSection title="Actions">
@@ -1,5 +1,5 @@
 import { createFileRoute } from '@tanstack/react-router';
-import { Cable, Database, Dice5, Network } from 'lucide-react';
+import { Cable, Database, Dice5, Network, RefreshCw } from 'lucide-react';
 import { useEffect, useMemo, useState } from 'react';
 
 import { ActionButton, CopyButton } from '@/lib/components/action';
@@ -34,6 +34,7 @@ import {
 	SAMPLE_MAC,
 	toEui64,
 	toIpv6LinkLocal,
+	updateOuiDatabase,
 	type VendorResult,
 } from '@/lib/services/mac';
 import { createToolOptionsStore, usePersistedRail } from '@/lib/stores';
@@ -75,6 +76,8 @@ function MacLookupPage() {
 	const [vendor, setVendor] = useState<VendorResult | null>(null);
 	const [vendorLoading, setVendorLoading] = useState<boolean>(false);
 	const [dbInfo, setDbInfo] = useState<OuiDatabaseInfo | null>(null);
+	const [dbUpdating, setDbUpdating] = useState<boolean>(false);
+	const [dbError, setDbError] = useState<string | null>(null);
 	const [showRail, setShowRail] = usePersistedRail('mac-lookup');
 
 	const parseResult = useMemo(() => parseMac(input), [input]);
@@ -125,6 +128,19 @@ function MacLookupPage() {
 		setInput(formatMac(mac, displayFormat, upperCase));
 	};
 
+	const handleUpdateDatabase = async () => {
+		setDbUpdating(true);
+		setDbError(null);
+		try {
+			setDbInfo(await updateOuiDatabase());
+			if (parseResult.ok) setVendor(await lookupVendor(parseResult.mac));
+		} catch (e) {
+			setDbError(String(e));
+		} finally {
+			setDbUpdating(false);
+		}
+	};
+
 	return (
 		<ToolShell
 			valid={validity}
@@ -141,10 +157,13 @@ function MacLookupPage() {
 					randomLocalOnly={randomLocalOnly}
 					randomVendorOui={randomVendorOui}
 					dbInfo={dbInfo}
+					dbUpdating={dbUpdating}
+					dbError={dbError}
 					onLoadSample={setInput}
 					onClear={() => setInput('')}
 					onPatch={patch}
 					onGenerateRandom={handleGenerateRandom}
+					onUpdateDatabase={handleUpdateDatabase}
 				/>
 			}
 		>
@@ -195,10 +214,13 @@ interface RailProps {
 	readonly randomLocalOnly: boolean;
 	readonly randomVendorOui: string;
 	readonly dbInfo: OuiDatabaseInfo | null;
+	readonly dbUpdating: boolean;
+	readonly dbError: string | null;
 	readonly onLoadSample: (value: string) => void;
 	readonly onClear: () => void;
 	readonly onPatch: (delta: Partial<MacLookupOptions>) => void;
 	readonly onGenerateRandom: () => void;
+	readonly onUpdateDatabase: () => void;
 }
 
 function MacLookupRail({
@@ -207,10 +229,13 @@ function MacLookupRail({
 	randomLocalOnly,
 	randomVendorOui,
 	dbInfo,
+	dbUpdating,
+	dbError,
 	onLoadSample,
 	onClear,
 	onPatch,
 	onGenerateRandom,
+	onUpdateDatabase,
 }: RailProps) {
 	return (
 		<>
@@ -281,6 +306,17 @@ function MacLookupRail({
 							{dbInfo.entries.toLocaleString()} entries — updated {dbInfo.updated}
 						</span>
 					</div>
+					<FormInfo>{dbInfo.source}</FormInfo>
+					<ActionButton
+						label="Update from IEEE"
+						icon={RefreshCw}
+						size="sm"
+						variant="outline"
+						loading={dbUpdating}
+						loadingLabel="Downloading..."
+						onClick={onUpdateDatabase}
+					/>
+					{dbError ? <FormError message={dbError} /> : null}
 				</FormSection>
 			) : null}
 
@@ -290,7 +326,7 @@ function MacLookupRail({
 					{ id: 'ip-converter', reason: 'Convert IPv4 ↔ IPv6 addresses' },
 					{ id: 'cidr-calculator', reason: 'Calculate subnets from CIDR notation' },
 				]}
-				aboutText="Vendor lookup uses the bundled IEEE OUI database via a Tauri command. All processing happens locally; no network calls."
+				aboutText="Vendor lookup uses the bundled IEEE OUI database via a Tauri command, or the full IEEE registry once downloaded. Lookups happen locally; only the update button makes network calls."
 			/>
 		</>
 	);
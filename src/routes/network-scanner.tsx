@@ -85,7 +85,7 @@ import {
 	SCAN_MODES,
 	type ScanMode,
 	type ScanProgress,
-	type ScanResults,
+	type ScanSummary,
 	startNetworkScan,
 	type UnifiedHost,
 	WEB_PORTS,
@@ -130,7 +130,7 @@ const replaceWithResolvedResults = (
 };
 
 interface StatusContentArgs {
-	readonly results: ScanResults | null;
+	readonly results: ScanSummary | null;
 	readonly isScanning: boolean;
 	readonly progress: ScanProgress | null;
 	readonly unifiedHostCount: number;
@@ -1095,7 +1095,7 @@ function HostDetailOrEmpty({
 }
 
 interface ResultsFooterProps {
-	readonly results: ScanResults;
+	readonly results: ScanSummary;
 }
 
 function ResultsFooter({ results }: ResultsFooterProps) {
@@ -1166,7 +1166,7 @@ function NetworkScannerPage() {
 	const [isScanning, setIsScanning] = useState(false);
 	const currentScanIdRef = useRef<string | null>(null);
 	const [progress, setProgress] = useState<ScanProgress | null>(null);
-	const [results, setResults] = useState<ScanResults | null>(null);
+	const [results, setResults] = useState<ScanSummary | null>(null);
 	const [discoveredHosts, setDiscoveredHosts] = useState<HostResult[]>([]);
 	const [error, setError] = useState<string | null>(null);
 
@@ -1266,12 +1266,12 @@ function NetworkScannerPage() {
 		}
 	}, []);
 
-	const onScanCompleted = useCallback((results: ScanResults) => {
-		setResults(results);
+	const onScanCompleted = useCallback((summary: ScanSummary) => {
+		setResults(summary);
 		setIsScanning(false);
 		setRecentlyDiscoveredIds(new Set());
 		toast.success('Scan completed', {
-			description: `Found ${results.totalOpenPorts} open ports on ${results.hostsWithOpenPorts} hosts`,
+			description: `Found ${summary.totalOpenPorts} open ports on ${summary.hostsWithOpenPorts} hosts`,
 		});
 	}, []);
 
@@ -1284,11 +1284,14 @@ function NetworkScannerPage() {
 
 	const handleProgressEvent = useCallback(
 		(event: ScanProgress) => {
-			setProgress(event);
+			// Per-port events would overwrite the host-level percentage shown in the header.
+			if (event.type !== 'port_progress') {
+				setProgress(event);
+			}
 			if (event.type === 'host_discovered') {
 				onHostDiscovered(event.host);
 			} else if (event.type === 'completed') {
-				onScanCompleted(event.results);
+				onScanCompleted(event.summary);
 			} else if (event.type === 'error') {
 				onScanError(event.message);
 			}
@@ -1439,13 +1442,13 @@ function NetworkScannerPage() {
 
 	const handleExportJson = () => {
 		if (!results) return;
-		exportFile(exportToJson(results), 'application/json', 'json');
+		exportFile(exportToJson({ ...results, hosts: discoveredHosts }), 'application/json', 'json');
 		toast.success('Exported as JSON');
 	};
 
 	const handleExportCsv = () => {
 		if (!results) return;
-		exportFile(exportToCsv(results), 'text/csv', 'csv');
+		exportFile(exportToCsv({ ...results, hosts: discoveredHosts }), 'text/csv', 'csv');
 		toast.success('Exported as CSV');
 	};
 
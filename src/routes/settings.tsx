@@ -109,6 +109,8 @@ const CODE_SAMPLE_TEXT = 'Aa Bb {} = 123';
 
 function SettingsPage() {
 	const [fontSettings, setFontSettings] = useState<FontSettings>({ ...DEFAULT_SETTINGS.font });
+	// Not edited on this page yet; kept so saving fonts does not reset it.
+	const [redactionSettings, setRedactionSettings] = useState(DEFAULT_SETTINGS.redaction);
 	const [systemFonts, setSystemFonts] = useState<readonly string[]>([]);
 	const [monospaceFonts, setMonospaceFonts] = useState<readonly string[]>([]);
 	const [settingsFilePath, setSettingsFilePath] = useState('');
@@ -129,6 +131,7 @@ function SettingsPage() {
 		getSettings()
 			.then((settings) => {
 				setFontSettings({ ...settings.font });
+				setRedactionSettings(settings.redaction);
 				initializedRef.current = true;
 			})
 			.catch(() => {
@@ -148,7 +151,7 @@ function SettingsPage() {
 
 	useEffect(() => {
 		if (!initializedRef.current) return;
-		const settings = { font: fontSettings };
+		const settings = { font: fontSettings, redaction: redactionSettings };
 		clearTimeout(saveTimerRef.current);
 		saveTimerRef.current = setTimeout(() => {
 			updateSettings(settings)
@@ -156,7 +159,7 @@ function SettingsPage() {
 				.catch(() => toast.error('Failed to save settings'));
 		}, 300);
 		return () => clearTimeout(saveTimerRef.current);
-	}, [fontSettings]);
+	}, [fontSettings, redactionSettings]);
 
 	const handleReset = async () => {
 		const confirmed = await confirm(
@@ -172,6 +175,7 @@ function SettingsPage() {
 
 		const defaults = await resetSettings();
 		setFontSettings({ ...defaults.font });
+		setRedactionSettings(defaults.redaction);
 		applyAllSettings(defaults);
 		toast.success('All settings have been reset');
 	};
@@ -68,6 +68,7 @@ function SshKeyGeneratorPage() {
 	const [elapsedMs, setElapsedMs] = useState(0);
 	const timerIntervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
 	const isCancelledRef = useRef(false);
+	const jobIdRef = useRef<string | null>(null);
 
 	const [showOptions, setShowOptions] = usePersistedRail('ssh-key-generator');
 	const [showFingerprint, setShowFingerprint] = useState(true);
@@ -107,12 +108,17 @@ function SshKeyGeneratorPage() {
 		startTimer();
 
 		try {
-			const result = await generateSshKeyPair({
-				algorithm,
-				comment: comment.trim() || undefined,
-				passphrase: passphrase || undefined,
-				method,
-			});
+			const jobId = crypto.randomUUID();
+			jobIdRef.current = jobId;
+			const result = await generateSshKeyPair(
+				{
+					algorithm,
+					comment: comment.trim() || undefined,
+					passphrase: passphrase || undefined,
+					method,
+				},
+				jobId
+			);
 			if (!isCancelledRef.current) {
 				setKeyResult(result);
 				setFlashCounter((c) => c + 1);
@@ -132,7 +138,7 @@ function SshKeyGeneratorPage() {
 
 	const handleCancel = async () => {
 		isCancelledRef.current = true;
-		await cancelWorkerOperation();
+		if (jobIdRef.current) await cancelWorkerOperation(jobIdRef.current);
 		stopTimer();
 		setIsGenerating(false);
 		toast.info('Key generation cancelled');